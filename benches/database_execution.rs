@@ -167,6 +167,7 @@ mod prax_benchmarks {
                 min_connections: 1,
                 statement_cache_size: 100,
                 connection_timeout: None, // Disable timeout for benchmark
+                acquire_timeout: None,
                 idle_timeout: None,
                 max_lifetime: None,
             };
@@ -271,6 +272,7 @@ mod prax_benchmarks {
                 max_connections: 5,
                 min_connections: 1,
                 connection_timeout: Some(Duration::from_secs(10)),
+                acquire_timeout: Some(Duration::from_secs(10)),
                 idle_timeout: None,
                 max_lifetime: None,
             };
@@ -363,6 +365,7 @@ mod prax_benchmarks {
                 max_connections: 1, // SQLite memory db needs single connection
                 min_connections: 1,
                 connection_timeout: Some(Duration::from_secs(10)),
+                acquire_timeout: Some(Duration::from_secs(10)),
                 idle_timeout: None,
                 max_lifetime: None,
             };
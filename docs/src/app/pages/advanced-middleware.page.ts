@@ -65,6 +65,34 @@ for (query_type, count) in &stats.queries_by_type {
     println!("  {}: {}", query_type, count);
 }`;
 
+  prometheusExporter = `use prax_query::metrics::{PoolSnapshot, PrometheusExporter};
+use prax_query::middleware::MetricsMiddleware;
+use std::sync::Arc;
+
+let exporter = Arc::new(PrometheusExporter::new());
+let metrics = MetricsMiddleware::new(exporter.clone());
+
+// Pool gauges are read on every scrape
+let pool = pg_pool.clone();
+exporter.register_pool("primary", move || {
+    let status = pool.status();
+    PoolSnapshot::new(status.size, status.available, status.max_size)
+        .with_waiting(status.waiting)
+});
+
+// axum
+let app = Router::new()
+    .route("/users", get(list_users))
+    .merge(prax_axum::metrics::metrics_router(exporter.clone()));
+
+// actix-web
+App::new().service(prax_actix::metrics::metrics_service(exporter.clone()));
+
+// prax_queries_total{model="User",operation="select",status="ok"} 42
+// prax_query_duration_seconds_bucket{model="User",operation="select",le="0.005"} 40
+// prax_cache_hit_ratio 0.35
+// prax_pool_utilization{pool="primary"} 0.6`;
+
   retryMiddleware = `use prax_query::middleware::{RetryMiddleware, RetryConfig, RetryPredicate};
 use std::time::Duration;
 
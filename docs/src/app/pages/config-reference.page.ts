@@ -248,7 +248,17 @@ preview_features = [
     "multi_schema",       # Multiple schema support
     "json_filtering",     # JSON field filtering
     "views",              # Database views
-]`;
+]
+
+# How generated code is split across files
+# "per-model": one file per model and enum (default)
+# "single": everything inline in mod.rs
+split = "per-model"
+
+# Gate each model module behind a \`model-<name>\` Cargo feature
+# so crates only compile the models they use
+# Default: false
+feature_gates = false`;
 
   // Migration configuration
   migrationConfig = `[migrations]
@@ -263,7 +273,30 @@ auto_migrate = false
 
 # Name of the migration history table
 # Default: "_prax_migrations"
-table_name = "_prax_migrations"`;
+table_name = "_prax_migrations"
+
+# Signature verification for \`prax migrate deploy\`
+# Signatures sit next to the SQL: migration.sql.minisig (minisign)
+# or migration.sql.asc (GPG)
+[migrations.signatures]
+# Reject unsigned migrations (or pass --require-signatures)
+# Default: false
+required = true
+minisign_keys = ["RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3"]
+gpg_fingerprints = ["0123456789ABCDEF0123456789ABCDEF01234567"]
+
+# Per-environment policies, chosen with --environment or PRAX_ENV
+# Production defaults to require_approval = true and
+# allow_destructive = false; other environments allow everything
+[migrations.environments.production]
+# Approve a run with --approved-by, or list approved migrations:
+# [[approval]]
+# migration = "20240101120000_add_user_roles"
+# approved_by = "alice@example.com"
+approvals_file = "./prax/approvals.toml"
+
+[migrations.environments.staging]
+require_approval = true`;
 
   // Seed configuration
   seedConfig = `[seed]
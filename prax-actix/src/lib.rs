@@ -8,6 +8,8 @@
 //! - **App Data**: Add `PraxClient` to Actix-web's app data
 //! - **Extractors**: Extract database connections in handlers
 //! - **Middleware**: Actor-based middleware for connection handling
+//! - **Metrics**: A Prometheus `/metrics` service for `prax_query::metrics::PrometheusExporter`
+//! - **Sessions**: An `actix-session` store backed by a Prax model (`sessions` feature)
 //!
 //! # Example
 //!
@@ -53,6 +55,10 @@ use tracing::{debug, info};
 
 use prax_query::connection::{DatabaseConfig, PoolConfig};
 
+pub mod metrics;
+#[cfg(feature = "sessions")]
+pub mod session;
+
 // Re-export key types
 pub use prax_query::filter::{Filter, FilterValue};
 pub use prax_query::prelude::*;
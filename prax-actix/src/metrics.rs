@@ -0,0 +1,73 @@
+//! Prometheus `/metrics` endpoint.
+//!
+//! [`metrics_service`] serves a
+//! [`PrometheusExporter`](prax_query::metrics::PrometheusExporter) for
+//! scraping. Register it as a service, next to the `MetricsMiddleware` that
+//! feeds the exporter:
+//!
+//! ```rust,ignore
+//! use prax_actix::metrics::metrics_service;
+//! use prax_query::metrics::PrometheusExporter;
+//! use prax_query::middleware::MetricsMiddleware;
+//! use std::sync::Arc;
+//!
+//! let exporter = Arc::new(PrometheusExporter::new());
+//! let db = db.with_middleware(MetricsMiddleware::new(exporter.clone()));
+//!
+//! HttpServer::new(move || {
+//!     App::new()
+//!         .route("/users", web::get().to(list_users))
+//!         .service(metrics_service(exporter.clone()))
+//! })
+//! ```
+
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Resource, web};
+use prax_query::metrics::{CONTENT_TYPE, PrometheusExporter};
+
+/// A resource serving `exporter` at `GET /metrics`.
+pub fn metrics_service(exporter: Arc<PrometheusExporter>) -> Resource {
+    web::resource("/metrics")
+        .app_data(web::Data::from(exporter))
+        .route(web::get().to(metrics_handler))
+}
+
+/// Render `exporter` as a scrape response, for mounting on a custom path.
+pub fn metrics_response(exporter: &PrometheusExporter) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(CONTENT_TYPE)
+        .body(exporter.render())
+}
+
+async fn metrics_handler(exporter: web::Data<PrometheusExporter>) -> HttpResponse {
+    metrics_response(&exporter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::{StatusCode, header};
+    use actix_web::{App, test};
+    use prax_query::middleware::{MetricsCollector, QueryType};
+
+    #[actix_web::test]
+    async fn test_metrics_service() {
+        let exporter = Arc::new(PrometheusExporter::new());
+        exporter.record_query(QueryType::Insert, Some("Post"), 1_000, false, false);
+
+        let app = test::init_service(App::new().service(metrics_service(exporter))).await;
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/metrics").to_request()).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), CONTENT_TYPE);
+        let body = test::read_body(response).await;
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(
+            text.contains(
+                r#"prax_queries_total{model="Post",operation="insert",status="error"} 1"#
+            )
+        );
+    }
+}
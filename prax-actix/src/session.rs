@@ -0,0 +1,215 @@
+//! An `actix-session` store backed by a Prax model.
+//!
+//! [`PraxSessionStore`] keeps sessions in the application's database through
+//! a [`SessionStore`](prax_query::session::SessionStore), so no Redis is
+//! needed:
+//!
+//! ```rust,ignore
+//! use actix_session::SessionMiddleware;
+//! use actix_web::cookie::Key;
+//! use prax_actix::session::PraxSessionStore;
+//!
+//! let store = PraxSessionStore::for_model::<session::Session>(engine.clone());
+//! HttpServer::new(move || {
+//!     App::new()
+//!         .wrap(SessionMiddleware::new(store.clone(), key.clone()))
+//!         .route("/", web::get().to(index))
+//! })
+//! ```
+//!
+//! Updates fail if another request saved the same session since this request
+//! loaded it. The loaded version travels in the session state under
+//! [`VERSION_KEY`].
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use actix_session::storage::{LoadError, SaveError, SessionKey, UpdateError};
+use actix_web::cookie::time::Duration;
+use prax_query::raw::RawDatabase;
+use prax_query::session::{SessionStore, VERSION_KEY};
+use prax_query::traits::Model;
+
+type SessionState = HashMap<String, String>;
+
+/// An `actix-session` store keeping sessions in a Prax model.
+pub struct PraxSessionStore<D> {
+    store: SessionStore<D>,
+}
+
+impl<D: RawDatabase> PraxSessionStore<D> {
+    /// Store sessions in the table of `M`; see
+    /// [`SessionStore::for_model`].
+    pub fn for_model<M: Model>(db: D) -> Self {
+        Self::new(SessionStore::for_model::<M>(db))
+    }
+
+    /// Wrap a configured store.
+    pub fn new(store: SessionStore<D>) -> Self {
+        Self { store }
+    }
+
+    /// Get the underlying store, e.g. for its purge policy.
+    pub fn store(&self) -> &SessionStore<D> {
+        &self.store
+    }
+}
+
+impl<D> Clone for PraxSessionStore<D> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl<D> std::fmt::Debug for PraxSessionStore<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PraxSessionStore")
+            .field(&self.store)
+            .finish()
+    }
+}
+
+impl<D: RawDatabase> actix_session::storage::SessionStore for PraxSessionStore<D> {
+    async fn load(&self, session_key: &SessionKey) -> Result<Option<SessionState>, LoadError> {
+        let Some(stored) = self
+            .store
+            .load(session_key.as_ref())
+            .await
+            .map_err(|e| LoadError::Other(e.into()))?
+        else {
+            return Ok(None);
+        };
+
+        let mut state: SessionState = if stored.data.is_empty() {
+            SessionState::new()
+        } else {
+            serde_json::from_str(&stored.data).map_err(|e| LoadError::Deserialization(e.into()))?
+        };
+        state.insert(VERSION_KEY.to_string(), stored.version.to_string());
+        Ok(Some(state))
+    }
+
+    async fn save(&self, state: SessionState, ttl: &Duration) -> Result<SessionKey, SaveError> {
+        let data = encode(&state).map_err(|e| SaveError::Serialization(e.into()))?;
+        let expires_at = expiry(ttl);
+        loop {
+            let key = uuid::Uuid::new_v4().simple().to_string();
+            if self
+                .store
+                .create(&key, &data, expires_at)
+                .await
+                .map_err(|e| SaveError::Other(e.into()))?
+            {
+                return SessionKey::try_from(key)
+                    .map_err(|e| SaveError::Other(anyhow::Error::msg(e.to_string())));
+            }
+        }
+    }
+
+    async fn update(
+        &self,
+        session_key: SessionKey,
+        state: SessionState,
+        ttl: &Duration,
+    ) -> Result<SessionKey, UpdateError> {
+        let version = state.get(VERSION_KEY).and_then(|v| v.parse().ok());
+        let data = encode(&state).map_err(|e| UpdateError::Serialization(e.into()))?;
+        self.store
+            .save(session_key.as_ref(), &data, expiry(ttl), version)
+            .await
+            .map_err(|e| UpdateError::Other(e.into()))?;
+        Ok(session_key)
+    }
+
+    async fn update_ttl(&self, session_key: &SessionKey, ttl: &Duration) -> anyhow::Result<()> {
+        self.store.touch(session_key.as_ref(), expiry(ttl)).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, session_key: &SessionKey) -> anyhow::Result<()> {
+        self.store.delete(session_key.as_ref()).await?;
+        Ok(())
+    }
+}
+
+/// Serialize the state without the version.
+fn encode(state: &SessionState) -> serde_json::Result<String> {
+    let state: HashMap<&str, &str> = state
+        .iter()
+        .filter(|(key, _)| key.as_str() != VERSION_KEY)
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    serde_json::to_string(&state)
+}
+
+fn expiry(ttl: &Duration) -> SystemTime {
+    let ttl = std::time::Duration::try_from(*ttl).unwrap_or_default();
+    SystemTime::now() + ttl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_session::storage::SessionStore as _;
+    use prax_query::filter::FilterValue;
+    use prax_query::traits::BoxFuture;
+
+    struct Session;
+
+    impl Model for Session {
+        const MODEL_NAME: &'static str = "Session";
+        const TABLE_NAME: &'static str = "sessions";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "data", "version", "expires_at"];
+        const TTL_COLUMN: Option<&'static str> = Some("expires_at");
+    }
+
+    /// A database holding one session at version 3.
+    struct OneSession;
+
+    impl RawDatabase for OneSession {
+        fn query(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, prax_query::QueryResult<Vec<serde_json::Value>>> {
+            let row = serde_json::json!({
+                "data": r#"{"user":"1"}"#,
+                "version": 3,
+                "expires_at": "2030-01-01T00:00:00Z",
+            });
+            Box::pin(async move { Ok(vec![row]) })
+        }
+
+        fn execute(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, prax_query::QueryResult<u64>> {
+            Box::pin(async { Ok(1) })
+        }
+
+        fn execute_in_transaction(
+            &self,
+            _statements: Vec<(String, Vec<FilterValue>)>,
+        ) -> BoxFuture<'_, prax_query::QueryResult<Vec<u64>>> {
+            unreachable!("sessions are saved one statement at a time")
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_load_carries_version() {
+        let store = PraxSessionStore::for_model::<Session>(OneSession);
+        let key = SessionKey::try_from("abc".to_string()).unwrap();
+        let state = store.load(&key).await.unwrap().unwrap();
+
+        assert_eq!(state["user"], "1");
+        assert_eq!(state[VERSION_KEY], "3");
+        assert_eq!(encode(&state).unwrap(), r#"{"user":"1"}"#);
+
+        let key = store.save(state, &Duration::hours(1)).await.unwrap();
+        assert_eq!(key.as_ref().len(), 32);
+    }
+}
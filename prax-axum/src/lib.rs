@@ -8,7 +8,11 @@
 //! - **State Extension**: Add `PraxClient` to Axum's state
 //! - **Extractors**: Extract database connections in handlers
 //! - **Middleware**: Tower-compatible middleware for connection handling
+//! - **Tracing**: A span per request, parenting the query spans of `TracingMiddleware`
 //! - **Transaction Support**: Request-scoped transactions via middleware
+//! - **Live Queries**: Stream live query events to WebSocket clients (`ws` feature)
+//! - **Metrics**: A Prometheus `/metrics` route for `prax_query::metrics::PrometheusExporter`
+//! - **Sessions**: A `tower-sessions` store backed by a Prax model (`sessions` feature)
 //!
 //! # Example
 //!
@@ -48,10 +52,17 @@ use axum::{
 };
 use thiserror::Error;
 use tower::{Layer, Service};
-use tracing::{debug, info};
+use tracing::instrument::Instrumented;
+use tracing::{Instrument, debug, info};
 
 use prax_query::connection::{DatabaseConfig, PoolConfig};
 
+#[cfg(feature = "ws")]
+pub mod live;
+pub mod metrics;
+#[cfg(feature = "sessions")]
+pub mod session;
+
 // Re-export key types
 pub use prax_query::filter::{Filter, FilterValue};
 pub use prax_query::prelude::*;
@@ -207,6 +218,9 @@ impl<S> Layer<S> for PraxLayer {
 }
 
 /// Tower middleware service for Prax.
+///
+/// Each request runs inside an `http.request` span, so query spans from
+/// `prax_query::middleware::TracingMiddleware` nest under it.
 #[derive(Clone)]
 pub struct PraxMiddleware<S> {
     inner: S,
@@ -221,15 +235,21 @@ where
 {
     type Response = S::Response;
     type Error = S::Error;
-    type Future = S::Future;
+    type Future = Instrumented<S::Future>;
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
         self.inner.poll_ready(cx)
     }
 
     fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
-        debug!("PraxMiddleware handling request");
-        self.inner.call(request)
+        let span = tracing::info_span!(
+            "http.request",
+            otel.kind = "server",
+            http.request.method = %request.method(),
+            url.path = %request.uri().path(),
+        );
+        span.in_scope(|| debug!("PraxMiddleware handling request"));
+        self.inner.call(request).instrument(span)
     }
 }
 
@@ -0,0 +1,83 @@
+//! Live queries over WebSockets.
+//!
+//! [`live_socket`] forwards the events of a
+//! [`LiveQuery`](prax_query::live::LiveQuery) to a WebSocket client as JSON
+//! text frames, until either side goes away:
+//!
+//! ```rust,ignore
+//! use axum::extract::{State, WebSocketUpgrade};
+//! use prax_axum::live::live_socket;
+//!
+//! async fn online_users(ws: WebSocketUpgrade, State(app): State<AppState>) -> Response {
+//!     let events = app
+//!         .db
+//!         .user()
+//!         .find_many()
+//!         .r#where(user::online::equals(true))
+//!         .live()
+//!         .changes(app.changes.clone())
+//!         .stream();
+//!     live_socket(ws, events)
+//! }
+//! ```
+//!
+//! Frames are the serialized [`LiveEvent`]s, e.g.
+//! `{"type": "update", "record": {...}}`. A failed refresh sends
+//! `{"type": "error", "code": "P...", "message": "..."}` and the query
+//! carries on.
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::response::Response;
+use futures::StreamExt;
+use prax_query::error::{QueryError, QueryResult};
+use prax_query::live::LiveEvent;
+use prax_query::traits::BoxStream;
+use serde::Serialize;
+use tracing::debug;
+
+/// Upgrade the connection and stream `events` to the client.
+pub fn live_socket<M>(
+    ws: WebSocketUpgrade,
+    events: BoxStream<'static, QueryResult<LiveEvent<M>>>,
+) -> Response
+where
+    M: Serialize + Send + 'static,
+{
+    ws.on_upgrade(move |socket| forward(socket, events))
+}
+
+async fn forward<M: Serialize>(
+    mut socket: WebSocket,
+    mut events: BoxStream<'static, QueryResult<LiveEvent<M>>>,
+) {
+    loop {
+        tokio::select! {
+            event = events.next() => {
+                let frame = match event {
+                    Some(Ok(event)) => serde_json::to_string(&event)
+                        .unwrap_or_else(|e| error_frame(&QueryError::serialization(e.to_string()))),
+                    Some(Err(e)) => error_frame(&e),
+                    None => break,
+                };
+                if socket.send(Message::Text(frame.into())).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum; anything else is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!("live query socket closed");
+}
+
+fn error_frame(err: &QueryError) -> String {
+    serde_json::json!({
+        "type": "error",
+        "code": err.code.code(),
+        "message": err.message,
+    })
+    .to_string()
+}
@@ -0,0 +1,79 @@
+//! Prometheus `/metrics` endpoint.
+//!
+//! [`metrics_router`] serves a
+//! [`PrometheusExporter`](prax_query::metrics::PrometheusExporter) for
+//! scraping. Merge it into the application router, next to the
+//! `MetricsMiddleware` that feeds the exporter:
+//!
+//! ```rust,ignore
+//! use prax_axum::metrics::metrics_router;
+//! use prax_query::metrics::PrometheusExporter;
+//! use prax_query::middleware::MetricsMiddleware;
+//! use std::sync::Arc;
+//!
+//! let exporter = Arc::new(PrometheusExporter::new());
+//! let db = db.with_middleware(MetricsMiddleware::new(exporter.clone()));
+//!
+//! let app = Router::new()
+//!     .route("/users", get(list_users))
+//!     .merge(metrics_router(exporter))
+//!     .with_state(state);
+//! ```
+
+use std::sync::Arc;
+
+use axum::Router;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use prax_query::metrics::{CONTENT_TYPE, PrometheusExporter};
+
+/// A router serving `exporter` at `GET /metrics`.
+pub fn metrics_router<S>(exporter: Arc<PrometheusExporter>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new().route(
+        "/metrics",
+        get(move || {
+            let exporter = exporter.clone();
+            async move { metrics_response(&exporter) }
+        }),
+    )
+}
+
+/// Render `exporter` as a scrape response, for mounting on a custom path.
+pub fn metrics_response(exporter: &PrometheusExporter) -> Response {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], exporter.render()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use prax_query::middleware::{MetricsCollector, QueryType};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_metrics_route() {
+        let exporter = Arc::new(PrometheusExporter::new());
+        exporter.record_query(QueryType::Select, Some("User"), 1_000, true, false);
+
+        let app: Router = metrics_router(exporter);
+        let response = app
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(
+            text.contains(r#"prax_queries_total{model="User",operation="select",status="ok"} 1"#)
+        );
+    }
+}
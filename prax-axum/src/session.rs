@@ -0,0 +1,200 @@
+//! A `tower-sessions` store backed by a Prax model.
+//!
+//! [`PraxSessionStore`] keeps sessions in the application's database through
+//! a [`SessionStore`](prax_query::session::SessionStore), so no Redis is
+//! needed:
+//!
+//! ```rust,ignore
+//! use prax_axum::session::PraxSessionStore;
+//! use tower_sessions::SessionManagerLayer;
+//!
+//! let store = PraxSessionStore::for_model::<session::Session>(engine.clone());
+//! let app = Router::new()
+//!     .route("/", get(handler))
+//!     .layer(SessionManagerLayer::new(store));
+//! ```
+//!
+//! Saves fail if another request saved the same session since this request
+//! loaded it. The loaded version travels in the session data under
+//! [`VERSION_KEY`].
+
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use prax_query::QueryError;
+use prax_query::raw::RawDatabase;
+use prax_query::session::{SessionStore, VERSION_KEY};
+use prax_query::traits::Model;
+use serde_json::Value;
+use tower_sessions_core::session::{Id, Record};
+use tower_sessions_core::session_store::{self, Error};
+
+/// A `tower-sessions` store keeping sessions in a Prax model.
+pub struct PraxSessionStore<D> {
+    store: SessionStore<D>,
+}
+
+impl<D: RawDatabase> PraxSessionStore<D> {
+    /// Store sessions in the table of `M`; see
+    /// [`SessionStore::for_model`].
+    pub fn for_model<M: Model>(db: D) -> Self {
+        Self::new(SessionStore::for_model::<M>(db))
+    }
+
+    /// Wrap a configured store.
+    pub fn new(store: SessionStore<D>) -> Self {
+        Self { store }
+    }
+
+    /// Get the underlying store, e.g. for its purge policy.
+    pub fn store(&self) -> &SessionStore<D> {
+        &self.store
+    }
+}
+
+impl<D> Clone for PraxSessionStore<D> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl<D> std::fmt::Debug for PraxSessionStore<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PraxSessionStore")
+            .field(&self.store)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<D: RawDatabase + 'static> session_store::SessionStore for PraxSessionStore<D> {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        let data = encode(record)?;
+        let expires_at = SystemTime::from(record.expiry_date);
+        while !self
+            .store
+            .create(&record.id.to_string(), &data, expires_at)
+            .await
+            .map_err(backend)?
+        {
+            record.id = Id::default();
+        }
+        record.data.insert(VERSION_KEY.to_string(), Value::from(1));
+        Ok(())
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        let version = record.data.get(VERSION_KEY).and_then(Value::as_i64);
+        self.store
+            .save(
+                &record.id.to_string(),
+                &encode(record)?,
+                SystemTime::from(record.expiry_date),
+                version,
+            )
+            .await
+            .map_err(backend)
+    }
+
+    async fn load(&self, id: &Id) -> session_store::Result<Option<Record>> {
+        let Some(stored) = self.store.load(&id.to_string()).await.map_err(backend)? else {
+            return Ok(None);
+        };
+
+        let mut data: std::collections::HashMap<String, Value> = if stored.data.is_empty() {
+            Default::default()
+        } else {
+            serde_json::from_str(&stored.data).map_err(|e| Error::Decode(e.to_string()))?
+        };
+        data.insert(VERSION_KEY.to_string(), Value::from(stored.version));
+
+        Ok(Some(Record {
+            id: *id,
+            data,
+            expiry_date: stored.expires_at.unwrap_or_else(SystemTime::now).into(),
+        }))
+    }
+
+    async fn delete(&self, id: &Id) -> session_store::Result<()> {
+        self.store.delete(&id.to_string()).await.map_err(backend)
+    }
+}
+
+/// Serialize the record's data without the version.
+fn encode(record: &Record) -> session_store::Result<String> {
+    let data: serde_json::Map<String, Value> = record
+        .data
+        .iter()
+        .filter(|(key, _)| key.as_str() != VERSION_KEY)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    serde_json::to_string(&data).map_err(|e| Error::Encode(e.to_string()))
+}
+
+fn backend(e: QueryError) -> Error {
+    Error::Backend(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prax_query::filter::FilterValue;
+    use prax_query::traits::BoxFuture;
+    use tower_sessions_core::SessionStore as _;
+
+    struct Session;
+
+    impl Model for Session {
+        const MODEL_NAME: &'static str = "Session";
+        const TABLE_NAME: &'static str = "sessions";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "data", "version", "expires_at"];
+        const TTL_COLUMN: Option<&'static str> = Some("expires_at");
+    }
+
+    /// A database holding one session at version 3.
+    struct OneSession;
+
+    impl RawDatabase for OneSession {
+        fn query(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, prax_query::QueryResult<Vec<Value>>> {
+            let row = serde_json::json!({
+                "data": r#"{"user":1}"#,
+                "version": 3,
+                "expires_at": "2030-01-01T00:00:00Z",
+            });
+            Box::pin(async move { Ok(vec![row]) })
+        }
+
+        fn execute(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, prax_query::QueryResult<u64>> {
+            Box::pin(async { Ok(1) })
+        }
+
+        fn execute_in_transaction(
+            &self,
+            _statements: Vec<(String, Vec<FilterValue>)>,
+        ) -> BoxFuture<'_, prax_query::QueryResult<Vec<u64>>> {
+            unreachable!("sessions are saved one statement at a time")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_carries_version() {
+        let store = PraxSessionStore::for_model::<Session>(OneSession);
+        let record = store.load(&Id::default()).await.unwrap().unwrap();
+
+        assert_eq!(record.data["user"], 1);
+        assert_eq!(record.data[VERSION_KEY], 3);
+        assert_eq!(record.expiry_date.year(), 2030);
+        assert_eq!(encode(&record).unwrap(), r#"{"user":1}"#);
+    }
+}
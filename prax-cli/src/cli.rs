@@ -11,6 +11,10 @@ use std::path::PathBuf;
 #[command(about = "Prax CLI - A modern ORM for Rust", long_about = None)]
 #[command(propagate_version = true)]
 pub struct Cli {
+    /// Emit machine-readable JSON output instead of styled text
+    #[arg(long, global = true)]
+    pub json: bool,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Command,
@@ -31,16 +35,78 @@ pub enum Command {
     /// Format schema file
     Format(FormatArgs),
 
+    /// Convert schemas to and from other tools' formats
+    Convert(ConvertArgs),
+
     /// Database migration commands
     Migrate(MigrateArgs),
 
     /// Direct database operations
     Db(DbArgs),
 
+    /// Interactive SQL and model query console
+    Console(ConsoleArgs),
+
+    /// Aggregate a JSONL query log into a latency and frequency report
+    AnalyzeLog(AnalyzeLogArgs),
+
+    /// Generate shell completion scripts
+    Completions(CompletionsArgs),
+
     /// Display version information
     Version,
 }
 
+impl Command {
+    /// Stable command name used in JSON output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Init(_) => "init",
+            Command::Generate(args) => match args.command {
+                Some(GenerateSubcommand::Crud(_)) => "generate crud",
+                None => "generate",
+            },
+            Command::Validate(_) => "validate",
+            Command::Format(_) => "format",
+            Command::Convert(_) => "convert",
+            Command::Migrate(args) => match args.command {
+                MigrateSubcommand::Dev(_) => "migrate dev",
+                MigrateSubcommand::Deploy(_) => "migrate deploy",
+                MigrateSubcommand::Reset(_) => "migrate reset",
+                MigrateSubcommand::Status => "migrate status",
+                MigrateSubcommand::Resolve(_) => "migrate resolve",
+                MigrateSubcommand::Diff(_) => "migrate diff",
+            },
+            Command::Db(args) => match args.command {
+                DbSubcommand::Push(_) => "db push",
+                DbSubcommand::Pull(_) => "db pull",
+                DbSubcommand::Seed(_) => "db seed",
+                DbSubcommand::Execute(_) => "db execute",
+                DbSubcommand::Copy(_) => "db copy",
+                DbSubcommand::Checksum(_) => "db checksum",
+                DbSubcommand::TopQueries(_) => "db top-queries",
+                DbSubcommand::AdviseIndexes(_) => "db advise-indexes",
+                DbSubcommand::Health(_) => "db health",
+            },
+            Command::Console(_) => "console",
+            Command::AnalyzeLog(_) => "analyze-log",
+            Command::Completions(_) => "completions",
+            Command::Version => "version",
+        }
+    }
+}
+
+// =============================================================================
+// Completions Command
+// =============================================================================
+
+/// Arguments for the `completions` command
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    pub shell: clap_complete::Shell,
+}
+
 // =============================================================================
 // Init Command
 // =============================================================================
@@ -64,13 +130,45 @@ pub struct InitArgs {
     #[arg(long)]
     pub no_example: bool,
 
+    /// Project template to lay down
+    #[arg(short, long, default_value = "basic")]
+    pub template: ProjectTemplate,
+
     /// Accept all defaults without prompting
     #[arg(short, long)]
     pub yes: bool,
 }
 
+/// Project templates for `prax init`
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProjectTemplate {
+    /// Prax configuration and schema only
+    #[default]
+    Basic,
+    /// Axum REST API
+    AxumApi,
+    /// Actix-web REST API
+    ActixApi,
+    /// Event analytics with DuckDB aggregation
+    Analytics,
+    /// Multi-tenant SaaS with row-level tenant isolation
+    MultiTenant,
+}
+
+impl std::fmt::Display for ProjectTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectTemplate::Basic => write!(f, "basic"),
+            ProjectTemplate::AxumApi => write!(f, "axum-api"),
+            ProjectTemplate::ActixApi => write!(f, "actix-api"),
+            ProjectTemplate::Analytics => write!(f, "analytics"),
+            ProjectTemplate::MultiTenant => write!(f, "multi-tenant"),
+        }
+    }
+}
+
 /// Supported database providers
-#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum DatabaseProvider {
     #[default]
     Postgresql,
@@ -94,7 +192,11 @@ impl std::fmt::Display for DatabaseProvider {
 
 /// Arguments for the `generate` command
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct GenerateArgs {
+    #[command(subcommand)]
+    pub command: Option<GenerateSubcommand>,
+
     /// Path to schema file
     #[arg(short, long)]
     pub schema: Option<PathBuf>,
@@ -112,6 +214,53 @@ pub struct GenerateArgs {
     pub watch: bool,
 }
 
+/// Generate subcommands
+#[derive(Subcommand, Debug)]
+pub enum GenerateSubcommand {
+    /// Scaffold CRUD handlers, DTOs, routes and tests for a model
+    Crud(GenerateCrudArgs),
+}
+
+/// Arguments for `generate crud`
+#[derive(Args, Debug)]
+pub struct GenerateCrudArgs {
+    /// Model to scaffold (as named in the schema)
+    pub model: String,
+
+    /// Web framework to target
+    #[arg(long, default_value = "axum")]
+    pub framework: CrudFramework,
+
+    /// Path to schema file
+    #[arg(short, long)]
+    pub schema: Option<PathBuf>,
+
+    /// Directory for API modules (defaults to src/api)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Overwrite existing files
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Web frameworks supported by the CRUD scaffolder
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CrudFramework {
+    #[default]
+    Axum,
+    Actix,
+}
+
+impl std::fmt::Display for CrudFramework {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrudFramework::Axum => write!(f, "axum"),
+            CrudFramework::Actix => write!(f, "actix"),
+        }
+    }
+}
+
 // =============================================================================
 // Validate Command
 // =============================================================================
@@ -122,6 +271,33 @@ pub struct ValidateArgs {
     /// Path to schema file
     #[arg(short, long)]
     pub schema: Option<PathBuf>,
+
+    /// Write an entity-relationship diagram of the schema to this file
+    #[arg(long, value_name = "FILE")]
+    pub erd: Option<PathBuf>,
+
+    /// Diagram format (defaults to dot for .dot/.gv files, mermaid otherwise)
+    #[arg(long, value_name = "FORMAT", requires = "erd")]
+    pub erd_format: Option<DiagramFormat>,
+}
+
+/// Entity-relationship diagram formats
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    /// Mermaid `erDiagram`
+    Mermaid,
+    /// Graphviz DOT
+    Dot,
+}
+
+impl DiagramFormat {
+    /// Pick a format from a file extension
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("dot" | "gv") => DiagramFormat::Dot,
+            _ => DiagramFormat::Mermaid,
+        }
+    }
 }
 
 // =============================================================================
@@ -140,6 +316,85 @@ pub struct FormatArgs {
     pub check: bool,
 }
 
+// =============================================================================
+// Convert Command
+// =============================================================================
+
+/// Arguments for the `convert` command
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// Schema file to convert (or a directory of .sql files)
+    pub input: PathBuf,
+
+    /// Format of the input (defaults from the file extension)
+    #[arg(long)]
+    pub from: Option<SchemaFormat>,
+
+    /// Format to convert to (defaults to prax, or prisma for .prax input)
+    #[arg(long)]
+    pub to: Option<SchemaFormat>,
+
+    /// Output file (defaults to the input path with the target extension)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Print the converted schema instead of writing a file
+    #[arg(long)]
+    pub print: bool,
+
+    /// Overwrite an existing output file
+    #[arg(long)]
+    pub force: bool,
+
+    /// SQL dialect of DDL input (defaults to the provider in prax.toml)
+    #[arg(long)]
+    pub provider: Option<String>,
+}
+
+/// Schema formats understood by `prax convert`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    /// Prax schema (.prax)
+    Prax,
+    /// Prisma schema (.prisma)
+    Prisma,
+    /// SQL DDL (.sql file or a directory of them)
+    Ddl,
+}
+
+impl SchemaFormat {
+    /// Pick a format from a file extension
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        if path.is_dir() {
+            return Some(SchemaFormat::Ddl);
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("prax") => Some(SchemaFormat::Prax),
+            Some("prisma") => Some(SchemaFormat::Prisma),
+            Some("sql") => Some(SchemaFormat::Ddl),
+            _ => None,
+        }
+    }
+
+    /// File extension for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SchemaFormat::Prax => "prax",
+            SchemaFormat::Prisma => "prisma",
+            SchemaFormat::Ddl => "sql",
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaFormat::Ddl => write!(f, "ddl"),
+            _ => write!(f, "{}", self.extension()),
+        }
+    }
+}
+
 // =============================================================================
 // Migrate Command
 // =============================================================================
@@ -158,7 +413,7 @@ pub enum MigrateSubcommand {
     Dev(MigrateDevArgs),
 
     /// Deploy pending migrations to production
-    Deploy,
+    Deploy(MigrateDeployArgs),
 
     /// Reset database and re-apply all migrations
     Reset(MigrateResetArgs),
@@ -191,6 +446,27 @@ pub struct MigrateDevArgs {
     /// Path to schema file
     #[arg(short, long)]
     pub schema: Option<PathBuf>,
+
+    /// Environment whose migration policy applies
+    #[arg(short, long, env = "PRAX_ENV", default_value = "development")]
+    pub environment: String,
+}
+
+/// Arguments for `migrate deploy`
+#[derive(Args, Debug)]
+pub struct MigrateDeployArgs {
+    /// Reject migrations not signed by a trusted key, even if the config
+    /// doesn't require signatures
+    #[arg(long)]
+    pub require_signatures: bool,
+
+    /// Environment whose migration policy applies
+    #[arg(short, long, env = "PRAX_ENV", default_value = "development")]
+    pub environment: String,
+
+    /// Approve every pending migration, recording who approved it
+    #[arg(long)]
+    pub approved_by: Option<String>,
 }
 
 /// Arguments for `migrate reset`
@@ -207,6 +483,10 @@ pub struct MigrateResetArgs {
     /// Skip applying migrations (just reset)
     #[arg(long)]
     pub skip_migrations: bool,
+
+    /// Environment whose migration policy applies
+    #[arg(short, long, env = "PRAX_ENV", default_value = "development")]
+    pub environment: String,
 }
 
 /// Arguments for `migrate resolve`
@@ -238,6 +518,15 @@ pub struct MigrateDiffArgs {
     /// Compare against a specific migration
     #[arg(long)]
     pub from_migration: Option<String>,
+
+    /// Print the SQL the next `migrate deploy` would run instead of diffing
+    /// the schema
+    #[arg(long)]
+    pub script: bool,
+
+    /// Fail if the next `migrate deploy` would run destructive changes
+    #[arg(long)]
+    pub check: bool,
 }
 
 // =============================================================================
@@ -265,6 +554,21 @@ pub enum DbSubcommand {
 
     /// Execute raw SQL
     Execute(DbExecuteArgs),
+
+    /// Copy data between datasources
+    Copy(DbCopyArgs),
+
+    /// Checksum table data, or compare it between two datasources
+    Checksum(DbChecksumArgs),
+
+    /// Show the slowest queries, optionally with server-side statistics
+    TopQueries(DbTopQueriesArgs),
+
+    /// Suggest missing indexes for the recorded queries
+    AdviseIndexes(DbAdviseIndexesArgs),
+
+    /// Report unused and duplicate indexes and table bloat
+    Health(DbHealthArgs),
 }
 
 /// Arguments for `db push`
@@ -386,3 +690,190 @@ pub struct DbExecuteArgs {
     #[arg(short = 'y', long)]
     pub force: bool,
 }
+
+/// Arguments for `db copy`
+#[derive(Args, Debug)]
+pub struct DbCopyArgs {
+    /// Source: a name from [datasources] in prax.toml, `default`, or a URL
+    #[arg(long)]
+    pub from: String,
+
+    /// Target: a name from [datasources] in prax.toml, `default`, or a URL
+    #[arg(long)]
+    pub to: String,
+
+    /// Path to schema file
+    #[arg(short, long)]
+    pub schema: Option<PathBuf>,
+
+    /// Models to copy (defaults to all, parents before children)
+    #[arg(short, long, value_delimiter = ',')]
+    pub models: Vec<String>,
+
+    /// Rows per batch
+    #[arg(long, default_value_t = 1000)]
+    pub batch_size: usize,
+
+    /// Continue an interrupted copy from its checkpoint
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Checkpoint file
+    #[arg(long, default_value = ".prax-copy.json")]
+    pub checkpoint: PathBuf,
+}
+
+/// Arguments for `db checksum`
+#[derive(Args, Debug)]
+pub struct DbChecksumArgs {
+    /// Datasource: a name from [datasources] in prax.toml, `default`, or a URL
+    #[arg(long, default_value = "default")]
+    pub from: String,
+
+    /// Datasource to compare with, failing if any chunk differs
+    #[arg(long)]
+    pub against: Option<String>,
+
+    /// Path to schema file
+    #[arg(short, long)]
+    pub schema: Option<PathBuf>,
+
+    /// Models to checksum (defaults to all)
+    #[arg(short, long = "model", value_delimiter = ',')]
+    pub models: Vec<String>,
+
+    /// Rows per chunk
+    #[arg(long, default_value_t = 10000)]
+    pub chunk: usize,
+}
+
+/// Arguments for `db top-queries`
+#[derive(Args, Debug)]
+pub struct DbTopQueriesArgs {
+    /// Datasource: a name from [datasources] in prax.toml, `default`, or a URL
+    #[arg(long, default_value = "default")]
+    pub from: String,
+
+    /// Read pg_stat_statements (PostgreSQL) or performance_schema (MySQL)
+    #[arg(long)]
+    pub server: bool,
+
+    /// Client-side statistics written by `QueryStatsMiddleware::write_snapshot`
+    #[arg(long, default_value = ".prax-query-stats.json")]
+    pub stats: PathBuf,
+
+    /// Number of queries to show
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+/// Arguments for `db advise-indexes`
+#[derive(Args, Debug)]
+pub struct DbAdviseIndexesArgs {
+    /// Datasource: a name from [datasources] in prax.toml, `default`, or a URL
+    #[arg(long, default_value = "default")]
+    pub from: String,
+
+    /// Also read pg_stat_statements (PostgreSQL) or performance_schema (MySQL)
+    #[arg(long)]
+    pub server: bool,
+
+    /// Client-side statistics written by `QueryStatsMiddleware::write_snapshot`
+    #[arg(long, default_value = ".prax-query-stats.json")]
+    pub stats: PathBuf,
+
+    /// Path to schema file
+    #[arg(short, long)]
+    pub schema: Option<PathBuf>,
+
+    /// Number of server statements to analyze
+    #[arg(short = 'n', long, default_value_t = 500)]
+    pub limit: usize,
+
+    /// Estimate each index's benefit from EXPLAIN costs (PostgreSQL; builds
+    /// the index in a transaction that is rolled back)
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Print the `@@index` additions as a patch to the schema
+    #[arg(long)]
+    pub patch: bool,
+
+    /// Write the patch to a file instead of stdout
+    #[arg(short, long, requires = "patch")]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for `db health`
+#[derive(Args, Debug)]
+pub struct DbHealthArgs {
+    /// Datasource: a name from [datasources] in prax.toml, `default`, or a URL
+    #[arg(long, default_value = "default")]
+    pub from: String,
+
+    /// Path to schema file
+    #[arg(short, long)]
+    pub schema: Option<PathBuf>,
+
+    /// Share of dead rows, in percent, from which a table counts as bloated
+    #[arg(long, default_value_t = 20.0)]
+    pub bloat: f64,
+
+    /// Exit with an error when any problem is found
+    #[arg(long)]
+    pub check: bool,
+}
+
+// =============================================================================
+// Console Command
+// =============================================================================
+
+/// Arguments for the `console` command
+#[derive(Args, Debug)]
+pub struct ConsoleArgs {
+    /// Path to schema file (enables model queries and completion)
+    #[arg(short, long)]
+    pub schema: Option<PathBuf>,
+
+    /// Database connection URL (overrides prax.toml and DATABASE_URL)
+    #[arg(short, long)]
+    pub url: Option<String>,
+
+    /// Start with EXPLAIN output enabled
+    #[arg(long)]
+    pub explain: bool,
+}
+
+// =============================================================================
+// Analyze Log Command
+// =============================================================================
+
+/// Arguments for the `analyze-log` command
+#[derive(Args, Debug)]
+pub struct AnalyzeLogArgs {
+    /// Query log files written by `QueryLogMiddleware`, including rotated ones
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// What to group queries by
+    #[arg(long, default_value = "query")]
+    pub by: LogGroupBy,
+
+    /// Number of groups to show
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+/// Groupings for `prax analyze-log`
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogGroupBy {
+    /// Statement fingerprint
+    #[default]
+    Query,
+    /// Model
+    Model,
+    /// Tenant
+    Tenant,
+    /// Query tag
+    Tag,
+}
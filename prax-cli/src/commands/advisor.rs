@@ -0,0 +1,696 @@
+//! Index suggestions from observed queries (`prax db advise-indexes`).
+//!
+//! Each recorded statement is reduced to its [`FilterShape`]: the columns it
+//! compares for equality, the columns it sorts on, the columns it compares
+//! as a range and the constant predicates it always applies. A candidate
+//! index puts the equality columns first, then the sort columns, then the
+//! first range column, and turns constant predicates such as
+//! `deleted_at IS NULL` into a partial index. Candidates an existing `@id`,
+//! `@unique` or `@@index` already serves are dropped, and a candidate that
+//! is a prefix of a longer one is folded into it.
+//!
+//! Suggestions are ranked by the total time of the statements they serve.
+//! On PostgreSQL, `--explain` estimates the benefit by comparing generic plan
+//! costs with and without the index. The index is really built for that,
+//! inside a transaction that is rolled back, so run it against a replica or
+//! a copy when tables are large.
+
+use std::collections::HashMap;
+
+use prax_query::diagnostics::{FilterShape, QueryInsight};
+use prax_schema::ast::{AttributeValue, FieldType, Schema};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::commands::copy::{Datasource, Dialect};
+use crate::error::{CliError, CliResult};
+
+/// Lines of context around each hunk of a schema patch
+const CONTEXT_LINES: usize = 3;
+
+/// Row limit of the `--explain` probe for statements with a `LIMIT`
+const PROBE_LIMIT: usize = 100;
+
+/// A missing index
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexSuggestion {
+    /// Model name
+    pub model: String,
+    /// Table name
+    pub table: String,
+    /// Indexed fields, in index order
+    pub fields: Vec<String>,
+    /// Indexed columns, in index order
+    pub columns: Vec<String>,
+    /// Partial index predicate, where the dialect supports one
+    pub predicate: Option<String>,
+    /// Fingerprints of the statements the index serves
+    pub queries: Vec<String>,
+    /// Calls of those statements
+    pub calls: u64,
+    /// Total time of those statements in milliseconds
+    pub total_ms: f64,
+    /// Statement creating the index
+    pub sql: String,
+    /// Plan cost with and without the index (with `--explain`)
+    pub estimate: Option<CostEstimate>,
+    /// Query standing in for the served statements when explaining
+    #[serde(skip)]
+    probe: Probe,
+}
+
+/// Generic plan cost of a suggestion's heaviest statement
+#[derive(Debug, Clone, Serialize)]
+pub struct CostEstimate {
+    /// Total cost without the index
+    pub before: f64,
+    /// Total cost with the index
+    pub after: f64,
+    /// Cost reduction in percent
+    pub improvement: f64,
+}
+
+impl CostEstimate {
+    fn new(before: f64, after: f64) -> Self {
+        let improvement = if before > 0.0 {
+            (before - after) / before * 100.0
+        } else {
+            0.0
+        };
+        Self {
+            before,
+            after,
+            improvement,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Probe {
+    sql: String,
+    params: usize,
+    /// Total time of the statement it stands in for
+    total_ms: f64,
+}
+
+impl IndexSuggestion {
+    /// Index name, following the `<table>_<columns>_idx` convention
+    pub fn name(&self) -> String {
+        format!("{}_{}_idx", self.table, self.columns.join("_"))
+    }
+
+    /// The `@@index` attribute declaring this index
+    pub fn attribute(&self) -> String {
+        format!("@@index([{}])", self.fields.join(", "))
+    }
+}
+
+/// A model's table as the advisor sees it
+struct AdvisedTable<'a> {
+    model: &'a str,
+    table: &'a str,
+    /// Lowercase column name to (field, column)
+    columns: HashMap<String, (String, String)>,
+    /// Columns of existing indexes, in index order
+    indexes: Vec<Vec<String>>,
+}
+
+impl<'a> AdvisedTable<'a> {
+    fn new(schema: &'a Schema, model: &'a prax_schema::ast::Model) -> Self {
+        let mut columns = HashMap::new();
+        let mut indexes = Vec::new();
+        let mut field_columns = HashMap::new();
+        for field in model.fields.values() {
+            if matches!(&field.field_type, FieldType::Model(name) if schema.models.contains_key(name))
+            {
+                continue;
+            }
+            let attrs = field.extract_attributes();
+            let column = attrs.map.unwrap_or_else(|| field.name().to_string());
+            if attrs.is_id || attrs.is_unique || attrs.is_indexed {
+                indexes.push(vec![column.clone()]);
+            }
+            field_columns.insert(field.name().to_string(), column.clone());
+            columns.insert(
+                column.to_lowercase(),
+                (field.name().to_string(), column.clone()),
+            );
+        }
+
+        for attribute in &model.attributes {
+            if !matches!(attribute.name(), "id" | "unique" | "index") {
+                continue;
+            }
+            if let Some(AttributeValue::FieldRefList(fields)) = attribute.first_arg() {
+                indexes.push(
+                    fields
+                        .iter()
+                        .map(|f| {
+                            field_columns
+                                .get(f.as_str())
+                                .cloned()
+                                .unwrap_or_else(|| f.to_string())
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        Self {
+            model: model.name(),
+            table: model.table_name(),
+            columns,
+            indexes,
+        }
+    }
+
+    fn column(&self, name: &str) -> Option<&(String, String)> {
+        self.columns.get(&name.to_lowercase())
+    }
+
+    /// Whether an existing index serves `columns`, whose first `equality`
+    /// columns may appear in any order.
+    fn covers(&self, columns: &[String], equality: usize) -> bool {
+        self.indexes.iter().any(|index| {
+            index.len() >= columns.len()
+                && columns[..equality]
+                    .iter()
+                    .all(|c| index[..equality].contains(c))
+                && index[equality..columns.len()] == columns[equality..]
+        })
+    }
+}
+
+/// Suggest missing indexes for the statements in `insights`.
+pub fn advise(
+    schema: &Schema,
+    insights: &[QueryInsight],
+    dialect: Dialect,
+) -> Vec<IndexSuggestion> {
+    let tables: Vec<AdvisedTable<'_>> = schema
+        .models
+        .values()
+        .map(|model| AdvisedTable::new(schema, model))
+        .collect();
+
+    let mut suggestions: Vec<IndexSuggestion> = Vec::new();
+    for insight in insights {
+        let Some(shape) = FilterShape::parse(&insight.query) else {
+            continue;
+        };
+        let Some(table) = tables
+            .iter()
+            .find(|t| t.table.eq_ignore_ascii_case(&shape.table))
+        else {
+            continue;
+        };
+        let Some(suggestion) = suggest(table, &shape, dialect) else {
+            continue;
+        };
+        let (calls, total_ms) = match (&insight.server, &insight.client) {
+            (Some(server), _) => (server.calls, server.total_ms),
+            (None, Some(client)) => (client.calls, client.total_ms),
+            (None, None) => (0, 0.0),
+        };
+
+        match suggestions.iter_mut().find(|s| {
+            s.table == suggestion.table
+                && s.columns == suggestion.columns
+                && s.predicate == suggestion.predicate
+        }) {
+            Some(existing) => {
+                // Explain the heaviest statement
+                if total_ms > existing.probe.total_ms {
+                    existing.probe = Probe {
+                        total_ms,
+                        ..suggestion.probe
+                    };
+                }
+                existing.queries.push(insight.fingerprint.clone());
+                existing.calls += calls;
+                existing.total_ms += total_ms;
+            }
+            None => suggestions.push(IndexSuggestion {
+                queries: vec![insight.fingerprint.clone()],
+                calls,
+                total_ms,
+                probe: Probe {
+                    total_ms,
+                    ..suggestion.probe
+                },
+                ..suggestion
+            }),
+        }
+    }
+
+    // Fold candidates into longer ones that start with the same columns
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.columns.len()));
+    let mut folded: Vec<IndexSuggestion> = Vec::new();
+    for suggestion in suggestions {
+        match folded.iter_mut().find(|longer| {
+            longer.table == suggestion.table
+                && longer.predicate == suggestion.predicate
+                && longer.columns.starts_with(&suggestion.columns)
+        }) {
+            Some(longer) => {
+                longer.queries.extend(suggestion.queries);
+                longer.calls += suggestion.calls;
+                longer.total_ms += suggestion.total_ms;
+            }
+            None => folded.push(suggestion),
+        }
+    }
+
+    folded.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+    folded
+}
+
+/// The index serving one statement, unless one already exists
+fn suggest(
+    table: &AdvisedTable<'_>,
+    shape: &FilterShape,
+    dialect: Dialect,
+) -> Option<IndexSuggestion> {
+    let mut columns: Vec<&(String, String)> = Vec::new();
+    for name in &shape.equality {
+        let column = table.column(name)?;
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    let equality = columns.len();
+
+    let sort: Option<Vec<&(String, String)>> =
+        shape.sort.iter().map(|s| table.column(&s.column)).collect();
+    // Sorting an unfiltered table only pays off when few rows are read
+    if let Some(sort) = sort.filter(|_| equality > 0 || shape.limited) {
+        for column in sort {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+    }
+    if let Some(column) = shape.range.iter().find_map(|name| table.column(name)) {
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    if columns.is_empty() {
+        return None;
+    }
+
+    let names: Vec<String> = columns.iter().map(|(_, column)| column.clone()).collect();
+    if table.covers(&names, equality) {
+        return None;
+    }
+
+    let constants: Vec<String> = shape
+        .constant
+        .iter()
+        .filter_map(|predicate| {
+            let (name, rest) = predicate.split_once(' ')?;
+            let (_, column) = table.column(name)?;
+            Some(format!("{} {}", dialect.quote(column), rest.to_uppercase()))
+        })
+        .collect();
+    // MySQL has no partial indexes
+    let predicate =
+        (!constants.is_empty() && dialect != Dialect::MySql).then(|| constants.join(" AND "));
+
+    let mut suggestion = IndexSuggestion {
+        model: table.model.to_string(),
+        table: table.table.to_string(),
+        fields: columns.iter().map(|(field, _)| field.clone()).collect(),
+        columns: names,
+        predicate,
+        queries: Vec::new(),
+        calls: 0,
+        total_ms: 0.0,
+        sql: String::new(),
+        estimate: None,
+        probe: probe(table, shape, &constants, dialect),
+    };
+    suggestion.sql = create_index_sql(&suggestion, dialect);
+    Some(suggestion)
+}
+
+/// `CREATE INDEX` statement for a suggestion
+pub fn create_index_sql(suggestion: &IndexSuggestion, dialect: Dialect) -> String {
+    let columns: Vec<String> = suggestion
+        .columns
+        .iter()
+        .map(|c| dialect.quote(c))
+        .collect();
+    let mut sql = format!(
+        "CREATE INDEX {} ON {} ({})",
+        dialect.quote(&suggestion.name()),
+        dialect.quote(&suggestion.table),
+        columns.join(", ")
+    );
+    if let Some(predicate) = &suggestion.predicate {
+        sql.push_str(" WHERE ");
+        sql.push_str(predicate);
+    }
+    sql.push(';');
+    sql
+}
+
+/// A parameterized query with the statement's filter and sort, for
+/// PostgreSQL's `PREPARE`
+fn probe(
+    table: &AdvisedTable<'_>,
+    shape: &FilterShape,
+    constants: &[String],
+    dialect: Dialect,
+) -> Probe {
+    let mut params = 0;
+    let mut conditions = Vec::new();
+    let mut param = |column: &str, operator: &str| {
+        params += 1;
+        format!("{} {} ${}", dialect.quote(column), operator, params)
+    };
+    for (_, column) in shape.equality.iter().filter_map(|name| table.column(name)) {
+        conditions.push(param(column, "="));
+    }
+    for (_, column) in shape.range.iter().filter_map(|name| table.column(name)) {
+        conditions.push(param(column, ">"));
+    }
+    conditions.extend(constants.iter().cloned());
+
+    let mut sql = format!("SELECT * FROM {}", dialect.quote(table.table));
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    let order: Vec<String> = shape
+        .sort
+        .iter()
+        .filter_map(|sort| {
+            let (_, column) = table.column(&sort.column)?;
+            let direction = if sort.descending { " DESC" } else { "" };
+            Some(format!("{}{}", dialect.quote(column), direction))
+        })
+        .collect();
+    if !order.is_empty() {
+        sql.push_str(" ORDER BY ");
+        sql.push_str(&order.join(", "));
+    }
+    if shape.limited {
+        sql.push_str(&format!(" LIMIT {}", PROBE_LIMIT));
+    }
+    Probe {
+        sql,
+        params,
+        total_ms: 0.0,
+    }
+}
+
+/// Estimate the benefit of a suggestion from PostgreSQL generic plan costs.
+///
+/// The index is created inside a transaction that is rolled back.
+pub fn estimate(datasource: &Datasource, suggestion: &IndexSuggestion) -> CliResult<CostEstimate> {
+    if datasource.dialect != Dialect::Postgres {
+        return Err(CliError::Config(format!(
+            "--explain needs PostgreSQL, not {}",
+            datasource.dialect
+        )));
+    }
+
+    let execute = if suggestion.probe.params == 0 {
+        "EXECUTE prax_advise".to_string()
+    } else {
+        format!(
+            "EXECUTE prax_advise({})",
+            vec!["NULL"; suggestion.probe.params].join(", ")
+        )
+    };
+    let script = format!(
+        "BEGIN;\n\
+         SET LOCAL lock_timeout = '5s';\n\
+         SET LOCAL plan_cache_mode = force_generic_plan;\n\
+         PREPARE prax_advise AS {probe};\n\
+         EXPLAIN (FORMAT JSON) {execute};\n\
+         {create}\n\
+         EXPLAIN (FORMAT JSON) {execute};\n\
+         ROLLBACK;\n",
+        probe = suggestion.probe.sql,
+        create = suggestion.sql,
+    );
+
+    let output = datasource.execute(&script)?;
+    let costs = serde_json::Deserializer::from_str(&output)
+        .into_iter::<Value>()
+        .map(|plan| {
+            plan.ok()
+                .and_then(|plan| plan[0]["Plan"]["Total Cost"].as_f64())
+                .ok_or_else(|| {
+                    CliError::Database(format!(
+                        "Unexpected EXPLAIN output from {}",
+                        datasource.name
+                    ))
+                })
+        })
+        .collect::<CliResult<Vec<f64>>>()?;
+    match costs[..] {
+        [before, after] => Ok(CostEstimate::new(before, after)),
+        _ => Err(CliError::Database(format!(
+            "Unexpected EXPLAIN output from {}",
+            datasource.name
+        ))),
+    }
+}
+
+/// A unified diff adding the suggested `@@index` attributes to the schema.
+///
+/// `@@index` has no predicate argument, so partial indexes get a comment
+/// with their `WHERE` clause for the migration to pick up by hand.
+pub fn schema_patch(
+    source: &str,
+    schema: &Schema,
+    path: &str,
+    suggestions: &[IndexSuggestion],
+) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let line_of = |offset: usize| source[..offset].matches('\n').count();
+
+    // Insertions before the closing brace of each model
+    let mut insertions: Vec<(usize, Vec<String>)> = Vec::new();
+    for model in schema.models.values() {
+        let added: Vec<&IndexSuggestion> = suggestions
+            .iter()
+            .filter(|s| s.model == model.name())
+            .collect();
+        let Some(brace) = source
+            .get(model.span.start..model.span.end)
+            .and_then(|text| text.rfind('}'))
+        else {
+            continue;
+        };
+        if added.is_empty() {
+            continue;
+        }
+        let at = line_of(model.span.start + brace);
+
+        let previous = lines[..at].iter().rev().find(|l| !l.trim().is_empty());
+        let indent = previous
+            .filter(|l| !l.trim_start().starts_with("model "))
+            .map(|l| &l[..l.len() - l.trim_start().len()])
+            .unwrap_or("    ");
+        let mut block = Vec::new();
+        if previous.is_some_and(|l| !l.trim_start().starts_with("@@")) {
+            block.push(String::new());
+        }
+        for suggestion in added {
+            if let Some(predicate) = &suggestion.predicate {
+                block.push(format!("{}// partial: WHERE {}", indent, predicate));
+            }
+            block.push(format!("{}{}", indent, suggestion.attribute()));
+        }
+        insertions.push((at, block));
+    }
+    insertions.sort_by_key(|(at, _)| *at);
+    if insertions.is_empty() {
+        return String::new();
+    }
+
+    // Group insertions whose context overlaps into one hunk
+    let mut hunks: Vec<Vec<&(usize, Vec<String>)>> = Vec::new();
+    for insertion in &insertions {
+        match hunks.last_mut() {
+            Some(hunk) if insertion.0 - hunk[hunk.len() - 1].0 <= 2 * CONTEXT_LINES => {
+                hunk.push(insertion)
+            }
+            _ => hunks.push(vec![insertion]),
+        }
+    }
+
+    let mut patch = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut added = 0;
+    for hunk in hunks {
+        let start = hunk[0].0.saturating_sub(CONTEXT_LINES);
+        let end = (hunk[hunk.len() - 1].0 + CONTEXT_LINES).min(lines.len());
+        let inserted: usize = hunk.iter().map(|(_, block)| block.len()).sum();
+        patch.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start + 1,
+            end - start,
+            start + 1 + added,
+            end - start + inserted
+        ));
+        for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+            for (_, block) in hunk.iter().filter(|(at, _)| *at == i) {
+                for added_line in block {
+                    patch.push('+');
+                    patch.push_str(added_line);
+                    patch.push('\n');
+                }
+            }
+            patch.push(' ');
+            patch.push_str(line);
+            patch.push('\n');
+        }
+        added += inserted;
+    }
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prax_query::diagnostics::StatementStats;
+
+    const SCHEMA: &str = r#"model User {
+    id    Int    @id @auto
+    email String @unique
+    name  String
+}
+
+model Post {
+    id        Int       @id @auto
+    authorId  Int       @map("author_id")
+    status    String
+    createdAt DateTime  @map("created_at")
+    deletedAt DateTime? @map("deleted_at")
+
+    @@map("posts")
+    @@index([status])
+}
+"#;
+
+    fn insight(query: &str, calls: u64, total_ms: f64) -> QueryInsight {
+        QueryInsight {
+            fingerprint: prax_query::diagnostics::fingerprint(query),
+            query: query.to_string(),
+            model: None,
+            client: Some(StatementStats {
+                fingerprint: prax_query::diagnostics::fingerprint(query),
+                query: query.to_string(),
+                model: None,
+                calls,
+                errors: 0,
+                total_ms,
+                mean_ms: total_ms / calls as f64,
+                max_ms: total_ms,
+            }),
+            server: None,
+        }
+    }
+
+    fn schema() -> Schema {
+        prax_schema::validate_schema(SCHEMA).unwrap()
+    }
+
+    #[test]
+    fn test_advise_orders_columns_and_skips_covered() {
+        let insights = [
+            insight(
+                r#"SELECT * FROM "posts" WHERE "author_id" = $1 AND "deleted_at" IS NULL ORDER BY "created_at" DESC LIMIT 10"#,
+                100,
+                900.0,
+            ),
+            // Folded into the index above
+            insight(
+                r#"SELECT * FROM "posts" WHERE "author_id" = $1 AND "deleted_at" IS NULL"#,
+                10,
+                50.0,
+            ),
+            // Served by @unique and @@index
+            insight(r#"SELECT * FROM "User" WHERE "email" = $1"#, 500, 400.0),
+            insight(r#"SELECT * FROM "posts" WHERE "status" = $1"#, 500, 400.0),
+            insight(r#"SELECT * FROM "User" WHERE "name" LIKE $1"#, 5, 100.0),
+        ];
+
+        let suggestions = advise(&schema(), &insights, Dialect::Postgres);
+        assert_eq!(suggestions.len(), 2);
+
+        let posts = &suggestions[0];
+        assert_eq!(posts.model, "Post");
+        assert_eq!(posts.fields, ["authorId", "createdAt"]);
+        assert_eq!(posts.columns, ["author_id", "created_at"]);
+        assert_eq!(posts.predicate.as_deref(), Some(r#""deleted_at" IS NULL"#));
+        assert_eq!(posts.calls, 110);
+        assert_eq!(posts.queries.len(), 2);
+        assert_eq!(
+            posts.sql,
+            r#"CREATE INDEX "posts_author_id_created_at_idx" ON "posts" ("author_id", "created_at") WHERE "deleted_at" IS NULL;"#
+        );
+        assert_eq!(
+            posts.probe.sql,
+            r#"SELECT * FROM "posts" WHERE "author_id" = $1 AND "deleted_at" IS NULL ORDER BY "created_at" DESC LIMIT 100"#
+        );
+
+        assert_eq!(suggestions[1].attribute(), "@@index([name])");
+    }
+
+    #[test]
+    fn test_advise_mysql_drops_predicate() {
+        let insights = [insight(
+            "SELECT * FROM `posts` WHERE `author_id` = ? AND `deleted_at` IS NULL",
+            1,
+            1.0,
+        )];
+        let suggestions = advise(&schema(), &insights, Dialect::MySql);
+        assert_eq!(suggestions[0].predicate, None);
+        assert_eq!(
+            suggestions[0].sql,
+            "CREATE INDEX `posts_author_id_idx` ON `posts` (`author_id`);"
+        );
+    }
+
+    #[test]
+    fn test_schema_patch() {
+        let schema = schema();
+        let insights = [
+            insight(r#"SELECT * FROM "User" WHERE "name" = $1"#, 1, 2.0),
+            insight(
+                r#"SELECT * FROM "posts" WHERE "author_id" = $1 AND "deleted_at" IS NULL"#,
+                1,
+                1.0,
+            ),
+        ];
+        let suggestions = advise(&schema, &insights, Dialect::Postgres);
+        let patch = schema_patch(SCHEMA, &schema, "schema.prax", &suggestions);
+
+        let expected = [
+            "--- a/schema.prax",
+            "+++ b/schema.prax",
+            "@@ -2,6 +2,8 @@",
+            "     id    Int    @id @auto",
+            "     email String @unique",
+            "     name  String",
+            "+",
+            "+    @@index([name])",
+            " }",
+            " ",
+            " model Post {",
+            "@@ -13,4 +15,6 @@",
+            " ",
+            r#"     @@map("posts")"#,
+            "     @@index([status])",
+            r#"+    // partial: WHERE "deleted_at" IS NULL"#,
+            "+    @@index([authorId])",
+            " }",
+        ];
+        assert_eq!(patch, expected.join("\n") + "\n");
+    }
+}
@@ -0,0 +1,107 @@
+//! `prax analyze-log` command - Aggregate a JSONL query log into a report.
+
+use prax_query::query_log::{LogGrouping, LogSummary, QueryLogRecord, summarize};
+use serde::Serialize;
+
+use crate::cli::{AnalyzeLogArgs, LogGroupBy};
+use crate::error::{CliError, CliResult};
+use crate::output;
+
+/// Machine-readable result of `prax analyze-log`
+#[derive(Debug, Serialize)]
+pub struct AnalyzeLogReport {
+    /// Log files read
+    pub files: Vec<String>,
+    /// Records read
+    pub records: usize,
+    /// What queries are grouped by
+    pub by: LogGrouping,
+    /// Groups by total time
+    pub groups: Vec<LogSummary>,
+}
+
+/// Run the analyze-log command
+pub async fn run(args: AnalyzeLogArgs) -> CliResult<()> {
+    output::header("Query Log");
+
+    let mut records = Vec::new();
+    for file in &args.files {
+        let read = QueryLogRecord::read_log(file).map_err(|e| {
+            CliError::Config(format!("Cannot read query log {}: {}", file.display(), e))
+        })?;
+        records.extend(read);
+    }
+
+    let by = match args.by {
+        LogGroupBy::Query => LogGrouping::Query,
+        LogGroupBy::Model => LogGrouping::Model,
+        LogGroupBy::Tenant => LogGrouping::Tenant,
+        LogGroupBy::Tag => LogGrouping::Tag,
+    };
+    let mut groups = summarize(&records, by);
+    groups.truncate(args.limit);
+
+    output::kv("Files", &args.files.len().to_string());
+    output::kv("Records", &records.len().to_string());
+    output::newline();
+
+    if groups.is_empty() {
+        output::info("No queries logged.");
+    } else {
+        let key = match by {
+            LogGrouping::Query | LogGrouping::Model => "Model",
+            LogGrouping::Tenant => "Tenant",
+            LogGrouping::Tag => "Tag",
+        };
+        let mut headers: Vec<String> = [
+            key, "Calls", "Errors", "Rows", "Mean ms", "p95 ms", "p99 ms", "Total ms",
+        ]
+        .map(String::from)
+        .to_vec();
+        if by == LogGrouping::Query {
+            headers.push("Query".to_string());
+        }
+
+        let ms = |value: f64| format!("{:.2}", value);
+        let rows: Vec<Vec<String>> = groups
+            .iter()
+            .map(|group| {
+                let name = match by {
+                    LogGrouping::Query => group.model.clone(),
+                    _ => Some(group.key.clone()).filter(|key| !key.is_empty()),
+                };
+                let mut row = vec![
+                    name.unwrap_or_else(|| "-".to_string()),
+                    group.calls.to_string(),
+                    group.errors.to_string(),
+                    group.rows.to_string(),
+                    ms(group.mean_ms),
+                    ms(group.p95_ms),
+                    ms(group.p99_ms),
+                    ms(group.total_ms),
+                ];
+                if let Some(query) = &group.query {
+                    let mut short: String = query.chars().take(80).collect();
+                    if short.len() < query.len() {
+                        short.push('…');
+                    }
+                    row.push(short);
+                }
+                row
+            })
+            .collect();
+        output::table(&headers, &rows);
+    }
+
+    output::report(&AnalyzeLogReport {
+        files: args
+            .files
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect(),
+        records: records.len(),
+        by,
+        groups,
+    });
+    Ok(())
+}
@@ -0,0 +1,403 @@
+//! Table checksums for verifying copies and replicas (`prax db checksum`).
+//!
+//! Rows are read in primary-key order, in chunks of a fixed number of rows,
+//! and every value is serialized canonically before hashing: numbers and
+//! decimals without insignificant digits, booleans as `TRUE`/`FALSE`,
+//! timestamps in UTC, bytes as lowercase hex and JSON with sorted keys. The
+//! same data hashes alike on PostgreSQL, MySQL, SQLite and DuckDB, whichever
+//! column types each of them stores it in.
+//!
+//! Comparing two datasources checksums the second over the key ranges of
+//! the first's chunks, so a missing, extra or changed row only changes the
+//! chunk it falls in. This relies on both databases ordering keys alike,
+//! which holds for numeric keys and for text keys in binary collations.
+//! Tables without a single-column key are chunked by row position instead.
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::commands::copy::{ColumnKind, CopyColumn, CopyTable, Datasource, Dialect, coerce};
+use crate::error::{CliError, CliResult};
+
+/// Checksum of a run of rows in key order
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkChecksum {
+    /// Key of the first row, for single-column keys
+    pub first_key: Option<Value>,
+    /// Key of the last row, for single-column keys
+    pub last_key: Option<Value>,
+    /// Number of rows
+    pub rows: u64,
+    /// SHA-256 of the canonical rows
+    pub hash: String,
+}
+
+/// Checksums of a model's table
+#[derive(Debug, Clone, Serialize)]
+pub struct TableChecksum {
+    /// Model name
+    pub model: String,
+    /// Table name
+    pub table: String,
+    /// Number of rows
+    pub rows: u64,
+    /// SHA-256 of all canonical rows, whatever the chunk size
+    pub hash: String,
+    /// Chunks in key order
+    pub chunks: Vec<ChunkChecksum>,
+}
+
+impl TableChecksum {
+    /// Indexes of the chunks that differ from `other`'s
+    pub fn mismatched_chunks(&self, other: &TableChecksum) -> Vec<usize> {
+        (0..self.chunks.len().max(other.chunks.len()))
+            .filter(|&i| match (self.chunks.get(i), other.chunks.get(i)) {
+                (Some(a), Some(b)) => a.rows != b.rows || a.hash != b.hash,
+                _ => true,
+            })
+            .collect()
+    }
+}
+
+/// Checksum a table in chunks of `chunk_size` rows
+pub fn checksum_table(
+    datasource: &Datasource,
+    table: &CopyTable,
+    chunk_size: usize,
+) -> CliResult<TableChecksum> {
+    let chunk_size = chunk_size.max(1);
+    let mut checksum = TableHasher::new(table);
+
+    loop {
+        let after = checksum
+            .last_key()
+            .map(|key| key_literal(table, key, datasource.dialect))
+            .transpose()?;
+        let sql = table.select_sql(
+            datasource.dialect,
+            after.as_deref(),
+            checksum.rows,
+            chunk_size,
+        );
+        let rows = read_rows(datasource, table, &sql)?;
+        if rows.is_empty() {
+            break;
+        }
+
+        checksum.push_chunk(&rows);
+        if rows.len() < chunk_size {
+            break;
+        }
+    }
+
+    Ok(checksum.finish())
+}
+
+/// Checksum a table over the key ranges of `reference`'s chunks, so that
+/// each chunk covers the same keys on both datasources
+pub fn checksum_ranges(
+    datasource: &Datasource,
+    table: &CopyTable,
+    reference: &TableChecksum,
+    chunk_size: usize,
+) -> CliResult<TableChecksum> {
+    if table.key().is_none() {
+        return checksum_table(datasource, table, chunk_size);
+    }
+
+    let mut checksum = TableHasher::new(table);
+    let count = reference.chunks.len().max(1);
+    for i in 0..count {
+        let bound = |key: Option<&Value>| {
+            key.map(|key| key_literal(table, key, datasource.dialect))
+                .transpose()
+        };
+        let after = bound(
+            i.checked_sub(1)
+                .and_then(|prev| reference.chunks[prev].last_key.as_ref()),
+        )?;
+        // The last chunk also takes any rows past the reference's last key
+        let upto = bound(
+            reference
+                .chunks
+                .get(i)
+                .filter(|_| i + 1 < count)
+                .and_then(|chunk| chunk.last_key.as_ref()),
+        )?;
+
+        let sql = table.select_range_sql(datasource.dialect, after.as_deref(), upto.as_deref());
+        let rows = read_rows(datasource, table, &sql)?;
+        checksum.push_chunk(&rows);
+    }
+
+    Ok(checksum.finish())
+}
+
+/// Hashes chunks, and the whole table across them
+struct TableHasher<'a> {
+    table: &'a CopyTable,
+    hasher: Sha256,
+    rows: u64,
+    chunks: Vec<ChunkChecksum>,
+}
+
+impl<'a> TableHasher<'a> {
+    fn new(table: &'a CopyTable) -> Self {
+        Self {
+            table,
+            hasher: Sha256::new(),
+            rows: 0,
+            chunks: Vec::new(),
+        }
+    }
+
+    fn last_key(&self) -> Option<&Value> {
+        self.chunks.last()?.last_key.as_ref()
+    }
+
+    fn push_chunk(&mut self, rows: &[Vec<Value>]) {
+        let mut chunk = Sha256::new();
+        for row in rows {
+            let line = canonical_row(self.table, row);
+            for hasher in [&mut chunk, &mut self.hasher] {
+                hasher.update(line.as_bytes());
+                hasher.update(b"\n");
+            }
+        }
+
+        let key = |row: Option<&Vec<Value>>| Some(row?.get(self.table.key()?)?.clone());
+        self.rows += rows.len() as u64;
+        self.chunks.push(ChunkChecksum {
+            first_key: key(rows.first()),
+            last_key: key(rows.last()),
+            rows: rows.len() as u64,
+            hash: hex(chunk),
+        });
+    }
+
+    fn finish(self) -> TableChecksum {
+        TableChecksum {
+            model: self.table.model.clone(),
+            table: self.table.table.clone(),
+            rows: self.rows,
+            hash: hex(self.hasher),
+            chunks: self.chunks,
+        }
+    }
+}
+
+fn read_rows(datasource: &Datasource, table: &CopyTable, sql: &str) -> CliResult<Vec<Vec<Value>>> {
+    Ok(datasource
+        .query_json(sql)?
+        .into_iter()
+        .map(|row| table.row_values(row))
+        .collect())
+}
+
+fn key_literal(table: &CopyTable, key: &Value, dialect: Dialect) -> CliResult<String> {
+    let column = table.key().map(|i| &table.columns[i]).ok_or_else(|| {
+        CliError::Database(format!("{}: no single-column primary key", table.model))
+    })?;
+    coerce(key, column, dialect).map_err(|e| CliError::Database(format!("{}: {}", table.model, e)))
+}
+
+fn hex(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Serialize a row so that the same data reads alike from every database
+pub fn canonical_row(table: &CopyTable, row: &[Value]) -> String {
+    let values = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| canonical_value(row.get(i).unwrap_or(&Value::Null), column))
+        .collect();
+    Value::Array(values).to_string()
+}
+
+fn canonical_value(value: &Value, column: &CopyColumn) -> Value {
+    if value.is_null() {
+        return Value::Null;
+    }
+
+    let canonical = match (column.kind, column.list) {
+        (ColumnKind::Float, false) => float(value).map(|f| f.to_string()),
+        (ColumnKind::Decimal, false) => decimal(value),
+        (ColumnKind::Json, false) => Some(canonical_json(&match value {
+            // JSON stored as text
+            Value::String(text) => serde_json::from_str(text).unwrap_or_else(|_| value.clone()),
+            other => other.clone(),
+        })),
+        // The PostgreSQL literal is already normalized: integers, booleans,
+        // UTC timestamps, hex bytes and lists in one form
+        _ => coerce(value, column, Dialect::Postgres).ok(),
+    };
+    // A value that doesn't fit its type still counts, as it was read
+    Value::String(canonical.unwrap_or_else(|| format!("invalid:{}", value)))
+}
+
+fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A decimal without leading or trailing zeros, so `1.50` and `1.5` agree
+fn decimal(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.trim().to_string(),
+        _ => return None,
+    };
+    if text.contains(['e', 'E']) {
+        return text.parse::<f64>().ok().map(|f| f.to_string());
+    }
+
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if int.is_empty() && frac.is_empty()
+        || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    let mut canonical = String::new();
+    if negative && !(int.is_empty() && frac.is_empty()) {
+        canonical.push('-');
+    }
+    canonical.push_str(if int.is_empty() { "0" } else { int });
+    if !frac.is_empty() {
+        canonical.push('.');
+        canonical.push_str(frac);
+    }
+    Some(canonical)
+}
+
+/// JSON with sorted object keys and numbers by value
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Object(map) => {
+            let mut fields: Vec<(&String, &Value)> = map.iter().collect();
+            fields.sort_by_key(|(name, _)| *name);
+            let fields: Vec<String> = fields
+                .into_iter()
+                .map(|(name, value)| format!("{:?}:{}", name, canonical_json(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.to_string(),
+            None => n.as_f64().map(|f| f.to_string()).unwrap_or_default(),
+        },
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table() -> CopyTable {
+        let column = |name: &str, kind| CopyColumn {
+            name: name.to_string(),
+            kind,
+            optional: true,
+            list: false,
+        };
+        CopyTable {
+            model: "Order".to_string(),
+            table: "orders".to_string(),
+            columns: vec![
+                column("id", ColumnKind::Int),
+                column("total", ColumnKind::Decimal),
+                column("paid", ColumnKind::Boolean),
+                column("placed_at", ColumnKind::DateTime),
+                column("meta", ColumnKind::Json),
+                column("ratio", ColumnKind::Float),
+            ],
+            primary_key: vec![0],
+            auto_key: true,
+        }
+    }
+
+    #[test]
+    fn test_canonical_row_across_dialects() {
+        let table = table();
+        // As PostgreSQL's row_to_json returns them
+        let postgres = canonical_row(
+            &table,
+            &[
+                json!(1),
+                json!("19.50"),
+                json!(true),
+                json!("2024-03-01T12:00:00+00:00"),
+                json!({"b": 1, "a": [1.0, "x"]}),
+                json!(0.5),
+            ],
+        );
+        // As MySQL's and SQLite's JSON_ARRAY return them
+        let mysql = canonical_row(
+            &table,
+            &[
+                json!("1"),
+                json!("19.5"),
+                json!(1),
+                json!("2024-03-01 12:00:00.000000"),
+                json!("{\"a\": [1, \"x\"], \"b\": 1}"),
+                json!("0.50"),
+            ],
+        );
+
+        assert_eq!(postgres, mysql);
+        assert_ne!(postgres, canonical_row(&table, &[json!(1), json!("19.51")]));
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(decimal(&json!("0010.500")).as_deref(), Some("10.5"));
+        assert_eq!(decimal(&json!("-0.00")).as_deref(), Some("0"));
+        assert_eq!(decimal(&json!(".25")).as_deref(), Some("0.25"));
+        assert_eq!(decimal(&json!(3)).as_deref(), Some("3"));
+        assert_eq!(decimal(&json!("abc")), None);
+    }
+
+    #[test]
+    fn test_mismatched_chunks() {
+        let chunk = |rows, hash: &str| ChunkChecksum {
+            first_key: None,
+            last_key: None,
+            rows,
+            hash: hash.to_string(),
+        };
+        let checksum = |chunks| TableChecksum {
+            model: "Order".to_string(),
+            table: "orders".to_string(),
+            rows: 0,
+            hash: String::new(),
+            chunks,
+        };
+
+        let source = checksum(vec![chunk(2, "a"), chunk(2, "b")]);
+        let target = checksum(vec![chunk(2, "a"), chunk(2, "c"), chunk(1, "d")]);
+        assert_eq!(source.mismatched_chunks(&target), vec![1, 2]);
+        assert!(source.mismatched_chunks(&source).is_empty());
+    }
+}
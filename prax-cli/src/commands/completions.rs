@@ -0,0 +1,14 @@
+//! `prax completions` command - Generate shell completion scripts.
+
+use clap::CommandFactory;
+
+use crate::cli::{Cli, CompletionsArgs};
+use crate::error::CliResult;
+
+/// Run the completions command
+pub async fn run(args: CompletionsArgs) -> CliResult<()> {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(args.shell, &mut command, name, &mut std::io::stdout());
+    Ok(())
+}
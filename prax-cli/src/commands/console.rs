@@ -0,0 +1,897 @@
+//! `prax console` command - Interactive SQL and model query REPL.
+//!
+//! The console accepts three kinds of input:
+//!
+//! - Meta commands prefixed with `.` (e.g. `.models`, `.explain`, `.quit`)
+//! - Model queries using a small query language:
+//!   `find User where email = 'a@b.c' and age > 18 limit 10` or
+//!   `count Post where published = true`
+//! - Anything else is sent to the database as raw SQL.
+//!
+//! Model and field names are resolved against the schema so `@@map` and
+//! `@map` attributes are honored, and tab completion is driven by the schema.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use prax_query::sql::DatabaseType;
+
+use crate::cli::ConsoleArgs;
+use crate::commands::introspect::{get_database_type, quote_identifier};
+use crate::commands::seed::get_database_url;
+use crate::config::{CONFIG_FILE_NAME, Config, SCHEMA_FILE_NAME};
+use crate::error::{CliError, CliResult};
+use crate::output::{self, success, warn};
+
+/// Keywords offered by tab completion.
+const KEYWORDS: &[&str] = &[
+    "find",
+    "count",
+    "where",
+    "and",
+    "limit",
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "ORDER BY",
+    "GROUP BY",
+    "LIMIT",
+    "INSERT INTO",
+    "UPDATE",
+    "DELETE FROM",
+    "EXPLAIN",
+];
+
+/// Meta commands understood by the console.
+const META_COMMANDS: &[&str] = &[".help", ".models", ".fields", ".explain", ".quit", ".exit"];
+
+/// Run the console command
+pub async fn run(args: ConsoleArgs) -> CliResult<()> {
+    output::header("Prax Console");
+
+    let cwd = std::env::current_dir()?;
+    let config = load_config(&cwd)?;
+    let schema_path = args.schema.unwrap_or_else(|| cwd.join(SCHEMA_FILE_NAME));
+
+    let schema = if schema_path.exists() {
+        let content = std::fs::read_to_string(&schema_path)?;
+        Some(parse_schema(&content)?)
+    } else {
+        warn(&format!(
+            "Schema file not found: {}. Model queries are disabled.",
+            schema_path.display()
+        ));
+        None
+    };
+
+    let database_url = match args.url {
+        Some(url) => url,
+        None => get_database_url(&config)?,
+    };
+    let db_type = get_database_type(&config.database.provider)?;
+
+    output::kv("Provider", &config.database.provider);
+    output::kv("Schema", &schema_path.display().to_string());
+    output::newline();
+    output::dim("Type .help for usage, .quit to exit.");
+    output::newline();
+
+    let executor = ConsoleExecutor::connect(&database_url, db_type).await?;
+    let mut session = ConsoleSession {
+        schema,
+        db_type,
+        explain: args.explain,
+    };
+
+    let mut editor: Editor<ConsoleHelper, rustyline::history::DefaultHistory> =
+        Editor::new().map_err(|e| CliError::Command(format!("Failed to start console: {}", e)))?;
+    editor.set_helper(Some(ConsoleHelper::new(session.schema.as_ref())));
+
+    loop {
+        let line = match editor.readline("prax> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(CliError::Command(format!("Failed to read input: {}", e))),
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        match parse_input(line) {
+            Ok(ConsoleInput::Meta(MetaCommand::Quit)) => break,
+            Ok(ConsoleInput::Meta(meta)) => session.handle_meta(meta),
+            Ok(input) => match session.to_sql(input) {
+                Ok(sql) => match executor.execute(&sql).await {
+                    Ok(result) => print_result(&result),
+                    Err(e) => output::error(&e.to_string()),
+                },
+                Err(e) => output::error(&e.to_string()),
+            },
+            Err(e) => output::error(&e.to_string()),
+        }
+    }
+
+    output::newline();
+    success("Bye!");
+
+    Ok(())
+}
+
+// =============================================================================
+// Input Parsing
+// =============================================================================
+
+/// A parsed line of console input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleInput {
+    /// A `.`-prefixed meta command.
+    Meta(MetaCommand),
+    /// A model query (`find` / `count`).
+    Model(ModelQuery),
+    /// Raw SQL passed through to the database.
+    Sql(String),
+}
+
+/// Console meta commands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetaCommand {
+    /// Show usage help.
+    Help,
+    /// List models in the schema.
+    Models,
+    /// List fields of a model.
+    Fields(String),
+    /// Toggle or set EXPLAIN mode.
+    Explain(Option<bool>),
+    /// Leave the console.
+    Quit,
+}
+
+/// Kind of model query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelQueryKind {
+    /// Return matching rows.
+    Find,
+    /// Return the number of matching rows.
+    Count,
+}
+
+/// A simple model query: `find|count <Model> [where <cond> [and <cond>]...] [limit <n>]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelQuery {
+    /// Query kind.
+    pub kind: ModelQueryKind,
+    /// Model name as written in the schema.
+    pub model: String,
+    /// Filter conditions, combined with AND.
+    pub conditions: Vec<Condition>,
+    /// Maximum number of rows to return.
+    pub limit: Option<u64>,
+}
+
+/// A single `field op value` filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// Field name as written in the schema.
+    pub field: String,
+    /// Comparison operator.
+    pub op: String,
+    /// Literal value.
+    pub value: Literal,
+}
+
+/// A literal value in a model query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    /// Quoted string.
+    String(String),
+    /// Numeric literal, kept verbatim.
+    Number(String),
+    /// Boolean literal.
+    Bool(bool),
+    /// NULL.
+    Null,
+}
+
+/// Parse one line of console input.
+pub fn parse_input(line: &str) -> CliResult<ConsoleInput> {
+    let line = line.trim().trim_end_matches(';').trim();
+
+    if let Some(meta) = line.strip_prefix('.') {
+        return parse_meta(meta).map(ConsoleInput::Meta);
+    }
+
+    let first = line.split_whitespace().next().unwrap_or("");
+    if first.eq_ignore_ascii_case("find") || first.eq_ignore_ascii_case("count") {
+        return parse_model_query(line).map(ConsoleInput::Model);
+    }
+
+    Ok(ConsoleInput::Sql(line.to_string()))
+}
+
+fn parse_meta(meta: &str) -> CliResult<MetaCommand> {
+    let mut parts = meta.split_whitespace();
+    let command = parts.next().unwrap_or("");
+    let arg = parts.next();
+
+    match command {
+        "help" | "h" | "?" => Ok(MetaCommand::Help),
+        "models" | "tables" => Ok(MetaCommand::Models),
+        "fields" | "describe" => arg
+            .map(|m| MetaCommand::Fields(m.to_string()))
+            .ok_or_else(|| CliError::Command("Usage: .fields <Model>".to_string())),
+        "explain" => match arg {
+            None => Ok(MetaCommand::Explain(None)),
+            Some("on") => Ok(MetaCommand::Explain(Some(true))),
+            Some("off") => Ok(MetaCommand::Explain(Some(false))),
+            Some(other) => Err(CliError::Command(format!(
+                "Invalid .explain argument '{}', expected on or off",
+                other
+            ))),
+        },
+        "quit" | "exit" | "q" => Ok(MetaCommand::Quit),
+        other => Err(CliError::Command(format!(
+            "Unknown command '.{}'. Type .help for usage.",
+            other
+        ))),
+    }
+}
+
+fn parse_model_query(line: &str) -> CliResult<ModelQuery> {
+    let tokens = tokenize(line)?;
+    let mut iter = tokens.into_iter().peekable();
+
+    let kind = match iter.next().as_deref().map(str::to_lowercase).as_deref() {
+        Some("find") => ModelQueryKind::Find,
+        Some("count") => ModelQueryKind::Count,
+        _ => return Err(CliError::Command("Expected 'find' or 'count'".to_string())),
+    };
+
+    let model = iter
+        .next()
+        .ok_or_else(|| CliError::Command("Expected a model name".to_string()))?;
+
+    let mut query = ModelQuery {
+        kind,
+        model,
+        conditions: Vec::new(),
+        limit: None,
+    };
+
+    while let Some(token) = iter.next() {
+        match token.to_lowercase().as_str() {
+            "where" | "and" => {
+                let field = iter
+                    .next()
+                    .ok_or_else(|| CliError::Command("Expected a field name".to_string()))?;
+                let op = iter.next().ok_or_else(|| {
+                    CliError::Command(format!("Expected an operator after '{}'", field))
+                })?;
+                let op = normalize_operator(&op)?;
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CliError::Command(format!("Expected a value after '{}'", op)))?;
+                query.conditions.push(Condition {
+                    field,
+                    op,
+                    value: parse_literal(&value)?,
+                });
+            }
+            "limit" => {
+                let value = iter.next().ok_or_else(|| {
+                    CliError::Command("Expected a number after 'limit'".to_string())
+                })?;
+                query.limit = Some(
+                    value
+                        .parse()
+                        .map_err(|_| CliError::Command(format!("Invalid limit '{}'", value)))?,
+                );
+            }
+            other => {
+                return Err(CliError::Command(format!("Unexpected token '{}'", other)));
+            }
+        }
+    }
+
+    Ok(query)
+}
+
+/// Split a model query into tokens, keeping quoted strings intact (with quotes).
+fn tokenize(line: &str) -> CliResult<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut token = String::from('\'');
+            let mut closed = false;
+            for ch in chars.by_ref() {
+                if ch == c {
+                    closed = true;
+                    break;
+                }
+                token.push(ch);
+            }
+            if !closed {
+                return Err(CliError::Command("Unterminated string literal".to_string()));
+            }
+            token.push('\'');
+            tokens.push(token);
+        } else if "=!<>".contains(c) {
+            let mut token = String::new();
+            while let Some(&ch) = chars.peek() {
+                if !"=!<>".contains(ch) {
+                    break;
+                }
+                token.push(ch);
+                chars.next();
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() || "=!<>'\"".contains(ch) {
+                    break;
+                }
+                token.push(ch);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn normalize_operator(op: &str) -> CliResult<String> {
+    match op.to_lowercase().as_str() {
+        "=" | "==" => Ok("=".to_string()),
+        "!=" | "<>" => Ok("<>".to_string()),
+        ">" | ">=" | "<" | "<=" => Ok(op.to_string()),
+        "like" => Ok("LIKE".to_string()),
+        other => Err(CliError::Command(format!(
+            "Unsupported operator '{}'",
+            other
+        ))),
+    }
+}
+
+fn parse_literal(token: &str) -> CliResult<Literal> {
+    if let Some(s) = token.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        return Ok(Literal::String(s.to_string()));
+    }
+    Ok(match token.to_lowercase().as_str() {
+        "true" => Literal::Bool(true),
+        "false" => Literal::Bool(false),
+        "null" => Literal::Null,
+        _ => match token.parse::<f64>() {
+            // `inf` and `NaN` parse too, but aren't SQL numbers
+            Ok(n) if !n.is_finite() => {
+                return Err(CliError::Command(format!(
+                    "Invalid number '{}'; quote it to compare as a string",
+                    token
+                )));
+            }
+            Ok(_) => Literal::Number(token.to_string()),
+            Err(_) => Literal::String(token.to_string()),
+        },
+    })
+}
+
+impl Literal {
+    /// Render the literal as inline SQL for `db_type`.
+    fn to_sql(&self, db_type: DatabaseType) -> String {
+        match self {
+            Literal::String(s) => format!("'{}'", s.replace('\'', "''")),
+            Literal::Number(n) => n.clone(),
+            // SQL Server has no boolean literals
+            Literal::Bool(b) if db_type == DatabaseType::MSSQL => {
+                if *b { "1" } else { "0" }.to_string()
+            }
+            Literal::Bool(b) => b.to_string().to_uppercase(),
+            Literal::Null => "NULL".to_string(),
+        }
+    }
+}
+
+impl ModelQuery {
+    /// Build SQL for this query, resolving names against the schema.
+    pub fn to_sql(&self, schema: &prax_schema::Schema, db_type: DatabaseType) -> CliResult<String> {
+        let model = schema
+            .get_model(&self.model)
+            .ok_or_else(|| CliError::Command(format!("Unknown model '{}'", self.model)))?;
+
+        let table = quote_identifier(model.table_name(), db_type);
+        let mut sql = match self.kind {
+            ModelQueryKind::Find => format!("SELECT * FROM {}", table),
+            ModelQueryKind::Count => format!("SELECT COUNT(*) AS count FROM {}", table),
+        };
+
+        let mut clauses = Vec::with_capacity(self.conditions.len());
+        for condition in &self.conditions {
+            let field = model.get_field(&condition.field).ok_or_else(|| {
+                CliError::Command(format!(
+                    "Unknown field '{}' on model '{}'",
+                    condition.field, self.model
+                ))
+            })?;
+            let column = field
+                .extract_attributes()
+                .map
+                .unwrap_or_else(|| field.name().to_string());
+            let column = quote_identifier(&column, db_type);
+
+            let clause = match (&condition.value, condition.op.as_str()) {
+                (Literal::Null, "=") => format!("{} IS NULL", column),
+                (Literal::Null, "<>") => format!("{} IS NOT NULL", column),
+                (value, op) => format!("{} {} {}", column, op, value.to_sql(db_type)),
+            };
+            clauses.push(clause);
+        }
+
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+
+        if let (ModelQueryKind::Find, Some(limit)) = (self.kind, self.limit) {
+            match db_type {
+                DatabaseType::MSSQL => {
+                    sql = sql.replacen("SELECT", &format!("SELECT TOP {}", limit), 1);
+                }
+                _ => sql.push_str(&format!(" LIMIT {}", limit)),
+            }
+        }
+
+        Ok(sql)
+    }
+}
+
+/// Prefix a statement with the dialect's EXPLAIN keyword.
+pub fn explain_sql(sql: &str, db_type: DatabaseType) -> String {
+    match db_type {
+        DatabaseType::SQLite => format!("EXPLAIN QUERY PLAN {}", sql),
+        _ => format!("EXPLAIN {}", sql),
+    }
+}
+
+// =============================================================================
+// Session
+// =============================================================================
+
+struct ConsoleSession {
+    schema: Option<prax_schema::Schema>,
+    db_type: DatabaseType,
+    explain: bool,
+}
+
+impl ConsoleSession {
+    fn handle_meta(&mut self, meta: MetaCommand) {
+        match meta {
+            MetaCommand::Help => print_help(),
+            MetaCommand::Models => match &self.schema {
+                Some(schema) => {
+                    for model in schema.models.values() {
+                        output::list_item(&format!("{} ({})", model.name(), model.table_name()));
+                    }
+                }
+                None => warn("No schema loaded."),
+            },
+            MetaCommand::Fields(name) => {
+                match self.schema.as_ref().and_then(|s| s.get_model(&name)) {
+                    Some(model) => {
+                        for field in model.fields.values() {
+                            output::kv(field.name(), &field.field_type.to_string());
+                        }
+                    }
+                    None => warn(&format!("Unknown model '{}'", name)),
+                }
+            }
+            MetaCommand::Explain(value) => {
+                self.explain = value.unwrap_or(!self.explain);
+                output::info(&format!(
+                    "EXPLAIN is {}",
+                    if self.explain { "on" } else { "off" }
+                ));
+            }
+            MetaCommand::Quit => {}
+        }
+    }
+
+    fn to_sql(&self, input: ConsoleInput) -> CliResult<String> {
+        let sql = match input {
+            ConsoleInput::Sql(sql) => sql,
+            ConsoleInput::Model(query) => {
+                let schema = self.schema.as_ref().ok_or_else(|| {
+                    CliError::Command("Model queries require a schema file".to_string())
+                })?;
+                let sql = query.to_sql(schema, self.db_type)?;
+                output::dim(&sql);
+                sql
+            }
+            ConsoleInput::Meta(_) => unreachable!("meta commands are handled separately"),
+        };
+
+        if self.explain {
+            Ok(explain_sql(&sql, self.db_type))
+        } else {
+            Ok(sql)
+        }
+    }
+}
+
+fn print_help() {
+    output::section("Model queries");
+    output::list_item("find <Model> [where <field> <op> <value> [and ...]] [limit <n>]");
+    output::list_item("count <Model> [where <field> <op> <value> [and ...]]");
+    output::dim("  Operators: = != > >= < <= like");
+    output::newline();
+    output::section("Meta commands");
+    output::kv(".models", "List models in the schema");
+    output::kv(".fields <Model>", "List fields of a model");
+    output::kv(".explain [on|off]", "Toggle EXPLAIN output");
+    output::kv(".quit", "Exit the console");
+    output::newline();
+    output::dim("Anything else is executed as raw SQL.");
+}
+
+fn print_result(result: &QueryOutput) {
+    if output::is_json_mode() {
+        if let Ok(json) = serde_json::to_string(result) {
+            println!("{}", json);
+        }
+        return;
+    }
+
+    if result.columns.is_empty() {
+        success(&format!("{} rows affected", result.rows_affected));
+        return;
+    }
+
+    output::table(&result.columns, &result.rows);
+    output::dim(&format!("({} rows)", result.rows.len()));
+}
+
+// =============================================================================
+// Completion
+// =============================================================================
+
+/// Schema-aware tab completion for the console.
+pub struct ConsoleHelper {
+    models: Vec<String>,
+    words: Vec<String>,
+}
+
+impl ConsoleHelper {
+    /// Build completion candidates from the schema.
+    pub fn new(schema: Option<&prax_schema::Schema>) -> Self {
+        let mut models = Vec::new();
+        let mut words: Vec<String> = KEYWORDS.iter().map(|k| k.to_string()).collect();
+
+        if let Some(schema) = schema {
+            for model in schema.models.values() {
+                models.push(model.name().to_string());
+                words.push(model.name().to_string());
+                if model.table_name() != model.name() {
+                    words.push(model.table_name().to_string());
+                }
+                for field in model.fields.values() {
+                    if !words.iter().any(|w| w == field.name()) {
+                        words.push(field.name().to_string());
+                    }
+                }
+            }
+        }
+
+        Self { models, words }
+    }
+
+    /// Return completion candidates for the word ending at `pos`.
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let start = before
+            .rfind(|c: char| c.is_whitespace() || c == '(' || c == ',')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let prefix = &before[start..];
+
+        let pool: Vec<&str> = if prefix.starts_with('.') {
+            META_COMMANDS.to_vec()
+        } else if is_model_position(&before[..start]) {
+            self.models.iter().map(String::as_str).collect()
+        } else {
+            self.words.iter().map(String::as_str).collect()
+        };
+
+        let lower = prefix.to_lowercase();
+        let matches = pool
+            .into_iter()
+            .filter(|w| w.to_lowercase().starts_with(&lower))
+            .map(String::from)
+            .collect();
+
+        (start, matches)
+    }
+}
+
+/// Whether the next word is expected to be a model name.
+fn is_model_position(before: &str) -> bool {
+    let mut words = before.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some(first), None) => {
+            first.eq_ignore_ascii_case("find") || first.eq_ignore_ascii_case("count")
+        }
+        _ => false,
+    }
+}
+
+impl Completer for ConsoleHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for ConsoleHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ConsoleHelper {
+    fn highlight_prompt<'b, 's: 'b, 'p: 'b>(
+        &'s self,
+        prompt: &'p str,
+        _default: bool,
+    ) -> Cow<'b, str> {
+        Cow::Borrowed(prompt)
+    }
+}
+
+impl Validator for ConsoleHelper {}
+
+impl Helper for ConsoleHelper {}
+
+// =============================================================================
+// Execution
+// =============================================================================
+
+/// Tabular query output.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct QueryOutput {
+    /// Column names.
+    pub columns: Vec<String>,
+    /// Row values rendered as text.
+    pub rows: Vec<Vec<String>>,
+    /// Rows affected for statements that return no rows.
+    pub rows_affected: u64,
+}
+
+/// Executes console statements against the configured database.
+enum ConsoleExecutor {
+    #[cfg(feature = "postgres")]
+    Postgres(tokio_postgres::Client),
+}
+
+impl ConsoleExecutor {
+    async fn connect(url: &str, db_type: DatabaseType) -> CliResult<Self> {
+        match db_type {
+            #[cfg(feature = "postgres")]
+            DatabaseType::PostgreSQL => {
+                let (client, connection) = tokio_postgres::connect(url, tokio_postgres::NoTls)
+                    .await
+                    .map_err(|e| CliError::Database(format!("Failed to connect: {}", e)))?;
+
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        eprintln!("Connection error: {}", e);
+                    }
+                });
+
+                Ok(ConsoleExecutor::Postgres(client))
+            }
+            other => {
+                let _ = url;
+                Err(CliError::Config(format!(
+                    "The console does not support {:?} yet. Compile with --features postgres and use a PostgreSQL database.",
+                    other
+                )))
+            }
+        }
+    }
+
+    async fn execute(&self, sql: &str) -> CliResult<QueryOutput> {
+        match self {
+            #[cfg(feature = "postgres")]
+            ConsoleExecutor::Postgres(client) => {
+                use tokio_postgres::SimpleQueryMessage;
+
+                let messages = client
+                    .simple_query(sql)
+                    .await
+                    .map_err(|e| CliError::Database(e.to_string()))?;
+
+                let mut result = QueryOutput::default();
+                for message in messages {
+                    match message {
+                        SimpleQueryMessage::Row(row) => {
+                            if result.columns.is_empty() {
+                                result.columns =
+                                    row.columns().iter().map(|c| c.name().to_string()).collect();
+                            }
+                            let values = (0..row.len())
+                                .map(|i| row.get(i).unwrap_or("NULL").to_string())
+                                .collect();
+                            result.rows.push(values);
+                        }
+                        SimpleQueryMessage::CommandComplete(n) => result.rows_affected = n,
+                        _ => {}
+                    }
+                }
+
+                Ok(result)
+            }
+            #[cfg(not(feature = "postgres"))]
+            _ => {
+                let _ = sql;
+                unreachable!("no console executors are enabled")
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn load_config(cwd: &Path) -> CliResult<Config> {
+    let config_path = cwd.join(CONFIG_FILE_NAME);
+    if config_path.exists() {
+        Config::load(&config_path)
+    } else {
+        Ok(Config::default())
+    }
+}
+
+fn parse_schema(content: &str) -> CliResult<prax_schema::Schema> {
+    prax_schema::parse_schema(content)
+        .map_err(|e| CliError::Schema(format!("Failed to parse schema: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> prax_schema::Schema {
+        prax_schema::parse_schema(
+            r#"
+            model User {
+                id        Int     @id @auto
+                email     String  @unique
+                firstName String? @map("first_name")
+                age       Int
+
+                @@map("users")
+            }
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_meta_commands() {
+        assert_eq!(
+            parse_input(".explain on").unwrap(),
+            ConsoleInput::Meta(MetaCommand::Explain(Some(true)))
+        );
+        assert_eq!(
+            parse_input(".quit").unwrap(),
+            ConsoleInput::Meta(MetaCommand::Quit)
+        );
+        assert!(parse_input(".bogus").is_err());
+    }
+
+    #[test]
+    fn test_raw_sql_passthrough() {
+        assert_eq!(
+            parse_input("SELECT 1;").unwrap(),
+            ConsoleInput::Sql("SELECT 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_find_query_to_sql() {
+        let ConsoleInput::Model(query) =
+            parse_input("find User where firstName = \"Ada\" and age >= 18 limit 5").unwrap()
+        else {
+            panic!("expected model query");
+        };
+        let sql = query.to_sql(&schema(), DatabaseType::PostgreSQL).unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "users" WHERE "first_name" = 'Ada' AND "age" >= 18 LIMIT 5"#
+        );
+    }
+
+    #[test]
+    fn test_count_query_with_null() {
+        let ConsoleInput::Model(query) = parse_input("count User where firstName != null").unwrap()
+        else {
+            panic!("expected model query");
+        };
+        let sql = query.to_sql(&schema(), DatabaseType::MySQL).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT COUNT(*) AS count FROM `users` WHERE `first_name` IS NOT NULL"
+        );
+    }
+
+    #[test]
+    fn test_string_literal_is_escaped() {
+        assert_eq!(
+            Literal::String("O'Brien".to_string()).to_sql(DatabaseType::PostgreSQL),
+            "'O''Brien'"
+        );
+    }
+
+    #[test]
+    fn test_non_finite_numbers_are_rejected() {
+        for value in ["inf", "-Infinity", "NaN"] {
+            assert!(parse_input(&format!("find User where age = {}", value)).is_err());
+        }
+        let ConsoleInput::Model(query) = parse_input("find User where email = 'NaN'").unwrap()
+        else {
+            panic!("expected model query");
+        };
+        assert_eq!(query.conditions[0].value, Literal::String("NaN".to_string()));
+    }
+
+    #[test]
+    fn test_bool_literal_per_dialect() {
+        assert_eq!(Literal::Bool(true).to_sql(DatabaseType::PostgreSQL), "TRUE");
+        assert_eq!(Literal::Bool(true).to_sql(DatabaseType::MSSQL), "1");
+        assert_eq!(Literal::Bool(false).to_sql(DatabaseType::MSSQL), "0");
+    }
+
+    #[test]
+    fn test_unknown_field_is_rejected() {
+        let ConsoleInput::Model(query) = parse_input("find User where nope = 1").unwrap() else {
+            panic!("expected model query");
+        };
+        assert!(query.to_sql(&schema(), DatabaseType::PostgreSQL).is_err());
+    }
+
+    #[test]
+    fn test_completion_candidates() {
+        let schema = schema();
+        let helper = ConsoleHelper::new(Some(&schema));
+
+        let (start, matches) = helper.candidates("find U", 6);
+        assert_eq!(start, 5);
+        assert_eq!(matches, vec!["User".to_string()]);
+
+        let (_, matches) = helper.candidates("find User where first", 21);
+        assert_eq!(matches, vec!["firstName".to_string()]);
+
+        let (_, matches) = helper.candidates(".ex", 3);
+        assert_eq!(matches, vec![".explain".to_string(), ".exit".to_string()]);
+    }
+}
@@ -0,0 +1,138 @@
+//! `prax convert` command - Convert schemas to and from other tools' formats.
+
+use serde::Serialize;
+
+use crate::cli::{ConvertArgs, SchemaFormat};
+use crate::commands::ddl::{ddl_to_prax, read_sql_sources};
+use crate::commands::prisma::{prax_to_prisma, prisma_to_prax};
+use crate::config::{CONFIG_FILE_NAME, Config};
+use crate::error::{CliError, CliResult};
+use crate::output::{self, success, warn};
+
+/// Machine-readable result of `prax convert`
+#[derive(Debug, Serialize)]
+pub struct ConvertReport {
+    /// Input file
+    pub input: String,
+    /// Input format
+    pub from: String,
+    /// Output format
+    pub to: String,
+    /// Written file, if any
+    pub output: Option<String>,
+    /// Converted schema (when printed)
+    pub schema: Option<String>,
+    /// Features that could not be converted
+    pub warnings: Vec<String>,
+}
+
+/// Run the convert command
+pub async fn run(args: ConvertArgs) -> CliResult<()> {
+    output::header("Convert Schema");
+
+    if !args.input.exists() {
+        return Err(CliError::Config(format!(
+            "Input file not found: {}",
+            args.input.display()
+        )));
+    }
+
+    let from = args
+        .from
+        .or_else(|| SchemaFormat::from_path(&args.input))
+        .ok_or_else(|| {
+            CliError::Config(format!(
+                "Cannot infer the format of {}; pass --from",
+                args.input.display()
+            ))
+        })?;
+    let to = args.to.unwrap_or(match from {
+        SchemaFormat::Prax => SchemaFormat::Prisma,
+        _ => SchemaFormat::Prax,
+    });
+
+    output::kv("Input", &args.input.display().to_string());
+    output::kv("From", &from.to_string());
+    output::kv("To", &to.to_string());
+
+    let provider = match (from, &args.provider) {
+        (SchemaFormat::Ddl, Some(provider)) => provider.clone(),
+        (SchemaFormat::Ddl, None) => load_config()?.database.provider,
+        _ => String::new(),
+    };
+    if from == SchemaFormat::Ddl {
+        output::kv("Provider", &provider);
+    }
+    output::newline();
+
+    let conversion = match (from, to) {
+        (SchemaFormat::Prisma, SchemaFormat::Prax) => {
+            prisma_to_prax(&std::fs::read_to_string(&args.input)?)?
+        }
+        (SchemaFormat::Prax, SchemaFormat::Prisma) => {
+            prax_to_prisma(&std::fs::read_to_string(&args.input)?)?
+        }
+        (SchemaFormat::Ddl, SchemaFormat::Prax) => {
+            ddl_to_prax(&read_sql_sources(&args.input)?, &provider)?
+        }
+        (from, to) => {
+            return Err(CliError::Config(format!(
+                "Conversion from {} to {} is not supported",
+                from, to
+            )));
+        }
+    };
+
+    let mut written = None;
+    if args.print {
+        if !output::is_json_mode() {
+            println!("{}", conversion.schema);
+        }
+    } else {
+        let output_path = match args.output {
+            Some(path) => path,
+            // A migrations directory converts to a schema next to it
+            None if args.input.is_dir() => args.input.with_file_name("schema.prax"),
+            None => args.input.with_extension(to.extension()),
+        };
+
+        if output_path.exists() && !args.force {
+            return Err(CliError::Config(format!(
+                "{} already exists; pass --force to overwrite",
+                output_path.display()
+            )));
+        }
+
+        std::fs::write(&output_path, &conversion.schema)?;
+        success(&format!("Schema written to {}", output_path.display()));
+        written = Some(output_path.display().to_string());
+    }
+
+    if !conversion.warnings.is_empty() {
+        output::newline();
+        output::section("Not converted");
+        for warning in &conversion.warnings {
+            warn(warning);
+        }
+    }
+
+    output::report(&ConvertReport {
+        input: args.input.display().to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+        output: written,
+        schema: args.print.then(|| conversion.schema.clone()),
+        warnings: conversion.warnings,
+    });
+
+    Ok(())
+}
+
+fn load_config() -> CliResult<Config> {
+    let config_path = std::env::current_dir()?.join(CONFIG_FILE_NAME);
+    if config_path.exists() {
+        Config::load(&config_path)
+    } else {
+        Ok(Config::default())
+    }
+}
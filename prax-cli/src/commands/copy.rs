@@ -0,0 +1,1280 @@
+//! Typed data copy between datasources (`prax db copy`).
+//!
+//! Rows are read from the source in primary-key order, coerced to the field
+//! types declared in the schema and inserted into the target in batches.
+//! Progress is checkpointed after every batch, so an interrupted copy
+//! continues where it stopped with `--resume`.
+//!
+//! Like seeding, the database client tools (`psql`, `mysql`, `sqlite3` and
+//! `duckdb`) perform the actual reads and writes, so any pair of supported
+//! databases can be copied between without extra drivers.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use prax_schema::ast::{AttributeValue, FieldType, ScalarType, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::db::mask_database_url;
+use crate::commands::seed::{expand_env_var, get_database_url};
+use crate::config::Config;
+use crate::error::{CliError, CliResult};
+use crate::output;
+
+/// SQL dialects `prax db copy` can read from and write to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// PostgreSQL (via `psql`)
+    Postgres,
+    /// MySQL / MariaDB (via `mysql`)
+    MySql,
+    /// SQLite (via `sqlite3`)
+    Sqlite,
+    /// DuckDB (via `duckdb`)
+    DuckDb,
+}
+
+impl Dialect {
+    /// Dialect for a provider name
+    pub fn from_provider(provider: &str) -> CliResult<Self> {
+        match provider.to_lowercase().as_str() {
+            "postgresql" | "postgres" | "pg" => Ok(Self::Postgres),
+            "mysql" | "mariadb" => Ok(Self::MySql),
+            "sqlite" | "sqlite3" => Ok(Self::Sqlite),
+            "duckdb" => Ok(Self::DuckDb),
+            _ => Err(CliError::Config(format!(
+                "Unsupported provider for copy: {}",
+                provider
+            ))),
+        }
+    }
+
+    /// Dialect for a connection URL or database file
+    pub fn from_url(url: &str) -> Option<Self> {
+        let scheme = url.split_once(':').map(|(s, _)| s.to_lowercase());
+        match scheme.as_deref() {
+            Some("postgres" | "postgresql") => Some(Self::Postgres),
+            Some("mysql" | "mariadb") => Some(Self::MySql),
+            Some("sqlite") => Some(Self::Sqlite),
+            Some("duckdb") => Some(Self::DuckDb),
+            _ => match Path::new(url).extension().and_then(|e| e.to_str()) {
+                Some("db" | "sqlite" | "sqlite3") => Some(Self::Sqlite),
+                Some("duckdb") => Some(Self::DuckDb),
+                _ => None,
+            },
+        }
+    }
+
+    /// Client tool used for this dialect
+    pub fn client(&self) -> &'static str {
+        match self {
+            Self::Postgres => "psql",
+            Self::MySql => "mysql",
+            Self::Sqlite => "sqlite3",
+            Self::DuckDb => "duckdb",
+        }
+    }
+
+    /// Quote an identifier
+    pub fn quote(&self, ident: &str) -> String {
+        match self {
+            Self::MySql => format!("`{}`", ident.replace('`', "``")),
+            _ => format!("\"{}\"", ident.replace('"', "\"\"")),
+        }
+    }
+
+    /// Quote a string literal
+    fn string(&self, value: &str) -> String {
+        let escaped = value.replace('\'', "''");
+        match self {
+            // MySQL treats backslashes as escapes by default
+            Self::MySql => format!("'{}'", escaped.replace('\\', "\\\\")),
+            _ => format!("'{}'", escaped),
+        }
+    }
+
+    fn boolean(&self, value: bool) -> String {
+        match (self, value) {
+            (Self::MySql | Self::Sqlite, true) => "1".to_string(),
+            (Self::MySql | Self::Sqlite, false) => "0".to_string(),
+            (_, true) => "TRUE".to_string(),
+            (_, false) => "FALSE".to_string(),
+        }
+    }
+
+    fn bytes(&self, hex: &str) -> String {
+        match self {
+            Self::Postgres => format!("decode('{}', 'hex')", hex),
+            Self::DuckDb => format!("unhex('{}')", hex),
+            _ => format!("X'{}'", hex),
+        }
+    }
+
+    /// Expression selecting a column so that it survives a JSON round trip
+    fn select_expr(&self, column: &CopyColumn) -> String {
+        let name = self.quote(&column.name);
+        match (column.kind, self) {
+            (ColumnKind::Bytes, Self::Postgres) => format!("encode({}, 'hex')", name),
+            (ColumnKind::Bytes, Self::MySql) => format!("HEX({})", name),
+            (ColumnKind::Bytes, _) => format!("hex({})", name),
+            // Keep full precision instead of going through a JSON number
+            (ColumnKind::Decimal, Self::Postgres) => format!("{}::text", name),
+            (ColumnKind::Decimal, Self::MySql) => format!("CAST({} AS CHAR)", name),
+            (ColumnKind::Decimal, Self::Sqlite) => format!("CAST({} AS TEXT)", name),
+            (ColumnKind::Decimal, Self::DuckDb) => format!("CAST({} AS VARCHAR)", name),
+            _ => name,
+        }
+    }
+}
+
+impl std::fmt::Display for Dialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Postgres => "postgresql",
+            Self::MySql => "mysql",
+            Self::Sqlite => "sqlite",
+            Self::DuckDb => "duckdb",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// ============================================================================
+// Datasources
+// ============================================================================
+
+/// A datasource to copy from or to
+#[derive(Debug, Clone)]
+pub struct Datasource {
+    /// Name given on the command line
+    pub name: String,
+    /// SQL dialect
+    pub dialect: Dialect,
+    /// Connection URL or database file
+    pub url: String,
+}
+
+impl Datasource {
+    /// Resolve `--from`/`--to`: a name from `[datasources]`, `default` for
+    /// the `[database]` section, or a connection URL.
+    pub fn resolve(spec: &str, config: &Config) -> CliResult<Self> {
+        if let Some(datasource) = config.datasources.get(spec) {
+            return Ok(Self {
+                name: spec.to_string(),
+                dialect: Dialect::from_provider(&datasource.provider)?,
+                url: expand_env_var(&datasource.url),
+            });
+        }
+
+        if spec == "default" {
+            return Ok(Self {
+                name: spec.to_string(),
+                dialect: Dialect::from_provider(&config.database.provider)?,
+                url: get_database_url(config)?,
+            });
+        }
+
+        let dialect = Dialect::from_url(spec).ok_or_else(|| {
+            CliError::Config(format!(
+                "Unknown datasource `{}`. Define it under [datasources.{}] in prax.toml or pass a connection URL",
+                spec, spec
+            ))
+        })?;
+        Ok(Self {
+            // Keep passwords out of messages and checkpoints
+            name: mask_database_url(spec),
+            dialect,
+            url: spec.to_string(),
+        })
+    }
+
+    /// Path of a file database
+    fn path(&self) -> &str {
+        ["sqlite://", "sqlite:", "duckdb://", "duckdb:", "file:"]
+            .iter()
+            .find_map(|prefix| self.url.strip_prefix(prefix))
+            .unwrap_or(&self.url)
+    }
+
+    fn command(&self) -> CliResult<Command> {
+        let mut cmd = Command::new(self.dialect.client());
+        match self.dialect {
+            Dialect::Postgres => {
+                cmd.args([
+                    "-X",
+                    "-q",
+                    "-A",
+                    "-t",
+                    "-v",
+                    "ON_ERROR_STOP=1",
+                    "-d",
+                    &self.url,
+                ]);
+            }
+            Dialect::MySql => {
+                let url = url::Url::parse(&self.url)
+                    .map_err(|e| CliError::Config(format!("Invalid MySQL URL: {}", e)))?;
+                cmd.args(["--batch", "--skip-column-names", "--raw"]);
+                cmd.args(["-h", url.host_str().unwrap_or("localhost")]);
+                cmd.args(["-P", &url.port().unwrap_or(3306).to_string()]);
+                cmd.args(["-u", url.username()]);
+                if let Some(password) = url.password() {
+                    cmd.arg(format!("-p{}", password));
+                }
+                cmd.args(["-D", url.path().trim_start_matches('/')]);
+            }
+            Dialect::Sqlite | Dialect::DuckDb => {
+                cmd.args(["-bail", "-noheader", "-list", self.path()]);
+            }
+        }
+        Ok(cmd)
+    }
+
+    /// Run SQL through the client tool and return its output.
+    ///
+    /// The SQL is passed on stdin, which has no length limit unlike
+    /// command-line arguments.
+    pub fn execute(&self, sql: &str) -> CliResult<String> {
+        let client = self.dialect.client();
+        let mut child = self
+            .command()?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    CliError::Command(format!(
+                        "{} not found. Install the {} client tools to copy data",
+                        client, self.dialect
+                    ))
+                } else {
+                    CliError::Io(e)
+                }
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(sql.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CliError::Database(format!(
+                "{} ({}) failed: {}",
+                self.name,
+                client,
+                stderr.trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Run a query returning one JSON document per row
+    pub(crate) fn query_json(&self, sql: &str) -> CliResult<Vec<Value>> {
+        self.execute(sql)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| {
+                    CliError::Database(format!("Unexpected output from {}: {}", self.name, e))
+                })
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Copy plan
+// ============================================================================
+
+/// How a column's values are coerced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// Integer types
+    Int,
+    /// Floating point types
+    Float,
+    /// Exact numerics, copied as text
+    Decimal,
+    /// Booleans
+    Boolean,
+    /// Strings, identifiers, enums and other text-like values
+    Text,
+    /// Timestamps
+    DateTime,
+    /// Dates
+    Date,
+    /// Times of day
+    Time,
+    /// JSON documents
+    Json,
+    /// Binary data, copied as hex
+    Bytes,
+}
+
+impl ColumnKind {
+    fn from_field_type(field_type: &FieldType) -> Self {
+        match field_type {
+            FieldType::Scalar(scalar) => match scalar {
+                ScalarType::Int | ScalarType::BigInt => Self::Int,
+                ScalarType::Float => Self::Float,
+                ScalarType::Decimal => Self::Decimal,
+                ScalarType::Boolean => Self::Boolean,
+                ScalarType::DateTime => Self::DateTime,
+                ScalarType::Date => Self::Date,
+                ScalarType::Time => Self::Time,
+                ScalarType::Json => Self::Json,
+                ScalarType::Bytes => Self::Bytes,
+                _ => Self::Text,
+            },
+            FieldType::Composite(_) => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// A column copied from a model
+#[derive(Debug, Clone)]
+pub struct CopyColumn {
+    /// Column name
+    pub name: String,
+    /// Value coercion
+    pub kind: ColumnKind,
+    /// Whether the field is optional
+    pub optional: bool,
+    /// Whether the field is a list
+    pub list: bool,
+}
+
+/// A model's table and the columns to copy
+#[derive(Debug, Clone)]
+pub struct CopyTable {
+    /// Model name
+    pub model: String,
+    /// Table name
+    pub table: String,
+    /// Scalar columns
+    pub columns: Vec<CopyColumn>,
+    /// Primary key columns (indexes into `columns`)
+    pub primary_key: Vec<usize>,
+    /// Whether the single primary key is auto-incremented
+    pub auto_key: bool,
+}
+
+impl CopyTable {
+    /// Column used for keyset pagination, if the key is a single column
+    pub(crate) fn key(&self) -> Option<usize> {
+        match self.primary_key.as_slice() {
+            [key] => Some(*key),
+            _ => None,
+        }
+    }
+
+    /// Query for the next batch, after `after` (a key literal) or `offset`
+    pub(crate) fn select_sql(
+        &self,
+        dialect: Dialect,
+        after: Option<&str>,
+        offset: u64,
+        limit: usize,
+    ) -> String {
+        let mut tail = String::new();
+        if let (Some(after), Some(key)) = (after, self.key()) {
+            tail.push_str(&format!(
+                " WHERE {} > {}",
+                dialect.quote(&self.columns[key].name),
+                after
+            ));
+        }
+        tail.push_str(&format!(
+            " ORDER BY {} LIMIT {}",
+            self.order_by(dialect),
+            limit
+        ));
+        if after.is_none() && offset > 0 {
+            tail.push_str(&format!(" OFFSET {}", offset));
+        }
+        self.select_rows(dialect, &tail)
+    }
+
+    /// Query for the rows with keys after `after` up to and including `upto`
+    /// (key literals), unbounded on a side that is `None`
+    pub(crate) fn select_range_sql(
+        &self,
+        dialect: Dialect,
+        after: Option<&str>,
+        upto: Option<&str>,
+    ) -> String {
+        let mut conditions = Vec::new();
+        if let Some(key) = self.key() {
+            let column = dialect.quote(&self.columns[key].name);
+            if let Some(after) = after {
+                conditions.push(format!("{} > {}", column, after));
+            }
+            if let Some(upto) = upto {
+                conditions.push(format!("{} <= {}", column, upto));
+            }
+        }
+
+        let mut tail = String::new();
+        if !conditions.is_empty() {
+            tail.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        tail.push_str(&format!(" ORDER BY {}", self.order_by(dialect)));
+        self.select_rows(dialect, &tail)
+    }
+
+    /// Primary key columns, or all columns without a key
+    fn order_by(&self, dialect: Dialect) -> String {
+        let order: Vec<String> = if self.primary_key.is_empty() {
+            (0..self.columns.len()).collect::<Vec<_>>()
+        } else {
+            self.primary_key.clone()
+        }
+        .into_iter()
+        .map(|i| dialect.quote(&self.columns[i].name))
+        .collect();
+        order.join(", ")
+    }
+
+    /// Query selecting every column as one JSON document per row
+    fn select_rows(&self, dialect: Dialect, tail: &str) -> String {
+        let from = dialect.quote(&self.table);
+        let exprs: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| dialect.select_expr(c))
+            .collect();
+        match dialect {
+            // json_build_array is limited to 100 arguments; row_to_json is not
+            Dialect::Postgres => {
+                let aliased: Vec<String> = exprs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, expr)| format!("{} AS c{}", expr, i))
+                    .collect();
+                format!(
+                    "SELECT row_to_json(t) FROM (SELECT {} FROM {}{}) t;",
+                    aliased.join(", "),
+                    from,
+                    tail
+                )
+            }
+            Dialect::MySql => format!(
+                "SELECT JSON_ARRAY({}) FROM {}{};",
+                exprs.join(", "),
+                from,
+                tail
+            ),
+            _ => format!(
+                "SELECT json_array({}) FROM {}{};",
+                exprs.join(", "),
+                from,
+                tail
+            ),
+        }
+    }
+
+    /// Turn a row as returned by [`CopyTable::select_sql`] into values
+    pub(crate) fn row_values(&self, row: Value) -> Vec<Value> {
+        match row {
+            Value::Array(values) => values,
+            Value::Object(mut map) => (0..self.columns.len())
+                .map(|i| map.remove(&format!("c{}", i)).unwrap_or(Value::Null))
+                .collect(),
+            other => vec![other],
+        }
+    }
+
+    /// Insert statement for a batch of coerced rows
+    fn insert_sql(&self, dialect: Dialect, rows: &[Vec<String>]) -> String {
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| dialect.quote(&c.name))
+            .collect();
+        let values: Vec<String> = rows
+            .iter()
+            .map(|row| format!("({})", row.join(", ")))
+            .collect();
+        format!(
+            "INSERT INTO {} ({}) VALUES\n{};\n",
+            dialect.quote(&self.table),
+            columns.join(", "),
+            values.join(",\n")
+        )
+    }
+}
+
+/// Tables to copy for the given models (all when empty), parents before
+/// children so foreign keys are satisfied.
+pub fn copy_plan(schema: &Schema, models: &[String]) -> CliResult<Vec<CopyTable>> {
+    for name in models {
+        if schema.get_model(name).is_none() {
+            return Err(CliError::Config(format!("Unknown model: {}", name)));
+        }
+    }
+
+    // Dependencies: models referenced by a relation that holds the foreign key
+    let mut pending: Vec<(&str, Vec<&str>)> = schema
+        .models
+        .values()
+        .filter(|m| models.is_empty() || models.iter().any(|n| n == m.name()))
+        .map(|model| {
+            let deps = model
+                .fields
+                .values()
+                .filter_map(|field| {
+                    let relation = field.extract_attributes().relation?;
+                    match &field.field_type {
+                        FieldType::Model(target)
+                            if !relation.fields.is_empty() && target != model.name() =>
+                        {
+                            Some(target.as_str())
+                        }
+                        _ => None,
+                    }
+                })
+                .collect();
+            (model.name(), deps)
+        })
+        .collect();
+
+    let mut ordered = Vec::new();
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|(_, deps)| {
+                deps.iter()
+                    .all(|dep| !pending.iter().any(|(name, _)| name == dep))
+            })
+            // A relation cycle: copy in schema order
+            .unwrap_or(0);
+        ordered.push(pending.remove(ready).0);
+    }
+
+    Ok(ordered
+        .into_iter()
+        .filter_map(|name| schema.get_model(name))
+        .map(|model| {
+            let composite_id = match model.get_attribute("id").and_then(|a| a.first_arg()) {
+                Some(AttributeValue::FieldRefList(fields)) => fields.clone(),
+                _ => vec![],
+            };
+
+            let mut columns = Vec::new();
+            let mut primary_key = Vec::new();
+            let mut auto_key = false;
+            for field in model.fields.values() {
+                // Enums and composite types parse as model references
+                let kind = match &field.field_type {
+                    FieldType::Model(name) if schema.models.contains_key(name) => continue,
+                    FieldType::Model(name) if schema.types.contains_key(name) => ColumnKind::Json,
+                    other => ColumnKind::from_field_type(other),
+                };
+                let attrs = field.extract_attributes();
+                if attrs.is_id || composite_id.iter().any(|f| f == field.name()) {
+                    primary_key.push(columns.len());
+                    auto_key = attrs.is_auto;
+                }
+                columns.push(CopyColumn {
+                    name: attrs.map.unwrap_or_else(|| field.name().to_string()),
+                    kind,
+                    optional: field.is_optional(),
+                    list: field.is_list(),
+                });
+            }
+
+            CopyTable {
+                model: model.name().to_string(),
+                table: model.table_name().to_string(),
+                columns,
+                auto_key: auto_key && primary_key.len() == 1,
+                primary_key,
+            }
+        })
+        .collect())
+}
+
+// ============================================================================
+// Coercion
+// ============================================================================
+
+/// Coerce a value read from the source into a SQL literal for the target.
+pub fn coerce(value: &Value, column: &CopyColumn, dialect: Dialect) -> Result<String, String> {
+    if value.is_null() {
+        return if column.optional {
+            Ok("NULL".to_string())
+        } else {
+            Err("NULL in a required field".to_string())
+        };
+    }
+
+    if column.list {
+        let items = match value {
+            Value::Array(items) => items.clone(),
+            // Lists stored as JSON text outside PostgreSQL
+            Value::String(text) => match serde_json::from_str(text) {
+                Ok(Value::Array(items)) => items,
+                _ => return Err(format!("expected a list, got {}", value)),
+            },
+            _ => return Err(format!("expected a list, got {}", value)),
+        };
+        let element = CopyColumn {
+            list: false,
+            optional: true,
+            ..column.clone()
+        };
+        return match dialect {
+            Dialect::Postgres if items.is_empty() => Ok("'{}'".to_string()),
+            Dialect::Postgres | Dialect::DuckDb => {
+                let literals = items
+                    .iter()
+                    .map(|item| coerce(item, &element, dialect))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(format!("ARRAY[{}]", literals.join(", ")))
+            }
+            _ => Ok(dialect.string(&Value::Array(items).to_string())),
+        };
+    }
+
+    match column.kind {
+        ColumnKind::Int => {
+            let int = match value {
+                Value::Number(n) => n
+                    .as_i64()
+                    .map(|i| i.to_string())
+                    .or_else(|| n.as_u64().map(|u| u.to_string()))
+                    .or_else(|| {
+                        n.as_f64()
+                            .filter(|f| f.fract() == 0.0)
+                            .map(|f| (f as i64).to_string())
+                    }),
+                Value::Bool(b) => Some(u8::from(*b).to_string()),
+                Value::String(s) => s.trim().parse::<i128>().ok().map(|i| i.to_string()),
+                _ => None,
+            };
+            int.ok_or_else(|| format!("expected an integer, got {}", value))
+        }
+        ColumnKind::Float => match value {
+            Value::Number(n) => Ok(n.to_string()),
+            Value::String(s) => s
+                .trim()
+                .parse::<f64>()
+                .map(|f| f.to_string())
+                .map_err(|_| format!("expected a number, got {}", value)),
+            _ => Err(format!("expected a number, got {}", value)),
+        },
+        ColumnKind::Decimal => {
+            let text = match value {
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => s.trim().to_string(),
+                _ => return Err(format!("expected a decimal, got {}", value)),
+            };
+            if text.parse::<f64>().is_ok()
+                && text
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+            {
+                Ok(text)
+            } else {
+                Err(format!("expected a decimal, got {}", value))
+            }
+        }
+        ColumnKind::Boolean => {
+            let boolean = match value {
+                Value::Bool(b) => Some(*b),
+                Value::Number(n) => n.as_f64().map(|f| f != 0.0),
+                Value::String(s) => match s.trim().to_lowercase().as_str() {
+                    "t" | "true" | "1" | "y" | "yes" | "on" => Some(true),
+                    "f" | "false" | "0" | "n" | "no" | "off" => Some(false),
+                    _ => None,
+                },
+                _ => None,
+            };
+            boolean
+                .map(|b| dialect.boolean(b))
+                .ok_or_else(|| format!("expected a boolean, got {}", value))
+        }
+        ColumnKind::Text => Ok(dialect.string(&match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })),
+        ColumnKind::DateTime => {
+            let timestamp = parse_datetime(value)
+                .ok_or_else(|| format!("expected a timestamp, got {}", value))?;
+            Ok(dialect.string(&match dialect {
+                Dialect::Postgres => timestamp.to_rfc3339(),
+                _ => timestamp.format("%Y-%m-%d %H:%M:%S%.6f").to_string(),
+            }))
+        }
+        ColumnKind::Date => {
+            let date = value
+                .as_str()
+                .and_then(|s| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok())
+                .or_else(|| parse_datetime(value).map(|t| t.date_naive()))
+                .ok_or_else(|| format!("expected a date, got {}", value))?;
+            Ok(dialect.string(&date.format("%Y-%m-%d").to_string()))
+        }
+        ColumnKind::Time => {
+            let time = value
+                .as_str()
+                .and_then(|s| NaiveTime::parse_from_str(s.trim(), "%H:%M:%S%.f").ok())
+                .or_else(|| parse_datetime(value).map(|t| t.time()))
+                .ok_or_else(|| format!("expected a time, got {}", value))?;
+            Ok(dialect.string(&time.format("%H:%M:%S%.f").to_string()))
+        }
+        ColumnKind::Json => {
+            let json = match value {
+                // JSON stored as text keeps its structure; other strings are
+                // JSON strings
+                Value::String(s) => serde_json::from_str::<Value>(s)
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|_| value.to_string()),
+                other => other.to_string(),
+            };
+            Ok(dialect.string(&json))
+        }
+        ColumnKind::Bytes => {
+            let hex = value
+                .as_str()
+                .map(|s| s.trim_start_matches("\\x"))
+                .filter(|s| s.len() % 2 == 0 && s.chars().all(|c| c.is_ascii_hexdigit()))
+                .ok_or_else(|| format!("expected hex-encoded bytes, got {}", value))?;
+            Ok(dialect.bytes(&hex.to_lowercase()))
+        }
+    }
+}
+
+/// Parse the timestamp formats the supported databases produce.
+fn parse_datetime(value: &Value) -> Option<DateTime<Utc>> {
+    let text = match value {
+        Value::String(s) => s.trim(),
+        // Unix seconds
+        Value::Number(n) => return DateTime::from_timestamp(n.as_i64()?, 0),
+        _ => return None,
+    };
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z"] {
+        if let Ok(timestamp) = DateTime::parse_from_str(text, format) {
+            return Some(timestamp.with_timezone(&Utc));
+        }
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(timestamp) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(timestamp.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|timestamp| timestamp.and_utc())
+}
+
+// ============================================================================
+// Checkpoints
+// ============================================================================
+
+/// Copy progress, saved after every batch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Source datasource
+    pub source: String,
+    /// Target datasource
+    pub target: String,
+    /// Progress per model
+    pub tables: BTreeMap<String, TableProgress>,
+}
+
+/// Progress of one model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableProgress {
+    /// Rows copied so far
+    pub rows: u64,
+    /// Key of the last copied row (keyset pagination)
+    pub last_key: Option<Value>,
+    /// Whether the model is fully copied
+    pub done: bool,
+}
+
+impl Checkpoint {
+    /// Load a checkpoint file
+    pub fn load(path: &Path) -> CliResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| CliError::Config(format!("Invalid checkpoint {}: {}", path.display(), e)))
+    }
+
+    /// Save the checkpoint
+    pub fn save(&self, path: &Path) -> CliResult<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| CliError::Config(format!("Failed to write checkpoint: {}", e)))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Runner
+// ============================================================================
+
+/// Copies rows between two datasources
+pub struct CopyRunner {
+    source: Datasource,
+    target: Datasource,
+    tables: Vec<CopyTable>,
+    batch_size: usize,
+    checkpoint_path: PathBuf,
+}
+
+impl CopyRunner {
+    /// Create a new copy runner
+    pub fn new(
+        source: Datasource,
+        target: Datasource,
+        tables: Vec<CopyTable>,
+        checkpoint_path: PathBuf,
+    ) -> Self {
+        Self {
+            source,
+            target,
+            tables,
+            batch_size: 1000,
+            checkpoint_path,
+        }
+    }
+
+    /// Set the number of rows per batch
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Copy all tables, continuing from the checkpoint when `resume` is set
+    pub async fn run(&self, resume: bool) -> CliResult<CopyResult> {
+        let started = Instant::now();
+        let mut checkpoint = if resume && self.checkpoint_path.exists() {
+            let checkpoint = Checkpoint::load(&self.checkpoint_path)?;
+            if checkpoint.source != self.source.name || checkpoint.target != self.target.name {
+                return Err(CliError::Config(format!(
+                    "Checkpoint {} is for a copy from {} to {}",
+                    self.checkpoint_path.display(),
+                    checkpoint.source,
+                    checkpoint.target
+                )));
+            }
+            checkpoint
+        } else {
+            Checkpoint {
+                source: self.source.name.clone(),
+                target: self.target.name.clone(),
+                ..Default::default()
+            }
+        };
+
+        let mut tables = Vec::new();
+        for table in &self.tables {
+            let mut progress = checkpoint
+                .tables
+                .get(&table.model)
+                .cloned()
+                .unwrap_or_default();
+            if progress.done {
+                output::list_item(&format!(
+                    "{}: already copied ({} rows)",
+                    table.model, progress.rows
+                ));
+                continue;
+            }
+
+            let table_started = Instant::now();
+            let resumed_rows = progress.rows;
+            loop {
+                let after = match (&progress.last_key, table.key()) {
+                    (Some(key), Some(index)) => Some(
+                        coerce(key, &table.columns[index], self.source.dialect)
+                            .map_err(|e| CliError::Database(format!("{}: {}", table.model, e)))?,
+                    ),
+                    _ => None,
+                };
+                let sql = table.select_sql(
+                    self.source.dialect,
+                    after.as_deref(),
+                    progress.rows,
+                    self.batch_size,
+                );
+                let rows: Vec<Vec<Value>> = self
+                    .source
+                    .query_json(&sql)?
+                    .into_iter()
+                    .map(|row| table.row_values(row))
+                    .collect();
+                if rows.is_empty() {
+                    break;
+                }
+
+                let literals = self.coerce_rows(table, progress.rows, &rows)?;
+                self.target
+                    .execute(&table.insert_sql(self.target.dialect, &literals))?;
+
+                progress.rows += rows.len() as u64;
+                if let Some(key) = table.key() {
+                    progress.last_key = rows.last().and_then(|row| row.get(key)).cloned();
+                }
+                checkpoint
+                    .tables
+                    .insert(table.model.clone(), progress.clone());
+                checkpoint.save(&self.checkpoint_path)?;
+
+                if rows.len() < self.batch_size {
+                    break;
+                }
+            }
+
+            if table.auto_key && progress.rows > resumed_rows {
+                self.reset_sequence(table)?;
+            }
+
+            progress.done = true;
+            checkpoint
+                .tables
+                .insert(table.model.clone(), progress.clone());
+            checkpoint.save(&self.checkpoint_path)?;
+
+            let copied = TableCopy {
+                model: table.model.clone(),
+                table: table.table.clone(),
+                rows: progress.rows - resumed_rows,
+                duration: table_started.elapsed(),
+            };
+            output::list_item(&format!(
+                "{}: {} rows ({:.0} rows/s)",
+                copied.model,
+                copied.rows,
+                copied.rows_per_second()
+            ));
+            tables.push(copied);
+        }
+
+        // The copy is complete; nothing left to resume
+        std::fs::remove_file(&self.checkpoint_path)?;
+
+        Ok(CopyResult {
+            tables,
+            duration: started.elapsed(),
+        })
+    }
+
+    fn coerce_rows(
+        &self,
+        table: &CopyTable,
+        offset: u64,
+        rows: &[Vec<Value>],
+    ) -> CliResult<Vec<Vec<String>>> {
+        rows.iter()
+            .enumerate()
+            .map(|(i, row)| {
+                table
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(c, column)| {
+                        let value = row.get(c).unwrap_or(&Value::Null);
+                        coerce(value, column, self.target.dialect).map_err(|e| {
+                            CliError::Database(format!(
+                                "{}.{} (row {}): {}",
+                                table.model,
+                                column.name,
+                                offset + i as u64 + 1,
+                                e
+                            ))
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Move a PostgreSQL sequence past the copied keys
+    fn reset_sequence(&self, table: &CopyTable) -> CliResult<()> {
+        let (Dialect::Postgres, Some(key)) = (self.target.dialect, table.key()) else {
+            return Ok(());
+        };
+        let dialect = self.target.dialect;
+        let table_name = dialect.quote(&table.table);
+        let column = &table.columns[key].name;
+        let sequence = format!(
+            "pg_get_serial_sequence({}, {})",
+            dialect.string(&table_name),
+            dialect.string(column)
+        );
+        self.target.execute(&format!(
+            "SELECT setval({}, MAX({})) FROM {} HAVING {} IS NOT NULL AND MAX({}) IS NOT NULL;",
+            sequence,
+            dialect.quote(column),
+            table_name,
+            sequence,
+            dialect.quote(column)
+        ))?;
+        Ok(())
+    }
+}
+
+/// Result of a copy
+#[derive(Debug)]
+pub struct CopyResult {
+    /// Tables copied in this run
+    pub tables: Vec<TableCopy>,
+    /// Total duration
+    pub duration: Duration,
+}
+
+impl CopyResult {
+    /// Rows copied in this run
+    pub fn rows(&self) -> u64 {
+        self.tables.iter().map(|t| t.rows).sum()
+    }
+
+    /// Overall throughput
+    pub fn rows_per_second(&self) -> f64 {
+        rate(self.rows(), self.duration)
+    }
+}
+
+/// Rows copied for one model
+#[derive(Debug)]
+pub struct TableCopy {
+    /// Model name
+    pub model: String,
+    /// Table name
+    pub table: String,
+    /// Rows copied in this run
+    pub rows: u64,
+    /// Time spent on the table
+    pub duration: Duration,
+}
+
+impl TableCopy {
+    /// Throughput for the table
+    pub fn rows_per_second(&self) -> f64 {
+        rate(self.rows, self.duration)
+    }
+}
+
+fn rate(rows: u64, duration: Duration) -> f64 {
+    let seconds = duration.as_secs_f64();
+    if seconds > 0.0 {
+        rows as f64 / seconds
+    } else {
+        rows as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        prax_schema::validate_schema(
+            r#"
+enum Role {
+    User
+    Admin
+}
+
+model Post {
+    id       Int      @id @auto
+    title    String
+    authorId Int      @map("author_id")
+    author   User     @relation(fields: [authorId], references: [id])
+    tags     String[]
+}
+
+model User {
+    id        Int      @id @auto
+    active    Boolean
+    role      Role
+    balance   Decimal?
+    createdAt DateTime @map("created_at")
+    posts     Post[]
+
+    @@map("users")
+}
+"#,
+        )
+        .unwrap()
+    }
+
+    fn column(kind: ColumnKind) -> CopyColumn {
+        CopyColumn {
+            name: "c".to_string(),
+            kind,
+            optional: false,
+            list: false,
+        }
+    }
+
+    #[test]
+    fn test_copy_plan_orders_parents_first() {
+        let plan = copy_plan(&schema(), &[]).unwrap();
+        let models: Vec<&str> = plan.iter().map(|t| t.model.as_str()).collect();
+        assert_eq!(models, ["User", "Post"]);
+
+        let users = &plan[0];
+        assert_eq!(users.table, "users");
+        assert_eq!(users.primary_key, [0]);
+        assert!(users.auto_key);
+        let names: Vec<&str> = users.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "active", "role", "balance", "created_at"]);
+        assert_eq!(users.columns[3].kind, ColumnKind::Decimal);
+
+        assert!(copy_plan(&schema(), &["Missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_coerce_across_dialects() {
+        let boolean = column(ColumnKind::Boolean);
+        assert_eq!(
+            coerce(&Value::from(1), &boolean, Dialect::Postgres).unwrap(),
+            "TRUE"
+        );
+        assert_eq!(
+            coerce(&Value::from("f"), &boolean, Dialect::MySql).unwrap(),
+            "0"
+        );
+
+        let timestamp = column(ColumnKind::DateTime);
+        assert_eq!(
+            coerce(
+                &Value::from("2024-05-01 10:30:00.000000"),
+                &timestamp,
+                Dialect::Postgres
+            )
+            .unwrap(),
+            "'2024-05-01T10:30:00+00:00'"
+        );
+        assert_eq!(
+            coerce(
+                &Value::from("2024-05-01T10:30:00+02:00"),
+                &timestamp,
+                Dialect::MySql
+            )
+            .unwrap(),
+            "'2024-05-01 08:30:00.000000'"
+        );
+
+        let int = column(ColumnKind::Int);
+        assert_eq!(
+            coerce(&Value::from("42"), &int, Dialect::Sqlite).unwrap(),
+            "42"
+        );
+        assert!(coerce(&Value::from("x"), &int, Dialect::Sqlite).is_err());
+        assert!(coerce(&Value::Null, &int, Dialect::Sqlite).is_err());
+
+        let text = column(ColumnKind::Text);
+        assert_eq!(
+            coerce(&Value::from("it's"), &text, Dialect::Postgres).unwrap(),
+            "'it''s'"
+        );
+        assert_eq!(
+            coerce(&Value::from("a\\b"), &text, Dialect::MySql).unwrap(),
+            "'a\\\\b'"
+        );
+
+        let json = column(ColumnKind::Json);
+        assert_eq!(
+            coerce(&Value::from("{\"a\": 1}"), &json, Dialect::Postgres).unwrap(),
+            "'{\"a\":1}'"
+        );
+
+        let bytes = column(ColumnKind::Bytes);
+        assert_eq!(
+            coerce(&Value::from("CAFE"), &bytes, Dialect::Sqlite).unwrap(),
+            "X'cafe'"
+        );
+        assert_eq!(
+            coerce(&Value::from("cafe"), &bytes, Dialect::Postgres).unwrap(),
+            "decode('cafe', 'hex')"
+        );
+
+        let tags = CopyColumn {
+            list: true,
+            ..column(ColumnKind::Text)
+        };
+        assert_eq!(
+            coerce(&serde_json::json!(["a", "b"]), &tags, Dialect::Postgres).unwrap(),
+            "ARRAY['a', 'b']"
+        );
+        assert_eq!(
+            coerce(&serde_json::json!(["a"]), &tags, Dialect::Sqlite).unwrap(),
+            "'[\"a\"]'"
+        );
+    }
+
+    #[test]
+    fn test_select_and_insert_sql() {
+        let plan = copy_plan(&schema(), &[]).unwrap();
+        let users = &plan[0];
+
+        let first = users.select_sql(Dialect::Sqlite, None, 0, 100);
+        assert!(first.starts_with("SELECT json_array(\"id\", \"active\""));
+        assert!(first.contains("CAST(\"balance\" AS TEXT)"));
+        assert!(first.ends_with("FROM \"users\" ORDER BY \"id\" LIMIT 100;"));
+
+        let next = users.select_sql(Dialect::MySql, Some("100"), 100, 100);
+        assert!(next.contains("WHERE `id` > 100 ORDER BY `id` LIMIT 100;"));
+        assert!(!next.contains("OFFSET"));
+
+        let pg = users.select_sql(Dialect::Postgres, None, 0, 10);
+        assert!(pg.starts_with("SELECT row_to_json(t) FROM (SELECT \"id\" AS c0"));
+        let row = users.row_values(serde_json::json!({"c1": true, "c0": 7}));
+        assert_eq!(row[0], Value::from(7));
+        assert_eq!(row[1], Value::from(true));
+
+        let insert = users.insert_sql(
+            Dialect::Postgres,
+            &[vec![
+                "1".into(),
+                "TRUE".into(),
+                "'User'".into(),
+                "NULL".into(),
+                "'x'".into(),
+            ]],
+        );
+        assert!(insert.starts_with(
+            "INSERT INTO \"users\" (\"id\", \"active\", \"role\", \"balance\", \"created_at\") VALUES"
+        ));
+    }
+
+    #[test]
+    fn test_datasource_resolution() {
+        let mut config = Config::default();
+        config.datasources.insert(
+            "analytics".to_string(),
+            crate::config::DatasourceConfig {
+                provider: "duckdb".to_string(),
+                url: "analytics.duckdb".to_string(),
+            },
+        );
+
+        let analytics = Datasource::resolve("analytics", &config).unwrap();
+        assert_eq!(analytics.dialect, Dialect::DuckDb);
+
+        let mysql = Datasource::resolve("mysql://root@localhost/app", &config).unwrap();
+        assert_eq!(mysql.dialect, Dialect::MySql);
+
+        let sqlite = Datasource::resolve("sqlite:./dev.db", &config).unwrap();
+        assert_eq!(sqlite.path(), "./dev.db");
+
+        assert!(Datasource::resolve("unknown", &config).is_err());
+    }
+}
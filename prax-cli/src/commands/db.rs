@@ -2,14 +2,173 @@
 
 use std::path::PathBuf;
 
+use serde::Serialize;
+
 use crate::cli::{DbArgs, OutputFormat};
+use crate::commands::advisor::{IndexSuggestion, advise, estimate, schema_patch};
+use crate::commands::checksum::{ChunkChecksum, TableChecksum, checksum_ranges, checksum_table};
+use crate::commands::copy::{CopyRunner, Datasource, Dialect, copy_plan};
+use crate::commands::health::{
+    FindingKind, HealthFinding, INDEX_USAGE_SQL, IndexUsage, check, format_bytes,
+};
 use crate::commands::introspect::{
     IntrospectionOptions, format_as_json, format_as_prax, format_as_sql, get_database_type,
+    preserve_existing_schema,
 };
 use crate::commands::seed::{SeedRunner, find_seed_file, get_database_url};
 use crate::config::{CONFIG_FILE_NAME, Config, SCHEMA_FILE_NAME};
 use crate::error::{CliError, CliResult};
 use crate::output::{self, success, warn};
+use prax_query::diagnostics::{
+    QueryInsight, ServerStatement, ServerStatsSource, StatementStats, TableMaintenance, correlate,
+};
+
+/// Machine-readable result of `prax db pull`
+#[derive(Debug, Serialize)]
+pub struct PullReport {
+    /// Database provider
+    pub provider: String,
+    /// Introspected tables
+    pub tables: Vec<PulledTable>,
+    /// Introspected enum names
+    pub enums: Vec<String>,
+    /// Introspected view names
+    pub views: Vec<String>,
+    /// File the schema was written to, if any
+    pub output: Option<String>,
+    /// Generated schema content (only with `--print`)
+    pub schema: Option<String>,
+}
+
+/// A table in a [`PullReport`]
+#[derive(Debug, Serialize)]
+pub struct PulledTable {
+    /// Table name
+    pub name: String,
+    /// Number of columns
+    pub columns: usize,
+}
+
+/// Machine-readable result of `prax db copy`
+#[derive(Debug, Serialize)]
+pub struct CopyReport {
+    /// Source datasource
+    pub from: String,
+    /// Target datasource
+    pub to: String,
+    /// Copied tables
+    pub tables: Vec<CopiedTable>,
+    /// Rows copied in this run
+    pub rows: u64,
+    /// Total duration in seconds
+    pub seconds: f64,
+    /// Overall throughput
+    pub rows_per_second: f64,
+}
+
+/// A table in a [`CopyReport`]
+#[derive(Debug, Serialize)]
+pub struct CopiedTable {
+    /// Model name
+    pub model: String,
+    /// Table name
+    pub table: String,
+    /// Rows copied in this run
+    pub rows: u64,
+    /// Time spent in seconds
+    pub seconds: f64,
+    /// Throughput for the table
+    pub rows_per_second: f64,
+}
+
+/// Machine-readable result of `prax db checksum`
+#[derive(Debug, Serialize)]
+pub struct ChecksumReport {
+    /// Checksummed datasource
+    pub from: String,
+    /// Datasource compared with, if any
+    pub against: Option<String>,
+    /// Rows per chunk
+    pub chunk_size: usize,
+    /// Checksums of each model's table
+    pub tables: Vec<TableChecksum>,
+    /// Chunks that differ between the datasources
+    pub mismatches: Vec<ChunkMismatch>,
+}
+
+/// A chunk in a [`ChecksumReport`] that differs between the datasources
+#[derive(Debug, Serialize)]
+pub struct ChunkMismatch {
+    /// Model name
+    pub model: String,
+    /// Chunk index
+    pub chunk: usize,
+    /// Key of the chunk's first row
+    pub first_key: Option<serde_json::Value>,
+    /// Key of the chunk's last row
+    pub last_key: Option<serde_json::Value>,
+    /// Rows in the chunk on the checksummed datasource
+    pub rows: u64,
+    /// Rows in the chunk on the compared datasource
+    pub against_rows: u64,
+}
+
+/// Machine-readable result of `prax db top-queries`
+#[derive(Debug, Serialize)]
+pub struct TopQueriesReport {
+    /// Datasource the server statistics were read from (with `--server`)
+    pub datasource: Option<String>,
+    /// Where the server keeps its statistics (with `--server`)
+    pub source: Option<ServerStatsSource>,
+    /// Client-side statistics file, if one was read
+    pub stats: Option<String>,
+    /// Queries by total time
+    pub queries: Vec<QueryInsight>,
+}
+
+/// Machine-readable result of `prax db advise-indexes`
+#[derive(Debug, Serialize)]
+pub struct AdviseIndexesReport {
+    /// Datasource read from (with `--server` or `--explain`)
+    pub datasource: Option<String>,
+    /// Client-side statistics file, if one was read
+    pub stats: Option<String>,
+    /// Statements analyzed
+    pub statements: usize,
+    /// Suggested indexes, by total time of the statements they serve
+    pub suggestions: Vec<IndexSuggestion>,
+    /// Schema patch (with `--patch`)
+    pub patch: Option<String>,
+    /// File the patch was written to, if any
+    pub output: Option<String>,
+}
+
+/// Machine-readable result of `prax db health`
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    /// Checked datasource
+    pub datasource: String,
+    /// Share of dead rows from which a table counts as bloated
+    pub bloat_percent: f64,
+    /// Indexes checked
+    pub indexes: usize,
+    /// Vacuum and analyze statistics of the models' tables
+    pub tables: Vec<ModelTable>,
+    /// Problems found
+    pub findings: Vec<HealthFinding>,
+}
+
+/// A model's table in a [`HealthReport`]
+#[derive(Debug, Serialize)]
+pub struct ModelTable {
+    /// Model name
+    pub model: String,
+    /// Whether the table sees heavy update and delete traffic
+    pub write_heavy: bool,
+    /// Table statistics and autovacuum settings
+    #[serde(flatten)]
+    pub stats: TableMaintenance,
+}
 
 /// Run the db command
 pub async fn run(args: DbArgs) -> CliResult<()> {
@@ -18,6 +177,13 @@ pub async fn run(args: DbArgs) -> CliResult<()> {
         crate::cli::DbSubcommand::Pull(pull_args) => run_pull(pull_args).await,
         crate::cli::DbSubcommand::Seed(seed_args) => run_seed(seed_args).await,
         crate::cli::DbSubcommand::Execute(exec_args) => run_execute(exec_args).await,
+        crate::cli::DbSubcommand::Copy(copy_args) => run_copy(copy_args).await,
+        crate::cli::DbSubcommand::Checksum(checksum_args) => run_checksum(checksum_args).await,
+        crate::cli::DbSubcommand::TopQueries(top_args) => run_top_queries(top_args).await,
+        crate::cli::DbSubcommand::AdviseIndexes(advise_args) => {
+            run_advise_indexes(advise_args).await
+        }
+        crate::cli::DbSubcommand::Health(health_args) => run_health(health_args).await,
     }
 }
 
@@ -43,7 +209,8 @@ async fn run_push(args: crate::cli::DbPushArgs) -> CliResult<()> {
     // Parse schema
     output::step(1, 4, "Parsing schema...");
     let schema_content = std::fs::read_to_string(&schema_path)?;
-    let schema = parse_schema(&schema_content)?;
+    let mut schema = parse_schema(&schema_content)?;
+    config.generator.client.apply_naming(&mut schema);
 
     // Introspect database
     output::step(2, 4, "Introspecting database...");
@@ -144,7 +311,7 @@ async fn run_pull(args: crate::cli::DbPullArgs) -> CliResult<()> {
 
     // Generate output
     output::step(2, 3, "Generating schema...");
-    let schema_content = match args.format {
+    let mut schema_content = match args.format {
         OutputFormat::Prax => format_as_prax(&db_schema, &config),
         OutputFormat::Json => format_as_json(&db_schema)?,
         OutputFormat::Sql => format_as_sql(&db_schema, db_type),
@@ -153,10 +320,13 @@ async fn run_pull(args: crate::cli::DbPullArgs) -> CliResult<()> {
     // Output schema
     output::step(3, 3, "Writing output...");
 
+    let mut written_to = None;
     if args.print {
         output::newline();
         output::section("Generated Schema");
-        println!("{}", schema_content);
+        if !output::is_json_mode() {
+            println!("{}", schema_content);
+        }
     } else {
         let output_path = args.output.unwrap_or_else(|| {
             let ext = match args.format {
@@ -176,12 +346,50 @@ async fn run_pull(args: crate::cli::DbPullArgs) -> CliResult<()> {
             }
         }
 
+        // Keep renamed models and fields, doc comments and plugin attributes
+        // such as `@search.indexed` across pulls
+        if matches!(args.format, OutputFormat::Prax) && output_path.exists() {
+            let existing = std::fs::read_to_string(&output_path)?;
+            let (content, preserved) =
+                preserve_existing_schema(&schema_content, &existing, &config)?;
+            if preserved.names > 0 {
+                output::info(&format!(
+                    "Preserved {} name(s) and doc comment(s) from the existing schema",
+                    preserved.names
+                ));
+            }
+            if preserved.attributes > 0 {
+                output::info(&format!(
+                    "Preserved {} custom attribute(s) from the existing schema",
+                    preserved.attributes
+                ));
+            }
+            schema_content = content;
+        }
+
         std::fs::write(&output_path, &schema_content)?;
 
         output::newline();
         success(&format!("Schema written to {}", output_path.display()));
+        written_to = Some(output_path.display().to_string());
     }
 
+    output::report(&PullReport {
+        provider: config.database.provider.clone(),
+        tables: db_schema
+            .tables
+            .iter()
+            .map(|t| PulledTable {
+                name: t.name.clone(),
+                columns: t.columns.len(),
+            })
+            .collect(),
+        enums: db_schema.enums.iter().map(|e| e.name.clone()).collect(),
+        views: db_schema.views.iter().map(|v| v.name.clone()).collect(),
+        output: written_to,
+        schema: args.print.then(|| schema_content.clone()),
+    });
+
     output::newline();
     output::section("Summary");
     output::kv("Tables", &db_schema.tables.len().to_string());
@@ -282,7 +490,7 @@ async fn run_seed(args: crate::cli::DbSeedArgs) -> CliResult<()> {
 }
 
 /// Mask sensitive parts of database URL for display
-fn mask_database_url(url: &str) -> String {
+pub(crate) fn mask_database_url(url: &str) -> String {
     if let Ok(parsed) = url::Url::parse(url) {
         let mut masked = parsed.clone();
         if parsed.password().is_some() {
@@ -299,6 +507,645 @@ fn mask_database_url(url: &str) -> String {
     }
 }
 
+/// Run `prax db copy` - Copy data between datasources
+async fn run_copy(args: crate::cli::DbCopyArgs) -> CliResult<()> {
+    output::header("Database Copy");
+
+    let cwd = std::env::current_dir()?;
+    let config = load_config(&cwd)?;
+    let schema_path = args.schema.unwrap_or_else(|| cwd.join(SCHEMA_FILE_NAME));
+
+    let source = Datasource::resolve(&args.from, &config)?;
+    let target = Datasource::resolve(&args.to, &config)?;
+    if source.url == target.url {
+        return Err(CliError::Config(
+            "Source and target are the same datasource".to_string(),
+        ));
+    }
+
+    output::kv("Schema", &schema_path.display().to_string());
+    output::kv(
+        "From",
+        &format!("{} ({})", mask_database_url(&source.url), source.dialect),
+    );
+    output::kv(
+        "To",
+        &format!("{} ({})", mask_database_url(&target.url), target.dialect),
+    );
+    output::kv("Batch size", &args.batch_size.to_string());
+    output::newline();
+
+    output::step(1, 2, "Planning copy...");
+    let schema_content = std::fs::read_to_string(&schema_path)?;
+    let schema = prax_schema::validate_schema(&schema_content)
+        .map_err(|e| CliError::Schema(format!("Failed to parse schema: {}", e)))?;
+    let tables = copy_plan(&schema, &args.models)?;
+
+    if args.resume && !args.checkpoint.exists() {
+        warn("No checkpoint found; starting from the beginning.");
+    } else if !args.resume && args.checkpoint.exists() {
+        warn(&format!(
+            "Ignoring checkpoint {}; pass --resume to continue it.",
+            args.checkpoint.display()
+        ));
+    }
+
+    output::step(2, 2, "Copying rows...");
+    let result = CopyRunner::new(source.clone(), target.clone(), tables, args.checkpoint)
+        .with_batch_size(args.batch_size)
+        .run(args.resume)
+        .await?;
+
+    output::newline();
+    success("Copy complete!");
+
+    output::report(&CopyReport {
+        from: source.name.clone(),
+        to: target.name.clone(),
+        tables: result
+            .tables
+            .iter()
+            .map(|t| CopiedTable {
+                model: t.model.clone(),
+                table: t.table.clone(),
+                rows: t.rows,
+                seconds: t.duration.as_secs_f64(),
+                rows_per_second: t.rows_per_second(),
+            })
+            .collect(),
+        rows: result.rows(),
+        seconds: result.duration.as_secs_f64(),
+        rows_per_second: result.rows_per_second(),
+    });
+
+    output::newline();
+    output::section("Summary");
+    output::kv("Rows copied", &result.rows().to_string());
+    output::kv("Duration", &format!("{:.2}s", result.duration.as_secs_f64()));
+    output::kv(
+        "Throughput",
+        &format!("{:.0} rows/s", result.rows_per_second()),
+    );
+
+    Ok(())
+}
+
+/// Run `prax db checksum` - Checksum table data, or compare two datasources
+async fn run_checksum(args: crate::cli::DbChecksumArgs) -> CliResult<()> {
+    output::header("Database Checksum");
+
+    let cwd = std::env::current_dir()?;
+    let config = load_config(&cwd)?;
+    let schema_path = args.schema.unwrap_or_else(|| cwd.join(SCHEMA_FILE_NAME));
+
+    let source = Datasource::resolve(&args.from, &config)?;
+    let target = args
+        .against
+        .as_deref()
+        .map(|spec| Datasource::resolve(spec, &config))
+        .transpose()?;
+
+    output::kv("Schema", &schema_path.display().to_string());
+    output::kv(
+        "From",
+        &format!("{} ({})", mask_database_url(&source.url), source.dialect),
+    );
+    if let Some(target) = &target {
+        output::kv(
+            "Against",
+            &format!("{} ({})", mask_database_url(&target.url), target.dialect),
+        );
+    }
+    output::kv("Chunk size", &args.chunk.to_string());
+    output::newline();
+
+    output::step(1, 2, "Planning checksum...");
+    let schema_content = std::fs::read_to_string(&schema_path)?;
+    let schema = prax_schema::validate_schema(&schema_content)
+        .map_err(|e| CliError::Schema(format!("Failed to parse schema: {}", e)))?;
+    let tables = copy_plan(&schema, &args.models)?;
+
+    output::step(2, 2, "Checksumming rows...");
+    let mut checksums = Vec::with_capacity(tables.len());
+    let mut mismatches = Vec::new();
+    for table in &tables {
+        let checksum = checksum_table(&source, table, args.chunk)?;
+        output::list_item(&format!(
+            "{}: {} rows in {} chunks, {}",
+            checksum.model,
+            checksum.rows,
+            checksum.chunks.len(),
+            &checksum.hash[..16]
+        ));
+
+        if let Some(target) = &target {
+            let other = checksum_ranges(target, table, &checksum, args.chunk)?;
+            for chunk in checksum.mismatched_chunks(&other) {
+                let ours = checksum.chunks.get(chunk);
+                let theirs = other.chunks.get(chunk);
+                let keys = |chunk: Option<&ChunkChecksum>| {
+                    chunk.and_then(|c| Some((c.first_key.clone()?, c.last_key.clone()?)))
+                };
+                let (first_key, last_key) = keys(ours)
+                    .or_else(|| keys(theirs))
+                    .map_or((None, None), |(first, last)| (Some(first), Some(last)));
+                let mismatch = ChunkMismatch {
+                    model: checksum.model.clone(),
+                    chunk,
+                    first_key,
+                    last_key,
+                    rows: ours.map_or(0, |c| c.rows),
+                    against_rows: theirs.map_or(0, |c| c.rows),
+                };
+                warn(&format!(
+                    "{} chunk {}{} differs: {} rows vs {}",
+                    mismatch.model,
+                    mismatch.chunk,
+                    match (&mismatch.first_key, &mismatch.last_key) {
+                        (Some(first), Some(last)) => format!(" (keys {}..={})", first, last),
+                        _ => String::new(),
+                    },
+                    mismatch.rows,
+                    mismatch.against_rows
+                ));
+                mismatches.push(mismatch);
+            }
+        }
+        checksums.push(checksum);
+    }
+
+    let mismatched = mismatches.len();
+    output::report(&ChecksumReport {
+        from: source.name.clone(),
+        against: target.as_ref().map(|t| t.name.clone()),
+        chunk_size: args.chunk,
+        tables: checksums,
+        mismatches,
+    });
+
+    output::newline();
+    match &target {
+        Some(target) if mismatched > 0 => Err(CliError::Database(format!(
+            "{} chunk(s) differ between {} and {}",
+            mismatched, source.name, target.name
+        ))),
+        Some(target) => {
+            success(&format!("{} and {} match", source.name, target.name));
+            Ok(())
+        }
+        None => {
+            success("Checksum complete!");
+            Ok(())
+        }
+    }
+}
+
+/// Run `prax db top-queries` - Show the slowest queries
+async fn run_top_queries(args: crate::cli::DbTopQueriesArgs) -> CliResult<()> {
+    output::header("Top Queries");
+
+    let cwd = std::env::current_dir()?;
+    let stats_path = cwd.join(&args.stats);
+    let client = read_query_stats(&stats_path, args.server)?;
+    if client.is_some() {
+        output::kv("Client statistics", &stats_path.display().to_string());
+    }
+
+    let mut server = None;
+    if args.server {
+        let config = load_config(&cwd)?;
+        let datasource = Datasource::resolve(&args.from, &config)?;
+        let (source, statements) = read_server_stats(&datasource, args.limit)?;
+        output::kv(
+            "Server",
+            &format!("{} ({})", mask_database_url(&datasource.url), source),
+        );
+        server = Some((datasource, source, statements));
+    }
+    output::newline();
+
+    let mut queries = correlate(
+        client.as_deref().unwrap_or_default(),
+        server
+            .as_ref()
+            .map_or(&[][..], |(_, _, statements)| statements),
+    );
+    queries.truncate(args.limit);
+
+    if queries.is_empty() {
+        output::info("No statements recorded yet.");
+    } else {
+        let ms =
+            |value: Option<f64>| value.map_or_else(|| "-".to_string(), |v| format!("{:.2}", v));
+        let mut headers = vec![
+            "Model".to_string(),
+            "Calls".to_string(),
+            "Mean ms".to_string(),
+        ];
+        if server.is_some() {
+            headers.extend(["Server calls", "Server mean ms", "Overhead ms"].map(String::from));
+        }
+        headers.push("Query".to_string());
+
+        let rows: Vec<Vec<String>> = queries
+            .iter()
+            .map(|insight| {
+                let mut row = vec![
+                    insight.model.clone().unwrap_or_else(|| "-".to_string()),
+                    insight
+                        .client
+                        .as_ref()
+                        .map_or_else(|| "-".to_string(), |c| c.calls.to_string()),
+                    ms(insight.client.as_ref().map(|c| c.mean_ms)),
+                ];
+                if server.is_some() {
+                    row.push(
+                        insight
+                            .server
+                            .as_ref()
+                            .map_or_else(|| "-".to_string(), |s| s.calls.to_string()),
+                    );
+                    row.push(ms(insight.server.as_ref().map(|s| s.mean_ms)));
+                    row.push(ms(insight.client_overhead_ms()));
+                }
+                let mut query: String = insight.query.chars().take(80).collect();
+                if query.len() < insight.query.len() {
+                    query.push('…');
+                }
+                row.push(query);
+                row
+            })
+            .collect();
+        output::table(&headers, &rows);
+    }
+
+    output::report(&TopQueriesReport {
+        datasource: server
+            .as_ref()
+            .map(|(datasource, _, _)| datasource.name.clone()),
+        source: server.as_ref().map(|(_, source, _)| *source),
+        stats: client.as_ref().map(|_| stats_path.display().to_string()),
+        queries,
+    });
+    Ok(())
+}
+
+/// Run `prax db advise-indexes` - Suggest missing indexes for recorded queries
+async fn run_advise_indexes(args: crate::cli::DbAdviseIndexesArgs) -> CliResult<()> {
+    let cwd = std::env::current_dir()?;
+    let config = load_config(&cwd)?;
+    let schema_file = args
+        .schema
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(SCHEMA_FILE_NAME));
+    let schema_path = cwd.join(&schema_file);
+    let stats_path = cwd.join(&args.stats);
+
+    // A patch printed to stdout is kept clean for piping
+    let piped = args.patch && args.output.is_none();
+    if !piped {
+        output::header("Index Advisor");
+        output::kv("Schema", &schema_path.display().to_string());
+    }
+
+    let schema_content = std::fs::read_to_string(&schema_path)?;
+    let schema = prax_schema::validate_schema(&schema_content)
+        .map_err(|e| CliError::Schema(format!("Failed to parse schema: {}", e)))?;
+
+    let client = read_query_stats(&stats_path, args.server)?;
+    if client.is_some() && !piped {
+        output::kv("Client statistics", &stats_path.display().to_string());
+    }
+
+    let datasource = if args.server || args.explain {
+        Some(Datasource::resolve(&args.from, &config)?)
+    } else {
+        None
+    };
+    let dialect = match &datasource {
+        Some(datasource) => datasource.dialect,
+        None if args.from == "default" => Dialect::from_provider(&config.database.provider)?,
+        None => Datasource::resolve(&args.from, &config)?.dialect,
+    };
+
+    let mut server = Vec::new();
+    if let Some(datasource) = datasource.as_ref().filter(|_| args.server) {
+        let (source, statements) = read_server_stats(datasource, args.limit)?;
+        if !piped {
+            output::kv(
+                "Server",
+                &format!("{} ({})", mask_database_url(&datasource.url), source),
+            );
+        }
+        server = statements;
+    }
+
+    let insights = correlate(client.as_deref().unwrap_or_default(), &server);
+    let mut suggestions = advise(&schema, &insights, dialect);
+
+    if let Some(datasource) = datasource.as_ref().filter(|_| args.explain) {
+        for suggestion in &mut suggestions {
+            suggestion.estimate = Some(estimate(datasource, suggestion)?);
+        }
+    }
+
+    let patch = args.patch.then(|| {
+        schema_patch(
+            &schema_content,
+            &schema,
+            &schema_file.display().to_string(),
+            &suggestions,
+        )
+    });
+
+    let mut written_to = None;
+    if piped {
+        if !output::is_json_mode() {
+            print!("{}", patch.as_deref().unwrap_or_default());
+        }
+    } else {
+        output::kv("Statements", &insights.len().to_string());
+        output::newline();
+
+        if suggestions.is_empty() {
+            success("No missing indexes found");
+        } else {
+            let mut headers = vec![
+                "Model".to_string(),
+                "Index".to_string(),
+                "Calls".to_string(),
+                "Total ms".to_string(),
+            ];
+            if args.explain {
+                headers.extend(["Cost before", "Cost after", "Benefit"].map(String::from));
+            }
+            let rows: Vec<Vec<String>> = suggestions
+                .iter()
+                .map(|suggestion| {
+                    let mut index = suggestion.attribute();
+                    if let Some(predicate) = &suggestion.predicate {
+                        index.push_str(&format!(" WHERE {}", predicate));
+                    }
+                    let mut row = vec![
+                        suggestion.model.clone(),
+                        index,
+                        suggestion.calls.to_string(),
+                        format!("{:.2}", suggestion.total_ms),
+                    ];
+                    if let Some(estimate) = &suggestion.estimate {
+                        row.push(format!("{:.2}", estimate.before));
+                        row.push(format!("{:.2}", estimate.after));
+                        row.push(format!("{:.0}%", estimate.improvement));
+                    }
+                    row
+                })
+                .collect();
+            output::table(&headers, &rows);
+
+            output::newline();
+            output::section("SQL");
+            for suggestion in &suggestions {
+                output::list_item(&suggestion.sql);
+            }
+        }
+
+        if let Some(output_path) = &args.output {
+            std::fs::write(output_path, patch.as_deref().unwrap_or_default())?;
+            output::newline();
+            success(&format!("Patch written to {}", output_path.display()));
+            written_to = Some(output_path.display().to_string());
+        }
+    }
+
+    output::report(&AdviseIndexesReport {
+        datasource: datasource.as_ref().map(|d| d.name.clone()),
+        stats: client.as_ref().map(|_| stats_path.display().to_string()),
+        statements: insights.len(),
+        suggestions,
+        patch,
+        output: written_to,
+    });
+    Ok(())
+}
+
+/// Run `prax db health` - Report unused and duplicate indexes and bloat
+async fn run_health(args: crate::cli::DbHealthArgs) -> CliResult<()> {
+    output::header("Database Health");
+
+    let cwd = std::env::current_dir()?;
+    let config = load_config(&cwd)?;
+    let schema_path = args
+        .schema
+        .clone()
+        .unwrap_or_else(|| cwd.join(SCHEMA_FILE_NAME));
+    let schema_content = std::fs::read_to_string(&schema_path)?;
+    let schema = prax_schema::validate_schema(&schema_content)
+        .map_err(|e| CliError::Schema(format!("Failed to parse schema: {}", e)))?;
+
+    let datasource = Datasource::resolve(&args.from, &config)?;
+    if datasource.dialect != Dialect::Postgres {
+        return Err(CliError::Config(format!(
+            "db health reads pg_stat_user_indexes and needs PostgreSQL, not {}",
+            datasource.dialect
+        )));
+    }
+    output::kv("Schema", &schema_path.display().to_string());
+    output::kv("Database", &mask_database_url(&datasource.url));
+    output::newline();
+
+    let indexes = IndexUsage::from_rows(datasource.query_json(INDEX_USAGE_SQL)?)?;
+    let tables = TableMaintenance::from_rows(datasource.query_json(&TableMaintenance::sql())?)
+        .map_err(|e| CliError::Database(e.to_string()))?;
+    let findings = check(&schema, &indexes, &tables, args.bloat);
+    let model_tables: Vec<ModelTable> = schema
+        .models
+        .values()
+        .filter_map(|model| {
+            let stats = tables.iter().find(|t| t.table == model.table_name())?;
+            Some(ModelTable {
+                model: model.name().to_string(),
+                write_heavy: stats.is_write_heavy(),
+                stats: stats.clone(),
+            })
+        })
+        .collect();
+
+    let heavy: Vec<&ModelTable> = model_tables.iter().filter(|t| t.write_heavy).collect();
+    if !heavy.is_empty() {
+        output::section("Write-heavy tables");
+        let headers = [
+            "Model",
+            "Updates",
+            "Deletes",
+            "Dead",
+            "Last vacuum",
+            "Last analyze",
+            "Autovacuum at",
+        ]
+        .map(String::from);
+        let time = |at: &Option<String>| {
+            at.as_deref().map_or_else(
+                || "never".to_string(),
+                |at| at.replace('T', " ").chars().take(16).collect(),
+            )
+        };
+        let rows: Vec<Vec<String>> = heavy
+            .iter()
+            .map(|table| {
+                let stats = &table.stats;
+                vec![
+                    table.model.clone(),
+                    stats.updates.to_string(),
+                    stats.deletes.to_string(),
+                    format!("{:.1}%", stats.dead_percent()),
+                    time(&stats.last_vacuum),
+                    time(&stats.last_analyze),
+                    if stats.autovacuum && stats.autovacuum_enabled {
+                        format!("{} dead rows", stats.vacuum_trigger())
+                    } else {
+                        "off".to_string()
+                    },
+                ]
+            })
+            .collect();
+        output::table(&headers, &rows);
+        output::newline();
+    }
+
+    if findings.is_empty() {
+        success(&format!(
+            "No unused or duplicate indexes and no table maintenance problems ({} indexes, {} tables)",
+            indexes.len(),
+            model_tables.len()
+        ));
+    } else {
+        let headers = [
+            "Model",
+            "Problem",
+            "Index",
+            "Declared by",
+            "Reclaimable",
+            "Detail",
+        ]
+        .map(String::from);
+        let rows: Vec<Vec<String>> = findings
+            .iter()
+            .map(|finding| {
+                vec![
+                    finding.model.clone(),
+                    finding.kind.to_string(),
+                    finding.index.clone().unwrap_or_else(|| "-".to_string()),
+                    finding.attribute.clone().unwrap_or_else(|| "-".to_string()),
+                    format_bytes(finding.reclaimable_bytes),
+                    finding.detail.clone(),
+                ]
+            })
+            .collect();
+        output::table(&headers, &rows);
+
+        let pruned = findings
+            .iter()
+            .filter(|f| f.kind != FindingKind::Bloat && f.attribute.is_some())
+            .count();
+        if pruned > 0 {
+            output::newline();
+            output::info(&format!(
+                "Remove {} index declaration(s) from the schema and create a migration to drop them",
+                pruned
+            ));
+        }
+
+        let mut recommendations: Vec<&str> = Vec::new();
+        for recommendation in findings.iter().filter_map(|f| f.recommendation.as_deref()) {
+            if !recommendations.contains(&recommendation) {
+                recommendations.push(recommendation);
+            }
+        }
+        if !recommendations.is_empty() {
+            output::newline();
+            output::section("Recommendations");
+            for recommendation in recommendations {
+                output::list_item(recommendation);
+            }
+        }
+    }
+
+    let problems = findings.len();
+    output::report(&HealthReport {
+        datasource: datasource.name.clone(),
+        bloat_percent: args.bloat,
+        indexes: indexes.len(),
+        tables: model_tables,
+        findings,
+    });
+
+    if args.check && problems > 0 {
+        return Err(CliError::Database(format!(
+            "{} health problem(s) found",
+            problems
+        )));
+    }
+    Ok(())
+}
+
+/// Client-side statistics written by `QueryStatsMiddleware`; optional when
+/// server statistics are read instead
+fn read_query_stats(
+    stats_path: &std::path::Path,
+    server: bool,
+) -> CliResult<Option<Vec<StatementStats>>> {
+    if stats_path.exists() {
+        StatementStats::read_snapshot(stats_path)
+            .map(Some)
+            .map_err(|e| {
+                CliError::Config(format!(
+                    "Invalid query statistics in {}: {}",
+                    stats_path.display(),
+                    e
+                ))
+            })
+    } else if server {
+        Ok(None)
+    } else {
+        Err(CliError::Config(format!(
+            "No query statistics at {}. Write them with QueryStatsMiddleware::write_snapshot, or pass --server",
+            stats_path.display()
+        )))
+    }
+}
+
+/// The `limit` most expensive statements recorded by the database server
+fn read_server_stats(
+    datasource: &Datasource,
+    limit: usize,
+) -> CliResult<(ServerStatsSource, Vec<ServerStatement>)> {
+    let source = match datasource.dialect {
+        Dialect::Postgres => ServerStatsSource::PgStatStatements,
+        Dialect::MySql => ServerStatsSource::PerformanceSchema,
+        dialect => {
+            return Err(CliError::Config(format!(
+                "{} keeps no statement statistics; --server needs PostgreSQL or MySQL",
+                dialect
+            )));
+        }
+    };
+
+    let rows = datasource
+        .query_json(&source.top_queries_sql(limit))
+        .map_err(|e| match e {
+            CliError::Database(message) if message.contains("pg_stat_statements") => {
+                CliError::Database(format!(
+                    "{}\nhint: add pg_stat_statements to shared_preload_libraries and run CREATE EXTENSION pg_stat_statements",
+                    message
+                ))
+            }
+            e => e,
+        })?;
+    let statements =
+        ServerStatement::from_rows(rows).map_err(|e| CliError::Database(e.to_string()))?;
+    Ok((source, statements))
+}
+
 /// Run `prax db execute` - Execute raw SQL
 async fn run_execute(args: crate::cli::DbExecuteArgs) -> CliResult<()> {
     output::header("Execute SQL");
@@ -0,0 +1,1437 @@
+//! Conversion from SQL DDL to Prax.
+//!
+//! A lightweight parser for the statements that shape a schema: `CREATE
+//! TABLE`, `CREATE INDEX`, `CREATE TYPE ... AS ENUM` and the common `ALTER
+//! TABLE` / `DROP` forms found in migration directories and
+//! `pg_dump --schema-only` output. The statements are collected into the same
+//! [`DatabaseSchema`] that `prax db pull` introspects, so converting a dump
+//! gives the same schema as pulling the database it came from.
+//!
+//! Everything else (functions, triggers, grants, data) is skipped and
+//! reported as a warning.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use prax_query::introspection::{
+    ColumnInfo, DatabaseSchema, EnumInfo, ForeignKeyInfo, IndexColumn, IndexInfo, NormalizedType,
+    ReferentialAction, SortOrder, TableInfo, generate_prax_schema, normalize_type,
+};
+use prax_query::sql::DatabaseType;
+use prax_schema::ast::{Documentation, Span};
+
+use crate::commands::format::format_definitions;
+use crate::commands::introspect::get_database_type;
+use crate::commands::prisma::Conversion;
+use crate::error::{CliError, CliResult};
+
+/// Read SQL from `path`: a single file, or every `.sql` file below a
+/// directory in path order (the order migration tools apply them in).
+pub fn read_sql_sources(path: &Path) -> CliResult<String> {
+    if !path.is_dir() {
+        return Ok(std::fs::read_to_string(path)?);
+    }
+
+    let mut files = Vec::new();
+    collect_sql_files(path, &mut files)?;
+    if files.is_empty() {
+        return Err(CliError::Config(format!(
+            "No .sql files found in {}",
+            path.display()
+        )));
+    }
+    files.sort();
+
+    let mut source = String::new();
+    for file in files {
+        source.push_str(&std::fs::read_to_string(&file)?);
+        // A file may end without a terminating semicolon
+        source.push_str("\n;\n");
+    }
+    Ok(source)
+}
+
+fn collect_sql_files(dir: &Path, files: &mut Vec<PathBuf>) -> CliResult<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sql_files(&path, files)?;
+        } else if path.extension().and_then(|e| e.to_str()) == Some("sql") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Convert SQL DDL for the given provider to a Prax schema.
+pub fn ddl_to_prax(source: &str, provider: &str) -> CliResult<Conversion> {
+    let db_type = get_database_type(provider)?;
+
+    let mut ddl = DdlSchema::new(db_type);
+    for statement in tokenize(source) {
+        ddl.statement(&statement);
+    }
+    let (database, warnings) = ddl.finish();
+
+    let generated = generate_prax_schema(&database);
+    let mut schema = prax_schema::parse_schema(&generated)
+        .map_err(|e| CliError::Schema(format!("Converted schema does not parse: {}", e)))?;
+
+    // The parser does not keep `///` lines ahead of a model, so table
+    // comments are attached to the AST directly.
+    for table in &database.tables {
+        let Some(comment) = &table.comment else {
+            continue;
+        };
+        let key = table.name.replace('_', "");
+        if let Some(model) = schema
+            .models
+            .values_mut()
+            .find(|m| m.table_name().replace('_', "").eq_ignore_ascii_case(&key))
+        {
+            model.documentation = Some(Documentation::new(comment.clone(), Span::new(0, 0)));
+        }
+    }
+
+    let mut output = String::new();
+    output.push_str("datasource db {\n");
+    output.push_str(&format!("    provider = \"{}\"\n", provider));
+    output.push_str("    url      = env(\"DATABASE_URL\")\n");
+    output.push_str("}\n\n");
+    output.push_str("generator client {\n");
+    output.push_str("    provider = \"prax-client-rust\"\n");
+    output.push_str("    output   = \"./src/generated\"\n");
+    output.push_str("}\n");
+    output.push_str(&format_definitions(&schema));
+
+    Ok(Conversion {
+        schema: output,
+        warnings,
+    })
+}
+
+// ============================================================================
+// Tokenizer
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Keyword or unquoted identifier.
+    Word(String),
+    /// Quoted identifier (`"x"`, `` `x` `` or `[x]`).
+    Quoted(String),
+    /// String literal, including dollar-quoted bodies.
+    Str(String),
+    /// Numeric literal.
+    Number(String),
+    /// Any other character.
+    Punct(char),
+}
+
+/// Split SQL into statements of tokens, dropping comments.
+fn tokenize(source: &str) -> Vec<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            ';' => {
+                if !current.is_empty() {
+                    statements.push(std::mem::take(&mut current));
+                }
+                i += 1;
+            }
+            '\'' => {
+                let (text, end) = read_quoted(&chars, i, '\'');
+                current.push(Token::Str(text));
+                i = end;
+            }
+            '"' | '`' => {
+                let (text, end) = read_quoted(&chars, i, c);
+                current.push(Token::Quoted(text));
+                i = end;
+            }
+            '[' if next != Some(']') && !next.is_some_and(|n| n.is_ascii_digit()) => {
+                let (text, end) = read_quoted(&chars, i, ']');
+                current.push(Token::Quoted(text));
+                i = end;
+            }
+            '$' => match dollar_tag(&chars, i) {
+                Some(tag) => {
+                    let body_start = i + tag.len();
+                    let mut end = body_start;
+                    while end < chars.len() && !chars[end..].starts_with(&tag) {
+                        end += 1;
+                    }
+                    current.push(Token::Str(chars[body_start..end].iter().collect()));
+                    i = (end + tag.len()).min(chars.len());
+                }
+                None => {
+                    current.push(Token::Punct(c));
+                    i += 1;
+                }
+            },
+            c if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                current.push(Token::Number(chars[start..i].iter().collect()));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    i += 1;
+                }
+                current.push(Token::Word(chars[start..i].iter().collect()));
+            }
+            c => {
+                current.push(Token::Punct(c));
+                i += 1;
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        statements.push(current);
+    }
+    statements
+}
+
+/// Read a quoted string starting at `start`; a doubled quote is an escape.
+fn read_quoted(chars: &[char], start: usize, close: char) -> (String, usize) {
+    let mut text = String::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == close {
+            if close != ']' && chars.get(i + 1) == Some(&close) {
+                text.push(close);
+                i += 2;
+                continue;
+            }
+            return (text, i + 1);
+        }
+        text.push(chars[i]);
+        i += 1;
+    }
+    (text, i)
+}
+
+/// The `$tag$` opening a dollar-quoted string at `start`, if any.
+fn dollar_tag(chars: &[char], start: usize) -> Option<Vec<char>> {
+    let mut end = start + 1;
+    while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+        end += 1;
+    }
+    (chars.get(end) == Some(&'$')).then(|| chars[start..=end].to_vec())
+}
+
+/// Render tokens back to SQL text.
+fn render(tokens: &[Token]) -> String {
+    let mut output = String::new();
+    let mut prev: Option<&Token> = None;
+    for token in tokens {
+        let tight = match (prev, token) {
+            (None, _) => true,
+            (_, Token::Punct('(' | ')' | ',' | '.' | ':' | ']')) => true,
+            (Some(Token::Punct('(' | '.' | ':' | '[')), _) => true,
+            (Some(Token::Punct('-')), _) => output == "-",
+            _ => false,
+        };
+        if !tight {
+            output.push(' ');
+        }
+        match token {
+            Token::Word(text) | Token::Number(text) => output.push_str(text),
+            Token::Quoted(text) => output.push_str(&format!("\"{}\"", text)),
+            Token::Str(text) => output.push_str(&format!("'{}'", text.replace('\'', "''"))),
+            Token::Punct(c) => output.push(*c),
+        }
+        prev = Some(token);
+    }
+    output
+}
+
+/// Split tokens on commas outside parentheses.
+fn split_top_level(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        parts.push(&tokens[start..]);
+    }
+    parts
+}
+
+/// Keywords that end a column type or default expression.
+fn is_constraint_keyword(word: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "NOT",
+        "NULL",
+        "PRIMARY",
+        "UNIQUE",
+        "DEFAULT",
+        "REFERENCES",
+        "AUTO_INCREMENT",
+        "AUTOINCREMENT",
+        "GENERATED",
+        "IDENTITY",
+        "CHECK",
+        "COLLATE",
+        "COMMENT",
+        "CONSTRAINT",
+        "ON",
+    ];
+    KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k))
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn rest(&self) -> &'a [Token] {
+        &self.tokens[self.pos.min(self.tokens.len())..]
+    }
+
+    fn is_keyword_at(&self, offset: usize, keyword: &str) -> bool {
+        matches!(
+            self.tokens.get(self.pos + offset),
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword)
+        )
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    /// Consume a keyword if it is next.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let matched = self.is_keyword_at(0, keyword);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// Consume a sequence of keywords if all of them are next.
+    fn keywords(&mut self, keywords: &[&str]) -> bool {
+        let matched = keywords
+            .iter()
+            .enumerate()
+            .all(|(i, keyword)| self.is_keyword_at(i, keyword));
+        if matched {
+            self.pos += keywords.len();
+        }
+        matched
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek()? {
+            Token::Word(name) | Token::Quoted(name) => {
+                self.pos += 1;
+                Some(name.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Read a possibly schema-qualified name, keeping the last part.
+    fn name(&mut self) -> Option<String> {
+        let mut name = self.ident()?;
+        while self.is_punct('.') {
+            self.pos += 1;
+            name = self.ident()?;
+        }
+        Some(name)
+    }
+
+    /// Consume a parenthesized group, returning the tokens inside it.
+    fn group(&mut self) -> Option<&'a [Token]> {
+        if !self.is_punct('(') {
+            return None;
+        }
+        let start = self.pos + 1;
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            self.pos += 1;
+            match token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(&self.tokens[start..self.pos - 1]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(&self.tokens[start..])
+    }
+
+    /// Consume an expression up to the next column constraint, dropping a
+    /// trailing `::type` cast.
+    fn expression(&mut self) -> &'a [Token] {
+        let start = self.pos;
+        let mut end = None;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Word(w) if is_constraint_keyword(w) => break,
+                Token::Punct('(') => {
+                    self.group();
+                }
+                Token::Punct(':') if self.tokens.get(self.pos + 1) == Some(&Token::Punct(':')) => {
+                    end.get_or_insert(self.pos);
+                    self.pos += 2;
+                }
+                _ => self.pos += 1,
+            }
+        }
+        &self.tokens[start..end.unwrap_or(self.pos)]
+    }
+
+    /// Read a column type: its words, the first argument list and whether it
+    /// is an array.
+    fn column_type(&mut self) -> ColumnType<'a> {
+        let mut column_type = ColumnType::default();
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Word(w) if is_constraint_keyword(w) => break,
+                Token::Word(w)
+                    if w.eq_ignore_ascii_case("CHARSET")
+                        || (w.eq_ignore_ascii_case("CHARACTER")
+                            && self.is_keyword_at(1, "SET")) =>
+                {
+                    break;
+                }
+                Token::Word(w) | Token::Quoted(w) => {
+                    if !["UNSIGNED", "SIGNED", "ZEROFILL"]
+                        .iter()
+                        .any(|k| w.eq_ignore_ascii_case(k))
+                    {
+                        column_type.words.push(w.to_lowercase());
+                    }
+                    self.pos += 1;
+                }
+                Token::Punct('(') => {
+                    let args = self.group();
+                    if column_type.args.is_none() {
+                        column_type.args = args;
+                    }
+                }
+                Token::Punct('[') => {
+                    while self.peek().is_some_and(|t| *t != Token::Punct(']')) {
+                        self.pos += 1;
+                    }
+                    self.pos += 1;
+                    column_type.array = true;
+                }
+                // Schema-qualified type: keep the last part
+                Token::Punct('.') => {
+                    column_type.words.clear();
+                    self.pos += 1;
+                }
+                _ => break,
+            }
+        }
+        column_type
+    }
+
+    /// Read the column list of a key or index, skipping expressions.
+    fn index_columns(&mut self) -> Vec<IndexColumn> {
+        let Some(group) = self.group() else {
+            return Vec::new();
+        };
+        split_top_level(group)
+            .into_iter()
+            .filter_map(|part| {
+                let mut parser = Parser::new(part);
+                let name = parser.ident()?;
+                // MySQL prefix length, e.g. `name(10)`
+                parser.group();
+                let order = if parser.keyword("DESC") {
+                    SortOrder::Desc
+                } else {
+                    SortOrder::Asc
+                };
+                Some(IndexColumn {
+                    name,
+                    order,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// Read `REFERENCES table (columns) [ON DELETE ...] [ON UPDATE ...]`.
+    fn references(&mut self, columns: Vec<String>) -> ForeignKeyInfo {
+        let referenced_table = self.name().unwrap_or_default();
+        let referenced_columns = self
+            .index_columns()
+            .into_iter()
+            .map(|column| column.name)
+            .collect();
+        let mut fk = ForeignKeyInfo {
+            columns,
+            referenced_table,
+            referenced_columns,
+            ..Default::default()
+        };
+
+        loop {
+            if self.keywords(&["ON", "DELETE"]) {
+                fk.on_delete = self.referential_action();
+            } else if self.keywords(&["ON", "UPDATE"]) {
+                fk.on_update = self.referential_action();
+            } else if self.keyword("MATCH") {
+                self.pos += 1;
+            } else if !(self.keyword("DEFERRABLE")
+                || self.keywords(&["NOT", "DEFERRABLE"])
+                || self.keywords(&["INITIALLY", "DEFERRED"])
+                || self.keywords(&["INITIALLY", "IMMEDIATE"]))
+            {
+                break;
+            }
+        }
+        fk
+    }
+
+    fn referential_action(&mut self) -> ReferentialAction {
+        if self.keyword("CASCADE") {
+            ReferentialAction::Cascade
+        } else if self.keyword("RESTRICT") {
+            ReferentialAction::Restrict
+        } else if self.keywords(&["SET", "NULL"]) {
+            ReferentialAction::SetNull
+        } else if self.keywords(&["SET", "DEFAULT"]) {
+            ReferentialAction::SetDefault
+        } else {
+            self.keywords(&["NO", "ACTION"]);
+            ReferentialAction::NoAction
+        }
+    }
+}
+
+/// A column type as written.
+#[derive(Default)]
+struct ColumnType<'a> {
+    words: Vec<String>,
+    args: Option<&'a [Token]>,
+    array: bool,
+}
+
+impl ColumnType<'_> {
+    fn name(&self) -> String {
+        self.words.join(" ")
+    }
+
+    fn numbers(&self) -> Vec<i32> {
+        self.args
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|token| match token {
+                Token::Number(n) => n.parse().ok(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn strings(&self) -> Vec<String> {
+        self.args
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|token| match token {
+                Token::Str(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Schema builder
+// ============================================================================
+
+/// Tables and enums collected from DDL statements.
+struct DdlSchema {
+    db_type: DatabaseType,
+    tables: Vec<TableInfo>,
+    enums: Vec<EnumInfo>,
+    warnings: Vec<String>,
+    skipped: BTreeMap<String, usize>,
+}
+
+impl DdlSchema {
+    fn new(db_type: DatabaseType) -> Self {
+        Self {
+            db_type,
+            tables: Vec::new(),
+            enums: Vec::new(),
+            warnings: Vec::new(),
+            skipped: BTreeMap::new(),
+        }
+    }
+
+    fn table_index(&self, name: &str) -> Option<usize> {
+        self.tables
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    fn statement(&mut self, tokens: &[Token]) {
+        let mut p = Parser::new(tokens);
+        let handled = if p.keyword("CREATE") {
+            p.keywords(&["OR", "REPLACE"]);
+            while ["TEMPORARY", "TEMP", "UNLOGGED", "GLOBAL", "LOCAL"]
+                .iter()
+                .any(|k| p.keyword(k))
+            {}
+            if p.keyword("TABLE") {
+                self.create_table(&mut p)
+            } else {
+                let unique = p.keyword("UNIQUE");
+                let _ = p.keyword("CLUSTERED") || p.keyword("NONCLUSTERED");
+                if p.keyword("INDEX") {
+                    self.create_index(&mut p, unique)
+                } else if !unique && p.keyword("TYPE") {
+                    self.create_type(&mut p)
+                } else {
+                    false
+                }
+            }
+        } else if p.keywords(&["ALTER", "TABLE"]) {
+            self.alter_table(&mut p)
+        } else if p.keyword("DROP") {
+            self.drop(&mut p)
+        } else if p.keywords(&["COMMENT", "ON"]) {
+            self.comment_on(&mut p)
+        } else {
+            false
+        };
+
+        if !handled {
+            *self.skipped.entry(statement_kind(tokens)).or_default() += 1;
+        }
+    }
+
+    fn create_table(&mut self, p: &mut Parser<'_>) -> bool {
+        p.keywords(&["IF", "NOT", "EXISTS"]);
+        let Some(name) = p.name() else {
+            return false;
+        };
+        // `CREATE TABLE ... AS SELECT` and `PARTITION OF` have no column list
+        let Some(body) = p.group() else {
+            return false;
+        };
+
+        let table = match self.table_index(&name) {
+            Some(index) => {
+                self.tables[index] = TableInfo {
+                    name,
+                    ..Default::default()
+                };
+                index
+            }
+            None => {
+                self.tables.push(TableInfo {
+                    name,
+                    ..Default::default()
+                });
+                self.tables.len() - 1
+            }
+        };
+        for element in split_top_level(body) {
+            self.table_element(table, element);
+        }
+        true
+    }
+
+    /// A column definition or table constraint.
+    fn table_element(&mut self, table: usize, tokens: &[Token]) {
+        let mut p = Parser::new(tokens);
+        let constraint = if p.keyword("CONSTRAINT") {
+            p.name()
+        } else {
+            None
+        };
+
+        if p.keywords(&["PRIMARY", "KEY"]) {
+            let _ = p.keyword("CLUSTERED") || p.keyword("NONCLUSTERED");
+            let columns = column_names(p.index_columns());
+            let table = &mut self.tables[table];
+            for column in table.columns.iter_mut() {
+                if columns.contains(&column.name) {
+                    column.is_primary_key = true;
+                    column.nullable = false;
+                }
+            }
+            table.primary_key = columns;
+        } else if p.keyword("UNIQUE") {
+            let _ = p.keyword("KEY") || p.keyword("INDEX");
+            let name = if p.is_punct('(') { None } else { p.name() };
+            let columns = p.index_columns();
+            self.add_index(table, constraint.or(name), columns, true);
+        } else if p.keywords(&["FOREIGN", "KEY"]) {
+            let name = if p.is_punct('(') { None } else { p.name() };
+            let columns = column_names(p.index_columns());
+            if p.keyword("REFERENCES") {
+                let mut fk = p.references(columns);
+                fk.name = constraint.or(name).unwrap_or_default();
+                self.tables[table].foreign_keys.push(fk);
+            }
+        } else if p.keyword("CHECK") || p.keyword("EXCLUDE") {
+            // Check constraints have no Prax equivalent
+        } else if constraint.is_none() && (p.keyword("KEY") || p.keyword("INDEX")) {
+            let name = if p.is_punct('(') { None } else { p.name() };
+            let columns = p.index_columns();
+            self.add_index(table, name, columns, false);
+        } else if constraint.is_none()
+            && (p.is_keyword_at(0, "FULLTEXT") || p.is_keyword_at(0, "SPATIAL"))
+        {
+            self.warnings.push(format!(
+                "{} index on `{}` was not converted",
+                render(&tokens[..1]).to_uppercase(),
+                self.tables[table].name
+            ));
+        } else if constraint.is_none() {
+            self.column(table, &mut p);
+        }
+    }
+
+    fn column(&mut self, table: usize, p: &mut Parser<'_>) {
+        let Some(name) = p.ident() else {
+            return;
+        };
+        let column_type = p.column_type();
+        let type_name = column_type.name();
+        let numbers = column_type.numbers();
+
+        let mut column = ColumnInfo {
+            name: name.clone(),
+            db_type: match column_type.args {
+                Some(args) => format!("{}({})", type_name, render(args)),
+                None => type_name.clone(),
+            },
+            nullable: true,
+            auto_increment: matches!(
+                type_name.as_str(),
+                "serial" | "bigserial" | "smallserial" | "serial4" | "serial8" | "serial2"
+            ),
+            max_length: numbers.first().copied(),
+            precision: numbers.first().copied(),
+            scale: numbers.get(1).copied(),
+            ..Default::default()
+        };
+
+        // MySQL inline enums become a named enum per column
+        if type_name == "enum" && self.db_type == DatabaseType::MySQL {
+            let enum_name = format!("{}_{}", self.tables[table].name, name);
+            self.enums.push(EnumInfo {
+                name: enum_name.clone(),
+                values: column_type.strings(),
+                ..Default::default()
+            });
+            column.enum_name = Some(enum_name.clone());
+            column.normalized_type = NormalizedType::Enum(enum_name);
+        } else {
+            let normalized = match type_name.as_str() {
+                "serial4" => NormalizedType::Int,
+                "serial8" => NormalizedType::BigInt,
+                "serial2" => NormalizedType::SmallInt,
+                _ => normalize_type(
+                    self.db_type,
+                    &type_name,
+                    column.max_length,
+                    column.precision,
+                    column.scale,
+                ),
+            };
+            column.normalized_type = if column_type.array {
+                NormalizedType::Array(Box::new(normalized))
+            } else {
+                normalized
+            };
+        }
+
+        let mut foreign_key = None;
+        while !p.at_end() {
+            if p.keyword("CONSTRAINT") {
+                p.name();
+            } else if p.keywords(&["NOT", "NULL"]) {
+                column.nullable = false;
+            } else if p.keyword("NULL") {
+                column.nullable = true;
+            } else if p.keywords(&["PRIMARY", "KEY"]) {
+                column.is_primary_key = true;
+                column.nullable = false;
+                let _ = p.keyword("ASC") || p.keyword("DESC");
+            } else if p.keyword("UNIQUE") {
+                column.is_unique = true;
+                p.keyword("KEY");
+            } else if p.keyword("DEFAULT") {
+                if !p.keyword("NULL") {
+                    set_default(&mut column, p.expression());
+                }
+            } else if p.keyword("AUTO_INCREMENT") || p.keyword("AUTOINCREMENT") {
+                column.auto_increment = true;
+            } else if p.keyword("IDENTITY") {
+                column.auto_increment = true;
+                p.group();
+            } else if p.keyword("GENERATED") {
+                let _ = p.keyword("ALWAYS") || p.keywords(&["BY", "DEFAULT"]);
+                p.keywords(&["ON", "NULL"]);
+                p.keyword("AS");
+                if p.keyword("IDENTITY") {
+                    column.auto_increment = true;
+                    p.group();
+                } else {
+                    p.group();
+                    let _ = p.keyword("STORED") || p.keyword("VIRTUAL");
+                    self.warnings.push(format!(
+                        "generated column `{}.{}` was converted as a regular column",
+                        self.tables[table].name, name
+                    ));
+                }
+            } else if p.keyword("REFERENCES") {
+                foreign_key = Some(p.references(vec![name.clone()]));
+            } else if p.keyword("CHECK") {
+                p.group();
+            } else if p.keyword("COLLATE") {
+                p.name();
+            } else if p.keyword("COMMENT") {
+                if let Some(Token::Str(comment)) = p.peek() {
+                    column.comment = Some(comment.clone());
+                    p.pos += 1;
+                }
+            } else if p.keywords(&["ON", "UPDATE"]) {
+                p.expression();
+            } else if p.group().is_none() {
+                p.pos += 1;
+            }
+        }
+
+        let table = &mut self.tables[table];
+        if column.is_primary_key {
+            table.primary_key = vec![name.clone()];
+        }
+        if let Some(fk) = foreign_key {
+            table.foreign_keys.push(fk);
+        }
+        match table.columns.iter_mut().find(|c| c.name == name) {
+            Some(existing) => *existing = column,
+            None => table.columns.push(column),
+        }
+    }
+
+    /// Add a key or index. A single-column unique key becomes `@unique`.
+    fn add_index(
+        &mut self,
+        table: usize,
+        name: Option<String>,
+        columns: Vec<IndexColumn>,
+        is_unique: bool,
+    ) {
+        if columns.is_empty() {
+            self.warnings.push(format!(
+                "expression index on `{}` was not converted",
+                self.tables[table].name
+            ));
+            return;
+        }
+
+        let table = &mut self.tables[table];
+        if is_unique && columns.len() == 1 {
+            if let Some(column) = table.columns.iter_mut().find(|c| c.name == columns[0].name) {
+                column.is_unique = true;
+                return;
+            }
+        }
+
+        let name = name.unwrap_or_else(|| {
+            let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+            format!("{}_{}_idx", table.name, names.join("_"))
+        });
+        table.indexes.push(IndexInfo {
+            name,
+            columns,
+            is_unique,
+            ..Default::default()
+        });
+    }
+
+    fn create_index(&mut self, p: &mut Parser<'_>, unique: bool) -> bool {
+        p.keyword("CONCURRENTLY");
+        p.keywords(&["IF", "NOT", "EXISTS"]);
+        let name = if p.is_keyword_at(0, "ON") {
+            None
+        } else {
+            p.name()
+        };
+        if !p.keyword("ON") {
+            return false;
+        }
+        p.keyword("ONLY");
+        let Some(table_name) = p.name() else {
+            return false;
+        };
+        if p.keyword("USING") {
+            p.pos += 1;
+        }
+        let Some(table) = self.table_index(&table_name) else {
+            self.warnings.push(format!(
+                "index on unknown table `{}` was skipped",
+                table_name
+            ));
+            return true;
+        };
+
+        let columns = p.index_columns();
+        self.add_index(table, name, columns, unique);
+        if p.keyword("WHERE") {
+            if let Some(index) = self.tables[table].indexes.last_mut() {
+                index.filter = Some(render(p.rest()));
+            }
+        }
+        true
+    }
+
+    fn create_type(&mut self, p: &mut Parser<'_>) -> bool {
+        let Some(name) = p.name() else {
+            return false;
+        };
+        if !p.keywords(&["AS", "ENUM"]) {
+            return false;
+        }
+        let values = p
+            .group()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|token| match token {
+                Token::Str(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect();
+
+        self.enums.retain(|e| !e.name.eq_ignore_ascii_case(&name));
+        self.enums.push(EnumInfo {
+            name,
+            values,
+            ..Default::default()
+        });
+        true
+    }
+
+    fn alter_table(&mut self, p: &mut Parser<'_>) -> bool {
+        p.keywords(&["IF", "EXISTS"]);
+        p.keyword("ONLY");
+        let Some(name) = p.name() else {
+            return false;
+        };
+        let Some(table) = self.table_index(&name) else {
+            self.warnings.push(format!(
+                "ALTER TABLE on unknown table `{}` was skipped",
+                name
+            ));
+            return true;
+        };
+
+        for action in split_top_level(p.rest()) {
+            let mut p = Parser::new(action);
+            if p.keyword("ADD") {
+                p.keyword("COLUMN");
+                p.keywords(&["IF", "NOT", "EXISTS"]);
+                self.table_element(table, p.rest());
+            } else if p.keywords(&["DROP", "COLUMN"]) {
+                p.keywords(&["IF", "EXISTS"]);
+                if let Some(column) = p.name() {
+                    let table = &mut self.tables[table];
+                    table.columns.retain(|c| c.name != column);
+                    table.primary_key.retain(|c| *c != column);
+                    table
+                        .foreign_keys
+                        .retain(|fk| !fk.columns.contains(&column));
+                    table
+                        .indexes
+                        .retain(|index| !index.columns.iter().any(|c| c.name == column));
+                }
+            } else if p.keywords(&["DROP", "CONSTRAINT"]) {
+                p.keywords(&["IF", "EXISTS"]);
+                if let Some(constraint) = p.name() {
+                    let table = &mut self.tables[table];
+                    table.foreign_keys.retain(|fk| fk.name != constraint);
+                    table.indexes.retain(|index| index.name != constraint);
+                }
+            } else if p.keyword("ALTER") || p.keyword("MODIFY") {
+                p.keyword("COLUMN");
+                self.alter_column(table, &mut p);
+            } else {
+                let kind = format!("ALTER TABLE {}", statement_kind(action));
+                *self.skipped.entry(kind).or_default() += 1;
+            }
+        }
+        true
+    }
+
+    fn alter_column(&mut self, table: usize, p: &mut Parser<'_>) {
+        let Some(name) = p.name() else {
+            return;
+        };
+        let Some(column) = self.tables[table]
+            .columns
+            .iter_mut()
+            .find(|c| c.name == name)
+        else {
+            return;
+        };
+
+        if p.keywords(&["SET", "DEFAULT"]) {
+            set_default(column, p.expression());
+        } else if p.keywords(&["DROP", "DEFAULT"]) {
+            column.default = None;
+        } else if p.keywords(&["SET", "NOT", "NULL"]) {
+            column.nullable = false;
+        } else if p.keywords(&["DROP", "NOT", "NULL"]) {
+            column.nullable = true;
+        } else if p.keywords(&["SET", "DATA", "TYPE"]) || p.keyword("TYPE") {
+            let column_type = p.column_type();
+            let numbers = column_type.numbers();
+            let normalized = normalize_type(
+                self.db_type,
+                &column_type.name(),
+                numbers.first().copied(),
+                numbers.first().copied(),
+                numbers.get(1).copied(),
+            );
+            column.db_type = column_type.name();
+            column.normalized_type = if column_type.array {
+                NormalizedType::Array(Box::new(normalized))
+            } else {
+                normalized
+            };
+        }
+    }
+
+    fn drop(&mut self, p: &mut Parser<'_>) -> bool {
+        let kind = if p.keyword("TABLE") {
+            "TABLE"
+        } else if p.keyword("TYPE") {
+            "TYPE"
+        } else if p.keyword("INDEX") {
+            "INDEX"
+        } else {
+            return false;
+        };
+        p.keyword("CONCURRENTLY");
+        p.keywords(&["IF", "EXISTS"]);
+
+        for part in split_top_level(p.rest()) {
+            let Some(name) = Parser::new(part).name() else {
+                continue;
+            };
+            match kind {
+                "TABLE" => self.tables.retain(|t| !t.name.eq_ignore_ascii_case(&name)),
+                "TYPE" => self.enums.retain(|e| !e.name.eq_ignore_ascii_case(&name)),
+                _ => {
+                    for table in &mut self.tables {
+                        table.indexes.retain(|index| index.name != name);
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// `COMMENT ON TABLE t IS '...'`, which becomes the model doc comment.
+    fn comment_on(&mut self, p: &mut Parser<'_>) -> bool {
+        if !p.keyword("TABLE") {
+            return false;
+        }
+        let Some(name) = p.name() else {
+            return false;
+        };
+        if !p.keyword("IS") {
+            return false;
+        }
+        if let (Some(index), Some(Token::Str(comment))) = (self.table_index(&name), p.peek()) {
+            self.tables[index].comment = Some(comment.replace('\n', " "));
+        }
+        true
+    }
+
+    /// Resolve references between statements and build the schema.
+    fn finish(mut self) -> (DatabaseSchema, Vec<String>) {
+        let enum_names: Vec<String> = self.enums.iter().map(|e| e.name.clone()).collect();
+        let table_keys: Vec<(String, Vec<String>)> = self
+            .tables
+            .iter()
+            .map(|t| (t.name.clone(), t.primary_key.clone()))
+            .collect();
+
+        for table in &mut self.tables {
+            for column in &mut table.columns {
+                resolve_type(column, &enum_names, &table.name, &mut self.warnings);
+            }
+
+            for fk in &mut table.foreign_keys {
+                match table_keys
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&fk.referenced_table))
+                {
+                    Some((name, primary_key)) => {
+                        fk.referenced_table = name.clone();
+                        if fk.referenced_columns.is_empty() {
+                            fk.referenced_columns = primary_key.clone();
+                        }
+                    }
+                    None => self.warnings.push(format!(
+                        "`{}` references unknown table `{}`",
+                        table.name, fk.referenced_table
+                    )),
+                }
+            }
+        }
+
+        for enum_info in &mut self.enums {
+            for value in &mut enum_info.values {
+                let ident = identifier(value);
+                if ident != *value {
+                    self.warnings.push(format!(
+                        "enum value `{}` of `{}` was renamed to `{}`",
+                        value, enum_info.name, ident
+                    ));
+                    *value = ident;
+                }
+            }
+        }
+
+        for (kind, count) in self.skipped {
+            self.warnings.push(format!(
+                "skipped {} `{}` statement{}",
+                count,
+                kind,
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+
+        let database = DatabaseSchema {
+            name: "ddl".to_string(),
+            tables: self.tables,
+            enums: self.enums,
+            ..Default::default()
+        };
+        (database, self.warnings)
+    }
+}
+
+fn column_names(columns: Vec<IndexColumn>) -> Vec<String> {
+    columns.into_iter().map(|c| c.name).collect()
+}
+
+/// Apply a `DEFAULT` expression; sequence defaults mean auto-increment.
+fn set_default(column: &mut ColumnInfo, expression: &[Token]) {
+    let default = render(expression);
+    if default.to_lowercase().starts_with("nextval(") {
+        column.auto_increment = true;
+        column.default = None;
+    } else if !default.is_empty() {
+        column.default = Some(default);
+    }
+}
+
+/// Map enum references and types the provider mapping does not know.
+fn resolve_type(
+    column: &mut ColumnInfo,
+    enum_names: &[String],
+    table: &str,
+    warnings: &mut Vec<String>,
+) {
+    let resolve = |ty: &NormalizedType, warnings: &mut Vec<String>| -> NormalizedType {
+        let NormalizedType::Unknown(name) = ty else {
+            return ty.clone();
+        };
+        if let Some(enum_name) = enum_names.iter().find(|e| e.eq_ignore_ascii_case(name)) {
+            return NormalizedType::Enum(enum_name.clone());
+        }
+        affinity_type(name).unwrap_or_else(|| {
+            warnings.push(format!(
+                "column `{}.{}` has unsupported type `{}` and was converted to String",
+                table, column.name, name
+            ));
+            NormalizedType::Text
+        })
+    };
+
+    let resolved = match &column.normalized_type {
+        NormalizedType::Array(inner) => NormalizedType::Array(Box::new(resolve(inner, warnings))),
+        other => resolve(other, warnings),
+    };
+    if let NormalizedType::Enum(name) = &resolved {
+        column.enum_name = Some(name.clone());
+    }
+    column.normalized_type = resolved;
+}
+
+/// Guess a type from its name, using SQLite's affinity rules extended to
+/// the common names of other databases.
+fn affinity_type(name: &str) -> Option<NormalizedType> {
+    let ty = if name.contains("bigint") || name == "int8" {
+        NormalizedType::BigInt
+    } else if name.contains("int") {
+        NormalizedType::Int
+    } else if ["char", "clob", "text", "string", "citext"]
+        .iter()
+        .any(|t| name.contains(t))
+    {
+        NormalizedType::Text
+    } else if name.contains("blob") || name.contains("binary") {
+        NormalizedType::Bytes
+    } else if ["real", "floa", "doub"].iter().any(|t| name.contains(t)) {
+        NormalizedType::Double
+    } else if name.contains("numeric") || name.contains("decimal") || name.contains("money") {
+        NormalizedType::Decimal {
+            precision: None,
+            scale: None,
+        }
+    } else if name.contains("bool") || name == "bit" {
+        NormalizedType::Boolean
+    } else if name.contains("timestamp") || name.contains("datetime") {
+        NormalizedType::DateTime
+    } else if name == "date" {
+        NormalizedType::Date
+    } else if name.starts_with("time") {
+        NormalizedType::Time
+    } else if name.contains("json") {
+        NormalizedType::Json
+    } else if name.contains("uuid") || name == "uniqueidentifier" {
+        NormalizedType::Uuid
+    } else {
+        return None;
+    };
+    Some(ty)
+}
+
+/// Turn an enum value into a valid identifier.
+fn identifier(value: &str) -> String {
+    let mut ident: String = value
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+/// Short description of a statement, e.g. `CREATE FUNCTION`.
+fn statement_kind(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map_while(|token| match token {
+            Token::Word(word) => Some(word.to_uppercase()),
+            _ => None,
+        })
+        .filter(|word| !matches!(word.as_str(), "OR" | "REPLACE"))
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSTGRES_DDL: &str = r#"
+-- Initial schema
+CREATE TYPE public.post_status AS ENUM ('draft', 'published');
+
+CREATE TABLE users (
+    id BIGSERIAL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL UNIQUE,
+    name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE "posts" (
+    "id" integer NOT NULL,
+    title text NOT NULL,
+    status post_status NOT NULL DEFAULT 'draft'::post_status,
+    author_id bigint,
+    tags text[],
+    CONSTRAINT posts_author_fk FOREIGN KEY (author_id) REFERENCES users ON DELETE CASCADE
+);
+
+CREATE SEQUENCE posts_id_seq;
+ALTER TABLE ONLY posts ALTER COLUMN id SET DEFAULT nextval('posts_id_seq'::regclass);
+ALTER TABLE ONLY posts ADD CONSTRAINT posts_pkey PRIMARY KEY (id);
+CREATE INDEX posts_title_idx ON public.posts USING btree (title DESC);
+
+CREATE FUNCTION touch() RETURNS trigger AS $$
+BEGIN
+    NEW.updated_at = now(); -- not a statement boundary
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON TABLE users IS 'Registered users';
+"#;
+
+    #[test]
+    fn test_tokenize_splits_statements() {
+        let statements = tokenize("CREATE TABLE a (x int); /* ; */ SELECT 'a;b'; -- ;\n");
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[1][1], Token::Str("a;b".to_string()));
+    }
+
+    #[test]
+    fn test_postgres_ddl_to_prax() {
+        let conversion = ddl_to_prax(POSTGRES_DDL, "postgresql").unwrap();
+        let schema = prax_schema::parse_schema(&conversion.schema).unwrap();
+
+        let users = schema.get_model("Users").unwrap();
+        let id = users.get_field("id").unwrap();
+        assert!(id.is_id());
+        assert!(id.has_attribute("auto"));
+        assert!(users.get_field("email").unwrap().is_unique());
+        assert!(users.get_field("name").unwrap().is_optional());
+        assert!(conversion.schema.contains("/// Registered users"));
+
+        let posts = schema.get_model("Posts").unwrap();
+        assert!(posts.get_field("id").unwrap().is_id());
+        assert!(posts.get_field("id").unwrap().has_attribute("auto"));
+        assert!(conversion.schema.contains("@default(draft)"));
+        assert!(posts.get_field("tags").unwrap().is_list());
+        assert!(conversion.schema.contains("onDelete: Cascade"));
+        assert!(conversion.schema.contains("references: [id]"));
+        assert!(conversion.schema.contains("@@index([title])"));
+        assert!(schema.get_enum("post_status").is_some());
+
+        assert!(
+            conversion
+                .warnings
+                .iter()
+                .any(|w| w == "skipped 1 `CREATE FUNCTION` statement")
+        );
+    }
+
+    #[test]
+    fn test_mysql_ddl_to_prax() {
+        let ddl = "CREATE TABLE `orders` (
+            `id` int unsigned NOT NULL AUTO_INCREMENT,
+            `state` enum('new','in-progress') NOT NULL DEFAULT 'new',
+            `total` decimal(10,2) DEFAULT NULL,
+            `customer_id` int NOT NULL,
+            `code` varchar(20) CHARACTER SET utf8mb4 NOT NULL,
+            PRIMARY KEY (`id`),
+            UNIQUE KEY `orders_code` (`code`),
+            KEY `orders_customer` (`customer_id`, `total`)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;";
+
+        let conversion = ddl_to_prax(ddl, "mysql").unwrap();
+        let schema = prax_schema::parse_schema(&conversion.schema).unwrap();
+
+        let orders = schema.get_model("Orders").unwrap();
+        assert!(orders.get_field("id").unwrap().has_attribute("auto"));
+        assert!(orders.get_field("code").unwrap().is_unique());
+        assert!(orders.get_field("total").unwrap().is_optional());
+        assert!(conversion.schema.contains("@@index([customerId, total])"));
+
+        let state = schema.get_enum("orders_state").unwrap();
+        assert_eq!(state.variants.len(), 2);
+        assert!(
+            conversion
+                .warnings
+                .iter()
+                .any(|w| w.contains("`in-progress`"))
+        );
+    }
+
+    #[test]
+    fn test_sqlite_composite_key_and_affinity() {
+        let ddl = "CREATE TABLE memberships (
+            user_id BIGINT NOT NULL REFERENCES users(id),
+            group_id INTEGER NOT NULL,
+            joined NUMERIC,
+            PRIMARY KEY (user_id, group_id)
+        );
+        CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT);
+        INSERT INTO users VALUES (1);";
+
+        let conversion = ddl_to_prax(ddl, "sqlite").unwrap();
+        assert!(conversion.schema.contains("@@id([userId, groupId])"));
+        assert!(conversion.schema.contains("joined  Decimal?"));
+        assert!(
+            conversion
+                .warnings
+                .iter()
+                .any(|w| w == "skipped 1 `INSERT INTO` statement")
+        );
+    }
+
+    #[test]
+    fn test_read_sql_sources_from_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("002_posts")).unwrap();
+        std::fs::write(
+            dir.path().join("001_users.sql"),
+            "CREATE TABLE users (id int)",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("002_posts/up.sql"),
+            "ALTER TABLE users ADD COLUMN email text;",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "DROP TABLE users;").unwrap();
+
+        let source = read_sql_sources(dir.path()).unwrap();
+        let conversion = ddl_to_prax(&source, "postgresql").unwrap();
+        assert!(conversion.schema.contains("email String?"));
+    }
+}
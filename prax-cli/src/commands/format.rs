@@ -78,7 +78,7 @@ fn format_schema(schema: &prax_schema::ast::Schema) -> String {
     output.push_str("    provider = \"postgresql\"\n");
     output.push_str("    url      = env(\"DATABASE_URL\")\n");
     output.push_str("}\n");
-    let mut first_section = false;
+    let first_section = false;
 
     // Format generator
     if !first_section {
@@ -88,7 +88,15 @@ fn format_schema(schema: &prax_schema::ast::Schema) -> String {
     output.push_str("    provider = \"prax-client-rust\"\n");
     output.push_str("    output   = \"./src/generated\"\n");
     output.push_str("}\n");
-    first_section = false;
+    output.push_str(&format_definitions(schema));
+
+    output
+}
+
+/// Format the enums, mixins, models, views and composite types of a schema
+pub(crate) fn format_definitions(schema: &prax_schema::ast::Schema) -> String {
+    let mut output = String::new();
+    let mut first_section = false;
 
     // Format enums first (since they're used by models)
     for enum_def in schema.enums.values() {
@@ -99,12 +107,21 @@ fn format_schema(schema: &prax_schema::ast::Schema) -> String {
         first_section = false;
     }
 
+    // Format mixins (since they're used by models)
+    for mixin in schema.mixins.values() {
+        if !first_section {
+            output.push('\n');
+        }
+        format_mixin(&mut output, mixin);
+        first_section = false;
+    }
+
     // Format models
     for model in schema.models.values() {
         if !first_section {
             output.push('\n');
         }
-        format_model(&mut output, model);
+        format_model(&mut output, model, schema);
         first_section = false;
     }
 
@@ -158,14 +175,36 @@ fn format_enum(output: &mut String, enum_def: &prax_schema::ast::Enum) {
     }
 
     // Enum-level attributes
-    for attr in &enum_def.attributes {
-        output.push_str(&format!("\n    {}", format_attribute(attr)));
+    if !enum_def.attributes.is_empty() {
+        output.push('\n');
+        for attr in &enum_def.attributes {
+            output.push_str(&format!("    {}\n", format_block_attribute(attr)));
+        }
     }
 
     output.push_str("}\n");
 }
 
-fn format_model(output: &mut String, model: &prax_schema::ast::Model) {
+fn format_mixin(output: &mut String, mixin: &prax_schema::ast::Mixin) {
+    // Documentation
+    if let Some(doc) = &mixin.documentation {
+        for line in doc.text.lines() {
+            output.push_str(&format!("/// {}\n", line));
+        }
+    }
+
+    output.push_str(&format!("mixin {} {{\n", mixin.name()));
+    let fields: Vec<_> = mixin.fields.values().collect();
+    let attributes: Vec<_> = mixin.attributes.iter().collect();
+    format_body(output, &fields, &attributes);
+    output.push_str("}\n");
+}
+
+fn format_model(
+    output: &mut String,
+    model: &prax_schema::ast::Model,
+    schema: &prax_schema::ast::Schema,
+) {
     // Documentation
     if let Some(doc) = &model.documentation {
         for line in doc.text.lines() {
@@ -173,24 +212,54 @@ fn format_model(output: &mut String, model: &prax_schema::ast::Model) {
         }
     }
 
-    output.push_str(&format!("model {} {{\n", model.name()));
+    if model.mixins.is_empty() {
+        output.push_str(&format!("model {} {{\n", model.name()));
+    } else {
+        let mixins: Vec<&str> = model.mixins.iter().map(|m| m.as_str()).collect();
+        output.push_str(&format!(
+            "model {} with {} {{\n",
+            model.name(),
+            mixins.join(", ")
+        ));
+    }
 
-    // Calculate alignment for fields
-    let max_name_len = model
+    // Fields and attributes copied from mixins are written in the mixin
+    let mixins: Vec<_> = model
+        .mixins
+        .iter()
+        .filter_map(|m| schema.get_mixin(m.as_str()))
+        .collect();
+    let fields: Vec<_> = model
         .fields
         .values()
-        .map(|f| f.name().len())
-        .max()
-        .unwrap_or(0);
+        .filter(|f| !mixins.iter().any(|m| m.get_field(f.name()) == Some(*f)))
+        .collect();
+    let attributes: Vec<_> = model
+        .attributes
+        .iter()
+        .filter(|a| !mixins.iter().any(|m| m.attributes.contains(a)))
+        .collect();
+    format_body(output, &fields, &attributes);
 
-    let max_type_len = model
-        .fields
-        .values()
+    output.push_str("}\n");
+}
+
+/// Format the aligned fields and block attributes of a model or mixin
+fn format_body(
+    output: &mut String,
+    fields: &[&prax_schema::ast::Field],
+    attributes: &[&prax_schema::ast::Attribute],
+) {
+    // Calculate alignment for fields
+    let max_name_len = fields.iter().map(|f| f.name().len()).max().unwrap_or(0);
+
+    let max_type_len = fields
+        .iter()
         .map(|f| format_field_type(&f.field_type, f.modifier).len())
         .max()
         .unwrap_or(0);
 
-    for field in model.fields.values() {
+    for field in fields {
         // Documentation
         if let Some(doc) = &field.documentation {
             for line in doc.text.lines() {
@@ -215,15 +284,12 @@ fn format_model(output: &mut String, model: &prax_schema::ast::Model) {
     }
 
     // Model-level attributes
-    let model_attrs: Vec<_> = model.attributes.iter().collect();
-    if !model_attrs.is_empty() {
+    if !attributes.is_empty() {
         output.push('\n');
-        for attr in model_attrs {
-            output.push_str(&format!("    {}\n", format_attribute(attr)));
+        for attr in attributes {
+            output.push_str(&format!("    {}\n", format_block_attribute(attr)));
         }
     }
-
-    output.push_str("}\n");
 }
 
 fn format_view(output: &mut String, view: &prax_schema::ast::View) {
@@ -270,7 +336,7 @@ fn format_view(output: &mut String, view: &prax_schema::ast::View) {
     if !view_attrs.is_empty() {
         output.push('\n');
         for attr in view_attrs {
-            output.push_str(&format!("    {}\n", format_attribute(attr)));
+            output.push_str(&format!("    {}\n", format_block_attribute(attr)));
         }
     }
 
@@ -343,6 +409,15 @@ fn format_field_type(
             ScalarType::Cuid2 => "Cuid2",
             ScalarType::NanoId => "NanoId",
             ScalarType::Ulid => "Ulid",
+            ScalarType::IntRange => "IntRange",
+            ScalarType::BigIntRange => "BigIntRange",
+            ScalarType::DateTimeRange => "DateTimeRange",
+            ScalarType::Inet => "Inet",
+            ScalarType::Cidr => "Cidr",
+            ScalarType::MacAddr => "MacAddr",
+            ScalarType::Geometry => "Geometry",
+            ScalarType::Geography => "Geography",
+            ScalarType::Money => "Money",
             ScalarType::Vector(_) => "Vector",
             ScalarType::HalfVector(_) => "HalfVector",
             ScalarType::SparseVector(_) => "SparseVector",
@@ -363,10 +438,17 @@ fn format_field_type(
     }
 }
 
+/// Format a field or enum variant attribute
 fn format_attribute(attr: &prax_schema::ast::Attribute) -> String {
-    // For model-level attributes we check if it's a known model attribute
-    let prefix = if attr.is_model_attribute() { "@@" } else { "@" };
+    format_attribute_with_prefix(attr, "@")
+}
 
+/// Format a model, view or enum level attribute, including custom ones
+fn format_block_attribute(attr: &prax_schema::ast::Attribute) -> String {
+    format_attribute_with_prefix(attr, "@@")
+}
+
+fn format_attribute_with_prefix(attr: &prax_schema::ast::Attribute, prefix: &str) -> String {
     if attr.args.is_empty() {
         format!("{}{}", prefix, attr.name())
     } else {
@@ -418,5 +500,12 @@ fn format_attribute_value(value: &prax_schema::ast::AttributeValue) -> String {
                     .join(", ")
             )
         }
+        AttributeValue::Object(entries) => {
+            let entry_strs: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, format_attribute_value(value)))
+                .collect();
+            format!("{{{}}}", entry_strs.join(", "))
+        }
     }
 }
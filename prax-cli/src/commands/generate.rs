@@ -1,14 +1,22 @@
 //! `prax generate` command - Generate Rust client code from schema.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::cli::GenerateArgs;
-use crate::config::{CONFIG_FILE_NAME, Config, SCHEMA_FILE_NAME};
+use crate::cli::{GenerateArgs, GenerateSubcommand};
+use crate::config::{
+    CONFIG_FILE_NAME, ClientGeneratorConfig, Config, OutputSplit, SCHEMA_FILE_NAME,
+};
 use crate::error::{CliError, CliResult};
 use crate::output::{self, success};
+use crate::plugins::{self, ExternalGenerator};
+use prax_plugin_api::{API_VERSION, GeneratorRequest};
 
 /// Run the generate command
 pub async fn run(args: GenerateArgs) -> CliResult<()> {
+    if let Some(GenerateSubcommand::Crud(crud_args)) = args.command {
+        return crate::commands::scaffold::run_crud(crud_args).await;
+    }
+
     output::header("Generate Prax Client");
 
     let cwd = std::env::current_dir()?;
@@ -46,7 +54,8 @@ pub async fn run(args: GenerateArgs) -> CliResult<()> {
 
     // Parse schema
     let schema_content = std::fs::read_to_string(&schema_path)?;
-    let schema = parse_schema(&schema_content)?;
+    let mut schema = parse_schema(&schema_content)?;
+    config.generator.client.apply_naming(&mut schema);
 
     output::step(2, 4, "Validating schema...");
 
@@ -59,7 +68,16 @@ pub async fn run(args: GenerateArgs) -> CliResult<()> {
     std::fs::create_dir_all(&output_dir)?;
 
     // Generate code
-    let generated_files = generate_code(&schema, &output_dir, &args, &config)?;
+    let mut generated_files = generate_code(&schema, &output_dir, &args, &config)?;
+
+    // Run external generator plugins
+    generated_files.extend(run_plugins(
+        &schema,
+        &schema_path,
+        &output_dir,
+        &cwd,
+        &config,
+    )?);
 
     output::step(4, 4, "Writing files...");
 
@@ -76,6 +94,18 @@ pub async fn run(args: GenerateArgs) -> CliResult<()> {
         output::list_item(&relative_path);
     }
 
+    // Feature-gated models need matching features in the consuming crate
+    if config.generator.client.feature_gates && !schema.models.is_empty() {
+        output::newline();
+        output::section("Cargo features");
+        output::info("Add these to the [features] table of the crate that includes the client:");
+        for model in schema.models.values() {
+            if let Some(feature) = model_feature(model.name(), &config.generator.client) {
+                output::list_item(&format!("{} = []", feature));
+            }
+        }
+    }
+
     output::newline();
     success(&format!(
         "Generated {} files in {:.2}s",
@@ -86,6 +116,46 @@ pub async fn run(args: GenerateArgs) -> CliResult<()> {
     Ok(())
 }
 
+/// Run the external generators configured under `[generator.plugins]`
+fn run_plugins(
+    schema: &prax_schema::Schema,
+    schema_path: &Path,
+    output_dir: &Path,
+    cwd: &Path,
+    config: &Config,
+) -> CliResult<Vec<PathBuf>> {
+    let mut generated_files = Vec::new();
+
+    for (name, plugin_config) in &config.generator.plugins {
+        let generator = ExternalGenerator::discover(name, plugin_config, cwd)?;
+        let manifest = generator.manifest()?;
+        output::info(&format!(
+            "Running generator plugin '{}' ({})",
+            name,
+            generator.command.display()
+        ));
+
+        let plugin_output = generator.output_dir(cwd, output_dir);
+        let request = GeneratorRequest {
+            api_version: API_VERSION,
+            name: manifest.name,
+            schema: schema.clone(),
+            schema_path: schema_path.to_path_buf(),
+            output: plugin_output.clone(),
+            options: plugin_config.options.clone(),
+        };
+
+        let response = generator.run(&request)?;
+        for warning in &response.warnings {
+            output::warn(&format!("{}: {}", name, warning));
+        }
+
+        generated_files.extend(plugins::write_files(name, &response, &plugin_output)?);
+    }
+
+    Ok(generated_files)
+}
+
 /// Parse the schema file
 fn parse_schema(content: &str) -> CliResult<prax_schema::Schema> {
     prax_schema::parse_schema(content)
@@ -106,14 +176,25 @@ fn validate_schema(schema: &prax_schema::Schema) -> CliResult<()> {
     Ok(())
 }
 
+/// A generated module, either written to its own file or inlined into `mod.rs`
+struct GeneratedModule {
+    /// Module name
+    name: String,
+    /// Module source
+    code: String,
+    /// Cargo feature gating the module, if any
+    feature: Option<String>,
+}
+
 /// Generate code from the schema
 fn generate_code(
     schema: &prax_schema::ast::Schema,
-    output_dir: &PathBuf,
+    output_dir: &Path,
     args: &GenerateArgs,
     config: &Config,
 ) -> CliResult<Vec<PathBuf>> {
     let mut generated_files = Vec::new();
+    let client_config = &config.generator.client;
 
     // Determine which features to generate
     let features = if !args.features.is_empty() {
@@ -126,47 +207,75 @@ fn generate_code(
             .unwrap_or_else(|| vec!["client".to_string()])
     };
 
-    // Generate main client module
-    let client_path = output_dir.join("mod.rs");
-    let client_code = generate_client_module(schema, &features)?;
-    std::fs::write(&client_path, client_code)?;
-    generated_files.push(client_path);
+    let mut modules = vec![
+        GeneratedModule {
+            name: "types".to_string(),
+            code: generate_types_module(schema)?,
+            feature: None,
+        },
+        GeneratedModule {
+            name: "filters".to_string(),
+            code: generate_filters_module(schema, client_config)?,
+            feature: None,
+        },
+    ];
 
     // Generate model modules
     for model in schema.models.values() {
-        let model_path = output_dir.join(format!("{}.rs", to_snake_case(model.name())));
-        let model_code = generate_model_module(model, &features)?;
-        std::fs::write(&model_path, model_code)?;
-        generated_files.push(model_path);
+        modules.push(GeneratedModule {
+            name: to_snake_case(model.name()),
+            code: generate_model_module(model, &features, client_config)?,
+            feature: model_feature(model.name(), client_config),
+        });
     }
 
     // Generate enum modules
     for enum_def in schema.enums.values() {
-        let enum_path = output_dir.join(format!("{}.rs", to_snake_case(enum_def.name())));
-        let enum_code = generate_enum_module(enum_def)?;
-        std::fs::write(&enum_path, enum_code)?;
-        generated_files.push(enum_path);
+        modules.push(GeneratedModule {
+            name: to_snake_case(enum_def.name()),
+            code: generate_enum_module(enum_def)?,
+            feature: None,
+        });
     }
 
-    // Generate type definitions
-    let types_path = output_dir.join("types.rs");
-    let types_code = generate_types_module(schema)?;
-    std::fs::write(&types_path, types_code)?;
-    generated_files.push(types_path);
+    // Generate main client module
+    let client_path = output_dir.join("mod.rs");
+    let client_code = generate_client_module(schema, &features, &modules, client_config)?;
+    std::fs::write(&client_path, client_code)?;
+    generated_files.push(client_path);
 
-    // Generate filters
-    let filters_path = output_dir.join("filters.rs");
-    let filters_code = generate_filters_module(schema)?;
-    std::fs::write(&filters_path, filters_code)?;
-    generated_files.push(filters_path);
+    if client_config.split == OutputSplit::PerModel {
+        for module in &modules {
+            let path = output_dir.join(format!("{}.rs", module.name));
+            std::fs::write(&path, &module.code)?;
+            generated_files.push(path);
+        }
+    }
 
     Ok(generated_files)
 }
 
+/// Cargo feature gating a model's module, when feature gates are enabled
+fn model_feature(model_name: &str, client_config: &ClientGeneratorConfig) -> Option<String> {
+    client_config
+        .feature_gates
+        .then(|| format!("model-{}", to_snake_case(model_name).replace('_', "-")))
+}
+
+/// `#[cfg(feature = "...")]` line for an optional feature
+fn cfg_line(feature: &Option<String>, indent: &str) -> String {
+    match feature {
+        Some(feature) => format!("{}#[cfg(feature = \"{}\")]\n", indent, feature),
+        None => String::new(),
+    }
+}
+
 /// Generate the main client module
 fn generate_client_module(
     schema: &prax_schema::ast::Schema,
     _features: &[String],
+    modules: &[GeneratedModule],
+    client_config: &ClientGeneratorConfig,
 ) -> CliResult<String> {
     let mut code = String::new();
 
@@ -175,15 +284,16 @@ fn generate_client_module(
     code.push_str("//! This module contains the generated Prax client.\n\n");
 
     // Module declarations
-    code.push_str("pub mod types;\n");
-    code.push_str("pub mod filters;\n\n");
-
-    for model in schema.models.values() {
-        code.push_str(&format!("pub mod {};\n", to_snake_case(model.name())));
-    }
-
-    for enum_def in schema.enums.values() {
-        code.push_str(&format!("pub mod {};\n", to_snake_case(enum_def.name())));
+    for module in modules {
+        code.push_str(&cfg_line(&module.feature, ""));
+        match client_config.split {
+            OutputSplit::PerModel => code.push_str(&format!("pub mod {};\n", module.name)),
+            OutputSplit::Single => {
+                code.push_str(&format!("pub mod {} {{\n", module.name));
+                code.push_str(&module.code);
+                code.push_str("}\n\n");
+            }
+        }
     }
 
     code.push_str("\n");
@@ -193,6 +303,7 @@ fn generate_client_module(
     code.push_str("pub use filters::*;\n\n");
 
     for model in schema.models.values() {
+        code.push_str(&cfg_line(&model_feature(model.name(), client_config), ""));
         code.push_str(&format!(
             "pub use {}::{};\n",
             to_snake_case(model.name()),
@@ -225,6 +336,10 @@ fn generate_client_module(
     for model in schema.models.values() {
         let snake_name = to_snake_case(model.name());
         code.push_str(&format!("    /// Access {} operations\n", model.name()));
+        code.push_str(&cfg_line(
+            &model_feature(model.name(), client_config),
+            "    ",
+        ));
         code.push_str(&format!(
             "    pub fn {}(&self) -> {}::{}Operations<E> {{\n",
             snake_name,
@@ -248,6 +363,7 @@ fn generate_client_module(
 fn generate_model_module(
     model: &prax_schema::ast::Model,
     features: &[String],
+    client_config: &ClientGeneratorConfig,
 ) -> CliResult<String> {
     let mut code = String::new();
 
@@ -280,6 +396,11 @@ fn generate_model_module(
             }
         }
 
+        // Relations to gated models are only present when that model is enabled
+        if let prax_schema::ast::FieldType::Model(target) = &field.field_type {
+            code.push_str(&cfg_line(&model_feature(target, client_config), "    "));
+        }
+
         code.push_str(&format!("    pub {}: {},\n", field_name, rust_type));
     }
 
@@ -449,15 +570,21 @@ fn generate_types_module(schema: &prax_schema::ast::Schema) -> CliResult<String>
 }
 
 /// Generate filters module
-fn generate_filters_module(schema: &prax_schema::ast::Schema) -> CliResult<String> {
+fn generate_filters_module(
+    schema: &prax_schema::ast::Schema,
+    client_config: &ClientGeneratorConfig,
+) -> CliResult<String> {
     let mut code = String::new();
 
     code.push_str("//! Filter types for queries\n\n");
     code.push_str("use prax_query::filter::{Filter, ScalarFilter};\n\n");
 
     for model in schema.models.values() {
+        let gate = cfg_line(&model_feature(model.name(), client_config), "");
+
         // Where input
         code.push_str(&format!("/// Filter input for {} queries\n", model.name()));
+        code.push_str(&gate);
         code.push_str("#[derive(Debug, Default, Clone)]\n");
         code.push_str(&format!("pub struct {}WhereInput {{\n", model.name()));
 
@@ -482,6 +609,7 @@ fn generate_filters_module(schema: &prax_schema::ast::Schema) -> CliResult<Strin
             "/// Order by input for {} queries\n",
             model.name()
         ));
+        code.push_str(&gate);
         code.push_str("#[derive(Debug, Default, Clone)]\n");
         code.push_str(&format!("pub struct {}OrderByInput {{\n", model.name()));
 
@@ -526,6 +654,17 @@ fn field_type_to_rust(
             ScalarType::Cuid2 => "String".to_string(),
             ScalarType::NanoId => "String".to_string(),
             ScalarType::Ulid => "String".to_string(),
+            ScalarType::IntRange => "std::ops::Range<i32>".to_string(),
+            ScalarType::BigIntRange => "std::ops::Range<i64>".to_string(),
+            ScalarType::DateTimeRange => {
+                "std::ops::Range<chrono::DateTime<chrono::Utc>>".to_string()
+            }
+            ScalarType::Inet | ScalarType::Cidr => "ipnetwork::IpNetwork".to_string(),
+            ScalarType::MacAddr => "macaddr::MacAddr6".to_string(),
+            ScalarType::Geometry | ScalarType::Geography => {
+                "prax_query::spatial::Geometry".to_string()
+            }
+            ScalarType::Money => "prax_query::money::Money".to_string(),
             ScalarType::Vector(_) | ScalarType::HalfVector(_) => "Vec<f32>".to_string(),
             ScalarType::SparseVector(_) => "Vec<(u32, f32)>".to_string(),
             ScalarType::Bit(_) => "Vec<u8>".to_string(),
@@ -557,6 +696,8 @@ fn field_to_filter_type(field_type: &prax_schema::ast::FieldType) -> String {
             | ScalarType::Cuid2
             | ScalarType::NanoId
             | ScalarType::Ulid => "ScalarFilter<String>".to_string(),
+            ScalarType::Inet | ScalarType::Cidr => "NetworkFilter".to_string(),
+            ScalarType::MacAddr => "ScalarFilter<macaddr::MacAddr6>".to_string(),
             ScalarType::Boolean => "ScalarFilter<bool>".to_string(),
             ScalarType::DateTime => "ScalarFilter<chrono::DateTime<chrono::Utc>>".to_string(),
             ScalarType::Date => "ScalarFilter<chrono::NaiveDate>".to_string(),
@@ -567,6 +708,11 @@ fn field_to_filter_type(field_type: &prax_schema::ast::FieldType) -> String {
             ScalarType::Vector(_) | ScalarType::HalfVector(_) => "VectorFilter".to_string(),
             ScalarType::SparseVector(_) => "SparseVectorFilter".to_string(),
             ScalarType::Bit(_) => "BitFilter".to_string(),
+            ScalarType::IntRange | ScalarType::BigIntRange | ScalarType::DateTimeRange => {
+                "RangeFilter".to_string()
+            }
+            ScalarType::Geometry | ScalarType::Geography => "SpatialFilter".to_string(),
+            ScalarType::Money => "MoneyFilter".to_string(),
         },
         FieldType::Enum(name) => format!("ScalarFilter<{}>", name),
         _ => "Filter".to_string(),
@@ -0,0 +1,489 @@
+//! Index usage and table bloat checks (`prax db health`).
+//!
+//! Index statistics come from `pg_stat_user_indexes` and table statistics
+//! from `pg_stat_user_tables`. Three kinds of problems are flagged on the
+//! tables of the schema's models:
+//!
+//! - unused indexes: never scanned since the statistics were last reset.
+//!   Unique and primary key indexes are left alone, as they enforce
+//!   constraints even when no query reads them.
+//! - duplicate indexes: an index whose columns are the leading columns of
+//!   another index of the same kind. Every write pays for both while the
+//!   longer one serves the same lookups.
+//! - bloat: tables where dead rows waiting for vacuum make up a large share
+//!   of the rows.
+//! - maintenance: autovacuum switched off or tuned too loosely for tables
+//!   with heavy update and delete traffic, vacuums that fell behind and stale
+//!   planner statistics, as advised by [`TableMaintenance::advise`].
+//!
+//! Findings name the `@@index`, `@@unique` or field attribute declaring the
+//! index, where there is one, so it can be pruned from the schema.
+
+use std::collections::HashMap;
+
+use prax_query::diagnostics::{MIN_DEAD_ROWS, MaintenanceIssue, TableMaintenance};
+use prax_schema::ast::{AttributeValue, FieldType, Model, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{CliError, CliResult};
+
+/// Usage statistics of every index in the current schema (PostgreSQL)
+pub const INDEX_USAGE_SQL: &str = "SELECT json_build_object('table', t.relname, 'index', i.relname, \
+     'columns', (SELECT coalesce(json_agg(a.attname ORDER BY k.ord), '[]') \
+     FROM unnest(x.indkey::int2[]) WITH ORDINALITY k(attnum, ord) \
+     JOIN pg_attribute a ON a.attrelid = x.indrelid AND a.attnum = k.attnum), \
+     'method', am.amname, 'unique', x.indisunique, 'primary', x.indisprimary, \
+     'partial', x.indpred IS NOT NULL OR x.indexprs IS NOT NULL, \
+     'scans', s.idx_scan, 'size_bytes', pg_relation_size(x.indexrelid)) \
+     FROM pg_index x \
+     JOIN pg_stat_user_indexes s ON s.indexrelid = x.indexrelid \
+     JOIN pg_class i ON i.oid = x.indexrelid \
+     JOIN pg_class t ON t.oid = x.indrelid \
+     JOIN pg_am am ON am.oid = i.relam \
+     WHERE s.schemaname = current_schema() \
+     ORDER BY t.relname, i.relname";
+
+/// Usage statistics of one index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexUsage {
+    /// Table name
+    pub table: String,
+    /// Index name
+    pub index: String,
+    /// Indexed columns, in index order
+    pub columns: Vec<String>,
+    /// Access method, e.g. `btree`
+    pub method: String,
+    /// Whether the index enforces uniqueness
+    pub unique: bool,
+    /// Whether the index backs the primary key
+    pub primary: bool,
+    /// Whether the index has a predicate or indexes expressions
+    pub partial: bool,
+    /// Index scans since the statistics were reset
+    pub scans: u64,
+    /// Size on disk
+    pub size_bytes: u64,
+}
+
+/// Kind of a [`HealthFinding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// An index that was never scanned
+    UnusedIndex,
+    /// An index made redundant by another one
+    DuplicateIndex,
+    /// A table with many dead rows
+    Bloat,
+    /// A table autovacuum does not keep up with
+    Maintenance,
+}
+
+impl std::fmt::Display for FindingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::UnusedIndex => "unused index",
+            Self::DuplicateIndex => "duplicate index",
+            Self::Bloat => "bloat",
+            Self::Maintenance => "maintenance",
+        })
+    }
+}
+
+/// A problem found by `prax db health`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthFinding {
+    /// What was found
+    pub kind: FindingKind,
+    /// Model name
+    pub model: String,
+    /// Table name
+    pub table: String,
+    /// Index name, for index findings
+    pub index: Option<String>,
+    /// Schema attribute declaring the index, if any
+    pub attribute: Option<String>,
+    /// Maintenance problem, for maintenance findings
+    pub issue: Option<MaintenanceIssue>,
+    /// Human-readable explanation
+    pub detail: String,
+    /// Statement or setting that fixes the problem
+    pub recommendation: Option<String>,
+    /// Bytes that pruning the index or vacuuming the table would reclaim
+    pub reclaimable_bytes: u64,
+}
+
+/// An index declared in the schema
+struct DeclaredIndex {
+    /// Attribute as written, e.g. `@@index([authorId])`
+    attribute: String,
+    /// Indexed columns, in index order
+    columns: Vec<String>,
+}
+
+/// Indexes declared on a model, by the columns they cover
+fn declared_indexes(schema: &Schema, model: &Model) -> Vec<DeclaredIndex> {
+    let mut declared = Vec::new();
+    let mut field_columns = HashMap::new();
+    for field in model.fields.values() {
+        if matches!(&field.field_type, FieldType::Model(name) if schema.models.contains_key(name)) {
+            continue;
+        }
+        let attrs = field.extract_attributes();
+        let column = attrs.map.unwrap_or_else(|| field.name().to_string());
+        for (present, attribute) in [
+            (attrs.is_id, "@id"),
+            (attrs.is_unique, "@unique"),
+            (attrs.is_indexed, "@index"),
+        ] {
+            if present {
+                declared.push(DeclaredIndex {
+                    attribute: format!("{} {}", field.name(), attribute),
+                    columns: vec![column.clone()],
+                });
+            }
+        }
+        field_columns.insert(field.name().to_string(), column);
+    }
+
+    for attribute in &model.attributes {
+        if !matches!(attribute.name(), "id" | "unique" | "index") {
+            continue;
+        }
+        if let Some(AttributeValue::FieldRefList(fields)) = attribute.first_arg() {
+            declared.push(DeclaredIndex {
+                attribute: format!("@@{}([{}])", attribute.name(), fields.join(", ")),
+                columns: fields
+                    .iter()
+                    .map(|f| {
+                        field_columns
+                            .get(f.as_str())
+                            .cloned()
+                            .unwrap_or_else(|| f.to_string())
+                    })
+                    .collect(),
+            });
+        }
+    }
+    declared
+}
+
+/// Whether `index` is made redundant by `other`: its columns lead `other`'s
+/// and `other` enforces at least the same constraint.
+fn is_redundant(index: &IndexUsage, other: &IndexUsage) -> bool {
+    if index.index == other.index
+        || index.table != other.table
+        || index.method != other.method
+        || index.partial
+        || other.partial
+        || index.primary
+        || index.unique && (!other.unique || other.columns.len() != index.columns.len())
+        || index.columns.is_empty()
+        || !other.columns.starts_with(&index.columns)
+    {
+        return false;
+    }
+    // Of two identical indexes, only the second by name is reported
+    index.columns.len() < other.columns.len()
+        || other.primary
+        || other.unique && !index.unique
+        || index.index > other.index
+}
+
+/// Flag unused and duplicate indexes, bloated tables and maintenance
+/// problems of the schema's models. Tables count as bloated when at least
+/// `bloat_percent` of their rows, and at least [`MIN_DEAD_ROWS`], are dead.
+pub fn check(
+    schema: &Schema,
+    indexes: &[IndexUsage],
+    tables: &[TableMaintenance],
+    bloat_percent: f64,
+) -> Vec<HealthFinding> {
+    let mut findings = Vec::new();
+
+    for model in schema.models.values() {
+        let table = model.table_name();
+        let declared = declared_indexes(schema, model);
+        let attribute = |index: &IndexUsage| {
+            declared
+                .iter()
+                .find(|d| d.columns == index.columns)
+                .map(|d| d.attribute.clone())
+        };
+        let table_indexes: Vec<&IndexUsage> = indexes.iter().filter(|i| i.table == table).collect();
+
+        for index in &table_indexes {
+            if let Some(other) = table_indexes
+                .iter()
+                .find(|other| is_redundant(index, other))
+            {
+                findings.push(HealthFinding {
+                    kind: FindingKind::DuplicateIndex,
+                    model: model.name().to_string(),
+                    table: table.to_string(),
+                    index: Some(index.index.clone()),
+                    attribute: attribute(index),
+                    issue: None,
+                    detail: format!(
+                        "({}) is covered by {} ({})",
+                        index.columns.join(", "),
+                        other.index,
+                        other.columns.join(", ")
+                    ),
+                    recommendation: None,
+                    reclaimable_bytes: index.size_bytes,
+                });
+            } else if index.scans == 0
+                && !index.unique
+                && !index.primary
+                // Lookups of a scanned duplicate move to the index covering it
+                && !table_indexes
+                    .iter()
+                    .any(|other| other.scans > 0 && is_redundant(other, index))
+            {
+                findings.push(HealthFinding {
+                    kind: FindingKind::UnusedIndex,
+                    model: model.name().to_string(),
+                    table: table.to_string(),
+                    index: Some(index.index.clone()),
+                    attribute: attribute(index),
+                    issue: None,
+                    detail: format!(
+                        "({}) was never scanned since statistics were reset",
+                        index.columns.join(", ")
+                    ),
+                    recommendation: None,
+                    reclaimable_bytes: index.size_bytes,
+                });
+            }
+        }
+
+        let Some(stats) = tables.iter().find(|t| t.table == table) else {
+            continue;
+        };
+        let dead = stats.dead_percent();
+        let reclaimable_bytes = (stats.size_bytes as f64 * dead / 100.0) as u64;
+        if stats.dead_rows >= MIN_DEAD_ROWS && dead >= bloat_percent {
+            findings.push(HealthFinding {
+                kind: FindingKind::Bloat,
+                model: model.name().to_string(),
+                table: table.to_string(),
+                index: None,
+                attribute: None,
+                issue: None,
+                detail: format!(
+                    "{:.0}% of rows are dead ({} dead, {} live), last vacuumed {}",
+                    dead,
+                    stats.dead_rows,
+                    stats.live_rows,
+                    stats.last_vacuum.as_deref().unwrap_or("never")
+                ),
+                recommendation: None,
+                reclaimable_bytes,
+            });
+        }
+        for advice in stats.advise() {
+            findings.push(HealthFinding {
+                kind: FindingKind::Maintenance,
+                model: model.name().to_string(),
+                table: table.to_string(),
+                index: None,
+                attribute: None,
+                issue: Some(advice.issue),
+                detail: advice.message,
+                recommendation: Some(advice.recommendation),
+                reclaimable_bytes: match advice.issue {
+                    MaintenanceIssue::VacuumBehind => reclaimable_bytes,
+                    _ => 0,
+                },
+            });
+        }
+    }
+
+    findings
+}
+
+/// Format a size in bytes with a binary unit, e.g. `8.0 KiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+impl IndexUsage {
+    /// Parse the rows of [`INDEX_USAGE_SQL`]
+    pub fn from_rows(rows: Vec<Value>) -> CliResult<Vec<Self>> {
+        rows.into_iter()
+            .map(|row| {
+                serde_json::from_value(row)
+                    .map_err(|e| CliError::Database(format!("Unexpected index statistics: {}", e)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        model Post {
+            id        Int      @id @auto
+            authorId  Int      @map("author_id")
+            status    String
+            createdAt DateTime @map("created_at")
+
+            @@index([authorId])
+            @@index([authorId, createdAt])
+            @@index([status])
+            @@map("posts")
+        }
+    "#;
+
+    fn index(name: &str, columns: &[&str], scans: u64) -> IndexUsage {
+        IndexUsage {
+            table: "posts".to_string(),
+            index: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            method: "btree".to_string(),
+            unique: false,
+            primary: false,
+            partial: false,
+            scans,
+            size_bytes: 8192,
+        }
+    }
+
+    #[test]
+    fn test_check_indexes() {
+        let schema = prax_schema::parse_schema(SCHEMA).unwrap();
+        let mut pkey = index("posts_pkey", &["id"], 0);
+        pkey.primary = true;
+        pkey.unique = true;
+        let indexes = vec![
+            pkey,
+            index("posts_author_id_idx", &["author_id"], 40),
+            index(
+                "posts_author_id_created_at_idx",
+                &["author_id", "created_at"],
+                0,
+            ),
+            index("posts_status_idx", &["status"], 0),
+            index("posts_status_idx1", &["status"], 0),
+        ];
+
+        let findings = check(&schema, &indexes, &[], 20.0);
+
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[0].kind, FindingKind::DuplicateIndex);
+        assert_eq!(findings[0].index.as_deref(), Some("posts_author_id_idx"));
+        assert_eq!(
+            findings[0].attribute.as_deref(),
+            Some("@@index([authorId])")
+        );
+        assert_eq!(
+            findings[0].detail,
+            "(author_id) is covered by posts_author_id_created_at_idx (author_id, created_at)"
+        );
+        // The primary key is never scanned but enforces a constraint, and
+        // the longer author index takes over the lookups of its duplicate
+        assert_eq!(findings[1].kind, FindingKind::UnusedIndex);
+        assert_eq!(findings[1].index.as_deref(), Some("posts_status_idx"));
+        assert_eq!(findings[1].attribute.as_deref(), Some("@@index([status])"));
+        assert_eq!(findings[2].kind, FindingKind::DuplicateIndex);
+        assert_eq!(findings[2].index.as_deref(), Some("posts_status_idx1"));
+    }
+
+    #[test]
+    fn test_unique_index_is_not_redundant_to_plain_one() {
+        let mut unique = index("posts_author_id_key", &["author_id"], 0);
+        unique.unique = true;
+        let longer = index(
+            "posts_author_id_created_at_idx",
+            &["author_id", "created_at"],
+            1,
+        );
+        let plain = index("posts_author_id_idx", &["author_id"], 1);
+
+        assert!(!is_redundant(&unique, &longer));
+        assert!(!is_redundant(&unique, &plain));
+        assert!(is_redundant(&plain, &unique));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(8192), "8.0 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MiB");
+    }
+
+    fn posts(live_rows: u64, dead_rows: u64) -> TableMaintenance {
+        TableMaintenance::from_rows(vec![serde_json::json!({
+            "table": "posts", "live_rows": live_rows, "dead_rows": dead_rows,
+            "inserts": live_rows + dead_rows, "updates": 0, "deletes": dead_rows,
+            "modified_since_analyze": 0, "size_bytes": 1_000_000,
+            "last_vacuum": null, "last_analyze": "2026-10-01T03:00:00+00:00",
+            "autovacuum": true, "autovacuum_enabled": true,
+            "vacuum_threshold": 50, "vacuum_scale_factor": 0.2,
+            "analyze_threshold": 50, "analyze_scale_factor": 0.1
+        })])
+        .unwrap()
+        .remove(0)
+    }
+
+    #[test]
+    fn test_check_bloat() {
+        let schema = prax_schema::parse_schema(SCHEMA).unwrap();
+
+        let findings = check(&schema, &[], &[posts(6_000, 4_000)], 20.0);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].kind, FindingKind::Bloat);
+        assert_eq!(findings[0].reclaimable_bytes, 400_000);
+        assert_eq!(
+            findings[0].detail,
+            "40% of rows are dead (4000 dead, 6000 live), last vacuumed never"
+        );
+        assert_eq!(findings[1].kind, FindingKind::Maintenance);
+        assert_eq!(findings[1].issue, Some(MaintenanceIssue::VacuumBehind));
+        assert_eq!(
+            findings[1].recommendation.as_deref(),
+            Some(r#"VACUUM (ANALYZE) "posts";"#)
+        );
+
+        // Small tables are left to autovacuum
+        assert!(check(&schema, &[], &[posts(60, 40)], 20.0).is_empty());
+    }
+
+    #[test]
+    fn test_check_autovacuum_settings() {
+        let schema = prax_schema::parse_schema(SCHEMA).unwrap();
+        let mut table = posts(1_000_000, 0);
+        table.updates = 800_000;
+        table.autovacuum_enabled = false;
+
+        let findings = check(&schema, &[], &[table], 20.0);
+        let issues: Vec<_> = findings.iter().filter_map(|f| f.issue).collect();
+        assert_eq!(
+            issues,
+            [
+                MaintenanceIssue::AutovacuumDisabled,
+                MaintenanceIssue::VacuumScaleFactor
+            ]
+        );
+        assert_eq!(findings[0].model, "Post");
+        assert_eq!(
+            findings[0].recommendation.as_deref(),
+            Some(r#"ALTER TABLE "posts" RESET (autovacuum_enabled);"#)
+        );
+    }
+}
@@ -267,6 +267,7 @@ pub mod postgres {
                     let is_primary: bool = idx_row.get(3);
                     let idx_type: Option<String> = idx_row.try_get(4).ok();
                     let filter: Option<String> = idx_row.try_get(5).ok();
+                    let ops: Option<String> = idx_row.try_get(6).ok().flatten();
 
                     let idx = idx_map.entry(idx_name.clone()).or_insert_with(|| {
                         IndexInfo {
@@ -282,6 +283,7 @@ pub mod postgres {
                     idx.columns.push(IndexColumn {
                         name: col_name,
                         order: SortOrder::Asc,
+                        ops,
                         ..Default::default()
                     });
                 }
@@ -381,6 +383,61 @@ pub mod postgres {
 
 /// Generate Prax schema output.
 pub fn format_as_prax(schema: &DatabaseSchema, config: &Config) -> String {
+    let mut output = prax_header(config);
+
+    // Use the generate_prax_schema function
+    output.push_str(&generate_prax_schema(schema));
+
+    output
+}
+
+/// What an existing schema contributed to freshly pulled output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Preserved {
+    /// Model and field names (with their `@map`/`@@map`) and doc comments
+    pub names: usize,
+    /// Custom (plugin-defined) attributes
+    pub attributes: usize,
+}
+
+impl Preserved {
+    /// Whether nothing was carried over.
+    pub fn is_empty(&self) -> bool {
+        self.names == 0 && self.attributes == 0
+    }
+}
+
+/// Carry names, doc comments and custom (plugin-defined) attributes from an
+/// existing Prax schema over to freshly pulled output.
+///
+/// Returns the schema to write and what was preserved. The pulled output is
+/// returned unchanged when there is nothing to preserve.
+pub fn preserve_existing_schema(
+    pulled: &str,
+    existing: &str,
+    config: &Config,
+) -> CliResult<(String, Preserved)> {
+    let Ok(previous) = prax_schema::parse_schema(existing) else {
+        return Ok((pulled.to_string(), Preserved::default()));
+    };
+
+    let mut schema = prax_schema::parse_schema(pulled)
+        .map_err(|e| CliError::Schema(format!("Pulled schema is invalid: {}", e)))?;
+    let preserved = Preserved {
+        names: schema.preserve_names(&previous),
+        attributes: schema.preserve_custom_attributes(&previous),
+    };
+    if preserved.is_empty() {
+        return Ok((pulled.to_string(), preserved));
+    }
+
+    let mut output = prax_header(config);
+    output.push_str(crate::commands::format::format_definitions(&schema).trim_start());
+    Ok((output, preserved))
+}
+
+/// Header, datasource and generator blocks of a pulled schema.
+fn prax_header(config: &Config) -> String {
     let mut output = String::new();
 
     output.push_str("// Generated by `prax db pull`\n");
@@ -396,9 +453,6 @@ pub fn format_as_prax(schema: &DatabaseSchema, config: &Config) -> String {
     output.push_str("    output   = \"./src/generated\"\n");
     output.push_str("}\n\n");
 
-    // Use the generate_prax_schema function
-    output.push_str(&generate_prax_schema(schema));
-
     output
 }
 
@@ -495,7 +549,7 @@ fn db_type_name(db_type: DatabaseType) -> &'static str {
     }
 }
 
-fn quote_identifier(name: &str, db_type: DatabaseType) -> String {
+pub(crate) fn quote_identifier(name: &str, db_type: DatabaseType) -> String {
     match db_type {
         DatabaseType::PostgreSQL => format!("\"{}\"", name),
         DatabaseType::MySQL => format!("`{}`", name),
@@ -504,3 +558,82 @@ fn quote_identifier(name: &str, db_type: DatabaseType) -> String {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PULLED: &str = r#"
+model User {
+    id    Int    @id @auto
+    email String @unique
+}
+"#;
+
+    #[test]
+    fn test_preserve_custom_attributes() {
+        let existing = r#"
+model User {
+    id    Int    @id @auto
+    email String @unique @search.indexed
+    @@audit.track
+}
+"#;
+
+        let (content, preserved) =
+            preserve_existing_schema(PULLED, existing, &Config::default()).unwrap();
+
+        assert_eq!(preserved.attributes, 2);
+        assert_eq!(preserved.names, 0);
+        assert!(content.starts_with("// Generated by `prax db pull`"));
+        assert!(content.contains("@unique @search.indexed"));
+        assert!(content.contains("@@audit.track"));
+        prax_schema::parse_schema(&content).unwrap();
+    }
+
+    #[test]
+    fn test_preserve_names_and_doc_comments() {
+        let existing = r#"
+/// A registered account
+model Account {
+    id   Int    @id @auto
+    /// Where we send mail
+    mail String @unique @map("email")
+    @@map("user")
+}
+"#;
+
+        let (content, preserved) =
+            preserve_existing_schema(PULLED, existing, &Config::default()).unwrap();
+
+        assert_eq!(
+            preserved,
+            Preserved {
+                names: 4,
+                attributes: 0
+            }
+        );
+        let schema = prax_schema::parse_schema(&content).unwrap();
+        let account = schema.get_model("Account").unwrap();
+        assert_eq!(account.table_name(), "user");
+        assert_eq!(
+            account.documentation.as_ref().unwrap().text,
+            "A registered account"
+        );
+        let mail = account.get_field("mail").unwrap();
+        assert!(mail.is_unique());
+        assert_eq!(
+            mail.documentation.as_ref().unwrap().text,
+            "Where we send mail"
+        );
+        assert!(content.contains(r#"@map("email")"#));
+    }
+
+    #[test]
+    fn test_preserve_existing_schema_without_changes() {
+        let (content, preserved) =
+            preserve_existing_schema(PULLED, PULLED, &Config::default()).unwrap();
+
+        assert!(preserved.is_empty());
+        assert_eq!(content, PULLED);
+    }
+}
@@ -2,17 +2,89 @@
 
 use std::path::PathBuf;
 
+use prax_migrate::{
+    DestructiveKind, EnvironmentPolicy, MigrationAudit, MigrationFile, MigrationPlan, MigrationSql,
+};
+use serde::Serialize;
+
 use crate::cli::MigrateArgs;
 use crate::commands::seed::{SeedRunner, find_seed_file, get_database_url};
 use crate::config::{CONFIG_FILE_NAME, Config, MIGRATIONS_DIR, SCHEMA_FILE_NAME};
 use crate::error::{CliError, CliResult};
 use crate::output::{self, success, warn};
 
+/// Machine-readable result of `prax migrate status`
+#[derive(Debug, Serialize)]
+pub struct MigrationStatusReport {
+    /// All migrations found on disk, oldest first
+    pub migrations: Vec<MigrationStatusEntry>,
+    /// Total number of migrations
+    pub total: usize,
+    /// Number of applied migrations
+    pub applied: usize,
+    /// Number of pending migrations
+    pub pending: usize,
+}
+
+/// A single migration in a [`MigrationStatusReport`]
+#[derive(Debug, Serialize)]
+pub struct MigrationStatusEntry {
+    /// Migration directory name
+    pub name: String,
+    /// Whether the migration has been applied
+    pub applied: bool,
+}
+
+/// Machine-readable result of `prax migrate diff`
+#[derive(Debug, Serialize)]
+pub struct MigrationDiffReport {
+    /// Path to the schema the diff was generated from
+    pub schema: String,
+    /// Whether the schema differs from the database
+    pub has_changes: bool,
+    /// Generated SQL
+    pub sql: String,
+    /// File the SQL was written to, if any
+    pub output: Option<String>,
+}
+
+/// Machine-readable result of `prax migrate diff --script` or `--check`
+#[derive(Debug, Serialize)]
+pub struct MigrationScriptReport {
+    /// Pending migrations, in the order deploy applies them
+    pub pending: Vec<String>,
+    /// SQL the next deploy would run
+    pub sql: String,
+    /// Statements that may lose data
+    pub destructive: Vec<DestructiveEntry>,
+    /// File the SQL was written to, if any
+    pub output: Option<String>,
+}
+
+/// A statement in a [`MigrationScriptReport`] that may lose data
+#[derive(Debug, Serialize)]
+pub struct DestructiveEntry {
+    /// Migration the statement belongs to
+    pub migration: String,
+    /// Why it may lose data
+    pub kind: DestructiveKind,
+    /// The statement
+    pub statement: String,
+}
+
+/// Contents of a migration's `.applied` marker
+#[derive(Serialize)]
+struct AppliedMarker<'a> {
+    applied_at: String,
+    #[serde(flatten)]
+    audit: &'a MigrationAudit,
+}
+
 /// Run the migrate command
 pub async fn run(args: MigrateArgs) -> CliResult<()> {
     match args.command {
         crate::cli::MigrateSubcommand::Dev(dev_args) => run_dev(dev_args).await,
-        crate::cli::MigrateSubcommand::Deploy => run_deploy().await,
+        crate::cli::MigrateSubcommand::Deploy(deploy_args) => run_deploy(deploy_args).await,
         crate::cli::MigrateSubcommand::Reset(reset_args) => run_reset(reset_args).await,
         crate::cli::MigrateSubcommand::Status => run_status().await,
         crate::cli::MigrateSubcommand::Resolve(resolve_args) => run_resolve(resolve_args).await,
@@ -32,9 +104,11 @@ async fn run_dev(args: crate::cli::MigrateDevArgs) -> CliResult<()> {
         .clone()
         .unwrap_or_else(|| cwd.join(SCHEMA_FILE_NAME));
     let migrations_dir = cwd.join(MIGRATIONS_DIR);
+    let policy = config.migrations.environment_policy(&args.environment)?;
 
     output::kv("Schema", &schema_path.display().to_string());
     output::kv("Migrations", &migrations_dir.display().to_string());
+    output::kv("Environment", &args.environment);
     output::newline();
 
     // Determine total steps (5 or 6 depending on seed)
@@ -43,7 +117,8 @@ async fn run_dev(args: crate::cli::MigrateDevArgs) -> CliResult<()> {
     // 1. Parse and validate schema
     output::step(1, total_steps, "Parsing schema...");
     let schema_content = std::fs::read_to_string(&schema_path)?;
-    let schema = parse_schema(&schema_content)?;
+    let mut schema = parse_schema(&schema_content)?;
+    config.generator.client.apply_naming(&mut schema);
 
     // 2. Check for pending migrations
     output::step(2, total_steps, "Checking migration status...");
@@ -65,12 +140,18 @@ async fn run_dev(args: crate::cli::MigrateDevArgs) -> CliResult<()> {
 
     // 4. Generate migration
     output::step(4, total_steps, "Generating migration...");
-    let migration_path = create_migration(&migrations_dir, &migration_name, &schema)?;
+    let migration_path = create_migration(
+        &migrations_dir,
+        &migration_name,
+        &schema,
+        &config.database.provider,
+    )?;
 
     // 5. Apply migration (if not --create-only)
     if !args.create_only {
         output::step(5, total_steps, "Applying migration...");
-        apply_migration(&migration_path, &config).await?;
+        let audits = check_environment(std::slice::from_ref(&migration_path), &policy)?;
+        apply_migration(&migration_path, &config, &audits[0]).await?;
     } else {
         output::step(5, total_steps, "Skipping apply (--create-only)...");
     }
@@ -113,18 +194,23 @@ async fn run_dev(args: crate::cli::MigrateDevArgs) -> CliResult<()> {
 }
 
 /// Run `prax migrate deploy` - production deployment
-async fn run_deploy() -> CliResult<()> {
+async fn run_deploy(args: crate::cli::MigrateDeployArgs) -> CliResult<()> {
     output::header("Migrate Deploy");
 
     let cwd = std::env::current_dir()?;
     let config = load_config(&cwd)?;
     let migrations_dir = cwd.join(MIGRATIONS_DIR);
+    let mut environment = config.migrations.environment_policy(&args.environment)?;
+    if let Some(approver) = args.approved_by {
+        environment = environment.approved_by(approver);
+    }
 
     output::kv("Migrations", &migrations_dir.display().to_string());
+    output::kv("Environment", &args.environment);
     output::newline();
 
     // Check for pending migrations
-    output::step(1, 3, "Checking for pending migrations...");
+    output::step(1, 5, "Checking for pending migrations...");
     let pending = check_pending_migrations(&migrations_dir)?;
 
     if pending.is_empty() {
@@ -139,18 +225,32 @@ async fn run_deploy() -> CliResult<()> {
     }
     output::newline();
 
+    // Verify signatures before anything is applied
+    output::step(2, 5, "Verifying signatures...");
+    let mut policy = config.migrations.signatures.policy();
+    if args.require_signatures {
+        policy.required = true;
+    }
+    verify_signatures(&pending, &policy)?;
+    output::newline();
+
+    // Check approvals and destructive changes before anything is applied
+    output::step(3, 5, "Checking environment policy...");
+    let audits = check_environment(&pending, &environment)?;
+    output::newline();
+
     // Apply migrations
-    output::step(2, 3, "Applying migrations...");
-    for migration in &pending {
+    output::step(4, 5, "Applying migrations...");
+    for (migration, audit) in pending.iter().zip(&audits) {
         output::list_item(&format!(
             "Applying {}",
             migration.file_name().unwrap().to_string_lossy()
         ));
-        apply_migration(migration, &config).await?;
+        apply_migration(migration, &config, audit).await?;
     }
 
     // Verify
-    output::step(3, 3, "Verifying migrations...");
+    output::step(5, 5, "Verifying migrations...");
 
     output::newline();
     success(&format!(
@@ -167,6 +267,14 @@ async fn run_reset(args: crate::cli::MigrateResetArgs) -> CliResult<()> {
 
     let cwd = std::env::current_dir()?;
     let config = load_config(&cwd)?;
+    let policy = config.migrations.environment_policy(&args.environment)?;
+
+    if !policy.allow_destructive {
+        return Err(CliError::Migration(format!(
+            "Resetting the database is destructive, which {} doesn't allow",
+            args.environment
+        )));
+    }
 
     if !args.force {
         warn("This will delete all data in the database!");
@@ -188,9 +296,10 @@ async fn run_reset(args: crate::cli::MigrateResetArgs) -> CliResult<()> {
     output::step(3, 4, "Applying migrations...");
     let migrations_dir = cwd.join(MIGRATIONS_DIR);
     let migrations = check_pending_migrations(&migrations_dir)?;
+    let audits = check_environment(&migrations, &policy)?;
 
-    for migration in &migrations {
-        apply_migration(migration, &config).await?;
+    for (migration, audit) in migrations.iter().zip(&audits) {
+        apply_migration(migration, &config, audit).await?;
     }
 
     // Run seed if requested
@@ -243,6 +352,21 @@ async fn run_status() -> CliResult<()> {
     }
     migrations.sort();
 
+    let entries: Vec<MigrationStatusEntry> = migrations
+        .iter()
+        .map(|m| MigrationStatusEntry {
+            name: m.file_name().unwrap().to_string_lossy().to_string(),
+            applied: is_migration_applied(m).unwrap_or(false),
+        })
+        .collect();
+    let applied_count = entries.iter().filter(|e| e.applied).count();
+    output::report(&MigrationStatusReport {
+        total: entries.len(),
+        applied: applied_count,
+        pending: entries.len() - applied_count,
+        migrations: entries,
+    });
+
     if migrations.is_empty() {
         output::info("No migrations found.");
         output::newline();
@@ -268,10 +392,6 @@ async fn run_status() -> CliResult<()> {
 
     output::newline();
 
-    let applied_count = migrations
-        .iter()
-        .filter(|m| is_migration_applied(m).unwrap_or(false))
-        .count();
     let pending_count = migrations.len() - applied_count;
 
     output::kv("Total", &migrations.len().to_string());
@@ -315,15 +435,21 @@ async fn run_resolve(args: crate::cli::MigrateResolveArgs) -> CliResult<()> {
 
 /// Run `prax migrate diff` - generate migration diff without applying
 async fn run_diff(args: crate::cli::MigrateDiffArgs) -> CliResult<()> {
+    if args.script || args.check {
+        return run_diff_script(args);
+    }
+
     output::header("Migrate Diff");
 
     let cwd = std::env::current_dir()?;
+    let config = load_config(&cwd)?;
     let schema_path = args.schema.unwrap_or_else(|| cwd.join(SCHEMA_FILE_NAME));
 
     // Parse schema
     output::step(1, 3, "Parsing schema...");
     let schema_content = std::fs::read_to_string(&schema_path)?;
-    let schema = parse_schema(&schema_content)?;
+    let mut schema = parse_schema(&schema_content)?;
+    config.generator.client.apply_naming(&mut schema);
 
     // Get current database state
     output::step(2, 3, "Introspecting database...");
@@ -331,10 +457,11 @@ async fn run_diff(args: crate::cli::MigrateDiffArgs) -> CliResult<()> {
 
     // Generate diff
     output::step(3, 3, "Generating diff...");
-    let diff_sql = generate_schema_diff(&schema)?;
+    let diff_sql = generate_schema_diff(&schema, &config.database.provider)?;
 
     output::newline();
 
+    let mut written_to = None;
     if diff_sql.is_empty() {
         success("Schema is in sync with database - no changes needed");
     } else {
@@ -345,6 +472,91 @@ async fn run_diff(args: crate::cli::MigrateDiffArgs) -> CliResult<()> {
             std::fs::write(&output_path, &diff_sql)?;
             output::newline();
             success(&format!("Diff written to {}", output_path.display()));
+            written_to = Some(output_path.display().to_string());
+        }
+    }
+
+    output::report(&MigrationDiffReport {
+        schema: schema_path.display().to_string(),
+        has_changes: !diff_sql.is_empty(),
+        sql: diff_sql,
+        output: written_to,
+    });
+
+    Ok(())
+}
+
+/// Run `prax migrate diff --script`/`--check` - show what the next deploy
+/// would run, flagging statements that may lose data
+fn run_diff_script(args: crate::cli::MigrateDiffArgs) -> CliResult<()> {
+    let cwd = std::env::current_dir()?;
+    let migrations_dir = cwd.join(MIGRATIONS_DIR);
+    let plan = pending_plan(&migrations_dir)?;
+    let script = plan.script();
+
+    // A script printed to stdout is kept clean for piping
+    let piped = args.script && args.output.is_none();
+    let warn_change: fn(&str) = if piped { output::warn_stderr } else { warn };
+
+    if piped {
+        if !output::is_json_mode() {
+            print!("{}", script);
+        }
+    } else {
+        output::header("Migrate Diff");
+        output::kv("Migrations", &migrations_dir.display().to_string());
+        output::kv("Pending", &plan.pending.len().to_string());
+        output::newline();
+    }
+
+    let mut written_to = None;
+    if let Some(output_path) = args.output.filter(|_| args.script) {
+        std::fs::write(&output_path, &script)?;
+        success(&format!("Script written to {}", output_path.display()));
+        written_to = Some(output_path.display().to_string());
+    }
+
+    let destructive: Vec<DestructiveEntry> = plan
+        .destructive_changes()
+        .into_iter()
+        .map(|(migration, change)| DestructiveEntry {
+            migration: migration.to_string(),
+            kind: change.kind,
+            statement: change.statement,
+        })
+        .collect();
+    for entry in &destructive {
+        let statement: Vec<&str> = entry
+            .statement
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with("--"))
+            .collect();
+        warn_change(&format!(
+            "{} {}: {}",
+            entry.migration,
+            entry.kind.as_str(),
+            statement.join(" ")
+        ));
+    }
+    let destructive_count = destructive.len();
+
+    output::report(&MigrationScriptReport {
+        pending: plan.pending.iter().map(|file| file.id.clone()).collect(),
+        sql: script,
+        destructive,
+        output: written_to,
+    });
+
+    if args.check {
+        if destructive_count > 0 {
+            return Err(CliError::Migration(format!(
+                "{} destructive change(s) in pending migrations",
+                destructive_count
+            )));
+        }
+        if !piped {
+            success("No destructive changes in pending migrations");
         }
     }
 
@@ -369,6 +581,22 @@ fn parse_schema(content: &str) -> CliResult<prax_schema::Schema> {
         .map_err(|e| CliError::Schema(format!("Failed to parse schema: {}", e)))
 }
 
+/// Plan applying the pending migrations, as `migrate deploy` would
+fn pending_plan(migrations_dir: &PathBuf) -> CliResult<MigrationPlan> {
+    let mut plan = MigrationPlan::empty();
+    for migration in check_pending_migrations(migrations_dir)? {
+        let name = migration.file_name().unwrap().to_string_lossy().to_string();
+        let up = std::fs::read_to_string(migration.join("migration.sql"))?;
+        let sql = MigrationSql {
+            up,
+            down: String::new(),
+        };
+        plan.pending
+            .push(MigrationFile::new(name.clone(), name, sql).with_path(migration));
+    }
+    Ok(plan)
+}
+
 fn check_pending_migrations(migrations_dir: &PathBuf) -> CliResult<Vec<PathBuf>> {
     let mut pending = Vec::new();
 
@@ -390,6 +618,61 @@ fn check_pending_migrations(migrations_dir: &PathBuf) -> CliResult<Vec<PathBuf>>
     Ok(pending)
 }
 
+/// Check every pending migration's signature, so one unsigned or tampered
+/// migration stops the deploy before any of them run
+fn verify_signatures(pending: &[PathBuf], policy: &prax_migrate::SignaturePolicy) -> CliResult<()> {
+    if !policy.is_active() {
+        output::list_item("No trusted keys configured, skipping");
+        return Ok(());
+    }
+
+    for migration in pending {
+        let name = migration.file_name().unwrap().to_string_lossy();
+        let sql_path = migration.join("migration.sql");
+        let sql = std::fs::read(&sql_path)?;
+
+        match policy
+            .verify(&sql_path, &sql)
+            .map_err(|e| CliError::Migration(e.to_string()))?
+        {
+            Some(signature) => output::list_item(&format!(
+                "{} signed by {} ({})",
+                name,
+                signature.signed_by,
+                signature.kind.as_str()
+            )),
+            None => warn(&format!("{} is not signed", name)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Check every migration against the environment's policy, so one
+/// unapproved or destructive migration stops the run before any of them
+/// apply, returning their audit records
+fn check_environment(
+    migrations: &[PathBuf],
+    policy: &EnvironmentPolicy,
+) -> CliResult<Vec<MigrationAudit>> {
+    let mut audits = Vec::with_capacity(migrations.len());
+
+    for migration in migrations {
+        let name = migration.file_name().unwrap().to_string_lossy();
+        let sql = std::fs::read_to_string(migration.join("migration.sql"))?;
+        let audit = policy
+            .check(&name, &sql)
+            .map_err(|e| CliError::Migration(e.to_string()))?;
+
+        if let Some(approver) = &audit.approved_by {
+            output::list_item(&format!("{} approved by {}", name, approver));
+        }
+        audits.push(audit);
+    }
+
+    Ok(audits)
+}
+
 fn is_migration_applied(migration_path: &PathBuf) -> CliResult<bool> {
     // Check for a marker file indicating the migration has been applied
     // In production, this would check the migration history table
@@ -401,6 +684,7 @@ fn create_migration(
     migrations_dir: &PathBuf,
     name: &str,
     schema: &prax_schema::ast::Schema,
+    provider: &str,
 ) -> CliResult<PathBuf> {
     // Create migration directory
     let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
@@ -410,7 +694,7 @@ fn create_migration(
     std::fs::create_dir_all(&migration_path)?;
 
     // Generate migration SQL
-    let sql = generate_schema_diff(schema)?;
+    let sql = generate_schema_diff(schema, provider)?;
 
     // Write migration.sql
     let sql_path = migration_path.join("migration.sql");
@@ -419,9 +703,16 @@ fn create_migration(
     Ok(migration_path)
 }
 
-fn generate_schema_diff(schema: &prax_schema::ast::Schema) -> CliResult<String> {
+fn generate_schema_diff(schema: &prax_schema::ast::Schema, provider: &str) -> CliResult<String> {
     use prax_schema::ast::{FieldType, ScalarType};
 
+    if provider == "mysql" {
+        return generate_dialect_schema_diff(schema, &prax_migrate::MySqlGenerator);
+    }
+    if provider == "sqlite" {
+        return generate_dialect_schema_diff(schema, &prax_migrate::SqliteSqlGenerator);
+    }
+
     let mut sql = String::new();
 
     sql.push_str("-- Migration generated by Prax\n\n");
@@ -542,6 +833,15 @@ fn generate_schema_diff(schema: &prax_schema::ast::Schema) -> CliResult<String>
                 ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => {
                     "TEXT".to_string()
                 }
+                ScalarType::IntRange => "INT4RANGE".to_string(),
+                ScalarType::BigIntRange => "INT8RANGE".to_string(),
+                ScalarType::DateTimeRange => "TSTZRANGE".to_string(),
+                ScalarType::Inet => "INET".to_string(),
+                ScalarType::Cidr => "CIDR".to_string(),
+                ScalarType::MacAddr => "MACADDR".to_string(),
+                ScalarType::Geometry => "GEOMETRY".to_string(),
+                ScalarType::Geography => "GEOGRAPHY".to_string(),
+                ScalarType::Money => "DECIMAL(19, 4)".to_string(),
                 ScalarType::Vector(dim) => match dim {
                     Some(d) => format!("vector({})", d),
                     None => "vector".to_string(),
@@ -565,7 +865,24 @@ fn generate_schema_diff(schema: &prax_schema::ast::Schema) -> CliResult<String>
     }
 }
 
-async fn apply_migration(migration_path: &PathBuf, _config: &Config) -> CliResult<()> {
+/// Generate migration SQL with a prax-migrate dialect generator.
+fn generate_dialect_schema_diff(
+    schema: &prax_schema::ast::Schema,
+    generator: &dyn prax_migrate::MigrationSqlGenerator,
+) -> CliResult<String> {
+    let diff = prax_migrate::SchemaDiffer::new(schema.clone())
+        .diff()
+        .map_err(|e| CliError::Migration(e.to_string()))?;
+    let sql = generator.generate(&diff);
+
+    Ok(format!("-- Migration generated by Prax\n\n{}\n", sql.up))
+}
+
+async fn apply_migration(
+    migration_path: &PathBuf,
+    _config: &Config,
+    audit: &MigrationAudit,
+) -> CliResult<()> {
     let sql_path = migration_path.join("migration.sql");
 
     if !sql_path.exists() {
@@ -580,9 +897,15 @@ async fn apply_migration(migration_path: &PathBuf, _config: &Config) -> CliResul
     // TODO: Execute SQL against database
     // This would use the database URL from config
 
-    // Mark as applied
+    // Mark as applied, recording who applied it and on whose approval
     let marker = migration_path.join(".applied");
-    std::fs::write(&marker, chrono::Utc::now().to_rfc3339())?;
+    let record = AppliedMarker {
+        applied_at: chrono::Utc::now().to_rfc3339(),
+        audit,
+    };
+    let json = serde_json::to_string_pretty(&record)
+        .map_err(|e| CliError::Migration(format!("Failed to write audit record: {}", e)))?;
+    std::fs::write(&marker, json)?;
 
     Ok(())
 }
@@ -648,5 +971,12 @@ fn format_attribute_value(value: &prax_schema::ast::AttributeValue) -> String {
                     .join(", ")
             )
         }
+        AttributeValue::Object(entries) => {
+            let entry_strs: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, format_attribute_value(value)))
+                .collect();
+            format!("{{{}}}", entry_strs.join(", "))
+        }
     }
 }
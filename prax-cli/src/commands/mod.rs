@@ -1,11 +1,23 @@
 //! CLI command implementations.
 
+pub mod advisor;
+pub mod analyze_log;
+pub mod checksum;
+pub mod completions;
+pub mod console;
+pub mod convert;
+pub mod copy;
 pub mod db;
+pub mod ddl;
 pub mod format;
 pub mod generate;
+pub mod health;
 pub mod init;
 pub mod introspect;
 pub mod migrate;
+pub mod prisma;
+pub mod scaffold;
 pub mod seed;
+pub mod templates;
 pub mod validate;
 pub mod version;
@@ -0,0 +1,792 @@
+//! Conversion between Prisma and Prax schemas.
+//!
+//! The Prax schema language is close to Prisma's, so models, enums,
+//! relations and most attributes carry over unchanged. Features without an
+//! equivalent on the other side are dropped and reported as warnings.
+
+use prax_schema::ast::{
+    Attribute, AttributeArg, AttributeValue, Field, FieldType, Ident, ScalarType,
+};
+
+use crate::commands::format::format_definitions;
+use crate::error::{CliError, CliResult};
+
+/// A converted schema.
+#[derive(Debug, Clone, Default)]
+pub struct Conversion {
+    /// Source of the converted schema.
+    pub schema: String,
+    /// Features that could not be converted.
+    pub warnings: Vec<String>,
+}
+
+impl Conversion {
+    fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+}
+
+// ============================================================================
+// Prisma -> Prax
+// ============================================================================
+
+/// A top-level block of a Prisma schema.
+struct Block {
+    kind: String,
+    name: String,
+    docs: Vec<String>,
+    lines: Vec<(usize, String)>,
+}
+
+/// What to do with a Prisma attribute.
+enum Mapped {
+    /// Keep the attribute, possibly rewritten.
+    Keep(String),
+    /// Drop the attribute, with the reason.
+    Drop(String),
+    /// `@ignore` / `@@ignore`: drop the whole field or model.
+    Ignore,
+}
+
+/// Convert a Prisma schema to Prax.
+pub fn prisma_to_prax(source: &str) -> CliResult<Conversion> {
+    let mut conversion = Conversion::default();
+    let mut datasource = None;
+    let mut body = String::new();
+    let mut block: Option<Block> = None;
+    let mut docs = Vec::new();
+
+    for (index, raw) in source.lines().enumerate() {
+        let line_no = index + 1;
+        let trimmed = raw.trim();
+
+        if trimmed.starts_with("///") {
+            match &mut block {
+                Some(block) => block.lines.push((line_no, trimmed.to_string())),
+                None => docs.push(trimmed.to_string()),
+            }
+            continue;
+        }
+
+        let line = strip_comment(trimmed).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match block.take() {
+            Some(current) if line == "}" => match current.kind.as_str() {
+                "datasource" => datasource = convert_datasource(&current, &mut conversion),
+                "generator" => conversion.warn(format!(
+                    "generator `{}` was replaced by the Prax client generator",
+                    current.name
+                )),
+                "model" | "view" | "type" => {
+                    body.push_str(&convert_model_block(&current, &mut conversion))
+                }
+                _ => body.push_str(&convert_enum_block(&current, &mut conversion)),
+            },
+            Some(mut current) => {
+                current.lines.push((line_no, line.to_string()));
+                block = Some(current);
+            }
+            None => match parse_block_header(line) {
+                Some((kind, name)) => {
+                    block = Some(Block {
+                        kind: kind.to_string(),
+                        name: name.to_string(),
+                        docs: std::mem::take(&mut docs),
+                        lines: Vec::new(),
+                    });
+                }
+                None => conversion.warn(format!("line {}: skipped `{}`", line_no, line)),
+            },
+        }
+    }
+
+    if let Some(block) = block {
+        return Err(CliError::Schema(format!(
+            "Unterminated block `{} {}`",
+            block.kind, block.name
+        )));
+    }
+
+    let schema = prax_schema::parse_schema(&body)
+        .map_err(|e| CliError::Schema(format!("Converted schema does not parse: {}", e)))?;
+
+    let mut output = String::new();
+    if let Some(datasource) = datasource {
+        output.push_str(&datasource);
+        output.push('\n');
+    }
+    output.push_str("generator client {\n");
+    output.push_str("    provider = \"prax-client-rust\"\n");
+    output.push_str("    output   = \"./src/generated\"\n");
+    output.push_str("}\n");
+    output.push_str(&format_definitions(&schema));
+
+    conversion.schema = output;
+    Ok(conversion)
+}
+
+/// Parse `model User {` into `("model", "User")`.
+fn parse_block_header(line: &str) -> Option<(&str, &str)> {
+    let line = line.strip_suffix('{')?.trim();
+    let (kind, name) = line.split_once(char::is_whitespace)?;
+    let name = name.trim();
+
+    let known = matches!(
+        kind,
+        "datasource" | "generator" | "model" | "enum" | "view" | "type"
+    );
+    (known && !name.is_empty() && !name.contains(char::is_whitespace)).then_some((kind, name))
+}
+
+fn convert_datasource(block: &Block, conversion: &mut Conversion) -> Option<String> {
+    let mut provider = None;
+    let mut properties = Vec::new();
+
+    for (line_no, line) in &block.lines {
+        let Some((key, value)) = line.split_once('=') else {
+            conversion.warn(format!("line {}: skipped `{}`", line_no, line));
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        match key {
+            "provider" => provider = Some(value.trim_matches('"').to_string()),
+            "url" | "extensions" => properties.push((key, value)),
+            _ => conversion.warn(format!(
+                "line {}: datasource property `{}` is not supported",
+                line_no, key
+            )),
+        }
+    }
+
+    let provider = match provider.as_deref() {
+        Some(name @ ("postgresql" | "postgres" | "mysql" | "sqlite" | "mongodb")) => {
+            name.to_string()
+        }
+        Some("cockroachdb") => {
+            conversion.warn("provider `cockroachdb` was converted to `postgresql`");
+            "postgresql".to_string()
+        }
+        Some(other) => {
+            conversion.warn(format!(
+                "provider `{}` is not supported; the datasource was dropped",
+                other
+            ));
+            return None;
+        }
+        None => return None,
+    };
+
+    let mut output = format!("datasource {} {{\n", block.name);
+    output.push_str(&format!("    provider = \"{}\"\n", provider));
+    for (key, value) in properties {
+        output.push_str(&format!("    {:<8} = {}\n", key, value));
+    }
+    output.push_str("}\n");
+    Some(output)
+}
+
+fn convert_model_block(block: &Block, conversion: &mut Conversion) -> String {
+    let mut lines = Vec::new();
+    let location = |line_no: &usize| format!("line {} ({})", line_no, block.name);
+
+    for (line_no, line) in &block.lines {
+        // Field documentation is not part of the Prax grammar
+        if line.starts_with("///") {
+            continue;
+        }
+
+        if line.starts_with("@@") {
+            for attr in split_attributes(line) {
+                match convert_block_attribute(&attr) {
+                    Mapped::Keep(attr) => lines.push(attr),
+                    Mapped::Drop(reason) => {
+                        conversion.warn(format!("{}: {}", location(line_no), reason))
+                    }
+                    Mapped::Ignore => {
+                        conversion.warn(format!(
+                            "{}: {} `{}` is marked @@ignore and was dropped",
+                            location(line_no),
+                            block.kind,
+                            block.name
+                        ));
+                        return String::new();
+                    }
+                }
+            }
+            continue;
+        }
+
+        let Some((name, rest)) = line.split_once(char::is_whitespace) else {
+            conversion.warn(format!("{}: skipped `{}`", location(line_no), line));
+            continue;
+        };
+        let (field_type, attrs) = split_type(rest.trim());
+
+        if field_type.starts_with("Unsupported(") {
+            conversion.warn(format!(
+                "{}: field `{}` has type {} and was dropped",
+                location(line_no),
+                name,
+                field_type
+            ));
+            continue;
+        }
+
+        let mut field = format!("{} {}", name, field_type);
+        let mut ignored = false;
+        for attr in split_attributes(attrs) {
+            match convert_field_attribute(&attr) {
+                Mapped::Keep(attr) => {
+                    field.push(' ');
+                    field.push_str(&attr);
+                }
+                Mapped::Drop(reason) => {
+                    conversion.warn(format!("{}: {} on `{}`", location(line_no), reason, name))
+                }
+                Mapped::Ignore => ignored = true,
+            }
+        }
+
+        if ignored {
+            conversion.warn(format!(
+                "{}: field `{}` is marked @ignore and was dropped",
+                location(line_no),
+                name
+            ));
+        } else {
+            lines.push(field);
+        }
+    }
+
+    render_block(block, &lines)
+}
+
+fn convert_enum_block(block: &Block, conversion: &mut Conversion) -> String {
+    let mut lines = Vec::new();
+
+    for (line_no, line) in &block.lines {
+        if line.starts_with("///") {
+            continue;
+        }
+
+        let (head, attrs) = if line.starts_with("@@") {
+            ("", line.as_str())
+        } else {
+            split_type(line)
+        };
+
+        let mut converted = head.to_string();
+        for attr in split_attributes(attrs) {
+            let mapped = if attr.starts_with("@@") {
+                convert_block_attribute(&attr)
+            } else {
+                convert_field_attribute(&attr)
+            };
+            match mapped {
+                Mapped::Keep(attr) => {
+                    if !converted.is_empty() {
+                        converted.push(' ');
+                    }
+                    converted.push_str(&attr);
+                }
+                Mapped::Drop(reason) => {
+                    conversion.warn(format!("line {} ({}): {}", line_no, block.name, reason))
+                }
+                Mapped::Ignore => conversion.warn(format!(
+                    "line {} ({}): `{}` is not supported",
+                    line_no, block.name, attr
+                )),
+            }
+        }
+
+        if !converted.is_empty() {
+            lines.push(converted);
+        }
+    }
+
+    render_block(block, &lines)
+}
+
+fn render_block(block: &Block, lines: &[String]) -> String {
+    let mut output = String::new();
+    for doc in &block.docs {
+        output.push_str(doc);
+        output.push('\n');
+    }
+    output.push_str(&format!("{} {} {{\n", block.kind, block.name));
+    for line in lines {
+        output.push_str("    ");
+        output.push_str(line);
+        output.push('\n');
+    }
+    output.push_str("}\n\n");
+    output
+}
+
+fn convert_field_attribute(attr: &str) -> Mapped {
+    let (name, args) = split_attribute(attr);
+
+    match name {
+        "id" | "unique" | "map" | "relation" => Mapped::Keep(attr.to_string()),
+        _ if name.starts_with("db.") => Mapped::Keep(attr.to_string()),
+        "updatedAt" => Mapped::Keep("@updated_at".to_string()),
+        "ignore" => Mapped::Ignore,
+        "default" => match args.map(str::trim) {
+            Some("autoincrement()") => Mapped::Keep("@auto".to_string()),
+            Some(value)
+                if ["dbgenerated(", "sequence(", "auto("]
+                    .iter()
+                    .any(|f| value.starts_with(f)) =>
+            {
+                Mapped::Drop(format!("`{}` has no Prax equivalent", attr))
+            }
+            _ => Mapped::Keep(attr.to_string()),
+        },
+        _ => Mapped::Drop(format!("`{}` is not supported", attr)),
+    }
+}
+
+fn convert_block_attribute(attr: &str) -> Mapped {
+    let (name, _) = split_attribute(attr);
+
+    match name {
+        "id" | "unique" | "index" | "map" => Mapped::Keep(attr.to_string()),
+        "ignore" => Mapped::Ignore,
+        "schema" => Mapped::Drop(format!(
+            "`{}` is not supported (multi-schema is a Prisma preview feature)",
+            attr
+        )),
+        _ => Mapped::Drop(format!("`{}` is not supported", attr)),
+    }
+}
+
+/// Split an attribute into its name and argument list.
+fn split_attribute(attr: &str) -> (&str, Option<&str>) {
+    let attr = attr.trim_start_matches('@');
+    match attr.split_once('(') {
+        Some((name, args)) => (name, args.strip_suffix(')')),
+        None => (attr, None),
+    }
+}
+
+/// Split a field's remainder into its type and attributes.
+///
+/// The type ends at the first whitespace outside parentheses, so
+/// `Unsupported("circle")` stays in one piece.
+fn split_type(rest: &str) -> (&str, &str) {
+    let mut depth = 0;
+    let mut in_string = false;
+
+    for (i, c) in rest.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth -= 1,
+            c if c.is_whitespace() && depth == 0 && !in_string => {
+                return (&rest[..i], rest[i..].trim());
+            }
+            _ => {}
+        }
+    }
+    (rest, "")
+}
+
+/// Split `@id @default(now()) @db.Timestamp(6)` into individual attributes.
+fn split_attributes(text: &str) -> Vec<String> {
+    let mut attrs = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut prev = ' ';
+
+    for c in text.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' | '[' if !in_string => depth += 1,
+            ')' | ']' if !in_string => depth -= 1,
+            '@' if !in_string && depth == 0 && prev.is_whitespace() => {
+                if !current.trim().is_empty() {
+                    attrs.push(current.trim().to_string());
+                }
+                current.clear();
+            }
+            _ => {}
+        }
+        current.push(c);
+        prev = c;
+    }
+
+    if !current.trim().is_empty() {
+        attrs.push(current.trim().to_string());
+    }
+    attrs
+}
+
+/// Remove a trailing `//` comment that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let bytes = line.as_bytes();
+
+    for i in 0..bytes.len() {
+        match bytes[i] {
+            b'"' => in_string = !in_string,
+            b'/' if !in_string && bytes.get(i + 1) == Some(&b'/') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+// ============================================================================
+// Prax -> Prisma
+// ============================================================================
+
+/// Convert a Prax schema to Prisma.
+pub fn prax_to_prisma(source: &str) -> CliResult<Conversion> {
+    let mut schema = prax_schema::parse_schema(source)
+        .map_err(|e| CliError::Schema(format!("Syntax error: {}", e)))?;
+    let mut conversion = Conversion::default();
+
+    for model in schema.models.values_mut() {
+        let location = model.name().to_string();
+        let mut indexes = Vec::new();
+
+        for field in model.fields.values_mut() {
+            let location = format!("{}.{}", location, field.name());
+            convert_prax_field(field, &location, &mut indexes, &mut conversion);
+        }
+        model.attributes.retain(|attr| {
+            keep_block_attribute(attr, &format!("model {}", location), &mut conversion)
+        });
+        model.attributes.extend(indexes);
+    }
+
+    for view in schema.views.values_mut() {
+        let location = view.name().to_string();
+        for field in view.fields.values_mut() {
+            let location = format!("{}.{}", location, field.name());
+            convert_prax_field(field, &location, &mut Vec::new(), &mut conversion);
+        }
+        view.attributes.retain(|attr| {
+            keep_block_attribute(attr, &format!("view {}", location), &mut conversion)
+        });
+    }
+    if !schema.views.is_empty() {
+        conversion.warn("views require the `views` preview feature in Prisma");
+    }
+
+    for composite in schema.types.values_mut() {
+        let location = composite.name().to_string();
+        for field in composite.fields.values_mut() {
+            let location = format!("{}.{}", location, field.name());
+            convert_prax_field(field, &location, &mut Vec::new(), &mut conversion);
+        }
+    }
+    if !schema.types.is_empty() {
+        conversion.warn("composite types are only supported by Prisma's MongoDB connector");
+    }
+
+    for enum_def in schema.enums.values_mut() {
+        let location = format!("enum {}", enum_def.name());
+        enum_def
+            .attributes
+            .retain(|attr| keep_block_attribute(attr, &location, &mut conversion));
+    }
+
+    for policy in &schema.policies {
+        conversion.warn(format!("policy `{}` was dropped", policy.name()));
+    }
+    for group in schema.server_groups.values() {
+        conversion.warn(format!(
+            "server group `{}` was dropped",
+            group.name.as_str()
+        ));
+    }
+    for sql in &schema.raw_sql {
+        conversion.warn(format!("raw SQL `{}` was dropped", sql.name));
+    }
+
+    let mut output = String::new();
+    if let Some(datasource) = &schema.datasource {
+        output.push_str(&format!("datasource {} {{\n", datasource.name));
+        output.push_str(&format!("    provider = \"{}\"\n", datasource.provider));
+        match (&datasource.url_env, &datasource.url) {
+            (Some(env), _) => output.push_str(&format!("    url      = env(\"{}\")\n", env)),
+            (None, Some(url)) => output.push_str(&format!("    url      = \"{}\"\n", url)),
+            (None, None) => output.push_str("    url      = env(\"DATABASE_URL\")\n"),
+        }
+        if !datasource.extensions.is_empty() {
+            let names: Vec<&str> = datasource.extensions.iter().map(|e| e.name()).collect();
+            output.push_str(&format!("    extensions = [{}]\n", names.join(", ")));
+            conversion.warn("datasource extensions require the `postgresqlExtensions` preview feature in Prisma");
+        }
+        output.push_str("}\n\n");
+    }
+    output.push_str("generator client {\n");
+    output.push_str("    provider = \"prisma-client-js\"\n");
+    output.push_str("}\n");
+    output.push_str(&format_definitions(&schema));
+
+    conversion.schema = output;
+    Ok(conversion)
+}
+
+/// Rewrite a Prax field in place for Prisma.
+fn convert_prax_field(
+    field: &mut Field,
+    location: &str,
+    indexes: &mut Vec<Attribute>,
+    conversion: &mut Conversion,
+) {
+    // Prisma has fewer scalar types; map the rest onto the closest one
+    let native = match &field.field_type {
+        FieldType::Scalar(ScalarType::Date) => Some("db.Date"),
+        FieldType::Scalar(ScalarType::Time) => Some("db.Time"),
+        _ => None,
+    };
+    let mapped = match &field.field_type {
+        FieldType::Scalar(ScalarType::Date | ScalarType::Time) => {
+            Some(FieldType::Scalar(ScalarType::DateTime))
+        }
+        FieldType::Scalar(
+            ScalarType::Uuid
+            | ScalarType::Cuid
+            | ScalarType::Cuid2
+            | ScalarType::NanoId
+            | ScalarType::Ulid,
+        ) => Some(FieldType::Scalar(ScalarType::String)),
+        FieldType::Scalar(
+            ScalarType::Vector(_)
+            | ScalarType::HalfVector(_)
+            | ScalarType::SparseVector(_)
+            | ScalarType::Bit(_),
+        ) => Some(FieldType::Unsupported(
+            field.field_type.type_name().to_lowercase().into(),
+        )),
+        FieldType::Scalar(
+            scalar @ (ScalarType::IntRange
+            | ScalarType::BigIntRange
+            | ScalarType::DateTimeRange
+            | ScalarType::Inet
+            | ScalarType::Cidr
+            | ScalarType::MacAddr
+            | ScalarType::Geometry
+            | ScalarType::Geography
+            | ScalarType::Money),
+        ) => Some(FieldType::Unsupported(
+            scalar.postgres_type().to_lowercase().into(),
+        )),
+        _ => None,
+    };
+    if let Some(mapped) = mapped {
+        conversion.warn(format!(
+            "{}: type {} was converted to {}",
+            location,
+            field.field_type.type_name(),
+            mapped.type_name()
+        ));
+        field.field_type = mapped;
+    }
+
+    let span = field.span;
+    let mut attributes = Vec::new();
+    for attr in std::mem::take(&mut field.attributes) {
+        match attr.name() {
+            "id" | "unique" | "default" | "map" | "relation" => attributes.push(attr),
+            name if name.starts_with("db.") => attributes.push(attr),
+            "auto" => attributes.push(Attribute::new(
+                Ident::new("default", span),
+                vec![AttributeArg::positional(
+                    AttributeValue::Function("autoincrement".into(), vec![]),
+                    span,
+                )],
+                span,
+            )),
+            "updated_at" | "updatedAt" => {
+                attributes.push(Attribute::simple(Ident::new("updatedAt", span), span))
+            }
+            "index" => indexes.push(Attribute::new(
+                Ident::new("index", span),
+                vec![AttributeArg::positional(
+                    AttributeValue::FieldRefList(vec![field.name.name.clone()]),
+                    span,
+                )],
+                span,
+            )),
+            name => conversion.warn(format!("{}: @{} was dropped", location, name)),
+        }
+    }
+    if let Some(native) = native.filter(|n| !attributes.iter().any(|a| a.is(n))) {
+        attributes.push(Attribute::simple(Ident::new(native, span), span));
+    }
+    field.attributes = attributes;
+}
+
+/// Whether a model, view or enum level attribute has a Prisma equivalent.
+fn keep_block_attribute(attr: &Attribute, location: &str, conversion: &mut Conversion) -> bool {
+    let keep = matches!(attr.name(), "id" | "unique" | "index" | "map");
+    if !keep {
+        conversion.warn(format!("{}: @@{} was dropped", location, attr.name()));
+    }
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRISMA: &str = r#"
+// Prisma schema
+datasource db {
+  provider  = "postgresql"
+  url       = env("DATABASE_URL")
+  directUrl = env("DIRECT_URL")
+}
+
+generator client {
+  provider = "prisma-client-js"
+}
+
+/// A registered user
+model User {
+  id        Int      @id @default(autoincrement())
+  email     String   @unique @db.VarChar(255)
+  role      Role     @default(USER)
+  posts     Post[]
+  location  Unsupported("point")?
+  createdAt DateTime @default(now()) @map("created_at")
+  updatedAt DateTime @updatedAt
+
+  @@map("users")
+  @@schema("auth")
+}
+
+model Post {
+  id       String @id @default(uuid())
+  title    String // inline comment
+  authorId Int
+  author   User   @relation(fields: [authorId], references: [id], onDelete: Cascade)
+  secret   String @ignore
+
+  @@index([authorId])
+}
+
+model Legacy {
+  id Int @id
+
+  @@ignore
+}
+
+enum Role {
+  USER
+  ADMIN @map("admin")
+
+  @@map("roles")
+}
+"#;
+
+    #[test]
+    fn test_prisma_to_prax() {
+        let conversion = prisma_to_prax(PRISMA).unwrap();
+        let schema = prax_schema::parse_schema(&conversion.schema).unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        assert!(user.get_field("id").unwrap().has_attribute("auto"));
+        assert!(
+            user.get_field("updatedAt")
+                .unwrap()
+                .has_attribute("updated_at")
+        );
+        assert!(user.get_field("location").is_none());
+        assert_eq!(user.table_name(), "users");
+
+        let post = schema.get_model("Post").unwrap();
+        assert!(post.get_field("secret").is_none());
+        assert!(post.get_field("author").unwrap().has_attribute("relation"));
+        assert!(schema.get_model("Legacy").is_none());
+        assert_eq!(schema.enums["Role"].db_name(), "roles");
+
+        assert!(conversion.schema.contains("provider = \"postgresql\""));
+        assert!(conversion.schema.contains("prax-client-rust"));
+        assert!(conversion.schema.contains("@@index([authorId])"));
+    }
+
+    #[test]
+    fn test_prisma_to_prax_reports_unsupported_features() {
+        let warnings = prisma_to_prax(PRISMA).unwrap().warnings;
+        let reported = |needle: &str| warnings.iter().any(|w| w.contains(needle));
+
+        assert!(reported("`directUrl`"));
+        assert!(reported("generator `client`"));
+        assert!(reported("Unsupported(\"point\")"));
+        assert!(reported("@@schema"));
+        assert!(reported("`secret` is marked @ignore"));
+        assert!(reported("`Legacy` is marked @@ignore"));
+    }
+
+    #[test]
+    fn test_prisma_to_prax_unterminated_block() {
+        assert!(prisma_to_prax("model User {\n  id Int @id\n").is_err());
+    }
+
+    #[test]
+    fn test_prax_to_prisma() {
+        let conversion = prax_to_prisma(
+            r#"
+datasource db {
+    provider = "postgresql"
+    url      = env("DATABASE_URL")
+}
+
+model User {
+    id        Int      @id @auto
+    email     String   @unique @validate.email
+    token     Uuid
+    birthday  Date?
+    slug      String   @index
+    updatedAt DateTime @updated_at
+}
+"#,
+        )
+        .unwrap();
+
+        let schema = &conversion.schema;
+        assert!(schema.contains("provider = \"prisma-client-js\""));
+        assert!(schema.contains("url      = env(\"DATABASE_URL\")"));
+        assert!(schema.contains("@id @default(autoincrement())"));
+        assert!(schema.contains("@updatedAt"));
+        assert!(schema.contains("@db.Date"));
+        assert!(schema.contains("@@index([slug])"));
+        assert!(!schema.contains("@validate"));
+
+        let reported = |needle: &str| conversion.warnings.iter().any(|w| w.contains(needle));
+        assert!(reported("User.email: @validate.email was dropped"));
+        assert!(reported("User.token: type Uuid was converted to String"));
+    }
+
+    #[test]
+    fn test_split_attributes() {
+        assert_eq!(
+            split_attributes(r#"@id @default(now()) @map("a @b") @@index([a, b])"#),
+            vec![
+                "@id",
+                "@default(now())",
+                "@map(\"a @b\")",
+                "@@index([a, b])"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_type() {
+        assert_eq!(
+            split_type(r#"Unsupported("circle x")? @map("c")"#),
+            (r#"Unsupported("circle x")?"#, r#"@map("c")"#)
+        );
+        assert_eq!(split_type("Int"), ("Int", ""));
+    }
+}
@@ -0,0 +1,805 @@
+//! `prax generate crud` command - Scaffold API handlers for a model.
+//!
+//! Scaffolding writes editable source files (DTOs, handlers, router
+//! registration and an integration test) that use the generated Prax client.
+//! Unlike `prax generate`, the output is meant to be customized and is never
+//! overwritten unless `--force` is passed.
+
+use std::path::{Path, PathBuf};
+
+use prax_schema::ast::{Field, Model};
+
+use crate::cli::{CrudFramework, GenerateCrudArgs};
+use crate::config::SCHEMA_FILE_NAME;
+use crate::error::{CliError, CliResult};
+use crate::output::{self, success, warn};
+
+/// A file produced by the scaffolder, relative to the project root.
+#[derive(Debug, Clone)]
+pub struct ScaffoldFile {
+    /// Path relative to the project root.
+    pub path: PathBuf,
+    /// File contents.
+    pub contents: String,
+}
+
+/// Run `prax generate crud <Model>`
+pub async fn run_crud(args: GenerateCrudArgs) -> CliResult<()> {
+    output::header("Scaffold CRUD");
+
+    let cwd = std::env::current_dir()?;
+    let schema_path = args.schema.unwrap_or_else(|| cwd.join(SCHEMA_FILE_NAME));
+    if !schema_path.exists() {
+        return Err(CliError::Config(format!(
+            "Schema file not found: {}",
+            schema_path.display()
+        )));
+    }
+
+    output::kv("Schema", &schema_path.display().to_string());
+    output::kv("Model", &args.model);
+    output::kv("Framework", &args.framework.to_string());
+    output::newline();
+
+    output::step(1, 3, "Reading schema...");
+    let schema_content = std::fs::read_to_string(&schema_path)?;
+    let schema = prax_schema::parse_schema(&schema_content)
+        .map_err(|e| CliError::Schema(format!("Failed to parse schema: {}", e)))?;
+
+    output::step(2, 3, "Generating scaffold...");
+    let api_dir = args.output.unwrap_or_else(|| PathBuf::from("src/api"));
+    let crate_name = read_crate_name(&cwd).unwrap_or_else(|| "app".to_string());
+    let files = scaffold_crud(&schema, &args.model, args.framework, &api_dir, &crate_name)?;
+
+    output::step(3, 3, "Writing files...");
+    output::newline();
+    output::section("Scaffolded files");
+
+    let mut written = 0;
+    for file in &files {
+        let target = cwd.join(&file.path);
+        if target.exists() && !args.force {
+            warn(&format!(
+                "{} already exists, skipping (use --force to overwrite)",
+                file.path.display()
+            ));
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&target, &file.contents)?;
+        output::list_item(&file.path.display().to_string());
+        written += 1;
+    }
+
+    output::newline();
+    success(&format!("Scaffolded {} files for {}", written, args.model));
+
+    let module = to_snake_case(&args.model);
+    output::newline();
+    output::section("Next steps");
+    output::list_item(&format!(
+        "Declare `pub mod {};` in {}/mod.rs",
+        module,
+        api_dir.display()
+    ));
+    match args.framework {
+        CrudFramework::Axum => output::list_item(&format!(
+            "Merge the routes: `Router::new().merge(api::{}::routes())`",
+            module
+        )),
+        CrudFramework::Actix => output::list_item(&format!(
+            "Register the routes: `App::new().configure(api::{}::configure)`",
+            module
+        )),
+    }
+    output::list_item("Make sure `AppState` exposes the generated client as `db`");
+
+    Ok(())
+}
+
+/// Build the scaffold for a model without touching the filesystem.
+///
+/// `crate_name` is the library crate the generated integration test imports
+/// the scaffolded module from.
+pub fn scaffold_crud(
+    schema: &prax_schema::Schema,
+    model_name: &str,
+    framework: CrudFramework,
+    api_dir: &Path,
+    crate_name: &str,
+) -> CliResult<Vec<ScaffoldFile>> {
+    let model = schema
+        .get_model(model_name)
+        .ok_or_else(|| CliError::Command(format!("Unknown model '{}'", model_name)))?;
+
+    let id_field = model.id_fields().into_iter().next().ok_or_else(|| {
+        CliError::Command(format!(
+            "Model '{}' needs a single @id field to scaffold CRUD handlers",
+            model_name
+        ))
+    })?;
+
+    let module = to_snake_case(model.name());
+    let module_dir = api_dir.join(&module);
+
+    Ok(vec![
+        ScaffoldFile {
+            path: module_dir.join("mod.rs"),
+            contents: generate_router(model, framework),
+        },
+        ScaffoldFile {
+            path: module_dir.join("dto.rs"),
+            contents: generate_dto(model),
+        },
+        ScaffoldFile {
+            path: module_dir.join("handlers.rs"),
+            contents: generate_handlers(model, id_field, framework),
+        },
+        ScaffoldFile {
+            path: PathBuf::from("tests").join(format!("{}_api.rs", module)),
+            contents: generate_test(model, framework, crate_name),
+        },
+    ])
+}
+
+/// Generate router registration for the model
+fn generate_router(model: &Model, framework: CrudFramework) -> String {
+    let name = model.name();
+    let path = format!("/{}", pluralize(&to_snake_case(name)));
+    let mut code = String::new();
+
+    code.push_str(&format!(
+        "//! {} API scaffolded by `prax generate crud {}`.\n\n",
+        name, name
+    ));
+    code.push_str("pub mod dto;\npub mod handlers;\n\n");
+
+    match framework {
+        CrudFramework::Axum => {
+            code.push_str("use axum::Router;\n");
+            code.push_str("use axum::routing::get;\n\n");
+            code.push_str("use crate::AppState;\n\n");
+            code.push_str(&format!("/// Routes for the {} resource\n", name));
+            code.push_str("pub fn routes() -> Router<AppState> {\n");
+            code.push_str("    Router::new()\n");
+            code.push_str(&format!(
+                "        .route(\"{}\", get(handlers::list).post(handlers::create))\n",
+                path
+            ));
+            code.push_str(&format!(
+                "        .route(\n            \"{}/{{id}}\",\n            get(handlers::get).patch(handlers::update).delete(handlers::delete),\n        )\n",
+                path
+            ));
+            code.push_str("}\n");
+        }
+        CrudFramework::Actix => {
+            code.push_str("use actix_web::web;\n\n");
+            code.push_str(&format!("/// Register routes for the {} resource\n", name));
+            code.push_str("pub fn configure(cfg: &mut web::ServiceConfig) {\n");
+            code.push_str(&format!(
+                "    cfg.service(\n        web::scope(\"{}\")\n",
+                path
+            ));
+            code.push_str("            .route(\"\", web::get().to(handlers::list))\n");
+            code.push_str("            .route(\"\", web::post().to(handlers::create))\n");
+            code.push_str("            .route(\"/{id}\", web::get().to(handlers::get))\n");
+            code.push_str("            .route(\"/{id}\", web::patch().to(handlers::update))\n");
+            code.push_str("            .route(\"/{id}\", web::delete().to(handlers::delete)),\n");
+            code.push_str("    );\n");
+            code.push_str("}\n");
+        }
+    }
+
+    code
+}
+
+/// Generate request/response DTOs for the model
+fn generate_dto(model: &Model) -> String {
+    let name = model.name();
+    let scalars: Vec<&Field> = model.fields.values().filter(|f| !f.is_relation()).collect();
+    let mut code = String::new();
+
+    code.push_str(&format!("//! Request and response types for {}.\n\n", name));
+    code.push_str("use serde::{Deserialize, Serialize};\n\n");
+    code.push_str(&format!("use crate::generated::{};\n\n", name));
+
+    // Create input: everything the database doesn't fill in
+    code.push_str(&format!("/// Request body for creating a {}\n", name));
+    code.push_str("#[derive(Debug, Clone, Deserialize, Serialize)]\n");
+    code.push_str("#[serde(rename_all = \"camelCase\")]\n");
+    code.push_str(&format!("pub struct Create{}Input {{\n", name));
+    for field in scalars.iter().filter(|f| !is_generated(f)) {
+        let rust_type = rust_type(field);
+        let ty = if field.has_attribute("default") && !field.is_optional() {
+            format!("Option<{}>", rust_type)
+        } else {
+            rust_type
+        };
+        code.push_str(&format!(
+            "    pub {}: {},\n",
+            to_snake_case(field.name()),
+            ty
+        ));
+    }
+    code.push_str("}\n\n");
+
+    // Update input: every writable field is optional
+    code.push_str(&format!("/// Request body for updating a {}\n", name));
+    code.push_str("#[derive(Debug, Clone, Default, Deserialize, Serialize)]\n");
+    code.push_str("#[serde(rename_all = \"camelCase\")]\n");
+    code.push_str(&format!("pub struct Update{}Input {{\n", name));
+    for field in scalars.iter().filter(|f| !is_generated(f)) {
+        let rust_type = rust_type(field);
+        let ty = if field.is_optional() {
+            rust_type
+        } else {
+            format!("Option<{}>", rust_type)
+        };
+        code.push_str(&format!(
+            "    pub {}: {},\n",
+            to_snake_case(field.name()),
+            ty
+        ));
+    }
+    code.push_str("}\n\n");
+
+    // Response
+    code.push_str(&format!("/// Response body for a {}\n", name));
+    code.push_str("#[derive(Debug, Clone, Deserialize, Serialize)]\n");
+    code.push_str("#[serde(rename_all = \"camelCase\")]\n");
+    code.push_str(&format!("pub struct {}Response {{\n", name));
+    for field in scalars.iter().filter(|f| !f.has_attribute("omit")) {
+        code.push_str(&format!(
+            "    pub {}: {},\n",
+            to_snake_case(field.name()),
+            rust_type(field)
+        ));
+    }
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl From<{}> for {}Response {{\n", name, name));
+    code.push_str(&format!("    fn from(model: {}) -> Self {{\n", name));
+    code.push_str("        Self {\n");
+    for field in scalars.iter().filter(|f| !f.has_attribute("omit")) {
+        let field_name = to_snake_case(field.name());
+        code.push_str(&format!(
+            "            {}: model.{},\n",
+            field_name, field_name
+        ));
+    }
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    // Pagination
+    code.push_str("/// Query parameters for list endpoints\n");
+    code.push_str("#[derive(Debug, Clone, Default, Deserialize)]\n");
+    code.push_str("pub struct ListParams {\n");
+    code.push_str("    pub skip: Option<u64>,\n");
+    code.push_str("    pub take: Option<u64>,\n");
+    code.push_str("}\n");
+
+    code
+}
+
+/// Generate handler functions for the model
+fn generate_handlers(model: &Model, id_field: &Field, framework: CrudFramework) -> String {
+    let name = model.name();
+    let accessor = to_snake_case(name);
+    let id_column = column_name(id_field);
+    let id_type = rust_type(id_field);
+    let writable: Vec<&Field> = model
+        .fields
+        .values()
+        .filter(|f| !f.is_relation() && !is_generated(f))
+        .collect();
+
+    let mut code = String::new();
+    code.push_str(&format!("//! HTTP handlers for {}.\n\n", name));
+
+    match framework {
+        CrudFramework::Axum => {
+            code.push_str("use axum::Json;\n");
+            code.push_str("use axum::extract::{Path, Query, State};\n");
+            code.push_str("use axum::http::StatusCode;\n");
+            code.push_str("use axum::response::{IntoResponse, Response};\n");
+        }
+        CrudFramework::Actix => {
+            code.push_str("use actix_web::{HttpResponse, ResponseError, web};\n");
+        }
+    }
+    code.push_str("use prax_query::filter::Filter;\n");
+    code.push_str("use prax_query::QueryError;\n\n");
+    code.push_str(&format!(
+        "use super::dto::{{Create{n}Input, ListParams, Update{n}Input, {n}Response}};\n",
+        n = name
+    ));
+    code.push_str("use crate::AppState;\n\n");
+
+    // Error type
+    code.push_str("/// Error returned by the handlers in this module\n");
+    code.push_str("#[derive(Debug)]\n");
+    code.push_str("pub struct ApiError(QueryError);\n\n");
+    code.push_str("impl From<QueryError> for ApiError {\n");
+    code.push_str("    fn from(err: QueryError) -> Self {\n");
+    code.push_str("        Self(err)\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    match framework {
+        CrudFramework::Axum => {
+            code.push_str("impl IntoResponse for ApiError {\n");
+            code.push_str("    fn into_response(self) -> Response {\n");
+            code.push_str("        let status = if self.0.is_not_found() {\n");
+            code.push_str("            StatusCode::NOT_FOUND\n");
+            code.push_str("        } else if self.0.is_constraint_violation() {\n");
+            code.push_str("            StatusCode::CONFLICT\n");
+            code.push_str("        } else {\n");
+            code.push_str("            StatusCode::INTERNAL_SERVER_ERROR\n");
+            code.push_str("        };\n");
+            code.push_str("        (status, self.0.to_string()).into_response()\n");
+            code.push_str("    }\n");
+            code.push_str("}\n\n");
+        }
+        CrudFramework::Actix => {
+            code.push_str("impl std::fmt::Display for ApiError {\n");
+            code.push_str(
+                "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n",
+            );
+            code.push_str("        self.0.fmt(f)\n");
+            code.push_str("    }\n");
+            code.push_str("}\n\n");
+            code.push_str("impl ResponseError for ApiError {\n");
+            code.push_str("    fn status_code(&self) -> actix_web::http::StatusCode {\n");
+            code.push_str("        use actix_web::http::StatusCode;\n\n");
+            code.push_str("        if self.0.is_not_found() {\n");
+            code.push_str("            StatusCode::NOT_FOUND\n");
+            code.push_str("        } else if self.0.is_constraint_violation() {\n");
+            code.push_str("            StatusCode::CONFLICT\n");
+            code.push_str("        } else {\n");
+            code.push_str("            StatusCode::INTERNAL_SERVER_ERROR\n");
+            code.push_str("        }\n");
+            code.push_str("    }\n");
+            code.push_str("}\n\n");
+        }
+    }
+
+    let id_filter = format!("Filter::Equals(\"{}\".into(), id.into())", id_column);
+
+    let axum = matches!(framework, CrudFramework::Axum);
+    let state = if axum {
+        "State(state): State<AppState>"
+    } else {
+        "state: web::Data<AppState>"
+    };
+    let id_arg = if axum {
+        format!("Path(id): Path<{}>", id_type)
+    } else {
+        format!("id: web::Path<{}>", id_type)
+    };
+    let params_arg = if axum {
+        "Query(params): Query<ListParams>"
+    } else {
+        "params: web::Query<ListParams>"
+    };
+    let body = |input: &str| {
+        if axum {
+            format!("Json(input): Json<{}>", input)
+        } else {
+            format!("input: web::Json<{}>", input)
+        }
+    };
+    let ok = |value: &str| {
+        if axum {
+            format!("Ok(Json({}))", value)
+        } else {
+            format!("Ok(HttpResponse::Ok().json({}))", value)
+        }
+    };
+    let created = |value: &str| {
+        if axum {
+            format!("Ok((StatusCode::CREATED, Json({})))", value)
+        } else {
+            format!("Ok(HttpResponse::Created().json({}))", value)
+        }
+    };
+    let no_content = if axum {
+        "Ok(StatusCode::NO_CONTENT)"
+    } else {
+        "Ok(HttpResponse::NoContent().finish())"
+    };
+    let result = |ty: &str| {
+        if axum {
+            format!("Result<{}, ApiError>", ty)
+        } else {
+            "Result<HttpResponse, ApiError>".to_string()
+        }
+    };
+    let id_binding = if axum {
+        ""
+    } else {
+        "    let id = id.into_inner();\n"
+    };
+    let input_binding = if axum {
+        ""
+    } else {
+        "    let input = input.into_inner();\n"
+    };
+    let response = format!("{}Response", name);
+
+    // list
+    code.push_str(&format!("/// List {} records\n", name));
+    code.push_str(&format!(
+        "pub async fn list(\n    {},\n    {},\n) -> {} {{\n",
+        state,
+        params_arg,
+        result(&format!("Json<Vec<{}>>", response))
+    ));
+    code.push_str(&format!(
+        "    let records = state\n        .db\n        .{}()\n        .find_many()\n        .skip(params.skip.unwrap_or(0))\n        .take(params.take.unwrap_or(50).min(100))\n        .exec()\n        .await?;\n",
+        accessor
+    ));
+    code.push_str(&format!(
+        "    {}\n}}\n\n",
+        ok(&format!(
+            "records.into_iter().map({}::from).collect::<Vec<_>>()",
+            response
+        ))
+    ));
+
+    // get
+    code.push_str(&format!("/// Fetch a single {} by id\n", name));
+    code.push_str(&format!(
+        "pub async fn get(\n    {},\n    {},\n) -> {} {{\n",
+        state,
+        id_arg,
+        result(&format!("Json<{}>", response))
+    ));
+    code.push_str(id_binding);
+    code.push_str(&format!(
+        "    let record = state\n        .db\n        .{}()\n        .find_unique()\n        .r#where({})\n        .exec()\n        .await?;\n",
+        accessor, id_filter
+    ));
+    code.push_str(&format!(
+        "    {}\n}}\n\n",
+        ok(&format!("{}::from(record)", response))
+    ));
+
+    // create
+    code.push_str(&format!("/// Create a new {}\n", name));
+    code.push_str(&format!(
+        "pub async fn create(\n    {},\n    {},\n) -> {} {{\n",
+        state,
+        body(&format!("Create{}Input", name)),
+        result(&format!("(StatusCode, Json<{}>)", response))
+    ));
+    code.push_str(input_binding);
+    code.push_str(&format!(
+        "    let mut query = state.db.{}().create();\n",
+        accessor
+    ));
+    for field in &writable {
+        let field_name = to_snake_case(field.name());
+        let column = column_name(field);
+        if field.is_optional() || field.has_attribute("default") {
+            code.push_str(&format!(
+                "    if let Some(value) = input.{} {{\n        query = query.set(\"{}\", value);\n    }}\n",
+                field_name, column
+            ));
+        } else {
+            code.push_str(&format!(
+                "    query = query.set(\"{}\", input.{});\n",
+                column, field_name
+            ));
+        }
+    }
+    code.push_str("    let record = query.exec().await?;\n");
+    code.push_str(&format!(
+        "    {}\n}}\n\n",
+        created(&format!("{}::from(record)", response))
+    ));
+
+    // update
+    code.push_str(&format!("/// Update an existing {}\n", name));
+    code.push_str(&format!(
+        "pub async fn update(\n    {},\n    {},\n    {},\n) -> {} {{\n",
+        state,
+        id_arg,
+        body(&format!("Update{}Input", name)),
+        result(&format!("Json<{}>", response))
+    ));
+    code.push_str(id_binding);
+    code.push_str(input_binding);
+    code.push_str(&format!(
+        "    let mut query = state.db.{}().update().r#where({});\n",
+        accessor, id_filter
+    ));
+    for field in &writable {
+        code.push_str(&format!(
+            "    if let Some(value) = input.{} {{\n        query = query.set(\"{}\", value);\n    }}\n",
+            to_snake_case(field.name()),
+            column_name(field)
+        ));
+    }
+    code.push_str("    let record = query.exec_one().await?;\n");
+    code.push_str(&format!(
+        "    {}\n}}\n\n",
+        ok(&format!("{}::from(record)", response))
+    ));
+
+    // delete
+    code.push_str(&format!("/// Delete a {}\n", name));
+    code.push_str(&format!(
+        "pub async fn delete(\n    {},\n    {},\n) -> {} {{\n",
+        state,
+        id_arg,
+        result("StatusCode")
+    ));
+    code.push_str(id_binding);
+    code.push_str(&format!(
+        "    state\n        .db\n        .{}()\n        .delete()\n        .r#where({})\n        .exec()\n        .await?;\n",
+        accessor, id_filter
+    ));
+    code.push_str(&format!("    {}\n}}\n", no_content));
+
+    code
+}
+
+/// Generate an integration test for the scaffolded DTOs and routes
+fn generate_test(model: &Model, framework: CrudFramework, crate_name: &str) -> String {
+    let name = model.name();
+    let module = to_snake_case(name);
+    let path = format!("/{}", pluralize(&module));
+    let mut code = String::new();
+
+    code.push_str(&format!(
+        "//! Tests for the scaffolded {} API.\n//!\n//! These tests cover request parsing and routing without a database.\n//! Add tests against a seeded test database as the handlers evolve.\n\n",
+        name
+    ));
+    code.push_str(&format!(
+        "use {{crate_name}}::api::{}::dto::{{ListParams, Update{}Input}};\n\n",
+        module, name
+    ));
+
+    code.push_str("#[test]\n");
+    code.push_str(&format!(
+        "fn test_{}_update_input_is_partial() {{\n",
+        module
+    ));
+    code.push_str(&format!(
+        "    let input: Update{}Input = serde_json::from_str(\"{{}}\").unwrap();\n",
+        name
+    ));
+    code.push_str("    let _ = input;\n");
+    code.push_str("}\n\n");
+
+    code.push_str("#[test]\n");
+    code.push_str(&format!("fn test_{}_list_params_default() {{\n", module));
+    code.push_str("    let params = ListParams::default();\n");
+    code.push_str("    assert!(params.skip.is_none());\n");
+    code.push_str("    assert!(params.take.is_none());\n");
+    code.push_str("}\n");
+
+    match framework {
+        CrudFramework::Axum => {
+            code.push_str("\n#[tokio::test]\n");
+            code.push_str(&format!("async fn test_{}_routes_build() {{\n", module));
+            code.push_str(&format!(
+                "    // Building the router validates the route paths ({}, {}/{{id}})\n",
+                path, path
+            ));
+            code.push_str(&format!(
+                "    let _router = {{crate_name}}::api::{}::routes();\n",
+                module
+            ));
+            code.push_str("}\n");
+        }
+        CrudFramework::Actix => {
+            code.push_str("\n#[actix_web::test]\n");
+            code.push_str(&format!("async fn test_{}_routes_register() {{\n", module));
+            code.push_str(&format!(
+                "    // Registering the scope validates the route paths ({}, {}/{{id}})\n",
+                path, path
+            ));
+            code.push_str(&format!(
+                "    let _app = actix_web::test::init_service(\n        actix_web::App::new().configure({{crate_name}}::api::{}::configure),\n    )\n    .await;\n",
+                module
+            ));
+            code.push_str("}\n");
+        }
+    }
+
+    code.replace("{crate_name}", crate_name)
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Read the package name from the project's Cargo.toml as a Rust identifier
+fn read_crate_name(cwd: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(cwd.join("Cargo.toml")).ok()?;
+    let manifest: toml::Value = toml::from_str(&content).ok()?;
+    let name = manifest.get("package")?.get("name")?.as_str()?;
+    Some(name.replace('-', "_"))
+}
+
+/// Whether the database fills in this field (ids, timestamps)
+fn is_generated(field: &Field) -> bool {
+    let attrs = field.extract_attributes();
+    (attrs.is_id && (attrs.is_auto || attrs.default.is_some())) || attrs.is_updated_at
+}
+
+fn column_name(field: &Field) -> String {
+    field
+        .extract_attributes()
+        .map
+        .unwrap_or_else(|| field.name().to_string())
+}
+
+fn rust_type(field: &Field) -> String {
+    use prax_schema::ast::{FieldType, ScalarType, TypeModifier};
+
+    let base = match &field.field_type {
+        FieldType::Scalar(scalar) => match scalar {
+            ScalarType::Int => "i32",
+            ScalarType::BigInt => "i64",
+            ScalarType::Float => "f64",
+            ScalarType::Boolean => "bool",
+            ScalarType::DateTime => "chrono::DateTime<chrono::Utc>",
+            ScalarType::Date => "chrono::NaiveDate",
+            ScalarType::Time => "chrono::NaiveTime",
+            ScalarType::Json => "serde_json::Value",
+            ScalarType::Bytes | ScalarType::Bit(_) => "Vec<u8>",
+            ScalarType::Decimal => "rust_decimal::Decimal",
+            ScalarType::Uuid => "uuid::Uuid",
+            ScalarType::Vector(_) | ScalarType::HalfVector(_) => "Vec<f32>",
+            ScalarType::SparseVector(_) => "Vec<(u32, f32)>",
+            _ => "String",
+        }
+        .to_string(),
+        FieldType::Enum(name) | FieldType::Composite(name) | FieldType::Model(name) => {
+            format!("crate::generated::{}", name)
+        }
+        FieldType::Unsupported(_) => "serde_json::Value".to_string(),
+    };
+
+    match field.modifier {
+        TypeModifier::Optional | TypeModifier::OptionalList => format!("Option<{}>", base),
+        TypeModifier::List => format!("Vec<{}>", base),
+        TypeModifier::Required => base,
+    }
+}
+
+/// Naive English pluralization for route paths
+fn pluralize(word: &str) -> String {
+    if word.ends_with('s') || word.ends_with('x') || word.ends_with("ch") || word.ends_with("sh") {
+        format!("{}es", word)
+    } else if word.ends_with('y')
+        && !word.ends_with("ay")
+        && !word.ends_with("ey")
+        && !word.ends_with("oy")
+    {
+        format!("{}ies", &word[..word.len() - 1])
+    } else {
+        format!("{}s", word)
+    }
+}
+
+/// Convert PascalCase to snake_case
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.push(c.to_lowercase().next().unwrap());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> prax_schema::Schema {
+        prax_schema::parse_schema(
+            r#"
+            model BlogPost {
+                id        Int      @id @auto
+                title     String
+                body      String?
+                published Boolean  @default(false)
+                updatedAt DateTime @updated_at
+            }
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_scaffold_file_layout() {
+        let files = scaffold_crud(
+            &schema(),
+            "BlogPost",
+            CrudFramework::Axum,
+            Path::new("src/api"),
+            "my_app",
+        )
+        .unwrap();
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("src/api/blog_post/mod.rs"),
+                PathBuf::from("src/api/blog_post/dto.rs"),
+                PathBuf::from("src/api/blog_post/handlers.rs"),
+                PathBuf::from("tests/blog_post_api.rs"),
+            ]
+        );
+        assert!(files[0].contents.contains("\"/blog_posts\""));
+        assert!(
+            files[3]
+                .contents
+                .contains("use my_app::api::blog_post::dto")
+        );
+    }
+
+    #[test]
+    fn test_dto_skips_generated_fields() {
+        let dto = generate_dto(schema().get_model("BlogPost").unwrap());
+        let create = dto.split("pub struct CreateBlogPostInput").nth(1).unwrap();
+        let create = &create[..create.find('}').unwrap()];
+        assert!(!create.contains("pub id"));
+        assert!(!create.contains("updated_at"));
+        assert!(create.contains("pub published: Option<bool>"));
+        assert!(create.contains("pub body: Option<String>"));
+    }
+
+    #[test]
+    fn test_actix_handlers() {
+        let files = scaffold_crud(
+            &schema(),
+            "BlogPost",
+            CrudFramework::Actix,
+            Path::new("src/api"),
+            "my_app",
+        )
+        .unwrap();
+        assert!(files[0].contents.contains("web::scope(\"/blog_posts\")"));
+        assert!(
+            files[2]
+                .contents
+                .contains("impl ResponseError for ApiError")
+        );
+        assert!(files[2].contents.contains("let id = id.into_inner();"));
+    }
+
+    #[test]
+    fn test_unknown_model() {
+        assert!(
+            scaffold_crud(
+                &schema(),
+                "Nope",
+                CrudFramework::Axum,
+                Path::new("src/api"),
+                "my_app"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_pluralize() {
+        assert_eq!(pluralize("user"), "users");
+        assert_eq!(pluralize("category"), "categories");
+        assert_eq!(pluralize("address"), "addresses");
+        assert_eq!(pluralize("day"), "days");
+    }
+}
@@ -132,6 +132,7 @@ impl SeedRunner {
         let has_bin_target = self.check_bin_target(seed_name)?;
 
         let mut records_affected = 0u64;
+        let mut tables_seeded = Vec::new();
 
         if has_bin_target {
             // Use cargo run directly
@@ -170,6 +171,9 @@ impl SeedRunner {
                 if let Some(count) = parse_seed_output(line) {
                     records_affected += count;
                 }
+                if let Some(table) = parse_seeded_table(line) {
+                    tables_seeded.push(table);
+                }
             }
 
             output::step(4, 4, "Verifying seed data...");
@@ -244,6 +248,9 @@ impl SeedRunner {
                 if let Some(count) = parse_seed_output(line) {
                     records_affected += count;
                 }
+                if let Some(table) = parse_seeded_table(line) {
+                    tables_seeded.push(table);
+                }
             }
 
             output::step(4, 4, "Verifying seed data...");
@@ -252,7 +259,7 @@ impl SeedRunner {
         Ok(SeedResult {
             file_type: self.file_type,
             records_affected,
-            tables_seeded: Vec::new(),
+            tables_seeded,
             duration: std::time::Duration::from_secs(0),
         })
     }
@@ -672,7 +679,7 @@ pub fn get_database_url(config: &Config) -> CliResult<String> {
 }
 
 /// Expand environment variables in a string
-fn expand_env_var(s: &str) -> String {
+pub(crate) fn expand_env_var(s: &str) -> String {
     let mut result = s.to_string();
 
     // Match ${VAR} pattern
@@ -725,6 +732,15 @@ fn parse_seed_output(line: &str) -> Option<u64> {
     None
 }
 
+/// Parse the seeded table from a `prax_query::seed::SeedRunner` line
+/// ("Seeded 10 records into users")
+fn parse_seeded_table(line: &str) -> Option<String> {
+    let re = regex_lite::Regex::new(r"^Seeded\s+\d+\s+records?\s+into\s+(\S+)").ok()?;
+    re.captures(line.trim())
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
 /// Parse affected rows from database output
 fn parse_affected_rows(output: &str) -> Option<u64> {
     // PostgreSQL: "INSERT 0 5" or "UPDATE 3"
@@ -833,6 +849,16 @@ mod tests {
         assert_eq!(parse_seed_output("Inserted: 50"), Some(50));
         assert_eq!(parse_seed_output("5 rows affected"), Some(5));
         assert_eq!(parse_seed_output("no numbers here"), None);
+        assert_eq!(parse_seed_output("Seeded 3 records into users"), Some(3));
+    }
+
+    #[test]
+    fn test_parse_seeded_table() {
+        assert_eq!(
+            parse_seeded_table("Seeded 3 records into users"),
+            Some("users".to_string())
+        );
+        assert_eq!(parse_seeded_table("Created 10 users"), None);
     }
 
     #[test]
@@ -1,10 +1,46 @@
 //! `prax validate` command - Validate Prax schema file.
 
-use crate::cli::ValidateArgs;
+use serde::Serialize;
+
+use crate::cli::{DiagramFormat, ValidateArgs};
 use crate::config::SCHEMA_FILE_NAME;
 use crate::error::{CliError, CliResult};
 use crate::output::{self, success, warn};
 
+/// Machine-readable result of `prax validate`
+#[derive(Debug, Serialize)]
+pub struct ValidateReport {
+    /// Path to the validated schema
+    pub schema: String,
+    /// Whether the schema passed validation
+    pub valid: bool,
+    /// Validation errors
+    pub errors: Vec<String>,
+    /// Non-fatal warnings
+    pub warnings: Vec<String>,
+    /// Schema statistics (present when the schema is valid)
+    pub summary: Option<SchemaSummary>,
+    /// Path of the written ER diagram, if requested
+    pub diagram: Option<String>,
+}
+
+/// Schema statistics reported by `prax validate`
+#[derive(Debug, Serialize)]
+pub struct SchemaSummary {
+    /// Number of models
+    pub models: usize,
+    /// Number of enums
+    pub enums: usize,
+    /// Number of views
+    pub views: usize,
+    /// Number of composite types
+    pub composites: usize,
+    /// Total number of model fields
+    pub fields: usize,
+    /// Number of relation fields
+    pub relations: usize,
+}
+
 /// Run the validate command
 pub async fn run(args: ValidateArgs) -> CliResult<()> {
     output::header("Validate Schema");
@@ -50,6 +86,14 @@ pub async fn run(args: ValidateArgs) -> CliResult<()> {
             }
         }
         Err(errors) => {
+            output::report(&ValidateReport {
+                schema: schema_path.display().to_string(),
+                valid: false,
+                errors: errors.clone(),
+                warnings: config_warnings.clone(),
+                summary: None,
+                diagram: None,
+            });
             output::error("Schema validation failed!");
             output::newline();
             output::section("Errors");
@@ -90,6 +134,43 @@ pub async fn run(args: ValidateArgs) -> CliResult<()> {
     output::kv("Total Fields", &total_fields.to_string());
     output::kv("Relations", &relations.to_string());
 
+    let diagram = match &args.erd {
+        Some(path) => {
+            let format = args
+                .erd_format
+                .unwrap_or_else(|| DiagramFormat::from_path(path));
+            let contents = match format {
+                DiagramFormat::Mermaid => schema.to_mermaid_er(),
+                DiagramFormat::Dot => schema.to_dot(),
+            };
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, contents)?;
+
+            output::newline();
+            success(&format!("ER diagram written to {}", path.display()));
+            Some(path.display().to_string())
+        }
+        None => None,
+    };
+
+    output::report(&ValidateReport {
+        schema: schema_path.display().to_string(),
+        valid: true,
+        errors: Vec::new(),
+        warnings: config_warnings,
+        summary: Some(SchemaSummary {
+            models: schema.models.len(),
+            enums: schema.enums.len(),
+            views: schema.views.len(),
+            composites: schema.types.len(),
+            fields: total_fields,
+            relations,
+        }),
+        diagram,
+    });
+
     Ok(())
 }
 
@@ -1,5 +1,7 @@
 //! `prax version` command - Display version information.
 
+use serde::Serialize;
+
 use crate::error::CliResult;
 use crate::output::{self, kv};
 
@@ -9,6 +11,19 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 /// Package name
 const NAME: &str = env!("CARGO_PKG_NAME");
 
+/// Machine-readable result of `prax version`
+#[derive(Debug, Serialize)]
+pub struct VersionReport {
+    /// CLI version
+    pub version: &'static str,
+    /// Binary package name
+    pub binary: &'static str,
+    /// Build mode (`debug` or `release`)
+    pub build: &'static str,
+    /// Enabled database features
+    pub features: Vec<&'static str>,
+}
+
 /// Run the version command
 pub async fn run() -> CliResult<()> {
     output::logo();
@@ -43,6 +58,13 @@ pub async fn run() -> CliResult<()> {
 
     kv("Features", &features.join(", "));
 
+    output::report(&VersionReport {
+        version: VERSION,
+        binary: NAME,
+        build: build_mode,
+        features: features.clone(),
+    });
+
     output::newline();
 
     // Additional info
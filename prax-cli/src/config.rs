@@ -1,9 +1,12 @@
 //! CLI configuration handling.
 
+use prax_plugin_api::ExternalGeneratorConfig;
+pub use prax_schema::config::{ClientGeneratorConfig, OutputSplit};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
-use crate::error::CliResult;
+use crate::error::{CliError, CliResult};
 
 /// Default config file name (lives in project root)
 pub const CONFIG_FILE_NAME: &str = "prax.toml";
@@ -35,6 +38,10 @@ pub struct Config {
 
     /// Seed configuration
     pub seed: SeedConfig,
+
+    /// Additional named datasources (`[datasources.<name>]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub datasources: BTreeMap<String, DatasourceConfig>,
 }
 
 impl Default for Config {
@@ -44,6 +51,7 @@ impl Default for Config {
             generator: GeneratorConfig::default(),
             migrations: MigrationConfig::default(),
             seed: SeedConfig::default(),
+            datasources: BTreeMap::new(),
         }
     }
 }
@@ -103,6 +111,16 @@ impl Default for DatabaseConfig {
     }
 }
 
+/// A named datasource, used by `prax db copy`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasourceConfig {
+    /// Database provider (postgresql, mysql, sqlite, duckdb)
+    pub provider: String,
+
+    /// Connection URL or file path (supports `${VAR}`)
+    pub url: String,
+}
+
 /// Generator configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -115,6 +133,13 @@ pub struct GeneratorConfig {
 
     /// Custom prelude imports
     pub prelude: Option<Vec<String>>,
+
+    /// Client options (`[generator.client]`), also read by `prax_schema!`
+    pub client: ClientGeneratorConfig,
+
+    /// External generator plugins (`[generator.plugins.<name>]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub plugins: BTreeMap<String, ExternalGeneratorConfig>,
 }
 
 impl Default for GeneratorConfig {
@@ -123,6 +148,8 @@ impl Default for GeneratorConfig {
             output: "./src/generated".to_string(),
             features: None,
             prelude: None,
+            client: ClientGeneratorConfig::default(),
+            plugins: BTreeMap::new(),
         }
     }
 }
@@ -139,6 +166,13 @@ pub struct MigrationConfig {
 
     /// Schema for migration table (PostgreSQL only)
     pub schema: Option<String>,
+
+    /// Signature verification for `migrate deploy` (`[migrations.signatures]`)
+    pub signatures: SignatureConfig,
+
+    /// Per-environment policies (`[migrations.environments.<name>]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, EnvironmentConfig>,
 }
 
 impl Default for MigrationConfig {
@@ -147,7 +181,81 @@ impl Default for MigrationConfig {
             directory: MIGRATIONS_DIR.to_string(),
             table_name: "_prax_migrations".to_string(),
             schema: None,
+            signatures: SignatureConfig::default(),
+            environments: BTreeMap::new(),
+        }
+    }
+}
+
+impl MigrationConfig {
+    /// The policy migrations are applied under in an environment
+    pub fn environment_policy(
+        &self,
+        environment: &str,
+    ) -> CliResult<prax_migrate::EnvironmentPolicy> {
+        let mut policy = prax_migrate::EnvironmentPolicy::new(environment);
+        let Some(config) = self.environments.get(environment) else {
+            return Ok(policy);
+        };
+
+        if let Some(require) = config.require_approval {
+            policy = policy.require_approval(require);
+        }
+        if let Some(allow) = config.allow_destructive {
+            policy = policy.allow_destructive(allow);
+        }
+        if let Some(path) = &config.approvals_file {
+            let approvals = prax_migrate::environment::load_approvals(path)
+                .map_err(|e| CliError::Config(e.to_string()))?;
+            policy = policy.approvals(approvals);
         }
+
+        Ok(policy)
+    }
+}
+
+/// Migration policy for one environment
+///
+/// Unset options keep the environment's default: production requires
+/// approval and refuses destructive migrations, other environments don't.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentConfig {
+    /// Require every migration to be approved
+    pub require_approval: Option<bool>,
+
+    /// Allow migrations that drop or truncate data
+    pub allow_destructive: Option<bool>,
+
+    /// File listing approved migrations (`[[approval]]` entries)
+    pub approvals_file: Option<PathBuf>,
+}
+
+/// Migration signature configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignatureConfig {
+    /// Reject migrations without a signature from a trusted key
+    pub required: bool,
+
+    /// Trusted minisign public keys
+    pub minisign_keys: Vec<String>,
+
+    /// Fingerprints of trusted GPG keys
+    pub gpg_fingerprints: Vec<String>,
+
+    /// GPG home directory holding the trusted keys
+    pub gpg_home: Option<PathBuf>,
+}
+
+impl SignatureConfig {
+    /// The policy migrations are verified against
+    pub fn policy(&self) -> prax_migrate::SignaturePolicy {
+        let mut policy = prax_migrate::SignaturePolicy::new().required(self.required);
+        policy.minisign_keys = self.minisign_keys.clone();
+        policy.gpg_fingerprints = self.gpg_fingerprints.clone();
+        policy.gpg_home = self.gpg_home.clone();
+        policy
     }
 }
 
@@ -9,26 +9,42 @@ use prax_cli::output;
 
 #[tokio::main]
 async fn main() {
+    // Parse CLI arguments
+    let cli = Cli::parse();
+    let command_name = cli.command.name();
+
+    // Completion scripts are always raw shell output
+    let json = cli.json && !matches!(cli.command, Command::Completions(_));
+    output::set_json_mode(json);
+
     // Run the CLI and handle errors
-    if let Err(e) = run().await {
+    let result = run(cli.command).await;
+
+    if json {
+        output::json_envelope(command_name, result.as_ref().err());
+    } else if let Err(ref e) = result {
         output::newline();
         output::error(&e.to_string());
+    }
+
+    if result.is_err() {
         std::process::exit(1);
     }
 }
 
-async fn run() -> CliResult<()> {
-    // Parse CLI arguments
-    let cli = Cli::parse();
-
+async fn run(command: Command) -> CliResult<()> {
     // Run the appropriate command
-    match cli.command {
+    match command {
         Command::Init(args) => commands::init::run(args).await,
         Command::Generate(args) => commands::generate::run(args).await,
         Command::Validate(args) => commands::validate::run(args).await,
         Command::Format(args) => commands::format::run(args).await,
+        Command::Convert(args) => commands::convert::run(args).await,
         Command::Migrate(args) => commands::migrate::run(args).await,
         Command::Db(args) => commands::db::run(args).await,
+        Command::Console(args) => commands::console::run(args).await,
+        Command::AnalyzeLog(args) => commands::analyze_log::run(args).await,
+        Command::Completions(args) => commands::completions::run(args).await,
         Command::Version => commands::version::run().await,
     }
 }
@@ -1,9 +1,103 @@
 //! Styled terminal output utilities.
+//!
+//! When JSON output is enabled with `--json`, the human-readable helpers in
+//! this module print nothing. Commands record a machine-readable report with
+//! [`report`] instead, and a single [`JsonEnvelope`] is written to stdout when
+//! the command finishes.
 
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use miette::Diagnostic;
 use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::error::CliError;
+
+/// Version of the JSON output envelope. Bumped on breaking changes only.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+static JSON_MODE: AtomicBool = AtomicBool::new(false);
+static JSON_REPORT: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+
+/// Enable or disable JSON output mode
+pub fn set_json_mode(enabled: bool) {
+    JSON_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Check whether JSON output mode is enabled
+pub fn is_json_mode() -> bool {
+    JSON_MODE.load(Ordering::Relaxed)
+}
+
+/// Record the machine-readable result of the current command
+///
+/// Does nothing unless JSON output mode is enabled.
+pub fn report<T: Serialize>(data: &T) {
+    if !is_json_mode() {
+        return;
+    }
+    let value = serde_json::to_value(data).unwrap_or(serde_json::Value::Null);
+    *JSON_REPORT.lock().unwrap_or_else(|e| e.into_inner()) = Some(value);
+}
+
+/// JSON document written to stdout in `--json` mode
+#[derive(Debug, Serialize)]
+pub struct JsonEnvelope {
+    /// Envelope schema version
+    pub version: u32,
+    /// Command that produced the output (e.g. `migrate status`)
+    pub command: String,
+    /// Whether the command succeeded
+    pub success: bool,
+    /// Command-specific report, or `null`
+    pub data: serde_json::Value,
+    /// Error details when the command failed
+    pub error: Option<JsonError>,
+}
+
+/// Error details in a [`JsonEnvelope`]
+#[derive(Debug, Serialize)]
+pub struct JsonError {
+    /// Stable error code (e.g. `prax::validation`)
+    pub code: String,
+    /// Human-readable message
+    pub message: String,
+}
+
+/// Print the JSON envelope for a finished command
+pub fn json_envelope(command: &str, error: Option<&CliError>) {
+    let data = JSON_REPORT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .unwrap_or(serde_json::Value::Null);
+
+    let envelope = JsonEnvelope {
+        version: JSON_SCHEMA_VERSION,
+        command: command.to_string(),
+        success: error.is_none(),
+        data,
+        error: error.map(|e| JsonError {
+            code: e
+                .code()
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "prax::unknown".to_string()),
+            message: e.to_string(),
+        }),
+    };
+
+    match serde_json::to_string_pretty(&envelope) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize JSON output: {}", e),
+    }
+}
 
 /// Print a header/title
 pub fn header(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!();
     println!("{}", text.bold().cyan());
     println!("{}", "─".repeat(text.len()).dimmed());
@@ -12,6 +106,9 @@ pub fn header(text: &str) {
 
 /// Print the Prax logo
 pub fn logo() {
+    if is_json_mode() {
+        return;
+    }
     let logo = r#"
     ██████╗ ██████╗  █████╗ ██╗  ██╗
     ██╔══██╗██╔══██╗██╔══██╗╚██╗██╔╝
@@ -25,66 +122,113 @@ pub fn logo() {
 
 /// Print a section header
 pub fn section(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("{}", text.bold().white());
 }
 
 /// Print a key-value pair
 pub fn kv(key: &str, value: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("  {}: {}", key.dimmed(), value);
 }
 
 /// Print a success message
 pub fn success(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("{} {}", "✔".green().bold(), text.green());
 }
 
 /// Print an info message
 pub fn info(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("{} {}", "ℹ".blue().bold(), text);
 }
 
 /// Print a warning message
 pub fn warn(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("{} {}", "⚠".yellow().bold(), text.yellow());
 }
 
+/// Print a warning message to stderr, leaving stdout to piped output
+pub fn warn_stderr(text: &str) {
+    if is_json_mode() {
+        return;
+    }
+    eprintln!("{} {}", "⚠".yellow().bold(), text.yellow());
+}
+
 /// Print an error message
 pub fn error(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     eprintln!("{} {}", "✖".red().bold(), text.red());
 }
 
 /// Print a step indicator
 pub fn step(current: usize, total: usize, text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("{} {}", format!("[{}/{}]", current, total).dimmed(), text);
 }
 
 /// Print a list header
 pub fn list(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("{}", text);
 }
 
 /// Print a list item
 pub fn list_item(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("  {} {}", "•".dimmed(), text);
 }
 
 /// Print a numbered list item
 pub fn numbered_item(number: usize, text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("  {}. {}", number.to_string().dimmed(), text);
 }
 
 /// Print a newline
 pub fn newline() {
+    if is_json_mode() {
+        return;
+    }
     println!();
 }
 
 /// Print dimmed text
 pub fn dim(text: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!("{}", text.dimmed());
 }
 
 /// Print code block with syntax highlighting hint
 pub fn code(code: &str, _language: &str) {
+    if is_json_mode() {
+        return;
+    }
     println!();
     for line in code.lines() {
         println!("  {}", line.bright_white());
@@ -92,6 +236,49 @@ pub fn code(code: &str, _language: &str) {
     println!();
 }
 
+/// Print rows as an aligned table
+pub fn table(headers: &[String], rows: &[Vec<String>]) {
+    if is_json_mode() {
+        return;
+    }
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            if let Some(width) = widths.get_mut(i) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    let pad =
+        |text: &str, width: usize| format!("{}{}", text, " ".repeat(width - text.chars().count()));
+
+    let header_line = headers
+        .iter()
+        .zip(&widths)
+        .map(|(h, &w)| pad(h, w))
+        .collect::<Vec<_>>()
+        .join(" │ ");
+    println!(" {}", header_line.bold());
+
+    let separator = widths
+        .iter()
+        .map(|&w| "─".repeat(w))
+        .collect::<Vec<_>>()
+        .join("─┼─");
+    println!(" {}", separator.dimmed());
+
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &w)| pad(cell, w))
+            .collect::<Vec<_>>()
+            .join(" │ ");
+        println!(" {}", line);
+    }
+}
+
 /// Style text as success (green)
 pub fn style_success(text: &str) -> String {
     text.green().to_string()
@@ -111,8 +298,9 @@ pub fn style_error(text: &str) -> String {
 pub fn confirm(prompt: &str) -> bool {
     use std::io::{self, Write};
 
-    print!("{} {} ", prompt, "[y/N]".dimmed());
-    io::stdout().flush().ok();
+    let mut out = prompt_writer();
+    write!(out, "{} {} ", prompt, "[y/N]".dimmed()).ok();
+    out.flush().ok();
 
     let mut input = String::new();
     if io::stdin().read_line(&mut input).is_err() {
@@ -126,8 +314,9 @@ pub fn confirm(prompt: &str) -> bool {
 pub fn input(prompt: &str) -> Option<String> {
     use std::io::{self, Write};
 
-    print!("{}: ", prompt);
-    io::stdout().flush().ok();
+    let mut out = prompt_writer();
+    write!(out, "{}: ", prompt).ok();
+    out.flush().ok();
 
     let mut input = String::new();
     if io::stdin().read_line(&mut input).is_err() {
@@ -146,13 +335,14 @@ pub fn input(prompt: &str) -> Option<String> {
 pub fn select(prompt: &str, options: &[&str]) -> Option<usize> {
     use std::io::{self, Write};
 
-    println!("{}", prompt);
+    let mut out = prompt_writer();
+    writeln!(out, "{}", prompt).ok();
     for (i, option) in options.iter().enumerate() {
-        println!("  {} {}", format!("{})", i + 1).dimmed(), option);
+        writeln!(out, "  {} {}", format!("{})", i + 1).dimmed(), option).ok();
     }
 
-    print!("{}: ", "Select".dimmed());
-    io::stdout().flush().ok();
+    write!(out, "{}: ", "Select".dimmed()).ok();
+    out.flush().ok();
 
     let mut input = String::new();
     if io::stdin().read_line(&mut input).is_err() {
@@ -166,3 +356,12 @@ pub fn select(prompt: &str, options: &[&str]) -> Option<usize> {
         .filter(|&n| n > 0 && n <= options.len())
         .map(|n| n - 1)
 }
+
+/// Prompts go to stderr in JSON mode so stdout stays machine-readable
+fn prompt_writer() -> Box<dyn std::io::Write> {
+    if is_json_mode() {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(std::io::stdout())
+    }
+}
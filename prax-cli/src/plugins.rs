@@ -0,0 +1,233 @@
+//! External generator plugins configured under `[generator.plugins]`.
+//!
+//! Each entry names a generator binary that `prax generate` runs after the
+//! built-in client generator. Binaries are resolved from the entry's
+//! `command`, or discovered on `PATH` as `prax-gen-<name>`. The exchange
+//! follows the protocol in [`prax_plugin_api::external`].
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use prax_plugin_api::external::{MANIFEST_FLAG, generator_binary_name};
+use prax_plugin_api::{
+    API_VERSION, ExternalGeneratorConfig, GeneratorManifest, GeneratorRequest, GeneratorResponse,
+    is_compatible,
+};
+
+use crate::error::{CliError, CliResult};
+
+/// A resolved external generator.
+#[derive(Debug, Clone)]
+pub struct ExternalGenerator {
+    /// Name from `[generator.plugins.<name>]`.
+    pub name: String,
+    /// Executable to run.
+    pub command: PathBuf,
+    /// Configuration entry.
+    pub config: ExternalGeneratorConfig,
+}
+
+impl ExternalGenerator {
+    /// Resolve the executable for a configured generator.
+    pub fn discover(name: &str, config: &ExternalGeneratorConfig, cwd: &Path) -> CliResult<Self> {
+        let command = match &config.command {
+            // Paths are relative to the project; bare names are looked up on PATH
+            Some(command) if command.contains('/') || command.contains('\\') => {
+                Some(cwd.join(command)).filter(|p| p.is_file())
+            }
+            Some(command) => find_in_path(command),
+            None => find_in_path(&generator_binary_name(name)),
+        };
+
+        let command = command.ok_or_else(|| {
+            CliError::Codegen(format!(
+                "Generator plugin '{}' not found (looked for '{}')",
+                name,
+                config
+                    .command
+                    .clone()
+                    .unwrap_or_else(|| generator_binary_name(name))
+            ))
+        })?;
+
+        Ok(Self {
+            name: name.to_string(),
+            command,
+            config: config.clone(),
+        })
+    }
+
+    /// Output directory for this generator.
+    pub fn output_dir(&self, cwd: &Path, client_output: &Path) -> PathBuf {
+        match &self.config.output {
+            Some(output) => cwd.join(output),
+            None => client_output.join(&self.name),
+        }
+    }
+
+    /// Ask the generator for its manifest and check API compatibility.
+    pub fn manifest(&self) -> CliResult<GeneratorManifest> {
+        let output = Command::new(&self.command)
+            .args(&self.config.args)
+            .arg(MANIFEST_FLAG)
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|e| self.error(format!("failed to start: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(self.error(format!("--manifest exited with {}", output.status)));
+        }
+
+        let manifest: GeneratorManifest = serde_json::from_slice(&output.stdout)
+            .map_err(|e| self.error(format!("invalid manifest: {}", e)))?;
+        self.check_version(manifest.api_version)?;
+
+        Ok(manifest)
+    }
+
+    /// Run the generator with a request and return its response.
+    pub fn run(&self, request: &GeneratorRequest) -> CliResult<GeneratorResponse> {
+        let mut child = Command::new(&self.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| self.error(format!("failed to start: {}", e)))?;
+
+        let input = serde_json::to_vec(request)
+            .map_err(|e| self.error(format!("request could not be encoded: {}", e)))?;
+        // A generator that exits early closes stdin; report its exit status instead
+        let written = match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(&input),
+            None => Ok(()),
+        };
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(self.error(format!("exited with {}", output.status)));
+        }
+        written?;
+
+        let response: GeneratorResponse = serde_json::from_slice(&output.stdout)
+            .map_err(|e| self.error(format!("invalid response: {}", e)))?;
+        self.check_version(response.api_version)?;
+
+        Ok(response)
+    }
+
+    fn check_version(&self, version: u32) -> CliResult<()> {
+        if is_compatible(version) {
+            Ok(())
+        } else {
+            Err(self.error(format!(
+                "speaks plugin API version {}, expected {}",
+                version, API_VERSION
+            )))
+        }
+    }
+
+    fn error(&self, message: String) -> CliError {
+        CliError::Codegen(format!("Generator plugin '{}' {}", self.name, message))
+    }
+}
+
+/// Write a generator's files below its output directory.
+pub fn write_files(
+    generator: &str,
+    response: &GeneratorResponse,
+    output_dir: &Path,
+) -> CliResult<Vec<PathBuf>> {
+    let mut written = Vec::new();
+
+    for file in &response.files {
+        if !file.is_contained() {
+            return Err(CliError::Codegen(format!(
+                "Generator plugin '{}' tried to write outside its output directory: {}",
+                generator,
+                file.path.display()
+            )));
+        }
+
+        let path = output_dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &file.contents)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Find an executable on `PATH`.
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        let candidate = dir.join(name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let exe = dir.join(format!("{}.exe", name));
+        exe.is_file().then_some(exe)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prax_plugin_api::GeneratedFile;
+
+    #[test]
+    fn test_discover_missing_generator() {
+        let err = ExternalGenerator::discover(
+            "definitely-not-installed",
+            &ExternalGeneratorConfig::default(),
+            Path::new("."),
+        )
+        .unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("prax-gen-definitely-not-installed")
+        );
+    }
+
+    #[test]
+    fn test_output_dir() {
+        let mut generator = ExternalGenerator {
+            name: "openapi".to_string(),
+            command: PathBuf::from("prax-gen-openapi"),
+            config: ExternalGeneratorConfig::default(),
+        };
+        let cwd = Path::new("/project");
+        let client = Path::new("/project/src/generated");
+
+        assert_eq!(
+            generator.output_dir(cwd, client),
+            PathBuf::from("/project/src/generated/openapi")
+        );
+
+        generator.config.output = Some("docs/api".to_string());
+        assert_eq!(
+            generator.output_dir(cwd, client),
+            PathBuf::from("/project/docs/api")
+        );
+    }
+
+    #[test]
+    fn test_write_files_rejects_escaping_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let response = GeneratorResponse {
+            files: vec![GeneratedFile {
+                path: PathBuf::from("../escape.txt"),
+                contents: String::new(),
+            }],
+            ..GeneratorResponse::new()
+        };
+
+        assert!(write_files("bad", &response, dir.path()).is_err());
+        assert!(!dir.path().parent().unwrap().join("escape.txt").exists());
+    }
+}
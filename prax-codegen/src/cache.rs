@@ -0,0 +1,188 @@
+//! On-disk cache for `prax_schema!` output.
+//!
+//! Generating code for a large schema is the dominant cost of expanding
+//! `prax_schema!`. The generated tokens only depend on the schema source,
+//! the prax.toml configuration, the plugin configuration and the version of
+//! this crate, so the output is cached on disk under a hash of those inputs
+//! and reused by later expansions.
+//!
+//! The cache lives in `<target>/prax-codegen/`. It can be relocated with
+//! `PRAX_CODEGEN_CACHE_DIR` or disabled with `PRAX_CODEGEN_CACHE=0`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use proc_macro2::TokenStream;
+
+/// Environment variable to disable the cache.
+pub const CACHE_ENABLED_VAR: &str = "PRAX_CODEGEN_CACHE";
+
+/// Environment variable overriding the cache directory.
+pub const CACHE_DIR_VAR: &str = "PRAX_CODEGEN_CACHE_DIR";
+
+/// Cache key for one expansion of `prax_schema!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheKey(u64);
+
+impl CacheKey {
+    /// Compute the key from every input that affects generated code.
+    pub fn new(schema: &str, config: Option<&str>, plugin_fingerprint: &str) -> Self {
+        let mut hasher = Fnv64::new();
+        hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.write(&[0]);
+        hasher.write(schema.as_bytes());
+        hasher.write(&[0]);
+        hasher.write(config.unwrap_or_default().as_bytes());
+        hasher.write(&[0]);
+        hasher.write(plugin_fingerprint.as_bytes());
+        Self(hasher.finish())
+    }
+
+    /// File name of the cache entry.
+    fn file_name(&self) -> String {
+        format!("{:016x}.rs", self.0)
+    }
+}
+
+/// Directory-backed cache of generated token streams.
+#[derive(Debug, Clone)]
+pub struct CodegenCache {
+    dir: PathBuf,
+}
+
+impl CodegenCache {
+    /// Create a cache rooted at the given directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Locate the cache for the crate currently being compiled.
+    ///
+    /// Returns `None` when caching is disabled or no suitable directory exists.
+    pub fn from_env() -> Option<Self> {
+        if let Ok(val) = env::var(CACHE_ENABLED_VAR) {
+            if matches!(val.to_lowercase().as_str(), "0" | "false" | "no" | "off") {
+                return None;
+            }
+        }
+
+        if let Ok(dir) = env::var(CACHE_DIR_VAR) {
+            return Some(Self::new(dir));
+        }
+
+        let target_dir = match env::var("CARGO_TARGET_DIR") {
+            Ok(dir) => PathBuf::from(dir),
+            Err(_) => find_target_dir(Path::new(&env::var("CARGO_MANIFEST_DIR").ok()?))?,
+        };
+
+        Some(Self::new(target_dir.join("prax-codegen")))
+    }
+
+    /// Load cached tokens for a key, if present and readable.
+    pub fn load(&self, key: CacheKey) -> Option<TokenStream> {
+        let content = std::fs::read_to_string(self.dir.join(key.file_name())).ok()?;
+        TokenStream::from_str(&content).ok()
+    }
+
+    /// Store tokens for a key.
+    ///
+    /// Failures are ignored; the cache is an optimization only.
+    pub fn store(&self, key: CacheKey, tokens: &TokenStream) {
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+
+        // Write then rename so concurrent rustc processes never read a partial entry
+        let path = self.dir.join(key.file_name());
+        let tmp = self
+            .dir
+            .join(format!("{}.{}.tmp", key.file_name(), std::process::id()));
+        if std::fs::write(&tmp, tokens.to_string()).is_ok() && std::fs::rename(&tmp, &path).is_err()
+        {
+            let _ = std::fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// Find the nearest `target` directory at or above the manifest directory.
+///
+/// Workspace members share the workspace root's target directory.
+fn find_target_dir(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("target"))
+        .find(|dir| dir.is_dir())
+        .or_else(|| Some(manifest_dir.join("target")))
+}
+
+/// 64-bit FNV-1a hasher.
+///
+/// Unlike `DefaultHasher`, the output is stable across Rust releases, which
+/// keeps cache entries valid after a toolchain upgrade.
+struct Fnv64(u64);
+
+impl Fnv64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn test_fnv_known_values() {
+        let mut hasher = Fnv64::new();
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_inputs() {
+        let base = CacheKey::new("model User {}", None, "all=false");
+
+        assert_eq!(base, CacheKey::new("model User {}", None, "all=false"));
+        assert_ne!(base, CacheKey::new("model Post {}", None, "all=false"));
+        assert_ne!(
+            base,
+            CacheKey::new("model User {}", Some("[generator]"), "all=false")
+        );
+        assert_ne!(base, CacheKey::new("model User {}", None, "all=true"));
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CodegenCache::new(dir.path().join("cache"));
+        let key = CacheKey::new("model User {}", None, "");
+
+        assert!(cache.load(key).is_none());
+
+        let tokens = quote! {
+            /// A user.
+            pub struct User { pub id: i32 }
+        };
+        cache.store(key, &tokens);
+
+        let loaded = cache.load(key).expect("cache hit");
+        assert_eq!(loaded.to_string(), tokens.to_string());
+    }
+}
@@ -0,0 +1,275 @@
+//! Code generation for the `PraxClient` entry point.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use prax_schema::ast::{PolicyCommand, Schema};
+
+use super::{pascal_ident, snake_ident};
+
+/// Generate `PraxClient`, with an accessor per model and interactive transactions.
+pub fn generate_client(schema: &Schema) -> TokenStream {
+    let accessors: Vec<_> = schema
+        .models
+        .values()
+        .map(|model| {
+            let method = snake_ident(model.name());
+            let model_name = pascal_ident(model.name());
+            let doc = format!(" Query the `{}` model.", model.name());
+
+            quote! {
+                #[doc = #doc]
+                pub fn #method(&self) -> prax_query::QueryBuilder<E, #method::#model_name> {
+                    prax_query::QueryBuilder::new(self.engine.clone())
+                }
+            }
+        })
+        .collect();
+
+    let tables: Vec<_> = schema
+        .models
+        .values()
+        .map(|model| {
+            let module = snake_ident(model.name());
+            let model_name = pascal_ident(model.name());
+            quote! { prax_query::verify::TableSpec::of::<#module::#model_name>() }
+        })
+        .collect();
+
+    let policies: Vec<_> = schema
+        .policies
+        .iter()
+        .flat_map(|policy| {
+            let name = policy.name();
+            let table = schema.get_model(policy.table()).map_or_else(
+                || policy.table().to_string(),
+                |m| m.table_name().to_string(),
+            );
+            let roles = policy.effective_roles();
+            let using = policy.using_expr.as_deref().map(|e| quote! { .using(#e) });
+            let check = policy
+                .check_expr
+                .as_deref()
+                .map(|e| quote! { .with_check(#e) });
+            let restrictive = policy.is_restrictive().then(|| quote! { .restrictive() });
+            let commands = if policy.commands.is_empty() {
+                vec![PolicyCommand::All]
+            } else {
+                policy.commands.clone()
+            };
+
+            commands.into_iter().map(move |command| {
+                let command = match command {
+                    PolicyCommand::All => quote! { All },
+                    PolicyCommand::Select => quote! { Select },
+                    PolicyCommand::Insert => quote! { Insert },
+                    PolicyCommand::Update => quote! { Update },
+                    PolicyCommand::Delete => quote! { Delete },
+                };
+                quote! {
+                    prax_query::security::RlsPolicy::new(#name, #table)
+                        .for_command(prax_query::security::PolicyCommand::#command)
+                        .to_roles([#(#roles),*])
+                        #using
+                        #check
+                        #restrictive
+                        .build()
+                }
+            })
+        })
+        .collect();
+
+    quote! {
+        /// The schema's row-level security policies, one per command.
+        ///
+        /// Pass them to `prax_query::middleware::PolicyMiddleware` to enforce
+        /// them on databases without native row-level security.
+        pub fn rls_policies() -> Vec<prax_query::security::RlsPolicy> {
+            vec![#(#policies),*]
+        }
+
+        /// Client running the generated models' operations on a query engine.
+        #[derive(Clone)]
+        pub struct PraxClient<E: prax_query::traits::QueryEngine> {
+            engine: E,
+        }
+
+        impl<E: prax_query::traits::QueryEngine> PraxClient<E> {
+            /// The tables of the schema's models.
+            pub const TABLES: &'static [prax_query::verify::TableSpec] = &[#(#tables),*];
+
+            /// Create a client over a query engine.
+            pub fn new(engine: E) -> Self {
+                Self { engine }
+            }
+
+            /// Get the query engine.
+            pub fn engine(&self) -> &E {
+                &self.engine
+            }
+
+            #(#accessors)*
+        }
+
+        impl<E> PraxClient<E>
+        where
+            E: prax_query::traits::QueryEngine + prax_query::raw::RawDatabase,
+        {
+            /// Check the live database against the schema.
+            ///
+            /// Reports every missing table, column and index and every column
+            /// whose type cannot hold its schema type. Call `ensure()` on the
+            /// report to refuse to start when the database does not match.
+            pub async fn verify_schema(
+                &self,
+            ) -> prax_query::error::QueryResult<prax_query::verify::SchemaReport> {
+                prax_query::verify::verify_schema(&self.engine, Self::TABLES).await
+            }
+        }
+
+        impl<E: prax_query::transaction::TransactionalEngine> PraxClient<E> {
+            /// Run `f` in a transaction, committing when it succeeds and rolling
+            /// back when it fails.
+            ///
+            /// `f` receives a client whose operations all run inside the
+            /// transaction. Set the isolation level, access mode or timeout on the
+            /// returned builder before awaiting it.
+            pub fn transaction<F, Fut, T>(
+                &self,
+                f: F,
+            ) -> prax_query::transaction::TransactionBuilder<
+                E,
+                impl FnOnce(prax_query::transaction::Transaction<E>) -> Fut + Send + 'static,
+                Fut,
+                T,
+            >
+            where
+                F: FnOnce(PraxClient<E>) -> Fut + Send + 'static,
+                Fut: std::future::Future<Output = prax_query::error::QueryResult<T>> + Send + 'static,
+                T: Send + 'static,
+            {
+                prax_query::transaction::TransactionBuilder::new(
+                    self.engine.clone(),
+                    move |tx: prax_query::transaction::Transaction<E>| {
+                        f(PraxClient::new(tx.engine().clone()))
+                    },
+                )
+            }
+
+            /// Run `f` inside a savepoint of the current transaction.
+            ///
+            /// A failure rolls back only the work done in `f` and is returned,
+            /// leaving the transaction usable. Savepoints nest.
+            pub async fn savepoint<F, Fut, T>(
+                &self,
+                name: &str,
+                f: F,
+            ) -> prax_query::error::QueryResult<T>
+            where
+                F: FnOnce(PraxClient<E>) -> Fut,
+                Fut: std::future::Future<Output = prax_query::error::QueryResult<T>>,
+            {
+                prax_query::transaction::run_savepoint(&self.engine, name, |engine| {
+                    f(PraxClient::new(engine))
+                })
+                .await
+            }
+
+            /// Run built operations all-or-nothing in one transaction,
+            /// returning their results in order.
+            ///
+            /// Takes a tuple or `Vec` of operations such as
+            /// `self.user().create(...)` that have not been executed. The first
+            /// failure rolls back every operation and is returned.
+            pub async fn batch<B>(&self, ops: B) -> prax_query::error::QueryResult<B::Output>
+            where
+                B: prax_query::batch::BatchOps<E>,
+            {
+                prax_query::batch::run_batch(
+                    &self.engine,
+                    &prax_query::transaction::TransactionConfig::default(),
+                    ops,
+                )
+                .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_client() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                posts Post[]
+            }
+
+            model Post {
+                id       Int  @id @auto
+                authorId Int
+                author   User @relation(fields: [authorId], references: [id])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let code = generate_client(&schema).to_string();
+        assert!(code.contains("pub struct PraxClient < E : prax_query :: traits :: QueryEngine >"));
+        assert!(
+            code.contains(
+                "pub fn user (& self) -> prax_query :: QueryBuilder < E , user :: User >"
+            )
+        );
+        assert!(
+            code.contains(
+                "pub fn post (& self) -> prax_query :: QueryBuilder < E , post :: Post >"
+            )
+        );
+        assert!(code.contains(
+            "impl < E : prax_query :: transaction :: TransactionalEngine > PraxClient < E >"
+        ));
+        assert!(code.contains("pub fn transaction < F , Fut , T >"));
+        assert!(code.contains("prax_query :: transaction :: run_savepoint"));
+        assert!(code.contains("pub async fn batch < B >"));
+        assert!(code.contains("prax_query :: batch :: run_batch"));
+        assert!(code.contains(
+            "& [prax_query :: verify :: TableSpec :: of :: < user :: User > () , prax_query :: verify :: TableSpec :: of :: < post :: Post > ()]"
+        ));
+        assert!(code.contains("pub async fn verify_schema"));
+        assert!(code.contains(
+            "pub fn rls_policies () -> Vec < prax_query :: security :: RlsPolicy > { vec ! [] }"
+        ));
+    }
+
+    #[test]
+    fn test_generate_rls_policies() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model Post {
+                id       Int @id @auto
+                authorId Int
+
+                @@map("posts")
+            }
+
+            policy OwnPosts on Post {
+                for [SELECT, UPDATE]
+                to authenticated
+                using "author_id = current_setting('app.user_id')::int"
+            }
+            "#,
+        )
+        .unwrap();
+
+        let code = generate_client(&schema).to_string();
+        assert!(code.contains(
+            "prax_query :: security :: RlsPolicy :: new (\"OwnPosts\" , \"posts\") . for_command (prax_query :: security :: PolicyCommand :: Select) . to_roles ([\"authenticated\"]) . using (\"author_id = current_setting('app.user_id')::int\") . build ()"
+        ));
+        assert!(code.contains("prax_query :: security :: PolicyCommand :: Update"));
+    }
+}
@@ -34,6 +34,10 @@ pub fn derive_model_impl(input: &DeriveInput) -> Result<TokenStream, syn::Error>
     let table_name = struct_attrs
         .table_name
         .unwrap_or_else(|| name.to_string().to_case(Case::Snake));
+    let table_name = match struct_attrs.schema_name {
+        Some(schema) => format!("{}.{}", schema, table_name),
+        None => table_name,
+    };
 
     // Parse field attributes
     let field_infos: Vec<FieldInfo> = fields.iter().map(parse_field).collect::<Result<_, _>>()?;
@@ -360,6 +364,20 @@ mod tests {
         assert!(code.contains("users"));
     }
 
+    #[test]
+    fn test_parse_model_with_schema() {
+        let input: DeriveInput = parse_quote! {
+            #[prax(schema = "accounting", table = "invoices")]
+            struct Invoice {
+                #[prax(id)]
+                id: i32,
+            }
+        };
+
+        let code = derive_model_impl(&input).unwrap().to_string();
+        assert!(code.contains("\"accounting.invoices\""));
+    }
+
     #[test]
     fn test_parse_model_without_id() {
         let input: DeriveInput = parse_quote! {
@@ -109,6 +109,28 @@ pub fn generate_enum_module(enum_def: &Enum) -> Result<TokenStream, syn::Error>
                     Self::variants()[0]
                 }
             }
+
+            impl prax_query::DbEnum for #enum_name {
+                const DB_NAME: &'static str = DB_NAME;
+
+                fn variants() -> &'static [Self] {
+                    Self::variants()
+                }
+
+                fn db_value(&self) -> &'static str {
+                    self.as_str()
+                }
+
+                fn from_db_value(value: &str) -> Option<Self> {
+                    Self::from_str(value)
+                }
+            }
+
+            impl From<#enum_name> for prax_query::filter::FilterValue {
+                fn from(value: #enum_name) -> Self {
+                    Self::String(value.as_str().to_string())
+                }
+            }
         }
 
         // Re-export the enum at the parent level
@@ -143,6 +165,8 @@ mod tests {
         assert!(code.contains("pub enum Role"));
         assert!(code.contains("User"));
         assert!(code.contains("Admin"));
+        assert!(code.contains("impl prax_query :: DbEnum for Role"));
+        assert!(code.contains("impl From < Role > for prax_query :: filter :: FilterValue"));
     }
 
     #[test]
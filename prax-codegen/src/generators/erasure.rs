@@ -0,0 +1,212 @@
+//! Code generation for subject erasure (`@subjectId` / `@personalData`).
+
+use std::collections::{HashSet, VecDeque};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use prax_schema::ast::{AttributeValue, ErasureStrategy, Field, FieldType, Model, Schema};
+
+/// A model reachable from the subject, with the scope selecting its subject rows.
+struct Reached<'a> {
+    model: &'a Model,
+    scope: TokenStream,
+}
+
+/// Generate `erasure_plan()` and `forget_subject()` for a model with a `@subjectId` field.
+pub fn generate_erasure(model: &Model, schema: &Schema) -> TokenStream {
+    let Some(subject_field) = model.subject_id_field() else {
+        return TokenStream::new();
+    };
+
+    let model_name = model.name();
+    let steps = reachable_models(model, column_name(subject_field), schema)
+        .into_iter()
+        // Children first, so parent rows are erased after the rows pointing at them
+        .rev()
+        .filter_map(|reached| erasure_step(&reached));
+
+    quote! {
+        /// Plan erasing the personal data of a subject and of related models.
+        pub fn erasure_plan() -> prax_query::erasure::ErasurePlan {
+            prax_query::erasure::ErasurePlan::new(#model_name)
+                #(#steps)*
+        }
+
+        /// Erase all personal data of a subject in a single transaction.
+        pub async fn forget_subject<D>(
+            db: &D,
+            subject_id: impl Into<prax_query::filter::FilterValue>,
+        ) -> prax_query::error::QueryResult<prax_query::erasure::ErasureReport>
+        where
+            D: prax_query::raw::RawDatabase + ?Sized,
+        {
+            erasure_plan().execute(db, subject_id).await
+        }
+    }
+}
+
+/// Walk relations breadth-first from the subject model.
+///
+/// Each model is reached once, through the first foreign key found pointing
+/// at an already reached model.
+fn reachable_models<'a>(
+    subject: &'a Model,
+    subject_column: String,
+    schema: &'a Schema,
+) -> Vec<Reached<'a>> {
+    let mut visited = HashSet::from([subject.name()]);
+    let mut queue = VecDeque::from([Reached {
+        model: subject,
+        scope: quote! { prax_query::erasure::ErasureScope::subject(#subject_column) },
+    }]);
+    let mut reached = Vec::new();
+
+    while let Some(parent) = queue.pop_front() {
+        for child in schema.models.values() {
+            if visited.contains(child.name()) {
+                continue;
+            }
+            let Some((fk_column, ref_column)) = foreign_key_to(child, parent.model) else {
+                continue;
+            };
+
+            let parent_table = parent.model.table_name();
+            let parent_scope = &parent.scope;
+            visited.insert(child.name());
+            queue.push_back(Reached {
+                model: child,
+                scope: quote! {
+                    prax_query::erasure::ErasureScope::related(
+                        #fk_column,
+                        #parent_table,
+                        #ref_column,
+                        #parent_scope,
+                    )
+                },
+            });
+        }
+        reached.push(parent);
+    }
+
+    reached
+}
+
+/// Find a single-column foreign key from `child` to `parent`.
+fn foreign_key_to(child: &Model, parent: &Model) -> Option<(String, String)> {
+    child.fields.values().find_map(|field| {
+        let FieldType::Model(target) = &field.field_type else {
+            return None;
+        };
+        if target != parent.name() {
+            return None;
+        }
+
+        let relation = field.extract_attributes().relation?;
+        let fk = child.fields.get(relation.fields.first()?.as_str())?;
+        let referenced = parent.fields.get(relation.references.first()?.as_str())?;
+        Some((column_name(fk), column_name(referenced)))
+    })
+}
+
+/// Generate the plan step erasing a reached model's personal data, if it has any.
+fn erasure_step(reached: &Reached<'_>) -> Option<TokenStream> {
+    let personal: Vec<_> = reached
+        .model
+        .fields
+        .values()
+        .filter_map(|f| f.personal_data().map(|data| (f, data)))
+        .collect();
+    if personal.is_empty() {
+        return None;
+    }
+
+    let model_name = reached.model.name();
+    let table = reached.model.table_name();
+    let scope = &reached.scope;
+
+    if personal
+        .iter()
+        .any(|(_, data)| data.strategy == ErasureStrategy::Delete)
+    {
+        return Some(quote! { .delete(#model_name, #table, #scope) });
+    }
+
+    let values = personal.iter().map(|(field, data)| {
+        let column = column_name(field);
+        let value = replacement_value(data.replacement.as_ref());
+        quote! { (#column, #value) }
+    });
+    Some(quote! { .anonymize(#model_name, #table, #scope, [#(#values),*]) })
+}
+
+/// Generate the `FilterValue` written when anonymizing.
+fn replacement_value(replacement: Option<&AttributeValue>) -> TokenStream {
+    match replacement {
+        Some(AttributeValue::String(s)) => {
+            quote! { prax_query::filter::FilterValue::String(#s.to_string()) }
+        }
+        Some(AttributeValue::Int(i)) => quote! { prax_query::filter::FilterValue::Int(#i) },
+        Some(AttributeValue::Float(f)) => quote! { prax_query::filter::FilterValue::Float(#f) },
+        Some(AttributeValue::Boolean(b)) => quote! { prax_query::filter::FilterValue::Bool(#b) },
+        _ => quote! { prax_query::filter::FilterValue::Null },
+    }
+}
+
+/// Get the column name of a field (from `@map`, or the field name).
+fn column_name(field: &Field) -> String {
+    field
+        .extract_attributes()
+        .map
+        .unwrap_or_else(|| field.name().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_erasure_plan() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id    Int     @id @auto @subjectId
+                email String? @unique @personalData
+                name  String  @personalData(replace: "Deleted user")
+                posts Post[]
+            }
+
+            model Post {
+                id       Int       @id @auto
+                authorId Int       @map("author_id")
+                author   User      @relation(fields: [authorId], references: [id])
+                comments Comment[]
+            }
+
+            model Comment {
+                id     Int    @id @auto
+                body   String @personalData(strategy: delete)
+                postId Int    @map("post_id")
+                post   Post   @relation(fields: [postId], references: [id])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        let code = generate_erasure(user, &schema).to_string();
+        assert!(code.contains("pub fn erasure_plan ()"));
+        assert!(code.contains("pub async fn forget_subject"));
+
+        // Comments are deleted first, through posts; posts hold no personal data
+        let delete = code.find(". delete (\"Comment\"").unwrap();
+        let anonymize = code.find(". anonymize (\"User\"").unwrap();
+        assert!(delete < anonymize);
+        assert!(code.contains("related (\"post_id\" , \"Post\" , \"id\""));
+        assert!(!code.contains("(\"Post\" , \"Post\""));
+        assert!(code.contains("FilterValue :: String (\"Deleted user\" . to_string ())"));
+
+        let post = schema.get_model("Post").unwrap();
+        assert!(generate_erasure(post, &schema).is_empty());
+    }
+}
@@ -1,15 +1,17 @@
 //! Code generation for field modules.
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 
-use prax_schema::ast::{Field, FieldType, Model, TypeModifier};
+use prax_schema::ast::{Field, FieldType, Model, Schema, TypeModifier};
 
+use super::loaders::generate_include_load;
 use super::{generate_doc_comment, pascal_ident, snake_ident};
-use crate::types::field_type_to_rust;
+use crate::types::{field_type_to_rust, to_screaming_snake};
 
-/// Generate the field module with select, order, and set operations.
-pub fn generate_field_module(field: &Field, model: &Model) -> TokenStream {
+/// Generate the field module with select, order, and set operations, and
+/// the include of a relation field.
+pub fn generate_field_module(field: &Field, model: &Model, schema: &Schema) -> TokenStream {
     let field_name = snake_ident(field.name());
     let field_name_pascal = pascal_ident(field.name());
     let field_type = field_type_to_rust(&field.field_type, &TypeModifier::Required);
@@ -48,8 +50,9 @@ pub fn generate_field_module(field: &Field, model: &Model) -> TokenStream {
         TokenStream::new()
     };
 
-    // Generate set operations for updates
-    let set_ops = if !is_relation {
+    // Generate set operations for updates; computed fields are never written
+    let is_virtual = field.is_virtual();
+    let set_ops = if !is_relation && !is_virtual {
         let set_type = if is_optional {
             quote! { Option<#field_type> }
         } else {
@@ -68,7 +71,7 @@ pub fn generate_field_module(field: &Field, model: &Model) -> TokenStream {
 
     // Generate increment/decrement for numeric types
     let numeric_ops = match &field.field_type {
-        FieldType::Scalar(s) if crate::types::supports_comparison(s) => {
+        FieldType::Scalar(s) if crate::types::supports_comparison(s) && !is_virtual => {
             if matches!(
                 s,
                 prax_schema::ast::ScalarType::Int
@@ -94,8 +97,12 @@ pub fn generate_field_module(field: &Field, model: &Model) -> TokenStream {
         _ => TokenStream::new(),
     };
 
-    // Generate filter operations
-    let filters = super::filters::generate_field_filters(field, model.name());
+    // Generate the include loading a relation
+    let include = generate_include_load(model, field, schema);
+
+    // Generate filter operations, which depend on the database
+    let provider = schema.datasource().map(|ds| &ds.provider);
+    let filters = super::filters::generate_field_filters(field, model.name(), provider);
 
     quote! {
         #doc
@@ -117,6 +124,7 @@ pub fn generate_field_module(field: &Field, model: &Model) -> TokenStream {
             #order_by
             #set_ops
             #numeric_ops
+            #include
 
             // Re-export filter operations
             #filters
@@ -241,6 +249,23 @@ pub fn generate_order_by_param(model: &Model) -> TokenStream {
                 format!("{} {}", col, dir)
             }
         }
+
+        impl From<OrderByParam> for prax_query::types::OrderByField {
+            fn from(param: OrderByParam) -> Self {
+                let (col, order) = param.column_and_order();
+                let order = match order {
+                    super::_prax_prelude::SortOrder::Asc => prax_query::types::SortOrder::Asc,
+                    super::_prax_prelude::SortOrder::Desc => prax_query::types::SortOrder::Desc,
+                };
+                prax_query::types::OrderByField::new_static(col, order)
+            }
+        }
+
+        impl From<OrderByParam> for prax_query::types::OrderBy {
+            fn from(param: OrderByParam) -> Self {
+                prax_query::types::OrderByField::from(param).into()
+            }
+        }
     }
 }
 
@@ -249,7 +274,7 @@ pub fn generate_set_param(model: &Model) -> TokenStream {
     let variants: Vec<_> = model
         .fields
         .values()
-        .filter(|f| !matches!(f.field_type, FieldType::Model(_)))
+        .filter(|f| !matches!(f.field_type, FieldType::Model(_)) && !f.is_virtual())
         .map(|f| {
             let name = pascal_ident(f.name());
             let field_type = field_type_to_rust(&f.field_type, &f.modifier);
@@ -260,7 +285,7 @@ pub fn generate_set_param(model: &Model) -> TokenStream {
     let variant_names: Vec<_> = model
         .fields
         .values()
-        .filter(|f| !matches!(f.field_type, FieldType::Model(_)))
+        .filter(|f| !matches!(f.field_type, FieldType::Model(_)) && !f.is_virtual())
         .map(|f| {
             let name = pascal_ident(f.name());
             let col = f
@@ -300,6 +325,158 @@ pub fn generate_set_param(model: &Model) -> TokenStream {
     }
 }
 
+/// Generate the bitset of changed fields used to diff records.
+pub fn generate_field_mask(model: &Model) -> TokenStream {
+    let model_name = pascal_ident(model.name());
+    let fields: Vec<_> = model
+        .fields
+        .values()
+        .filter(|f| !matches!(f.field_type, FieldType::Model(_)) && !f.is_virtual())
+        .collect();
+    let words = fields.len().div_ceil(64).max(1);
+
+    let flags = fields.iter().enumerate().map(|(i, f)| {
+        let flag = format_ident!("{}", to_screaming_snake(f.name()));
+        let doc = format!(" The `{}` field.", f.name());
+        quote! {
+            #[doc = #doc]
+            pub const #flag: Self = Self::bit(#i);
+        }
+    });
+    let columns = fields.iter().map(|f| {
+        let flag = format_ident!("{}", to_screaming_snake(f.name()));
+        let name = snake_ident(f.name());
+        quote! {
+            if self.contains(Self::#flag) {
+                columns.push(#name::COLUMN);
+            }
+        }
+    });
+    let set_params = fields.iter().map(|f| {
+        let flag = format_ident!("{}", to_screaming_snake(f.name()));
+        let name = snake_ident(f.name());
+        let variant = pascal_ident(f.name());
+        quote! {
+            if self.contains(Self::#flag) {
+                params.push(SetParam::#variant(row.#name.clone()));
+            }
+        }
+    });
+    let comparisons = fields.iter().map(|f| {
+        let flag = format_ident!("{}", to_screaming_snake(f.name()));
+        let name = snake_ident(f.name());
+        quote! {
+            if self.#name != other.#name {
+                mask.insert(FieldMask::#flag);
+            }
+        }
+    });
+    let field_count = fields.len();
+
+    quote! {
+        /// A set of this model's stored fields, one bit per field.
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct FieldMask([u64; #words]);
+
+        impl FieldMask {
+            #(#flags)*
+
+            const fn bit(index: usize) -> Self {
+                let mut words = [0; #words];
+                words[index / 64] = 1 << (index % 64);
+                Self(words)
+            }
+
+            /// Get the mask of every stored field.
+            pub fn all() -> Self {
+                let mut mask = Self::default();
+                for index in 0..#field_count {
+                    mask.insert(Self::bit(index));
+                }
+                mask
+            }
+
+            /// Check whether every field of `other` is in this mask.
+            pub fn contains(&self, other: Self) -> bool {
+                self.0.iter().zip(other.0).all(|(word, other)| word & other == other)
+            }
+
+            /// Check whether any field of `other` is in this mask.
+            pub fn intersects(&self, other: Self) -> bool {
+                self.0.iter().zip(other.0).any(|(word, other)| word & other != 0)
+            }
+
+            /// Add the fields of `other` to this mask.
+            pub fn insert(&mut self, other: Self) {
+                for (word, other) in self.0.iter_mut().zip(other.0) {
+                    *word |= other;
+                }
+            }
+
+            /// Remove the fields of `other` from this mask.
+            pub fn remove(&mut self, other: Self) {
+                for (word, other) in self.0.iter_mut().zip(other.0) {
+                    *word &= !other;
+                }
+            }
+
+            /// Get the number of fields in this mask.
+            pub fn len(&self) -> usize {
+                self.0.iter().map(|word| word.count_ones() as usize).sum()
+            }
+
+            /// Check whether this mask has no fields.
+            pub fn is_empty(&self) -> bool {
+                self.0.iter().all(|word| *word == 0)
+            }
+
+            /// Get the parameters setting the fields in this mask to their values in `row`.
+            pub fn set_params(&self, row: &#model_name) -> Vec<SetParam> {
+                let mut params = Vec::new();
+                #(#set_params)*
+                params
+            }
+        }
+
+        impl std::ops::BitOr for FieldMask {
+            type Output = Self;
+
+            fn bitor(mut self, other: Self) -> Self {
+                self.insert(other);
+                self
+            }
+        }
+
+        impl std::ops::BitOrAssign for FieldMask {
+            fn bitor_assign(&mut self, other: Self) {
+                self.insert(other);
+            }
+        }
+
+        impl prax_query::diff::FieldMask for FieldMask {
+            fn is_empty(&self) -> bool {
+                FieldMask::is_empty(self)
+            }
+
+            fn columns(&self) -> Vec<&'static str> {
+                let mut columns = Vec::new();
+                #(#columns)*
+                columns
+            }
+        }
+
+        impl prax_query::diff::Diffable for #model_name {
+            type Mask = FieldMask;
+
+            fn changed_fields(&self, other: &Self) -> FieldMask {
+                let mut mask = FieldMask::default();
+                #(#comparisons)*
+                mask
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -359,6 +536,7 @@ mod tests {
 
         assert!(code.contains("pub enum OrderByParam"));
         assert!(code.contains("SortOrder"));
+        assert!(code.contains("impl From < OrderByParam > for prax_query :: types :: OrderBy"));
     }
 
     #[test]
@@ -371,4 +549,17 @@ mod tests {
         assert!(code.contains("Id"));
         assert!(code.contains("Name"));
     }
+
+    #[test]
+    fn test_generate_field_mask() {
+        let model = make_model();
+        let mask = generate_field_mask(&model);
+        let code = mask.to_string();
+
+        assert!(code.contains("pub struct FieldMask ([u64 ; 1usize])"));
+        assert!(code.contains("pub const EMAIL : Self = Self :: bit (2usize)"));
+        assert!(code.contains("columns . push (name :: COLUMN)"));
+        assert!(code.contains("params . push (SetParam :: Email (row . email . clone ()))"));
+        assert!(code.contains("impl prax_query :: diff :: Diffable for User"));
+    }
 }
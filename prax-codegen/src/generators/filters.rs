@@ -3,13 +3,21 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use prax_schema::ast::{Field, FieldType, ScalarType, TypeModifier};
+use prax_schema::ast::{DatabaseProvider, Field, FieldType, ScalarType, TypeModifier};
 
 use super::{pascal_ident, snake_ident};
 use crate::types::{field_type_to_rust, supports_comparison, supports_in_op, supports_string_ops};
 
 /// Generate filter operations for a field.
-pub fn generate_field_filters(field: &Field, _model_name: &str) -> TokenStream {
+///
+/// Range and network operators are rendered for the `provider`'s storage:
+/// databases other than PostgreSQL store ranges in two bound columns and
+/// addresses as text. JSON operators use the provider's JSON functions.
+pub fn generate_field_filters(
+    field: &Field,
+    _model_name: &str,
+    provider: Option<&DatabaseProvider>,
+) -> TokenStream {
     let field_name = snake_ident(field.name());
     let field_type = field_type_to_rust(&field.field_type, &TypeModifier::Required);
     let where_variant = pascal_ident(field.name());
@@ -24,22 +32,26 @@ pub fn generate_field_filters(field: &Field, _model_name: &str) -> TokenStream {
         .unwrap_or_else(|| field.name().to_string());
 
     let is_optional = field.modifier.is_optional();
-
-    // Base equality operations
-    let mut ops = vec![
-        quote! {
-            /// Filter by exact value equality.
-            pub fn equals(value: #field_type) -> super::WhereParam {
-                super::WhereParam::#where_variant(WhereOp::Equals(value))
-            }
-        },
-        quote! {
-            /// Filter by not equal.
-            pub fn not(value: #field_type) -> super::WhereParam {
-                super::WhereParam::#where_variant(WhereOp::Not(value))
-            }
-        },
-    ];
+    let is_money = matches!(field.field_type, FieldType::Scalar(ScalarType::Money));
+
+    // Base equality operations; money compares two columns, below
+    let mut ops = Vec::new();
+    if !is_money {
+        ops.extend([
+            quote! {
+                /// Filter by exact value equality.
+                pub fn equals(value: #field_type) -> super::WhereParam {
+                    super::WhereParam::#where_variant(WhereOp::Equals(value))
+                }
+            },
+            quote! {
+                /// Filter by not equal.
+                pub fn not(value: #field_type) -> super::WhereParam {
+                    super::WhereParam::#where_variant(WhereOp::Not(value))
+                }
+            },
+        ]);
+    }
 
     // Optional-specific operations
     if is_optional {
@@ -125,6 +137,244 @@ pub fn generate_field_filters(field: &Field, _model_name: &str) -> TokenStream {
         }
     }
 
+    // Range and network operations
+    let emulate_types = provider.is_some_and(|p| *p != DatabaseProvider::PostgreSQL);
+    let storage = if emulate_types {
+        quote! { .emulated() }
+    } else {
+        TokenStream::new()
+    };
+    let mut native_arm = TokenStream::new();
+    let range_element = match &field.field_type {
+        FieldType::Scalar(scalar) => scalar.range_element(),
+        _ => None,
+    };
+    if let Some(element) = range_element {
+        let element_type = field_type_to_rust(&FieldType::Scalar(element), &TypeModifier::Required);
+        ops.push(quote! {
+            /// Filter by ranges containing a value.
+            pub fn contains(value: #element_type) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Range(
+                    prax_query::range::RangeFilter::contains(value)#storage,
+                ))
+            }
+        });
+        ops.push(quote! {
+            /// Filter by ranges containing all of another range.
+            pub fn contains_range(
+                range: impl Into<prax_query::range::RangeValue>,
+            ) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Range(
+                    prax_query::range::RangeFilter::contains_range(range)#storage,
+                ))
+            }
+        });
+        ops.push(quote! {
+            /// Filter by ranges overlapping another range.
+            pub fn overlaps(range: impl Into<prax_query::range::RangeValue>) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Range(
+                    prax_query::range::RangeFilter::overlaps(range)#storage,
+                ))
+            }
+        });
+        ops.push(quote! {
+            /// Filter by ranges adjacent to another range.
+            pub fn adjacent(range: impl Into<prax_query::range::RangeValue>) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Range(
+                    prax_query::range::RangeFilter::adjacent(range)#storage,
+                ))
+            }
+        });
+        native_arm = quote! {
+            Self::Range(filter) => filter.clone().into_filter(COLUMN).to_sql(param_idx - 1).0,
+        };
+    }
+    if matches!(&field.field_type, FieldType::Scalar(scalar) if scalar.is_network()) {
+        ops.push(quote! {
+            /// Filter by addresses within a network.
+            pub fn contained_in(
+                network: impl Into<prax_query::network::IpValue>,
+            ) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Network(
+                    prax_query::network::NetworkFilter::contained_in(network)#storage,
+                ))
+            }
+        });
+        ops.push(quote! {
+            /// Filter by networks containing an address.
+            pub fn contains(addr: impl Into<prax_query::network::IpValue>) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Network(
+                    prax_query::network::NetworkFilter::contains(addr)#storage,
+                ))
+            }
+        });
+        ops.push(quote! {
+            /// Filter by addresses of the same family (IPv4 or IPv6).
+            pub fn same_family(
+                addr: impl Into<prax_query::network::IpValue>,
+            ) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Network(
+                    prax_query::network::NetworkFilter::same_family(addr)#storage,
+                ))
+            }
+        });
+        native_arm = quote! {
+            Self::Network(filter) => filter.clone().into_filter(COLUMN).to_sql(param_idx - 1).0,
+        };
+    }
+    if let FieldType::Scalar(scalar) = &field.field_type {
+        if scalar.is_spatial() {
+            let column = if *scalar == ScalarType::Geography {
+                quote! { .geography() }
+            } else {
+                TokenStream::new()
+            };
+            ops.push(quote! {
+                /// Filter by shapes inside another shape.
+                pub fn within(
+                    other: impl Into<prax_query::spatial::Geometry>,
+                ) -> super::WhereParam {
+                    super::WhereParam::#where_variant(WhereOp::Spatial(
+                        prax_query::spatial::SpatialFilter::within(other)#column,
+                    ))
+                }
+            });
+            ops.push(quote! {
+                /// Filter by shapes intersecting another shape.
+                pub fn intersects(
+                    other: impl Into<prax_query::spatial::Geometry>,
+                ) -> super::WhereParam {
+                    super::WhereParam::#where_variant(WhereOp::Spatial(
+                        prax_query::spatial::SpatialFilter::intersects(other)#column,
+                    ))
+                }
+            });
+            ops.push(quote! {
+                /// Filter by shapes within a distance of another shape.
+                pub fn dwithin(
+                    other: impl Into<prax_query::spatial::Geometry>,
+                    distance: f64,
+                ) -> super::WhereParam {
+                    super::WhereParam::#where_variant(WhereOp::Spatial(
+                        prax_query::spatial::SpatialFilter::dwithin(other, distance)#column,
+                    ))
+                }
+            });
+            native_arm = quote! {
+                Self::Spatial(filter) => filter.clone().into_filter(COLUMN).to_sql(param_idx - 1).0,
+            };
+        }
+    }
+    if matches!(field.field_type, FieldType::Scalar(ScalarType::Json)) {
+        let dialect = match provider {
+            Some(DatabaseProvider::MySQL) => {
+                quote! { .for_database(prax_query::sql::DatabaseType::MySQL) }
+            }
+            Some(DatabaseProvider::SQLite) => {
+                quote! { .for_database(prax_query::sql::DatabaseType::SQLite) }
+            }
+            _ => TokenStream::new(),
+        };
+        ops.push(quote! {
+            /// Filter by the value at a path inside the document, such as
+            /// `path(["settings", "theme"]).equals("dark")`.
+            pub fn path<S: Into<String>>(path: impl IntoIterator<Item = S>) -> JsonPathFilter {
+                JsonPathFilter(path.into_iter().map(Into::into).collect())
+            }
+
+            /// Filters on the value at a path inside the document.
+            #[derive(Debug, Clone)]
+            pub struct JsonPathFilter(Vec<String>);
+
+            impl JsonPathFilter {
+                /// Filter by values equal to a JSON value.
+                pub fn equals(self, value: impl Into<serde_json::Value>) -> super::WhereParam {
+                    self.filter(prax_query::json::JsonFieldFilter::equals(value))
+                }
+
+                /// Filter by arrays containing a value, or each element of an array.
+                pub fn array_contains(
+                    self,
+                    value: impl Into<serde_json::Value>,
+                ) -> super::WhereParam {
+                    self.filter(prax_query::json::JsonFieldFilter::array_contains(value))
+                }
+
+                /// Filter by strings containing a substring.
+                pub fn string_contains(self, value: impl Into<String>) -> super::WhereParam {
+                    self.filter(prax_query::json::JsonFieldFilter::string_contains(value))
+                }
+
+                /// Filter by objects with a key.
+                pub fn has_key(self, key: impl Into<String>) -> super::WhereParam {
+                    self.filter(prax_query::json::JsonFieldFilter::has_key(key))
+                }
+
+                fn filter(self, filter: prax_query::json::JsonFieldFilter) -> super::WhereParam {
+                    super::WhereParam::#where_variant(WhereOp::Json(
+                        filter.at_path(self.0)#dialect,
+                    ))
+                }
+            }
+
+            /// Filter by documents that are arrays containing a value, or each
+            /// element of an array.
+            pub fn array_contains(value: impl Into<serde_json::Value>) -> super::WhereParam {
+                path(Vec::<String>::new()).array_contains(value)
+            }
+
+            /// Filter by documents that are strings containing a substring.
+            pub fn string_contains(value: impl Into<String>) -> super::WhereParam {
+                path(Vec::<String>::new()).string_contains(value)
+            }
+
+            /// Filter by documents that are objects with a key.
+            pub fn has_key(key: impl Into<String>) -> super::WhereParam {
+                path(Vec::<String>::new()).has_key(key)
+            }
+        });
+        native_arm = quote! {
+            Self::Json(filter) => filter.clone().into_filter(COLUMN).to_sql(param_idx - 1).0,
+        };
+    }
+    if is_money {
+        for (name, doc) in [
+            ("equals", "Filter by the same amount in the same currency."),
+            ("not", "Filter by a different amount or currency."),
+            ("gt", "Filter by greater amounts in the same currency."),
+            (
+                "gte",
+                "Filter by greater or equal amounts in the same currency.",
+            ),
+            ("lt", "Filter by smaller amounts in the same currency."),
+            (
+                "lte",
+                "Filter by smaller or equal amounts in the same currency.",
+            ),
+        ] {
+            let op = quote::format_ident!("{}", name);
+            ops.push(quote! {
+                #[doc = #doc]
+                pub fn #op(value: prax_query::money::Money) -> super::WhereParam {
+                    super::WhereParam::#where_variant(WhereOp::Money(
+                        prax_query::money::MoneyFilter::#op(value),
+                    ))
+                }
+            });
+        }
+        ops.push(quote! {
+            /// Filter by any amount in a currency.
+            pub fn currency(currency: prax_query::money::Currency) -> super::WhereParam {
+                super::WhereParam::#where_variant(WhereOp::Money(
+                    prax_query::money::MoneyFilter::currency(currency),
+                ))
+            }
+        });
+        native_arm = quote! {
+            Self::Money(filter) => filter.into_filter(COLUMN).to_sql(param_idx - 1).0,
+        };
+    }
+
     // Generate the where op enum for this field
     let where_op_variants = generate_where_op_variants(&field.field_type, is_optional);
 
@@ -169,6 +419,7 @@ pub fn generate_field_filters(field: &Field, _model_name: &str) -> TokenStream {
                         Self::Contains(_) => format!("{} LIKE '%' || ${} || '%'", COLUMN, param_idx),
                         Self::StartsWith(_) => format!("{} LIKE ${} || '%'", COLUMN, param_idx),
                         Self::EndsWith(_) => format!("{} LIKE '%' || ${}", COLUMN, param_idx),
+                        #native_arm
                     }
                 }
             }
@@ -198,6 +449,15 @@ fn generate_where_op_variants(field_type: &FieldType, is_optional: bool) -> Toke
             ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => {
                 quote! { String }
             }
+            ScalarType::IntRange => quote! { std::ops::Range<i32> },
+            ScalarType::BigIntRange => quote! { std::ops::Range<i64> },
+            ScalarType::DateTimeRange => quote! { std::ops::Range<chrono::DateTime<chrono::Utc>> },
+            ScalarType::Inet | ScalarType::Cidr => quote! { ipnetwork::IpNetwork },
+            ScalarType::MacAddr => quote! { macaddr::MacAddr6 },
+            ScalarType::Geometry | ScalarType::Geography => {
+                quote! { prax_query::spatial::Geometry }
+            }
+            ScalarType::Money => quote! { prax_query::money::Money },
             // PostgreSQL vector types
             ScalarType::Vector(_) | ScalarType::HalfVector(_) => quote! { Vec<f32> },
             ScalarType::SparseVector(_) => quote! { Vec<(u32, f32)> },
@@ -235,6 +495,26 @@ fn generate_where_op_variants(field_type: &FieldType, is_optional: bool) -> Toke
             variants.push(quote! { StartsWith(String) });
             variants.push(quote! { EndsWith(String) });
         }
+
+        if scalar.is_range() {
+            variants.push(quote! { Range(prax_query::range::RangeFilter) });
+        }
+
+        if scalar.is_network() {
+            variants.push(quote! { Network(prax_query::network::NetworkFilter) });
+        }
+
+        if scalar.is_spatial() {
+            variants.push(quote! { Spatial(prax_query::spatial::SpatialFilter) });
+        }
+
+        if *scalar == ScalarType::Money {
+            variants.push(quote! { Money(prax_query::money::MoneyFilter) });
+        }
+
+        if *scalar == ScalarType::Json {
+            variants.push(quote! { Json(prax_query::json::JsonFieldFilter) });
+        }
     }
 
     quote! {
@@ -265,7 +545,7 @@ mod tests {
             make_span(),
         );
 
-        let filters = generate_field_filters(&field, "User");
+        let filters = generate_field_filters(&field, "User", None);
         let code = filters.to_string();
 
         assert!(code.contains("pub fn equals"));
@@ -285,7 +565,7 @@ mod tests {
             make_span(),
         );
 
-        let filters = generate_field_filters(&field, "User");
+        let filters = generate_field_filters(&field, "User", None);
         let code = filters.to_string();
 
         assert!(code.contains("pub fn gt"));
@@ -304,10 +584,118 @@ mod tests {
             make_span(),
         );
 
-        let filters = generate_field_filters(&field, "User");
+        let filters = generate_field_filters(&field, "User", None);
         let code = filters.to_string();
 
         assert!(code.contains("pub fn is_null"));
         assert!(code.contains("pub fn is_not_null"));
     }
+
+    #[test]
+    fn test_generate_range_field_filters() {
+        let field = Field::new(
+            make_ident("seats"),
+            FieldType::Scalar(ScalarType::IntRange),
+            TypeModifier::Required,
+            vec![],
+            make_span(),
+        );
+
+        let code = generate_field_filters(&field, "Booking", None).to_string();
+        assert!(code.contains("pub fn contains (value : i32)"));
+        assert!(code.contains("pub fn overlaps"));
+        assert!(code.contains("pub fn adjacent"));
+        assert!(code.contains("Range (prax_query :: range :: RangeFilter)"));
+        assert!(!code.contains("emulated"));
+
+        let code =
+            generate_field_filters(&field, "Booking", Some(&DatabaseProvider::MySQL)).to_string();
+        assert!(code.contains("RangeFilter :: overlaps (range) . emulated ()"));
+    }
+
+    #[test]
+    fn test_generate_network_field_filters() {
+        let field = Field::new(
+            make_ident("subnet"),
+            FieldType::Scalar(ScalarType::Cidr),
+            TypeModifier::Required,
+            vec![],
+            make_span(),
+        );
+
+        let code = generate_field_filters(&field, "Device", None).to_string();
+        assert!(code.contains("pub fn contained_in"));
+        assert!(code.contains("pub fn same_family"));
+        assert!(code.contains("Network (prax_query :: network :: NetworkFilter)"));
+        assert!(code.contains("Equals (ipnetwork :: IpNetwork)"));
+
+        let code =
+            generate_field_filters(&field, "Device", Some(&DatabaseProvider::MySQL)).to_string();
+        assert!(code.contains("NetworkFilter :: contains (addr) . emulated ()"));
+    }
+
+    #[test]
+    fn test_generate_spatial_field_filters() {
+        let field = Field::new(
+            make_ident("location"),
+            FieldType::Scalar(ScalarType::Geography),
+            TypeModifier::Required,
+            vec![],
+            make_span(),
+        );
+
+        let code = generate_field_filters(&field, "Store", None).to_string();
+        assert!(code.contains("pub fn within"));
+        assert!(code.contains("SpatialFilter :: dwithin (other , distance) . geography ()"));
+        assert!(code.contains("Spatial (prax_query :: spatial :: SpatialFilter)"));
+        assert!(!code.contains("In (Vec"));
+    }
+
+    #[test]
+    fn test_generate_money_field_filters() {
+        let field = Field::new(
+            make_ident("total"),
+            FieldType::Scalar(ScalarType::Money),
+            TypeModifier::Required,
+            vec![],
+            make_span(),
+        );
+
+        let code = generate_field_filters(&field, "Order", None).to_string();
+        assert!(
+            code.contains(
+                "WhereOp :: Money (prax_query :: money :: MoneyFilter :: equals (value) ,)"
+            )
+        );
+        assert!(code.contains("MoneyFilter :: gte (value)"));
+        assert!(code.contains("pub fn currency"));
+        assert!(code.contains("Money (prax_query :: money :: MoneyFilter)"));
+        assert!(!code.contains("WhereOp :: Equals (value)"));
+        assert!(!code.contains("In (Vec"));
+    }
+
+    #[test]
+    fn test_generate_json_field_filters() {
+        let field = Field::new(
+            make_ident("metadata"),
+            FieldType::Scalar(ScalarType::Json),
+            TypeModifier::Required,
+            vec![],
+            make_span(),
+        );
+
+        let code = generate_field_filters(&field, "User", None).to_string();
+        assert!(code.contains("pub fn path"));
+        assert!(code.contains("pub fn array_contains"));
+        assert!(code.contains("pub fn string_contains"));
+        assert!(code.contains("pub fn has_key"));
+        assert!(code.contains("Json (prax_query :: json :: JsonFieldFilter)"));
+        assert!(code.contains("filter . at_path (self . 0) ,"));
+
+        let provider = DatabaseProvider::SQLite;
+        let code = generate_field_filters(&field, "User", Some(&provider)).to_string();
+        assert!(code.contains(
+            "filter . at_path (self . 0) . for_database (prax_query :: sql :: DatabaseType :: SQLite)"
+        ));
+    }
 }
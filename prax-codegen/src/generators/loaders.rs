@@ -0,0 +1,341 @@
+//! Code generation for relation loaders: async-graphql batch loaders and
+//! the includes of `find_unique`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use prax_schema::ast::{Field, FieldType, Model, Schema, TypeModifier};
+
+use super::{pascal_ident, snake_ident};
+use crate::types::field_type_to_rust;
+
+/// How a relation's records are keyed.
+struct LoadedRelation<'a> {
+    /// The relation field.
+    field: &'a Field,
+    /// The related model.
+    target: &'a Model,
+    /// Field of this model holding the key.
+    key: &'a Field,
+    /// Field of the related model matching the key.
+    target_key: &'a Field,
+    /// Whether the relation loads many records per key.
+    many: bool,
+}
+
+/// Generate a `Loader` per relation of a model, for async-graphql's
+/// `DataLoader`.
+///
+/// Implicit many-to-many relations and relations over composite keys are
+/// skipped.
+pub fn generate_batch_loaders(model: &Model, schema: &Schema) -> TokenStream {
+    let loaders = model
+        .fields
+        .values()
+        .filter_map(|field| loaded_relation(model, field, schema))
+        .map(|relation| generate_loader(model, &relation));
+
+    quote! { #(#loaders)* }
+}
+
+/// Resolve the keys of a relation field.
+fn loaded_relation<'a>(
+    model: &'a Model,
+    field: &'a Field,
+    schema: &'a Schema,
+) -> Option<LoadedRelation<'a>> {
+    let FieldType::Model(target_name) = &field.field_type else {
+        return None;
+    };
+    let target = schema.get_model(target_name)?;
+    let relation = field.extract_attributes().relation;
+
+    // Owning side: the foreign key is on this model
+    if let Some(relation) = relation.as_ref().filter(|r| !r.fields.is_empty()) {
+        if relation.fields.len() != 1 {
+            return None;
+        }
+        let key = model.fields.get(relation.fields[0].as_str())?;
+        let target_key = match relation.references.first() {
+            Some(name) => target.fields.get(name.as_str())?,
+            None => single_id(target)?,
+        };
+        return Some(LoadedRelation {
+            field,
+            target,
+            key,
+            target_key,
+            many: false,
+        });
+    }
+
+    // Other side: the foreign key is on the related model
+    let relation_name = relation.and_then(|r| r.name);
+    let back_relation = target.fields.values().find_map(|f| match &f.field_type {
+        FieldType::Model(name) if name == model.name() => f
+            .extract_attributes()
+            .relation
+            .filter(|r| r.fields.len() == 1 && r.name == relation_name),
+        _ => None,
+    })?;
+    let target_key = target.fields.get(back_relation.fields[0].as_str())?;
+    let key = match back_relation.references.first() {
+        Some(name) => model.fields.get(name.as_str())?,
+        None => single_id(model)?,
+    };
+
+    Some(LoadedRelation {
+        field,
+        target,
+        key,
+        target_key,
+        many: field.modifier.is_list(),
+    })
+}
+
+/// Get a model's primary key field, unless the key is composite.
+fn single_id(model: &Model) -> Option<&Field> {
+    match model.id_fields().as_slice() {
+        [field] => Some(*field),
+        _ => None,
+    }
+}
+
+/// Generate the loader of one relation.
+fn generate_loader(model: &Model, relation: &LoadedRelation<'_>) -> TokenStream {
+    let loader = format_ident!(
+        "{}{}Loader",
+        pascal_ident(model.name()),
+        pascal_ident(relation.field.name())
+    );
+    let target_module = snake_ident(relation.target.name());
+    let target_name = pascal_ident(relation.target.name());
+    let target = quote! { super::#target_module::#target_name };
+    let key_type = field_type_to_rust(&relation.key.field_type, &TypeModifier::Required);
+    let target_key = snake_ident(relation.target_key.name());
+    let column = relation
+        .target_key
+        .extract_attributes()
+        .map
+        .unwrap_or_else(|| relation.target_key.name().to_string());
+    let doc = format!(
+        " Batch loader for `{}.{}`, for async-graphql's `DataLoader`.",
+        model.name(),
+        relation.field.name()
+    );
+
+    let (value, load) = if relation.many {
+        (
+            quote! { Vec<#target> },
+            quote! { prax_query::lazy::OneToManyLoader::load_batch },
+        )
+    } else {
+        (
+            quote! { #target },
+            quote! { prax_query::lazy::ManyToOneLoader::load_batch },
+        )
+    };
+
+    quote! {
+        #[doc = #doc]
+        ///
+        /// The keys requested within one tick are loaded with a single `IN` query.
+        pub struct #loader<E: prax_query::traits::QueryEngine> {
+            engine: E,
+        }
+
+        impl<E: prax_query::traits::QueryEngine> #loader<E> {
+            /// Create a loader running its queries on `engine`.
+            pub fn new(engine: E) -> Self {
+                Self { engine }
+            }
+        }
+
+        impl<E: prax_query::traits::QueryEngine> async_graphql::dataloader::Loader<#key_type>
+            for #loader<E>
+        {
+            type Value = #value;
+            type Error = std::sync::Arc<prax_query::QueryError>;
+
+            async fn load(
+                &self,
+                keys: &[#key_type],
+            ) -> Result<std::collections::HashMap<#key_type, Self::Value>, Self::Error> {
+                #load(&self.engine, #column, keys, |record: &#target| -> Option<#key_type> {
+                    record.#target_key.clone().into()
+                })
+                .await
+                .map_err(std::sync::Arc::new)
+            }
+        }
+    }
+}
+
+/// Generate `fetch()`, the include of a relation field for
+/// [`FindUniqueOperation::include`](prax_query::operations::FindUniqueOperation::include),
+/// placed in the relation's field module.
+///
+/// Nothing is generated for relations the batch loaders skip.
+pub fn generate_include_load(model: &Model, field: &Field, schema: &Schema) -> TokenStream {
+    let Some(relation) = loaded_relation(model, field, schema) else {
+        return TokenStream::new();
+    };
+
+    let model_name = pascal_ident(model.name());
+    let target_module = snake_ident(relation.target.name());
+    let target_name = pascal_ident(relation.target.name());
+    let target = quote! { super::super::#target_module::#target_name };
+    let key = snake_ident(relation.key.name());
+    let key_type = field_type_to_rust(&relation.key.field_type, &TypeModifier::Required);
+    let target_key = snake_ident(relation.target_key.name());
+    let column = relation
+        .target_key
+        .extract_attributes()
+        .map
+        .unwrap_or_else(|| relation.target_key.name().to_string());
+    let relation_name = field.name();
+    let doc = format!(
+        " Include `{}.{}`, loaded on `engine` concurrently with the other includes.",
+        model.name(),
+        relation_name
+    );
+    let taken_as = format!(
+        " Take it from `LoadedRecord::relations` as `{}<{}>`.",
+        if relation.many { "Vec" } else { "Option" },
+        relation.target.name()
+    );
+
+    let (value, load, missing) = if relation.many {
+        (
+            quote! { Vec<#target> },
+            quote! { prax_query::lazy::OneToManyLoader::load_batch },
+            quote! { Vec::new() },
+        )
+    } else {
+        (
+            quote! { Option<#target> },
+            quote! { prax_query::lazy::ManyToOneLoader::load_batch },
+            quote! { None },
+        )
+    };
+    let found = if relation.many {
+        quote! { loaded.remove(&key).unwrap_or_default() }
+    } else {
+        quote! { loaded.remove(&key) }
+    };
+
+    quote! {
+        #[doc = #doc]
+        ///
+        #[doc = #taken_as]
+        pub fn fetch<E: prax_query::traits::QueryEngine>(
+            engine: E,
+        ) -> prax_query::relations::IncludeLoad<super::#model_name> {
+            prax_query::relations::IncludeLoad::new(
+                prax_query::relations::IncludeSpec::new(#relation_name),
+                move |parent: std::sync::Arc<super::#model_name>| async move {
+                    let key: Option<#key_type> = parent.#key.clone().into();
+                    let Some(key) = key else {
+                        return Ok::<#value, prax_query::QueryError>(#missing);
+                    };
+                    let mut loaded = #load(
+                        &engine,
+                        #column,
+                        std::slice::from_ref(&key),
+                        |record: &#target| -> Option<#key_type> { record.#target_key.clone().into() },
+                    )
+                    .await?;
+                    Ok(#found)
+                },
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_batch_loaders() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                posts Post[]
+            }
+
+            model Post {
+                id       Int  @id @auto
+                authorId Int? @map("author_id")
+                author   User? @relation(fields: [authorId], references: [id])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let user = generate_batch_loaders(schema.get_model("User").unwrap(), &schema).to_string();
+        assert!(
+            user.contains("pub struct UserPostsLoader < E : prax_query :: traits :: QueryEngine >")
+        );
+        assert!(
+            user.contains(
+                "async_graphql :: dataloader :: Loader < i32 > for UserPostsLoader < E >"
+            )
+        );
+        assert!(user.contains("type Value = Vec < super :: post :: Post >"));
+        assert!(user.contains(
+            "prax_query :: lazy :: OneToManyLoader :: load_batch (& self . engine , \"author_id\" , keys"
+        ));
+        assert!(user.contains("record . author_id . clone () . into ()"));
+
+        let post = generate_batch_loaders(schema.get_model("Post").unwrap(), &schema).to_string();
+        assert!(post.contains("pub struct PostAuthorLoader"));
+        assert!(post.contains("type Value = super :: user :: User"));
+        assert!(post.contains(
+            "prax_query :: lazy :: ManyToOneLoader :: load_batch (& self . engine , \"id\" , keys"
+        ));
+    }
+    #[test]
+    fn test_generate_include_load() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                posts Post[]
+            }
+
+            model Post {
+                id       Int  @id @auto
+                authorId Int? @map("author_id")
+                author   User? @relation(fields: [authorId], references: [id])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        let posts =
+            generate_include_load(user, user.get_field("posts").unwrap(), &schema).to_string();
+        assert!(posts.contains(
+            "pub fn fetch < E : prax_query :: traits :: QueryEngine > (engine : E ,) -> prax_query :: relations :: IncludeLoad < super :: User >"
+        ));
+        assert!(posts.contains("IncludeSpec :: new (\"posts\")"));
+        assert!(posts.contains("let key : Option < i32 > = parent . id . clone () . into ()"));
+        assert!(posts.contains(
+            "prax_query :: lazy :: OneToManyLoader :: load_batch (& engine , \"author_id\" , std :: slice :: from_ref (& key)"
+        ));
+        assert!(posts.contains("loaded . remove (& key) . unwrap_or_default ()"));
+        assert!(generate_include_load(user, user.get_field("id").unwrap(), &schema).is_empty());
+
+        let post = schema.get_model("Post").unwrap();
+        let author =
+            generate_include_load(post, post.get_field("author").unwrap(), &schema).to_string();
+        assert!(author.contains("parent . author_id . clone () . into ()"));
+        assert!(
+            author
+                .contains("prax_query :: lazy :: ManyToOneLoader :: load_batch (& engine , \"id\"")
+        );
+        assert!(author.contains("Option < super :: super :: user :: User >"));
+    }
+}
@@ -1,18 +1,22 @@
 //! Code generators for Prax models, enums, types, and views.
 
+mod client;
 mod derive;
 mod enum_gen;
+mod erasure;
 mod fields;
 mod filters;
+mod loaders;
 mod model;
 mod type_gen;
 mod view;
 
+pub use client::generate_client;
 pub use derive::derive_model_impl;
 pub use enum_gen::generate_enum_module;
 #[allow(unused_imports)]
 pub use model::generate_model_module;
-pub use model::generate_model_module_with_style;
+pub use model::generate_model_module_with_plugins;
 pub use type_gen::generate_type_module;
 pub use view::generate_view_module;
 
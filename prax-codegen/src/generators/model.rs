@@ -1,16 +1,19 @@
 //! Code generation for Prax models.
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 
 use prax_schema::ModelStyle;
-use prax_schema::ast::{FieldType, Model, Schema, TypeModifier};
+use prax_schema::ast::{Field, FieldType, Index, Model, RetentionStrategy, Schema, TypeModifier};
 
+use super::erasure::generate_erasure;
 use super::fields::{
-    generate_field_module, generate_order_by_param, generate_select_param, generate_set_param,
+    generate_field_mask, generate_field_module, generate_order_by_param, generate_select_param,
+    generate_set_param,
 };
+use super::loaders::generate_batch_loaders;
 use super::{generate_doc_comment, pascal_ident, snake_ident};
-use crate::types::field_type_to_rust;
+use crate::types::{field_type_to_rust, to_screaming_snake, to_snake_case};
 
 /// Generate the complete module for a model.
 ///
@@ -26,14 +29,25 @@ pub fn generate_model_module_with_style(
     model: &Model,
     schema: &Schema,
     model_style: ModelStyle,
+) -> Result<TokenStream, syn::Error> {
+    generate_model_module_with_plugins(model, schema, model_style, TokenStream::new())
+}
+
+/// Generate the complete module for a model, placing the tokens emitted by
+/// plugin model hooks inside it.
+pub fn generate_model_module_with_plugins(
+    model: &Model,
+    schema: &Schema,
+    model_style: ModelStyle,
+    plugin_tokens: TokenStream,
 ) -> Result<TokenStream, syn::Error> {
     let model_name = pascal_ident(model.name());
     let module_name = snake_ident(model.name());
 
     let doc = generate_doc_comment(model.documentation.as_ref().map(|d| d.text.as_str()));
 
-    // Get database table name
-    let table_name = model.table_name().to_string();
+    // Get database table name, qualified with its `@@schema` if any
+    let table_name = model.qualified_table_name();
     let table_name_str = table_name.as_str();
 
     // Get primary key field(s)
@@ -73,7 +87,10 @@ pub fn generate_model_module_with_style(
         .values()
         .filter(|f| {
             let attrs = f.extract_attributes();
-            !attrs.is_auto && !attrs.is_updated_at && !matches!(f.field_type, FieldType::Model(_))
+            !attrs.is_auto
+                && !attrs.is_updated_at
+                && !f.is_virtual()
+                && !matches!(f.field_type, FieldType::Model(_))
         })
         .map(|field| {
             let field_name = snake_ident(field.name());
@@ -92,13 +109,46 @@ pub fn generate_model_module_with_style(
         })
         .collect();
 
+    // Relations whose foreign key lives on the related model accept nested writes
+    let nested = nested_relations(model, schema);
+    let graphql_skip = if model_style.is_graphql() {
+        quote! { #[graphql(skip)] }
+    } else {
+        TokenStream::new()
+    };
+
+    let nested_create_fields: Vec<_> = nested
+        .iter()
+        .map(|relation| {
+            let field_name = snake_ident(relation.field.name());
+            let target = snake_ident(relation.target.name());
+            let doc = format!(
+                " Nested writes for the `{}` relation.",
+                relation.field.name()
+            );
+
+            quote! {
+                #[doc = #doc]
+                #[serde(skip)]
+                #graphql_skip
+                pub #field_name: Option<super::_prax_prelude::NestedCreate<
+                    super::#target::CreateInput,
+                    super::#target::WhereParam,
+                >>
+            }
+        })
+        .collect();
+
     // Generate UpdateInput fields (all optional)
     let update_fields: Vec<_> = model
         .fields
         .values()
         .filter(|f| {
             let attrs = f.extract_attributes();
-            !attrs.is_auto && !attrs.is_updated_at && !matches!(f.field_type, FieldType::Model(_))
+            !attrs.is_auto
+                && !attrs.is_updated_at
+                && !f.is_virtual()
+                && !matches!(f.field_type, FieldType::Model(_))
         })
         .map(|field| {
             let field_name = snake_ident(field.name());
@@ -110,20 +160,108 @@ pub fn generate_model_module_with_style(
         })
         .collect();
 
+    // Build an UpdateInput from the changed fields of two records. List
+    // fields are left out, as UpdateInput holds a single element for them,
+    // and a cleared optional field can't be told from an unchanged one
+    let (update_diff_fields, update_mask_fields): (Vec<_>, Vec<_>) = model
+        .fields
+        .values()
+        .filter(|f| {
+            let attrs = f.extract_attributes();
+            !attrs.is_auto
+                && !attrs.is_updated_at
+                && !f.is_virtual()
+                && !matches!(f.field_type, FieldType::Model(_))
+        })
+        .map(|field| {
+            let field_name = snake_ident(field.name());
+            let flag = format_ident!("{}", to_screaming_snake(field.name()));
+            let value = if field.modifier.is_optional() {
+                quote! { new.#field_name.clone() }
+            } else {
+                quote! { Some(new.#field_name.clone()) }
+            };
+            let from_diff = (!field.modifier.is_list()).then(|| {
+                quote! {
+                    if changed.contains(FieldMask::#flag) {
+                        input.#field_name = #value;
+                    }
+                }
+            });
+            let mask = quote! {
+                if self.#field_name.is_some() {
+                    mask.insert(FieldMask::#flag);
+                }
+            };
+            (from_diff, mask)
+        })
+        .unzip();
+    let update_diff_fields: Vec<_> = update_diff_fields.into_iter().flatten().collect();
+    let from_diff_body = if update_diff_fields.is_empty() {
+        quote! {
+            let _ = (old, new);
+            Self::default()
+        }
+    } else {
+        quote! {
+            let changed = prax_query::diff::Diffable::changed_fields(old, new);
+            let mut input = Self::default();
+            #(#update_diff_fields)*
+            input
+        }
+    };
+    let field_mask_body = if update_mask_fields.is_empty() {
+        quote! { FieldMask::default() }
+    } else {
+        quote! {
+            let mut mask = FieldMask::default();
+            #(#update_mask_fields)*
+            mask
+        }
+    };
+    let field_mask_alias = format_ident!("{}FieldMask", model_name);
+
+    let nested_update_fields: Vec<_> = nested
+        .iter()
+        .map(|relation| {
+            let field_name = snake_ident(relation.field.name());
+            let target = snake_ident(relation.target.name());
+            let doc = format!(
+                " Nested writes for the `{}` relation.",
+                relation.field.name()
+            );
+
+            quote! {
+                #[doc = #doc]
+                #[serde(skip)]
+                #graphql_skip
+                pub #field_name: Option<super::_prax_prelude::NestedUpdate<
+                    super::#target::CreateInput,
+                    super::#target::UpdateInput,
+                    super::#target::WhereParam,
+                >>
+            }
+        })
+        .collect();
+
     // Generate field modules
     let field_modules: Vec<_> = model
         .fields
         .values()
-        .map(|field| generate_field_module(field, model))
+        .map(|field| generate_field_module(field, model, schema))
         .collect();
 
     // Generate where param enum
     let where_param = generate_where_param(model);
 
+    // Generate accessors for multi-column unique constraints
+    let compound_uniques = generate_compound_uniques(model);
+
     // Generate select, order by, and set params
     let select_param = generate_select_param(model);
     let order_by_param = generate_order_by_param(model);
     let set_param = generate_set_param(model);
+    let field_mask = generate_field_mask(model);
 
     // Generate query builder
     let query_builder = generate_query_builder(model, &table_name);
@@ -132,7 +270,107 @@ pub fn generate_model_module_with_style(
     let precompiled_sql = generate_precompiled_sql(model, &table_name);
 
     // Generate relation helpers
-    let relation_helpers = generate_relation_helpers(model, schema);
+    let relation_helpers = generate_relation_helpers(model, &nested);
+
+    // Generate batch loaders for GraphQL resolvers
+    let batch_loaders = if model_style.is_graphql() {
+        generate_batch_loaders(model, schema)
+    } else {
+        TokenStream::new()
+    };
+
+    // Generate the retention policy, if declared
+    let retention_policy = generate_retention_policy(model);
+    let ttl_policy = generate_ttl_policy(model);
+    let model_ttl_column = match model.ttl_field() {
+        Some(field) => {
+            let field_mod = snake_ident(field.name());
+            quote! { const TTL_COLUMN: Option<&'static str> = Some(#field_mod::COLUMN); }
+        }
+        None => TokenStream::new(),
+    };
+
+    // Generate subject erasure, if this model identifies data subjects
+    let erasure = generate_erasure(model, schema);
+
+    // Generate history accessors, if versioned
+    let history = generate_history(model);
+
+    // Generate the audited table, if audited
+    let audit = generate_audit(model);
+
+    // Generate transition helpers for state machine fields
+    let state_machines = generate_state_machines(model, schema);
+
+    // Generate hierarchy queries, if a tree
+    let tree = generate_tree(model);
+    let id_codec = generate_id_codec(model);
+
+    // Computed fields, selected as expressions in reads
+    let virtual_fields: Vec<_> = model
+        .fields
+        .values()
+        .filter_map(|f| {
+            let expr = f.virtual_expr()?;
+            let field_mod = snake_ident(f.name());
+            Some(quote! { (#field_mod::COLUMN, #expr) })
+        })
+        .collect();
+    let virtual_fields = if virtual_fields.is_empty() {
+        TokenStream::new()
+    } else {
+        quote! {
+            /// Computed fields (`@virtual`) as `(column, SQL expression)` pairs.
+            pub const VIRTUAL_FIELDS: &[(&str, &str)] = &[#(#virtual_fields),*];
+        }
+    };
+
+    // Stored columns, for running the model through prax-query operations
+    let stored_columns: Vec<_> = model
+        .fields
+        .values()
+        .filter(|f| !f.is_virtual() && !matches!(f.field_type, FieldType::Model(_)))
+        .map(|f| {
+            let field_mod = snake_ident(f.name());
+            quote! { #field_mod::COLUMN }
+        })
+        .collect();
+    let column_specs: Vec<_> = model
+        .fields
+        .values()
+        .filter(|f| !matches!(f.field_type, FieldType::Model(_)))
+        .map(|f| {
+            let field_mod = snake_ident(f.name());
+            let kind = column_kind(f);
+            quote! {
+                prax_query::drift::ColumnSpec::new(
+                    #field_mod::COLUMN,
+                    prax_query::drift::ColumnKind::#kind,
+                )
+            }
+        })
+        .collect();
+    let indexes = model_indexes(model);
+    let model_virtual_fields = if model.fields.values().any(|f| f.is_virtual()) {
+        quote! {
+            const VIRTUAL_FIELDS: &'static [(&'static str, &'static str)] = VIRTUAL_FIELDS;
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    // Generate translation accessors for translatable fields
+    let translations = generate_translations(model);
+    let translations_field = if model.translatable_fields().is_empty() {
+        TokenStream::new()
+    } else {
+        quote! {
+            /// Translations loaded by `load_translations()`.
+            #[serde(skip)]
+            #graphql_skip
+            pub translations: prax_query::translation::TranslationSet,
+        }
+    };
 
     // Generate GraphQL derives if model_style is GraphQL
     let model_name_str = model.name();
@@ -170,11 +408,14 @@ pub fn generate_model_module_with_style(
             /// Primary key column(s).
             pub const PRIMARY_KEY: &[&str] = &[#(#pk_field_names),*];
 
+            #virtual_fields
+
             #doc
             /// Represents a row from the `#table_name_str` table.
             #model_derives
             pub struct #model_name {
                 #(#data_fields,)*
+                #translations_field
             }
 
             impl super::_prax_prelude::PraxModel for #model_name {
@@ -182,16 +423,45 @@ pub fn generate_model_module_with_style(
                 const PRIMARY_KEY: &'static [&'static str] = PRIMARY_KEY;
             }
 
+            impl prax_query::traits::Model for #model_name {
+                const MODEL_NAME: &'static str = #model_name_str;
+                const TABLE_NAME: &'static str = TABLE_NAME;
+                const PRIMARY_KEY: &'static [&'static str] = PRIMARY_KEY;
+                const COLUMNS: &'static [&'static str] = &[#(#stored_columns),*];
+                #model_virtual_fields
+                const COLUMN_SPECS: &'static [prax_query::drift::ColumnSpec] = &[#(#column_specs),*];
+                const INDEXES: &'static [prax_query::verify::IndexSpec] = &[#(#indexes),*];
+                #model_ttl_column
+            }
+
             /// Input type for creating a new record.
             #create_input_derives
             pub struct CreateInput {
                 #(#create_fields,)*
+                #(#nested_create_fields,)*
             }
 
             /// Input type for updating a record.
             #update_input_derives
             pub struct UpdateInput {
                 #(#update_fields,)*
+                #(#nested_update_fields,)*
+            }
+
+            impl UpdateInput {
+                /// Build the update turning `old` into `new`, setting only the
+                /// fields that differ.
+                ///
+                /// List fields, and optional fields cleared in `new`, are left
+                /// unset; [`FieldMask::set_params`] covers every field.
+                pub fn from_diff(old: &#model_name, new: &#model_name) -> Self {
+                    #from_diff_body
+                }
+
+                /// Get the mask of the fields this update sets.
+                pub fn field_mask(&self) -> FieldMask {
+                    #field_mask_body
+                }
             }
 
             // Field modules
@@ -200,11 +470,17 @@ pub fn generate_model_module_with_style(
             // Where param enum
             #where_param
 
+            // Compound unique accessors
+            #compound_uniques
+
             // Select, OrderBy, and Set params
             #select_param
             #order_by_param
             #set_param
 
+            // Field mask for diffing records
+            #field_mask
+
             // Query builder
             #query_builder
 
@@ -213,13 +489,183 @@ pub fn generate_model_module_with_style(
 
             // Relation helpers
             #relation_helpers
+
+            // Batch loaders
+            #batch_loaders
+
+            // Retention policy
+            #retention_policy
+            #ttl_policy
+
+            // Subject erasure
+            #erasure
+
+            // Version history
+            #history
+
+            // Audit logging
+            #audit
+
+            // State machine transitions
+            #state_machines
+
+            // Hierarchy queries
+            #tree
+
+            // Public ids
+            #id_codec
+
+            // Translations
+            #translations
+
+            // Plugin output
+            #plugin_tokens
         }
 
         // Re-export the model type at the parent level
         pub use #module_name::#model_name;
+        pub use #module_name::FieldMask as #field_mask_alias;
     })
 }
 
+/// Get the `IndexSpec`s of a model's `@unique` fields and `@@unique` and
+/// `@@index` attributes.
+fn model_indexes(model: &Model) -> Vec<TokenStream> {
+    let spec = |fields: &[&str], unique: bool| {
+        let columns = fields.iter().map(|name| {
+            let field_mod = snake_ident(name);
+            quote! { #field_mod::COLUMN }
+        });
+        quote! { prax_query::verify::IndexSpec::new(&[#(#columns),*], #unique) }
+    };
+
+    let mut indexes: Vec<_> = model
+        .fields
+        .values()
+        .filter(|f| f.is_unique() && !f.is_id())
+        .map(|f| spec(&[f.name()], true))
+        .collect();
+
+    for attr in &model.attributes {
+        let unique = match attr.name() {
+            "unique" => true,
+            "index" => false,
+            _ => continue,
+        };
+        if let Some(prax_schema::ast::AttributeValue::FieldRefList(fields)) = attr.first_arg() {
+            let fields: Vec<&str> = fields.iter().map(|f| f.as_str()).collect();
+            if fields.iter().all(|f| model.fields.contains_key(*f)) {
+                indexes.push(spec(&fields, unique));
+            }
+        }
+    }
+
+    indexes
+}
+
+/// Generate an accessor per multi-column `@@unique` or `@@id`.
+///
+/// `@@unique([email, tenantId])` becomes `email_tenant_id(email, tenant_id)`,
+/// a `WhereParam` matching one record for `find_unique` and `connect`, next
+/// to a module of the same name holding the columns and the upsert conflict
+/// target. A `name:` argument overrides the accessor name.
+///
+/// A `nullsNotDistinct: true` constraint's conflict target matches NULLs
+/// as equal. PostgreSQL can't use a deferrable constraint as the conflict
+/// target, so those get no `conflict_target()`.
+fn generate_compound_uniques(model: &Model) -> TokenStream {
+    let mut names = std::collections::HashSet::new();
+
+    model
+        .attributes
+        .iter()
+        .filter(|attr| matches!(attr.name(), "unique" | "id"))
+        .filter_map(|attr| {
+            let Some(prax_schema::ast::AttributeValue::FieldRefList(refs)) = attr.first_arg()
+            else {
+                return None;
+            };
+            let fields: Vec<&Field> = refs
+                .iter()
+                .map(|name| model.fields.get(name.as_str()))
+                .collect::<Option<_>>()?;
+            if fields.len() < 2
+                || fields
+                    .iter()
+                    .any(|f| matches!(f.field_type, FieldType::Model(_)))
+            {
+                return None;
+            }
+
+            let name = attr
+                .get_arg("name")
+                .and_then(|v| v.as_string())
+                .map(str::to_string)
+                .unwrap_or_else(|| {
+                    fields
+                        .iter()
+                        .map(|f| to_snake_case(f.name()))
+                        .collect::<Vec<_>>()
+                        .join("_")
+                });
+            if !names.insert(name.clone()) {
+                return None;
+            }
+
+            let accessor = snake_ident(&name);
+            let field_mods: Vec<_> = fields.iter().map(|f| snake_ident(f.name())).collect();
+            let params = fields.iter().zip(&field_mods).map(|(f, field_mod)| {
+                let ty = field_type_to_rust(&f.field_type, &TypeModifier::Required);
+                quote! { #field_mod: #ty }
+            });
+            let doc = format!(
+                " Match the record with these values of the unique `({})`.",
+                refs.iter()
+                    .map(|r| r.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+
+            let constraint = attr
+                .is("unique")
+                .then(|| Index::from_attribute(attr).ok())
+                .flatten();
+            let conflict_target = match &constraint {
+                Some(unique) if unique.deferrable.is_some() => quote! {},
+                Some(unique) if unique.nulls_not_distinct => quote! {
+                    /// The constraint as an upsert's conflict target.
+                    pub fn conflict_target() -> prax_query::upsert::ConflictTarget {
+                        prax_query::upsert::ConflictTarget::nulls_not_distinct(
+                            COLUMNS.iter().copied()
+                        )
+                    }
+                },
+                _ => quote! {
+                    /// The constraint as an upsert's conflict target.
+                    pub fn conflict_target() -> prax_query::upsert::ConflictTarget {
+                        prax_query::upsert::ConflictTarget::columns(COLUMNS.iter().copied())
+                    }
+                },
+            };
+
+            Some(quote! {
+                #[doc = #doc]
+                pub fn #accessor(#(#params),*) -> WhereParam {
+                    WhereParam::And(vec![#(#field_mods::equals(#field_mods)),*])
+                }
+
+                #[doc = #doc]
+                pub mod #accessor {
+                    /// Columns of the constraint.
+                    pub const COLUMNS: &[&str] = &[#(super::#field_mods::COLUMN),*];
+
+                    #conflict_target
+                }
+            })
+        })
+        .collect()
+}
+
 /// Get the primary key field names for a model.
 fn get_primary_key_fields(model: &Model) -> Vec<String> {
     // Check for composite @@id
@@ -238,6 +684,444 @@ fn get_primary_key_fields(model: &Model) -> Vec<String> {
         .collect()
 }
 
+/// Get the primary key column names for a model.
+fn get_primary_key_columns(model: &Model) -> Vec<String> {
+    get_primary_key_fields(model)
+        .into_iter()
+        .map(|name| {
+            model
+                .fields
+                .get(name.as_str())
+                .and_then(|f| f.extract_attributes().map)
+                .unwrap_or(name)
+        })
+        .collect()
+}
+
+/// Generate `retention_policy()` for a model with `@@retention`.
+fn generate_retention_policy(model: &Model) -> TokenStream {
+    let Some(policy) = model.retention_policy() else {
+        return quote! {};
+    };
+
+    let field_mod = snake_ident(&policy.field);
+    let keep_secs = policy.keep.as_secs();
+    let strategy = match policy.strategy {
+        RetentionStrategy::Delete => quote! {},
+        RetentionStrategy::Archive => quote! {
+            .archive_to_table(format!("{}_archive", TABLE_NAME))
+        },
+    };
+
+    quote! {
+        /// Data retention policy declared with `@@retention`.
+        pub fn retention_policy() -> prax_query::retention::RetentionPolicy {
+            prax_query::retention::RetentionPolicy::new(
+                TABLE_NAME,
+                #field_mod::COLUMN,
+                std::time::Duration::from_secs(#keep_secs),
+            )
+            .key(PRIMARY_KEY.iter().copied())
+            #strategy
+        }
+    }
+}
+
+/// Generate `ttl_policy()` for a model with `@@ttl`, purging rows once
+/// they expire.
+fn generate_ttl_policy(model: &Model) -> TokenStream {
+    let Some(field) = model.ttl_field() else {
+        return quote! {};
+    };
+    let field_mod = snake_ident(field.name());
+
+    quote! {
+        /// Purge policy deleting rows past their `@@ttl` expiry, for a
+        /// `RetentionRunner`.
+        pub fn ttl_policy() -> prax_query::retention::RetentionPolicy {
+            prax_query::retention::RetentionPolicy::new(
+                TABLE_NAME,
+                #field_mod::COLUMN,
+                std::time::Duration::ZERO,
+            )
+            .key(PRIMARY_KEY.iter().copied())
+        }
+    }
+}
+
+/// Generate `history_table()` and `history()` for a model with `@@versioned`.
+fn generate_history(model: &Model) -> TokenStream {
+    let Some(history_table) = model.history_table_name() else {
+        return quote! {};
+    };
+
+    let model_name = pascal_ident(model.name());
+    let key_columns = get_primary_key_columns(model);
+
+    quote! {
+        /// History table recording every version of every record (`@@versioned`).
+        pub fn history_table() -> prax_query::versioning::HistoryTable {
+            prax_query::versioning::HistoryTable::new(#history_table, [#(#key_columns),*])
+        }
+
+        impl #model_name {
+            /// Query the recorded versions of this record.
+            pub fn history(&self) -> prax_query::versioning::HistoryQuery {
+                history_table().record_of(self)
+            }
+        }
+    }
+}
+
+/// Generate `audit_table()` for a model with `@@audited`.
+fn generate_audit(model: &Model) -> TokenStream {
+    if !model.is_audited() {
+        return quote! {};
+    }
+
+    let excluded: Vec<_> = model
+        .audit_excluded_fields()
+        .iter()
+        .map(|field| snake_ident(field.name()))
+        .collect();
+    let exclude = if excluded.is_empty() {
+        quote! {}
+    } else {
+        quote! { .exclude([#(#excluded::COLUMN),*]) }
+    };
+
+    quote! {
+        /// Table registration for `AuditMiddleware` (`@@audited`).
+        pub fn audit_table() -> prax_query::middleware::AuditedTable {
+            prax_query::middleware::AuditedTable::new(TABLE_NAME, PRIMARY_KEY.iter().copied())
+                #exclude
+        }
+    }
+}
+
+/// Generate `encode_id`/`decode_id` for a model with a single integer
+/// primary key, turning it into an opaque id for external APIs.
+fn generate_id_codec(model: &Model) -> TokenStream {
+    let pk_fields = get_primary_key_fields(model);
+    let [key] = pk_fields.as_slice() else {
+        return quote! {};
+    };
+    let Some(field) = model.get_field(key) else {
+        return quote! {};
+    };
+    let id_type = match &field.field_type {
+        FieldType::Scalar(prax_schema::ast::ScalarType::Int) => quote! { i32 },
+        FieldType::Scalar(prax_schema::ast::ScalarType::BigInt) => quote! { i64 },
+        _ => return quote! {},
+    };
+    if field.modifier != TypeModifier::Required {
+        return quote! {};
+    }
+
+    let model_name = pascal_ident(model.name());
+    let model_name_str = model.name();
+    let key_ident = snake_ident(key);
+
+    quote! {
+        /// Encode a primary key as an opaque id for external APIs.
+        ///
+        /// Keys must be non-negative.
+        pub fn encode_id(codec: &dyn prax_query::id_codec::IdCodec, id: #id_type) -> String {
+            codec.encode(id as u64)
+        }
+
+        /// Decode an id from [`encode_id`] into a primary key.
+        ///
+        /// Ids that don't decode are reported as a missing record.
+        pub fn decode_id(
+            codec: &dyn prax_query::id_codec::IdCodec,
+            id: &str,
+        ) -> prax_query::error::QueryResult<#id_type> {
+            codec
+                .decode(id)
+                .and_then(|id| #id_type::try_from(id).ok())
+                .ok_or_else(|| prax_query::error::QueryError::not_found(#model_name_str))
+        }
+
+        impl #model_name {
+            /// Get this record's primary key as an opaque id.
+            pub fn public_id(&self, codec: &dyn prax_query::id_codec::IdCodec) -> String {
+                encode_id(codec, self.#key_ident)
+            }
+        }
+    }
+}
+
+/// Generate the `TREE` constant and hierarchy queries for a model with `@@tree`.
+fn generate_tree(model: &Model) -> TokenStream {
+    let Some(tree) = model.tree() else {
+        return quote! {};
+    };
+    let (Some(key), Some(parent)) = (
+        model.id_fields().first().map(|f| f.name()),
+        model.get_field(&tree.parent),
+    ) else {
+        return quote! {};
+    };
+
+    let model_name = pascal_ident(model.name());
+    let key_ident = snake_ident(key);
+    let parent_ident = snake_ident(parent.name());
+    let storage = match tree.closure_table_name(model.table_name()) {
+        Some(closure) => quote! {
+            prax_query::tree::Tree::closure_table(TABLE_NAME, #key_ident::COLUMN, #parent_ident::COLUMN, #closure)
+        },
+        None => {
+            let path = prax_schema::ast::Tree::PATH_COLUMN;
+            quote! {
+                prax_query::tree::Tree::materialized_path(TABLE_NAME, #key_ident::COLUMN, #parent_ident::COLUMN, #path)
+            }
+        }
+    };
+
+    quote! {
+        /// Hierarchy of this model (`@@tree`).
+        pub const TREE: prax_query::tree::Tree = #storage;
+
+        impl #model_name {
+            /// Fetch this node's direct children.
+            pub async fn children<D: prax_query::raw::RawDatabase + ?Sized>(
+                &self,
+                db: &D,
+            ) -> prax_query::error::QueryResult<Vec<Self>> {
+                TREE.children(db, self).await
+            }
+
+            /// Fetch all of this node's descendants, nearest first.
+            pub async fn descendants<D: prax_query::raw::RawDatabase + ?Sized>(
+                &self,
+                db: &D,
+            ) -> prax_query::error::QueryResult<Vec<Self>> {
+                TREE.descendants(db, self).await
+            }
+
+            /// Fetch this node's ancestors, root first.
+            pub async fn ancestors<D: prax_query::raw::RawDatabase + ?Sized>(
+                &self,
+                db: &D,
+            ) -> prax_query::error::QueryResult<Vec<Self>> {
+                TREE.ancestors(db, self).await
+            }
+
+            /// Move this node and its subtree under `new_parent`, or make it a root.
+            ///
+            /// Fails without changes if `new_parent` is this node or one of its
+            /// descendants.
+            pub async fn move_subtree<D: prax_query::raw::RawDatabase + ?Sized>(
+                &mut self,
+                db: &D,
+                new_parent: Option<&Self>,
+            ) -> prax_query::error::QueryResult<()> {
+                TREE.move_subtree(db, TREE.key_of(&*self), new_parent.map(|p| TREE.key_of(p)))
+                    .await?;
+                self.#parent_ident = new_parent.map(|p| p.#key_ident.clone());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Generate the `TRANSLATIONS` constant, the `<Model>Translation` type and
+/// per-locale accessors for a model with `@translatable` fields.
+fn generate_translations(model: &Model) -> TokenStream {
+    let (Some(table), Some(key)) = (
+        model.translation_table_name(),
+        model.id_fields().first().map(|f| f.name()),
+    ) else {
+        return quote! {};
+    };
+
+    let model_name = pascal_ident(model.name());
+    let translation_name = format_ident!("{}Translation", model_name);
+    let key_ident = snake_ident(key);
+    let fields = model.translatable_fields();
+    let field_idents: Vec<_> = fields.iter().map(|f| snake_ident(f.name())).collect();
+
+    let accessors = fields.iter().zip(&field_idents).map(|(field, ident)| {
+        let method = format_ident!("{}_in", ident);
+        let doc = format!(
+            " Get `{}` in `locale`, falling back through its parent locales to the untranslated value.",
+            field.name()
+        );
+        if field.modifier.is_optional() {
+            quote! {
+                #[doc = #doc]
+                pub fn #method(&self, locale: &str) -> Option<&str> {
+                    self.translations
+                        .resolve(#ident::COLUMN, &[locale])
+                        .or(self.#ident.as_deref())
+                }
+            }
+        } else {
+            quote! {
+                #[doc = #doc]
+                pub fn #method(&self, locale: &str) -> &str {
+                    self.translations
+                        .resolve(#ident::COLUMN, &[locale])
+                        .unwrap_or(self.#ident.as_str())
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// Translation table of the `@translatable` fields.
+        pub const TRANSLATIONS: prax_query::translation::Translations =
+            prax_query::translation::Translations::new(
+                #table,
+                #key_ident::COLUMN,
+                &[#(#field_idents::COLUMN),*],
+            );
+
+        /// The translatable fields of a record in one locale.
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        pub struct #translation_name {
+            /// The locale, such as `de` or `de-AT`.
+            pub locale: String,
+            #(
+                pub #field_idents: Option<String>,
+            )*
+        }
+
+        impl #model_name {
+            #(#accessors)*
+
+            /// Load the translations of `records` in `locales`, including
+            /// their parent locales.
+            pub async fn load_translations<D: prax_query::raw::RawDatabase + ?Sized>(
+                db: &D,
+                records: &mut [Self],
+                locales: &[&str],
+            ) -> prax_query::error::QueryResult<()> {
+                let sets = TRANSLATIONS.load(db, &*records, locales).await?;
+                for (record, set) in records.iter_mut().zip(sets) {
+                    record.translations = set;
+                }
+                Ok(())
+            }
+
+            /// Replace this record's translation in `translation.locale`.
+            pub async fn save_translation<D: prax_query::raw::RawDatabase + ?Sized>(
+                &self,
+                db: &D,
+                translation: &#translation_name,
+            ) -> prax_query::error::QueryResult<()> {
+                let values = [#((#field_idents::COLUMN, translation.#field_idents.clone())),*];
+                TRANSLATIONS
+                    .save(db, TRANSLATIONS.key_of(self), &translation.locale, &values)
+                    .await
+            }
+
+            /// Delete this record's translation in `locale`.
+            pub async fn remove_translation<D: prax_query::raw::RawDatabase + ?Sized>(
+                &self,
+                db: &D,
+                locale: &str,
+            ) -> prax_query::error::QueryResult<()> {
+                TRANSLATIONS.remove(db, TRANSLATIONS.key_of(self), locale).await
+            }
+        }
+    }
+}
+
+/// Generate state machine constants and `transition_to()` helpers for
+/// fields with `@stateMachine`.
+///
+/// With several state machine fields, the helpers are named
+/// `transition_<field>_to()`.
+fn generate_state_machines(model: &Model, schema: &Schema) -> TokenStream {
+    let machines: Vec<_> = model
+        .fields
+        .values()
+        .filter_map(|field| {
+            let machine = field.state_machine()?;
+            let enum_def = schema.get_enum(field.field_type.type_name())?;
+            Some((field, machine, enum_def))
+        })
+        .collect();
+    if machines.is_empty() {
+        return TokenStream::new();
+    }
+
+    let model_name = pascal_ident(model.name());
+    let key_columns = get_primary_key_columns(model);
+    let single = machines.len() == 1;
+
+    let items = machines.iter().map(|(field, machine, enum_def)| {
+        let field_name = field.name();
+        let field_ident = snake_ident(field_name);
+        let const_ident = format_ident!("{}_STATE_MACHINE", field_ident.to_string().to_uppercase());
+        let method = if single {
+            format_ident!("transition_to")
+        } else {
+            format_ident!("transition_{}_to", field_ident)
+        };
+        let state_type = field_type_to_rust(&field.field_type, &TypeModifier::Required);
+        let db_value = |state: &str| {
+            enum_def
+                .get_variant(state)
+                .map(|v| v.db_value().to_string())
+                .unwrap_or_else(|| state.to_string())
+        };
+        let transitions = machine.transitions.iter().map(|(from, targets)| {
+            let from = db_value(from);
+            let targets = targets.iter().map(|t| db_value(t));
+            quote! { (#from, &[#(#targets),*]) }
+        });
+        let const_doc = format!(
+            " Allowed transitions of `{}` (`@stateMachine`).",
+            field_name
+        );
+        let method_doc = format!(
+            " Move `{}` to `to`, if `@stateMachine` allows it from the current value.",
+            field_name
+        );
+
+        quote! {
+            #[doc = #const_doc]
+            pub const #const_ident: prax_query::state_machine::StateMachine =
+                prax_query::state_machine::StateMachine::new(
+                    #field_name,
+                    #field_ident::COLUMN,
+                    &[#(#transitions),*],
+                );
+
+            impl #model_name {
+                #[doc = #method_doc]
+                ///
+                /// The update only applies while the stored value is unchanged, so
+                /// disallowed and concurrent transitions fail with an
+                /// `InvalidTransition` error.
+                pub async fn #method<E: prax_query::traits::QueryEngine>(
+                    &mut self,
+                    engine: &E,
+                    to: #state_type,
+                ) -> prax_query::error::QueryResult<()> {
+                    prax_query::state_machine::StateTransition::of(
+                        #const_ident,
+                        TABLE_NAME,
+                        &[#(#key_columns),*],
+                        &*self,
+                        self.#field_ident.as_str(),
+                        to.as_str(),
+                    )
+                    .exec(engine)
+                    .await?;
+                    self.#field_ident = to;
+                    Ok(())
+                }
+            }
+        }
+    });
+
+    quote! { #(#items)* }
+}
+
 /// Generate the WhereParam enum for a model.
 fn generate_where_param(model: &Model) -> TokenStream {
     let variants: Vec<_> = model
@@ -446,6 +1330,56 @@ fn generate_query_builder(_model: &Model, _table_name: &str) -> TokenStream {
     }
 }
 
+/// Get the `prax_query::drift::ColumnKind` variant checked for a field in strict mode.
+///
+/// Lists, vectors and computed fields have no portable type name, so only
+/// their presence is checked.
+fn column_kind(field: &Field) -> proc_macro2::Ident {
+    use prax_schema::ast::ScalarType;
+
+    let kind = if field.is_list() || field.is_virtual() {
+        "Other"
+    } else {
+        match &field.field_type {
+            FieldType::Scalar(scalar) => match scalar {
+                ScalarType::Int => "Int",
+                ScalarType::BigInt => "BigInt",
+                ScalarType::Float => "Float",
+                ScalarType::Decimal => "Decimal",
+                ScalarType::String
+                | ScalarType::Cuid
+                | ScalarType::Cuid2
+                | ScalarType::NanoId
+                | ScalarType::Ulid => "String",
+                ScalarType::Boolean => "Boolean",
+                ScalarType::DateTime => "DateTime",
+                ScalarType::Date => "Date",
+                ScalarType::Time => "Time",
+                ScalarType::Json => "Json",
+                ScalarType::Bytes => "Bytes",
+                ScalarType::Uuid => "Uuid",
+                ScalarType::IntRange
+                | ScalarType::BigIntRange
+                | ScalarType::DateTimeRange
+                | ScalarType::Inet
+                | ScalarType::Cidr
+                | ScalarType::MacAddr
+                | ScalarType::Geometry
+                | ScalarType::Geography
+                | ScalarType::Money
+                | ScalarType::Vector(_)
+                | ScalarType::HalfVector(_)
+                | ScalarType::SparseVector(_)
+                | ScalarType::Bit(_) => "Other",
+            },
+            FieldType::Enum(_) => "Enum",
+            FieldType::Composite(_) => "Json",
+            FieldType::Model(_) | FieldType::Unsupported(_) => "Other",
+        }
+    };
+    format_ident!("{}", kind)
+}
+
 /// Generate pre-compiled SQL constants for common queries.
 ///
 /// This generates `const` SQL strings that can be used directly without
@@ -453,12 +1387,15 @@ fn generate_query_builder(_model: &Model, _table_name: &str) -> TokenStream {
 fn generate_precompiled_sql(model: &Model, table_name: &str) -> TokenStream {
     let pk_fields = get_primary_key_fields(model);
 
-    // Generate column list for SELECT (all scalar fields)
+    // Generate column list for SELECT (all scalar fields, computing virtual ones)
     let columns: Vec<_> = model
         .fields
         .values()
         .filter(|f| !matches!(f.field_type, FieldType::Model(_)))
-        .map(|f| f.name().to_string())
+        .map(|f| match f.virtual_expr() {
+            Some(expr) => format!("({}) AS {}", expr, f.name()),
+            None => f.name().to_string(),
+        })
         .collect();
     let column_list = columns.join(", ");
 
@@ -468,7 +1405,10 @@ fn generate_precompiled_sql(model: &Model, table_name: &str) -> TokenStream {
         .values()
         .filter(|f| {
             let attrs = f.extract_attributes();
-            !attrs.is_auto && !attrs.is_updated_at && !matches!(f.field_type, FieldType::Model(_))
+            !attrs.is_auto
+                && !attrs.is_updated_at
+                && !f.is_virtual()
+                && !matches!(f.field_type, FieldType::Model(_))
         })
         .map(|f| f.name().to_string())
         .collect();
@@ -485,7 +1425,10 @@ fn generate_precompiled_sql(model: &Model, table_name: &str) -> TokenStream {
         .values()
         .filter(|f| {
             let attrs = f.extract_attributes();
-            !attrs.is_auto && !attrs.is_updated_at && !matches!(f.field_type, FieldType::Model(_))
+            !attrs.is_auto
+                && !attrs.is_updated_at
+                && !f.is_virtual()
+                && !matches!(f.field_type, FieldType::Model(_))
         })
         .enumerate()
         .map(|(i, f)| format!("{} = ${}", f.name(), i + 1))
@@ -648,8 +1591,65 @@ fn generate_precompiled_sql(model: &Model, table_name: &str) -> TokenStream {
     }
 }
 
+/// A relation whose foreign key lives on the related model.
+///
+/// Related records can be created, connected, updated, and deleted through
+/// the parent, so these relations accept nested writes.
+struct NestedRelation<'a> {
+    /// The relation field on the parent model.
+    field: &'a Field,
+    /// The related model.
+    target: &'a Model,
+    /// The foreign key column on the related model.
+    foreign_key: String,
+}
+
+/// Find the relations of a model that support nested writes.
+///
+/// The owning side of a relation (with `@relation(fields: [...])`) and
+/// implicit many-to-many relations are skipped.
+fn nested_relations<'a>(model: &'a Model, schema: &'a Schema) -> Vec<NestedRelation<'a>> {
+    model
+        .fields
+        .values()
+        .filter_map(|field| {
+            let FieldType::Model(target_name) = &field.field_type else {
+                return None;
+            };
+
+            let relation = field.extract_attributes().relation;
+            if relation.as_ref().is_some_and(|r| !r.fields.is_empty()) {
+                return None;
+            }
+            let relation_name = relation.and_then(|r| r.name);
+
+            let target = schema.get_model(target_name)?;
+            let back_relation = target.fields.values().find_map(|f| match &f.field_type {
+                FieldType::Model(name) if name == model.name() => f
+                    .extract_attributes()
+                    .relation
+                    .filter(|r| !r.fields.is_empty() && r.name == relation_name),
+                _ => None,
+            })?;
+
+            let fk_field = back_relation.fields.first()?;
+            let foreign_key = target
+                .fields
+                .get(fk_field)
+                .and_then(|f| f.extract_attributes().map)
+                .unwrap_or_else(|| fk_field.to_string());
+
+            Some(NestedRelation {
+                field,
+                target,
+                foreign_key,
+            })
+        })
+        .collect()
+}
+
 /// Generate relation helper types.
-fn generate_relation_helpers(model: &Model, _schema: &Schema) -> TokenStream {
+fn generate_relation_helpers(model: &Model, nested: &[NestedRelation<'_>]) -> TokenStream {
     let relation_fields: Vec<_> = model
         .fields
         .values()
@@ -673,6 +1673,42 @@ fn generate_relation_helpers(model: &Model, _schema: &Schema) -> TokenStream {
         })
         .collect();
 
+    let nested_writers: Vec<_> = nested
+        .iter()
+        .map(|relation| {
+            let fn_name = snake_ident(relation.field.name());
+            let target = snake_ident(relation.target.name());
+            let foreign_key = relation.foreign_key.as_str();
+            let doc = format!(
+                " SQL builder for nested writes on the `{}` relation.",
+                relation.field.name()
+            );
+
+            quote! {
+                #[doc = #doc]
+                pub fn #fn_name() -> prax_query::nested::NestedWriteBuilder {
+                    prax_query::nested::NestedWriteBuilder::one_to_many(
+                        super::TABLE_NAME,
+                        super::PRIMARY_KEY.iter().map(|c| c.to_string()).collect(),
+                        super::super::#target::TABLE_NAME,
+                        #foreign_key,
+                    )
+                }
+            }
+        })
+        .collect();
+
+    let nested_writes = if nested_writers.is_empty() {
+        TokenStream::new()
+    } else {
+        quote! {
+            /// Builders for the SQL of nested writes, one per relation.
+            pub mod nested_writes {
+                #(#nested_writers)*
+            }
+        }
+    };
+
     quote! {
         /// Include related records in the query.
         #[derive(Debug, Clone, Default)]
@@ -681,6 +1717,8 @@ fn generate_relation_helpers(model: &Model, _schema: &Schema) -> TokenStream {
             None,
             #(#include_variants,)*
         }
+
+        #nested_writes
     }
 }
 
@@ -750,6 +1788,437 @@ mod tests {
         assert!(code.contains("INSERT"));
     }
 
+    #[test]
+    fn test_update_input_from_diff() {
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+
+        assert!(code.contains("pub fn from_diff (old : & User , new : & User) -> Self"));
+        // The auto id is never updated; an optional field is set as is
+        assert!(!code.contains("input . id ="));
+        assert!(code.contains("input . email = Some (new . email . clone ())"));
+        assert!(code.contains("input . name = new . name . clone ()"));
+        assert!(code.contains("pub fn field_mask (& self) -> FieldMask"));
+        assert!(code.contains("pub use user :: FieldMask as UserFieldMask"));
+    }
+
+    #[test]
+    fn test_generate_id_codec() {
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "pub fn encode_id (codec : & dyn prax_query :: id_codec :: IdCodec , id : i32) -> String"
+        ));
+        assert!(code.contains("i32 :: try_from (id)"));
+        assert!(code.contains("QueryError :: not_found (\"User\")"));
+        assert!(code.contains("encode_id (codec , self . id)"));
+
+        // String keys are already opaque
+        let schema = prax_schema::validate_schema(
+            r#"
+            model Session {
+                token String @id
+            }
+            "#,
+        )
+        .unwrap();
+        let model = schema.get_model("Session").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("encode_id"));
+    }
+
+    #[test]
+    fn test_generate_compound_uniques() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model Member {
+                id       Int    @id @auto
+                email    String
+                tenantId Int    @map("tenant_id")
+                handle   String
+                team     String
+
+                @@unique([email, tenantId])
+                @@unique([team, handle], name: "team_handle_key")
+                @@unique([email])
+                @@unique([handle, tenantId], nullsNotDistinct: true)
+                @@unique([team, tenantId], deferrable: InitiallyDeferred)
+                @@index([team, email])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("Member").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "pub fn email_tenant_id (email : String , tenant_id : i32) -> WhereParam { \
+             WhereParam :: And (vec ! [email :: equals (email) , tenant_id :: equals (tenant_id)]) }"
+        ));
+        assert!(code.contains(
+            "pub mod email_tenant_id { # [doc = r\" Columns of the constraint.\"] \
+             pub const COLUMNS : & [& str] = & [super :: email :: COLUMN , super :: tenant_id :: COLUMN] ;"
+        ));
+        assert!(code.contains("ConflictTarget :: columns (COLUMNS . iter () . copied ())"));
+        assert!(code.contains("pub fn team_handle_key (team : String , handle : String)"));
+        assert!(code.contains(
+            "pub mod handle_tenant_id { # [doc = r\" Columns of the constraint.\"] \
+             pub const COLUMNS : & [& str] = & [super :: handle :: COLUMN , super :: tenant_id :: COLUMN] ; \
+             # [doc = r\" The constraint as an upsert's conflict target.\"] \
+             pub fn conflict_target () -> prax_query :: upsert :: ConflictTarget { \
+             prax_query :: upsert :: ConflictTarget :: nulls_not_distinct (COLUMNS . iter () . copied ()) } }"
+        ));
+        assert!(code.contains(
+            "pub mod team_tenant_id { # [doc = r\" Columns of the constraint.\"] \
+             pub const COLUMNS : & [& str] = & [super :: team :: COLUMN , super :: tenant_id :: COLUMN] ; }"
+        ));
+
+        // Single columns have field accessors already, and indexes aren't unique
+        assert!(!code.contains("pub fn email ("));
+        assert!(!code.contains("pub fn team_email"));
+    }
+
+    #[test]
+    fn test_generate_nested_write_inputs() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                posts Post[]
+            }
+
+            model Post {
+                id       Int    @id @auto
+                authorId Int    @map("author_id")
+                author   User   @relation(fields: [authorId], references: [id])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        let code = generate_model_module(user, &schema).unwrap().to_string();
+        assert!(code.contains("pub posts : Option < super :: _prax_prelude :: NestedCreate"));
+        assert!(code.contains("pub posts : Option < super :: _prax_prelude :: NestedUpdate"));
+        assert!(code.contains("pub mod nested_writes"));
+        assert!(code.contains("\"author_id\""));
+
+        // The owning side holds the foreign key, so it gets no nested writes
+        let post = schema.get_model("Post").unwrap();
+        let code = generate_model_module(post, &schema).unwrap().to_string();
+        assert!(!code.contains("NestedCreate"));
+        assert!(!code.contains("nested_writes"));
+    }
+
+    #[test]
+    fn test_generate_retention_policy() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model AuditLog {
+                id        Int      @id @auto
+                createdAt DateTime @default(now())
+
+                @@retention(field: createdAt, keep: "90d", strategy: archive)
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("AuditLog").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains("pub fn retention_policy ()"));
+        assert!(code.contains("created_at :: COLUMN"));
+        assert!(code.contains("from_secs (7776000u64)"));
+        assert!(code.contains("archive_to_table"));
+
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("retention_policy"));
+    }
+
+    #[test]
+    fn test_generate_ttl_policy() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model Session {
+                id        Int       @id @auto
+                expiresAt DateTime?
+                @@ttl(field: expiresAt)
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("Session").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "const TTL_COLUMN : Option < & 'static str > = Some (expires_at :: COLUMN) ;"
+        ));
+        assert!(code.contains("pub fn ttl_policy ()"));
+        assert!(code.contains("Duration :: ZERO"));
+
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("TTL_COLUMN"));
+    }
+
+    #[test]
+    fn test_generate_schema_qualified_table() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model Invoice {
+                id Int @id @auto
+
+                @@schema("accounting")
+                @@map("invoices")
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("Invoice").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains("pub const TABLE_NAME : & str = \"accounting.invoices\""));
+        assert!(code.contains("FROM accounting.invoices"));
+    }
+
+    #[test]
+    fn test_generate_history() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id    Int    @id @auto @map("user_id")
+                email String
+
+                @@map("users")
+                @@versioned
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains("HistoryTable :: new (\"users_history\" , [\"user_id\"])"));
+        assert!(code.contains("impl User"));
+        assert!(code.contains("history_table () . record_of (self)"));
+
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("history_table"));
+    }
+
+    #[test]
+    fn test_generate_audit() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id           Int    @id @auto
+                email        String
+                passwordHash String @map("password_hash")
+
+                @@audited(exclude: [passwordHash])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains("pub fn audit_table () -> prax_query :: middleware :: AuditedTable"));
+        assert!(code.contains(". exclude ([password_hash :: COLUMN])"));
+
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("audit_table"));
+    }
+
+    #[test]
+    fn test_generate_tree() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model Category {
+                id       Int        @id @auto
+                parentId Int?       @map("parent_id")
+                parent   Category?  @relation("CategoryTree", fields: [parentId], references: [id])
+                children Category[] @relation("CategoryTree")
+
+                @@map("categories")
+                @@tree(strategy: closure_table, parent: parentId)
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("Category").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "Tree :: closure_table (TABLE_NAME , id :: COLUMN , parent_id :: COLUMN , \"categories_closure\")"
+        ));
+        assert!(code.contains("TREE . ancestors (db , self) . await"));
+        assert!(code.contains("self . parent_id = new_parent . map (| p | p . id . clone ())"));
+
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("TREE"));
+    }
+
+    #[test]
+    fn test_generate_virtual_fields() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model OrderLine {
+                id        Int @id @auto
+                quantity  Int
+                unitPrice Int
+                total     Int @virtual(expr: "quantity * unitPrice")
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("OrderLine").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "pub const VIRTUAL_FIELDS : & [(& str , & str)] = & [(total :: COLUMN , \"quantity * unitPrice\")]"
+        ));
+        // Computed in reads, never written
+        assert!(
+            code.contains("SELECT id, quantity, unitPrice, (quantity * unitPrice) AS total FROM")
+        );
+        assert!(code.contains("INSERT INTO OrderLine (quantity, unitPrice) VALUES"));
+        assert!(code.contains(
+            "const COLUMNS : & 'static [& 'static str] = & [id :: COLUMN , quantity :: COLUMN , unit_price :: COLUMN] ;"
+        ));
+        assert!(code.contains(
+            "const VIRTUAL_FIELDS : & 'static [(& 'static str , & 'static str)] = VIRTUAL_FIELDS ;"
+        ));
+        assert!(code.contains("pub total : i32"));
+        assert!(!code.contains("Total (i32)"));
+    }
+
+    #[test]
+    fn test_generate_column_specs() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id     Int      @id @auto
+                email  String   @unique
+                active Boolean
+                tags   String[]
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "prax_query :: drift :: ColumnSpec :: new (id :: COLUMN , prax_query :: drift :: ColumnKind :: Int ,)"
+        ));
+        assert!(
+            code.contains("(active :: COLUMN , prax_query :: drift :: ColumnKind :: Boolean ,)")
+        );
+        assert!(code.contains("(tags :: COLUMN , prax_query :: drift :: ColumnKind :: Other ,)"));
+    }
+
+    #[test]
+    fn test_generate_indexes() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model User {
+                id        Int    @id @auto
+                email     String @unique
+                firstName String
+                lastName  String
+
+                @@index([lastName, firstName])
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "const INDEXES : & 'static [prax_query :: verify :: IndexSpec] = & [prax_query :: verify :: IndexSpec :: new (& [email :: COLUMN] , true) , prax_query :: verify :: IndexSpec :: new (& [last_name :: COLUMN , first_name :: COLUMN] , false)]"
+        ));
+    }
+
+    #[test]
+    fn test_generate_translations() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            model Post {
+                id    Int     @id @auto
+                title String  @translatable
+                body  String? @translatable @map("content")
+                views Int
+
+                @@map("posts")
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("Post").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains(
+            "Translations :: new (\"posts_translations\" , id :: COLUMN , & [title :: COLUMN , body :: COLUMN] ,)"
+        ));
+        assert!(code.contains("pub struct PostTranslation"));
+        assert!(code.contains("pub translations : prax_query :: translation :: TranslationSet"));
+        assert!(code.contains("pub fn title_in (& self , locale : & str) -> & str"));
+        assert!(code.contains("pub fn body_in (& self , locale : & str) -> Option < & str >"));
+        assert!(!code.contains("views_in"));
+
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("TRANSLATIONS"));
+        assert!(!code.contains("pub translations"));
+    }
+
+    #[test]
+    fn test_generate_state_machine() {
+        let schema = prax_schema::validate_schema(
+            r#"
+            enum PostStatus {
+                draft
+                review
+                published @map("live")
+            }
+
+            model Post {
+                id     Int        @id @auto
+                status PostStatus @default(draft) @stateMachine(transitions: {draft: [review], review: [published, draft]})
+            }
+            "#,
+        )
+        .unwrap();
+
+        let model = schema.get_model("Post").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(code.contains("pub const STATUS_STATE_MACHINE"));
+        assert!(code.contains(
+            "& [(\"draft\" , & [\"review\"]) , (\"review\" , & [\"live\" , \"draft\"])]"
+        ));
+        assert!(code.contains("pub async fn transition_to < E"));
+        assert!(code.contains("self . status = to"));
+
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+        let code = generate_model_module(model, &schema).unwrap().to_string();
+        assert!(!code.contains("transition_to"));
+    }
+
     #[test]
     fn test_get_primary_key_fields() {
         let schema = make_simple_schema();
@@ -803,4 +2272,22 @@ mod tests {
             "Should NOT have SimpleObject derive"
         );
     }
+
+    #[test]
+    fn test_generate_model_module_with_plugins() {
+        let schema = make_simple_schema();
+        let model = schema.get_model("User").unwrap();
+
+        let plugin_tokens = quote! { pub mod _plugin_output {} };
+        let code =
+            generate_model_module_with_plugins(model, &schema, ModelStyle::Standard, plugin_tokens)
+                .unwrap()
+                .to_string();
+
+        // Plugin output is nested in the model module, before the re-export
+        let module_start = code.find("pub mod user").unwrap();
+        let plugin = code.find("pub mod _plugin_output").unwrap();
+        let reexport = code.find("pub use user :: User").unwrap();
+        assert!(module_start < plugin && plugin < reexport);
+    }
 }
@@ -28,10 +28,20 @@
 //! # Enable runtime validation
 //! PRAX_PLUGIN_VALIDATOR=1 cargo build
 //!
+//! # Enable TypeScript declarations for frontends
+//! PRAX_PLUGIN_TYPESCRIPT=1 cargo build
+//!
 //! # Enable all plugins
 //! PRAX_PLUGINS_ALL=1 cargo build
 //! ```
 //!
+//! # Caching
+//!
+//! Output of [`prax_schema!`] is cached under `target/prax-codegen/`, keyed by
+//! a hash of the schema, `prax.toml` and the plugin configuration. Set
+//! `PRAX_CODEGEN_CACHE=0` to disable the cache or `PRAX_CODEGEN_CACHE_DIR` to
+//! move it.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -54,13 +64,14 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{DeriveInput, LitStr, parse_macro_input};
 
+mod cache;
 mod generators;
 mod plugins;
 mod schema_reader;
 mod types;
 
 use generators::{
-    generate_enum_module, generate_model_module_with_style, generate_type_module,
+    generate_client, generate_enum_module, generate_model_module_with_plugins, generate_type_module,
     generate_view_module,
 };
 
@@ -84,7 +95,9 @@ use generators::{
 /// - A module with the model name (snake_case)
 /// - A `Data` struct representing a row from the database
 /// - A `CreateInput` struct for creating new records
-/// - A `UpdateInput` struct for updating records
+/// - A `UpdateInput` struct for updating records, with `from_diff` to build
+///   a minimal update from two records
+/// - A `FieldMask` bitset of the model's fields, re-exported as `<Model>FieldMask`
 /// - Field modules with filter operations (`equals`, `contains`, `in_`, etc.)
 /// - A `WhereParam` enum for type-safe filtering
 /// - An `OrderByParam` enum for sorting
@@ -157,11 +170,53 @@ pub fn derive_model(input: TokenStream) -> TokenStream {
 
 /// Internal function to generate code from a schema file.
 fn generate_from_schema(schema_path: &str) -> Result<proc_macro2::TokenStream, syn::Error> {
+    use cache::{CacheKey, CodegenCache};
+    use plugins::PluginConfig;
+    use schema_reader::read_schema_source;
+
+    // Read the schema file along with prax.toml configuration
+    let source = read_schema_source(schema_path).map_err(|e| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Failed to parse schema: {}", e),
+        )
+    })?;
+
+    let key = CacheKey::new(
+        &source.content,
+        source.config_content.as_deref(),
+        &PluginConfig::from_env().fingerprint(),
+    );
+    let cache = CodegenCache::from_env();
+
+    let mut output = match cache.as_ref().and_then(|c| c.load(key)) {
+        Some(tokens) => tokens,
+        None => {
+            let tokens = generate_schema_tokens(&source)?;
+            if let Some(cache) = &cache {
+                cache.store(key, &tokens);
+            }
+            tokens
+        }
+    };
+
+    // Make cargo rebuild when the schema file changes
+    let schema_file = source.path.display().to_string();
+    output.extend(quote! {
+        const _: &[u8] = include_bytes!(#schema_file);
+    });
+
+    Ok(output)
+}
+
+/// Generate the tokens for a schema, bypassing the cache.
+fn generate_schema_tokens(
+    source: &schema_reader::SchemaSource,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
     use plugins::{PluginConfig, PluginContext, PluginRegistry};
-    use schema_reader::read_schema_with_config;
 
-    // Read and parse the schema file along with prax.toml configuration
-    let schema_with_config = read_schema_with_config(schema_path).map_err(|e| {
+    // Parse the schema file along with prax.toml configuration
+    let schema_with_config = source.parse().map_err(|e| {
         syn::Error::new(
             proc_macro2::Span::call_site(),
             format!("Failed to parse schema: {}", e),
@@ -223,19 +278,20 @@ fn generate_from_schema(schema_path: &str) -> Result<proc_macro2::TokenStream, s
 
     // Generate models with the configured model style
     for (_, model_def) in &schema.models {
-        output.extend(generate_model_module_with_style(
+        // Run plugin model hooks; their tokens go inside the model module
+        let plugin_output = plugin_registry.run_model(&plugin_ctx, model_def);
+        output.extend(generate_model_module_with_plugins(
             model_def,
             &schema,
             model_style,
+            plugin_output.tokens,
         )?);
-
-        // Run plugin model hooks
-        let plugin_output = plugin_registry.run_model(&plugin_ctx, model_def);
-        if !plugin_output.is_empty() {
-            output.extend(plugin_output.tokens);
-        }
+        output.extend(plugin_output.root_items);
     }
 
+    // Generate the client with an accessor per model
+    output.extend(generate_client(&schema));
+
     // Run plugin finish hooks
     let finish_output = plugin_registry.run_finish(&plugin_ctx);
     output.extend(finish_output.tokens);
@@ -313,6 +369,121 @@ fn generate_prelude() -> proc_macro2::TokenStream {
                 }
             }
 
+            /// Nested writes on a relation when creating the parent record.
+            #[derive(Debug, Clone)]
+            pub enum NestedCreate<C, W> {
+                /// Create new related records.
+                Create(Vec<C>),
+                /// Connect existing records, creating those that don't match.
+                ConnectOrCreate(Vec<(W, C)>),
+                /// Connect existing records.
+                Connect(Vec<W>),
+            }
+
+            impl<C, W> NestedCreate<C, W> {
+                /// Create a related record.
+                pub fn create(data: C) -> Self {
+                    Self::Create(vec![data])
+                }
+
+                /// Create several related records.
+                pub fn create_many(data: Vec<C>) -> Self {
+                    Self::Create(data)
+                }
+
+                /// Connect a record, or create it if none matches the filter.
+                pub fn connect_or_create(filter: W, data: C) -> Self {
+                    Self::ConnectOrCreate(vec![(filter, data)])
+                }
+
+                /// Connect existing records.
+                pub fn connect(filters: Vec<W>) -> Self {
+                    Self::Connect(filters)
+                }
+            }
+
+            /// Nested writes on a relation when updating the parent record.
+            #[derive(Debug, Clone)]
+            pub enum NestedUpdate<C, U, W> {
+                /// Create new related records.
+                Create(Vec<C>),
+                /// Connect existing records, creating those that don't match.
+                ConnectOrCreate(Vec<(W, C)>),
+                /// Connect existing records.
+                Connect(Vec<W>),
+                /// Disconnect related records.
+                Disconnect(Vec<W>),
+                /// Replace all related records with the matching ones.
+                Set(Vec<W>),
+                /// Update related records.
+                Update(Vec<(W, U)>),
+                /// Update a related record, or create it if none matches.
+                Upsert(Vec<(W, C, U)>),
+                /// Update all related records matching a filter.
+                UpdateMany(W, U),
+                /// Delete related records.
+                Delete(Vec<W>),
+                /// Delete all related records matching a filter.
+                DeleteMany(W),
+            }
+
+            impl<C, U, W> NestedUpdate<C, U, W> {
+                /// Create a related record.
+                pub fn create(data: C) -> Self {
+                    Self::Create(vec![data])
+                }
+
+                /// Create several related records.
+                pub fn create_many(data: Vec<C>) -> Self {
+                    Self::Create(data)
+                }
+
+                /// Connect a record, or create it if none matches the filter.
+                pub fn connect_or_create(filter: W, data: C) -> Self {
+                    Self::ConnectOrCreate(vec![(filter, data)])
+                }
+
+                /// Connect existing records.
+                pub fn connect(filters: Vec<W>) -> Self {
+                    Self::Connect(filters)
+                }
+
+                /// Disconnect related records.
+                pub fn disconnect(filters: Vec<W>) -> Self {
+                    Self::Disconnect(filters)
+                }
+
+                /// Replace all related records with the matching ones.
+                pub fn set(filters: Vec<W>) -> Self {
+                    Self::Set(filters)
+                }
+
+                /// Update a related record.
+                pub fn update(filter: W, data: U) -> Self {
+                    Self::Update(vec![(filter, data)])
+                }
+
+                /// Update a related record, or create it if none matches.
+                pub fn upsert(filter: W, create: C, update: U) -> Self {
+                    Self::Upsert(vec![(filter, create, update)])
+                }
+
+                /// Update all related records matching a filter.
+                pub fn update_many(filter: W, data: U) -> Self {
+                    Self::UpdateMany(filter, data)
+                }
+
+                /// Delete related records.
+                pub fn delete(filters: Vec<W>) -> Self {
+                    Self::Delete(filters)
+                }
+
+                /// Delete all related records matching a filter.
+                pub fn delete_many(filter: W) -> Self {
+                    Self::DeleteMany(filter)
+                }
+            }
+
             /// Sort direction for order by clauses.
             #[derive(Debug, Clone, Copy, PartialEq, Eq)]
             pub enum SortOrder {
@@ -365,6 +536,8 @@ mod tests {
         assert!(code.contains("pub trait PraxModel"));
         assert!(code.contains("pub enum SortOrder"));
         assert!(code.contains("pub enum SetParam"));
+        assert!(code.contains("pub enum NestedCreate"));
+        assert!(code.contains("pub enum NestedUpdate"));
     }
 
     #[test]
@@ -20,6 +20,12 @@
 //!
 //! For async-graphql derive macros: `PRAX_PLUGIN_GRAPHQL_ASYNC=1`
 //!
+//! To expose integer primary keys as opaque `ID`s: `PRAX_PLUGIN_GRAPHQL_PUBLIC_IDS=1`.
+//! The `id` field is then resolved through the
+//! `Arc<dyn prax_query::id_codec::IdCodec>` in the schema data, and the raw
+//! key is neither output nor filterable; decode incoming ids with the
+//! model's `decode_id`.
+//!
 //! ```rust,ignore
 //! use async_graphql::{Object, InputObject, Enum};
 //!
@@ -52,7 +58,7 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
-use prax_schema::ast::{Enum, FieldType, Model, ScalarType, TypeModifier, View};
+use prax_schema::ast::{Enum, Field, FieldType, Model, ScalarType, TypeModifier, View};
 
 use crate::plugins::{Plugin, PluginContext, PluginOutput};
 
@@ -99,6 +105,12 @@ impl Plugin for GraphQLPlugin {
         let mut filter_fields: Vec<TokenStream> = Vec::new();
         let mut field_names: Vec<String> = Vec::new();
 
+        let public_id = ctx
+            .config
+            .is_enabled_by_name("graphql_public_ids")
+            .then(|| public_id_field(model))
+            .flatten();
+
         for field in model.fields.values() {
             let field_name = field.name();
             let field_ident = format_ident!("{}", field_name);
@@ -118,6 +130,11 @@ impl Plugin for GraphQLPlugin {
 
             // SDL field (skip hidden)
             if !meta.hidden {
+                let gql_type = if public_id.is_some_and(|id| id.name() == field_name) {
+                    "ID!".to_string()
+                } else {
+                    gql_type
+                };
                 let mut sdl_field = format!("  {}: {}", field_name, gql_type);
                 if meta.is_deprecated() {
                     if let Some(msg) = meta.deprecation_message() {
@@ -132,8 +149,10 @@ impl Plugin for GraphQLPlugin {
             // Build graphql attributes
             let mut gql_attrs: Vec<TokenStream> = Vec::new();
 
-            // Hidden/skip
-            if meta.hidden || meta.internal {
+            let is_public_id = public_id.is_some_and(|id| id.name() == field_name);
+
+            // Hidden/skip; public ids are resolved from the raw key
+            if meta.hidden || meta.internal || is_public_id {
                 gql_attrs.push(quote! { skip });
             }
 
@@ -213,7 +232,7 @@ impl Plugin for GraphQLPlugin {
             }
 
             // Filter fields for scalar types
-            if !meta.sensitive && !meta.hidden {
+            if !meta.sensitive && !meta.hidden && !is_public_id {
                 if let FieldType::Scalar(scalar) = &field.field_type {
                     let filter_type = scalar_to_filter_type(scalar);
                     if let Some(ft) = filter_type {
@@ -230,17 +249,45 @@ impl Plugin for GraphQLPlugin {
         let input_sdl = generate_input_sdl(model);
 
         // Check if async-graphql mode is enabled
-        let async_graphql_enabled = ctx.config.is_enabled("graphql_async");
+        let async_graphql_enabled = ctx.config.is_enabled_by_name("graphql_async");
+
+        let (output_attr, public_id_resolver) = match public_id {
+            Some(field) => {
+                let field_ident = format_ident!("{}", field.name());
+                let field_name = field.name();
+                (
+                    quote! { #[graphql(name = #model_name, complex)] },
+                    quote! {
+                        #[async_graphql::ComplexObject]
+                        impl #model_ident {
+                            /// The record's id, encoded by the `IdCodec` in the schema data.
+                            #[graphql(name = #field_name)]
+                            async fn public_id(
+                                &self,
+                                ctx: &async_graphql::Context<'_>,
+                            ) -> async_graphql::Result<async_graphql::ID> {
+                                let codec = ctx
+                                    .data::<std::sync::Arc<dyn prax_query::id_codec::IdCodec>>()?;
+                                Ok(async_graphql::ID(codec.encode(self.#field_ident as u64)))
+                            }
+                        }
+                    },
+                )
+            }
+            None => (quote! { #[graphql(name = #model_name)] }, quote! {}),
+        };
 
         let async_graphql_derives = if async_graphql_enabled {
             quote! {
                 /// Output type for GraphQL queries.
                 #[derive(async_graphql::SimpleObject, Clone, Debug)]
-                #[graphql(name = #model_name)]
+                #output_attr
                 pub struct #model_ident {
                     #(#output_fields)*
                 }
 
+                #public_id_resolver
+
                 /// Input type for GraphQL mutations.
                 #[derive(async_graphql::InputObject, Clone, Debug, Default)]
                 #[graphql(name = #input_ident)]
@@ -335,7 +382,7 @@ impl Plugin for GraphQLPlugin {
 
         let variant_names: Vec<_> = enum_def.variants.iter().map(|v| v.name()).collect();
 
-        let async_graphql_enabled = ctx.config.is_enabled("graphql_async");
+        let async_graphql_enabled = ctx.config.is_enabled_by_name("graphql_async");
 
         let async_graphql_enum = if async_graphql_enabled {
             quote! {
@@ -400,7 +447,7 @@ impl Plugin for GraphQLPlugin {
         let fields_str = sdl_fields.join("\n");
         let sdl = format!("type {} {{\n{}\n}}", view_name, fields_str);
 
-        let async_graphql_enabled = ctx.config.is_enabled("graphql_async");
+        let async_graphql_enabled = ctx.config.is_enabled_by_name("graphql_async");
 
         let async_graphql_view = if async_graphql_enabled {
             quote! {
@@ -513,7 +560,7 @@ impl Plugin for GraphQLPlugin {
         let enum_count = ctx.schema.enums.len();
         let view_count = ctx.schema.views.len();
 
-        let async_graphql_enabled = ctx.config.is_enabled("graphql_async");
+        let async_graphql_enabled = ctx.config.is_enabled_by_name("graphql_async");
 
         let common_types = if async_graphql_enabled {
             quote! {
@@ -660,6 +707,18 @@ impl Plugin for GraphQLPlugin {
 }
 
 /// Convert a Prax field type to GraphQL type string.
+/// Get the primary key field exposed as an opaque `ID`: a model's only `@id`
+/// field, if it is a required `Int` or `BigInt`.
+fn public_id_field(model: &Model) -> Option<&Field> {
+    let mut ids = model.fields.values().filter(|f| f.is_id());
+    let field = ids.next()?;
+    let integer = matches!(
+        field.field_type,
+        FieldType::Scalar(ScalarType::Int | ScalarType::BigInt)
+    );
+    (ids.next().is_none() && integer && field.modifier == TypeModifier::Required).then_some(field)
+}
+
 fn field_type_to_graphql(field_type: &FieldType, modifier: &TypeModifier) -> String {
     let base_type = match field_type {
         FieldType::Scalar(scalar) => match scalar {
@@ -674,6 +733,14 @@ fn field_type_to_graphql(field_type: &FieldType, modifier: &TypeModifier) -> Str
             ScalarType::Uuid => "ID",
             // String-based ID types are represented as ID in GraphQL
             ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => "ID",
+            // Ranges as range literals
+            ScalarType::IntRange | ScalarType::BigIntRange | ScalarType::DateTimeRange => "String",
+            // Addresses in their text form
+            ScalarType::Inet | ScalarType::Cidr | ScalarType::MacAddr => "String",
+            // Shapes as EWKT
+            ScalarType::Geometry | ScalarType::Geography => "String",
+            // Amount and currency as an object
+            ScalarType::Money => "JSON",
             // Vector types are represented as [Float!] in GraphQL
             ScalarType::Vector(_) | ScalarType::HalfVector(_) => "[Float!]",
             ScalarType::SparseVector(_) => "[[Float!]!]", // Array of [index, value] pairs
@@ -718,6 +785,12 @@ fn field_type_to_rust(field_type: &FieldType, modifier: &TypeModifier) -> TokenS
             ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => {
                 quote! { String }
             }
+            ScalarType::IntRange | ScalarType::BigIntRange | ScalarType::DateTimeRange => {
+                quote! { String }
+            }
+            ScalarType::Inet | ScalarType::Cidr | ScalarType::MacAddr => quote! { String },
+            ScalarType::Geometry | ScalarType::Geography => quote! { String },
+            ScalarType::Money => quote! { serde_json::Value },
             // PostgreSQL vector types
             ScalarType::Vector(_) | ScalarType::HalfVector(_) => quote! { Vec<f32> },
             ScalarType::SparseVector(_) => quote! { Vec<(u32, f32)> },
@@ -769,7 +842,17 @@ fn scalar_to_filter_type(scalar: &ScalarType) -> Option<TokenStream> {
         | ScalarType::HalfVector(_)
         | ScalarType::SparseVector(_)
         | ScalarType::Bit(_) => None,
-        ScalarType::Json | ScalarType::Bytes => None,
+        ScalarType::Json
+        | ScalarType::Bytes
+        | ScalarType::IntRange
+        | ScalarType::BigIntRange
+        | ScalarType::DateTimeRange
+        | ScalarType::Inet
+        | ScalarType::Cidr
+        | ScalarType::MacAddr
+        | ScalarType::Geometry
+        | ScalarType::Geography
+        | ScalarType::Money => None,
     }
 }
 
@@ -795,7 +878,7 @@ fn generate_input_sdl(model: &Model) -> String {
 mod tests {
     use super::*;
     use prax_schema::Schema;
-    use prax_schema::ast::{EnumVariant, Field, Ident, Span};
+    use prax_schema::ast::{Attribute, EnumVariant, Ident, Span};
 
     fn make_span() -> Span {
         Span::new(0, 0)
@@ -837,6 +920,30 @@ mod tests {
         assert!(code.contains("INPUT_SDL"));
     }
 
+    #[test]
+    fn test_graphql_plugin_public_ids() {
+        let schema = Schema::new();
+        let mut config = crate::plugins::PluginConfig::new();
+        config.enable("PRAX_PLUGIN_GRAPHQL_ASYNC");
+        config.enable("PRAX_PLUGIN_GRAPHQL_PUBLIC_IDS");
+        let ctx = PluginContext::new(&schema, &config);
+
+        let mut model = Model::new(make_ident("User"), make_span());
+        model.add_field(Field::new(
+            make_ident("id"),
+            FieldType::Scalar(ScalarType::Int),
+            TypeModifier::Required,
+            vec![Attribute::simple(make_ident("id"), make_span())],
+            make_span(),
+        ));
+
+        let code = GraphQLPlugin.on_model(&ctx, &model).tokens.to_string();
+        assert!(code.contains("id: ID!"));
+        assert!(code.contains("# [graphql (name = \"User\" , complex)]"));
+        assert!(code.contains("# [graphql (skip)] pub id : i32"));
+        assert!(code.contains("codec . encode (self . id as u64)"));
+    }
+
     #[test]
     fn test_graphql_plugin_enum() {
         let schema = Schema::new();
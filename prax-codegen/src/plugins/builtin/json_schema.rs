@@ -128,6 +128,10 @@ fn scalar_to_json_type(field_type: &FieldType, modifier: &TypeModifier) -> &'sta
             ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => {
                 "string"
             }
+            ScalarType::IntRange | ScalarType::BigIntRange | ScalarType::DateTimeRange => "string",
+            ScalarType::Inet | ScalarType::Cidr | ScalarType::MacAddr => "string",
+            ScalarType::Geometry | ScalarType::Geography => "string",
+            ScalarType::Money => "object",
             ScalarType::Json => "object",
             ScalarType::Bytes => "string", // base64 encoded
             // Vector types are arrays of numbers
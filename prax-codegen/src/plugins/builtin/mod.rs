@@ -4,10 +4,12 @@ mod debug;
 mod graphql;
 mod json_schema;
 mod serde_plugin;
+mod typescript;
 mod validator;
 
 pub use debug::DebugPlugin;
 pub use graphql::GraphQLPlugin;
 pub use json_schema::JsonSchemaPlugin;
 pub use serde_plugin::SerdePlugin;
+pub use typescript::TypeScriptPlugin;
 pub use validator::ValidatorPlugin;
@@ -0,0 +1,336 @@
+//! TypeScript plugin - generates `.d.ts` declarations for models.
+//!
+//! The declarations describe the JSON the generated types serialize to, so
+//! frontends consuming the API stay in sync with the schema:
+//!
+//! ```typescript
+//! export type Role = "USER" | "ADMIN";
+//!
+//! export interface User {
+//!   id: number;
+//!   email_address: string;
+//!   role: Role;
+//!   nickname: string | null;
+//!   tags: string[];
+//! }
+//! ```
+//!
+//! Keys follow `@map`, and optional fields are `null` rather than missing.
+//!
+//! Enable with: `PRAX_PLUGIN_TYPESCRIPT=1`
+
+use quote::quote;
+
+use prax_schema::ast::{Documentation, Field, FieldType, ScalarType, TypeModifier};
+
+use crate::plugins::{Plugin, PluginContext, PluginOutput};
+use crate::types::to_snake_case;
+
+/// TypeScript plugin that generates type declarations.
+///
+/// When enabled, each model module gets a `_typescript::DECLARATION`
+/// constant, and a root `_typescript` module holds the declarations of every
+/// model, enum, composite type and view, with a `write` function to save
+/// them as a `.d.ts` file.
+///
+/// Enable with: `PRAX_PLUGIN_TYPESCRIPT=1`
+pub struct TypeScriptPlugin;
+
+impl Plugin for TypeScriptPlugin {
+    fn name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn env_var(&self) -> &'static str {
+        "PRAX_PLUGIN_TYPESCRIPT"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generates TypeScript declarations for models, enums and views"
+    }
+
+    fn on_model(&self, _ctx: &PluginContext, model: &prax_schema::ast::Model) -> PluginOutput {
+        let declaration = interface(
+            model.name(),
+            model.documentation.as_ref(),
+            model.fields.values(),
+        );
+
+        PluginOutput::with_tokens(quote! {
+            /// TypeScript declarations for this model.
+            pub mod _typescript {
+                /// The TypeScript interface for this model.
+                pub const DECLARATION: &str = #declaration;
+            }
+        })
+    }
+
+    fn on_finish(&self, ctx: &PluginContext) -> PluginOutput {
+        let schema = ctx.schema;
+        let mut parts = vec!["// Generated by Prax. Do not edit.".to_string()];
+
+        for enum_def in schema.enums.values() {
+            let variants: Vec<String> = enum_def
+                .variants
+                .iter()
+                .map(|v| format!("\"{}\"", v.db_value()))
+                .collect();
+            parts.push(format!(
+                "{}export type {} = {};",
+                jsdoc(enum_def.documentation.as_ref(), ""),
+                enum_def.name(),
+                variants.join(" | ")
+            ));
+        }
+        for type_def in schema.types.values() {
+            parts.push(interface(
+                type_def.name(),
+                type_def.documentation.as_ref(),
+                type_def.fields.values(),
+            ));
+        }
+        for model in schema.models.values() {
+            parts.push(interface(
+                model.name(),
+                model.documentation.as_ref(),
+                model.fields.values(),
+            ));
+        }
+        for view in schema.views.values() {
+            parts.push(interface(
+                view.name(),
+                view.documentation.as_ref(),
+                view.fields.values(),
+            ));
+        }
+
+        let declarations = parts.join("\n\n") + "\n";
+
+        PluginOutput::with_tokens(quote! {
+            /// TypeScript declarations for the schema.
+            pub mod _typescript {
+                /// The `.d.ts` declarations of every model, enum, composite
+                /// type and view.
+                pub const DECLARATIONS: &str = #declarations;
+
+                /// Write the declarations to a `.d.ts` file.
+                pub fn write(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+                    std::fs::write(path, DECLARATIONS)
+                }
+            }
+        })
+    }
+}
+
+/// Render an interface with a property per field.
+fn interface<'a>(
+    name: &str,
+    documentation: Option<&Documentation>,
+    fields: impl Iterator<Item = &'a Field>,
+) -> String {
+    let mut out = format!("{}export interface {} {{\n", jsdoc(documentation, ""), name);
+    for field in fields {
+        out.push_str(&jsdoc(field.documentation.as_ref(), "  "));
+        out.push_str(&format!(
+            "  {}: {};\n",
+            property_key(&json_key(field)),
+            field_type_to_typescript(&field.field_type, &field.modifier)
+        ));
+    }
+    out.push('}');
+    out
+}
+
+/// The key a field serializes under: its `@map` name, or the snake_case
+/// name of the generated Rust field.
+fn json_key(field: &Field) -> String {
+    field
+        .attributes
+        .iter()
+        .find(|a| a.name() == "map")
+        .and_then(|a| a.first_arg())
+        .and_then(|v| v.as_string())
+        .map(str::to_string)
+        .unwrap_or_else(|| to_snake_case(field.name()))
+}
+
+/// Quote a property key unless it is a valid identifier.
+fn property_key(key: &str) -> String {
+    let mut chars = key.chars();
+    let is_ident = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_ident {
+        key.to_string()
+    } else {
+        format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Render documentation as a JSDoc comment.
+fn jsdoc(documentation: Option<&Documentation>, indent: &str) -> String {
+    let Some(doc) = documentation else {
+        return String::new();
+    };
+    let lines: Vec<&str> = doc.text.lines().map(str::trim).collect();
+    match lines.as_slice() {
+        [] => String::new(),
+        [line] => format!("{}/** {} */\n", indent, line),
+        lines => {
+            let mut out = format!("{}/**\n", indent);
+            for line in lines {
+                if line.is_empty() {
+                    out.push_str(&format!("{} *\n", indent));
+                } else {
+                    out.push_str(&format!("{} * {}\n", indent, line));
+                }
+            }
+            out.push_str(&format!("{} */\n", indent));
+            out
+        }
+    }
+}
+
+/// Convert a field type to the TypeScript type of its JSON.
+fn field_type_to_typescript(field_type: &FieldType, modifier: &TypeModifier) -> String {
+    let base = match field_type {
+        FieldType::Scalar(scalar) => scalar_to_typescript(scalar).to_string(),
+        FieldType::Enum(name) | FieldType::Model(name) | FieldType::Composite(name) => {
+            name.to_string()
+        }
+        FieldType::Unsupported(_) => "unknown".to_string(),
+    };
+
+    match modifier {
+        TypeModifier::Required => base,
+        TypeModifier::Optional => format!("{} | null", base),
+        TypeModifier::List => format!("{}[]", base),
+        TypeModifier::OptionalList => format!("{}[] | null", base),
+    }
+}
+
+/// Convert a scalar type to the TypeScript type of its JSON.
+fn scalar_to_typescript(scalar: &ScalarType) -> &'static str {
+    match scalar {
+        ScalarType::Int | ScalarType::BigInt | ScalarType::Float => "number",
+        // Decimals serialize as strings to keep their precision
+        ScalarType::Decimal => "string",
+        ScalarType::Boolean => "boolean",
+        ScalarType::String
+        | ScalarType::DateTime
+        | ScalarType::Date
+        | ScalarType::Time
+        | ScalarType::Uuid => "string",
+        ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => "string",
+        ScalarType::IntRange | ScalarType::BigIntRange => "{ start: number; end: number }",
+        ScalarType::DateTimeRange => "{ start: string; end: string }",
+        ScalarType::Inet | ScalarType::Cidr | ScalarType::MacAddr => "string",
+        ScalarType::Geometry | ScalarType::Geography => "string",
+        ScalarType::Money => "{ amount: string; currency: string }",
+        ScalarType::Json => "unknown",
+        ScalarType::Bytes | ScalarType::Bit(_) => "number[]",
+        ScalarType::Vector(_) | ScalarType::HalfVector(_) => "number[]",
+        ScalarType::SparseVector(_) => "[number, number][]",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> prax_schema::Schema {
+        prax_schema::parse_schema(
+            r#"
+            enum Role {
+                User  @map("user")
+                Admin @map("admin")
+            }
+
+            /// A registered user.
+            model User {
+                id        Int       @id @auto
+                email     String    @map("email-address")
+                role      Role
+                nickname  String?
+                tags      String[]
+                balance   Decimal
+                createdAt DateTime
+            }
+
+            view UserStats {
+                userId Int @map("user_id")
+                posts  Int
+            }
+        "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_typescript_plugin_model() {
+        let schema = schema();
+        let config = crate::plugins::PluginConfig::new();
+        let ctx = PluginContext::new(&schema, &config);
+
+        let output = TypeScriptPlugin.on_model(&ctx, schema.get_model("User").unwrap());
+        let code = output.tokens.to_string();
+        assert!(code.contains("_typescript"));
+        assert!(code.contains("DECLARATION"));
+    }
+
+    #[test]
+    fn test_typescript_declarations() {
+        let schema = schema();
+        let user = schema.get_model("User").unwrap();
+
+        assert_eq!(
+            interface(
+                user.name(),
+                user.documentation.as_ref(),
+                user.fields.values()
+            ),
+            "/** A registered user. */\n\
+             export interface User {\n  \
+               id: number;\n  \
+               \"email-address\": string;\n  \
+               role: Role;\n  \
+               nickname: string | null;\n  \
+               tags: string[];\n  \
+               balance: string;\n  \
+               created_at: string;\n\
+             }"
+        );
+
+        let config = crate::plugins::PluginConfig::new();
+        let ctx = PluginContext::new(&schema, &config);
+        let code = TypeScriptPlugin.on_finish(&ctx).tokens.to_string();
+        assert!(code.contains(r#"export type Role = \"user\" | \"admin\";"#));
+        assert!(
+            code.contains(
+                "export interface UserStats {\\n  user_id: number;\\n  posts: number;\\n}"
+            )
+        );
+        assert!(code.contains("fn write"));
+    }
+
+    #[test]
+    fn test_field_type_to_typescript() {
+        assert_eq!(
+            field_type_to_typescript(
+                &FieldType::Scalar(ScalarType::Money),
+                &TypeModifier::OptionalList
+            ),
+            "{ amount: string; currency: string }[] | null"
+        );
+        assert_eq!(
+            field_type_to_typescript(
+                &FieldType::Scalar(ScalarType::Json),
+                &TypeModifier::Optional
+            ),
+            "unknown | null"
+        );
+        assert_eq!(property_key("user_id"), "user_id");
+        assert_eq!(property_key("2fa"), "\"2fa\"");
+    }
+}
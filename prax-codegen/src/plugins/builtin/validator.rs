@@ -1,20 +1,24 @@
 //! Validator plugin - generates runtime validation methods.
 
-use quote::quote;
+use quote::{format_ident, quote};
 
-use prax_schema::ast::{FieldType, Model, ScalarType};
+use prax_schema::ast::{Field, FieldType, Model, ScalarType, ValidationRule, ValidationType};
 
+use crate::generators::{pascal_ident, snake_ident};
 use crate::plugins::{Plugin, PluginContext, PluginOutput};
 
 /// Validator plugin that generates runtime validation methods.
 ///
-/// When enabled, this plugin generates `validate()` methods for each model
-/// that check field constraints like:
-/// - Required fields are not null
-/// - String length constraints
-/// - Numeric range constraints
-/// - Email format validation
-/// - Custom regex patterns
+/// When enabled, this plugin implements `Validate` for each model and its
+/// `CreateInput` and `UpdateInput` structs, checking field constraints like:
+/// - Required strings are not empty
+/// - String length constraints from `@length(min: 3, max: 120)`
+/// - Numeric range constraints from `@range(min: 0, max: 100)`
+/// - Email format validation from `@email`
+/// - Custom regex patterns from `@regex("^[a-z]+$")`
+///
+/// `validate()` returns every failing check as a `ValidationError` naming
+/// the field and the rule.
 ///
 /// Enable with: `PRAX_PLUGIN_VALIDATOR=1`
 pub struct ValidatorPlugin;
@@ -37,19 +41,27 @@ impl Plugin for ValidatorPlugin {
             /// Validation error types.
             pub mod _validation {
                 /// A validation error.
-                #[derive(Debug, Clone)]
+                #[derive(Debug, Clone, PartialEq, Eq)]
                 pub struct ValidationError {
                     /// The field that failed validation.
                     pub field: String,
+                    /// The rule that failed: `empty`, `email`, `length`, `range`
+                    /// or `regex`.
+                    pub rule: &'static str,
                     /// The error message.
                     pub message: String,
                 }
 
                 impl ValidationError {
                     /// Create a new validation error.
-                    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+                    pub fn new(
+                        field: impl Into<String>,
+                        rule: &'static str,
+                        message: impl Into<String>,
+                    ) -> Self {
                         Self {
                             field: field.into(),
+                            rule,
                             message: message.into(),
                         }
                     }
@@ -81,127 +93,264 @@ impl Plugin for ValidatorPlugin {
     }
 
     fn on_model(&self, _ctx: &PluginContext, model: &Model) -> PluginOutput {
-        let model_name = model.name();
+        let model_name = pascal_ident(model.name());
 
-        // Generate validation checks for each field
-        let validations: Vec<_> = model
+        let checked: Vec<_> = model
             .fields
             .values()
-            .filter_map(|field| {
-                let field_name = field.name();
-                let field_name_str = field_name.to_string();
-
-                let mut checks = Vec::new();
-
-                // Check for @unique (informational only at runtime)
-                if field.has_attribute("unique") {
-                    // Uniqueness can only be checked at database level
-                }
-
-                // Check string length constraints from native types or attributes
-                if matches!(field.field_type, FieldType::Scalar(ScalarType::String)) {
-                    // Check for length attributes
-                    for attr in &field.attributes {
-                        if attr.name() == "db" {
-                            // Parse native type for length info (e.g., @db.VarChar(255))
-                            // This is simplified; real implementation would parse the native type
-                        }
-                    }
-                }
-
-                // Check for required fields
-                if !field.modifier.is_optional() {
-                    if let FieldType::Scalar(ScalarType::String) = &field.field_type {
-                        checks.push(quote! {
-                            if self.#field_name.is_empty() {
-                                errors.push(super::super::_validation::ValidationError::new(
-                                    #field_name_str,
-                                    "cannot be empty"
-                                ));
-                            }
-                        });
+            .filter_map(|field| field_checks(field).map(|check| (field, check)))
+            .collect();
+        let has_validations = !checked.is_empty();
+
+        let check_fns = checked.iter().map(|(_, check)| check);
+
+        // Model fields are optional when the schema says so
+        let model_checks = checked
+            .iter()
+            .map(|(field, _)| call_check(field, field.modifier.is_optional()));
+
+        // Input fields match the CreateInput and UpdateInput structs
+        let input_fields: Vec<_> = checked
+            .iter()
+            .map(|(field, _)| *field)
+            .filter(|field| {
+                let attrs = field.extract_attributes();
+                !attrs.is_auto && !attrs.is_updated_at && !field.is_virtual()
+            })
+            .collect();
+        let create_checks = input_fields.iter().map(|field| {
+            let optional =
+                field.modifier.is_optional() || field.extract_attributes().default.is_some();
+            call_check(field, optional)
+        });
+        let update_checks = input_fields.iter().map(|field| call_check(field, true));
+
+        let impls = [
+            (
+                quote! { super::#model_name },
+                model_checks.collect::<Vec<_>>(),
+            ),
+            (quote! { super::CreateInput }, create_checks.collect()),
+            (quote! { super::UpdateInput }, update_checks.collect()),
+        ]
+        .into_iter()
+        .map(|(target, checks)| {
+            let body = if checks.is_empty() {
+                quote! { Ok(()) }
+            } else {
+                quote! {
+                    let mut errors = Vec::new();
+
+                    #(#checks)*
+
+                    if errors.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(errors)
                     }
                 }
-
-                // Check optional fields
-                if field.modifier.is_optional() {
-                    if let FieldType::Scalar(ScalarType::String) = &field.field_type {
-                        checks.push(quote! {
-                            if let Some(ref val) = self.#field_name {
-                                if val.is_empty() {
-                                    errors.push(super::super::_validation::ValidationError::new(
-                                        #field_name_str,
-                                        "if provided, cannot be empty"
-                                    ));
-                                }
-                            }
-                        });
+            };
+            quote! {
+                impl super::super::_validation::Validate for #target {
+                    fn validate(&self) -> super::super::_validation::ValidationResult {
+                        #body
                     }
                 }
+            }
+        });
 
-                // Check for email fields (by name convention or attribute)
-                let is_email = field_name.to_lowercase().contains("email")
-                    || field.attributes.iter().any(|a| a.name() == "email");
-
-                if is_email && matches!(field.field_type, FieldType::Scalar(ScalarType::String)) {
-                    let email_check = if field.modifier.is_optional() {
-                        quote! {
-                            if let Some(ref email) = self.#field_name {
-                                if !email.contains('@') || !email.contains('.') {
-                                    errors.push(super::super::_validation::ValidationError::new(
-                                        #field_name_str,
-                                        "must be a valid email address"
-                                    ));
-                                }
-                            }
-                        }
-                    } else {
-                        quote! {
-                            if !self.#field_name.contains('@') || !self.#field_name.contains('.') {
-                                errors.push(super::super::_validation::ValidationError::new(
-                                    #field_name_str,
-                                    "must be a valid email address"
-                                ));
-                            }
-                        }
-                    };
-                    checks.push(email_check);
-                }
-
-                if checks.is_empty() {
-                    None
-                } else {
-                    Some(quote! { #(#checks)* })
-                }
-            })
-            .collect();
-
-        let has_validations = !validations.is_empty();
+        let import = if has_validations {
+            quote! { use super::super::_validation::ValidationError; }
+        } else {
+            quote! {}
+        };
 
         PluginOutput::with_tokens(quote! {
-            /// Validation implementation for this model.
+            /// Validation implementation for this model and its inputs.
             pub mod _validator {
-                use super::*;
+                #import
 
                 /// Whether this model has any validation rules.
                 pub const HAS_VALIDATIONS: bool = #has_validations;
 
-                impl super::super::_validation::Validate for super::#model_name {
-                    fn validate(&self) -> super::super::_validation::ValidationResult {
-                        let mut errors = Vec::new();
+                #(#check_fns)*
 
-                        #(#validations)*
+                #(#impls)*
+            }
+        })
+    }
+}
 
-                        if errors.is_empty() {
-                            Ok(())
-                        } else {
-                            Err(errors)
-                        }
+/// Generate the `check_<field>` function validating one field value, or
+/// `None` if the field has nothing to check.
+///
+/// Checks cover emptiness of strings, `@email` (or an email-like field
+/// name), `@length` and `@regex` on strings, and `@range` on numbers. List
+/// fields are not checked.
+fn field_checks(field: &Field) -> Option<proc_macro2::TokenStream> {
+    let FieldType::Scalar(scalar) = &field.field_type else {
+        return None;
+    };
+    if field.is_list() {
+        return None;
+    }
+    let label = field.name();
+    let value_type = match scalar {
+        ScalarType::String => quote! { str },
+        ScalarType::Int => quote! { i32 },
+        ScalarType::BigInt => quote! { i64 },
+        ScalarType::Float => quote! { f64 },
+        _ => return None,
+    };
+    let is_string = *scalar == ScalarType::String;
+    let number = match scalar {
+        ScalarType::Int => quote! { f64::from(*value) },
+        ScalarType::BigInt => quote! { (*value as f64) },
+        _ => quote! { *value },
+    };
+
+    let error = |rule: &str, message: String| {
+        quote! {
+            errors.push(ValidationError::new(#label, #rule, #message));
+        }
+    };
+
+    let mut checks = Vec::new();
+
+    if is_string {
+        let message = if field.modifier.is_optional() {
+            "if provided, cannot be empty"
+        } else {
+            "cannot be empty"
+        };
+        let push = error("empty", message.to_string());
+        checks.push(quote! {
+            if value.is_empty() {
+                #push
+            }
+        });
+    }
+
+    // Email fields are recognized by name convention or attribute
+    let is_email = field.name().to_lowercase().contains("email")
+        || field.has_attribute("email")
+        || field
+            .validation
+            .rules
+            .iter()
+            .any(|rule| rule.rule_type == ValidationType::Email);
+    if is_email && is_string {
+        let message = field
+            .validation
+            .rules
+            .iter()
+            .find(|rule| rule.rule_type == ValidationType::Email)
+            .map_or_else(|| "must be a valid email address".to_string(), message);
+        let push = error("email", message);
+        checks.push(quote! {
+            if !::prax_query::validation::is_email(value) {
+                #push
+            }
+        });
+    }
+
+    for rule in &field.validation.rules {
+        let check = match (&rule.rule_type, is_string) {
+            (ValidationType::Length { min, max }, true) => {
+                let push = error("length", message(rule));
+                quote! {
+                    if !(#min..=#max).contains(&value.chars().count()) {
+                        #push
                     }
                 }
             }
-        })
+            (ValidationType::MinLength(min), true) => {
+                let push = error("length", message(rule));
+                quote! {
+                    if value.chars().count() < #min {
+                        #push
+                    }
+                }
+            }
+            (ValidationType::MaxLength(max), true) => {
+                let push = error("length", message(rule));
+                quote! {
+                    if value.chars().count() > #max {
+                        #push
+                    }
+                }
+            }
+            (ValidationType::Regex(pattern), true) => {
+                let push = error("regex", message(rule));
+                quote! {
+                    if !::prax_query::validation::matches_pattern(#pattern, value) {
+                        #push
+                    }
+                }
+            }
+            (ValidationType::Range { min, max }, false) => {
+                let push = error("range", message(rule));
+                quote! {
+                    if !(#min..=#max).contains(&#number) {
+                        #push
+                    }
+                }
+            }
+            (ValidationType::Min(min), false) => {
+                let push = error("range", message(rule));
+                quote! {
+                    if #number < #min {
+                        #push
+                    }
+                }
+            }
+            (ValidationType::Max(max), false) => {
+                let push = error("range", message(rule));
+                quote! {
+                    if #number > #max {
+                        #push
+                    }
+                }
+            }
+            _ => continue,
+        };
+        checks.push(check);
+    }
+
+    if checks.is_empty() {
+        return None;
     }
+
+    let check_fn = format_ident!("check_{}", snake_ident(field.name()));
+    Some(quote! {
+        fn #check_fn(value: &#value_type, errors: &mut Vec<ValidationError>) {
+            #(#checks)*
+        }
+    })
+}
+
+/// Call a field's `check_<field>` function on `self`, skipping `None` for
+/// optional fields.
+fn call_check(field: &Field, optional: bool) -> proc_macro2::TokenStream {
+    let field_name = snake_ident(field.name());
+    let check_fn = format_ident!("check_{}", field_name);
+    if optional {
+        quote! {
+            if let Some(value) = &self.#field_name {
+                #check_fn(value, &mut errors);
+            }
+        }
+    } else {
+        quote! {
+            #check_fn(&self.#field_name, &mut errors);
+        }
+    }
+}
+
+/// The rule's custom message, or its default message without the field
+/// name, which the error already carries.
+fn message(rule: &ValidationRule) -> String {
+    rule.error_message("").trim_start().to_string()
 }
 
 #[cfg(test)]
@@ -340,6 +489,7 @@ mod tests {
         let code = output.tokens.to_string();
         assert!(code.contains("struct ValidationError"));
         assert!(code.contains("pub field"));
+        assert!(code.contains("pub rule"));
         assert!(code.contains("pub message"));
         assert!(code.contains("fn new"));
     }
@@ -593,4 +743,41 @@ mod tests {
         let code = output.tokens.to_string();
         assert!(code.contains("impl std :: error :: Error"));
     }
+
+    #[test]
+    fn test_validator_plugin_shorthand_attributes() {
+        let schema = prax_schema::parse_schema(
+            r#"
+            model Post {
+                id     Int     @id @auto
+                title  String  @length(min: 3, max: 120)
+                slug   String  @regex("^[a-z0-9-]+$")
+                rating Float?  @range(min: 0, max: 5)
+                views  Int     @default(0) @range(min: 0)
+            }
+        "#,
+        )
+        .unwrap();
+        let config = crate::plugins::PluginConfig::new();
+        let ctx = PluginContext::new(&schema, &config);
+
+        let plugin = ValidatorPlugin;
+        let output = plugin.on_model(&ctx, schema.get_model("Post").unwrap());
+
+        let code = output.tokens.to_string();
+        assert!(code.contains("fn check_title (value : & str"));
+        assert!(code.contains("(3usize ..= 120usize) . contains (& value . chars () . count ())"));
+        assert!(code.contains("\"title\" , \"length\" , \"must be between 3 and 120 characters\""));
+        assert!(code.contains("matches_pattern (\"^[a-z0-9-]+$\" , value)"));
+        assert!(code.contains("(0f64 ..= 5f64) . contains (& * value)"));
+        assert!(code.contains("f64 :: from (* value) < 0f64"));
+
+        // The model, CreateInput and UpdateInput are all validated
+        assert!(code.contains("Validate for super :: Post"));
+        assert!(code.contains("Validate for super :: CreateInput"));
+        assert!(code.contains("Validate for super :: UpdateInput"));
+        assert!(code.contains("check_views (& self . views , & mut errors)"));
+        assert!(code.contains("if let Some (value) = & self . views { check_views"));
+        assert!(!code.contains("check_id"));
+    }
 }
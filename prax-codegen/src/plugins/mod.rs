@@ -17,6 +17,9 @@
 //! # Enable GraphQL types
 //! PRAX_PLUGIN_GRAPHQL=1 cargo build
 //!
+//! # Enable TypeScript declarations
+//! PRAX_PLUGIN_TYPESCRIPT=1 cargo build
+//!
 //! # Enable all plugins
 //! PRAX_PLUGINS_ALL=1 cargo build
 //!
@@ -26,10 +29,12 @@
 //!
 //! # Custom Plugins
 //!
-//! Implement the [`Plugin`] trait to create custom plugins:
+//! The plugin interface lives in the `prax-plugin-api` crate, which carries
+//! the stability guarantees for third-party plugins. Implement the [`Plugin`]
+//! trait to create custom plugins:
 //!
 //! ```rust,ignore
-//! use prax_codegen::plugins::{Plugin, PluginContext, PluginOutput};
+//! use prax_plugin_api::{Plugin, PluginContext, PluginOutput};
 //!
 //! struct MyPlugin;
 //!
@@ -43,131 +48,33 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Custom Attributes
+//!
+//! Attributes in a namespace Prax does not own, such as `@search.indexed`
+//! or `@@audit.track("all")`, are accepted by the parser and kept on the
+//! AST. Plugins declare the namespaces they read with
+//! [`Plugin::attribute_namespaces`] and query them per element:
+//!
+//! ```rust,ignore
+//! fn on_model(&self, _ctx: &PluginContext, model: &Model) -> PluginOutput {
+//!     for field in model.fields.values() {
+//!         if field.custom_attributes().has("search", "indexed") {
+//!             // ...
+//!         }
+//!     }
+//!     PluginOutput::new()
+//! }
+//! ```
 
 pub mod builtin;
-pub mod config;
 
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use prax_schema::ast::{CompositeType, Enum, Model, Schema, View};
-
-pub use config::PluginConfig;
-
-/// Output from a plugin hook.
-#[derive(Debug, Default, Clone)]
-pub struct PluginOutput {
-    /// Additional tokens to add to the module.
-    pub tokens: TokenStream,
-    /// Additional items to add at the crate root level.
-    pub root_items: TokenStream,
-    /// Additional imports needed.
-    pub imports: Vec<String>,
-}
-
-#[allow(dead_code)]
-impl PluginOutput {
-    /// Create an empty plugin output.
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// Create output with tokens.
-    pub fn with_tokens(tokens: TokenStream) -> Self {
-        Self {
-            tokens,
-            ..Default::default()
-        }
-    }
+use prax_schema::ast::{CompositeType, Enum, Model, View};
 
-    /// Add tokens to the output.
-    pub fn add_tokens(&mut self, tokens: TokenStream) {
-        self.tokens.extend(tokens);
-    }
-
-    /// Add root-level items.
-    pub fn add_root_items(&mut self, tokens: TokenStream) {
-        self.root_items.extend(tokens);
-    }
-
-    /// Add an import.
-    pub fn add_import(&mut self, import: impl Into<String>) {
-        self.imports.push(import.into());
-    }
-
-    /// Merge another output into this one.
-    pub fn merge(&mut self, other: PluginOutput) {
-        self.tokens.extend(other.tokens);
-        self.root_items.extend(other.root_items);
-        self.imports.extend(other.imports);
-    }
-
-    /// Check if the output is empty.
-    pub fn is_empty(&self) -> bool {
-        self.tokens.is_empty() && self.root_items.is_empty() && self.imports.is_empty()
-    }
-}
-
-/// Context provided to plugins during code generation.
-#[derive(Debug)]
-pub struct PluginContext<'a> {
-    /// The full schema being processed.
-    pub schema: &'a Schema,
-    /// Plugin configuration.
-    pub config: &'a PluginConfig,
-}
-
-impl<'a> PluginContext<'a> {
-    /// Create a new plugin context.
-    pub fn new(schema: &'a Schema, config: &'a PluginConfig) -> Self {
-        Self { schema, config }
-    }
-}
-
-/// Trait for implementing code generation plugins.
-pub trait Plugin: Send + Sync {
-    /// The unique name of this plugin.
-    fn name(&self) -> &'static str;
-
-    /// The environment variable that controls this plugin.
-    /// Should follow the pattern `PRAX_PLUGIN_<NAME>`.
-    fn env_var(&self) -> &'static str;
-
-    /// Description of what this plugin does.
-    fn description(&self) -> &'static str {
-        "No description provided"
-    }
-
-    /// Called once at the start of code generation.
-    fn on_start(&self, _ctx: &PluginContext) -> PluginOutput {
-        PluginOutput::new()
-    }
-
-    /// Called for each model in the schema.
-    fn on_model(&self, _ctx: &PluginContext, _model: &Model) -> PluginOutput {
-        PluginOutput::new()
-    }
-
-    /// Called for each enum in the schema.
-    fn on_enum(&self, _ctx: &PluginContext, _enum_def: &Enum) -> PluginOutput {
-        PluginOutput::new()
-    }
-
-    /// Called for each composite type in the schema.
-    fn on_type(&self, _ctx: &PluginContext, _type_def: &CompositeType) -> PluginOutput {
-        PluginOutput::new()
-    }
-
-    /// Called for each view in the schema.
-    fn on_view(&self, _ctx: &PluginContext, _view: &View) -> PluginOutput {
-        PluginOutput::new()
-    }
-
-    /// Called once at the end of code generation.
-    fn on_finish(&self, _ctx: &PluginContext) -> PluginOutput {
-        PluginOutput::new()
-    }
-}
+pub use prax_plugin_api::{Plugin, PluginConfig, PluginContext, PluginOutput};
 
 /// Registry of all available plugins.
 #[derive(Default)]
@@ -189,6 +96,7 @@ impl PluginRegistry {
         registry.register(Box::new(builtin::GraphQLPlugin));
         registry.register(Box::new(builtin::SerdePlugin));
         registry.register(Box::new(builtin::ValidatorPlugin));
+        registry.register(Box::new(builtin::TypeScriptPlugin));
         registry
     }
 
@@ -279,6 +187,14 @@ pub fn generate_plugin_docs(registry: &PluginRegistry) -> TokenStream {
         doc_lines.push(format!("## {}", plugin.name()));
         doc_lines.push(format!("- **Env var**: `{}`", plugin.env_var()));
         doc_lines.push(format!("- **Description**: {}", plugin.description()));
+        if !plugin.attribute_namespaces().is_empty() {
+            let namespaces: Vec<String> = plugin
+                .attribute_namespaces()
+                .iter()
+                .map(|ns| format!("`@{}.*`", ns))
+                .collect();
+            doc_lines.push(format!("- **Attributes**: {}", namespaces.join(", ")));
+        }
         doc_lines.push(String::new());
     }
 
@@ -292,6 +208,7 @@ pub fn generate_plugin_docs(registry: &PluginRegistry) -> TokenStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use prax_schema::ast::Schema;
 
     struct TestPlugin;
 
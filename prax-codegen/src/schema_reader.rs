@@ -20,8 +20,43 @@ pub fn read_and_parse_schema(path: &str) -> Result<Schema, SchemaReadError> {
     Ok(result.schema)
 }
 
-/// Read and parse a schema file along with prax.toml configuration.
-pub fn read_schema_with_config(path: &str) -> Result<SchemaWithConfig, SchemaReadError> {
+/// Raw, unparsed schema and configuration sources.
+pub struct SchemaSource {
+    /// Resolved path of the schema file.
+    pub path: PathBuf,
+    /// Schema file contents.
+    pub content: String,
+    /// Contents of the nearest prax.toml, if any.
+    pub config_content: Option<String>,
+}
+
+impl SchemaSource {
+    /// Parse and validate the schema and configuration.
+    pub fn parse(&self) -> Result<SchemaWithConfig, SchemaReadError> {
+        // validate_schema parses and validates in one step
+        let mut schema =
+            validate_schema(&self.content).map_err(|e| SchemaReadError::Validation {
+                path: self.path.display().to_string(),
+                error: e.to_string(),
+            })?;
+
+        let config = self
+            .config_content
+            .as_deref()
+            .and_then(|c| PraxConfig::from_str(c).ok())
+            .unwrap_or_default();
+        config.generator.client.apply_naming(&mut schema);
+        let model_style = config.generator.client.model_style;
+
+        Ok(SchemaWithConfig {
+            schema,
+            model_style,
+        })
+    }
+}
+
+/// Read a schema file and the nearest prax.toml without parsing them.
+pub fn read_schema_source(path: &str) -> Result<SchemaSource, SchemaReadError> {
     let full_path = resolve_schema_path(path)?;
 
     let content = std::fs::read_to_string(&full_path).map_err(|e| SchemaReadError::Io {
@@ -29,25 +64,24 @@ pub fn read_schema_with_config(path: &str) -> Result<SchemaWithConfig, SchemaRea
         error: e.to_string(),
     })?;
 
-    // validate_schema parses and validates in one step
-    let schema = validate_schema(&content).map_err(|e| SchemaReadError::Validation {
-        path: full_path.display().to_string(),
-        error: e.to_string(),
-    })?;
-
     // Try to load prax.toml from the same directory or parent directories
-    let model_style = load_prax_config(&full_path)
-        .map(|c| c.generator.client.model_style)
-        .unwrap_or_default();
+    let config_content = find_prax_config(&full_path);
 
-    Ok(SchemaWithConfig {
-        schema,
-        model_style,
+    Ok(SchemaSource {
+        path: full_path,
+        content,
+        config_content,
     })
 }
 
-/// Try to load prax.toml from the schema file's directory or parent directories.
-fn load_prax_config(schema_path: &Path) -> Option<PraxConfig> {
+/// Read and parse a schema file along with prax.toml configuration.
+#[allow(dead_code)]
+pub fn read_schema_with_config(path: &str) -> Result<SchemaWithConfig, SchemaReadError> {
+    read_schema_source(path)?.parse()
+}
+
+/// Read prax.toml from the schema file's directory or parent directories.
+fn find_prax_config(schema_path: &Path) -> Option<String> {
     let mut search_dir = schema_path.parent()?;
 
     // Search up to 5 parent directories
@@ -55,8 +89,8 @@ fn load_prax_config(schema_path: &Path) -> Option<PraxConfig> {
         let config_path = search_dir.join("prax.toml");
         if config_path.exists() {
             if let Ok(content) = std::fs::read_to_string(&config_path) {
-                if let Ok(config) = PraxConfig::from_str(&content) {
-                    return Some(config);
+                if PraxConfig::from_str(&content).is_ok() {
+                    return Some(content);
                 }
             }
         }
@@ -25,6 +25,17 @@ pub fn scalar_to_rust_type(scalar: &ScalarType) -> TokenStream {
         ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => {
             quote! { String }
         }
+        // Ranges as half-open std ranges
+        ScalarType::IntRange => quote! { std::ops::Range<i32> },
+        ScalarType::BigIntRange => quote! { std::ops::Range<i64> },
+        ScalarType::DateTimeRange => quote! { std::ops::Range<chrono::DateTime<chrono::Utc>> },
+        // Network address types (require the ipnetwork and macaddr crates)
+        ScalarType::Inet | ScalarType::Cidr => quote! { ipnetwork::IpNetwork },
+        ScalarType::MacAddr => quote! { macaddr::MacAddr6 },
+        // PostGIS shapes as EWKT
+        ScalarType::Geometry | ScalarType::Geography => quote! { prax_query::spatial::Geometry },
+        // Amount and currency (requires the rust_decimal crate)
+        ScalarType::Money => quote! { prax_query::money::Money },
         // PostgreSQL vector types (require pgvector crate)
         ScalarType::Vector(_) | ScalarType::HalfVector(_) => quote! { Vec<f32> },
         ScalarType::SparseVector(_) => quote! { Vec<(u32, f32)> },
@@ -79,6 +90,16 @@ pub fn field_type_to_sql_type(field_type: &FieldType) -> &'static str {
             ScalarType::Uuid => "UUID",
             // String-based ID types (stored as TEXT/VARCHAR in database)
             ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => "TEXT",
+            ScalarType::IntRange => "INT4RANGE",
+            ScalarType::BigIntRange => "INT8RANGE",
+            ScalarType::DateTimeRange => "TSTZRANGE",
+            ScalarType::Inet => "INET",
+            ScalarType::Cidr => "CIDR",
+            ScalarType::MacAddr => "MACADDR",
+            ScalarType::Geometry => "GEOMETRY",
+            ScalarType::Geography => "GEOGRAPHY",
+            // The amount column; the currency is stored beside it
+            ScalarType::Money => "DECIMAL(19, 4)",
             // PostgreSQL vector extension types (dimension is handled separately)
             ScalarType::Vector(_) => "vector",
             ScalarType::HalfVector(_) => "halfvec",
@@ -130,6 +151,18 @@ pub fn default_value_for_type(scalar: &ScalarType) -> TokenStream {
         ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => {
             quote! { String::new() }
         }
+        ScalarType::IntRange | ScalarType::BigIntRange => quote! { 0..0 },
+        ScalarType::DateTimeRange => {
+            quote! { chrono::DateTime::UNIX_EPOCH..chrono::DateTime::UNIX_EPOCH }
+        }
+        ScalarType::Inet | ScalarType::Cidr => {
+            quote! { ipnetwork::IpNetwork::from(std::net::Ipv4Addr::UNSPECIFIED) }
+        }
+        ScalarType::MacAddr => quote! { macaddr::MacAddr6::nil() },
+        ScalarType::Geometry | ScalarType::Geography => {
+            quote! { prax_query::spatial::Geometry::default() }
+        }
+        ScalarType::Money => quote! { prax_query::money::Money::default() },
         // Vector types default to empty vector
         ScalarType::Vector(_) | ScalarType::HalfVector(_) => quote! { Vec::new() },
         ScalarType::SparseVector(_) => quote! { Vec::new() },
@@ -159,7 +192,14 @@ pub fn supports_string_ops(scalar: &ScalarType) -> bool {
 /// Check if a type supports the `in` operation.
 pub fn supports_in_op(field_type: &FieldType) -> bool {
     match field_type {
-        FieldType::Scalar(scalar) => !matches!(scalar, ScalarType::Json | ScalarType::Bytes),
+        FieldType::Scalar(scalar) => !matches!(
+            scalar,
+            ScalarType::Json
+                | ScalarType::Bytes
+                | ScalarType::Geometry
+                | ScalarType::Geography
+                | ScalarType::Money
+        ),
         FieldType::Enum(_) => true,
         _ => false,
     }
@@ -246,6 +286,31 @@ mod tests {
             "String"
         );
         assert_eq!(scalar_to_rust_type(&ScalarType::Ulid).to_string(), "String");
+
+        assert_eq!(
+            scalar_to_rust_type(&ScalarType::IntRange).to_string(),
+            "std :: ops :: Range < i32 >"
+        );
+        assert_eq!(
+            scalar_to_rust_type(&ScalarType::DateTimeRange).to_string(),
+            "std :: ops :: Range < chrono :: DateTime < chrono :: Utc >>"
+        );
+        assert_eq!(
+            scalar_to_rust_type(&ScalarType::Cidr).to_string(),
+            "ipnetwork :: IpNetwork"
+        );
+        assert_eq!(
+            scalar_to_rust_type(&ScalarType::MacAddr).to_string(),
+            "macaddr :: MacAddr6"
+        );
+        assert_eq!(
+            scalar_to_rust_type(&ScalarType::Geography).to_string(),
+            "prax_query :: spatial :: Geometry"
+        );
+        assert_eq!(
+            scalar_to_rust_type(&ScalarType::Money).to_string(),
+            "prax_query :: money :: Money"
+        );
     }
 
     #[test]
@@ -390,6 +455,14 @@ mod tests {
             field_type_to_sql_type(&FieldType::Scalar(ScalarType::Ulid)),
             "TEXT"
         );
+        assert_eq!(
+            field_type_to_sql_type(&FieldType::Scalar(ScalarType::DateTimeRange)),
+            "TSTZRANGE"
+        );
+        assert_eq!(
+            field_type_to_sql_type(&FieldType::Scalar(ScalarType::BigIntRange)),
+            "INT8RANGE"
+        );
     }
 
     #[test]
@@ -2,6 +2,9 @@
 
 use std::sync::Arc;
 
+use duckdb::arrow::record_batch::RecordBatch;
+#[cfg(feature = "polars")]
+use duckdb::polars::prelude::DataFrame;
 use duckdb::{Connection, Statement};
 use parking_lot::Mutex;
 use serde_json::Value as JsonValue;
@@ -194,6 +197,54 @@ impl DuckDbConnection {
         Ok(rowid)
     }
 
+    /// Execute a query and return the result as Arrow record batches.
+    ///
+    /// Columns are handed over in DuckDB's own columnar layout instead of
+    /// being converted row by row, which is what analytical consumers want.
+    #[instrument(skip(self, params), fields(sql = %sql))]
+    pub fn query_arrow(&self, sql: &str, params: &[FilterValue]) -> DuckDbResult<Vec<RecordBatch>> {
+        debug!("Executing Arrow query");
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(sql)?;
+
+        let duckdb_params: Vec<DuckDbParam<'_>> = params.iter().map(DuckDbParam).collect();
+        let param_refs: Vec<&dyn duckdb::ToSql> = duckdb_params
+            .iter()
+            .map(|p| p as &dyn duckdb::ToSql)
+            .collect();
+
+        let batches = stmt.query_arrow(param_refs.as_slice())?.collect();
+        Ok(batches)
+    }
+
+    /// Execute a query and return the result as a Polars `DataFrame`.
+    #[cfg(feature = "polars")]
+    #[instrument(skip(self, params), fields(sql = %sql))]
+    pub fn query_polars(&self, sql: &str, params: &[FilterValue]) -> DuckDbResult<DataFrame> {
+        debug!("Executing Polars query");
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(sql)?;
+
+        let duckdb_params: Vec<DuckDbParam<'_>> = params.iter().map(DuckDbParam).collect();
+        let param_refs: Vec<&dyn duckdb::ToSql> = duckdb_params
+            .iter()
+            .map(|p| p as &dyn duckdb::ToSql)
+            .collect();
+
+        // DuckDB yields one frame per vector chunk
+        let mut frames = stmt.query_polars(param_refs.as_slice())?;
+        let Some(mut df) = frames.next() else {
+            return Ok(DataFrame::empty());
+        };
+        for frame in frames {
+            df.vstack_mut(&frame)
+                .map_err(|e| DuckDbError::query(e.to_string()))?;
+        }
+        Ok(df)
+    }
+
     /// Convert DuckDB rows to JSON.
     fn rows_to_json(&self, mut rows: duckdb::Rows<'_>) -> DuckDbResult<Vec<JsonValue>> {
         let mut results = Vec::new();
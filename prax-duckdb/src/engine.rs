@@ -2,10 +2,16 @@
 
 use std::collections::HashMap;
 
+use duckdb::arrow::record_batch::RecordBatch;
+#[cfg(feature = "polars")]
+use duckdb::polars::prelude::DataFrame;
 use serde_json::Value as JsonValue;
 use tracing::{debug, instrument};
 
+use prax_query::error::QueryResult;
 use prax_query::filter::FilterValue;
+use prax_query::raw::RawDatabase;
+use prax_query::traits::BoxFuture;
 use prax_query::types::SortOrder;
 
 use crate::error::{DuckDbError, DuckDbResult};
@@ -484,6 +490,28 @@ impl DuckDbEngine {
         conn.copy_to_csv(query, path, header).await
     }
 
+    /// Execute a query and return the result as Arrow record batches.
+    ///
+    /// Skips the per-row JSON conversion of [`execute_raw`](Self::execute_raw),
+    /// so large analytical results stay columnar from DuckDB to the caller.
+    #[instrument(skip(self, params), fields(sql = %sql))]
+    pub async fn query_arrow(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+    ) -> DuckDbResult<Vec<RecordBatch>> {
+        let conn = self.pool.get().await?;
+        conn.query_arrow(sql, params).await
+    }
+
+    /// Execute a query and return the result as a Polars `DataFrame`.
+    #[cfg(feature = "polars")]
+    #[instrument(skip(self, params), fields(sql = %sql))]
+    pub async fn query_polars(&self, sql: &str, params: &[FilterValue]) -> DuckDbResult<DataFrame> {
+        let conn = self.pool.get().await?;
+        conn.query_polars(sql, params).await
+    }
+
     /// Query a Parquet file.
     pub async fn query_parquet(&self, path: &str) -> DuckDbResult<Vec<DuckDbQueryResult>> {
         let conn = self.pool.get().await?;
@@ -542,6 +570,49 @@ impl DuckDbEngine {
     }
 }
 
+impl RawDatabase for DuckDbEngine {
+    fn query(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+    ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let rows = self.execute_raw(&sql, &params).await?;
+            Ok(rows.into_iter().map(DuckDbQueryResult::into_json).collect())
+        })
+    }
+
+    fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+        let sql = sql.to_string();
+        Box::pin(async move { Ok(self.raw_sql_execute(&sql, &params).await?) })
+    }
+
+    fn execute_in_transaction(
+        &self,
+        statements: Vec<(String, Vec<FilterValue>)>,
+    ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+        Box::pin(async move {
+            let conn = self.pool.get().await?;
+            conn.execute_batch("BEGIN TRANSACTION").await?;
+
+            let mut affected = Vec::with_capacity(statements.len());
+            for (sql, params) in &statements {
+                match conn.execute(sql, params).await {
+                    Ok(n) => affected.push(n as u64),
+                    Err(e) => {
+                        conn.execute_batch("ROLLBACK").await?;
+                        return Err(e.into());
+                    }
+                }
+            }
+
+            conn.execute_batch("COMMIT").await?;
+            Ok(affected)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -592,5 +663,33 @@ mod tests {
         let count = engine.count("test", &HashMap::new()).await.unwrap();
         assert_eq!(count, 3);
     }
+
+    #[tokio::test]
+    async fn test_query_arrow() {
+        let pool = DuckDbPool::new(DuckDbConfig::in_memory()).await.unwrap();
+        let engine = DuckDbEngine::new(pool);
+
+        engine
+            .raw_sql_batch(
+                "CREATE TABLE test (id INTEGER, name VARCHAR);
+                 INSERT INTO test SELECT i, 'row ' || i FROM range(5000) t(i);",
+            )
+            .await
+            .unwrap();
+
+        let batches = engine
+            .query_arrow(
+                "SELECT id, name FROM test WHERE id >= ?",
+                &[FilterValue::Int(1000)],
+            )
+            .await
+            .unwrap();
+
+        let rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+        assert_eq!(rows, 4000);
+        let schema = batches[0].schema();
+        assert_eq!(schema.field(0).name(), "id");
+        assert_eq!(schema.field(1).name(), "name");
+    }
 }
 
@@ -0,0 +1,1344 @@
+//! Hybrid transactional/analytical processing with DuckDB as an accelerator.
+//!
+//! [`HybridAnalyticsEngine`] keeps the primary OLTP database as the source of
+//! truth and mirrors selected tables into DuckDB. Analytical reads (aggregates,
+//! `GROUP BY`, window functions) over mirrored tables are answered by DuckDB,
+//! while point reads and all writes continue to hit the primary.
+//!
+//! # Refresh Modes
+//!
+//! | Mode                       | Initial load | Subsequent refreshes                    |
+//! |----------------------------|--------------|-----------------------------------------|
+//! | [`RefreshMode::Full`]        | Snapshot     | Reload and swap the table atomically    |
+//! | [`RefreshMode::Incremental`] | Snapshot     | Pull rows whose change column advanced  |
+//! | [`RefreshMode::ChangeFeed`]  | Snapshot     | Apply events from [`HybridAnalyticsEngine::apply_changes`] |
+//!
+//! Incremental refreshes rely on a monotonically increasing column such as
+//! `updated_at` and do not observe deletes; use a full refresh or a change feed
+//! for tables where rows are removed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use prax_duckdb::hybrid::{HybridAnalyticsEngine, MirrorSpec, QueryRoute};
+//!
+//! let hybrid = HybridAnalyticsEngine::new(primary, DuckDbEngine::new(pool))
+//!     .with_max_staleness(Duration::from_secs(60));
+//!
+//! hybrid.mirror(MirrorSpec::new("orders").key(["id"]).incremental("updated_at"))?;
+//! hybrid.refresh_all().await?;
+//! let _refresher = hybrid.spawn_refresh(Duration::from_secs(30));
+//!
+//! // Answered by DuckDB
+//! let revenue = hybrid
+//!     .query("SELECT region, SUM(total) AS revenue FROM orders GROUP BY region", vec![])
+//!     .await?;
+//!
+//! // Answered by the primary
+//! let order = hybrid
+//!     .query("SELECT * FROM orders WHERE id = $1", vec![42.into()])
+//!     .await?;
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex as AsyncMutex;
+use tracing::{debug, info, instrument, warn};
+
+use prax_query::error::{QueryError, QueryResult};
+use prax_query::filter::FilterValue;
+use prax_query::operations::{AggregateOperation, GroupByOperation};
+use prax_query::raw::RawDatabase;
+use prax_query::sql::DatabaseType;
+use prax_query::traits::{Model, QueryEngine};
+
+use crate::engine::{DuckDbEngine, DuckDbQueryResult};
+
+/// Default number of rows copied per batch.
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+/// Maximum number of bound parameters per generated statement.
+const MAX_PARAMS: usize = 30_000;
+
+/// Suffix of the table a full refresh loads into before swapping it in.
+const STAGING_SUFFIX: &str = "__prax_staging";
+
+/// Aggregate functions that mark a query as analytical.
+const AGGREGATES: &[&str] = &[
+    "COUNT",
+    "SUM",
+    "AVG",
+    "MIN",
+    "MAX",
+    "MEDIAN",
+    "STDDEV",
+    "STDDEV_POP",
+    "STDDEV_SAMP",
+    "VARIANCE",
+    "VAR_POP",
+    "VAR_SAMP",
+    "PERCENTILE_CONT",
+    "PERCENTILE_DISC",
+    "STRING_AGG",
+    "ARRAY_AGG",
+    "GROUP_CONCAT",
+    "BOOL_AND",
+    "BOOL_OR",
+    "APPROX_COUNT_DISTINCT",
+];
+
+/// How a mirrored table is kept up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefreshMode {
+    /// Reload the whole table and swap it in atomically.
+    Full,
+    /// Pull rows whose change column advanced since the last refresh.
+    Incremental {
+        /// Monotonically increasing column, e.g. `updated_at`.
+        column: String,
+    },
+    /// Snapshot once, then apply events pushed through
+    /// [`HybridAnalyticsEngine::apply_changes`].
+    ChangeFeed,
+}
+
+/// A table mirrored from the primary into DuckDB.
+#[derive(Debug, Clone)]
+pub struct MirrorSpec {
+    /// Table name, identical on both sides.
+    pub table: String,
+    /// Columns to mirror (empty mirrors every column).
+    pub columns: Vec<String>,
+    /// Primary key columns, required for incremental and change feed refreshes.
+    pub key: Vec<String>,
+    /// DuckDB column types overriding the inferred ones.
+    pub column_types: HashMap<String, String>,
+    /// Refresh strategy.
+    pub refresh: RefreshMode,
+}
+
+impl MirrorSpec {
+    /// Mirror a table with full refreshes.
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            columns: Vec::new(),
+            key: Vec::new(),
+            column_types: HashMap::new(),
+            refresh: RefreshMode::Full,
+        }
+    }
+
+    /// Mirror the table of a model, using its columns and primary key.
+    pub fn for_model<M: Model>() -> Self {
+        Self::new(M::TABLE_NAME)
+            .columns(M::COLUMNS.iter().copied())
+            .key(M::PRIMARY_KEY.iter().copied())
+    }
+
+    /// Set the mirrored columns.
+    pub fn columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the primary key columns.
+    pub fn key<I, S>(mut self, key: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.key = key.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Override the DuckDB type of a column, e.g. `TIMESTAMP` or `DECIMAL(12,2)`.
+    pub fn column_type(mut self, column: impl Into<String>, sql_type: impl Into<String>) -> Self {
+        self.column_types.insert(column.into(), sql_type.into());
+        self
+    }
+
+    /// Refresh by reloading the whole table.
+    pub fn full_refresh(mut self) -> Self {
+        self.refresh = RefreshMode::Full;
+        self
+    }
+
+    /// Refresh incrementally using a monotonically increasing column.
+    pub fn incremental(mut self, column: impl Into<String>) -> Self {
+        self.refresh = RefreshMode::Incremental {
+            column: column.into(),
+        };
+        self
+    }
+
+    /// Refresh from change events after the initial snapshot.
+    pub fn change_feed(mut self) -> Self {
+        self.refresh = RefreshMode::ChangeFeed;
+        self
+    }
+
+    fn validate(&self) -> QueryResult<()> {
+        let needs_key = !matches!(self.refresh, RefreshMode::Full);
+        if needs_key && self.key.is_empty() {
+            return Err(QueryError::invalid_input(
+                "key",
+                format!(
+                    "mirror of `{}` needs a primary key for incremental refreshes",
+                    self.table
+                ),
+            ));
+        }
+
+        if !self.columns.is_empty() {
+            let change_column = match &self.refresh {
+                RefreshMode::Incremental { column } => Some(column),
+                _ => None,
+            };
+            for required in self.key.iter().chain(change_column) {
+                if !self.columns.contains(required) {
+                    return Err(QueryError::invalid_input(
+                        "columns",
+                        format!("mirror of `{}` must include `{}`", self.table, required),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// SELECT list for reading this table from the primary.
+    fn select_list(&self, db_type: DatabaseType) -> String {
+        if self.columns.is_empty() {
+            "*".to_string()
+        } else {
+            self.columns
+                .iter()
+                .map(|c| quote_in(db_type, c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+}
+
+/// A change captured from the primary.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent {
+    /// A row was inserted or updated; carries the full row.
+    Upsert(JsonValue),
+    /// A row was deleted; carries at least the key columns.
+    Delete(JsonValue),
+}
+
+/// Where a query is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryRoute {
+    /// The primary OLTP database.
+    Primary,
+    /// The DuckDB mirror.
+    Analytics,
+}
+
+/// Outcome of refreshing one mirrored table.
+#[derive(Debug, Clone)]
+pub struct RefreshStats {
+    /// Mirrored table.
+    pub table: String,
+    /// Whether the table was reloaded from scratch.
+    pub full: bool,
+    /// Rows read from the primary.
+    pub rows: u64,
+    /// Time spent refreshing.
+    pub duration: Duration,
+}
+
+/// Current state of a mirrored table.
+#[derive(Debug, Clone)]
+pub struct MirrorStatus {
+    /// Mirrored table.
+    pub table: String,
+    /// Refresh strategy.
+    pub refresh: RefreshMode,
+    /// Whether the initial snapshot has been loaded.
+    pub loaded: bool,
+    /// Rows in the mirror after the last refresh.
+    pub rows: u64,
+    /// When the mirror was last brought up to date.
+    pub last_refresh: Option<Instant>,
+}
+
+/// Position of an incremental refresh in the change column.
+#[derive(Debug, Clone)]
+enum Cursor {
+    /// Rows whose change column is at least this value.
+    From(JsonValue),
+    /// Rows after this `(change column, key...)` tuple.
+    After(Vec<JsonValue>),
+}
+
+#[derive(Debug, Clone, Default)]
+struct MirrorState {
+    /// Column names and DuckDB types, set once the table exists.
+    columns: Vec<(String, String)>,
+    cursor: Option<Cursor>,
+    rows: u64,
+    last_refresh: Option<Instant>,
+}
+
+impl MirrorState {
+    fn is_loaded(&self) -> bool {
+        !self.columns.is_empty()
+    }
+}
+
+struct Mirror {
+    spec: MirrorSpec,
+    state: MirrorState,
+    /// Serializes refreshes and change application per table.
+    lock: Arc<AsyncMutex<()>>,
+}
+
+/// Routes analytical queries to DuckDB mirrors of primary tables.
+///
+/// See the [module documentation](self) for an overview.
+pub struct HybridAnalyticsEngine<P> {
+    primary: Arc<P>,
+    analytics: DuckDbEngine,
+    mirrors: Arc<RwLock<HashMap<String, Mirror>>>,
+    batch_size: usize,
+    max_staleness: Option<Duration>,
+}
+
+impl<P> Clone for HybridAnalyticsEngine<P> {
+    fn clone(&self) -> Self {
+        Self {
+            primary: self.primary.clone(),
+            analytics: self.analytics.clone(),
+            mirrors: self.mirrors.clone(),
+            batch_size: self.batch_size,
+            max_staleness: self.max_staleness,
+        }
+    }
+}
+
+impl<P: RawDatabase + 'static> HybridAnalyticsEngine<P> {
+    /// Create a hybrid engine over a primary database and a DuckDB engine.
+    pub fn new(primary: P, analytics: DuckDbEngine) -> Self {
+        Self {
+            primary: Arc::new(primary),
+            analytics,
+            mirrors: Arc::new(RwLock::new(HashMap::new())),
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_staleness: None,
+        }
+    }
+
+    /// Set the number of rows copied per batch.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Fall back to the primary when a mirror is older than `max_staleness`.
+    ///
+    /// Change feed mirrors are exempt, since their freshness depends on the
+    /// feed rather than on refreshes.
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = Some(max_staleness);
+        self
+    }
+
+    /// Get the primary database.
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Get the DuckDB engine holding the mirrors.
+    pub fn analytics(&self) -> &DuckDbEngine {
+        &self.analytics
+    }
+
+    /// Register a table to mirror. The mirror is populated by the next refresh.
+    pub fn mirror(&self, spec: MirrorSpec) -> QueryResult<()> {
+        spec.validate()?;
+        debug!(table = %spec.table, refresh = ?spec.refresh, "Registering mirror");
+
+        self.mirrors.write().insert(
+            spec.table.clone(),
+            Mirror {
+                spec,
+                state: MirrorState::default(),
+                lock: Arc::new(AsyncMutex::new(())),
+            },
+        );
+        Ok(())
+    }
+
+    /// Names of the mirrored tables.
+    pub fn mirrored_tables(&self) -> Vec<String> {
+        let mut tables: Vec<String> = self.mirrors.read().keys().cloned().collect();
+        tables.sort();
+        tables
+    }
+
+    /// Get the state of a mirrored table.
+    pub fn status(&self, table: &str) -> Option<MirrorStatus> {
+        self.mirrors.read().get(table).map(|mirror| MirrorStatus {
+            table: mirror.spec.table.clone(),
+            refresh: mirror.spec.refresh.clone(),
+            loaded: mirror.state.is_loaded(),
+            rows: mirror.state.rows,
+            last_refresh: mirror.state.last_refresh,
+        })
+    }
+
+    /// Decide where a query would be executed.
+    ///
+    /// Analytical SELECTs run on DuckDB when every table they read is mirrored
+    /// and fresh; everything else runs on the primary.
+    pub fn route(&self, sql: &str) -> QueryRoute {
+        let shape = SqlShape::analyze(sql);
+        if !shape.analytical || shape.tables.is_empty() {
+            return QueryRoute::Primary;
+        }
+
+        let mirrors = self.mirrors.read();
+        let all_fresh = shape.tables.iter().all(|table| {
+            mirrors
+                .values()
+                .find(|mirror| mirror.spec.table.eq_ignore_ascii_case(table))
+                .is_some_and(|mirror| self.is_fresh(mirror))
+        });
+
+        if all_fresh {
+            QueryRoute::Analytics
+        } else {
+            QueryRoute::Primary
+        }
+    }
+
+    fn is_fresh(&self, mirror: &Mirror) -> bool {
+        if !mirror.state.is_loaded() {
+            return false;
+        }
+        match (self.max_staleness, &mirror.spec.refresh) {
+            (_, RefreshMode::ChangeFeed) | (None, _) => true,
+            (Some(max), _) => mirror
+                .state
+                .last_refresh
+                .is_some_and(|at| at.elapsed() <= max),
+        }
+    }
+
+    /// Execute a read query on the database chosen by [`route`](Self::route).
+    #[instrument(skip(self, params), fields(sql = %sql))]
+    pub async fn query(&self, sql: &str, params: Vec<FilterValue>) -> QueryResult<Vec<JsonValue>> {
+        match self.route(sql) {
+            QueryRoute::Analytics => {
+                debug!("Routing query to DuckDB");
+                self.query_analytics(sql, params).await
+            }
+            QueryRoute::Primary => {
+                debug!("Routing query to primary");
+                self.primary.query(sql, params).await
+            }
+        }
+    }
+
+    /// Execute a read query on the DuckDB mirrors.
+    pub async fn query_analytics(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+    ) -> QueryResult<Vec<JsonValue>> {
+        let rows = self.analytics.execute_raw(sql, &params).await?;
+        Ok(rows.into_iter().map(DuckDbQueryResult::into_json).collect())
+    }
+
+    /// Execute a write statement on the primary.
+    ///
+    /// Mirrors pick the change up on their next refresh.
+    pub async fn execute(&self, sql: &str, params: Vec<FilterValue>) -> QueryResult<u64> {
+        self.primary.execute(sql, params).await
+    }
+
+    /// Run an aggregate operation, on DuckDB when its table is mirrored.
+    pub async fn aggregate<M: Model, E: QueryEngine>(
+        &self,
+        operation: &AggregateOperation<M, E>,
+    ) -> QueryResult<Vec<JsonValue>> {
+        let (sql, params) = operation.build_sql();
+        self.query(&sql, params).await
+    }
+
+    /// Run a group by operation, on DuckDB when its table is mirrored.
+    pub async fn group_by<M: Model, E: QueryEngine>(
+        &self,
+        operation: &GroupByOperation<M, E>,
+    ) -> QueryResult<Vec<JsonValue>> {
+        let (sql, params) = operation.build_sql();
+        self.query(&sql, params).await
+    }
+
+    /// Refresh every mirrored table.
+    pub async fn refresh_all(&self) -> QueryResult<Vec<RefreshStats>> {
+        let mut stats = Vec::new();
+        for table in self.mirrored_tables() {
+            stats.push(self.refresh(&table).await?);
+        }
+        Ok(stats)
+    }
+
+    /// Refresh one mirrored table from the primary.
+    #[instrument(skip(self))]
+    pub async fn refresh(&self, table: &str) -> QueryResult<RefreshStats> {
+        let lock = self.mirror_lock(table)?;
+        let _guard = lock.lock().await;
+        let (spec, state) = self.snapshot(table)?;
+
+        let started = Instant::now();
+        let full = !state.is_loaded() || spec.refresh == RefreshMode::Full;
+        let (rows, mut state) = match &spec.refresh {
+            _ if full => self.reload(&spec).await?,
+            RefreshMode::Incremental { column } => self.pull_changes(&spec, column, state).await?,
+            _ => (0, state),
+        };
+
+        state.rows = self.analytics.count(&spec.table, &HashMap::new()).await?;
+        state.last_refresh = Some(Instant::now());
+        let mirror_rows = state.rows;
+        self.store(table, state);
+
+        let duration = started.elapsed();
+        info!(
+            table = %table,
+            full,
+            rows,
+            mirror_rows,
+            duration_ms = duration.as_millis() as u64,
+            "Refreshed mirror"
+        );
+
+        Ok(RefreshStats {
+            table: spec.table,
+            full,
+            rows,
+            duration,
+        })
+    }
+
+    /// Refresh all mirrors every `interval` in a background task.
+    ///
+    /// Failures are logged and retried on the next tick.
+    pub fn spawn_refresh(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let engine = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                for table in engine.mirrored_tables() {
+                    if let Err(e) = engine.refresh(&table).await {
+                        warn!(table = %table, error = %e, "Mirror refresh failed");
+                    }
+                }
+            }
+        })
+    }
+
+    /// Apply change events captured from the primary to a mirrored table.
+    ///
+    /// Events are applied in order within a single transaction. Returns the
+    /// number of events applied.
+    #[instrument(skip(self, events))]
+    pub async fn apply_changes(
+        &self,
+        table: &str,
+        events: impl IntoIterator<Item = ChangeEvent>,
+    ) -> QueryResult<u64> {
+        let lock = self.mirror_lock(table)?;
+        let _guard = lock.lock().await;
+        let (spec, mut state) = self.snapshot(table)?;
+
+        if !state.is_loaded() {
+            return Err(QueryError::internal(format!(
+                "mirror of `{}` must be refreshed before applying changes",
+                table
+            )));
+        }
+
+        let mut statements = Vec::new();
+        let mut applied = 0;
+        for event in events {
+            match &event {
+                ChangeEvent::Upsert(row) => {
+                    statements.push(delete_by_key(&spec, std::slice::from_ref(row)));
+                    statements.extend(insert_rows(
+                        &spec.table,
+                        &state.columns,
+                        std::slice::from_ref(row),
+                    ));
+                }
+                ChangeEvent::Delete(row) => {
+                    statements.push(delete_by_key(&spec, std::slice::from_ref(row)));
+                }
+            }
+            applied += 1;
+        }
+
+        self.write(statements).await?;
+        state.rows = self.analytics.count(&spec.table, &HashMap::new()).await?;
+        state.last_refresh = Some(Instant::now());
+        self.store(table, state);
+
+        Ok(applied)
+    }
+
+    fn mirror_lock(&self, table: &str) -> QueryResult<Arc<AsyncMutex<()>>> {
+        self.mirrors
+            .read()
+            .get(table)
+            .map(|mirror| mirror.lock.clone())
+            .ok_or_else(|| not_mirrored(table))
+    }
+
+    fn snapshot(&self, table: &str) -> QueryResult<(MirrorSpec, MirrorState)> {
+        self.mirrors
+            .read()
+            .get(table)
+            .map(|mirror| (mirror.spec.clone(), mirror.state.clone()))
+            .ok_or_else(|| not_mirrored(table))
+    }
+
+    fn store(&self, table: &str, state: MirrorState) {
+        if let Some(mirror) = self.mirrors.write().get_mut(table) {
+            mirror.state = state;
+        }
+    }
+
+    /// Load a full snapshot into a staging table and swap it in.
+    async fn reload(&self, spec: &MirrorSpec) -> QueryResult<(u64, MirrorState)> {
+        let mut state = MirrorState::default();
+        let table = self.quote_primary(&spec.table);
+
+        // Take the watermark before the snapshot so rows changed while copying
+        // are pulled again by the next incremental refresh.
+        if let RefreshMode::Incremental { column } = &spec.refresh {
+            let sql = format!(
+                "SELECT MAX({}) AS watermark FROM {}",
+                self.quote_primary(column),
+                table
+            );
+            let rows = self.primary.query(&sql, Vec::new()).await?;
+            state.cursor = rows
+                .first()
+                .and_then(|row| row.get("watermark"))
+                .filter(|value| !value.is_null())
+                .cloned()
+                .map(Cursor::From);
+        }
+
+        let staging = format!("{}{}", spec.table, STAGING_SUFFIX);
+        let select = format!(
+            "SELECT {} FROM {}",
+            spec.select_list(self.primary.database_type()),
+            table
+        );
+        let mut copied = 0u64;
+
+        loop {
+            // Without a key there is no stable order to page over
+            let sql = if spec.key.is_empty() {
+                select.clone()
+            } else {
+                format!(
+                    "{} ORDER BY {} LIMIT {} OFFSET {}",
+                    select,
+                    self.key_list(spec, None),
+                    self.batch_size,
+                    copied
+                )
+            };
+            let rows = self.primary.query(&sql, Vec::new()).await?;
+
+            if !state.is_loaded() {
+                state.columns = define_columns(spec, &rows)?;
+                self.analytics
+                    .raw_sql_batch(&format!(
+                        "DROP TABLE IF EXISTS {staging}; CREATE TABLE {staging} ({});",
+                        state
+                            .columns
+                            .iter()
+                            .map(|(name, ty)| format!("{} {}", quote(name), ty))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        staging = quote(&staging)
+                    ))
+                    .await?;
+            }
+
+            self.write(insert_rows(&staging, &state.columns, &rows))
+                .await?;
+            copied += rows.len() as u64;
+
+            if spec.key.is_empty() || rows.len() < self.batch_size {
+                break;
+            }
+        }
+
+        self.analytics
+            .raw_sql_batch(&format!(
+                "BEGIN TRANSACTION; DROP TABLE IF EXISTS {table}; ALTER TABLE {staging} RENAME TO {table}; COMMIT;",
+                table = quote(&spec.table),
+                staging = quote(&staging)
+            ))
+            .await?;
+
+        Ok((copied, state))
+    }
+
+    /// Pull rows whose change column advanced past the cursor and upsert them.
+    async fn pull_changes(
+        &self,
+        spec: &MirrorSpec,
+        column: &str,
+        mut state: MirrorState,
+    ) -> QueryResult<(u64, MirrorState)> {
+        let order = self.key_list(spec, Some(column));
+        let mut pulled = 0u64;
+
+        loop {
+            let (filter, params) = match &state.cursor {
+                None => (String::new(), Vec::new()),
+                Some(Cursor::From(value)) => (
+                    format!(
+                        " WHERE {} >= {}",
+                        self.quote_primary(column),
+                        self.primary.database_type().placeholder(1)
+                    ),
+                    vec![json_to_filter_value(value)],
+                ),
+                Some(Cursor::After(values)) => (
+                    format!(
+                        " WHERE ({}) > ({})",
+                        order,
+                        (1..=values.len())
+                            .map(|i| self.primary.database_type().placeholder(i))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    values.iter().map(json_to_filter_value).collect(),
+                ),
+            };
+            let sql = format!(
+                "SELECT {} FROM {}{} ORDER BY {} LIMIT {}",
+                spec.select_list(self.primary.database_type()),
+                self.quote_primary(&spec.table),
+                filter,
+                order,
+                self.batch_size
+            );
+
+            let rows = self.primary.query(&sql, params).await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+
+            let mut statements = vec![delete_by_key(spec, &rows)];
+            statements.extend(insert_rows(&spec.table, &state.columns, &rows));
+            self.write(statements).await?;
+
+            state.cursor = Some(Cursor::After(
+                std::iter::once(column)
+                    .chain(spec.key.iter().map(String::as_str))
+                    .map(|c| last.get(c).cloned().unwrap_or(JsonValue::Null))
+                    .collect(),
+            ));
+            pulled += rows.len() as u64;
+
+            if rows.len() < self.batch_size {
+                break;
+            }
+        }
+
+        Ok((pulled, state))
+    }
+
+    /// Comma-separated key columns, optionally led by the change column.
+    fn key_list(&self, spec: &MirrorSpec, leading: Option<&str>) -> String {
+        leading
+            .into_iter()
+            .chain(spec.key.iter().map(String::as_str))
+            .map(|c| self.quote_primary(c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Quote an identifier for the primary.
+    fn quote_primary(&self, ident: &str) -> String {
+        quote_in(self.primary.database_type(), ident)
+    }
+
+    /// Run statements against DuckDB in a single transaction.
+    async fn write(&self, statements: Vec<(String, Vec<FilterValue>)>) -> QueryResult<()> {
+        if statements.is_empty() {
+            return Ok(());
+        }
+        self.analytics.execute_in_transaction(statements).await?;
+        Ok(())
+    }
+}
+
+fn not_mirrored(table: &str) -> QueryError {
+    QueryError::invalid_input("table", format!("`{}` is not mirrored", table))
+}
+
+fn quote(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quote an identifier in the dialect of `db_type`.
+fn quote_in(db_type: DatabaseType, ident: &str) -> String {
+    match db_type {
+        DatabaseType::MySQL => format!("`{}`", ident.replace('`', "``")),
+        _ => quote(ident),
+    }
+}
+
+/// Decide the DuckDB columns of a mirror from its spec and the first rows.
+fn define_columns(spec: &MirrorSpec, rows: &[JsonValue]) -> QueryResult<Vec<(String, String)>> {
+    let names: Vec<String> = if !spec.columns.is_empty() {
+        spec.columns.clone()
+    } else if let Some(JsonValue::Object(row)) = rows.first() {
+        row.keys().cloned().collect()
+    } else {
+        return Err(QueryError::internal(format!(
+            "cannot infer the columns of empty table `{}`; list them with MirrorSpec::columns",
+            spec.table
+        )));
+    };
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let ty =
+                spec.column_types.get(&name).cloned().unwrap_or_else(|| {
+                    infer_type(rows.iter().filter_map(|row| row.get(&name))).into()
+                });
+            (name, ty)
+        })
+        .collect())
+}
+
+/// Infer a DuckDB type from sample values.
+fn infer_type<'a>(values: impl Iterator<Item = &'a JsonValue>) -> &'static str {
+    let mut inferred = None;
+    for value in values {
+        let ty = match value {
+            JsonValue::Null => continue,
+            JsonValue::Bool(_) => "BOOLEAN",
+            JsonValue::Number(n) if n.is_i64() || n.is_u64() => "BIGINT",
+            JsonValue::Number(_) => "DOUBLE",
+            _ => "VARCHAR",
+        };
+        inferred = Some(match (inferred, ty) {
+            (None, ty) => ty,
+            (Some(a), b) if a == b => a,
+            (Some("BIGINT"), "DOUBLE") | (Some("DOUBLE"), "BIGINT") => "DOUBLE",
+            _ => "VARCHAR",
+        });
+    }
+    inferred.unwrap_or("VARCHAR")
+}
+
+/// Build batched INSERT statements for rows.
+fn insert_rows(
+    table: &str,
+    columns: &[(String, String)],
+    rows: &[JsonValue],
+) -> Vec<(String, Vec<FilterValue>)> {
+    if rows.is_empty() || columns.is_empty() {
+        return Vec::new();
+    }
+
+    let column_list = columns
+        .iter()
+        .map(|(name, _)| quote(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let tuple = format!("({})", vec!["?"; columns.len()].join(", "));
+    let rows_per_statement = (MAX_PARAMS / columns.len()).max(1);
+
+    rows.chunks(rows_per_statement)
+        .map(|chunk| {
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                quote(table),
+                column_list,
+                vec![tuple.as_str(); chunk.len()].join(", ")
+            );
+            let params = chunk
+                .iter()
+                .flat_map(|row| {
+                    columns.iter().map(move |(name, _)| {
+                        row.get(name)
+                            .map(json_to_filter_value)
+                            .unwrap_or(FilterValue::Null)
+                    })
+                })
+                .collect();
+            (sql, params)
+        })
+        .collect()
+}
+
+/// Build a DELETE statement matching the keys of rows.
+fn delete_by_key(spec: &MirrorSpec, rows: &[JsonValue]) -> (String, Vec<FilterValue>) {
+    let condition = format!(
+        "({})",
+        spec.key
+            .iter()
+            .map(|k| format!("{} = ?", quote(k)))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    );
+    let sql = format!(
+        "DELETE FROM {} WHERE {}",
+        quote(&spec.table),
+        vec![condition.as_str(); rows.len()].join(" OR ")
+    );
+    let params = rows
+        .iter()
+        .flat_map(|row| {
+            spec.key.iter().map(move |k| {
+                row.get(k)
+                    .map(json_to_filter_value)
+                    .unwrap_or(FilterValue::Null)
+            })
+        })
+        .collect();
+    (sql, params)
+}
+
+fn json_to_filter_value(value: &JsonValue) -> FilterValue {
+    match value {
+        JsonValue::Null => FilterValue::Null,
+        JsonValue::Bool(b) => FilterValue::Bool(*b),
+        JsonValue::Number(n) => n
+            .as_i64()
+            .map(FilterValue::Int)
+            .unwrap_or_else(|| FilterValue::Float(n.as_f64().unwrap_or_default())),
+        JsonValue::String(s) => FilterValue::String(s.clone()),
+        other => FilterValue::String(other.to_string()),
+    }
+}
+
+/// What the router needs to know about a SQL statement.
+#[derive(Debug, Default, PartialEq)]
+struct SqlShape {
+    /// Read-only query using aggregates, GROUP BY or window functions.
+    analytical: bool,
+    /// Tables read by the query, excluding CTE names.
+    tables: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Symbol(char),
+}
+
+impl Token {
+    fn is_word(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn identifier(&self) -> Option<&str> {
+        match self {
+            Token::Word(w) if !is_reserved(w) => Some(w),
+            Token::Quoted(q) => Some(q),
+            _ => None,
+        }
+    }
+}
+
+fn is_reserved(word: &str) -> bool {
+    const RESERVED: &[&str] = &[
+        "SELECT",
+        "FROM",
+        "WHERE",
+        "GROUP",
+        "ORDER",
+        "BY",
+        "HAVING",
+        "LIMIT",
+        "OFFSET",
+        "JOIN",
+        "INNER",
+        "LEFT",
+        "RIGHT",
+        "FULL",
+        "OUTER",
+        "CROSS",
+        "ON",
+        "USING",
+        "AS",
+        "UNION",
+        "EXCEPT",
+        "INTERSECT",
+        "WITH",
+        "LATERAL",
+        "WINDOW",
+        "QUALIFY",
+    ];
+    RESERVED.iter().any(|r| r.eq_ignore_ascii_case(word))
+}
+
+impl SqlShape {
+    fn analyze(sql: &str) -> Self {
+        let tokens = tokenize(sql);
+        let mut shape = SqlShape::default();
+
+        if !tokens
+            .first()
+            .is_some_and(|t| t.is_word("SELECT") || t.is_word("WITH"))
+        {
+            return shape;
+        }
+
+        let followed_by_paren = |i: usize| tokens.get(i + 1) == Some(&Token::Symbol('('));
+        let mut ctes = Vec::new();
+        // Whether each open parenthesis holds a query, as opposed to a
+        // function call like `EXTRACT(YEAR FROM created_at)`
+        let mut in_query = vec![true];
+
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                Token::Symbol('(') => in_query.push(
+                    tokens
+                        .get(i + 1)
+                        .is_some_and(|t| t.is_word("SELECT") || t.is_word("WITH")),
+                ),
+                Token::Symbol(')') if in_query.len() > 1 => {
+                    in_query.pop();
+                }
+                _ => {}
+            }
+
+            // Statements that modify data never run on the mirror
+            if ["INSERT", "UPDATE", "DELETE", "MERGE"]
+                .iter()
+                .any(|kw| token.is_word(kw))
+            {
+                return SqlShape::default();
+            }
+
+            if token.is_word("GROUP") && tokens.get(i + 1).is_some_and(|t| t.is_word("BY")) {
+                shape.analytical = true;
+            }
+            if (token.is_word("OVER") || AGGREGATES.iter().any(|agg| token.is_word(agg)))
+                && followed_by_paren(i)
+            {
+                shape.analytical = true;
+            }
+
+            // `name AS (` introduces a common table expression
+            if let Some(name) = token.identifier() {
+                if tokens.get(i + 1).is_some_and(|t| t.is_word("AS"))
+                    && tokens.get(i + 2) == Some(&Token::Symbol('('))
+                {
+                    ctes.push(name.to_string());
+                }
+            }
+
+            let reads = token.is_word("FROM") || token.is_word("JOIN");
+            if reads && in_query.last() == Some(&true) {
+                shape.collect_tables(&tokens, i + 1);
+            }
+        }
+
+        shape
+            .tables
+            .retain(|table| !ctes.iter().any(|cte| cte.eq_ignore_ascii_case(table)));
+        shape.tables.dedup();
+        shape
+    }
+
+    /// Collect the comma-separated table references starting at `start`.
+    fn collect_tables(&mut self, tokens: &[Token], mut i: usize) {
+        loop {
+            let Some(mut name) = tokens.get(i).and_then(Token::identifier) else {
+                // Subquery or nothing to read
+                return;
+            };
+            i += 1;
+            // Keep the last part of qualified names
+            while tokens.get(i) == Some(&Token::Symbol('.')) {
+                match tokens.get(i + 1).and_then(Token::identifier) {
+                    Some(part) => {
+                        name = part;
+                        i += 2;
+                    }
+                    None => break,
+                }
+            }
+            // Table functions are not mirrored tables
+            if tokens.get(i) == Some(&Token::Symbol('(')) {
+                self.tables.push(format!("{}()", name));
+                return;
+            }
+            self.tables.push(name.to_string());
+
+            if tokens.get(i).is_some_and(|t| t.is_word("AS")) {
+                i += 1;
+            }
+            if tokens.get(i).and_then(Token::identifier).is_some() {
+                i += 1;
+            }
+            if tokens.get(i) != Some(&Token::Symbol(',')) {
+                return;
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Split SQL into words, quoted identifiers and symbols, dropping literals and comments.
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' => {
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == '\'' {
+                        if chars.get(i + 1) == Some(&'\'') {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            '"' | '`' => {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && chars[i] != c {
+                    i += 1;
+                }
+                tokens.push(Token::Quoted(
+                    chars[start..i.min(chars.len())].iter().collect(),
+                ));
+                i += 1;
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+            _ => {
+                tokens.push(Token::Symbol(c));
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DuckDbConfig;
+    use crate::pool::DuckDbPool;
+
+    async fn engine() -> DuckDbEngine {
+        DuckDbEngine::new(DuckDbPool::new(DuckDbConfig::in_memory()).await.unwrap())
+    }
+
+    #[test]
+    fn test_sql_shape() {
+        let shape = SqlShape::analyze(
+            "SELECT region, SUM(total) FROM public.orders o JOIN \"customers\" c ON c.id = o.customer_id GROUP BY region",
+        );
+        assert!(shape.analytical);
+        assert_eq!(shape.tables, vec!["orders", "customers"]);
+
+        let shape = SqlShape::analyze("SELECT * FROM orders WHERE note = 'SUM(x) GROUP BY'");
+        assert!(!shape.analytical);
+
+        let shape = SqlShape::analyze(
+            "SELECT EXTRACT(YEAR FROM created_at) AS y, COUNT(*) FROM orders GROUP BY 1",
+        );
+        assert_eq!(shape.tables, vec!["orders"]);
+
+        let shape = SqlShape::analyze(
+            "WITH recent AS (SELECT * FROM orders) SELECT COUNT(*) FROM recent, items",
+        );
+        assert!(shape.analytical);
+        assert_eq!(shape.tables, vec!["orders", "items"]);
+
+        assert_eq!(
+            SqlShape::analyze("DELETE FROM orders WHERE id IN (SELECT MAX(id) FROM orders)"),
+            SqlShape::default()
+        );
+    }
+
+    #[test]
+    fn test_infer_type() {
+        let values = [
+            serde_json::json!(1),
+            serde_json::json!(2.5),
+            JsonValue::Null,
+        ];
+        assert_eq!(infer_type(values.iter()), "DOUBLE");
+        assert_eq!(infer_type([serde_json::json!(true)].iter()), "BOOLEAN");
+        assert_eq!(
+            infer_type([serde_json::json!(1), serde_json::json!("a")].iter()),
+            "VARCHAR"
+        );
+        assert_eq!(infer_type(std::iter::empty()), "VARCHAR");
+    }
+
+    #[test]
+    fn test_mirror_spec_validation() {
+        assert!(MirrorSpec::new("orders").validate().is_ok());
+        assert!(
+            MirrorSpec::new("orders")
+                .incremental("updated_at")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            MirrorSpec::new("orders")
+                .key(["id"])
+                .columns(["id", "total"])
+                .incremental("updated_at")
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_routing_and_refresh() {
+        let primary = engine().await;
+        primary
+            .raw_sql_batch(
+                "CREATE TABLE orders (id INTEGER, region VARCHAR, total DOUBLE, updated_at INTEGER);
+                 INSERT INTO orders VALUES (1, 'eu', 10.0, 1), (2, 'us', 20.0, 1), (3, 'eu', 5.0, 2);",
+            )
+            .await
+            .unwrap();
+
+        let hybrid = HybridAnalyticsEngine::new(primary, engine().await).with_batch_size(2);
+        hybrid
+            .mirror(
+                MirrorSpec::new("orders")
+                    .key(["id"])
+                    .incremental("updated_at"),
+            )
+            .unwrap();
+
+        let aggregate =
+            "SELECT region, SUM(total) AS total FROM orders GROUP BY region ORDER BY region";
+        // Not loaded yet
+        assert_eq!(hybrid.route(aggregate), QueryRoute::Primary);
+
+        let stats = hybrid.refresh("orders").await.unwrap();
+        assert!(stats.full);
+        assert_eq!(stats.rows, 3);
+        assert_eq!(hybrid.route(aggregate), QueryRoute::Analytics);
+        assert_eq!(
+            hybrid.route("SELECT * FROM orders WHERE id = $1"),
+            QueryRoute::Primary
+        );
+
+        hybrid
+            .execute(
+                "UPDATE orders SET total = 15.0, updated_at = 3 WHERE id = 3",
+                Vec::new(),
+            )
+            .await
+            .unwrap();
+        let stats = hybrid.refresh("orders").await.unwrap();
+        assert!(!stats.full);
+
+        let rows = hybrid.query(aggregate, Vec::new()).await.unwrap();
+        assert_eq!(rows[0]["total"], serde_json::json!(25.0));
+        assert_eq!(hybrid.status("orders").unwrap().rows, 3);
+    }
+
+    #[tokio::test]
+    async fn test_change_feed() {
+        let primary = engine().await;
+        primary
+            .raw_sql_batch(
+                "CREATE TABLE events (id INTEGER, kind VARCHAR);
+                 INSERT INTO events VALUES (1, 'click'), (2, 'view');",
+            )
+            .await
+            .unwrap();
+
+        let hybrid = HybridAnalyticsEngine::new(primary, engine().await);
+        hybrid
+            .mirror(MirrorSpec::new("events").key(["id"]).change_feed())
+            .unwrap();
+        hybrid.refresh("events").await.unwrap();
+
+        let applied = hybrid
+            .apply_changes(
+                "events",
+                vec![
+                    ChangeEvent::Upsert(serde_json::json!({"id": 2, "kind": "click"})),
+                    ChangeEvent::Upsert(serde_json::json!({"id": 3, "kind": "click"})),
+                    ChangeEvent::Delete(serde_json::json!({"id": 1})),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(applied, 3);
+
+        let rows = hybrid
+            .query_analytics(
+                "SELECT COUNT(*) AS n FROM events WHERE kind = 'click'",
+                Vec::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows[0]["n"], serde_json::json!(2));
+    }
+}
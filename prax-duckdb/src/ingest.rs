@@ -0,0 +1,491 @@
+//! Typed ingestion of Parquet and CSV files.
+//!
+//! [`DuckDbEngine::read_parquet`] and [`DuckDbEngine::read_csv`] build reads
+//! over one or more files or glob patterns, instead of hand-written
+//! `read_parquet`/`COPY` SQL:
+//!
+//! ```rust,ignore
+//! let report = engine
+//!     .read_parquet("data/*.parquet")
+//!     .columns(["id", "region", "revenue"])
+//!     .filter("year = ?", [2024])
+//!     .into_table("staging")
+//!     .await?;
+//! println!("Loaded {} rows", report.rows);
+//!
+//! // Check what DuckDB infers before loading
+//! let schema = engine.read_csv("exports/*.csv").delimiter(';').describe().await?;
+//! for column in &schema.columns {
+//!     println!("{}: {}", column.name, column.data_type);
+//! }
+//! ```
+//!
+//! Reading Parquet files needs the `parquet` feature (or the extension being
+//! installed); CSV support is built in.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use prax_query::filter::FilterValue;
+use tracing::{debug, info};
+
+use crate::engine::{DuckDbEngine, DuckDbQueryResult};
+use crate::error::DuckDbResult;
+
+/// A file format DuckDB can read.
+pub trait FileFormat {
+    /// The DuckDB table function reading the format.
+    const FUNCTION: &'static str;
+}
+
+/// Apache Parquet files.
+#[derive(Debug, Clone, Copy)]
+pub struct Parquet;
+
+impl FileFormat for Parquet {
+    const FUNCTION: &'static str = "read_parquet";
+}
+
+/// Delimited text files.
+#[derive(Debug, Clone, Copy)]
+pub struct Csv;
+
+impl FileFormat for Csv {
+    const FUNCTION: &'static str = "read_csv";
+}
+
+/// A read of Parquet files.
+pub type ParquetReader = FileReader<Parquet>;
+
+/// A read of CSV files.
+pub type CsvReader = FileReader<Csv>;
+
+/// A column of a file read, as inferred by DuckDB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferredColumn {
+    /// Column name.
+    pub name: String,
+    /// DuckDB type, e.g. `BIGINT` or `VARCHAR`.
+    pub data_type: String,
+    /// Whether the column may hold NULLs.
+    pub nullable: bool,
+}
+
+/// The files and columns a read resolves to.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IngestSchema {
+    /// Files matched by the paths and glob patterns.
+    pub files: Vec<String>,
+    /// Columns of the read, after projection.
+    pub columns: Vec<InferredColumn>,
+}
+
+/// Outcome of loading files into a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestReport {
+    /// The target table.
+    pub table: String,
+    /// Rows written.
+    pub rows: u64,
+    /// Time spent.
+    pub elapsed: Duration,
+}
+
+/// A read of files in one format, filtered and projected before loading.
+#[derive(Clone)]
+pub struct FileReader<F> {
+    engine: DuckDbEngine,
+    paths: Vec<String>,
+    options: Vec<(&'static str, String)>,
+    columns: Vec<String>,
+    filters: Vec<(String, Vec<FilterValue>)>,
+    limit: Option<u64>,
+    _format: PhantomData<F>,
+}
+
+impl<F: FileFormat> FileReader<F> {
+    /// Create a read of a file or glob pattern.
+    pub fn new(engine: DuckDbEngine, path: impl Into<String>) -> Self {
+        Self {
+            engine,
+            paths: vec![path.into()],
+            options: Vec::new(),
+            columns: Vec::new(),
+            filters: Vec::new(),
+            limit: None,
+            _format: PhantomData,
+        }
+    }
+
+    /// Read another file or glob pattern as well.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Only read the given columns.
+    pub fn columns(mut self, columns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.columns.extend(columns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only read rows matching a SQL condition with `?` placeholders.
+    ///
+    /// Several filters are combined with `AND`.
+    pub fn filter(
+        mut self,
+        condition: impl Into<String>,
+        params: impl IntoIterator<Item = impl Into<FilterValue>>,
+    ) -> Self {
+        self.filters.push((
+            condition.into(),
+            params.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    /// Only read rows where a column equals a value.
+    pub fn eq(self, column: &str, value: impl Into<FilterValue>) -> Self {
+        let condition = format!("{} = ?", quote_identifier(column));
+        self.filter(condition, [value.into()])
+    }
+
+    /// Read at most `limit` rows.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Match columns across files by name instead of position.
+    pub fn union_by_name(self) -> Self {
+        self.option("union_by_name", "true")
+    }
+
+    /// Add a `filename` column holding the file each row was read from.
+    pub fn filename(self) -> Self {
+        self.option("filename", "true")
+    }
+
+    /// Set a reader option, replacing an earlier value.
+    fn option(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.options.retain(|(existing, _)| *existing != name);
+        self.options.push((name, value.into()));
+        self
+    }
+
+    /// Build the table function call reading the files.
+    pub fn source_sql(&self) -> String {
+        let paths: Vec<_> = self.paths.iter().map(|p| sql_string(p)).collect();
+        let mut args = vec![format!("[{}]", paths.join(", "))];
+        args.extend(
+            self.options
+                .iter()
+                .map(|(name, value)| format!("{} = {}", name, value)),
+        );
+        format!("{}({})", F::FUNCTION, args.join(", "))
+    }
+
+    /// Build the SELECT reading the files.
+    pub fn to_sql(&self) -> (String, Vec<FilterValue>) {
+        let columns = if self.columns.is_empty() {
+            "*".to_string()
+        } else {
+            self.columns
+                .iter()
+                .map(|c| quote_identifier(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut sql = format!("SELECT {} FROM {}", columns, self.source_sql());
+        let mut params = Vec::new();
+
+        if !self.filters.is_empty() {
+            let conditions: Vec<_> = self
+                .filters
+                .iter()
+                .map(|(condition, filter_params)| {
+                    params.extend(filter_params.iter().cloned());
+                    format!("({})", condition)
+                })
+                .collect();
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        (sql, params)
+    }
+
+    /// List the files matched by the paths and glob patterns.
+    pub async fn files(&self) -> DuckDbResult<Vec<String>> {
+        let mut files = Vec::new();
+        for path in &self.paths {
+            let sql = format!("SELECT file FROM glob({})", sql_string(path));
+            let rows = self.engine.execute_raw(&sql, &[]).await?;
+            files.extend(rows.into_iter().filter_map(|row| {
+                row.data
+                    .get("file")
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+            }));
+        }
+        Ok(files)
+    }
+
+    /// Report the matched files and the column types DuckDB infers for them.
+    ///
+    /// Filters and the limit do not change the columns, so they are left out.
+    pub async fn describe(&self) -> DuckDbResult<IngestSchema> {
+        let mut unfiltered = self.clone();
+        unfiltered.filters.clear();
+        unfiltered.limit = None;
+        let (sql, _) = unfiltered.to_sql();
+        let rows = self
+            .engine
+            .execute_raw(&format!("DESCRIBE {}", sql), &[])
+            .await?;
+
+        let columns = rows
+            .into_iter()
+            .filter_map(|row| {
+                let name = row.data.get("column_name")?.as_str()?.to_string();
+                let data_type = row.data.get("column_type")?.as_str()?.to_string();
+                let nullable = row.data.get("null").and_then(|v| v.as_str()) != Some("NO");
+                Some(InferredColumn {
+                    name,
+                    data_type,
+                    nullable,
+                })
+            })
+            .collect();
+
+        Ok(IngestSchema {
+            files: self.files().await?,
+            columns,
+        })
+    }
+
+    /// Read the rows.
+    pub async fn fetch(&self) -> DuckDbResult<Vec<DuckDbQueryResult>> {
+        let (sql, params) = self.to_sql();
+        self.engine.execute_raw(&sql, &params).await
+    }
+
+    /// Count the rows the read matches.
+    pub async fn count(&self) -> DuckDbResult<u64> {
+        let (sql, params) = self.to_sql();
+        self.engine
+            .raw_sql_scalar::<u64>(&format!("SELECT COUNT(*) FROM ({})", sql), &params)
+            .await
+    }
+
+    /// Create a table from the rows; fails if the table exists.
+    pub async fn into_table(&self, table: &str) -> DuckDbResult<IngestReport> {
+        self.create(table, "CREATE TABLE").await
+    }
+
+    /// Create a table from the rows, replacing an existing table.
+    pub async fn replace_table(&self, table: &str) -> DuckDbResult<IngestReport> {
+        self.create(table, "CREATE OR REPLACE TABLE").await
+    }
+
+    /// Append the rows to an existing table, matching columns by name.
+    pub async fn append_to(&self, table: &str) -> DuckDbResult<IngestReport> {
+        let start = Instant::now();
+        let (select, params) = self.to_sql();
+        let sql = format!("INSERT INTO {} BY NAME {}", quote_identifier(table), select);
+        debug!(sql = %sql, "Appending files to table");
+
+        let rows = self.engine.raw_sql_execute(&sql, &params).await?;
+        Ok(self.report(table, rows, start))
+    }
+
+    async fn create(&self, table: &str, statement: &str) -> DuckDbResult<IngestReport> {
+        let start = Instant::now();
+        let (select, params) = self.to_sql();
+        let sql = format!("{} {} AS {}", statement, quote_identifier(table), select);
+        debug!(sql = %sql, "Loading files into table");
+
+        self.engine.raw_sql_execute(&sql, &params).await?;
+        let rows = self.engine.count(table, &HashMap::new()).await?;
+        Ok(self.report(table, rows, start))
+    }
+
+    fn report(&self, table: &str, rows: u64, start: Instant) -> IngestReport {
+        info!(table = %table, rows, paths = ?self.paths, "Ingested files");
+        IngestReport {
+            table: table.to_string(),
+            rows,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+impl FileReader<Parquet> {
+    /// Read `key=value` directories as partition columns.
+    pub fn hive_partitioning(self) -> Self {
+        self.option("hive_partitioning", "true")
+    }
+}
+
+impl FileReader<Csv> {
+    /// Set whether the first line holds column names; detected by default.
+    pub fn header(self, header: bool) -> Self {
+        self.option("header", header.to_string())
+    }
+
+    /// Set the column delimiter; detected by default.
+    pub fn delimiter(self, delimiter: char) -> Self {
+        self.option("delim", sql_string(&delimiter.to_string()))
+    }
+
+    /// Set the quote character; detected by default.
+    pub fn quote(self, quote: char) -> Self {
+        self.option("quote", sql_string(&quote.to_string()))
+    }
+
+    /// Read the given string as NULL.
+    pub fn null_string(self, null: &str) -> Self {
+        self.option("nullstr", sql_string(null))
+    }
+
+    /// Skip lines at the start of each file.
+    pub fn skip_rows(self, rows: u64) -> Self {
+        self.option("skip", rows.to_string())
+    }
+
+    /// Set the number of rows sampled to detect column types.
+    pub fn sample_size(self, rows: u64) -> Self {
+        self.option("sample_size", rows.to_string())
+    }
+
+    /// Override the detected types of some columns.
+    pub fn column_types<'a>(self, types: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let entries: Vec<_> = types
+            .into_iter()
+            .map(|(column, data_type)| format!("{}: {}", sql_string(column), sql_string(data_type)))
+            .collect();
+        self.option("types", format!("{{{}}}", entries.join(", ")))
+    }
+
+    /// Read every column as `VARCHAR` instead of detecting types.
+    pub fn all_varchar(self) -> Self {
+        self.option("all_varchar", "true")
+    }
+}
+
+impl<F> std::fmt::Debug for FileReader<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileReader")
+            .field("paths", &self.paths)
+            .field("options", &self.options)
+            .field("columns", &self.columns)
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DuckDbEngine {
+    /// Read Parquet files matching a path or glob pattern.
+    pub fn read_parquet(&self, path: impl Into<String>) -> ParquetReader {
+        FileReader::new(self.clone(), path)
+    }
+
+    /// Read CSV files matching a path or glob pattern.
+    pub fn read_csv(&self, path: impl Into<String>) -> CsvReader {
+        FileReader::new(self.clone(), path)
+    }
+}
+
+/// A single-quoted SQL string literal.
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// A double-quoted SQL identifier.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DuckDbConfig, DuckDbPool};
+
+    async fn engine() -> DuckDbEngine {
+        let pool = DuckDbPool::new(DuckDbConfig::in_memory()).await.unwrap();
+        DuckDbEngine::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_reader_sql() {
+        let db = engine().await;
+        let (sql, params) = db
+            .read_parquet("data/*.parquet")
+            .path("archive/it's.parquet")
+            .union_by_name()
+            .columns(["id", "region"])
+            .filter("year = ?", [2024])
+            .eq("region", "eu")
+            .limit(10)
+            .to_sql();
+
+        assert_eq!(
+            sql,
+            "SELECT \"id\", \"region\" FROM read_parquet(['data/*.parquet', 'archive/it''s.parquet'], \
+             union_by_name = true) WHERE (year = ?) AND (\"region\" = ?) LIMIT 10"
+        );
+        assert_eq!(
+            params,
+            vec![FilterValue::Int(2024), FilterValue::String("eu".into())]
+        );
+
+        let source = db
+            .read_csv("in.csv")
+            .header(true)
+            .delimiter(';')
+            .header(false)
+            .column_types([("id", "BIGINT")])
+            .source_sql();
+        assert_eq!(
+            source,
+            "read_csv(['in.csv'], delim = ';', header = false, types = {'id': 'BIGINT'})"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csv_into_table() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.csv"), "id,name\n1,Alice\n2,Bob\n").unwrap();
+        std::fs::write(dir.path().join("b.csv"), "id,name\n3,Carol\n").unwrap();
+        let pattern = dir.path().join("*.csv").to_string_lossy().into_owned();
+
+        let db = engine().await;
+        let reader = db.read_csv(&pattern).header(true);
+
+        let schema = reader.describe().await.unwrap();
+        assert_eq!(schema.files.len(), 2);
+        assert_eq!(
+            schema
+                .columns
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "name"]
+        );
+
+        let report = reader.into_table("people").await.unwrap();
+        assert_eq!(report.rows, 3);
+
+        let report = reader
+            .filter("id > ?", [1])
+            .append_to("people")
+            .await
+            .unwrap();
+        assert_eq!(report.rows, 2);
+        assert_eq!(db.count("people", &HashMap::new()).await.unwrap(), 5);
+    }
+}
@@ -12,6 +12,11 @@
 //! - **JSON support**: Query JSON data directly
 //! - **SQL compatibility**: Full SQL support with extensions
 //! - **Async support**: Async operations via Tokio task spawning
+//! - **Hybrid analytics**: Mirror OLTP tables and route aggregates to DuckDB
+//! - **Retention archives**: Archive expired rows to Parquet files
+//! - **File ingest**: Typed Parquet/CSV readers loading files into tables
+//! - **Arrow results**: Columnar Arrow record batches, or Polars DataFrames with
+//!   the `polars` feature
 //!
 //! # When to Use DuckDB
 //!
@@ -69,20 +74,45 @@
 //!     &[]
 //! ).await?;
 //! ```
+//!
+//! # Columnar Results
+//!
+//! `execute_raw` converts every row to JSON. For large results, fetch Arrow
+//! record batches instead, or a Polars `DataFrame` with the `polars` feature:
+//!
+//! ```rust,ignore
+//! let batches = engine
+//!     .query_arrow("SELECT region, SUM(revenue) FROM sales GROUP BY region", &[])
+//!     .await?;
+//! let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+//!
+//! let df = engine.query_polars("SELECT * FROM sales", &[]).await?;
+//! ```
 
 pub mod config;
 pub mod connection;
 pub mod engine;
 pub mod error;
+pub mod hybrid;
+pub mod ingest;
 pub mod pool;
+pub mod retention;
 pub mod row;
 pub mod types;
 
 pub use config::{AccessMode, DuckDbConfig, DuckDbConfigBuilder, ThreadMode};
 pub use connection::DuckDbConnection;
+pub use duckdb::arrow;
+#[cfg(feature = "polars")]
+pub use duckdb::polars;
 pub use engine::{DuckDbEngine, DuckDbQueryResult};
 pub use error::{DuckDbError, DuckDbResult};
+pub use hybrid::{HybridAnalyticsEngine, MirrorSpec, RefreshMode};
+pub use ingest::{
+    CsvReader, FileReader, InferredColumn, IngestReport, IngestSchema, ParquetReader,
+};
 pub use pool::{DuckDbPool, DuckDbPoolBuilder, PoolConfig};
+pub use retention::ParquetArchive;
 pub use row::FromDuckDbRow;
 
 /// Prelude for convenient imports.
@@ -91,6 +121,8 @@ pub mod prelude {
     pub use crate::connection::DuckDbConnection;
     pub use crate::engine::{DuckDbEngine, DuckDbQueryResult};
     pub use crate::error::{DuckDbError, DuckDbResult};
+    pub use crate::hybrid::{HybridAnalyticsEngine, MirrorSpec, RefreshMode};
+    pub use crate::ingest::{CsvReader, IngestReport, ParquetReader};
     pub use crate::pool::{DuckDbPool, DuckDbPoolBuilder};
     pub use crate::row::FromDuckDbRow;
 }
@@ -208,6 +208,37 @@ impl PooledConnection {
             .map_err(|e| DuckDbError::internal(format!("Task join error: {}", e)))?
     }
 
+    /// Query and return the result as Arrow record batches.
+    pub async fn query_arrow(
+        &self,
+        sql: &str,
+        params: &[prax_query::filter::FilterValue],
+    ) -> DuckDbResult<Vec<duckdb::arrow::record_batch::RecordBatch>> {
+        let conn = self.connection().clone();
+        let sql = sql.to_string();
+        let params = params.to_vec();
+
+        tokio::task::spawn_blocking(move || conn.query_arrow(&sql, &params))
+            .await
+            .map_err(|e| DuckDbError::internal(format!("Task join error: {}", e)))?
+    }
+
+    /// Query and return the result as a Polars `DataFrame`.
+    #[cfg(feature = "polars")]
+    pub async fn query_polars(
+        &self,
+        sql: &str,
+        params: &[prax_query::filter::FilterValue],
+    ) -> DuckDbResult<duckdb::polars::prelude::DataFrame> {
+        let conn = self.connection().clone();
+        let sql = sql.to_string();
+        let params = params.to_vec();
+
+        tokio::task::spawn_blocking(move || conn.query_polars(&sql, &params))
+            .await
+            .map_err(|e| DuckDbError::internal(format!("Task join error: {}", e)))?
+    }
+
     /// Query and return the first row.
     pub async fn query_one(
         &self,
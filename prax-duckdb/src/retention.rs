@@ -0,0 +1,168 @@
+//! Data retention support for DuckDB.
+//!
+//! [`DuckDbEngine`] implements [`RawDatabase`](prax_query::raw::RawDatabase),
+//! so retention policies can purge DuckDB tables directly. [`ParquetArchive`]
+//! is a [`RetentionArchive`] sink that writes every archived batch to its own
+//! Parquet file, which works for any primary database:
+//!
+//! ```rust,ignore
+//! use prax_duckdb::retention::ParquetArchive;
+//! use prax_query::retention::RetentionRunner;
+//!
+//! let archive = ParquetArchive::new(DuckDbEngine::new(pool), "/var/archive");
+//! let policy = audit_log::retention_policy().archive_to(archive);
+//!
+//! RetentionRunner::new(primary).run(&policy).await?;
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use prax_query::error::QueryResult;
+use prax_query::retention::RetentionArchive;
+use prax_query::traits::BoxFuture;
+use serde_json::Value as JsonValue;
+use tracing::debug;
+
+use crate::engine::DuckDbEngine;
+use crate::error::{DuckDbError, DuckDbResult};
+
+/// Archives expired rows to Parquet files.
+///
+/// Each batch becomes `<dir>/<table>-<unix millis>-<seq>.parquet`. Rows are
+/// staged as newline-delimited JSON and converted by DuckDB, so column types
+/// are inferred from the JSON values. Requires the `json` and `parquet`
+/// features (or the extensions being installed).
+#[derive(Clone)]
+pub struct ParquetArchive {
+    engine: DuckDbEngine,
+    dir: PathBuf,
+    seq: std::sync::Arc<std::sync::atomic::AtomicU64>,
+}
+
+impl ParquetArchive {
+    /// Create an archive writing into `dir`, which must exist.
+    pub fn new(engine: DuckDbEngine, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            engine,
+            dir: dir.into(),
+            seq: Default::default(),
+        }
+    }
+
+    /// The directory archive files are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn batch_path(&self, table: &str, extension: &str) -> PathBuf {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let seq = self.seq.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.dir
+            .join(format!("{}-{}-{}.{}", table, millis, seq, extension))
+    }
+
+    async fn write_batch(&self, table: &str, rows: Vec<JsonValue>) -> DuckDbResult<PathBuf> {
+        let staging = self.batch_path(table, "ndjson");
+        let target = staging.with_extension("parquet");
+
+        let mut ndjson = String::new();
+        for row in &rows {
+            let line = serde_json::to_string(row)
+                .map_err(|e| DuckDbError::deserialization(e.to_string()))?;
+            ndjson.push_str(&line);
+            ndjson.push('\n');
+        }
+        tokio::fs::write(&staging, ndjson).await?;
+
+        let result = self
+            .engine
+            .copy_to_parquet(
+                &format!("SELECT * FROM read_json_auto('{}')", sql_path(&staging)),
+                &sql_path(&target),
+            )
+            .await;
+        let _ = tokio::fs::remove_file(&staging).await;
+        result?;
+
+        debug!(
+            table = %table,
+            rows = rows.len(),
+            path = %target.display(),
+            "Archived rows to Parquet"
+        );
+        Ok(target)
+    }
+}
+
+impl RetentionArchive for ParquetArchive {
+    fn archive<'a>(
+        &'a self,
+        table: &'a str,
+        rows: Vec<JsonValue>,
+    ) -> BoxFuture<'a, QueryResult<()>> {
+        Box::pin(async move {
+            self.write_batch(table, rows).await?;
+            Ok(())
+        })
+    }
+}
+
+impl std::fmt::Debug for ParquetArchive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParquetArchive")
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A path as a single-quoted SQL string body.
+fn sql_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\'', "''")
+}
+
+#[cfg(all(test, feature = "json", feature = "parquet"))]
+mod tests {
+    use super::*;
+    use crate::{DuckDbConfig, DuckDbPool};
+    use prax_query::retention::{RetentionPolicy, RetentionRunner};
+    use std::time::Duration;
+
+    async fn engine() -> DuckDbEngine {
+        let pool = DuckDbPool::new(DuckDbConfig::in_memory()).await.unwrap();
+        DuckDbEngine::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_retention_archives_to_parquet() {
+        let db = engine().await;
+        db.raw_sql_batch(
+            "CREATE TABLE events (id INTEGER, created_at TIMESTAMP);
+             INSERT INTO events VALUES
+                 (1, NOW() - INTERVAL '10 days'),
+                 (2, NOW() - INTERVAL '9 days'),
+                 (3, NOW());",
+        )
+        .await
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let policy = RetentionPolicy::new("events", "created_at", Duration::from_secs(86_400))
+            .key(["id"])
+            .archive_to(ParquetArchive::new(db.clone(), dir.path()));
+
+        let runner = RetentionRunner::new(db.clone());
+        let dry_run = runner.dry_run(&policy).await.unwrap();
+        assert_eq!(dry_run.expired_rows, 2);
+
+        let report = runner.run(&policy).await.unwrap();
+        assert_eq!(report.rows_archived, 2);
+        assert_eq!(report.rows_purged, 2);
+        assert_eq!(db.count("events", &Default::default()).await.unwrap(), 1);
+
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+    }
+}
@@ -13,6 +13,7 @@ pub fn filter_value_to_duckdb(value: &FilterValue) -> Value {
         FilterValue::Float(f) => Value::Double(*f),
         FilterValue::String(s) => Value::Text(s.clone()),
         FilterValue::Json(j) => Value::Text(j.to_string()),
+        FilterValue::Bytes(b) => Value::Blob(b.clone()),
         FilterValue::List(list) => {
             // DuckDB supports arrays, but for simplicity convert to JSON
             let json_array: Vec<JsonValue> = list.iter().map(filter_value_to_json).collect();
@@ -32,6 +33,7 @@ pub fn filter_value_to_json(value: &FilterValue) -> JsonValue {
             .unwrap_or(JsonValue::Null),
         FilterValue::String(s) => JsonValue::String(s.clone()),
         FilterValue::Json(j) => j.clone(),
+        FilterValue::Bytes(b) => JsonValue::from(b.clone()),
         FilterValue::List(list) => JsonValue::Array(list.iter().map(filter_value_to_json).collect()),
     }
 }
@@ -209,6 +211,7 @@ impl duckdb::ToSql for DuckDbParam<'_> {
             FilterValue::Float(f) => Ok(ToSqlOutput::Owned(Value::Double(*f))),
             FilterValue::String(s) => Ok(ToSqlOutput::Owned(Value::Text(s.clone()))),
             FilterValue::Json(j) => Ok(ToSqlOutput::Owned(Value::Text(j.to_string()))),
+            FilterValue::Bytes(b) => Ok(ToSqlOutput::Borrowed(ValueRef::Blob(b))),
             FilterValue::List(list) => {
                 let json_array: Vec<JsonValue> = list.iter().map(filter_value_to_json).collect();
                 Ok(ToSqlOutput::Owned(Value::Text(
@@ -0,0 +1,50 @@
+/*
+ * C interface to a Prax client built with prax-ffi.
+ *
+ * Requests and responses are NUL-terminated UTF-8 JSON envelopes:
+ *
+ *   {"id": 1, "model": "User", "action": "findMany", "args": {}}
+ *   {"id": 1, "ok": true, "data": [...]}
+ *   {"id": 1, "ok": false, "error": {"code": "P1001", "message": "..."}}
+ *
+ * Every string returned by the library is owned by the caller and must be
+ * released with prax_ffi_string_free.
+ */
+
+#ifndef PRAX_FFI_H
+#define PRAX_FFI_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct PraxClient PraxClient;
+
+/* Receives an async response on a library thread; free `response`. */
+typedef void (*PraxCallback)(void *user_data, char *response);
+
+/* Create a client from a JSON configuration (may be NULL).
+ * Returns NULL on failure; see prax_ffi_last_error. */
+PraxClient *prax_ffi_client_new(const char *config);
+
+/* Take the error left by the last failed prax_ffi_client_new on this
+ * thread, or NULL. */
+char *prax_ffi_last_error(void);
+
+/* Run a request, blocking until it completes. */
+char *prax_ffi_call(const PraxClient *client, const char *request);
+
+/* Run a request in the background; `callback` is called exactly once. */
+void prax_ffi_call_async(const PraxClient *client, const char *request,
+                         PraxCallback callback, void *user_data);
+
+void prax_ffi_string_free(char *s);
+
+/* Free a client once no calls are in progress. */
+void prax_ffi_client_free(PraxClient *client);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PRAX_FFI_H */
@@ -0,0 +1,321 @@
+//! The C ABI.
+//!
+//! Strings cross the boundary as NUL-terminated UTF-8. Every string returned
+//! by this module is owned by the caller and must be released with
+//! [`prax_ffi_string_free`]; clients are released with
+//! [`prax_ffi_client_free`]. The constructor, `prax_ffi_client_new`, is
+//! generated in the client's own crate by [`export_client!`](crate::export_client).
+//!
+//! Failures never unwind across the boundary: calls answer with an error
+//! envelope, and a failed constructor returns null and leaves its error for
+//! [`prax_ffi_last_error`].
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+
+use prax_query::error::{ErrorCode, QueryError, QueryResult};
+use serde_json::Value;
+
+use crate::client::FfiClient;
+use crate::envelope::Response;
+use crate::router::Router;
+
+/// Receives the response to [`prax_ffi_call_async`], on a runtime thread.
+///
+/// The callback owns `response` and must free it with [`prax_ffi_string_free`].
+pub type PraxCallback = extern "C" fn(user_data: *mut c_void, response: *mut c_char);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Create a client, for the `prax_ffi_client_new` generated by
+/// [`export_client!`](crate::export_client).
+///
+/// `config` is parsed as JSON and passed to `init`; a null `config` passes
+/// `null`. Returns null on failure.
+///
+/// # Safety
+///
+/// `config` must be null or point to a NUL-terminated string.
+pub unsafe fn client_new<F, Fut>(config: *const c_char, init: F) -> *mut FfiClient
+where
+    F: FnOnce(Value) -> Fut,
+    Fut: Future<Output = QueryResult<Router>>,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let config = if config.is_null() {
+            Value::Null
+        } else {
+            let config = unsafe { read_str(config) }?;
+            serde_json::from_str(config).map_err(|e| {
+                QueryError::new(
+                    ErrorCode::InvalidConfiguration,
+                    format!("Malformed configuration: {}", e),
+                )
+            })?
+        };
+        FfiClient::connect(config, init)
+    }))
+    .unwrap_or_else(|panic| Err(panicked(panic)));
+
+    match result {
+        Ok(client) => Box::into_raw(Box::new(client)),
+        Err(err) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(err.to_string()));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Take the error left by the last failed `prax_ffi_client_new` on this
+/// thread, or null if there is none.
+#[unsafe(no_mangle)]
+pub extern "C" fn prax_ffi_last_error() -> *mut c_char {
+    LAST_ERROR
+        .with(|last| last.borrow_mut().take())
+        .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+/// Run a JSON request, blocking until it completes, and return the JSON
+/// response.
+///
+/// # Safety
+///
+/// `client` must be null or a live client from `prax_ffi_client_new`, and
+/// `request` must be null or point to a NUL-terminated string. Must not be
+/// called from a runtime thread, such as inside a [`PraxCallback`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prax_ffi_call(
+    client: *const FfiClient,
+    request: *const c_char,
+) -> *mut c_char {
+    let response = panic::catch_unwind(AssertUnwindSafe(|| {
+        let client = unsafe { client.as_ref() }.ok_or_else(null_client)?;
+        let request = unsafe { read_str(request) }?;
+        Ok(client.call(request))
+    }))
+    .unwrap_or_else(|panic| Err(panicked(panic)))
+    .unwrap_or_else(|err| Response::error(Value::Null, err).to_json());
+    into_c_string(response)
+}
+
+/// Run a JSON request in the background, passing the JSON response and
+/// `user_data` to `callback` when it completes.
+///
+/// The callback runs on a runtime thread, once, even if the request could
+/// not be started.
+///
+/// # Safety
+///
+/// As for [`prax_ffi_call`]; in addition `user_data` must be safe to use from
+/// another thread.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prax_ffi_call_async(
+    client: *const FfiClient,
+    request: *const c_char,
+    callback: PraxCallback,
+    user_data: *mut c_void,
+) {
+    let user_data = UserData(user_data);
+    let respond = move |response: String| {
+        let user_data = user_data;
+        callback(user_data.0, into_c_string(response));
+    };
+
+    let started = panic::catch_unwind(AssertUnwindSafe(|| {
+        let client = unsafe { client.as_ref() }.ok_or_else(null_client)?;
+        let request = unsafe { read_str(request) }?.to_string();
+        Ok((client, request))
+    }))
+    .unwrap_or_else(|panic| Err(panicked(panic)));
+
+    match started {
+        Ok((client, request)) => client.call_async(request, respond),
+        Err(err) => {
+            let response = Response::error(Value::Null, err).to_json();
+            std::thread::spawn(move || respond(response));
+        }
+    }
+}
+
+/// Free a string returned by this library.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library, not already freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prax_ffi_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Free a client, closing its connections.
+///
+/// # Safety
+///
+/// `client` must be null or a client from `prax_ffi_client_new`, not already
+/// freed, with no calls still in progress.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prax_ffi_client_free(client: *mut FfiClient) {
+    if !client.is_null() {
+        drop(unsafe { Box::from_raw(client) });
+    }
+}
+
+/// Generate `prax_ffi_client_new` for a client crate built as a `cdylib`.
+///
+/// `init` is an async function from the JSON configuration to the
+/// [`Router`] exposing the client's models.
+///
+/// ```rust,ignore
+/// async fn init(config: serde_json::Value) -> QueryResult<Router> {
+///     let pool = PgPool::builder().url(config["url"].as_str().unwrap_or_default()).build().await?;
+///     let client = Arc::new(PraxClient::new(PgEngine::new(pool)));
+///     Ok(Router::new().route("User", "findMany", {
+///         let client = client.clone();
+///         move |args: UserFindManyArgs| {
+///             let client = client.clone();
+///             async move { client.user().find_many().r#where(args.r#where).exec().await }
+///         }
+///     }))
+/// }
+///
+/// prax_ffi::export_client!(init);
+/// ```
+#[macro_export]
+macro_rules! export_client {
+    ($init:expr) => {
+        /// Create a client from a JSON configuration, or return null and
+        /// leave the error for `prax_ffi_last_error`.
+        ///
+        /// # Safety
+        ///
+        /// `config` must be null or point to a NUL-terminated string.
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn prax_ffi_client_new(
+            config: *const ::std::ffi::c_char,
+        ) -> *mut $crate::FfiClient {
+            let init = $init;
+            unsafe { $crate::c_api::client_new(config, init) }
+        }
+    };
+}
+
+/// A caller's context pointer, which the caller vouches is thread-safe.
+struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+
+/// Borrow a NUL-terminated UTF-8 string.
+unsafe fn read_str<'a>(s: *const c_char) -> QueryResult<&'a str> {
+    if s.is_null() {
+        return Err(QueryError::new(
+            ErrorCode::InvalidParameter,
+            "Expected a string, got null",
+        ));
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|e| QueryError::new(ErrorCode::InvalidParameter, format!("Invalid UTF-8: {}", e)))
+}
+
+/// Hand a string to the caller.
+fn into_c_string(s: String) -> *mut c_char {
+    // Serialized JSON escapes NUL, so only error text could contain one.
+    CString::new(s)
+        .unwrap_or_else(|e| {
+            let mut bytes = e.into_vec();
+            bytes.retain(|b| *b != 0);
+            CString::new(bytes).unwrap_or_default()
+        })
+        .into_raw()
+}
+
+fn null_client() -> QueryError {
+    QueryError::new(ErrorCode::InvalidParameter, "Client is null")
+}
+
+fn panicked(panic: Box<dyn std::any::Any + Send>) -> QueryError {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    QueryError::internal(format!("panicked: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{self, Sender};
+
+    async fn init(config: Value) -> QueryResult<Router> {
+        if config["fail"] == true {
+            return Err(QueryError::connection("refused"));
+        }
+        Ok(Router::new().route("Math", "double", |n: i64| async move { Ok(n * 2) }))
+    }
+
+    crate::export_client!(init);
+
+    fn take(s: *mut c_char) -> String {
+        let owned = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { prax_ffi_string_free(s) };
+        owned
+    }
+
+    #[test]
+    fn test_call_round_trip() {
+        let config = CString::new("{}").unwrap();
+        let client = unsafe { prax_ffi_client_new(config.as_ptr()) };
+        assert!(!client.is_null());
+
+        let request =
+            CString::new(r#"{"id": 1, "model": "Math", "action": "double", "args": 21}"#).unwrap();
+        let response = take(unsafe { prax_ffi_call(client, request.as_ptr()) });
+        assert_eq!(response, r#"{"id":1,"ok":true,"data":42}"#);
+
+        let response = take(unsafe { prax_ffi_call(client, std::ptr::null()) });
+        assert!(response.contains("Expected a string, got null"));
+
+        unsafe { prax_ffi_client_free(client) };
+    }
+
+    #[test]
+    fn test_call_async_round_trip() {
+        extern "C" fn done(user_data: *mut c_void, response: *mut c_char) {
+            let tx = unsafe { Box::from_raw(user_data as *mut Sender<String>) };
+            tx.send(take(response)).unwrap();
+        }
+
+        let client = unsafe { prax_ffi_client_new(std::ptr::null()) };
+        let request = CString::new(r#"{"model": "Math", "action": "double", "args": 4}"#).unwrap();
+        let (tx, rx) = mpsc::channel::<String>();
+        let user_data = Box::into_raw(Box::new(tx)) as *mut c_void;
+        unsafe { prax_ffi_call_async(client, request.as_ptr(), done, user_data) };
+        assert_eq!(rx.recv().unwrap(), r#"{"id":null,"ok":true,"data":8}"#);
+
+        let (tx, rx) = mpsc::channel::<String>();
+        let user_data = Box::into_raw(Box::new(tx)) as *mut c_void;
+        unsafe { prax_ffi_call_async(std::ptr::null(), request.as_ptr(), done, user_data) };
+        assert!(rx.recv().unwrap().contains("Client is null"));
+
+        unsafe { prax_ffi_client_free(client) };
+    }
+
+    #[test]
+    fn test_client_new_failure() {
+        let config = CString::new(r#"{"fail": true}"#).unwrap();
+        assert!(unsafe { prax_ffi_client_new(config.as_ptr()) }.is_null());
+        assert!(take(prax_ffi_last_error()).contains("refused"));
+        assert!(prax_ffi_last_error().is_null());
+
+        let config = CString::new("{").unwrap();
+        assert!(unsafe { prax_ffi_client_new(config.as_ptr()) }.is_null());
+        assert!(take(prax_ffi_last_error()).contains("Malformed configuration"));
+    }
+}
@@ -0,0 +1,119 @@
+//! The client handed across the FFI boundary.
+
+use std::future::Future;
+
+use prax_query::error::{QueryError, QueryResult};
+use serde_json::Value;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::router::Router;
+
+/// A [`Router`] together with the runtime its queries run on.
+///
+/// Connection pools must be created on the runtime that later drives their
+/// connections, so [`FfiClient::connect`] builds the runtime first and runs
+/// the initializer on it.
+pub struct FfiClient {
+    router: Router,
+    runtime: Runtime,
+}
+
+impl FfiClient {
+    /// Build a runtime, run `init` on it with the caller's configuration,
+    /// and wrap the router it returns.
+    pub fn connect<F, Fut>(config: Value, init: F) -> QueryResult<Self>
+    where
+        F: FnOnce(Value) -> Fut,
+        Fut: Future<Output = QueryResult<Router>>,
+    {
+        let runtime = Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("prax-ffi")
+            .build()
+            .map_err(|e| QueryError::internal(format!("failed to start runtime: {}", e)))?;
+        let router = runtime.block_on(init(config))?;
+        Ok(Self::with_runtime(router, runtime))
+    }
+
+    /// Wrap a router and the runtime its pools were created on.
+    pub fn with_runtime(router: Router, runtime: Runtime) -> Self {
+        Self { router, runtime }
+    }
+
+    /// The router requests are dispatched to.
+    pub fn router(&self) -> &Router {
+        &self.router
+    }
+
+    /// Run a JSON request, blocking until it completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called from within an async runtime.
+    pub fn call(&self, request: &str) -> String {
+        self.runtime.block_on(self.router.dispatch_json(request))
+    }
+
+    /// Run a JSON request in the background, passing the JSON response to
+    /// `callback` on a runtime thread.
+    pub fn call_async<C>(&self, request: String, callback: C)
+    where
+        C: FnOnce(String) + Send + 'static,
+    {
+        let router = self.router.clone();
+        self.runtime.spawn(async move {
+            callback(router.dispatch_json(&request).await);
+        });
+    }
+}
+
+impl std::fmt::Debug for FfiClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FfiClient")
+            .field("router", &self.router)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn client() -> FfiClient {
+        FfiClient::connect(serde_json::json!({"greeting": "hi"}), |config| async move {
+            let greeting = config["greeting"].as_str().unwrap_or_default().to_string();
+            Ok(Router::new().route("Greeting", "get", move |_: ()| {
+                let greeting = greeting.clone();
+                async move { Ok(greeting) }
+            }))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_call() {
+        let response = client().call(r#"{"id": 1, "model": "Greeting", "action": "get"}"#);
+        assert_eq!(response, r#"{"id":1,"ok":true,"data":"hi"}"#);
+    }
+
+    #[test]
+    fn test_call_async() {
+        let client = client();
+        let (tx, rx) = mpsc::channel();
+        client.call_async(
+            r#"{"id": 2, "model": "Greeting", "action": "get"}"#.into(),
+            move |response| tx.send(response).unwrap(),
+        );
+        assert_eq!(rx.recv().unwrap(), r#"{"id":2,"ok":true,"data":"hi"}"#);
+    }
+
+    #[test]
+    fn test_connect_error() {
+        let err = FfiClient::connect(Value::Null, |_| async {
+            Err(QueryError::connection("refused"))
+        })
+        .unwrap_err();
+        assert!(err.is_connection_error());
+    }
+}
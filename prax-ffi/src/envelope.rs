@@ -0,0 +1,192 @@
+//! JSON request and response envelopes.
+//!
+//! Every call across the FFI boundary is one JSON request naming a model and
+//! an action, answered by one JSON response carrying either the result or a
+//! structured error:
+//!
+//! ```json
+//! {"id": 7, "model": "User", "action": "findUnique", "args": {"id": 1}}
+//!
+//! {"id": 7, "ok": true, "data": {"id": 1, "email": "alice@example.com"}}
+//! {"id": 7, "ok": false, "error": {"code": "P1001", "message": "No User record found matching the query"}}
+//! ```
+//!
+//! The `id` is echoed back untouched so callers can match responses to
+//! requests made through the asynchronous entry points.
+
+use prax_query::error::{ErrorCode, QueryError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A call into the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    /// Caller-chosen correlation id, echoed in the response.
+    #[serde(default)]
+    pub id: Value,
+    /// The model the action belongs to, e.g. `User`.
+    pub model: String,
+    /// The action to run, e.g. `findMany`.
+    pub action: String,
+    /// The action's arguments.
+    #[serde(default)]
+    pub args: Value,
+}
+
+impl Request {
+    /// Create a request without an id.
+    pub fn new(model: impl Into<String>, action: impl Into<String>, args: Value) -> Self {
+        Self {
+            id: Value::Null,
+            model: model.into(),
+            action: action.into(),
+            args,
+        }
+    }
+
+    /// Set the correlation id.
+    pub fn with_id(mut self, id: impl Into<Value>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Parse a request from JSON.
+    ///
+    /// A malformed request is answered with an error response, keeping its
+    /// `id` when one can be recovered.
+    pub fn parse(json: &str) -> Result<Self, Response> {
+        serde_json::from_str(json).map_err(|e| {
+            let id = serde_json::from_str::<Value>(json)
+                .ok()
+                .and_then(|v| v.get("id").cloned())
+                .unwrap_or(Value::Null);
+            Response::error(
+                id,
+                QueryError::new(
+                    ErrorCode::InvalidParameter,
+                    format!("Malformed request: {}", e),
+                ),
+            )
+        })
+    }
+}
+
+/// The answer to a [`Request`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    /// The id of the request being answered.
+    pub id: Value,
+    /// Whether the action succeeded.
+    pub ok: bool,
+    /// The action's result, when it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// What went wrong, when it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>,
+}
+
+impl Response {
+    /// A successful response.
+    pub fn success(id: Value, data: Value) -> Self {
+        Self {
+            id,
+            ok: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    /// A failed response.
+    pub fn error(id: Value, error: QueryError) -> Self {
+        Self {
+            id,
+            ok: false,
+            data: None,
+            error: Some(ErrorBody::from(&error)),
+        }
+    }
+
+    /// Serialize the response to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|e| {
+            // Only reachable with non-string map keys in `data`.
+            Response::error(
+                self.id.clone(),
+                QueryError::serialization(format!("Unencodable response: {}", e)),
+            )
+            .to_json()
+        })
+    }
+}
+
+/// A [`QueryError`] as seen from the other side of the boundary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    /// The error code, e.g. `P1001`.
+    pub code: String,
+    /// The error message.
+    pub message: String,
+    /// The model involved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// The field involved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Suggestions for fixing the error.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+    /// Help text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+}
+
+impl From<&QueryError> for ErrorBody {
+    fn from(err: &QueryError) -> Self {
+        Self {
+            code: err.code.code(),
+            message: err.message.clone(),
+            model: err.context().model.clone(),
+            field: err.context().field.clone(),
+            suggestions: err
+                .context()
+                .suggestions
+                .iter()
+                .map(|s| s.text.clone())
+                .collect(),
+            help: err.context().help.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_defaults() {
+        let request: Request =
+            serde_json::from_str(r#"{"model": "User", "action": "count"}"#).unwrap();
+        assert_eq!(request, Request::new("User", "count", Value::Null));
+
+        let err = Request::parse(r#"{"id": 4, "model": "User"}"#).unwrap_err();
+        assert_eq!(err.id, json!(4));
+        assert_eq!(err.error.unwrap().code, "P5003");
+        assert_eq!(Request::parse("not json").unwrap_err().id, Value::Null);
+    }
+
+    #[test]
+    fn test_response_json() {
+        let ok = Response::success(json!("a"), json!([1, 2]));
+        assert_eq!(ok.to_json(), r#"{"id":"a","ok":true,"data":[1,2]}"#);
+
+        let err = QueryError::not_found("User").with_field("email");
+        let failed = Response::error(json!(1), err);
+        let value: Value = serde_json::from_str(&failed.to_json()).unwrap();
+        assert_eq!(value["ok"], json!(false));
+        assert_eq!(value["error"]["code"], json!("P1001"));
+        assert_eq!(value["error"]["field"], json!("email"));
+        assert!(value.get("data").is_none());
+    }
+}
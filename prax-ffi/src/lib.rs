@@ -0,0 +1,61 @@
+//! Foreign function interface for Prax clients.
+//!
+//! This crate exposes a compiled Prax client to other languages, so existing
+//! Node.js or Deno services can call the same typed data layer while they
+//! are migrated to Rust piece by piece.
+//!
+//! # Features
+//!
+//! - **JSON envelopes**: Each call is a JSON [`Request`] naming a model and
+//!   action, answered by a JSON [`Response`] with the data or a structured
+//!   error
+//! - **Router**: Map `(model, action)` pairs to typed handlers over the
+//!   generated client
+//! - **C ABI**: `prax_ffi_*` functions for `Deno.dlopen`, Node's `ffi-napi`
+//!   or any C caller, with a header in `include/prax_ffi.h`
+//! - **napi-rs**: A `PraxClient` JavaScript class (`napi` feature)
+//!
+//! # Example
+//!
+//! Build the client crate as a `cdylib`:
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//!
+//! use prax_ffi::Router;
+//! use prax_query::error::QueryResult;
+//!
+//! async fn init(config: serde_json::Value) -> QueryResult<Router> {
+//!     let url = config["url"].as_str().unwrap_or_default();
+//!     let pool = PgPool::builder().url(url).build().await?;
+//!     let client = Arc::new(PraxClient::new(PgEngine::new(pool)));
+//!
+//!     Ok(Router::new().route("User", "findUnique", move |args: UserWhereUnique| {
+//!         let client = client.clone();
+//!         async move { client.user().find_unique().r#where(args).exec().await }
+//!     }))
+//! }
+//!
+//! prax_ffi::export_client!(init);
+//! ```
+//!
+//! and call it from Deno:
+//!
+//! ```js
+//! const lib = Deno.dlopen("./libmy_client.so", {
+//!   prax_ffi_client_new: { parameters: ["buffer"], result: "pointer" },
+//!   prax_ffi_call: { parameters: ["pointer", "buffer"], result: "pointer" },
+//!   prax_ffi_string_free: { parameters: ["pointer"], result: "void" },
+//! });
+//! ```
+
+pub mod c_api;
+pub mod client;
+pub mod envelope;
+#[cfg(feature = "napi")]
+pub mod node;
+pub mod router;
+
+pub use client::FfiClient;
+pub use envelope::{ErrorBody, Request, Response};
+pub use router::Router;
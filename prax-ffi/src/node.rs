@@ -0,0 +1,100 @@
+//! Node.js bindings through napi-rs.
+//!
+//! [`PraxClient`] is a JavaScript class over an [`FfiClient`]. The client
+//! crate exports its own constructor, since only it knows how to build the
+//! router:
+//!
+//! ```rust,ignore
+//! use napi_derive::napi;
+//! use prax_ffi::node::PraxClient;
+//!
+//! #[napi]
+//! pub fn connect(config: String) -> napi::Result<PraxClient> {
+//!     PraxClient::connect(&config, init)
+//! }
+//! ```
+//!
+//! ```js
+//! const { connect } = require('./index.node');
+//!
+//! const client = connect(JSON.stringify({ url: process.env.DATABASE_URL }));
+//! const users = JSON.parse(
+//!   await client.call(JSON.stringify({ model: 'User', action: 'findMany', args: {} })),
+//! );
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use prax_query::error::QueryResult;
+
+use crate::client::FfiClient;
+use crate::router::Router;
+
+/// A Prax client callable from JavaScript.
+#[napi]
+pub struct PraxClient {
+    inner: Arc<FfiClient>,
+}
+
+impl PraxClient {
+    /// Parse a JSON configuration and build a client with `init`.
+    pub fn connect<F, Fut>(config: &str, init: F) -> Result<Self>
+    where
+        F: FnOnce(serde_json::Value) -> Fut,
+        Fut: Future<Output = QueryResult<Router>>,
+    {
+        let config = serde_json::from_str(config)
+            .map_err(|e| Error::from_reason(format!("Malformed configuration: {}", e)))?;
+        FfiClient::connect(config, init)
+            .map(Self::from)
+            .map_err(|e| Error::from_reason(e.to_string()))
+    }
+}
+
+impl From<FfiClient> for PraxClient {
+    fn from(client: FfiClient) -> Self {
+        Self {
+            inner: Arc::new(client),
+        }
+    }
+}
+
+#[napi]
+impl PraxClient {
+    /// Run a JSON request, resolving to the JSON response.
+    #[napi]
+    pub fn call(&self, request: String) -> AsyncTask<Call> {
+        AsyncTask::new(Call {
+            client: Arc::clone(&self.inner),
+            request,
+        })
+    }
+
+    /// Run a JSON request, blocking the event loop until it completes.
+    #[napi]
+    pub fn call_sync(&self, request: String) -> String {
+        self.inner.call(&request)
+    }
+}
+
+/// A request run on the libuv thread pool.
+pub struct Call {
+    client: Arc<FfiClient>,
+    request: String,
+}
+
+impl Task for Call {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(self.client.call(&self.request))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
@@ -0,0 +1,192 @@
+//! Routing requests to typed handlers.
+//!
+//! A [`Router`] maps each `(model, action)` pair to a handler that
+//! deserializes the request's `args`, runs the query through the generated
+//! client and serializes the result, so the foreign caller reaches the same
+//! typed data layer as Rust code.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use prax_query::error::{ErrorCode, QueryError, QueryResult};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::envelope::{Request, Response};
+
+type BoxFuture<T> = std::pin::Pin<Box<dyn Future<Output = T> + Send>>;
+type Handler = Arc<dyn Fn(Value) -> BoxFuture<QueryResult<Value>> + Send + Sync>;
+
+/// Dispatches requests to the handlers registered for them.
+#[derive(Clone, Default)]
+pub struct Router {
+    routes: BTreeMap<(String, String), Handler>,
+}
+
+impl Router {
+    /// Create an empty router.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler for `model.action`.
+    ///
+    /// The request's `args` are deserialized into `A` (`null` for a missing
+    /// `args`), and the handler's result is serialized as the response data.
+    /// Registering the same pair again replaces the earlier handler.
+    pub fn route<A, R, F, Fut>(
+        mut self,
+        model: impl Into<String>,
+        action: impl Into<String>,
+        handler: F,
+    ) -> Self
+    where
+        A: DeserializeOwned + Send + 'static,
+        R: Serialize + 'static,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = QueryResult<R>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let erased: Handler = Arc::new(move |args| {
+            let handler = Arc::clone(&handler);
+            Box::pin(async move {
+                let args = serde_json::from_value(args)
+                    .map_err(|e| QueryError::invalid_input("args", e.to_string()))?;
+                let result = handler(args).await?;
+                serde_json::to_value(result).map_err(|e| QueryError::serialization(e.to_string()))
+            })
+        });
+        self.routes.insert((model.into(), action.into()), erased);
+        self
+    }
+
+    /// The registered `(model, action)` pairs, in order.
+    pub fn routes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.routes
+            .keys()
+            .map(|(model, action)| (model.as_str(), action.as_str()))
+    }
+
+    /// Run a request.
+    pub async fn dispatch(&self, request: Request) -> Response {
+        let Request {
+            id,
+            model,
+            action,
+            args,
+        } = request;
+        debug!(model = %model, action = %action, "Dispatching FFI request");
+
+        let Some(handler) = self.routes.get(&(model.clone(), action.clone())) else {
+            let err = QueryError::new(
+                ErrorCode::InvalidParameter,
+                format!("Unknown action {}.{}", model, action),
+            )
+            .with_model(model);
+            return Response::error(id, err);
+        };
+
+        match handler(args).await {
+            Ok(data) => Response::success(id, data),
+            Err(err) => Response::error(id, err),
+        }
+    }
+
+    /// Run a request given as JSON, answering in JSON.
+    pub async fn dispatch_json(&self, request: &str) -> String {
+        match Request::parse(request) {
+            Ok(request) => self.dispatch(request).await,
+            Err(response) => response,
+        }
+        .to_json()
+    }
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct FindUnique {
+        id: i64,
+    }
+
+    #[derive(Serialize)]
+    struct User {
+        id: i64,
+        email: String,
+    }
+
+    fn router() -> Router {
+        Router::new()
+            .route("User", "findUnique", |args: FindUnique| async move {
+                if args.id == 1 {
+                    Ok(User {
+                        id: 1,
+                        email: "alice@example.com".into(),
+                    })
+                } else {
+                    Err(QueryError::not_found("User"))
+                }
+            })
+            .route("User", "count", |_: ()| async { Ok(2u64) })
+    }
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let router = router();
+        let found = router
+            .dispatch(Request::new("User", "findUnique", json!({"id": 1})).with_id(9))
+            .await;
+        assert_eq!(
+            found,
+            Response::success(json!(9), json!({"id": 1, "email": "alice@example.com"}))
+        );
+
+        let missing = router
+            .dispatch(Request::new("User", "findUnique", json!({"id": 2})))
+            .await;
+        assert_eq!(missing.error.unwrap().code, "P1001");
+
+        let count = router
+            .dispatch_json(r#"{"model": "User", "action": "count"}"#)
+            .await;
+        assert_eq!(count, r#"{"id":null,"ok":true,"data":2}"#);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_errors() {
+        let router = router();
+
+        let unknown = router
+            .dispatch(Request::new("Post", "findMany", Value::Null))
+            .await;
+        let error = unknown.error.unwrap();
+        assert_eq!(error.message, "Unknown action Post.findMany");
+        assert_eq!(error.model.as_deref(), Some("Post"));
+
+        let bad_args = router
+            .dispatch(Request::new("User", "findUnique", json!({"id": "one"})))
+            .await;
+        assert_eq!(bad_args.error.unwrap().field.as_deref(), Some("args"));
+
+        assert_eq!(
+            router.routes().collect::<Vec<_>>(),
+            [("User", "count"), ("User", "findUnique")]
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Post-migration assertions.
+//!
+//! A migration directory may hold assertions checked right after the
+//! migration is applied, in `assert.sql`, `assert.toml`, or both:
+//!
+//! ```text
+//! migrations/20240101120000_backfill_emails/
+//! ├── up.sql
+//! ├── assert.sql      # queries that must return a true value
+//! └── assert.toml     # row counts, constraints and sample queries
+//! ```
+//!
+//! Each statement in `assert.sql` passes if its first row's first column
+//! is true, i.e. not NULL, `0` or false. `assert.toml` declares the
+//! common checks without writing the SQL:
+//!
+//! ```toml
+//! [[row_count]]
+//! table = "users"
+//! where = "email IS NULL"
+//! equals = 0
+//!
+//! [[constraint]]
+//! table = "users"
+//! name = "users_email_key"
+//!
+//! [[query]]
+//! name = "admin kept"
+//! sql = "SELECT role FROM users WHERE id = 1"
+//! equals = "admin"
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::{MigrateResult, MigrationError};
+use crate::executor::split_statements;
+
+/// A check run after a migration is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assertion {
+    /// What is being checked, for diagnostics.
+    pub name: String,
+    /// The query whose first column of its first row is checked.
+    pub sql: String,
+    /// The value the query must return.
+    pub expect: Expectation,
+}
+
+/// The value an [`Assertion`]'s query must return.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// Any value but NULL, `0` or false.
+    Truthy,
+    /// Exactly this value, compared as text.
+    Equals(String),
+    /// An integer within the bounds, inclusive.
+    Range {
+        /// Smallest value allowed.
+        min: Option<i64>,
+        /// Largest value allowed.
+        max: Option<i64>,
+    },
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truthy => write!(f, "a true value"),
+            Self::Equals(value) => write!(f, "{}", value),
+            Self::Range {
+                min: Some(min),
+                max: Some(max),
+            } if min == max => write!(f, "{}", min),
+            Self::Range { min, max } => match (min, max) {
+                (Some(min), Some(max)) => write!(f, "between {} and {}", min, max),
+                (Some(min), None) => write!(f, "at least {}", min),
+                (None, Some(max)) => write!(f, "at most {}", max),
+                (None, None) => write!(f, "an integer"),
+            },
+        }
+    }
+}
+
+impl Assertion {
+    /// Check the value the assertion's query returned; `None` stands for no
+    /// rows or NULL.
+    ///
+    /// Returns a diagnostic describing the failure.
+    pub fn check(&self, value: Option<&str>) -> Result<(), String> {
+        let value = value.map(str::trim);
+        let passed = match (&self.expect, value) {
+            (_, None) => false,
+            (Expectation::Truthy, Some(value)) => !matches!(
+                value.to_ascii_lowercase().as_str(),
+                "" | "0" | "f" | "false"
+            ),
+            (Expectation::Equals(expected), Some(value)) => value == expected,
+            (Expectation::Range { min, max }, Some(value)) => {
+                value.parse::<i64>().is_ok_and(|value| {
+                    min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+                })
+            }
+        };
+
+        if passed {
+            Ok(())
+        } else {
+            Err(format!(
+                "{}: expected {}, got {}",
+                self.name,
+                self.expect,
+                value.unwrap_or("no value")
+            ))
+        }
+    }
+}
+
+/// The assertions in a migration directory; empty if it has none.
+pub async fn load_assertions(dir: &Path) -> MigrateResult<Vec<Assertion>> {
+    let mut assertions = Vec::new();
+
+    let sql_path = dir.join("assert.sql");
+    if sql_path.exists() {
+        let sql = tokio::fs::read_to_string(&sql_path).await?;
+        assertions.extend(parse_sql_assertions(&sql));
+    }
+
+    let toml_path = dir.join("assert.toml");
+    if toml_path.exists() {
+        let toml = tokio::fs::read_to_string(&toml_path).await?;
+        assertions.extend(parse_toml_assertions(&toml).map_err(|e| {
+            MigrationError::InvalidMigration(format!("{}: {}", toml_path.display(), e))
+        })?);
+    }
+
+    Ok(assertions)
+}
+
+/// Assertions from `assert.sql`: each statement must return a true value.
+pub fn parse_sql_assertions(sql: &str) -> Vec<Assertion> {
+    split_statements(sql)
+        .into_iter()
+        .map(|sql| Assertion {
+            name: sql.lines().last().unwrap_or_default().trim().to_string(),
+            sql,
+            expect: Expectation::Truthy,
+        })
+        .collect()
+}
+
+/// Assertions from `assert.toml`.
+pub fn parse_toml_assertions(toml: &str) -> Result<Vec<Assertion>, toml::de::Error> {
+    let file: AssertionFile = toml::from_str(toml)?;
+    let mut assertions = Vec::new();
+
+    for check in file.row_count {
+        let filter = check
+            .filter
+            .as_ref()
+            .map(|filter| format!(" WHERE {}", filter))
+            .unwrap_or_default();
+        let (min, max) = match check.equals {
+            Some(count) => (Some(count), Some(count)),
+            None => (check.min, check.max),
+        };
+        assertions.push(Assertion {
+            name: format!("row count of {}{}", check.table, filter),
+            sql: format!("SELECT COUNT(*) FROM {}{}", check.table, filter),
+            expect: Expectation::Range { min, max },
+        });
+    }
+
+    for check in file.constraint {
+        assertions.push(Assertion {
+            name: format!("constraint {} on {}", check.name, check.table),
+            sql: format!(
+                "SELECT COUNT(*) FROM information_schema.table_constraints \
+                 WHERE table_name = '{}' AND constraint_name = '{}'",
+                check.table.replace('\'', "''"),
+                check.name.replace('\'', "''")
+            ),
+            expect: Expectation::Range {
+                min: Some(1),
+                max: None,
+            },
+        });
+    }
+
+    for check in file.query {
+        assertions.push(Assertion {
+            name: check.name.unwrap_or_else(|| check.sql.clone()),
+            sql: check.sql,
+            expect: check
+                .equals
+                .map(Expectation::Equals)
+                .unwrap_or(Expectation::Truthy),
+        });
+    }
+
+    Ok(assertions)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AssertionFile {
+    #[serde(default)]
+    row_count: Vec<RowCountCheck>,
+    #[serde(default)]
+    constraint: Vec<ConstraintCheck>,
+    #[serde(default)]
+    query: Vec<QueryCheck>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RowCountCheck {
+    table: String,
+    #[serde(rename = "where")]
+    filter: Option<String>,
+    equals: Option<i64>,
+    min: Option<i64>,
+    max: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConstraintCheck {
+    table: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QueryCheck {
+    name: Option<String>,
+    sql: String,
+    equals: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml_assertions() {
+        let assertions = parse_toml_assertions(
+            r#"
+            [[row_count]]
+            table = "users"
+            where = "email IS NULL"
+            equals = 0
+
+            [[constraint]]
+            table = "users"
+            name = "users_email_key"
+
+            [[query]]
+            name = "admin kept"
+            sql = "SELECT role FROM users WHERE id = 1"
+            equals = "admin"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(assertions.len(), 3);
+        assert_eq!(
+            assertions[0].sql,
+            "SELECT COUNT(*) FROM users WHERE email IS NULL"
+        );
+        assert!(assertions[0].check(Some("0")).is_ok());
+        assert_eq!(
+            assertions[0].check(Some("3")).unwrap_err(),
+            "row count of users WHERE email IS NULL: expected 0, got 3"
+        );
+        assert!(
+            assertions[1]
+                .sql
+                .contains("constraint_name = 'users_email_key'")
+        );
+        assert!(assertions[1].check(Some("1")).is_ok());
+        assert!(assertions[1].check(Some("0")).is_err());
+        assert_eq!(assertions[2].name, "admin kept");
+        assert!(assertions[2].check(Some("admin")).is_ok());
+        assert!(assertions[2].check(None).is_err());
+
+        assert!(parse_toml_assertions("[[row_count]]\ntable = \"t\"\nequal = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_sql_assertions_are_truthy() {
+        let assertions = parse_sql_assertions(
+            "-- every user has an email\n\
+             SELECT COUNT(*) = 0 FROM users WHERE email IS NULL;\n\
+             SELECT 1 FROM pg_indexes WHERE indexname = 'users_email_idx';",
+        );
+
+        assert_eq!(assertions.len(), 2);
+        assert_eq!(
+            assertions[0].name,
+            "SELECT COUNT(*) = 0 FROM users WHERE email IS NULL"
+        );
+        assert!(assertions[0].check(Some("t")).is_ok());
+        assert!(assertions[0].check(Some("true")).is_ok());
+        assert!(assertions[0].check(Some("f")).is_err());
+        assert!(assertions[1].check(Some("1")).is_ok());
+        assert!(assertions[1].check(None).is_err());
+    }
+}
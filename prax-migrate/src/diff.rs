@@ -1,15 +1,19 @@
 //! Schema diffing for generating migrations.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use prax_schema::Schema;
-use prax_schema::ast::{Field, IndexType, Model, VectorOps, View};
+use prax_schema::ast::{
+    Deferrable, Field, FieldType, Index, IndexType, Model, Tree, TreeStrategy, VectorOps, View,
+};
 
 use crate::error::MigrateResult;
 
 /// A diff between two schemas.
 #[derive(Debug, Clone, Default)]
 pub struct SchemaDiff {
+    /// Database schemas (namespaces) to create, from `@@schema`.
+    pub create_schemas: Vec<String>,
     /// PostgreSQL extensions to create.
     pub create_extensions: Vec<ExtensionDiff>,
     /// PostgreSQL extensions to drop.
@@ -26,6 +30,9 @@ pub struct SchemaDiff {
     pub drop_enums: Vec<String>,
     /// Enums to alter.
     pub alter_enums: Vec<EnumAlterDiff>,
+    /// Every enum in the target schema, for dialects that inline enum
+    /// values into column types.
+    pub enums: Vec<EnumDiff>,
     /// Views to create.
     pub create_views: Vec<ViewDiff>,
     /// Views to drop.
@@ -52,7 +59,8 @@ pub struct ExtensionDiff {
 impl SchemaDiff {
     /// Check if there are any differences.
     pub fn is_empty(&self) -> bool {
-        self.create_extensions.is_empty()
+        self.create_schemas.is_empty()
+            && self.create_extensions.is_empty()
             && self.drop_extensions.is_empty()
             && self.create_models.is_empty()
             && self.drop_models.is_empty()
@@ -71,6 +79,9 @@ impl SchemaDiff {
     pub fn summary(&self) -> String {
         let mut parts = Vec::new();
 
+        if !self.create_schemas.is_empty() {
+            parts.push(format!("Create {} schemas", self.create_schemas.len()));
+        }
         if !self.create_extensions.is_empty() {
             parts.push(format!(
                 "Create {} extensions",
@@ -126,6 +137,8 @@ pub struct ModelDiff {
     pub name: String,
     /// Table name.
     pub table_name: String,
+    /// Database schema (namespace) holding the table (`@@schema`).
+    pub schema: Option<String>,
     /// Fields to create.
     pub fields: Vec<FieldDiff>,
     /// Primary key columns.
@@ -134,6 +147,29 @@ pub struct ModelDiff {
     pub indexes: Vec<IndexDiff>,
     /// Unique constraints.
     pub unique_constraints: Vec<UniqueConstraint>,
+    /// Exclusion constraints (PostgreSQL).
+    pub exclusion_constraints: Vec<ExclusionConstraint>,
+    /// History table recording row versions (`@@versioned`).
+    pub history_table: Option<String>,
+    /// Hierarchy storage (`@@tree`).
+    pub tree: Option<TreeDiff>,
+    /// Translation table of `@translatable` fields.
+    pub translation: Option<TranslationDiff>,
+}
+
+impl ModelDiff {
+    /// Get the column names of the primary key.
+    pub fn primary_key_columns(&self) -> Vec<&str> {
+        self.primary_key
+            .iter()
+            .map(|name| {
+                self.fields
+                    .iter()
+                    .find(|f| &f.name == name)
+                    .map_or(name.as_str(), |f| f.column_name.as_str())
+            })
+            .collect()
+    }
 }
 
 /// Diff for altering a model.
@@ -143,6 +179,8 @@ pub struct ModelAlterDiff {
     pub name: String,
     /// Table name.
     pub table_name: String,
+    /// Database schema (namespace) holding the table (`@@schema`).
+    pub schema: Option<String>,
     /// Fields to add.
     pub add_fields: Vec<FieldDiff>,
     /// Fields to drop.
@@ -153,6 +191,102 @@ pub struct ModelAlterDiff {
     pub add_indexes: Vec<IndexDiff>,
     /// Indexes to drop.
     pub drop_indexes: Vec<String>,
+    /// Unique constraints to add.
+    pub add_unique_constraints: Vec<UniqueConstraint>,
+    /// Unique constraints to drop, by name.
+    pub drop_unique_constraints: Vec<String>,
+    /// Exclusion constraints to add.
+    pub add_exclusion_constraints: Vec<ExclusionConstraint>,
+    /// Exclusion constraints to drop, by name.
+    pub drop_exclusion_constraints: Vec<String>,
+    /// Full definition of the altered model, for dialects that rebuild
+    /// tables instead of altering them in place.
+    pub target: Option<ModelDiff>,
+    /// Changes to the history table (`@@versioned`).
+    pub history: Vec<HistoryDiff>,
+    /// Changes to the hierarchy storage (`@@tree`).
+    pub tree: Vec<TreeChange>,
+    /// Changes to the translation table (`@translatable`).
+    pub translation: Vec<TranslationChange>,
+}
+
+/// Change to the history table of a `@@versioned` model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryDiff {
+    /// Start recording row versions into a new history table.
+    Create(String),
+    /// Stop recording row versions and drop the history table.
+    Drop(String),
+}
+
+/// Hierarchy storage of a `@@tree` model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeDiff {
+    /// How the hierarchy is stored.
+    pub strategy: TreeStrategy,
+    /// Primary key column.
+    pub key_column: String,
+    /// Column holding the parent's key.
+    pub parent_column: String,
+    /// Closure table, for `closure_table` trees.
+    pub closure_table: Option<String>,
+}
+
+impl TreeDiff {
+    /// Get the name of the trigger maintaining the tree on insert.
+    pub fn trigger_name(&self, table: &str) -> String {
+        match &self.closure_table {
+            Some(closure) => format!("{}_insert", closure),
+            None => format!("{}_path_insert", table),
+        }
+    }
+
+    /// Get the materialized path column, added to the model's table.
+    pub fn path_field() -> FieldDiff {
+        FieldDiff {
+            name: Tree::PATH_COLUMN.to_string(),
+            column_name: Tree::PATH_COLUMN.to_string(),
+            sql_type: "VARCHAR(768)".to_string(),
+            nullable: false,
+            default: Some("'/'".to_string()),
+            is_primary_key: false,
+            is_auto_increment: false,
+            is_unique: false,
+        }
+    }
+}
+
+/// Change to the hierarchy storage of a `@@tree` model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeChange {
+    /// Create the storage and fill it from the parent column.
+    Create(TreeDiff),
+    /// Drop the storage.
+    Drop(TreeDiff),
+}
+
+/// Translation table of a model with `@translatable` fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationDiff {
+    /// Translation table name.
+    pub table_name: String,
+    /// Primary key column, shared with the model's table.
+    pub key_column: String,
+    /// Translated columns.
+    pub columns: Vec<String>,
+}
+
+/// Change to the translation table of a model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslationChange {
+    /// Create the translation table.
+    Create(TranslationDiff),
+    /// Drop the translation table.
+    Drop(TranslationDiff),
+    /// Add a translated column.
+    AddColumn(String),
+    /// Drop a translated column.
+    DropColumn(String),
 }
 
 /// Diff for a field.
@@ -195,6 +329,9 @@ pub struct FieldAlterDiff {
     pub old_default: Option<String>,
     /// New default (if changed).
     pub new_default: Option<String>,
+    /// The column after the change, for dialects that can only restate a
+    /// whole column definition.
+    pub column: FieldDiff,
 }
 
 /// Diff for an enum.
@@ -215,17 +352,33 @@ pub struct EnumAlterDiff {
     pub add_values: Vec<String>,
     /// Values to remove.
     pub remove_values: Vec<String>,
+    /// Columns of existing tables typed with the enum.
+    pub columns: Vec<EnumColumnDiff>,
 }
 
-/// Index diff.
+/// A column typed with an enum.
 #[derive(Debug, Clone)]
+pub struct EnumColumnDiff {
+    /// Table name.
+    pub table_name: String,
+    /// The column.
+    pub field: FieldDiff,
+}
+
+/// Index diff.
+#[derive(Debug, Clone, PartialEq)]
 pub struct IndexDiff {
     /// Index name.
     pub name: String,
     /// Table name.
     pub table_name: String,
+    /// Database schema (namespace) holding the table.
+    pub schema: Option<String>,
     /// Columns in the index.
     pub columns: Vec<String>,
+    /// Operator class per column (PostgreSQL); `None` uses the column
+    /// type's default.
+    pub column_ops: Vec<Option<String>>,
     /// Whether this is a unique index.
     pub unique: bool,
     /// Index type (btree, hash, hnsw, ivfflat, etc.).
@@ -250,7 +403,9 @@ impl IndexDiff {
         Self {
             name: name.into(),
             table_name: table_name.into(),
+            schema: None,
             columns,
+            column_ops: Vec::new(),
             unique: false,
             index_type: None,
             vector_ops: None,
@@ -272,6 +427,17 @@ impl IndexDiff {
         self
     }
 
+    /// Set the operator class of each column.
+    pub fn with_column_ops(mut self, ops: Vec<Option<String>>) -> Self {
+        self.column_ops = ops;
+        self
+    }
+
+    /// Operator class of the column at `position`, if one was given.
+    pub fn ops_for(&self, position: usize) -> Option<&str> {
+        self.column_ops.get(position)?.as_deref()
+    }
+
     /// Set vector options.
     pub fn with_vector_ops(mut self, ops: VectorOps) -> Self {
         self.vector_ops = Some(ops);
@@ -305,12 +471,42 @@ impl IndexDiff {
 }
 
 /// Unique constraint.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UniqueConstraint {
     /// Constraint name.
     pub name: Option<String>,
     /// Columns.
     pub columns: Vec<String>,
+    /// Whether NULLs conflict with each other (PostgreSQL 15+).
+    pub nulls_not_distinct: bool,
+    /// When the constraint is checked (PostgreSQL).
+    pub deferrable: Option<Deferrable>,
+}
+
+impl UniqueConstraint {
+    /// Create a unique constraint on `columns`.
+    pub fn new(name: Option<String>, columns: Vec<String>) -> Self {
+        Self {
+            name,
+            columns,
+            nulls_not_distinct: false,
+            deferrable: None,
+        }
+    }
+}
+
+/// Exclusion constraint (PostgreSQL).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExclusionConstraint {
+    /// Constraint name.
+    pub name: String,
+    /// Index method enforcing the constraint.
+    pub using: IndexType,
+    /// Columns, each with the operator comparing it.
+    pub elements: Vec<(String, String)>,
+    /// Whether the `btree_gist` extension is needed, for a GiST constraint
+    /// comparing plain scalar columns such as `room WITH =`.
+    pub requires_btree_gist: bool,
 }
 
 /// Diff for creating or altering a view.
@@ -370,6 +566,22 @@ impl SchemaDiffer {
     pub fn diff(&self) -> MigrateResult<SchemaDiff> {
         let mut result = SchemaDiff::default();
 
+        // Schemas must exist before the tables placed in them
+        let source_schemas: HashSet<&str> = self
+            .source
+            .iter()
+            .flat_map(|s| s.models.values())
+            .filter_map(|m| m.schema_name())
+            .collect();
+        for model in self.target.models.values() {
+            if let Some(schema) = model.schema_name()
+                && !source_schemas.contains(schema)
+                && !result.create_schemas.iter().any(|s| s == schema)
+            {
+                result.create_schemas.push(schema.to_string());
+            }
+        }
+
         let source_models: HashMap<&str, &Model> = self
             .source
             .as_ref()
@@ -413,16 +625,68 @@ impl SchemaDiffer {
             self.target.enums.values().map(|e| (e.name(), e)).collect();
 
         for (name, enum_def) in &target_enums {
-            if !source_enums.contains_key(name) {
+            let values: Vec<String> = enum_def
+                .variants
+                .iter()
+                .map(|v| v.name.to_string())
+                .collect();
+            result.enums.push(EnumDiff {
+                name: (*name).to_string(),
+                values: values.clone(),
+            });
+
+            let Some(source_enum) = source_enums.get(name) else {
                 result.create_enums.push(EnumDiff {
                     name: (*name).to_string(),
-                    values: enum_def
-                        .variants
-                        .iter()
-                        .map(|v| v.name.to_string())
-                        .collect(),
+                    values,
                 });
+                continue;
+            };
+            let source_values: Vec<String> = source_enum
+                .variants
+                .iter()
+                .map(|v| v.name.to_string())
+                .collect();
+            if source_values == values {
+                continue;
             }
+
+            // Columns of tables that already exist, which keep their type
+            let columns = target_models
+                .iter()
+                .filter(|(model, _)| source_models.contains_key(*model))
+                .flat_map(|(_, model)| {
+                    model
+                        .fields
+                        .values()
+                        .filter(|f| {
+                            matches!(
+                                &f.field_type,
+                                FieldType::Enum(n)
+                                    | FieldType::Model(n) if n == name
+                            )
+                        })
+                        .map(field_to_diff)
+                        .map(|field| EnumColumnDiff {
+                            table_name: model.table_name().to_string(),
+                            field,
+                        })
+                })
+                .collect();
+            result.alter_enums.push(EnumAlterDiff {
+                name: (*name).to_string(),
+                add_values: values
+                    .iter()
+                    .filter(|v| !source_values.contains(v))
+                    .cloned()
+                    .collect(),
+                remove_values: source_values
+                    .iter()
+                    .filter(|v| !values.contains(v))
+                    .cloned()
+                    .collect(),
+                columns,
+            });
         }
 
         for name in source_enums.keys() {
@@ -483,11 +747,36 @@ impl SchemaDiffer {
 
 /// Convert a model to a diff for creation.
 fn model_to_diff(model: &Model) -> ModelDiff {
-    let fields: Vec<FieldDiff> = model
+    let mut fields: Vec<FieldDiff> = model
         .fields
         .values()
-        .filter(|f| !f.is_relation())
-        .map(field_to_diff)
+        .filter(|f| !f.is_relation() && !f.is_virtual())
+        .flat_map(field_to_diffs)
+        .collect();
+
+    let translation = translation_to_diff(model, &fields);
+    let tree = tree_to_diff(model, &fields);
+    if tree
+        .as_ref()
+        .is_some_and(|t| t.strategy == TreeStrategy::MaterializedPath)
+    {
+        fields.push(TreeDiff::path_field());
+    }
+
+    let indexes = model
+        .indexes()
+        .iter()
+        .map(|index| index_to_diff(model, &fields, index))
+        .collect();
+    let unique_constraints = model
+        .unique_constraints()
+        .iter()
+        .map(|unique| unique_to_diff(model, &fields, unique))
+        .collect();
+    let exclusion_constraints = model
+        .exclusion_constraints()
+        .iter()
+        .map(|exclusion| exclusion_to_diff(model, &fields, exclusion))
         .collect();
 
     let primary_key: Vec<String> = model
@@ -500,16 +789,219 @@ fn model_to_diff(model: &Model) -> ModelDiff {
     ModelDiff {
         name: model.name().to_string(),
         table_name: model.table_name().to_string(),
+        schema: model.schema_name().map(str::to_string),
         fields,
         primary_key,
-        indexes: Vec::new(),
-        unique_constraints: Vec::new(),
+        indexes,
+        unique_constraints,
+        exclusion_constraints,
+        history_table: model.history_table_name(),
+        translation,
+        tree,
     }
 }
 
+/// Convert an `@@index` of a model to a diff.
+///
+/// Unnamed indexes are named `{table}_{columns}_idx`.
+fn index_to_diff(model: &Model, fields: &[FieldDiff], index: &Index) -> IndexDiff {
+    let table_name = model.table_name();
+    let columns: Vec<String> = index
+        .fields
+        .iter()
+        .map(|f| {
+            fields
+                .iter()
+                .find(|field| field.name == f.name.as_str())
+                .map_or_else(|| f.name.to_string(), |field| field.column_name.clone())
+        })
+        .collect();
+    let name = index.name.as_ref().map_or_else(
+        || format!("{}_{}_idx", table_name, columns.join("_")),
+        ToString::to_string,
+    );
+
+    let mut diff = IndexDiff::new(name, table_name, columns);
+    diff.schema = model.schema_name().map(str::to_string);
+    diff.index_type = index.index_type;
+    // B-trees can't answer spatial queries, so spatial columns default to GiST
+    let spatial = diff.columns.iter().any(|column| {
+        fields
+            .iter()
+            .any(|field| field.column_name == *column && is_spatial_sql_type(&field.sql_type))
+    });
+    if diff.index_type.is_none() && spatial {
+        diff.index_type = Some(IndexType::Gist);
+    }
+    diff.vector_ops = index.vector_ops;
+    diff.hnsw_m = index.hnsw_m;
+    diff.hnsw_ef_construction = index.hnsw_ef_construction;
+    diff.ivfflat_lists = index.ivfflat_lists;
+    if index.fields.iter().any(|f| f.ops.is_some()) {
+        diff.column_ops = index
+            .fields
+            .iter()
+            .map(|f| f.ops.as_ref().map(ToString::to_string))
+            .collect();
+    }
+    diff
+}
+
+/// Convert an `@@unique` to a unique constraint, named
+/// `{table}_{columns}_key` unless it sets a name.
+fn unique_to_diff(model: &Model, fields: &[FieldDiff], unique: &Index) -> UniqueConstraint {
+    let index = index_to_diff(model, fields, unique);
+    let name = unique.name.as_ref().map_or_else(
+        || format!("{}_{}_key", index.table_name, index.columns.join("_")),
+        ToString::to_string,
+    );
+
+    let mut constraint = UniqueConstraint::new(Some(name), index.columns);
+    constraint.nulls_not_distinct = unique.nulls_not_distinct;
+    constraint.deferrable = unique.deferrable;
+    constraint
+}
+
+/// Convert an `@@exclude` to an exclusion constraint, named
+/// `{table}_{columns}_excl` unless it sets a name.
+fn exclusion_to_diff(
+    model: &Model,
+    fields: &[FieldDiff],
+    exclusion: &prax_schema::ast::ExclusionConstraint,
+) -> ExclusionConstraint {
+    let elements: Vec<(&FieldDiff, String)> = exclusion
+        .elements
+        .iter()
+        .filter_map(|e| {
+            let field = fields.iter().find(|f| f.name == e.field.as_str())?;
+            Some((field, e.operator.to_string()))
+        })
+        .collect();
+    let columns: Vec<&str> = elements
+        .iter()
+        .map(|(f, _)| f.column_name.as_str())
+        .collect();
+    let name = exclusion.name.as_ref().map_or_else(
+        || format!("{}_{}_excl", model.table_name(), columns.join("_")),
+        ToString::to_string,
+    );
+
+    // GiST has operator classes for range types only; scalars need btree_gist
+    let requires_btree_gist = exclusion.using == IndexType::Gist
+        && elements
+            .iter()
+            .any(|(f, _)| !f.sql_type.to_uppercase().ends_with("RANGE"));
+
+    ExclusionConstraint {
+        name,
+        using: exclusion.using,
+        elements: elements
+            .into_iter()
+            .map(|(f, op)| (f.column_name.clone(), op))
+            .collect(),
+        requires_btree_gist,
+    }
+}
+
+/// Diff the `@@exclude` constraints of two versions of a model.
+///
+/// A constraint whose definition changed is dropped and added again.
+fn diff_exclusion_constraints(
+    source: &[ExclusionConstraint],
+    target: &[ExclusionConstraint],
+) -> (Vec<ExclusionConstraint>, Vec<String>) {
+    let add = target
+        .iter()
+        .filter(|exclusion| !source.contains(exclusion))
+        .cloned()
+        .collect();
+    let drop = source
+        .iter()
+        .filter(|exclusion| !target.contains(exclusion))
+        .map(|exclusion| exclusion.name.clone())
+        .collect();
+    (add, drop)
+}
+
+/// Diff the `@@unique` constraints of two versions of a model.
+///
+/// A constraint whose definition changed is dropped and added again.
+fn diff_unique_constraints(
+    source: &[UniqueConstraint],
+    target: &[UniqueConstraint],
+) -> (Vec<UniqueConstraint>, Vec<String>) {
+    let add = target
+        .iter()
+        .filter(|unique| !source.contains(unique))
+        .cloned()
+        .collect();
+    let drop = source
+        .iter()
+        .filter(|unique| !target.contains(unique))
+        .filter_map(|unique| unique.name.clone())
+        .collect();
+    (add, drop)
+}
+
+/// Diff the `@@index`es of two versions of a model.
+///
+/// An index whose definition changed is dropped and created again.
+fn diff_indexes(source: &[IndexDiff], target: &[IndexDiff]) -> (Vec<IndexDiff>, Vec<String>) {
+    let add = target
+        .iter()
+        .filter(|index| !source.contains(index))
+        .cloned()
+        .collect();
+    let drop = source
+        .iter()
+        .filter(|index| !target.contains(index))
+        .map(|index| index.name.clone())
+        .collect();
+    (add, drop)
+}
+
+/// Convert the `@translatable` fields of a model to a diff.
+fn translation_to_diff(model: &Model, fields: &[FieldDiff]) -> Option<TranslationDiff> {
+    let table_name = model.translation_table_name()?;
+    let column = |name: &str| {
+        fields
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| f.column_name.clone())
+    };
+
+    Some(TranslationDiff {
+        table_name,
+        key_column: column(model.id_fields().first()?.name())?,
+        columns: model
+            .translatable_fields()
+            .iter()
+            .filter_map(|f| column(f.name()))
+            .collect(),
+    })
+}
+
+/// Convert the `@@tree` of a model to a diff.
+fn tree_to_diff(model: &Model, fields: &[FieldDiff]) -> Option<TreeDiff> {
+    let tree = model.tree()?;
+    let column = |name: &str| {
+        fields
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| f.column_name.clone())
+    };
+
+    Some(TreeDiff {
+        strategy: tree.strategy,
+        key_column: column(model.id_fields().first()?.name())?,
+        parent_column: column(&tree.parent)?,
+        closure_table: tree.closure_table_name(model.table_name()),
+    })
+}
+
 /// Convert a field to a diff.
 fn field_to_diff(field: &Field) -> FieldDiff {
-    let sql_type = field_type_to_sql(&field.field_type);
+    let sql_type = field_sql_type(field);
     let nullable = field.is_optional();
     let is_primary_key = field.has_attribute("id");
     let is_auto_increment = field.has_attribute("auto");
@@ -540,6 +1032,45 @@ fn field_to_diff(field: &Field) -> FieldDiff {
     }
 }
 
+/// Convert a field to the diffs of its columns. A `Money` field has two:
+/// `<column>_amount` and `<column>_currency`.
+fn field_to_diffs(field: &Field) -> Vec<FieldDiff> {
+    let diff = field_to_diff(field);
+    if !is_money(field) {
+        return vec![diff];
+    }
+
+    [("amount", "DECIMAL(19, 4)"), ("currency", "CHAR(3)")]
+        .map(|(part, sql_type)| FieldDiff {
+            name: format!("{}_{}", diff.name, part),
+            column_name: format!("{}_{}", diff.column_name, part),
+            sql_type: sql_type.to_string(),
+            ..diff.clone()
+        })
+        .to_vec()
+}
+
+fn is_money(field: &Field) -> bool {
+    matches!(
+        field.field_type,
+        prax_schema::ast::FieldType::Scalar(prax_schema::ast::ScalarType::Money)
+    )
+}
+
+/// Get the SQL type of a field, including the shape and SRID of a spatial
+/// field, such as `GEOMETRY(Point, 4326)`.
+fn field_sql_type(field: &Field) -> String {
+    match (&field.field_type, field.spatial_type()) {
+        (prax_schema::ast::FieldType::Scalar(scalar), Some(spatial)) => spatial.column_type(scalar),
+        (field_type, _) => field_type_to_sql(field_type),
+    }
+}
+
+/// Check if a column type is a PostGIS type.
+pub(crate) fn is_spatial_sql_type(sql_type: &str) -> bool {
+    sql_type.starts_with("GEOMETRY") || sql_type.starts_with("GEOGRAPHY")
+}
+
 /// Convert a field type to SQL.
 fn field_type_to_sql(field_type: &prax_schema::ast::FieldType) -> String {
     use prax_schema::ast::{FieldType, ScalarType};
@@ -562,6 +1093,15 @@ fn field_type_to_sql(field_type: &prax_schema::ast::FieldType) -> String {
             ScalarType::Cuid | ScalarType::Cuid2 | ScalarType::NanoId | ScalarType::Ulid => {
                 "TEXT".to_string()
             }
+            ScalarType::IntRange => "INT4RANGE".to_string(),
+            ScalarType::BigIntRange => "INT8RANGE".to_string(),
+            ScalarType::DateTimeRange => "TSTZRANGE".to_string(),
+            ScalarType::Inet => "INET".to_string(),
+            ScalarType::Cidr => "CIDR".to_string(),
+            ScalarType::MacAddr => "MACADDR".to_string(),
+            ScalarType::Geometry => "GEOMETRY".to_string(),
+            ScalarType::Geography => "GEOGRAPHY".to_string(),
+            ScalarType::Money => "DECIMAL(19, 4)".to_string(),
             // PostgreSQL vector extension types
             ScalarType::Vector(dim) => match dim {
                 Some(d) => format!("vector({})", d),
@@ -592,14 +1132,14 @@ fn diff_models(source: &Model, target: &Model) -> Option<ModelAlterDiff> {
     let source_fields: HashMap<&str, &Field> = source
         .fields
         .values()
-        .filter(|f| !f.is_relation())
+        .filter(|f| !f.is_relation() && !f.is_virtual())
         .map(|f| (f.name(), f))
         .collect();
 
     let target_fields: HashMap<&str, &Field> = target
         .fields
         .values()
-        .filter(|f| !f.is_relation())
+        .filter(|f| !f.is_relation() && !f.is_virtual())
         .map(|f| (f.name(), f))
         .collect();
 
@@ -610,14 +1150,14 @@ fn diff_models(source: &Model, target: &Model) -> Option<ModelAlterDiff> {
     // Find fields to add
     for (name, field) in &target_fields {
         if !source_fields.contains_key(name) {
-            add_fields.push(field_to_diff(field));
+            add_fields.extend(field_to_diffs(field));
         }
     }
 
     // Find fields to drop
-    for name in source_fields.keys() {
+    for (name, field) in &source_fields {
         if !target_fields.contains_key(name) {
-            drop_fields.push((*name).to_string());
+            drop_fields.extend(field_to_diffs(field).into_iter().map(|f| f.name));
         }
     }
 
@@ -626,25 +1166,139 @@ fn diff_models(source: &Model, target: &Model) -> Option<ModelAlterDiff> {
         if let Some(source_field) = source_fields.get(name)
             && let Some(alter) = diff_fields(source_field, target_field)
         {
-            alter_fields.push(alter);
+            match (is_money(source_field), is_money(target_field)) {
+                (false, false) => alter_fields.push(alter),
+                // Only nullability differs, on both columns
+                (true, true) => {
+                    alter_fields.extend(field_to_diffs(target_field).into_iter().map(|column| {
+                        FieldAlterDiff {
+                            name: column.name.clone(),
+                            column_name: column.column_name.clone(),
+                            column,
+                            ..alter.clone()
+                        }
+                    }))
+                }
+                // A different number of columns
+                _ => {
+                    drop_fields.extend(field_to_diffs(source_field).into_iter().map(|f| f.name));
+                    add_fields.extend(field_to_diffs(target_field));
+                }
+            }
+        }
+    }
+
+    let mut history = Vec::new();
+    let source_history = source.history_table_name();
+    let target_history = target.history_table_name();
+    if source_history != target_history {
+        history.extend(source_history.map(HistoryDiff::Drop));
+        history.extend(target_history.map(HistoryDiff::Create));
+    }
+
+    let source_tree = model_to_diff(source).tree;
+    let target_diff = model_to_diff(target);
+    let mut tree = Vec::new();
+    if source_tree != target_diff.tree {
+        let is_path = |t: &Option<TreeDiff>| {
+            t.as_ref()
+                .is_some_and(|t| t.strategy == TreeStrategy::MaterializedPath)
+        };
+        match (is_path(&source_tree), is_path(&target_diff.tree)) {
+            (true, false) => drop_fields.push(Tree::PATH_COLUMN.to_string()),
+            (false, true) => add_fields.push(TreeDiff::path_field()),
+            _ => {}
         }
+        tree.extend(source_tree.map(TreeChange::Drop));
+        tree.extend(target_diff.tree.clone().map(TreeChange::Create));
     }
 
-    if add_fields.is_empty() && drop_fields.is_empty() && alter_fields.is_empty() {
+    let translation = diff_translations(
+        model_to_diff(source).translation,
+        target_diff.translation.clone(),
+    );
+
+    let source_diff = model_to_diff(source);
+    let (add_indexes, drop_indexes) = diff_indexes(&source_diff.indexes, &target_diff.indexes);
+    let (add_unique_constraints, drop_unique_constraints) = diff_unique_constraints(
+        &source_diff.unique_constraints,
+        &target_diff.unique_constraints,
+    );
+    let (add_exclusion_constraints, drop_exclusion_constraints) = diff_exclusion_constraints(
+        &source_diff.exclusion_constraints,
+        &target_diff.exclusion_constraints,
+    );
+
+    if add_fields.is_empty()
+        && drop_fields.is_empty()
+        && alter_fields.is_empty()
+        && history.is_empty()
+        && tree.is_empty()
+        && translation.is_empty()
+        && add_indexes.is_empty()
+        && drop_indexes.is_empty()
+        && add_unique_constraints.is_empty()
+        && drop_unique_constraints.is_empty()
+        && add_exclusion_constraints.is_empty()
+        && drop_exclusion_constraints.is_empty()
+    {
         None
     } else {
         Some(ModelAlterDiff {
             name: target.name().to_string(),
             table_name: target.table_name().to_string(),
+            schema: target.schema_name().map(str::to_string),
             add_fields,
             drop_fields,
             alter_fields,
-            add_indexes: Vec::new(),
-            drop_indexes: Vec::new(),
+            add_indexes,
+            drop_indexes,
+            add_unique_constraints,
+            drop_unique_constraints,
+            add_exclusion_constraints,
+            drop_exclusion_constraints,
+            target: Some(target_diff),
+            history,
+            tree,
+            translation,
         })
     }
 }
 
+/// Diff the translation tables of two versions of a model.
+///
+/// Translated columns are added and dropped in place; a renamed table or a
+/// changed key recreates the table.
+fn diff_translations(
+    source: Option<TranslationDiff>,
+    target: Option<TranslationDiff>,
+) -> Vec<TranslationChange> {
+    match (source, target) {
+        (Some(source), Some(target))
+            if source.table_name == target.table_name && source.key_column == target.key_column =>
+        {
+            let added = target
+                .columns
+                .iter()
+                .filter(|c| !source.columns.contains(c))
+                .cloned()
+                .map(TranslationChange::AddColumn);
+            let dropped = source
+                .columns
+                .iter()
+                .filter(|c| !target.columns.contains(c))
+                .cloned()
+                .map(TranslationChange::DropColumn);
+            added.chain(dropped).collect()
+        }
+        (source, target) => source
+            .map(TranslationChange::Drop)
+            .into_iter()
+            .chain(target.map(TranslationChange::Create))
+            .collect(),
+    }
+}
+
 /// Convert a view to a diff for creation.
 fn view_to_diff(view: &View) -> Option<ViewDiff> {
     // Views require a @@sql attribute to be migrated
@@ -682,8 +1336,8 @@ fn view_to_diff(view: &View) -> Option<ViewDiff> {
 
 /// Diff two fields and return alterations if any.
 fn diff_fields(source: &Field, target: &Field) -> Option<FieldAlterDiff> {
-    let source_type = field_type_to_sql(&source.field_type);
-    let target_type = field_type_to_sql(&target.field_type);
+    let source_type = field_sql_type(source);
+    let target_type = field_sql_type(target);
 
     let source_nullable = source.is_optional();
     let target_nullable = target.is_optional();
@@ -728,6 +1382,7 @@ fn diff_fields(source: &Field, target: &Field) -> Option<FieldAlterDiff> {
         },
         old_default: None,
         new_default: None,
+        column: field_to_diff(target),
     })
 }
 
@@ -747,10 +1402,15 @@ mod tests {
         diff.create_models.push(ModelDiff {
             name: "User".to_string(),
             table_name: "users".to_string(),
+            schema: None,
             fields: Vec::new(),
             primary_key: Vec::new(),
             indexes: Vec::new(),
             unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            tree: None,
+            translation: None,
         });
 
         let summary = diff.summary();
@@ -829,4 +1489,395 @@ mod tests {
         assert_eq!(view_diff.fields[0].name, "id");
         assert_eq!(view_diff.fields[1].column_name, "user_name");
     }
+
+    #[test]
+    fn test_diff_versioned_model() {
+        let source = prax_schema::parse_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                email String
+                @@map("users")
+            }
+        "#,
+        )
+        .unwrap();
+        let target = prax_schema::parse_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                email String
+                @@map("users")
+                @@versioned
+            }
+        "#,
+        )
+        .unwrap();
+
+        let diff = SchemaDiffer::new(target.clone()).diff().unwrap();
+        assert_eq!(
+            diff.create_models[0].history_table.as_deref(),
+            Some("users_history")
+        );
+
+        let diff = SchemaDiffer::new(target.clone())
+            .with_source(source.clone())
+            .diff()
+            .unwrap();
+        assert_eq!(
+            diff.alter_models[0].history,
+            vec![HistoryDiff::Create("users_history".to_string())]
+        );
+
+        let diff = SchemaDiffer::new(source)
+            .with_source(target)
+            .diff()
+            .unwrap();
+        assert_eq!(
+            diff.alter_models[0].history,
+            vec![HistoryDiff::Drop("users_history".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_diff_enum_values() {
+        let schema = |values: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                enum Role {{
+                    {}
+                }}
+
+                model User {{
+                    id   Int  @id @auto
+                    role Role
+                    @@map("users")
+                }}
+            "#,
+                values
+            ))
+            .unwrap()
+        };
+
+        let diff = SchemaDiffer::new(schema("USER ADMIN OWNER"))
+            .with_source(schema("USER ADMIN"))
+            .diff()
+            .unwrap();
+        assert!(diff.create_enums.is_empty());
+        assert_eq!(diff.enums[0].values, ["USER", "ADMIN", "OWNER"]);
+        let alter = &diff.alter_enums[0];
+        assert_eq!(alter.add_values, ["OWNER"]);
+        assert!(alter.remove_values.is_empty());
+        assert_eq!(alter.columns.len(), 1);
+        assert_eq!(alter.columns[0].table_name, "users");
+        assert_eq!(alter.columns[0].field.column_name, "role");
+
+        let diff = SchemaDiffer::new(schema("USER ADMIN"))
+            .with_source(schema("USER ADMIN"))
+            .diff()
+            .unwrap();
+        assert!(diff.alter_enums.is_empty());
+        assert_eq!(diff.enums.len(), 1);
+    }
+
+    #[test]
+    fn test_diff_model_indexes() {
+        let schema = |index: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model Post {{
+                    id    Int    @id @auto
+                    title String @map("post_title")
+                    data  Json
+                    @@map("posts")
+                    {}
+                }}
+            "#,
+                index
+            ))
+            .unwrap()
+        };
+
+        let target = schema("@@index([data], type: Gin, ops: JsonbPathOps)");
+        let diff = SchemaDiffer::new(target.clone()).diff().unwrap();
+        assert_eq!(
+            diff.create_models[0].indexes,
+            vec![
+                IndexDiff::new("posts_data_idx", "posts", vec!["data".to_string()])
+                    .with_type(IndexType::Gin)
+                    .with_column_ops(vec![Some("jsonb_path_ops".to_string())])
+            ]
+        );
+
+        let source =
+            schema("@@index([title], type: Gin, ops: gin_trgm_ops, name: \"posts_search\")");
+        let diff = SchemaDiffer::new(target)
+            .with_source(source)
+            .diff()
+            .unwrap();
+        let alter = &diff.alter_models[0];
+        assert_eq!(alter.drop_indexes, vec!["posts_search".to_string()]);
+        assert_eq!(alter.add_indexes[0].name, "posts_data_idx");
+
+        let unchanged = schema("@@index([title], type: Gist, ops: gist_trgm_ops)");
+        let diff = SchemaDiffer::new(unchanged.clone())
+            .with_source(unchanged)
+            .diff()
+            .unwrap();
+        assert!(diff.alter_models.is_empty());
+
+        let diff = SchemaDiffer::new(schema("@@index([title], type: Gist, ops: gist_trgm_ops)"))
+            .diff()
+            .unwrap();
+        assert_eq!(diff.create_models[0].indexes[0].columns, vec!["post_title"]);
+    }
+
+    #[test]
+    fn test_diff_model_unique_constraints() {
+        let schema = |unique: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model Seat {{
+                    id        Int       @id @auto
+                    email     String
+                    deletedAt DateTime? @map("deleted_at")
+                    @@map("seats")
+                    {}
+                }}
+            "#,
+                unique
+            ))
+            .unwrap()
+        };
+
+        let target = schema("@@unique([email, deletedAt], nullsNotDistinct: true)");
+        let diff = SchemaDiffer::new(target.clone()).diff().unwrap();
+        let mut expected = UniqueConstraint::new(
+            Some("seats_email_deleted_at_key".to_string()),
+            vec!["email".to_string(), "deleted_at".to_string()],
+        );
+        expected.nulls_not_distinct = true;
+        assert_eq!(diff.create_models[0].unique_constraints, vec![expected]);
+
+        let source = schema("@@unique([email, deletedAt])");
+        let diff = SchemaDiffer::new(target)
+            .with_source(source)
+            .diff()
+            .unwrap();
+        let alter = &diff.alter_models[0];
+        assert_eq!(
+            alter.drop_unique_constraints,
+            vec!["seats_email_deleted_at_key".to_string()]
+        );
+        assert!(alter.add_unique_constraints[0].nulls_not_distinct);
+    }
+
+    #[test]
+    fn test_diff_model_schemas() {
+        let target = prax_schema::parse_schema(
+            r#"
+            model Invoice {
+                id Int @id @auto
+                @@schema("accounting")
+                @@map("invoices")
+                @@index([id])
+            }
+
+            model Ledger {
+                id Int @id @auto
+                @@schema("accounting")
+            }
+
+            model User {
+                id Int @id @auto
+            }
+        "#,
+        )
+        .unwrap();
+
+        let diff = SchemaDiffer::new(target.clone()).diff().unwrap();
+        assert_eq!(diff.create_schemas, vec!["accounting".to_string()]);
+        let invoice = diff
+            .create_models
+            .iter()
+            .find(|m| m.name == "Invoice")
+            .unwrap();
+        assert_eq!(invoice.schema.as_deref(), Some("accounting"));
+        assert_eq!(invoice.table_name, "invoices");
+        assert_eq!(invoice.indexes[0].schema.as_deref(), Some("accounting"));
+
+        let diff = SchemaDiffer::new(target.clone())
+            .with_source(target)
+            .diff()
+            .unwrap();
+        assert!(diff.create_schemas.is_empty());
+    }
+
+    #[test]
+    fn test_diff_model_exclusion_constraints() {
+        let schema = |exclude: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model Booking {{
+                    id     Int           @id @auto
+                    roomId Int           @map("room_id")
+                    during DateTimeRange
+                    @@map("bookings")
+                    {}
+                }}
+            "#,
+                exclude
+            ))
+            .unwrap()
+        };
+
+        let target = schema(r#"@@exclude([roomId, during], using: Gist, with: ["=", "&&"])"#);
+        let diff = SchemaDiffer::new(target.clone()).diff().unwrap();
+        let model = &diff.create_models[0];
+        let during = model.fields.iter().find(|f| f.name == "during").unwrap();
+        assert_eq!(during.sql_type, "TSTZRANGE");
+        assert_eq!(
+            model.exclusion_constraints,
+            vec![ExclusionConstraint {
+                name: "bookings_room_id_during_excl".to_string(),
+                using: IndexType::Gist,
+                elements: vec![
+                    ("room_id".to_string(), "=".to_string()),
+                    ("during".to_string(), "&&".to_string()),
+                ],
+                requires_btree_gist: true,
+            }]
+        );
+
+        let source = schema(r#"@@exclude([during], with: "&&")"#);
+        let diff = SchemaDiffer::new(target)
+            .with_source(source)
+            .diff()
+            .unwrap();
+        let alter = &diff.alter_models[0];
+        assert_eq!(
+            alter.drop_exclusion_constraints,
+            vec!["bookings_during_excl".to_string()]
+        );
+        assert_eq!(alter.add_exclusion_constraints.len(), 1);
+    }
+
+    #[test]
+    fn test_diff_tree_model() {
+        let schema = |tree: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model Category {{
+                    id       Int     @id @auto
+                    name     String
+                    parentId Int?    @map("parent_id")
+                    @@map("categories")
+                    {}
+                }}
+            "#,
+                tree
+            ))
+            .unwrap()
+        };
+        let plain = schema("");
+        let closure = schema("@@tree(strategy: closure_table)");
+        let path = schema("@@tree(strategy: materialized_path)");
+
+        let diff = SchemaDiffer::new(closure.clone()).diff().unwrap();
+        let tree = diff.create_models[0].tree.as_ref().unwrap();
+        assert_eq!(tree.key_column, "id");
+        assert_eq!(tree.parent_column, "parent_id");
+        assert_eq!(tree.closure_table.as_deref(), Some("categories_closure"));
+
+        let diff = SchemaDiffer::new(path.clone()).diff().unwrap();
+        assert!(diff.create_models[0].fields.iter().any(|f| f.column_name == "path"));
+
+        let diff = SchemaDiffer::new(path.clone())
+            .with_source(plain)
+            .diff()
+            .unwrap();
+        let alter = &diff.alter_models[0];
+        assert_eq!(alter.add_fields[0].column_name, "path");
+        assert!(matches!(alter.tree.as_slice(), [TreeChange::Create(_)]));
+
+        let diff = SchemaDiffer::new(closure)
+            .with_source(path)
+            .diff()
+            .unwrap();
+        let alter = &diff.alter_models[0];
+        assert_eq!(alter.drop_fields, vec!["path".to_string()]);
+        assert!(matches!(
+            alter.tree.as_slice(),
+            [TreeChange::Drop(_), TreeChange::Create(_)]
+        ));
+    }
+
+    #[test]
+    fn test_diff_virtual_field() {
+        let schema = |total: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model OrderLine {{
+                    id        Int @id @auto
+                    quantity  Int
+                    unitPrice Int
+                    total     Int {}
+                }}
+            "#,
+                total
+            ))
+            .unwrap()
+        };
+        let computed = schema("@virtual(expr: \"quantity * unitPrice\")");
+
+        // Virtual fields have no column
+        let diff = SchemaDiffer::new(computed.clone()).diff().unwrap();
+        assert!(
+            !diff.create_models[0]
+                .fields
+                .iter()
+                .any(|f| f.name == "total")
+        );
+
+        // Making a stored field virtual drops its column
+        let diff = SchemaDiffer::new(computed)
+            .with_source(schema(""))
+            .diff()
+            .unwrap();
+        assert_eq!(diff.alter_models[0].drop_fields, vec!["total".to_string()]);
+    }
+
+    #[test]
+    fn test_diff_translatable_model() {
+        let schema = |body: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model Post {{
+                    id    Int     @id @auto
+                    title String  @translatable
+                    body  String? {}
+                }}
+            "#,
+                body
+            ))
+            .unwrap()
+        };
+        let plain = schema("");
+        let translated = schema("@translatable @map(\"content\")");
+
+        let diff = SchemaDiffer::new(translated.clone()).diff().unwrap();
+        let translation = diff.create_models[0].translation.as_ref().unwrap();
+        assert_eq!(translation.table_name, "PostTranslation");
+        assert_eq!(translation.key_column, "id");
+        assert_eq!(translation.columns, vec!["title", "content"]);
+
+        let diff = SchemaDiffer::new(translated)
+            .with_source(plain)
+            .diff()
+            .unwrap();
+        assert_eq!(
+            diff.alter_models[0].translation,
+            vec![TranslationChange::AddColumn("content".to_string())]
+        );
+    }
 }
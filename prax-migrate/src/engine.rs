@@ -1,14 +1,22 @@
 //! Migration engine implementation.
 
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
 
+use tokio::sync::mpsc;
+
+use crate::assertion::{Assertion, load_assertions};
 use crate::diff::{SchemaDiff, SchemaDiffer};
+use crate::environment::{DestructiveChange, EnvironmentPolicy, destructive_changes};
 use crate::error::{MigrateResult, MigrationError};
+use crate::executor::{MigrationExecutor, split_statements};
 use crate::file::{MigrationFile, MigrationFileManager};
 use crate::history::{MigrationHistoryRepository, MigrationRecord};
+use crate::progress::{CancelToken, MigrationEvent, ProgressCallback};
 use crate::resolution::{Resolution, ResolutionConfig};
-use crate::sql::{MigrationSql, PostgresSqlGenerator};
+use crate::signature::SignaturePolicy;
+use crate::sql::{MigrationSql, MigrationSqlGenerator, PostgresSqlGenerator};
 
 /// Configuration for the migration engine.
 #[derive(Debug, Clone)]
@@ -25,6 +33,10 @@ pub struct MigrationConfig {
     pub fail_on_checksum_mismatch: bool,
     /// Whether to apply baseline migrations automatically.
     pub auto_baseline: bool,
+    /// Signatures migrations must carry to be applied.
+    pub signatures: SignaturePolicy,
+    /// What migrations may be applied in the target environment.
+    pub environment: EnvironmentPolicy,
 }
 
 impl Default for MigrationConfig {
@@ -36,6 +48,8 @@ impl Default for MigrationConfig {
             allow_data_loss: false,
             fail_on_checksum_mismatch: true,
             auto_baseline: false,
+            signatures: SignaturePolicy::default(),
+            environment: EnvironmentPolicy::default(),
         }
     }
 }
@@ -81,6 +95,18 @@ impl MigrationConfig {
         self.auto_baseline = auto;
         self
     }
+
+    /// Set the signatures migrations must carry to be applied.
+    pub fn signatures(mut self, policy: SignaturePolicy) -> Self {
+        self.signatures = policy;
+        self
+    }
+
+    /// Set the policy of the environment migrations are applied in.
+    pub fn environment(mut self, policy: EnvironmentPolicy) -> Self {
+        self.environment = policy;
+        self
+    }
 }
 
 /// Result of a migration operation.
@@ -98,6 +124,8 @@ pub struct MigrationResult {
     pub skipped_migrations: Vec<String>,
     /// Warnings generated during migration.
     pub warnings: Vec<String>,
+    /// Whether applying stopped early because it was cancelled.
+    pub cancelled: bool,
 }
 
 impl MigrationResult {
@@ -127,6 +155,10 @@ impl MigrationResult {
             parts.push(format!("{} skipped", self.skipped_migrations.len()));
         }
 
+        if self.cancelled {
+            parts.push("cancelled".to_string());
+        }
+
         if parts.is_empty() {
             "No migrations applied".to_string()
         } else {
@@ -247,6 +279,39 @@ impl MigrationPlan {
             parts.join("; ")
         }
     }
+
+    /// The SQL that applying the pending migrations runs, each migration
+    /// headed by a comment with its ID.
+    pub fn script(&self) -> String {
+        let mut script = String::new();
+        for file in &self.pending {
+            let sql = file.up_sql.trim();
+            if !script.is_empty() {
+                script.push('\n');
+            }
+            script.push_str(&format!("-- Migration: {}\n{}", file.id, sql));
+            // Migrations run one at a time; terminate the last statement
+            // so the next migration's doesn't run into it
+            if !sql.is_empty() && !sql.ends_with(';') {
+                script.push(';');
+            }
+            script.push('\n');
+        }
+        script
+    }
+
+    /// The statements of pending migrations that may lose data, with the
+    /// ID of their migration.
+    pub fn destructive_changes(&self) -> Vec<(&str, DestructiveChange)> {
+        self.pending
+            .iter()
+            .flat_map(|file| {
+                destructive_changes(&file.up_sql)
+                    .into_iter()
+                    .map(|change| (file.id.as_str(), change))
+            })
+            .collect()
+    }
 }
 
 /// The main migration engine.
@@ -254,8 +319,11 @@ pub struct MigrationEngine<H: MigrationHistoryRepository> {
     config: MigrationConfig,
     history: H,
     file_manager: MigrationFileManager,
-    sql_generator: PostgresSqlGenerator,
+    sql_generator: Box<dyn MigrationSqlGenerator>,
     resolutions: ResolutionConfig,
+    executor: Option<Box<dyn MigrationExecutor>>,
+    observers: Vec<ProgressCallback>,
+    cancel: CancelToken,
 }
 
 impl<H: MigrationHistoryRepository> MigrationEngine<H> {
@@ -266,8 +334,11 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
             config,
             history,
             file_manager,
-            sql_generator: PostgresSqlGenerator,
+            sql_generator: Box::new(PostgresSqlGenerator),
             resolutions: ResolutionConfig::new(),
+            executor: None,
+            observers: Vec::new(),
+            cancel: CancelToken::new(),
         }
     }
 
@@ -282,11 +353,64 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
             config,
             history,
             file_manager,
-            sql_generator: PostgresSqlGenerator,
+            sql_generator: Box::new(PostgresSqlGenerator),
             resolutions,
+            executor: None,
+            observers: Vec::new(),
+            cancel: CancelToken::new(),
         }
     }
 
+    /// Use a different SQL generator, e.g. [`MySqlGenerator`](crate::MySqlGenerator).
+    ///
+    /// Migrations are generated for PostgreSQL by default.
+    pub fn with_sql_generator(mut self, generator: impl MigrationSqlGenerator + 'static) -> Self {
+        self.sql_generator = Box::new(generator);
+        self
+    }
+
+    /// Execute migrations with `executor`.
+    ///
+    /// Without one, migrations are validated and recorded but no SQL runs,
+    /// and their assertions aren't checked.
+    pub fn with_executor(mut self, executor: impl MigrationExecutor + 'static) -> Self {
+        self.executor = Some(Box::new(executor));
+        self
+    }
+
+    /// Call `callback` with each [`MigrationEvent`] while applying.
+    pub fn on_progress(
+        mut self,
+        callback: impl Fn(&MigrationEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.observers.push(Arc::new(callback));
+        self
+    }
+
+    /// Receive [`MigrationEvent`]s on a channel while applying.
+    pub fn progress_events(&mut self) -> mpsc::UnboundedReceiver<MigrationEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.observers.push(Arc::new(move |event: &MigrationEvent| {
+            let _ = tx.send(event.clone());
+        }));
+        rx
+    }
+
+    /// Stop applying when `token` is cancelled.
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// A token that cancels applying migrations.
+    ///
+    /// A migration stopped partway through is recorded as a partial-apply
+    /// resolution; applying it again resumes after the statements that
+    /// already ran.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
     /// Load resolutions from the configured file.
     pub async fn load_resolutions(&mut self) -> MigrateResult<()> {
         self.resolutions = ResolutionConfig::load(&self.config.resolutions_file).await?;
@@ -365,7 +489,7 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
                 plan.pending.push(file);
             } else if let Some(record) = applied.iter().find(|r| r.id == effective_id) {
                 // Check for checksum mismatch
-                if record.checksum != file.checksum {
+                if !file.checksum_matches(&record.checksum) {
                     if self
                         .resolutions
                         .accepts_checksum(&file.id, &record.checksum, &file.checksum)
@@ -443,6 +567,7 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
             baselined_migrations: Vec::new(),
             skipped_migrations: Vec::new(),
             warnings: Vec::new(),
+            cancelled: false,
         };
 
         let start = Instant::now();
@@ -458,6 +583,12 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
         let files = self.file_manager.list_migrations().await?;
 
         for file in files {
+            // Stop between migrations once cancelled
+            if self.cancel.is_cancelled() {
+                result.cancelled = true;
+                break;
+            }
+
             // Check if this migration should be skipped
             if self.resolutions.should_skip(&file.id) {
                 result.skipped_migrations.push(file.id.clone());
@@ -472,9 +603,25 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
                 .unwrap_or_else(|| file.id.clone());
 
             if applied_ids.contains(effective_id.as_str()) {
+                // A migration that failed its assertions blocks the rest
+                // until it is fixed by hand and force-applied
+                let failure = applied
+                    .iter()
+                    .find(|r| r.id == effective_id)
+                    .and_then(|r| r.failure.as_ref());
+                if let Some(failure) =
+                    failure.filter(|_| !self.resolutions.is_force_applied(&file.id))
+                {
+                    return Err(MigrationError::AssertionFailed {
+                        id: file.id.clone(),
+                        failures: vec![failure.clone()],
+                        rolled_back: false,
+                    });
+                }
+
                 // Check for unresolved checksum mismatch
                 if let Some(record) = applied.iter().find(|r| r.id == effective_id)
-                    && record.checksum != file.checksum
+                    && !file.checksum_matches(&record.checksum)
                     && !self.resolutions.accepts_checksum(
                         &file.id,
                         &record.checksum,
@@ -499,14 +646,31 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
                         .push(format!("[DRY RUN] Would baseline: {}", file.id));
                 } else {
                     // Record as applied without running
+                    let audit = self.config.environment.audit(None);
                     self.history
-                        .record_applied(&file.id, &file.checksum, 0)
+                        .record_applied(&file.id, &file.checksum, 0, None, Some(&audit))
                         .await?;
                     result.baselined_migrations.push(file.id.clone());
                 }
                 continue;
             }
 
+            // Reject unsigned or tampered migrations before running them
+            let signature = self.config.signatures.verify_migration(&file)?;
+
+            // Reject migrations the environment doesn't allow
+            let audit = self.config.environment.check(&file.id, &file.up_sql)?;
+
+            let mut assertions = load_assertions(&file.path).await?;
+            if self.executor.is_none() && !assertions.is_empty() {
+                result.warnings.push(format!(
+                    "{}: {} assertions not checked without an executor",
+                    file.id,
+                    assertions.len()
+                ));
+                assertions.clear();
+            }
+
             if self.config.dry_run {
                 result.applied_migrations.push(file.id.clone());
                 result
@@ -515,14 +679,51 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
                 continue;
             }
 
+            // Resume a migration that was cancelled partway through
+            let resume_at = match self.resolutions.partially_applied(&file.id) {
+                Some((checksum, applied)) if file.checksum_matches(checksum) => applied,
+                Some((checksum, _)) => {
+                    return Err(MigrationError::ChecksumMismatch {
+                        id: file.id.clone(),
+                        expected: checksum.to_string(),
+                        actual: file.checksum.clone(),
+                    });
+                }
+                None => 0,
+            };
+
             // Apply migration
             let migration_start = Instant::now();
-            self.apply_migration(&file).await?;
+            let applied = self.apply_migration(&file, resume_at, &assertions).await;
             let duration_ms = migration_start.elapsed().as_millis() as i64;
+            if let Err(MigrationError::AssertionFailed {
+                failures,
+                rolled_back: false,
+                ..
+            }) = &applied
+            {
+                // The changes stay, so the migration is recorded as failed
+                self.history
+                    .record_failed(&file.id, &file.checksum, duration_ms, &failures.join("\n"))
+                    .await?;
+            }
+            applied?;
+            if resume_at > 0 {
+                self.update_resolutions(|resolutions| {
+                    resolutions.remove(&file.id);
+                })
+                .await?;
+            }
 
             // Record in history
             self.history
-                .record_applied(&file.id, &file.checksum, duration_ms)
+                .record_applied(
+                    &file.id,
+                    &file.checksum,
+                    duration_ms,
+                    signature.as_ref(),
+                    Some(&audit),
+                )
                 .await?;
 
             result.applied_migrations.push(file.id);
@@ -533,13 +734,244 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
         Ok(result)
     }
 
-    /// Apply a single migration.
-    async fn apply_migration(&self, _migration: &MigrationFile) -> MigrateResult<()> {
-        // This would execute the SQL through the query engine
-        // For now, we just validate the structure
+    /// Apply a single migration, statement by statement, starting after the
+    /// `resume_at` statements an earlier attempt applied, then check its
+    /// assertions.
+    ///
+    /// With an executor that has transactional DDL, the migration runs in a
+    /// transaction that is rolled back if it fails or is cancelled.
+    /// `BEGIN` and `COMMIT` statements in the migration are then skipped.
+    /// `PRAGMA foreign_keys` has no effect inside a transaction, so a
+    /// migration turning foreign keys off (a SQLite table rebuild) has them
+    /// turned off before the transaction and back on after it.
+    ///
+    /// Without one, a transaction the migration opens itself is rolled back
+    /// if one of its statements fails.
+    async fn apply_migration(
+        &self,
+        migration: &MigrationFile,
+        resume_at: usize,
+        assertions: &[Assertion],
+    ) -> MigrateResult<()> {
+        let transactional = resume_at == 0
+            && self
+                .executor
+                .as_ref()
+                .is_some_and(|executor| executor.transactional_ddl());
+        let foreign_keys_off = transactional
+            && split_statements(&migration.up_sql)
+                .iter()
+                .any(|statement| normalize(statement) == "pragma foreign_keys = off");
+        let start = Instant::now();
+
+        if foreign_keys_off {
+            self.execute("PRAGMA foreign_keys = OFF").await?;
+        }
+        if transactional {
+            self.execute("BEGIN").await?;
+        }
+        let mut outcome = self
+            .run_statements(migration, resume_at, transactional)
+            .await;
+        if outcome.is_ok() {
+            outcome = self
+                .check_assertions(migration, assertions, transactional)
+                .await;
+        }
+        if transactional {
+            self.execute(if outcome.is_ok() {
+                "COMMIT"
+            } else {
+                "ROLLBACK"
+            })
+            .await?;
+        }
+        if foreign_keys_off {
+            self.execute("PRAGMA foreign_keys = ON").await?;
+        }
+        outcome?;
+
+        self.emit(MigrationEvent::Applied {
+            id: migration.id.clone(),
+            elapsed: start.elapsed(),
+        });
         Ok(())
     }
 
+    async fn run_statements(
+        &self,
+        migration: &MigrationFile,
+        resume_at: usize,
+        transactional: bool,
+    ) -> MigrateResult<()> {
+        let statements = split_statements(&migration.up_sql);
+        let total = statements.len();
+
+        self.emit(MigrationEvent::Started {
+            id: migration.id.clone(),
+            statements: total,
+            resumed_at: resume_at,
+        });
+
+        // Whether a transaction the migration opened itself (a SQLite table
+        // rebuild) is open; it is rolled back if a statement fails
+        let mut in_transaction = false;
+
+        for (index, statement) in statements.iter().enumerate().skip(resume_at) {
+            // The first statement of an attempt always runs, so cancelling
+            // leaves the migration partially applied, unless the
+            // transaction is rolled back. The migration's own transaction
+            // runs to its end.
+            if index > resume_at && !in_transaction && self.cancel.is_cancelled() {
+                let applied = if transactional { 0 } else { index };
+                if !transactional {
+                    self.update_resolutions(|resolutions| {
+                        resolutions.add(Resolution::partial_apply(
+                            &migration.id,
+                            &migration.checksum,
+                            index,
+                            total,
+                            format!("Cancelled after {} of {} statements", index, total),
+                        ));
+                    })
+                    .await?;
+                }
+                self.emit(MigrationEvent::Cancelled {
+                    id: migration.id.clone(),
+                    applied,
+                    total,
+                });
+                return Err(MigrationError::Cancelled {
+                    id: migration.id.clone(),
+                    applied,
+                    total,
+                });
+            }
+
+            let normalized = normalize(statement);
+            let opens = matches!(normalized.as_str(), "begin" | "begin transaction");
+            let closes = matches!(
+                normalized.as_str(),
+                "commit" | "commit transaction" | "end" | "end transaction"
+            );
+
+            let statement_start = Instant::now();
+            let rows_affected = if transactional && (opens || closes) {
+                // Already in the engine's transaction
+                0
+            } else {
+                match self.execute_statement(migration, statement).await {
+                    Ok(rows_affected) => rows_affected,
+                    Err(err) => {
+                        if in_transaction {
+                            self.execute("ROLLBACK").await?;
+                        }
+                        return Err(err);
+                    }
+                }
+            };
+            if !transactional && (opens || closes) {
+                in_transaction = opens;
+            }
+            self.emit(MigrationEvent::Statement {
+                id: migration.id.clone(),
+                index,
+                total,
+                rows_affected,
+                elapsed: statement_start.elapsed(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn check_assertions(
+        &self,
+        migration: &MigrationFile,
+        assertions: &[Assertion],
+        transactional: bool,
+    ) -> MigrateResult<()> {
+        let Some(executor) = &self.executor else {
+            return Ok(());
+        };
+
+        let mut failures = Vec::new();
+        for assertion in assertions {
+            let value = executor.query_value(&assertion.sql).await?;
+            let checked = assertion.check(value.as_deref());
+            self.emit(MigrationEvent::Assertion {
+                id: migration.id.clone(),
+                name: assertion.name.clone(),
+                passed: checked.is_ok(),
+            });
+            if let Err(failure) = checked {
+                failures.push(failure);
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(MigrationError::AssertionFailed {
+                id: migration.id.clone(),
+                failures,
+                rolled_back: transactional,
+            })
+        }
+    }
+
+    /// Execute a statement of a migration.
+    ///
+    /// `PRAGMA foreign_key_check` fails the migration if it reports any
+    /// violation, instead of returning it.
+    async fn execute_statement(
+        &self,
+        migration: &MigrationFile,
+        statement: &str,
+    ) -> MigrateResult<u64> {
+        let Some(executor) = &self.executor else {
+            return Ok(0);
+        };
+        if !normalize(statement).starts_with("pragma foreign_key_check") {
+            return executor.execute(statement).await;
+        }
+
+        // The first column of each violation is the table holding the row
+        match executor.query_value(statement).await? {
+            Some(table) => Err(MigrationError::ForeignKeyViolation {
+                id: migration.id.clone(),
+                table,
+            }),
+            None => Ok(0),
+        }
+    }
+
+    async fn execute(&self, sql: &str) -> MigrateResult<u64> {
+        match &self.executor {
+            Some(executor) => executor.execute(sql).await,
+            None => Ok(0),
+        }
+    }
+
+    fn emit(&self, event: MigrationEvent) {
+        for observer in &self.observers {
+            observer(&event);
+        }
+    }
+
+    /// Change the resolutions file.
+    ///
+    /// The file is re-read rather than overwritten with the resolutions in
+    /// memory, which may not have been loaded from it.
+    async fn update_resolutions(
+        &self,
+        update: impl FnOnce(&mut ResolutionConfig),
+    ) -> MigrateResult<()> {
+        let mut resolutions = ResolutionConfig::load(&self.config.resolutions_file).await?;
+        update(&mut resolutions);
+        resolutions.save(&self.config.resolutions_file).await
+    }
+
     /// Rollback the last migration.
     pub async fn rollback(&self) -> MigrateResult<Option<String>> {
         if self.config.dry_run {
@@ -565,6 +997,9 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
                     )));
                 }
 
+                // Rolling back is gated like applying the down migration
+                self.config.environment.check(&m.id, &m.down_sql)?;
+
                 // Execute down migration
                 self.rollback_migration(&m).await?;
 
@@ -579,8 +1014,10 @@ impl<H: MigrationHistoryRepository> MigrationEngine<H> {
     }
 
     /// Rollback a single migration.
-    async fn rollback_migration(&self, _migration: &MigrationFile) -> MigrateResult<()> {
-        // This would execute the down SQL through the query engine
+    async fn rollback_migration(&self, migration: &MigrationFile) -> MigrateResult<()> {
+        for statement in split_statements(&migration.down_sql) {
+            self.execute(&statement).await?;
+        }
         Ok(())
     }
 
@@ -650,9 +1087,26 @@ pub struct MigrationStatus {
     pub total_pending: usize,
 }
 
+/// A statement without leading comments, lowercased with single spaces.
+fn normalize(statement: &str) -> String {
+    let code: Vec<&str> = statement
+        .lines()
+        .skip_while(|line| {
+            let line = line.trim();
+            line.is_empty() || line.starts_with("--")
+        })
+        .collect();
+    code.join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::environment::DestructiveKind;
 
     #[test]
     fn test_config_default() {
@@ -707,6 +1161,35 @@ mod tests {
         assert!(plan.summary().contains("1 pending"));
     }
 
+    #[test]
+    fn test_migration_plan_script() {
+        let mut plan = MigrationPlan::empty();
+        for (id, sql) in [
+            ("001_users", "CREATE TABLE users (id INT);\n"),
+            ("002_drop_legacy", "ALTER TABLE users DROP COLUMN legacy"),
+        ] {
+            plan.pending.push(MigrationFile::new(
+                id,
+                id,
+                MigrationSql {
+                    up: sql.to_string(),
+                    down: String::new(),
+                },
+            ));
+        }
+
+        assert_eq!(
+            plan.script(),
+            "-- Migration: 001_users\nCREATE TABLE users (id INT);\n\n\
+             -- Migration: 002_drop_legacy\nALTER TABLE users DROP COLUMN legacy;\n"
+        );
+
+        let changes = plan.destructive_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, "002_drop_legacy");
+        assert_eq!(changes[0].1.kind, DestructiveKind::Drop);
+    }
+
     #[test]
     fn test_migration_plan_with_unresolved_checksum() {
         let mut plan = MigrationPlan::empty();
@@ -720,6 +1203,443 @@ mod tests {
         assert!(plan.summary().contains("UNRESOLVED"));
     }
 
+    #[derive(Default)]
+    struct MemoryHistory {
+        records: std::sync::Mutex<Vec<MigrationRecord>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationHistoryRepository for MemoryHistory {
+        async fn initialize(&self) -> MigrateResult<()> {
+            Ok(())
+        }
+
+        async fn get_applied(&self) -> MigrateResult<Vec<MigrationRecord>> {
+            Ok(self.records.lock().unwrap().clone())
+        }
+
+        async fn is_applied(&self, id: &str) -> MigrateResult<bool> {
+            Ok(self.records.lock().unwrap().iter().any(|r| r.id == id))
+        }
+
+        async fn record_applied(
+            &self,
+            id: &str,
+            checksum: &str,
+            duration_ms: i64,
+            signature: Option<&crate::signature::MigrationSignature>,
+            audit: Option<&crate::environment::MigrationAudit>,
+        ) -> MigrateResult<()> {
+            self.records.lock().unwrap().push(MigrationRecord {
+                id: id.to_string(),
+                checksum: checksum.to_string(),
+                applied_at: chrono::Utc::now(),
+                duration_ms,
+                rolled_back: false,
+                signature: signature.cloned(),
+                failure: None,
+                audit: audit.cloned(),
+            });
+            Ok(())
+        }
+
+        async fn record_failed(
+            &self,
+            id: &str,
+            checksum: &str,
+            duration_ms: i64,
+            diagnostics: &str,
+        ) -> MigrateResult<()> {
+            self.records.lock().unwrap().push(MigrationRecord {
+                id: id.to_string(),
+                checksum: checksum.to_string(),
+                applied_at: chrono::Utc::now(),
+                duration_ms,
+                rolled_back: false,
+                signature: None,
+                failure: Some(diagnostics.to_string()),
+                audit: None,
+            });
+            Ok(())
+        }
+
+        async fn record_rollback(&self, _id: &str) -> MigrateResult<()> {
+            Ok(())
+        }
+
+        async fn get_last_applied(&self) -> MigrateResult<Option<MigrationRecord>> {
+            Ok(self.records.lock().unwrap().last().cloned())
+        }
+
+        async fn acquire_lock(&self) -> MigrateResult<crate::history::MigrationLock> {
+            Ok(crate::history::MigrationLock::new(1, || {}))
+        }
+    }
+
+    /// Records statements, cancelling after the first one.
+    struct CancellingExecutor {
+        statements: Arc<std::sync::Mutex<Vec<String>>>,
+        cancel: Option<CancelToken>,
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationExecutor for CancellingExecutor {
+        async fn execute(&self, sql: &str) -> MigrateResult<u64> {
+            self.statements.lock().unwrap().push(sql.to_string());
+            if let Some(cancel) = &self.cancel {
+                cancel.cancel();
+            }
+            Ok(1)
+        }
+
+        async fn query_value(&self, _sql: &str) -> MigrateResult<Option<String>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_resume_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        let migration_dir = dir.path().join("20240101000000_backfill");
+        std::fs::create_dir_all(&migration_dir).unwrap();
+        std::fs::write(
+            migration_dir.join("up.sql"),
+            "UPDATE a SET x = 1;\nUPDATE b SET x = 1;\nUPDATE c SET x = 1;\n",
+        )
+        .unwrap();
+        let config = MigrationConfig::new()
+            .migrations_dir(dir.path())
+            .resolutions_file(dir.path().join("resolutions.toml"));
+
+        // The first attempt is cancelled after its first statement
+        let statements = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cancel = CancelToken::new();
+        let mut engine = MigrationEngine::new(config.clone(), MemoryHistory::default())
+            .with_executor(CancellingExecutor {
+                statements: statements.clone(),
+                cancel: Some(cancel.clone()),
+            })
+            .with_cancel_token(cancel);
+        let mut events = engine.progress_events();
+
+        let err = engine.migrate().await.unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::Cancelled {
+                applied: 1,
+                total: 3,
+                ..
+            }
+        ));
+        assert_eq!(*statements.lock().unwrap(), vec!["UPDATE a SET x = 1"]);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            MigrationEvent::Started { statements: 3, .. }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            MigrationEvent::Statement {
+                index: 0,
+                rows_affected: 1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            MigrationEvent::Cancelled { applied: 1, .. }
+        ));
+
+        // The next attempt resumes after it
+        statements.lock().unwrap().clear();
+        let mut engine = MigrationEngine::new(config.clone(), MemoryHistory::default())
+            .with_executor(CancellingExecutor {
+                statements: statements.clone(),
+                cancel: None,
+            });
+        engine.load_resolutions().await.unwrap();
+        assert_eq!(
+            engine
+                .resolutions()
+                .partially_applied("20240101000000")
+                .map(|(_, applied)| applied),
+            Some(1)
+        );
+
+        let result = engine.migrate().await.unwrap();
+        assert_eq!(result.applied_migrations, vec!["20240101000000"]);
+        assert_eq!(
+            *statements.lock().unwrap(),
+            vec!["UPDATE b SET x = 1", "UPDATE c SET x = 1"]
+        );
+        let resolutions = ResolutionConfig::load(dir.path().join("resolutions.toml"))
+            .await
+            .unwrap();
+        assert!(resolutions.partially_applied("20240101000000").is_none());
+    }
+
+    /// Records statements and answers every query with `value`.
+    struct ScriptedExecutor {
+        statements: Arc<std::sync::Mutex<Vec<String>>>,
+        value: &'static str,
+        transactional: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationExecutor for ScriptedExecutor {
+        async fn execute(&self, sql: &str) -> MigrateResult<u64> {
+            self.statements.lock().unwrap().push(sql.to_string());
+            Ok(0)
+        }
+
+        async fn query_value(&self, _sql: &str) -> MigrateResult<Option<String>> {
+            Ok(Some(self.value.to_string()))
+        }
+
+        fn transactional_ddl(&self) -> bool {
+            self.transactional
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_assertions() {
+        let dir = tempfile::tempdir().unwrap();
+        let migration_dir = dir.path().join("20240101000000_backfill");
+        std::fs::create_dir_all(&migration_dir).unwrap();
+        std::fs::write(migration_dir.join("up.sql"), "UPDATE users SET email = '';").unwrap();
+        std::fs::write(
+            migration_dir.join("assert.toml"),
+            "[[row_count]]\ntable = \"users\"\nwhere = \"email IS NULL\"\nequals = 0\n",
+        )
+        .unwrap();
+        let config = MigrationConfig::new()
+            .migrations_dir(dir.path())
+            .resolutions_file(dir.path().join("resolutions.toml"));
+        let statements = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        // Transactional DDL rolls the migration back
+        let engine = MigrationEngine::new(config.clone(), MemoryHistory::default()).with_executor(
+            ScriptedExecutor {
+                statements: statements.clone(),
+                value: "3",
+                transactional: true,
+            },
+        );
+        let err = engine.migrate().await.unwrap_err();
+        assert!(matches!(
+            &err,
+            MigrationError::AssertionFailed {
+                rolled_back: true,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("expected 0, got 3"));
+        assert_eq!(
+            *statements.lock().unwrap(),
+            vec!["BEGIN", "UPDATE users SET email = ''", "ROLLBACK"]
+        );
+        assert!(engine.status().await.unwrap().applied.is_empty());
+
+        // Otherwise the migration is recorded as failed, blocking the next run
+        let mut engine = MigrationEngine::new(config, MemoryHistory::default()).with_executor(
+            ScriptedExecutor {
+                statements: statements.clone(),
+                value: "3",
+                transactional: false,
+            },
+        );
+        assert!(matches!(
+            engine.migrate().await.unwrap_err(),
+            MigrationError::AssertionFailed {
+                rolled_back: false,
+                ..
+            }
+        ));
+        let applied = engine.status().await.unwrap().applied;
+        assert_eq!(
+            applied[0].failure.as_deref(),
+            Some("row count of users WHERE email IS NULL: expected 0, got 3")
+        );
+        assert!(engine.migrate().await.is_err());
+
+        engine
+            .resolutions_mut()
+            .add(Resolution::force_apply("20240101000000", "Fixed by hand"));
+        assert!(
+            engine
+                .migrate()
+                .await
+                .unwrap()
+                .applied_migrations
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_foreign_keys_around_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let migration_dir = dir.path().join("20240101000000_rebuild");
+        std::fs::create_dir_all(&migration_dir).unwrap();
+        std::fs::write(
+            migration_dir.join("up.sql"),
+            "PRAGMA foreign_keys = OFF;\nBEGIN TRANSACTION;\nDROP TABLE users;\nPRAGMA foreign_key_check;\nCOMMIT;\nPRAGMA foreign_keys = ON;",
+        )
+        .unwrap();
+        let config = MigrationConfig::new()
+            .migrations_dir(dir.path())
+            .resolutions_file(dir.path().join("resolutions.toml"));
+        let statements = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        // The check reports a violating row in `posts`
+        let engine = MigrationEngine::new(config, MemoryHistory::default()).with_executor(
+            ScriptedExecutor {
+                statements: statements.clone(),
+                value: "posts",
+                transactional: true,
+            },
+        );
+        let err = engine.migrate().await.unwrap_err();
+        assert!(matches!(
+            &err,
+            MigrationError::ForeignKeyViolation { table, .. } if table == "posts"
+        ));
+        assert_eq!(
+            *statements.lock().unwrap(),
+            vec![
+                "PRAGMA foreign_keys = OFF",
+                "BEGIN",
+                "PRAGMA foreign_keys = OFF",
+                "DROP TABLE users",
+                "ROLLBACK",
+                "PRAGMA foreign_keys = ON",
+            ]
+        );
+        assert!(engine.status().await.unwrap().applied.is_empty());
+    }
+
+    /// Runs statements on an in-memory SQLite database, without transactional
+    /// DDL.
+    struct SqliteExecutor(std::sync::Mutex<rusqlite::Connection>);
+
+    #[async_trait::async_trait]
+    impl MigrationExecutor for SqliteExecutor {
+        async fn execute(&self, sql: &str) -> MigrateResult<u64> {
+            let conn = self.0.lock().unwrap();
+            conn.execute_batch(sql)
+                .map_err(|e| MigrationError::database(e.to_string()))?;
+            Ok(conn.changes())
+        }
+
+        async fn query_value(&self, sql: &str) -> MigrateResult<Option<String>> {
+            let conn = self.0.lock().unwrap();
+            let mut stmt = conn
+                .prepare(sql)
+                .map_err(|e| MigrationError::database(e.to_string()))?;
+            let mut rows = stmt
+                .query([])
+                .map_err(|e| MigrationError::database(e.to_string()))?;
+            match rows.next().map_err(|e| MigrationError::database(e.to_string()))? {
+                Some(row) => Ok(row.get::<_, Option<String>>(0).ok().flatten()),
+                None => Ok(None),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_rebuild_keeps_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let migration_dir = dir.path().join("20240101000000_rebuild");
+        std::fs::create_dir_all(&migration_dir).unwrap();
+        // A rebuild dropping `name`, whose new unique index fails after the
+        // old table was dropped
+        std::fs::write(
+            migration_dir.join("up.sql"),
+            r#"PRAGMA foreign_keys = OFF;
+BEGIN TRANSACTION;
+CREATE TABLE "_prax_new_users" ("id" INTEGER PRIMARY KEY, "email" TEXT NOT NULL);
+INSERT INTO "_prax_new_users" ("id", "email") SELECT "id", "email" FROM "users";
+DROP TABLE "users";
+ALTER TABLE "_prax_new_users" RENAME TO "users";
+CREATE UNIQUE INDEX "users_email_key" ON "users" ("email");
+PRAGMA foreign_key_check;
+COMMIT;
+PRAGMA foreign_keys = ON;"#,
+        )
+        .unwrap();
+        let config = MigrationConfig::new()
+            .migrations_dir(dir.path())
+            .resolutions_file(dir.path().join("resolutions.toml"));
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL, name TEXT);
+             INSERT INTO users VALUES (1, 'a@example.com', 'A'), (2, 'a@example.com', 'B');",
+        )
+        .unwrap();
+        let engine = MigrationEngine::new(config, MemoryHistory::default())
+            .with_executor(SqliteExecutor(std::sync::Mutex::new(conn)));
+
+        let err = engine.migrate().await.unwrap_err();
+        assert!(matches!(err, MigrationError::Database(_)));
+        assert!(engine.status().await.unwrap().applied.is_empty());
+
+        let executor = engine.executor.as_ref().unwrap();
+        assert_eq!(
+            executor
+                .query_value("SELECT group_concat(name) FROM users")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("A,B")
+        );
+        assert_eq!(
+            executor
+                .query_value("SELECT name FROM sqlite_master WHERE name LIKE '_prax_new_%'")
+                .await
+                .unwrap(),
+            None
+        );
+        // Not left in a transaction
+        executor.execute("BEGIN").await.unwrap();
+        executor.execute("COMMIT").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_environment_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let migration_dir = dir.path().join("20240101000000_add_role");
+        std::fs::create_dir_all(&migration_dir).unwrap();
+        std::fs::write(
+            migration_dir.join("up.sql"),
+            "ALTER TABLE users ADD COLUMN role TEXT;",
+        )
+        .unwrap();
+        let config = MigrationConfig::new()
+            .migrations_dir(dir.path())
+            .resolutions_file(dir.path().join("resolutions.toml"));
+        let production = EnvironmentPolicy::new("production").applied_by("deploy-bot");
+
+        let engine = MigrationEngine::new(
+            config.clone().environment(production.clone()),
+            MemoryHistory::default(),
+        );
+        assert!(matches!(
+            engine.migrate().await.unwrap_err(),
+            MigrationError::NotApproved { .. }
+        ));
+        assert!(engine.status().await.unwrap().applied.is_empty());
+
+        let engine = MigrationEngine::new(
+            config.environment(production.approved_by("alice")),
+            MemoryHistory::default(),
+        );
+        assert_eq!(engine.migrate().await.unwrap().applied_count, 1);
+        let applied = engine.status().await.unwrap().applied;
+        let audit = applied[0].audit.as_ref().unwrap();
+        assert_eq!(audit.environment, "production");
+        assert_eq!(audit.applied_by, "deploy-bot");
+        assert_eq!(audit.approved_by.as_deref(), Some("alice"));
+    }
+
     #[test]
     fn test_migration_result_summary() {
         let result = MigrationResult {
@@ -729,6 +1649,7 @@ mod tests {
             baselined_migrations: vec!["b1".into()],
             skipped_migrations: vec!["s1".into(), "s2".into()],
             warnings: Vec::new(),
+            cancelled: false,
         };
 
         assert_eq!(result.total_processed(), 4);
@@ -0,0 +1,464 @@
+//! Per-environment migration policies.
+//!
+//! An [`EnvironmentPolicy`] decides which migrations may be applied in an
+//! environment. By default production is gated: every migration needs an
+//! approval, given for the run or listed in an approvals file, and
+//! migrations that drop or truncate data are refused. Other environments
+//! apply everything.
+//!
+//! Approvals files list the migrations that were approved, and by whom:
+//!
+//! ```toml
+//! [[approval]]
+//! migration = "20240101120000_add_user_roles"
+//! approved_by = "alice@example.com"
+//! ```
+//!
+//! Each migration applied is recorded with a [`MigrationAudit`] of who
+//! applied it, who approved it, and in which environment.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MigrateResult, MigrationError};
+use crate::executor::split_statements;
+
+/// The name of the production environment.
+pub const PRODUCTION: &str = "production";
+
+/// The environment variable naming who applies migrations, overriding the
+/// login name.
+pub const APPLIED_BY_VAR: &str = "PRAX_APPLIED_BY";
+
+/// An approval to apply a migration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Approval {
+    /// Migration ID.
+    pub migration: String,
+    /// Who approved it.
+    pub approved_by: String,
+}
+
+/// Who applied a migration, where, and on whose approval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationAudit {
+    /// The environment it was applied in.
+    pub environment: String,
+    /// Who applied it.
+    pub applied_by: String,
+    /// Who approved it, if it was approved.
+    pub approved_by: Option<String>,
+}
+
+/// What migrations may be applied in an environment.
+#[derive(Debug, Clone)]
+pub struct EnvironmentPolicy {
+    /// The environment's name.
+    pub environment: String,
+    /// Whether migrations must be approved to be applied.
+    pub require_approval: bool,
+    /// Whether migrations that drop or truncate data may be applied.
+    pub allow_destructive: bool,
+    /// Who approved this run's migrations, approving all of them.
+    pub approved_by: Option<String>,
+    /// Approvals of individual migrations.
+    pub approvals: Vec<Approval>,
+    /// Who applies the migrations.
+    pub applied_by: String,
+}
+
+impl Default for EnvironmentPolicy {
+    fn default() -> Self {
+        Self::new("development")
+    }
+}
+
+impl EnvironmentPolicy {
+    /// Create the default policy for an environment: gated for production,
+    /// open otherwise.
+    pub fn new(environment: impl Into<String>) -> Self {
+        let environment = environment.into();
+        let production = environment == PRODUCTION;
+        Self {
+            environment,
+            require_approval: production,
+            allow_destructive: !production,
+            approved_by: None,
+            approvals: Vec::new(),
+            applied_by: current_user(),
+        }
+    }
+
+    /// Set whether migrations must be approved.
+    pub fn require_approval(mut self, require: bool) -> Self {
+        self.require_approval = require;
+        self
+    }
+
+    /// Set whether destructive migrations may be applied.
+    pub fn allow_destructive(mut self, allow: bool) -> Self {
+        self.allow_destructive = allow;
+        self
+    }
+
+    /// Approve every migration in this run.
+    pub fn approved_by(mut self, approver: impl Into<String>) -> Self {
+        self.approved_by = Some(approver.into());
+        self
+    }
+
+    /// Add approvals of individual migrations.
+    pub fn approvals(mut self, approvals: impl IntoIterator<Item = Approval>) -> Self {
+        self.approvals.extend(approvals);
+        self
+    }
+
+    /// Set who applies the migrations.
+    pub fn applied_by(mut self, user: impl Into<String>) -> Self {
+        self.applied_by = user.into();
+        self
+    }
+
+    /// Who approved a migration, if anyone did.
+    pub fn approval_for(&self, id: &str) -> Option<&str> {
+        self.approved_by.as_deref().or_else(|| {
+            self.approvals
+                .iter()
+                .find(|approval| approval.migration == id)
+                .map(|approval| approval.approved_by.as_str())
+        })
+    }
+
+    /// Check that a migration may be applied, returning its audit record.
+    pub fn check(&self, id: &str, sql: &str) -> MigrateResult<MigrationAudit> {
+        if !self.allow_destructive {
+            let statements = destructive_statements(sql);
+            if !statements.is_empty() {
+                return Err(MigrationError::Destructive {
+                    id: id.to_string(),
+                    environment: self.environment.clone(),
+                    statements,
+                });
+            }
+        }
+
+        let approved_by = self.approval_for(id).map(String::from);
+        if self.require_approval && approved_by.is_none() {
+            return Err(MigrationError::NotApproved {
+                id: id.to_string(),
+                environment: self.environment.clone(),
+            });
+        }
+
+        Ok(self.audit(approved_by))
+    }
+
+    /// The audit record of a migration applied under this policy.
+    pub fn audit(&self, approved_by: Option<String>) -> MigrationAudit {
+        MigrationAudit {
+            environment: self.environment.clone(),
+            applied_by: self.applied_by.clone(),
+            approved_by,
+        }
+    }
+}
+
+/// Read an approvals file.
+pub fn load_approvals(path: &Path) -> MigrateResult<Vec<Approval>> {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct ApprovalsFile {
+        #[serde(default)]
+        approval: Vec<Approval>,
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    let file: ApprovalsFile = toml::from_str(&content)
+        .map_err(|e| MigrationError::InvalidMigration(format!("{}: {}", path.display(), e)))?;
+    Ok(file.approval)
+}
+
+/// The user running migrations: [`APPLIED_BY_VAR`] if set, else the login
+/// name.
+pub fn current_user() -> String {
+    [APPLIED_BY_VAR, "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|user| !user.is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// The statements in `sql` that drop or truncate data: dropping a table,
+/// schema, database or column, `TRUNCATE`, and `DELETE` without `WHERE`.
+pub fn destructive_statements(sql: &str) -> Vec<String> {
+    destructive_changes(sql)
+        .into_iter()
+        .filter(|change| change.kind != DestructiveKind::NarrowType)
+        .map(|change| change.statement)
+        .collect()
+}
+
+/// Why a statement may lose data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DestructiveKind {
+    /// Drops a table, schema, database or column.
+    Drop,
+    /// Truncates a table or deletes all its rows.
+    Truncate,
+    /// Changes a column to a type that may not hold its current values.
+    NarrowType,
+}
+
+impl DestructiveKind {
+    /// A short description.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Drop => "drops data",
+            Self::Truncate => "deletes all rows",
+            Self::NarrowType => "may narrow a column type",
+        }
+    }
+}
+
+/// A statement that may lose data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DestructiveChange {
+    /// Why it may lose data.
+    pub kind: DestructiveKind,
+    /// The statement.
+    pub statement: String,
+}
+
+/// The statements in `sql` that may lose data: those
+/// [`destructive_statements`] returns, and column type changes.
+///
+/// The statements don't say what type a column had, so a type change counts
+/// as narrowing unless the new type is one that holds any value of its
+/// family, such as `TEXT` or `BIGINT`.
+pub fn destructive_changes(sql: &str) -> Vec<DestructiveChange> {
+    split_statements(sql)
+        .into_iter()
+        .filter_map(|statement| {
+            classify(&statement).map(|kind| DestructiveChange { kind, statement })
+        })
+        .collect()
+}
+
+fn classify(statement: &str) -> Option<DestructiveKind> {
+    let words: Vec<String> = statement
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or_default())
+        .flat_map(str::split_whitespace)
+        .map(str::to_ascii_uppercase)
+        .collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+    match words.as_slice() {
+        ["DROP", "TABLE" | "SCHEMA" | "DATABASE", ..] => Some(DestructiveKind::Drop),
+        ["TRUNCATE", ..] => Some(DestructiveKind::Truncate),
+        ["DELETE", ..] if !words.contains(&"WHERE") => Some(DestructiveKind::Truncate),
+        ["ALTER", "TABLE", rest @ ..] => {
+            if drops_column(rest) {
+                Some(DestructiveKind::Drop)
+            } else if narrows_column(rest) {
+                Some(DestructiveKind::NarrowType)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn drops_column(words: &[&str]) -> bool {
+    words.windows(2).any(|pair| {
+        pair[0] == "DROP"
+            && !matches!(
+                pair[1],
+                "CONSTRAINT"
+                    | "INDEX"
+                    | "KEY"
+                    | "DEFAULT"
+                    | "NOT"
+                    | "PRIMARY"
+                    | "FOREIGN"
+                    | "CHECK"
+                    | "IDENTITY"
+                    | "EXPRESSION"
+            )
+    })
+}
+
+/// Whether an `ALTER TABLE` changes a column to a type that may narrow it:
+/// `ALTER COLUMN c TYPE t` (PostgreSQL), `MODIFY c t` and `CHANGE a b t`
+/// (MySQL), or `ALTER COLUMN c t` (SQL Server).
+fn narrows_column(words: &[&str]) -> bool {
+    let mut i = 0;
+    while i < words.len() {
+        let target = match &words[i..] {
+            ["ALTER", "COLUMN", _, "TYPE", ty, ..]
+            | ["ALTER", "COLUMN", _, "SET", "DATA", "TYPE", ty, ..]
+            | ["ALTER", _, "TYPE", ty, ..]
+            | ["ALTER", _, "SET", "DATA", "TYPE", ty, ..]
+            | ["MODIFY", "COLUMN", _, ty, ..]
+            | ["CHANGE", "COLUMN", _, _, ty, ..] => Some(*ty),
+            ["ALTER", "COLUMN", _, next, ..] if !matches!(*next, "SET" | "DROP" | "ADD") => {
+                Some(*next)
+            }
+            ["MODIFY", _, ty, ..] => Some(*ty),
+            ["CHANGE", _, _, ty, ..] => Some(*ty),
+            _ => None,
+        };
+        if target.is_some_and(|ty| !is_widest_type(ty)) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Whether a type holds any value of its family, so changing to it can't
+/// lose data.
+fn is_widest_type(ty: &str) -> bool {
+    let ty = ty.trim_end_matches([',', ';']);
+    matches!(
+        ty,
+        "TEXT"
+            | "LONGTEXT"
+            | "CLOB"
+            | "VARCHAR(MAX)"
+            | "NVARCHAR(MAX)"
+            | "BIGINT"
+            | "INT8"
+            | "NUMERIC"
+            | "DECIMAL"
+            | "DOUBLE"
+            | "FLOAT8"
+            | "JSON"
+            | "JSONB"
+            | "BYTEA"
+            | "BLOB"
+            | "LONGBLOB"
+            | "VARBINARY(MAX)"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destructive_statements() {
+        let sql = "-- drop the old table\n\
+                   DROP TABLE sessions;\n\
+                   CREATE TABLE logins (id INT);\n\
+                   ALTER TABLE users DROP COLUMN legacy_id;\n\
+                   ALTER TABLE users DROP CONSTRAINT users_email_key;\n\
+                   alter table users drop nickname;\n\
+                   DELETE FROM logins WHERE id < 10;\n\
+                   DELETE FROM logins;\n\
+                   TRUNCATE audit_log;";
+
+        assert_eq!(
+            destructive_statements(sql),
+            vec![
+                "-- drop the old table\nDROP TABLE sessions",
+                "ALTER TABLE users DROP COLUMN legacy_id",
+                "alter table users drop nickname",
+                "DELETE FROM logins",
+                "TRUNCATE audit_log",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_destructive_changes_narrowing() {
+        let sql = "ALTER TABLE users ALTER COLUMN email TYPE VARCHAR(50);\n\
+                   ALTER TABLE users ALTER COLUMN bio TYPE TEXT;\n\
+                   ALTER TABLE users ALTER COLUMN bio SET DEFAULT '';\n\
+                   ALTER TABLE users MODIFY COLUMN age SMALLINT;\n\
+                   ALTER TABLE users MODIFY views BIGINT NOT NULL;\n\
+                   ALTER TABLE users ALTER COLUMN score INT;\n\
+                   DROP TABLE sessions;";
+
+        let changes = destructive_changes(sql);
+        let kinds: Vec<_> = changes
+            .iter()
+            .map(|change| (change.kind, change.statement.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    DestructiveKind::NarrowType,
+                    "ALTER TABLE users ALTER COLUMN email TYPE VARCHAR(50)"
+                ),
+                (
+                    DestructiveKind::NarrowType,
+                    "ALTER TABLE users MODIFY COLUMN age SMALLINT"
+                ),
+                (
+                    DestructiveKind::NarrowType,
+                    "ALTER TABLE users ALTER COLUMN score INT"
+                ),
+                (DestructiveKind::Drop, "DROP TABLE sessions"),
+            ]
+        );
+
+        // The environment policy only refuses dropped data
+        assert_eq!(destructive_statements(sql), vec!["DROP TABLE sessions"]);
+    }
+
+    #[test]
+    fn test_production_requires_approval() {
+        let policy = EnvironmentPolicy::new(PRODUCTION).applied_by("deploy-bot");
+        let sql = "ALTER TABLE users ADD COLUMN role TEXT;";
+
+        assert!(matches!(
+            policy.check("20240101_add_role", sql),
+            Err(MigrationError::NotApproved { .. })
+        ));
+
+        let policy = policy.approvals([Approval {
+            migration: "20240101_add_role".to_string(),
+            approved_by: "alice".to_string(),
+        }]);
+        let audit = policy.check("20240101_add_role", sql).unwrap();
+        assert_eq!(audit.environment, PRODUCTION);
+        assert_eq!(audit.applied_by, "deploy-bot");
+        assert_eq!(audit.approved_by.as_deref(), Some("alice"));
+
+        // Approval doesn't unblock destructive migrations
+        let err = policy
+            .approved_by("bob")
+            .check("20240102_drop_sessions", "DROP TABLE sessions;")
+            .unwrap_err();
+        assert!(matches!(err, MigrationError::Destructive { .. }));
+
+        let staging = EnvironmentPolicy::new("staging");
+        assert!(
+            staging
+                .check("20240102_drop_sessions", "DROP TABLE sessions;")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_load_approvals() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("approvals.toml");
+        std::fs::write(
+            &path,
+            "[[approval]]\nmigration = \"20240101_add_role\"\napproved_by = \"alice\"\n",
+        )
+        .unwrap();
+
+        let approvals = load_approvals(&path).unwrap();
+        assert_eq!(approvals.len(), 1);
+        assert_eq!(approvals[0].approved_by, "alice");
+
+        std::fs::write(&path, "[[approvals]]\nmigration = \"x\"\n").unwrap();
+        assert!(load_approvals(&path).is_err());
+    }
+}
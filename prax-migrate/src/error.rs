@@ -35,6 +35,78 @@ pub enum MigrationError {
         actual: String,
     },
 
+    /// Migration file lacks a required signature.
+    #[error("Migration file '{0}' is not signed")]
+    Unsigned(String),
+
+    /// Migration signature failed verification.
+    #[error("Invalid signature '{path}': {reason}")]
+    InvalidSignature {
+        /// Path to the signature file.
+        path: String,
+        /// Why verification failed.
+        reason: String,
+    },
+
+    /// Applying was cancelled partway through a migration.
+    #[error("Migration '{id}' was cancelled after {applied} of {total} statements")]
+    Cancelled {
+        /// Migration ID.
+        id: String,
+        /// Statements that were applied.
+        applied: usize,
+        /// Number of statements in the migration.
+        total: usize,
+    },
+
+    /// Post-migration assertions failed.
+    #[error(
+        "Migration '{id}' failed its assertions ({}): {}",
+        if *.rolled_back { "rolled back" } else { "changes kept" },
+        .failures.join("; ")
+    )]
+    AssertionFailed {
+        /// Migration ID.
+        id: String,
+        /// Diagnostics for each failed assertion.
+        failures: Vec<String>,
+        /// Whether the migration's changes were rolled back.
+        rolled_back: bool,
+    },
+
+    /// The migration leaves rows violating foreign keys
+    /// (`PRAGMA foreign_key_check` on SQLite).
+    #[error("Migration '{id}' leaves rows in '{table}' violating foreign keys")]
+    ForeignKeyViolation {
+        /// Migration ID.
+        id: String,
+        /// Table with violating rows.
+        table: String,
+    },
+
+    /// The environment requires an approval the migration doesn't have.
+    #[error("Migration '{id}' must be approved to be applied in {environment}")]
+    NotApproved {
+        /// Migration ID.
+        id: String,
+        /// The environment.
+        environment: String,
+    },
+
+    /// The migration drops or truncates data, which the environment forbids.
+    #[error(
+        "Migration '{id}' is destructive, which {environment} doesn't allow: {}",
+        .statements.join("; ")
+    )]
+    Destructive {
+        /// Migration ID.
+        id: String,
+        /// The environment.
+        environment: String,
+        /// The destructive statements.
+        statements: Vec<String>,
+    },
+
     /// Migration already applied.
     #[error("Migration '{0}' has already been applied")]
     AlreadyApplied(String),
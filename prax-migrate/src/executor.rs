@@ -0,0 +1,189 @@
+//! Executing migration SQL.
+//!
+//! The engine runs migrations through a [`MigrationExecutor`], one
+//! statement at a time, so it can report progress and stop between
+//! statements. [`split_statements`] splits a migration file on `;`,
+//! skipping those inside quotes, comments, PostgreSQL dollar-quoted bodies
+//! and `BEGIN ... END` trigger bodies.
+
+use crate::error::MigrateResult;
+
+/// Executes migration statements against a database.
+#[async_trait::async_trait]
+pub trait MigrationExecutor: Send + Sync {
+    /// Execute a single statement, returning the number of rows it affected.
+    async fn execute(&self, sql: &str) -> MigrateResult<u64>;
+
+    /// Run a query, returning the first column of its first row as text;
+    /// `None` if it returned no rows or NULL.
+    async fn query_value(&self, sql: &str) -> MigrateResult<Option<String>>;
+
+    /// Whether schema changes can be rolled back, so each migration runs in
+    /// a transaction (`BEGIN`, then `COMMIT` or `ROLLBACK`).
+    ///
+    /// Executors for databases that can roll back DDL (PostgreSQL, SQLite)
+    /// should return true; MySQL commits DDL implicitly. Without it, only
+    /// the transactions a migration opens itself protect its statements.
+    fn transactional_ddl(&self) -> bool {
+        false
+    }
+}
+
+/// Split SQL into statements.
+///
+/// Statements are trimmed and keep their comments; text after the last `;`
+/// that is only whitespace or comments is dropped. The body of a
+/// `CREATE TRIGGER ... BEGIN ... END` (SQLite, MySQL) stays in one statement.
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut has_code = false;
+    // Whether the statement starts with CREATE, and creates a trigger
+    let mut first_word = true;
+    let mut create = false;
+    let mut trigger = false;
+    // Nesting of BEGIN/CASE ... END in a trigger
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                has_code = true;
+                i += 1;
+                while i < bytes.len() {
+                    if bytes[i] == quote {
+                        // A doubled quote is an escaped one
+                        if bytes.get(i + 1) == Some(&quote) {
+                            i += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map(|end| i + 2 + end + 1)
+                    .unwrap_or(bytes.len());
+            }
+            b'$' => {
+                has_code = true;
+                if let Some(tag) = dollar_quote_tag(&sql[i..]) {
+                    i = sql[i + tag.len()..]
+                        .find(tag)
+                        .map(|end| i + tag.len() + end + tag.len() - 1)
+                        .unwrap_or(bytes.len());
+                }
+            }
+            b';' if depth == 0 => {
+                if has_code {
+                    statements.push(sql[start..i].trim().to_string());
+                }
+                start = i + 1;
+                has_code = false;
+                first_word = true;
+                create = false;
+                trigger = false;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                has_code = true;
+                let end = sql[i..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+                    .map_or(bytes.len(), |len| i + len);
+                let word = &sql[i..end];
+                let is = |keyword: &str| word.eq_ignore_ascii_case(keyword);
+                if first_word {
+                    create = is("CREATE");
+                    first_word = false;
+                } else if create && !trigger {
+                    trigger = is("TRIGGER");
+                } else if trigger && (is("BEGIN") || is("CASE")) {
+                    depth += 1;
+                } else if trigger && is("END") {
+                    depth = depth.saturating_sub(1);
+                }
+                i = end - 1;
+            }
+            c if !c.is_ascii_whitespace() => has_code = true,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if has_code {
+        statements.push(sql[start..].trim().to_string());
+    }
+    statements
+}
+
+/// The `$tag$` opening a dollar-quoted string at the start of `sql`.
+fn dollar_quote_tag(sql: &str) -> Option<&str> {
+    let rest = &sql[1..];
+    let end = rest.find('$')?;
+    let tag = &rest[..end];
+    let valid = tag
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_ascii_alphabetic() || c == '_')
+        && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| &sql[..end + 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let sql = "-- Create users\n\
+                   CREATE TABLE users (id INT, name TEXT DEFAULT 'a;b');\n\
+                   INSERT INTO users VALUES (1, 'it''s; fine'); /* trailing; comment */\n\
+                   CREATE FUNCTION f() RETURNS trigger AS $body$ BEGIN RETURN NULL; END; $body$ LANGUAGE plpgsql;\n\
+                   SELECT $1, $$a;b$$\n\
+                   -- done;\n";
+
+        assert_eq!(
+            split_statements(sql),
+            vec![
+                "-- Create users\nCREATE TABLE users (id INT, name TEXT DEFAULT 'a;b')",
+                "INSERT INTO users VALUES (1, 'it''s; fine')",
+                "/* trailing; comment */\nCREATE FUNCTION f() RETURNS trigger AS $body$ BEGIN RETURN NULL; END; $body$ LANGUAGE plpgsql",
+                "SELECT $1, $$a;b$$\n-- done;",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_statements_trigger_body() {
+        let sql = "BEGIN;\n\
+                   CREATE TRIGGER t AFTER UPDATE ON users FOR EACH ROW BEGIN\n    \
+                   UPDATE log SET n = CASE WHEN n > 0 THEN n ELSE 0 END;\n    \
+                   INSERT INTO log VALUES (NEW.id);\n\
+                   END;\n\
+                   COMMIT;";
+
+        assert_eq!(
+            split_statements(sql),
+            vec![
+                "BEGIN",
+                "CREATE TRIGGER t AFTER UPDATE ON users FOR EACH ROW BEGIN\n    UPDATE log SET n = CASE WHEN n > 0 THEN n ELSE 0 END;\n    INSERT INTO log VALUES (NEW.id);\nEND",
+                "COMMIT",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_statements_empty() {
+        assert!(split_statements("").is_empty());
+        assert!(split_statements("  ;\n-- nothing here;\n").is_empty());
+    }
+}
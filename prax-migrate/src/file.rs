@@ -42,6 +42,15 @@ impl MigrationFile {
         }
     }
 
+    /// Whether a checksum recorded in the history matches this file.
+    ///
+    /// Records written before checksums were SHA-256 hold a shorter hash,
+    /// which is still accepted.
+    pub fn checksum_matches(&self, recorded: &str) -> bool {
+        recorded == self.checksum
+            || (recorded.len() == 16 && recorded == legacy_checksum(&self.up_sql))
+    }
+
     /// Set the path for this migration file.
     pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
         self.path = path.into();
@@ -50,7 +59,17 @@ impl MigrationFile {
 }
 
 /// Compute a checksum for migration content.
-fn compute_checksum(content: &str) -> String {
+///
+/// The checksum is the SHA-256 of the content, so it identifies the SQL
+/// itself and can be recomputed with standard tools (`sha256sum up.sql`).
+pub fn compute_checksum(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// The checksum recorded by versions before checksums were SHA-256.
+fn legacy_checksum(content: &str) -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -249,6 +268,26 @@ mod tests {
 
         assert_eq!(checksum1, checksum2);
         assert_ne!(checksum1, checksum3);
+        assert_eq!(
+            compute_checksum(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_checksum_matches_legacy() {
+        let file = MigrationFile::new(
+            "20231215120000",
+            "create_users",
+            MigrationSql {
+                up: "CREATE TABLE users();".to_string(),
+                down: String::new(),
+            },
+        );
+
+        assert!(file.checksum_matches(&file.checksum));
+        assert!(file.checksum_matches(&legacy_checksum(&file.up_sql)));
+        assert!(!file.checksum_matches(&legacy_checksum("DROP TABLE users;")));
     }
 
     #[test]
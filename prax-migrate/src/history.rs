@@ -3,14 +3,16 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::environment::MigrationAudit;
 use crate::error::MigrateResult;
+use crate::signature::MigrationSignature;
 
 /// A record of an applied migration.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MigrationRecord {
     /// Migration ID/name.
     pub id: String,
-    /// Checksum of the migration content.
+    /// SHA-256 checksum of the migration content.
     pub checksum: String,
     /// When the migration was applied.
     pub applied_at: DateTime<Utc>,
@@ -18,6 +20,16 @@ pub struct MigrationRecord {
     pub duration_ms: i64,
     /// Whether this migration was rolled back.
     pub rolled_back: bool,
+    /// The verified signature the migration was applied with, if signed.
+    #[serde(default)]
+    pub signature: Option<MigrationSignature>,
+    /// Diagnostics from failed post-migration assertions, if the migration
+    /// was applied but failed them.
+    #[serde(default)]
+    pub failure: Option<String>,
+    /// Who applied the migration, where, and on whose approval.
+    #[serde(default)]
+    pub audit: Option<MigrationAudit>,
 }
 
 /// Migration history repository.
@@ -32,9 +44,26 @@ pub trait MigrationHistoryRepository: Send + Sync {
     /// Check if a migration has been applied.
     async fn is_applied(&self, id: &str) -> MigrateResult<bool>;
 
-    /// Record a migration as applied.
-    async fn record_applied(&self, id: &str, checksum: &str, duration_ms: i64)
-    -> MigrateResult<()>;
+    /// Record a migration as applied, with its signature if it was signed
+    /// and the audit record of who applied it.
+    async fn record_applied(
+        &self,
+        id: &str,
+        checksum: &str,
+        duration_ms: i64,
+        signature: Option<&MigrationSignature>,
+        audit: Option<&MigrationAudit>,
+    ) -> MigrateResult<()>;
+
+    /// Record a migration whose changes were made but whose assertions
+    /// failed, with the failures as diagnostics.
+    async fn record_failed(
+        &self,
+        id: &str,
+        checksum: &str,
+        duration_ms: i64,
+        diagnostics: &str,
+    ) -> MigrateResult<()>;
 
     /// Mark a migration as rolled back.
     async fn record_rollback(&self, id: &str) -> MigrateResult<()>;
@@ -82,9 +111,24 @@ CREATE TABLE IF NOT EXISTS "_prax_migrations" (
     checksum VARCHAR(64) NOT NULL,
     applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
     duration_ms BIGINT NOT NULL DEFAULT 0,
-    rolled_back BOOLEAN NOT NULL DEFAULT FALSE
+    rolled_back BOOLEAN NOT NULL DEFAULT FALSE,
+    signature_kind VARCHAR(16),
+    signed_by TEXT,
+    signature TEXT,
+    failure TEXT,
+    environment VARCHAR(64),
+    applied_by TEXT,
+    approved_by TEXT
 );
 
+ALTER TABLE "_prax_migrations" ADD COLUMN IF NOT EXISTS signature_kind VARCHAR(16);
+ALTER TABLE "_prax_migrations" ADD COLUMN IF NOT EXISTS signed_by TEXT;
+ALTER TABLE "_prax_migrations" ADD COLUMN IF NOT EXISTS signature TEXT;
+ALTER TABLE "_prax_migrations" ADD COLUMN IF NOT EXISTS failure TEXT;
+ALTER TABLE "_prax_migrations" ADD COLUMN IF NOT EXISTS environment VARCHAR(64);
+ALTER TABLE "_prax_migrations" ADD COLUMN IF NOT EXISTS applied_by TEXT;
+ALTER TABLE "_prax_migrations" ADD COLUMN IF NOT EXISTS approved_by TEXT;
+
 CREATE INDEX IF NOT EXISTS "_prax_migrations_applied_at_idx"
     ON "_prax_migrations" (applied_at DESC);
 "#;
@@ -105,6 +149,9 @@ mod tests {
             applied_at: Utc::now(),
             duration_ms: 150,
             rolled_back: false,
+            signature: None,
+            failure: None,
+            audit: None,
         };
 
         assert!(!record.rolled_back);
@@ -115,5 +162,7 @@ mod tests {
     fn test_init_sql_has_table() {
         assert!(POSTGRES_INIT_SQL.contains("_prax_migrations"));
         assert!(POSTGRES_INIT_SQL.contains("checksum"));
+        assert!(POSTGRES_INIT_SQL.contains("ADD COLUMN IF NOT EXISTS signed_by"));
+        assert!(POSTGRES_INIT_SQL.contains("ADD COLUMN IF NOT EXISTS applied_by"));
     }
 }
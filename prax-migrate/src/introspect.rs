@@ -195,6 +195,8 @@ pub struct IndexInfo {
     pub is_primary: bool,
     /// Index method (btree, hash, etc.).
     pub index_method: String,
+    /// Operator class per column; `None` for the column type's default.
+    pub column_ops: Vec<Option<String>>,
 }
 
 /// Trait for database introspection.
@@ -386,9 +388,59 @@ impl SchemaBuilder {
             model.add_field(field);
         }
 
+        // Add @@index for secondary indexes; unique ones come from constraints
+        for index in self.indexes.get(&table.name).into_iter().flatten() {
+            if !index.is_primary && !index.is_unique {
+                model.attributes.push(self.build_index(index));
+            }
+        }
+
         Ok(model)
     }
 
+    /// Build an `@@index` attribute from index info.
+    fn build_index(&self, index: &IndexInfo) -> Attribute {
+        let span = Span::new(0, 0);
+        let mut args = vec![
+            AttributeArg::positional(
+                AttributeValue::FieldRefList(index.columns.iter().map(Into::into).collect()),
+                span,
+            ),
+            AttributeArg::named(
+                Ident::new("name", span),
+                AttributeValue::String(index.name.clone()),
+                span,
+            ),
+        ];
+
+        if index.index_method != "btree" {
+            args.push(AttributeArg::named(
+                Ident::new("type", span),
+                AttributeValue::Ident(to_pascal_case(&index.index_method).into()),
+                span,
+            ));
+        }
+
+        // `ops` names one operator class per column, so an index mixing
+        // default and explicit operator classes keeps only the method
+        let ops: Option<Vec<&str>> = index.column_ops.iter().map(|o| o.as_deref()).collect();
+        match ops.as_deref() {
+            Some([ops]) => args.push(AttributeArg::named(
+                Ident::new("ops", span),
+                AttributeValue::Ident((*ops).into()),
+                span,
+            )),
+            Some(ops) if !ops.is_empty() => args.push(AttributeArg::named(
+                Ident::new("ops", span),
+                AttributeValue::FieldRefList(ops.iter().map(|&o| o.into()).collect()),
+                span,
+            )),
+            _ => {}
+        }
+
+        Attribute::new(Ident::new("index", span), args, span)
+    }
+
     /// Build a field from column info.
     fn build_field(
         &self,
@@ -487,6 +539,14 @@ impl SchemaBuilder {
             "json" | "jsonb" => ScalarType::Json,
             "bytea" => ScalarType::Bytes,
             "uuid" => ScalarType::Uuid,
+            "int4range" => ScalarType::IntRange,
+            "int8range" => ScalarType::BigIntRange,
+            "tstzrange" => ScalarType::DateTimeRange,
+            "inet" => ScalarType::Inet,
+            "cidr" => ScalarType::Cidr,
+            "macaddr" => ScalarType::MacAddr,
+            "geometry" => ScalarType::Geometry,
+            "geography" => ScalarType::Geography,
             _ => {
                 // Try to match by data_type as fallback
                 match data_type {
@@ -679,13 +739,19 @@ pub mod postgres_queries {
             array_agg(a.attname ORDER BY array_position(ix.indkey, a.attnum)) AS columns,
             ix.indisunique AS is_unique,
             ix.indisprimary AS is_primary,
-            am.amname AS index_method
+            am.amname AS index_method,
+            array_agg(
+                CASE WHEN opc.opcdefault THEN NULL ELSE opc.opcname END
+                ORDER BY array_position(ix.indkey, a.attnum)
+            ) AS column_ops
         FROM pg_index ix
         JOIN pg_class i ON ix.indexrelid = i.oid
         JOIN pg_class t ON ix.indrelid = t.oid
         JOIN pg_namespace n ON t.relnamespace = n.oid
         JOIN pg_am am ON i.relam = am.oid
         JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey)
+        -- int2vector and oidvector are both subscripted from 0
+        JOIN pg_opclass opc ON opc.oid = ix.indclass[array_position(ix.indkey, a.attnum)]
         WHERE n.nspname = $1 AND t.relname = $2
         GROUP BY i.relname, t.relname, ix.indisunique, ix.indisprimary, am.amname
     "#;
@@ -707,6 +773,7 @@ pub mod postgres_queries {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use prax_schema::ast::IndexType;
 
     #[test]
     fn test_to_pascal_case() {
@@ -823,4 +890,72 @@ mod tests {
         let (ft, _) = builder.sql_type_to_prax("uuid", "uuid").unwrap();
         assert!(matches!(ft, FieldType::Scalar(ScalarType::Uuid)));
     }
+
+    #[test]
+    fn test_build_model_indexes() {
+        let column = |name: &str, udt_name: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: udt_name.to_string(),
+            udt_name: udt_name.to_string(),
+            character_maximum_length: None,
+            numeric_precision: None,
+            is_nullable: false,
+            column_default: None,
+            ordinal_position: 0,
+            comment: None,
+        };
+        let index =
+            |name: &str, columns: &[&str], method: &str, ops: Vec<Option<&str>>| IndexInfo {
+                name: name.to_string(),
+                table_name: "posts".to_string(),
+                columns: columns.iter().map(ToString::to_string).collect(),
+                is_unique: false,
+                is_primary: false,
+                index_method: method.to_string(),
+                column_ops: ops
+                    .into_iter()
+                    .map(|o| o.map(ToString::to_string))
+                    .collect(),
+            };
+
+        let result = SchemaBuilder::new(IntrospectionConfig::default())
+            .with_tables(vec![TableInfo {
+                name: "posts".to_string(),
+                schema: "public".to_string(),
+                table_type: "BASE TABLE".to_string(),
+                comment: None,
+            }])
+            .with_columns(
+                "posts",
+                vec![column("title", "text"), column("data", "jsonb")],
+            )
+            .with_indexes(
+                "posts",
+                vec![
+                    index(
+                        "posts_data_idx",
+                        &["data"],
+                        "gin",
+                        vec![Some("jsonb_path_ops")],
+                    ),
+                    index("posts_title_idx", &["title"], "btree", vec![None]),
+                    index(
+                        "posts_search_idx",
+                        &["title", "data"],
+                        "gin",
+                        vec![Some("gin_trgm_ops"), None],
+                    ),
+                ],
+            )
+            .build()
+            .unwrap();
+
+        let indexes = result.schema.get_model("Posts").unwrap().indexes();
+        assert_eq!(indexes.len(), 3);
+        assert_eq!(indexes[0].index_type, Some(IndexType::Gin));
+        assert_eq!(indexes[0].fields[0].ops.as_deref(), Some("jsonb_path_ops"));
+        assert_eq!(indexes[1].index_type, None);
+        assert_eq!(indexes[2].name.as_deref(), Some("posts_search_idx"));
+        assert!(indexes[2].fields.iter().all(|f| f.ops.is_none()));
+    }
 }
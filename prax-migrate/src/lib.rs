@@ -4,7 +4,10 @@
 //!
 //! This crate provides functionality for:
 //! - Schema diffing between Prax schema definitions and database state
-//! - SQL migration generation for PostgreSQL (with MySQL/SQLite planned)
+//! - SQL migration generation for PostgreSQL, MySQL and SQLite
+//! - History tables and triggers for `@@versioned` models
+//! - Closure tables and path columns for `@@tree` models
+//! - Translation tables for `@translatable` fields
 //! - Migration file management on the filesystem
 //! - Migration history tracking in the database
 //! - Safe, transactional migration application and rollback
@@ -79,7 +82,8 @@
 //! migrations/
 //! ├── 20231215120000_create_users/
 //! │   ├── up.sql
-//! │   └── down.sql
+//! │   ├── down.sql
+//! │   └── assert.toml         # Optional post-migration assertions
 //! ├── 20231216090000_add_posts/
 //! │   ├── up.sql
 //! │   └── down.sql
@@ -124,33 +128,106 @@
 //! // Save to file
 //! resolutions.save("migrations/resolutions.toml").await?;
 //! ```
+//!
+//! ## Signed Migrations
+//!
+//! Checksums are the SHA-256 of `up.sql`. To prove that only reviewed
+//! migrations reach production, sign each SQL file with minisign
+//! (`up.sql.minisig`) or GPG (`up.sql.asc`) and require signatures from
+//! trusted keys:
+//!
+//! ```rust,ignore
+//! use prax_migrate::{MigrationConfig, SignaturePolicy};
+//!
+//! let config = MigrationConfig::new().signatures(
+//!     SignaturePolicy::new()
+//!         .required(true)
+//!         .trust_minisign_key("RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3")
+//!         .trust_gpg_key("0123456789ABCDEF0123456789ABCDEF01234567"),
+//! );
+//! ```
+//!
+//! Unsigned or tampered migrations are then rejected before they run, and
+//! the verified signature is kept in the migration's history record.
+//!
+//! ## Progress and Cancellation
+//!
+//! Migrations run one statement at a time through a [`MigrationExecutor`],
+//! reporting each statement's rows affected and elapsed time:
+//!
+//! ```rust,ignore
+//! let engine = MigrationEngine::new(config, history)
+//!     .with_executor(executor)
+//!     .on_progress(|event| println!("{:?}", event));
+//!
+//! let cancel = engine.cancel_token();
+//! tokio::spawn(async move {
+//!     tokio::signal::ctrl_c().await.ok();
+//!     cancel.cancel();
+//! });
+//!
+//! engine.migrate().await?;
+//! ```
+//!
+//! Cancelling stops before the next statement. A migration stopped partway
+//! through is recorded in `resolutions.toml`, and the next run resumes it
+//! after the statements that already ran.
+//!
+//! ## Environment Policies
+//!
+//! An [`EnvironmentPolicy`] gates migrations per environment. Production
+//! requires every migration to be approved and refuses migrations that
+//! drop or truncate data:
+//!
+//! ```rust,ignore
+//! use prax_migrate::{EnvironmentPolicy, MigrationConfig, environment};
+//!
+//! let policy = EnvironmentPolicy::new("production")
+//!     .approvals(environment::load_approvals("prax/approvals.toml".as_ref())?);
+//! let config = MigrationConfig::new().environment(policy);
+//! ```
+//!
+//! Each applied migration's history record keeps who applied it, who
+//! approved it, and the environment.
 
+pub mod assertion;
 pub mod diff;
 pub mod engine;
+pub mod environment;
 pub mod error;
+pub mod executor;
 pub mod file;
 pub mod history;
 pub mod introspect;
 pub mod procedure;
+pub mod progress;
 pub mod resolution;
 pub mod shadow;
+pub mod signature;
 pub mod sql;
 
 // Re-exports
+pub use assertion::{Assertion, Expectation};
 pub use diff::{
-    EnumAlterDiff, EnumDiff, FieldAlterDiff, FieldDiff, IndexDiff, ModelAlterDiff, ModelDiff,
-    SchemaDiff, SchemaDiffer, UniqueConstraint,
+    EnumAlterDiff, EnumColumnDiff, EnumDiff, ExclusionConstraint, FieldAlterDiff, FieldDiff, HistoryDiff, IndexDiff, ModelAlterDiff,
+    ModelDiff, SchemaDiff, SchemaDiffer, TranslationChange, TranslationDiff, TreeChange, TreeDiff,
+    UniqueConstraint,
 };
 pub use engine::{
     MigrationConfig, MigrationEngine, MigrationPlan, MigrationResult, MigrationStatus,
 };
+pub use environment::{
+    Approval, DestructiveChange, DestructiveKind, EnvironmentPolicy, MigrationAudit,
+};
 pub use error::{MigrateResult, MigrationError};
+pub use executor::{MigrationExecutor, split_statements};
 pub use file::{MigrationFile, MigrationFileManager};
 pub use history::{MigrationHistoryRepository, MigrationLock, MigrationRecord};
 pub use introspect::{
     ColumnInfo, ConstraintInfo, EnumInfo, IndexInfo, IntrospectionConfig, IntrospectionResult,
     Introspector, SchemaBuilder, SkippedTable, TableInfo,
 };
+pub use progress::{CancelToken, MigrationEvent, ProgressCallback};
 pub use resolution::{
     ConflictStrategy, Resolution, ResolutionAction, ResolutionBuilder, ResolutionConfig,
     ResolutionCounts, ResolutionWarning,
@@ -159,6 +236,7 @@ pub use shadow::{
     FieldDrift, IndexDrift, SchemaDrift, ShadowConfig, ShadowDatabase, ShadowDatabaseManager,
     ShadowDiffResult, ShadowState, detect_drift,
 };
+pub use signature::{MigrationSignature, SignatureKind, SignaturePolicy};
 pub use procedure::{
     // Procedure types
     ChangeType, ParameterMode, ParallelSafety, ProcedureAlterDiff, ProcedureChange,
@@ -175,4 +253,6 @@ pub use procedure::{
     // MongoDB Atlas Triggers
     AtlasOperation, AtlasTrigger, AtlasTriggerType, AuthOperation,
 };
-pub use sql::{MigrationSql, PostgresSqlGenerator};
+pub use sql::{
+    MigrationSql, MigrationSqlGenerator, MySqlGenerator, PostgresSqlGenerator, SqliteSqlGenerator,
+};
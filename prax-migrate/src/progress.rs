@@ -0,0 +1,106 @@
+//! Progress events and cancellation for applying migrations.
+//!
+//! Migrations are applied one statement at a time. The engine reports each
+//! statement to its progress observers as it completes, and checks its
+//! [`CancelToken`] before starting the next one, so a long migration can be
+//! followed while it runs and stopped between statements.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Something that happened while applying migrations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationEvent {
+    /// A migration is about to run.
+    Started {
+        /// Migration ID.
+        id: String,
+        /// Number of statements in the migration.
+        statements: usize,
+        /// Statements already applied by an earlier, cancelled attempt.
+        resumed_at: usize,
+    },
+    /// A statement finished.
+    Statement {
+        /// Migration ID.
+        id: String,
+        /// Index of the statement, from 0.
+        index: usize,
+        /// Number of statements in the migration.
+        total: usize,
+        /// Rows the statement affected.
+        rows_affected: u64,
+        /// How long the statement took.
+        elapsed: Duration,
+    },
+    /// A post-migration assertion was checked.
+    Assertion {
+        /// Migration ID.
+        id: String,
+        /// What the assertion checks.
+        name: String,
+        /// Whether it passed.
+        passed: bool,
+    },
+    /// A migration finished.
+    Applied {
+        /// Migration ID.
+        id: String,
+        /// How long the migration took.
+        elapsed: Duration,
+    },
+    /// Applying stopped because it was cancelled partway through a
+    /// migration.
+    Cancelled {
+        /// Migration ID.
+        id: String,
+        /// Statements that were applied.
+        applied: usize,
+        /// Number of statements in the migration.
+        total: usize,
+    },
+}
+
+/// A callback receiving [`MigrationEvent`]s.
+pub type ProgressCallback = Arc<dyn Fn(&MigrationEvent) + Send + Sync>;
+
+/// A handle for cancelling migrations from another task.
+///
+/// Cancellation is cooperative: the statement running when
+/// [`cancel`](Self::cancel) is called finishes, and the engine stops
+/// before the next one.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that isn't cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_token_is_shared() {
+        let token = CancelToken::new();
+        let handle = token.clone();
+        assert!(!token.is_cancelled());
+
+        handle.cancel();
+        assert!(token.is_cancelled());
+    }
+}
@@ -6,6 +6,7 @@
 //! - Baseline migrations (mark existing schema as a baseline)
 //! - Renamed migrations (map old IDs to new ones)
 //! - Skipped migrations (intentionally skip certain migrations)
+//! - Partially applied migrations (cancelled between statements)
 //!
 //! # Example
 //!
@@ -194,6 +195,13 @@ impl ResolutionConfig {
             .unwrap_or(false)
     }
 
+    /// Check if a migration is force-applied.
+    pub fn is_force_applied(&self, migration_id: &str) -> bool {
+        self.get(migration_id)
+            .map(|r| matches!(r.action, ResolutionAction::ForceApply))
+            .unwrap_or(false)
+    }
+
     /// Check if a checksum mismatch is accepted.
     pub fn accepts_checksum(
         &self,
@@ -216,6 +224,19 @@ impl ResolutionConfig {
             .unwrap_or(false)
     }
 
+    /// The checksum a partially applied migration had when it was stopped,
+    /// and the number of its statements that were applied.
+    pub fn partially_applied(&self, migration_id: &str) -> Option<(&str, usize)> {
+        self.get(migration_id).and_then(|r| match &r.action {
+            ResolutionAction::PartialApply {
+                checksum,
+                applied_statements,
+                ..
+            } => Some((checksum.as_str(), *applied_statements)),
+            _ => None,
+        })
+    }
+
     /// Get the renamed migration ID if this migration was renamed.
     pub fn get_renamed(&self, old_id: &str) -> Option<&str> {
         self.resolutions.values().find_map(|r| {
@@ -288,6 +309,7 @@ impl ResolutionConfig {
                 ResolutionAction::Rename { .. } => counts.renamed += 1,
                 ResolutionAction::ResolveConflict { .. } => counts.conflicts_resolved += 1,
                 ResolutionAction::ForceApply => counts.force_applied += 1,
+                ResolutionAction::PartialApply { .. } => counts.partially_applied += 1,
             }
         }
 
@@ -418,6 +440,29 @@ impl Resolution {
         }
     }
 
+    /// Create a resolution recording that a migration was partially applied.
+    pub fn partial_apply(
+        migration_id: impl Into<String>,
+        checksum: impl Into<String>,
+        applied_statements: usize,
+        total_statements: usize,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            migration_id: migration_id.into(),
+            action: ResolutionAction::PartialApply {
+                checksum: checksum.into(),
+                applied_statements,
+                total_statements,
+            },
+            reason: reason.into(),
+            created_at: Utc::now(),
+            created_by: None,
+            expires_at: None,
+            metadata: HashMap::new(),
+        }
+    }
+
     /// Set who created this resolution.
     pub fn with_author(mut self, author: impl Into<String>) -> Self {
         self.created_by = Some(author.into());
@@ -471,6 +516,16 @@ pub enum ResolutionAction {
     },
     /// Force apply this migration even if it would normally be blocked.
     ForceApply,
+    /// The migration was stopped partway through; applying it again
+    /// resumes after the statements that already ran.
+    PartialApply {
+        /// Checksum of the migration when it was stopped.
+        checksum: String,
+        /// Number of statements that were applied.
+        applied_statements: usize,
+        /// Number of statements in the migration.
+        total_statements: usize,
+    },
 }
 
 /// Strategy for resolving migration conflicts.
@@ -568,6 +623,8 @@ pub struct ResolutionCounts {
     pub conflicts_resolved: usize,
     /// Number of force-applied migrations.
     pub force_applied: usize,
+    /// Number of partially applied migrations.
+    pub partially_applied: usize,
 }
 
 impl ResolutionCounts {
@@ -579,6 +636,7 @@ impl ResolutionCounts {
             + self.renamed
             + self.conflicts_resolved
             + self.force_applied
+            + self.partially_applied
     }
 }
 
@@ -845,6 +903,27 @@ mod tests {
         assert!(!valid.is_expired());
     }
 
+    #[test]
+    fn test_partially_applied() {
+        let mut config = ResolutionConfig::new();
+        config.add(Resolution::partial_apply(
+            "20240101_backfill",
+            "abc123",
+            2,
+            5,
+            "Cancelled while applying",
+        ));
+
+        let toml = toml::to_string_pretty(&config).unwrap();
+        let config: ResolutionConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(
+            config.partially_applied("20240101_backfill"),
+            Some(("abc123", 2))
+        );
+        assert_eq!(config.partially_applied("other"), None);
+        assert_eq!(config.count_by_type().partially_applied, 1);
+    }
+
     #[test]
     fn test_get_renamed() {
         let mut config = ResolutionConfig::new();
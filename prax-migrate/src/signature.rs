@@ -0,0 +1,372 @@
+//! Migration signatures.
+//!
+//! Signatures are detached and sit next to the SQL they sign, as the
+//! signing tools write them: `up.sql.minisig` for minisign, `up.sql.asc` or
+//! `up.sql.sig` for GPG. A [`SignaturePolicy`] lists the keys trusted to
+//! sign migrations and whether unsigned ones may be applied. Minisign
+//! signatures are checked in-process; GPG ones by running `gpg --verify`
+//! and reading its status output.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MigrateResult, MigrationError};
+use crate::file::MigrationFile;
+
+/// The tool a migration was signed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureKind {
+    /// A minisign (Ed25519) signature.
+    Minisign,
+    /// A GPG detached signature.
+    Gpg,
+}
+
+impl SignatureKind {
+    /// The kind's name, as stored in the history table.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minisign => "minisign",
+            Self::Gpg => "gpg",
+        }
+    }
+
+    /// Extensions of signature files of this kind, appended to the SQL
+    /// file's name.
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Minisign => &["minisig"],
+            Self::Gpg => &["asc", "sig"],
+        }
+    }
+}
+
+/// A verified signature of a migration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationSignature {
+    /// The tool it was made with.
+    pub kind: SignatureKind,
+    /// The trusted key it was made with: a minisign public key, or a GPG
+    /// fingerprint.
+    pub signed_by: String,
+    /// The detached signature, as read from disk.
+    pub signature: String,
+}
+
+/// Which signatures migrations must carry to be applied.
+///
+/// A policy that trusts no keys and doesn't require signatures is
+/// inactive: signature files are ignored, as before signing existed.
+#[derive(Debug, Clone)]
+pub struct SignaturePolicy {
+    /// Whether migrations without a signature are rejected.
+    pub required: bool,
+    /// Trusted minisign public keys, base64 or in `.pub` file form.
+    pub minisign_keys: Vec<String>,
+    /// Fingerprints of trusted GPG keys.
+    pub gpg_fingerprints: Vec<String>,
+    /// The GPG executable.
+    pub gpg_program: PathBuf,
+    /// GPG home directory holding the trusted keys; GPG's default if unset.
+    pub gpg_home: Option<PathBuf>,
+}
+
+impl Default for SignaturePolicy {
+    fn default() -> Self {
+        Self {
+            required: false,
+            minisign_keys: Vec::new(),
+            gpg_fingerprints: Vec::new(),
+            gpg_program: PathBuf::from("gpg"),
+            gpg_home: None,
+        }
+    }
+}
+
+impl SignaturePolicy {
+    /// Create an inactive policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether unsigned migrations are rejected.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Trust a minisign public key.
+    pub fn trust_minisign_key(mut self, key: impl Into<String>) -> Self {
+        self.minisign_keys.push(key.into());
+        self
+    }
+
+    /// Trust a GPG key by its fingerprint.
+    pub fn trust_gpg_key(mut self, fingerprint: impl Into<String>) -> Self {
+        self.gpg_fingerprints.push(fingerprint.into());
+        self
+    }
+
+    /// Set the GPG executable.
+    pub fn gpg_program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.gpg_program = program.into();
+        self
+    }
+
+    /// Set the GPG home directory holding the trusted keys.
+    pub fn gpg_home(mut self, home: impl Into<PathBuf>) -> Self {
+        self.gpg_home = Some(home.into());
+        self
+    }
+
+    /// Whether signatures are checked at all.
+    pub fn is_active(&self) -> bool {
+        self.required || !self.minisign_keys.is_empty() || !self.gpg_fingerprints.is_empty()
+    }
+
+    /// Verify a migration's `up.sql` and, if it has one, `down.sql`.
+    ///
+    /// Returns the signature of `up.sql`, or `None` if it is unsigned and
+    /// the policy allows that.
+    pub fn verify_migration(
+        &self,
+        file: &MigrationFile,
+    ) -> MigrateResult<Option<MigrationSignature>> {
+        let signature = self.verify(&file.path.join("up.sql"), file.up_sql.as_bytes())?;
+        if !file.down_sql.is_empty() {
+            self.verify(&file.path.join("down.sql"), file.down_sql.as_bytes())?;
+        }
+        Ok(signature)
+    }
+
+    /// Verify `content`, read from `sql_path`, against the signature file
+    /// next to it.
+    ///
+    /// The content is passed in rather than re-read so that what is
+    /// verified is exactly what gets applied.
+    pub fn verify(
+        &self,
+        sql_path: &Path,
+        content: &[u8],
+    ) -> MigrateResult<Option<MigrationSignature>> {
+        if !self.is_active() {
+            return Ok(None);
+        }
+
+        let Some((kind, path)) = find_signature(sql_path) else {
+            if self.required {
+                return Err(MigrationError::Unsigned(sql_path.display().to_string()));
+            }
+            return Ok(None);
+        };
+
+        let signature = std::fs::read_to_string(&path)?;
+        let signed_by = match kind {
+            SignatureKind::Minisign => self.verify_minisign(content, &signature),
+            SignatureKind::Gpg => self.verify_gpg(content, &path),
+        }
+        .map_err(|reason| MigrationError::InvalidSignature {
+            path: path.display().to_string(),
+            reason,
+        })?;
+
+        Ok(Some(MigrationSignature {
+            kind,
+            signed_by,
+            signature,
+        }))
+    }
+
+    fn verify_minisign(&self, content: &[u8], signature: &str) -> Result<String, String> {
+        let signature = minisign_verify::Signature::decode(signature.trim())
+            .map_err(|e| format!("malformed minisign signature: {}", e))?;
+        if self.minisign_keys.is_empty() {
+            return Err("no minisign keys are trusted".to_string());
+        }
+
+        for key in &self.minisign_keys {
+            let key = key.trim();
+            let public_key = if key.contains('\n') {
+                minisign_verify::PublicKey::decode(key)
+            } else {
+                minisign_verify::PublicKey::from_base64(key)
+            }
+            .map_err(|e| format!("invalid trusted minisign key: {}", e))?;
+
+            if public_key.verify(content, &signature, false).is_ok() {
+                return Ok(key.lines().last().unwrap_or(key).trim().to_string());
+            }
+        }
+        Err("not signed by a trusted minisign key".to_string())
+    }
+
+    fn verify_gpg(&self, content: &[u8], signature_path: &Path) -> Result<String, String> {
+        if self.gpg_fingerprints.is_empty() {
+            return Err("no GPG keys are trusted".to_string());
+        }
+
+        let mut command = Command::new(&self.gpg_program);
+        command.args(["--batch", "--no-tty", "--status-fd", "1"]);
+        if let Some(home) = &self.gpg_home {
+            command.arg("--homedir").arg(home);
+        }
+        command
+            .arg("--verify")
+            .arg(signature_path)
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("failed to run {}: {}", self.gpg_program.display(), e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(content)
+                .map_err(|e| format!("failed to pass the migration to gpg: {}", e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("gpg failed: {}", e))?;
+
+        let signers = valid_signers(&String::from_utf8_lossy(&output.stdout));
+        if !output.status.success() || signers.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().last().unwrap_or("bad signature").trim();
+            return Err(format!("gpg could not verify the signature: {}", reason));
+        }
+
+        self.gpg_fingerprints
+            .iter()
+            .map(|fingerprint| normalize_fingerprint(fingerprint))
+            .find(|fingerprint| signers.contains(fingerprint))
+            .ok_or_else(|| {
+                format!(
+                    "signed by {}, which is not a trusted GPG key",
+                    signers.join(", ")
+                )
+            })
+    }
+}
+
+/// The signature file next to `sql_path`, if there is one.
+fn find_signature(sql_path: &Path) -> Option<(SignatureKind, PathBuf)> {
+    let file_name = sql_path.file_name()?.to_str()?;
+    [SignatureKind::Minisign, SignatureKind::Gpg]
+        .into_iter()
+        .flat_map(|kind| kind.extensions().iter().map(move |ext| (kind, ext)))
+        .map(|(kind, ext)| {
+            (
+                kind,
+                sql_path.with_file_name(format!("{}.{}", file_name, ext)),
+            )
+        })
+        .find(|(_, path)| path.is_file())
+}
+
+/// Fingerprints of the keys behind good signatures in GPG's status output:
+/// each `VALIDSIG` line names the signing (sub)key and its primary key.
+fn valid_signers(status: &str) -> Vec<String> {
+    let mut signers = Vec::new();
+    for line in status.lines() {
+        let Some(fields) = line.strip_prefix("[GNUPG:] VALIDSIG ") else {
+            continue;
+        };
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        for fingerprint in [fields.first(), fields.get(9)].into_iter().flatten() {
+            let fingerprint = normalize_fingerprint(fingerprint);
+            if !signers.contains(&fingerprint) {
+                signers.push(fingerprint);
+            }
+        }
+    }
+    signers
+}
+
+fn normalize_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+
+    // A prehashed signature of "test" by `PUBLIC_KEY`.
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==
+";
+
+    #[test]
+    fn test_minisign_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let sql = dir.path().join("up.sql");
+        std::fs::write(dir.path().join("up.sql.minisig"), SIGNATURE).unwrap();
+
+        let policy = SignaturePolicy::new()
+            .required(true)
+            .trust_minisign_key(PUBLIC_KEY);
+        let signature = policy.verify(&sql, b"test").unwrap().unwrap();
+        assert_eq!(signature.kind, SignatureKind::Minisign);
+        assert_eq!(signature.signed_by, PUBLIC_KEY);
+
+        let err = policy.verify(&sql, b"tampered").unwrap_err();
+        assert!(matches!(err, MigrationError::InvalidSignature { .. }));
+
+        let untrusted = SignaturePolicy::new()
+            .trust_minisign_key("RWRUTpOUKD5TAFp9Z0Eqsk5+Z7NMAZZRAukKMIYcs0/jHi7M490DnNGD");
+        assert!(untrusted.verify(&sql, b"test").is_err());
+    }
+
+    #[test]
+    fn test_unsigned_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let sql = dir.path().join("up.sql");
+
+        assert!(
+            SignaturePolicy::new()
+                .verify(&sql, b"test")
+                .unwrap()
+                .is_none()
+        );
+        let optional = SignaturePolicy::new().trust_minisign_key(PUBLIC_KEY);
+        assert!(optional.verify(&sql, b"test").unwrap().is_none());
+
+        let required = SignaturePolicy::new().required(true);
+        assert!(matches!(
+            required.verify(&sql, b"test"),
+            Err(MigrationError::Unsigned(_))
+        ));
+    }
+
+    #[test]
+    fn test_valid_signers() {
+        let status = "[GNUPG:] NEWSIG\n\
+             [GNUPG:] GOODSIG 1D2F7B4C9A3E5D60 Release <release@example.com>\n\
+             [GNUPG:] VALIDSIG 0123456789ABCDEF0123456789ABCDEF01234567 2024-01-01 1704067200 0 4 0 22 10 00 89ABCDEF0123456789ABCDEF0123456789ABCDEF\n";
+        assert_eq!(
+            valid_signers(status),
+            vec![
+                "0123456789ABCDEF0123456789ABCDEF01234567",
+                "89ABCDEF0123456789ABCDEF0123456789ABCDEF",
+            ]
+        );
+        assert!(valid_signers("[GNUPG:] BADSIG 1D2F7B4C9A3E5D60 Release\n").is_empty());
+        assert_eq!(
+            normalize_fingerprint("0123 4567 89ab cdef"),
+            "0123456789ABCDEF"
+        );
+    }
+}
@@ -1,10 +1,110 @@
 //! SQL generation for migrations.
 
+use std::collections::HashMap;
+
 use crate::diff::{
-    EnumAlterDiff, EnumDiff, ExtensionDiff, FieldAlterDiff, FieldDiff, IndexDiff, ModelAlterDiff,
-    ModelDiff, SchemaDiff, ViewDiff,
+    EnumAlterDiff, EnumDiff, ExclusionConstraint, ExtensionDiff, FieldAlterDiff, FieldDiff,
+    HistoryDiff, IndexDiff, ModelAlterDiff, ModelDiff, SchemaDiff, TranslationChange,
+    TranslationDiff, TreeChange, TreeDiff, UniqueConstraint, ViewDiff, is_spatial_sql_type,
 };
 
+/// Surrogate key of a history table row.
+const HISTORY_VERSION_ID: &str = "_version_id";
+/// When a row version became current.
+const HISTORY_VALID_FROM: &str = "_valid_from";
+/// When a row version was superseded; `NULL` while current.
+const HISTORY_VALID_TO: &str = "_valid_to";
+/// The change that produced a row version (`INSERT`, `UPDATE` or `DELETE`).
+const HISTORY_OPERATION: &str = "_operation";
+
+/// Closure table column holding the ancestor's key.
+const TREE_ANCESTOR: &str = "ancestor";
+/// Closure table column holding the descendant's key.
+const TREE_DESCENDANT: &str = "descendant";
+/// Closure table column holding the distance between the two nodes.
+const TREE_DEPTH: &str = "depth";
+/// Materialized path column.
+const TREE_PATH: &str = prax_schema::ast::Tree::PATH_COLUMN;
+
+/// Translation table column holding the locale of a row.
+const TRANSLATION_LOCALE: &str = "locale";
+
+/// Convert the key column and translated columns of a model to their
+/// translation table form. Translated columns are nullable so a locale can
+/// leave a field untranslated.
+fn translation_fields(
+    model: &ModelDiff,
+    translation: &TranslationDiff,
+) -> Option<(FieldDiff, Vec<FieldDiff>)> {
+    let column = |name: &str| model.fields.iter().find(|f| f.column_name == name);
+    let key = FieldDiff {
+        nullable: false,
+        ..history_field(column(&translation.key_column)?)
+    };
+    let columns = translation
+        .columns
+        .iter()
+        .filter_map(|name| column(name).map(history_field))
+        .collect();
+    Some((key, columns))
+}
+
+/// Convert the primary key column to the ancestor and descendant columns of
+/// a closure table.
+fn closure_fields(model: &ModelDiff, tree: &TreeDiff) -> Option<[FieldDiff; 2]> {
+    let key = model
+        .fields
+        .iter()
+        .find(|f| f.column_name == tree.key_column)?;
+    Some([TREE_ANCESTOR, TREE_DESCENDANT].map(|column| FieldDiff {
+        name: column.to_string(),
+        column_name: column.to_string(),
+        nullable: false,
+        default: None,
+        is_primary_key: false,
+        is_auto_increment: false,
+        is_unique: false,
+        ..key.clone()
+    }))
+}
+
+/// Convert a column to its history table form, without constraints or defaults.
+fn history_field(field: &FieldDiff) -> FieldDiff {
+    FieldDiff {
+        nullable: true,
+        default: None,
+        is_primary_key: false,
+        is_auto_increment: false,
+        is_unique: false,
+        ..field.clone()
+    }
+}
+
+/// Replace range columns with `<column>_lower` and `<column>_upper` columns
+/// of the element type, for databases without range types. Both bounds are
+/// nullable, as `NULL` marks an unbounded side.
+fn split_range_fields(fields: &mut Vec<FieldDiff>) {
+    *fields = std::mem::take(fields)
+        .into_iter()
+        .flat_map(|field| {
+            let element = match field.sql_type.as_str() {
+                "INT4RANGE" => "INTEGER",
+                "INT8RANGE" => "BIGINT",
+                "TSTZRANGE" => "TIMESTAMP WITH TIME ZONE",
+                _ => return vec![field],
+            };
+            ["lower", "upper"]
+                .map(|bound| FieldDiff {
+                    name: format!("{}_{}", field.name, bound),
+                    column_name: format!("{}_{}", field.column_name, bound),
+                    sql_type: element.to_string(),
+                    ..history_field(&field)
+                })
+                .to_vec()
+        })
+        .collect();
+}
+
 /// SQL generator for PostgreSQL.
 pub struct PostgresSqlGenerator;
 
@@ -14,6 +114,12 @@ impl PostgresSqlGenerator {
         let mut up = Vec::new();
         let mut down = Vec::new();
 
+        // Create schemas first; like extensions, they are left in place on
+        // the way down
+        for schema in &diff.create_schemas {
+            up.push(format!("CREATE SCHEMA IF NOT EXISTS \"{}\";", schema));
+        }
+
         // Create extensions first (they provide types used by tables)
         for ext in &diff.create_extensions {
             up.push(self.create_extension(ext));
@@ -26,6 +132,53 @@ impl PostgresSqlGenerator {
             // Can't easily recreate dropped extensions without knowing schema/version
         }
 
+        // Exclusion constraints comparing plain scalars need btree_gist. It
+        // is left installed on the way down, as other objects may use it
+        let needs_btree_gist = diff
+            .create_models
+            .iter()
+            .flat_map(|model| &model.exclusion_constraints)
+            .chain(
+                diff.alter_models
+                    .iter()
+                    .flat_map(|alter| &alter.add_exclusion_constraints),
+            )
+            .any(|exclusion| exclusion.requires_btree_gist);
+        let has_btree_gist = diff
+            .create_extensions
+            .iter()
+            .any(|e| e.name == "btree_gist");
+        if needs_btree_gist && !has_btree_gist {
+            up.push(self.create_extension(&ExtensionDiff {
+                name: "btree_gist".to_string(),
+                schema: None,
+                version: None,
+            }));
+        }
+
+        // Spatial columns need PostGIS, which is likewise left installed
+        let needs_postgis = diff
+            .create_models
+            .iter()
+            .flat_map(|model| model.fields.iter().map(|f| f.sql_type.as_str()))
+            .chain(diff.alter_models.iter().flat_map(|alter| {
+                let added = alter.add_fields.iter().map(|f| f.sql_type.as_str());
+                let altered = alter
+                    .alter_fields
+                    .iter()
+                    .filter_map(|f| f.new_type.as_deref());
+                added.chain(altered)
+            }))
+            .any(is_spatial_sql_type);
+        let has_postgis = diff.create_extensions.iter().any(|e| e.name == "postgis");
+        if needs_postgis && !has_postgis {
+            up.push(self.create_extension(&ExtensionDiff {
+                name: "postgis".to_string(),
+                schema: None,
+                version: None,
+            }));
+        }
+
         // Create enums (they might be used in tables)
         for enum_diff in &diff.create_enums {
             up.push(self.create_enum(enum_diff));
@@ -47,7 +200,25 @@ impl PostgresSqlGenerator {
         // Create models
         for model in &diff.create_models {
             up.push(self.create_table(model));
-            down.push(self.drop_table(&model.table_name));
+            for index in &model.indexes {
+                up.push(self.create_index(index));
+            }
+            if let Some(history) = &model.history_table {
+                up.extend(self.create_history(model, history));
+                down.extend(self.drop_history(&model.table_name, history));
+            }
+            if let Some(translation) = &model.translation {
+                up.push(self.create_translations(model, translation));
+                down.push(self.drop_table(&translation.table_name));
+            }
+            if let Some(tree) = &model.tree {
+                up.extend(self.create_tree(model, tree));
+                down.extend(self.drop_tree(&model.table_name, tree));
+            }
+            down.push(format!(
+                "DROP TABLE IF EXISTS {} CASCADE;",
+                self.table_ref(model.schema.as_deref(), &model.table_name)
+            ));
         }
 
         // Drop models
@@ -59,6 +230,9 @@ impl PostgresSqlGenerator {
         // Alter models
         for alter in &diff.alter_models {
             up.extend(self.alter_table(alter));
+            up.extend(self.alter_history(alter));
+            up.extend(self.alter_tree(alter));
+            up.extend(self.alter_translations(alter));
             // Reverse alterations could be generated but complex
         }
 
@@ -173,22 +347,61 @@ impl PostgresSqlGenerator {
 
         // Add unique constraints
         for uc in &model.unique_constraints {
-            let cols: Vec<String> = uc.columns.iter().map(|c| format!("\"{}\"", c)).collect();
-            let constraint = if let Some(name) = &uc.name {
-                format!("CONSTRAINT \"{}\" UNIQUE ({})", name, cols.join(", "))
-            } else {
-                format!("UNIQUE ({})", cols.join(", "))
-            };
-            columns.push(constraint);
+            columns.push(self.unique_constraint(uc));
+        }
+
+        for exclusion in &model.exclusion_constraints {
+            columns.push(self.exclusion_constraint(exclusion));
         }
 
         format!(
-            "CREATE TABLE \"{}\" (\n    {}\n);",
-            model.table_name,
+            "CREATE TABLE {} (\n    {}\n);",
+            self.table_ref(model.schema.as_deref(), &model.table_name),
             columns.join(",\n    ")
         )
     }
 
+    /// Quote a table name, qualified with its schema when it has one.
+    fn table_ref(&self, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            Some(schema) => format!("\"{}\".\"{}\"", schema, table),
+            None => format!("\"{}\"", table),
+        }
+    }
+
+    /// Generate an exclusion table constraint.
+    fn exclusion_constraint(&self, exclusion: &ExclusionConstraint) -> String {
+        let elements: Vec<String> = exclusion
+            .elements
+            .iter()
+            .map(|(column, op)| format!("\"{}\" WITH {}", column, op))
+            .collect();
+        format!(
+            "CONSTRAINT \"{}\" EXCLUDE USING {} ({})",
+            exclusion.name,
+            exclusion.using.as_sql(),
+            elements.join(", ")
+        )
+    }
+
+    /// Generate a unique table constraint.
+    fn unique_constraint(&self, uc: &UniqueConstraint) -> String {
+        let cols: Vec<String> = uc.columns.iter().map(|c| format!("\"{}\"", c)).collect();
+        let mut constraint = match &uc.name {
+            Some(name) => format!("CONSTRAINT \"{}\" UNIQUE", name),
+            None => "UNIQUE".to_string(),
+        };
+        if uc.nulls_not_distinct {
+            constraint.push_str(" NULLS NOT DISTINCT");
+        }
+        constraint.push_str(&format!(" ({})", cols.join(", ")));
+        if let Some(deferrable) = uc.deferrable {
+            constraint.push(' ');
+            constraint.push_str(deferrable.as_sql());
+        }
+        constraint
+    }
+
     /// Generate column definition.
     fn column_definition(&self, field: &FieldDiff) -> String {
         let mut parts = vec![format!("\"{}\"", field.column_name), field.sql_type.clone()];
@@ -222,15 +435,433 @@ impl PostgresSqlGenerator {
         format!("DROP TABLE IF EXISTS \"{}\" CASCADE;", name)
     }
 
+    /// Generate the history table and trigger of a `@@versioned` model.
+    fn create_history(&self, model: &ModelDiff, history: &str) -> Vec<String> {
+        let mut columns = vec![format!("\"{}\" BIGSERIAL PRIMARY KEY", HISTORY_VERSION_ID)];
+        for field in &model.fields {
+            columns.push(self.column_definition(&history_field(field)));
+        }
+        columns.push(format!(
+            "\"{}\" TIMESTAMP WITH TIME ZONE NOT NULL",
+            HISTORY_VALID_FROM
+        ));
+        columns.push(format!("\"{}\" TIMESTAMP WITH TIME ZONE", HISTORY_VALID_TO));
+        columns.push(format!("\"{}\" VARCHAR(6) NOT NULL", HISTORY_OPERATION));
+
+        let mut key: Vec<String> = model
+            .primary_key_columns()
+            .iter()
+            .map(|c| format!("\"{}\"", c))
+            .collect();
+        key.push(format!("\"{}\"", HISTORY_VALID_FROM));
+
+        let mut stmts = vec![
+            format!(
+                "CREATE TABLE \"{}\" (\n    {}\n);",
+                history,
+                columns.join(",\n    ")
+            ),
+            format!(
+                "CREATE INDEX \"{}_key_idx\" ON \"{}\"({});",
+                history,
+                history,
+                key.join(", ")
+            ),
+        ];
+        stmts.extend(self.history_trigger(model, history));
+        stmts
+    }
+
+    /// Generate the trigger function recording row versions into the history table.
+    ///
+    /// Every change closes the open version of the row and, except for deletes,
+    /// records the new one. A delete leaves a zero-length tombstone version.
+    fn history_trigger(&self, model: &ModelDiff, history: &str) -> Vec<String> {
+        let key = model.primary_key_columns();
+        if key.is_empty() {
+            return vec![format!(
+                "-- Cannot record history for \"{}\": the table has no primary key",
+                model.table_name
+            )];
+        }
+
+        let columns: Vec<String> = model
+            .fields
+            .iter()
+            .map(|f| format!("\"{}\"", f.column_name))
+            .collect();
+        let values = |row: &str| -> String {
+            columns
+                .iter()
+                .map(|c| format!("{}.{}", row, c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let key_match: Vec<String> = key
+            .iter()
+            .map(|c| format!("\"{}\" = OLD.\"{}\"", c, c))
+            .collect();
+        let insert = format!(
+            "INSERT INTO \"{}\" ({}, \"{}\", \"{}\", \"{}\")",
+            history,
+            columns.join(", "),
+            HISTORY_VALID_FROM,
+            HISTORY_VALID_TO,
+            HISTORY_OPERATION
+        );
+
+        let function = [
+            format!(
+                "CREATE OR REPLACE FUNCTION \"{}_record\"() RETURNS TRIGGER AS $$",
+                history
+            ),
+            "BEGIN".to_string(),
+            "    IF TG_OP IN ('UPDATE', 'DELETE') THEN".to_string(),
+            format!(
+                "        UPDATE \"{}\" SET \"{}\" = now()",
+                history, HISTORY_VALID_TO
+            ),
+            format!(
+                "        WHERE {} AND \"{}\" IS NULL;",
+                key_match.join(" AND "),
+                HISTORY_VALID_TO
+            ),
+            "    END IF;".to_string(),
+            "    IF TG_OP = 'DELETE' THEN".to_string(),
+            format!("        {}", insert),
+            format!("        VALUES ({}, now(), now(), TG_OP);", values("OLD")),
+            "        RETURN OLD;".to_string(),
+            "    END IF;".to_string(),
+            format!("    {}", insert),
+            format!("    VALUES ({}, now(), NULL, TG_OP);", values("NEW")),
+            "    RETURN NEW;".to_string(),
+            "END;".to_string(),
+            "$$ LANGUAGE plpgsql;".to_string(),
+        ]
+        .join("\n");
+
+        vec![
+            function,
+            format!(
+                "DROP TRIGGER IF EXISTS \"{}_trigger\" ON \"{}\";",
+                history, model.table_name
+            ),
+            format!(
+                "CREATE TRIGGER \"{}_trigger\"\nAFTER INSERT OR UPDATE OR DELETE ON \"{}\"\nFOR EACH ROW EXECUTE FUNCTION \"{}_record\"();",
+                history, model.table_name, history
+            ),
+        ]
+    }
+
+    /// Generate statements removing the history table of a model.
+    fn drop_history(&self, table: &str, history: &str) -> Vec<String> {
+        vec![
+            format!(
+                "DROP TRIGGER IF EXISTS \"{}_trigger\" ON \"{}\";",
+                history, table
+            ),
+            format!("DROP FUNCTION IF EXISTS \"{}_record\"();", history),
+            self.drop_table(history),
+        ]
+    }
+
+    /// Generate statements keeping the history of a `@@versioned` model in step
+    /// with an altered table.
+    ///
+    /// Dropped columns are kept in the history table so old versions stay readable.
+    fn alter_history(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        for change in &alter.history {
+            if let HistoryDiff::Drop(history) = change {
+                stmts.extend(self.drop_history(&alter.table_name, history));
+            }
+        }
+
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+        let Some(history) = &target.history_table else {
+            return stmts;
+        };
+
+        let created = alter
+            .history
+            .contains(&HistoryDiff::Create(history.clone()));
+        if created {
+            stmts.extend(self.create_history(target, history));
+        } else if !alter.add_fields.is_empty()
+            || !alter.drop_fields.is_empty()
+            || !alter.alter_fields.is_empty()
+        {
+            for field in &alter.add_fields {
+                stmts.push(format!(
+                    "ALTER TABLE \"{}\" ADD COLUMN {};",
+                    history,
+                    self.column_definition(&history_field(field))
+                ));
+            }
+            for field in &alter.alter_fields {
+                let field = FieldAlterDiff {
+                    new_nullable: None,
+                    new_default: None,
+                    ..field.clone()
+                };
+                stmts.extend(self.alter_column(&self.table_ref(None, history), &field));
+            }
+            stmts.extend(self.history_trigger(target, history));
+        }
+
+        stmts
+    }
+
+    /// Generate the translation table of a model with `@translatable` fields.
+    fn create_translations(&self, model: &ModelDiff, translation: &TranslationDiff) -> String {
+        let table = &translation.table_name;
+        let Some((key, fields)) = translation_fields(model, translation) else {
+            return format!(
+                "-- Cannot create \"{}\": \"{}\" has no column \"{}\"",
+                table, model.table_name, translation.key_column
+            );
+        };
+        let mut columns = vec![format!(
+            "{} REFERENCES \"{}\"(\"{}\") ON DELETE CASCADE",
+            self.column_definition(&key),
+            model.table_name,
+            translation.key_column
+        )];
+        columns.push(format!("\"{}\" VARCHAR(35) NOT NULL", TRANSLATION_LOCALE));
+        columns.extend(fields.iter().map(|f| self.column_definition(f)));
+        columns.push(format!(
+            "PRIMARY KEY (\"{}\", \"{}\")",
+            translation.key_column, TRANSLATION_LOCALE
+        ));
+        format!(
+            "CREATE TABLE \"{}\" (\n    {}\n);",
+            table,
+            columns.join(",\n    ")
+        )
+    }
+
+    /// Generate statements creating, dropping or changing the columns of the
+    /// translation table of an altered model.
+    fn alter_translations(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+
+        for change in &alter.translation {
+            match change {
+                TranslationChange::Drop(translation) => {
+                    stmts.push(self.drop_table(&translation.table_name));
+                }
+                TranslationChange::Create(translation) => {
+                    stmts.push(self.create_translations(target, translation));
+                }
+                TranslationChange::AddColumn(column) => {
+                    let (Some(translation), Some(field)) = (
+                        &target.translation,
+                        target.fields.iter().find(|f| &f.column_name == column),
+                    ) else {
+                        continue;
+                    };
+                    stmts.push(format!(
+                        "ALTER TABLE \"{}\" ADD COLUMN {};",
+                        translation.table_name,
+                        self.column_definition(&history_field(field))
+                    ));
+                }
+                TranslationChange::DropColumn(column) => {
+                    if let Some(translation) = &target.translation {
+                        stmts.push(format!(
+                            "ALTER TABLE \"{}\" DROP COLUMN \"{}\";",
+                            translation.table_name, column
+                        ));
+                    }
+                }
+            }
+        }
+
+        stmts
+    }
+
+    /// Generate the closure table and insert trigger of a `@@tree` model.
+    fn create_tree(&self, model: &ModelDiff, tree: &TreeDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+        if let Some(closure) = &tree.closure_table {
+            let Some(fields) = closure_fields(model, tree) else {
+                return vec![format!(
+                    "-- Cannot create \"{}\": \"{}\" has no column \"{}\"",
+                    closure, model.table_name, tree.key_column
+                )];
+            };
+            let mut columns: Vec<String> = fields
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{} REFERENCES \"{}\"(\"{}\") ON DELETE CASCADE",
+                        self.column_definition(f),
+                        model.table_name,
+                        tree.key_column
+                    )
+                })
+                .collect();
+            columns.push(format!("\"{}\" INTEGER NOT NULL", TREE_DEPTH));
+            columns.push(format!(
+                "PRIMARY KEY (\"{}\", \"{}\")",
+                TREE_ANCESTOR, TREE_DESCENDANT
+            ));
+            stmts.push(format!(
+                "CREATE TABLE \"{}\" (\n    {}\n);",
+                closure,
+                columns.join(",\n    ")
+            ));
+            stmts.push(format!(
+                "CREATE INDEX \"{}_{}_idx\" ON \"{}\"(\"{}\");",
+                closure, TREE_DESCENDANT, closure, TREE_DESCENDANT
+            ));
+        }
+        stmts.extend(self.tree_trigger(model, tree));
+        stmts
+    }
+
+    /// Generate the trigger maintaining a tree as nodes are inserted.
+    ///
+    /// A closure table gains a row linking the new node to itself and to each
+    /// of its parent's ancestors; a materialized path is its parent's path
+    /// followed by the parent's key.
+    fn tree_trigger(&self, model: &ModelDiff, tree: &TreeDiff) -> Vec<String> {
+        let table = &model.table_name;
+        let trigger = tree.trigger_name(table);
+        let key = &tree.key_column;
+        let parent = &tree.parent_column;
+
+        let (timing, body) = match &tree.closure_table {
+            Some(closure) => (
+                "AFTER",
+                vec![
+                    format!(
+                        "    INSERT INTO \"{}\" (\"{}\", \"{}\", \"{}\")",
+                        closure, TREE_ANCESTOR, TREE_DESCENDANT, TREE_DEPTH
+                    ),
+                    format!(
+                        "    SELECT \"{}\", NEW.\"{}\", \"{}\" + 1 FROM \"{}\" WHERE \"{}\" = NEW.\"{}\"",
+                        TREE_ANCESTOR, key, TREE_DEPTH, closure, TREE_DESCENDANT, parent
+                    ),
+                    format!("    UNION ALL SELECT NEW.\"{}\", NEW.\"{}\", 0;", key, key),
+                ],
+            ),
+            None => (
+                "BEFORE",
+                vec![format!(
+                    "    NEW.\"{}\" := COALESCE((SELECT p.\"{}\" || CAST(p.\"{}\" AS TEXT) || '/' FROM \"{}\" p WHERE p.\"{}\" = NEW.\"{}\"), '/');",
+                    TREE_PATH, TREE_PATH, key, table, key, parent
+                )],
+            ),
+        };
+
+        let mut function = vec![
+            format!(
+                "CREATE OR REPLACE FUNCTION \"{}\"() RETURNS TRIGGER AS $$",
+                trigger
+            ),
+            "BEGIN".to_string(),
+        ];
+        function.extend(body);
+        function.extend([
+            "    RETURN NEW;".to_string(),
+            "END;".to_string(),
+            "$$ LANGUAGE plpgsql;".to_string(),
+        ]);
+
+        vec![
+            function.join("\n"),
+            format!("DROP TRIGGER IF EXISTS \"{}\" ON \"{}\";", trigger, table),
+            format!(
+                "CREATE TRIGGER \"{}\"\n{} INSERT ON \"{}\"\nFOR EACH ROW EXECUTE FUNCTION \"{}\"();",
+                trigger, timing, table, trigger
+            ),
+        ]
+    }
+
+    /// Generate the statement filling a new tree's storage from the parent column.
+    fn backfill_tree(&self, model: &ModelDiff, tree: &TreeDiff) -> String {
+        let table = &model.table_name;
+        let key = &tree.key_column;
+        let parent = &tree.parent_column;
+        match &tree.closure_table {
+            Some(closure) => format!(
+                "WITH RECURSIVE tree_paths (\"{a}\", \"{d}\", \"{depth}\") AS (\n    \
+                 SELECT \"{key}\", \"{key}\", 0 FROM \"{table}\"\n    \
+                 UNION ALL\n    \
+                 SELECT tp.\"{a}\", n.\"{key}\", tp.\"{depth}\" + 1 FROM tree_paths tp JOIN \"{table}\" n ON n.\"{parent}\" = tp.\"{d}\"\n\
+                 )\n\
+                 INSERT INTO \"{closure}\" (\"{a}\", \"{d}\", \"{depth}\") SELECT \"{a}\", \"{d}\", \"{depth}\" FROM tree_paths;",
+                a = TREE_ANCESTOR,
+                d = TREE_DESCENDANT,
+                depth = TREE_DEPTH,
+            ),
+            None => format!(
+                "WITH RECURSIVE tree_paths (\"{key}\", \"{path}\") AS (\n    \
+                 SELECT \"{key}\", CAST('/' AS TEXT) FROM \"{table}\" WHERE \"{parent}\" IS NULL\n    \
+                 UNION ALL\n    \
+                 SELECT n.\"{key}\", tp.\"{path}\" || CAST(tp.\"{key}\" AS TEXT) || '/' FROM tree_paths tp JOIN \"{table}\" n ON n.\"{parent}\" = tp.\"{key}\"\n\
+                 )\n\
+                 UPDATE \"{table}\" SET \"{path}\" = tree_paths.\"{path}\" FROM tree_paths WHERE \"{table}\".\"{key}\" = tree_paths.\"{key}\";",
+                path = TREE_PATH,
+            ),
+        }
+    }
+
+    /// Generate statements removing the storage and trigger of a tree.
+    fn drop_tree(&self, table: &str, tree: &TreeDiff) -> Vec<String> {
+        let trigger = tree.trigger_name(table);
+        let mut stmts = vec![
+            format!("DROP TRIGGER IF EXISTS \"{}\" ON \"{}\";", trigger, table),
+            format!("DROP FUNCTION IF EXISTS \"{}\"();", trigger),
+        ];
+        if let Some(closure) = &tree.closure_table {
+            stmts.push(self.drop_table(closure));
+        }
+        stmts
+    }
+
+    /// Generate statements adding, removing or changing the storage of a
+    /// `@@tree` model. A new storage is filled from the parent column.
+    fn alter_tree(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        for change in &alter.tree {
+            if let TreeChange::Drop(tree) = change {
+                stmts.extend(self.drop_tree(&alter.table_name, tree));
+            }
+        }
+
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+        let Some(tree) = &target.tree else {
+            return stmts;
+        };
+        if alter.tree.contains(&TreeChange::Create(tree.clone())) {
+            stmts.extend(self.create_tree(target, tree));
+            stmts.push(self.backfill_tree(target, tree));
+        }
+
+        stmts
+    }
+
     /// Generate ALTER TABLE statements.
     fn alter_table(&self, alter: &ModelAlterDiff) -> Vec<String> {
         let mut stmts = Vec::new();
+        let table = self.table_ref(alter.schema.as_deref(), &alter.table_name);
 
         // Add columns
         for field in &alter.add_fields {
             stmts.push(format!(
-                "ALTER TABLE \"{}\" ADD COLUMN {};",
-                alter.table_name,
+                "ALTER TABLE {} ADD COLUMN {};",
+                table,
                 self.column_definition(field)
             ));
         }
@@ -238,14 +869,23 @@ impl PostgresSqlGenerator {
         // Drop columns
         for name in &alter.drop_fields {
             stmts.push(format!(
-                "ALTER TABLE \"{}\" DROP COLUMN IF EXISTS \"{}\";",
-                alter.table_name, name
+                "ALTER TABLE {} DROP COLUMN IF EXISTS \"{}\";",
+                table, name
             ));
         }
 
         // Alter columns
         for field in &alter.alter_fields {
-            stmts.extend(self.alter_column(&alter.table_name, field));
+            stmts.extend(self.alter_column(&table, field));
+        }
+
+        // Drop indexes first so a changed index can be recreated under
+        // the same name
+        for name in &alter.drop_indexes {
+            stmts.push(format!(
+                "DROP INDEX IF EXISTS {};",
+                self.table_ref(alter.schema.as_deref(), name)
+            ));
         }
 
         // Add indexes
@@ -253,21 +893,47 @@ impl PostgresSqlGenerator {
             stmts.push(self.create_index(index));
         }
 
-        // Drop indexes
-        for name in &alter.drop_indexes {
-            stmts.push(format!("DROP INDEX IF EXISTS \"{}\";", name));
+        // Unique constraints, likewise dropped before being added again
+        for name in &alter.drop_unique_constraints {
+            stmts.push(format!(
+                "ALTER TABLE {} DROP CONSTRAINT IF EXISTS \"{}\";",
+                table, name
+            ));
+        }
+
+        for uc in &alter.add_unique_constraints {
+            stmts.push(format!(
+                "ALTER TABLE {} ADD {};",
+                table,
+                self.unique_constraint(uc)
+            ));
+        }
+
+        for name in &alter.drop_exclusion_constraints {
+            stmts.push(format!(
+                "ALTER TABLE {} DROP CONSTRAINT IF EXISTS \"{}\";",
+                table, name
+            ));
+        }
+
+        for exclusion in &alter.add_exclusion_constraints {
+            stmts.push(format!(
+                "ALTER TABLE {} ADD {};",
+                table,
+                self.exclusion_constraint(exclusion)
+            ));
         }
 
         stmts
     }
 
-    /// Generate ALTER COLUMN statements.
+    /// Generate ALTER COLUMN statements on an already quoted table.
     fn alter_column(&self, table: &str, field: &FieldAlterDiff) -> Vec<String> {
         let mut stmts = Vec::new();
 
         if let Some(new_type) = &field.new_type {
             stmts.push(format!(
-                "ALTER TABLE \"{}\" ALTER COLUMN \"{}\" TYPE {} USING \"{}\"::{};",
+                "ALTER TABLE {} ALTER COLUMN \"{}\" TYPE {} USING \"{}\"::{};",
                 table, field.column_name, new_type, field.column_name, new_type
             ));
         }
@@ -275,12 +941,12 @@ impl PostgresSqlGenerator {
         if let Some(new_nullable) = field.new_nullable {
             if new_nullable {
                 stmts.push(format!(
-                    "ALTER TABLE \"{}\" ALTER COLUMN \"{}\" DROP NOT NULL;",
+                    "ALTER TABLE {} ALTER COLUMN \"{}\" DROP NOT NULL;",
                     table, field.column_name
                 ));
             } else {
                 stmts.push(format!(
-                    "ALTER TABLE \"{}\" ALTER COLUMN \"{}\" SET NOT NULL;",
+                    "ALTER TABLE {} ALTER COLUMN \"{}\" SET NOT NULL;",
                     table, field.column_name
                 ));
             }
@@ -288,7 +954,7 @@ impl PostgresSqlGenerator {
 
         if let Some(new_default) = &field.new_default {
             stmts.push(format!(
-                "ALTER TABLE \"{}\" ALTER COLUMN \"{}\" SET DEFAULT {};",
+                "ALTER TABLE {} ALTER COLUMN \"{}\" SET DEFAULT {};",
                 table, field.column_name, new_default
             ));
         }
@@ -311,12 +977,20 @@ impl PostgresSqlGenerator {
             None => String::new(),
         };
 
-        let cols: Vec<String> = index.columns.iter().map(|c| format!("\"{}\"", c)).collect();
+        let cols: Vec<String> = index
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match index.ops_for(i) {
+                Some(ops) => format!("\"{}\" {}", c, ops),
+                None => format!("\"{}\"", c),
+            })
+            .collect();
         format!(
-            "CREATE {}INDEX \"{}\" ON \"{}\"{}({});",
+            "CREATE {}INDEX \"{}\" ON {}{}({});",
             unique,
             index.name,
-            index.table_name,
+            self.table_ref(index.schema.as_deref(), &index.table_name),
             using_clause,
             cols.join(", ")
         )
@@ -371,9 +1045,9 @@ impl PostgresSqlGenerator {
         };
 
         format!(
-            "CREATE INDEX \"{}\" ON \"{}\" USING {} ({}){};",
+            "CREATE INDEX \"{}\" ON {} USING {} ({}){};",
             index.name,
-            index.table_name,
+            self.table_ref(index.schema.as_deref(), &index.table_name),
             index_type.as_sql(),
             col_expr,
             with_clause
@@ -420,6 +1094,30 @@ impl PostgresSqlGenerator {
     }
 }
 
+/// A generator of migration SQL for one database dialect.
+pub trait MigrationSqlGenerator: Send + Sync {
+    /// Generate SQL for a schema diff.
+    fn generate(&self, diff: &SchemaDiff) -> MigrationSql;
+}
+
+impl MigrationSqlGenerator for PostgresSqlGenerator {
+    fn generate(&self, diff: &SchemaDiff) -> MigrationSql {
+        PostgresSqlGenerator::generate(self, diff)
+    }
+}
+
+impl MigrationSqlGenerator for MySqlGenerator {
+    fn generate(&self, diff: &SchemaDiff) -> MigrationSql {
+        MySqlGenerator::generate(self, diff)
+    }
+}
+
+impl MigrationSqlGenerator for SqliteSqlGenerator {
+    fn generate(&self, diff: &SchemaDiff) -> MigrationSql {
+        SqliteSqlGenerator::generate(self, diff)
+    }
+}
+
 /// Generated SQL for a migration.
 #[derive(Debug, Clone)]
 pub struct MigrationSql {
@@ -437,6 +1135,12 @@ impl MigrationSql {
 }
 
 /// SQL generator for MySQL.
+///
+/// MySQL has no standalone enum types, so enum columns are emulated with an
+/// inline `ENUM(...)` column type built from the enum values in the diff,
+/// and altering an enum restates the columns typed with it. Enums whose
+/// values the diff doesn't carry are stored as `VARCHAR`. Range columns are
+/// stored as two bound columns.
 pub struct MySqlGenerator;
 
 impl MySqlGenerator {
@@ -445,12 +1149,38 @@ impl MySqlGenerator {
         let mut up = Vec::new();
         let mut down = Vec::new();
 
-        // Create enums (MySQL uses ENUM type in column definitions)
-        // Enums in MySQL are defined per-column, not as separate types
+        // Enums in MySQL are defined per-column, not as separate types, so
+        // creating or dropping an enum produces no statements of its own
+        let enums: HashMap<&str, &[String]> = diff
+            .enums
+            .iter()
+            .chain(&diff.create_enums)
+            .map(|e| (e.name.as_str(), e.values.as_slice()))
+            .collect();
 
         // Create models
         for model in &diff.create_models {
-            up.push(self.create_table(model));
+            let mut model = model.clone();
+            split_range_fields(&mut model.fields);
+            for field in &mut model.fields {
+                Self::inline_enum(&mut field.sql_type, &enums);
+            }
+            up.push(self.create_table(&model));
+            for index in &model.indexes {
+                up.push(self.create_index(index));
+            }
+            if let Some(history) = &model.history_table {
+                up.extend(self.create_history(&model, history));
+                down.extend(self.drop_history(history));
+            }
+            if let Some(translation) = &model.translation {
+                up.push(self.create_translations(&model, translation));
+                down.push(self.drop_table(&translation.table_name));
+            }
+            if let Some(tree) = &model.tree {
+                up.extend(self.create_tree(&model, tree));
+                down.extend(self.drop_tree(&model.table_name, tree));
+            }
             down.push(self.drop_table(&model.table_name));
         }
 
@@ -459,9 +1189,32 @@ impl MySqlGenerator {
             up.push(self.drop_table(name));
         }
 
+        // Alter enums by restating the columns typed with them
+        for alter in &diff.alter_enums {
+            if !enums.contains_key(alter.name.as_str()) {
+                continue;
+            }
+            for column in &alter.columns {
+                let mut field = column.field.clone();
+                Self::inline_enum(&mut field.sql_type, &enums);
+                up.push(self.modify_column(&column.table_name, &field));
+            }
+        }
+
         // Alter models
         for alter in &diff.alter_models {
-            up.extend(self.alter_table(alter));
+            let mut alter = alter.clone();
+            split_range_fields(&mut alter.add_fields);
+            for field in &mut alter.add_fields {
+                Self::inline_enum(&mut field.sql_type, &enums);
+            }
+            for field in &mut alter.alter_fields {
+                Self::inline_enum(&mut field.column.sql_type, &enums);
+            }
+            up.extend(self.alter_table(&alter));
+            up.extend(self.alter_history(&alter));
+            up.extend(self.alter_tree(&alter));
+            up.extend(self.alter_translations(&alter));
         }
 
         // Create indexes
@@ -498,6 +1251,18 @@ impl MySqlGenerator {
         }
     }
 
+    /// Replace an enum type reference, quoted or not, with an inline
+    /// `ENUM(...)` type.
+    fn inline_enum(sql_type: &mut String, enums: &HashMap<&str, &[String]>) {
+        if let Some(values) = enums.get(sql_type.trim_matches('"')) {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| format!("'{}'", v.replace('\'', "''")))
+                .collect();
+            *sql_type = format!("ENUM({})", values.join(", "));
+        }
+    }
+
     /// Generate CREATE TABLE statement.
     fn create_table(&self, model: &ModelDiff) -> String {
         let mut columns = Vec::new();
@@ -534,35 +1299,51 @@ impl MySqlGenerator {
         )
     }
 
-    /// Generate column definition for MySQL.
-    fn column_definition(&self, field: &FieldDiff) -> String {
-        let mut parts = vec![format!("`{}`", field.column_name)];
-
-        // MySQL type mapping
-        let sql_type = match field.sql_type.as_str() {
-            "INTEGER" if field.is_auto_increment => "INT AUTO_INCREMENT".to_string(),
+    /// Map a generated SQL type to its MySQL equivalent.
+    fn mysql_type(sql_type: &str) -> String {
+        match sql_type {
             "INTEGER" => "INT".to_string(),
-            "BIGINT" if field.is_auto_increment => "BIGINT AUTO_INCREMENT".to_string(),
             "TEXT" => "VARCHAR(255)".to_string(), // Default length for VARCHAR
             "DOUBLE PRECISION" => "DOUBLE".to_string(),
-            "TIMESTAMP WITH TIME ZONE" => "DATETIME".to_string(),
+            "DECIMAL" => "DECIMAL(65,30)".to_string(),
+            "TIMESTAMP WITH TIME ZONE" => "DATETIME(3)".to_string(),
             "BOOLEAN" => "TINYINT(1)".to_string(),
             "BYTEA" => "BLOB".to_string(),
             "JSONB" | "JSON" => "JSON".to_string(),
+            "UUID" => "CHAR(36)".to_string(),
+            // Addresses as text (see `prax_query::network`)
+            "INET" | "CIDR" => "VARCHAR(36)".to_string(),
+            "MACADDR" => "CHAR(17)".to_string(),
+            // Enums without known values in this diff
+            other if other.starts_with('"') => "VARCHAR(255)".to_string(),
             other => other.to_string(),
-        };
-        parts.push(sql_type);
+        }
+    }
+
+    /// Generate column definition for MySQL.
+    fn column_definition(&self, field: &FieldDiff) -> String {
+        let sql_type = Self::mysql_type(&field.sql_type);
+        let mut parts = vec![format!("`{}`", field.column_name), sql_type.clone()];
 
         if !field.nullable && !field.is_primary_key {
             parts.push("NOT NULL".to_string());
         }
 
-        if field.is_unique && !field.is_primary_key {
-            parts.push("UNIQUE".to_string());
+        if let Some(default) = &field.default {
+            // BLOB and JSON columns only accept expression defaults
+            if matches!(sql_type.as_str(), "BLOB" | "JSON") {
+                parts.push(format!("DEFAULT ({})", default));
+            } else {
+                parts.push(format!("DEFAULT {}", default));
+            }
         }
 
-        if let Some(default) = &field.default {
-            parts.push(format!("DEFAULT {}", default));
+        if field.is_auto_increment && matches!(sql_type.as_str(), "INT" | "BIGINT") {
+            parts.push("AUTO_INCREMENT".to_string());
+        }
+
+        if field.is_unique && !field.is_primary_key {
+            parts.push("UNIQUE".to_string());
         }
 
         parts.join(" ")
@@ -573,15 +1354,419 @@ impl MySqlGenerator {
         format!("DROP TABLE IF EXISTS `{}`;", name)
     }
 
-    /// Generate ALTER TABLE statements.
-    fn alter_table(&self, alter: &ModelAlterDiff) -> Vec<String> {
-        let mut stmts = Vec::new();
+    /// Generate the history table and triggers of a `@@versioned` model.
+    fn create_history(&self, model: &ModelDiff, history: &str) -> Vec<String> {
+        let mut columns = vec![format!(
+            "`{}` BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY",
+            HISTORY_VERSION_ID
+        )];
+        for field in &model.fields {
+            columns.push(self.column_definition(&history_field(field)));
+        }
+        columns.push(format!("`{}` DATETIME(6) NOT NULL", HISTORY_VALID_FROM));
+        columns.push(format!("`{}` DATETIME(6) NULL", HISTORY_VALID_TO));
+        columns.push(format!("`{}` VARCHAR(6) NOT NULL", HISTORY_OPERATION));
 
-        // Add columns
-        for field in &alter.add_fields {
-            stmts.push(format!(
-                "ALTER TABLE `{}` ADD COLUMN {};",
-                alter.table_name,
+        let mut key: Vec<String> = model
+            .primary_key_columns()
+            .iter()
+            .map(|c| format!("`{}`", c))
+            .collect();
+        key.push(format!("`{}`", HISTORY_VALID_FROM));
+        columns.push(format!("INDEX `{}_key_idx` ({})", history, key.join(", ")));
+
+        let mut stmts = vec![format!(
+            "CREATE TABLE `{}` (\n    {}\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;",
+            history,
+            columns.join(",\n    ")
+        )];
+        stmts.extend(self.history_triggers(model, history));
+        stmts
+    }
+
+    /// Names of the triggers recording row versions into a history table.
+    fn history_trigger_names(history: &str) -> [String; 5] {
+        ["insert", "update_close", "update", "delete_close", "delete"]
+            .map(|event| format!("{}_{}", history, event))
+    }
+
+    /// Generate the triggers recording row versions into the history table.
+    ///
+    /// Every change closes the open version of the row and, except for deletes,
+    /// records the new one. A delete leaves a zero-length tombstone version.
+    /// MySQL triggers fire for a single event and migrations are applied
+    /// statement by statement, so each step is its own single-statement
+    /// trigger, ordered with `FOLLOWS`.
+    fn history_triggers(&self, model: &ModelDiff, history: &str) -> Vec<String> {
+        let key = model.primary_key_columns();
+        if key.is_empty() {
+            return vec![format!(
+                "-- Cannot record history for `{}`: the table has no primary key",
+                model.table_name
+            )];
+        }
+
+        let table = &model.table_name;
+        let columns: Vec<String> = model
+            .fields
+            .iter()
+            .map(|f| format!("`{}`", f.column_name))
+            .collect();
+        let insert = |row: &str, valid_to: &str, operation: &str| -> String {
+            let values: Vec<String> = columns.iter().map(|c| format!("{}.{}", row, c)).collect();
+            format!(
+                "INSERT INTO `{}` ({}, `{}`, `{}`, `{}`) VALUES ({}, CURRENT_TIMESTAMP(6), {}, '{}')",
+                history,
+                columns.join(", "),
+                HISTORY_VALID_FROM,
+                HISTORY_VALID_TO,
+                HISTORY_OPERATION,
+                values.join(", "),
+                valid_to,
+                operation
+            )
+        };
+        let key_match: Vec<String> = key
+            .iter()
+            .map(|c| format!("`{}` = OLD.`{}`", c, c))
+            .collect();
+        let close = format!(
+            "UPDATE `{}` SET `{}` = CURRENT_TIMESTAMP(6) WHERE {} AND `{}` IS NULL",
+            history,
+            HISTORY_VALID_TO,
+            key_match.join(" AND "),
+            HISTORY_VALID_TO
+        );
+
+        let [on_insert, update_close, on_update, delete_close, on_delete] =
+            Self::history_trigger_names(history);
+        let mut stmts: Vec<String> = Self::history_trigger_names(history)
+            .iter()
+            .map(|name| format!("DROP TRIGGER IF EXISTS `{}`;", name))
+            .collect();
+        stmts.extend([
+            format!(
+                "CREATE TRIGGER `{}` AFTER INSERT ON `{}` FOR EACH ROW\n{};",
+                on_insert,
+                table,
+                insert("NEW", "NULL", "INSERT")
+            ),
+            format!(
+                "CREATE TRIGGER `{}` AFTER UPDATE ON `{}` FOR EACH ROW\n{};",
+                update_close, table, close
+            ),
+            format!(
+                "CREATE TRIGGER `{}` AFTER UPDATE ON `{}` FOR EACH ROW FOLLOWS `{}`\n{};",
+                on_update,
+                table,
+                update_close,
+                insert("NEW", "NULL", "UPDATE")
+            ),
+            format!(
+                "CREATE TRIGGER `{}` AFTER DELETE ON `{}` FOR EACH ROW\n{};",
+                delete_close, table, close
+            ),
+            format!(
+                "CREATE TRIGGER `{}` AFTER DELETE ON `{}` FOR EACH ROW FOLLOWS `{}`\n{};",
+                on_delete,
+                table,
+                delete_close,
+                insert("OLD", "CURRENT_TIMESTAMP(6)", "DELETE")
+            ),
+        ]);
+        stmts
+    }
+
+    /// Generate statements removing the history table of a model.
+    fn drop_history(&self, history: &str) -> Vec<String> {
+        let mut stmts: Vec<String> = Self::history_trigger_names(history)
+            .iter()
+            .map(|name| format!("DROP TRIGGER IF EXISTS `{}`;", name))
+            .collect();
+        stmts.push(self.drop_table(history));
+        stmts
+    }
+
+    /// Generate statements keeping the history of a `@@versioned` model in step
+    /// with an altered table.
+    ///
+    /// Dropped columns are kept in the history table so old versions stay readable.
+    fn alter_history(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        for change in &alter.history {
+            if let HistoryDiff::Drop(history) = change {
+                stmts.extend(self.drop_history(history));
+            }
+        }
+
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+        let Some(history) = &target.history_table else {
+            return stmts;
+        };
+
+        let created = alter
+            .history
+            .contains(&HistoryDiff::Create(history.clone()));
+        if created {
+            stmts.extend(self.create_history(target, history));
+        } else if !alter.add_fields.is_empty()
+            || !alter.drop_fields.is_empty()
+            || !alter.alter_fields.is_empty()
+        {
+            for field in &alter.add_fields {
+                stmts.push(format!(
+                    "ALTER TABLE `{}` ADD COLUMN {};",
+                    history,
+                    self.column_definition(&history_field(field))
+                ));
+            }
+            for field in &alter.alter_fields {
+                let field = FieldAlterDiff {
+                    new_nullable: None,
+                    new_default: None,
+                    column: history_field(&field.column),
+                    ..field.clone()
+                };
+                stmts.extend(self.alter_column(history, &field));
+            }
+            stmts.extend(self.history_triggers(target, history));
+        }
+
+        stmts
+    }
+
+    /// Generate the translation table of a model with `@translatable` fields.
+    fn create_translations(&self, model: &ModelDiff, translation: &TranslationDiff) -> String {
+        let table = &translation.table_name;
+        let Some((key, fields)) = translation_fields(model, translation) else {
+            return format!(
+                "-- Cannot create `{}`: `{}` has no column `{}`",
+                table, model.table_name, translation.key_column
+            );
+        };
+        let mut columns = vec![self.column_definition(&key)];
+        columns.push(format!("`{}` VARCHAR(35) NOT NULL", TRANSLATION_LOCALE));
+        columns.extend(fields.iter().map(|f| self.column_definition(f)));
+        columns.push(format!(
+            "PRIMARY KEY (`{}`, `{}`)",
+            translation.key_column, TRANSLATION_LOCALE
+        ));
+        columns.push(format!(
+            "CONSTRAINT `{}_{}_fkey` FOREIGN KEY (`{}`) REFERENCES `{}`(`{}`) ON DELETE CASCADE",
+            table,
+            translation.key_column,
+            translation.key_column,
+            model.table_name,
+            translation.key_column
+        ));
+        format!(
+            "CREATE TABLE `{}` (\n    {}\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;",
+            table,
+            columns.join(",\n    ")
+        )
+    }
+
+    /// Generate statements creating, dropping or changing the columns of the
+    /// translation table of an altered model.
+    fn alter_translations(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+
+        for change in &alter.translation {
+            match change {
+                TranslationChange::Drop(translation) => {
+                    stmts.push(self.drop_table(&translation.table_name));
+                }
+                TranslationChange::Create(translation) => {
+                    stmts.push(self.create_translations(target, translation));
+                }
+                TranslationChange::AddColumn(column) => {
+                    let (Some(translation), Some(field)) = (
+                        &target.translation,
+                        target.fields.iter().find(|f| &f.column_name == column),
+                    ) else {
+                        continue;
+                    };
+                    stmts.push(format!(
+                        "ALTER TABLE `{}` ADD COLUMN {};",
+                        translation.table_name,
+                        self.column_definition(&history_field(field))
+                    ));
+                }
+                TranslationChange::DropColumn(column) => {
+                    if let Some(translation) = &target.translation {
+                        stmts.push(format!(
+                            "ALTER TABLE `{}` DROP COLUMN `{}`;",
+                            translation.table_name, column
+                        ));
+                    }
+                }
+            }
+        }
+
+        stmts
+    }
+
+    /// Generate the closure table and insert trigger of a `@@tree` model.
+    fn create_tree(&self, model: &ModelDiff, tree: &TreeDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+        if let Some(closure) = &tree.closure_table {
+            let Some(fields) = closure_fields(model, tree) else {
+                return vec![format!(
+                    "-- Cannot create `{}`: `{}` has no column `{}`",
+                    closure, model.table_name, tree.key_column
+                )];
+            };
+            let mut columns: Vec<String> =
+                fields.iter().map(|f| self.column_definition(f)).collect();
+            columns.push(format!("`{}` INT NOT NULL", TREE_DEPTH));
+            columns.push(format!(
+                "PRIMARY KEY (`{}`, `{}`)",
+                TREE_ANCESTOR, TREE_DESCENDANT
+            ));
+            columns.push(format!(
+                "INDEX `{}_{}_idx` (`{}`)",
+                closure, TREE_DESCENDANT, TREE_DESCENDANT
+            ));
+            for column in [TREE_ANCESTOR, TREE_DESCENDANT] {
+                columns.push(format!(
+                    "CONSTRAINT `{}_{}_fkey` FOREIGN KEY (`{}`) REFERENCES `{}`(`{}`) ON DELETE CASCADE",
+                    closure, column, column, model.table_name, tree.key_column
+                ));
+            }
+            stmts.push(format!(
+                "CREATE TABLE `{}` (\n    {}\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;",
+                closure,
+                columns.join(",\n    ")
+            ));
+        }
+        stmts.extend(self.tree_trigger(model, tree));
+        stmts
+    }
+
+    /// Generate the trigger maintaining a tree as nodes are inserted.
+    ///
+    /// A closure table gains a row linking the new node to itself and to each
+    /// of its parent's ancestors; a materialized path is its parent's path
+    /// followed by the parent's key.
+    fn tree_trigger(&self, model: &ModelDiff, tree: &TreeDiff) -> Vec<String> {
+        let table = &model.table_name;
+        let trigger = tree.trigger_name(table);
+        let key = &tree.key_column;
+        let parent = &tree.parent_column;
+
+        let create = match &tree.closure_table {
+            Some(closure) => format!(
+                "CREATE TRIGGER `{}` AFTER INSERT ON `{}` FOR EACH ROW\n\
+                 INSERT INTO `{}` (`{}`, `{}`, `{}`) \
+                 SELECT `{}`, NEW.`{}`, `{}` + 1 FROM `{}` WHERE `{}` = NEW.`{}` \
+                 UNION ALL SELECT NEW.`{}`, NEW.`{}`, 0;",
+                trigger,
+                table,
+                closure,
+                TREE_ANCESTOR,
+                TREE_DESCENDANT,
+                TREE_DEPTH,
+                TREE_ANCESTOR,
+                key,
+                TREE_DEPTH,
+                closure,
+                TREE_DESCENDANT,
+                parent,
+                key,
+                key
+            ),
+            None => format!(
+                "CREATE TRIGGER `{}` BEFORE INSERT ON `{}` FOR EACH ROW\n\
+                 SET NEW.`{}` = COALESCE((SELECT CONCAT(p.`{}`, CAST(p.`{}` AS CHAR), '/') FROM `{}` p WHERE p.`{}` = NEW.`{}`), '/');",
+                trigger, table, TREE_PATH, TREE_PATH, key, table, key, parent
+            ),
+        };
+
+        vec![format!("DROP TRIGGER IF EXISTS `{}`;", trigger), create]
+    }
+
+    /// Generate the statement filling a new tree's storage from the parent column.
+    fn backfill_tree(&self, model: &ModelDiff, tree: &TreeDiff) -> String {
+        let table = &model.table_name;
+        let key = &tree.key_column;
+        let parent = &tree.parent_column;
+        match &tree.closure_table {
+            Some(closure) => format!(
+                "INSERT INTO `{closure}` (`{a}`, `{d}`, `{depth}`)\n\
+                 WITH RECURSIVE tree_paths (`{a}`, `{d}`, `{depth}`) AS (\n    \
+                 SELECT `{key}`, `{key}`, 0 FROM `{table}`\n    \
+                 UNION ALL\n    \
+                 SELECT tp.`{a}`, n.`{key}`, tp.`{depth}` + 1 FROM tree_paths tp JOIN `{table}` n ON n.`{parent}` = tp.`{d}`\n\
+                 )\n\
+                 SELECT `{a}`, `{d}`, `{depth}` FROM tree_paths;",
+                a = TREE_ANCESTOR,
+                d = TREE_DESCENDANT,
+                depth = TREE_DEPTH,
+            ),
+            None => format!(
+                "WITH RECURSIVE tree_paths (`{key}`, `{path}`) AS (\n    \
+                 SELECT `{key}`, CAST('/' AS CHAR(768)) FROM `{table}` WHERE `{parent}` IS NULL\n    \
+                 UNION ALL\n    \
+                 SELECT n.`{key}`, CONCAT(tp.`{path}`, CAST(tp.`{key}` AS CHAR), '/') FROM tree_paths tp JOIN `{table}` n ON n.`{parent}` = tp.`{key}`\n\
+                 )\n\
+                 UPDATE `{table}` t JOIN tree_paths ON t.`{key}` = tree_paths.`{key}` SET t.`{path}` = tree_paths.`{path}`;",
+                path = TREE_PATH,
+            ),
+        }
+    }
+
+    /// Generate statements removing the storage and trigger of a tree.
+    fn drop_tree(&self, table: &str, tree: &TreeDiff) -> Vec<String> {
+        let mut stmts = vec![format!(
+            "DROP TRIGGER IF EXISTS `{}`;",
+            tree.trigger_name(table)
+        )];
+        if let Some(closure) = &tree.closure_table {
+            stmts.push(self.drop_table(closure));
+        }
+        stmts
+    }
+
+    /// Generate statements adding, removing or changing the storage of a
+    /// `@@tree` model. A new storage is filled from the parent column.
+    fn alter_tree(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        for change in &alter.tree {
+            if let TreeChange::Drop(tree) = change {
+                stmts.extend(self.drop_tree(&alter.table_name, tree));
+            }
+        }
+
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+        let Some(tree) = &target.tree else {
+            return stmts;
+        };
+        if alter.tree.contains(&TreeChange::Create(tree.clone())) {
+            stmts.extend(self.create_tree(target, tree));
+            stmts.push(self.backfill_tree(target, tree));
+        }
+
+        stmts
+    }
+
+    /// Generate ALTER TABLE statements.
+    fn alter_table(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        // Add columns
+        for field in &alter.add_fields {
+            stmts.push(format!(
+                "ALTER TABLE `{}` ADD COLUMN {};",
+                alter.table_name,
                 self.column_definition(field)
             ));
         }
@@ -599,39 +1784,93 @@ impl MySqlGenerator {
             stmts.extend(self.alter_column(&alter.table_name, field));
         }
 
-        stmts
-    }
+        // Drop indexes first so a changed index can be recreated under
+        // the same name
+        for name in &alter.drop_indexes {
+            stmts.push(self.drop_index(name, &alter.table_name));
+        }
 
-    /// Generate ALTER COLUMN statements.
-    fn alter_column(&self, table: &str, field: &FieldAlterDiff) -> Vec<String> {
-        let mut stmts = Vec::new();
+        // Add indexes
+        for index in &alter.add_indexes {
+            stmts.push(self.create_index(index));
+        }
 
-        if let Some(new_type) = &field.new_type {
+        // Unique constraints are indexes in MySQL, which can neither defer
+        // them nor treat NULLs as equal
+        for name in &alter.drop_unique_constraints {
+            stmts.push(self.drop_index(name, &alter.table_name));
+        }
+
+        for uc in &alter.add_unique_constraints {
+            let cols: Vec<String> = uc.columns.iter().map(|c| format!("`{}`", c)).collect();
             stmts.push(format!(
-                "ALTER TABLE `{}` MODIFY COLUMN `{}` {};",
-                table, field.column_name, new_type
+                "ALTER TABLE `{}` ADD CONSTRAINT `{}` UNIQUE ({});",
+                alter.table_name,
+                uc.name.as_deref().unwrap_or_default(),
+                cols.join(", ")
             ));
         }
 
         stmts
     }
 
+    /// Generate ALTER COLUMN statements.
+    ///
+    /// MySQL changes a column's type or nullability by restating the whole
+    /// column with `MODIFY COLUMN`, which resets whatever it leaves out, so
+    /// the column's type, nullability and default after the change are all
+    /// restated.
+    fn alter_column(&self, table: &str, field: &FieldAlterDiff) -> Vec<String> {
+        if field.new_type.is_none() && field.new_nullable.is_none() && field.new_default.is_none() {
+            return Vec::new();
+        }
+        let column = FieldDiff {
+            default: field
+                .new_default
+                .clone()
+                .or_else(|| field.column.default.clone()),
+            ..field.column.clone()
+        };
+        vec![self.modify_column(table, &column)]
+    }
+
+    /// Restate a column with `MODIFY COLUMN`, leaving its unique index as is.
+    fn modify_column(&self, table: &str, field: &FieldDiff) -> String {
+        let field = FieldDiff {
+            is_unique: false,
+            ..field.clone()
+        };
+        format!(
+            "ALTER TABLE `{}` MODIFY COLUMN {};",
+            table,
+            self.column_definition(&field)
+        )
+    }
+
     /// Generate CREATE INDEX statement.
     fn create_index(&self, index: &IndexDiff) -> String {
-        let unique = if index.unique { "UNIQUE " } else { "" };
+        use prax_schema::ast::IndexType;
 
-        // Handle FULLTEXT index for MySQL
-        let index_type = match &index.index_type {
-            Some(prax_schema::ast::IndexType::FullText) => "FULLTEXT ",
+        // FULLTEXT indexes can't be unique; PostgreSQL-only index types
+        // (GIN, GiST, BRIN, SP-GiST, vector indexes) fall back to a B-tree
+        // index and operator classes are dropped
+        let kind = match &index.index_type {
+            Some(IndexType::FullText) => "FULLTEXT ",
+            _ if index.unique => "UNIQUE ",
+            _ => "",
+        };
+        let using = match &index.index_type {
+            Some(IndexType::Hash) => " USING HASH",
+            Some(IndexType::BTree) => " USING BTREE",
             _ => "",
         };
 
         let cols: Vec<String> = index.columns.iter().map(|c| format!("`{}`", c)).collect();
         format!(
-            "CREATE {}{}INDEX `{}` ON `{}`({});",
-            unique,
-            index_type,
+            "CREATE {}INDEX `{}`{} ON `{}`({});",
+            kind,
             index.name,
+            using,
             index.table_name,
             cols.join(", ")
         )
@@ -659,17 +1898,56 @@ impl MySqlGenerator {
 }
 
 /// SQL generator for SQLite.
-pub struct SqliteGenerator;
-
-impl SqliteGenerator {
+///
+/// SQLite can only add columns in place. Dropping or altering columns, and
+/// adding columns SQLite refuses to add (primary key, unique, or `NOT NULL`
+/// without a default), rebuild the table instead: a new table is created,
+/// the rows are copied over, and it replaces the old one, all inside a
+/// transaction with foreign key enforcement paused.
+///
+/// Enum columns are `TEXT` with a `CHECK` constraint listing the values of
+/// the enums created in the same diff; columns of enums defined by an
+/// earlier migration are plain `TEXT`. Range columns are stored as two bound
+/// columns.
+pub struct SqliteSqlGenerator;
+
+impl SqliteSqlGenerator {
     /// Generate SQL for a schema diff.
     pub fn generate(&self, diff: &SchemaDiff) -> MigrationSql {
         let mut up = Vec::new();
         let mut down = Vec::new();
 
+        // SQLite has no enum types, so enums produce no statements of their
+        // own and are checked per column
+        let enums: HashMap<&str, &[String]> = diff
+            .create_enums
+            .iter()
+            .map(|e| (e.name.as_str(), e.values.as_slice()))
+            .collect();
+
         // Create models
         for model in &diff.create_models {
-            up.push(self.create_table(model));
+            let mut model = model.clone();
+            split_range_fields(&mut model.fields);
+            for field in &mut model.fields {
+                Self::inline_enum(field, &enums);
+            }
+            up.push(self.create_table(&model));
+            for index in &model.indexes {
+                up.push(self.create_index(index));
+            }
+            if let Some(history) = &model.history_table {
+                up.extend(self.create_history(&model, history));
+                down.push(self.drop_table(history));
+            }
+            if let Some(translation) = &model.translation {
+                up.push(self.create_translations(&model, translation));
+                down.push(self.drop_table(&translation.table_name));
+            }
+            if let Some(tree) = &model.tree {
+                up.extend(self.create_tree(&model, tree));
+                down.extend(self.drop_tree(&model.table_name, tree));
+            }
             down.push(self.drop_table(&model.table_name));
         }
 
@@ -678,6 +1956,25 @@ impl SqliteGenerator {
             up.push(self.drop_table(name));
         }
 
+        // Alter models
+        for alter in &diff.alter_models {
+            let mut alter = alter.clone();
+            split_range_fields(&mut alter.add_fields);
+            for field in &mut alter.add_fields {
+                Self::inline_enum(field, &enums);
+            }
+            if let Some(target) = &mut alter.target {
+                split_range_fields(&mut target.fields);
+                for field in &mut target.fields {
+                    Self::inline_enum(field, &enums);
+                }
+            }
+            up.extend(self.alter_table(&alter));
+            up.extend(self.alter_history(&alter));
+            up.extend(self.alter_tree(&alter));
+            up.extend(self.alter_translations(&alter));
+        }
+
         // Create indexes
         for index in &diff.create_indexes {
             up.push(self.create_index(index));
@@ -753,6 +2050,30 @@ impl SqliteGenerator {
         )
     }
 
+    /// Replace an enum type reference with `TEXT` checked against the
+    /// enum's values.
+    fn inline_enum(field: &mut FieldDiff, enums: &HashMap<&str, &[String]>) {
+        let Some(name) = field
+            .sql_type
+            .strip_prefix('"')
+            .and_then(|t| t.strip_suffix('"'))
+        else {
+            return;
+        };
+
+        if let Some(values) = enums.get(name) {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| format!("'{}'", v.replace('\'', "''")))
+                .collect();
+            field.sql_type = format!(
+                "TEXT CHECK (\"{}\" IN ({}))",
+                field.column_name,
+                values.join(", ")
+            );
+        }
+    }
+
     /// Generate column definition for SQLite.
     fn column_definition(&self, field: &FieldDiff) -> String {
         let mut parts = vec![format!("\"{}\"", field.column_name)];
@@ -770,6 +2091,9 @@ impl SqliteGenerator {
             "BOOLEAN" => "INTEGER".to_string(),
             "BYTEA" | "BLOB" => "BLOB".to_string(),
             "JSONB" | "JSON" => "TEXT".to_string(), // SQLite stores JSON as TEXT
+            "INET" | "CIDR" | "MACADDR" => "TEXT".to_string(),
+            // Enums without known values in this diff
+            other if other.starts_with('"') => "TEXT".to_string(),
             other => other.to_string(),
         };
         parts.push(sql_type);
@@ -794,6 +2118,508 @@ impl SqliteGenerator {
         format!("DROP TABLE IF EXISTS \"{}\";", name)
     }
 
+    /// Generate the history table and triggers of a `@@versioned` model.
+    fn create_history(&self, model: &ModelDiff, history: &str) -> Vec<String> {
+        let mut columns = vec![format!(
+            "\"{}\" INTEGER PRIMARY KEY AUTOINCREMENT",
+            HISTORY_VERSION_ID
+        )];
+        for field in &model.fields {
+            columns.push(self.column_definition(&history_field(field)));
+        }
+        columns.push(format!("\"{}\" TEXT NOT NULL", HISTORY_VALID_FROM));
+        columns.push(format!("\"{}\" TEXT", HISTORY_VALID_TO));
+        columns.push(format!("\"{}\" TEXT NOT NULL", HISTORY_OPERATION));
+
+        let mut key: Vec<String> = model
+            .primary_key_columns()
+            .iter()
+            .map(|c| format!("\"{}\"", c))
+            .collect();
+        key.push(format!("\"{}\"", HISTORY_VALID_FROM));
+
+        let mut stmts = vec![
+            format!(
+                "CREATE TABLE \"{}\" (\n    {}\n);",
+                history,
+                columns.join(",\n    ")
+            ),
+            format!(
+                "CREATE INDEX \"{}_key_idx\" ON \"{}\"({});",
+                history,
+                history,
+                key.join(", ")
+            ),
+        ];
+        stmts.extend(self.history_triggers(model, history));
+        stmts
+    }
+
+    /// Generate the triggers recording row versions into the history table.
+    ///
+    /// Every change closes the open version of the row and, except for deletes,
+    /// records the new one. A delete leaves a zero-length tombstone version.
+    /// Timestamps are stored as ISO 8601 text so they compare in order.
+    fn history_triggers(&self, model: &ModelDiff, history: &str) -> Vec<String> {
+        let key = model.primary_key_columns();
+        if key.is_empty() {
+            return vec![format!(
+                "-- Cannot record history for \"{}\": the table has no primary key",
+                model.table_name
+            )];
+        }
+
+        const NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+        let table = &model.table_name;
+        let columns: Vec<String> = model
+            .fields
+            .iter()
+            .map(|f| format!("\"{}\"", f.column_name))
+            .collect();
+        let insert = |row: &str, valid_to: &str, operation: &str| -> String {
+            let values: Vec<String> = columns.iter().map(|c| format!("{}.{}", row, c)).collect();
+            format!(
+                "INSERT INTO \"{}\" ({}, \"{}\", \"{}\", \"{}\") VALUES ({}, {}, {}, '{}');",
+                history,
+                columns.join(", "),
+                HISTORY_VALID_FROM,
+                HISTORY_VALID_TO,
+                HISTORY_OPERATION,
+                values.join(", "),
+                NOW,
+                valid_to,
+                operation
+            )
+        };
+        let key_match: Vec<String> = key
+            .iter()
+            .map(|c| format!("\"{}\" = OLD.\"{}\"", c, c))
+            .collect();
+        let close = format!(
+            "UPDATE \"{}\" SET \"{}\" = {} WHERE {} AND \"{}\" IS NULL;",
+            history,
+            HISTORY_VALID_TO,
+            NOW,
+            key_match.join(" AND "),
+            HISTORY_VALID_TO
+        );
+
+        vec![
+            format!("DROP TRIGGER IF EXISTS \"{}_insert\";", history),
+            format!("DROP TRIGGER IF EXISTS \"{}_update\";", history),
+            format!("DROP TRIGGER IF EXISTS \"{}_delete\";", history),
+            format!(
+                "CREATE TRIGGER \"{}_insert\" AFTER INSERT ON \"{}\" FOR EACH ROW BEGIN\n    {}\nEND;",
+                history,
+                table,
+                insert("NEW", "NULL", "INSERT")
+            ),
+            format!(
+                "CREATE TRIGGER \"{}_update\" AFTER UPDATE ON \"{}\" FOR EACH ROW BEGIN\n    {}\n    {}\nEND;",
+                history,
+                table,
+                close,
+                insert("NEW", "NULL", "UPDATE")
+            ),
+            format!(
+                "CREATE TRIGGER \"{}_delete\" AFTER DELETE ON \"{}\" FOR EACH ROW BEGIN\n    {}\n    {}\nEND;",
+                history,
+                table,
+                close,
+                insert("OLD", NOW, "DELETE")
+            ),
+        ]
+    }
+
+    /// Generate statements keeping the history of a `@@versioned` model in step
+    /// with an altered table.
+    ///
+    /// Dropped columns are kept in the history table so old versions stay
+    /// readable. Triggers are recreated whenever columns change, since a table
+    /// rebuild drops them.
+    fn alter_history(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        for change in &alter.history {
+            if let HistoryDiff::Drop(history) = change {
+                for event in ["insert", "update", "delete"] {
+                    stmts.push(format!("DROP TRIGGER IF EXISTS \"{}_{}\";", history, event));
+                }
+                stmts.push(self.drop_table(history));
+            }
+        }
+
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+        let Some(history) = &target.history_table else {
+            return stmts;
+        };
+
+        let created = alter
+            .history
+            .contains(&HistoryDiff::Create(history.clone()));
+        if created {
+            stmts.extend(self.create_history(target, history));
+        } else if !alter.add_fields.is_empty()
+            || !alter.drop_fields.is_empty()
+            || !alter.alter_fields.is_empty()
+        {
+            // Column types are only affinities in SQLite, so only new columns matter
+            for field in &alter.add_fields {
+                stmts.push(format!(
+                    "ALTER TABLE \"{}\" ADD COLUMN {};",
+                    history,
+                    self.column_definition(&history_field(field))
+                ));
+            }
+            stmts.extend(self.history_triggers(target, history));
+        }
+
+        stmts
+    }
+
+    /// Generate the translation table of a model with `@translatable` fields.
+    fn create_translations(&self, model: &ModelDiff, translation: &TranslationDiff) -> String {
+        let table = &translation.table_name;
+        let Some((key, fields)) = translation_fields(model, translation) else {
+            return format!(
+                "-- Cannot create \"{}\": \"{}\" has no column \"{}\"",
+                table, model.table_name, translation.key_column
+            );
+        };
+        let mut columns = vec![format!(
+            "{} REFERENCES \"{}\"(\"{}\") ON DELETE CASCADE",
+            self.column_definition(&key),
+            model.table_name,
+            translation.key_column
+        )];
+        columns.push(format!("\"{}\" VARCHAR(35) NOT NULL", TRANSLATION_LOCALE));
+        columns.extend(fields.iter().map(|f| self.column_definition(f)));
+        columns.push(format!(
+            "PRIMARY KEY (\"{}\", \"{}\")",
+            translation.key_column, TRANSLATION_LOCALE
+        ));
+        format!(
+            "CREATE TABLE \"{}\" (\n    {}\n);",
+            table,
+            columns.join(",\n    ")
+        )
+    }
+
+    /// Generate statements creating, dropping or changing the columns of the
+    /// translation table of an altered model.
+    fn alter_translations(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+
+        for change in &alter.translation {
+            match change {
+                TranslationChange::Drop(translation) => {
+                    stmts.push(self.drop_table(&translation.table_name));
+                }
+                TranslationChange::Create(translation) => {
+                    stmts.push(self.create_translations(target, translation));
+                }
+                TranslationChange::AddColumn(column) => {
+                    let (Some(translation), Some(field)) = (
+                        &target.translation,
+                        target.fields.iter().find(|f| &f.column_name == column),
+                    ) else {
+                        continue;
+                    };
+                    stmts.push(format!(
+                        "ALTER TABLE \"{}\" ADD COLUMN {};",
+                        translation.table_name,
+                        self.column_definition(&history_field(field))
+                    ));
+                }
+                TranslationChange::DropColumn(column) => {
+                    if let Some(translation) = &target.translation {
+                        stmts.push(format!(
+                            "ALTER TABLE \"{}\" DROP COLUMN \"{}\";",
+                            translation.table_name, column
+                        ));
+                    }
+                }
+            }
+        }
+
+        stmts
+    }
+
+    /// Generate the closure table and insert trigger of a `@@tree` model.
+    fn create_tree(&self, model: &ModelDiff, tree: &TreeDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+        if let Some(closure) = &tree.closure_table {
+            let Some(fields) = closure_fields(model, tree) else {
+                return vec![format!(
+                    "-- Cannot create \"{}\": \"{}\" has no column \"{}\"",
+                    closure, model.table_name, tree.key_column
+                )];
+            };
+            let mut columns: Vec<String> = fields
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{} REFERENCES \"{}\"(\"{}\") ON DELETE CASCADE",
+                        self.column_definition(f),
+                        model.table_name,
+                        tree.key_column
+                    )
+                })
+                .collect();
+            columns.push(format!("\"{}\" INTEGER NOT NULL", TREE_DEPTH));
+            columns.push(format!(
+                "PRIMARY KEY (\"{}\", \"{}\")",
+                TREE_ANCESTOR, TREE_DESCENDANT
+            ));
+            stmts.push(format!(
+                "CREATE TABLE \"{}\" (\n    {}\n);",
+                closure,
+                columns.join(",\n    ")
+            ));
+            stmts.push(format!(
+                "CREATE INDEX \"{}_{}_idx\" ON \"{}\"(\"{}\");",
+                closure, TREE_DESCENDANT, closure, TREE_DESCENDANT
+            ));
+        }
+        stmts.extend(self.tree_trigger(model, tree));
+        stmts
+    }
+
+    /// Generate the trigger maintaining a tree as nodes are inserted.
+    ///
+    /// A closure table gains a row linking the new node to itself and to each
+    /// of its parent's ancestors; a materialized path is its parent's path
+    /// followed by the parent's key. SQLite triggers can't assign to `NEW`,
+    /// so the path is set by an update once the row exists.
+    fn tree_trigger(&self, model: &ModelDiff, tree: &TreeDiff) -> Vec<String> {
+        let table = &model.table_name;
+        let trigger = tree.trigger_name(table);
+        let key = &tree.key_column;
+        let parent = &tree.parent_column;
+
+        let body = match &tree.closure_table {
+            Some(closure) => format!(
+                "INSERT INTO \"{}\" (\"{}\", \"{}\", \"{}\") \
+                 SELECT \"{}\", NEW.\"{}\", \"{}\" + 1 FROM \"{}\" WHERE \"{}\" = NEW.\"{}\" \
+                 UNION ALL SELECT NEW.\"{}\", NEW.\"{}\", 0;",
+                closure,
+                TREE_ANCESTOR,
+                TREE_DESCENDANT,
+                TREE_DEPTH,
+                TREE_ANCESTOR,
+                key,
+                TREE_DEPTH,
+                closure,
+                TREE_DESCENDANT,
+                parent,
+                key,
+                key
+            ),
+            None => format!(
+                "UPDATE \"{}\" SET \"{}\" = COALESCE((SELECT p.\"{}\" || CAST(p.\"{}\" AS TEXT) || '/' FROM \"{}\" p WHERE p.\"{}\" = NEW.\"{}\"), '/') WHERE \"{}\" = NEW.\"{}\";",
+                table, TREE_PATH, TREE_PATH, key, table, key, parent, key, key
+            ),
+        };
+
+        vec![
+            format!("DROP TRIGGER IF EXISTS \"{}\";", trigger),
+            format!(
+                "CREATE TRIGGER \"{}\" AFTER INSERT ON \"{}\" FOR EACH ROW BEGIN\n    {}\nEND;",
+                trigger, table, body
+            ),
+        ]
+    }
+
+    /// Generate the statement filling a new tree's storage from the parent column.
+    fn backfill_tree(&self, model: &ModelDiff, tree: &TreeDiff) -> String {
+        let table = &model.table_name;
+        let key = &tree.key_column;
+        let parent = &tree.parent_column;
+        match &tree.closure_table {
+            Some(closure) => format!(
+                "WITH RECURSIVE tree_paths (\"{a}\", \"{d}\", \"{depth}\") AS (\n    \
+                 SELECT \"{key}\", \"{key}\", 0 FROM \"{table}\"\n    \
+                 UNION ALL\n    \
+                 SELECT tp.\"{a}\", n.\"{key}\", tp.\"{depth}\" + 1 FROM tree_paths tp JOIN \"{table}\" n ON n.\"{parent}\" = tp.\"{d}\"\n\
+                 )\n\
+                 INSERT INTO \"{closure}\" (\"{a}\", \"{d}\", \"{depth}\") SELECT \"{a}\", \"{d}\", \"{depth}\" FROM tree_paths;",
+                a = TREE_ANCESTOR,
+                d = TREE_DESCENDANT,
+                depth = TREE_DEPTH,
+            ),
+            None => format!(
+                "WITH RECURSIVE tree_paths (\"{key}\", \"{path}\") AS (\n    \
+                 SELECT \"{key}\", CAST('/' AS TEXT) FROM \"{table}\" WHERE \"{parent}\" IS NULL\n    \
+                 UNION ALL\n    \
+                 SELECT n.\"{key}\", tp.\"{path}\" || CAST(tp.\"{key}\" AS TEXT) || '/' FROM tree_paths tp JOIN \"{table}\" n ON n.\"{parent}\" = tp.\"{key}\"\n\
+                 )\n\
+                 UPDATE \"{table}\" SET \"{path}\" = COALESCE((SELECT tree_paths.\"{path}\" FROM tree_paths WHERE tree_paths.\"{key}\" = \"{table}\".\"{key}\"), \"{path}\");",
+                path = TREE_PATH,
+            ),
+        }
+    }
+
+    /// Generate statements removing the storage and trigger of a tree.
+    fn drop_tree(&self, table: &str, tree: &TreeDiff) -> Vec<String> {
+        let mut stmts = vec![format!(
+            "DROP TRIGGER IF EXISTS \"{}\";",
+            tree.trigger_name(table)
+        )];
+        if let Some(closure) = &tree.closure_table {
+            stmts.push(self.drop_table(closure));
+        }
+        stmts
+    }
+
+    /// Generate statements adding, removing or changing the storage of a
+    /// `@@tree` model. A new storage is filled from the parent column.
+    ///
+    /// The insert trigger is recreated whenever the table is rebuilt, since a
+    /// rebuild drops it.
+    fn alter_tree(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        for change in &alter.tree {
+            if let TreeChange::Drop(tree) = change {
+                stmts.extend(self.drop_tree(&alter.table_name, tree));
+            }
+        }
+
+        let Some(target) = &alter.target else {
+            return stmts;
+        };
+        let Some(tree) = &target.tree else {
+            return stmts;
+        };
+        if alter.tree.contains(&TreeChange::Create(tree.clone())) {
+            stmts.extend(self.create_tree(target, tree));
+            stmts.push(self.backfill_tree(target, tree));
+        } else if Self::requires_rebuild(alter) {
+            stmts.extend(self.tree_trigger(target, tree));
+        }
+
+        stmts
+    }
+
+    /// Generate statements altering a table.
+    fn alter_table(&self, alter: &ModelAlterDiff) -> Vec<String> {
+        let mut stmts = Vec::new();
+
+        if !Self::requires_rebuild(alter) {
+            for field in &alter.add_fields {
+                stmts.push(format!(
+                    "ALTER TABLE \"{}\" ADD COLUMN {};",
+                    alter.table_name,
+                    self.column_definition(field)
+                ));
+            }
+        } else if let Some(target) = &alter.target {
+            // The rebuild drops the old indexes and creates the target's
+            stmts.push(self.rebuild_table(alter, target));
+            return stmts;
+        } else {
+            stmts.push(format!(
+                "-- Cannot rebuild \"{}\": the diff does not include the full table definition",
+                alter.table_name
+            ));
+        }
+
+        for name in &alter.drop_indexes {
+            stmts.push(self.drop_index(name));
+        }
+
+        for index in &alter.add_indexes {
+            stmts.push(self.create_index(index));
+        }
+
+        stmts
+    }
+
+    /// Check if an alteration needs a table rebuild.
+    fn requires_rebuild(alter: &ModelAlterDiff) -> bool {
+        !alter.drop_fields.is_empty()
+            || !alter.alter_fields.is_empty()
+            || !alter.add_unique_constraints.is_empty()
+            || !alter.drop_unique_constraints.is_empty()
+            || alter
+                .add_fields
+                .iter()
+                .any(|f| f.is_primary_key || f.is_unique || (!f.nullable && f.default.is_none()))
+    }
+
+    /// Generate the create-copy-swap sequence rebuilding a table as `target`.
+    ///
+    /// The sequence runs in its own transaction, which the migration engine
+    /// skips when it already runs the migration in one. Foreign keys are
+    /// turned off around it, and the migration fails if the rebuilt table
+    /// leaves rows violating them (`PRAGMA foreign_key_check`).
+    fn rebuild_table(&self, alter: &ModelAlterDiff, target: &ModelDiff) -> String {
+        let table = &target.table_name;
+        let new_table = format!("_prax_new_{}", table);
+
+        let mut new_model = target.clone();
+        new_model.table_name = new_table.clone();
+
+        // Added columns have no data to copy; everything else carries over
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        for field in &target.fields {
+            if alter
+                .add_fields
+                .iter()
+                .any(|f| f.column_name == field.column_name)
+            {
+                continue;
+            }
+
+            let column = format!("\"{}\"", field.column_name);
+            let now_required = alter
+                .alter_fields
+                .iter()
+                .any(|a| a.column_name == field.column_name && a.new_nullable == Some(false));
+            let value = match &field.default {
+                Some(default) if now_required => format!("COALESCE({}, {})", column, default),
+                _ => column.clone(),
+            };
+            columns.push(column);
+            values.push(value);
+        }
+
+        let mut stmts = vec![
+            "PRAGMA foreign_keys = OFF;".to_string(),
+            "BEGIN TRANSACTION;".to_string(),
+            self.create_table(&new_model),
+        ];
+        if !columns.is_empty() {
+            stmts.push(format!(
+                "INSERT INTO \"{}\" ({}) SELECT {} FROM \"{}\";",
+                new_table,
+                columns.join(", "),
+                values.join(", "),
+                table
+            ));
+        }
+        stmts.push(format!("DROP TABLE \"{}\";", table));
+        stmts.push(format!(
+            "ALTER TABLE \"{}\" RENAME TO \"{}\";",
+            new_table, table
+        ));
+        // Indexes are dropped with the old table
+        for index in &target.indexes {
+            stmts.push(self.create_index(index));
+        }
+        stmts.push("PRAGMA foreign_key_check;".to_string());
+        stmts.push("COMMIT;".to_string());
+        stmts.push("PRAGMA foreign_keys = ON;".to_string());
+
+        stmts.join("\n")
+    }
+
     /// Generate CREATE INDEX statement.
     fn create_index(&self, index: &IndexDiff) -> String {
         let unique = if index.unique { "UNIQUE " } else { "" };
@@ -827,6 +2653,8 @@ impl SqliteGenerator {
 }
 
 /// SQL generator for Microsoft SQL Server.
+///
+/// Range columns are stored as two bound columns.
 pub struct MssqlGenerator;
 
 impl MssqlGenerator {
@@ -835,10 +2663,27 @@ impl MssqlGenerator {
         let mut up = Vec::new();
         let mut down = Vec::new();
 
-        // Create models
+        // Create schemas first; they are left in place on the way down
+        for schema in &diff.create_schemas {
+            up.push(format!(
+                "IF NOT EXISTS (SELECT * FROM sys.schemas WHERE name = N'{}')\n    EXEC('CREATE SCHEMA [{}]');",
+                schema.replace('\'', "''"),
+                schema.replace('\'', "''")
+            ));
+        }
+
+        // Create models, storing ranges in two bound columns
         for model in &diff.create_models {
-            up.push(self.create_table(model));
-            down.push(self.drop_table(&model.table_name));
+            let mut model = model.clone();
+            split_range_fields(&mut model.fields);
+            up.push(self.create_table(&model));
+            for index in &model.indexes {
+                up.push(self.create_index(index));
+            }
+            down.push(format!(
+                "DROP TABLE IF EXISTS {};",
+                self.table_ref(model.schema.as_deref(), &model.table_name)
+            ));
         }
 
         // Drop models
@@ -848,18 +2693,22 @@ impl MssqlGenerator {
 
         // Alter models
         for alter in &diff.alter_models {
-            up.extend(self.alter_table(alter));
+            let mut alter = alter.clone();
+            split_range_fields(&mut alter.add_fields);
+            up.extend(self.alter_table(&alter));
         }
 
         // Create indexes
         for index in &diff.create_indexes {
             up.push(self.create_index(index));
-            down.push(self.drop_index(&index.name, &index.table_name));
+            let table = self.table_ref(index.schema.as_deref(), &index.table_name);
+            down.push(self.drop_index(&index.name, &table));
         }
 
         // Drop indexes
         for index in &diff.drop_indexes {
-            up.push(self.drop_index(&index.name, &index.table_name));
+            let table = self.table_ref(index.schema.as_deref(), &index.table_name);
+            up.push(self.drop_index(&index.name, &table));
         }
 
         // Create views (after tables they depend on)
@@ -913,12 +2762,20 @@ impl MssqlGenerator {
         }
 
         format!(
-            "CREATE TABLE [{}] (\n    {}\n);",
-            model.table_name,
+            "CREATE TABLE {} (\n    {}\n);",
+            self.table_ref(model.schema.as_deref(), &model.table_name),
             columns.join(",\n    ")
         )
     }
 
+    /// Quote a table name, qualified with its schema when it has one.
+    fn table_ref(&self, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            Some(schema) => format!("[{}].[{}]", schema, table),
+            None => format!("[{}]", table),
+        }
+    }
+
     /// Generate column definition for MSSQL.
     fn column_definition(&self, field: &FieldDiff) -> String {
         let mut parts = vec![format!("[{}]", field.column_name)];
@@ -935,6 +2792,8 @@ impl MssqlGenerator {
             "JSONB" | "JSON" => "NVARCHAR(MAX)".to_string(), // MSSQL 2016+ has JSON support
             "UUID" => "UNIQUEIDENTIFIER".to_string(),
             "DECIMAL" => "DECIMAL(18,2)".to_string(),
+            "INET" | "CIDR" => "VARCHAR(36)".to_string(),
+            "MACADDR" => "CHAR(17)".to_string(),
             other => other.to_string(),
         };
         parts.push(sql_type);
@@ -966,39 +2825,63 @@ impl MssqlGenerator {
     /// Generate ALTER TABLE statements.
     fn alter_table(&self, alter: &ModelAlterDiff) -> Vec<String> {
         let mut stmts = Vec::new();
+        let table = self.table_ref(alter.schema.as_deref(), &alter.table_name);
 
         // Add columns
         for field in &alter.add_fields {
             stmts.push(format!(
-                "ALTER TABLE [{}] ADD {};",
-                alter.table_name,
+                "ALTER TABLE {} ADD {};",
+                table,
                 self.column_definition(field)
             ));
         }
 
         // Drop columns
         for name in &alter.drop_fields {
-            stmts.push(format!(
-                "ALTER TABLE [{}] DROP COLUMN [{}];",
-                alter.table_name, name
-            ));
+            stmts.push(format!("ALTER TABLE {} DROP COLUMN [{}];", table, name));
         }
 
         // Alter columns
         for field in &alter.alter_fields {
-            stmts.extend(self.alter_column(&alter.table_name, field));
+            stmts.extend(self.alter_column(&table, field));
+        }
+
+        // Drop indexes
+        for name in &alter.drop_indexes {
+            stmts.push(self.drop_index(name, &table));
+        }
+
+        // Add indexes
+        for index in &alter.add_indexes {
+            stmts.push(self.create_index(index));
+        }
+
+        // SQL Server unique constraints already treat NULLs as equal and
+        // can't be deferred
+        for name in &alter.drop_unique_constraints {
+            stmts.push(format!("ALTER TABLE {} DROP CONSTRAINT [{}];", table, name));
+        }
+
+        for uc in &alter.add_unique_constraints {
+            let cols: Vec<String> = uc.columns.iter().map(|c| format!("[{}]", c)).collect();
+            stmts.push(format!(
+                "ALTER TABLE {} ADD CONSTRAINT [{}] UNIQUE ({});",
+                table,
+                uc.name.as_deref().unwrap_or_default(),
+                cols.join(", ")
+            ));
         }
 
         stmts
     }
 
-    /// Generate ALTER COLUMN statements.
+    /// Generate ALTER COLUMN statements on an already quoted table.
     fn alter_column(&self, table: &str, field: &FieldAlterDiff) -> Vec<String> {
         let mut stmts = Vec::new();
 
         if let Some(new_type) = &field.new_type {
             stmts.push(format!(
-                "ALTER TABLE [{}] ALTER COLUMN [{}] {};",
+                "ALTER TABLE {} ALTER COLUMN [{}] {};",
                 table, field.column_name, new_type
             ));
         }
@@ -1012,14 +2895,17 @@ impl MssqlGenerator {
 
         let cols: Vec<String> = index.columns.iter().map(|c| format!("[{}]", c)).collect();
         format!(
-            "CREATE {}INDEX [{}] ON [{}]({});",
-            unique, index.name, index.table_name, cols.join(", ")
+            "CREATE {}INDEX [{}] ON {}({});",
+            unique,
+            index.name,
+            self.table_ref(index.schema.as_deref(), &index.table_name),
+            cols.join(", ")
         )
     }
 
-    /// Generate DROP INDEX statement.
+    /// Generate DROP INDEX statement on an already quoted table.
     fn drop_index(&self, name: &str, table: &str) -> String {
-        format!("DROP INDEX [{}] ON [{}];", name, table)
+        format!("DROP INDEX [{}] ON {};", name, table)
     }
 
     /// Generate CREATE VIEW statement.
@@ -1059,6 +2945,7 @@ impl MssqlGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use prax_schema::ast::TreeStrategy;
 
     #[test]
     fn test_create_enum() {
@@ -1081,6 +2968,7 @@ mod tests {
         let model = ModelDiff {
             name: "User".to_string(),
             table_name: "users".to_string(),
+            schema: None,
             fields: vec![
                 FieldDiff {
                     name: "id".to_string(),
@@ -1106,6 +2994,10 @@ mod tests {
             primary_key: vec!["id".to_string()],
             indexes: Vec::new(),
             unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: None,
         };
 
         let sql = generator.create_table(&model);
@@ -1117,6 +3009,185 @@ mod tests {
         assert!(sql.contains("PRIMARY KEY"));
     }
 
+    fn versioned_users() -> ModelDiff {
+        let field = |name: &str, sql_type: &str| FieldDiff {
+            name: name.to_string(),
+            column_name: name.to_string(),
+            sql_type: sql_type.to_string(),
+            nullable: false,
+            default: None,
+            is_primary_key: name == "id",
+            is_auto_increment: name == "id",
+            is_unique: name == "email",
+        };
+
+        ModelDiff {
+            name: "User".to_string(),
+            table_name: "users".to_string(),
+            schema: None,
+            fields: vec![field("id", "INTEGER"), field("email", "TEXT")],
+            primary_key: vec!["id".to_string()],
+            indexes: Vec::new(),
+            unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: Some("users_history".to_string()),
+            translation: None,
+            tree: None,
+        }
+    }
+
+    #[test]
+    fn test_create_versioned_table() {
+        let generator = PostgresSqlGenerator;
+        let mut diff = SchemaDiff::default();
+        diff.create_models.push(versioned_users());
+
+        let sql = generator.generate(&diff);
+        assert!(sql.up.contains(
+            "CREATE TABLE \"users_history\" (\n    \"_version_id\" BIGSERIAL PRIMARY KEY,\n    \"id\" INTEGER,\n    \"email\" TEXT,"
+        ));
+        assert!(sql.up.contains(
+            "CREATE INDEX \"users_history_key_idx\" ON \"users_history\"(\"id\", \"_valid_from\");"
+        ));
+        assert!(
+            sql.up
+                .contains("WHERE \"id\" = OLD.\"id\" AND \"_valid_to\" IS NULL;")
+        );
+        assert!(
+            sql.up
+                .contains("VALUES (OLD.\"id\", OLD.\"email\", now(), now(), TG_OP);")
+        );
+        assert!(sql.up.contains(
+            "AFTER INSERT OR UPDATE OR DELETE ON \"users\"\nFOR EACH ROW EXECUTE FUNCTION \"users_history_record\"();"
+        ));
+        assert!(
+            sql.down
+                .contains("DROP FUNCTION IF EXISTS \"users_history_record\"();")
+        );
+    }
+
+    fn tree_categories(strategy: TreeStrategy) -> ModelDiff {
+        let field = |name: &str| FieldDiff {
+            name: name.to_string(),
+            column_name: name.to_string(),
+            sql_type: "INTEGER".to_string(),
+            nullable: name == "parentId",
+            default: None,
+            is_primary_key: name == "id",
+            is_auto_increment: name == "id",
+            is_unique: false,
+        };
+
+        let mut fields = vec![field("id"), field("parentId")];
+        let closure_table = match strategy {
+            TreeStrategy::ClosureTable => Some("categories_closure".to_string()),
+            TreeStrategy::MaterializedPath => {
+                fields.push(TreeDiff::path_field());
+                None
+            }
+        };
+        ModelDiff {
+            name: "Category".to_string(),
+            table_name: "categories".to_string(),
+            schema: None,
+            fields,
+            primary_key: vec!["id".to_string()],
+            indexes: Vec::new(),
+            unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: Some(TreeDiff {
+                strategy,
+                key_column: "id".to_string(),
+                parent_column: "parentId".to_string(),
+                closure_table,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_create_closure_table_tree() {
+        let generator = PostgresSqlGenerator;
+        let mut diff = SchemaDiff::default();
+        diff.create_models
+            .push(tree_categories(TreeStrategy::ClosureTable));
+
+        let sql = generator.generate(&diff);
+        assert!(sql.up.contains(
+            "CREATE TABLE \"categories_closure\" (\n    \"ancestor\" INTEGER NOT NULL REFERENCES \"categories\"(\"id\") ON DELETE CASCADE,"
+        ));
+        assert!(
+            sql.up
+                .contains("PRIMARY KEY (\"ancestor\", \"descendant\")")
+        );
+        assert!(sql.up.contains(
+            "SELECT \"ancestor\", NEW.\"id\", \"depth\" + 1 FROM \"categories_closure\" WHERE \"descendant\" = NEW.\"parentId\""
+        ));
+        assert!(sql.up.contains(
+            "AFTER INSERT ON \"categories\"\nFOR EACH ROW EXECUTE FUNCTION \"categories_closure_insert\"();"
+        ));
+        // The closure table references the model's table, so it is dropped first
+        let closure = sql
+            .down
+            .find("DROP TABLE IF EXISTS \"categories_closure\"")
+            .unwrap();
+        let table = sql
+            .down
+            .find("DROP TABLE IF EXISTS \"categories\" CASCADE;")
+            .unwrap();
+        assert!(closure < table);
+    }
+
+    #[test]
+    fn test_create_translation_table() {
+        let field = |name: &str, sql_type: &str| FieldDiff {
+            name: name.to_string(),
+            column_name: name.to_string(),
+            sql_type: sql_type.to_string(),
+            nullable: false,
+            default: None,
+            is_primary_key: name == "id",
+            is_auto_increment: name == "id",
+            is_unique: false,
+        };
+        let model = ModelDiff {
+            name: "Post".to_string(),
+            table_name: "posts".to_string(),
+            schema: None,
+            fields: vec![field("id", "INTEGER"), field("title", "TEXT")],
+            primary_key: vec!["id".to_string()],
+            indexes: Vec::new(),
+            unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: Some(TranslationDiff {
+                table_name: "posts_translations".to_string(),
+                key_column: "id".to_string(),
+                columns: vec!["title".to_string()],
+            }),
+            tree: None,
+        };
+        let mut diff = SchemaDiff::default();
+        diff.create_models.push(model);
+
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains(
+            "CREATE TABLE \"posts_translations\" (\n    \"id\" INTEGER NOT NULL REFERENCES \"posts\"(\"id\") ON DELETE CASCADE,\n    \"locale\" VARCHAR(35) NOT NULL,\n    \"title\" TEXT,\n    PRIMARY KEY (\"id\", \"locale\")\n);"
+        ));
+        let translations = sql
+            .down
+            .find("DROP TABLE IF EXISTS \"posts_translations\"")
+            .unwrap();
+        let table = sql.down.find("DROP TABLE IF EXISTS \"posts\"").unwrap();
+        assert!(translations < table);
+
+        let sql = MySqlGenerator.generate(&diff);
+        assert!(sql.up.contains(
+            "CONSTRAINT `posts_translations_id_fkey` FOREIGN KEY (`id`) REFERENCES `posts`(`id`) ON DELETE CASCADE"
+        ));
+    }
+
     #[test]
     fn test_create_index() {
         let generator = PostgresSqlGenerator;
@@ -1182,12 +3253,163 @@ mod tests {
         assert!(sql.contains("idx_tags"));
     }
 
+    #[test]
+    fn test_create_index_with_ops() {
+        use prax_schema::ast::IndexType;
+
+        let index = IndexDiff::new(
+            "posts_title_body_idx",
+            "posts",
+            vec!["title".to_string(), "body".to_string()],
+        )
+        .with_type(IndexType::Gin)
+        .with_column_ops(vec![Some("gin_trgm_ops".to_string()), None]);
+
+        assert_eq!(
+            PostgresSqlGenerator.create_index(&index),
+            "CREATE INDEX \"posts_title_body_idx\" ON \"posts\" USING GIN(\"title\" gin_trgm_ops, \"body\");"
+        );
+        assert_eq!(
+            MySqlGenerator.create_index(&index),
+            "CREATE INDEX `posts_title_body_idx` ON `posts`(`title`, `body`);"
+        );
+
+        let index = IndexDiff::new("posts_id_idx", "posts", vec!["id".to_string()])
+            .with_type(IndexType::SpGist);
+        assert!(
+            PostgresSqlGenerator
+                .create_index(&index)
+                .contains("USING SPGIST(\"id\")")
+        );
+    }
+
+    #[test]
+    fn test_create_model_with_indexes() {
+        let schema = prax_schema::parse_schema(
+            r#"
+            model Post {
+                id   Int  @id @auto
+                data Json
+                @@map("posts")
+                @@index([data], type: Gin, ops: jsonb_path_ops)
+            }
+        "#,
+        )
+        .unwrap();
+        let diff = crate::diff::SchemaDiffer::new(schema).diff().unwrap();
+
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains(
+            "CREATE INDEX \"posts_data_idx\" ON \"posts\" USING GIN(\"data\" jsonb_path_ops);"
+        ));
+        let sql = SqliteSqlGenerator.generate(&diff);
+        assert!(
+            sql.up
+                .contains("CREATE INDEX \"posts_data_idx\" ON \"posts\"(\"data\");")
+        );
+    }
+
+    #[test]
+    fn test_create_model_with_unique_constraints() {
+        let schema = |unique: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model Seat {{
+                    id        Int       @id @auto
+                    email     String
+                    deletedAt DateTime?
+                    @@map("seats")
+                    {}
+                }}
+            "#,
+                unique
+            ))
+            .unwrap()
+        };
+        let target = schema(
+            "@@unique([email, deletedAt], nullsNotDistinct: true, deferrable: InitiallyDeferred)",
+        );
+
+        let diff = crate::diff::SchemaDiffer::new(target.clone())
+            .diff()
+            .unwrap();
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains(
+            "CONSTRAINT \"seats_email_deletedAt_key\" UNIQUE NULLS NOT DISTINCT (\"email\", \"deletedAt\") DEFERRABLE INITIALLY DEFERRED"
+        ));
+
+        let diff = crate::diff::SchemaDiffer::new(target)
+            .with_source(schema("@@unique([email, deletedAt])"))
+            .diff()
+            .unwrap();
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains(
+            "ALTER TABLE \"seats\" DROP CONSTRAINT IF EXISTS \"seats_email_deletedAt_key\";\n\n\
+             ALTER TABLE \"seats\" ADD CONSTRAINT \"seats_email_deletedAt_key\" UNIQUE NULLS NOT DISTINCT"
+        ));
+        let sql = SqliteSqlGenerator.generate(&diff);
+        assert!(sql.up.contains("_prax_new_seats"));
+    }
+
+    #[test]
+    fn test_create_model_with_exclusion_constraint() {
+        let schema = |exclude: &str| {
+            prax_schema::parse_schema(&format!(
+                r#"
+                model Booking {{
+                    id     Int           @id @auto
+                    room   Int
+                    during DateTimeRange
+                    @@map("bookings")
+                    {}
+                }}
+            "#,
+                exclude
+            ))
+            .unwrap()
+        };
+
+        let target = schema(r#"@@exclude([room, during], using: Gist, with: ["=", "&&"])"#);
+        let diff = crate::diff::SchemaDiffer::new(target.clone())
+            .diff()
+            .unwrap();
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(
+            sql.up
+                .starts_with("CREATE EXTENSION IF NOT EXISTS \"btree_gist\";")
+        );
+        assert!(sql.up.contains("\"during\" TSTZRANGE NOT NULL"));
+        assert!(sql.up.contains(
+            "CONSTRAINT \"bookings_room_during_excl\" EXCLUDE USING GIST (\"room\" WITH =, \"during\" WITH &&)"
+        ));
+
+        // Ranges alone need no extension
+        let diff = crate::diff::SchemaDiffer::new(target)
+            .with_source(schema(
+                r#"@@exclude([during], with: "&&", name: "no_overlap")"#,
+            ))
+            .diff()
+            .unwrap();
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains(
+            "ALTER TABLE \"bookings\" DROP CONSTRAINT IF EXISTS \"no_overlap\";\n\n\
+             ALTER TABLE \"bookings\" ADD CONSTRAINT \"bookings_room_during_excl\" EXCLUDE"
+        ));
+
+        let diff = crate::diff::SchemaDiffer::new(schema(r#"@@exclude([during], with: "&&")"#))
+            .diff()
+            .unwrap();
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(!sql.up.contains("btree_gist"));
+    }
+
     #[test]
     fn test_alter_table_add_column() {
         let generator = PostgresSqlGenerator;
         let alter = ModelAlterDiff {
             name: "User".to_string(),
             table_name: "users".to_string(),
+            schema: None,
             add_fields: vec![FieldDiff {
                 name: "age".to_string(),
                 column_name: "age".to_string(),
@@ -1202,6 +3424,14 @@ mod tests {
             alter_fields: Vec::new(),
             add_indexes: Vec::new(),
             drop_indexes: Vec::new(),
+            add_unique_constraints: Vec::new(),
+            drop_unique_constraints: Vec::new(),
+            add_exclusion_constraints: Vec::new(),
+            drop_exclusion_constraints: Vec::new(),
+            target: None,
+            history: Vec::new(),
+            translation: Vec::new(),
+            tree: Vec::new(),
         };
 
         let stmts = generator.alter_table(&alter);
@@ -1352,6 +3582,7 @@ mod tests {
         let model = ModelDiff {
             name: "User".to_string(),
             table_name: "users".to_string(),
+            schema: None,
             fields: vec![
                 FieldDiff {
                     name: "id".to_string(),
@@ -1367,6 +3598,10 @@ mod tests {
             primary_key: vec!["id".to_string()],
             indexes: Vec::new(),
             unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: None,
         };
 
         let sql = generator.create_table(&model);
@@ -1375,11 +3610,337 @@ mod tests {
         assert!(sql.contains("ENGINE=InnoDB"));
     }
 
+    #[test]
+    fn test_mysql_enum_emulation() {
+        use crate::diff::SchemaDiff;
+
+        let generator = MySqlGenerator;
+        let mut diff = SchemaDiff::default();
+        diff.create_enums.push(EnumDiff {
+            name: "Role".to_string(),
+            values: vec!["USER".to_string(), "ADMIN".to_string()],
+        });
+        diff.create_models.push(ModelDiff {
+            name: "User".to_string(),
+            table_name: "users".to_string(),
+            schema: None,
+            fields: vec![
+                FieldDiff {
+                    name: "id".to_string(),
+                    column_name: "id".to_string(),
+                    sql_type: "BIGINT".to_string(),
+                    nullable: false,
+                    default: None,
+                    is_primary_key: true,
+                    is_auto_increment: true,
+                    is_unique: false,
+                },
+                FieldDiff {
+                    name: "role".to_string(),
+                    column_name: "role".to_string(),
+                    sql_type: "\"Role\"".to_string(),
+                    nullable: false,
+                    default: Some("'USER'".to_string()),
+                    is_primary_key: false,
+                    is_auto_increment: false,
+                    is_unique: false,
+                },
+            ],
+            primary_key: vec!["id".to_string()],
+            indexes: Vec::new(),
+            unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: None,
+        });
+
+        let sql = generator.generate(&diff);
+        assert!(sql.up.contains("`id` BIGINT AUTO_INCREMENT"));
+        assert!(
+            sql.up
+                .contains("`role` ENUM('USER', 'ADMIN') NOT NULL DEFAULT 'USER'")
+        );
+        assert!(!sql.up.contains("CREATE TYPE"));
+        assert_eq!(sql.down, "DROP TABLE IF EXISTS `users`;");
+    }
+
+    #[test]
+    fn test_sqlite_enum_check() {
+        use crate::diff::SchemaDiff;
+
+        let role = |sql_type: &str| FieldDiff {
+            name: "role".to_string(),
+            column_name: "role".to_string(),
+            sql_type: sql_type.to_string(),
+            nullable: false,
+            default: Some("'USER'".to_string()),
+            is_primary_key: false,
+            is_auto_increment: false,
+            is_unique: false,
+        };
+
+        let mut diff = SchemaDiff::default();
+        diff.create_enums.push(EnumDiff {
+            name: "Role".to_string(),
+            values: vec!["USER".to_string(), "ADMIN".to_string()],
+        });
+        diff.create_models.push(ModelDiff {
+            name: "User".to_string(),
+            table_name: "users".to_string(),
+            schema: None,
+            fields: vec![role("\"Role\"")],
+            primary_key: Vec::new(),
+            indexes: Vec::new(),
+            unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: None,
+        });
+        diff.alter_models
+            .push(sqlite_users_alter(vec![role("\"Status\"")], Vec::new()));
+
+        let sql = SqliteSqlGenerator.generate(&diff);
+        assert!(sql.up.contains(
+            "\"role\" TEXT CHECK (\"role\" IN ('USER', 'ADMIN')) NOT NULL DEFAULT 'USER'"
+        ));
+        assert!(!sql.up.contains("CREATE TYPE"));
+        // Values of enums created by earlier migrations aren't known
+        assert!(sql.up.contains("\"role\" TEXT NOT NULL DEFAULT 'USER'"));
+    }
+
+    #[test]
+    fn test_mysql_create_index() {
+        use prax_schema::ast::IndexType;
+
+        let generator = MySqlGenerator;
+
+        let index = IndexDiff::new("idx_email", "users", vec!["email".to_string()]).unique();
+        assert_eq!(
+            generator.create_index(&index),
+            "CREATE UNIQUE INDEX `idx_email` ON `users`(`email`);"
+        );
+
+        let index = IndexDiff::new("idx_name", "users", vec!["name".to_string()])
+            .with_type(IndexType::Hash);
+        assert_eq!(
+            generator.create_index(&index),
+            "CREATE INDEX `idx_name` USING HASH ON `users`(`name`);"
+        );
+
+        let index = IndexDiff::new("idx_bio", "users", vec!["bio".to_string()])
+            .unique()
+            .with_type(IndexType::FullText);
+        assert_eq!(
+            generator.create_index(&index),
+            "CREATE FULLTEXT INDEX `idx_bio` ON `users`(`bio`);"
+        );
+
+        // PostgreSQL-only index types fall back to a plain index
+        let index =
+            IndexDiff::new("idx_tags", "users", vec!["tags".to_string()]).with_type(IndexType::Gin);
+        assert_eq!(
+            generator.create_index(&index),
+            "CREATE INDEX `idx_tags` ON `users`(`tags`);"
+        );
+
+        assert_eq!(
+            generator.drop_index("idx_tags", "users"),
+            "DROP INDEX `idx_tags` ON `users`;"
+        );
+    }
+
+    #[test]
+    fn test_mysql_versioned_triggers() {
+        let generator = MySqlGenerator;
+        let stmts = generator.create_history(&versioned_users(), "users_history");
+
+        assert!(stmts[0].contains("`_version_id` BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY"));
+        assert!(stmts[0].contains("INDEX `users_history_key_idx` (`id`, `_valid_from`)"));
+        assert!(
+            stmts.contains(&"DROP TRIGGER IF EXISTS `users_history_update_close`;".to_string())
+        );
+        assert!(stmts.iter().any(|s| s.starts_with(
+            "CREATE TRIGGER `users_history_update` AFTER UPDATE ON `users` FOR EACH ROW FOLLOWS `users_history_update_close`"
+        )));
+        assert!(stmts.iter().any(|s| s.ends_with(
+            "VALUES (OLD.`id`, OLD.`email`, CURRENT_TIMESTAMP(6), CURRENT_TIMESTAMP(6), 'DELETE');"
+        )));
+        // Statements are applied one at a time, so trigger bodies are single statements
+        assert!(stmts.iter().all(|s| s.matches(';').count() == 1));
+    }
+
+    #[test]
+    fn test_mysql_materialized_path_trigger() {
+        let generator = MySqlGenerator;
+        let model = tree_categories(TreeStrategy::MaterializedPath);
+        let stmts = generator.create_tree(&model, model.tree.as_ref().unwrap());
+
+        assert_eq!(
+            stmts,
+            vec![
+                "DROP TRIGGER IF EXISTS `categories_path_insert`;".to_string(),
+                "CREATE TRIGGER `categories_path_insert` BEFORE INSERT ON `categories` FOR EACH ROW\n\
+                 SET NEW.`path` = COALESCE((SELECT CONCAT(p.`path`, CAST(p.`id` AS CHAR), '/') FROM `categories` p WHERE p.`id` = NEW.`parentId`), '/');"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mysql_alter_column() {
+        let generator = MySqlGenerator;
+        let field = FieldAlterDiff {
+            name: "age".to_string(),
+            column_name: "age".to_string(),
+            old_type: Some("TEXT".to_string()),
+            new_type: Some("INTEGER".to_string()),
+            old_nullable: Some(true),
+            new_nullable: Some(false),
+            old_default: None,
+            new_default: None,
+            column: FieldDiff {
+                name: "age".to_string(),
+                column_name: "age".to_string(),
+                sql_type: "INTEGER".to_string(),
+                nullable: false,
+                default: None,
+                is_primary_key: false,
+                is_auto_increment: false,
+                is_unique: false,
+            },
+        };
+
+        let stmts = generator.alter_column("users", &field);
+        assert_eq!(
+            stmts,
+            vec!["ALTER TABLE `users` MODIFY COLUMN `age` INT NOT NULL;".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_mysql_alter_column_restates_definition() {
+        let generator = MySqlGenerator;
+        let column = FieldDiff {
+            name: "name".to_string(),
+            column_name: "name".to_string(),
+            sql_type: "TEXT".to_string(),
+            nullable: false,
+            default: Some("'anonymous'".to_string()),
+            is_primary_key: false,
+            is_auto_increment: false,
+            is_unique: true,
+        };
+
+        // Only nullability changed: the type is restated as is
+        let field = FieldAlterDiff {
+            name: "name".to_string(),
+            column_name: "name".to_string(),
+            old_type: None,
+            new_type: None,
+            old_nullable: Some(true),
+            new_nullable: Some(false),
+            old_default: None,
+            new_default: None,
+            column: column.clone(),
+        };
+        assert_eq!(
+            generator.alter_column("users", &field),
+            vec![
+                "ALTER TABLE `users` MODIFY COLUMN `name` VARCHAR(255) NOT NULL DEFAULT 'anonymous';"
+                    .to_string()
+            ]
+        );
+
+        // Only the type changed: NOT NULL and the default are kept
+        let field = FieldAlterDiff {
+            old_type: Some("TEXT".to_string()),
+            new_type: Some("JSON".to_string()),
+            old_nullable: None,
+            new_nullable: None,
+            column: FieldDiff {
+                sql_type: "JSON".to_string(),
+                ..column
+            },
+            ..field
+        };
+        assert_eq!(
+            generator.alter_column("users", &field),
+            vec![
+                "ALTER TABLE `users` MODIFY COLUMN `name` JSON NOT NULL DEFAULT ('anonymous');"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mysql_alter_enum() {
+        use crate::diff::{EnumColumnDiff, SchemaDiff};
+
+        let generator = MySqlGenerator;
+        let role = FieldDiff {
+            name: "role".to_string(),
+            column_name: "role".to_string(),
+            sql_type: "\"Role\"".to_string(),
+            nullable: false,
+            default: Some("'USER'".to_string()),
+            is_primary_key: false,
+            is_auto_increment: false,
+            is_unique: false,
+        };
+        let mut diff = SchemaDiff::default();
+        diff.enums.push(EnumDiff {
+            name: "Role".to_string(),
+            values: vec!["USER".to_string(), "ADMIN".to_string(), "OWNER".to_string()],
+        });
+        diff.alter_enums.push(EnumAlterDiff {
+            name: "Role".to_string(),
+            add_values: vec!["OWNER".to_string()],
+            remove_values: Vec::new(),
+            columns: vec![EnumColumnDiff {
+                table_name: "users".to_string(),
+                field: role.clone(),
+            }],
+        });
+        // A column added later of an enum created by an earlier migration
+        diff.alter_models.push(ModelAlterDiff {
+            name: "Member".to_string(),
+            table_name: "members".to_string(),
+            schema: None,
+            add_fields: vec![FieldDiff {
+                nullable: true,
+                default: None,
+                ..role
+            }],
+            drop_fields: Vec::new(),
+            alter_fields: Vec::new(),
+            add_indexes: Vec::new(),
+            drop_indexes: Vec::new(),
+            add_unique_constraints: Vec::new(),
+            drop_unique_constraints: Vec::new(),
+            add_exclusion_constraints: Vec::new(),
+            drop_exclusion_constraints: Vec::new(),
+            target: None,
+            history: Vec::new(),
+            tree: Vec::new(),
+            translation: Vec::new(),
+        });
+
+        let sql = generator.generate(&diff);
+        assert!(sql.up.contains(
+            "ALTER TABLE `users` MODIFY COLUMN `role` ENUM('USER', 'ADMIN', 'OWNER') NOT NULL DEFAULT 'USER';"
+        ));
+        assert!(sql.up.contains(
+            "ALTER TABLE `members` ADD COLUMN `role` ENUM('USER', 'ADMIN', 'OWNER');"
+        ));
+    }
+
     // ==================== SQLite Generator Tests ====================
 
     #[test]
     fn test_sqlite_create_view() {
-        let generator = SqliteGenerator;
+        let generator = SqliteSqlGenerator;
         let view = ViewDiff {
             name: "UserStats".to_string(),
             view_name: "user_stats".to_string(),
@@ -1397,7 +3958,7 @@ mod tests {
 
     #[test]
     fn test_sqlite_drop_view() {
-        let generator = SqliteGenerator;
+        let generator = SqliteSqlGenerator;
         let sql = generator.drop_view("user_stats");
         assert!(sql.contains("DROP VIEW IF EXISTS"));
         assert!(sql.contains("\"user_stats\""));
@@ -1407,7 +3968,7 @@ mod tests {
     fn test_sqlite_generate_with_views() {
         use crate::diff::SchemaDiff;
 
-        let generator = SqliteGenerator;
+        let generator = SqliteSqlGenerator;
         let mut diff = SchemaDiff::default();
         diff.create_views.push(ViewDiff {
             name: "ActiveUsers".to_string(),
@@ -1427,10 +3988,11 @@ mod tests {
 
     #[test]
     fn test_sqlite_create_table_with_autoincrement() {
-        let generator = SqliteGenerator;
+        let generator = SqliteSqlGenerator;
         let model = ModelDiff {
             name: "User".to_string(),
             table_name: "users".to_string(),
+            schema: None,
             fields: vec![
                 FieldDiff {
                     name: "id".to_string(),
@@ -1446,6 +4008,10 @@ mod tests {
             primary_key: vec!["id".to_string()],
             indexes: Vec::new(),
             unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: None,
         };
 
         let sql = generator.create_table(&model);
@@ -1453,6 +4019,198 @@ mod tests {
         assert!(sql.contains("INTEGER PRIMARY KEY"));
     }
 
+    fn sqlite_users_alter(add_fields: Vec<FieldDiff>, drop_fields: Vec<String>) -> ModelAlterDiff {
+        let field = |name: &str, sql_type: &str, nullable: bool| FieldDiff {
+            name: name.to_string(),
+            column_name: name.to_string(),
+            sql_type: sql_type.to_string(),
+            nullable,
+            default: None,
+            is_primary_key: name == "id",
+            is_auto_increment: name == "id",
+            is_unique: false,
+        };
+
+        let mut fields = vec![field("id", "INTEGER", false), field("email", "TEXT", false)];
+        fields.extend(add_fields.iter().cloned());
+
+        ModelAlterDiff {
+            name: "User".to_string(),
+            table_name: "users".to_string(),
+            schema: None,
+            add_fields,
+            drop_fields,
+            alter_fields: Vec::new(),
+            add_indexes: Vec::new(),
+            drop_indexes: Vec::new(),
+            add_unique_constraints: Vec::new(),
+            drop_unique_constraints: Vec::new(),
+            add_exclusion_constraints: Vec::new(),
+            drop_exclusion_constraints: Vec::new(),
+            target: Some(ModelDiff {
+                name: "User".to_string(),
+                table_name: "users".to_string(),
+                schema: None,
+                fields,
+                primary_key: vec!["id".to_string()],
+                indexes: Vec::new(),
+                unique_constraints: Vec::new(),
+                exclusion_constraints: Vec::new(),
+                history_table: None,
+                translation: None,
+                tree: None,
+            }),
+            history: Vec::new(),
+            translation: Vec::new(),
+            tree: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sqlite_add_column_in_place() {
+        let generator = SqliteSqlGenerator;
+        let age = FieldDiff {
+            name: "age".to_string(),
+            column_name: "age".to_string(),
+            sql_type: "INTEGER".to_string(),
+            nullable: true,
+            default: None,
+            is_primary_key: false,
+            is_auto_increment: false,
+            is_unique: false,
+        };
+
+        let stmts = generator.alter_table(&sqlite_users_alter(vec![age], Vec::new()));
+        assert_eq!(
+            stmts,
+            vec!["ALTER TABLE \"users\" ADD COLUMN \"age\" INTEGER;".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_sqlite_rebuild_table() {
+        let generator = SqliteSqlGenerator;
+        let mut diff = SchemaDiff::default();
+        diff.alter_models
+            .push(sqlite_users_alter(Vec::new(), vec!["name".to_string()]));
+
+        let sql = generator.generate(&diff);
+        let expected = [
+            "PRAGMA foreign_keys = OFF;",
+            "BEGIN TRANSACTION;",
+            "CREATE TABLE \"_prax_new_users\" (",
+            "INSERT INTO \"_prax_new_users\" (\"id\", \"email\") SELECT \"id\", \"email\" FROM \"users\";",
+            "DROP TABLE \"users\";",
+            "ALTER TABLE \"_prax_new_users\" RENAME TO \"users\";",
+            "PRAGMA foreign_key_check;",
+            "COMMIT;",
+            "PRAGMA foreign_keys = ON;",
+        ];
+        let mut rest = sql.up.as_str();
+        for stmt in expected {
+            let at = rest
+                .find(stmt)
+                .unwrap_or_else(|| panic!("missing `{}`", stmt));
+            rest = &rest[at + stmt.len()..];
+        }
+
+        // Without the full definition there is nothing to rebuild from
+        let mut alter = sqlite_users_alter(Vec::new(), vec!["name".to_string()]);
+        alter.target = None;
+        assert!(generator.alter_table(&alter)[0].starts_with("-- Cannot rebuild"));
+    }
+
+    #[test]
+    fn test_sqlite_rebuild_with_new_index() {
+        let generator = SqliteSqlGenerator;
+        let name = FieldDiff {
+            name: "name".to_string(),
+            column_name: "name".to_string(),
+            sql_type: "TEXT".to_string(),
+            nullable: false,
+            default: None,
+            is_primary_key: false,
+            is_auto_increment: false,
+            is_unique: false,
+        };
+        let index = IndexDiff::new("users_email_idx", "users", vec!["email".to_string()]);
+        let mut alter = sqlite_users_alter(vec![name], Vec::new());
+        alter.add_indexes.push(index.clone());
+        if let Some(target) = &mut alter.target {
+            target.indexes.push(index);
+        }
+
+        let stmts = generator.alter_table(&alter);
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(
+            stmts[0].matches("CREATE INDEX \"users_email_idx\"").count(),
+            1
+        );
+        assert!(stmts[0].contains("\"name\" TEXT NOT NULL"));
+    }
+
+    #[test]
+    fn test_sqlite_rebuild_versioned_table() {
+        let generator = SqliteSqlGenerator;
+        let mut alter = sqlite_users_alter(Vec::new(), vec!["name".to_string()]);
+        if let Some(target) = &mut alter.target {
+            target.history_table = Some("users_history".to_string());
+        }
+        let mut diff = SchemaDiff::default();
+        diff.alter_models.push(alter);
+
+        // The rebuild drops the old table's triggers, so they are recreated after it
+        let sql = generator.generate(&diff);
+        let commit = sql.up.find("COMMIT;").unwrap();
+        let trigger = sql
+            .up
+            .find("CREATE TRIGGER \"users_history_update\" AFTER UPDATE ON \"users\"")
+            .unwrap();
+        assert!(commit < trigger);
+        assert!(sql.up.contains(
+            "INSERT INTO \"users_history\" (\"id\", \"email\", \"_valid_from\", \"_valid_to\", \"_operation\") VALUES (NEW.\"id\", NEW.\"email\", strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), NULL, 'INSERT');"
+        ));
+        assert!(!sql.up.contains("CREATE TABLE \"users_history\""));
+    }
+
+    #[test]
+    fn test_sqlite_add_tree() {
+        let generator = SqliteSqlGenerator;
+        let mut alter = sqlite_users_alter(vec![TreeDiff::path_field()], Vec::new());
+        let target = tree_categories(TreeStrategy::MaterializedPath);
+        alter.tree = vec![TreeChange::Create(target.tree.clone().unwrap())];
+        alter.target = Some(target);
+
+        let stmts = generator.alter_tree(&alter);
+        assert!(stmts.iter().any(|s| s.starts_with(
+            "CREATE TRIGGER \"categories_path_insert\" AFTER INSERT ON \"categories\" FOR EACH ROW BEGIN"
+        )));
+        // Existing rows get their paths from the parent column
+        assert!(stmts.last().unwrap().starts_with(
+            "WITH RECURSIVE tree_paths (\"id\", \"path\") AS (\n    SELECT \"id\", CAST('/' AS TEXT) FROM \"categories\" WHERE \"parentId\" IS NULL"
+        ));
+    }
+
+    #[test]
+    fn test_sqlite_triggers_split_whole() {
+        let generator = SqliteSqlGenerator;
+        let mut diff = SchemaDiff::default();
+        diff.create_models.push(versioned_users());
+        diff.create_models
+            .push(tree_categories(TreeStrategy::ClosureTable));
+
+        let sql = generator.generate(&diff);
+        let statements = crate::executor::split_statements(&sql.up);
+        let triggers: Vec<_> = statements
+            .iter()
+            .filter(|s| s.starts_with("CREATE TRIGGER"))
+            .collect();
+        assert_eq!(triggers.len(), 4);
+        for trigger in triggers {
+            assert!(trigger.ends_with("\nEND"), "split trigger: {}", trigger);
+        }
+    }
+
     // ==================== MSSQL Generator Tests ====================
 
     #[test]
@@ -1528,6 +4286,7 @@ mod tests {
         let model = ModelDiff {
             name: "User".to_string(),
             table_name: "users".to_string(),
+            schema: None,
             fields: vec![
                 FieldDiff {
                     name: "id".to_string(),
@@ -1543,6 +4302,10 @@ mod tests {
             primary_key: vec!["id".to_string()],
             indexes: Vec::new(),
             unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: None,
         };
 
         let sql = generator.create_table(&model);
@@ -1550,4 +4313,198 @@ mod tests {
         assert!(sql.contains("IDENTITY(1,1)"));
         assert!(sql.contains("[PK_users]"));
     }
+
+    #[test]
+    fn test_schema_qualified_tables() {
+        let mut diff = SchemaDiff::default();
+        diff.create_schemas.push("accounting".to_string());
+        let mut index = IndexDiff::new("invoices_number_idx", "invoices", vec!["number".into()]);
+        index.schema = Some("accounting".to_string());
+        diff.create_models.push(ModelDiff {
+            name: "Invoice".to_string(),
+            table_name: "invoices".to_string(),
+            schema: Some("accounting".to_string()),
+            fields: vec![FieldDiff {
+                name: "number".to_string(),
+                column_name: "number".to_string(),
+                sql_type: "TEXT".to_string(),
+                nullable: false,
+                default: None,
+                is_primary_key: true,
+                is_auto_increment: false,
+                is_unique: false,
+            }],
+            primary_key: vec!["number".to_string()],
+            indexes: vec![index],
+            unique_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            history_table: None,
+            translation: None,
+            tree: None,
+        });
+
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(
+            sql.up
+                .starts_with("CREATE SCHEMA IF NOT EXISTS \"accounting\";")
+        );
+        assert!(sql.up.contains("CREATE TABLE \"accounting\".\"invoices\""));
+        assert!(
+            sql.up
+                .contains("ON \"accounting\".\"invoices\"(\"number\")")
+        );
+        assert!(
+            sql.down
+                .contains("DROP TABLE IF EXISTS \"accounting\".\"invoices\" CASCADE;")
+        );
+
+        let sql = MssqlGenerator.generate(&diff);
+        assert!(sql.up.starts_with(
+            "IF NOT EXISTS (SELECT * FROM sys.schemas WHERE name = N'accounting')\n    EXEC('CREATE SCHEMA [accounting]');"
+        ));
+        assert!(sql.up.contains("CREATE TABLE [accounting].[invoices]"));
+        assert!(sql.up.contains("ON [accounting].[invoices]([number])"));
+        assert!(
+            sql.down
+                .contains("DROP TABLE IF EXISTS [accounting].[invoices];")
+        );
+    }
+
+    #[test]
+    fn test_range_columns() {
+        let schema = prax_schema::parse_schema(
+            r#"
+            model Booking {
+                id     Int           @id @auto
+                seats  IntRange
+                during DateTimeRange?
+                @@map("bookings")
+            }
+        "#,
+        )
+        .unwrap();
+        let diff = crate::diff::SchemaDiffer::new(schema).diff().unwrap();
+
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains("\"seats\" INT4RANGE NOT NULL"));
+        assert!(sql.up.contains("\"during\" TSTZRANGE"));
+
+        // Databases without range types get two nullable bound columns
+        let sql = MySqlGenerator.generate(&diff);
+        assert!(sql.up.contains("`seats_lower` INT,"));
+        assert!(sql.up.contains("`seats_upper` INT,"));
+        assert!(sql.up.contains("`during_upper` DATETIME(3)"));
+        assert!(!sql.up.contains("RANGE"));
+
+        let sql = SqliteSqlGenerator.generate(&diff);
+        assert!(sql.up.contains("\"seats_lower\" INTEGER,"));
+        assert!(sql.up.contains("\"during_lower\" TEXT"));
+
+        let sql = MssqlGenerator.generate(&diff);
+        assert!(sql.up.contains("[during_upper] DATETIMEOFFSET"));
+    }
+
+    #[test]
+    fn test_network_columns() {
+        let schema = prax_schema::parse_schema(
+            r#"
+            model Device {
+                id     Int     @id @auto
+                ip     Inet
+                subnet Cidr?
+                mac    MacAddr
+            }
+        "#,
+        )
+        .unwrap();
+        let diff = crate::diff::SchemaDiffer::new(schema).diff().unwrap();
+
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains("\"ip\" INET NOT NULL"));
+        assert!(sql.up.contains("\"subnet\" CIDR"));
+        assert!(sql.up.contains("\"mac\" MACADDR NOT NULL"));
+
+        // Other databases store addresses as text
+        let sql = MySqlGenerator.generate(&diff);
+        assert!(sql.up.contains("`ip` VARCHAR(36) NOT NULL"));
+        assert!(sql.up.contains("`mac` CHAR(17) NOT NULL"));
+
+        let sql = SqliteSqlGenerator.generate(&diff);
+        assert!(sql.up.contains("\"subnet\" TEXT"));
+
+        let sql = MssqlGenerator.generate(&diff);
+        assert!(sql.up.contains("[ip] VARCHAR(36) NOT NULL"));
+    }
+
+    #[test]
+    fn test_spatial_columns() {
+        let schema = prax_schema::parse_schema(
+            r#"
+            model Store {
+                id       Int       @id @auto
+                location Geography @db.Geography(Point, 4326)
+                area     Geometry?
+
+                @@index([location])
+            }
+        "#,
+        )
+        .unwrap();
+        let diff = crate::diff::SchemaDiffer::new(schema).diff().unwrap();
+
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(
+            sql.up
+                .starts_with("CREATE EXTENSION IF NOT EXISTS \"postgis\";")
+        );
+        assert!(
+            sql.up
+                .contains("\"location\" GEOGRAPHY(Point, 4326) NOT NULL")
+        );
+        assert!(sql.up.contains("\"area\" GEOMETRY,"));
+        assert!(sql.up.contains(
+            "CREATE INDEX \"Store_location_idx\" ON \"Store\" USING GIST(\"location\");"
+        ));
+    }
+
+    #[test]
+    fn test_money_columns() {
+        let schema = |fields: &str| {
+            prax_schema::parse_schema(&format!(
+                "model Order {{\n id Int @id @auto\n {}\n @@map(\"orders\")\n}}",
+                fields
+            ))
+            .unwrap()
+        };
+        let diff = crate::diff::SchemaDiffer::new(schema("total Money\n refund Money?"))
+            .diff()
+            .unwrap();
+
+        let sql = PostgresSqlGenerator.generate(&diff);
+        assert!(sql.up.contains("\"total_amount\" DECIMAL(19, 4) NOT NULL"));
+        assert!(sql.up.contains("\"total_currency\" CHAR(3) NOT NULL"));
+        assert!(sql.up.contains("\"refund_currency\" CHAR(3)"));
+        assert!(!sql.up.contains("\"total\""));
+
+        let sql = MySqlGenerator.generate(&diff);
+        assert!(sql.up.contains("`total_amount` DECIMAL(19, 4) NOT NULL"));
+        let sql = SqliteSqlGenerator.generate(&diff);
+        assert!(sql.up.contains("\"refund_amount\" DECIMAL(19, 4)"));
+        let sql = MssqlGenerator.generate(&diff);
+        assert!(sql.up.contains("[total_currency] CHAR(3) NOT NULL"));
+
+        // Both columns follow the field
+        let diff = crate::diff::SchemaDiffer::new(schema("total Money?\n refund Decimal"))
+            .with_source(schema("total Money\n refund Money?"))
+            .diff()
+            .unwrap();
+        let alter = &diff.alter_models[0];
+        let mut altered: Vec<_> = alter.alter_fields.iter().map(|f| &f.column_name).collect();
+        altered.sort();
+        assert_eq!(altered, ["total_amount", "total_currency"]);
+        let mut dropped = alter.drop_fields.clone();
+        dropped.sort();
+        assert_eq!(dropped, ["refund_amount", "refund_currency"]);
+        assert_eq!(alter.add_fields[0].column_name, "refund");
+    }
 }
@@ -0,0 +1,462 @@
+//! Typed change streams with resumable subscriptions.
+//!
+//! [`MongoEngine::watch_collection`](crate::MongoEngine::watch_collection)
+//! subscribes to the inserts, updates, replacements and deletes of a model's
+//! collection and yields them as [`ChangeEvent`]s. When a
+//! [`ResumeTokenStore`] is configured, the resume token of each handled event
+//! is persisted, and a later subscription under the same name picks up after
+//! the last handled event, so consumers recover after a restart.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use futures::TryStreamExt;
+//! use prax_mongodb::change_stream::{ChangeEvent, CollectionTokenStore, WatchOptions};
+//!
+//! let store = CollectionTokenStore::new(engine.client());
+//! let mut events = engine
+//!     .watch_collection::<User>(WatchOptions::new().name("user-sync").token_store(store))
+//!     .await?;
+//!
+//! while let Some(event) = events.try_next().await? {
+//!     match event {
+//!         ChangeEvent::Insert { document, .. } => index_user(&document).await?,
+//!         ChangeEvent::Delete { key } => remove_user(&key).await?,
+//!         _ => {}
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use bson::{Bson, Document, doc};
+use futures::TryStreamExt;
+use futures::stream::{self, BoxStream};
+use mongodb::Collection;
+use mongodb::change_stream::ChangeStream;
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken};
+use mongodb::options::{ChangeStreamOptions, FullDocumentType, ReplaceOptions};
+use prax_query::traits::BoxFuture;
+use serde::de::DeserializeOwned;
+use tracing::debug;
+
+use crate::client::MongoClient;
+use crate::error::{MongoError, MongoResult};
+
+/// Stream of typed change events returned by
+/// [`MongoEngine::watch_collection`](crate::MongoEngine::watch_collection).
+pub type ChangeEventStream<T> = BoxStream<'static, MongoResult<ChangeEvent<T>>>;
+
+/// A change to a document of a watched collection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent<T> {
+    /// A document was inserted.
+    Insert {
+        /// The `_id` (and shard key) of the document.
+        key: Document,
+        /// The inserted document.
+        document: T,
+    },
+    /// A document was updated.
+    Update {
+        /// The `_id` (and shard key) of the document.
+        key: Document,
+        /// The document after the update; `None` if it was deleted before it
+        /// could be looked up.
+        document: Option<T>,
+        /// Fields set by the update.
+        updated_fields: Document,
+        /// Fields removed by the update.
+        removed_fields: Vec<String>,
+    },
+    /// A document was replaced.
+    Replace {
+        /// The `_id` (and shard key) of the document.
+        key: Document,
+        /// The replacement document.
+        document: T,
+    },
+    /// A document was deleted.
+    Delete {
+        /// The `_id` (and shard key) of the document.
+        key: Document,
+    },
+}
+
+impl<T> ChangeEvent<T> {
+    /// Convert a driver event, returning `None` for operations other than
+    /// inserts, updates, replacements and deletes.
+    pub fn from_event(event: ChangeStreamEvent<T>) -> MongoResult<Option<Self>> {
+        let key = event.document_key.unwrap_or_default();
+        let change = match event.operation_type {
+            OperationType::Insert => Self::Insert {
+                document: event
+                    .full_document
+                    .ok_or_else(|| MongoError::serialization("insert event without a document"))?,
+                key,
+            },
+            OperationType::Update => {
+                let description = event.update_description;
+                Self::Update {
+                    key,
+                    document: event.full_document,
+                    updated_fields: description
+                        .as_ref()
+                        .map(|d| d.updated_fields.clone())
+                        .unwrap_or_default(),
+                    removed_fields: description.map(|d| d.removed_fields).unwrap_or_default(),
+                }
+            }
+            OperationType::Replace => Self::Replace {
+                document: event
+                    .full_document
+                    .ok_or_else(|| MongoError::serialization("replace event without a document"))?,
+                key,
+            },
+            OperationType::Delete => Self::Delete { key },
+            _ => return Ok(None),
+        };
+        Ok(Some(change))
+    }
+
+    /// Get the `_id` (and shard key) of the changed document.
+    pub fn key(&self) -> &Document {
+        match self {
+            Self::Insert { key, .. }
+            | Self::Update { key, .. }
+            | Self::Replace { key, .. }
+            | Self::Delete { key } => key,
+        }
+    }
+
+    /// Get the operation name (`insert`, `update`, `replace` or `delete`).
+    pub fn operation(&self) -> &'static str {
+        match self {
+            Self::Insert { .. } => "insert",
+            Self::Update { .. } => "update",
+            Self::Replace { .. } => "replace",
+            Self::Delete { .. } => "delete",
+        }
+    }
+}
+
+/// Persistent storage for change stream resume tokens.
+///
+/// Tokens are keyed by the subscription name set with [`WatchOptions::name`].
+pub trait ResumeTokenStore: Send + Sync {
+    /// Load the last saved token of a subscription.
+    fn load<'a>(&'a self, name: &'a str) -> BoxFuture<'a, MongoResult<Option<ResumeToken>>>;
+
+    /// Save the token of the last handled event of a subscription.
+    fn save<'a>(&'a self, name: &'a str, token: &'a ResumeToken) -> BoxFuture<'a, MongoResult<()>>;
+}
+
+/// Resume token store keeping tokens in memory.
+///
+/// Tokens survive re-subscribing within a process but not a restart.
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: Mutex<HashMap<String, ResumeToken>>,
+}
+
+impl MemoryTokenStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ResumeTokenStore for MemoryTokenStore {
+    fn load<'a>(&'a self, name: &'a str) -> BoxFuture<'a, MongoResult<Option<ResumeToken>>> {
+        let token = self.tokens.lock().unwrap().get(name).cloned();
+        Box::pin(async move { Ok(token) })
+    }
+
+    fn save<'a>(&'a self, name: &'a str, token: &'a ResumeToken) -> BoxFuture<'a, MongoResult<()>> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), token.clone());
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Resume token store keeping one document per subscription in a collection.
+#[derive(Clone)]
+pub struct CollectionTokenStore {
+    collection: Collection<Document>,
+}
+
+impl CollectionTokenStore {
+    /// Default collection holding the tokens.
+    pub const DEFAULT_COLLECTION: &'static str = "_prax_resume_tokens";
+
+    /// Create a store over the default collection.
+    pub fn new(client: &MongoClient) -> Self {
+        Self::with_collection(client, Self::DEFAULT_COLLECTION)
+    }
+
+    /// Create a store over the given collection.
+    pub fn with_collection(client: &MongoClient, collection: &str) -> Self {
+        Self {
+            collection: client.collection_doc(collection),
+        }
+    }
+}
+
+impl ResumeTokenStore for CollectionTokenStore {
+    fn load<'a>(&'a self, name: &'a str) -> BoxFuture<'a, MongoResult<Option<ResumeToken>>> {
+        Box::pin(async move {
+            let Some(doc) = self.collection.find_one(doc! { "_id": name }, None).await? else {
+                return Ok(None);
+            };
+            match doc.get("token") {
+                Some(token) => Ok(Some(bson::from_bson(token.clone())?)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save<'a>(&'a self, name: &'a str, token: &'a ResumeToken) -> BoxFuture<'a, MongoResult<()>> {
+        Box::pin(async move {
+            let replacement = doc! {
+                "_id": name,
+                "token": bson::to_bson(token)?,
+                "updatedAt": bson::DateTime::now(),
+            };
+            let options = ReplaceOptions::builder().upsert(true).build();
+            self.collection
+                .replace_one(doc! { "_id": name }, replacement, options)
+                .await?;
+            Ok(())
+        })
+    }
+}
+
+/// Options of a change stream subscription.
+#[derive(Clone, Default)]
+pub struct WatchOptions {
+    name: Option<String>,
+    pipeline: Vec<Document>,
+    skip_update_lookup: bool,
+    batch_size: Option<u32>,
+    token_store: Option<Arc<dyn ResumeTokenStore>>,
+}
+
+impl WatchOptions {
+    /// Create default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the subscription name keying the resume token; the collection name
+    /// by default.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Add an aggregation stage filtering or reshaping the events, such as
+    /// `{ "$match": { "fullDocument.active": true } }`.
+    pub fn stage(mut self, stage: Document) -> Self {
+        self.pipeline.push(stage);
+        self
+    }
+
+    /// Look up the current document for update events (enabled by default).
+    pub fn update_lookup(mut self, enabled: bool) -> Self {
+        self.skip_update_lookup = !enabled;
+        self
+    }
+
+    /// Set the number of events fetched per batch.
+    pub fn batch_size(mut self, size: u32) -> Self {
+        self.batch_size = Some(size);
+        self
+    }
+
+    /// Persist resume tokens in the given store.
+    pub fn token_store(mut self, store: impl ResumeTokenStore + 'static) -> Self {
+        self.token_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Persist resume tokens in a shared store.
+    pub fn shared_token_store(mut self, store: Arc<dyn ResumeTokenStore>) -> Self {
+        self.token_store = Some(store);
+        self
+    }
+
+    /// Get the full aggregation pipeline of the subscription.
+    pub fn to_pipeline(&self) -> Vec<Document> {
+        let operations: Vec<Bson> = ["insert", "update", "replace", "delete"]
+            .into_iter()
+            .map(Bson::from)
+            .collect();
+        let mut pipeline = vec![doc! { "$match": { "operationType": { "$in": operations } } }];
+        pipeline.extend(self.pipeline.iter().cloned());
+        pipeline
+    }
+}
+
+/// Open a typed change stream on a collection.
+pub(crate) async fn watch<T>(
+    collection: Collection<T>,
+    options: WatchOptions,
+) -> MongoResult<ChangeEventStream<T>>
+where
+    T: DeserializeOwned + Unpin + Send + Sync + 'static,
+{
+    let name = options
+        .name
+        .clone()
+        .unwrap_or_else(|| collection.name().to_string());
+
+    let resume_after = match &options.token_store {
+        Some(store) => store.load(&name).await?,
+        None => None,
+    };
+    debug!(
+        collection = %collection.name(),
+        subscription = %name,
+        resuming = resume_after.is_some(),
+        "Opening change stream"
+    );
+
+    let full_document = (!options.skip_update_lookup).then_some(FullDocumentType::UpdateLookup);
+    let stream_options = ChangeStreamOptions::builder()
+        .full_document(full_document)
+        .resume_after(resume_after)
+        .batch_size(options.batch_size)
+        .build();
+    let changes = collection
+        .watch(options.to_pipeline(), stream_options)
+        .await?;
+
+    let state = WatchState {
+        changes,
+        store: options.token_store,
+        name,
+        handled: None,
+    };
+    Ok(Box::pin(stream::unfold(state, WatchState::next)))
+}
+
+struct WatchState<T: DeserializeOwned> {
+    changes: ChangeStream<ChangeStreamEvent<T>>,
+    store: Option<Arc<dyn ResumeTokenStore>>,
+    name: String,
+    /// Token of the last event yielded, saved once the next one is requested.
+    handled: Option<ResumeToken>,
+}
+
+impl<T> WatchState<T>
+where
+    T: DeserializeOwned + Unpin + Send + Sync,
+{
+    /// Yield the next event, first saving the token of the previous one.
+    ///
+    /// Saving a token only after its event was handled means an event in
+    /// flight when the process stops is delivered again on restart.
+    async fn next(mut self) -> Option<(MongoResult<ChangeEvent<T>>, Self)> {
+        if let Some(token) = self.handled.take() {
+            if let Some(store) = &self.store {
+                if let Err(e) = store.save(&self.name, &token).await {
+                    self.handled = Some(token);
+                    return Some((Err(e), self));
+                }
+            }
+        }
+
+        loop {
+            let event = match self.changes.try_next().await {
+                Ok(Some(event)) => event,
+                Ok(None) => return None,
+                Err(e) => return Some((Err(e.into()), self)),
+            };
+            let token = event.id.clone();
+            match ChangeEvent::from_event(event) {
+                Ok(Some(change)) => {
+                    self.handled = Some(token);
+                    return Some((Ok(change), self));
+                }
+                Ok(None) => continue,
+                Err(e) => {
+                    self.handled = Some(token);
+                    return Some((Err(e), self));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(doc: Document) -> ChangeStreamEvent<Document> {
+        bson::from_document(doc).unwrap()
+    }
+
+    #[test]
+    fn test_change_event_from_event() {
+        let insert = event(doc! {
+            "_id": { "_data": "01" },
+            "operationType": "insert",
+            "documentKey": { "_id": 1 },
+            "fullDocument": { "_id": 1, "name": "Alice" },
+        });
+        let change = ChangeEvent::from_event(insert).unwrap().unwrap();
+        assert_eq!(change.operation(), "insert");
+        assert_eq!(change.key(), &doc! { "_id": 1 });
+
+        let update = event(doc! {
+            "_id": { "_data": "02" },
+            "operationType": "update",
+            "documentKey": { "_id": 1 },
+            "updateDescription": { "updatedFields": { "name": "Bob" }, "removedFields": ["age"] },
+        });
+        assert_eq!(
+            ChangeEvent::from_event(update).unwrap(),
+            Some(ChangeEvent::Update {
+                key: doc! { "_id": 1 },
+                document: None,
+                updated_fields: doc! { "name": "Bob" },
+                removed_fields: vec!["age".to_string()],
+            })
+        );
+
+        let drop = event(doc! { "_id": { "_data": "03" }, "operationType": "drop" });
+        assert_eq!(ChangeEvent::from_event(drop).unwrap(), None);
+
+        let replace = event(doc! {
+            "_id": { "_data": "04" },
+            "operationType": "replace",
+            "documentKey": { "_id": 1 },
+        });
+        assert!(ChangeEvent::from_event(replace).is_err());
+    }
+
+    #[test]
+    fn test_watch_options_pipeline() {
+        let options = WatchOptions::new().stage(doc! { "$match": { "fullDocument.active": true } });
+        let pipeline = options.to_pipeline();
+        assert_eq!(pipeline.len(), 2);
+        assert_eq!(
+            pipeline[0],
+            doc! { "$match": { "operationType": { "$in": ["insert", "update", "replace", "delete"] } } }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_token_store() {
+        let store = MemoryTokenStore::new();
+        assert!(store.load("users").await.unwrap().is_none());
+
+        let token = event(doc! { "_id": { "_data": "05" }, "operationType": "delete" }).id;
+        store.save("users", &token).await.unwrap();
+        assert_eq!(store.load("users").await.unwrap(), Some(token));
+        assert!(store.load("posts").await.unwrap().is_none());
+    }
+}
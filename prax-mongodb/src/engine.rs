@@ -10,6 +10,7 @@ use prax_query::filter::FilterValue;
 use prax_query::traits::{BoxFuture, Model, QueryEngine};
 use tracing::debug;
 
+use crate::change_stream::{self, ChangeEventStream, WatchOptions};
 use crate::client::MongoClient;
 use crate::error::MongoError;
 use crate::types::filter_value_to_bson;
@@ -52,6 +53,23 @@ impl MongoEngine {
         self.client.collection(name)
     }
 
+    /// Subscribe to the changes of a model's collection.
+    ///
+    /// The stream yields inserts, updates, replacements and deletes as typed
+    /// [`ChangeEvent`](crate::change_stream::ChangeEvent)s. With a token store
+    /// set on the options, it resumes after the last handled event of the
+    /// previous subscription of the same name. Change streams require a
+    /// replica set or sharded cluster.
+    pub async fn watch_collection<T>(
+        &self,
+        options: WatchOptions,
+    ) -> MongoResult<ChangeEventStream<T>>
+    where
+        T: Model + serde::de::DeserializeOwned + Unpin + Send + Sync + 'static,
+    {
+        change_stream::watch(self.collection::<T>(), options).await
+    }
+
     /// Convert filter values to a MongoDB filter document.
     fn build_filter(sql: &str, params: &[FilterValue]) -> MongoResult<Document> {
         // For MongoDB, we expect the "sql" to actually be a JSON representation
@@ -246,6 +246,12 @@ impl FilterBuilder {
     }
 }
 
+impl From<FilterBuilder> for Document {
+    fn from(builder: FilterBuilder) -> Self {
+        builder.build()
+    }
+}
+
 /// Create an empty filter (matches all documents).
 pub fn all() -> Document {
     doc! {}
@@ -7,8 +7,8 @@
 //! - Built-in connection pooling
 //! - Document serialization/deserialization via BSON
 //! - Type-safe query building
-//! - Aggregation pipeline support
-//! - Change streams for real-time updates
+//! - Aggregation pipeline support, with typed pipelines over models
+//! - Change streams for real-time updates, with resume-token persistence
 //!
 //! ## Example
 //!
@@ -51,22 +51,29 @@
 //! }
 //! ```
 
+pub mod change_stream;
 pub mod client;
 pub mod config;
 pub mod document;
 pub mod engine;
 pub mod error;
 pub mod filter;
+pub mod pipeline;
 pub mod types;
 pub mod view;
 
 pub use bson::oid::ObjectId;
 pub use bson::{Bson, Document, doc};
+pub use change_stream::{
+    ChangeEvent, ChangeEventStream, CollectionTokenStore, MemoryTokenStore, ResumeTokenStore,
+    WatchOptions,
+};
 pub use client::{MongoClient, MongoClientBuilder};
 pub use config::{MongoConfig, MongoConfigBuilder};
 pub use engine::MongoEngine;
 pub use error::{MongoError, MongoResult};
 pub use filter::FilterBuilder;
+pub use pipeline::PipelineBuilder;
 pub use view::{
     AggregationView, AggregationViewBuilder, MaterializedAggregationView, MergeAction,
     MergeNotMatchedAction, MergeOptions,
@@ -74,12 +81,14 @@ pub use view::{
 
 /// Prelude for convenient imports.
 pub mod prelude {
+    pub use crate::change_stream::{ChangeEvent, ResumeTokenStore, WatchOptions};
     pub use crate::client::{MongoClient, MongoClientBuilder};
     pub use crate::config::{MongoConfig, MongoConfigBuilder};
     pub use crate::document::DocumentExt;
     pub use crate::engine::MongoEngine;
     pub use crate::error::{MongoError, MongoResult};
     pub use crate::filter::FilterBuilder;
+    pub use crate::pipeline::PipelineBuilder;
     pub use crate::view::{
         accumulators, stages, AggregationView, AggregationViewBuilder, MaterializedAggregationView,
         MergeAction, MergeNotMatchedAction, MergeOptions,
@@ -0,0 +1,398 @@
+//! Typed aggregation pipelines.
+//!
+//! [`PipelineBuilder`] builds `$match`, `$group`, `$lookup`, `$unwind` and
+//! `$project` stages from model field names, such as the generated
+//! `user::email::COLUMN` constants, and deserializes the results into any
+//! `serde` type.
+//!
+//! Field names are checked as stages are added: the first stage sees the
+//! model's columns, `$lookup` adds its `as` field, and `$group` and
+//! `$project` replace the known fields with their outputs. Misspelled or
+//! stale fields are reported by [`build`](PipelineBuilder::build) instead of
+//! silently matching nothing.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use prax_mongodb::pipeline::PipelineBuilder;
+//! use prax_mongodb::view::accumulators;
+//! use prax_mongodb::FilterBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct AuthorStats {
+//!     #[serde(rename = "_id")]
+//!     author: ObjectId,
+//!     posts: i64,
+//!     comments: i64,
+//! }
+//!
+//! let stats: Vec<AuthorStats> = PipelineBuilder::<Post>::new()
+//!     .match_stage(FilterBuilder::new().eq(post::published::COLUMN, true))
+//!     .lookup_stage::<Comment>(post::id::COLUMN, comment::post_id::COLUMN, "comments")
+//!     .group_stage(
+//!         Some(post::author_id::COLUMN),
+//!         [
+//!             ("posts", accumulators::count()),
+//!             ("comments", accumulators::sum(doc! { "$size": "$comments" })),
+//!         ],
+//!     )
+//!     .run(&engine)
+//!     .await?;
+//! ```
+
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+
+use bson::{Bson, Document, doc};
+use futures::TryStreamExt;
+use prax_query::traits::Model;
+use prax_query::types::SortOrder;
+use serde::de::DeserializeOwned;
+use tracing::debug;
+
+use crate::engine::MongoEngine;
+use crate::error::{MongoError, MongoResult};
+
+/// Get the collection a model is stored in.
+fn collection_name<M: Model>() -> String {
+    format!("{}s", M::MODEL_NAME.to_lowercase())
+}
+
+/// Create a field path expression (`"$field"`) for use in accumulators and
+/// other aggregation expressions.
+pub fn field(name: &str) -> Bson {
+    Bson::String(format!("${}", name))
+}
+
+/// Builder for aggregation pipelines over the collection of model `T`.
+#[derive(Debug, Clone)]
+pub struct PipelineBuilder<T> {
+    collection: String,
+    stages: Vec<Document>,
+    /// Fields of the documents flowing out of the last stage, or `None`
+    /// once a raw stage makes them unknown.
+    fields: Option<BTreeSet<String>>,
+    unknown: Vec<String>,
+    _model: PhantomData<fn() -> T>,
+}
+
+impl<T: Model> Default for PipelineBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Model> PipelineBuilder<T> {
+    /// Create an empty pipeline over the model's collection.
+    pub fn new() -> Self {
+        let mut fields: BTreeSet<String> = T::COLUMNS.iter().map(|c| c.to_string()).collect();
+        fields.insert("_id".to_string());
+        Self {
+            collection: collection_name::<T>(),
+            stages: Vec::new(),
+            fields: Some(fields),
+            unknown: Vec::new(),
+            _model: PhantomData,
+        }
+    }
+
+    /// Run against a collection other than the model's default one.
+    pub fn collection(mut self, name: impl Into<String>) -> Self {
+        self.collection = name.into();
+        self
+    }
+
+    /// Add a `$match` stage from a filter document or [`FilterBuilder`](crate::FilterBuilder).
+    pub fn match_stage(mut self, filter: impl Into<Document>) -> Self {
+        let filter = filter.into();
+        self.check_filter(&filter);
+        self.stages.push(doc! { "$match": filter });
+        self
+    }
+
+    /// Add a `$group` stage keyed by a field, or over all documents with
+    /// `None`, computing the named accumulators.
+    ///
+    /// The output documents have `_id` and the accumulator fields.
+    pub fn group_stage<'a>(
+        mut self,
+        key: Option<&str>,
+        accumulators: impl IntoIterator<Item = (&'a str, Bson)>,
+    ) -> Self {
+        let id = match key {
+            Some(key) => {
+                self.check(key);
+                field(key)
+            }
+            None => Bson::Null,
+        };
+        let mut group = doc! { "_id": id };
+        let mut outputs = BTreeSet::from(["_id".to_string()]);
+        for (name, accumulator) in accumulators {
+            group.insert(name, accumulator);
+            outputs.insert(name.to_string());
+        }
+        self.stages.push(doc! { "$group": group });
+        self.fields = Some(outputs);
+        self
+    }
+
+    /// Add a `$lookup` stage joining the collection of model `F`, storing the
+    /// matching documents as an array in `as_field`.
+    pub fn lookup_stage<F: Model>(
+        mut self,
+        local_field: &str,
+        foreign_field: &str,
+        as_field: &str,
+    ) -> Self {
+        self.check(local_field);
+        if foreign_field != "_id" && !F::COLUMNS.contains(&root(foreign_field)) {
+            self.unknown
+                .push(format!("{}.{}", F::MODEL_NAME, foreign_field));
+        }
+        self.stages.push(doc! {
+            "$lookup": {
+                "from": collection_name::<F>(),
+                "localField": local_field,
+                "foreignField": foreign_field,
+                "as": as_field,
+            }
+        });
+        if let Some(fields) = &mut self.fields {
+            fields.insert(as_field.to_string());
+        }
+        self
+    }
+
+    /// Add an `$unwind` stage, producing one document per element of the
+    /// array field. Documents with a missing or empty array are dropped.
+    pub fn unwind_stage(mut self, path: &str) -> Self {
+        self.check(path);
+        self.stages.push(doc! { "$unwind": field(path) });
+        self
+    }
+
+    /// Add an `$unwind` stage that keeps documents with a missing or empty
+    /// array, as a left outer join would.
+    pub fn unwind_stage_preserving(mut self, path: &str) -> Self {
+        self.check(path);
+        self.stages.push(doc! {
+            "$unwind": { "path": field(path), "preserveNullAndEmptyArrays": true }
+        });
+        self
+    }
+
+    /// Add a `$project` stage keeping only the given fields (and `_id`).
+    pub fn project_stage<'a>(mut self, fields: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut projection = Document::new();
+        let mut outputs = BTreeSet::from(["_id".to_string()]);
+        for name in fields {
+            self.check(name);
+            projection.insert(name, 1);
+            outputs.insert(root(name).to_string());
+        }
+        self.stages.push(doc! { "$project": projection });
+        self.fields = Some(outputs);
+        self
+    }
+
+    /// Add a `$sort` stage on a field.
+    pub fn sort_stage(mut self, name: &str, order: SortOrder) -> Self {
+        self.check(name);
+        let direction = match order {
+            SortOrder::Asc => 1,
+            SortOrder::Desc => -1,
+        };
+        self.stages.push(doc! { "$sort": { name: direction } });
+        self
+    }
+
+    /// Add a `$limit` stage.
+    pub fn limit_stage(mut self, limit: i64) -> Self {
+        self.stages.push(doc! { "$limit": limit });
+        self
+    }
+
+    /// Add a `$skip` stage.
+    pub fn skip_stage(mut self, skip: i64) -> Self {
+        self.stages.push(doc! { "$skip": skip });
+        self
+    }
+
+    /// Add a hand-written stage.
+    ///
+    /// The fields of its output are unknown, so later stages are no longer
+    /// checked.
+    pub fn add_stage(mut self, stage: Document) -> Self {
+        self.stages.push(stage);
+        self.fields = None;
+        self
+    }
+
+    /// Get the collection the pipeline runs against.
+    pub fn collection_name(&self) -> &str {
+        &self.collection
+    }
+
+    /// Get the stages added so far.
+    pub fn stages(&self) -> &[Document] {
+        &self.stages
+    }
+
+    /// Build the pipeline, failing if a stage referenced an unknown field.
+    pub fn build(self) -> MongoResult<Vec<Document>> {
+        if !self.unknown.is_empty() {
+            return Err(MongoError::query(format!(
+                "unknown field(s) in {} pipeline: {}",
+                T::MODEL_NAME,
+                self.unknown.join(", ")
+            )));
+        }
+        Ok(self.stages)
+    }
+
+    /// Run the pipeline and deserialize each result document into `R`.
+    pub async fn run<R: DeserializeOwned>(self, engine: &MongoEngine) -> MongoResult<Vec<R>> {
+        let collection = engine.client().collection_doc(&self.collection);
+        let pipeline = self.build()?;
+        debug!(collection = %collection.name(), stages = pipeline.len(), "Running pipeline");
+
+        let cursor = collection.aggregate(pipeline, None).await?;
+        let docs: Vec<Document> = cursor.try_collect().await?;
+        docs.into_iter()
+            .map(|doc| bson::from_document(doc).map_err(MongoError::from))
+            .collect()
+    }
+
+    /// Record `path` as unknown unless its first segment is a known field.
+    fn check(&mut self, path: &str) {
+        if let Some(fields) = &self.fields {
+            if !fields.contains(root(path)) {
+                self.unknown.push(path.to_string());
+            }
+        }
+    }
+
+    /// Check the fields of a filter, descending into `$and`, `$or` and `$nor`.
+    fn check_filter(&mut self, filter: &Document) {
+        for (key, value) in filter {
+            match key.as_str() {
+                "$and" | "$or" | "$nor" => {
+                    if let Bson::Array(conditions) = value {
+                        for condition in conditions {
+                            if let Bson::Document(condition) = condition {
+                                self.check_filter(condition);
+                            }
+                        }
+                    }
+                }
+                key if key.starts_with('$') => {}
+                key => self.check(key),
+            }
+        }
+    }
+}
+
+/// Get the top-level field of a dotted path.
+fn root(path: &str) -> &str {
+    path.split('.').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FilterBuilder;
+    use crate::view::accumulators;
+
+    struct Post;
+
+    impl Model for Post {
+        const MODEL_NAME: &'static str = "Post";
+        const TABLE_NAME: &'static str = "posts";
+        const PRIMARY_KEY: &'static [&'static str] = &["_id"];
+        const COLUMNS: &'static [&'static str] = &["title", "author_id", "published", "tags"];
+    }
+
+    struct Comment;
+
+    impl Model for Comment {
+        const MODEL_NAME: &'static str = "Comment";
+        const TABLE_NAME: &'static str = "comments";
+        const PRIMARY_KEY: &'static [&'static str] = &["_id"];
+        const COLUMNS: &'static [&'static str] = &["post_id", "body"];
+    }
+
+    #[test]
+    fn test_pipeline_stages() {
+        let pipeline = PipelineBuilder::<Post>::new()
+            .match_stage(FilterBuilder::new().eq("published", true))
+            .lookup_stage::<Comment>("_id", "post_id", "comments")
+            .unwind_stage("comments")
+            .group_stage(
+                Some("author_id"),
+                [
+                    ("comments", accumulators::count()),
+                    ("titles", accumulators::add_to_set(field("title"))),
+                ],
+            )
+            .sort_stage("comments", SortOrder::Desc)
+            .limit_stage(10)
+            .build()
+            .unwrap();
+
+        assert_eq!(pipeline.len(), 6);
+        assert_eq!(pipeline[0], doc! { "$match": { "published": true } });
+        assert_eq!(
+            pipeline[1],
+            doc! {
+                "$lookup": {
+                    "from": "comments",
+                    "localField": "_id",
+                    "foreignField": "post_id",
+                    "as": "comments",
+                }
+            }
+        );
+        assert_eq!(pipeline[2], doc! { "$unwind": "$comments" });
+        assert_eq!(
+            pipeline[3],
+            doc! {
+                "$group": {
+                    "_id": "$author_id",
+                    "comments": { "$sum": 1 },
+                    "titles": { "$addToSet": "$title" },
+                }
+            }
+        );
+        assert_eq!(pipeline[4], doc! { "$sort": { "comments": -1 } });
+    }
+
+    #[test]
+    fn test_pipeline_rejects_unknown_fields() {
+        let err = PipelineBuilder::<Post>::new()
+            .match_stage(doc! { "$or": [{ "publshed": true }, { "tags.0": "rust" }] })
+            .lookup_stage::<Comment>("_id", "post", "comments")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "query error: unknown field(s) in Post pipeline: publshed, Comment.post"
+        );
+
+        // Only the group outputs are known after $group
+        let err = PipelineBuilder::<Post>::new()
+            .group_stage(Some("author_id"), [("posts", accumulators::count())])
+            .project_stage(["posts", "title"])
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().ends_with("pipeline: title"));
+
+        // Raw stages stop the checks
+        let pipeline = PipelineBuilder::<Post>::new()
+            .add_stage(doc! { "$replaceRoot": { "newRoot": "$meta" } })
+            .project_stage(["views"])
+            .build()
+            .unwrap();
+        assert_eq!(pipeline[1], doc! { "$project": { "views": 1 } });
+    }
+}
@@ -28,6 +28,10 @@ pub fn filter_value_to_bson(value: &FilterValue) -> MongoResult<Bson> {
             })?;
             Ok(bson)
         }
+        FilterValue::Bytes(bytes) => Ok(Bson::Binary(bson::Binary {
+            subtype: bson::spec::BinarySubtype::Generic,
+            bytes: bytes.clone(),
+        })),
         FilterValue::List(list) => {
             let bson_values: Result<Vec<Bson>, _> = list.iter().map(filter_value_to_bson).collect();
             Ok(Bson::Array(bson_values?))
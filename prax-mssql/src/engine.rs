@@ -4,10 +4,13 @@ use std::marker::PhantomData;
 
 use prax_query::QueryResult;
 use prax_query::filter::FilterValue;
+use prax_query::raw::RawDatabase;
+use prax_query::sql::DatabaseType;
 use prax_query::traits::{BoxFuture, Model, QueryEngine};
 use tracing::debug;
 
 use crate::pool::MssqlPool;
+use crate::row::row_to_json;
 use crate::types::filter_value_to_sql;
 
 /// Microsoft SQL Server query engine that implements the Prax QueryEngine trait.
@@ -290,6 +293,79 @@ impl QueryEngine for MssqlEngine {
             Ok(count as u64)
         })
     }
+
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::MSSQL
+    }
+}
+
+impl RawDatabase for MssqlEngine {
+    fn query(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+    ) -> BoxFuture<'_, QueryResult<Vec<serde_json::Value>>> {
+        let sql = Self::convert_params(sql);
+        Box::pin(async move {
+            debug!(sql = %sql, "Executing JSON query");
+
+            let mut conn = self
+                .pool
+                .get()
+                .await
+                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+
+            let mssql_params = Self::to_params(&params)?;
+            let param_refs: Vec<&dyn tiberius::ToSql> =
+                mssql_params.iter().map(|p| p.as_ref()).collect();
+
+            let rows = conn.query(&sql, &param_refs).await?;
+            Ok(rows.iter().map(row_to_json).collect::<Result<_, _>>()?)
+        })
+    }
+
+    fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+        self.execute_raw(sql, params)
+    }
+
+    fn execute_in_transaction(
+        &self,
+        statements: Vec<(String, Vec<FilterValue>)>,
+    ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+        Box::pin(async move {
+            let mut conn = self
+                .pool
+                .get()
+                .await
+                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            conn.begin_transaction().await?;
+
+            let mut affected = Vec::with_capacity(statements.len());
+            for (sql, params) in &statements {
+                let sql = Self::convert_params(sql);
+                debug!(sql = %sql, "Executing transaction statement");
+
+                let mssql_params = Self::to_params(params)?;
+                let param_refs: Vec<&dyn tiberius::ToSql> =
+                    mssql_params.iter().map(|p| p.as_ref()).collect();
+
+                match conn.execute(&sql, &param_refs).await {
+                    Ok(n) => affected.push(n),
+                    Err(e) => {
+                        conn.rollback().await?;
+                        return Err(e.into());
+                    }
+                }
+            }
+
+            conn.commit().await?;
+            Ok(affected)
+        })
+    }
+
+    fn database_type(&self) -> DatabaseType {
+        QueryEngine::database_type(self)
+    }
 }
 
 /// A typed query builder that uses the MSSQL engine.
@@ -1,6 +1,8 @@
 //! Microsoft SQL Server row types and deserialization.
 
-use tiberius::Row;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::Value as JsonValue;
+use tiberius::{ColumnData, FromSql, Row};
 
 use crate::error::{MssqlError, MssqlResult};
 
@@ -51,6 +53,45 @@ impl MssqlRow for Row {
     }
 }
 
+/// Convert a row to a JSON object keyed by column name.
+///
+/// `DECIMAL` and `NUMERIC` values become strings, so no digit is lost;
+/// dates and times become ISO 8601 strings and binary values arrays of bytes.
+pub fn row_to_json(row: &Row) -> MssqlResult<JsonValue> {
+    let mut object = serde_json::Map::with_capacity(row.columns().len());
+    for (column, data) in row.cells() {
+        object.insert(column.name().to_string(), column_to_json(data)?);
+    }
+    Ok(JsonValue::Object(object))
+}
+
+fn column_to_json(data: &ColumnData<'static>) -> MssqlResult<JsonValue> {
+    let value = match data {
+        ColumnData::U8(v) => v.map(JsonValue::from),
+        ColumnData::I16(v) => v.map(JsonValue::from),
+        ColumnData::I32(v) => v.map(JsonValue::from),
+        ColumnData::I64(v) => v.map(JsonValue::from),
+        ColumnData::F32(v) => v.map(JsonValue::from),
+        ColumnData::F64(v) => v.map(JsonValue::from),
+        ColumnData::Bit(v) => v.map(JsonValue::from),
+        ColumnData::String(v) => v.as_deref().map(JsonValue::from),
+        ColumnData::Guid(v) => v.map(|v| JsonValue::from(v.to_string())),
+        ColumnData::Binary(v) => v.as_deref().map(JsonValue::from),
+        ColumnData::Numeric(v) => v.map(|v| JsonValue::from(v.to_string())),
+        ColumnData::Xml(v) => v.as_ref().map(|v| JsonValue::from(v.to_string())),
+        ColumnData::DateTime(_) | ColumnData::SmallDateTime(_) | ColumnData::DateTime2(_) => {
+            NaiveDateTime::from_sql(data)?
+                .map(|v| JsonValue::from(v.format("%Y-%m-%dT%H:%M:%S%.f").to_string()))
+        }
+        ColumnData::Date(_) => NaiveDate::from_sql(data)?.map(|v| JsonValue::from(v.to_string())),
+        ColumnData::Time(_) => NaiveTime::from_sql(data)?.map(|v| JsonValue::from(v.to_string())),
+        ColumnData::DateTimeOffset(_) => {
+            DateTime::<FixedOffset>::from_sql(data)?.map(|v| JsonValue::from(v.to_rfc3339()))
+        }
+    };
+    Ok(value.unwrap_or(JsonValue::Null))
+}
+
 /// Trait for deserializing a SQL Server row into a type.
 pub trait FromMssqlRow: Sized {
     /// Deserialize from a SQL Server row.
@@ -16,6 +16,7 @@ pub fn filter_value_to_sql(value: &FilterValue) -> MssqlResult<Box<dyn tiberius:
             // MSSQL stores JSON as NVARCHAR
             Ok(Box::new(j.to_string()))
         }
+        FilterValue::Bytes(b) => Ok(Box::new(b.clone())),
         FilterValue::List(_) => {
             // Lists need special handling - they should be converted to table-valued parameters
             // or used with IN clauses
@@ -2,13 +2,20 @@
 
 use std::collections::HashMap;
 
+use futures::TryStreamExt;
+use futures::stream::{self, BoxStream};
 use mysql_async::prelude::*;
 use mysql_async::{Params, Row, Value};
 use serde_json::Value as JsonValue;
+use tokio::sync::mpsc;
 use tracing::{debug, instrument};
 
+use prax_query::capabilities::Capabilities;
+use prax_query::explain::QueryPlan;
 use prax_query::filter::FilterValue;
+use prax_query::sql::DatabaseType;
 use prax_query::types::SortOrder;
+use prax_query::{QueryError, QueryResult};
 
 use crate::error::MysqlError;
 use crate::pool::MysqlPool;
@@ -124,11 +131,13 @@ impl MysqlEngine {
         (sql, params)
     }
 
-    /// Build an INSERT query.
+    /// Build an INSERT query, returning the inserted row if the server
+    /// supports `RETURNING`.
     fn build_insert(
         &self,
         table: &str,
         data: &HashMap<String, FilterValue>,
+        capabilities: &Capabilities,
     ) -> (String, Vec<Value>) {
         let mut columns = Vec::new();
         let mut placeholders = Vec::new();
@@ -140,12 +149,15 @@ impl MysqlEngine {
             params.push(filter_value_to_mysql(val));
         }
 
-        let sql = format!(
+        let mut sql = format!(
             "INSERT INTO `{}` ({}) VALUES ({})",
             table,
             columns.join(", "),
             placeholders.join(", ")
         );
+        if capabilities.supports_returning() {
+            sql.push_str(" RETURNING *");
+        }
 
         (sql, params)
     }
@@ -221,7 +233,7 @@ impl MysqlEngine {
     }
 
     /// Convert a MySQL row to a JSON object.
-    fn row_to_json(&self, row: &Row) -> JsonValue {
+    fn row_to_json(row: &Row) -> JsonValue {
         let mut map = serde_json::Map::new();
 
         for (i, column) in row.columns_ref().iter().enumerate() {
@@ -256,7 +268,7 @@ impl MysqlEngine {
 
         let results: Vec<MysqlQueryResult> = rows
             .iter()
-            .map(|row| MysqlQueryResult::new(self.row_to_json(row)))
+            .map(|row| MysqlQueryResult::new(Self::row_to_json(row)))
             .collect();
 
         Ok(results)
@@ -281,7 +293,7 @@ impl MysqlEngine {
             .await?;
 
         match row {
-            Some(r) => Ok(MysqlQueryResult::new(self.row_to_json(&r))),
+            Some(r) => Ok(MysqlQueryResult::new(Self::row_to_json(&r))),
             None => Err(MysqlError::query(format!(
                 "No row found in table '{}' with the given filters",
                 table
@@ -307,7 +319,7 @@ impl MysqlEngine {
             .exec_first(&sql, Params::Positional(params))
             .await?;
 
-        Ok(row.map(|r| MysqlQueryResult::new(self.row_to_json(&r))))
+        Ok(row.map(|r| MysqlQueryResult::new(Self::row_to_json(&r))))
     }
 
     /// Execute an INSERT and return the result.
@@ -317,10 +329,22 @@ impl MysqlEngine {
         table: &str,
         data: &HashMap<String, FilterValue>,
     ) -> Result<MysqlQueryResult, MysqlError> {
-        let (sql, params) = self.build_insert(table, data);
+        let mut conn = self.pool.get().await?;
+        let capabilities = self.pool.capabilities();
+
+        let (sql, params) = self.build_insert(table, data, &capabilities);
         debug!(sql = %sql, "Executing insert");
 
-        let mut conn = self.pool.get().await?;
+        // MariaDB 10.5+ returns the row as stored, with defaults filled in
+        if capabilities.supports_returning() {
+            let row: Option<Row> = conn
+                .inner_mut()
+                .exec_first(&sql, Params::Positional(params))
+                .await?;
+            return row
+                .map(|row| MysqlQueryResult::new(Self::row_to_json(&row)))
+                .ok_or_else(|| MysqlError::query("INSERT ... RETURNING returned no row"));
+        }
 
         conn.inner_mut()
             .exec_drop(&sql, Params::Positional(params))
@@ -401,12 +425,92 @@ impl MysqlEngine {
 
         let results: Vec<MysqlQueryResult> = rows
             .iter()
-            .map(|row| MysqlQueryResult::new(self.row_to_json(row)))
+            .map(|row| MysqlQueryResult::new(Self::row_to_json(row)))
             .collect();
 
         Ok(results)
     }
 
+    /// Execute raw SQL and yield the results in batches of `fetch_size` rows.
+    ///
+    /// Rows are read off the connection as batches are consumed, so at most
+    /// one batch is buffered. When the stream is dropped early, the driver
+    /// discards the unread rows before the connection is reused.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut batches = engine.raw_sql_batches("SELECT * FROM events", &[], 500);
+    /// while let Some(batch) = batches.next().await {
+    ///     process(batch?);
+    /// }
+    /// ```
+    pub fn raw_sql_batches(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+        fetch_size: usize,
+    ) -> BoxStream<'static, Result<Vec<MysqlQueryResult>, MysqlError>> {
+        let pool = self.pool.clone();
+        let sql = sql.to_string();
+        let mysql_params: Vec<Value> = params.iter().map(filter_value_to_mysql).collect();
+        let fetch_size = fetch_size.max(1);
+        let (tx, rx) = mpsc::channel(1);
+
+        tokio::spawn(async move {
+            let result = async {
+                debug!(sql = %sql, fetch_size, "Executing raw SQL in batches");
+
+                let mut conn = pool.get().await?;
+                let mut rows = conn
+                    .inner_mut()
+                    .exec_iter(sql.as_str(), Params::Positional(mysql_params))
+                    .await?;
+
+                let mut batch = Vec::with_capacity(fetch_size);
+                while let Some(row) = rows.next().await? {
+                    batch.push(MysqlQueryResult::new(Self::row_to_json(&row)));
+                    if batch.len() == fetch_size {
+                        let full = std::mem::replace(&mut batch, Vec::with_capacity(fetch_size));
+                        if tx.send(Ok(full)).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                if !batch.is_empty() {
+                    let _ = tx.send(Ok(batch)).await;
+                }
+                Ok::<_, MysqlError>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+
+        Box::pin(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|batch| (batch, rx))
+        }))
+    }
+
+    /// Execute raw SQL and stream the results row by row.
+    ///
+    /// Rows are fetched in batches of `fetch_size`; see
+    /// [`raw_sql_batches`](Self::raw_sql_batches).
+    pub fn raw_sql_stream(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+        fetch_size: usize,
+    ) -> BoxStream<'static, Result<MysqlQueryResult, MysqlError>> {
+        Box::pin(
+            self.raw_sql_batches(sql, params, fetch_size)
+                .map_ok(|rows| stream::iter(rows.into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
     // =========================================================================
     // Raw SQL Functions
     // =========================================================================
@@ -464,7 +568,7 @@ impl MysqlEngine {
 
         let results: Vec<MysqlQueryResult> = rows
             .iter()
-            .map(|row| MysqlQueryResult::new(self.row_to_json(row)))
+            .map(|row| MysqlQueryResult::new(Self::row_to_json(row)))
             .collect();
 
         Ok(results)
@@ -532,7 +636,7 @@ impl MysqlEngine {
             .await?;
 
         match row {
-            Some(r) => Ok(MysqlQueryResult::new(self.row_to_json(&r))),
+            Some(r) => Ok(MysqlQueryResult::new(Self::row_to_json(&r))),
             None => Err(MysqlError::query("raw_sql_first returned no rows")),
         }
     }
@@ -564,7 +668,7 @@ impl MysqlEngine {
             .exec_first(sql, Params::Positional(mysql_params))
             .await?;
 
-        Ok(row.map(|r| MysqlQueryResult::new(self.row_to_json(&r))))
+        Ok(row.map(|r| MysqlQueryResult::new(Self::row_to_json(&r))))
     }
 
     /// Execute a raw SQL query and return a single scalar value as JSON.
@@ -636,6 +740,36 @@ impl MysqlEngine {
         Ok(())
     }
 
+    /// Get MySQL's plan for a query, running it to measure the plan when
+    /// `analyze` is set.
+    ///
+    /// Plans come from `EXPLAIN FORMAT=JSON`, or from the tree format of
+    /// `EXPLAIN ANALYZE`.
+    #[instrument(skip(self, params), fields(sql = %sql))]
+    pub async fn explain(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+        analyze: bool,
+    ) -> QueryResult<QueryPlan> {
+        let explain = QueryPlan::explain_sql(DatabaseType::MySQL, sql, analyze)?;
+        let rows = self.execute_raw(&explain, params).await?;
+
+        // The plan is the single column of the single row
+        let plan = rows
+            .first()
+            .and_then(|row| row.json().as_object())
+            .and_then(|columns| columns.values().next())
+            .and_then(JsonValue::as_str)
+            .ok_or_else(|| QueryError::deserialization("EXPLAIN returned no plan"))?;
+
+        if analyze {
+            QueryPlan::from_mysql_tree(plan)
+        } else {
+            QueryPlan::from_mysql_json(plan)
+        }
+    }
+
     /// Count rows matching the filter.
     #[instrument(skip(self, filters), fields(table = %table))]
     pub async fn count(
@@ -687,6 +821,7 @@ fn filter_value_to_json(value: &FilterValue) -> JsonValue {
             .unwrap_or(JsonValue::Null),
         FilterValue::String(s) => JsonValue::String(s.clone()),
         FilterValue::Json(j) => j.clone(),
+        FilterValue::Bytes(b) => JsonValue::from(b.clone()),
         FilterValue::List(list) => {
             JsonValue::Array(list.iter().map(filter_value_to_json).collect())
         }
@@ -1,7 +1,9 @@
 //! Error types for MySQL operations.
 
 use std::fmt;
+use std::time::Duration;
 
+use prax_query::connection::PoolSnapshot;
 use prax_query::error::QueryError;
 
 /// Result type for MySQL operations.
@@ -12,6 +14,13 @@ pub type MysqlResult<T> = Result<T, MysqlError>;
 pub enum MysqlError {
     /// Pool error.
     Pool(String),
+    /// Timed out waiting for a pooled connection.
+    PoolTimeout {
+        /// The acquire timeout.
+        timeout: Duration,
+        /// The pool's state when the checkout gave up.
+        stats: Box<PoolSnapshot>,
+    },
     /// MySQL driver error.
     Mysql(mysql_async::Error),
     /// Configuration error.
@@ -76,6 +85,12 @@ impl fmt::Display for MysqlError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Pool(msg) => write!(f, "Pool error: {}", msg),
+            Self::PoolTimeout { timeout, stats } => write!(
+                f,
+                "Pool timeout: no connection after {}ms ({})",
+                timeout.as_millis(),
+                stats
+            ),
             Self::Mysql(e) => write!(f, "MySQL error: {}", e),
             Self::Config(msg) => write!(f, "Configuration error: {}", msg),
             Self::Connection(msg) => write!(f, "Connection error: {}", msg),
@@ -107,6 +122,7 @@ impl From<MysqlError> for QueryError {
     fn from(err: MysqlError) -> Self {
         match err {
             MysqlError::Pool(msg) => QueryError::connection(msg),
+            MysqlError::PoolTimeout { timeout, stats } => QueryError::pool_timeout(timeout, *stats),
             MysqlError::Mysql(e) => QueryError::database(e.to_string()),
             MysqlError::Config(msg) => QueryError::internal(format!("config: {}", msg)),
             MysqlError::Connection(msg) => QueryError::connection(msg),
@@ -146,5 +162,14 @@ mod tests {
         let err = MysqlError::timeout("connection timed out");
         let query_err: QueryError = err.into();
         assert!(query_err.is_timeout());
+
+        let err = MysqlError::PoolTimeout {
+            timeout: Duration::from_millis(250),
+            stats: Box::default(),
+        };
+        assert!(err.to_string().contains("no connection after 250ms"));
+        let query_err: QueryError = err.into();
+        assert!(query_err.is_retryable());
+        assert!(query_err.context().pool.is_some());
     }
 }
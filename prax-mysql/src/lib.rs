@@ -38,4 +38,5 @@ pub use connection::MysqlConnection;
 pub use engine::{MysqlEngine, MysqlQueryResult};
 pub use error::{MysqlError, MysqlResult};
 pub use pool::{MysqlPool, MysqlPoolBuilder, PoolConfig};
-pub use row::FromMysqlRow;
+pub use row::{FromMysqlRow, check_drift};
+pub use types::MysqlEnum;
@@ -1,10 +1,16 @@
 //! Connection pool for MySQL.
 
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
-use mysql_async::{Opts, Pool};
-use tracing::{debug, info};
+use mysql_async::prelude::*;
+use mysql_async::{Conn, Opts, Pool};
+use prax_query::capabilities::Capabilities;
+use prax_query::connection::{PoolSnapshot, PoolTelemetry};
+use prax_query::sql::DatabaseType;
+use tokio::sync::OnceCell;
+use tracing::{debug, info, warn};
 
 use crate::config::MysqlConfig;
 use crate::connection::MysqlConnection;
@@ -15,6 +21,10 @@ use crate::error::{MysqlError, MysqlResult};
 pub struct MysqlPool {
     inner: Pool,
     config: Arc<MysqlConfig>,
+    capabilities: Arc<OnceCell<Arc<Capabilities>>>,
+    max_connections: usize,
+    acquire_timeout: Option<Duration>,
+    telemetry: Arc<PoolTelemetry>,
 }
 
 impl MysqlPool {
@@ -51,16 +61,86 @@ impl MysqlPool {
         Ok(Self {
             inner: pool,
             config: Arc::new(config),
+            capabilities: Arc::new(OnceCell::new()),
+            max_connections: pool_config.max_connections,
+            acquire_timeout: pool_config.acquire_timeout,
+            telemetry: Arc::new(PoolTelemetry::new()),
         })
     }
 
     /// Get a connection from the pool.
+    ///
+    /// Fails with [`MysqlError::PoolTimeout`] when no connection frees up
+    /// within the acquire timeout.
     pub async fn get(&self) -> MysqlResult<MysqlConnection> {
         debug!("Acquiring connection from pool");
-        let conn = self.inner.get_conn().await?;
+        let mut conn = self
+            .telemetry
+            .acquire(self.acquire_timeout, self.inner.get_conn())
+            .await
+            .ok_or_else(|| self.timed_out())??;
+        if !self.capabilities.initialized() {
+            self.probe(&mut conn).await;
+        }
         Ok(MysqlConnection::new(conn))
     }
 
+    /// Get the server's capabilities, probed on the first connection.
+    ///
+    /// Until a connection succeeds, those of a current MySQL server are
+    /// assumed.
+    pub fn capabilities(&self) -> Arc<Capabilities> {
+        self.capabilities
+            .get()
+            .cloned()
+            .unwrap_or_else(|| Arc::new(Capabilities::assumed(DatabaseType::MySQL)))
+    }
+
+    /// Probe the server's version.
+    ///
+    /// A failed probe is retried on the next connection.
+    async fn probe(&self, conn: &mut Conn) {
+        match conn
+            .query_first::<String, _>(Capabilities::version_query(DatabaseType::MySQL))
+            .await
+        {
+            Ok(version) => {
+                let capabilities = Capabilities::new(
+                    DatabaseType::MySQL,
+                    version.as_deref().unwrap_or_default(),
+                    Vec::<String>::new(),
+                );
+                debug!(
+                    version = ?capabilities.version(),
+                    mariadb = capabilities.is_mariadb(),
+                    "Probed MySQL capabilities"
+                );
+                let _ = self.capabilities.set(Arc::new(capabilities));
+            }
+            Err(e) => warn!(error = %e, "Failed to probe MySQL capabilities"),
+        }
+    }
+
+    /// Get a snapshot of the pool's size, queue depth and checkout wait times.
+    pub fn snapshot(&self) -> PoolSnapshot {
+        let metrics = self.inner.metrics();
+        self.telemetry.snapshot(
+            self.max_connections,
+            metrics.connection_count.load(Ordering::Relaxed),
+            metrics.connections_in_pool.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Build the error for a checkout that ran out of time.
+    fn timed_out(&self) -> MysqlError {
+        let stats = self.snapshot();
+        warn!(pool = %stats, "Timed out waiting for a MySQL connection");
+        MysqlError::PoolTimeout {
+            timeout: self.acquire_timeout.unwrap_or_default(),
+            stats: Box::new(stats),
+        }
+    }
+
     /// Get the pool configuration.
     pub fn config(&self) -> &MysqlConfig {
         &self.config
@@ -68,7 +148,6 @@ impl MysqlPool {
 
     /// Check if the pool is healthy by attempting to get a connection.
     pub async fn is_healthy(&self) -> bool {
-        use mysql_async::prelude::*;
         match self.inner.get_conn().await {
             Ok(mut conn) => conn.query_drop("SELECT 1").await.is_ok(),
             Err(_) => false,
@@ -95,8 +174,11 @@ pub struct PoolConfig {
     pub max_connections: usize,
     /// Minimum number of connections to keep alive.
     pub min_connections: usize,
-    /// Maximum time to wait for a connection.
+    /// Maximum time to establish a new connection.
     pub connection_timeout: Option<Duration>,
+    /// Maximum time to wait for a connection to check out; `None` waits
+    /// indefinitely.
+    pub acquire_timeout: Option<Duration>,
     /// Maximum idle time before a connection is closed.
     pub idle_timeout: Option<Duration>,
     /// Maximum lifetime of a connection.
@@ -109,6 +191,7 @@ impl Default for PoolConfig {
             max_connections: 10,
             min_connections: 1,
             connection_timeout: Some(Duration::from_secs(30)),
+            acquire_timeout: Some(Duration::from_secs(30)),
             idle_timeout: Some(Duration::from_secs(600)), // 10 minutes
             max_lifetime: Some(Duration::from_secs(1800)), // 30 minutes
         }
@@ -163,6 +246,18 @@ impl MysqlPoolBuilder {
         self
     }
 
+    /// Set the acquire timeout.
+    pub fn acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_config.acquire_timeout = Some(timeout);
+        self
+    }
+
+    /// Wait indefinitely for a connection to check out.
+    pub fn no_acquire_timeout(mut self) -> Self {
+        self.pool_config.acquire_timeout = None;
+        self
+    }
+
     /// Set the idle timeout.
     pub fn idle_timeout(mut self, timeout: Duration) -> Self {
         self.pool_config.idle_timeout = Some(timeout);
@@ -204,9 +299,14 @@ mod tests {
     fn test_pool_builder() {
         let builder = MysqlPoolBuilder::new()
             .url("mysql://localhost/test")
-            .max_connections(20);
+            .max_connections(20)
+            .acquire_timeout(Duration::from_secs(2));
 
         assert!(builder.url.is_some());
         assert_eq!(builder.pool_config.max_connections, 20);
+        assert_eq!(
+            builder.pool_config.acquire_timeout,
+            Some(Duration::from_secs(2))
+        );
     }
 }
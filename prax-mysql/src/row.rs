@@ -1,6 +1,8 @@
 //! Row deserialization traits for MySQL.
 
-use mysql_async::Row;
+use mysql_async::consts::{ColumnFlags, ColumnType};
+use mysql_async::{Column, Row};
+use prax_query::drift::{ColumnSpec, DriftError, DriftGuard};
 
 /// Trait for converting a MySQL row to a Rust type.
 ///
@@ -111,6 +113,84 @@ impl FromMysqlRow for serde_json::Value {
     }
 }
 
+/// Character set id of binary strings and blobs.
+const BINARY_CHARSET: u16 = 63;
+
+/// Get the SQL type name of a result column, for drift checks.
+pub fn column_type_name(column: &Column) -> &'static str {
+    let binary = column.character_set() == BINARY_CHARSET;
+    if column.flags().contains(ColumnFlags::ENUM_FLAG) {
+        return "enum";
+    }
+
+    match column.column_type() {
+        ColumnType::MYSQL_TYPE_DECIMAL | ColumnType::MYSQL_TYPE_NEWDECIMAL => "decimal",
+        ColumnType::MYSQL_TYPE_TINY => "tinyint",
+        ColumnType::MYSQL_TYPE_SHORT => "smallint",
+        ColumnType::MYSQL_TYPE_INT24 => "mediumint",
+        ColumnType::MYSQL_TYPE_LONG => "int",
+        ColumnType::MYSQL_TYPE_LONGLONG => "bigint",
+        ColumnType::MYSQL_TYPE_FLOAT => "float",
+        ColumnType::MYSQL_TYPE_DOUBLE => "double",
+        ColumnType::MYSQL_TYPE_TIMESTAMP | ColumnType::MYSQL_TYPE_TIMESTAMP2 => "timestamp",
+        ColumnType::MYSQL_TYPE_DATETIME | ColumnType::MYSQL_TYPE_DATETIME2 => "datetime",
+        ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => "date",
+        ColumnType::MYSQL_TYPE_TIME | ColumnType::MYSQL_TYPE_TIME2 => "time",
+        ColumnType::MYSQL_TYPE_YEAR => "year",
+        ColumnType::MYSQL_TYPE_BIT => "bit",
+        ColumnType::MYSQL_TYPE_JSON => "json",
+        ColumnType::MYSQL_TYPE_ENUM => "enum",
+        ColumnType::MYSQL_TYPE_SET => "set",
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+        | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+        | ColumnType::MYSQL_TYPE_LONG_BLOB
+        | ColumnType::MYSQL_TYPE_BLOB => {
+            if binary {
+                "blob"
+            } else {
+                "text"
+            }
+        }
+        ColumnType::MYSQL_TYPE_VARCHAR
+        | ColumnType::MYSQL_TYPE_VAR_STRING
+        | ColumnType::MYSQL_TYPE_STRING => {
+            if binary {
+                "varbinary"
+            } else {
+                "varchar"
+            }
+        }
+        ColumnType::MYSQL_TYPE_GEOMETRY => "geometry",
+        ColumnType::MYSQL_TYPE_NULL => "null",
+        _ => "unknown",
+    }
+}
+
+/// Check the columns of a result row against a model's column specs.
+///
+/// Each statement is verified once per `guard`; see [`prax_query::drift`].
+pub fn check_drift(
+    guard: &DriftGuard,
+    sql: &str,
+    table: &str,
+    specs: &[ColumnSpec],
+    row: &Row,
+) -> Result<(), DriftError> {
+    let columns: Vec<_> = row
+        .columns_ref()
+        .iter()
+        .map(|column| (column.name_str(), column_type_name(column)))
+        .collect();
+    guard.check(
+        sql,
+        table,
+        specs,
+        columns
+            .iter()
+            .map(|(name, db_type)| (name.as_ref(), *db_type)),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +216,16 @@ mod tests {
         assert!(display.contains("email"));
         assert!(display.contains("missing value"));
     }
+
+    #[test]
+    fn test_column_type_name() {
+        let column = Column::new(ColumnType::MYSQL_TYPE_LONG);
+        assert_eq!(column_type_name(&column), "int");
+
+        let column = Column::new(ColumnType::MYSQL_TYPE_BLOB).with_character_set(BINARY_CHARSET);
+        assert_eq!(column_type_name(&column), "blob");
+
+        let column = Column::new(ColumnType::MYSQL_TYPE_STRING).with_flags(ColumnFlags::ENUM_FLAG);
+        assert_eq!(column_type_name(&column), "enum");
+    }
 }
@@ -1,9 +1,11 @@
 //! Type conversion utilities for MySQL.
 
-use mysql_async::Value;
+use mysql_async::prelude::FromValue;
+use mysql_async::{FromValueError, Value};
 use serde_json::Value as JsonValue;
 
 use prax_query::filter::FilterValue;
+use prax_query::types::DbEnum;
 
 /// Convert a FilterValue to a MySQL Value.
 pub fn filter_value_to_mysql(value: &FilterValue) -> Value {
@@ -14,6 +16,7 @@ pub fn filter_value_to_mysql(value: &FilterValue) -> Value {
         FilterValue::Float(f) => Value::from(*f),
         FilterValue::String(s) => Value::from(s.as_str()),
         FilterValue::Json(j) => Value::from(j.to_string()),
+        FilterValue::Bytes(b) => Value::Bytes(b.clone()),
         FilterValue::List(list) => {
             // For lists, we serialize to JSON
             let json_array: Vec<JsonValue> = list
@@ -27,6 +30,7 @@ pub fn filter_value_to_mysql(value: &FilterValue) -> Value {
                         .unwrap_or(JsonValue::Null),
                     FilterValue::String(s) => JsonValue::String(s.clone()),
                     FilterValue::Json(j) => j.clone(),
+                    FilterValue::Bytes(b) => JsonValue::from(b.clone()),
                     FilterValue::List(_) => JsonValue::Null, // Nested lists not directly supported
                 })
                 .collect();
@@ -86,6 +90,37 @@ pub fn from_mysql_value(value: Value) -> JsonValue {
     }
 }
 
+/// A [`DbEnum`] bound to, or decoded from, an `ENUM` column.
+///
+/// ```rust,ignore
+/// let role: MysqlEnum<Role> = row.get("role").unwrap();
+/// assert_eq!(role.0, Role::Admin);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MysqlEnum<T>(pub T);
+
+impl<T: DbEnum> From<MysqlEnum<T>> for Value {
+    fn from(value: MysqlEnum<T>) -> Self {
+        Value::from(value.0.db_value())
+    }
+}
+
+impl<T: DbEnum> TryFrom<Value> for MysqlEnum<T> {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let variant = match &value {
+            Value::Bytes(bytes) => std::str::from_utf8(bytes).ok().and_then(T::from_db_value),
+            _ => None,
+        };
+        variant.map(MysqlEnum).ok_or(FromValueError(value))
+    }
+}
+
+impl<T: DbEnum> FromValue for MysqlEnum<T> {
+    type Intermediate = Self;
+}
+
 /// Simple base64 encoding for binary data.
 fn base64_encode(data: &[u8]) -> String {
     const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -123,6 +158,54 @@ fn base64_encode(data: &[u8]) -> String {
 mod tests {
     use super::*;
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Role {
+        User,
+        Admin,
+    }
+
+    impl DbEnum for Role {
+        const DB_NAME: &'static str = "Role";
+
+        fn variants() -> &'static [Self] {
+            &[Self::User, Self::Admin]
+        }
+
+        fn db_value(&self) -> &'static str {
+            match self {
+                Self::User => "USER",
+                Self::Admin => "ADMIN",
+            }
+        }
+
+        fn from_db_value(value: &str) -> Option<Self> {
+            Self::variants()
+                .iter()
+                .copied()
+                .find(|v| v.db_value() == value)
+        }
+    }
+
+    #[test]
+    fn test_mysql_enum() {
+        assert_eq!(
+            Value::from(MysqlEnum(Role::Admin)),
+            Value::Bytes(b"ADMIN".to_vec())
+        );
+
+        let role: MysqlEnum<Role> = mysql_async::from_value(Value::Bytes(b"USER".to_vec()));
+        assert_eq!(role.0, Role::User);
+        assert!(
+            mysql_async::from_value_opt::<MysqlEnum<Role>>(Value::Bytes(b"OWNER".to_vec()))
+                .is_err()
+        );
+        assert!(
+            mysql_async::from_value_opt::<Option<MysqlEnum<Role>>>(Value::NULL)
+                .unwrap()
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_filter_value_to_mysql_null() {
         let result = filter_value_to_mysql(&FilterValue::Null);
@@ -155,6 +238,33 @@ mod tests {
         assert!(matches!(result, Value::Bytes(_)));
     }
 
+    #[test]
+    fn test_filter_value_to_mysql_bytes() {
+        let result = filter_value_to_mysql(&FilterValue::Bytes(vec![0, 1]));
+        assert!(matches!(result, Value::Bytes(b) if b == [0, 1]));
+    }
+
+    #[test]
+    fn test_ttl_cutoff_is_a_datetime_literal() {
+        use prax_query::sql::DatabaseType;
+        use prax_query::ttl::not_expired;
+
+        let (sql, params) = not_expired("expires_at", DatabaseType::MySQL).to_sql(0);
+        assert_eq!(
+            sql,
+            "(expires_at IS NULL OR expires_at > CAST($1 AS DATETIME(6)))"
+        );
+        let Value::Bytes(now) = filter_value_to_mysql(&params[0]) else {
+            panic!("expected a string cutoff");
+        };
+        let now = String::from_utf8(now).unwrap();
+        assert!(
+            chrono::NaiveDateTime::parse_from_str(&now, "%Y-%m-%d %H:%M:%S%.6f").is_ok(),
+            "{}",
+            now
+        );
+    }
+
     #[test]
     fn test_from_mysql_value_null() {
         let result = from_mysql_value(Value::NULL);
@@ -33,7 +33,6 @@ impl Default for PluginConfig {
     }
 }
 
-#[allow(dead_code)]
 impl PluginConfig {
     /// Create a new empty config (all plugins disabled).
     pub fn new() -> Self {
@@ -170,6 +169,25 @@ impl PluginConfig {
         }
     }
 
+    /// Deterministic description of the effective configuration.
+    ///
+    /// Used as part of the codegen cache key so that toggling a plugin
+    /// invalidates cached output.
+    pub fn fingerprint(&self) -> String {
+        let mut overrides: Vec<_> = self.overrides.iter().collect();
+        overrides.sort();
+        let mut enabled = self.enabled_list.clone();
+        enabled.sort();
+
+        format!(
+            "all={};enabled={};overrides={:?};style={:?}",
+            self.all_enabled,
+            enabled.join(","),
+            overrides,
+            self.model_style
+        )
+    }
+
     /// Create a config from prax.toml settings and environment variables.
     ///
     /// Environment variables take precedence over prax.toml settings.
@@ -243,6 +261,22 @@ mod tests {
         assert!(!config.is_enabled("PRAX_PLUGIN_OTHER"));
     }
 
+    #[test]
+    fn test_plugin_config_fingerprint() {
+        let mut a = PluginConfig::new();
+        a.enable_by_name("debug");
+        a.enable_by_name("serde");
+
+        let mut b = PluginConfig::new();
+        b.enable_by_name("serde");
+        b.enable_by_name("debug");
+
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        b.disable_by_name("debug");
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+
     #[test]
     fn test_plugin_config_is_enabled_by_name() {
         let mut config = PluginConfig::new();
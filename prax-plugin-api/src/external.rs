@@ -0,0 +1,296 @@
+//! Protocol for external generator binaries.
+//!
+//! External generators are standalone executables that `prax generate`
+//! runs after the built-in client generator, in the style of Prisma
+//! generators. They are configured in `prax.toml`:
+//!
+//! ```toml
+//! [generator.plugins.openapi]
+//! # Optional; defaults to `prax-gen-openapi` on PATH
+//! command = "./tools/prax-gen-openapi"
+//! output = "./generated/openapi"
+//! # Any other keys are passed to the generator as options
+//! title = "My API"
+//! ```
+//!
+//! The exchange is one JSON document each way:
+//!
+//! 1. `prax` writes a [`GeneratorRequest`] to the generator's stdin.
+//! 2. The generator writes a [`GeneratorResponse`] to stdout and exits with
+//!    status 0. Anything written to stderr is shown to the user.
+//!
+//! When invoked with `--manifest`, a generator prints its
+//! [`GeneratorManifest`] instead. [`run_generator`] implements both sides
+//! of this contract.
+
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::ExitCode;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use prax_schema::Schema;
+
+use crate::{API_VERSION, PluginError, PluginResult, is_compatible};
+
+/// Prefix of generator binaries discovered on `PATH`.
+pub const GENERATOR_PREFIX: &str = "prax-gen-";
+
+/// Flag asking a generator to print its manifest.
+pub const MANIFEST_FLAG: &str = "--manifest";
+
+/// Name of the binary discovered on `PATH` for a generator.
+pub fn generator_binary_name(name: &str) -> String {
+    format!("{}{}", GENERATOR_PREFIX, name)
+}
+
+/// A `[generator.plugins.<name>]` entry in `prax.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExternalGeneratorConfig {
+    /// Executable to run. Defaults to `prax-gen-<name>` on `PATH`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// Extra command-line arguments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// Output directory. Defaults to `<generator output>/<name>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+
+    /// Generator-specific options, passed through untouched.
+    #[serde(flatten)]
+    pub options: Map<String, Value>,
+}
+
+/// Self-description printed by a generator for `--manifest`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorManifest {
+    /// Generator name.
+    pub name: String,
+    /// Plugin API version the generator speaks.
+    pub api_version: u32,
+    /// Short description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl GeneratorManifest {
+    /// Create a manifest for the current API version.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            api_version: API_VERSION,
+            description: None,
+        }
+    }
+
+    /// Set the description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+/// Request sent to a generator on stdin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratorRequest {
+    /// Plugin API version of the host.
+    pub api_version: u32,
+    /// Generator name as configured in `prax.toml`.
+    pub name: String,
+    /// The parsed and validated schema.
+    pub schema: Schema,
+    /// Path of the schema file.
+    pub schema_path: PathBuf,
+    /// Directory the generated files will be written to.
+    pub output: PathBuf,
+    /// Generator-specific options from `prax.toml`.
+    #[serde(default)]
+    pub options: Map<String, Value>,
+}
+
+/// Response written by a generator to stdout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GeneratorResponse {
+    /// Plugin API version the generator speaks.
+    #[serde(default = "default_api_version")]
+    pub api_version: u32,
+    /// Files to write, relative to the output directory.
+    #[serde(default)]
+    pub files: Vec<GeneratedFile>,
+    /// Warnings to show to the user.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl GeneratorResponse {
+    /// Create an empty response.
+    pub fn new() -> Self {
+        Self {
+            api_version: API_VERSION,
+            ..Default::default()
+        }
+    }
+
+    /// Add a file.
+    pub fn file(mut self, path: impl Into<PathBuf>, contents: impl Into<String>) -> Self {
+        self.files.push(GeneratedFile {
+            path: path.into(),
+            contents: contents.into(),
+        });
+        self
+    }
+
+    /// Add a warning.
+    pub fn warning(mut self, warning: impl Into<String>) -> Self {
+        self.warnings.push(warning.into());
+        self
+    }
+}
+
+fn default_api_version() -> u32 {
+    API_VERSION
+}
+
+/// A file produced by a generator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratedFile {
+    /// Path relative to the output directory.
+    pub path: PathBuf,
+    /// File contents.
+    pub contents: String,
+}
+
+impl GeneratedFile {
+    /// Whether the path stays inside the output directory.
+    ///
+    /// Hosts must reject files for which this returns `false`.
+    pub fn is_contained(&self) -> bool {
+        is_contained(&self.path)
+    }
+}
+
+fn is_contained(path: &Path) -> bool {
+    !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Run a generator binary.
+///
+/// Handles `--manifest`, reads the [`GeneratorRequest`] from stdin, checks
+/// the API version, calls `generate` and writes the [`GeneratorResponse`]
+/// to stdout. Errors are printed to stderr and turned into a failing exit
+/// code.
+///
+/// ```rust,no_run
+/// use prax_plugin_api::external::{GeneratorManifest, GeneratorResponse, run_generator};
+///
+/// fn main() -> std::process::ExitCode {
+///     run_generator(GeneratorManifest::new("model-list"), |request| {
+///         let names: Vec<_> = request.schema.models.keys().map(|n| n.to_string()).collect();
+///         Ok(GeneratorResponse::new().file("models.txt", names.join("\n")))
+///     })
+/// }
+/// ```
+pub fn run_generator<F>(manifest: GeneratorManifest, generate: F) -> ExitCode
+where
+    F: FnOnce(GeneratorRequest) -> PluginResult<GeneratorResponse>,
+{
+    let result = if std::env::args().skip(1).any(|a| a == MANIFEST_FLAG) {
+        write_json(&manifest)
+    } else {
+        read_request()
+            .and_then(generate)
+            .and_then(|r| write_json(&r))
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}: {}", manifest.name, e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Read and version-check a request from stdin.
+fn read_request() -> PluginResult<GeneratorRequest> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let request: GeneratorRequest = serde_json::from_str(&input)?;
+
+    if !is_compatible(request.api_version) {
+        return Err(PluginError::IncompatibleVersion {
+            expected: API_VERSION,
+            found: request.api_version,
+        });
+    }
+
+    Ok(request)
+}
+
+fn write_json<T: Serialize>(value: &T) -> PluginResult<()> {
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer(&mut stdout, value)?;
+    stdout.write_all(b"\n")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generator_binary_name() {
+        assert_eq!(generator_binary_name("openapi"), "prax-gen-openapi");
+    }
+
+    #[test]
+    fn test_generated_file_containment() {
+        let file = |p: &str| GeneratedFile {
+            path: PathBuf::from(p),
+            contents: String::new(),
+        };
+
+        assert!(file("models.txt").is_contained());
+        assert!(file("nested/./models.txt").is_contained());
+        assert!(!file("../escape.txt").is_contained());
+        assert!(!file("/etc/passwd").is_contained());
+        assert!(!file("").is_contained());
+    }
+
+    #[test]
+    fn test_response_defaults_api_version() {
+        let response: GeneratorResponse =
+            serde_json::from_str(r#"{"files": [{"path": "a.txt", "contents": "a"}]}"#).unwrap();
+
+        assert_eq!(response.api_version, API_VERSION);
+        assert_eq!(response.files.len(), 1);
+        assert!(response.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_request_roundtrip() {
+        let schema = prax_schema::parse_schema("model User {\n  id Int @id\n}\n").unwrap();
+        let request = GeneratorRequest {
+            api_version: API_VERSION,
+            name: "test".to_string(),
+            schema,
+            schema_path: PathBuf::from("schema.prax"),
+            output: PathBuf::from("generated/test"),
+            options: Map::new(),
+        };
+
+        let json = serde_json::to_string(&request).unwrap();
+        let parsed: GeneratorRequest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.schema, request.schema);
+        assert_eq!(parsed.output, request.output);
+    }
+}
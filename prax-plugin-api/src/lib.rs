@@ -0,0 +1,99 @@
+//! Stable plugin interface for Prax code generation.
+//!
+//! This crate is the public contract between Prax and third-party code
+//! generation plugins. There are two kinds of plugins:
+//!
+//! - **In-process plugins** implement [`Plugin`] and hook into token
+//!   generation of the `prax_schema!` macro.
+//! - **External generators** are standalone binaries run by
+//!   `prax generate`, configured under `[generator.plugins]` in `prax.toml`.
+//!   See the [`external`] module for the protocol.
+//!
+//! # Stability
+//!
+//! The plugin interface is versioned by [`API_VERSION`], independently of
+//! the crate version:
+//!
+//! - Adding a hook with a default implementation, a field to a protocol
+//!   message with a serde default, or a new helper is **not** a breaking
+//!   change and does not bump [`API_VERSION`].
+//! - Removing or changing the signature of a hook, or changing the meaning
+//!   of a protocol field, bumps [`API_VERSION`] and the crate's major
+//!   version.
+//!
+//! Hosts refuse to run plugins whose API version is not
+//! [compatible](is_compatible).
+//!
+//! # Example
+//!
+//! ```rust
+//! use prax_plugin_api::prax_schema::ast::Model;
+//! use prax_plugin_api::{Plugin, PluginContext, PluginOutput};
+//!
+//! struct TableNames;
+//!
+//! impl Plugin for TableNames {
+//!     fn name(&self) -> &'static str {
+//!         "table-names"
+//!     }
+//!
+//!     fn env_var(&self) -> &'static str {
+//!         "PRAX_PLUGIN_TABLE_NAMES"
+//!     }
+//!
+//!     fn on_model(&self, _ctx: &PluginContext, model: &Model) -> PluginOutput {
+//!         let table = model.table_name();
+//!         PluginOutput::with_tokens(
+//!             format!("pub const TABLE: &str = {:?};", table).parse().unwrap(),
+//!         )
+//!     }
+//! }
+//! ```
+
+pub mod config;
+pub mod external;
+pub mod plugin;
+
+pub use prax_schema;
+
+pub use config::PluginConfig;
+pub use external::{
+    ExternalGeneratorConfig, GeneratedFile, GeneratorManifest, GeneratorRequest, GeneratorResponse,
+};
+pub use plugin::{Plugin, PluginContext, PluginOutput};
+
+/// Version of the plugin interface implemented by this crate.
+pub const API_VERSION: u32 = 1;
+
+/// Whether a plugin speaking `version` can be used with this host.
+pub fn is_compatible(version: u32) -> bool {
+    version == API_VERSION
+}
+
+/// Errors raised while running a plugin.
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    /// I/O error talking to the plugin.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Malformed protocol message.
+    #[error("invalid plugin message: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The plugin speaks a different API version.
+    #[error("incompatible plugin API version {found} (expected {expected})")]
+    IncompatibleVersion {
+        /// Version supported by the host.
+        expected: u32,
+        /// Version reported by the plugin.
+        found: u32,
+    },
+
+    /// The generator failed.
+    #[error("{0}")]
+    Generator(String),
+}
+
+/// Result type for plugin operations.
+pub type PluginResult<T> = Result<T, PluginError>;
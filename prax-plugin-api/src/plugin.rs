@@ -0,0 +1,139 @@
+//! In-process code generation plugins.
+
+use proc_macro2::TokenStream;
+
+use prax_schema::ast::{CompositeType, Enum, Model, Schema, View};
+
+use crate::API_VERSION;
+use crate::config::PluginConfig;
+
+/// Output from a plugin hook.
+#[derive(Debug, Default, Clone)]
+pub struct PluginOutput {
+    /// Additional tokens to add to the module.
+    pub tokens: TokenStream,
+    /// Additional items to add at the crate root level.
+    pub root_items: TokenStream,
+    /// Additional imports needed.
+    pub imports: Vec<String>,
+}
+
+impl PluginOutput {
+    /// Create an empty plugin output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create output with tokens.
+    pub fn with_tokens(tokens: TokenStream) -> Self {
+        Self {
+            tokens,
+            ..Default::default()
+        }
+    }
+
+    /// Add tokens to the output.
+    pub fn add_tokens(&mut self, tokens: TokenStream) {
+        self.tokens.extend(tokens);
+    }
+
+    /// Add root-level items.
+    pub fn add_root_items(&mut self, tokens: TokenStream) {
+        self.root_items.extend(tokens);
+    }
+
+    /// Add an import.
+    pub fn add_import(&mut self, import: impl Into<String>) {
+        self.imports.push(import.into());
+    }
+
+    /// Merge another output into this one.
+    pub fn merge(&mut self, other: PluginOutput) {
+        self.tokens.extend(other.tokens);
+        self.root_items.extend(other.root_items);
+        self.imports.extend(other.imports);
+    }
+
+    /// Check if the output is empty.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty() && self.root_items.is_empty() && self.imports.is_empty()
+    }
+}
+
+/// Context provided to plugins during code generation.
+#[derive(Debug)]
+pub struct PluginContext<'a> {
+    /// The full schema being processed.
+    pub schema: &'a Schema,
+    /// Plugin configuration.
+    pub config: &'a PluginConfig,
+}
+
+impl<'a> PluginContext<'a> {
+    /// Create a new plugin context.
+    pub fn new(schema: &'a Schema, config: &'a PluginConfig) -> Self {
+        Self { schema, config }
+    }
+}
+
+/// Trait for implementing code generation plugins.
+///
+/// Every hook has a default implementation, so new hooks can be added in a
+/// minor release without breaking existing plugins.
+pub trait Plugin: Send + Sync {
+    /// The unique name of this plugin.
+    fn name(&self) -> &'static str;
+
+    /// The environment variable that controls this plugin.
+    /// Should follow the pattern `PRAX_PLUGIN_<NAME>`.
+    fn env_var(&self) -> &'static str;
+
+    /// Description of what this plugin does.
+    fn description(&self) -> &'static str {
+        "No description provided"
+    }
+
+    /// The plugin API version this plugin was written against.
+    fn api_version(&self) -> u32 {
+        API_VERSION
+    }
+
+    /// Custom attribute namespaces this plugin interprets.
+    ///
+    /// Attributes such as `@search.indexed` are passed through the parser
+    /// untouched; read them with `custom_attributes()` on models, fields,
+    /// enums and views.
+    fn attribute_namespaces(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Called once at the start of code generation.
+    fn on_start(&self, _ctx: &PluginContext) -> PluginOutput {
+        PluginOutput::new()
+    }
+
+    /// Called for each model in the schema.
+    fn on_model(&self, _ctx: &PluginContext, _model: &Model) -> PluginOutput {
+        PluginOutput::new()
+    }
+
+    /// Called for each enum in the schema.
+    fn on_enum(&self, _ctx: &PluginContext, _enum_def: &Enum) -> PluginOutput {
+        PluginOutput::new()
+    }
+
+    /// Called for each composite type in the schema.
+    fn on_type(&self, _ctx: &PluginContext, _type_def: &CompositeType) -> PluginOutput {
+        PluginOutput::new()
+    }
+
+    /// Called for each view in the schema.
+    fn on_view(&self, _ctx: &PluginContext, _view: &View) -> PluginOutput {
+        PluginOutput::new()
+    }
+
+    /// Called once at the end of code generation.
+    fn on_finish(&self, _ctx: &PluginContext) -> PluginOutput {
+        PluginOutput::new()
+    }
+}
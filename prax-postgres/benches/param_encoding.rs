@@ -0,0 +1,177 @@
+//! Benchmarks for binary parameter encoding and column decoding.
+//!
+//! Each group compares the text path, where a value is rendered as text and
+//! parsed back on the other side of the connection, with the binary path
+//! used by `PgParam` and `PgNumeric`. `row_to_json/binary` tracks the cost
+//! of rendering binary columns as JSON for history and projection queries.
+
+use std::hint::black_box;
+
+use bytes::BytesMut;
+use chrono::{DateTime, Utc};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use prax_postgres::PgNumeric;
+use prax_postgres::binary::{decode_column, encode_into};
+use prax_query::filter::FilterValue;
+use tokio_postgres::types::{FromSql, Type};
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// One value of each type whose encoding changed, with its text form.
+fn sample_params() -> Vec<(&'static str, Type, FilterValue)> {
+    vec![
+        (
+            "timestamptz",
+            Type::TIMESTAMPTZ,
+            "2024-02-29T10:30:00.123456+00:00".into(),
+        ),
+        (
+            "uuid",
+            Type::UUID,
+            "67e55044-10b1-426f-9247-bb680e5fe0c8".into(),
+        ),
+        (
+            "numeric",
+            Type::NUMERIC,
+            "12345678901234567890.123456789".into(),
+        ),
+        (
+            "int4[]",
+            Type::INT4_ARRAY,
+            FilterValue::List((0..32).map(FilterValue::Int).collect()),
+        ),
+        ("bytea", Type::BYTEA, FilterValue::Bytes(vec![0xAB; 256])),
+    ]
+}
+
+/// Render a value as the text protocol sends it.
+fn to_text(value: &FilterValue) -> String {
+    match value {
+        FilterValue::String(s) => s.clone(),
+        FilterValue::Int(i) => i.to_string(),
+        FilterValue::List(items) => {
+            let items: Vec<String> = items.iter().map(to_text).collect();
+            format!("{{{}}}", items.join(","))
+        }
+        FilterValue::Bytes(b) => {
+            let hex: String = b.iter().map(|byte| format!("{:02x}", byte)).collect();
+            format!("\\x{}", hex)
+        }
+        other => format!("{:?}", other),
+    }
+}
+
+/// Parse text into a typed value, as the receiving side of the text
+/// protocol must.
+fn parse_text(ty: &Type, text: &str) -> usize {
+    match *ty {
+        Type::TIMESTAMPTZ => text
+            .parse::<DateTime<Utc>>()
+            .map(|t| t.timestamp_micros() as usize)
+            .unwrap_or(0),
+        Type::UUID => uuid::Uuid::parse_str(text)
+            .map(|u| u.as_bytes().len())
+            .unwrap_or(0),
+        // Lossy: text numerics are commonly read as floats
+        Type::NUMERIC => text.parse::<f64>().map(|n| n as usize).unwrap_or(0),
+        Type::INT4_ARRAY => text
+            .trim_matches(|c| c == '{' || c == '}')
+            .split(',')
+            .filter_map(|n| n.parse::<i32>().ok())
+            .count(),
+        _ => text
+            .trim_start_matches("\\x")
+            .as_bytes()
+            .chunks(2)
+            .filter_map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+            .count(),
+    }
+}
+
+/// Decode a binary value into the same typed value.
+fn parse_binary(ty: &Type, raw: &[u8]) -> usize {
+    match *ty {
+        Type::TIMESTAMPTZ => DateTime::<Utc>::from_sql(ty, raw)
+            .map(|t| t.timestamp_micros() as usize)
+            .unwrap_or(0),
+        Type::UUID => uuid::Uuid::from_sql(ty, raw)
+            .map(|u| u.as_bytes().len())
+            .unwrap_or(0),
+        Type::NUMERIC => PgNumeric::from_sql(ty, raw).map(|n| n.0.len()).unwrap_or(0),
+        Type::INT4_ARRAY => Vec::<i32>::from_sql(ty, raw).map(|v| v.len()).unwrap_or(0),
+        _ => Vec::<u8>::from_sql(ty, raw).map(|v| v.len()).unwrap_or(0),
+    }
+}
+
+// ============================================================================
+// Parameter Encoding Benchmarks
+// ============================================================================
+
+fn bench_param_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("param_encoding");
+
+    for (name, ty, value) in sample_params() {
+        group.bench_with_input(BenchmarkId::new("text", name), &value, |b, value| {
+            b.iter(|| {
+                let text = to_text(black_box(value));
+                black_box(parse_text(&ty, &text))
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("binary", name), &value, |b, value| {
+            let mut buf = BytesMut::with_capacity(512);
+            b.iter(|| {
+                buf.clear();
+                black_box(encode_into(black_box(value), &ty, &mut buf).unwrap());
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// Column Decoding Benchmarks
+// ============================================================================
+
+fn bench_column_decoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("column_decoding");
+
+    for (name, ty, value) in sample_params() {
+        let text = to_text(&value);
+        group.bench_with_input(BenchmarkId::new("text", name), &text, |b, text| {
+            b.iter(|| black_box(parse_text(&ty, black_box(text))))
+        });
+
+        let mut raw = BytesMut::new();
+        encode_into(&value, &ty, &mut raw).unwrap();
+        group.bench_with_input(BenchmarkId::new("binary", name), &raw, |b, raw| {
+            b.iter(|| black_box(parse_binary(&ty, black_box(raw))))
+        });
+    }
+
+    group.bench_function("row_to_json/binary", |b| {
+        let columns: Vec<(Type, Vec<u8>)> = sample_params()
+            .into_iter()
+            .map(|(_, ty, value)| {
+                let mut raw = BytesMut::new();
+                encode_into(&value, &ty, &mut raw).unwrap();
+                (ty, raw.to_vec())
+            })
+            .collect();
+        b.iter(|| {
+            let row: Vec<serde_json::Value> = columns
+                .iter()
+                .map(|(ty, raw)| decode_column(ty, Some(black_box(raw))).unwrap())
+                .collect();
+            black_box(row)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_param_encoding, bench_column_decoding);
+criterion_main!(benches);
@@ -0,0 +1,757 @@
+//! Binary encoding of parameters and result columns.
+//!
+//! Parameters are bound with [`PgParam`], which encodes a [`FilterValue`] in
+//! the binary format of the type the server inferred for its placeholder. A
+//! string bound to a `timestamptz` is sent as eight bytes of microseconds, a
+//! `uuid` as sixteen bytes, a `numeric` as base-10000 digits and a list as an
+//! array, so the server never parses parameter text.
+//!
+//! Result columns are decoded the same way by [`decode_column`], which turns
+//! a binary value into JSON without a round trip through `row_to_json` text.
+//! A `numeric` keeps every digit: it becomes a JSON number only when a
+//! number represents it exactly, and a string otherwise. Use [`PgNumeric`]
+//! to bind or read a `numeric` as exact decimal text.
+
+use std::error::Error;
+use std::fmt::{self, Display, Write as _};
+
+use bytes::{BufMut, BytesMut};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use prax_query::filter::FilterValue;
+use tokio_postgres::Row;
+use tokio_postgres::types::{FromSql, IsNull, Kind, ToSql, Type, to_sql_checked};
+
+use crate::error::{PgError, PgResult};
+
+type BoxError = Box<dyn Error + Sync + Send>;
+
+/// A parameter encoded in the binary format of the type it is bound to.
+///
+/// Accepts every type; a value that does not fit the type, such as a string
+/// that is not a UUID bound to a `uuid`, fails when the query is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgParam(pub FilterValue);
+
+impl ToSql for PgParam {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        encode_into(&self.0, ty, out)
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+/// A `numeric` as exact decimal text, such as `"-12.50"`, `"NaN"` or
+/// `"Infinity"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PgNumeric(pub String);
+
+impl Display for PgNumeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl ToSql for PgNumeric {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        encode_numeric(&self.0, out)?;
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for PgNumeric {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        decode_numeric(raw).map(PgNumeric)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+}
+
+/// Encode a value in the binary format of `ty`.
+pub fn encode_into(value: &FilterValue, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+    if let Kind::Domain(base) = ty.kind() {
+        return encode_into(value, base, out);
+    }
+    let mismatch = || -> BoxError { format!("cannot encode {:?} as {}", value, ty.name()).into() };
+    let invalid = |e: &dyn Display| -> BoxError {
+        format!("invalid {} value {:?}: {}", ty.name(), value, e).into()
+    };
+
+    match value {
+        FilterValue::Null => Ok(IsNull::Yes),
+        FilterValue::Bool(b) if *ty == Type::BOOL => b.to_sql(ty, out),
+        FilterValue::Int(i) => match *ty {
+            Type::INT2 => i16::try_from(*i).map_err(|e| invalid(&e))?.to_sql(ty, out),
+            Type::INT4 => i32::try_from(*i).map_err(|e| invalid(&e))?.to_sql(ty, out),
+            Type::INT8 => i.to_sql(ty, out),
+            Type::FLOAT4 => (*i as f32).to_sql(ty, out),
+            Type::FLOAT8 => (*i as f64).to_sql(ty, out),
+            Type::NUMERIC => {
+                encode_numeric(&i.to_string(), out)?;
+                Ok(IsNull::No)
+            }
+            _ => Err(mismatch()),
+        },
+        FilterValue::Float(f) => match *ty {
+            Type::FLOAT4 => (*f as f32).to_sql(ty, out),
+            Type::FLOAT8 => f.to_sql(ty, out),
+            Type::NUMERIC => {
+                let text = match f {
+                    f if f.is_nan() => "NaN".to_string(),
+                    f if f.is_infinite() && *f > 0.0 => "Infinity".to_string(),
+                    f if f.is_infinite() => "-Infinity".to_string(),
+                    f => f.to_string(),
+                };
+                encode_numeric(&text, out)?;
+                Ok(IsNull::No)
+            }
+            _ => Err(mismatch()),
+        },
+        FilterValue::String(s) => match *ty {
+            Type::UUID => uuid::Uuid::parse_str(s)
+                .map_err(|e| invalid(&e))?
+                .to_sql(ty, out),
+            Type::DATE => s
+                .parse::<NaiveDate>()
+                .map_err(|e| invalid(&e))?
+                .to_sql(ty, out),
+            Type::TIME => s
+                .parse::<NaiveTime>()
+                .map_err(|e| invalid(&e))?
+                .to_sql(ty, out),
+            Type::TIMESTAMP => s
+                .parse::<NaiveDateTime>()
+                .map_err(|e| invalid(&e))?
+                .to_sql(ty, out),
+            Type::TIMESTAMPTZ => s
+                .parse::<DateTime<FixedOffset>>()
+                .map_err(|e| invalid(&e))?
+                .with_timezone(&Utc)
+                .to_sql(ty, out),
+            Type::NUMERIC => {
+                encode_numeric(s, out).map_err(|e| invalid(&e))?;
+                Ok(IsNull::No)
+            }
+            Type::BYTEA => match s.strip_prefix("\\x") {
+                Some(hex) => decode_hex(hex).map_err(|e| invalid(&e))?.to_sql(ty, out),
+                None => s.as_bytes().to_sql(ty, out),
+            },
+            Type::JSON | Type::JSONB => match serde_json::from_str::<serde_json::Value>(s) {
+                Ok(json) => json.to_sql(ty, out),
+                Err(_) => serde_json::Value::String(s.clone()).to_sql(ty, out),
+            },
+            _ if <&str as ToSql>::accepts(ty) => s.as_str().to_sql(ty, out),
+            _ if matches!(ty.kind(), Kind::Enum(_)) => {
+                // An enum label's binary format is its text
+                out.extend_from_slice(s.as_bytes());
+                Ok(IsNull::No)
+            }
+            _ => Err(mismatch()),
+        },
+        FilterValue::Json(j) => match *ty {
+            Type::JSON | Type::JSONB => j.to_sql(ty, out),
+            _ if <&str as ToSql>::accepts(ty) => j.to_string().as_str().to_sql(ty, out),
+            _ => Err(mismatch()),
+        },
+        FilterValue::Bytes(b) if *ty == Type::BYTEA => b.as_slice().to_sql(ty, out),
+        FilterValue::List(items) => match ty.kind() {
+            Kind::Array(member) => {
+                encode_array(items, member, out)?;
+                Ok(IsNull::No)
+            }
+            _ => Err(mismatch()),
+        },
+        FilterValue::Bool(_) | FilterValue::Bytes(_) => Err(mismatch()),
+    }
+}
+
+/// Encode a one-dimensional array of `member` values.
+fn encode_array(items: &[FilterValue], member: &Type, out: &mut BytesMut) -> Result<(), BoxError> {
+    let len = i32::try_from(items.len())?;
+    out.put_i32(i32::from(len > 0));
+    let flags = out.len();
+    out.put_i32(0);
+    out.put_u32(member.oid());
+    if len > 0 {
+        out.put_i32(len);
+        out.put_i32(1);
+    }
+
+    let mut has_nulls = false;
+    for item in items {
+        let at = out.len();
+        out.put_i32(0);
+        let size = match encode_into(item, member, out)? {
+            IsNull::Yes => {
+                has_nulls = true;
+                -1
+            }
+            IsNull::No => i32::try_from(out.len() - at - 4)?,
+        };
+        out[at..at + 4].copy_from_slice(&size.to_be_bytes());
+    }
+    out[flags..flags + 4].copy_from_slice(&i32::from(has_nulls).to_be_bytes());
+    Ok(())
+}
+
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_PINF: u16 = 0xD000;
+const NUMERIC_NINF: u16 = 0xF000;
+
+/// Encode decimal text as a `numeric`: a digit count, the weight of the
+/// first digit, a sign, the display scale and base-10000 digits.
+fn encode_numeric(text: &str, out: &mut BytesMut) -> Result<(), BoxError> {
+    let text = text.trim();
+    let is = |names: &[&str]| names.iter().any(|name| text.eq_ignore_ascii_case(name));
+    let special = if is(&["nan"]) {
+        Some(NUMERIC_NAN)
+    } else if is(&["infinity", "+infinity", "inf", "+inf"]) {
+        Some(NUMERIC_PINF)
+    } else if is(&["-infinity", "-inf"]) {
+        Some(NUMERIC_NINF)
+    } else {
+        None
+    };
+    if let Some(sign) = special {
+        out.put_i16(0);
+        out.put_i16(0);
+        out.put_u16(sign);
+        out.put_u16(0);
+        return Ok(());
+    }
+
+    let (negative, unsigned) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (int.is_empty() && frac.is_empty()) || !is_digits(int) || !is_digits(frac) {
+        return Err(format!("invalid numeric {:?}", text).into());
+    }
+    let scale = u16::try_from(frac.len())
+        .ok()
+        .filter(|scale| *scale <= 0x3FFF)
+        .ok_or("numeric scale out of range")?;
+
+    let int = int.trim_start_matches('0');
+    // Group digits in fours from the decimal point, padding with zeros
+    let lead = (4 - int.len() % 4) % 4;
+    let mut digits = Vec::with_capacity((lead + int.len() + frac.len()) / 4 + 1);
+    let (mut group, mut filled) = (0i16, lead);
+    for d in int.bytes().chain(frac.bytes()) {
+        group = group * 10 + i16::from(d - b'0');
+        filled += 1;
+        if filled == 4 {
+            digits.push(group);
+            (group, filled) = (0, 0);
+        }
+    }
+    if filled > 0 {
+        digits.push(group * 10i16.pow(4 - filled as u32));
+    }
+    let mut weight = i16::try_from((lead + int.len()) / 4)? - 1;
+    let leading = digits.iter().take_while(|d| **d == 0).count();
+    digits.drain(..leading);
+    weight -= i16::try_from(leading)?;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    let sign = if digits.is_empty() {
+        weight = 0;
+        NUMERIC_POS
+    } else if negative {
+        NUMERIC_NEG
+    } else {
+        NUMERIC_POS
+    };
+    out.put_i16(i16::try_from(digits.len())?);
+    out.put_i16(weight);
+    out.put_u16(sign);
+    out.put_u16(scale);
+    for digit in digits {
+        out.put_i16(digit);
+    }
+    Ok(())
+}
+
+/// Decode a `numeric` into exact decimal text at its display scale.
+fn decode_numeric(raw: &[u8]) -> Result<String, BoxError> {
+    let word = |i: usize| -> Result<[u8; 2], BoxError> {
+        raw.get(i * 2..i * 2 + 2)
+            .map(|b| [b[0], b[1]])
+            .ok_or_else(|| "invalid message length: numeric too short".into())
+    };
+    let count = usize::try_from(i16::from_be_bytes(word(0)?))?;
+    let weight = i32::from(i16::from_be_bytes(word(1)?));
+    let sign = u16::from_be_bytes(word(2)?);
+    let scale = usize::from(u16::from_be_bytes(word(3)?));
+    if raw.len() != 8 + count * 2 {
+        return Err("invalid message length: numeric size mismatch".into());
+    }
+    match sign {
+        NUMERIC_NAN => return Ok("NaN".to_string()),
+        NUMERIC_PINF => return Ok("Infinity".to_string()),
+        NUMERIC_NINF => return Ok("-Infinity".to_string()),
+        NUMERIC_POS | NUMERIC_NEG => {}
+        _ => return Err(format!("invalid numeric sign {:#06x}", sign).into()),
+    }
+    let digits = &raw[8..];
+    // The digit at index `i` is worth 10000^(weight - i)
+    let digit = |i: i32| -> u16 {
+        usize::try_from(i)
+            .ok()
+            .filter(|i| *i < count)
+            .map_or(0, |i| {
+                u16::from_be_bytes([digits[i * 2], digits[i * 2 + 1]])
+            })
+    };
+    let push_group = |text: &mut String, group: u16| {
+        for place in [1000, 100, 10, 1] {
+            text.push(char::from(b'0' + (group / place % 10) as u8));
+        }
+    };
+
+    let mut text = String::with_capacity(count * 4 + 3);
+    if sign == NUMERIC_NEG {
+        text.push('-');
+    }
+    if weight < 0 {
+        text.push('0');
+    } else {
+        write!(text, "{}", digit(0))?;
+        for i in 1..=weight {
+            push_group(&mut text, digit(i));
+        }
+    }
+    if scale > 0 {
+        text.push('.');
+        let start = text.len();
+        for k in 1..=i32::try_from(scale.div_ceil(4))? {
+            push_group(&mut text, digit(weight + k));
+        }
+        text.truncate(start + scale);
+    }
+    Ok(text)
+}
+
+/// Parse hex digits, as in the `\x` text format of a `bytea`.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 {
+        return Err("odd number of hex digits".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("invalid hex {:?}", hex))
+        })
+        .collect()
+}
+
+/// Check whether [`decode_column`] can decode values of type `ty`.
+pub fn can_decode(ty: &Type) -> bool {
+    match ty.kind() {
+        Kind::Array(member) | Kind::Domain(member) => can_decode(member),
+        Kind::Enum(_) => true,
+        _ => {
+            matches!(
+                *ty,
+                Type::BOOL
+                    | Type::INT2
+                    | Type::INT4
+                    | Type::INT8
+                    | Type::OID
+                    | Type::FLOAT4
+                    | Type::FLOAT8
+                    | Type::NUMERIC
+                    | Type::UUID
+                    | Type::DATE
+                    | Type::TIME
+                    | Type::TIMESTAMP
+                    | Type::TIMESTAMPTZ
+                    | Type::JSON
+                    | Type::JSONB
+                    | Type::BYTEA
+            ) || <&str as FromSql>::accepts(ty)
+        }
+    }
+}
+
+/// Decode a binary column value of type `ty` into JSON.
+///
+/// Values render as `row_to_json` would render them, except that a
+/// `timestamptz` is always in UTC and a `numeric` that a JSON number cannot
+/// hold exactly is a string.
+pub fn decode_column(ty: &Type, raw: Option<&[u8]>) -> Result<serde_json::Value, BoxError> {
+    use serde_json::Value as Json;
+
+    let Some(raw) = raw else {
+        return Ok(Json::Null);
+    };
+    match ty.kind() {
+        Kind::Domain(base) => return decode_column(base, Some(raw)),
+        Kind::Array(member) => return decode_array(member, raw),
+        Kind::Enum(_) => return Ok(Json::String(std::str::from_utf8(raw)?.to_string())),
+        _ => {}
+    }
+
+    let value = match *ty {
+        Type::BOOL => Json::Bool(bool::from_sql(ty, raw)?),
+        Type::INT2 => Json::from(i16::from_sql(ty, raw)?),
+        Type::INT4 => Json::from(i32::from_sql(ty, raw)?),
+        Type::INT8 => Json::from(i64::from_sql(ty, raw)?),
+        Type::OID => Json::from(u32::from_sql(ty, raw)?),
+        Type::FLOAT4 => float_json(f64::from(f32::from_sql(ty, raw)?)),
+        Type::FLOAT8 => float_json(f64::from_sql(ty, raw)?),
+        Type::NUMERIC => numeric_json(decode_numeric(raw)?),
+        Type::UUID => Json::String(uuid::Uuid::from_sql(ty, raw)?.to_string()),
+        Type::DATE => match i32::from_sql(&Type::INT4, raw)? {
+            i32::MAX => Json::from("infinity"),
+            i32::MIN => Json::from("-infinity"),
+            _ => Json::String(NaiveDate::from_sql(ty, raw)?.to_string()),
+        },
+        Type::TIME => Json::String(
+            NaiveTime::from_sql(ty, raw)?
+                .format("%H:%M:%S%.f")
+                .to_string(),
+        ),
+        Type::TIMESTAMP | Type::TIMESTAMPTZ => match i64::from_sql(&Type::INT8, raw)? {
+            i64::MAX => Json::from("infinity"),
+            i64::MIN => Json::from("-infinity"),
+            _ if *ty == Type::TIMESTAMP => Json::String(
+                NaiveDateTime::from_sql(ty, raw)?
+                    .format("%Y-%m-%dT%H:%M:%S%.f")
+                    .to_string(),
+            ),
+            _ => Json::String(
+                DateTime::<Utc>::from_sql(ty, raw)?
+                    .format("%Y-%m-%dT%H:%M:%S%.f%:z")
+                    .to_string(),
+            ),
+        },
+        Type::JSON | Type::JSONB => Json::from_sql(ty, raw)?,
+        Type::BYTEA => {
+            const HEX: &[u8; 16] = b"0123456789abcdef";
+            let mut hex = String::with_capacity(2 + raw.len() * 2);
+            hex.push_str("\\x");
+            for byte in raw {
+                hex.push(char::from(HEX[usize::from(byte >> 4)]));
+                hex.push(char::from(HEX[usize::from(byte & 0xF)]));
+            }
+            Json::String(hex)
+        }
+        _ if <&str as FromSql>::accepts(ty) => Json::String(<&str>::from_sql(ty, raw)?.to_string()),
+        _ => return Err(format!("cannot decode a {} column", ty.name()).into()),
+    };
+    Ok(value)
+}
+
+/// Decode an array into nested JSON arrays, one level per dimension.
+fn decode_array(member: &Type, raw: &[u8]) -> Result<serde_json::Value, BoxError> {
+    let mut buf = raw;
+    let ndim = read_i32(&mut buf)?;
+    let _has_nulls = read_i32(&mut buf)?;
+    let _member_oid = read_i32(&mut buf)?;
+    let mut dims = Vec::new();
+    for _ in 0..ndim {
+        dims.push(usize::try_from(read_i32(&mut buf)?)?);
+        let _lower_bound = read_i32(&mut buf)?;
+    }
+    let count = if dims.is_empty() {
+        0
+    } else {
+        dims.iter().product()
+    };
+
+    let mut values = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = read_i32(&mut buf)?;
+        let value = match usize::try_from(len) {
+            Ok(len) => {
+                let (value, rest) = buf
+                    .split_at_checked(len)
+                    .ok_or("invalid message length: array element too short")?;
+                buf = rest;
+                decode_column(member, Some(value))?
+            }
+            Err(_) => serde_json::Value::Null,
+        };
+        values.push(value);
+    }
+
+    // Fold the innermost dimensions into arrays first
+    for &len in dims.iter().skip(1).rev() {
+        let rows = values.len().checked_div(len).unwrap_or(0);
+        let mut items = values.into_iter();
+        values = (0..rows)
+            .map(|_| serde_json::Value::Array(items.by_ref().take(len).collect()))
+            .collect();
+    }
+    Ok(serde_json::Value::Array(values))
+}
+
+/// Read a big-endian `i32` off the front of `buf`.
+fn read_i32(buf: &mut &[u8]) -> Result<i32, BoxError> {
+    let (head, rest) = buf
+        .split_first_chunk::<4>()
+        .ok_or("invalid message length: array too short")?;
+    *buf = rest;
+    Ok(i32::from_be_bytes(*head))
+}
+
+/// A float as a JSON number, or as text where JSON has no number for it.
+fn float_json(f: f64) -> serde_json::Value {
+    match serde_json::Number::from_f64(f) {
+        Some(n) => serde_json::Value::Number(n),
+        None if f.is_nan() => "NaN".into(),
+        None if f > 0.0 => "Infinity".into(),
+        None => "-Infinity".into(),
+    }
+}
+
+/// A numeric as a JSON number if one holds it exactly, or else as text.
+fn numeric_json(text: String) -> serde_json::Value {
+    if let Ok(i) = text.parse::<i64>() {
+        return i.into();
+    }
+    let exact = match text.split_once('.') {
+        Some((int, frac)) => {
+            let frac = frac.trim_end_matches('0');
+            if frac.is_empty() {
+                int.to_string()
+            } else {
+                format!("{}.{}", int, frac)
+            }
+        }
+        None => text.clone(),
+    };
+    match text.parse::<f64>() {
+        Ok(f) if f.is_finite() && f.to_string() == exact => float_json(f),
+        _ => serde_json::Value::String(text),
+    }
+}
+
+/// The raw bytes of a column, whatever its type.
+struct Raw<'a>(Option<&'a [u8]>);
+
+impl<'a> FromSql<'a> for Raw<'a> {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        Ok(Raw(Some(raw)))
+    }
+
+    fn from_sql_null(_: &Type) -> Result<Self, BoxError> {
+        Ok(Raw(None))
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
+
+/// Decode a row into a JSON object keyed by column name.
+pub fn row_to_json(row: &Row) -> PgResult<serde_json::Value> {
+    let mut object = serde_json::Map::with_capacity(row.len());
+    for (i, column) in row.columns().iter().enumerate() {
+        let Raw(raw) = row.try_get(i)?;
+        let value = decode_column(column.type_(), raw)
+            .map_err(|e| PgError::type_conversion(format!("column {}: {}", column.name(), e)))?;
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(serde_json::Value::Object(object))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn encoded(value: FilterValue, ty: &Type) -> Option<Vec<u8>> {
+        let mut buf = BytesMut::new();
+        match PgParam(value).to_sql_checked(ty, &mut buf).unwrap() {
+            IsNull::Yes => None,
+            IsNull::No => Some(buf.to_vec()),
+        }
+    }
+
+    fn round_trip(value: FilterValue, ty: &Type) -> serde_json::Value {
+        decode_column(ty, encoded(value, ty).as_deref()).unwrap()
+    }
+
+    #[test]
+    fn test_numeric_round_trip() {
+        for text in [
+            "0",
+            "1",
+            "-1",
+            "10000",
+            "12.50",
+            "-0.0001",
+            "0.000012345",
+            "12345678901234567890.123456789012",
+            "NaN",
+            "Infinity",
+            "-Infinity",
+        ] {
+            let mut buf = BytesMut::new();
+            encode_numeric(text, &mut buf).unwrap();
+            assert_eq!(decode_numeric(&buf).unwrap(), text);
+        }
+
+        // 1234.5 = [1234, 5000] at weight 0, scale 1
+        let mut buf = BytesMut::new();
+        encode_numeric("1234.5", &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &[0, 2, 0, 0, 0, 0, 0, 1, 0x04, 0xD2, 0x13, 0x88][..]
+        );
+
+        assert!(encode_numeric("1e5", &mut BytesMut::new()).is_err());
+        assert!(encode_numeric("-", &mut BytesMut::new()).is_err());
+    }
+
+    #[test]
+    fn test_numeric_keeps_precision() {
+        assert_eq!(
+            round_trip("12345678901234567890.5".into(), &Type::NUMERIC),
+            json!("12345678901234567890.5")
+        );
+        assert_eq!(round_trip("12.50".into(), &Type::NUMERIC), json!(12.5));
+        assert_eq!(round_trip(FilterValue::Int(42), &Type::NUMERIC), json!(42));
+        assert_eq!(
+            round_trip(FilterValue::Float(0.1), &Type::NUMERIC),
+            json!(0.1)
+        );
+
+        let mut buf = BytesMut::new();
+        PgNumeric("-3.14159265358979323846".into())
+            .to_sql_checked(&Type::NUMERIC, &mut buf)
+            .unwrap();
+        let numeric = PgNumeric::from_sql(&Type::NUMERIC, &buf).unwrap();
+        assert_eq!(numeric.to_string(), "-3.14159265358979323846");
+    }
+
+    #[test]
+    fn test_encode_by_parameter_type() {
+        assert_eq!(
+            encoded(FilterValue::Int(7), &Type::INT4),
+            Some(vec![0, 0, 0, 7])
+        );
+        assert_eq!(
+            encoded("2000-01-01T00:00:01Z".into(), &Type::TIMESTAMPTZ),
+            Some(1_000_000i64.to_be_bytes().to_vec())
+        );
+        assert_eq!(
+            encoded("\\xdead".into(), &Type::BYTEA),
+            Some(vec![0xDE, 0xAD])
+        );
+        assert_eq!(encoded(FilterValue::Null, &Type::UUID), None);
+
+        let mut buf = BytesMut::new();
+        assert!(
+            PgParam("soon".into())
+                .to_sql_checked(&Type::TIMESTAMPTZ, &mut buf)
+                .is_err()
+        );
+        assert!(
+            PgParam(FilterValue::Bool(true))
+                .to_sql_checked(&Type::INT4, &mut buf)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_ttl_cutoff_binds_as_timestamptz() {
+        use prax_query::sql::DatabaseType;
+        use prax_query::ttl::not_expired;
+
+        let (sql, mut params) = not_expired("expires_at", DatabaseType::PostgreSQL).to_sql(0);
+        assert_eq!(sql, "(expires_at IS NULL OR expires_at > $1::timestamptz)");
+        let cutoff = params.remove(0);
+        let FilterValue::String(now) = &cutoff else {
+            panic!("expected a string cutoff, got {:?}", cutoff);
+        };
+        let now = now.parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            round_trip(cutoff, &Type::TIMESTAMPTZ),
+            json!(now.format("%Y-%m-%dT%H:%M:%S%.f%:z").to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_columns() {
+        let uuid = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        assert_eq!(round_trip(uuid.into(), &Type::UUID), json!(uuid));
+        assert_eq!(
+            round_trip("2024-02-29T10:30:00.5".into(), &Type::TIMESTAMP),
+            json!("2024-02-29T10:30:00.500")
+        );
+        assert_eq!(
+            round_trip("2024-02-29T10:30:00+02:00".into(), &Type::TIMESTAMPTZ),
+            json!("2024-02-29T08:30:00+00:00")
+        );
+        assert_eq!(
+            decode_column(&Type::DATE, Some(&i32::MAX.to_be_bytes())).unwrap(),
+            json!("infinity")
+        );
+        assert_eq!(
+            round_trip(FilterValue::Bytes(vec![0, 0xFF]), &Type::BYTEA),
+            json!("\\x00ff")
+        );
+        assert_eq!(
+            round_trip(FilterValue::Float(f64::NAN), &Type::FLOAT8),
+            json!("NaN")
+        );
+        assert!(!can_decode(&Type::INTERVAL));
+        assert!(can_decode(&Type::TEXT_ARRAY));
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        let list = FilterValue::List(vec![
+            FilterValue::Int(1),
+            FilterValue::Null,
+            FilterValue::Int(3),
+        ]);
+        assert_eq!(round_trip(list, &Type::INT4_ARRAY), json!([1, null, 3]));
+        assert_eq!(
+            round_trip(FilterValue::List(vec![]), &Type::UUID_ARRAY),
+            json!([])
+        );
+        assert_eq!(
+            round_trip(
+                FilterValue::List(vec!["1.10".into(), "2".into()]),
+                &Type::NUMERIC_ARRAY
+            ),
+            json!([1.1, 2])
+        );
+
+        // A 2x2 array as Postgres sends it
+        let mut raw = BytesMut::new();
+        for word in [2, 0, 23, 2, 1, 2, 1] {
+            raw.put_i32(word);
+        }
+        for n in 1..=4 {
+            raw.put_i32(4);
+            raw.put_i32(n);
+        }
+        assert_eq!(
+            decode_column(&Type::INT4_ARRAY, Some(&raw)).unwrap(),
+            json!([[1, 2], [3, 4]])
+        );
+    }
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use deadpool_postgres::Object;
-use tokio_postgres::Row;
+use tokio_postgres::{Portal, Row, Statement};
 use tracing::debug;
 
 use crate::error::PgResult;
@@ -93,6 +93,12 @@ impl PgConnection {
         Ok(count)
     }
 
+    /// Prepare a statement through the statement cache, to inspect its
+    /// parameter and column types before running it.
+    pub async fn prepare(&self, sql: &str) -> PgResult<Statement> {
+        self.statement_cache.get_or_prepare(&self.client, sql).await
+    }
+
     /// Execute a batch of statements in a single round-trip.
     pub async fn batch_execute(&self, sql: &str) -> PgResult<()> {
         debug!(sql = %sql, "Executing batch");
@@ -110,6 +116,11 @@ impl PgConnection {
         })
     }
 
+    /// Close the connection instead of returning it to the pool.
+    pub(crate) fn discard(self) {
+        drop(Object::take(self.client));
+    }
+
     /// Get the underlying tokio-postgres client.
     ///
     /// This is useful for advanced operations not covered by this wrapper.
@@ -226,6 +237,31 @@ impl<'a> PgTransaction<'a> {
         Ok(count)
     }
 
+    /// Bind a query to a portal whose rows can be fetched incrementally.
+    pub async fn bind(
+        &self,
+        sql: &str,
+        params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    ) -> PgResult<Portal> {
+        debug!(sql = %sql, "Binding portal");
+
+        let stmt = self
+            .statement_cache
+            .get_or_prepare_in_txn(&self.txn, sql)
+            .await?;
+
+        let portal = self.txn.bind(&stmt, params).await?;
+        Ok(portal)
+    }
+
+    /// Fetch up to `max_rows` rows from a portal.
+    ///
+    /// Fewer than `max_rows` rows means the portal is exhausted.
+    pub async fn query_portal(&self, portal: &Portal, max_rows: i32) -> PgResult<Vec<Row>> {
+        let rows = self.txn.query_portal(portal, max_rows).await?;
+        Ok(rows)
+    }
+
     /// Create a savepoint.
     pub async fn savepoint(&mut self, name: &str) -> PgResult<()> {
         debug!(name = %name, "Creating savepoint");
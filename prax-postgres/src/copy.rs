@@ -0,0 +1,223 @@
+//! Bulk inserts through the binary `COPY` protocol.
+//!
+//! Rows are streamed with `COPY ... FROM STDIN (FORMAT binary)` in chunks of
+//! [`DEFAULT_CHUNK_SIZE`] rows. Each value is encoded for the type of its
+//! column, read from the table before the first chunk, so an `Int` lands in
+//! an `int4` column as four bytes rather than failing the server's format
+//! check. A failure is reported as [`PgError::CopyRow`] with the index of the
+//! offending row in the input.
+
+use std::error::Error;
+use std::pin::pin;
+
+use bytes::{Bytes, BytesMut};
+use prax_query::filter::FilterValue;
+use tokio_postgres::Client;
+use tokio_postgres::binary_copy::BinaryCopyInWriter;
+use tokio_postgres::types::{IsNull, ToSql, Type, to_sql_checked};
+use tracing::debug;
+
+use crate::binary::encode_into;
+use crate::error::{PgError, PgResult};
+
+/// Rows sent per `COPY` statement.
+pub const DEFAULT_CHUNK_SIZE: usize = 10_000;
+
+/// Copy rows into `table`, `chunk_size` rows per `COPY` statement.
+///
+/// Returns the number of rows copied. Chunks already copied are not undone
+/// when a later one fails; run inside a transaction for all-or-nothing.
+pub async fn copy_in(
+    client: &Client,
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<FilterValue>],
+    chunk_size: usize,
+) -> PgResult<u64> {
+    let chunk_size = chunk_size.max(1);
+    let types = column_types(client, table, columns).await?;
+    let statement = format!(
+        "COPY {} ({}) FROM STDIN (FORMAT binary)",
+        table,
+        columns.join(", ")
+    );
+
+    let mut copied = 0;
+    for (i, chunk) in rows.chunks(chunk_size).enumerate() {
+        let offset = i * chunk_size;
+        debug!(table = %table, offset, rows = chunk.len(), "Copying chunk");
+        copied += copy_chunk(client, &statement, &types, chunk, offset).await?;
+    }
+    Ok(copied)
+}
+
+/// Look up the types of the target columns.
+async fn column_types(client: &Client, table: &str, columns: &[String]) -> PgResult<Vec<Type>> {
+    let probe = format!("SELECT {} FROM {} LIMIT 0", columns.join(", "), table);
+    let statement = client.prepare(&probe).await?;
+    Ok(statement
+        .columns()
+        .iter()
+        .map(|column| column.type_().clone())
+        .collect())
+}
+
+/// Copy one chunk of rows, the first of which is row `offset` of the input.
+async fn copy_chunk(
+    client: &Client,
+    statement: &str,
+    types: &[Type],
+    rows: &[Vec<FilterValue>],
+    offset: usize,
+) -> PgResult<u64> {
+    let sink = client.copy_in(statement).await?;
+    let mut writer = pin!(BinaryCopyInWriter::new(sink, types));
+
+    for (i, row) in rows.iter().enumerate() {
+        let row_index = offset + i;
+        let values = encode_row(row, types).map_err(|e| PgError::copy_row(row_index, e))?;
+        let refs: Vec<&(dyn ToSql + Sync)> = values.iter().map(|v| v.as_ref() as _).collect();
+        writer
+            .as_mut()
+            .write(&refs)
+            .await
+            .map_err(|e| at_row(e, offset, Some(row_index)))?;
+    }
+
+    writer
+        .as_mut()
+        .finish()
+        .await
+        .map_err(|e| at_row(e, offset, None))
+}
+
+/// Attach the failing row to a server error, from the line number in its
+/// `COPY <table>, line <n>` context, or else to the row being written.
+fn at_row(err: tokio_postgres::Error, offset: usize, writing: Option<usize>) -> PgError {
+    let line = err
+        .as_db_error()
+        .and_then(|db| db.where_())
+        .and_then(copy_line);
+    match line.map(|line| offset + line - 1).or(writing) {
+        Some(row) => PgError::copy_row(row, PgError::Postgres(err)),
+        None => PgError::Postgres(err),
+    }
+}
+
+/// Parse the 1-based line number out of a `COPY` error context.
+fn copy_line(context: &str) -> Option<usize> {
+    let rest = &context[context.find(", line ")? + ", line ".len()..];
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..digits].parse().ok().filter(|line| *line > 0)
+}
+
+/// Encode a row for the column types.
+fn encode_row(row: &[FilterValue], types: &[Type]) -> PgResult<Vec<Box<dyn ToSql + Sync + Send>>> {
+    if row.len() != types.len() {
+        return Err(PgError::type_conversion(format!(
+            "row has {} values for {} columns",
+            row.len(),
+            types.len()
+        )));
+    }
+    row.iter().zip(types).map(|(v, ty)| encode(v, ty)).collect()
+}
+
+/// Encode a value for a column of type `ty`.
+///
+/// The value is encoded up front, so a value that does not fit the column
+/// fails here rather than part way through the `COPY`.
+pub fn encode(value: &FilterValue, ty: &Type) -> PgResult<Box<dyn ToSql + Sync + Send>> {
+    let mut buf = BytesMut::new();
+    let encoded = match encode_into(value, ty, &mut buf) {
+        Ok(IsNull::Yes) => None,
+        Ok(IsNull::No) => Some(buf.freeze()),
+        Err(e) => return Err(PgError::type_conversion(e.to_string())),
+    };
+    Ok(Box::new(Encoded(encoded)))
+}
+
+/// A value already in the binary format of its column.
+#[derive(Debug)]
+struct Encoded(Option<Bytes>);
+
+impl ToSql for Encoded {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match &self.0 {
+            Some(bytes) => {
+                out.extend_from_slice(bytes);
+                Ok(IsNull::No)
+            }
+            None => Ok(IsNull::Yes),
+        }
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded_bytes(value: &FilterValue, ty: &Type) -> Option<Vec<u8>> {
+        let mut buf = BytesMut::new();
+        match encode(value, ty)
+            .unwrap()
+            .to_sql_checked(ty, &mut buf)
+            .unwrap()
+        {
+            IsNull::Yes => None,
+            IsNull::No => Some(buf.to_vec()),
+        }
+    }
+
+    #[test]
+    fn test_encode_by_column_type() {
+        assert_eq!(
+            encoded_bytes(&FilterValue::Int(7), &Type::INT4),
+            Some(vec![0, 0, 0, 7])
+        );
+        assert_eq!(
+            encoded_bytes(&FilterValue::Int(7), &Type::INT2),
+            Some(vec![0, 7])
+        );
+        assert_eq!(encoded_bytes(&FilterValue::Null, &Type::UUID), None);
+        assert_eq!(
+            encoded_bytes(&FilterValue::String("2024-02-29".into()), &Type::DATE),
+            Some(8_825i32.to_be_bytes().to_vec())
+        );
+        assert!(
+            encoded_bytes(
+                &FilterValue::String("67e55044-10b1-426f-9247-bb680e5fe0c8".into()),
+                &Type::UUID
+            )
+            .is_some()
+        );
+
+        assert!(encode(&FilterValue::Int(1 << 40), &Type::INT4).is_err());
+        assert!(encode(&FilterValue::String("soon".into()), &Type::TIMESTAMPTZ).is_err());
+        assert!(encode(&FilterValue::Bool(true), &Type::INT4).is_err());
+    }
+
+    #[test]
+    fn test_encode_row_checks_width() {
+        let err = encode_row(&[FilterValue::Int(1)], &[Type::INT4, Type::TEXT]).unwrap_err();
+        assert!(err.to_string().contains("1 values for 2 columns"));
+    }
+
+    #[test]
+    fn test_copy_line() {
+        assert_eq!(
+            copy_line("COPY posts, line 3, column title: \"x\""),
+            Some(3)
+        );
+        assert_eq!(copy_line("COPY posts, line 12"), Some(12));
+        assert_eq!(copy_line("SQL statement"), None);
+    }
+}
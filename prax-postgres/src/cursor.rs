@@ -0,0 +1,77 @@
+//! Incremental row fetching through portals.
+//!
+//! A portal is PostgreSQL's server-side cursor for a bound statement. Fetching
+//! from it with a row limit keeps only one batch in memory at a time, and the
+//! rest of the result set is never sent if the consumer stops early.
+
+use futures::stream::{self, BoxStream};
+use prax_query::filter::FilterValue;
+use tokio::sync::mpsc;
+use tokio_postgres::Row;
+use tracing::debug;
+
+use crate::error::PgResult;
+use crate::pool::PgPool;
+use crate::types::filter_values_to_params;
+
+/// A stream of row batches fetched from a portal.
+pub type RowBatches = BoxStream<'static, PgResult<Vec<Row>>>;
+
+/// Fetch the rows of a query `fetch_size` rows at a time.
+///
+/// The query runs in a transaction on a dedicated pooled connection. Dropping
+/// the stream stops fetching and rolls the transaction back, closing the portal.
+pub fn fetch_batches(
+    pool: PgPool,
+    sql: String,
+    params: Vec<FilterValue>,
+    fetch_size: usize,
+) -> RowBatches {
+    // One batch in flight while the consumer processes the previous one
+    let (tx, rx) = mpsc::channel(1);
+
+    tokio::spawn(async move {
+        if let Err(e) = run(pool, &sql, &params, fetch_size, &tx).await {
+            let _ = tx.send(Err(e)).await;
+        }
+    });
+
+    Box::pin(stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|batch| (batch, rx))
+    }))
+}
+
+async fn run(
+    pool: PgPool,
+    sql: &str,
+    params: &[FilterValue],
+    fetch_size: usize,
+    tx: &mpsc::Sender<PgResult<Vec<Row>>>,
+) -> PgResult<()> {
+    let max_rows = i32::try_from(fetch_size).unwrap_or(i32::MAX).max(1);
+    debug!(sql = %sql, max_rows, "Fetching rows through portal");
+
+    let pg_params = filter_values_to_params(params)?;
+    let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+        pg_params.iter().map(|p| p.as_ref() as _).collect();
+
+    let mut conn = pool.get().await?;
+    let txn = conn.transaction().await?;
+    let portal = txn.bind(sql, &param_refs).await?;
+
+    loop {
+        let rows = txn.query_portal(&portal, max_rows).await?;
+        let exhausted = rows.len() < max_rows as usize;
+
+        if !rows.is_empty() && tx.send(Ok(rows)).await.is_err() {
+            // The stream was dropped; the transaction rolls back on drop
+            debug!("Row batch consumer went away, closing portal");
+            return Ok(());
+        }
+        if exhausted {
+            break;
+        }
+    }
+
+    txn.commit().await
+}
@@ -1,12 +1,29 @@
 //! PostgreSQL query engine implementation.
 
 use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use tokio::sync::{Mutex, MutexGuard};
 
 use prax_query::QueryResult;
+use prax_query::capabilities::Capabilities;
+use prax_query::drift::DriftGuard;
+use prax_query::explain::QueryPlan;
 use prax_query::filter::FilterValue;
-use prax_query::traits::{BoxFuture, Model, QueryEngine};
+use prax_query::raw::RawDatabase;
+use prax_query::sql::DatabaseType;
+use prax_query::traits::{BoxFuture, BoxStream, Model, QueryEngine};
+use prax_query::transaction::{TransactionConfig, TransactionalEngine, run_savepoint};
+use tokio_postgres::Row;
 use tracing::debug;
 
+use crate::binary;
+use crate::connection::PgConnection;
+use crate::copy;
+use crate::cursor::{self, RowBatches};
+use crate::notify::{self, Notifications};
 use crate::pool::PgPool;
 use crate::types::filter_value_to_sql;
 
@@ -14,12 +31,79 @@ use crate::types::filter_value_to_sql;
 #[derive(Clone)]
 pub struct PgEngine {
     pool: PgPool,
+    /// The connection of the open transaction, when bound to one.
+    transaction: Option<Arc<TransactionConnection>>,
+    /// Verified statements, in strict mode.
+    drift: Option<Arc<DriftGuard>>,
+    /// Rows per `COPY` statement of a bulk insert.
+    copy_chunk_size: usize,
+}
+
+/// The connection an interactive transaction runs on.
+///
+/// Emptied on commit or rollback. A connection still held when the last
+/// engine clone drops is closed rather than pooled, so the server rolls back.
+struct TransactionConnection(Mutex<Option<PgConnection>>);
+
+impl Drop for TransactionConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.0.get_mut().take() {
+            debug!("Discarding connection of an unfinished transaction");
+            conn.discard();
+        }
+    }
+}
+
+/// A connection to run one statement on.
+#[allow(clippy::large_enum_variant)]
+enum EngineConnection<'a> {
+    Pooled(PgConnection),
+    Transaction(MutexGuard<'a, Option<PgConnection>>),
+}
+
+impl Deref for EngineConnection<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Self::Pooled(conn) => conn,
+            Self::Transaction(guard) => guard.as_ref().expect("checked when acquired"),
+        }
+    }
 }
 
 impl PgEngine {
     /// Create a new PostgreSQL engine with the given connection pool.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            transaction: None,
+            drift: None,
+            copy_chunk_size: copy::DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Enable strict mode: check the columns each statement returns against
+    /// the model's schema the first time it yields a row.
+    ///
+    /// A column that is missing or whose type cannot hold the schema type
+    /// fails the query with a `SchemaDrift` error naming it. See
+    /// [`prax_query::drift`].
+    pub fn strict(mut self) -> Self {
+        self.drift = Some(Arc::new(DriftGuard::new()));
+        self
+    }
+
+    /// Check if strict mode is enabled.
+    pub fn is_strict(&self) -> bool {
+        self.drift.is_some()
+    }
+
+    /// Set the number of rows sent per `COPY` statement by
+    /// [`copy_in`](QueryEngine::copy_in).
+    pub fn with_copy_chunk_size(mut self, rows: usize) -> Self {
+        self.copy_chunk_size = rows.max(1);
+        self
     }
 
     /// Get a reference to the connection pool.
@@ -27,8 +111,109 @@ impl PgEngine {
         &self.pool
     }
 
+    /// Get the server's capabilities, probed once per pool.
+    pub fn capabilities(&self) -> Arc<Capabilities> {
+        self.pool.capabilities()
+    }
+
+    /// Check if this engine is bound to an interactive transaction.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Get a connection: the transaction's if bound to one, else a pooled one.
+    async fn connection(&self) -> QueryResult<EngineConnection<'_>> {
+        match &self.transaction {
+            Some(transaction) => {
+                let guard = transaction.0.lock().await;
+                if guard.is_none() {
+                    return Err(prax_query::QueryError::transaction(
+                        "the transaction has already finished",
+                    ));
+                }
+                Ok(EngineConnection::Transaction(guard))
+            }
+            None => self
+                .pool
+                .get()
+                .await
+                .map(EngineConnection::Pooled)
+                .map_err(|e| prax_query::QueryError::connection(e.to_string())),
+        }
+    }
+
+    /// In strict mode, check the columns of a result row against `T`'s schema.
+    fn check_drift<T: Model>(&self, sql: &str, row: &Row) -> QueryResult<()> {
+        if let Some(guard) = &self.drift {
+            let columns = row.columns().iter().map(|c| (c.name(), c.type_().name()));
+            guard.check(sql, T::TABLE_NAME, T::COLUMN_SPECS, columns)?;
+        }
+        Ok(())
+    }
+
+    /// End the bound transaction with `COMMIT` or `ROLLBACK`.
+    async fn finish(&self, sql: &str) -> QueryResult<()> {
+        let transaction = self
+            .transaction
+            .as_ref()
+            .ok_or_else(|| prax_query::QueryError::transaction("no transaction to end"))?;
+        let conn = transaction.0.lock().await.take().ok_or_else(|| {
+            prax_query::QueryError::transaction("the transaction has already finished")
+        })?;
+
+        debug!(sql = %sql, "Ending transaction");
+        conn.batch_execute(sql).await?;
+        Ok(())
+    }
+
+    /// Fetch the rows of a query in batches of `fetch_size` through a portal.
+    ///
+    /// See [`cursor::fetch_batches`] for the transaction and early-termination
+    /// behavior. Batches are read on a connection of their own, outside any
+    /// transaction this engine is bound to.
+    pub fn query_rows_batched(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+        fetch_size: usize,
+    ) -> RowBatches {
+        cursor::fetch_batches(self.pool.clone(), sql.to_string(), params, fetch_size)
+    }
+
+    /// Listen for notifications on `channel`.
+    ///
+    /// Listening takes a connection of its own, reconnecting and
+    /// resubscribing when it drops; see [`notify`](crate::notify) for how
+    /// missed notifications are reported. Dropping the stream stops
+    /// listening.
+    ///
+    /// ```rust,ignore
+    /// let mut changes = engine.listen("prax_changes").await?;
+    /// while let Some(notification) = changes.next().await {
+    ///     if notification.missed {
+    ///         cache.clear().await?;
+    ///     } else if let Ok(change) = ChangeEvent::from_json(&notification.payload) {
+    ///         cache.invalidate(&change.into()).await?;
+    ///     }
+    /// }
+    /// ```
+    pub async fn listen(&self, channel: impl Into<String>) -> QueryResult<Notifications> {
+        let config = self.pool.config().to_pg_config();
+        Ok(notify::listen(config, vec![channel.into()]).await?)
+    }
+
+    /// Send a notification on `channel` with `pg_notify`.
+    ///
+    /// Inside a transaction, the notification is delivered on commit.
+    pub async fn notify(&self, channel: &str, payload: &str) -> QueryResult<()> {
+        debug!(channel = %channel, "Sending notification");
+        let conn = self.connection().await?;
+        conn.execute("SELECT pg_notify($1, $2)", &[&channel, &payload])
+            .await?;
+        Ok(())
+    }
+
     /// Convert filter values to PostgreSQL parameters.
-    #[allow(clippy::result_large_err)]
     fn to_params(
         values: &[FilterValue],
     ) -> Result<Vec<Box<dyn tokio_postgres::types::ToSql + Sync + Send>>, prax_query::QueryError>
@@ -52,11 +237,7 @@ impl QueryEngine for PgEngine {
         Box::pin(async move {
             debug!(sql = %sql, "Executing query_many");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
@@ -66,6 +247,9 @@ impl QueryEngine for PgEngine {
                 .query(&sql, &param_refs)
                 .await
                 .map_err(|e| prax_query::QueryError::database(e.to_string()))?;
+            if let Some(row) = rows.first() {
+                self.check_drift::<T>(&sql, row)?;
+            }
 
             // For now, we'll return an empty vec since we need FromPgRow implementation
             // In practice, this would deserialize rows into T
@@ -84,23 +268,20 @@ impl QueryEngine for PgEngine {
         Box::pin(async move {
             debug!(sql = %sql, "Executing query_one");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
                 pg_params.iter().map(|p| p.as_ref() as _).collect();
 
-            let _row = conn.query_one(&sql, &param_refs).await.map_err(|e| {
+            let row = conn.query_one(&sql, &param_refs).await.map_err(|e| {
                 if e.to_string().contains("no rows") {
                     prax_query::QueryError::not_found(T::MODEL_NAME)
                 } else {
                     prax_query::QueryError::database(e.to_string())
                 }
             })?;
+            self.check_drift::<T>(&sql, &row)?;
 
             // Placeholder - would deserialize row into T
             Err(prax_query::QueryError::internal(
@@ -109,6 +290,26 @@ impl QueryEngine for PgEngine {
         })
     }
 
+    fn query_batches<T: Model + Send + 'static>(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+        fetch_size: usize,
+    ) -> BoxStream<'static, QueryResult<Vec<T>>> {
+        debug!(sql = %sql, fetch_size, "Executing query_batches");
+
+        Box::pin(
+            self.query_rows_batched(sql, params, fetch_size)
+                .map(|batch| {
+                    let rows = batch.map_err(prax_query::QueryError::from)?;
+
+                    // Placeholder - would deserialize rows into T
+                    let _ = rows;
+                    Ok(Vec::new())
+                }),
+        )
+    }
+
     fn query_optional<T: Model + Send + 'static>(
         &self,
         sql: &str,
@@ -118,11 +319,7 @@ impl QueryEngine for PgEngine {
         Box::pin(async move {
             debug!(sql = %sql, "Executing query_optional");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
@@ -134,7 +331,9 @@ impl QueryEngine for PgEngine {
                 .map_err(|e| prax_query::QueryError::database(e.to_string()))?;
 
             match row {
-                Some(_row) => {
+                Some(row) => {
+                    self.check_drift::<T>(&sql, &row)?;
+
                     // Placeholder - would deserialize row into T
                     Err(prax_query::QueryError::internal(
                         "deserialization not yet implemented".to_string(),
@@ -154,20 +353,17 @@ impl QueryEngine for PgEngine {
         Box::pin(async move {
             debug!(sql = %sql, "Executing insert");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
                 pg_params.iter().map(|p| p.as_ref() as _).collect();
 
-            let _row = conn
+            let row = conn
                 .query_one(&sql, &param_refs)
                 .await
                 .map_err(|e| prax_query::QueryError::database(e.to_string()))?;
+            self.check_drift::<T>(&sql, &row)?;
 
             // Placeholder - would deserialize row into T
             Err(prax_query::QueryError::internal(
@@ -185,11 +381,7 @@ impl QueryEngine for PgEngine {
         Box::pin(async move {
             debug!(sql = %sql, "Executing update");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
@@ -199,6 +391,9 @@ impl QueryEngine for PgEngine {
                 .query(&sql, &param_refs)
                 .await
                 .map_err(|e| prax_query::QueryError::database(e.to_string()))?;
+            if let Some(row) = rows.first() {
+                self.check_drift::<T>(&sql, row)?;
+            }
 
             // Placeholder - would deserialize rows into Vec<T>
             let _ = rows;
@@ -215,11 +410,7 @@ impl QueryEngine for PgEngine {
         Box::pin(async move {
             debug!(sql = %sql, "Executing delete");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
@@ -239,11 +430,7 @@ impl QueryEngine for PgEngine {
         Box::pin(async move {
             debug!(sql = %sql, "Executing raw SQL");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
@@ -258,16 +445,56 @@ impl QueryEngine for PgEngine {
         })
     }
 
+    /// Bulk insert through binary `COPY`, in chunks of
+    /// [`with_copy_chunk_size`](PgEngine::with_copy_chunk_size) rows.
+    ///
+    /// Outside a transaction the chunks run in one of their own, so a failed
+    /// row leaves the table untouched. The error names the row's index.
+    fn copy_in(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: Vec<Vec<FilterValue>>,
+    ) -> BoxFuture<'_, QueryResult<u64>> {
+        let table = table.to_string();
+        let columns = columns.to_vec();
+        Box::pin(async move {
+            debug!(table = %table, rows = rows.len(), "Copying rows");
+            if rows.is_empty() {
+                return Ok(0);
+            }
+
+            let conn = self.connection().await?;
+            let standalone = !self.in_transaction();
+            if standalone {
+                conn.batch_execute("BEGIN").await?;
+            }
+
+            let copied =
+                copy::copy_in(conn.inner(), &table, &columns, &rows, self.copy_chunk_size).await;
+            match copied {
+                Ok(count) => {
+                    if standalone {
+                        conn.batch_execute("COMMIT").await?;
+                    }
+                    Ok(count)
+                }
+                Err(e) => {
+                    if standalone {
+                        let _ = conn.batch_execute("ROLLBACK").await;
+                    }
+                    Err(e.into())
+                }
+            }
+        })
+    }
+
     fn count(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
         let sql = sql.to_string();
         Box::pin(async move {
             debug!(sql = %sql, "Executing count");
 
-            let conn = self
-                .pool
-                .get()
-                .await
-                .map_err(|e| prax_query::QueryError::connection(e.to_string()))?;
+            let conn = self.connection().await?;
 
             let pg_params = Self::to_params(&params)?;
             let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
@@ -282,6 +509,156 @@ impl QueryEngine for PgEngine {
             Ok(count as u64)
         })
     }
+
+    fn explain(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+        analyze: bool,
+    ) -> BoxFuture<'_, QueryResult<QueryPlan>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let explain = QueryPlan::explain_sql(DatabaseType::PostgreSQL, &sql, analyze)?;
+            debug!(sql = %explain, "Executing explain");
+
+            let conn = self.connection().await?;
+
+            let pg_params = Self::to_params(&params)?;
+            let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+                pg_params.iter().map(|p| p.as_ref() as _).collect();
+
+            let row = conn
+                .query_one(&explain, &param_refs)
+                .await
+                .map_err(|e| prax_query::QueryError::database(e.to_string()))?;
+
+            let plan: serde_json::Value = row
+                .try_get(0)
+                .map_err(|e| prax_query::QueryError::deserialization(e.to_string()))?;
+            QueryPlan::from_postgres_json(&plan.to_string())
+        })
+    }
+}
+
+impl RawDatabase for PgEngine {
+    fn query(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+    ) -> BoxFuture<'_, QueryResult<Vec<serde_json::Value>>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            debug!(sql = %sql, "Executing JSON query");
+
+            let conn = self.connection().await?;
+
+            let pg_params = Self::to_params(&params)?;
+            let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+                pg_params.iter().map(|p| p.as_ref() as _).collect();
+
+            // Decode binary columns directly where every column type allows it
+            let statement = conn.prepare(&sql).await?;
+            if statement
+                .columns()
+                .iter()
+                .all(|column| binary::can_decode(column.type_()))
+            {
+                let rows = conn
+                    .query(&sql, &param_refs)
+                    .await
+                    .map_err(|e| prax_query::QueryError::database(e.to_string()))?;
+                return rows
+                    .iter()
+                    .map(|row| binary::row_to_json(row).map_err(Into::into))
+                    .collect();
+            }
+
+            // Otherwise let PostgreSQL render each row, so every column type maps to JSON
+            let sql = format!("SELECT row_to_json(h) FROM ({}) AS h", sql);
+            let rows = conn
+                .query(&sql, &param_refs)
+                .await
+                .map_err(|e| prax_query::QueryError::database(e.to_string()))?;
+
+            Ok(rows.iter().map(|row| row.get(0)).collect())
+        })
+    }
+
+    fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+        self.execute_raw(sql, params)
+    }
+
+    fn execute_in_transaction(
+        &self,
+        statements: Vec<(String, Vec<FilterValue>)>,
+    ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+        Box::pin(async move {
+            if self.in_transaction() {
+                // Nested in an interactive transaction; a savepoint keeps the
+                // statements atomic without ending it
+                return run_savepoint(self, "prax_batch", |engine| async move {
+                    let mut affected = Vec::with_capacity(statements.len());
+                    for (sql, params) in statements {
+                        affected.push(engine.execute_raw(&sql, params).await?);
+                    }
+                    Ok(affected)
+                })
+                .await;
+            }
+
+            let mut conn = self.pool.get().await?;
+            // Rolled back on drop if a statement fails
+            let txn = conn.transaction().await?;
+
+            let mut affected = Vec::with_capacity(statements.len());
+            for (sql, params) in &statements {
+                debug!(sql = %sql, "Executing transaction statement");
+                let pg_params = Self::to_params(params)?;
+                let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+                    pg_params.iter().map(|p| p.as_ref() as _).collect();
+                affected.push(txn.execute(sql, &param_refs).await?);
+            }
+
+            txn.commit().await?;
+            Ok(affected)
+        })
+    }
+}
+
+impl TransactionalEngine for PgEngine {
+    fn begin(&self, config: &TransactionConfig) -> BoxFuture<'_, QueryResult<Self>> {
+        let sql = config.to_begin_sql();
+        Box::pin(async move {
+            if self.in_transaction() {
+                return Err(prax_query::QueryError::transaction(
+                    "already in a transaction; use a savepoint to nest",
+                ));
+            }
+
+            let conn = self.pool.get().await?;
+            debug!(sql = %sql, "Beginning transaction");
+            conn.batch_execute(&sql).await?;
+
+            Ok(Self {
+                pool: self.pool.clone(),
+                transaction: Some(Arc::new(TransactionConnection(Mutex::new(Some(conn))))),
+                drift: self.drift.clone(),
+                copy_chunk_size: self.copy_chunk_size,
+            })
+        })
+    }
+
+    fn commit(&self) -> BoxFuture<'_, QueryResult<()>> {
+        Box::pin(self.finish("COMMIT"))
+    }
+
+    fn rollback(&self) -> BoxFuture<'_, QueryResult<()>> {
+        Box::pin(self.finish("ROLLBACK"))
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
 }
 
 /// A typed query builder that uses the PostgreSQL engine.
@@ -1,6 +1,9 @@
 //! Error types for PostgreSQL operations.
 
+use std::time::Duration;
+
 use prax_query::QueryError;
+use prax_query::connection::PoolSnapshot;
 use thiserror::Error;
 
 /// Result type for PostgreSQL operations.
@@ -13,6 +16,15 @@ pub enum PgError {
     #[error("pool error: {0}")]
     Pool(#[from] deadpool_postgres::PoolError),
 
+    /// Timed out waiting for a pooled connection.
+    #[error("timed out after {}ms waiting for a pooled connection ({stats})", timeout.as_millis())]
+    PoolTimeout {
+        /// The acquire timeout.
+        timeout: Duration,
+        /// The pool's state when the checkout gave up.
+        stats: Box<PoolSnapshot>,
+    },
+
     /// PostgreSQL error.
     #[error("postgres error: {0}")]
     Postgres(#[from] tokio_postgres::Error),
@@ -37,6 +49,15 @@ pub enum PgError {
     #[error("type conversion error: {0}")]
     TypeConversion(String),
 
+    /// A row of a bulk `COPY` failed.
+    #[error("COPY failed at row {row}: {source}")]
+    CopyRow {
+        /// Index of the row in the input.
+        row: usize,
+        /// Why the row failed.
+        source: Box<PgError>,
+    },
+
     /// Timeout error.
     #[error("operation timed out after {0}ms")]
     Timeout(u64),
@@ -72,14 +93,25 @@ impl PgError {
         Self::TypeConversion(message.into())
     }
 
+    /// Create an error for a failed row of a bulk `COPY`.
+    pub fn copy_row(row: usize, source: PgError) -> Self {
+        Self::CopyRow {
+            row,
+            source: Box::new(source),
+        }
+    }
+
     /// Check if this is a connection error.
     pub fn is_connection_error(&self) -> bool {
-        matches!(self, Self::Pool(_) | Self::Connection(_))
+        matches!(
+            self,
+            Self::Pool(_) | Self::PoolTimeout { .. } | Self::Connection(_)
+        )
     }
 
     /// Check if this is a timeout error.
     pub fn is_timeout(&self) -> bool {
-        matches!(self, Self::Timeout(_))
+        matches!(self, Self::Timeout(_) | Self::PoolTimeout { .. })
     }
 }
 
@@ -87,6 +119,7 @@ impl From<PgError> for QueryError {
     fn from(err: PgError) -> Self {
         match err {
             PgError::Pool(e) => QueryError::connection(e.to_string()),
+            PgError::PoolTimeout { timeout, stats } => QueryError::pool_timeout(timeout, *stats),
             PgError::Postgres(e) => {
                 // Try to categorize PostgreSQL errors
                 let code = e.code();
@@ -112,6 +145,11 @@ impl From<PgError> for QueryError {
             PgError::Query(msg) => QueryError::database(msg),
             PgError::Deserialization(msg) => QueryError::serialization(msg),
             PgError::TypeConversion(msg) => QueryError::serialization(msg),
+            PgError::CopyRow { row, source } => {
+                let mut err = QueryError::from(*source);
+                err.message = format!("COPY failed at row {}: {}", row, err.message);
+                err
+            }
             PgError::Timeout(ms) => QueryError::timeout(ms),
             PgError::Internal(msg) => QueryError::internal(msg),
         }
@@ -139,5 +177,24 @@ mod tests {
         let pg_err = PgError::Timeout(1000);
         let query_err: QueryError = pg_err.into();
         assert!(query_err.is_timeout());
+
+        let stats = PoolSnapshot {
+            max_size: 4,
+            size: 4,
+            waiting: 2,
+            ..Default::default()
+        };
+        let pg_err = PgError::PoolTimeout {
+            timeout: Duration::from_millis(250),
+            stats: Box::new(stats.clone()),
+        };
+        assert!(pg_err.to_string().contains("4/4 connections open"));
+        let query_err: QueryError = pg_err.into();
+        assert_eq!(query_err.code, prax_query::ErrorCode::PoolTimeout);
+        assert_eq!(query_err.context().pool, Some(stats));
+
+        let pg_err = PgError::copy_row(41, PgError::type_conversion("bad date"));
+        let query_err: QueryError = pg_err.into();
+        assert!(query_err.message.starts_with("COPY failed at row 41: "));
     }
 }
@@ -5,7 +5,11 @@
 //! This crate provides:
 //! - Connection pool management using `deadpool-postgres`
 //! - Prepared statement caching for improved performance
-//! - Type-safe parameter binding
+//! - Type-safe parameter binding in the binary wire format
+//! - Batched row fetching through portals
+//! - Bulk inserts through binary `COPY`
+//! - `LISTEN`/`NOTIFY` with automatic reconnects
+//! - The server side of offline-first sync
 //! - Row deserialization into Prax models
 //!
 //! ## Example
@@ -29,22 +33,32 @@
 //! }
 //! ```
 
+pub mod binary;
 pub mod config;
 pub mod connection;
+pub mod copy;
+pub mod cursor;
 pub mod engine;
 pub mod error;
+pub mod notify;
 pub mod pool;
 pub mod row;
 pub mod statement;
+pub mod sync;
 pub mod types;
 
+pub use binary::{PgNumeric, PgParam};
 pub use config::{PgConfig, PgConfigBuilder};
 pub use connection::PgConnection;
+pub use cursor::RowBatches;
 pub use engine::PgEngine;
 pub use error::{PgError, PgResult};
+pub use notify::{Notification, Notifications};
 pub use pool::{PgPool, PgPoolBuilder, PoolConfig, PoolStatus};
 pub use row::PgRow;
 pub use statement::PreparedStatementCache;
+pub use sync::PgSyncStore;
+pub use types::PgEnum;
 
 /// Prelude for convenient imports.
 pub mod prelude {
@@ -0,0 +1,196 @@
+//! `LISTEN`/`NOTIFY` notifications.
+//!
+//! Pooled connections are driven by the pool and drop asynchronous
+//! messages, so listening uses a connection of its own. When that
+//! connection drops, it is re-established with exponential backoff and
+//! every channel is subscribed again. Notifications sent in the meantime
+//! are lost; the stream marks the gap with a [`Notification::missed`] for
+//! each channel, so caches can fall back to invalidating everything the
+//! channel covers.
+
+use std::time::Duration;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use tokio::sync::mpsc;
+use tokio_postgres::{AsyncMessage, Client, Config, NoTls};
+use tracing::{debug, warn};
+
+use crate::error::PgResult;
+
+/// A stream of notifications; dropping it closes the connection.
+pub type Notifications = BoxStream<'static, Notification>;
+
+/// Notifications buffered before the listener waits for the consumer.
+const BUFFER: usize = 256;
+
+/// Delay before the first reconnect attempt.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(100);
+
+/// Longest delay between reconnect attempts.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// A notification received on a channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// The channel it was sent on.
+    pub channel: String,
+    /// The payload; empty for notifications sent without one.
+    pub payload: String,
+    /// The process ID of the sending backend; `0` when missed.
+    pub process_id: i32,
+    /// Whether this marks a reconnect, during which notifications on the
+    /// channel may have been missed.
+    pub missed: bool,
+}
+
+impl Notification {
+    /// The marker for notifications missed on `channel` while reconnecting.
+    pub fn missed(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            payload: String::new(),
+            process_id: 0,
+            missed: true,
+        }
+    }
+}
+
+impl From<tokio_postgres::Notification> for Notification {
+    fn from(notification: tokio_postgres::Notification) -> Self {
+        Self {
+            channel: notification.channel().to_string(),
+            payload: notification.payload().to_string(),
+            process_id: notification.process_id(),
+            missed: false,
+        }
+    }
+}
+
+/// Listen on `channels` with a dedicated connection.
+///
+/// Fails if the first connection or `LISTEN` fails; later connection
+/// losses are retried until the stream is dropped.
+pub async fn listen(config: Config, channels: Vec<String>) -> PgResult<Notifications> {
+    let session = Session::connect(&config, &channels).await?;
+    let (tx, rx) = mpsc::channel(BUFFER);
+
+    tokio::spawn(run(config, channels, session, tx));
+
+    Ok(Box::pin(stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|notification| (notification, rx))
+    })))
+}
+
+/// The `LISTEN` statement for a channel.
+pub fn listen_sql(channel: &str) -> String {
+    format!("LISTEN \"{}\"", channel.replace('"', "\"\""))
+}
+
+/// A listening connection.
+struct Session {
+    /// Keeps the connection open.
+    _client: Client,
+    messages: mpsc::UnboundedReceiver<Notification>,
+}
+
+impl Session {
+    async fn connect(config: &Config, channels: &[String]) -> PgResult<Self> {
+        let (client, mut connection) = config.connect(NoTls).await?;
+        let (tx, messages) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut incoming = stream::poll_fn(move |cx| connection.poll_message(cx));
+            while let Some(message) = incoming.next().await {
+                match message {
+                    Ok(AsyncMessage::Notification(notification)) => {
+                        if tx.send(notification.into()).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(error = %e, "LISTEN connection failed");
+                        break;
+                    }
+                }
+            }
+        });
+
+        for channel in channels {
+            client.batch_execute(&listen_sql(channel)).await?;
+        }
+        debug!(channels = ?channels, "Listening for notifications");
+
+        Ok(Self {
+            _client: client,
+            messages,
+        })
+    }
+}
+
+async fn run(
+    config: Config,
+    channels: Vec<String>,
+    mut session: Session,
+    tx: mpsc::Sender<Notification>,
+) {
+    loop {
+        loop {
+            tokio::select! {
+                message = session.messages.recv() => match message {
+                    Some(notification) => {
+                        if tx.send(notification).await.is_err() {
+                            return;
+                        }
+                    }
+                    None => break,
+                },
+                _ = tx.closed() => return,
+            }
+        }
+
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        session = loop {
+            warn!(
+                delay_ms = delay.as_millis() as u64,
+                "LISTEN connection lost, reconnecting"
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = tx.closed() => return,
+            }
+            match Session::connect(&config, &channels).await {
+                Ok(session) => break session,
+                Err(e) => {
+                    warn!(error = %e, "Reconnecting LISTEN connection failed");
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        };
+
+        for channel in &channels {
+            if tx.send(Notification::missed(channel)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listen_sql() {
+        assert_eq!(listen_sql("prax_changes"), r#"LISTEN "prax_changes""#);
+        assert_eq!(listen_sql(r#"a"b"#), r#"LISTEN "a""b""#);
+    }
+
+    #[test]
+    fn test_missed_notification() {
+        let missed = Notification::missed("cache");
+        assert!(missed.missed);
+        assert_eq!(missed.channel, "cache");
+        assert!(missed.payload.is_empty());
+    }
+}
@@ -3,9 +3,13 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod, Runtime};
+use prax_query::capabilities::Capabilities;
+use prax_query::connection::{PoolSnapshot, PoolTelemetry};
+use prax_query::sql::DatabaseType;
+use tokio::sync::OnceCell;
 use tokio_postgres::NoTls;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::config::PgConfig;
 use crate::connection::PgConnection;
@@ -18,6 +22,9 @@ pub struct PgPool {
     inner: Pool,
     config: Arc<PgConfig>,
     statement_cache: Arc<PreparedStatementCache>,
+    capabilities: Arc<OnceCell<Arc<Capabilities>>>,
+    acquire_timeout: Option<Duration>,
+    telemetry: Arc<PoolTelemetry>,
 }
 
 impl PgPool {
@@ -38,7 +45,7 @@ impl PgPool {
 
         let pool = Pool::builder(mgr)
             .max_size(pool_config.max_connections)
-            .wait_timeout(pool_config.connection_timeout)
+            .runtime(Runtime::Tokio1)
             .create_timeout(pool_config.connection_timeout)
             .recycle_timeout(pool_config.idle_timeout)
             .build()
@@ -58,16 +65,70 @@ impl PgPool {
             statement_cache: Arc::new(PreparedStatementCache::new(
                 pool_config.statement_cache_size,
             )),
+            capabilities: Arc::new(OnceCell::new()),
+            acquire_timeout: pool_config.acquire_timeout,
+            telemetry: Arc::new(PoolTelemetry::new()),
         })
     }
 
     /// Get a connection from the pool.
+    ///
+    /// Fails with [`PgError::PoolTimeout`] when no connection frees up within
+    /// the acquire timeout.
     pub async fn get(&self) -> PgResult<PgConnection> {
         debug!("Acquiring connection from pool");
-        let client = self.inner.get().await?;
+        let client = self
+            .telemetry
+            .acquire(self.acquire_timeout, self.inner.get())
+            .await
+            .ok_or_else(|| self.timed_out())??;
+        if !self.capabilities.initialized() {
+            self.probe(&client).await;
+        }
         Ok(PgConnection::new(client, self.statement_cache.clone()))
     }
 
+    /// Get the server's capabilities, probed on the first connection.
+    ///
+    /// Until a connection succeeds, those of a current server are assumed.
+    pub fn capabilities(&self) -> Arc<Capabilities> {
+        self.capabilities
+            .get()
+            .cloned()
+            .unwrap_or_else(|| Arc::new(Capabilities::assumed(DatabaseType::PostgreSQL)))
+    }
+
+    /// Probe the server's version and extensions.
+    ///
+    /// A failed probe is retried on the next connection.
+    async fn probe(&self, client: &deadpool_postgres::Client) {
+        let probed = async {
+            let version: String = client
+                .query_one(Capabilities::version_query(DatabaseType::PostgreSQL), &[])
+                .await?
+                .get(0);
+            let mut extensions = Vec::new();
+            if let Some(query) = Capabilities::extensions_query(DatabaseType::PostgreSQL) {
+                for row in client.query(query, &[]).await? {
+                    extensions.push(row.get::<_, String>(0));
+                }
+            }
+            Ok::<_, tokio_postgres::Error>(Capabilities::new(
+                DatabaseType::PostgreSQL,
+                &version,
+                extensions,
+            ))
+        };
+
+        match probed.await {
+            Ok(capabilities) => {
+                debug!(version = ?capabilities.version(), "Probed PostgreSQL capabilities");
+                let _ = self.capabilities.set(Arc::new(capabilities));
+            }
+            Err(e) => warn!(error = %e, "Failed to probe PostgreSQL capabilities"),
+        }
+    }
+
     /// Get the current pool status.
     pub fn status(&self) -> PoolStatus {
         let status = self.inner.status();
@@ -79,6 +140,23 @@ impl PgPool {
         }
     }
 
+    /// Get a snapshot of the pool's size, queue depth and checkout wait times.
+    pub fn snapshot(&self) -> PoolSnapshot {
+        let status = self.inner.status();
+        self.telemetry
+            .snapshot(status.max_size, status.size, status.available)
+    }
+
+    /// Build the error for a checkout that ran out of time.
+    fn timed_out(&self) -> PgError {
+        let stats = self.snapshot();
+        warn!(pool = %stats, "Timed out waiting for a PostgreSQL connection");
+        PgError::PoolTimeout {
+            timeout: self.acquire_timeout.unwrap_or_default(),
+            stats: Box::new(stats),
+        }
+    }
+
     /// Get the pool configuration.
     pub fn config(&self) -> &PgConfig {
         &self.config
@@ -228,8 +306,11 @@ pub struct PoolConfig {
     pub max_connections: usize,
     /// Minimum number of connections to keep alive.
     pub min_connections: usize,
-    /// Maximum time to wait for a connection.
+    /// Maximum time to establish a new connection.
     pub connection_timeout: Option<Duration>,
+    /// Maximum time to wait for a connection to check out; `None` waits
+    /// indefinitely.
+    pub acquire_timeout: Option<Duration>,
     /// Maximum idle time before a connection is closed.
     pub idle_timeout: Option<Duration>,
     /// Maximum lifetime of a connection.
@@ -244,6 +325,7 @@ impl Default for PoolConfig {
             max_connections: 10,
             min_connections: 1,
             connection_timeout: Some(Duration::from_secs(30)),
+            acquire_timeout: Some(Duration::from_secs(30)),
             idle_timeout: Some(Duration::from_secs(600)), // 10 minutes
             max_lifetime: Some(Duration::from_secs(1800)), // 30 minutes
             statement_cache_size: 100,
@@ -299,6 +381,18 @@ impl PgPoolBuilder {
         self
     }
 
+    /// Set the acquire timeout.
+    pub fn acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_config.acquire_timeout = Some(timeout);
+        self
+    }
+
+    /// Wait indefinitely for a connection to check out.
+    pub fn no_acquire_timeout(mut self) -> Self {
+        self.pool_config.acquire_timeout = None;
+        self
+    }
+
     /// Set the idle timeout.
     pub fn idle_timeout(mut self, timeout: Duration) -> Self {
         self.pool_config.idle_timeout = Some(timeout);
@@ -348,10 +442,22 @@ mod tests {
         let builder = PgPoolBuilder::new()
             .url("postgresql://localhost/test")
             .max_connections(20)
+            .acquire_timeout(Duration::from_secs(2))
             .statement_cache_size(200);
 
         assert!(builder.url.is_some());
         assert_eq!(builder.pool_config.max_connections, 20);
         assert_eq!(builder.pool_config.statement_cache_size, 200);
+        assert_eq!(
+            builder.pool_config.acquire_timeout,
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            PgPoolBuilder::new()
+                .no_acquire_timeout()
+                .pool_config
+                .acquire_timeout,
+            None
+        );
     }
 }
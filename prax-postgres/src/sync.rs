@@ -0,0 +1,325 @@
+//! The server side of offline-first sync.
+//!
+//! Clients' changes are applied to the tables and appended to the
+//! `_prax_changes` log, keeping the origin and timestamp they were made
+//! with. [`PgSyncStore::track`] adds triggers that log the server's own
+//! writes too, so edits made outside of sync reach the clients. See
+//! [`prax_query::sync`] for the protocol.
+
+use serde_json::Value as JsonValue;
+use tokio_postgres::types::ToSql;
+use tracing::debug;
+
+use prax_query::error::{QueryError, QueryResult};
+use prax_query::live::ChangeOp;
+use prax_query::sql::quote_identifier;
+use prax_query::sync::{Change, SyncStore};
+use prax_query::traits::{BoxFuture, Model};
+
+use crate::error::{PgError, PgResult};
+use crate::pool::PgPool;
+
+/// The table changes are logged in.
+pub const CHANGES_TABLE: &str = "_prax_changes";
+
+/// The origin of writes made on the server itself.
+pub const SERVER_ORIGIN: &str = "server";
+
+/// Set while applying clients' changes, so the triggers skip them.
+const APPLYING_SETTING: &str = "prax.sync_applying";
+
+/// A PostgreSQL database that clients sync with.
+#[derive(Clone)]
+pub struct PgSyncStore {
+    pool: PgPool,
+}
+
+impl PgSyncStore {
+    /// Serve sync from the database behind `pool`.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The connection pool.
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Start logging the server's own writes to the model's table.
+    ///
+    /// Creates the change log on first use.
+    pub async fn track<M: Model>(&self) -> PgResult<()> {
+        let conn = self.pool.get().await?;
+        conn.batch_execute(&changelog_sql::<M>()).await
+    }
+}
+
+/// The change log table, and a trigger logging writes to the model's table
+/// that weren't made by a sync.
+pub fn changelog_sql<M: Model>() -> String {
+    let table = M::TABLE_NAME;
+    let name = format!("prax_changes_{}", table);
+    let key = M::PRIMARY_KEY
+        .iter()
+        .map(|column| {
+            format!(
+                "'{}', r.{}",
+                column.replace('\'', "''"),
+                quote_identifier(column)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS {CHANGES_TABLE} (\n    \
+         seq BIGSERIAL PRIMARY KEY,\n    \
+         table_name TEXT NOT NULL,\n    \
+         row_key JSONB NOT NULL,\n    \
+         op TEXT NOT NULL,\n    \
+         row JSONB,\n    \
+         ts BIGINT NOT NULL,\n    \
+         origin TEXT NOT NULL\n\
+         );\n\
+         CREATE OR REPLACE FUNCTION {name}() RETURNS trigger AS $$\n\
+         DECLARE\n    r RECORD;\n\
+         BEGIN\n    \
+         IF current_setting('{APPLYING_SETTING}', true) = 'on' THEN RETURN NULL; END IF;\n    \
+         IF TG_OP = 'DELETE' THEN r := OLD; ELSE r := NEW; END IF;\n    \
+         INSERT INTO {CHANGES_TABLE} (table_name, row_key, op, row, ts, origin) VALUES (\n        \
+         TG_TABLE_NAME, jsonb_build_object({key}), TG_OP,\n        \
+         CASE WHEN TG_OP = 'DELETE' THEN NULL ELSE to_jsonb(r) END,\n        \
+         (extract(epoch FROM clock_timestamp()) * 1000)::bigint, '{SERVER_ORIGIN}');\n    \
+         RETURN NULL;\n\
+         END;\n\
+         $$ LANGUAGE plpgsql;\n\
+         DROP TRIGGER IF EXISTS {name} ON {table};\n\
+         CREATE TRIGGER {name} AFTER INSERT OR UPDATE OR DELETE ON {table}\n    \
+         FOR EACH ROW EXECUTE FUNCTION {name}();",
+        name = name,
+        key = key,
+        table = quote_identifier(table),
+    )
+}
+
+/// The statement applying a change; its one parameter is the change's row,
+/// or its key for deletes.
+///
+/// Column values are read with `jsonb_populate_record`, so they're cast
+/// to the table's column types. Inserts and updates upsert the row by its
+/// key, so applying a change twice leaves the same row.
+pub fn apply_sql(change: &Change) -> QueryResult<String> {
+    let key = object_of(&change.key, "key")?;
+    let table = quote_identifier(&change.table);
+    let record = format!("jsonb_populate_record(NULL::{}, $1)", table);
+
+    let Some(row) = &change.row else {
+        let conditions = key
+            .keys()
+            .map(|column| {
+                let column = quote_identifier(column);
+                format!("t.{} = k.{}", column, column)
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        return Ok(format!(
+            "DELETE FROM {} t USING {} k WHERE {}",
+            table, record, conditions
+        ));
+    };
+
+    let row = object_of(row, "row")?;
+    let columns = row
+        .keys()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let updates = row
+        .keys()
+        .filter(|column| !key.contains_key(*column))
+        .map(|column| {
+            let column = quote_identifier(column);
+            format!("{} = EXCLUDED.{}", column, column)
+        })
+        .collect::<Vec<_>>();
+    let target = key
+        .keys()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let action = if updates.is_empty() {
+        "NOTHING".to_string()
+    } else {
+        format!("UPDATE SET {}", updates.join(", "))
+    };
+
+    Ok(format!(
+        "INSERT INTO {} ({}) SELECT {} FROM {} ON CONFLICT ({}) DO {}",
+        table, columns, columns, record, target, action
+    ))
+}
+
+fn object_of<'a>(
+    value: &'a JsonValue,
+    what: &str,
+) -> QueryResult<&'a serde_json::Map<String, JsonValue>> {
+    value
+        .as_object()
+        .filter(|object| !object.is_empty())
+        .ok_or_else(|| {
+            QueryError::invalid_input(what, format!("expected an object, got {}", value))
+        })
+}
+
+fn op_name(op: ChangeOp) -> &'static str {
+    match op {
+        ChangeOp::Insert => "INSERT",
+        ChangeOp::Update => "UPDATE",
+        ChangeOp::Delete => "DELETE",
+    }
+}
+
+fn change_from_row(row: &tokio_postgres::Row) -> PgResult<Change> {
+    let op = match row.try_get::<_, &str>("op")? {
+        "INSERT" => ChangeOp::Insert,
+        "UPDATE" => ChangeOp::Update,
+        "DELETE" => ChangeOp::Delete,
+        other => {
+            return Err(PgError::deserialization(format!(
+                "invalid change op: {}",
+                other
+            )));
+        }
+    };
+
+    Ok(Change {
+        seq: row.try_get("seq")?,
+        table: row.try_get("table_name")?,
+        key: row.try_get("row_key")?,
+        op,
+        row: row.try_get("row")?,
+        timestamp: row.try_get("ts")?,
+        origin: row.try_get("origin")?,
+    })
+}
+
+impl SyncStore for PgSyncStore {
+    fn changes_since(&self, after: i64) -> BoxFuture<'_, QueryResult<Vec<Change>>> {
+        Box::pin(async move {
+            let conn = self.pool.get().await?;
+            let rows = conn
+                .query(
+                    &format!(
+                        "SELECT seq, table_name, row_key, op, row, ts, origin \
+                         FROM {CHANGES_TABLE} WHERE seq > $1 ORDER BY seq"
+                    ),
+                    &[&after],
+                )
+                .await?;
+            let changes = rows
+                .iter()
+                .map(change_from_row)
+                .collect::<PgResult<Vec<_>>>()?;
+            Ok(changes)
+        })
+    }
+
+    fn apply(&self, changes: Vec<Change>) -> BoxFuture<'_, QueryResult<()>> {
+        Box::pin(async move {
+            let statements = changes
+                .iter()
+                .map(apply_sql)
+                .collect::<QueryResult<Vec<_>>>()?;
+            debug!(changes = changes.len(), "Applying synced changes");
+
+            let mut conn = self.pool.get().await?;
+            let txn = conn.transaction().await?;
+            txn.execute(
+                &format!("SELECT set_config('{APPLYING_SETTING}', 'on', true)"),
+                &[],
+            )
+            .await?;
+
+            let log = format!(
+                "INSERT INTO {CHANGES_TABLE} (table_name, row_key, op, row, ts, origin) \
+                 VALUES ($1, $2, $3, $4, $5, $6)"
+            );
+            for (change, sql) in changes.iter().zip(&statements) {
+                let record = change.row.as_ref().unwrap_or(&change.key);
+                txn.execute(sql, &[record]).await?;
+
+                let params: [&(dyn ToSql + Sync); 6] = [
+                    &change.table,
+                    &change.key,
+                    &op_name(change.op),
+                    &change.row,
+                    &change.timestamp,
+                    &change.origin,
+                ];
+                txn.execute(&log, &params).await?;
+            }
+            txn.commit().await?;
+            Ok(())
+        })
+    }
+}
+
+impl std::fmt::Debug for PgSyncStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PgSyncStore").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Note;
+
+    impl Model for Note {
+        const MODEL_NAME: &'static str = "Note";
+        const TABLE_NAME: &'static str = "notes";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "body"];
+    }
+
+    #[test]
+    fn test_changelog_sql() {
+        let sql = changelog_sql::<Note>();
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS _prax_changes"));
+        assert!(sql.contains("IF current_setting('prax.sync_applying', true) = 'on'"));
+        assert!(sql.contains("jsonb_build_object('id', r.id)"));
+        assert!(sql.contains(
+            "CREATE TRIGGER prax_changes_notes AFTER INSERT OR UPDATE OR DELETE ON notes"
+        ));
+    }
+
+    #[test]
+    fn test_apply_sql() {
+        let mut change = Change {
+            seq: 1,
+            table: "notes".to_string(),
+            key: json!({ "id": 1 }),
+            op: ChangeOp::Update,
+            row: Some(json!({ "id": 1, "body": "hi" })),
+            timestamp: 0,
+            origin: "phone".to_string(),
+        };
+        assert_eq!(
+            apply_sql(&change).unwrap(),
+            "INSERT INTO notes (body, id) SELECT body, id FROM jsonb_populate_record(NULL::notes, $1) \
+             ON CONFLICT (id) DO UPDATE SET body = EXCLUDED.body"
+        );
+
+        change.op = ChangeOp::Delete;
+        change.row = None;
+        assert_eq!(
+            apply_sql(&change).unwrap(),
+            "DELETE FROM notes t USING jsonb_populate_record(NULL::notes, $1) k WHERE t.id = k.id"
+        );
+
+        change.key = json!({});
+        assert!(apply_sql(&change).is_err());
+    }
+}
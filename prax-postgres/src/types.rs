@@ -1,27 +1,21 @@
 //! Type conversions for PostgreSQL.
 
+use std::error::Error;
+
+use bytes::BytesMut;
 use prax_query::filter::FilterValue;
-use tokio_postgres::types::{ToSql, Type};
+use prax_query::types::DbEnum;
+use tokio_postgres::types::{FromSql, IsNull, Kind, ToSql, Type, to_sql_checked};
 
-use crate::error::{PgError, PgResult};
+use crate::binary::PgParam;
+use crate::error::PgResult;
 
 /// Convert a FilterValue to a type that can be used as a PostgreSQL parameter.
+///
+/// The value is sent in the binary format of the type the server infers for
+/// its placeholder; see [`PgParam`]. Lists bind to array parameters.
 pub fn filter_value_to_sql(value: &FilterValue) -> PgResult<Box<dyn ToSql + Sync + Send>> {
-    match value {
-        FilterValue::Null => Ok(Box::new(Option::<String>::None)),
-        FilterValue::Bool(b) => Ok(Box::new(*b)),
-        FilterValue::Int(i) => Ok(Box::new(*i)),
-        FilterValue::Float(f) => Ok(Box::new(*f)),
-        FilterValue::String(s) => Ok(Box::new(s.clone())),
-        FilterValue::Json(j) => Ok(Box::new(j.clone())),
-        FilterValue::List(_) => {
-            // Lists need special handling - they should be converted to arrays
-            // For now, return an error and handle lists specially in the engine
-            Err(PgError::type_conversion(
-                "list values should be handled specially",
-            ))
-        }
-    }
+    Ok(Box::new(PgParam(value.clone())))
 }
 
 /// Convert filter values to PostgreSQL parameters.
@@ -31,6 +25,42 @@ pub fn filter_values_to_params(
     values.iter().map(filter_value_to_sql).collect()
 }
 
+/// A [`DbEnum`] bound to, or decoded from, a native enum or text column.
+///
+/// ```rust,ignore
+/// let role: PgEnum<Role> = row.get_value("role")?;
+/// assert_eq!(role.0, Role::Admin);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PgEnum<T>(pub T);
+
+impl<T: DbEnum> ToSql for PgEnum<T> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.0.db_value().to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&str as ToSql>::accepts(ty) || matches!(ty.kind(), Kind::Enum(_))
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a, T: DbEnum> FromSql<'a> for PgEnum<T> {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let value = <&str as FromSql>::from_sql(&Type::TEXT, raw)?;
+        T::decode(value).map(PgEnum).map_err(Into::into)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&str as FromSql>::accepts(ty) || matches!(ty.kind(), Kind::Enum(_))
+    }
+}
+
 /// PostgreSQL type mapping utilities.
 pub mod pg_types {
     use super::*;
@@ -92,6 +122,74 @@ mod tests {
 
         let result = filter_value_to_sql(&FilterValue::Bool(true));
         assert!(result.is_ok());
+
+        let result = filter_value_to_sql(&FilterValue::List(vec![FilterValue::Int(1)]));
+        assert!(result.is_ok());
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Role {
+        User,
+        Admin,
+    }
+
+    impl DbEnum for Role {
+        const DB_NAME: &'static str = "Role";
+
+        fn variants() -> &'static [Self] {
+            &[Self::User, Self::Admin]
+        }
+
+        fn db_value(&self) -> &'static str {
+            match self {
+                Self::User => "USER",
+                Self::Admin => "ADMIN",
+            }
+        }
+
+        fn from_db_value(value: &str) -> Option<Self> {
+            Self::variants()
+                .iter()
+                .copied()
+                .find(|v| v.db_value() == value)
+        }
+    }
+
+    fn role_type() -> Type {
+        Type::new(
+            "Role".to_string(),
+            0,
+            Kind::Enum(vec!["USER".to_string(), "ADMIN".to_string()]),
+            "public".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_strings_bind_to_enum_types() {
+        let param = filter_value_to_sql(&FilterValue::String("ADMIN".to_string())).unwrap();
+        let mut buf = BytesMut::new();
+        param.to_sql_checked(&role_type(), &mut buf).unwrap();
+        assert_eq!(&buf[..], b"ADMIN");
+
+        let mut buf = BytesMut::new();
+        assert!(param.to_sql_checked(&Type::TEXT, &mut buf).is_ok());
+        assert!(param.to_sql_checked(&Type::INT4, &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_pg_enum() {
+        let mut buf = BytesMut::new();
+        PgEnum(Role::User)
+            .to_sql_checked(&role_type(), &mut buf)
+            .unwrap();
+        assert_eq!(&buf[..], b"USER");
+
+        assert!(<PgEnum<Role> as FromSql>::accepts(&role_type()));
+        assert!(!<PgEnum<Role> as FromSql>::accepts(&Type::INT4));
+        let role = PgEnum::<Role>::from_sql(&role_type(), b"ADMIN").unwrap();
+        assert_eq!(role.0, Role::Admin);
+        let err = PgEnum::<Role>::from_sql(&role_type(), b"OWNER").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value for enum Role: \"OWNER\"");
     }
 
     #[test]
@@ -0,0 +1,203 @@
+//! Converting JSON rows to Arrow record batches.
+//!
+//! Each column's type is inferred from its values: booleans, integers,
+//! floats and strings map to the matching Arrow arrays, and anything mixed
+//! or nested is kept as JSON text. A type hint from the schema, such as a
+//! timestamp for a `DateTime` field, is applied with an Arrow cast when the
+//! values allow it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, NullArray, StringArray};
+use arrow::compute::{CastOptions, cast_with_options};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use serde::de::{Deserialize, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_json::Value;
+
+/// Build a record batch with `columns` from JSON object rows.
+///
+/// A missing key reads as null.
+pub fn record_batch(
+    columns: &[String],
+    rows: &[Value],
+    hints: &HashMap<String, DataType>,
+) -> Result<RecordBatch, ArrowError> {
+    let arrays: Vec<ArrayRef> = columns
+        .iter()
+        .map(|column| {
+            let values: Vec<&Value> = rows
+                .iter()
+                .map(|row| row.get(column).unwrap_or(&Value::Null))
+                .collect();
+            let array = infer_array(&values);
+            match hints.get(column) {
+                Some(hint) if hint != array.data_type() && *array.data_type() != DataType::Null => {
+                    let strict = CastOptions {
+                        safe: false,
+                        ..Default::default()
+                    };
+                    cast_with_options(&array, hint, &strict).unwrap_or(array)
+                }
+                _ => array,
+            }
+        })
+        .collect();
+
+    let fields: Vec<Field> = columns
+        .iter()
+        .zip(&arrays)
+        .map(|(name, array)| Field::new(name, array.data_type().clone(), true))
+        .collect();
+
+    RecordBatch::try_new_with_options(
+        Arc::new(Schema::new(fields)),
+        arrays,
+        &arrow::record_batch::RecordBatchOptions::new().with_row_count(Some(rows.len())),
+    )
+}
+
+/// The keys of a JSON object, in the order they appear in `json`.
+///
+/// Rows are parsed into maps that sort their keys, so result columns are
+/// read from the text of the first row instead.
+pub fn columns_of(json: &str) -> serde_json::Result<Vec<String>> {
+    struct Keys(Vec<String>);
+
+    impl<'de> Deserialize<'de> for Keys {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct KeysVisitor;
+
+            impl<'de> Visitor<'de> for KeysVisitor {
+                type Value = Keys;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("a JSON object")
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Keys, A::Error> {
+                    let mut keys = Vec::new();
+                    while let Some(key) = map.next_key::<String>()? {
+                        map.next_value::<IgnoredAny>()?;
+                        keys.push(key);
+                    }
+                    Ok(Keys(keys))
+                }
+            }
+
+            deserializer.deserialize_map(KeysVisitor)
+        }
+    }
+
+    serde_json::from_str::<Keys>(json).map(|keys| keys.0)
+}
+
+fn infer_array(values: &[&Value]) -> ArrayRef {
+    let present = || values.iter().filter(|v| !v.is_null());
+
+    if present().next().is_none() {
+        Arc::new(NullArray::new(values.len()))
+    } else if present().all(|v| v.is_boolean()) {
+        Arc::new(values.iter().map(|v| v.as_bool()).collect::<BooleanArray>())
+    } else if present().all(|v| v.is_i64()) {
+        Arc::new(values.iter().map(|v| v.as_i64()).collect::<Int64Array>())
+    } else if present().all(|v| v.is_number()) {
+        Arc::new(values.iter().map(|v| v.as_f64()).collect::<Float64Array>())
+    } else {
+        Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    Value::Null => None,
+                    Value::String(s) => Some(s.clone()),
+                    other => Some(other.to_string()),
+                })
+                .collect::<StringArray>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, TimestampMicrosecondArray};
+    use arrow::datatypes::TimeUnit;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_batch_inference() {
+        let first = r#"{"id": 1, "score": 1.5, "name": "a", "active": true, "meta": {"k": 1}, "gone": null}"#;
+        let rows = vec![
+            serde_json::from_str(first).unwrap(),
+            json!({"id": 2, "score": 2, "name": null, "active": false, "meta": [1]}),
+        ];
+        let columns = columns_of(first).unwrap();
+        assert_eq!(columns, ["id", "score", "name", "active", "meta", "gone"]);
+
+        let batch = record_batch(&columns, &rows, &HashMap::new()).unwrap();
+        let types: Vec<_> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                DataType::Int64,
+                DataType::Float64,
+                DataType::Utf8,
+                DataType::Boolean,
+                DataType::Utf8,
+                DataType::Null,
+            ]
+        );
+        assert_eq!(batch.num_rows(), 2);
+        assert!(batch.column(2).is_null(1));
+
+        let meta = batch
+            .column(4)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(meta.value(0), r#"{"k":1}"#);
+    }
+
+    #[test]
+    fn test_record_batch_hints() {
+        let rows = vec![
+            json!({"created_at": "2024-01-02T03:04:05+00:00", "code": "x"}),
+            json!({"created_at": null, "code": "y"}),
+        ];
+        let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+        let hints = HashMap::from([
+            ("created_at".to_string(), timestamp.clone()),
+            ("code".to_string(), DataType::Int64),
+        ]);
+
+        let columns = ["created_at".to_string(), "code".to_string()];
+        let batch = record_batch(&columns, &rows, &hints).unwrap();
+        assert_eq!(batch.column(0).data_type(), &timestamp);
+        let created = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(created.value(0), 1_704_164_645_000_000);
+        assert!(created.is_null(1));
+
+        // Values that don't fit the hint keep their inferred type
+        assert_eq!(batch.column(1).data_type(), &DataType::Utf8);
+    }
+
+    #[test]
+    fn test_record_batch_empty() {
+        let batch = record_batch(&["id".to_string()], &[], &HashMap::new()).unwrap();
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.num_columns(), 1);
+
+        assert!(columns_of("[1]").is_err());
+    }
+}
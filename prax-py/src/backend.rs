@@ -0,0 +1,150 @@
+//! The database engines queries run on.
+//!
+//! Each backend wraps the caller's SQL so every row comes back as one JSON
+//! object, which keeps the conversion to Arrow independent of the engine's
+//! own value types.
+
+use prax_query::error::{ErrorCode, QueryError, QueryResult};
+use prax_query::filter::FilterValue;
+use serde_json::Value;
+
+use crate::arrow::columns_of;
+
+/// Rows returned by a query, as JSON objects.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rows {
+    /// Result columns, in select order.
+    pub columns: Vec<String>,
+    /// One JSON object per row.
+    pub rows: Vec<Value>,
+}
+
+impl Rows {
+    fn from_json(json: impl IntoIterator<Item = String>) -> QueryResult<Self> {
+        let mut rows = Self::default();
+        for row in json {
+            if rows.rows.is_empty() {
+                rows.columns = columns_of(&row).map_err(invalid_row)?;
+            }
+            rows.rows
+                .push(serde_json::from_str(&row).map_err(invalid_row)?);
+        }
+        Ok(rows)
+    }
+}
+
+fn invalid_row(err: serde_json::Error) -> QueryError {
+    QueryError::serialization(format!("Invalid row: {}", err))
+}
+
+/// A connection pool for one of the supported engines.
+#[derive(Clone)]
+pub enum Backend {
+    /// PostgreSQL, for `postgres://` and `postgresql://` URLs.
+    #[cfg(feature = "postgres")]
+    Postgres(prax_postgres::PgPool),
+    /// DuckDB, for `duckdb://` URLs.
+    #[cfg(feature = "duckdb")]
+    DuckDb(prax_duckdb::DuckDbPool),
+}
+
+impl Backend {
+    /// Connect to the database at `url`, choosing the engine by scheme.
+    pub async fn connect(url: &str) -> QueryResult<Self> {
+        let scheme = url.split_once("://").map(|(scheme, _)| scheme);
+        match scheme {
+            #[cfg(feature = "postgres")]
+            Some("postgres" | "postgresql") => {
+                let pool = prax_postgres::PgPool::builder().url(url).build().await?;
+                Ok(Self::Postgres(pool))
+            }
+            #[cfg(feature = "duckdb")]
+            Some("duckdb") => {
+                let pool = prax_duckdb::DuckDbPool::builder().url(url).build().await?;
+                Ok(Self::DuckDb(pool))
+            }
+            _ => Err(QueryError::new(
+                ErrorCode::InvalidConfiguration,
+                format!("Unsupported database URL: {}", url),
+            )
+            .with_help("Use a postgres:// or duckdb:// URL")),
+        }
+    }
+
+    /// Run a query and return its rows.
+    ///
+    /// Placeholders are written `$1`, `$2`, ... for every engine.
+    pub async fn query(&self, sql: &str, params: &[FilterValue]) -> QueryResult<Rows> {
+        match self {
+            #[cfg(feature = "postgres")]
+            Self::Postgres(pool) => {
+                let sql = format!(
+                    "SELECT row_to_json(t)::text AS prax_row FROM ({}) AS t",
+                    sql
+                );
+                let params = prax_postgres::types::filter_values_to_params(params)?;
+                let refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = params
+                    .iter()
+                    .map(|p| p.as_ref() as &(dyn tokio_postgres::types::ToSql + Sync))
+                    .collect();
+
+                let conn = pool.get().await?;
+                let rows = conn.query(&sql, &refs).await?;
+                Rows::from_json(rows.iter().map(|row| row.get::<_, String>(0)))
+            }
+            #[cfg(feature = "duckdb")]
+            Self::DuckDb(pool) => {
+                let sql = format!("SELECT to_json(t)::VARCHAR AS prax_row FROM ({}) AS t", sql);
+
+                let conn = pool.get().await?;
+                let rows = conn.query(&sql, params).await?;
+                Rows::from_json(rows.into_iter().filter_map(|mut row| {
+                    match row.get_mut("prax_row").map(Value::take) {
+                        Some(Value::String(json)) => Some(json),
+                        _ => None,
+                    }
+                }))
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("no database engine enabled"),
+        }
+    }
+}
+
+impl std::fmt::Debug for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "postgres")]
+            Self::Postgres(_) => f.write_str("Backend::Postgres"),
+            #[cfg(feature = "duckdb")]
+            Self::DuckDb(_) => f.write_str("Backend::DuckDb"),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("no database engine enabled"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rows_from_json() {
+        let rows = Rows::from_json([
+            r#"{"b": 1, "a": "x"}"#.to_string(),
+            r#"{"b": 2, "a": null}"#.to_string(),
+        ])
+        .unwrap();
+        assert_eq!(rows.columns, ["b", "a"]);
+        assert_eq!(rows.rows[1], json!({"b": 2, "a": null}));
+
+        assert!(Rows::from_json(["{".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_connect_unsupported() {
+        let err = Backend::connect("mysql://localhost/app").await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidConfiguration);
+    }
+}
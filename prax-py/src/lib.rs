@@ -0,0 +1,43 @@
+//! Python bindings for Prax.
+//!
+//! This crate builds the `prax` Python extension module, giving data
+//! scientists the application's schema and connection handling instead of
+//! hand-copied SQL. Results come back as `pyarrow.Table`s or pandas
+//! `DataFrame`s.
+//!
+//! # Features
+//!
+//! - **Engines**: PostgreSQL (`postgres` feature) and DuckDB (`duckdb`
+//!   feature), chosen by URL scheme
+//! - **Model API**: `find_many` and `count` by model and field name, with
+//!   `@map`/`@@map` resolved from the schema file
+//! - **Raw SQL**: `sql` with `$1`-style parameters
+//! - **Arrow**: Columns typed from the schema where it pins a type down,
+//!   inferred from the values otherwise
+//!
+//! # Example
+//!
+//! Build with `maturin develop`, then:
+//!
+//! ```python
+//! import prax
+//!
+//! db = prax.Client.connect("postgres://localhost/app", schema="prax/schema.prax")
+//! table = db.find_many("User", where={"role": ["admin", "owner"]}, order_by="-createdAt")
+//! df = db.sql("SELECT date_trunc('day', created_at) AS day, count(*) FROM users GROUP BY 1",
+//!             pandas=True)
+//! ```
+//!
+//! The query building and Arrow conversion are plain Rust and can be used
+//! without the `python` feature.
+
+pub mod arrow;
+pub mod backend;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod query;
+pub mod schema;
+
+pub use backend::{Backend, Rows};
+pub use query::FindMany;
+pub use schema::{Column, ModelInfo, SchemaMap};
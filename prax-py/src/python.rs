@@ -0,0 +1,243 @@
+//! The `prax` Python module.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use ::arrow::datatypes::DataType;
+use ::arrow::pyarrow::ToPyArrow;
+use prax_query::filter::FilterValue;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::arrow::record_batch;
+use crate::backend::{Backend, Rows};
+use crate::query::{self, FindMany};
+use crate::schema::{ModelInfo, SchemaMap};
+
+create_exception!(
+    prax,
+    QueryError,
+    PyException,
+    "A query failed. The message starts with the Prax error code."
+);
+
+fn query_error(err: prax_query::error::QueryError) -> PyErr {
+    QueryError::new_err(format!("{}: {}", err.code.code(), err.message))
+}
+
+/// A connection to a Postgres or DuckDB database.
+///
+/// ```python
+/// import prax
+///
+/// db = prax.Client.connect("postgres://localhost/app", schema="prax/schema.prax")
+/// users = db.find_many("User", where={"active": True}, order_by="-createdAt", take=100)
+/// df = db.sql("SELECT * FROM events WHERE kind = $1", ["signup"], pandas=True)
+/// ```
+#[pyclass(module = "prax")]
+pub struct Client {
+    runtime: Runtime,
+    backend: Backend,
+    schema: Option<SchemaMap>,
+}
+
+#[pymethods]
+impl Client {
+    /// Connect to `url`, optionally loading the application's schema file
+    /// for the model API.
+    #[staticmethod]
+    #[pyo3(signature = (url, schema=None))]
+    fn connect(py: Python<'_>, url: String, schema: Option<PathBuf>) -> PyResult<Self> {
+        let schema = schema
+            .map(SchemaMap::load)
+            .transpose()
+            .map_err(query_error)?;
+        let runtime = Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("prax-py")
+            .build()
+            .map_err(|e| QueryError::new_err(format!("failed to start runtime: {}", e)))?;
+        let backend = py
+            .allow_threads(|| runtime.block_on(Backend::connect(&url)))
+            .map_err(query_error)?;
+
+        Ok(Self {
+            runtime,
+            backend,
+            schema,
+        })
+    }
+
+    /// The models of the loaded schema.
+    fn models(&self) -> Vec<String> {
+        self.schema
+            .iter()
+            .flat_map(|schema| schema.model_names().map(str::to_string))
+            .collect()
+    }
+
+    /// Run raw SQL with `$1`-style placeholders, returning a
+    /// `pyarrow.Table`, or a `pandas.DataFrame` when `pandas` is set.
+    #[pyo3(signature = (query, params=None, pandas=false))]
+    fn sql(
+        &self,
+        py: Python<'_>,
+        query: String,
+        params: Option<Vec<Bound<'_, PyAny>>>,
+        pandas: bool,
+    ) -> PyResult<PyObject> {
+        let params = params
+            .unwrap_or_default()
+            .iter()
+            .map(filter_value)
+            .collect::<PyResult<Vec<_>>>()?;
+        let rows = self.run(py, &query, params)?;
+        to_table(py, &rows.columns, &rows, &HashMap::new(), pandas)
+    }
+
+    /// Find the records of `model` matching the `where` equalities.
+    ///
+    /// `order_by` takes field names, with a leading `-` for descending
+    /// order. Columns are named after the schema's fields and typed from
+    /// it, e.g. `DateTime` fields become UTC timestamps.
+    #[pyo3(signature = (model, r#where=None, order_by=None, take=None, skip=None, pandas=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn find_many(
+        &self,
+        py: Python<'_>,
+        model: &str,
+        r#where: Option<Bound<'_, PyDict>>,
+        order_by: Option<Bound<'_, PyAny>>,
+        take: Option<u64>,
+        skip: Option<u64>,
+        pandas: bool,
+    ) -> PyResult<PyObject> {
+        let model = self.model(model)?;
+        let order_by = match order_by {
+            None => Vec::new(),
+            Some(order) if order.is_instance_of::<PyString>() => vec![order.extract()?],
+            Some(order) => order.extract::<Vec<String>>()?,
+        };
+        let query = FindMany {
+            filters: filters(r#where)?,
+            order_by: order_by.iter().map(|o| FindMany::parse_order(o)).collect(),
+            take,
+            skip,
+        };
+        let (sql, params) = query.build(model).map_err(query_error)?;
+
+        let rows = self.run(py, &sql, params)?;
+        let columns: Vec<String> = model.columns.iter().map(|c| c.field.clone()).collect();
+        let hints = model
+            .columns
+            .iter()
+            .filter_map(|c| Some((c.field.clone(), c.data_type.clone()?)))
+            .collect::<HashMap<String, DataType>>();
+        to_table(py, &columns, &rows, &hints, pandas)
+    }
+
+    /// Count the records of `model` matching the `where` equalities.
+    #[pyo3(signature = (model, r#where=None))]
+    fn count(
+        &self,
+        py: Python<'_>,
+        model: &str,
+        r#where: Option<Bound<'_, PyDict>>,
+    ) -> PyResult<i64> {
+        let (sql, params) =
+            query::count(self.model(model)?, &filters(r#where)?).map_err(query_error)?;
+        let rows = self.run(py, &sql, params)?;
+        Ok(rows
+            .rows
+            .first()
+            .and_then(|row| row.get("count"))
+            .and_then(|count| count.as_i64())
+            .unwrap_or(0))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<prax.Client {:?}>", self.backend)
+    }
+}
+
+impl Client {
+    fn model(&self, name: &str) -> PyResult<&ModelInfo> {
+        self.schema
+            .as_ref()
+            .ok_or_else(|| QueryError::new_err("No schema loaded; pass schema= to Client.connect"))?
+            .model(name)
+            .map_err(query_error)
+    }
+
+    fn run(&self, py: Python<'_>, sql: &str, params: Vec<FilterValue>) -> PyResult<Rows> {
+        py.allow_threads(|| self.runtime.block_on(self.backend.query(sql, &params)))
+            .map_err(query_error)
+    }
+}
+
+fn filters(r#where: Option<Bound<'_, PyDict>>) -> PyResult<Vec<(String, FilterValue)>> {
+    r#where
+        .iter()
+        .flat_map(|dict| dict.iter())
+        .map(|(field, value)| Ok((field.extract()?, filter_value(&value)?)))
+        .collect()
+}
+
+fn filter_value(value: &Bound<'_, PyAny>) -> PyResult<FilterValue> {
+    Ok(if value.is_none() {
+        FilterValue::Null
+    } else if value.is_instance_of::<PyBool>() {
+        FilterValue::Bool(value.extract()?)
+    } else if value.is_instance_of::<PyInt>() {
+        FilterValue::Int(value.extract()?)
+    } else if value.is_instance_of::<PyFloat>() {
+        FilterValue::Float(value.extract()?)
+    } else if value.is_instance_of::<PyString>() {
+        FilterValue::String(value.extract()?)
+    } else if let Ok(bytes) = value.downcast::<PyBytes>() {
+        FilterValue::Bytes(bytes.as_bytes().to_vec())
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        FilterValue::List(
+            value
+                .try_iter()?
+                .map(|item| filter_value(&item?))
+                .collect::<PyResult<_>>()?,
+        )
+    } else if value.hasattr("isoformat")? {
+        // datetime, date and time
+        FilterValue::String(value.call_method0("isoformat")?.extract()?)
+    } else {
+        FilterValue::String(value.str()?.extract()?)
+    })
+}
+
+fn to_table(
+    py: Python<'_>,
+    columns: &[String],
+    rows: &Rows,
+    hints: &HashMap<String, DataType>,
+    pandas: bool,
+) -> PyResult<PyObject> {
+    let batch = record_batch(columns, &rows.rows, hints)
+        .map_err(|e| QueryError::new_err(format!("failed to build Arrow table: {}", e)))?;
+    let table = py
+        .import("pyarrow")?
+        .getattr("Table")?
+        .call_method1("from_batches", (vec![batch.to_pyarrow(py)?],))?;
+    if pandas {
+        Ok(table.call_method0("to_pandas")?.unbind())
+    } else {
+        Ok(table.unbind())
+    }
+}
+
+/// The `prax` extension module.
+#[pymodule]
+pub fn prax(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Client>()?;
+    m.add("QueryError", m.py().get_type::<QueryError>())?;
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! SQL for the model query API.
+//!
+//! Queries are built with prax-query's filters, ordering and pagination, so
+//! Python callers get the same SQL as the generated Rust client. Result
+//! columns are aliased to their field names.
+
+use prax_query::error::QueryResult;
+use prax_query::filter::{Filter, FilterValue};
+use prax_query::pagination::Pagination;
+use prax_query::types::{OrderBy, OrderByField, SortOrder};
+
+use crate::schema::ModelInfo;
+
+/// A `find_many` query against one model.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FindMany {
+    /// `field = value` conditions, combined with `AND`; a list value
+    /// matches any of its elements.
+    pub filters: Vec<(String, FilterValue)>,
+    /// Fields to order by.
+    pub order_by: Vec<(String, SortOrder)>,
+    /// Maximum number of records.
+    pub take: Option<u64>,
+    /// Number of records to skip.
+    pub skip: Option<u64>,
+}
+
+impl FindMany {
+    /// Parse an ordering in the `"-created_at"` style: a leading `-` sorts
+    /// descending.
+    pub fn parse_order(field: &str) -> (String, SortOrder) {
+        match field.strip_prefix('-') {
+            Some(field) => (field.to_string(), SortOrder::Desc),
+            None => (field.to_string(), SortOrder::Asc),
+        }
+    }
+
+    /// Build the SQL and its parameters.
+    pub fn build(&self, model: &ModelInfo) -> QueryResult<(String, Vec<FilterValue>)> {
+        let select = model
+            .columns
+            .iter()
+            .map(|c| {
+                if c.name == c.field {
+                    c.name.clone()
+                } else {
+                    format!("{} AS {}", c.name, c.field)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut sql = format!("SELECT {} FROM {}", select, model.table);
+
+        let (filter, params) = where_clause(model, &self.filters)?;
+        sql.push_str(&filter);
+
+        let order = self
+            .order_by
+            .iter()
+            .map(|(field, order)| Ok(OrderByField::new(model.column(field)?.name.clone(), *order)))
+            .collect::<QueryResult<Vec<_>>>()?;
+        let order = OrderBy::from_fields(order);
+        if !order.is_empty() {
+            sql.push_str(" ORDER BY ");
+            sql.push_str(&order.to_sql());
+        }
+
+        let pagination = Pagination {
+            skip: self.skip,
+            take: self.take,
+            cursor: None,
+        };
+        if !pagination.is_empty() {
+            sql.push(' ');
+            pagination.write_sql(&mut sql);
+        }
+
+        Ok((sql, params))
+    }
+}
+
+/// Build a `count` query and its parameters.
+pub fn count(
+    model: &ModelInfo,
+    filters: &[(String, FilterValue)],
+) -> QueryResult<(String, Vec<FilterValue>)> {
+    let (filter, params) = where_clause(model, filters)?;
+    Ok((
+        format!("SELECT COUNT(*) AS count FROM {}{}", model.table, filter),
+        params,
+    ))
+}
+
+/// The ` WHERE ...` clause for `filters`, or nothing.
+fn where_clause(
+    model: &ModelInfo,
+    filters: &[(String, FilterValue)],
+) -> QueryResult<(String, Vec<FilterValue>)> {
+    let conditions = filters
+        .iter()
+        .map(|(field, value)| {
+            let column = model.column(field)?.name.clone();
+            Ok(match value {
+                FilterValue::List(values) => Filter::In(column.into(), values.clone()),
+                value => Filter::Equals(column.into(), value.clone()),
+            })
+        })
+        .collect::<QueryResult<Vec<_>>>()?;
+
+    let filter = Filter::and(conditions);
+    if filter.is_none() {
+        return Ok((String::new(), Vec::new()));
+    }
+    let (sql, params) = filter.to_sql(0);
+    Ok((format!(" WHERE {}", sql), params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::SchemaMap;
+
+    fn users() -> ModelInfo {
+        SchemaMap::parse(
+            r#"
+            model User {
+                id        Int      @id
+                email     String   @map("email_address")
+                active    Boolean
+                createdAt DateTime @map("created_at")
+
+                @@map("users")
+            }
+            "#,
+        )
+        .unwrap()
+        .model("User")
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_find_many_sql() {
+        let query = FindMany {
+            filters: vec![
+                ("active".into(), FilterValue::Bool(true)),
+                (
+                    "id".into(),
+                    FilterValue::List(vec![FilterValue::Int(1), FilterValue::Int(2)]),
+                ),
+            ],
+            order_by: vec![FindMany::parse_order("-createdAt")],
+            take: Some(10),
+            skip: Some(20),
+        };
+
+        let (sql, params) = query.build(&users()).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT id, email_address AS email, active, created_at AS createdAt FROM users \
+             WHERE (active = $1 AND id IN ($2, $3)) ORDER BY created_at DESC LIMIT 10 OFFSET 20"
+        );
+        assert_eq!(params.len(), 3);
+
+        let (sql, params) = FindMany::default().build(&users()).unwrap();
+        assert!(sql.ends_with("FROM users"));
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_count_sql() {
+        let (sql, params) = count(&users(), &[("email".into(), "a@b.c".into())]).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT COUNT(*) AS count FROM users WHERE email_address = $1"
+        );
+        assert_eq!(params, [FilterValue::String("a@b.c".into())]);
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let query = FindMany {
+            order_by: vec![("name".into(), SortOrder::Asc)],
+            ..Default::default()
+        };
+        assert!(query.build(&users()).is_err());
+        assert!(count(&users(), &[("name".into(), FilterValue::Null)]).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Model metadata read from the application's Prax schema.
+//!
+//! Python callers name models and fields as the schema does; this module
+//! resolves them to tables and columns, honoring `@@map` and `@map`, and
+//! records the Arrow type each scalar column should land as.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use arrow::datatypes::{DataType, TimeUnit};
+use prax_query::error::{ErrorCode, QueryError, QueryResult};
+use prax_schema::{FieldType, ScalarType, Schema, TypeModifier};
+
+/// A scalar field of a model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    /// The field name in the schema.
+    pub field: String,
+    /// The database column name.
+    pub name: String,
+    /// The Arrow type for the column, when the schema type pins one down.
+    pub data_type: Option<DataType>,
+}
+
+/// A model's table and scalar columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelInfo {
+    /// The model name.
+    pub name: String,
+    /// The database table name.
+    pub table: String,
+    /// Scalar columns, in schema order.
+    pub columns: Vec<Column>,
+}
+
+impl ModelInfo {
+    /// Build from a parsed schema model.
+    pub fn from_model(model: &prax_schema::Model) -> Self {
+        let columns = model
+            .fields
+            .values()
+            .filter(|f| !f.is_relation() && !f.is_virtual())
+            .map(|f| Column {
+                field: f.name().to_string(),
+                name: f
+                    .extract_attributes()
+                    .map
+                    .unwrap_or_else(|| f.name().to_string()),
+                data_type: arrow_type(&f.field_type, &f.modifier),
+            })
+            .collect();
+
+        Self {
+            name: model.name().to_string(),
+            table: model.table_name().to_string(),
+            columns,
+        }
+    }
+
+    /// Look up a column by field name.
+    pub fn column(&self, field: &str) -> QueryResult<&Column> {
+        self.columns
+            .iter()
+            .find(|c| c.field == field)
+            .ok_or_else(|| {
+                QueryError::invalid_input(field, format!("{} has no field {}", self.name, field))
+                    .with_model(&self.name)
+            })
+    }
+}
+
+/// The models of a schema, by name.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaMap {
+    models: BTreeMap<String, ModelInfo>,
+}
+
+impl SchemaMap {
+    /// Build from a parsed schema.
+    pub fn from_schema(schema: &Schema) -> Self {
+        Self {
+            models: schema
+                .models
+                .values()
+                .map(|m| (m.name().to_string(), ModelInfo::from_model(m)))
+                .collect(),
+        }
+    }
+
+    /// Parse a schema from source.
+    pub fn parse(source: &str) -> QueryResult<Self> {
+        prax_schema::parse_schema(source)
+            .map(|schema| Self::from_schema(&schema))
+            .map_err(schema_error)
+    }
+
+    /// Load a schema file, e.g. `prax/schema.prax`.
+    pub fn load(path: impl AsRef<Path>) -> QueryResult<Self> {
+        prax_schema::parse_schema_file(path)
+            .map(|schema| Self::from_schema(&schema))
+            .map_err(schema_error)
+    }
+
+    /// Look up a model by name.
+    pub fn model(&self, name: &str) -> QueryResult<&ModelInfo> {
+        self.models.get(name).ok_or_else(|| {
+            QueryError::new(
+                ErrorCode::InvalidParameter,
+                format!("Unknown model {}", name),
+            )
+            .with_model(name)
+        })
+    }
+
+    /// The model names, in order.
+    pub fn model_names(&self) -> impl Iterator<Item = &str> {
+        self.models.keys().map(String::as_str)
+    }
+}
+
+fn schema_error(err: prax_schema::SchemaError) -> QueryError {
+    QueryError::new(ErrorCode::InvalidConfiguration, err.to_string())
+}
+
+/// The Arrow type for a schema field, when it is not simply inferred from
+/// the JSON values.
+fn arrow_type(field_type: &FieldType, modifier: &TypeModifier) -> Option<DataType> {
+    if matches!(modifier, TypeModifier::List | TypeModifier::OptionalList) {
+        return None;
+    }
+    match field_type {
+        FieldType::Scalar(scalar) => match scalar {
+            ScalarType::Int | ScalarType::BigInt => Some(DataType::Int64),
+            ScalarType::Float | ScalarType::Decimal => Some(DataType::Float64),
+            ScalarType::Boolean => Some(DataType::Boolean),
+            ScalarType::DateTime => Some(DataType::Timestamp(
+                TimeUnit::Microsecond,
+                Some("UTC".into()),
+            )),
+            ScalarType::Date => Some(DataType::Date32),
+            ScalarType::String
+            | ScalarType::Uuid
+            | ScalarType::Cuid
+            | ScalarType::Cuid2
+            | ScalarType::NanoId
+            | ScalarType::Ulid => Some(DataType::Utf8),
+            _ => None,
+        },
+        FieldType::Enum(_) => Some(DataType::Utf8),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        model User {
+            id        Int      @id @auto
+            email     String   @unique @map("email_address")
+            createdAt DateTime @map("created_at")
+            posts     Post[]
+
+            @@map("users")
+        }
+
+        model Post {
+            id       Int    @id
+            authorId Int
+            author   User   @relation(fields: [authorId], references: [id])
+        }
+    "#;
+
+    #[test]
+    fn test_schema_map() {
+        let schema = SchemaMap::parse(SCHEMA).unwrap();
+        assert_eq!(schema.model_names().collect::<Vec<_>>(), ["Post", "User"]);
+
+        let user = schema.model("User").unwrap();
+        assert_eq!(user.table, "users");
+        assert_eq!(
+            user.columns
+                .iter()
+                .map(|c| (c.field.as_str(), c.name.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("id", "id"),
+                ("email", "email_address"),
+                ("createdAt", "created_at")
+            ]
+        );
+        assert_eq!(
+            user.column("createdAt").unwrap().data_type,
+            Some(DataType::Timestamp(
+                TimeUnit::Microsecond,
+                Some("UTC".into())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_schema_map_errors() {
+        let schema = SchemaMap::parse(SCHEMA).unwrap();
+        assert!(schema.model("Comment").is_err());
+
+        let err = schema
+            .model("User")
+            .unwrap()
+            .column("password")
+            .unwrap_err();
+        assert_eq!(err.context().field.as_deref(), Some("password"));
+
+        assert!(SchemaMap::parse("model {").is_err());
+    }
+}
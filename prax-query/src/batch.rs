@@ -16,9 +16,28 @@
 //!
 //! let results = engine.execute_batch(batch).await?;
 //! ```
+//!
+//! # Typed Batches
+//!
+//! [`run_batch`] runs operations built by the generated client, such as
+//! `find_many()` or `create(...)`, all-or-nothing in one transaction and
+//! returns their typed results positionally, like Prisma's
+//! `$transaction([...])`:
+//!
+//! ```rust,ignore
+//! let (user, posts, deleted) = client
+//!     .batch((
+//!         client.user().create(data),
+//!         client.post().find_many().r#where(post::published::equals(true)),
+//!         client.session().delete_many().r#where(session::expired::equals(true)),
+//!     ))
+//!     .await?;
+//! ```
 
 use crate::filter::FilterValue;
 use crate::sql::{DatabaseType, FastSqlBuilder, QueryCapacity};
+use crate::traits::{BoxFuture, QueryEngine};
+use crate::transaction::{TransactionConfig, TransactionalEngine, run_transaction};
 use std::collections::HashMap;
 
 /// A batch of operations to execute together.
@@ -573,6 +592,98 @@ impl PipelineResult {
     }
 }
 
+// ============================================================================
+// Typed Batches
+// ============================================================================
+
+/// An operation built by the client but not yet executed.
+///
+/// Implemented by the model operations (`find_many`, `create`, `update`,
+/// `delete_many`, `count`, ...) so [`run_batch`] can run them on the engine
+/// of its transaction instead of the one they were built with.
+pub trait BatchOp<E: QueryEngine>: Send + 'static {
+    /// What the operation returns, as from its `exec()`.
+    type Output: Send + 'static;
+
+    /// Execute the operation on `engine`.
+    fn exec_on(self, engine: E) -> BoxFuture<'static, crate::error::QueryResult<Self::Output>>;
+}
+
+/// Operations that [`run_batch`] runs in order: a tuple of [`BatchOp`]s,
+/// returning a tuple of their outputs, or a `Vec` of one kind of operation.
+pub trait BatchOps<E: QueryEngine>: Send + 'static {
+    /// The results, in the order of the operations.
+    type Output: Send + 'static;
+
+    /// Execute the operations one after another on `engine`, stopping at
+    /// the first failure.
+    fn exec_on(self, engine: E) -> BoxFuture<'static, crate::error::QueryResult<Self::Output>>;
+}
+
+impl<E: QueryEngine, T: BatchOp<E>> BatchOps<E> for Vec<T> {
+    type Output = Vec<T::Output>;
+
+    fn exec_on(self, engine: E) -> BoxFuture<'static, crate::error::QueryResult<Self::Output>> {
+        Box::pin(async move {
+            let mut results = Vec::with_capacity(self.len());
+            for op in self {
+                results.push(op.exec_on(engine.clone()).await?);
+            }
+            Ok(results)
+        })
+    }
+}
+
+macro_rules! impl_batch_ops_for_tuple {
+    ($($op:ident),+) => {
+        impl<E: QueryEngine, $($op: BatchOp<E>),+> BatchOps<E> for ($($op,)+) {
+            type Output = ($($op::Output,)+);
+
+            #[allow(non_snake_case)]
+            fn exec_on(
+                self,
+                engine: E,
+            ) -> BoxFuture<'static, crate::error::QueryResult<Self::Output>> {
+                let ($($op,)+) = self;
+                Box::pin(async move { Ok(($($op.exec_on(engine.clone()).await?,)+)) })
+            }
+        }
+    };
+}
+
+impl_batch_ops_for_tuple!(A);
+impl_batch_ops_for_tuple!(A, B);
+impl_batch_ops_for_tuple!(A, B, C);
+impl_batch_ops_for_tuple!(A, B, C, D);
+impl_batch_ops_for_tuple!(A, B, C, D, F);
+impl_batch_ops_for_tuple!(A, B, C, D, F, G);
+impl_batch_ops_for_tuple!(A, B, C, D, F, G, H);
+impl_batch_ops_for_tuple!(A, B, C, D, F, G, H, I);
+impl_batch_ops_for_tuple!(A, B, C, D, F, G, H, I, J);
+impl_batch_ops_for_tuple!(A, B, C, D, F, G, H, I, J, K);
+impl_batch_ops_for_tuple!(A, B, C, D, F, G, H, I, J, K, L);
+impl_batch_ops_for_tuple!(A, B, C, D, F, G, H, I, J, K, L, M);
+
+/// Run `ops` in order in one transaction on `engine`, returning their
+/// results positionally.
+///
+/// Every operation runs on the transaction's connection. The transaction
+/// commits when all of them succeed; the first failure rolls back the work
+/// of those before it and is returned. On an engine already bound to a
+/// transaction the batch runs in a savepoint of it, as with
+/// [`run_transaction`].
+pub async fn run_batch<E, B>(
+    engine: &E,
+    config: &TransactionConfig,
+    ops: B,
+) -> crate::error::QueryResult<B::Output>
+where
+    E: TransactionalEngine,
+    B: BatchOps<E>,
+{
+    run_transaction(engine, config, move |tx| ops.exec_on(tx)).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -641,4 +752,225 @@ mod tests {
         assert_eq!(batch_result.total_affected, 2);
         assert!(!batch_result.all_succeeded());
     }
+
+    mod engine {
+        use std::sync::{Arc, Mutex};
+
+        use crate::error::{QueryError, QueryResult};
+        use crate::filter::FilterValue;
+        use crate::traits::{BoxFuture, Model, QueryEngine};
+        use crate::transaction::{TransactionConfig, TransactionalEngine};
+
+        /// Engine recording every statement it runs, tagged with whether it
+        /// ran in a transaction. Statements on `missing` fail.
+        #[derive(Clone, Default)]
+        pub struct RecordingEngine {
+            pub log: Arc<Mutex<Vec<String>>>,
+            pub in_transaction: bool,
+        }
+
+        impl RecordingEngine {
+            pub fn statements(&self) -> Vec<String> {
+                self.log.lock().unwrap().clone()
+            }
+
+            fn run(&self, sql: &str) -> BoxFuture<'_, QueryResult<u64>> {
+                let tag = if self.in_transaction { "tx" } else { "pool" };
+                self.log.lock().unwrap().push(format!("{tag}: {sql}"));
+                let failed = sql.contains("missing");
+                Box::pin(async move {
+                    if failed {
+                        Err(QueryError::not_found("missing"))
+                    } else {
+                        Ok(1)
+                    }
+                })
+            }
+        }
+
+        impl QueryEngine for RecordingEngine {
+            fn query_many<T: Model + Send + 'static>(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<Vec<T>>> {
+                let result = self.run(sql);
+                Box::pin(async move { result.await.map(|_| Vec::new()) })
+            }
+
+            fn query_one<T: Model + Send + 'static>(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<T>> {
+                let _ = self.run(sql);
+                Box::pin(async { Err(QueryError::not_found("test")) })
+            }
+
+            fn query_optional<T: Model + Send + 'static>(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<Option<T>>> {
+                let result = self.run(sql);
+                Box::pin(async move { result.await.map(|_| None) })
+            }
+
+            fn execute_insert<T: Model + Send + 'static>(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<T>> {
+                let _ = self.run(sql);
+                Box::pin(async { Err(QueryError::not_found("test")) })
+            }
+
+            fn execute_update<T: Model + Send + 'static>(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<Vec<T>>> {
+                let result = self.run(sql);
+                Box::pin(async move { result.await.map(|_| Vec::new()) })
+            }
+
+            fn execute_delete(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<u64>> {
+                self.run(sql)
+            }
+
+            fn execute_raw(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<u64>> {
+                self.run(sql)
+            }
+
+            fn count(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<u64>> {
+                self.run(sql)
+            }
+        }
+
+        impl TransactionalEngine for RecordingEngine {
+            fn begin(&self, _config: &TransactionConfig) -> BoxFuture<'_, QueryResult<Self>> {
+                self.log.lock().unwrap().push("BEGIN".to_string());
+                let tx = Self {
+                    log: self.log.clone(),
+                    in_transaction: true,
+                };
+                Box::pin(async move { Ok(tx) })
+            }
+
+            fn commit(&self) -> BoxFuture<'_, QueryResult<()>> {
+                self.log.lock().unwrap().push("COMMIT".to_string());
+                Box::pin(async { Ok(()) })
+            }
+
+            fn rollback(&self) -> BoxFuture<'_, QueryResult<()>> {
+                self.log.lock().unwrap().push("ROLLBACK".to_string());
+                Box::pin(async { Ok(()) })
+            }
+
+            fn in_transaction(&self) -> bool {
+                self.in_transaction
+            }
+        }
+    }
+
+    struct User;
+
+    impl crate::traits::Model for User {
+        const MODEL_NAME: &'static str = "User";
+        const TABLE_NAME: &'static str = "users";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "name"];
+    }
+
+    struct Missing;
+
+    impl crate::traits::Model for Missing {
+        const MODEL_NAME: &'static str = "Missing";
+        const TABLE_NAME: &'static str = "missing";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id"];
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_returns_typed_results_in_order() {
+        use crate::operations::{CountOperation, DeleteManyOperation, FindManyOperation};
+
+        let engine = engine::RecordingEngine::default();
+        let (count, users, deleted) = run_batch(
+            &engine,
+            &TransactionConfig::default(),
+            (
+                CountOperation::<_, User>::new(engine.clone()),
+                FindManyOperation::<_, User>::new(engine.clone()),
+                DeleteManyOperation::<_, User>::new(engine.clone()),
+            ),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(count, 1);
+        assert!(users.is_empty());
+        assert_eq!(deleted, 1);
+
+        let statements = engine.statements();
+        assert_eq!(statements.first().map(String::as_str), Some("BEGIN"));
+        assert_eq!(statements.last().map(String::as_str), Some("COMMIT"));
+        assert_eq!(statements.len(), 5);
+        assert!(statements[1..4].iter().all(|sql| sql.starts_with("tx: ")));
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_rolls_back_on_failure() {
+        use crate::operations::{CountOperation, DeleteManyOperation};
+
+        let engine = engine::RecordingEngine::default();
+        let err = run_batch(
+            &engine,
+            &TransactionConfig::default(),
+            (
+                DeleteManyOperation::<_, User>::new(engine.clone()),
+                CountOperation::<_, Missing>::new(engine.clone()),
+                CountOperation::<_, User>::new(engine.clone()),
+            ),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.is_not_found());
+        let statements = engine.statements();
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements.last().map(String::as_str), Some("ROLLBACK"));
+        assert!(statements.iter().all(|sql| !sql.starts_with("pool: ")));
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_vec() {
+        use crate::operations::CountOperation;
+
+        let engine = engine::RecordingEngine::default();
+        let counts = run_batch(
+            &engine,
+            &TransactionConfig::default(),
+            (0..3)
+                .map(|_| CountOperation::<_, User>::new(engine.clone()))
+                .collect::<Vec<_>>(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(counts, vec![1, 1, 1]);
+        assert_eq!(engine.statements().len(), 5);
+    }
 }
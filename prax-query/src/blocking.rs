@@ -0,0 +1,107 @@
+//! Blocking execution for synchronous code.
+//!
+//! CLI utilities, build scripts and tests often have no async runtime of
+//! their own. This module drives Prax's async engines on a lightweight
+//! current-thread Tokio runtime, created lazily once per thread, so such code
+//! can run queries without an `async fn main`.
+//!
+//! Each thread gets its own runtime, and the connections a pool opens are
+//! driven by the runtime of the thread that opened them: create and use a
+//! client on the same thread.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use prax_query::blocking::{self, Wait};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let pool = blocking::block_on(PgPool::builder().url(url).build())?;
+//!     let client = PraxClient::new(PgEngine::new(pool));
+//!
+//!     let users = client.user().find_many().exec().wait()?;
+//!     println!("{} users", users.len());
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Calling into this module from inside an async runtime panics, as blocking
+//! there would stall the runtime's other tasks; `.await` the future instead.
+
+use std::future::{Future, IntoFuture};
+
+use tokio::runtime::{Builder, Handle, Runtime};
+
+thread_local! {
+    static RUNTIME: Runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build the blocking runtime");
+}
+
+/// Run a future to completion on this thread's runtime.
+///
+/// # Panics
+///
+/// Panics when called from within an async runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    if Handle::try_current().is_ok() {
+        panic!("prax_query::blocking cannot run inside an async runtime; await the future instead");
+    }
+    RUNTIME.with(|runtime| runtime.block_on(future))
+}
+
+/// Wait for a future from synchronous code.
+///
+/// Implemented for every future, including the ones returned by `exec()`.
+pub trait Wait: IntoFuture + Sized {
+    /// Run the future to completion on this thread's runtime.
+    ///
+    /// See [`block_on`].
+    fn wait(self) -> Self::Output {
+        block_on(self.into_future())
+    }
+}
+
+impl<F: IntoFuture> Wait for F {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::error::{QueryError, QueryResult};
+
+    async fn count_after(delay: Duration) -> QueryResult<u64> {
+        tokio::time::sleep(delay).await;
+        Ok(3)
+    }
+
+    #[test]
+    fn test_block_on() {
+        assert_eq!(block_on(count_after(Duration::from_millis(1))).unwrap(), 3);
+
+        let spawned = block_on(async { tokio::spawn(async { 7 }).await.unwrap() });
+        assert_eq!(spawned, 7);
+    }
+
+    #[test]
+    fn test_wait() {
+        assert_eq!(count_after(Duration::ZERO).wait().unwrap(), 3);
+
+        let failed = async { Err::<(), _>(QueryError::not_found("User")) }.wait();
+        assert!(failed.unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_runtime_per_thread() {
+        let handle = std::thread::spawn(|| count_after(Duration::ZERO).wait().unwrap());
+        assert_eq!(handle.join().unwrap(), 3);
+        assert_eq!(count_after(Duration::ZERO).wait().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "cannot run inside an async runtime")]
+    async fn test_block_on_inside_runtime() {
+        let _ = count_after(Duration::ZERO).wait();
+    }
+}
@@ -0,0 +1,271 @@
+//! Database capabilities probed at runtime.
+//!
+//! Servers in the field lag behind the releases Prax targets: an SQLite older
+//! than 3.35 has no `RETURNING`, MySQL never had it while MariaDB gained it in
+//! 10.5, and JSON functions arrived in different versions on every backend.
+//! Drivers probe the server once per pool with [`Capabilities::version_query`]
+//! and [`Capabilities::extensions_query`], build [`Capabilities`] from the
+//! answers, and consult them when building SQL, so queries degrade to an older
+//! form instead of failing at runtime.
+//!
+//! | Feature   | PostgreSQL | MySQL | MariaDB | SQLite           | MSSQL |
+//! |-----------|------------|-------|---------|------------------|-------|
+//! | RETURNING | ✅         | ❌    | 10.5    | 3.35             | ❌    |
+//! | JSON      | 9.4        | 5.7.8 | 10.2.7  | 3.38 (or `json1`)| 13    |
+//!
+//! # Example
+//!
+//! ```rust
+//! use prax_query::capabilities::Capabilities;
+//! use prax_query::sql::DatabaseType;
+//!
+//! let caps = Capabilities::new(DatabaseType::SQLite, "3.31.1", ["json1"]);
+//! assert!(!caps.supports_returning());
+//! assert!(caps.supports_json());
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::sql::DatabaseType;
+
+/// A server version, such as `8.0.35`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ServerVersion {
+    /// Major version.
+    pub major: u32,
+    /// Minor version.
+    pub minor: u32,
+    /// Patch version.
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    /// Create a version.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the first `major[.minor[.patch]]` in a version string, such as
+    /// `PostgreSQL 16.2 on x86_64-pc-linux-gnu` or `10.11.2-MariaDB`.
+    pub fn parse(version: &str) -> Option<Self> {
+        let start = version.find(|c: char| c.is_ascii_digit())?;
+        let mut parts = version[start..]
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()?
+            .split('.')
+            .map(|part| part.parse::<u32>().ok());
+
+        let major = parts.next()??;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// What a database server supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    database_type: DatabaseType,
+    version: Option<ServerVersion>,
+    mariadb: bool,
+    extensions: BTreeSet<String>,
+    returning: bool,
+    json: bool,
+}
+
+impl Capabilities {
+    /// Derive the capabilities of a server from its version string and its
+    /// installed extensions.
+    ///
+    /// An unparseable version is assumed to be current.
+    pub fn new(
+        database_type: DatabaseType,
+        version: &str,
+        extensions: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let parsed = ServerVersion::parse(version);
+        let mariadb = database_type == DatabaseType::MySQL
+            && version.to_ascii_lowercase().contains("mariadb");
+        let extensions: BTreeSet<String> = extensions
+            .into_iter()
+            .map(|e| e.into().to_lowercase())
+            .collect();
+        let at_least = |major, minor, patch| {
+            parsed.is_none_or(|v| v >= ServerVersion::new(major, minor, patch))
+        };
+
+        let (returning, json) = match database_type {
+            DatabaseType::PostgreSQL => (true, at_least(9, 4, 0)),
+            DatabaseType::MySQL if mariadb => (at_least(10, 5, 0), at_least(10, 2, 7)),
+            DatabaseType::MySQL => (false, at_least(5, 7, 8)),
+            DatabaseType::SQLite => (
+                at_least(3, 35, 0),
+                at_least(3, 38, 0) || extensions.contains("json1"),
+            ),
+            DatabaseType::MSSQL => (false, at_least(13, 0, 0)),
+        };
+
+        Self {
+            database_type,
+            version: parsed,
+            mariadb,
+            extensions,
+            returning,
+            json,
+        }
+    }
+
+    /// Capabilities of a current server, used until one has been probed.
+    pub fn assumed(database_type: DatabaseType) -> Self {
+        Self::new(database_type, "", Vec::<String>::new())
+    }
+
+    /// Query returning the server version as a single text column.
+    pub fn version_query(database_type: DatabaseType) -> &'static str {
+        match database_type {
+            DatabaseType::PostgreSQL => "SHOW server_version",
+            DatabaseType::MySQL => "SELECT VERSION()",
+            DatabaseType::SQLite => "SELECT sqlite_version()",
+            DatabaseType::MSSQL => "SELECT CAST(SERVERPROPERTY('ProductVersion') AS NVARCHAR(128))",
+        }
+    }
+
+    /// Query listing the installed extensions as a single text column, if the
+    /// database has any.
+    ///
+    /// SQLite reports its compile options; pass them through
+    /// [`Capabilities::sqlite_extension`].
+    pub fn extensions_query(database_type: DatabaseType) -> Option<&'static str> {
+        match database_type {
+            DatabaseType::PostgreSQL => Some("SELECT extname FROM pg_extension"),
+            DatabaseType::SQLite => Some("PRAGMA compile_options"),
+            DatabaseType::MySQL | DatabaseType::MSSQL => None,
+        }
+    }
+
+    /// Get the extension an SQLite compile option enables, such as `json1`
+    /// for `ENABLE_JSON1`.
+    pub fn sqlite_extension(option: &str) -> Option<String> {
+        option
+            .strip_prefix("ENABLE_")
+            .map(|extension| extension.to_lowercase())
+    }
+
+    /// Get the database type.
+    pub fn database_type(&self) -> DatabaseType {
+        self.database_type
+    }
+
+    /// Get the server version, if one was probed.
+    pub fn version(&self) -> Option<ServerVersion> {
+        self.version
+    }
+
+    /// Check if the server is at least the given version.
+    ///
+    /// Always true when no version was probed.
+    pub fn version_at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        self.version
+            .is_none_or(|v| v >= ServerVersion::new(major, minor, patch))
+    }
+
+    /// Check if a MySQL server is MariaDB.
+    pub fn is_mariadb(&self) -> bool {
+        self.mariadb
+    }
+
+    /// Get the installed extensions.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.extensions.iter().map(String::as_str)
+    }
+
+    /// Check if an extension is installed.
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions.contains(&name.to_lowercase())
+    }
+
+    /// Check if `INSERT` accepts a `RETURNING` clause.
+    pub fn supports_returning(&self) -> bool {
+        self.returning
+    }
+
+    /// Check if the server has built-in JSON functions.
+    pub fn supports_json(&self) -> bool {
+        self.json
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_version() {
+        assert_eq!(
+            ServerVersion::parse("PostgreSQL 16.2 on x86_64-pc-linux-gnu"),
+            Some(ServerVersion::new(16, 2, 0))
+        );
+        assert_eq!(
+            ServerVersion::parse("10.11.2-MariaDB-1:10.11.2+maria~ubu2204"),
+            Some(ServerVersion::new(10, 11, 2))
+        );
+        assert_eq!(
+            ServerVersion::parse("16.0.1000.6"),
+            Some(ServerVersion::new(16, 0, 1000))
+        );
+        assert_eq!(ServerVersion::parse("unknown"), None);
+        assert_eq!(ServerVersion::new(3, 35, 0).to_string(), "3.35.0");
+    }
+
+    #[test]
+    fn test_capabilities_by_version() {
+        let sqlite = Capabilities::new(DatabaseType::SQLite, "3.31.1", Vec::<String>::new());
+        assert!(!sqlite.supports_returning());
+        assert!(!sqlite.supports_json());
+        assert!(!sqlite.version_at_least(3, 35, 0));
+
+        let sqlite = Capabilities::new(DatabaseType::SQLite, "3.45.1", Vec::<String>::new());
+        assert!(sqlite.supports_returning());
+        assert!(sqlite.supports_json());
+
+        let mysql = Capabilities::new(DatabaseType::MySQL, "8.0.35", Vec::<String>::new());
+        assert!(!mysql.is_mariadb());
+        assert!(!mysql.supports_returning());
+        assert!(mysql.supports_json());
+
+        let mariadb =
+            Capabilities::new(DatabaseType::MySQL, "10.6.16-MariaDB", Vec::<String>::new());
+        assert!(mariadb.is_mariadb());
+        assert!(mariadb.supports_returning());
+
+        let postgres = Capabilities::new(DatabaseType::PostgreSQL, "9.3.25", ["PostGIS"]);
+        assert!(postgres.supports_returning());
+        assert!(!postgres.supports_json());
+        assert!(postgres.has_extension("postgis"));
+    }
+
+    #[test]
+    fn test_assumed_capabilities() {
+        let caps = Capabilities::assumed(DatabaseType::SQLite);
+        assert_eq!(caps.version(), None);
+        assert!(caps.supports_returning());
+        assert!(caps.version_at_least(99, 0, 0));
+        assert!(!Capabilities::assumed(DatabaseType::MySQL).supports_returning());
+        assert_eq!(
+            Capabilities::sqlite_extension("ENABLE_FTS5"),
+            Some("fts5".to_string())
+        );
+        assert_eq!(Capabilities::sqlite_extension("THREADSAFE=1"), None);
+    }
+}
@@ -78,6 +78,7 @@ mod env;
 mod options;
 mod parser;
 mod pool;
+mod telemetry;
 
 pub use config::{DatabaseConfig, DatabaseConfigBuilder, MultiDatabaseConfig};
 pub use env::{EnvExpander, EnvSource};
@@ -87,6 +88,7 @@ pub use options::{
 };
 pub use parser::{ConnectionString, Driver, ParsedUrl};
 pub use pool::PoolConfig;
+pub use telemetry::{PoolSnapshot, PoolTelemetry, WaitHistogram};
 
 use thiserror::Error;
 
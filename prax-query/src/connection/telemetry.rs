@@ -0,0 +1,361 @@
+//! Pool checkout telemetry.
+//!
+//! Drivers wrap each checkout in [`PoolTelemetry::acquire`], which bounds the
+//! wait with the configured acquire timeout, tracks how many tasks are queued
+//! for a connection and records how long each one waited. When a checkout
+//! times out, [`PoolTelemetry::snapshot`] captures the pool's state for
+//! [`QueryError::pool_timeout`](crate::error::QueryError::pool_timeout).
+
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::middleware::record_pool_wait;
+
+/// Upper bounds of the wait histogram buckets; the last bucket is unbounded.
+const BUCKET_BOUNDS: [Duration; 8] = [
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+];
+
+/// Histogram of the time spent waiting for a pooled connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaitHistogram {
+    /// Buckets: [0-1ms], [1-5ms], [5-10ms], [10-50ms], [50-100ms], [100-500ms], [500ms-1s], [1-5s], [5s+]
+    buckets: [u64; 9],
+    /// Total waits recorded.
+    count: u64,
+    /// Sum of all waits.
+    total: Duration,
+    /// Longest wait.
+    max: Duration,
+}
+
+impl WaitHistogram {
+    /// Create a new histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a wait.
+    pub fn record(&mut self, wait: Duration) {
+        let bucket = BUCKET_BOUNDS
+            .iter()
+            .position(|bound| wait <= *bound)
+            .unwrap_or(BUCKET_BOUNDS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.total += wait;
+        self.max = self.max.max(wait);
+    }
+
+    /// Get bucket counts.
+    pub fn buckets(&self) -> &[u64; 9] {
+        &self.buckets
+    }
+
+    /// Get bucket labels.
+    pub fn bucket_labels() -> &'static [&'static str; 9] {
+        &[
+            "0-1ms",
+            "1-5ms",
+            "5-10ms",
+            "10-50ms",
+            "50-100ms",
+            "100-500ms",
+            "500ms-1s",
+            "1-5s",
+            "5s+",
+        ]
+    }
+
+    /// Get the number of waits recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Get the mean wait, if any were recorded.
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0)
+            .then(|| Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64))
+    }
+
+    /// Get the longest wait.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Get the upper bound of the bucket holding the given percentile of
+    /// waits, such as `0.99`.
+    ///
+    /// Waits in the unbounded bucket report the longest wait.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((self.count as f64 * percentile.clamp(0.0, 1.0)).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, &count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(BUCKET_BOUNDS.get(i).copied().unwrap_or(self.max));
+            }
+        }
+        Some(self.max)
+    }
+}
+
+/// The state of a connection pool at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolSnapshot {
+    /// Maximum number of connections.
+    pub max_size: usize,
+    /// Number of open connections.
+    pub size: usize,
+    /// Number of open connections not checked out.
+    pub idle: usize,
+    /// Number of tasks waiting for a connection.
+    pub waiting: usize,
+    /// Most tasks ever waiting for a connection at once.
+    pub peak_waiting: usize,
+    /// Number of successful checkouts.
+    pub acquired: u64,
+    /// Number of checkouts that timed out.
+    pub timeouts: u64,
+    /// Time spent waiting by successful checkouts.
+    pub wait: WaitHistogram,
+}
+
+impl PoolSnapshot {
+    /// Get the number of connections checked out.
+    pub fn in_use(&self) -> usize {
+        self.size.saturating_sub(self.idle)
+    }
+}
+
+impl fmt::Display for PoolSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} connections open, {} in use, {} idle, {} waiting (peak {}), {} timeouts",
+            self.size,
+            self.max_size,
+            self.in_use(),
+            self.idle,
+            self.waiting,
+            self.peak_waiting,
+            self.timeouts
+        )?;
+        if let Some(p99) = self.wait.percentile(0.99) {
+            write!(f, ", p99 wait {}ms", p99.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
+/// Queue depth and wait times of a connection pool's checkouts.
+///
+/// # Example
+///
+/// ```rust
+/// use prax_query::connection::PoolTelemetry;
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let telemetry = PoolTelemetry::new();
+/// let conn = telemetry
+///     .acquire(Some(Duration::from_secs(1)), async { Ok::<_, ()>("conn") })
+///     .await;
+/// assert_eq!(conn, Some(Ok("conn")));
+///
+/// let snapshot = telemetry.snapshot(10, 1, 0);
+/// assert_eq!(snapshot.acquired, 1);
+/// assert_eq!(snapshot.wait.count(), 1);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct PoolTelemetry {
+    waiting: AtomicUsize,
+    peak_waiting: AtomicUsize,
+    acquired: AtomicU64,
+    timeouts: AtomicU64,
+    wait: Mutex<WaitHistogram>,
+}
+
+impl PoolTelemetry {
+    /// Create empty telemetry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run a checkout, giving up after `timeout`.
+    ///
+    /// Returns `None` when the checkout timed out. The wait of a successful
+    /// checkout is recorded in the histogram and on the current
+    /// [`TracingMiddleware`](crate::middleware::TracingMiddleware) span.
+    pub async fn acquire<F, T, E>(
+        &self,
+        timeout: Option<Duration>,
+        checkout: F,
+    ) -> Option<Result<T, E>>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        let _queued = Queued::new(self);
+        let start = Instant::now();
+
+        let result = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, checkout).await.ok(),
+            None => Some(checkout.await),
+        };
+
+        let wait = start.elapsed();
+        match &result {
+            Some(Ok(_)) => {
+                self.acquired.fetch_add(1, Ordering::Relaxed);
+                self.wait.lock().record(wait);
+                record_pool_wait(wait);
+            }
+            Some(Err(_)) => {}
+            None => {
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        result
+    }
+
+    /// Get the number of tasks waiting for a connection.
+    pub fn waiting(&self) -> usize {
+        self.waiting.load(Ordering::Relaxed)
+    }
+
+    /// Capture the pool's state, given the sizes the pool itself reports.
+    pub fn snapshot(&self, max_size: usize, size: usize, idle: usize) -> PoolSnapshot {
+        PoolSnapshot {
+            max_size,
+            size,
+            idle,
+            waiting: self.waiting(),
+            peak_waiting: self.peak_waiting.load(Ordering::Relaxed),
+            acquired: self.acquired.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            wait: self.wait.lock().clone(),
+        }
+    }
+
+    /// Reset the counters and the histogram.
+    ///
+    /// Tasks currently waiting stay counted.
+    pub fn reset(&self) {
+        self.peak_waiting.store(self.waiting(), Ordering::Relaxed);
+        self.acquired.store(0, Ordering::Relaxed);
+        self.timeouts.store(0, Ordering::Relaxed);
+        *self.wait.lock() = WaitHistogram::new();
+    }
+}
+
+/// A task counted in the queue until dropped, so cancelled checkouts leave it.
+struct Queued<'a>(&'a PoolTelemetry);
+
+impl<'a> Queued<'a> {
+    fn new(telemetry: &'a PoolTelemetry) -> Self {
+        let depth = telemetry.waiting.fetch_add(1, Ordering::Relaxed) + 1;
+        telemetry.peak_waiting.fetch_max(depth, Ordering::Relaxed);
+        Self(telemetry)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.waiting.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_histogram() {
+        let mut hist = WaitHistogram::new();
+        assert_eq!(hist.percentile(0.99), None);
+        assert_eq!(hist.mean(), None);
+
+        for _ in 0..98 {
+            hist.record(Duration::from_micros(500));
+        }
+        hist.record(Duration::from_millis(30));
+        hist.record(Duration::from_secs(8));
+
+        assert_eq!(hist.count(), 100);
+        assert_eq!(hist.buckets()[0], 98);
+        assert_eq!(hist.buckets()[3], 1);
+        assert_eq!(hist.buckets()[8], 1);
+        assert_eq!(hist.max(), Duration::from_secs(8));
+        assert_eq!(hist.percentile(0.5), Some(Duration::from_millis(1)));
+        assert_eq!(hist.percentile(0.99), Some(Duration::from_millis(50)));
+        assert_eq!(hist.percentile(1.0), Some(Duration::from_secs(8)));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_timeout() {
+        let telemetry = PoolTelemetry::new();
+
+        let result = telemetry
+            .acquire(
+                Some(Duration::from_millis(10)),
+                std::future::pending::<Result<(), ()>>(),
+            )
+            .await;
+        assert_eq!(result, None);
+
+        let result = telemetry
+            .acquire(None, async { Err::<(), _>("refused") })
+            .await;
+        assert_eq!(result, Some(Err("refused")));
+
+        let snapshot = telemetry.snapshot(4, 4, 0);
+        assert_eq!(snapshot.timeouts, 1);
+        assert_eq!(snapshot.acquired, 0);
+        assert_eq!(snapshot.waiting, 0);
+        assert_eq!(snapshot.peak_waiting, 1);
+        assert_eq!(snapshot.in_use(), 4);
+        assert_eq!(
+            snapshot.to_string(),
+            "4/4 connections open, 4 in use, 0 idle, 0 waiting (peak 1), 1 timeouts"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queue_depth() {
+        let telemetry = PoolTelemetry::new();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+        let waiter = telemetry.acquire(None, async {
+            rx.await.map_err(|_| ())?;
+            Ok::<_, ()>(())
+        });
+        let observer = async {
+            tokio::task::yield_now().await;
+            let depth = telemetry.waiting();
+            tx.send(()).unwrap();
+            depth
+        };
+
+        let (result, depth) = tokio::join!(waiter, observer);
+        assert_eq!(result, Some(Ok(())));
+        assert_eq!(depth, 1);
+        assert_eq!(telemetry.waiting(), 0);
+        assert_eq!(telemetry.snapshot(1, 1, 1).acquired, 1);
+    }
+}
@@ -47,13 +47,14 @@
 //! ```
 
 use crate::filter::FilterValue;
+use crate::value::Value;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Trait for types that can be used as create data.
 pub trait CreateData: Send + Sync {
     /// Get the field values as a map.
-    fn into_fields(self) -> HashMap<String, FieldValue>;
+    fn into_fields(self) -> HashMap<String, FieldOp>;
 
     /// Get the model name.
     fn model_name() -> &'static str;
@@ -62,36 +63,25 @@ pub trait CreateData: Send + Sync {
 /// Trait for types that can be used as update data.
 pub trait UpdateData: Send + Sync {
     /// Get the field values as a map (only set fields).
-    fn into_fields(self) -> HashMap<String, FieldValue>;
+    fn into_fields(self) -> HashMap<String, FieldOp>;
 
     /// Get the model name.
     fn model_name() -> &'static str;
 }
 
-/// A field value that can be set in create/update operations.
+/// A value set on a field in create/update operations.
+///
+/// Create and update data bind the same [`Value`] as every other query
+/// parameter.
+pub type FieldValue = Value;
+
+/// What a create or update does to a field: set it to a value, or apply a
+/// relation or update operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
-pub enum FieldValue {
-    /// Null value.
-    Null,
-    /// Boolean value.
-    Bool(bool),
-    /// Integer value.
-    Int(i64),
-    /// Float value.
-    Float(f64),
-    /// String value.
-    String(String),
-    /// JSON value.
-    Json(serde_json::Value),
-    /// Bytes value.
-    Bytes(Vec<u8>),
-    /// DateTime as ISO string.
-    DateTime(String),
-    /// UUID as string.
-    Uuid(String),
-    /// Array of values.
-    Array(Vec<FieldValue>),
+pub enum FieldOp {
+    /// Set to a value.
+    Set(FieldValue),
     /// Nested create data.
     Nested(Box<DataBuilder>),
     /// Connect to existing record.
@@ -109,89 +99,63 @@ pub enum FieldValue {
     /// Divide by value.
     Divide(f64),
     /// Append to array.
-    Push(Box<FieldValue>),
+    Push(FieldValue),
     /// Unset the field.
     Unset,
 }
 
-impl FieldValue {
-    /// Convert to FilterValue for query operations.
-    pub fn to_filter_value(&self) -> Option<FilterValue> {
+impl FieldOp {
+    /// Get the [`Value`] bound as a query parameter.
+    ///
+    /// Returns `None` for relation and update operations (`Nested`,
+    /// `Connect`, `Increment`, ...), which are not plain values.
+    pub fn to_value(&self) -> Option<Value> {
         match self {
-            Self::Null => Some(FilterValue::Null),
-            Self::Bool(b) => Some(FilterValue::Bool(*b)),
-            Self::Int(i) => Some(FilterValue::Int(*i)),
-            Self::Float(f) => Some(FilterValue::Float(*f)),
-            Self::String(s) => Some(FilterValue::String(s.clone())),
-            Self::Json(j) => Some(FilterValue::Json(j.clone())),
-            Self::DateTime(s) => Some(FilterValue::String(s.clone())),
-            Self::Uuid(s) => Some(FilterValue::String(s.clone())),
+            Self::Set(value) => Some(value.clone()),
             _ => None,
         }
     }
-}
-
-// Convenient From implementations
-impl From<bool> for FieldValue {
-    fn from(v: bool) -> Self {
-        Self::Bool(v)
-    }
-}
-
-impl From<i32> for FieldValue {
-    fn from(v: i32) -> Self {
-        Self::Int(v as i64)
-    }
-}
 
-impl From<i64> for FieldValue {
-    fn from(v: i64) -> Self {
-        Self::Int(v)
-    }
-}
-
-impl From<f32> for FieldValue {
-    fn from(v: f32) -> Self {
-        Self::Float(v as f64)
-    }
-}
-
-impl From<f64> for FieldValue {
-    fn from(v: f64) -> Self {
-        Self::Float(v)
-    }
-}
-
-impl From<String> for FieldValue {
-    fn from(v: String) -> Self {
-        Self::String(v)
-    }
-}
-
-impl From<&str> for FieldValue {
-    fn from(v: &str) -> Self {
-        Self::String(v.to_string())
+    /// Convert to FilterValue for query operations.
+    pub fn to_filter_value(&self) -> Option<FilterValue> {
+        self.to_value()
     }
 }
 
-impl From<serde_json::Value> for FieldValue {
-    fn from(v: serde_json::Value) -> Self {
-        Self::Json(v)
-    }
+macro_rules! impl_field_op_from {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl From<$ty> for FieldOp {
+                fn from(v: $ty) -> Self {
+                    Self::Set(v.into())
+                }
+            }
+        )*
+    };
 }
 
-impl<T: Into<FieldValue>> From<Option<T>> for FieldValue {
+impl_field_op_from!(
+    Value,
+    bool,
+    i32,
+    i64,
+    f32,
+    f64,
+    String,
+    &str,
+    &[u8],
+    serde_json::Value,
+);
+
+impl<T: Into<Value>> From<Option<T>> for FieldOp {
     fn from(v: Option<T>) -> Self {
-        match v {
-            Some(val) => val.into(),
-            None => Self::Null,
-        }
+        Self::Set(v.into())
     }
 }
 
-impl<T: Into<FieldValue>> From<Vec<T>> for FieldValue {
+impl<T: Into<Value>> From<Vec<T>> for FieldOp {
     fn from(v: Vec<T>) -> Self {
-        Self::Array(v.into_iter().map(Into::into).collect())
+        Self::Set(v.into())
     }
 }
 
@@ -201,7 +165,7 @@ pub struct ConnectData {
     /// Field name to match on.
     pub field: String,
     /// Value to match.
-    pub value: Box<FieldValue>,
+    pub value: FieldValue,
 }
 
 impl ConnectData {
@@ -209,7 +173,7 @@ impl ConnectData {
     pub fn id(id: impl Into<FieldValue>) -> Self {
         Self {
             field: "id".to_string(),
-            value: Box::new(id.into()),
+            value: id.into(),
         }
     }
 
@@ -217,7 +181,7 @@ impl ConnectData {
     pub fn by(field: impl Into<String>, value: impl Into<FieldValue>) -> Self {
         Self {
             field: field.into(),
-            value: Box::new(value.into()),
+            value: value.into(),
         }
     }
 }
@@ -228,7 +192,7 @@ impl ConnectData {
 /// nested creates, connects, and all update operations.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DataBuilder {
-    fields: HashMap<String, FieldValue>,
+    fields: HashMap<String, FieldOp>,
 }
 
 impl DataBuilder {
@@ -237,65 +201,67 @@ impl DataBuilder {
         Self::default()
     }
 
-    /// Set a field value.
-    pub fn set(mut self, field: impl Into<String>, value: impl Into<FieldValue>) -> Self {
+    /// Set a field value, or apply an operation such as [`connect!`] or
+    /// [`increment!`] to it.
+    pub fn set(mut self, field: impl Into<String>, value: impl Into<FieldOp>) -> Self {
         self.fields.insert(field.into(), value.into());
         self
     }
 
     /// Set a field to null.
     pub fn set_null(mut self, field: impl Into<String>) -> Self {
-        self.fields.insert(field.into(), FieldValue::Null);
+        self.fields
+            .insert(field.into(), FieldOp::Set(FieldValue::Null));
         self
     }
 
     /// Set a field to its default value.
     pub fn set_default(mut self, field: impl Into<String>) -> Self {
-        self.fields.insert(field.into(), FieldValue::Default);
+        self.fields.insert(field.into(), FieldOp::Default);
         self
     }
 
     /// Unset a field (for updates).
     pub fn unset(mut self, field: impl Into<String>) -> Self {
-        self.fields.insert(field.into(), FieldValue::Unset);
+        self.fields.insert(field.into(), FieldOp::Unset);
         self
     }
 
     /// Increment a numeric field.
     pub fn increment(mut self, field: impl Into<String>, by: i64) -> Self {
-        self.fields.insert(field.into(), FieldValue::Increment(by));
+        self.fields.insert(field.into(), FieldOp::Increment(by));
         self
     }
 
     /// Decrement a numeric field.
     pub fn decrement(mut self, field: impl Into<String>, by: i64) -> Self {
-        self.fields.insert(field.into(), FieldValue::Decrement(by));
+        self.fields.insert(field.into(), FieldOp::Decrement(by));
         self
     }
 
     /// Multiply a numeric field.
     pub fn multiply(mut self, field: impl Into<String>, by: f64) -> Self {
-        self.fields.insert(field.into(), FieldValue::Multiply(by));
+        self.fields.insert(field.into(), FieldOp::Multiply(by));
         self
     }
 
     /// Divide a numeric field.
     pub fn divide(mut self, field: impl Into<String>, by: f64) -> Self {
-        self.fields.insert(field.into(), FieldValue::Divide(by));
+        self.fields.insert(field.into(), FieldOp::Divide(by));
         self
     }
 
     /// Push a value to an array field.
     pub fn push(mut self, field: impl Into<String>, value: impl Into<FieldValue>) -> Self {
         self.fields
-            .insert(field.into(), FieldValue::Push(Box::new(value.into())));
+            .insert(field.into(), FieldOp::Push(value.into()));
         self
     }
 
     /// Connect to an existing related record by ID.
     pub fn connect(mut self, relation: impl Into<String>, id: impl Into<FieldValue>) -> Self {
         self.fields
-            .insert(relation.into(), FieldValue::Connect(ConnectData::id(id)));
+            .insert(relation.into(), FieldOp::Connect(ConnectData::id(id)));
         self
     }
 
@@ -308,26 +274,26 @@ impl DataBuilder {
     ) -> Self {
         self.fields.insert(
             relation.into(),
-            FieldValue::Connect(ConnectData::by(field, value)),
+            FieldOp::Connect(ConnectData::by(field, value)),
         );
         self
     }
 
     /// Disconnect from a related record.
     pub fn disconnect(mut self, relation: impl Into<String>) -> Self {
-        self.fields.insert(relation.into(), FieldValue::Disconnect);
+        self.fields.insert(relation.into(), FieldOp::Disconnect);
         self
     }
 
     /// Create a nested record.
     pub fn create_nested(mut self, relation: impl Into<String>, data: DataBuilder) -> Self {
         self.fields
-            .insert(relation.into(), FieldValue::Nested(Box::new(data)));
+            .insert(relation.into(), FieldOp::Nested(Box::new(data)));
         self
     }
 
     /// Get the fields map.
-    pub fn into_fields(self) -> HashMap<String, FieldValue> {
+    pub fn into_fields(self) -> HashMap<String, FieldOp> {
         self.fields
     }
 
@@ -337,7 +303,7 @@ impl DataBuilder {
     }
 
     /// Get a field value.
-    pub fn get(&self, field: &str) -> Option<&FieldValue> {
+    pub fn get(&self, field: &str) -> Option<&FieldOp> {
         self.fields.get(field)
     }
 
@@ -364,7 +330,7 @@ impl IntoData for DataBuilder {
     }
 }
 
-impl IntoData for HashMap<String, FieldValue> {
+impl IntoData for HashMap<String, FieldOp> {
     fn into_data(self) -> DataBuilder {
         DataBuilder { fields: self }
     }
@@ -376,7 +342,7 @@ impl IntoData for serde_json::Value {
             serde_json::Value::Object(map) => {
                 let fields = map
                     .into_iter()
-                    .map(|(k, v)| (k, json_to_field_value(v)))
+                    .map(|(k, v)| (k, FieldOp::Set(json_to_value(v))))
                     .collect();
                 DataBuilder { fields }
             }
@@ -385,24 +351,22 @@ impl IntoData for serde_json::Value {
     }
 }
 
-fn json_to_field_value(value: serde_json::Value) -> FieldValue {
+fn json_to_value(value: serde_json::Value) -> Value {
     match value {
-        serde_json::Value::Null => FieldValue::Null,
-        serde_json::Value::Bool(b) => FieldValue::Bool(b),
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Bool(b),
         serde_json::Value::Number(n) => {
             if let Some(i) = n.as_i64() {
-                FieldValue::Int(i)
+                Value::Int(i)
             } else if let Some(f) = n.as_f64() {
-                FieldValue::Float(f)
+                Value::Float(f)
             } else {
-                FieldValue::Json(serde_json::Value::Number(n))
+                Value::Json(serde_json::Value::Number(n))
             }
         }
-        serde_json::Value::String(s) => FieldValue::String(s),
-        serde_json::Value::Array(arr) => {
-            FieldValue::Array(arr.into_iter().map(json_to_field_value).collect())
-        }
-        serde_json::Value::Object(_) => FieldValue::Json(value),
+        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::Array(arr) => Value::List(arr.into_iter().map(json_to_value).collect()),
+        serde_json::Value::Object(_) => Value::Json(value),
     }
 }
 
@@ -455,10 +419,10 @@ macro_rules! data {
 #[macro_export]
 macro_rules! connect {
     (id: $id:expr) => {
-        $crate::data::FieldValue::Connect($crate::data::ConnectData::id($id))
+        $crate::data::FieldOp::Connect($crate::data::ConnectData::id($id))
     };
     ($field:ident : $value:expr) => {
-        $crate::data::FieldValue::Connect($crate::data::ConnectData::by(stringify!($field), $value))
+        $crate::data::FieldOp::Connect($crate::data::ConnectData::by(stringify!($field), $value))
     };
 }
 
@@ -466,7 +430,7 @@ macro_rules! connect {
 #[macro_export]
 macro_rules! increment {
     ($value:expr) => {
-        $crate::data::FieldValue::Increment($value)
+        $crate::data::FieldOp::Increment($value)
     };
 }
 
@@ -474,7 +438,7 @@ macro_rules! increment {
 #[macro_export]
 macro_rules! decrement {
     ($value:expr) => {
-        $crate::data::FieldValue::Decrement($value)
+        $crate::data::FieldOp::Decrement($value)
     };
 }
 
@@ -586,8 +550,11 @@ mod tests {
             .set_null("deleted_at")
             .set_default("created_at");
 
-        assert!(matches!(data.get("deleted_at"), Some(FieldValue::Null)));
-        assert!(matches!(data.get("created_at"), Some(FieldValue::Default)));
+        assert!(matches!(
+            data.get("deleted_at"),
+            Some(FieldOp::Set(FieldValue::Null))
+        ));
+        assert!(matches!(data.get("created_at"), Some(FieldOp::Default)));
     }
 
     #[test]
@@ -598,8 +565,8 @@ mod tests {
             .multiply("price", 1.1)
             .divide("score", 2.0);
 
-        assert!(matches!(data.get("views"), Some(FieldValue::Increment(1))));
-        assert!(matches!(data.get("stock"), Some(FieldValue::Decrement(5))));
+        assert!(matches!(data.get("views"), Some(FieldOp::Increment(1))));
+        assert!(matches!(data.get("stock"), Some(FieldOp::Decrement(5))));
     }
 
     #[test]
@@ -608,8 +575,8 @@ mod tests {
             .connect("author", 1)
             .connect_by("category", "slug", "tech");
 
-        assert!(matches!(data.get("author"), Some(FieldValue::Connect(_))));
-        assert!(matches!(data.get("category"), Some(FieldValue::Connect(_))));
+        assert!(matches!(data.get("author"), Some(FieldOp::Connect(_))));
+        assert!(matches!(data.get("category"), Some(FieldOp::Connect(_))));
     }
 
     #[test]
@@ -621,7 +588,9 @@ mod tests {
         };
 
         assert_eq!(data.len(), 3);
-        assert!(matches!(data.get("name"), Some(FieldValue::String(s)) if s == "Bob"));
+        assert!(
+            matches!(data.get("name"), Some(FieldOp::Set(FieldValue::String(s))) if s == "Bob")
+        );
     }
 
     #[test]
@@ -671,4 +640,31 @@ mod tests {
         let by_email = ConnectData::by("email", "bob@example.com");
         assert_eq!(by_email.field, "email");
     }
+
+    #[test]
+    fn test_field_op_to_value() {
+        let bytes = FieldOp::from(&[1u8, 2][..]);
+        assert_eq!(bytes.to_value(), Some(Value::Bytes(vec![1, 2])));
+
+        let tags = FieldOp::from(vec!["a", "u"]);
+        assert_eq!(
+            tags.to_value(),
+            Some(Value::List(vec![
+                Value::String("a".into()),
+                Value::String("u".into())
+            ]))
+        );
+        assert_eq!(FieldOp::Increment(1).to_value(), None);
+        assert_eq!(FieldOp::Default.to_filter_value(), None);
+    }
+
+    #[test]
+    fn test_json_to_data_lists() {
+        let data = serde_json::json!({ "tags": ["a", 1] }).into_data();
+        assert!(matches!(
+            data.get("tags"),
+            Some(FieldOp::Set(FieldValue::List(tags)))
+                if *tags == [Value::String("a".into()), Value::Int(1)]
+        ));
+    }
 }
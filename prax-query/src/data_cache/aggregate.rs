@@ -0,0 +1,532 @@
+//! Windowed result cache for repeated aggregate queries.
+//!
+//! Dashboards issue the same `GROUP BY` over a sliding time range every few
+//! seconds. [`AggregateCache`] splits the range into fixed-width time buckets
+//! and caches each bucket's result under `(query fingerprint, filter bucket,
+//! time bucket)`. Buckets that have closed are kept for a long TTL while the
+//! open bucket uses a short one, so a dashboard refresh only recomputes the most
+//! recent bucket and any bucket that just scrolled into view.
+//!
+//! The query must be decomposable by time: the result for a window is the
+//! concatenation of the results for its buckets, as with a time-series
+//! `GROUP BY date_trunc('minute', created_at), region`. Windows are widened to
+//! bucket boundaries.
+//!
+//! Results are stored column-major in a [`ColumnarResult`], which keeps
+//! repeated column names out of every row.
+//!
+//! ```rust,ignore
+//! use prax_query::data_cache::{AggregateCache, AggregateCacheConfig, AggregateQuery, TimeWindow};
+//!
+//! let cache = AggregateCache::new(manager).with_config(
+//!     AggregateCacheConfig::default()
+//!         .bucket_width(Duration::from_secs(60))
+//!         .open_ttl(Duration::from_secs(5)),
+//! );
+//!
+//! let query = AggregateQuery::new("Order", sql, &params);
+//! let result = cache
+//!     .get_or_compute(&query, TimeWindow::last(Duration::from_secs(3600)), |bucket| {
+//!         run_aggregate(sql, &params, bucket.start, bucket.end)
+//!     })
+//!     .await?;
+//! ```
+
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tracing::debug;
+
+use super::backend::CacheBackend;
+use super::key::{CacheKey, KeyPattern, compute_hash};
+use super::options::CacheOptions;
+use super::{CacheManager, CacheResult};
+use crate::filter::FilterValue;
+use crate::operations::{AggregateOperation, GroupByOperation};
+use crate::traits::{Model, QueryEngine};
+
+/// A query result stored column by column.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnarResult {
+    columns: Vec<String>,
+    values: Vec<Vec<JsonValue>>,
+}
+
+impl ColumnarResult {
+    /// Create an empty result with the given columns.
+    pub fn new(columns: Vec<String>) -> Self {
+        let values = vec![Vec::new(); columns.len()];
+        Self { columns, values }
+    }
+
+    /// Build a result from rows of JSON objects.
+    pub fn from_rows(rows: &[JsonValue]) -> Self {
+        let mut result = Self::default();
+        for (index, row) in rows.iter().enumerate() {
+            let Some(object) = row.as_object() else {
+                continue;
+            };
+            for (name, value) in object {
+                let column = result.column_index(name, index);
+                result.values[column].push(value.clone());
+            }
+            result.pad(index + 1);
+        }
+        result
+    }
+
+    /// Column names.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Values of a column.
+    pub fn column(&self, name: &str) -> Option<&[JsonValue]> {
+        self.columns
+            .iter()
+            .position(|c| c == name)
+            .map(|i| self.values[i].as_slice())
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.values.first().map_or(0, Vec::len)
+    }
+
+    /// Check if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append the rows of another result, matching columns by name.
+    pub fn append(&mut self, other: ColumnarResult) {
+        let rows = self.len();
+        let added = other.len();
+        for (name, values) in other.columns.into_iter().zip(other.values) {
+            let column = self.column_index(&name, rows);
+            self.values[column].extend(values);
+        }
+        self.pad(rows + added);
+    }
+
+    /// Convert back to rows of JSON objects.
+    pub fn to_rows(&self) -> Vec<JsonValue> {
+        (0..self.len())
+            .map(|row| {
+                JsonValue::Object(
+                    self.columns
+                        .iter()
+                        .zip(&self.values)
+                        .map(|(name, values)| (name.clone(), values[row].clone()))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Find or add a column, back-filling `rows` nulls for a new one.
+    fn column_index(&mut self, name: &str, rows: usize) -> usize {
+        match self.columns.iter().position(|c| c == name) {
+            Some(index) => index,
+            None => {
+                self.columns.push(name.to_string());
+                self.values.push(vec![JsonValue::Null; rows]);
+                self.columns.len() - 1
+            }
+        }
+    }
+
+    /// Fill missing trailing values with nulls.
+    fn pad(&mut self, rows: usize) {
+        for values in &mut self.values {
+            values.resize(rows, JsonValue::Null);
+        }
+    }
+}
+
+/// Identity of a cached aggregate: the query shape and its filter values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateQuery {
+    entity: String,
+    fingerprint: u64,
+    filter_bucket: u64,
+}
+
+impl AggregateQuery {
+    /// Identify an aggregate by its SQL and parameters.
+    ///
+    /// Whitespace differences in the SQL do not change the fingerprint.
+    pub fn new(entity: impl Into<String>, sql: &str, params: &[FilterValue]) -> Self {
+        let normalized = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+        Self {
+            entity: entity.into(),
+            fingerprint: compute_hash(&normalized),
+            filter_bucket: compute_hash(&serde_json::to_string(params).unwrap_or_default()),
+        }
+    }
+
+    /// Identify an aggregate operation.
+    pub fn aggregate<M: Model, E: QueryEngine>(operation: &AggregateOperation<M, E>) -> Self {
+        let (sql, params) = operation.build_sql();
+        Self::new(M::MODEL_NAME, &sql, &params)
+    }
+
+    /// Identify a group by operation.
+    pub fn group_by<M: Model, E: QueryEngine>(operation: &GroupByOperation<M, E>) -> Self {
+        let (sql, params) = operation.build_sql();
+        Self::new(M::MODEL_NAME, &sql, &params)
+    }
+
+    /// Entity the aggregate reads.
+    pub fn entity(&self) -> &str {
+        &self.entity
+    }
+
+    /// Hash of the normalized SQL.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Hash of the filter values.
+    pub fn filter_bucket(&self) -> u64 {
+        self.filter_bucket
+    }
+}
+
+/// A half-open time range `[start, end)` in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeWindow {
+    /// Inclusive start.
+    pub start: i64,
+    /// Exclusive end.
+    pub end: i64,
+}
+
+impl TimeWindow {
+    /// Create a window.
+    pub fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    /// The window ending now.
+    pub fn last(duration: Duration) -> Self {
+        let now = unix_now();
+        Self::new(now - duration.as_secs() as i64, now)
+    }
+
+    /// Split into buckets of `width` seconds aligned to the Unix epoch.
+    pub fn buckets(&self, width: i64) -> Vec<TimeWindow> {
+        let width = width.max(1);
+        let mut start = self.start.div_euclid(width) * width;
+        let mut buckets = Vec::new();
+        while start < self.end {
+            buckets.push(TimeWindow::new(start, start + width));
+            start += width;
+        }
+        buckets
+    }
+}
+
+/// Configuration for an [`AggregateCache`].
+#[derive(Debug, Clone)]
+pub struct AggregateCacheConfig {
+    /// Width of a time bucket.
+    pub bucket_width: Duration,
+    /// TTL of buckets that may still change.
+    pub open_ttl: Duration,
+    /// TTL of buckets that have closed.
+    pub closed_ttl: Duration,
+    /// How long after a bucket ends late writes may still land in it.
+    pub settle_time: Duration,
+    /// Windows with more buckets than this bypass the cache.
+    pub max_buckets: usize,
+}
+
+impl Default for AggregateCacheConfig {
+    fn default() -> Self {
+        Self {
+            bucket_width: Duration::from_secs(60),
+            open_ttl: Duration::from_secs(5),
+            closed_ttl: Duration::from_secs(3600),
+            settle_time: Duration::ZERO,
+            max_buckets: 1440,
+        }
+    }
+}
+
+impl AggregateCacheConfig {
+    /// Set the bucket width.
+    pub fn bucket_width(mut self, width: Duration) -> Self {
+        self.bucket_width = width;
+        self
+    }
+
+    /// Set the TTL of open buckets.
+    pub fn open_ttl(mut self, ttl: Duration) -> Self {
+        self.open_ttl = ttl;
+        self
+    }
+
+    /// Set the TTL of closed buckets.
+    pub fn closed_ttl(mut self, ttl: Duration) -> Self {
+        self.closed_ttl = ttl;
+        self
+    }
+
+    /// Set how long a bucket stays open after it ends.
+    pub fn settle_time(mut self, settle: Duration) -> Self {
+        self.settle_time = settle;
+        self
+    }
+
+    /// Set the maximum number of buckets per window.
+    pub fn max_buckets(mut self, max: usize) -> Self {
+        self.max_buckets = max;
+        self
+    }
+}
+
+/// Caches aggregate results per time bucket.
+///
+/// See the [module documentation](self) for an overview.
+pub struct AggregateCache<B: CacheBackend> {
+    manager: CacheManager<B>,
+    config: AggregateCacheConfig,
+}
+
+impl<B: CacheBackend> AggregateCache<B> {
+    /// Create an aggregate cache on top of a cache manager.
+    pub fn new(manager: CacheManager<B>) -> Self {
+        Self {
+            manager,
+            config: AggregateCacheConfig::default(),
+        }
+    }
+
+    /// Set the configuration.
+    pub fn with_config(mut self, config: AggregateCacheConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &AggregateCacheConfig {
+        &self.config
+    }
+
+    /// Get the underlying cache manager.
+    pub fn manager(&self) -> &CacheManager<B> {
+        &self.manager
+    }
+
+    /// Get a windowed aggregate, computing only the buckets not in the cache.
+    ///
+    /// `compute` receives each bucket to evaluate. Cache failures are treated
+    /// as misses; errors from `compute` are returned as-is.
+    pub async fn get_or_compute<F, Fut, E>(
+        &self,
+        query: &AggregateQuery,
+        window: TimeWindow,
+        compute: F,
+    ) -> Result<ColumnarResult, E>
+    where
+        F: FnMut(TimeWindow) -> Fut,
+        Fut: Future<Output = Result<ColumnarResult, E>>,
+    {
+        self.get_or_compute_at(query, window, unix_now(), compute)
+            .await
+    }
+
+    /// Like [`get_or_compute`](Self::get_or_compute), with an explicit current time.
+    pub async fn get_or_compute_at<F, Fut, E>(
+        &self,
+        query: &AggregateQuery,
+        window: TimeWindow,
+        now: i64,
+        mut compute: F,
+    ) -> Result<ColumnarResult, E>
+    where
+        F: FnMut(TimeWindow) -> Fut,
+        Fut: Future<Output = Result<ColumnarResult, E>>,
+    {
+        let buckets = window.buckets(self.config.bucket_width.as_secs() as i64);
+        if buckets.len() > self.config.max_buckets {
+            debug!(
+                buckets = buckets.len(),
+                max = self.config.max_buckets,
+                "Window too wide for the aggregate cache"
+            );
+            return compute(window).await;
+        }
+
+        let mut result = ColumnarResult::default();
+        let mut computed = 0;
+        for bucket in &buckets {
+            let key = self.key(query, bucket);
+            let cached = self
+                .manager
+                .get::<ColumnarResult>(&key)
+                .await
+                .ok()
+                .flatten();
+
+            let part = match cached {
+                Some(part) => part,
+                None => {
+                    let part = compute(*bucket).await?;
+                    let options = CacheOptions::ttl(self.ttl_for(bucket, now));
+                    // Best effort, like CacheManager::get_or_set
+                    let _ = self.manager.set(&key, &part, Some(&options)).await;
+                    computed += 1;
+                    part
+                }
+            };
+            result.append(part);
+        }
+
+        debug!(
+            fingerprint = query.fingerprint,
+            buckets = buckets.len(),
+            computed,
+            "Served windowed aggregate"
+        );
+        Ok(result)
+    }
+
+    /// Drop every cached bucket of a query.
+    pub async fn invalidate(&self, query: &AggregateQuery) -> CacheResult<u64> {
+        let pattern = KeyPattern::new(format!(
+            "prax:{}:agg:{:x}:*",
+            query.entity, query.fingerprint
+        ));
+        self.manager.invalidate_pattern(&pattern).await
+    }
+
+    fn key(&self, query: &AggregateQuery, bucket: &TimeWindow) -> CacheKey {
+        CacheKey::new(
+            query.entity.as_str(),
+            format!(
+                "agg:{:x}:{:x}:{}-{}",
+                query.fingerprint, query.filter_bucket, bucket.start, bucket.end
+            ),
+        )
+    }
+
+    /// Closed buckets live long; buckets that may still change expire quickly.
+    fn ttl_for(&self, bucket: &TimeWindow, now: i64) -> Duration {
+        if bucket.end + self.config.settle_time.as_secs() as i64 <= now {
+            self.config.closed_ttl
+        } else {
+            self.config.open_ttl
+        }
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_cache::{MemoryCache, MemoryCacheConfig};
+    use serde_json::json;
+    use std::convert::Infallible;
+
+    fn bucket_result(bucket: TimeWindow) -> ColumnarResult {
+        ColumnarResult::from_rows(&[json!({ "minute": bucket.start, "count": 1 })])
+    }
+
+    #[test]
+    fn test_columnar_result() {
+        let mut result = ColumnarResult::from_rows(&[
+            json!({ "region": "eu", "total": 10 }),
+            json!({ "region": "us" }),
+        ]);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.column("total"), Some(&[json!(10), json!(null)][..]));
+
+        result.append(ColumnarResult::from_rows(&[
+            json!({ "region": "apac", "count": 3 }),
+        ]));
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.columns(), ["region", "total", "count"]);
+        assert_eq!(
+            result.to_rows()[2],
+            json!({ "region": "apac", "total": null, "count": 3 })
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_and_buckets() {
+        let a = AggregateQuery::new("Order", "SELECT  COUNT(*)\nFROM orders", &[]);
+        let b = AggregateQuery::new("Order", "SELECT COUNT(*) FROM orders", &[]);
+        let c = AggregateQuery::new("Order", "SELECT COUNT(*) FROM orders", &[1.into()]);
+        assert_eq!(a, b);
+        assert_eq!(a.fingerprint(), c.fingerprint());
+        assert_ne!(a.filter_bucket(), c.filter_bucket());
+
+        let buckets = TimeWindow::new(90, 200).buckets(60);
+        assert_eq!(
+            buckets,
+            vec![
+                TimeWindow::new(60, 120),
+                TimeWindow::new(120, 180),
+                TimeWindow::new(180, 240)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_only_open_bucket_recomputed() {
+        let manager = CacheManager::new(MemoryCache::new(MemoryCacheConfig::default()));
+        let cache = AggregateCache::new(manager).with_config(
+            AggregateCacheConfig::default()
+                .bucket_width(Duration::from_secs(60))
+                .open_ttl(Duration::ZERO),
+        );
+        let query = AggregateQuery::new("Order", "SELECT COUNT(*) FROM orders", &[]);
+        let window = TimeWindow::new(0, 180);
+        let now = 150;
+
+        let mut computed = Vec::new();
+        let result = cache
+            .get_or_compute_at(&query, window, now, |bucket| {
+                computed.push(bucket.start);
+                async move { Ok::<_, Infallible>(bucket_result(bucket)) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(computed, vec![0, 60, 120]);
+
+        let mut computed = Vec::new();
+        let result = cache
+            .get_or_compute_at(&query, window, now, |bucket| {
+                computed.push(bucket.start);
+                async move { Ok::<_, Infallible>(bucket_result(bucket)) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            result.column("minute"),
+            Some(&[json!(0), json!(60), json!(120)][..])
+        );
+        assert_eq!(computed, vec![120]);
+
+        cache.invalidate(&query).await.unwrap();
+        let mut computed = 0;
+        cache
+            .get_or_compute_at(&query, window, now, |bucket| {
+                computed += 1;
+                async move { Ok::<_, Infallible>(bucket_result(bucket)) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(computed, 3);
+    }
+}
@@ -161,6 +161,34 @@ impl InvalidationEvent {
     }
 }
 
+impl From<crate::live::ChangeEvent> for InvalidationEvent {
+    /// Invalidate the written table, and its record when the key is a
+    /// single column, e.g. for events received through `LISTEN`.
+    fn from(change: crate::live::ChangeEvent) -> Self {
+        use crate::live::ChangeOp;
+
+        let event_type = match change.op {
+            ChangeOp::Insert => InvalidationEventType::Insert,
+            ChangeOp::Update => InvalidationEventType::Update,
+            ChangeOp::Delete => InvalidationEventType::Delete,
+        };
+        let mut event = Self::new(event_type, change.table);
+
+        let id = change
+            .key
+            .as_ref()
+            .and_then(|key| key.as_object())
+            .filter(|key| key.len() == 1)
+            .and_then(|key| key.values().next());
+        match id {
+            Some(serde_json::Value::String(id)) => event.record_id = Some(id.clone()),
+            Some(serde_json::Value::Null) | None => {}
+            Some(id) => event.record_id = Some(id.to_string()),
+        }
+        event
+    }
+}
+
 /// Type of invalidation event.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InvalidationEventType {
@@ -294,6 +322,22 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_event_from_change() {
+        use crate::live::{ChangeEvent, ChangeOp};
+
+        let change =
+            ChangeEvent::new("users", ChangeOp::Delete).with_key(serde_json::json!({"id": 7}));
+        let event = InvalidationEvent::from(change);
+        assert_eq!(event.event_type, InvalidationEventType::Delete);
+        assert_eq!(event.entity, "users");
+        assert_eq!(event.record_id.as_deref(), Some("7"));
+
+        let event = InvalidationEvent::from(ChangeEvent::new("users", ChangeOp::Insert));
+        assert_eq!(event.record_id, None);
+        assert_eq!(event.all_tags(), [EntityTag::entity("users")]);
+    }
+
     #[test]
     fn test_entity_tag() {
         let tag = EntityTag::entity("User");
@@ -9,6 +9,7 @@
 //! - **Tiered caching** combining L1 (memory) and L2 (Redis) for optimal performance
 //! - **Automatic invalidation** based on TTL, entity changes, or custom patterns
 //! - **Cache-aside pattern** with transparent integration into queries
+//! - **Windowed aggregate caching** for dashboards that repeat the same `GROUP BY`
 //!
 //! # Architecture
 //!
@@ -86,6 +87,7 @@
 //! | Redis | 1-5ms | Large | Multi-instance | Shared state, large datasets |
 //! | Tiered | < 1ms (L1 hit) | Both | Multi-instance | Production systems |
 
+mod aggregate;
 mod backend;
 mod invalidation;
 mod key;
@@ -95,6 +97,9 @@ mod redis;
 mod stats;
 mod tiered;
 
+pub use aggregate::{
+    AggregateCache, AggregateCacheConfig, AggregateQuery, ColumnarResult, TimeWindow,
+};
 pub use backend::{CacheBackend, CacheEntry, CacheError, CacheResult};
 pub use invalidation::{EntityTag, InvalidationEvent, InvalidationStrategy};
 pub use key::{CacheKey, CacheKeyBuilder, KeyPattern};
@@ -248,6 +253,19 @@ impl<B: CacheBackend> CacheManager<B> {
         self.backend.invalidate_tags(tags).await
     }
 
+    /// Invalidate the entries an event affects: its record, or the whole
+    /// entity when it names no record, and any tags it carries.
+    pub async fn invalidate(&self, event: &InvalidationEvent) -> CacheResult<u64> {
+        let mut invalidated = match &event.record_id {
+            Some(id) => self.invalidate_record(&event.entity, id).await?,
+            None => self.invalidate_entity(&event.entity).await?,
+        };
+        if !event.tags.is_empty() {
+            invalidated += self.invalidate_tags(&event.tags).await?;
+        }
+        Ok(invalidated)
+    }
+
     /// Clear all entries from the cache.
     pub async fn clear(&self) -> CacheResult<()> {
         self.backend.clear().await
@@ -374,6 +392,26 @@ mod tests {
         assert_eq!(value, "computed value");
         assert_eq!(call_count, 1); // Not incremented
     }
+
+    #[tokio::test]
+    async fn test_invalidate_event() {
+        let cache = CacheManager::new(MemoryCache::new(MemoryCacheConfig::default()));
+        let alice = CacheKey::entity_record("users", 1);
+        let bob = CacheKey::entity_record("users", 2);
+        cache.set(&alice, &"alice", None).await.unwrap();
+        cache.set(&bob, &"bob", None).await.unwrap();
+
+        let event = InvalidationEvent::update("users").with_record(1);
+        assert_eq!(cache.invalidate(&event).await.unwrap(), 1);
+        assert!(!cache.exists(&alice).await.unwrap());
+        assert!(cache.exists(&bob).await.unwrap());
+
+        cache
+            .invalidate(&InvalidationEvent::insert("users"))
+            .await
+            .unwrap();
+        assert!(!cache.exists(&bob).await.unwrap());
+    }
 }
 
 
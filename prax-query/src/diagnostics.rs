@@ -0,0 +1,1146 @@
+//! Server-side query insights (`diagnostics` feature).
+//!
+//! [`QueryStatsMiddleware`] keeps client-side statistics per statement
+//! fingerprint: calls, errors and latency as seen by the application,
+//! including pool waits and network time. The database keeps its own view in
+//! `pg_stat_statements` (PostgreSQL) or
+//! `performance_schema.events_statements_summary_by_digest` (MySQL).
+//! [`correlate`] joins the two on the fingerprint, so a slow query can be
+//! traced to its model and the gap between client and server latency shows
+//! where the time goes.
+//!
+//! ```rust,ignore
+//! use prax_query::diagnostics::{
+//!     QueryStatsMiddleware, ServerStatement, ServerStatsSource, correlate,
+//! };
+//!
+//! let stats = QueryStatsMiddleware::new();
+//! let client = client.with_middleware(stats.clone());
+//!
+//! // Later, e.g. from an admin endpoint or on shutdown
+//! stats.write_snapshot(".prax-query-stats.json")?;
+//!
+//! // Or correlate in-process
+//! let sql = ServerStatsSource::PgStatStatements.top_queries_sql(20);
+//! let server = ServerStatement::from_rows(engine.query_json(&sql).await?)?;
+//! for insight in correlate(&stats.snapshot(), &server) {
+//!     println!("{} {:?}", insight.query, insight.client_overhead_ms());
+//! }
+//! ```
+//!
+//! `prax db top-queries` shows a snapshot file, and with `--server` the
+//! correlated server statistics. [`FilterShape`] extracts the columns a
+//! statement filters and sorts on, which `prax db advise-indexes` turns into
+//! index suggestions.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{QueryError, QueryResult};
+use crate::middleware::{
+    BoxFuture, Middleware, MiddlewareResult, Next, QueryContext, QueryResponse, redact_statement,
+};
+
+/// Default number of distinct fingerprints a [`QueryStatsMiddleware`] tracks.
+pub const DEFAULT_MAX_STATEMENTS: usize = 5_000;
+
+/// Normalize a statement so that the client's SQL and the server's
+/// normalized text agree.
+///
+/// Literals and placeholders become `?`, value lists collapse to a single
+/// `?`, identifier quotes and comments such as [query tags](crate::tags)
+/// are dropped and the text is lowercased with whitespace removed around
+/// punctuation.
+///
+/// ```rust
+/// use prax_query::diagnostics::normalize_statement;
+///
+/// assert_eq!(
+///     normalize_statement(r#"SELECT * FROM "users" WHERE "id" IN ($1, $2, $3)"#),
+///     normalize_statement("SELECT * FROM `users` WHERE `id` IN (...)"),
+/// );
+/// ```
+pub fn normalize_statement(sql: &str) -> String {
+    let redacted = redact_statement(sql);
+    let mut out = String::with_capacity(redacted.len());
+    let mut chars = redacted.chars().peekable();
+    let mut space = false;
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                space = true;
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for inner in chars.by_ref() {
+                    if prev == '*' && inner == '/' {
+                        break;
+                    }
+                    prev = inner;
+                }
+                space = true;
+                continue;
+            }
+            '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let mut ident = String::new();
+                for inner in chars.by_ref() {
+                    if inner == close {
+                        break;
+                    }
+                    ident.extend(inner.to_lowercase());
+                }
+                ident
+            }
+            '$' if chars.peek().is_some_and(|n| n.is_ascii_digit()) => {
+                while chars.peek().is_some_and(|n| n.is_ascii_digit()) {
+                    chars.next();
+                }
+                "?".to_string()
+            }
+            '@' if chars.peek().is_some_and(|n| *n == 'P' || *n == 'p') => {
+                chars.next();
+                while chars.peek().is_some_and(|n| n.is_ascii_digit()) {
+                    chars.next();
+                }
+                "?".to_string()
+            }
+            '.' if chars.peek() == Some(&'.') => {
+                while chars.peek() == Some(&'.') {
+                    chars.next();
+                }
+                "?".to_string()
+            }
+            c => c.to_lowercase().collect(),
+        };
+
+        if space
+            && !out.is_empty()
+            && !matches!(out.chars().last(), Some('(' | ',' | '.'))
+            && !matches!(token.chars().next(), Some('(' | ')' | ',' | '.'))
+        {
+            out.push(' ');
+        }
+        space = false;
+        out.push_str(&token);
+    }
+
+    while out.ends_with(';') {
+        out.pop();
+    }
+    // `(?,?,?)` and `(?),(?)` vary with the number of values
+    for (list, single) in [("?,?", "?"), ("(?),(?)", "(?)")] {
+        while out.contains(list) {
+            out = out.replace(list, single);
+        }
+    }
+    out
+}
+
+/// Stable identifier of a statement's [normalized](normalize_statement) form.
+pub fn fingerprint(sql: &str) -> String {
+    hash_normalized(&normalize_statement(sql))
+}
+
+fn hash_normalized(normalized: &str) -> String {
+    // FNV-1a, stable across processes unlike `DefaultHasher`
+    let hash = normalized
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+/// Client-side statistics of one statement fingerprint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatementStats {
+    /// See [`fingerprint`].
+    pub fingerprint: String,
+    /// Normalized statement text.
+    pub query: String,
+    /// Model the statement was issued for, if known.
+    pub model: Option<String>,
+    /// Executions.
+    pub calls: u64,
+    /// Executions that failed.
+    pub errors: u64,
+    /// Total time in milliseconds.
+    pub total_ms: f64,
+    /// Mean time in milliseconds.
+    pub mean_ms: f64,
+    /// Slowest execution in milliseconds.
+    pub max_ms: f64,
+}
+
+impl StatementStats {
+    /// Read statistics written by [`QueryStatsMiddleware::write_snapshot`].
+    pub fn read_snapshot(path: impl AsRef<Path>) -> std::io::Result<Vec<Self>> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    query: String,
+    model: Option<String>,
+    calls: u64,
+    errors: u64,
+    total_us: u64,
+    max_us: u64,
+}
+
+#[derive(Debug)]
+struct StatsState {
+    max_statements: usize,
+    entries: RwLock<HashMap<String, Entry>>,
+}
+
+/// Middleware that collects [`StatementStats`] per fingerprint.
+///
+/// Clones share their statistics. Once `max_statements` fingerprints are
+/// tracked, new ones are ignored until [`reset`](Self::reset).
+#[derive(Debug, Clone)]
+pub struct QueryStatsMiddleware {
+    state: Arc<StatsState>,
+}
+
+impl QueryStatsMiddleware {
+    /// Track up to [`DEFAULT_MAX_STATEMENTS`] fingerprints.
+    pub fn new() -> Self {
+        Self::with_max_statements(DEFAULT_MAX_STATEMENTS)
+    }
+
+    /// Track up to `max_statements` fingerprints.
+    pub fn with_max_statements(max_statements: usize) -> Self {
+        Self {
+            state: Arc::new(StatsState {
+                max_statements,
+                entries: RwLock::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Record one execution of `sql`.
+    pub fn record(&self, sql: &str, model: Option<&str>, duration_us: u64, success: bool) {
+        let query = normalize_statement(sql);
+        let key = hash_normalized(&query);
+        let mut entries = self.state.entries.write().unwrap();
+        if !entries.contains_key(&key) && entries.len() >= self.state.max_statements {
+            return;
+        }
+
+        let entry = entries.entry(key).or_insert_with(|| Entry {
+            query,
+            model: None,
+            calls: 0,
+            errors: 0,
+            total_us: 0,
+            max_us: 0,
+        });
+        if entry.model.is_none() {
+            entry.model = model.map(str::to_string);
+        }
+        entry.calls += 1;
+        if !success {
+            entry.errors += 1;
+        }
+        entry.total_us += duration_us;
+        entry.max_us = entry.max_us.max(duration_us);
+    }
+
+    /// Current statistics, by total time descending.
+    pub fn snapshot(&self) -> Vec<StatementStats> {
+        let entries = self.state.entries.read().unwrap();
+        let mut stats: Vec<StatementStats> = entries
+            .iter()
+            .map(|(fingerprint, entry)| {
+                let total_ms = entry.total_us as f64 / 1000.0;
+                StatementStats {
+                    fingerprint: fingerprint.clone(),
+                    query: entry.query.clone(),
+                    model: entry.model.clone(),
+                    calls: entry.calls,
+                    errors: entry.errors,
+                    total_ms,
+                    mean_ms: total_ms / entry.calls.max(1) as f64,
+                    max_ms: entry.max_us as f64 / 1000.0,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        stats
+    }
+
+    /// Write the [`snapshot`](Self::snapshot) as JSON, for
+    /// `prax db top-queries`.
+    pub fn write_snapshot(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.snapshot())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+
+    /// Forget all statistics.
+    pub fn reset(&self) {
+        self.state.entries.write().unwrap().clear();
+    }
+}
+
+impl Default for QueryStatsMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Middleware for QueryStatsMiddleware {
+    fn handle<'a>(
+        &'a self,
+        ctx: QueryContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        Box::pin(async move {
+            let sql = ctx.sql().to_string();
+            let model = ctx.metadata().model.clone();
+            let start = Instant::now();
+
+            let result = next.run(ctx).await;
+
+            // Cached responses never reach the server
+            let from_cache = result.as_ref().is_ok_and(|response| response.from_cache);
+            if !from_cache {
+                self.record(
+                    &sql,
+                    model.as_deref(),
+                    start.elapsed().as_micros() as u64,
+                    result.is_ok(),
+                );
+            }
+            result
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "QueryStatsMiddleware"
+    }
+}
+
+/// Where a database keeps its statement statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerStatsSource {
+    /// The `pg_stat_statements` extension (PostgreSQL).
+    PgStatStatements,
+    /// `performance_schema.events_statements_summary_by_digest` (MySQL).
+    PerformanceSchema,
+}
+
+impl ServerStatsSource {
+    /// Name of the source.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PgStatStatements => "pg_stat_statements",
+            Self::PerformanceSchema => "performance_schema",
+        }
+    }
+
+    /// Query returning the current database's top `limit` statements by
+    /// total time, one JSON object per row in the shape of
+    /// [`ServerStatement`]. Reads of the statistics themselves are left out.
+    pub fn top_queries_sql(&self, limit: usize) -> String {
+        match self {
+            // total_time was renamed to total_exec_time in PostgreSQL 13
+            Self::PgStatStatements => format!(
+                "SELECT json_build_object('query', s.query, 'calls', s.calls, \
+                 'total_ms', t.total_ms, 'mean_ms', t.total_ms / greatest(s.calls, 1), \
+                 'rows', s.rows) \
+                 FROM pg_stat_statements s \
+                 CROSS JOIN LATERAL (SELECT coalesce((to_jsonb(s) ->> 'total_exec_time')::float8, \
+                 (to_jsonb(s) ->> 'total_time')::float8, 0) AS total_ms) t \
+                 WHERE s.dbid = (SELECT oid FROM pg_database WHERE datname = current_database()) \
+                 AND s.query NOT LIKE '%pg_stat_statements%' \
+                 ORDER BY t.total_ms DESC LIMIT {}",
+                limit
+            ),
+            // Timers are in picoseconds
+            Self::PerformanceSchema => format!(
+                "SELECT JSON_OBJECT('query', DIGEST_TEXT, 'calls', COUNT_STAR, \
+                 'total_ms', SUM_TIMER_WAIT / 1000000000, 'mean_ms', AVG_TIMER_WAIT / 1000000000, \
+                 'rows', SUM_ROWS_SENT + SUM_ROWS_AFFECTED) \
+                 FROM performance_schema.events_statements_summary_by_digest \
+                 WHERE SCHEMA_NAME = DATABASE() AND DIGEST_TEXT IS NOT NULL \
+                 AND DIGEST_TEXT NOT LIKE '%events_statements_summary_by_digest%' \
+                 ORDER BY SUM_TIMER_WAIT DESC LIMIT {}",
+                limit
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for ServerStatsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Server-side statistics of one normalized statement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerStatement {
+    /// Statement text as normalized by the server.
+    pub query: String,
+    /// Executions.
+    pub calls: u64,
+    /// Total execution time in milliseconds.
+    pub total_ms: f64,
+    /// Mean execution time in milliseconds.
+    pub mean_ms: f64,
+    /// Rows returned or affected.
+    pub rows: u64,
+}
+
+impl ServerStatement {
+    /// Parse the rows of [`ServerStatsSource::top_queries_sql`].
+    pub fn from_rows(rows: Vec<serde_json::Value>) -> QueryResult<Vec<Self>> {
+        rows.into_iter()
+            .map(|row| {
+                serde_json::from_value(row).map_err(|e| {
+                    QueryError::serialization(format!("unexpected statement statistics: {}", e))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Client and server statistics of one fingerprint.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryInsight {
+    /// See [`fingerprint`].
+    pub fingerprint: String,
+    /// Normalized statement text.
+    pub query: String,
+    /// Model the statement was issued for, if known.
+    pub model: Option<String>,
+    /// Statistics collected by the application.
+    pub client: Option<StatementStats>,
+    /// Statistics collected by the database.
+    pub server: Option<ServerStatement>,
+}
+
+impl QueryInsight {
+    /// Mean client latency minus mean server execution time: pool waits,
+    /// network round trips and decoding.
+    pub fn client_overhead_ms(&self) -> Option<f64> {
+        match (&self.client, &self.server) {
+            (Some(client), Some(server)) => Some(client.mean_ms - server.mean_ms),
+            _ => None,
+        }
+    }
+}
+
+/// Join client and server statistics on their fingerprints.
+///
+/// Server statements come first in their order, followed by statements only
+/// the client saw. Server statements without a client match were issued by
+/// something other than this application, or normalized differently.
+pub fn correlate(client: &[StatementStats], server: &[ServerStatement]) -> Vec<QueryInsight> {
+    let mut by_fingerprint: HashMap<&str, &StatementStats> = client
+        .iter()
+        .map(|stats| (stats.fingerprint.as_str(), stats))
+        .collect();
+    let mut insights: Vec<QueryInsight> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for statement in server {
+        let id = fingerprint(&statement.query);
+        if let Some(&i) = positions.get(&id) {
+            // Statements the server kept apart but normalize alike
+            let merged = insights[i].server.as_mut().expect("server statement");
+            merged.calls += statement.calls;
+            merged.total_ms += statement.total_ms;
+            merged.rows += statement.rows;
+            merged.mean_ms = merged.total_ms / merged.calls.max(1) as f64;
+            continue;
+        }
+
+        let stats = by_fingerprint.remove(id.as_str());
+        positions.insert(id.clone(), insights.len());
+        insights.push(QueryInsight {
+            query: stats.map_or_else(
+                || normalize_statement(&statement.query),
+                |s| s.query.clone(),
+            ),
+            model: stats.and_then(|s| s.model.clone()),
+            fingerprint: id,
+            client: stats.cloned(),
+            server: Some(statement.clone()),
+        });
+    }
+
+    insights.extend(
+        client
+            .iter()
+            .filter(|stats| by_fingerprint.contains_key(stats.fingerprint.as_str()))
+            .map(|stats| QueryInsight {
+                fingerprint: stats.fingerprint.clone(),
+                query: stats.query.clone(),
+                model: stats.model.clone(),
+                client: Some(stats.clone()),
+                server: None,
+            }),
+    );
+    insights
+}
+
+/// A column in an `ORDER BY`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SortColumn {
+    /// Column name.
+    pub column: String,
+    /// Whether the column sorts descending.
+    pub descending: bool,
+}
+
+/// The columns a single-table statement filters and sorts on.
+///
+/// Parsed from the [normalized](normalize_statement) text, so literals and
+/// placeholders look alike. Statements with joins, subqueries, unions or
+/// `OR` have no shape.
+///
+/// ```rust
+/// use prax_query::diagnostics::FilterShape;
+///
+/// let shape = FilterShape::parse(
+///     r#"SELECT * FROM "posts" WHERE "author_id" = $1 AND "deleted_at" IS NULL ORDER BY "created_at" DESC LIMIT 10"#,
+/// )
+/// .unwrap();
+/// assert_eq!(shape.table, "posts");
+/// assert_eq!(shape.equality, ["author_id"]);
+/// assert_eq!(shape.constant, ["deleted_at is null"]);
+/// assert!(shape.sort[0].descending);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FilterShape {
+    /// Table name, without schema.
+    pub table: String,
+    /// Columns compared with `=` or `IN`.
+    pub equality: Vec<String>,
+    /// Columns compared with `<`, `>`, `BETWEEN` or `LIKE`.
+    pub range: Vec<String>,
+    /// `ORDER BY` columns.
+    pub sort: Vec<SortColumn>,
+    /// Predicates without parameters, such as `deleted_at is null`.
+    pub constant: Vec<String>,
+    /// Whether the statement has a `LIMIT`.
+    pub limited: bool,
+}
+
+impl FilterShape {
+    /// Shape of a `SELECT`, `UPDATE` or `DELETE` statement.
+    pub fn parse(sql: &str) -> Option<Self> {
+        const CLAUSES: [&str; 8] = [
+            " set ",
+            " where ",
+            " group by ",
+            " order by ",
+            " limit ",
+            " offset ",
+            " for ",
+            " returning ",
+        ];
+
+        let mut sql = normalize_statement(sql);
+        // Statements prepared in SQL are recorded with their `PREPARE`
+        if sql.starts_with("prepare ") {
+            sql = sql[sql.find(" as ")? + " as ".len()..].to_string();
+        }
+        if [" join ", "(select ", " union ", " exists("]
+            .iter()
+            .any(|marker| sql.contains(marker))
+        {
+            return None;
+        }
+        let body = if let Some(rest) = sql.strip_prefix("select ") {
+            &rest[rest.find(" from ")? + " from ".len()..]
+        } else if let Some(rest) = sql.strip_prefix("delete from ") {
+            rest
+        } else {
+            sql.strip_prefix("update ")?
+        };
+        let clause = |keyword: &str| {
+            let start = body.find(keyword)? + keyword.len();
+            let end = CLAUSES
+                .iter()
+                .filter_map(|c| body[start..].find(c).map(|i| start + i))
+                .min()
+                .unwrap_or(body.len());
+            Some(&body[start..end])
+        };
+
+        let head = &body[..CLAUSES
+            .iter()
+            .filter_map(|c| body.find(c))
+            .min()
+            .unwrap_or(body.len())];
+        let mut words = head.split(' ').filter(|w| *w != "as");
+        let table_ref = words.next()?;
+        let alias = words.next();
+        if table_ref.contains(',') || alias.is_some_and(|a| a.contains(',')) {
+            return None;
+        }
+        let table = table_ref.rsplit('.').next()?;
+        let column = |expr: &str| -> Option<String> {
+            let (qualifier, name) = match expr.rsplit_once('.') {
+                Some((qualifier, name)) => (Some(qualifier), name),
+                None => (None, expr),
+            };
+            let ours = qualifier.is_none_or(|q| q == table || q == table_ref || Some(q) == alias);
+            let plain = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            (ours && plain).then(|| name.to_string())
+        };
+
+        let mut shape = FilterShape {
+            table: table.to_string(),
+            limited: body.contains(" limit "),
+            ..Default::default()
+        };
+        if let Some(filter) = clause(" where ") {
+            if filter.contains(" or ") {
+                return None;
+            }
+            for predicate in filter
+                .replace(" between ? and ?", " between ?")
+                .split(" and ")
+            {
+                let words: Vec<&str> = predicate.split(' ').collect();
+                let Some(name) = words.first().copied().and_then(&column) else {
+                    continue;
+                };
+                match &words[1..] {
+                    ["=", "?"] | ["in(?)"] | ["=", "any(?)"] => {
+                        push_unique(&mut shape.equality, name)
+                    }
+                    ["=", "true" | "false"] | ["is", "null"] | ["is", "not", "null"] => {
+                        let predicate = format!("{} {}", name, words[1..].join(" "));
+                        push_unique(&mut shape.constant, predicate);
+                    }
+                    [">" | "<" | ">=" | "<=" | "between" | "like" | "ilike", "?"] => {
+                        push_unique(&mut shape.range, name)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if let Some(order) = clause(" order by ") {
+            for item in order.split(',') {
+                let mut words = item.split(' ');
+                let Some(name) = words.next().and_then(&column) else {
+                    break;
+                };
+                shape.sort.push(SortColumn {
+                    column: name,
+                    descending: words.next() == Some("desc"),
+                });
+            }
+        }
+        Some(shape)
+    }
+
+    /// Whether the statement filters or sorts on any column.
+    pub fn is_empty(&self) -> bool {
+        self.equality.is_empty() && self.range.is_empty() && self.sort.is_empty()
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+/// Tables with fewer dead or modified rows get no maintenance advice.
+pub const MIN_DEAD_ROWS: u64 = 1_000;
+
+/// Updates and deletes from which a table counts as write-heavy.
+pub const MIN_HEAVY_WRITES: u64 = 10_000;
+
+/// Live rows from which the default autovacuum scale factor lets too many
+/// dead rows pile up on a write-heavy table.
+pub const LARGE_TABLE_ROWS: u64 = 100_000;
+
+/// Vacuum scale factor recommended for large write-heavy tables.
+pub const RECOMMENDED_VACUUM_SCALE_FACTOR: f64 = 0.02;
+
+/// Analyze scale factor recommended for large write-heavy tables.
+pub const RECOMMENDED_ANALYZE_SCALE_FACTOR: f64 = 0.01;
+
+/// Vacuum and analyze statistics of one table, with its effective
+/// autovacuum settings (PostgreSQL).
+///
+/// Read with [`TableMaintenance::sql`], which returns one JSON object per
+/// table of the current schema:
+///
+/// ```rust,ignore
+/// use prax_query::diagnostics::TableMaintenance;
+///
+/// let tables = TableMaintenance::from_rows(engine.query_json(&TableMaintenance::sql()).await?)?;
+/// for table in &tables {
+///     for advice in table.advise() {
+///         println!("{}: {} ({})", table.table, advice.message, advice.recommendation);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableMaintenance {
+    /// Table name.
+    pub table: String,
+    /// Estimated live rows.
+    pub live_rows: u64,
+    /// Estimated dead rows not yet vacuumed.
+    pub dead_rows: u64,
+    /// Rows inserted since the statistics were reset.
+    pub inserts: u64,
+    /// Rows updated since the statistics were reset.
+    pub updates: u64,
+    /// Rows deleted since the statistics were reset.
+    pub deletes: u64,
+    /// Rows modified since the table was last analyzed.
+    pub modified_since_analyze: u64,
+    /// Size on disk, including TOAST.
+    pub size_bytes: u64,
+    /// Last manual or automatic vacuum.
+    pub last_vacuum: Option<String>,
+    /// Last manual or automatic analyze.
+    pub last_analyze: Option<String>,
+    /// Whether the autovacuum launcher runs on the server.
+    pub autovacuum: bool,
+    /// Whether autovacuum is enabled for the table.
+    pub autovacuum_enabled: bool,
+    /// Dead rows autovacuum waits for on top of the scale factor.
+    pub vacuum_threshold: u64,
+    /// Share of live rows that must be dead before autovacuum runs.
+    pub vacuum_scale_factor: f64,
+    /// Modified rows autoanalyze waits for on top of the scale factor.
+    pub analyze_threshold: u64,
+    /// Share of live rows that must be modified before autoanalyze runs.
+    pub analyze_scale_factor: f64,
+}
+
+/// Kind of a [`MaintenanceAdvice`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceIssue {
+    /// Autovacuum is off for a write-heavy table.
+    AutovacuumDisabled,
+    /// More rows are dead than autovacuum should have allowed.
+    VacuumBehind,
+    /// The scale factor lets too many dead rows pile up on a large
+    /// write-heavy table.
+    VacuumScaleFactor,
+    /// The planner statistics miss many modifications.
+    StaleStatistics,
+}
+
+/// A maintenance problem of a table and how to fix it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MaintenanceAdvice {
+    /// What was found.
+    pub issue: MaintenanceIssue,
+    /// Human-readable explanation.
+    pub message: String,
+    /// Statement or setting that fixes it.
+    pub recommendation: String,
+}
+
+/// A table-level storage parameter, or the server setting it overrides.
+fn reloption(name: &str, cast: &str) -> String {
+    format!(
+        "coalesce((SELECT option_value FROM pg_options_to_table(c.reloptions) \
+         WHERE option_name = '{name}')::{cast}, current_setting('{name}')::{cast})"
+    )
+}
+
+impl TableMaintenance {
+    /// Query returning one JSON object per table of the current schema, in
+    /// the shape of [`TableMaintenance`].
+    pub fn sql() -> String {
+        format!(
+            "SELECT json_build_object('table', s.relname, \
+             'live_rows', s.n_live_tup, 'dead_rows', s.n_dead_tup, \
+             'inserts', s.n_tup_ins, 'updates', s.n_tup_upd, 'deletes', s.n_tup_del, \
+             'modified_since_analyze', s.n_mod_since_analyze, \
+             'size_bytes', pg_table_size(s.relid), \
+             'last_vacuum', greatest(s.last_vacuum, s.last_autovacuum), \
+             'last_analyze', greatest(s.last_analyze, s.last_autoanalyze), \
+             'autovacuum', current_setting('autovacuum')::bool, \
+             'autovacuum_enabled', coalesce((SELECT option_value \
+             FROM pg_options_to_table(c.reloptions) \
+             WHERE option_name = 'autovacuum_enabled')::bool, true), \
+             'vacuum_threshold', {}, 'vacuum_scale_factor', {}, \
+             'analyze_threshold', {}, 'analyze_scale_factor', {}) \
+             FROM pg_stat_user_tables s \
+             JOIN pg_class c ON c.oid = s.relid \
+             WHERE s.schemaname = current_schema() \
+             ORDER BY s.relname",
+            reloption("autovacuum_vacuum_threshold", "int8"),
+            reloption("autovacuum_vacuum_scale_factor", "float8"),
+            reloption("autovacuum_analyze_threshold", "int8"),
+            reloption("autovacuum_analyze_scale_factor", "float8"),
+        )
+    }
+
+    /// Parse the rows of [`sql`](Self::sql).
+    pub fn from_rows(rows: Vec<serde_json::Value>) -> QueryResult<Vec<Self>> {
+        rows.into_iter()
+            .map(|row| {
+                serde_json::from_value(row).map_err(|e| {
+                    QueryError::serialization(format!("unexpected table statistics: {}", e))
+                })
+            })
+            .collect()
+    }
+
+    /// Share of dead rows, in percent.
+    pub fn dead_percent(&self) -> f64 {
+        let total = self.live_rows + self.dead_rows;
+        if total == 0 {
+            0.0
+        } else {
+            self.dead_rows as f64 * 100.0 / total as f64
+        }
+    }
+
+    /// Dead rows at which autovacuum vacuums the table.
+    pub fn vacuum_trigger(&self) -> u64 {
+        self.vacuum_threshold + (self.vacuum_scale_factor * self.live_rows as f64) as u64
+    }
+
+    /// Modified rows at which autoanalyze analyzes the table.
+    pub fn analyze_trigger(&self) -> u64 {
+        self.analyze_threshold + (self.analyze_scale_factor * self.live_rows as f64) as u64
+    }
+
+    /// Whether the table sees heavy update and delete traffic: at least
+    /// [`MIN_HEAVY_WRITES`] rows, and at least half its live rows, were
+    /// updated or deleted since the statistics were reset.
+    pub fn is_write_heavy(&self) -> bool {
+        let writes = self.updates + self.deletes;
+        writes >= MIN_HEAVY_WRITES && writes * 2 >= self.live_rows
+    }
+
+    /// Maintenance problems of the table, with a recommendation for each.
+    ///
+    /// Disabled autovacuum and a scale factor too high for the table's size
+    /// are only reported for [write-heavy](Self::is_write_heavy) tables; a
+    /// vacuum that fell behind and stale statistics are reported for any
+    /// table with at least [`MIN_DEAD_ROWS`] dead or modified rows.
+    pub fn advise(&self) -> Vec<MaintenanceAdvice> {
+        let mut advice = Vec::new();
+        let table = format!("\"{}\"", self.table.replace('"', "\"\""));
+        let heavy = self.is_write_heavy();
+
+        if heavy && !self.autovacuum {
+            advice.push(MaintenanceAdvice {
+                issue: MaintenanceIssue::AutovacuumDisabled,
+                message: "autovacuum is off on the server".to_string(),
+                recommendation: "ALTER SYSTEM SET autovacuum = on; SELECT pg_reload_conf();"
+                    .to_string(),
+            });
+        } else if heavy && !self.autovacuum_enabled {
+            advice.push(MaintenanceAdvice {
+                issue: MaintenanceIssue::AutovacuumDisabled,
+                message: "autovacuum is disabled for the table".to_string(),
+                recommendation: format!("ALTER TABLE {} RESET (autovacuum_enabled);", table),
+            });
+        }
+
+        let trigger = self.vacuum_trigger();
+        if self.dead_rows >= MIN_DEAD_ROWS && self.dead_rows > trigger {
+            advice.push(MaintenanceAdvice {
+                issue: MaintenanceIssue::VacuumBehind,
+                message: format!(
+                    "{} dead rows, autovacuum should have run at {} dead rows; last vacuumed {}",
+                    self.dead_rows,
+                    trigger,
+                    self.last_vacuum.as_deref().unwrap_or("never")
+                ),
+                recommendation: format!("VACUUM (ANALYZE) {};", table),
+            });
+        }
+
+        if heavy
+            && self.live_rows >= LARGE_TABLE_ROWS
+            && self.vacuum_scale_factor > RECOMMENDED_VACUUM_SCALE_FACTOR * 2.0
+        {
+            advice.push(MaintenanceAdvice {
+                issue: MaintenanceIssue::VacuumScaleFactor,
+                message: format!(
+                    "autovacuum waits for {} dead rows ({:.0}% of the table)",
+                    trigger,
+                    self.vacuum_scale_factor * 100.0
+                ),
+                recommendation: format!(
+                    "ALTER TABLE {} SET (autovacuum_vacuum_scale_factor = {}, \
+                     autovacuum_analyze_scale_factor = {});",
+                    table, RECOMMENDED_VACUUM_SCALE_FACTOR, RECOMMENDED_ANALYZE_SCALE_FACTOR
+                ),
+            });
+        }
+
+        let never_analyzed = self.last_analyze.is_none() && self.live_rows >= MIN_DEAD_ROWS;
+        if never_analyzed
+            || self.modified_since_analyze >= MIN_DEAD_ROWS
+                && self.modified_since_analyze > self.analyze_trigger()
+        {
+            advice.push(MaintenanceAdvice {
+                issue: MaintenanceIssue::StaleStatistics,
+                message: match &self.last_analyze {
+                    Some(at) => format!(
+                        "{} rows modified since the last analyze at {}",
+                        self.modified_since_analyze, at
+                    ),
+                    None => "never analyzed".to_string(),
+                },
+                recommendation: format!("ANALYZE {};", table),
+            });
+        }
+
+        advice
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_matches_server_text() {
+        let client = r#"SELECT "users"."id", "users"."email" FROM "users" WHERE "users"."id" IN ($1, $2) AND "active" = true LIMIT 10"#;
+        let postgres = "SELECT users.id, users.email FROM users WHERE users.id IN ($1, $2, $3) AND active = true LIMIT $4";
+        let mysql = "SELECT `users` . `id` , `users` . `email` FROM `users` WHERE `users` . `id` IN (...) AND `active` = true LIMIT ?";
+
+        assert_eq!(
+            normalize_statement(client),
+            "select users.id,users.email from users where users.id in(?) and active = true limit ?"
+        );
+        assert_eq!(fingerprint(client), fingerprint(postgres));
+        assert_eq!(fingerprint(client), fingerprint(mysql));
+
+        assert_eq!(
+            normalize_statement("INSERT INTO t (a, b) VALUES (1, 'x'), (2, 'y');"),
+            normalize_statement("INSERT INTO t (a, b) VALUES ($1, $2)")
+        );
+        assert_ne!(
+            fingerprint("SELECT * FROM users"),
+            fingerprint("SELECT * FROM posts")
+        );
+        assert_eq!(
+            fingerprint("SELECT * FROM users /*team='checkout'*/"),
+            fingerprint("SELECT * FROM users")
+        );
+    }
+
+    #[test]
+    fn test_stats_and_correlation() {
+        let stats = QueryStatsMiddleware::with_max_statements(2);
+        stats.record(
+            "SELECT * FROM users WHERE id = $1",
+            Some("User"),
+            4_000,
+            true,
+        );
+        stats.record("SELECT * FROM users WHERE id = 7", None, 2_000, false);
+        stats.record("DELETE FROM posts WHERE id = $1", Some("Post"), 1_000, true);
+        stats.record("SELECT 1", None, 1_000, true);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        let users = &snapshot[0];
+        assert_eq!(users.model.as_deref(), Some("User"));
+        assert_eq!((users.calls, users.errors), (2, 1));
+        assert_eq!(
+            (users.total_ms, users.mean_ms, users.max_ms),
+            (6.0, 3.0, 4.0)
+        );
+
+        let server = vec![
+            ServerStatement {
+                query: "SELECT * FROM users WHERE id = $1".into(),
+                calls: 2,
+                total_ms: 2.0,
+                mean_ms: 1.0,
+                rows: 1,
+            },
+            ServerStatement {
+                query: "VACUUM".into(),
+                calls: 1,
+                total_ms: 1.5,
+                mean_ms: 1.5,
+                rows: 0,
+            },
+            ServerStatement {
+                query: "select * from users where id = $1".into(),
+                calls: 2,
+                total_ms: 1.0,
+                mean_ms: 0.5,
+                rows: 1,
+            },
+        ];
+        let insights = correlate(&snapshot, &server);
+        assert_eq!(insights.len(), 3);
+        assert_eq!(insights[0].model.as_deref(), Some("User"));
+        assert_eq!(insights[0].server.as_ref().unwrap().calls, 4);
+        assert_eq!(insights[0].client_overhead_ms(), Some(3.0 - 0.75));
+        assert_eq!(insights[1].query, "vacuum");
+        assert!(insights[1].client.is_none());
+        assert_eq!(insights[2].model.as_deref(), Some("Post"));
+        assert!(insights[2].server.is_none());
+
+        stats.reset();
+        assert!(stats.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let stats = QueryStatsMiddleware::new();
+        stats.record("SELECT * FROM users", Some("User"), 1_500, true);
+
+        let path =
+            std::env::temp_dir().join(format!("prax-query-stats-{}.json", std::process::id()));
+        stats.write_snapshot(&path).unwrap();
+        let read = StatementStats::read_snapshot(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read, stats.snapshot());
+    }
+
+    #[test]
+    fn test_filter_shape() {
+        let shape = FilterShape::parse(
+            r#"SELECT "u"."id" FROM "public"."users" AS "u" WHERE "u"."tenant_id" IN ($1, $2) AND "u"."created_at" BETWEEN $3 AND $4 AND "u"."active" = true ORDER BY "u"."name", "u"."id" DESC LIMIT 20"#,
+        )
+        .unwrap();
+        assert_eq!(shape.table, "users");
+        assert_eq!(shape.equality, ["tenant_id"]);
+        assert_eq!(shape.range, ["created_at"]);
+        assert_eq!(shape.constant, ["active = true"]);
+        assert_eq!(
+            shape.sort,
+            [
+                SortColumn {
+                    column: "name".into(),
+                    descending: false
+                },
+                SortColumn {
+                    column: "id".into(),
+                    descending: true
+                }
+            ]
+        );
+        assert!(shape.limited);
+
+        let shape =
+            FilterShape::parse("UPDATE orders SET status = $1 WHERE customer_id = $2").unwrap();
+        assert_eq!(shape.table, "orders");
+        assert_eq!(shape.equality, ["customer_id"]);
+        assert!(!shape.limited);
+
+        let shape =
+            FilterShape::parse("PREPARE q(int) AS DELETE FROM sessions WHERE expires_at < $1")
+                .unwrap();
+        assert_eq!(shape.range, ["expires_at"]);
+
+        assert!(FilterShape::parse("SELECT 1").is_none());
+        assert!(FilterShape::parse("SELECT * FROM a JOIN b ON a.id = b.a_id").is_none());
+        assert!(FilterShape::parse("SELECT * FROM a WHERE x = 1 OR y = 2").is_none());
+        assert!(FilterShape::parse("INSERT INTO a (x) VALUES (1)").is_none());
+        assert!(
+            FilterShape::parse("SELECT * FROM users")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_top_queries_sql() {
+        let pg = ServerStatsSource::PgStatStatements.top_queries_sql(5);
+        assert!(pg.contains("FROM pg_stat_statements"));
+        assert!(pg.ends_with("LIMIT 5"));
+        let mysql = ServerStatsSource::PerformanceSchema.top_queries_sql(5);
+        assert!(mysql.contains("events_statements_summary_by_digest"));
+
+        let rows = vec![serde_json::json!({
+            "query": "SELECT 1", "calls": 3, "total_ms": 1.5, "mean_ms": 0.5, "rows": 3
+        })];
+        let parsed = ServerStatement::from_rows(rows).unwrap();
+        assert_eq!(parsed[0].calls, 3);
+        assert!(ServerStatement::from_rows(vec![serde_json::json!({"query": 1})]).is_err());
+    }
+
+    fn orders() -> TableMaintenance {
+        TableMaintenance::from_rows(vec![serde_json::json!({
+            "table": "orders", "live_rows": 1_000_000, "dead_rows": 150_000,
+            "inserts": 1_000_000, "updates": 2_000_000, "deletes": 100_000,
+            "modified_since_analyze": 20_000, "size_bytes": 200_000_000,
+            "last_vacuum": "2026-10-01T03:00:00+00:00",
+            "last_analyze": "2026-10-01T03:00:00+00:00",
+            "autovacuum": true, "autovacuum_enabled": true,
+            "vacuum_threshold": 50, "vacuum_scale_factor": 0.2,
+            "analyze_threshold": 50, "analyze_scale_factor": 0.1
+        })])
+        .unwrap()
+        .remove(0)
+    }
+
+    #[test]
+    fn test_table_maintenance_advise() {
+        assert!(TableMaintenance::sql().contains("FROM pg_stat_user_tables s"));
+
+        let mut table = orders();
+        assert!(table.is_write_heavy());
+        assert_eq!(table.vacuum_trigger(), 200_050);
+        assert!((table.dead_percent() - 13.04).abs() < 0.01);
+
+        // Below the vacuum and analyze triggers, only the scale factor is off
+        let advice = table.advise();
+        assert_eq!(advice.len(), 1);
+        assert_eq!(advice[0].issue, MaintenanceIssue::VacuumScaleFactor);
+        assert_eq!(
+            advice[0].recommendation,
+            "ALTER TABLE \"orders\" SET (autovacuum_vacuum_scale_factor = 0.02, \
+             autovacuum_analyze_scale_factor = 0.01);"
+        );
+
+        table.autovacuum_enabled = false;
+        table.dead_rows = 300_000;
+        table.modified_since_analyze = 150_000;
+        let issues: Vec<_> = table.advise().into_iter().map(|a| a.issue).collect();
+        assert_eq!(
+            issues,
+            [
+                MaintenanceIssue::AutovacuumDisabled,
+                MaintenanceIssue::VacuumBehind,
+                MaintenanceIssue::VacuumScaleFactor,
+                MaintenanceIssue::StaleStatistics
+            ]
+        );
+
+        // A table rarely updated gets no tuning advice
+        table.updates = 1_000;
+        table.deletes = 0;
+        let issues: Vec<_> = table.advise().into_iter().map(|a| a.issue).collect();
+        assert_eq!(
+            issues,
+            [
+                MaintenanceIssue::VacuumBehind,
+                MaintenanceIssue::StaleStatistics
+            ]
+        );
+    }
+}
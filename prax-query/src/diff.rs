@@ -0,0 +1,231 @@
+//! Diffing query results against another dataset.
+//!
+//! Sync and import jobs compare an external dataset with what the database
+//! holds, then write only what changed. [`diff_results`] matches rows of the
+//! two sets by key and sorts them into inserts, updates and deletes; each
+//! update carries a [`FieldMask`] of the fields that differ, so the write
+//! can set just those columns.
+//!
+//! Generated models implement [`Diffable`] with a `FieldMask` bitset of one
+//! bit per stored field, whose `set_params` turns the changed fields into
+//! update parameters:
+//!
+//! ```rust,ignore
+//! use prax_query::diff::diff_results;
+//!
+//! let current = client.product().find_many().exec().await?;
+//! let diff = diff_results(&current, &feed, |p| p.sku.clone());
+//!
+//! for product in &diff.inserts {
+//!     client.product().create(product.to_create_input()).exec().await?;
+//! }
+//! for update in &diff.updates {
+//!     client
+//!         .product()
+//!         .update(product::sku::equals(update.new.sku.clone()))
+//!         .data(update.changed.set_params(update.new))
+//!         .exec()
+//!         .await?;
+//! }
+//! for product in &diff.deletes {
+//!     client.product().delete(product::sku::equals(product.sku.clone())).exec().await?;
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// The set of fields that differ between two records.
+pub trait FieldMask: Copy + fmt::Debug + Default + PartialEq {
+    /// Check whether no field differs.
+    fn is_empty(&self) -> bool;
+
+    /// Get the columns of the fields that differ, in declaration order.
+    fn columns(&self) -> Vec<&'static str>;
+}
+
+/// A record that can be compared field by field.
+pub trait Diffable {
+    /// The mask of fields that differ.
+    type Mask: FieldMask;
+
+    /// Get the fields in which `other` differs from this record.
+    fn changed_fields(&self, other: &Self) -> Self::Mask;
+}
+
+/// A row present in both datasets with different contents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowUpdate<'a, T: Diffable> {
+    /// The row in the old dataset.
+    pub old: &'a T,
+    /// The row in the new dataset.
+    pub new: &'a T,
+    /// The fields that differ.
+    pub changed: T::Mask,
+}
+
+/// The writes turning one dataset into another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultDiff<'a, T: Diffable> {
+    /// Rows only in the new dataset, in its order.
+    pub inserts: Vec<&'a T>,
+    /// Rows in both datasets whose fields differ, in the new dataset's order.
+    pub updates: Vec<RowUpdate<'a, T>>,
+    /// Rows only in the old dataset, in its order.
+    pub deletes: Vec<&'a T>,
+}
+
+impl<T: Diffable> ResultDiff<'_, T> {
+    /// Check whether the datasets hold the same rows.
+    pub fn is_empty(&self) -> bool {
+        self.inserts.is_empty() && self.updates.is_empty() && self.deletes.is_empty()
+    }
+
+    /// Get the number of writes.
+    pub fn len(&self) -> usize {
+        self.inserts.len() + self.updates.len() + self.deletes.len()
+    }
+}
+
+/// Compare two datasets, matching rows by the key `key_fn` extracts.
+///
+/// Keys are expected to be unique within each dataset. A key repeated in
+/// the new dataset is matched once and then inserted; a key repeated in the
+/// old one is matched against its last row, and its other rows are
+/// deleted.
+pub fn diff_results<'a, T, K, F>(old: &'a [T], new: &'a [T], key_fn: F) -> ResultDiff<'a, T>
+where
+    T: Diffable,
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+{
+    // Index of the last old row per key
+    let mut unmatched: HashMap<K, usize> = old
+        .iter()
+        .enumerate()
+        .map(|(i, row)| (key_fn(row), i))
+        .collect();
+    let mut matched = vec![false; old.len()];
+    let mut inserts = Vec::new();
+    let mut updates = Vec::new();
+
+    for row in new {
+        match unmatched.remove(&key_fn(row)) {
+            Some(i) => {
+                matched[i] = true;
+                let changed = old[i].changed_fields(row);
+                if !changed.is_empty() {
+                    updates.push(RowUpdate {
+                        old: &old[i],
+                        new: row,
+                        changed,
+                    });
+                }
+            }
+            None => inserts.push(row),
+        }
+    }
+
+    let deletes = old
+        .iter()
+        .zip(matched)
+        .filter_map(|(row, matched)| (!matched).then_some(row))
+        .collect();
+
+    ResultDiff {
+        inserts,
+        updates,
+        deletes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Product {
+        sku: &'static str,
+        name: &'static str,
+        price: i64,
+    }
+
+    /// As generated for a model.
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    struct ProductMask {
+        sku: bool,
+        name: bool,
+        price: bool,
+    }
+
+    impl FieldMask for ProductMask {
+        fn is_empty(&self) -> bool {
+            !(self.sku || self.name || self.price)
+        }
+
+        fn columns(&self) -> Vec<&'static str> {
+            [
+                ("sku", self.sku),
+                ("name", self.name),
+                ("price", self.price),
+            ]
+            .into_iter()
+            .filter_map(|(column, changed)| changed.then_some(column))
+            .collect()
+        }
+    }
+
+    impl Diffable for Product {
+        type Mask = ProductMask;
+
+        fn changed_fields(&self, other: &Self) -> ProductMask {
+            ProductMask {
+                sku: self.sku != other.sku,
+                name: self.name != other.name,
+                price: self.price != other.price,
+            }
+        }
+    }
+
+    fn product(sku: &'static str, name: &'static str, price: i64) -> Product {
+        Product { sku, name, price }
+    }
+
+    #[test]
+    fn test_diff_results() {
+        let old = vec![
+            product("a", "Apple", 100),
+            product("b", "Banana", 50),
+            product("c", "Cherry", 300),
+        ];
+        let new = vec![
+            product("d", "Date", 400),
+            product("c", "Cherry", 350),
+            product("a", "Apple", 100),
+        ];
+
+        let diff = diff_results(&old, &new, |p| p.sku);
+        assert_eq!(diff.inserts, vec![&new[0]]);
+        assert_eq!(diff.deletes, vec![&old[1]]);
+        assert_eq!(diff.updates.len(), 1);
+        assert_eq!(diff.updates[0].old, &old[2]);
+        assert_eq!(diff.updates[0].new, &new[1]);
+        assert_eq!(diff.updates[0].changed.columns(), vec!["price"]);
+        assert_eq!(diff.len(), 3);
+
+        assert!(diff_results(&old, &old, |p| p.sku).is_empty());
+    }
+
+    #[test]
+    fn test_diff_duplicate_keys() {
+        let old = vec![product("a", "Apple", 100), product("a", "Avocado", 200)];
+        let new = vec![product("a", "Avocado", 200), product("a", "Apricot", 150)];
+
+        // The last old row is matched, the earlier one deleted
+        let diff = diff_results(&old, &new, |p| p.sku);
+        assert!(diff.updates.is_empty());
+        assert_eq!(diff.inserts, vec![&new[1]]);
+        assert_eq!(diff.deletes, vec![&old[0]]);
+    }
+}
@@ -0,0 +1,441 @@
+//! Strict deserialization: detect schema/database drift at runtime.
+//!
+//! Generated models describe their columns with [`ColumnSpec`]s
+//! ([`Model::COLUMN_SPECS`](crate::traits::Model::COLUMN_SPECS)). In strict
+//! mode an engine checks the columns a statement returns against them the
+//! first time the statement yields a row, and fails with a [`DriftError`]
+//! instead of coercing values silently or failing deep inside the driver:
+//!
+//! ```text
+//! column users.age is TEXT but schema says Int
+//! ```
+//!
+//! ```rust
+//! use prax_query::drift::{ColumnKind, ColumnSpec, DriftGuard};
+//!
+//! const USERS: &[ColumnSpec] = &[
+//!     ColumnSpec::new("id", ColumnKind::Int),
+//!     ColumnSpec::new("age", ColumnKind::Int),
+//! ];
+//!
+//! let guard = DriftGuard::new();
+//! let sql = "SELECT id, age FROM users";
+//!
+//! let err = guard
+//!     .check(sql, "users", USERS, [("id", "int4"), ("age", "text")])
+//!     .unwrap_err();
+//! assert_eq!(err.to_string(), "column users.age is TEXT but schema says Int");
+//! ```
+
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+use crate::error::{ErrorCode, QueryError};
+
+/// The schema type of a column, as far as drift checks are concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnKind {
+    /// `Int`.
+    Int,
+    /// `BigInt`.
+    BigInt,
+    /// `Float`.
+    Float,
+    /// `Decimal`.
+    Decimal,
+    /// `String` and the generated identifier types (`Cuid`, `NanoId`, ...).
+    String,
+    /// `Boolean`.
+    Boolean,
+    /// `DateTime`.
+    DateTime,
+    /// `Date`.
+    Date,
+    /// `Time`.
+    Time,
+    /// `Json` and composite types.
+    Json,
+    /// `Bytes`.
+    Bytes,
+    /// `Uuid`.
+    Uuid,
+    /// A schema enum, stored as a database enum or as text.
+    Enum,
+    /// A type drift checks cannot reason about, such as vectors; never reported.
+    Other,
+}
+
+impl ColumnKind {
+    /// Get the schema type name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Int => "Int",
+            Self::BigInt => "BigInt",
+            Self::Float => "Float",
+            Self::Decimal => "Decimal",
+            Self::String => "String",
+            Self::Boolean => "Boolean",
+            Self::DateTime => "DateTime",
+            Self::Date => "Date",
+            Self::Time => "Time",
+            Self::Json => "Json",
+            Self::Bytes => "Bytes",
+            Self::Uuid => "Uuid",
+            Self::Enum => "Enum",
+            Self::Other => "Unsupported",
+        }
+    }
+
+    /// Check if a column of database type `db_type` can hold this kind.
+    ///
+    /// `db_type` is the type name the driver reports (`int4`, `VARCHAR(255)`,
+    /// `INTEGER`, ...); case and length or precision arguments are ignored.
+    pub fn accepts(&self, db_type: &str) -> bool {
+        let db_type = db_type.to_ascii_lowercase();
+        let base = db_type
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches(" unsigned");
+
+        const INTS: &[&str] = &[
+            "int",
+            "int2",
+            "int4",
+            "integer",
+            "smallint",
+            "mediumint",
+            "tinyint",
+            "serial",
+        ];
+        const BIG_INTS: &[&str] = &["int8", "bigint", "bigserial"];
+        const TEXTS: &[&str] = &[
+            "text",
+            "varchar",
+            "character varying",
+            "char",
+            "character",
+            "bpchar",
+            "name",
+            "citext",
+            "tinytext",
+            "mediumtext",
+            "longtext",
+            "nvarchar",
+            "nchar",
+            "ntext",
+        ];
+
+        match self {
+            Self::Int => INTS.contains(&base) || base == "year",
+            Self::BigInt => INTS.contains(&base) || BIG_INTS.contains(&base),
+            Self::Float => matches!(
+                base,
+                "float" | "float4" | "float8" | "real" | "double" | "double precision"
+            ),
+            Self::Decimal => matches!(base, "numeric" | "decimal" | "money"),
+            Self::String => TEXTS.contains(&base),
+            // MySQL and SQLite store booleans as small integers
+            Self::Boolean => matches!(base, "bool" | "boolean" | "bit" | "tinyint"),
+            Self::DateTime => matches!(
+                base,
+                "timestamp"
+                    | "timestamptz"
+                    | "timestamp with time zone"
+                    | "timestamp without time zone"
+                    | "datetime"
+                    | "datetime2"
+                    | "datetimeoffset"
+            ),
+            Self::Date => base == "date",
+            Self::Time => matches!(base, "time" | "timetz" | "time with time zone"),
+            Self::Json => matches!(base, "json" | "jsonb") || TEXTS.contains(&base),
+            Self::Bytes => matches!(
+                base,
+                "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary"
+            ),
+            Self::Uuid => {
+                matches!(base, "uuid" | "uniqueidentifier" | "binary") || TEXTS.contains(&base)
+            }
+            // Database enums carry user-defined type names
+            Self::Enum => {
+                !(INTS.contains(&base)
+                    || BIG_INTS.contains(&base)
+                    || Self::Float.accepts(base)
+                    || Self::Decimal.accepts(base)
+                    || Self::Boolean.accepts(base)
+                    || Self::DateTime.accepts(base)
+                    || Self::Date.accepts(base)
+                    || Self::Time.accepts(base)
+                    || Self::Bytes.accepts(base)
+                    || matches!(base, "json" | "jsonb" | "uuid"))
+            }
+            Self::Other => true,
+        }
+    }
+}
+
+impl fmt::Display for ColumnKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A model column as the schema declares it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColumnSpec {
+    /// Column name.
+    pub name: &'static str,
+    /// Schema type.
+    pub kind: ColumnKind,
+}
+
+impl ColumnSpec {
+    /// Create a column spec.
+    pub const fn new(name: &'static str, kind: ColumnKind) -> Self {
+        Self { name, kind }
+    }
+}
+
+/// A difference between the schema and the columns a statement returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriftError {
+    /// A schema column is absent from the result.
+    MissingColumn {
+        /// Table name.
+        table: String,
+        /// Column name.
+        column: String,
+    },
+    /// A column's database type cannot hold its schema type.
+    TypeMismatch {
+        /// Table name.
+        table: String,
+        /// Column name.
+        column: String,
+        /// Database type, as the driver reports it.
+        actual: String,
+        /// Schema type.
+        expected: ColumnKind,
+    },
+}
+
+impl DriftError {
+    /// Get the table name.
+    pub fn table(&self) -> &str {
+        match self {
+            Self::MissingColumn { table, .. } | Self::TypeMismatch { table, .. } => table,
+        }
+    }
+
+    /// Get the column name.
+    pub fn column(&self) -> &str {
+        match self {
+            Self::MissingColumn { column, .. } | Self::TypeMismatch { column, .. } => column,
+        }
+    }
+}
+
+impl fmt::Display for DriftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumn { table, column } => {
+                write!(f, "column {}.{} is missing from the result", table, column)
+            }
+            Self::TypeMismatch {
+                table,
+                column,
+                actual,
+                expected,
+            } => write!(
+                f,
+                "column {}.{} is {} but schema says {}",
+                table,
+                column,
+                actual.to_ascii_uppercase(),
+                expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DriftError {}
+
+impl From<DriftError> for QueryError {
+    fn from(err: DriftError) -> Self {
+        QueryError::new(ErrorCode::SchemaDrift, err.to_string())
+            .with_model(err.table())
+            .with_field(err.column())
+            .with_suggestion("Apply pending migrations with `prax migrate deploy`")
+            .with_suggestion("Compare the schema with the database using `prax migrate diff`")
+    }
+}
+
+/// Check a result's columns against a model's column specs.
+///
+/// `columns` yields `(name, database type)` pairs; extra columns are ignored.
+/// An empty database type, as drivers report for some expressions, matches
+/// any schema type.
+pub fn verify_columns<'a>(
+    table: &str,
+    specs: &[ColumnSpec],
+    columns: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<(), DriftError> {
+    let columns: Vec<_> = columns.into_iter().collect();
+
+    for spec in specs {
+        let Some((_, db_type)) = columns.iter().find(|(name, _)| *name == spec.name) else {
+            return Err(DriftError::MissingColumn {
+                table: table.to_string(),
+                column: spec.name.to_string(),
+            });
+        };
+        if !db_type.is_empty() && !spec.kind.accepts(db_type) {
+            return Err(DriftError::TypeMismatch {
+                table: table.to_string(),
+                column: spec.name.to_string(),
+                actual: db_type.to_string(),
+                expected: spec.kind,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Strict mode state: the statements whose columns have been verified.
+///
+/// Each statement is checked once; later executions skip the check, so strict
+/// mode costs one comparison per distinct statement.
+#[derive(Debug, Default)]
+pub struct DriftGuard {
+    verified: RwLock<HashSet<u64>>,
+}
+
+impl DriftGuard {
+    /// Create a guard with no verified statements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verify the columns of `sql` against `specs`, unless already verified.
+    ///
+    /// A statement that drifted is checked again on its next use.
+    pub fn check<'a>(
+        &self,
+        sql: &str,
+        table: &str,
+        specs: &[ColumnSpec],
+        columns: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<(), DriftError> {
+        if specs.is_empty() {
+            return Ok(());
+        }
+
+        let mut hasher = DefaultHasher::new();
+        sql.hash(&mut hasher);
+        let key = hasher.finish();
+
+        if self.verified.read().unwrap().contains(&key) {
+            return Ok(());
+        }
+
+        verify_columns(table, specs, columns)?;
+        self.verified.write().unwrap().insert(key);
+        Ok(())
+    }
+
+    /// Get the number of verified statements.
+    pub fn verified_count(&self) -> usize {
+        self.verified.read().unwrap().len()
+    }
+
+    /// Forget verified statements, so each is checked again, as after a migration.
+    pub fn reset(&self) {
+        self.verified.write().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USERS: &[ColumnSpec] = &[
+        ColumnSpec::new("id", ColumnKind::Int),
+        ColumnSpec::new("email", ColumnKind::String),
+        ColumnSpec::new("role", ColumnKind::Enum),
+        ColumnSpec::new("created_at", ColumnKind::DateTime),
+    ];
+
+    #[test]
+    fn test_column_kind_accepts() {
+        assert!(ColumnKind::Int.accepts("int4"));
+        assert!(ColumnKind::Int.accepts("INT(11) UNSIGNED"));
+        assert!(!ColumnKind::Int.accepts("int8"));
+        assert!(ColumnKind::BigInt.accepts("INTEGER"));
+        assert!(ColumnKind::String.accepts("VARCHAR(255)"));
+        assert!(ColumnKind::Boolean.accepts("tinyint(1)"));
+        assert!(ColumnKind::Enum.accepts("user_role"));
+        assert!(!ColumnKind::Enum.accepts("int4"));
+        assert!(!ColumnKind::Uuid.accepts("int8"));
+    }
+
+    #[test]
+    fn test_verify_columns() {
+        let columns = [
+            ("id", "int4"),
+            ("email", "text"),
+            ("role", "user_role"),
+            ("created_at", "timestamptz"),
+            ("extra", "text"),
+        ];
+        assert!(verify_columns("users", USERS, columns).is_ok());
+
+        let untyped = [("id", ""), ("email", ""), ("role", ""), ("created_at", "")];
+        assert!(verify_columns("users", USERS, untyped).is_ok());
+
+        let err = verify_columns("users", USERS, [("id", "text")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column users.id is TEXT but schema says Int"
+        );
+
+        let err = verify_columns("users", USERS, [("id", "int4")]).unwrap_err();
+        assert_eq!(
+            err,
+            DriftError::MissingColumn {
+                table: "users".to_string(),
+                column: "email".to_string(),
+            }
+        );
+
+        let query_err = QueryError::from(err);
+        assert!(query_err.is_schema_drift());
+    }
+
+    #[test]
+    fn test_drift_guard_checks_once() {
+        let guard = DriftGuard::new();
+        let good = [
+            ("id", "int4"),
+            ("email", "text"),
+            ("role", "text"),
+            ("created_at", "timestamp"),
+        ];
+
+        assert!(guard.check("SELECT 1", "users", USERS, good).is_ok());
+        assert_eq!(guard.verified_count(), 1);
+        // Verified statements are not checked again
+        assert!(guard.check("SELECT 1", "users", USERS, []).is_ok());
+
+        assert!(guard.check("SELECT 2", "users", USERS, []).is_err());
+        assert_eq!(guard.verified_count(), 1);
+
+        guard.reset();
+        assert_eq!(guard.verified_count(), 0);
+    }
+}
@@ -0,0 +1,400 @@
+//! Erasure of a data subject's personal data across related models.
+//!
+//! Fields holding personal data are annotated in the schema with
+//! `@personalData`, and the field identifying a data subject with
+//! `@subjectId`. For the subject model, the generated client exposes an
+//! [`ErasurePlan`] covering the subject model and every model related to it,
+//! together with a `forget_subject` function:
+//!
+//! ```rust,ignore
+//! let report = user::forget_subject(&db, 42).await?;
+//! store_compliance_record(serde_json::to_string(&report)?);
+//! ```
+//!
+//! All statements run in one transaction: either every row is erased or
+//! nothing is. Rows are erased children first, so deletes do not violate
+//! foreign keys of rows still pointing at them, and the subject's own row is
+//! erased last.
+//!
+//! Plans can also be assembled by hand:
+//!
+//! ```rust
+//! use prax_query::erasure::{ErasurePlan, ErasureScope};
+//! use prax_query::filter::FilterValue;
+//! use prax_query::sql::DatabaseType;
+//!
+//! let user = ErasureScope::subject("id");
+//! let posts = ErasureScope::related("author_id", "users", "id", user.clone());
+//!
+//! let plan = ErasurePlan::new("User")
+//!     .delete("Post", "posts", posts)
+//!     .anonymize("User", "users", user, [("email", FilterValue::Null)]);
+//!
+//! let statements = plan.statements(DatabaseType::PostgreSQL, 42.into());
+//! assert_eq!(
+//!     statements[0].0,
+//!     "DELETE FROM posts WHERE author_id IN (SELECT id FROM users WHERE id = $1)"
+//! );
+//! assert_eq!(statements[1].0, "UPDATE users SET email = $1 WHERE id = $2");
+//! ```
+
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::error::QueryResult;
+use crate::filter::FilterValue;
+use crate::raw::RawDatabase;
+use crate::sql::{DatabaseType, quote_identifier};
+
+/// Which rows of a table belong to the subject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErasureScope {
+    /// Rows whose column holds the subject id.
+    Subject {
+        /// The subject id column.
+        column: String,
+    },
+    /// Rows referencing rows of a parent table that belong to the subject.
+    Related {
+        /// The foreign key column.
+        column: String,
+        /// The referenced table.
+        parent_table: String,
+        /// The referenced column.
+        parent_column: String,
+        /// Which parent rows belong to the subject.
+        parent: Box<ErasureScope>,
+    },
+}
+
+impl ErasureScope {
+    /// Rows whose `column` holds the subject id.
+    pub fn subject(column: impl Into<String>) -> Self {
+        Self::Subject {
+            column: column.into(),
+        }
+    }
+
+    /// Rows whose `column` references `parent_table.parent_column` of rows in `parent`.
+    pub fn related(
+        column: impl Into<String>,
+        parent_table: impl Into<String>,
+        parent_column: impl Into<String>,
+        parent: ErasureScope,
+    ) -> Self {
+        Self::Related {
+            column: column.into(),
+            parent_table: parent_table.into(),
+            parent_column: parent_column.into(),
+            parent: Box::new(parent),
+        }
+    }
+
+    /// Build the WHERE condition, with the subject id bound to `param`.
+    fn to_sql(&self, db: DatabaseType, param: usize) -> String {
+        match self {
+            Self::Subject { column } => {
+                format!("{} = {}", quote_identifier(column), db.placeholder(param))
+            }
+            Self::Related {
+                column,
+                parent_table,
+                parent_column,
+                parent,
+            } => format!(
+                "{} IN (SELECT {} FROM {} WHERE {})",
+                quote_identifier(column),
+                quote_identifier(parent_column),
+                quote_identifier(parent_table),
+                parent.to_sql(db, param)
+            ),
+        }
+    }
+}
+
+/// What happens to the subject's rows in a table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErasureAction {
+    /// Delete the rows.
+    Delete,
+    /// Overwrite personal data columns with the given values.
+    Anonymize(Vec<(String, FilterValue)>),
+}
+
+impl ErasureAction {
+    /// Get the action name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Anonymize(_) => "anonymize",
+        }
+    }
+}
+
+/// Erasure of the subject's rows in one table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErasureStep {
+    /// The model name.
+    pub model: String,
+    /// The table name.
+    pub table: String,
+    /// Which rows belong to the subject.
+    pub scope: ErasureScope,
+    /// What happens to them.
+    pub action: ErasureAction,
+}
+
+impl ErasureStep {
+    /// Build the statement erasing the subject's rows.
+    fn to_sql(&self, db: DatabaseType, subject_id: &FilterValue) -> (String, Vec<FilterValue>) {
+        let table = quote_identifier(&self.table);
+        match &self.action {
+            ErasureAction::Delete => (
+                format!("DELETE FROM {} WHERE {}", table, self.scope.to_sql(db, 1)),
+                vec![subject_id.clone()],
+            ),
+            ErasureAction::Anonymize(values) => {
+                let assignments: Vec<_> = values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (column, _))| {
+                        format!("{} = {}", quote_identifier(column), db.placeholder(i + 1))
+                    })
+                    .collect();
+                let mut params: Vec<_> = values.iter().map(|(_, v)| v.clone()).collect();
+                params.push(subject_id.clone());
+                (
+                    format!(
+                        "UPDATE {} SET {} WHERE {}",
+                        table,
+                        assignments.join(", "),
+                        self.scope.to_sql(db, values.len() + 1)
+                    ),
+                    params,
+                )
+            }
+        }
+    }
+}
+
+/// The steps erasing a subject, in execution order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErasurePlan {
+    /// The subject model.
+    pub subject: String,
+    /// Erasure steps, executed in order.
+    pub steps: Vec<ErasureStep>,
+}
+
+impl ErasurePlan {
+    /// Create an empty plan for a subject model.
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Delete the subject's rows in a table.
+    pub fn delete(
+        mut self,
+        model: impl Into<String>,
+        table: impl Into<String>,
+        scope: ErasureScope,
+    ) -> Self {
+        self.steps.push(ErasureStep {
+            model: model.into(),
+            table: table.into(),
+            scope,
+            action: ErasureAction::Delete,
+        });
+        self
+    }
+
+    /// Overwrite personal data columns of the subject's rows in a table.
+    pub fn anonymize<I, C>(
+        mut self,
+        model: impl Into<String>,
+        table: impl Into<String>,
+        scope: ErasureScope,
+        values: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = (C, FilterValue)>,
+        C: Into<String>,
+    {
+        self.steps.push(ErasureStep {
+            model: model.into(),
+            table: table.into(),
+            scope,
+            action: ErasureAction::Anonymize(
+                values.into_iter().map(|(c, v)| (c.into(), v)).collect(),
+            ),
+        });
+        self
+    }
+
+    /// Build the statements erasing a subject.
+    pub fn statements(
+        &self,
+        db: DatabaseType,
+        subject_id: FilterValue,
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        self.steps
+            .iter()
+            .map(|step| step.to_sql(db, &subject_id))
+            .collect()
+    }
+
+    /// Erase a subject in a single transaction.
+    pub async fn execute<D: RawDatabase + ?Sized>(
+        &self,
+        db: &D,
+        subject_id: impl Into<FilterValue>,
+    ) -> QueryResult<ErasureReport> {
+        let subject_id = subject_id.into();
+        let statements = self.statements(db.database_type(), subject_id.clone());
+        let affected = db
+            .execute_in_transaction(statements)
+            .await
+            .map_err(|e| e.with_context(format!("erasing {} subject", self.subject)))?;
+
+        let entries: Vec<_> = self
+            .steps
+            .iter()
+            .zip(affected)
+            .map(|(step, rows)| ErasureEntry {
+                model: step.model.clone(),
+                table: step.table.clone(),
+                action: step.action.name().to_string(),
+                columns: match &step.action {
+                    ErasureAction::Delete => Vec::new(),
+                    ErasureAction::Anonymize(values) => {
+                        values.iter().map(|(c, _)| c.clone()).collect()
+                    }
+                },
+                rows,
+            })
+            .collect();
+
+        let report = ErasureReport {
+            subject: self.subject.clone(),
+            subject_id,
+            erased_at: SystemTime::now(),
+            entries,
+        };
+        info!(
+            subject = %report.subject,
+            rows = report.total_rows(),
+            "Erased data subject"
+        );
+        Ok(report)
+    }
+}
+
+/// Rows erased in one table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErasureEntry {
+    /// The model name.
+    pub model: String,
+    /// The table name.
+    pub table: String,
+    /// `delete` or `anonymize`.
+    pub action: String,
+    /// Anonymized columns.
+    pub columns: Vec<String>,
+    /// Rows affected.
+    pub rows: u64,
+}
+
+/// Record of a completed erasure, for compliance records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErasureReport {
+    /// The subject model.
+    pub subject: String,
+    /// The erased subject's id.
+    pub subject_id: FilterValue,
+    /// When the erasure was committed.
+    pub erased_at: SystemTime,
+    /// Rows erased per table.
+    pub entries: Vec<ErasureEntry>,
+}
+
+impl ErasureReport {
+    /// Total number of rows deleted or anonymized.
+    pub fn total_rows(&self) -> u64 {
+        self.entries.iter().map(|e| e.rows).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::testing::FakeDatabase;
+
+    fn plan() -> ErasurePlan {
+        let user = ErasureScope::subject("id");
+        let posts = ErasureScope::related("author_id", "users", "id", user.clone());
+        let comments = ErasureScope::related("post_id", "posts", "id", posts.clone());
+
+        ErasurePlan::new("User")
+            .delete("Comment", "comments", comments)
+            .anonymize("Post", "posts", posts, [("ip_address", FilterValue::Null)])
+            .anonymize(
+                "User",
+                "users",
+                user,
+                [
+                    ("email", FilterValue::Null),
+                    ("name", FilterValue::String("Deleted user".into())),
+                ],
+            )
+    }
+
+    #[test]
+    fn test_nested_scope_sql() {
+        let statements = plan().statements(DatabaseType::PostgreSQL, 7.into());
+        assert_eq!(
+            statements[0].0,
+            "DELETE FROM comments WHERE post_id IN (SELECT id FROM posts \
+             WHERE author_id IN (SELECT id FROM users WHERE id = $1))"
+        );
+        assert_eq!(
+            statements[2].0,
+            "UPDATE users SET email = $1, name = $2 WHERE id = $3"
+        );
+        assert_eq!(
+            statements[2].1,
+            vec![
+                FilterValue::Null,
+                FilterValue::String("Deleted user".into()),
+                FilterValue::Int(7)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_reports_each_table() {
+        let db = FakeDatabase::new().with_database_type(DatabaseType::MySQL);
+        let report = plan().execute(&db, 7).await.unwrap();
+
+        assert_eq!(report.subject, "User");
+        assert_eq!(report.subject_id, FilterValue::Int(7));
+        assert_eq!(report.total_rows(), 3);
+        assert_eq!(report.entries[0].action, "delete");
+        assert_eq!(report.entries[2].columns, vec!["email", "name"]);
+        assert!(db.transactions()[0][1].0.ends_with("WHERE id = ?)"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_propagates_failure() {
+        let db = FakeDatabase::new().failing("deadlock detected");
+        let err = plan().execute(&db, 7).await.unwrap_err();
+        assert_eq!(
+            err.context().operation.as_deref(),
+            Some("erasing User subject")
+        );
+    }
+}
@@ -58,8 +58,11 @@
 //! ```
 
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::connection::PoolSnapshot;
+
 /// Result type for query operations.
 pub type QueryResult<T> = Result<T, QueryError>;
 
@@ -87,6 +90,8 @@ pub enum ErrorCode {
     CheckConstraint = 2003,
     /// Not null constraint violation (P2004).
     NotNullConstraint = 2004,
+    /// State machine transition not allowed (P2005).
+    InvalidTransition = 2005,
 
     // Connection errors (3xxx)
     /// Database connection failed (P3001).
@@ -99,6 +104,8 @@ pub enum ErrorCode {
     AuthenticationFailed = 3004,
     /// SSL/TLS error (P3005).
     SslError = 3005,
+    /// Timed out waiting for a pooled connection (P3006).
+    PoolTimeout = 3006,
 
     // Transaction errors (4xxx)
     /// Transaction failed (P4001).
@@ -121,6 +128,8 @@ pub enum ErrorCode {
     QueryTooComplex = 5004,
     /// General database error (P5005).
     DatabaseError = 5005,
+    /// Tenant resource quota exceeded (P5006).
+    QuotaExceeded = 5006,
 
     // Data errors (6xxx)
     /// Invalid data type (P6001).
@@ -131,6 +140,8 @@ pub enum ErrorCode {
     DeserializationError = 6003,
     /// Data truncation (P6004).
     DataTruncation = 6004,
+    /// Database columns differ from the schema (P6005).
+    SchemaDrift = 6005,
 
     // Configuration errors (7xxx)
     /// Invalid configuration (P7001).
@@ -165,11 +176,13 @@ impl ErrorCode {
             Self::ForeignKeyConstraint => "Foreign key constraint violation",
             Self::CheckConstraint => "Check constraint violation",
             Self::NotNullConstraint => "Not null constraint violation",
+            Self::InvalidTransition => "Invalid state transition",
             Self::ConnectionFailed => "Database connection failed",
             Self::PoolExhausted => "Connection pool exhausted",
             Self::ConnectionTimeout => "Connection timeout",
             Self::AuthenticationFailed => "Authentication failed",
             Self::SslError => "SSL/TLS error",
+            Self::PoolTimeout => "Connection pool checkout timed out",
             Self::TransactionFailed => "Transaction failed",
             Self::Deadlock => "Deadlock detected",
             Self::SerializationFailure => "Serialization failure",
@@ -179,10 +192,12 @@ impl ErrorCode {
             Self::InvalidParameter => "Invalid parameter",
             Self::QueryTooComplex => "Query too complex",
             Self::DatabaseError => "Database error",
+            Self::QuotaExceeded => "Tenant quota exceeded",
             Self::InvalidDataType => "Invalid data type",
             Self::SerializationError => "Serialization error",
             Self::DeserializationError => "Deserialization error",
             Self::DataTruncation => "Data truncation",
+            Self::SchemaDrift => "Schema drift",
             Self::InvalidConfiguration => "Invalid configuration",
             Self::MissingConfiguration => "Missing configuration",
             Self::InvalidConnectionString => "Invalid connection string",
@@ -245,8 +260,22 @@ pub struct ErrorContext {
     pub help: Option<String>,
     /// Related errors.
     pub related: Vec<String>,
+    /// The connection pool's state (if the error came from a checkout).
+    pub pool: Option<PoolSnapshot>,
 }
 
+/// The context of an error that has none.
+static EMPTY_CONTEXT: ErrorContext = ErrorContext {
+    operation: None,
+    model: None,
+    field: None,
+    sql: None,
+    suggestions: Vec::new(),
+    help: None,
+    related: Vec::new(),
+    pool: None,
+};
+
 impl ErrorContext {
     /// Create new empty context.
     pub fn new() -> Self {
@@ -303,8 +332,8 @@ pub struct QueryError {
     pub code: ErrorCode,
     /// The error message.
     pub message: String,
-    /// Additional context.
-    pub context: ErrorContext,
+    /// Additional context, boxed to keep `QueryResult` small.
+    pub context: Option<Box<ErrorContext>>,
     /// The source error (if any).
     #[source]
     pub source: Option<Box<dyn std::error::Error + Send + Sync>>,
@@ -322,20 +351,30 @@ impl QueryError {
         Self {
             code,
             message: message.into(),
-            context: ErrorContext::default(),
+            context: None,
             source: None,
         }
     }
 
+    /// The error's context; empty if none was added.
+    pub fn context(&self) -> &ErrorContext {
+        self.context.as_deref().unwrap_or(&EMPTY_CONTEXT)
+    }
+
+    /// The error's context, created on first use.
+    pub fn context_mut(&mut self) -> &mut ErrorContext {
+        self.context.get_or_insert_with(Box::default)
+    }
+
     /// Add context about the operation.
     pub fn with_context(mut self, operation: impl Into<String>) -> Self {
-        self.context.operation = Some(operation.into());
+        self.context_mut().operation = Some(operation.into());
         self
     }
 
     /// Add a suggestion for fixing the error.
     pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
-        self.context.suggestions.push(Suggestion::new(suggestion));
+        self.context_mut().suggestions.push(Suggestion::new(suggestion));
         self
     }
 
@@ -345,7 +384,7 @@ impl QueryError {
         text: impl Into<String>,
         code: impl Into<String>,
     ) -> Self {
-        self.context
+        self.context_mut()
             .suggestions
             .push(Suggestion::new(text).with_code(code));
         self
@@ -353,25 +392,25 @@ impl QueryError {
 
     /// Add help text.
     pub fn with_help(mut self, help: impl Into<String>) -> Self {
-        self.context.help = Some(help.into());
+        self.context_mut().help = Some(help.into());
         self
     }
 
     /// Set the model.
     pub fn with_model(mut self, model: impl Into<String>) -> Self {
-        self.context.model = Some(model.into());
+        self.context_mut().model = Some(model.into());
         self
     }
 
     /// Set the field.
     pub fn with_field(mut self, field: impl Into<String>) -> Self {
-        self.context.field = Some(field.into());
+        self.context_mut().field = Some(field.into());
         self
     }
 
     /// Set the SQL query.
     pub fn with_sql(mut self, sql: impl Into<String>) -> Self {
-        self.context.sql = Some(sql.into());
+        self.context_mut().sql = Some(sql.into());
         self
     }
 
@@ -524,6 +563,24 @@ impl QueryError {
         .with_help("Consider using connection pooling middleware like PgBouncer for high-traffic applications")
     }
 
+    /// Create a pool timeout error, carrying the pool's state when the
+    /// checkout gave up.
+    pub fn pool_timeout(timeout: Duration, pool: PoolSnapshot) -> Self {
+        let mut err = Self::new(
+            ErrorCode::PoolTimeout,
+            format!(
+                "Timed out after {}ms waiting for a pooled connection ({})",
+                timeout.as_millis(),
+                pool
+            ),
+        )
+        .with_suggestion("Increase max_connections if the pool is saturated")
+        .with_suggestion("Increase the acquire timeout if waits are long but bounded")
+        .with_suggestion("Check for connections held across slow work or never released");
+        err.context_mut().pool = Some(pool);
+        err
+    }
+
     /// Create an authentication error.
     pub fn authentication_failed(message: impl Into<String>) -> Self {
         let message = message.into();
@@ -548,6 +605,43 @@ impl QueryError {
         .with_help("Consider paginating large result sets")
     }
 
+    /// Create a tenant quota error.
+    pub fn quota_exceeded(tenant_id: impl Into<String>, resource: impl Into<String>) -> Self {
+        Self::new(
+            ErrorCode::QuotaExceeded,
+            format!(
+                "Tenant '{}' exceeded its {} quota",
+                tenant_id.into(),
+                resource.into()
+            ),
+        )
+        .with_suggestion("Retry after the tenant's quota window resets")
+    }
+
+    /// Create a state machine transition error.
+    pub fn invalid_transition(
+        model: impl Into<String>,
+        field: impl Into<String>,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        let model = model.into();
+        let field = field.into();
+        Self::new(
+            ErrorCode::InvalidTransition,
+            format!(
+                "Cannot transition {}.{} from '{}' to '{}'",
+                model,
+                field,
+                from.into(),
+                to.into()
+            ),
+        )
+        .with_model(&model)
+        .with_field(&field)
+        .with_suggestion("Check the transitions declared with @stateMachine")
+    }
+
     /// Create a transaction error.
     pub fn transaction(message: impl Into<String>) -> Self {
         let message = message.into();
@@ -641,16 +735,32 @@ impl QueryError {
     pub fn is_timeout(&self) -> bool {
         matches!(
             self.code,
-            ErrorCode::QueryTimeout | ErrorCode::ConnectionTimeout
+            ErrorCode::QueryTimeout | ErrorCode::ConnectionTimeout | ErrorCode::PoolTimeout
         )
     }
 
+    /// Check if this is a tenant quota error.
+    pub fn is_quota_exceeded(&self) -> bool {
+        self.code == ErrorCode::QuotaExceeded
+    }
+
+    /// Check if this is a state machine transition error.
+    pub fn is_invalid_transition(&self) -> bool {
+        self.code == ErrorCode::InvalidTransition
+    }
+
+    /// Check if this is a schema drift error.
+    pub fn is_schema_drift(&self) -> bool {
+        self.code == ErrorCode::SchemaDrift
+    }
+
     /// Check if this is a connection error.
     pub fn is_connection_error(&self) -> bool {
         matches!(
             self.code,
             ErrorCode::ConnectionFailed
                 | ErrorCode::PoolExhausted
+                | ErrorCode::PoolTimeout
                 | ErrorCode::ConnectionTimeout
                 | ErrorCode::AuthenticationFailed
                 | ErrorCode::SslError
@@ -663,6 +773,7 @@ impl QueryError {
             self.code,
             ErrorCode::ConnectionTimeout
                 | ErrorCode::PoolExhausted
+                | ErrorCode::PoolTimeout
                 | ErrorCode::QueryTimeout
                 | ErrorCode::Deadlock
                 | ErrorCode::SerializationFailure
@@ -689,18 +800,18 @@ impl QueryError {
         output.push_str(&format!("Error [{}]: {}\n", self.code.code(), self.message));
 
         // Context
-        if let Some(ref op) = self.context.operation {
+        if let Some(ref op) = self.context().operation {
             output.push_str(&format!("  → While: {}\n", op));
         }
-        if let Some(ref model) = self.context.model {
+        if let Some(ref model) = self.context().model {
             output.push_str(&format!("  → Model: {}\n", model));
         }
-        if let Some(ref field) = self.context.field {
+        if let Some(ref field) = self.context().field {
             output.push_str(&format!("  → Field: {}\n", field));
         }
 
         // SQL (truncated if too long)
-        if let Some(ref sql) = self.context.sql {
+        if let Some(ref sql) = self.context().sql {
             let sql_display = if sql.len() > 200 {
                 format!("{}...", &sql[..200])
             } else {
@@ -710,9 +821,9 @@ impl QueryError {
         }
 
         // Suggestions
-        if !self.context.suggestions.is_empty() {
+        if !self.context().suggestions.is_empty() {
             output.push_str("\nSuggestions:\n");
-            for (i, suggestion) in self.context.suggestions.iter().enumerate() {
+            for (i, suggestion) in self.context().suggestions.iter().enumerate() {
                 output.push_str(&format!("  {}. {}\n", i + 1, suggestion.text));
                 if let Some(ref code) = suggestion.code {
                     output.push_str(&format!(
@@ -724,7 +835,7 @@ impl QueryError {
         }
 
         // Help
-        if let Some(ref help) = self.context.help {
+        if let Some(ref help) = self.context().help {
             output.push_str(&format!("\nHelp: {}\n", help));
         }
 
@@ -746,20 +857,20 @@ impl QueryError {
         ));
 
         // Context (dim)
-        if let Some(ref op) = self.context.operation {
+        if let Some(ref op) = self.context().operation {
             output.push_str(&format!("  \x1b[2m→ While:\x1b[0m {}\n", op));
         }
-        if let Some(ref model) = self.context.model {
+        if let Some(ref model) = self.context().model {
             output.push_str(&format!("  \x1b[2m→ Model:\x1b[0m {}\n", model));
         }
-        if let Some(ref field) = self.context.field {
+        if let Some(ref field) = self.context().field {
             output.push_str(&format!("  \x1b[2m→ Field:\x1b[0m {}\n", field));
         }
 
         // Suggestions (yellow)
-        if !self.context.suggestions.is_empty() {
+        if !self.context().suggestions.is_empty() {
             output.push_str("\n\x1b[1;33mSuggestions:\x1b[0m\n");
-            for (i, suggestion) in self.context.suggestions.iter().enumerate() {
+            for (i, suggestion) in self.context().suggestions.iter().enumerate() {
                 output.push_str(&format!(
                     "  \x1b[33m{}.\x1b[0m {}\n",
                     i + 1,
@@ -775,7 +886,7 @@ impl QueryError {
         }
 
         // Help (cyan)
-        if let Some(ref help) = self.context.help {
+        if let Some(ref help) = self.context().help {
             output.push_str(&format!("\n\x1b[1;36mHelp:\x1b[0m {}\n", help));
         }
 
@@ -832,15 +943,15 @@ mod tests {
         let err = QueryError::not_found("User");
         assert!(err.is_not_found());
         assert!(err.message.contains("User"));
-        assert!(!err.context.suggestions.is_empty());
+        assert!(!err.context().suggestions.is_empty());
     }
 
     #[test]
     fn test_unique_violation_error() {
         let err = QueryError::unique_violation("User", "email");
         assert!(err.is_constraint_violation());
-        assert_eq!(err.context.model, Some("User".to_string()));
-        assert_eq!(err.context.field, Some("email".to_string()));
+        assert_eq!(err.context().model, Some("User".to_string()));
+        assert_eq!(err.context().field, Some("email".to_string()));
     }
 
     #[test]
@@ -857,10 +968,10 @@ mod tests {
             .with_suggestion("Use a different query method");
 
         assert_eq!(
-            err.context.operation,
+            err.context().operation,
             Some("Finding user by email".to_string())
         );
-        assert!(err.context.suggestions.len() >= 2); // Original + new one
+        assert!(err.context().suggestions.len() >= 2); // Original + new one
     }
 
     #[test]
@@ -878,6 +989,26 @@ mod tests {
         assert!(QueryError::pool_exhausted(10).is_connection_error());
     }
 
+    #[test]
+    fn test_pool_timeout_error() {
+        let pool = PoolSnapshot {
+            max_size: 10,
+            size: 10,
+            waiting: 3,
+            peak_waiting: 5,
+            ..Default::default()
+        };
+        let err = QueryError::pool_timeout(Duration::from_secs(5), pool.clone());
+
+        assert_eq!(err.code, ErrorCode::PoolTimeout);
+        assert!(err.is_timeout());
+        assert!(err.is_retryable());
+        assert!(err.is_connection_error());
+        assert!(err.message.contains("5000ms"));
+        assert!(err.message.contains("10/10 connections open, 10 in use"));
+        assert_eq!(err.context().pool, Some(pool));
+    }
+
     #[test]
     fn test_display_full() {
         let err = QueryError::unique_violation("User", "email").with_context("Creating new user");
@@ -905,7 +1036,7 @@ mod tests {
         );
 
         assert_eq!(err.code, ErrorCode::InvalidParameter);
-        assert_eq!(err.context.field, Some("email".to_string()));
+        assert_eq!(err.context().field, Some("email".to_string()));
     }
 
     #[test]
@@ -913,7 +1044,7 @@ mod tests {
         let err = QueryError::not_found("User")
             .with_code_suggestion("Try this instead", "client.user().find_first()");
 
-        let suggestion = err.context.suggestions.last().unwrap();
+        let suggestion = err.context().suggestions.last().unwrap();
         assert!(suggestion.code.is_some());
     }
 }
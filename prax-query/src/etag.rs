@@ -0,0 +1,229 @@
+//! Content hashes of query results for HTTP caching.
+//!
+//! An [`ETag`] is a stable hash of records as serialized, so the same rows
+//! give the same tag across requests, processes and restarts. The
+//! `exec_with_etag` variants of the find operations return the results as a
+//! [`Tagged`] value carrying a tag for the whole result and one per row,
+//! letting a handler answer `If-None-Match` with `304 Not Modified` without
+//! rendering the response:
+//!
+//! ```rust,ignore
+//! let users = client.user().find_many().exec_with_etag().await?;
+//! if users.etag().matches(request.header("If-None-Match").unwrap_or_default()) {
+//!     return Response::not_modified();
+//! }
+//! Response::ok(users.etag().to_string(), Json(users.into_inner()))
+//! ```
+//!
+//! Tags are weak (`W/"..."`): they identify the records, not the bytes of a
+//! particular response body rendered from them.
+
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+
+use serde::Serialize;
+
+/// FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a prime.
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// A stable content hash, formatted as a weak HTTP entity tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ETag(u64);
+
+impl ETag {
+    /// Hash a value as serialized to JSON.
+    pub fn of<T: Serialize + ?Sized>(value: &T) -> Self {
+        let mut hasher = Fnv(FNV_OFFSET);
+        // A value failing to serialize hashes the output written up to the
+        // failure, which is just as stable
+        let _ = serde_json::to_writer(&mut hasher, value);
+        Self(hasher.0)
+    }
+
+    /// Combine the tags of rows, in order, into the tag of a result set.
+    pub fn combine<'a>(rows: impl IntoIterator<Item = &'a ETag>) -> Self {
+        let mut hasher = Fnv(FNV_OFFSET);
+        for row in rows {
+            hasher.update(&row.0.to_le_bytes());
+        }
+        Self(hasher.0)
+    }
+
+    /// Get the hash.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Check whether an `If-None-Match` header value lists this tag, so the
+    /// client's copy is current.
+    ///
+    /// Tags are compared weakly, ignoring `W/` prefixes, and `*` matches any
+    /// tag.
+    pub fn matches(&self, if_none_match: &str) -> bool {
+        let opaque = format!("\"{:016x}\"", self.0);
+        if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == opaque)
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "W/\"{:016x}\"", self.0)
+    }
+}
+
+/// A 64-bit FNV-1a hasher fed as a writer.
+struct Fnv(u64);
+
+impl Fnv {
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+impl io::Write for Fnv {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A query result with its content hash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tagged<T> {
+    value: T,
+    etag: ETag,
+    row_etags: Vec<ETag>,
+}
+
+impl<T: Serialize> Tagged<T> {
+    /// Tag a single value.
+    pub fn new(value: T) -> Self {
+        let etag = ETag::of(&value);
+        Self {
+            value,
+            etag,
+            row_etags: Vec::new(),
+        }
+    }
+}
+
+impl<M: Serialize> Tagged<Vec<M>> {
+    /// Tag a result set, hashing each row and combining the row tags.
+    pub fn rows(rows: Vec<M>) -> Self {
+        let row_etags: Vec<ETag> = rows.iter().map(ETag::of).collect();
+        Self {
+            etag: ETag::combine(&row_etags),
+            value: rows,
+            row_etags,
+        }
+    }
+}
+
+impl<T> Tagged<T> {
+    /// Get the tag of the whole result.
+    pub fn etag(&self) -> ETag {
+        self.etag
+    }
+
+    /// Get the tags of the rows of a result set, in order.
+    ///
+    /// Empty for single values, which are tagged by [`etag`](Self::etag).
+    pub fn row_etags(&self) -> &[ETag] {
+        &self.row_etags
+    }
+
+    /// Check whether an `If-None-Match` header value lists the result's tag.
+    pub fn is_not_modified(&self, if_none_match: Option<&str>) -> bool {
+        if_none_match.is_some_and(|header| self.etag.matches(header))
+    }
+
+    /// Get the result.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Tagged<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct User {
+        id: i32,
+        email: &'static str,
+    }
+
+    fn users() -> Vec<User> {
+        vec![
+            User {
+                id: 1,
+                email: "alice@example.com",
+            },
+            User {
+                id: 2,
+                email: "bob@example.com",
+            },
+        ]
+    }
+
+    #[test]
+    fn test_etag_is_stable() {
+        let tag = ETag::of(&serde_json::json!({"id": 1}));
+        // FNV-1a of `{"id":1}`, fixed across runs and builds
+        assert_eq!(tag.to_string(), "W/\"d51b1db3d1dff09b\"");
+        assert_eq!(ETag::of("a"), ETag::of("a"));
+        assert_ne!(ETag::of("a"), ETag::of("b"));
+    }
+
+    #[test]
+    fn test_tagged_rows() {
+        let tagged = Tagged::rows(users());
+        assert_eq!(tagged.len(), 2);
+        assert_eq!(tagged.row_etags()[1], ETag::of(&tagged[1]));
+        assert_eq!(tagged.etag(), Tagged::rows(users()).etag());
+
+        let mut changed = users();
+        changed[1].email = "robert@example.com";
+        let changed = Tagged::rows(changed);
+        assert_eq!(changed.row_etags()[0], tagged.row_etags()[0]);
+        assert_ne!(changed.etag(), tagged.etag());
+
+        let single = Tagged::new(None::<User>);
+        assert!(single.row_etags().is_empty());
+        assert_eq!(single.etag(), ETag::of(&serde_json::Value::Null));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let tagged = Tagged::rows(users());
+        let tag = tagged.etag().to_string();
+        let strong = tag.trim_start_matches("W/");
+
+        assert!(tagged.is_not_modified(Some(&tag)));
+        assert!(tagged.is_not_modified(Some(strong)));
+        assert!(tagged.is_not_modified(Some(&format!("\"other\", {}", tag))));
+        assert!(tagged.is_not_modified(Some("*")));
+        assert!(!tagged.is_not_modified(Some("\"other\"")));
+        assert!(!tagged.is_not_modified(None));
+    }
+}
@@ -0,0 +1,579 @@
+//! Query plans from `EXPLAIN`.
+//!
+//! The find and count operations have `explain` and `explain_analyze`
+//! variants returning the database's plan for the query instead of its rows.
+//! Each database reports plans in its own format, so they are parsed into a
+//! common [`QueryPlan`] tree:
+//!
+//! | Database   | `explain`                    | `explain_analyze`                 |
+//! |------------|------------------------------|-----------------------------------|
+//! | PostgreSQL | `EXPLAIN (FORMAT JSON)`      | `EXPLAIN (ANALYZE, FORMAT JSON)`  |
+//! | MySQL      | `EXPLAIN FORMAT=JSON`        | `EXPLAIN ANALYZE` (tree format)   |
+//! | SQLite     | `EXPLAIN QUERY PLAN`         | not supported                     |
+//!
+//! `explain_analyze` runs the query to measure it, so its side effects
+//! happen; it is only offered on reads.
+//!
+//! ```rust,ignore
+//! let plan = client
+//!     .user()
+//!     .find_many()
+//!     .r#where(user::email::ends_with("@example.com"))
+//!     .explain()
+//!     .await?;
+//!
+//! for table in plan.full_scans() {
+//!     println!("full scan on {}", table);
+//! }
+//! println!("{}", plan);
+//! ```
+//!
+//! [`SlowQueryPlanMiddleware`](crate::middleware::SlowQueryPlanMiddleware)
+//! captures plans of slow queries automatically.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::error::{QueryError, QueryResult};
+use crate::sql::DatabaseType;
+
+/// A step of a query plan.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PlanNode {
+    /// The operation, as the database names it (e.g. `Seq Scan`).
+    pub operation: String,
+    /// The table the step reads.
+    pub relation: Option<String>,
+    /// The index the step reads through.
+    pub index: Option<String>,
+    /// Whether the step reads every row of its table.
+    pub full_scan: bool,
+    /// The planner's cost estimate, in the database's own units.
+    pub cost: Option<f64>,
+    /// The planner's estimate of the rows the step produces.
+    pub estimated_rows: Option<f64>,
+    /// The rows the step produced (analyzed plans only).
+    pub actual_rows: Option<f64>,
+    /// The time the step took in milliseconds (analyzed plans only).
+    pub actual_time_ms: Option<f64>,
+    /// The steps feeding this one.
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            ..Self::default()
+        }
+    }
+
+    fn walk<'a>(&'a self, nodes: &mut Vec<&'a PlanNode>) {
+        nodes.push(self);
+        for child in &self.children {
+            child.walk(nodes);
+        }
+    }
+
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}-> {}", "", self.operation, indent = depth * 2)?;
+        if let Some(relation) = &self.relation {
+            write!(f, " on {}", relation)?;
+        }
+        if let Some(index) = &self.index {
+            write!(f, " using {}", index)?;
+        }
+        if let Some(cost) = self.cost {
+            write!(f, " (cost={:.2}", cost)?;
+            if let Some(rows) = self.estimated_rows {
+                write!(f, " rows={}", rows)?;
+            }
+            write!(f, ")")?;
+        }
+        if let Some(time) = self.actual_time_ms {
+            write!(f, " (actual time={:.3}ms", time)?;
+            if let Some(rows) = self.actual_rows {
+                write!(f, " rows={}", rows)?;
+            }
+            write!(f, ")")?;
+        }
+        writeln!(f)?;
+        for child in &self.children {
+            child.fmt_tree(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// The plan a database chose for a query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryPlan {
+    /// The top-level steps; most plans have one.
+    pub roots: Vec<PlanNode>,
+    /// Whether the query ran to measure the plan.
+    pub analyzed: bool,
+    /// Time spent planning in milliseconds, where reported.
+    pub planning_time_ms: Option<f64>,
+    /// Time spent executing in milliseconds (analyzed plans only).
+    pub execution_time_ms: Option<f64>,
+    /// The plan as the database returned it.
+    pub raw: String,
+}
+
+impl QueryPlan {
+    /// Build the statement explaining `sql` on `database`.
+    pub fn explain_sql(database: DatabaseType, sql: &str, analyze: bool) -> QueryResult<String> {
+        match (database, analyze) {
+            (DatabaseType::PostgreSQL, false) => Ok(format!("EXPLAIN (FORMAT JSON) {}", sql)),
+            (DatabaseType::PostgreSQL, true) => {
+                Ok(format!("EXPLAIN (ANALYZE, FORMAT JSON) {}", sql))
+            }
+            (DatabaseType::MySQL, false) => Ok(format!("EXPLAIN FORMAT=JSON {}", sql)),
+            (DatabaseType::MySQL, true) => Ok(format!("EXPLAIN ANALYZE {}", sql)),
+            (DatabaseType::SQLite, false) => Ok(format!("EXPLAIN QUERY PLAN {}", sql)),
+            (DatabaseType::SQLite, true) => Err(QueryError::unsupported(
+                "SQLite does not support EXPLAIN ANALYZE",
+            )),
+            (DatabaseType::MSSQL, _) => Err(QueryError::unsupported(
+                "Query plans are not supported for MSSQL",
+            )),
+        }
+    }
+
+    /// Parse the output of PostgreSQL's `EXPLAIN (FORMAT JSON)`.
+    pub fn from_postgres_json(raw: &str) -> QueryResult<Self> {
+        let json: JsonValue = serde_json::from_str(raw).map_err(invalid_plan)?;
+        let output = json
+            .as_array()
+            .and_then(|plans| plans.first())
+            .unwrap_or(&json);
+        let plan = output
+            .get("Plan")
+            .ok_or_else(|| invalid_plan("missing `Plan`"))?;
+        let execution_time_ms = output.get("Execution Time").and_then(JsonValue::as_f64);
+
+        Ok(Self {
+            roots: vec![postgres_node(plan)],
+            analyzed: execution_time_ms.is_some(),
+            planning_time_ms: output.get("Planning Time").and_then(JsonValue::as_f64),
+            execution_time_ms,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// Parse the output of MySQL's `EXPLAIN FORMAT=JSON`.
+    pub fn from_mysql_json(raw: &str) -> QueryResult<Self> {
+        let json: JsonValue = serde_json::from_str(raw).map_err(invalid_plan)?;
+        let block = json
+            .get("query_block")
+            .ok_or_else(|| invalid_plan("missing `query_block`"))?;
+
+        let mut root = PlanNode::new("Query block");
+        root.cost = block
+            .get("cost_info")
+            .and_then(|c| c.get("query_cost"))
+            .and_then(number);
+        root.children = mysql_children(block);
+
+        Ok(Self {
+            roots: vec![root],
+            analyzed: false,
+            planning_time_ms: None,
+            execution_time_ms: None,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// Parse the tree output of MySQL's `EXPLAIN ANALYZE`.
+    ///
+    /// Each line is a step, `->` followed by its operation, estimates and
+    /// measurements, indented four spaces per level.
+    pub fn from_mysql_tree(raw: &str) -> QueryResult<Self> {
+        // Open steps by depth, each waiting for its children
+        let mut stack: Vec<(usize, PlanNode)> = Vec::new();
+        let mut roots = Vec::new();
+
+        for line in raw.lines() {
+            let Some(arrow) = line.find("->") else {
+                continue;
+            };
+            let depth = arrow / 4;
+            let node = mysql_tree_node(line[arrow + 2..].trim());
+
+            while stack.last().is_some_and(|(d, _)| *d >= depth) {
+                let (_, done) = stack.pop().unwrap();
+                attach(&mut stack, &mut roots, done);
+            }
+            stack.push((depth, node));
+        }
+        while let Some((_, done)) = stack.pop() {
+            attach(&mut stack, &mut roots, done);
+        }
+
+        if roots.is_empty() {
+            return Err(invalid_plan("no plan steps"));
+        }
+        let execution_time_ms = roots.first().and_then(|r| r.actual_time_ms);
+        Ok(Self {
+            roots,
+            analyzed: true,
+            planning_time_ms: None,
+            execution_time_ms,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// Parse the rows of SQLite's `EXPLAIN QUERY PLAN`, given as
+    /// `(id, parent, detail)`.
+    pub fn from_sqlite_rows(rows: impl IntoIterator<Item = (i64, i64, String)>) -> Self {
+        let rows: Vec<_> = rows.into_iter().collect();
+        let raw = rows
+            .iter()
+            .map(|(id, parent, detail)| format!("{}|{}|{}", id, parent, detail))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        fn children(rows: &[(i64, i64, String)], parent: i64) -> Vec<PlanNode> {
+            rows.iter()
+                .filter(|(_, p, _)| *p == parent)
+                .map(|(id, _, detail)| PlanNode {
+                    children: children(rows, *id),
+                    ..sqlite_node(detail)
+                })
+                .collect()
+        }
+
+        Self {
+            roots: children(&rows, 0),
+            analyzed: false,
+            planning_time_ms: None,
+            execution_time_ms: None,
+            raw,
+        }
+    }
+
+    /// Get every step of the plan, depth first.
+    pub fn nodes(&self) -> Vec<&PlanNode> {
+        let mut nodes = Vec::new();
+        for root in &self.roots {
+            root.walk(&mut nodes);
+        }
+        nodes
+    }
+
+    /// Get the tables the plan reads in full.
+    pub fn full_scans(&self) -> Vec<&str> {
+        self.nodes()
+            .into_iter()
+            .filter(|n| n.full_scan)
+            .filter_map(|n| n.relation.as_deref())
+            .collect()
+    }
+
+    /// Get the indexes the plan reads through.
+    pub fn indexes(&self) -> Vec<&str> {
+        self.nodes()
+            .into_iter()
+            .filter_map(|n| n.index.as_deref())
+            .collect()
+    }
+
+    /// Get the planner's cost estimate for the whole query.
+    pub fn total_cost(&self) -> Option<f64> {
+        self.roots.first().and_then(|r| r.cost)
+    }
+}
+
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for root in &self.roots {
+            root.fmt_tree(f, 0)?;
+        }
+        if let Some(time) = self.planning_time_ms {
+            writeln!(f, "Planning time: {:.3}ms", time)?;
+        }
+        if let Some(time) = self.execution_time_ms {
+            writeln!(f, "Execution time: {:.3}ms", time)?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_plan(reason: impl fmt::Display) -> QueryError {
+    QueryError::deserialization(format!("Invalid query plan: {}", reason))
+}
+
+/// Read a number MySQL may report as a string.
+fn number(value: &JsonValue) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+fn postgres_node(plan: &JsonValue) -> PlanNode {
+    let text = |key: &str| {
+        plan.get(key)
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+    };
+    let float = |key: &str| plan.get(key).and_then(JsonValue::as_f64);
+    let operation = text("Node Type").unwrap_or_default();
+
+    PlanNode {
+        full_scan: operation == "Seq Scan",
+        relation: text("Relation Name"),
+        index: text("Index Name"),
+        cost: float("Total Cost"),
+        estimated_rows: float("Plan Rows"),
+        actual_rows: float("Actual Rows"),
+        actual_time_ms: float("Actual Total Time"),
+        children: plan
+            .get("Plans")
+            .and_then(JsonValue::as_array)
+            .map(|plans| plans.iter().map(postgres_node).collect())
+            .unwrap_or_default(),
+        operation,
+    }
+}
+
+/// Read the steps nested in a MySQL query block or operation.
+fn mysql_children(block: &JsonValue) -> Vec<PlanNode> {
+    let Some(object) = block.as_object() else {
+        return Vec::new();
+    };
+    let mut nodes = Vec::new();
+    for (key, value) in object {
+        let operation = match key.as_str() {
+            "table" => {
+                nodes.push(mysql_table(value));
+                continue;
+            }
+            "nested_loop" => {
+                let mut node = PlanNode::new("Nested loop");
+                node.children = value
+                    .as_array()
+                    .map(|steps| steps.iter().flat_map(mysql_children).collect())
+                    .unwrap_or_default();
+                nodes.push(node);
+                continue;
+            }
+            "ordering_operation" => "Sort",
+            "grouping_operation" => "Group",
+            "duplicates_removal" => "Distinct",
+            "windowing" => "Window",
+            "union_result" => "Union",
+            _ => continue,
+        };
+        let mut node = PlanNode::new(operation);
+        node.children = mysql_children(value);
+        nodes.push(node);
+    }
+    nodes
+}
+
+fn mysql_table(table: &JsonValue) -> PlanNode {
+    let access = table
+        .get("access_type")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default();
+    let operation = match access {
+        "ALL" => "Full scan",
+        "index" => "Index scan",
+        "range" => "Index range scan",
+        "ref" | "eq_ref" | "ref_or_null" | "const" | "system" => "Index lookup",
+        _ => access,
+    };
+
+    let mut node = PlanNode::new(operation);
+    node.full_scan = access == "ALL";
+    node.relation = table
+        .get("table_name")
+        .and_then(JsonValue::as_str)
+        .map(str::to_string);
+    node.index = table
+        .get("key")
+        .and_then(JsonValue::as_str)
+        .map(str::to_string);
+    node.cost = table
+        .get("cost_info")
+        .and_then(|c| c.get("prefix_cost"))
+        .and_then(number);
+    node.estimated_rows = table.get("rows_examined_per_scan").and_then(number);
+    node
+}
+
+/// Parse a step of MySQL's tree format, such as
+/// `Table scan on users  (cost=1.25 rows=10) (actual time=0.04..0.05 rows=10 loops=1)`.
+fn mysql_tree_node(step: &str) -> PlanNode {
+    let operation = step.split("  (").next().unwrap_or(step).trim();
+    let mut node = PlanNode::new(operation);
+
+    // `name` in `... on name` or `... using name`, up to the next space
+    let word_after = |marker: &str| {
+        operation.find(marker).map(|i| {
+            operation[i + marker.len()..]
+                .split([' ', '('])
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+    };
+    node.relation = word_after(" on ");
+    node.index = word_after(" using ");
+    node.full_scan = operation.starts_with("Table scan on ");
+
+    // `key=value` pairs of the estimate and measurement groups
+    let value_of = |group: &str, key: &str| {
+        step.find(group).and_then(|i| {
+            let rest = &step[i + group.len()..];
+            let rest = &rest[..rest.find(')').unwrap_or(rest.len())];
+            rest.split(' ')
+                .find_map(|pair| pair.strip_prefix(key))
+                .map(str::to_string)
+        })
+    };
+    node.cost = value_of("(cost=", "").and_then(|v| v.parse().ok());
+    node.estimated_rows = value_of("(cost=", "rows=").and_then(|v| v.parse().ok());
+    node.actual_time_ms = value_of("(actual time=", "")
+        .and_then(|v| v.split("..").last().and_then(|t| t.parse().ok()));
+    node.actual_rows = value_of("(actual time=", "rows=").and_then(|v| v.parse().ok());
+    node
+}
+
+fn attach(stack: &mut [(usize, PlanNode)], roots: &mut Vec<PlanNode>, node: PlanNode) {
+    match stack.last_mut() {
+        Some((_, parent)) => parent.children.push(node),
+        None => roots.push(node),
+    }
+}
+
+/// Parse a SQLite plan step, such as `SEARCH users USING INDEX idx (email=?)`.
+fn sqlite_node(detail: &str) -> PlanNode {
+    let mut node = PlanNode::new(detail);
+    let mut words = detail.split_whitespace();
+    let verb = words.next().unwrap_or_default();
+    if matches!(verb, "SCAN" | "SEARCH") {
+        // Older versions write `SCAN TABLE users`
+        let relation = match words.next() {
+            Some("TABLE") => words.next(),
+            relation => relation,
+        };
+        node.relation = relation.map(str::to_string);
+        node.index = detail
+            .split_once(" INDEX ")
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .map(str::to_string);
+        node.full_scan = verb == "SCAN" && node.index.is_none();
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postgres_plan() {
+        let plan = QueryPlan::from_postgres_json(
+            r#"[{"Plan": {
+                "Node Type": "Hash Join", "Total Cost": 52.5, "Plan Rows": 40,
+                "Actual Rows": 12, "Actual Total Time": 0.42,
+                "Plans": [
+                    {"Node Type": "Seq Scan", "Relation Name": "posts", "Total Cost": 30.0, "Plan Rows": 2000},
+                    {"Node Type": "Index Scan", "Relation Name": "users", "Index Name": "users_pkey", "Total Cost": 8.3}
+                ]},
+                "Planning Time": 0.1, "Execution Time": 0.5}]"#,
+        )
+        .unwrap();
+
+        assert!(plan.analyzed);
+        assert_eq!(plan.total_cost(), Some(52.5));
+        assert_eq!(plan.execution_time_ms, Some(0.5));
+        assert_eq!(plan.nodes().len(), 3);
+        assert_eq!(plan.full_scans(), vec!["posts"]);
+        assert_eq!(plan.indexes(), vec!["users_pkey"]);
+        assert!(
+            plan.to_string()
+                .contains("  -> Index Scan on users using users_pkey (cost=8.30)")
+        );
+    }
+
+    #[test]
+    fn test_mysql_plans() {
+        let plan = QueryPlan::from_mysql_json(
+            r#"{"query_block": {"select_id": 1, "cost_info": {"query_cost": "3.40"},
+                "ordering_operation": {"nested_loop": [
+                    {"table": {"table_name": "posts", "access_type": "ALL", "rows_examined_per_scan": 10,
+                               "cost_info": {"prefix_cost": "1.25"}}},
+                    {"table": {"table_name": "users", "access_type": "eq_ref", "key": "PRIMARY"}}
+                ]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(plan.total_cost(), Some(3.4));
+        let operations: Vec<_> = plan.nodes().iter().map(|n| n.operation.as_str()).collect();
+        assert_eq!(
+            operations,
+            [
+                "Query block",
+                "Sort",
+                "Nested loop",
+                "Full scan",
+                "Index lookup"
+            ]
+        );
+        assert_eq!(plan.full_scans(), vec!["posts"]);
+        assert_eq!(plan.indexes(), vec!["PRIMARY"]);
+
+        let plan = QueryPlan::from_mysql_tree(
+            "-> Nested loop inner join  (cost=4.50 rows=10) (actual time=0.10..0.30 rows=8 loops=1)\n    \
+                 -> Table scan on posts  (cost=1.25 rows=10) (actual time=0.04..0.05 rows=10 loops=1)\n    \
+                 -> Single-row index lookup on users using PRIMARY (id=posts.author_id)  (cost=0.25 rows=1) (actual time=0.01..0.01 rows=1 loops=10)\n",
+        )
+        .unwrap();
+        assert!(plan.analyzed);
+        assert_eq!(plan.roots.len(), 1);
+        assert_eq!(plan.roots[0].children.len(), 2);
+        assert_eq!(plan.total_cost(), Some(4.5));
+        assert_eq!(plan.execution_time_ms, Some(0.3));
+        assert_eq!(plan.full_scans(), vec!["posts"]);
+        assert_eq!(plan.roots[0].children[1].relation.as_deref(), Some("users"));
+        assert_eq!(plan.indexes(), vec!["PRIMARY"]);
+        assert_eq!(plan.roots[0].children[0].actual_rows, Some(10.0));
+    }
+
+    #[test]
+    fn test_sqlite_plan() {
+        let plan = QueryPlan::from_sqlite_rows([
+            (3, 0, "SCAN posts".to_string()),
+            (
+                5,
+                0,
+                "SEARCH users USING INTEGER PRIMARY KEY (rowid=?)".to_string(),
+            ),
+            (
+                7,
+                0,
+                "SEARCH tags USING INDEX tags_post_idx (post_id=?)".to_string(),
+            ),
+            (9, 7, "USE TEMP B-TREE FOR ORDER BY".to_string()),
+        ]);
+
+        assert_eq!(plan.roots.len(), 3);
+        assert_eq!(plan.roots[2].children.len(), 1);
+        assert_eq!(plan.full_scans(), vec!["posts"]);
+        assert_eq!(plan.indexes(), vec!["tags_post_idx"]);
+
+        assert!(
+            QueryPlan::explain_sql(DatabaseType::SQLite, "SELECT 1", true)
+                .unwrap_err()
+                .to_string()
+                .contains("EXPLAIN ANALYZE")
+        );
+        assert_eq!(
+            QueryPlan::explain_sql(DatabaseType::MySQL, "SELECT 1", false).unwrap(),
+            "EXPLAIN FORMAT=JSON SELECT 1"
+        );
+    }
+}
@@ -191,6 +191,11 @@ impl Point {
         Self::with_srid(longitude, latitude, 4326)
     }
 
+    /// Generate WKT (Well-Known Text).
+    pub fn to_wkt(&self) -> String {
+        format!("POINT({} {})", self.longitude, self.latitude)
+    }
+
     /// Generate PostGIS point.
     pub fn to_postgis(&self) -> String {
         if let Some(srid) = self.srid {
@@ -61,11 +61,18 @@
 //! let filter = Filter::IsNotNull("verified_at".into());
 //! ```
 
-use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::borrow::Cow;
 use tracing::debug;
 
+use crate::json::JsonFieldFilter;
+use crate::network::NetworkFilter;
+use crate::range::RangeFilter;
+use crate::spatial::SpatialFilter;
+use crate::ttl::ExpiryFilter;
+
+pub use crate::value::Value;
+
 /// A list of filter values for IN/NOT IN clauses.
 ///
 /// Uses `Vec<FilterValue>` for minimal Filter enum size (~64 bytes).
@@ -114,117 +121,8 @@ pub type FieldName = Cow<'static, str>;
 
 /// A filter value that can be used in comparisons.
 ///
-/// # Examples
-///
-/// ```rust
-/// use prax_query::FilterValue;
-///
-/// // From integers
-/// let val: FilterValue = 42.into();
-/// let val: FilterValue = 42i64.into();
-///
-/// // From strings
-/// let val: FilterValue = "hello".into();
-/// let val: FilterValue = String::from("world").into();
-///
-/// // From booleans
-/// let val: FilterValue = true.into();
-///
-/// // From floats
-/// let val: FilterValue = 3.14f64.into();
-///
-/// // Null value
-/// let val = FilterValue::Null;
-///
-/// // From vectors
-/// let val: FilterValue = vec![1, 2, 3].into();
-///
-/// // From Option (Some becomes value, None becomes Null)
-/// let val: FilterValue = Some(42).into();
-/// let val: FilterValue = Option::<i32>::None.into();
-/// assert!(val.is_null());
-/// ```
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum FilterValue {
-    /// Null value.
-    Null,
-    /// Boolean value.
-    Bool(bool),
-    /// Integer value.
-    Int(i64),
-    /// Float value.
-    Float(f64),
-    /// String value.
-    String(String),
-    /// JSON value.
-    Json(serde_json::Value),
-    /// List of values.
-    List(Vec<FilterValue>),
-}
-
-impl FilterValue {
-    /// Check if this is a null value.
-    pub fn is_null(&self) -> bool {
-        matches!(self, Self::Null)
-    }
-
-    /// Convert to SQL parameter placeholder.
-    pub fn to_sql_placeholder(&self, param_index: usize) -> String {
-        format!("${}", param_index)
-    }
-}
-
-impl From<bool> for FilterValue {
-    fn from(v: bool) -> Self {
-        Self::Bool(v)
-    }
-}
-
-impl From<i32> for FilterValue {
-    fn from(v: i32) -> Self {
-        Self::Int(v as i64)
-    }
-}
-
-impl From<i64> for FilterValue {
-    fn from(v: i64) -> Self {
-        Self::Int(v)
-    }
-}
-
-impl From<f64> for FilterValue {
-    fn from(v: f64) -> Self {
-        Self::Float(v)
-    }
-}
-
-impl From<String> for FilterValue {
-    fn from(v: String) -> Self {
-        Self::String(v)
-    }
-}
-
-impl From<&str> for FilterValue {
-    fn from(v: &str) -> Self {
-        Self::String(v.to_string())
-    }
-}
-
-impl<T: Into<FilterValue>> From<Vec<T>> for FilterValue {
-    fn from(v: Vec<T>) -> Self {
-        Self::List(v.into_iter().map(Into::into).collect())
-    }
-}
-
-impl<T: Into<FilterValue>> From<Option<T>> for FilterValue {
-    fn from(v: Option<T>) -> Self {
-        match v {
-            Some(v) => v.into(),
-            None => Self::Null,
-        }
-    }
-}
+/// Filters bind the same [`Value`] as every other query parameter.
+pub type FilterValue = Value;
 
 /// Scalar filter operations.
 #[derive(Debug, Clone, PartialEq)]
@@ -355,6 +253,18 @@ pub enum Filter {
     Or(Box<[Filter]>),
     /// Logical NOT of a filter.
     Not(Box<Filter>),
+
+    /// Range operator on a range column (see [`crate::range`]).
+    Range(FieldName, Box<RangeFilter>),
+
+    /// Network operator on an `inet` or `cidr` column (see [`crate::network`]).
+    Network(FieldName, Box<NetworkFilter>),
+    /// Spatial operator on a `geometry` or `geography` column (see [`crate::spatial`]).
+    Spatial(FieldName, Box<SpatialFilter>),
+    /// Operator on the value at a path inside a JSON column (see [`crate::json`]).
+    Json(FieldName, Box<JsonFieldFilter>),
+    /// Expiry of a TTL column after the current time (see [`crate::ttl`]).
+    Expiry(FieldName, Box<ExpiryFilter>),
 }
 
 impl Filter {
@@ -766,8 +676,7 @@ impl Filter {
                     .iter()
                     .map(|v| {
                         params.push(v.clone());
-                        param_idx += params.len();
-                        format!("${}", param_idx)
+                        format!("${}", param_idx + params.len())
                     })
                     .collect();
                 format!("{} IN ({})", col, placeholders.join(", "))
@@ -780,8 +689,7 @@ impl Filter {
                     .iter()
                     .map(|v| {
                         params.push(v.clone());
-                        param_idx += params.len();
-                        format!("${}", param_idx)
+                        format!("${}", param_idx + params.len())
                     })
                     .collect();
                 format!("{} NOT IN ({})", col, placeholders.join(", "))
@@ -824,7 +732,7 @@ impl Filter {
                 }
                 let parts: Vec<_> = filters
                     .iter()
-                    .map(|f| f.to_sql_with_params(param_idx + params.len(), params))
+                    .map(|f| f.to_sql_with_params(param_idx, params))
                     .collect();
                 format!("({})", parts.join(" AND "))
             }
@@ -834,7 +742,7 @@ impl Filter {
                 }
                 let parts: Vec<_> = filters
                     .iter()
-                    .map(|f| f.to_sql_with_params(param_idx + params.len(), params))
+                    .map(|f| f.to_sql_with_params(param_idx, params))
                     .collect();
                 format!("({})", parts.join(" OR "))
             }
@@ -842,6 +750,12 @@ impl Filter {
                 let inner = filter.to_sql_with_params(param_idx, params);
                 format!("NOT ({})", inner)
             }
+
+            Self::Range(col, filter) => filter.to_sql_with_params(col, param_idx, params),
+            Self::Network(col, filter) => filter.to_sql_with_params(col, param_idx, params),
+            Self::Spatial(col, filter) => filter.to_sql_with_params(col, param_idx, params),
+            Self::Json(col, filter) => filter.to_sql_with_params(col, param_idx, params),
+            Self::Expiry(col, filter) => filter.to_sql_with_params(col, param_idx, params),
         }
     }
 
@@ -1388,6 +1302,37 @@ mod tests {
         assert_eq!(params.len(), 2);
     }
 
+    #[test]
+    fn test_filter_nested_placeholders() {
+        let filter = Filter::or([
+            Filter::Lt("a".into(), FilterValue::Int(1)),
+            Filter::and([
+                Filter::Equals("a".into(), FilterValue::Int(1)),
+                Filter::Gt("b".into(), FilterValue::Int(2)),
+            ]),
+        ]);
+
+        let (sql, params) = filter.to_sql(0);
+        assert_eq!(sql, "(a < $1 OR (a = $2 AND b > $3))");
+        assert_eq!(params.len(), 3);
+
+        let (sql, _) = filter.to_sql(2);
+        assert_eq!(sql, "(a < $3 OR (a = $4 AND b > $5))");
+    }
+
+    #[test]
+    fn test_filter_in_placeholders() {
+        let filter = Filter::and([
+            Filter::Equals("a".into(), FilterValue::Int(1)),
+            Filter::In("b".into(), vec![FilterValue::Int(2), FilterValue::Int(3)]),
+            Filter::NotIn("c".into(), vec![FilterValue::Int(4), FilterValue::Int(5)]),
+        ]);
+
+        let (sql, params) = filter.to_sql(0);
+        assert_eq!(sql, "(a = $1 AND b IN ($2, $3) AND c NOT IN ($4, $5))");
+        assert_eq!(params.len(), 5);
+    }
+
     #[test]
     fn test_filter_or() {
         let f1 = Filter::Equals("status".into(), "active".into());
@@ -0,0 +1,565 @@
+//! Obfuscating integer ids for external APIs.
+//!
+//! Sequential integer keys leak how many records exist and invite
+//! enumeration when they appear in URLs. An [`IdCodec`] turns them into short
+//! opaque strings at the API boundary while queries keep using the integer
+//! keys. Two codecs are provided, compatible with the libraries of the same
+//! name in other languages:
+//!
+//! - [`Sqids`], the successor of Hashids, with a shuffled alphabet and an
+//!   optional blocklist of words that must not appear in ids
+//! - [`Hashids`], salted, for APIs that already issue Hashids
+//!
+//! Generated models with a single integer primary key get `encode_id` and
+//! `decode_id` helpers; ids that fail to decode are reported as
+//! [`QueryError::not_found`], so a tampered id looks the same as a missing
+//! record:
+//!
+//! ```rust,ignore
+//! use prax_query::id_codec::{IdCodec, Sqids};
+//!
+//! let codec = Sqids::new().min_length(8);
+//!
+//! // GET /users/{id}
+//! let id = user::decode_id(&codec, &path_id)?;
+//! let user = client.user().find_unique(user::id::equals(id)).exec().await?;
+//! let body = json!({ "id": user.public_id(&codec), "email": user.email });
+//! ```
+//!
+//! Codecs obfuscate ids; they do not encrypt them. Anyone who learns the
+//! alphabet (and salt) can decode them.
+
+use std::collections::HashSet;
+
+use crate::error::{QueryError, QueryResult};
+
+/// Converts integer ids to opaque strings and back.
+pub trait IdCodec: Send + Sync {
+    /// Encode an id.
+    fn encode(&self, id: u64) -> String;
+
+    /// Decode an id, or `None` if `encoded` wasn't produced by [`encode`](Self::encode).
+    fn decode(&self, encoded: &str) -> Option<u64>;
+
+    /// Decode an id of a `model` record, reporting failures as not found.
+    fn decode_or_not_found(&self, model: &str, encoded: &str) -> QueryResult<u64> {
+        self.decode(encoded)
+            .ok_or_else(|| QueryError::not_found(model))
+    }
+}
+
+const SQIDS_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The [Sqids](https://sqids.org) codec.
+///
+/// The default blocklist of the reference implementations is not bundled;
+/// pass words to [`blocklist`](Self::blocklist) to keep them out of ids.
+#[derive(Debug, Clone)]
+pub struct Sqids {
+    alphabet: Vec<char>,
+    min_length: usize,
+    blocklist: Vec<String>,
+}
+
+impl Default for Sqids {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sqids {
+    /// Create a codec with the default alphabet.
+    pub fn new() -> Self {
+        Self {
+            alphabet: sqids_shuffle(&SQIDS_ALPHABET.chars().collect::<Vec<_>>()),
+            min_length: 0,
+            blocklist: Vec::new(),
+        }
+    }
+
+    /// Create a codec with a custom alphabet of at least 3 distinct
+    /// single-byte characters.
+    pub fn with_alphabet(alphabet: &str) -> QueryResult<Self> {
+        let chars: Vec<char> = alphabet.chars().collect();
+        let distinct: HashSet<char> = chars.iter().copied().collect();
+        if chars.len() < 3 || distinct.len() != chars.len() || !alphabet.is_ascii() {
+            return Err(QueryError::invalid_input(
+                "alphabet",
+                "alphabet must have at least 3 distinct ASCII characters",
+            ));
+        }
+        Ok(Self {
+            alphabet: sqids_shuffle(&chars),
+            ..Self::new()
+        })
+    }
+
+    /// Pad ids to at least `min_length` characters.
+    pub fn min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Keep ids from containing any of `words`, ignoring case.
+    ///
+    /// Words shorter than 3 characters or using characters outside the
+    /// alphabet can't appear and are dropped.
+    pub fn blocklist(mut self, words: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let alphabet: HashSet<char> = self
+            .alphabet
+            .iter()
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        self.blocklist = words
+            .into_iter()
+            .map(|word| word.into().to_lowercase())
+            .filter(|word| word.len() >= 3 && word.chars().all(|c| alphabet.contains(&c)))
+            .collect();
+        self
+    }
+
+    /// Encode a list of numbers into one id.
+    pub fn encode_numbers(&self, numbers: &[u64]) -> String {
+        if numbers.is_empty() {
+            return String::new();
+        }
+        let mut increment = 0;
+        loop {
+            let id = self.encode_with_increment(numbers, increment);
+            // Past the alphabet length every offset has been tried
+            if increment >= self.alphabet.len() || !self.is_blocked(&id) {
+                return id;
+            }
+            increment += 1;
+        }
+    }
+
+    /// Decode an id into its list of numbers, or an empty list if it is
+    /// malformed.
+    pub fn decode_numbers(&self, id: &str) -> Vec<u64> {
+        let mut numbers = Vec::new();
+        let Some(prefix) = id.chars().next() else {
+            return numbers;
+        };
+        if !id.chars().all(|c| self.alphabet.contains(&c)) {
+            return numbers;
+        }
+
+        let offset = self.alphabet.iter().position(|&c| c == prefix).unwrap_or(0);
+        let mut alphabet = self.rotated(offset);
+        alphabet.reverse();
+
+        let mut rest: Vec<char> = id.chars().skip(1).collect();
+        while !rest.is_empty() {
+            let separator = alphabet[0];
+            let end = rest.iter().position(|&c| c == separator);
+            let chunk = &rest[..end.unwrap_or(rest.len())];
+            if chunk.is_empty() {
+                break;
+            }
+            match sqids_to_number(chunk, &alphabet[1..]) {
+                Some(number) => numbers.push(number),
+                None => return Vec::new(),
+            }
+            match end {
+                Some(end) => {
+                    alphabet = sqids_shuffle(&alphabet);
+                    rest.drain(..=end);
+                }
+                None => break,
+            }
+        }
+        numbers
+    }
+
+    fn rotated(&self, offset: usize) -> Vec<char> {
+        let mut alphabet = self.alphabet.clone();
+        alphabet.rotate_left(offset);
+        alphabet
+    }
+
+    fn encode_with_increment(&self, numbers: &[u64], increment: usize) -> String {
+        let len = self.alphabet.len();
+        let offset = numbers
+            .iter()
+            .enumerate()
+            .fold(numbers.len(), |acc, (i, &n)| {
+                self.alphabet[(n % len as u64) as usize] as usize + i + acc
+            })
+            % len;
+        let mut alphabet = self.rotated((offset + increment) % len);
+        let prefix = alphabet[0];
+        alphabet.reverse();
+
+        let mut id = String::from(prefix);
+        for (i, &number) in numbers.iter().enumerate() {
+            id.extend(sqids_to_id(number, &alphabet[1..]));
+            if i + 1 < numbers.len() {
+                id.push(alphabet[0]);
+                alphabet = sqids_shuffle(&alphabet);
+            }
+        }
+
+        if id.len() < self.min_length {
+            id.push(alphabet[0]);
+            while id.len() < self.min_length {
+                alphabet = sqids_shuffle(&alphabet);
+                let take = (self.min_length - id.len()).min(alphabet.len());
+                id.extend(&alphabet[..take]);
+            }
+        }
+        id
+    }
+
+    fn is_blocked(&self, id: &str) -> bool {
+        let id = id.to_lowercase();
+        self.blocklist.iter().any(|word| {
+            if word.len() > id.len() {
+                false
+            } else if id.len() <= 3 || word.len() <= 3 {
+                id == *word
+            } else if word.chars().any(|c| c.is_ascii_digit()) {
+                id.starts_with(word.as_str()) || id.ends_with(word.as_str())
+            } else {
+                id.contains(word.as_str())
+            }
+        })
+    }
+}
+
+impl IdCodec for Sqids {
+    fn encode(&self, id: u64) -> String {
+        self.encode_numbers(&[id])
+    }
+
+    fn decode(&self, encoded: &str) -> Option<u64> {
+        match self.decode_numbers(encoded)[..] {
+            // Only the canonical encoding of an id decodes
+            [id] if self.encode(id) == encoded => Some(id),
+            _ => None,
+        }
+    }
+}
+
+fn sqids_shuffle(alphabet: &[char]) -> Vec<char> {
+    let mut chars = alphabet.to_vec();
+    let len = chars.len();
+    let (mut i, mut j) = (0, len - 1);
+    while j > 0 {
+        let r = (i * j + chars[i] as usize + chars[j] as usize) % len;
+        chars.swap(i, r);
+        i += 1;
+        j -= 1;
+    }
+    chars
+}
+
+fn sqids_to_id(mut number: u64, alphabet: &[char]) -> Vec<char> {
+    let base = alphabet.len() as u64;
+    let mut id = Vec::new();
+    loop {
+        id.push(alphabet[(number % base) as usize]);
+        number /= base;
+        if number == 0 {
+            break;
+        }
+    }
+    id.reverse();
+    id
+}
+
+fn sqids_to_number(id: &[char], alphabet: &[char]) -> Option<u64> {
+    id.iter().try_fold(0u64, |acc, c| {
+        let digit = alphabet.iter().position(|a| a == c)? as u64;
+        acc.checked_mul(alphabet.len() as u64)?.checked_add(digit)
+    })
+}
+
+const HASHIDS_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890";
+const HASHIDS_SEPARATORS: &str = "cfhistuCFHISTU";
+const HASHIDS_SEPARATOR_RATIO: f64 = 3.5;
+const HASHIDS_GUARD_RATIO: f64 = 12.0;
+
+/// The [Hashids](https://hashids.org) codec.
+#[derive(Debug, Clone)]
+pub struct Hashids {
+    salt: Vec<char>,
+    min_length: usize,
+    alphabet: Vec<char>,
+    separators: Vec<char>,
+    guards: Vec<char>,
+}
+
+impl Hashids {
+    /// Create a codec salted with `salt`, using the default alphabet.
+    pub fn new(salt: &str) -> Self {
+        Self::build(salt, HASHIDS_ALPHABET.chars().collect())
+    }
+
+    /// Create a codec salted with `salt` and a custom alphabet of at least
+    /// 16 distinct ASCII characters without spaces.
+    pub fn with_alphabet(salt: &str, alphabet: &str) -> QueryResult<Self> {
+        let mut chars: Vec<char> = Vec::new();
+        for c in alphabet.chars() {
+            if !chars.contains(&c) {
+                chars.push(c);
+            }
+        }
+        if chars.len() < 16 || !alphabet.is_ascii() || chars.contains(&' ') {
+            return Err(QueryError::invalid_input(
+                "alphabet",
+                "alphabet must have at least 16 distinct ASCII characters and no spaces",
+            ));
+        }
+        Ok(Self::build(salt, chars))
+    }
+
+    fn build(salt: &str, alphabet: Vec<char>) -> Self {
+        let salt: Vec<char> = salt.chars().collect();
+        let mut separators: Vec<char> = HASHIDS_SEPARATORS
+            .chars()
+            .filter(|c| alphabet.contains(c))
+            .collect();
+        let mut alphabet: Vec<char> = alphabet
+            .into_iter()
+            .filter(|c| !separators.contains(c))
+            .collect();
+        hashids_shuffle(&mut separators, &salt);
+
+        if separators.is_empty()
+            || alphabet.len() as f64 / separators.len() as f64 > HASHIDS_SEPARATOR_RATIO
+        {
+            let wanted = ((alphabet.len() as f64 / HASHIDS_SEPARATOR_RATIO).ceil() as usize).max(2);
+            if wanted > separators.len() {
+                let missing = wanted - separators.len();
+                separators.extend(alphabet.drain(..missing));
+            } else {
+                separators.truncate(wanted);
+            }
+        }
+        hashids_shuffle(&mut alphabet, &salt);
+
+        let guard_count = (alphabet.len() as f64 / HASHIDS_GUARD_RATIO).ceil() as usize;
+        let guards = if alphabet.len() < 3 {
+            separators.drain(..guard_count).collect()
+        } else {
+            alphabet.drain(..guard_count).collect()
+        };
+
+        Self {
+            salt,
+            min_length: 0,
+            alphabet,
+            separators,
+            guards,
+        }
+    }
+
+    /// Pad ids to at least `min_length` characters.
+    pub fn min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Encode a list of numbers into one id.
+    pub fn encode_numbers(&self, numbers: &[u64]) -> String {
+        if numbers.is_empty() {
+            return String::new();
+        }
+        let hash = numbers
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, &n)| acc.wrapping_add(n % (i as u64 + 100)));
+        let lottery = self.alphabet[(hash % self.alphabet.len() as u64) as usize];
+
+        let mut alphabet = self.alphabet.clone();
+        let mut id = vec![lottery];
+        for (i, &number) in numbers.iter().enumerate() {
+            self.shuffle_for(&mut alphabet, lottery);
+            let start = id.len();
+            id.extend(hashids_hash(number, &alphabet));
+            if i + 1 < numbers.len() {
+                let n = number % (id[start] as u64 + i as u64);
+                id.push(self.separators[(n % self.separators.len() as u64) as usize]);
+            }
+        }
+
+        if id.len() < self.min_length {
+            let guard = (hash as usize).wrapping_add(id[0] as usize) % self.guards.len();
+            id.insert(0, self.guards[guard]);
+            if id.len() < self.min_length {
+                let guard = (hash as usize).wrapping_add(id[2] as usize) % self.guards.len();
+                id.push(self.guards[guard]);
+            }
+        }
+
+        let half = alphabet.len() / 2;
+        while id.len() < self.min_length {
+            let salt = alphabet.clone();
+            hashids_shuffle(&mut alphabet, &salt);
+            let mut padded = alphabet[half..].to_vec();
+            padded.extend(&id);
+            padded.extend(&alphabet[..half]);
+            id = padded;
+            if id.len() > self.min_length {
+                let start = (id.len() - self.min_length) / 2;
+                id = id[start..start + self.min_length].to_vec();
+            }
+        }
+        id.into_iter().collect()
+    }
+
+    /// Decode an id into its list of numbers, or an empty list if it is
+    /// malformed.
+    pub fn decode_numbers(&self, id: &str) -> Vec<u64> {
+        let parts: Vec<&str> = id.split(|c| self.guards.contains(&c)).collect();
+        let part = match parts.len() {
+            2 | 3 => parts[1],
+            _ => parts[0],
+        };
+        let mut chars = part.chars();
+        let Some(lottery) = chars.next() else {
+            return Vec::new();
+        };
+
+        let mut alphabet = self.alphabet.clone();
+        let mut numbers = Vec::new();
+        for chunk in chars.as_str().split(|c| self.separators.contains(&c)) {
+            self.shuffle_for(&mut alphabet, lottery);
+            match hashids_unhash(chunk, &alphabet) {
+                Some(number) => numbers.push(number),
+                None => return Vec::new(),
+            }
+        }
+
+        // Reject ids that decode but aren't what encoding produces
+        if self.encode_numbers(&numbers) != id {
+            return Vec::new();
+        }
+        numbers
+    }
+
+    fn shuffle_for(&self, alphabet: &mut [char], lottery: char) {
+        let mut buffer = vec![lottery];
+        buffer.extend(&self.salt);
+        buffer.extend(alphabet.iter());
+        buffer.truncate(alphabet.len());
+        hashids_shuffle(alphabet, &buffer);
+    }
+}
+
+impl IdCodec for Hashids {
+    fn encode(&self, id: u64) -> String {
+        self.encode_numbers(&[id])
+    }
+
+    fn decode(&self, encoded: &str) -> Option<u64> {
+        match self.decode_numbers(encoded)[..] {
+            [id] => Some(id),
+            _ => None,
+        }
+    }
+}
+
+fn hashids_shuffle(alphabet: &mut [char], salt: &[char]) {
+    if salt.is_empty() || alphabet.is_empty() {
+        return;
+    }
+    let (mut v, mut p) = (0, 0);
+    for i in (1..alphabet.len()).rev() {
+        v %= salt.len();
+        let n = salt[v] as usize;
+        p += n;
+        let j = (n + v + p) % i;
+        alphabet.swap(i, j);
+        v += 1;
+    }
+}
+
+fn hashids_hash(mut number: u64, alphabet: &[char]) -> Vec<char> {
+    let base = alphabet.len() as u64;
+    let mut hash = Vec::new();
+    loop {
+        hash.push(alphabet[(number % base) as usize]);
+        number /= base;
+        if number == 0 {
+            break;
+        }
+    }
+    hash.reverse();
+    hash
+}
+
+fn hashids_unhash(hash: &str, alphabet: &[char]) -> Option<u64> {
+    if hash.is_empty() {
+        return None;
+    }
+    hash.chars().try_fold(0u64, |acc, c| {
+        let digit = alphabet.iter().position(|&a| a == c)? as u64;
+        acc.checked_mul(alphabet.len() as u64)?.checked_add(digit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn test_sqids() {
+        let sqids = Sqids::new();
+        assert_eq!(sqids.encode_numbers(&[1, 2, 3]), "86Rf07");
+        assert_eq!(sqids.decode_numbers("86Rf07"), [1, 2, 3]);
+        assert_eq!(sqids.encode(0), "bM");
+        assert_eq!(sqids.encode(1), "Uk");
+        assert_eq!(sqids.decode("bM"), Some(0));
+        assert_eq!(sqids.decode(&sqids.encode(u64::MAX)), Some(u64::MAX));
+
+        // Lists and foreign characters aren't single ids
+        assert_eq!(sqids.decode("86Rf07"), None);
+        assert_eq!(sqids.decode("b-M"), None);
+        assert_eq!(sqids.decode(""), None);
+
+        let padded = Sqids::new().min_length(10);
+        let id = padded.encode(1);
+        assert_eq!(id.len(), 10);
+        assert_eq!(padded.decode(&id), Some(1));
+
+        let blocked = Sqids::new().blocklist(["86Rf07"]);
+        let id = blocked.encode_numbers(&[1, 2, 3]);
+        assert_ne!(id, "86Rf07");
+        assert_eq!(blocked.decode_numbers(&id), [1, 2, 3]);
+
+        assert!(Sqids::with_alphabet("aa").is_err());
+    }
+
+    #[test]
+    fn test_hashids() {
+        let hashids = Hashids::new("this is my salt");
+        assert_eq!(hashids.encode(12345), "NkK9");
+        assert_eq!(hashids.decode("NkK9"), Some(12345));
+        assert_eq!(
+            hashids.encode_numbers(&[683, 94108, 123, 5]),
+            "aBMswoO2UB3Sj"
+        );
+        assert_eq!(
+            hashids.decode_numbers("aBMswoO2UB3Sj"),
+            [683, 94108, 123, 5]
+        );
+        assert_eq!(hashids.decode("NkK8"), None);
+
+        let padded = Hashids::new("this is my salt").min_length(8);
+        assert_eq!(padded.encode(1), "gB0NV05e");
+        assert_eq!(padded.decode("gB0NV05e"), Some(1));
+
+        assert!(Hashids::with_alphabet("salt", "abc").is_err());
+    }
+
+    #[test]
+    fn test_decode_or_not_found() {
+        let codec = Sqids::new();
+        assert_eq!(codec.decode_or_not_found("User", "Uk").unwrap(), 1);
+        let err = codec.decode_or_not_found("User", "nope!").unwrap_err();
+        assert_eq!(err.code, ErrorCode::RecordNotFound);
+    }
+}
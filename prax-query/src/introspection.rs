@@ -246,6 +246,8 @@ pub struct IndexColumn {
     pub order: SortOrder,
     /// Nulls position.
     pub nulls: NullsOrder,
+    /// Operator class (PostgreSQL); `None` for the column type's default.
+    pub ops: Option<String>,
 }
 
 /// Sort order for index columns.
@@ -581,13 +583,15 @@ pub mod queries {
                         ix.indisunique as is_unique, \
                         ix.indisprimary as is_primary, \
                         am.amname as index_type, \
-                        pg_get_expr(ix.indpred, ix.indrelid) as filter \
+                        pg_get_expr(ix.indpred, ix.indrelid) as filter, \
+                        CASE WHEN opc.opcdefault THEN NULL ELSE opc.opcname END as ops \
                      FROM pg_index ix \
                      JOIN pg_class t ON t.oid = ix.indrelid \
                      JOIN pg_class i ON i.oid = ix.indexrelid \
                      JOIN pg_namespace n ON n.oid = t.relnamespace \
                      JOIN pg_am am ON i.relam = am.oid \
                      JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey) \
+                     JOIN pg_opclass opc ON opc.oid = ix.indclass[array_position(ix.indkey, a.attnum)] \
                      WHERE t.relname = '{}' AND n.nspname = '{}' \
                      ORDER BY i.relname, array_position(ix.indkey, a.attnum)",
                     table, schema_filter
@@ -876,8 +880,8 @@ fn generate_model(table: &TableInfo, all_tables: &[TableInfo]) -> String {
 fn generate_field(col: &ColumnInfo, primary_key: &[String]) -> String {
     let mut attrs = Vec::new();
 
-    // Check if primary key
-    if primary_key.contains(&col.name) {
+    // Check if primary key (composite keys become @@id)
+    if primary_key.len() == 1 && primary_key.contains(&col.name) {
         attrs.push("@id".to_string());
     }
 
@@ -894,7 +898,10 @@ fn generate_field(col: &ColumnInfo, primary_key: &[String]) -> String {
     // Default
     if let Some(ref default) = col.default {
         if !col.auto_increment {
-            let default_val = simplify_default(default);
+            let default_val = match col.normalized_type {
+                NormalizedType::Enum(_) => enum_default(default),
+                _ => simplify_default(default),
+            };
             attrs.push(format!("@default({})", default_val));
         }
     }
@@ -976,7 +983,11 @@ fn generate_model_attributes(table: &TableInfo) -> String {
             if idx.is_unique {
                 output.push_str(&format!("    @@unique([{}])\n", cols.join(", ")));
             } else {
-                output.push_str(&format!("    @@index([{}])\n", cols.join(", ")));
+                output.push_str(&format!(
+                    "    @@index([{}]{})\n",
+                    cols.join(", "),
+                    index_options(idx)
+                ));
             }
         }
     }
@@ -984,6 +995,43 @@ fn generate_model_attributes(table: &TableInfo) -> String {
     output
 }
 
+/// The `type:` and `ops:` arguments of an `@@index`.
+///
+/// `ops` names one operator class per column, so an index mixing default and
+/// explicit operator classes keeps only its type.
+fn index_options(idx: &IndexInfo) -> String {
+    let mut options = String::new();
+
+    let index_type = idx.index_type.as_deref().map(str::to_lowercase);
+    let index_type = match index_type.as_deref() {
+        Some("hash") => Some("Hash"),
+        Some("gin") => Some("Gin"),
+        Some("gist") => Some("Gist"),
+        Some("brin") => Some("Brin"),
+        Some("spgist") => Some("SpGist"),
+        Some("fulltext") => Some("FullText"),
+        Some("hnsw") => Some("Hnsw"),
+        Some("ivfflat") => Some("IvfFlat"),
+        _ => None,
+    };
+    if let Some(index_type) = index_type {
+        options.push_str(&format!(", type: {}", index_type));
+    }
+
+    let ops: Option<Vec<&str>> = idx.columns.iter().map(|c| c.ops.as_deref()).collect();
+    match ops.as_deref() {
+        Some([first, rest @ ..]) if rest.iter().all(|o| o == first) => {
+            options.push_str(&format!(", ops: {}", first));
+        }
+        Some(ops) if !ops.is_empty() => {
+            options.push_str(&format!(", ops: [{}]", ops.join(", ")));
+        }
+        _ => {}
+    }
+
+    options
+}
+
 fn generate_view(view: &ViewInfo) -> String {
     let mut output = String::new();
 
@@ -1238,6 +1286,15 @@ fn simplify_default(default: &str) -> String {
     format!("dbgenerated(\"{}\")", d.replace('"', "\\\""))
 }
 
+/// Default of an enum column: `'draft'::post_status` becomes `draft`.
+fn enum_default(default: &str) -> String {
+    let value = default.split("::").next().unwrap_or(default).trim();
+    match value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        Some(variant) => variant.to_string(),
+        None => simplify_default(default),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1316,6 +1373,45 @@ mod tests {
         assert!(schema.contains("createdAt DateTime?"));
     }
 
+    #[test]
+    fn test_generate_index_options() {
+        let column = |name: &str, ops: Option<&str>| IndexColumn {
+            name: name.to_string(),
+            ops: ops.map(str::to_string),
+            ..Default::default()
+        };
+        let table = TableInfo {
+            name: "posts".to_string(),
+            indexes: vec![
+                IndexInfo {
+                    name: "posts_data_idx".to_string(),
+                    columns: vec![column("data", Some("jsonb_path_ops"))],
+
+                    index_type: Some("gin".to_string()),
+                    ..Default::default()
+                },
+                IndexInfo {
+                    name: "posts_title_body_idx".to_string(),
+                    columns: vec![column("title", Some("gin_trgm_ops")), column("body", None)],
+                    index_type: Some("gin".to_string()),
+                    ..Default::default()
+                },
+                IndexInfo {
+                    name: "posts_created_at_idx".to_string(),
+                    columns: vec![column("created_at", None)],
+                    index_type: Some("btree".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let attributes = generate_model_attributes(&table);
+        assert!(attributes.contains("@@index([data], type: Gin, ops: jsonb_path_ops)"));
+        assert!(attributes.contains("@@index([title, body], type: Gin)"));
+        assert!(attributes.contains("@@index([createdAt])"));
+    }
+
     #[test]
     fn test_simplify_default() {
         assert_eq!(simplify_default("NOW()"), "now()");
@@ -1325,6 +1421,12 @@ mod tests {
         assert_eq!(simplify_default("true"), "true");
     }
 
+    #[test]
+    fn test_enum_default() {
+        assert_eq!(enum_default("'draft'::post_status"), "draft");
+        assert_eq!(enum_default("'active'"), "active");
+    }
+
     #[test]
     fn test_queries_tables() {
         let pg = queries::tables_query(DatabaseType::PostgreSQL, Some("public"));
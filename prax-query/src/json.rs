@@ -31,7 +31,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 use crate::error::{QueryError, QueryResult};
-use crate::filter::FilterValue;
+use crate::filter::{FieldName, Filter, FilterValue};
 use crate::sql::DatabaseType;
 
 /// A JSON path expression for navigating JSON documents.
@@ -469,6 +469,297 @@ impl JsonFilter {
     }
 }
 
+/// An operator of a [`JsonFieldFilter`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonFieldOp {
+    /// The value equals a JSON value.
+    Equals(JsonValue),
+    /// The array contains a value, or each element of an array of values.
+    ArrayContains(JsonValue),
+    /// The string contains a substring.
+    StringContains(String),
+    /// The object has a key.
+    HasKey(String),
+}
+
+/// A filter on the value at a path inside a JSON column.
+///
+/// Generated clients expose these on `Json` fields:
+///
+/// ```rust,ignore
+/// user::metadata::path(["settings", "theme"]).equals("dark")
+/// user::metadata::path(["tags"]).array_contains("admin")
+/// user::metadata::has_key("settings")
+/// ```
+///
+/// PostgreSQL queries `jsonb` with its operators, MySQL and SQLite with
+/// `JSON_EXTRACT` and `json_extract`, and MSSQL with `JSON_VALUE`.
+/// Path segments made of digits index into arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonFieldFilter {
+    /// The path from the column to the filtered value.
+    pub path: Vec<PathSegment>,
+    /// The operator.
+    pub op: JsonFieldOp,
+    /// The database the SQL is generated for.
+    pub db_type: DatabaseType,
+}
+
+impl JsonFieldFilter {
+    fn new(op: JsonFieldOp) -> Self {
+        Self {
+            path: Vec::new(),
+            op,
+            db_type: DatabaseType::PostgreSQL,
+        }
+    }
+
+    /// Match values equal to `value`.
+    pub fn equals(value: impl Into<JsonValue>) -> Self {
+        Self::new(JsonFieldOp::Equals(value.into()))
+    }
+
+    /// Match arrays containing `value`, or every element of it if it is an
+    /// array.
+    pub fn array_contains(value: impl Into<JsonValue>) -> Self {
+        Self::new(JsonFieldOp::ArrayContains(value.into()))
+    }
+
+    /// Match strings containing `value`.
+    pub fn string_contains(value: impl Into<String>) -> Self {
+        Self::new(JsonFieldOp::StringContains(value.into()))
+    }
+
+    /// Match objects with the key `key`.
+    pub fn has_key(key: impl Into<String>) -> Self {
+        Self::new(JsonFieldOp::HasKey(key.into()))
+    }
+
+    /// Apply the filter to the value at `path` instead of the whole column.
+    pub fn at_path<S: Into<String>>(mut self, path: impl IntoIterator<Item = S>) -> Self {
+        self.path = path
+            .into_iter()
+            .map(|segment| {
+                let segment = segment.into();
+                match segment.parse() {
+                    Ok(idx) if segment.bytes().all(|b| b.is_ascii_digit()) => {
+                        PathSegment::Index(idx)
+                    }
+                    _ => PathSegment::Field(segment),
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Generate SQL for `db_type` (default PostgreSQL).
+    pub fn for_database(mut self, db_type: DatabaseType) -> Self {
+        self.db_type = db_type;
+        self
+    }
+
+    /// Apply the filter to `column`.
+    pub fn into_filter(self, column: impl Into<FieldName>) -> Filter {
+        Filter::Json(column.into(), Box::new(self))
+    }
+
+    /// Generate the condition on `column`, pushing its parameters.
+    pub(crate) fn to_sql_with_params(
+        &self,
+        column: &str,
+        param_idx: usize,
+        params: &mut Vec<FilterValue>,
+    ) -> String {
+        let mut bind = |value: FilterValue| {
+            params.push(value);
+            format!("${}", param_idx + params.len())
+        };
+
+        if self.db_type == DatabaseType::PostgreSQL {
+            let keys: Vec<_> = self
+                .path
+                .iter()
+                .map(|segment| bind(FilterValue::String(segment_text(segment))))
+                .collect();
+            let (target, text) = if keys.is_empty() {
+                (column.to_string(), format!("({} #>> '{{}}')", column))
+            } else {
+                let keys = keys.join(", ");
+                (
+                    format!("jsonb_extract_path({}, {})", column, keys),
+                    format!("jsonb_extract_path_text({}, {})", column, keys),
+                )
+            };
+            return match &self.op {
+                JsonFieldOp::Equals(value) => {
+                    format!(
+                        "{} = {}::jsonb",
+                        target,
+                        bind(FilterValue::Json(value.clone()))
+                    )
+                }
+                JsonFieldOp::ArrayContains(value) => {
+                    let value = JsonValue::Array(array_elements(value));
+                    format!("{} @> {}::jsonb", target, bind(FilterValue::Json(value)))
+                }
+                JsonFieldOp::StringContains(s) => {
+                    format!(
+                        "{} LIKE {}",
+                        text,
+                        bind(FilterValue::String(format!("%{}%", s)))
+                    )
+                }
+                JsonFieldOp::HasKey(key) => {
+                    format!("{} ? {}", target, bind(FilterValue::String(key.clone())))
+                }
+            };
+        }
+
+        if let JsonFieldOp::HasKey(key) = &self.op {
+            let mut path = self.path.clone();
+            path.push(PathSegment::Field(key.clone()));
+            let path = bind(FilterValue::String(sql_json_path(&path)));
+            return match self.db_type {
+                DatabaseType::MySQL => format!("JSON_CONTAINS_PATH({}, 'one', {})", column, path),
+                DatabaseType::SQLite => format!("json_type({}, {}) IS NOT NULL", column, path),
+                _ => format!("JSON_PATH_EXISTS({}, {}) = 1", column, path),
+            };
+        }
+
+        let path = bind(FilterValue::String(sql_json_path(&self.path)));
+        match (&self.op, self.db_type) {
+            (JsonFieldOp::Equals(value), DatabaseType::MySQL) => format!(
+                "JSON_EXTRACT({}, {}) = CAST({} AS JSON)",
+                column,
+                path,
+                bind(FilterValue::Json(value.clone()))
+            ),
+            (JsonFieldOp::Equals(value), DatabaseType::SQLite) => format!(
+                "json_extract({}, {}) = json_extract({}, '$')",
+                column,
+                path,
+                bind(FilterValue::Json(value.clone()))
+            ),
+            (JsonFieldOp::Equals(value), _) => {
+                let function = if value.is_object() || value.is_array() {
+                    "JSON_QUERY"
+                } else {
+                    "JSON_VALUE"
+                };
+                format!(
+                    "{}({}, {}) = {}",
+                    function,
+                    column,
+                    path,
+                    bind(FilterValue::String(scalar_text(value)))
+                )
+            }
+            (JsonFieldOp::ArrayContains(value), DatabaseType::MySQL) => format!(
+                "JSON_CONTAINS({}, {}, {})",
+                column,
+                bind(FilterValue::Json(JsonValue::Array(array_elements(value)))),
+                path
+            ),
+            (JsonFieldOp::ArrayContains(value), db_type) => {
+                // One membership test per element, against the array's rows
+                let conditions: Vec<_> = array_elements(value)
+                    .iter()
+                    .map(|element| {
+                        if db_type == DatabaseType::SQLite {
+                            format!(
+                                "EXISTS (SELECT 1 FROM json_each({}, {}) WHERE value = json_extract({}, '$'))",
+                                column,
+                                path,
+                                bind(FilterValue::Json(element.clone()))
+                            )
+                        } else {
+                            format!(
+                                "EXISTS (SELECT 1 FROM OPENJSON({}, {}) WHERE value = {})",
+                                column,
+                                path,
+                                bind(FilterValue::String(scalar_text(element)))
+                            )
+                        }
+                    })
+                    .collect();
+                if conditions.is_empty() {
+                    "1=1".to_string()
+                } else {
+                    format!("({})", conditions.join(" AND "))
+                }
+            }
+            (JsonFieldOp::StringContains(s), db_type) => {
+                let value = match db_type {
+                    DatabaseType::MySQL => {
+                        format!("JSON_UNQUOTE(JSON_EXTRACT({}, {}))", column, path)
+                    }
+                    DatabaseType::SQLite => format!("json_extract({}, {})", column, path),
+                    _ => format!("JSON_VALUE({}, {})", column, path),
+                };
+                format!(
+                    "{} LIKE {}",
+                    value,
+                    bind(FilterValue::String(format!("%{}%", s)))
+                )
+            }
+            (JsonFieldOp::HasKey(_), _) => unreachable!("handled above"),
+        }
+    }
+}
+
+/// The values an array must contain: the elements of an array, or the
+/// value itself.
+fn array_elements(value: &JsonValue) -> Vec<JsonValue> {
+    match value {
+        JsonValue::Array(elements) => elements.clone(),
+        other => vec![other.clone()],
+    }
+}
+
+/// A JSON value as returned by MSSQL's `JSON_VALUE` and `OPENJSON`:
+/// strings unquoted, other values as JSON text.
+fn scalar_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn segment_text(segment: &PathSegment) -> String {
+    match segment {
+        PathSegment::Field(name) => name.clone(),
+        PathSegment::Index(idx) => idx.to_string(),
+        PathSegment::Wildcard => "*".to_string(),
+        PathSegment::RecursiveDescent => "**".to_string(),
+    }
+}
+
+/// Build a `$.a[0]` path, quoting keys that aren't plain identifiers.
+fn sql_json_path(path: &[PathSegment]) -> String {
+    let mut out = String::from("$");
+    for segment in path {
+        match segment {
+            PathSegment::Field(name)
+                if !name.is_empty()
+                    && !name.starts_with(|c: char| c.is_ascii_digit())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                out.push('.');
+                out.push_str(name);
+            }
+            PathSegment::Field(name) => {
+                out.push_str(".\"");
+                out.push_str(&name.replace('\\', "\\\\").replace('"', "\\\""));
+                out.push('"');
+            }
+            PathSegment::Index(idx) => out.push_str(&format!("[{}]", idx)),
+            PathSegment::Wildcard => out.push_str("[*]"),
+            PathSegment::RecursiveDescent => out.push_str("**"),
+        }
+    }
+    out
+}
+
 /// JSON mutation operations.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum JsonOp {
@@ -1312,6 +1603,76 @@ mod tests {
         assert!(sql.contains("-> 'role'"));
     }
 
+    #[test]
+    fn test_json_field_filter_postgres() {
+        let (sql, params) = JsonFieldFilter::equals("dark")
+            .at_path(["settings", "theme"])
+            .into_filter("metadata")
+            .to_sql(0);
+        assert_eq!(sql, "jsonb_extract_path(metadata, $1, $2) = $3::jsonb");
+        assert_eq!(params[2], FilterValue::Json(JsonValue::from("dark")));
+
+        let (sql, params) = JsonFieldFilter::array_contains("admin")
+            .at_path(["tags"])
+            .into_filter("metadata")
+            .to_sql(0);
+        assert_eq!(sql, "jsonb_extract_path(metadata, $1) @> $2::jsonb");
+        assert_eq!(params[1], FilterValue::Json(serde_json::json!(["admin"])));
+
+        let (sql, _) = JsonFieldFilter::string_contains("lo")
+            .into_filter("metadata")
+            .to_sql(2);
+        assert_eq!(sql, "(metadata #>> '{}') LIKE $3");
+
+        let (sql, _) = JsonFieldFilter::has_key("settings")
+            .into_filter("metadata")
+            .to_sql(0);
+        assert_eq!(sql, "metadata ? $1");
+    }
+
+    #[test]
+    fn test_json_field_filter_mysql_sqlite() {
+        let filter = JsonFieldFilter::equals(3).at_path(["items", "0", "my key"]);
+        let (sql, params) = filter
+            .clone()
+            .for_database(DatabaseType::MySQL)
+            .into_filter("data")
+            .to_sql(0);
+        assert_eq!(sql, "JSON_EXTRACT(data, $1) = CAST($2 AS JSON)");
+        assert_eq!(params[0], FilterValue::String(r#"$.items[0]."my key""#.into()));
+
+        let (sql, _) = filter
+            .for_database(DatabaseType::SQLite)
+            .into_filter("data")
+            .to_sql(0);
+        assert_eq!(sql, "json_extract(data, $1) = json_extract($2, '$')");
+
+        let (sql, params) = JsonFieldFilter::has_key("theme")
+            .at_path(["settings"])
+            .for_database(DatabaseType::MySQL)
+            .into_filter("data")
+            .to_sql(0);
+        assert_eq!(sql, "JSON_CONTAINS_PATH(data, 'one', $1)");
+        assert_eq!(params[0], FilterValue::String("$.settings.theme".into()));
+
+        let (sql, params) = JsonFieldFilter::array_contains(serde_json::json!(["a", "b"]))
+            .for_database(DatabaseType::SQLite)
+            .into_filter("tags")
+            .to_sql(0);
+        assert_eq!(
+            sql,
+            "(EXISTS (SELECT 1 FROM json_each(tags, $1) WHERE value = json_extract($2, '$')) \
+             AND EXISTS (SELECT 1 FROM json_each(tags, $1) WHERE value = json_extract($3, '$')))"
+        );
+        assert_eq!(params.len(), 3);
+
+        let (sql, _) = JsonFieldFilter::string_contains("x")
+            .for_database(DatabaseType::MySQL)
+            .into_filter("data")
+            .to_sql(0);
+        assert_eq!(sql, "JSON_UNQUOTE(JSON_EXTRACT(data, $1)) LIKE $2");
+    }
+
     mod mongodb_tests {
         use super::super::mongodb::*;
 
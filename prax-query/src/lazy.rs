@@ -21,10 +21,17 @@
 //! ```
 
 use std::cell::UnsafeCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::future::Future;
+use std::hash::Hash;
 use std::sync::atomic::{AtomicU8, Ordering};
 
+use crate::error::QueryResult;
+use crate::filter::FilterValue;
+use crate::traits::{Model, QueryEngine};
+use crate::types::Select;
+
 /// State of a lazy value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -334,6 +341,31 @@ impl OneToManyLoader {
             local_key_value: local_key_value.into(),
         }
     }
+
+    /// Load the related records of many parents with a single `IN` query.
+    ///
+    /// `keys` are the parents' local key values; `key_of` extracts the
+    /// foreign key from a related record. Parents without related records
+    /// are absent from the map.
+    pub async fn load_batch<E, M, K>(
+        engine: &E,
+        foreign_key: &str,
+        keys: &[K],
+        key_of: impl Fn(&M) -> Option<K>,
+    ) -> QueryResult<HashMap<K, Vec<M>>>
+    where
+        E: QueryEngine,
+        M: Model + Send + 'static,
+        K: Clone + Eq + Hash + Into<FilterValue>,
+    {
+        let mut grouped: HashMap<K, Vec<M>> = HashMap::new();
+        for record in query_in::<E, M, K>(engine, foreign_key, keys).await? {
+            if let Some(key) = key_of(&record) {
+                grouped.entry(key).or_default().push(record);
+            }
+        }
+        Ok(grouped)
+    }
 }
 
 /// Configuration for a many-to-one relation loader.
@@ -360,6 +392,56 @@ impl ManyToOneLoader {
             foreign_key_value: foreign_key_value.into(),
         }
     }
+
+    /// Load the records referenced by many foreign keys with a single `IN`
+    /// query.
+    ///
+    /// `key_of` extracts the referenced key from a loaded record. Keys
+    /// without a record are absent from the map.
+    pub async fn load_batch<E, M, K>(
+        engine: &E,
+        primary_key: &str,
+        keys: &[K],
+        key_of: impl Fn(&M) -> Option<K>,
+    ) -> QueryResult<HashMap<K, M>>
+    where
+        E: QueryEngine,
+        M: Model + Send + 'static,
+        K: Clone + Eq + Hash + Into<FilterValue>,
+    {
+        Ok(query_in::<E, M, K>(engine, primary_key, keys)
+            .await?
+            .into_iter()
+            .filter_map(|record| key_of(&record).map(|key| (key, record)))
+            .collect())
+    }
+}
+
+/// Select the records of `M` whose `column` is one of `keys`.
+async fn query_in<E, M, K>(engine: &E, column: &str, keys: &[K]) -> QueryResult<Vec<M>>
+where
+    E: QueryEngine,
+    M: Model + Send + 'static,
+    K: Clone + Eq + Hash + Into<FilterValue>,
+{
+    let mut seen = HashSet::with_capacity(keys.len());
+    let params: Vec<FilterValue> = keys
+        .iter()
+        .filter(|key| seen.insert(*key))
+        .map(|key| key.clone().into())
+        .collect();
+    if params.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {} IN ({})",
+        Select::All.to_sql_with_virtual(M::VIRTUAL_FIELDS),
+        M::TABLE_NAME,
+        column,
+        crate::sql::postgres_in_pattern(1, params.len())
+    );
+    engine.query_many::<M>(&sql, params).await
 }
 
 #[cfg(test)]
@@ -449,4 +531,172 @@ mod tests {
         assert!(!relation.is_loaded());
         assert!(relation.get().is_none());
     }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Post {
+        id: i32,
+        author_id: Option<i32>,
+    }
+
+    impl Model for Post {
+        const MODEL_NAME: &'static str = "Post";
+        const TABLE_NAME: &'static str = "posts";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "author_id"];
+    }
+
+    type RecordedQueries = std::sync::Arc<std::sync::Mutex<Vec<(String, Vec<FilterValue>)>>>;
+
+    /// Engine answering `query_many` with fixed posts and recording the query.
+    #[derive(Clone, Default)]
+    struct PostEngine {
+        posts: Vec<Post>,
+        queries: RecordedQueries,
+    }
+
+    impl QueryEngine for PostEngine {
+        fn query_many<T: Model + Send + 'static>(
+            &self,
+            sql: &str,
+            params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            self.queries.lock().unwrap().push((sql.to_string(), params));
+            let rows: Box<dyn std::any::Any + Send> = Box::new(self.posts.clone());
+            Box::pin(async move { Ok(*rows.downcast::<Vec<T>>().unwrap()) })
+        }
+
+        fn query_one<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            Box::pin(async { Err(crate::error::QueryError::not_found("test")) })
+        }
+
+        fn query_optional<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Option<T>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn execute_insert<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            Box::pin(async { Err(crate::error::QueryError::not_found("test")) })
+        }
+
+        fn execute_update<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn execute_delete(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn execute_raw(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn count(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_one_to_many_load_batch() {
+        let engine = PostEngine {
+            posts: vec![
+                Post {
+                    id: 1,
+                    author_id: Some(10),
+                },
+                Post {
+                    id: 2,
+                    author_id: Some(20),
+                },
+                Post {
+                    id: 3,
+                    author_id: Some(10),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let grouped =
+            OneToManyLoader::load_batch(&engine, "author_id", &[10, 20, 10, 30], |p: &Post| {
+                p.author_id
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            grouped[&10].iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(grouped[&20].len(), 1);
+        assert!(!grouped.contains_key(&30));
+
+        let queries = engine.queries.lock().unwrap();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(
+            queries[0].0,
+            "SELECT * FROM posts WHERE author_id IN ($1, $2, $3)"
+        );
+        assert_eq!(
+            queries[0].1,
+            vec![
+                FilterValue::Int(10),
+                FilterValue::Int(20),
+                FilterValue::Int(30)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_many_to_one_load_batch() {
+        let engine = PostEngine {
+            posts: vec![
+                Post {
+                    id: 1,
+                    author_id: None,
+                },
+                Post {
+                    id: 2,
+                    author_id: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let by_id = ManyToOneLoader::load_batch(&engine, "id", &[1, 2], |p: &Post| Some(p.id))
+            .await
+            .unwrap();
+        assert_eq!(by_id[&2].id, 2);
+
+        let empty = ManyToOneLoader::load_batch(&engine, "id", &[], |p: &Post| Some(p.id))
+            .await
+            .unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(engine.queries.lock().unwrap().len(), 1);
+    }
 }
@@ -42,6 +42,7 @@
 //!
 //! ## Filter Values
 //!
+//! Filter values are [`Value`]s, the parameter type shared by all drivers.
 //! Convert Rust types to filter values:
 //!
 //! ```rust
@@ -149,55 +150,89 @@
 pub mod advanced;
 pub mod async_optimize;
 pub mod batch;
+pub mod blocking;
 pub mod builder;
 pub mod cache;
+pub mod capabilities;
 pub mod connection;
 pub mod cte;
 pub mod data;
 pub mod data_cache;
 pub mod db_optimize;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+pub mod diff;
+pub mod drift;
+pub mod erasure;
 pub mod error;
+pub mod etag;
+pub mod explain;
 pub mod extension;
 pub mod filter;
+pub mod id_codec;
 pub mod intern;
 pub mod introspection;
 pub mod json;
 pub mod lazy;
+pub mod live;
 pub mod logging;
 #[macro_use]
 pub mod macros;
 pub mod mem_optimize;
 pub mod memory;
+pub mod metrics;
 pub mod middleware;
+pub mod money;
 pub mod nested;
+pub mod network;
 pub mod operations;
 pub mod pagination;
 pub mod partition;
 pub mod pool;
 pub mod procedure;
 pub mod profiling;
+pub mod projection;
 pub mod query;
+#[cfg(feature = "diagnostics")]
+pub mod query_log;
+pub mod range;
+pub mod rate_limit;
 pub mod raw;
 pub mod relations;
 pub mod replication;
+pub mod retention;
 pub mod row;
 pub mod search;
 pub mod security;
+pub mod seed;
 pub mod sequence;
+pub mod session;
+pub mod spatial;
 pub mod sql;
+pub mod state_machine;
 pub mod static_filter;
+pub mod sync;
+pub mod tags;
 pub mod tenant;
 pub mod traits;
 pub mod transaction;
+pub mod translation;
+pub mod tree;
+pub mod ttl;
 pub mod trigger;
 pub mod typed_filter;
 pub mod types;
 pub mod upsert;
+pub mod validation;
+pub mod value;
+pub mod verify;
+pub mod versioning;
 pub mod window;
 pub mod zero_copy;
 
 pub use error::{ErrorCode, ErrorContext, QueryError, QueryResult, Suggestion};
 pub use extension::{Extension, ExtensionBuilder, Point, Polygon};
+pub use value::{Value, ValueRef};
 pub use filter::{
     AndFilterBuilder, FieldName, Filter, FilterValue, FluentFilterBuilder, LargeValueList,
     OrFilterBuilder, ScalarFilter, SmallValueList, ValueList,
@@ -205,6 +240,7 @@ pub use filter::{
 pub use json::{
     JsonAgg, JsonFilter, JsonIndex, JsonIndexBuilder, JsonOp, JsonPath, PathSegment,
 };
+pub use live::{ChangeEvent, ChangeFeed, ChangeOp, ChangeSource, LiveEvent, LiveQuery};
 pub use nested::{NestedWrite, NestedWriteBuilder, NestedWriteOperations};
 pub use operations::{
     CreateOperation, DeleteOperation, FindManyOperation, FindUniqueOperation, UpdateOperation,
@@ -212,7 +248,7 @@ pub use operations::{
     MaterializedViewAccessor, RefreshMaterializedViewOperation, ViewAccessor, ViewCountOperation,
     ViewFindFirstOperation, ViewFindManyOperation, ViewQueryBuilder,
 };
-pub use pagination::{Cursor, CursorDirection, Pagination};
+pub use pagination::{Cursor, CursorDirection, Page, PageInfo, Pagination};
 pub use partition::{
     HashPartitionDef, ListPartitionDef, Partition, PartitionBuilder, PartitionDef, PartitionType,
     RangeBound, RangePartitionDef,
@@ -222,7 +258,7 @@ pub use procedure::{
     ProcedureResult,
 };
 pub use query::QueryBuilder;
-pub use raw::{RawExecuteOperation, RawQueryOperation, Sql};
+pub use raw::{RawDatabase, RawExecuteOperation, RawQueryOperation, Sql};
 pub use relations::{Include, IncludeSpec, RelationLoader, RelationSpec, SelectSpec};
 pub use search::{
     FullTextIndex, FullTextIndexBuilder, FuzzyOptions, HighlightOptions, RankingOptions,
@@ -234,16 +270,19 @@ pub use security::{
     TenantPolicy, TenantSource,
 };
 pub use sequence::{OwnedBy, Sequence, SequenceBuilder};
+pub use sync::{MergePolicy, Resolution, SyncReport, Syncer};
 pub use traits::{
     Executable, IntoFilter, MaterializedView, Model, QueryEngine, View, ViewQueryEngine,
 };
-pub use transaction::{IsolationLevel, Transaction, TransactionConfig};
+pub use transaction::{
+    IsolationLevel, Savepoint, Transaction, TransactionConfig, TransactionalEngine,
+};
 pub use trigger::{
     Trigger, TriggerAction, TriggerBuilder, TriggerCondition, TriggerEvent, TriggerLevel,
     TriggerTiming, UpdateOf,
 };
 pub use types::{
-    NullsOrder, OrderBy, OrderByBuilder, OrderByField, Select, SortOrder, order_patterns,
+    DbEnum, NullsOrder, OrderBy, OrderByBuilder, OrderByField, Select, SortOrder, order_patterns,
 };
 pub use upsert::{
     Assignment, AssignmentValue, ConflictAction, ConflictTarget, UpdateSpec, Upsert, UpsertBuilder,
@@ -255,9 +294,9 @@ pub use window::{
 
 // Re-export middleware types
 pub use middleware::{
-    LoggingMiddleware, MetricsMiddleware, Middleware, MiddlewareBuilder, MiddlewareChain,
-    MiddlewareStack, QueryContext, QueryMetadata, QueryMetrics, QueryType, RetryMiddleware,
-    TimingMiddleware,
+    AuditMiddleware, DualWriteMiddleware, LoggingMiddleware, MetricsMiddleware, Middleware, MiddlewareBuilder,
+    MiddlewareChain, MiddlewareStack, QueryContext, QueryMetadata, QueryMetrics, QueryType,
+    RetryMiddleware, TimingMiddleware, TracingMiddleware,
 };
 
 // Re-export connection types
@@ -279,7 +318,7 @@ pub use advanced::{
 
 // Re-export data types
 pub use data::{
-    BatchCreate, ConnectData, CreateData, DataBuilder, FieldValue, IntoData, UpdateData,
+    BatchCreate, ConnectData, CreateData, DataBuilder, FieldOp, FieldValue, IntoData, UpdateData,
 };
 
 // Re-export introspection types
@@ -337,8 +376,8 @@ pub use cache::{
 
 // Re-export batch types
 pub use batch::{
-    Batch, BatchBuilder, BatchOperation, BatchResult, OperationResult, Pipeline, PipelineBuilder,
-    PipelineQuery, PipelineResult, QueryResult as PipelineQueryResult,
+    Batch, BatchBuilder, BatchOp, BatchOperation, BatchOps, BatchResult, OperationResult, Pipeline,
+    PipelineBuilder, PipelineQuery, PipelineResult, QueryResult as PipelineQueryResult, run_batch,
 };
 
 // Re-export row deserialization types
@@ -380,6 +419,7 @@ pub use logging::{
 pub use replication::{
     ConnectionRouter, HealthStatus, LagMeasurement, LagMonitor, ReadPreference,
     ReplicaConfig, ReplicaHealth, ReplicaRole, ReplicaSetBuilder, ReplicaSetConfig,
+    RouterEvent,
 };
 
 // Re-export async optimization types
@@ -421,14 +461,16 @@ pub mod prelude {
     pub use crate::error::{QueryError, QueryResult};
     pub use crate::extension::{Extension, Point, Polygon};
     pub use crate::filter::{Filter, FilterValue, ScalarFilter};
+    pub use crate::value::{Value, ValueRef};
     pub use crate::advanced::{LateralJoin, Returning, RowLock, TableSample};
     pub use crate::cte::{Cte, CteBuilder, WithClause};
     pub use crate::introspection::{DatabaseSchema, TableInfo, generate_prax_schema};
     pub use crate::replication::{ConnectionRouter, ReadPreference, ReplicaSetConfig};
     pub use crate::json::{JsonFilter, JsonOp, JsonPath};
+    pub use crate::live::{ChangeEvent, ChangeFeed, ChangeOp, LiveEvent, LiveQuery};
     pub use crate::nested::{NestedWrite, NestedWriteBuilder, NestedWriteOperations};
     pub use crate::operations::*;
-    pub use crate::pagination::{Cursor, CursorDirection, Pagination};
+    pub use crate::pagination::{Cursor, CursorDirection, Page, PageInfo, Pagination};
     pub use crate::partition::{Partition, PartitionBuilder, PartitionType, RangeBound};
     pub use crate::procedure::{
         Parameter, ParameterMode, ProcedureCall, ProcedureEngine, ProcedureResult,
@@ -443,7 +485,9 @@ pub mod prelude {
     pub use crate::traits::{
         Executable, IntoFilter, MaterializedView, Model, QueryEngine, View, ViewQueryEngine,
     };
-    pub use crate::transaction::{IsolationLevel, Transaction, TransactionConfig};
+    pub use crate::transaction::{
+        IsolationLevel, Savepoint, Transaction, TransactionConfig, TransactionalEngine,
+    };
     pub use crate::trigger::{
         Trigger, TriggerAction, TriggerBuilder, TriggerCondition, TriggerEvent, TriggerLevel,
         TriggerTiming,
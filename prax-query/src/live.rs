@@ -0,0 +1,642 @@
+//! Live queries: a `find_many` that keeps its results up to date.
+//!
+//! A live query yields a snapshot of the matching records, then an insert,
+//! update or delete event whenever a record enters, changes within or
+//! leaves the result set. It re-runs the query when a [`ChangeSource`]
+//! reports a change to the model's table (a CDC feed, PostgreSQL
+//! `LISTEN/NOTIFY`, or writes published by the application itself), and
+//! diffs the results by primary key. Without a change source it polls.
+//!
+//! ```rust,ignore
+//! let feed = ChangeFeed::new(1024);
+//!
+//! // Publish writes made by this process
+//! feed.publish(ChangeEvent::new("users", ChangeOp::Insert));
+//!
+//! let mut online = client
+//!     .user()
+//!     .find_many()
+//!     .r#where(user::online::equals(true))
+//!     .live()
+//!     .changes(feed.clone())
+//!     .stream();
+//!
+//! while let Some(event) = online.next().await {
+//!     match event? {
+//!         LiveEvent::Snapshot { records } => render(records),
+//!         LiveEvent::Insert { record } | LiveEvent::Update { record } => upsert(record),
+//!         LiveEvent::Delete { key } => remove(key),
+//!     }
+//! }
+//! ```
+//!
+//! Events serialize as `{"type": "insert", "record": {...}}`, ready to be
+//! forwarded to WebSocket subscribers.
+
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{FutureExt, StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use tokio::sync::broadcast;
+
+use crate::error::{QueryError, QueryResult};
+use crate::filter::FilterValue;
+use crate::sql::{quote_identifier, quote_table_name};
+use crate::traits::{BoxStream, Model, QueryEngine};
+
+/// How often a live query without a change source re-runs.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The kind of write behind a [`ChangeEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChangeOp {
+    /// Rows were inserted.
+    Insert,
+    /// Rows were updated.
+    Update,
+    /// Rows were deleted.
+    Delete,
+}
+
+/// A write to a table, as reported by a [`ChangeSource`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// The table written to.
+    pub table: String,
+    /// The kind of write.
+    pub op: ChangeOp,
+    /// The primary key of the row written, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<JsonValue>,
+}
+
+impl ChangeEvent {
+    /// Create an event for a write to `table`.
+    pub fn new(table: impl Into<String>, op: ChangeOp) -> Self {
+        Self {
+            table: table.into(),
+            op,
+            key: None,
+        }
+    }
+
+    /// Set the primary key of the row written.
+    pub fn with_key(mut self, key: JsonValue) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Parse a `{"table", "op", "key"}` payload, such as one sent by the
+    /// trigger from [`notify_trigger_sql`].
+    pub fn from_json(payload: &str) -> QueryResult<Self> {
+        serde_json::from_str(payload)
+            .map_err(|e| QueryError::serialization(format!("Invalid change event: {}", e)))
+    }
+
+    /// Encode the event as a JSON payload.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// A source of change events, such as a CDC feed or a `LISTEN` connection.
+pub trait ChangeSource: Send + Sync + 'static {
+    /// Subscribe to changes to `table`.
+    ///
+    /// Live queries end when the stream ends, unless they also poll.
+    fn changes(&self, table: &str) -> BoxStream<'static, ChangeEvent>;
+}
+
+/// An in-process [`ChangeSource`] that events are published to.
+///
+/// Publish the application's own writes, or bridge an external feed into it.
+/// Subscribers that fall behind by more than the capacity receive a single
+/// `Update` event for the table, which makes live queries resynchronize.
+#[derive(Debug, Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<ChangeEvent>,
+}
+
+impl ChangeFeed {
+    /// Create a feed buffering up to `capacity` events per subscriber.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Publish an event to current subscribers.
+    pub fn publish(&self, event: ChangeEvent) {
+        // No subscribers is not an error
+        let _ = self.sender.send(event);
+    }
+
+    /// The number of current subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl ChangeSource for ChangeFeed {
+    fn changes(&self, table: &str) -> BoxStream<'static, ChangeEvent> {
+        let table = table.to_string();
+        let receiver = self.sender.subscribe();
+        Box::pin(stream::unfold(receiver, move |mut receiver| {
+            let table = table.clone();
+            async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(event) if event.table == table => return Some((event, receiver)),
+                        Ok(_) => continue,
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            return Some((ChangeEvent::new(table, ChangeOp::Update), receiver));
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            }
+        }))
+    }
+}
+
+/// An update to a live query's results.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent<M> {
+    /// The current results; always the first event.
+    Snapshot {
+        /// The matching records.
+        records: Vec<M>,
+    },
+    /// A record now matches the query.
+    Insert {
+        /// The record.
+        record: M,
+    },
+    /// A matching record changed.
+    Update {
+        /// The record as it is now.
+        record: M,
+    },
+    /// A record no longer matches the query.
+    Delete {
+        /// The record's primary key, as an object of key columns.
+        key: JsonValue,
+    },
+}
+
+/// A live `find_many`, created with
+/// [`FindManyOperation::live`](crate::operations::FindManyOperation::live).
+pub struct LiveQuery<E: QueryEngine, M: Model> {
+    engine: E,
+    sql: String,
+    params: Vec<FilterValue>,
+    source: Option<Arc<dyn ChangeSource>>,
+    poll_interval: Option<Duration>,
+    _model: PhantomData<M>,
+}
+
+impl<E: QueryEngine, M: Model> LiveQuery<E, M> {
+    /// Create a live query running `sql`.
+    pub fn new(engine: E, sql: String, params: Vec<FilterValue>) -> Self {
+        Self {
+            engine,
+            sql,
+            params,
+            source: None,
+            poll_interval: None,
+            _model: PhantomData,
+        }
+    }
+
+    /// Re-run the query when `source` reports a change to the model's table.
+    ///
+    /// Polling is off unless [`poll_every`](Self::poll_every) is also set.
+    pub fn changes(mut self, source: impl ChangeSource) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// Re-run the query every `interval`, in addition to any change source.
+    ///
+    /// Without a change source, live queries poll every
+    /// [`DEFAULT_POLL_INTERVAL`].
+    pub fn poll_every(mut self, interval: Duration) -> Self {
+        self.poll_interval = Some(interval);
+        self
+    }
+
+    /// Get the SQL the live query runs.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Start the live query.
+    ///
+    /// The stream yields the snapshot, then one event per changed record.
+    /// Failed refreshes yield an error and the query carries on.
+    pub fn stream(self) -> BoxStream<'static, QueryResult<LiveEvent<M>>>
+    where
+        M: Serialize + Send + 'static,
+    {
+        let changes = self.source.map(|source| source.changes(M::TABLE_NAME));
+        let poll_interval = match (&changes, self.poll_interval) {
+            (None, None) => Some(DEFAULT_POLL_INTERVAL),
+            (_, interval) => interval,
+        };
+        let ticker = poll_interval.map(|interval| {
+            let mut ticker =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker
+        });
+
+        let state = LiveState {
+            engine: self.engine,
+            sql: self.sql,
+            params: self.params,
+            changes,
+            ticker,
+            known: None,
+            failed: false,
+            pending: VecDeque::new(),
+            _model: PhantomData,
+        };
+        Box::pin(stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((Ok(event), state));
+                }
+                // The snapshot is taken straight away, unless it just failed
+                if (state.known.is_some() || state.failed) && !state.wait().await {
+                    return None;
+                }
+                state.failed = false;
+                if let Err(e) = state.refresh().await {
+                    state.failed = true;
+                    return Some((Err(e), state));
+                }
+            }
+        }))
+    }
+}
+
+struct LiveState<E, M> {
+    engine: E,
+    sql: String,
+    params: Vec<FilterValue>,
+    changes: Option<BoxStream<'static, ChangeEvent>>,
+    ticker: Option<tokio::time::Interval>,
+    /// Serialized records by key, once the snapshot has been taken.
+    known: Option<HashMap<String, (JsonValue, JsonValue)>>,
+    failed: bool,
+    pending: VecDeque<LiveEvent<M>>,
+    _model: PhantomData<M>,
+}
+
+enum Trigger {
+    Change,
+    Tick,
+    Closed,
+}
+
+impl<E: QueryEngine, M: Model + Serialize + Send + 'static> LiveState<E, M> {
+    /// Wait until the results may have changed; false once nothing can
+    /// trigger a refresh any more.
+    async fn wait(&mut self) -> bool {
+        let trigger = match (&mut self.changes, &mut self.ticker) {
+            (Some(changes), Some(ticker)) => tokio::select! {
+                event = changes.next() => if event.is_some() { Trigger::Change } else { Trigger::Closed },
+                _ = ticker.tick() => Trigger::Tick,
+            },
+            (Some(changes), None) => match changes.next().await {
+                Some(_) => Trigger::Change,
+                None => Trigger::Closed,
+            },
+            (None, Some(ticker)) => {
+                ticker.tick().await;
+                Trigger::Tick
+            }
+            (None, None) => return false,
+        };
+
+        match trigger {
+            Trigger::Change => {
+                // One refresh covers a burst of writes
+                if let Some(changes) = &mut self.changes {
+                    while let Some(Some(_)) = changes.next().now_or_never() {}
+                }
+                true
+            }
+            Trigger::Tick => true,
+            Trigger::Closed => {
+                self.changes = None;
+                self.ticker.is_some()
+            }
+        }
+    }
+
+    /// Re-run the query and queue events for the differences.
+    async fn refresh(&mut self) -> QueryResult<()> {
+        let records = self
+            .engine
+            .query_many::<M>(&self.sql, self.params.clone())
+            .await?;
+
+        let mut current = HashMap::with_capacity(records.len());
+        let mut changed = Vec::new();
+        for record in &records {
+            let value = serde_json::to_value(record).map_err(|e| {
+                QueryError::serialization(format!("Failed to serialize {}: {}", M::MODEL_NAME, e))
+            })?;
+            let key = record_key::<M>(&value);
+            let id = key.to_string();
+            let event = match self.known.as_mut().and_then(|known| known.remove(&id)) {
+                None => Some(ChangeOp::Insert),
+                Some((_, previous)) if previous != value => Some(ChangeOp::Update),
+                Some(_) => None,
+            };
+            changed.push(event);
+            current.insert(id, (key, value));
+        }
+
+        match self.known.replace(current) {
+            None => self.pending.push_back(LiveEvent::Snapshot { records }),
+            Some(removed) => {
+                for (record, op) in records.into_iter().zip(changed) {
+                    match op {
+                        Some(ChangeOp::Insert) => {
+                            self.pending.push_back(LiveEvent::Insert { record })
+                        }
+                        Some(_) => self.pending.push_back(LiveEvent::Update { record }),
+                        None => {}
+                    }
+                }
+                self.pending.extend(
+                    removed
+                        .into_values()
+                        .map(|(key, _)| LiveEvent::Delete { key }),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The primary key columns of a serialized record.
+fn record_key<M: Model>(value: &JsonValue) -> JsonValue {
+    let key: Map<String, JsonValue> = M::PRIMARY_KEY
+        .iter()
+        .map(|column| {
+            let field = value.get(*column).cloned().unwrap_or(JsonValue::Null);
+            (column.to_string(), field)
+        })
+        .collect();
+    JsonValue::Object(key)
+}
+
+/// PostgreSQL trigger that sends a [`ChangeEvent`] to `channel` with
+/// `pg_notify` after every insert, update and delete on the model's table.
+///
+/// Pair it with a `LISTEN` connection publishing into a [`ChangeFeed`].
+pub fn notify_trigger_sql<M: Model>(channel: &str) -> String {
+    let table = M::TABLE_NAME;
+    let name = format!("prax_notify_{}", table.replace('.', "_"));
+    let key = M::PRIMARY_KEY
+        .iter()
+        .map(|column| format!("'{}', r.{}", column, quote_identifier(column)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "CREATE OR REPLACE FUNCTION {name}() RETURNS trigger AS $$\n\
+         DECLARE\n    r RECORD;\n\
+         BEGIN\n    \
+         IF TG_OP = 'DELETE' THEN r := OLD; ELSE r := NEW; END IF;\n    \
+         PERFORM pg_notify('{channel}', json_build_object('table', TG_TABLE_NAME, 'op', TG_OP, 'key', json_build_object({key}))::text);\n    \
+         RETURN NULL;\n\
+         END;\n\
+         $$ LANGUAGE plpgsql;\n\
+         DROP TRIGGER IF EXISTS {name} ON {table};\n\
+         CREATE TRIGGER {name} AFTER INSERT OR UPDATE OR DELETE ON {table}\n    \
+         FOR EACH ROW EXECUTE FUNCTION {name}();",
+        name = name,
+        channel = channel.replace('\'', "''"),
+        key = key,
+        table = quote_table_name(table),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::BoxFuture;
+    use std::any::Any;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    struct User {
+        id: i64,
+        name: String,
+    }
+
+    impl Model for User {
+        const MODEL_NAME: &'static str = "User";
+        const TABLE_NAME: &'static str = "users";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "name"];
+    }
+
+    fn user(id: i64, name: &str) -> User {
+        User {
+            id,
+            name: name.to_string(),
+        }
+    }
+
+    /// Returns the current contents of a shared table.
+    #[derive(Clone, Default)]
+    struct TableEngine {
+        users: Arc<Mutex<Vec<User>>>,
+    }
+
+    impl TableEngine {
+        fn rows<T: 'static>(&self) -> QueryResult<Vec<T>> {
+            let rows: Box<dyn Any> = Box::new(self.users.lock().unwrap().clone());
+            rows.downcast::<Vec<T>>()
+                .map(|rows| *rows)
+                .map_err(|_| QueryError::internal("unexpected model"))
+        }
+    }
+
+    impl QueryEngine for TableEngine {
+        fn query_many<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<T>>> {
+            let rows = self.rows();
+            Box::pin(async move { rows })
+        }
+
+        fn query_one<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<T>> {
+            Box::pin(async { Err(QueryError::not_found("User")) })
+        }
+
+        fn query_optional<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Option<T>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn execute_insert<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<T>> {
+            Box::pin(async { Err(QueryError::not_found("User")) })
+        }
+
+        fn execute_update<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn execute_delete(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn execute_raw(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn count(&self, _sql: &str, _params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_live_query_changes() {
+        let engine = TableEngine::default();
+        *engine.users.lock().unwrap() = vec![user(1, "alice"), user(2, "bob")];
+        let feed = ChangeFeed::new(16);
+
+        let mut live = LiveQuery::<_, User>::new(engine.clone(), "SELECT".into(), Vec::new())
+            .changes(feed.clone())
+            .stream();
+
+        let snapshot = live.next().await.unwrap().unwrap();
+        assert_eq!(
+            snapshot,
+            LiveEvent::Snapshot {
+                records: vec![user(1, "alice"), user(2, "bob")]
+            }
+        );
+
+        *engine.users.lock().unwrap() = vec![user(1, "alicia"), user(3, "carol")];
+        feed.publish(ChangeEvent::new("posts", ChangeOp::Insert));
+        feed.publish(ChangeEvent::new("users", ChangeOp::Update));
+        feed.publish(ChangeEvent::new("users", ChangeOp::Insert));
+
+        let mut events = Vec::new();
+        for _ in 0..3 {
+            events.push(live.next().await.unwrap().unwrap());
+        }
+        assert_eq!(
+            events,
+            [
+                LiveEvent::Update {
+                    record: user(1, "alicia")
+                },
+                LiveEvent::Insert {
+                    record: user(3, "carol")
+                },
+                LiveEvent::Delete {
+                    key: serde_json::json!({"id": 2})
+                },
+            ]
+        );
+
+        // The burst was coalesced into one refresh; closing the feed ends
+        // the query
+        drop(feed);
+        assert!(live.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_live_query_polling() {
+        let engine = TableEngine::default();
+        let mut live = LiveQuery::<_, User>::new(engine.clone(), "SELECT".into(), Vec::new())
+            .poll_every(Duration::from_millis(10))
+            .stream();
+
+        assert_eq!(
+            live.next().await.unwrap().unwrap(),
+            LiveEvent::Snapshot { records: vec![] }
+        );
+
+        engine.users.lock().unwrap().push(user(7, "dave"));
+        assert_eq!(
+            live.next().await.unwrap().unwrap(),
+            LiveEvent::Insert {
+                record: user(7, "dave")
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_event_json() {
+        let event =
+            ChangeEvent::from_json(r#"{"table":"users","op":"DELETE","key":{"id":4}}"#).unwrap();
+        assert_eq!(
+            event,
+            ChangeEvent::new("users", ChangeOp::Delete).with_key(serde_json::json!({"id": 4}))
+        );
+        assert_eq!(ChangeEvent::from_json(&event.to_json()).unwrap(), event);
+        assert!(ChangeEvent::from_json(r#"{"table":"users"}"#).is_err());
+
+        let live: LiveEvent<User> = LiveEvent::Insert {
+            record: user(1, "alice"),
+        };
+        assert_eq!(
+            serde_json::to_string(&live).unwrap(),
+            r#"{"type":"insert","record":{"id":1,"name":"alice"}}"#
+        );
+    }
+
+    #[test]
+    fn test_notify_trigger_sql() {
+        let sql = notify_trigger_sql::<User>("prax_changes");
+        assert!(sql.contains("CREATE OR REPLACE FUNCTION prax_notify_users()"));
+        assert!(sql.contains("pg_notify('prax_changes'"));
+        assert!(sql.contains("json_build_object('id', r.id)"));
+        assert!(sql.contains("AFTER INSERT OR UPDATE OR DELETE ON users"));
+    }
+}
@@ -0,0 +1,623 @@
+//! Prometheus export of query metrics.
+//!
+//! [`PrometheusExporter`] is a [`MetricsCollector`], so it plugs straight into
+//! [`MetricsMiddleware`](crate::middleware::MetricsMiddleware). On top of the
+//! aggregate [`QueryMetrics`] it keeps per model and operation counters and
+//! latency histograms, and renders everything in the Prometheus text
+//! exposition format:
+//!
+//! ```rust,ignore
+//! use prax_query::metrics::{PoolSnapshot, PrometheusExporter};
+//! use prax_query::middleware::MetricsMiddleware;
+//! use std::sync::Arc;
+//!
+//! let exporter = Arc::new(PrometheusExporter::new());
+//! let client = client.with_middleware(MetricsMiddleware::new(exporter.clone()));
+//!
+//! // Pool gauges are read on every scrape
+//! let pool = pg_pool.clone();
+//! exporter.register_pool("primary", move || {
+//!     let status = pool.status();
+//!     PoolSnapshot::new(status.size, status.available, status.max_size)
+//!         .with_waiting(status.waiting)
+//! });
+//!
+//! // Serve this from a /metrics endpoint
+//! let body = exporter.render();
+//! ```
+//!
+//! `prax-axum` and `prax-actix` ship ready-made `/metrics` routes.
+//!
+//! Queries tagged for cost attribution (see [`crate::tags`]) are counted
+//! once per `key:value` tag.
+//!
+//! The exported series, with the default `prax` namespace:
+//!
+//! | Metric | Type | Labels |
+//! |--------|------|--------|
+//! | `prax_queries_total` | counter | `model`, `operation`, `status` |
+//! | `prax_query_duration_seconds` | histogram | `model`, `operation` |
+//! | `prax_tagged_queries_total` | counter | `tag`, `status` |
+//! | `prax_tagged_query_seconds_total` | counter | `tag` |
+//! | `prax_cache_hits_total` / `prax_cache_misses_total` | counter | |
+//! | `prax_cache_hit_ratio` | gauge | |
+//! | `prax_pool_connections` | gauge | `pool`, `state` (`active`, `idle`) |
+//! | `prax_pool_max_connections` | gauge | `pool` |
+//! | `prax_pool_waiting` | gauge | `pool` |
+//! | `prax_pool_utilization` | gauge | `pool` |
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::middleware::{InMemoryMetricsCollector, MetricsCollector, QueryMetrics, QueryType};
+use crate::tags::QueryTags;
+
+/// Content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Default latency histogram buckets, in seconds.
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Point-in-time utilization of a connection pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolSnapshot {
+    /// Connections currently open.
+    pub size: usize,
+    /// Open connections that are idle.
+    pub idle: usize,
+    /// Maximum number of connections.
+    pub max_size: usize,
+    /// Tasks waiting for a connection.
+    pub waiting: usize,
+}
+
+impl PoolSnapshot {
+    /// Create a snapshot from the pool's size, idle count and maximum size.
+    pub fn new(size: usize, idle: usize, max_size: usize) -> Self {
+        Self {
+            size,
+            idle,
+            max_size,
+            waiting: 0,
+        }
+    }
+
+    /// Set the number of tasks waiting for a connection.
+    pub fn with_waiting(mut self, waiting: usize) -> Self {
+        self.waiting = waiting;
+        self
+    }
+
+    /// Connections checked out of the pool.
+    pub fn active(&self) -> usize {
+        self.size.saturating_sub(self.idle)
+    }
+
+    /// Share of the maximum pool size in use (0.0 to 1.0).
+    pub fn utilization(&self) -> f64 {
+        if self.max_size == 0 {
+            0.0
+        } else {
+            self.active() as f64 / self.max_size as f64
+        }
+    }
+}
+
+type PoolSource = Box<dyn Fn() -> PoolSnapshot + Send + Sync>;
+
+/// Counters and latency histogram of one model and operation, or of one
+/// tag without the histogram.
+#[derive(Debug, Default)]
+struct Series {
+    ok: u64,
+    error: u64,
+    buckets: Vec<u64>,
+    sum_us: u64,
+}
+
+/// Metrics collector that renders Prometheus text format.
+///
+/// # Example
+///
+/// ```rust
+/// use prax_query::metrics::PrometheusExporter;
+/// use prax_query::middleware::{MetricsCollector, QueryType};
+///
+/// let exporter = PrometheusExporter::new();
+/// exporter.record_query(QueryType::Select, Some("User"), 1_200, true, false);
+///
+/// let text = exporter.render();
+/// assert!(text.contains(
+///     r#"prax_queries_total{model="User",operation="select",status="ok"} 1"#
+/// ));
+/// ```
+pub struct PrometheusExporter {
+    namespace: String,
+    buckets: Vec<f64>,
+    totals: InMemoryMetricsCollector,
+    series: RwLock<BTreeMap<(String, &'static str), Series>>,
+    tagged: RwLock<BTreeMap<String, Series>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    pools: RwLock<Vec<(String, PoolSource)>>,
+}
+
+impl PrometheusExporter {
+    /// Create an exporter with the `prax` namespace and default buckets.
+    pub fn new() -> Self {
+        Self {
+            namespace: "prax".to_string(),
+            buckets: DEFAULT_BUCKETS.to_vec(),
+            totals: InMemoryMetricsCollector::new(),
+            series: RwLock::new(BTreeMap::new()),
+            tagged: RwLock::new(BTreeMap::new()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            pools: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Prefix metric names with `namespace` instead of `prax`.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Use custom latency buckets, as upper bounds in seconds.
+    pub fn with_buckets(mut self, buckets: impl IntoIterator<Item = f64>) -> Self {
+        let mut buckets: Vec<f64> = buckets.into_iter().filter(|b| b.is_finite()).collect();
+        buckets.sort_by(|a, b| a.total_cmp(b));
+        buckets.dedup();
+        self.buckets = buckets;
+        self
+    }
+
+    /// Report the utilization of a connection pool.
+    ///
+    /// `snapshot` is called on every [`render`](Self::render). Registering a
+    /// name twice replaces the earlier source.
+    pub fn register_pool<F>(&self, name: impl Into<String>, snapshot: F)
+    where
+        F: Fn() -> PoolSnapshot + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let mut pools = self.pools.write().unwrap();
+        pools.retain(|(existing, _)| *existing != name);
+        pools.push((name, Box::new(snapshot)));
+    }
+
+    /// Share of successful queries served from the cache (0.0 to 1.0).
+    pub fn cache_hit_ratio(&self) -> f64 {
+        let hits = self.cache_hits.load(Ordering::SeqCst);
+        let misses = self.cache_misses.load(Ordering::SeqCst);
+        if hits + misses == 0 {
+            0.0
+        } else {
+            hits as f64 / (hits + misses) as f64
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let ns = &self.namespace;
+        let mut out = String::new();
+
+        {
+            let series = self.series.read().unwrap();
+
+            header(
+                &mut out,
+                ns,
+                "queries_total",
+                "counter",
+                "Queries executed.",
+            );
+            for ((model, operation), s) in series.iter() {
+                let labels = format!(
+                    "model=\"{}\",operation=\"{}\"",
+                    escape_label(model),
+                    operation
+                );
+                for (status, count) in [("ok", s.ok), ("error", s.error)] {
+                    if count > 0 {
+                        let _ = writeln!(
+                            out,
+                            "{ns}_queries_total{{{labels},status=\"{status}\"}} {count}"
+                        );
+                    }
+                }
+            }
+
+            header(
+                &mut out,
+                ns,
+                "query_duration_seconds",
+                "histogram",
+                "Query latency in seconds.",
+            );
+            for ((model, operation), s) in series.iter() {
+                let labels = format!(
+                    "model=\"{}\",operation=\"{}\"",
+                    escape_label(model),
+                    operation
+                );
+                let count = s.ok + s.error;
+                let mut cumulative = 0;
+                for (bound, hits) in self.buckets.iter().zip(&s.buckets) {
+                    cumulative += hits;
+                    let _ = writeln!(
+                        out,
+                        "{ns}_query_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {cumulative}"
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "{ns}_query_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {count}"
+                );
+                let _ = writeln!(
+                    out,
+                    "{ns}_query_duration_seconds_sum{{{labels}}} {}",
+                    s.sum_us as f64 / 1_000_000.0
+                );
+                let _ = writeln!(out, "{ns}_query_duration_seconds_count{{{labels}}} {count}");
+            }
+        }
+
+        {
+            let tagged = self.tagged.read().unwrap();
+            if !tagged.is_empty() {
+                header(
+                    &mut out,
+                    ns,
+                    "tagged_queries_total",
+                    "counter",
+                    "Queries executed by tag.",
+                );
+                for (tag, s) in tagged.iter() {
+                    let tag = escape_label(tag);
+                    for (status, count) in [("ok", s.ok), ("error", s.error)] {
+                        if count > 0 {
+                            let _ = writeln!(
+                                out,
+                                "{ns}_tagged_queries_total{{tag=\"{tag}\",status=\"{status}\"}} {count}"
+                            );
+                        }
+                    }
+                }
+                header(
+                    &mut out,
+                    ns,
+                    "tagged_query_seconds_total",
+                    "counter",
+                    "Time spent in queries by tag, in seconds.",
+                );
+                for (tag, s) in tagged.iter() {
+                    let _ = writeln!(
+                        out,
+                        "{ns}_tagged_query_seconds_total{{tag=\"{}\"}} {}",
+                        escape_label(tag),
+                        s.sum_us as f64 / 1_000_000.0
+                    );
+                }
+            }
+        }
+
+        header(
+            &mut out,
+            ns,
+            "cache_hits_total",
+            "counter",
+            "Successful queries served from the cache.",
+        );
+        let _ = writeln!(
+            out,
+            "{ns}_cache_hits_total {}",
+            self.cache_hits.load(Ordering::SeqCst)
+        );
+        header(
+            &mut out,
+            ns,
+            "cache_misses_total",
+            "counter",
+            "Successful queries that reached the database.",
+        );
+        let _ = writeln!(
+            out,
+            "{ns}_cache_misses_total {}",
+            self.cache_misses.load(Ordering::SeqCst)
+        );
+        header(
+            &mut out,
+            ns,
+            "cache_hit_ratio",
+            "gauge",
+            "Share of successful queries served from the cache.",
+        );
+        let _ = writeln!(out, "{ns}_cache_hit_ratio {}", self.cache_hit_ratio());
+
+        let pools = self.pools.read().unwrap();
+        if !pools.is_empty() {
+            let snapshots: Vec<(String, PoolSnapshot)> = pools
+                .iter()
+                .map(|(name, source)| (escape_label(name), source()))
+                .collect();
+
+            header(
+                &mut out,
+                ns,
+                "pool_connections",
+                "gauge",
+                "Open pool connections by state.",
+            );
+            for (pool, s) in &snapshots {
+                let _ = writeln!(
+                    out,
+                    "{ns}_pool_connections{{pool=\"{pool}\",state=\"active\"}} {}",
+                    s.active()
+                );
+                let _ = writeln!(
+                    out,
+                    "{ns}_pool_connections{{pool=\"{pool}\",state=\"idle\"}} {}",
+                    s.idle
+                );
+            }
+            header(
+                &mut out,
+                ns,
+                "pool_max_connections",
+                "gauge",
+                "Maximum pool size.",
+            );
+            for (pool, s) in &snapshots {
+                let _ = writeln!(
+                    out,
+                    "{ns}_pool_max_connections{{pool=\"{pool}\"}} {}",
+                    s.max_size
+                );
+            }
+            header(
+                &mut out,
+                ns,
+                "pool_waiting",
+                "gauge",
+                "Tasks waiting for a connection.",
+            );
+            for (pool, s) in &snapshots {
+                let _ = writeln!(out, "{ns}_pool_waiting{{pool=\"{pool}\"}} {}", s.waiting);
+            }
+            header(
+                &mut out,
+                ns,
+                "pool_utilization",
+                "gauge",
+                "Share of the maximum pool size in use.",
+            );
+            for (pool, s) in &snapshots {
+                let _ = writeln!(
+                    out,
+                    "{ns}_pool_utilization{{pool=\"{pool}\"}} {}",
+                    s.utilization()
+                );
+            }
+        }
+
+        out
+    }
+}
+
+impl Default for PrometheusExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for PrometheusExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrometheusExporter")
+            .field("namespace", &self.namespace)
+            .field("buckets", &self.buckets)
+            .field("pools", &self.pools.read().unwrap().len())
+            .finish()
+    }
+}
+
+impl MetricsCollector for PrometheusExporter {
+    fn record_query(
+        &self,
+        query_type: QueryType,
+        model: Option<&str>,
+        duration_us: u64,
+        success: bool,
+        from_cache: bool,
+    ) {
+        self.totals
+            .record_query(query_type, model, duration_us, success, from_cache);
+
+        if success {
+            let counter = if from_cache {
+                &self.cache_hits
+            } else {
+                &self.cache_misses
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let seconds = duration_us as f64 / 1_000_000.0;
+        let mut series = self.series.write().unwrap();
+        let s = series
+            .entry((model.unwrap_or_default().to_string(), query_type.as_str()))
+            .or_default();
+        if success {
+            s.ok += 1;
+        } else {
+            s.error += 1;
+        }
+        s.sum_us += duration_us;
+        if s.buckets.is_empty() {
+            s.buckets = vec![0; self.buckets.len()];
+        }
+        // Per-bucket counts; render() accumulates them
+        if let Some(i) = self.buckets.iter().position(|bound| seconds <= *bound) {
+            s.buckets[i] += 1;
+        }
+    }
+
+    fn record_tags(&self, tags: &QueryTags, duration_us: u64, success: bool) {
+        self.totals.record_tags(tags, duration_us, success);
+
+        let mut tagged = self.tagged.write().unwrap();
+        for label in tags.labels() {
+            let s = tagged.entry(label).or_default();
+            if success {
+                s.ok += 1;
+            } else {
+                s.error += 1;
+            }
+            s.sum_us += duration_us;
+        }
+    }
+
+    fn get_metrics(&self) -> QueryMetrics {
+        self.totals.get_metrics()
+    }
+
+    fn reset(&self) {
+        self.totals.reset();
+        self.series.write().unwrap().clear();
+        self.tagged.write().unwrap().clear();
+        self.cache_hits.store(0, Ordering::SeqCst);
+        self.cache_misses.store(0, Ordering::SeqCst);
+    }
+}
+
+fn header(out: &mut String, namespace: &str, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {namespace}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {namespace}_{name} {kind}");
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_and_histogram() {
+        let exporter = PrometheusExporter::new().with_buckets([0.001, 0.01, 0.1]);
+        exporter.record_query(QueryType::Select, Some("User"), 500, true, false);
+        exporter.record_query(QueryType::Select, Some("User"), 5_000, true, false);
+        exporter.record_query(QueryType::Select, Some("User"), 2_000_000, false, false);
+        exporter.record_query(QueryType::Insert, None, 800, true, false);
+
+        let text = exporter.render();
+        assert!(text.contains("# TYPE prax_queries_total counter"));
+        assert!(
+            text.contains(r#"prax_queries_total{model="User",operation="select",status="ok"} 2"#)
+        );
+        assert!(
+            text.contains(
+                r#"prax_queries_total{model="User",operation="select",status="error"} 1"#
+            )
+        );
+        assert!(text.contains(r#"prax_queries_total{model="",operation="insert",status="ok"} 1"#));
+        assert!(!text.contains(r#"operation="insert",status="error""#));
+
+        assert!(text.contains(
+            r#"prax_query_duration_seconds_bucket{model="User",operation="select",le="0.001"} 1"#
+        ));
+        assert!(text.contains(
+            r#"prax_query_duration_seconds_bucket{model="User",operation="select",le="0.01"} 2"#
+        ));
+        assert!(text.contains(
+            r#"prax_query_duration_seconds_bucket{model="User",operation="select",le="0.1"} 2"#
+        ));
+        assert!(text.contains(
+            r#"prax_query_duration_seconds_bucket{model="User",operation="select",le="+Inf"} 3"#
+        ));
+        assert!(text.contains(
+            r#"prax_query_duration_seconds_sum{model="User",operation="select"} 2.0055"#
+        ));
+        assert!(
+            text.contains(
+                r#"prax_query_duration_seconds_count{model="User",operation="select"} 3"#
+            )
+        );
+
+        assert_eq!(exporter.get_metrics().total_queries, 4);
+    }
+
+    #[test]
+    fn test_cache_ratio() {
+        let exporter = PrometheusExporter::new();
+        exporter.record_query(QueryType::Select, Some("Post"), 100, true, true);
+        exporter.record_query(QueryType::Select, Some("Post"), 100, true, true);
+        exporter.record_query(QueryType::Select, Some("Post"), 100, true, false);
+        exporter.record_query(QueryType::Select, Some("Post"), 100, false, false);
+
+        assert!((exporter.cache_hit_ratio() - 2.0 / 3.0).abs() < 1e-9);
+        let text = exporter.render();
+        assert!(text.contains("prax_cache_hits_total 2\n"));
+        assert!(text.contains("prax_cache_misses_total 1\n"));
+
+        exporter.reset();
+        assert_eq!(exporter.cache_hit_ratio(), 0.0);
+        assert!(!exporter.render().contains("prax_queries_total{"));
+    }
+
+    #[test]
+    fn test_pool_gauges() {
+        let exporter = PrometheusExporter::new().with_namespace("app");
+        assert!(!exporter.render().contains("app_pool_"));
+
+        exporter.register_pool("primary", || PoolSnapshot::new(8, 2, 10).with_waiting(3));
+        let text = exporter.render();
+        assert!(text.contains(r#"app_pool_connections{pool="primary",state="active"} 6"#));
+        assert!(text.contains(r#"app_pool_connections{pool="primary",state="idle"} 2"#));
+        assert!(text.contains(r#"app_pool_max_connections{pool="primary"} 10"#));
+        assert!(text.contains(r#"app_pool_waiting{pool="primary"} 3"#));
+        assert!(text.contains(r#"app_pool_utilization{pool="primary"} 0.6"#));
+
+        exporter.register_pool("primary", || PoolSnapshot::new(1, 1, 10));
+        let text = exporter.render();
+        assert_eq!(text.matches("app_pool_max_connections{").count(), 1);
+        assert!(text.contains(r#"app_pool_utilization{pool="primary"} 0"#));
+    }
+
+    #[test]
+    fn test_tagged_queries() {
+        let exporter = PrometheusExporter::new();
+        assert!(!exporter.render().contains("prax_tagged_"));
+
+        let tags: QueryTags = ["team:checkout", "feature:cart"].into_iter().collect();
+        exporter.record_tags(&tags, 250_000, true);
+        exporter.record_tags(&tags, 500_000, false);
+        exporter.record_tags(&["team:search"].into_iter().collect(), 1_000, true);
+
+        let text = exporter.render();
+        assert!(text.contains(r#"prax_tagged_queries_total{tag="team:checkout",status="ok"} 1"#));
+        assert!(
+            text.contains(r#"prax_tagged_queries_total{tag="team:checkout",status="error"} 1"#)
+        );
+        assert!(text.contains(r#"prax_tagged_queries_total{tag="team:search",status="ok"} 1"#));
+        assert!(text.contains(r#"prax_tagged_query_seconds_total{tag="feature:cart"} 0.75"#));
+        assert_eq!(exporter.get_metrics().queries_by_tag["team:checkout"], 2);
+
+        exporter.reset();
+        assert!(!exporter.render().contains("prax_tagged_"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_label("a\nb"), "a\\nb");
+    }
+}
@@ -0,0 +1,787 @@
+//! Row-level audit logging of writes to audited tables.
+//!
+//! [`AuditMiddleware`] records an [`AuditEntry`] for every row an `INSERT`,
+//! `UPDATE` or `DELETE` changes in a table registered with
+//! [`AuditMiddleware::audit`], typically a model's generated `audit_table()`
+//! (`@@audited`). Each entry holds the row's before and after images, the
+//! columns that changed, and who made the change.
+//!
+//! - Before images of updates and deletes are read with a `SELECT` over the
+//!   statement's `WHERE` clause, just before it runs.
+//! - After images of creates and updates are the rows the statement returns
+//!   (`RETURNING`). Updates that return no rows are read back by key.
+//!   Creates are only recorded from returned rows.
+//! - Updates that change no column are not recorded, and excluded columns
+//!   never appear in an entry.
+//!
+//! The actor is the query's `user_id`, else its [`ACTOR_TAG`] tag, else the
+//! `"actor"` metadata of the current tenant (see [`crate::tenant`]).
+//!
+//! Entries are written to the `_prax_audit_log` table by default, through the
+//! rest of the middleware chain, so they share the statement's connection and
+//! transaction; [`AuditMiddleware::create_table_sql`] gives its DDL. An
+//! [`AuditSink`] records them elsewhere instead. Either way, a failure to
+//! record fails the statement.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use prax_query::middleware::{AuditMiddleware, MiddlewareStack};
+//! use prax_query::sql::DatabaseType;
+//!
+//! let audit = AuditMiddleware::new(DatabaseType::PostgreSQL)
+//!     .audit(user::audit_table())
+//!     .audit(order::audit_table());
+//! engine.execute_raw(&audit.create_table_sql(), vec![]).await?;
+//!
+//! let stack = MiddlewareStack::new().with(audit);
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use super::context::{QueryContext, QueryMetadata, QueryType};
+use super::dual_write::{key_of, quote, select_by_keys, translate_statement, write_table};
+use super::policy::update_or_delete_target;
+use super::types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
+use crate::error::QueryResult;
+use crate::filter::FilterValue;
+use crate::sql::DatabaseType;
+use crate::tenant::{current_tenant, current_tenant_id};
+
+/// Metadata tag (and tenant metadata key) naming who runs a query.
+pub const ACTOR_TAG: &str = "actor";
+
+/// Default name of the audit log table.
+pub const AUDIT_LOG_TABLE: &str = "_prax_audit_log";
+
+/// Columns of the audit log table, after its generated id.
+const LOG_COLUMNS: &[&str] = &[
+    "table_name",
+    "operation",
+    "record_key",
+    "actor",
+    "tenant_id",
+    "request_id",
+    "changed_columns",
+    "before_image",
+    "after_image",
+    "created_at",
+];
+
+/// A table whose changes are audited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedTable {
+    table: String,
+    key: Vec<String>,
+    excluded: Vec<String>,
+}
+
+impl AuditedTable {
+    /// Audit `table`, identifying rows by the key columns.
+    pub fn new<I, S>(table: impl Into<String>, key: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            table: table.into(),
+            key: key.into_iter().map(Into::into).collect(),
+            excluded: Vec::new(),
+        }
+    }
+
+    /// Leave columns, such as secrets, out of the recorded images.
+    pub fn exclude<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.excluded.extend(columns.into_iter().map(Into::into));
+        self
+    }
+
+    /// The table name.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// The key columns.
+    pub fn key(&self) -> &[String] {
+        &self.key
+    }
+
+    /// The excluded columns.
+    pub fn excluded(&self) -> &[String] {
+        &self.excluded
+    }
+
+    fn is_excluded(&self, column: &str) -> bool {
+        self.excluded.iter().any(|c| c.eq_ignore_ascii_case(column))
+    }
+
+    /// A row without its excluded columns.
+    fn image(&self, row: &JsonValue) -> JsonMap<String, JsonValue> {
+        row.as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(column, _)| !self.is_excluded(column))
+            .map(|(column, value)| (column.clone(), value.clone()))
+            .collect()
+    }
+
+    /// The key of a row as an object, and its canonical form for matching.
+    fn key_of(&self, row: &JsonValue) -> Option<(JsonValue, String)> {
+        let values = key_of(row, &self.key)?;
+        let canonical = JsonValue::Array(values.clone()).to_string();
+        let key = self.key.iter().cloned().zip(values).collect();
+        Some((JsonValue::Object(key), canonical))
+    }
+}
+
+/// The kind of change an audit entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOperation {
+    /// A row was inserted.
+    Create,
+    /// A row was updated.
+    Update,
+    /// A row was deleted.
+    Delete,
+}
+
+impl AuditOperation {
+    /// The operation's name, as stored in the audit log.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// A recorded change to one row.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditEntry {
+    /// Table the row belongs to.
+    pub table: String,
+    /// Kind of change.
+    pub operation: AuditOperation,
+    /// Key columns and values of the row.
+    pub key: JsonValue,
+    /// Who made the change.
+    pub actor: Option<String>,
+    /// Tenant the change was made for.
+    pub tenant_id: Option<String>,
+    /// Request the change was made in.
+    pub request_id: Option<String>,
+    /// Columns whose values changed.
+    pub changed_columns: Vec<String>,
+    /// The row before the change (`None` for creates).
+    pub before: Option<JsonValue>,
+    /// The row after the change (`None` for deletes, and for updates whose
+    /// row could not be read back).
+    pub after: Option<JsonValue>,
+    /// When the change was made, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+}
+
+/// A destination for audit entries other than the audit log table.
+pub trait AuditSink: Send + Sync + 'static {
+    /// Record the entries of one statement.
+    fn record(&self, entries: Vec<AuditEntry>) -> BoxFuture<'_, QueryResult<()>>;
+}
+
+/// An [`AuditSink`] keeping entries in memory, for tests and debugging.
+///
+/// Clones share their entries.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryAuditSink {
+    entries: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+impl MemoryAuditSink {
+    /// Create an empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The recorded entries, oldest first.
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().clone()
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn record(&self, entries: Vec<AuditEntry>) -> BoxFuture<'_, QueryResult<()>> {
+        self.entries.lock().extend(entries);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[derive(Clone)]
+enum Destination {
+    Table(String),
+    Sink(Arc<dyn AuditSink>),
+}
+
+/// Middleware recording changes to audited tables.
+#[derive(Clone)]
+pub struct AuditMiddleware {
+    db_type: DatabaseType,
+    tables: HashMap<String, AuditedTable>,
+    destination: Destination,
+}
+
+/// Who made a change, resolved once per statement.
+struct Actor {
+    actor: Option<String>,
+    tenant_id: Option<String>,
+    request_id: Option<String>,
+}
+
+impl AuditMiddleware {
+    /// Audit writes to a database of the given type, recording them in the
+    /// `_prax_audit_log` table.
+    pub fn new(db_type: DatabaseType) -> Self {
+        Self {
+            db_type,
+            tables: HashMap::new(),
+            destination: Destination::Table(AUDIT_LOG_TABLE.to_string()),
+        }
+    }
+
+    /// Record entries in another table with the same columns.
+    pub fn log_table(mut self, table: impl Into<String>) -> Self {
+        self.destination = Destination::Table(table.into());
+        self
+    }
+
+    /// Record entries in a sink instead of the audit log table.
+    pub fn with_sink(mut self, sink: impl AuditSink) -> Self {
+        self.destination = Destination::Sink(Arc::new(sink));
+        self
+    }
+
+    /// Audit changes to a table.
+    pub fn audit(mut self, table: AuditedTable) -> Self {
+        self.tables.insert(table.table.to_lowercase(), table);
+        self
+    }
+
+    /// DDL creating the audit log table.
+    ///
+    /// Keys, column lists and row images are stored as JSON text.
+    pub fn create_table_sql(&self) -> String {
+        let table = match &self.destination {
+            Destination::Table(table) => table.as_str(),
+            Destination::Sink(_) => AUDIT_LOG_TABLE,
+        };
+        let (id, text, short, if_not_exists) = match self.db_type {
+            DatabaseType::PostgreSQL => ("BIGSERIAL PRIMARY KEY", "TEXT", "VARCHAR(255)", true),
+            DatabaseType::SQLite => ("INTEGER PRIMARY KEY AUTOINCREMENT", "TEXT", "TEXT", true),
+            DatabaseType::MySQL => (
+                "BIGINT AUTO_INCREMENT PRIMARY KEY",
+                "TEXT",
+                "VARCHAR(255)",
+                true,
+            ),
+            DatabaseType::MSSQL => (
+                "BIGINT IDENTITY(1,1) PRIMARY KEY",
+                "NVARCHAR(MAX)",
+                "NVARCHAR(255)",
+                false,
+            ),
+        };
+        let types = [
+            id,
+            short,
+            "VARCHAR(16)",
+            text,
+            short,
+            short,
+            short,
+            text,
+            text,
+            text,
+            "BIGINT",
+        ];
+        let columns: Vec<String> = std::iter::once("id")
+            .chain(LOG_COLUMNS.iter().copied())
+            .zip(types)
+            .map(|(column, ty)| {
+                let not_null = matches!(
+                    column,
+                    "table_name" | "operation" | "record_key" | "changed_columns" | "created_at"
+                );
+                format!(
+                    "    {} {}{}",
+                    quote(self.db_type, column),
+                    ty,
+                    if not_null { " NOT NULL" } else { "" }
+                )
+            })
+            .collect();
+
+        format!(
+            "CREATE TABLE {}{} (\n{}\n)",
+            if if_not_exists { "IF NOT EXISTS " } else { "" },
+            quote(self.db_type, table),
+            columns.join(",\n")
+        )
+    }
+
+    /// Resolve who runs a statement.
+    fn actor(metadata: &QueryMetadata) -> Actor {
+        let actor = metadata
+            .user_id
+            .clone()
+            .or_else(|| metadata.tags.get(ACTOR_TAG).cloned())
+            .or_else(|| {
+                current_tenant()
+                    .and_then(|tenant| tenant.info.get_metadata::<String>(ACTOR_TAG).cloned())
+            });
+        Actor {
+            actor,
+            tenant_id: metadata
+                .tenant_id
+                .clone()
+                .or_else(|| current_tenant_id().map(|id| id.to_string())),
+            request_id: metadata.request_id.clone(),
+        }
+    }
+
+    /// Build the query reading the rows an UPDATE or DELETE is about to
+    /// change.
+    fn before_query(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+    ) -> Option<(String, Vec<FilterValue>)> {
+        let target = update_or_delete_target(sql)?;
+        let table = &sql[target.table_ref];
+        Some(match target.condition {
+            Some(condition) => {
+                let (condition, params) = self.bind_condition(sql, condition, params);
+                (
+                    format!("SELECT * FROM {} WHERE {}", table, condition.trim()),
+                    params,
+                )
+            }
+            None => (format!("SELECT * FROM {}", table), Vec::new()),
+        })
+    }
+
+    /// Extract a WHERE condition with the parameters it uses.
+    fn bind_condition(
+        &self,
+        sql: &str,
+        condition: Range<usize>,
+        params: &[FilterValue],
+    ) -> (String, Vec<FilterValue>) {
+        let preceding = &sql[..condition.start];
+        let condition = &sql[condition];
+        match self.db_type {
+            DatabaseType::PostgreSQL => {
+                // Renumber the placeholders from $1 by translating there and back
+                let (sql, bound) = translate_statement(
+                    condition,
+                    params,
+                    DatabaseType::PostgreSQL,
+                    DatabaseType::SQLite,
+                );
+                translate_statement(&sql, &bound, DatabaseType::SQLite, DatabaseType::PostgreSQL)
+            }
+            DatabaseType::MySQL | DatabaseType::SQLite => {
+                let count = |sql: &str| {
+                    translate_statement(sql, params, self.db_type, DatabaseType::PostgreSQL)
+                        .1
+                        .len()
+                };
+                let bound = params
+                    .iter()
+                    .skip(count(preceding))
+                    .take(count(condition))
+                    .cloned()
+                    .collect();
+                (condition.to_string(), bound)
+            }
+            // Placeholders are numbered and extra parameters are allowed
+            DatabaseType::MSSQL => (condition.to_string(), params.to_vec()),
+        }
+    }
+
+    /// Pair before and after images into entries.
+    fn entries(
+        &self,
+        table: &AuditedTable,
+        operation: AuditOperation,
+        before: &[JsonValue],
+        after: &[JsonValue],
+        actor: &Actor,
+    ) -> Vec<AuditEntry> {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let entry = |key: JsonValue,
+                     changed_columns: Vec<String>,
+                     before: Option<JsonMap<String, JsonValue>>,
+                     after: Option<JsonMap<String, JsonValue>>| AuditEntry {
+            table: table.table.clone(),
+            operation,
+            key,
+            actor: actor.actor.clone(),
+            tenant_id: actor.tenant_id.clone(),
+            request_id: actor.request_id.clone(),
+            changed_columns,
+            before: before.map(JsonValue::Object),
+            after: after.map(JsonValue::Object),
+            timestamp_ms,
+        };
+
+        match operation {
+            AuditOperation::Create => after
+                .iter()
+                .filter_map(|row| {
+                    let (key, _) = table.key_of(row)?;
+                    let image = table.image(row);
+                    Some(entry(
+                        key,
+                        image.keys().cloned().collect(),
+                        None,
+                        Some(image),
+                    ))
+                })
+                .collect(),
+            AuditOperation::Delete => before
+                .iter()
+                .filter_map(|row| {
+                    let (key, _) = table.key_of(row)?;
+                    let image = table.image(row);
+                    Some(entry(
+                        key,
+                        image.keys().cloned().collect(),
+                        Some(image),
+                        None,
+                    ))
+                })
+                .collect(),
+            AuditOperation::Update => {
+                let after: HashMap<String, &JsonValue> = after
+                    .iter()
+                    .filter_map(|row| Some((table.key_of(row)?.1, row)))
+                    .collect();
+                before
+                    .iter()
+                    .filter_map(|row| {
+                        let (key, canonical) = table.key_of(row)?;
+                        let before = table.image(row);
+                        let Some(row) = after.get(&canonical) else {
+                            return Some(entry(key, Vec::new(), Some(before), None));
+                        };
+                        let after = table.image(row);
+                        let mut changed: Vec<String> = after
+                            .iter()
+                            .filter(|(column, value)| before.get(*column) != Some(*value))
+                            .map(|(column, _)| column.clone())
+                            .collect();
+                        changed.extend(
+                            before
+                                .keys()
+                                .filter(|column| !after.contains_key(*column))
+                                .cloned(),
+                        );
+                        if changed.is_empty() {
+                            return None;
+                        }
+                        Some(entry(key, changed, Some(before), Some(after)))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Build the INSERT writing entries to the audit log table.
+    fn insert_entries(&self, table: &str, entries: &[AuditEntry]) -> (String, Vec<FilterValue>) {
+        let json = |value: &Option<JsonValue>| match value {
+            Some(value) => FilterValue::String(value.to_string()),
+            None => FilterValue::Null,
+        };
+        let text = |value: &Option<String>| match value {
+            Some(value) => FilterValue::String(value.clone()),
+            None => FilterValue::Null,
+        };
+
+        let mut params = Vec::with_capacity(entries.len() * LOG_COLUMNS.len());
+        let rows: Vec<String> = entries
+            .iter()
+            .map(|entry| {
+                let start = params.len();
+                params.extend([
+                    FilterValue::String(entry.table.clone()),
+                    FilterValue::String(entry.operation.as_str().to_string()),
+                    FilterValue::String(entry.key.to_string()),
+                    text(&entry.actor),
+                    text(&entry.tenant_id),
+                    text(&entry.request_id),
+                    FilterValue::String(
+                        serde_json::to_string(&entry.changed_columns).unwrap_or_default(),
+                    ),
+                    json(&entry.before),
+                    json(&entry.after),
+                    FilterValue::Int(entry.timestamp_ms as i64),
+                ]);
+                let placeholders: Vec<_> = (start + 1..=params.len())
+                    .map(|i| self.db_type.placeholder(i))
+                    .collect();
+                format!("({})", placeholders.join(", "))
+            })
+            .collect();
+        let columns: Vec<_> = LOG_COLUMNS
+            .iter()
+            .map(|column| quote(self.db_type, column))
+            .collect();
+
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES {}",
+            quote(self.db_type, table),
+            columns.join(", "),
+            rows.join(", ")
+        );
+        (sql, params)
+    }
+
+    /// Record the entries of a statement.
+    async fn record(&self, entries: Vec<AuditEntry>, next: Next<'_>) -> QueryResult<()> {
+        match &self.destination {
+            Destination::Table(table) => {
+                let (sql, params) = self.insert_entries(table, &entries);
+                next.run(QueryContext::new(sql, params)).await.map(|_| ())
+            }
+            Destination::Sink(sink) => sink.record(entries).await,
+        }
+    }
+}
+
+impl Middleware for AuditMiddleware {
+    fn handle<'a>(
+        &'a self,
+        ctx: QueryContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        Box::pin(async move {
+            let Some(table) = write_table(ctx.sql()).and_then(|table| self.tables.get(&table))
+            else {
+                return next.run(ctx).await;
+            };
+            let operation = match ctx.query_type() {
+                QueryType::Insert => AuditOperation::Create,
+                QueryType::Update => AuditOperation::Update,
+                _ => AuditOperation::Delete,
+            };
+            let actor = Self::actor(ctx.metadata());
+
+            let before = match self.before_query(ctx.sql(), ctx.params()) {
+                Some((sql, params)) if operation != AuditOperation::Create => {
+                    let response = next.clone().run(QueryContext::new(sql, params)).await?;
+                    rows(response.data)
+                }
+                _ => Vec::new(),
+            };
+
+            let response = next.clone().run(ctx).await?;
+
+            let mut after = match operation {
+                AuditOperation::Delete => Vec::new(),
+                _ => rows(response.data.clone()),
+            };
+            if operation == AuditOperation::Update && after.is_empty() && !before.is_empty() {
+                let keys: Vec<_> = before
+                    .iter()
+                    .filter_map(|row| key_of(row, &table.key))
+                    .collect();
+                let (sql, params) = select_by_keys(self.db_type, &table.table, &table.key, &keys);
+                let reread = next.clone().run(QueryContext::new(sql, params)).await?;
+                after = rows(reread.data);
+            }
+
+            let entries = self.entries(table, operation, &before, &after, &actor);
+            if !entries.is_empty() {
+                self.record(entries, next).await?;
+            }
+            Ok(response)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "AuditMiddleware"
+    }
+}
+
+/// The rows in a response.
+fn rows(data: JsonValue) -> Vec<JsonValue> {
+    match data {
+        JsonValue::Array(rows) => rows.into_iter().filter(JsonValue::is_object).collect(),
+        row @ JsonValue::Object(_) => vec![row],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareChain;
+    use serde_json::json;
+
+    /// Run a statement through `chain`, answering SELECTs with `stored` and
+    /// other statements with `returned`, and return every statement run.
+    async fn run(
+        chain: &MiddlewareChain,
+        ctx: QueryContext,
+        stored: JsonValue,
+        returned: JsonValue,
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let log = executed.clone();
+        chain
+            .execute(ctx, move |ctx| {
+                log.lock()
+                    .push((ctx.sql().to_string(), ctx.params().to_vec()));
+                let data = if ctx.query_type() == QueryType::Select {
+                    stored.clone()
+                } else {
+                    returned.clone()
+                };
+                Box::pin(async move { Ok(QueryResponse::new(data)) })
+            })
+            .await
+            .unwrap();
+        executed.lock().clone()
+    }
+
+    fn users() -> AuditedTable {
+        AuditedTable::new("users", ["id"]).exclude(["password_hash"])
+    }
+
+    #[tokio::test]
+    async fn test_audit_update_records_changed_columns() {
+        let sink = MemoryAuditSink::new();
+        let mut chain = MiddlewareChain::new();
+        chain.push(
+            AuditMiddleware::new(DatabaseType::PostgreSQL)
+                .audit(users())
+                .with_sink(sink.clone()),
+        );
+
+        let ctx = QueryContext::new(
+            r#"UPDATE "users" SET "email" = $1, "password_hash" = $2 WHERE "id" = $3 RETURNING *"#,
+            vec!["new@example.com".into(), "x".into(), FilterValue::Int(7)],
+        )
+        .with_metadata(
+            QueryMetadata::new()
+                .with_user_id("admin")
+                .with_tenant_id("acme"),
+        );
+        let executed = run(
+            &chain,
+            ctx,
+            json!([{"id": 7, "email": "old@example.com", "password_hash": "a", "name": "Ann"}]),
+            json!([{"id": 7, "email": "new@example.com", "password_hash": "b", "name": "Ann"}]),
+        )
+        .await;
+
+        assert_eq!(
+            executed[0],
+            (
+                r#"SELECT * FROM "users" WHERE "id" = $1"#.to_string(),
+                vec![FilterValue::Int(7)]
+            )
+        );
+
+        let entries = sink.entries();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.operation, AuditOperation::Update);
+        assert_eq!(entry.key, json!({"id": 7}));
+        assert_eq!(entry.actor.as_deref(), Some("admin"));
+        assert_eq!(entry.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(entry.changed_columns, vec!["email".to_string()]);
+        assert_eq!(
+            entry.before,
+            Some(json!({"id": 7, "email": "old@example.com", "name": "Ann"}))
+        );
+        assert_eq!(
+            entry.after,
+            Some(json!({"id": 7, "email": "new@example.com", "name": "Ann"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_writes_to_log_table() {
+        let mut chain = MiddlewareChain::new();
+        chain.push(AuditMiddleware::new(DatabaseType::SQLite).audit(users()));
+
+        let ctx = QueryContext::new(
+            "DELETE FROM users WHERE name = ? AND id > ?",
+            vec!["Ann".into(), FilterValue::Int(1)],
+        )
+        .with_metadata(QueryMetadata::new().with_tag(ACTOR_TAG, "cron"));
+        let executed = run(&chain, ctx, json!([{"id": 7, "name": "Ann"}]), json!([])).await;
+
+        assert_eq!(executed.len(), 3);
+        assert_eq!(
+            executed[0],
+            (
+                "SELECT * FROM users WHERE name = ? AND id > ?".to_string(),
+                vec!["Ann".into(), FilterValue::Int(1)]
+            )
+        );
+        let (sql, params) = &executed[2];
+        assert!(sql.starts_with(r#"INSERT INTO "_prax_audit_log" ("table_name", "operation""#));
+        assert_eq!(params.len(), LOG_COLUMNS.len());
+        assert_eq!(params[1], FilterValue::String("delete".into()));
+        assert_eq!(params[3], FilterValue::String("cron".into()));
+        assert_eq!(params[8], FilterValue::Null);
+    }
+
+    #[tokio::test]
+    async fn test_unaudited_and_unchanged_rows_are_skipped() {
+        let sink = MemoryAuditSink::new();
+        let mut chain = MiddlewareChain::new();
+        chain.push(
+            AuditMiddleware::new(DatabaseType::PostgreSQL)
+                .audit(users())
+                .with_sink(sink.clone()),
+        );
+
+        let row = json!([{"id": 7, "password_hash": "a"}]);
+        let ctx = QueryContext::new(
+            r#"UPDATE users SET password_hash = $1 WHERE id = $2 RETURNING *"#,
+            vec!["a".into(), FilterValue::Int(7)],
+        );
+        run(&chain, ctx, row.clone(), row.clone()).await;
+
+        let ctx = QueryContext::new("INSERT INTO posts (title) VALUES ($1)", vec!["Hi".into()]);
+        let executed = run(&chain, ctx, json!([]), json!([{"id": 1}])).await;
+
+        assert_eq!(executed.len(), 1);
+        assert!(sink.entries().is_empty());
+    }
+
+    #[test]
+    fn test_create_table_sql() {
+        let sql = AuditMiddleware::new(DatabaseType::MySQL).create_table_sql();
+        assert!(sql.starts_with("CREATE TABLE IF NOT EXISTS `_prax_audit_log`"));
+        assert!(sql.contains("`id` BIGINT AUTO_INCREMENT PRIMARY KEY"));
+        assert!(sql.contains("`created_at` BIGINT NOT NULL"));
+    }
+}
@@ -6,6 +6,10 @@ use super::types::{
 };
 use std::sync::Arc;
 
+/// The handler at the end of a chain, shared by every run of it.
+type FinalHandler<'a> =
+    Arc<dyn Fn(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + Sync + 'a>;
+
 /// A chain of middleware that processes queries.
 ///
 /// The chain executes middleware in order, with each middleware able to:
@@ -56,54 +60,32 @@ impl MiddlewareChain {
         final_handler: F,
     ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>>
     where
-        F: FnOnce(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + 'a,
+        F: Fn(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + Sync + 'a,
     {
-        self.execute_at(0, ctx, final_handler)
+        self.execute_at(0, ctx, Arc::new(final_handler))
     }
 
-    fn execute_at<'a, F>(
+    fn execute_at<'a>(
         &'a self,
         index: usize,
         ctx: QueryContext,
-        final_handler: F,
-    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>>
-    where
-        F: FnOnce(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + 'a,
-    {
-        if index >= self.middlewares.len() {
+        final_handler: FinalHandler<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        let Some(middleware) = self.middlewares.get(index) else {
             // End of chain, call the final handler
             return final_handler(ctx);
-        }
-
-        let middleware = &self.middlewares[index];
+        };
 
         // Skip disabled middleware
         if !middleware.enabled() {
             return self.execute_at(index + 1, ctx, final_handler);
         }
 
-        // Create the next handler that will call the rest of the chain
-
-        ({
-            // We need to move the final_handler but also use it in the closure
-            // This requires some careful handling
-            Box::pin(async move {
-                // This is a placeholder - the actual implementation needs
-                // to properly chain the middleware
-                middleware
-                    .handle(
-                        ctx,
-                        Next {
-                            inner: Box::new(move |ctx| {
-                                // Recursively call the rest of the chain
-                                // Note: This is simplified - real impl would be more complex
-                                final_handler(ctx)
-                            }),
-                        },
-                    )
-                    .await
-            })
-        }) as _
+        // The next handler runs the rest of the chain, and may be run again
+        let next = Next {
+            inner: Arc::new(move |ctx| self.execute_at(index + 1, ctx, final_handler.clone())),
+        };
+        middleware.handle(ctx, next)
     }
 }
 
@@ -168,7 +150,7 @@ impl MiddlewareStack {
         final_handler: F,
     ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>>
     where
-        F: FnOnce(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + 'a,
+        F: Fn(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + Sync + 'a,
     {
         self.chain.execute(ctx, final_handler)
     }
@@ -1,6 +1,7 @@
 //! Query context for middleware.
 
 use crate::filter::FilterValue;
+use crate::tags::QueryTags;
 use std::collections::HashMap;
 use std::time::Instant;
 
@@ -57,6 +58,22 @@ impl QueryType {
         }
     }
 
+    /// Lowercase name of the query type, e.g. `"select"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Select => "select",
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Count => "count",
+            Self::Raw => "raw",
+            Self::TransactionBegin => "transaction_begin",
+            Self::TransactionCommit => "transaction_commit",
+            Self::TransactionRollback => "transaction_rollback",
+            Self::Unknown => "unknown",
+        }
+    }
+
     /// Check if this is a read operation.
     pub fn is_read(&self) -> bool {
         matches!(self, Self::Select | Self::Count)
@@ -106,6 +123,9 @@ pub struct QueryMetadata {
     pub schema_override: Option<String>,
     /// Custom tags for filtering.
     pub tags: HashMap<String, String>,
+    /// Cost attribution tags from the statement's sqlcommenter comment
+    /// (see [`crate::tags`]).
+    pub query_tags: QueryTags,
     /// Custom attributes.
     pub attributes: HashMap<String, serde_json::Value>,
 }
@@ -127,6 +147,7 @@ impl QueryMetadata {
             tenant_id: None,
             schema_override: None,
             tags: HashMap::new(),
+            query_tags: QueryTags::new(),
             attributes: HashMap::new(),
         }
     }
@@ -167,6 +188,12 @@ impl QueryMetadata {
         self
     }
 
+    /// Set the cost attribution tags.
+    pub fn with_query_tags(mut self, tags: QueryTags) -> Self {
+        self.query_tags = tags;
+        self
+    }
+
     /// Add an attribute.
     pub fn with_attribute(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
         self.attributes.insert(key.into(), value);
@@ -207,14 +234,18 @@ pub struct QueryContext {
 
 impl QueryContext {
     /// Create a new query context.
+    ///
+    /// Tags in a trailing sqlcommenter comment of `sql` become the
+    /// metadata's [`query_tags`](QueryMetadata::query_tags).
     pub fn new(sql: impl Into<String>, params: Vec<FilterValue>) -> Self {
         let sql = sql.into();
         let query_type = QueryType::from_sql(&sql);
+        let metadata = QueryMetadata::new().with_query_tags(QueryTags::from_sql(&sql));
         Self {
             sql,
             params,
             query_type,
-            metadata: QueryMetadata::new(),
+            metadata,
             started_at: Instant::now(),
             phase: QueryPhase::Before,
             skip_execution: false,
@@ -269,8 +300,11 @@ impl QueryContext {
         &mut self.metadata
     }
 
-    /// Set metadata.
-    pub fn with_metadata(mut self, metadata: QueryMetadata) -> Self {
+    /// Set metadata, keeping the statement's query tags if it has none.
+    pub fn with_metadata(mut self, mut metadata: QueryMetadata) -> Self {
+        if metadata.query_tags.is_empty() {
+            metadata.query_tags = std::mem::take(&mut self.metadata.query_tags);
+        }
         self.metadata = metadata;
         self
     }
@@ -394,6 +428,13 @@ mod tests {
         assert_eq!(metadata.tags.get("env"), Some(&"production".to_string()));
     }
 
+    #[test]
+    fn test_context_query_tags() {
+        let ctx = QueryContext::new("SELECT * FROM carts /*team='checkout'*/", vec![]);
+        assert_eq!(ctx.metadata().query_tags.get("team"), Some("checkout"));
+        assert!(ctx.metadata().tags.is_empty());
+    }
+
     #[test]
     fn test_context_skip_execution() {
         let mut ctx = QueryContext::new("SELECT * FROM users", vec![]);
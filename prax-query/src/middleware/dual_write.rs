@@ -0,0 +1,737 @@
+//! Blue/green cutover by mirroring writes to a second datasource.
+//!
+//! During a migration window, such as a move from one database engine to
+//! another, [`DualWriteMiddleware`] replays every write that succeeds on the
+//! primary against a secondary [`RawDatabase`]. Reads, and writes the
+//! primary rejects, are never mirrored. A failed mirror is recorded rather
+//! than failing the query, so the primary stays the source of truth until
+//! cutover.
+//!
+//! Divergence is tracked per table: keys of written rows (from rows the
+//! primary returns, e.g. through `RETURNING`) are sampled, and
+//! [`DualWriteMiddleware::readiness`] fetches the sampled rows from both sides
+//! and compares their checksums. The resulting [`CutoverReport`] says whether
+//! the secondary is ready to take over.
+//!
+//! Statements are mirrored inside or outside transactions exactly as they
+//! reach the middleware, so a write the primary later rolls back is still
+//! mirrored; the readiness check reports the rows this leaves behind.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use prax_query::middleware::{DualWriteMiddleware, MiddlewareStack};
+//! use prax_query::sql::DatabaseType;
+//!
+//! let dual = DualWriteMiddleware::new(mysql_engine)
+//!     .translate(DatabaseType::PostgreSQL, DatabaseType::MySQL)
+//!     .track("users", ["id"])
+//!     .track("orders", ["id"]);
+//! let stack = MiddlewareStack::new().with(dual.clone());
+//!
+//! // ... later, before switching over
+//! let report = dual.readiness(&pg_engine).await?;
+//! if report.is_ready() {
+//!     println!("{}", report.summary());
+//! }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use super::context::{QueryContext, QueryType};
+use super::types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
+use crate::error::QueryResult;
+use crate::filter::FilterValue;
+use crate::raw::RawDatabase;
+use crate::sql::DatabaseType;
+
+/// Number of mirror errors kept for the report.
+const MAX_RECENT_ERRORS: usize = 20;
+
+/// Middleware mirroring writes to a secondary datasource.
+///
+/// Clones share their state, so keep one to build the [`CutoverReport`] after
+/// adding another to a middleware stack.
+#[derive(Clone)]
+pub struct DualWriteMiddleware {
+    secondary: Arc<dyn RawDatabase>,
+    primary_type: DatabaseType,
+    secondary_type: DatabaseType,
+    tables: HashMap<String, Vec<String>>,
+    sample_size: usize,
+    sample_every: u64,
+    state: Arc<State>,
+}
+
+#[derive(Default)]
+struct State {
+    mirrored: AtomicU64,
+    failed: AtomicU64,
+    sampled_writes: AtomicU64,
+    recent_errors: Mutex<VecDeque<String>>,
+    /// Sampled keys per tracked table, oldest first.
+    keys: Mutex<HashMap<String, VecDeque<Vec<JsonValue>>>>,
+}
+
+impl DualWriteMiddleware {
+    /// Mirror writes to `secondary`, which runs the same dialect as the
+    /// primary unless [`translate`](Self::translate) says otherwise.
+    pub fn new(secondary: impl RawDatabase + 'static) -> Self {
+        Self {
+            secondary: Arc::new(secondary),
+            primary_type: DatabaseType::PostgreSQL,
+            secondary_type: DatabaseType::PostgreSQL,
+            tables: HashMap::new(),
+            sample_size: 100,
+            sample_every: 1,
+            state: Arc::default(),
+        }
+    }
+
+    /// Translate mirrored statements from the primary's dialect to the
+    /// secondary's: placeholders and quoted identifiers are rewritten.
+    ///
+    /// Other dialect differences are not translated; statements using them
+    /// fail to mirror and show up in the report.
+    pub fn translate(mut self, primary: DatabaseType, secondary: DatabaseType) -> Self {
+        self.primary_type = primary;
+        self.secondary_type = secondary;
+        self
+    }
+
+    /// Track divergence on a table, identifying rows by the key columns.
+    pub fn track<I, S>(mut self, table: impl Into<String>, key: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tables.insert(
+            table.into().to_lowercase(),
+            key.into_iter().map(Into::into).collect(),
+        );
+        self
+    }
+
+    /// Set the number of keys sampled per table (default 100).
+    pub fn sample_size(mut self, size: usize) -> Self {
+        self.sample_size = size.max(1);
+        self
+    }
+
+    /// Sample the keys of one write in `n` (default every write).
+    pub fn sample_every(mut self, n: u64) -> Self {
+        self.sample_every = n.max(1);
+        self
+    }
+
+    /// Number of writes mirrored successfully.
+    pub fn mirrored(&self) -> u64 {
+        self.state.mirrored.load(Ordering::Relaxed)
+    }
+
+    /// Number of writes that failed to mirror.
+    pub fn failed(&self) -> u64 {
+        self.state.failed.load(Ordering::Relaxed)
+    }
+
+    /// Replay a write on the secondary, recording the outcome.
+    async fn mirror(&self, sql: &str, params: &[FilterValue]) -> bool {
+        let (sql, params) =
+            translate_statement(sql, params, self.primary_type, self.secondary_type);
+        match self.secondary.execute(&sql, params).await {
+            Ok(_) => {
+                self.state.mirrored.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(error) => {
+                tracing::warn!(error = %error, "Failed to mirror write to the secondary");
+                self.state.failed.fetch_add(1, Ordering::Relaxed);
+                let mut errors = self.state.recent_errors.lock();
+                if errors.len() == MAX_RECENT_ERRORS {
+                    errors.pop_front();
+                }
+                errors.push_back(format!("{}: {}", sql, error));
+                false
+            }
+        }
+    }
+
+    /// Sample the keys of the rows a write returned.
+    fn sample(&self, table: &str, response: &QueryResponse) {
+        let Some(key) = self.tables.get(table) else {
+            return;
+        };
+        let n = self.state.sampled_writes.fetch_add(1, Ordering::Relaxed);
+        if n % self.sample_every != 0 {
+            return;
+        }
+
+        let rows = match &response.data {
+            JsonValue::Array(rows) => rows.iter().collect(),
+            row @ JsonValue::Object(_) => vec![row],
+            _ => Vec::new(),
+        };
+        let mut keys = self.state.keys.lock();
+        let sampled = keys.entry(table.to_string()).or_default();
+        for values in rows.into_iter().filter_map(|row| key_of(row, key)) {
+            if sampled.contains(&values) {
+                continue;
+            }
+            if sampled.len() == self.sample_size {
+                sampled.pop_front();
+            }
+            sampled.push_back(values);
+        }
+    }
+
+    /// Compare the sampled rows of every tracked table on `primary` and the
+    /// secondary.
+    ///
+    /// Tables without sampled keys, such as those only written without
+    /// returning rows, are checked on their last keys on the primary.
+    pub async fn readiness(&self, primary: &dyn RawDatabase) -> QueryResult<CutoverReport> {
+        let mut tables: Vec<_> = self.tables.iter().collect();
+        tables.sort();
+
+        let mut report = CutoverReport {
+            mirrored: self.mirrored(),
+            failed: self.failed(),
+            recent_errors: self.state.recent_errors.lock().iter().cloned().collect(),
+            tables: Vec::with_capacity(tables.len()),
+        };
+
+        for (table, key) in tables {
+            let sampled: Vec<_> = self
+                .state
+                .keys
+                .lock()
+                .get(table)
+                .map(|keys| keys.iter().cloned().collect())
+                .unwrap_or_default();
+            let keys = if sampled.is_empty() {
+                self.latest_keys(primary, table, key).await?
+            } else {
+                sampled
+            };
+            report
+                .tables
+                .push(self.compare(primary, table, key, keys).await?);
+        }
+
+        Ok(report)
+    }
+
+    /// The last keys of a table on the primary, in key order.
+    async fn latest_keys(
+        &self,
+        primary: &dyn RawDatabase,
+        table: &str,
+        key: &[String],
+    ) -> QueryResult<Vec<Vec<JsonValue>>> {
+        let columns: Vec<_> = key.iter().map(|c| quote(self.primary_type, c)).collect();
+        let order: Vec<_> = columns.iter().map(|c| format!("{} DESC", c)).collect();
+        let sql = format!(
+            "SELECT {} FROM {} ORDER BY {} LIMIT {}",
+            columns.join(", "),
+            quote(self.primary_type, table),
+            order.join(", "),
+            self.sample_size
+        );
+        let rows = primary.query(&sql, Vec::new()).await?;
+        Ok(rows.iter().filter_map(|row| key_of(row, key)).collect())
+    }
+
+    /// Compare the rows with the given keys on both sides.
+    async fn compare(
+        &self,
+        primary: &dyn RawDatabase,
+        table: &str,
+        key: &[String],
+        keys: Vec<Vec<JsonValue>>,
+    ) -> QueryResult<TableDivergence> {
+        let mut divergence = TableDivergence {
+            table: table.to_string(),
+            sampled: keys.len(),
+            matching: 0,
+            missing_in_secondary: Vec::new(),
+            missing_in_primary: Vec::new(),
+            mismatched: Vec::new(),
+        };
+        if keys.is_empty() {
+            return Ok(divergence);
+        }
+
+        let (sql, params) = select_by_keys(self.primary_type, table, key, &keys);
+        let primary_rows = checksums(primary.query(&sql, params).await?, key);
+        let (sql, params) = select_by_keys(self.secondary_type, table, key, &keys);
+        let secondary_rows = checksums(self.secondary.query(&sql, params).await?, key);
+
+        for values in keys {
+            let label = key_label(&values);
+            let id = canonical(&JsonValue::Array(values));
+            match (primary_rows.get(&id), secondary_rows.get(&id)) {
+                (Some(a), Some(b)) if a == b => divergence.matching += 1,
+                (Some(_), Some(_)) => divergence.mismatched.push(label),
+                (Some(_), None) => divergence.missing_in_secondary.push(label),
+                (None, Some(_)) => divergence.missing_in_primary.push(label),
+                // Deleted on both sides
+                (None, None) => divergence.matching += 1,
+            }
+        }
+
+        Ok(divergence)
+    }
+}
+
+impl Middleware for DualWriteMiddleware {
+    fn handle<'a>(
+        &'a self,
+        ctx: QueryContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        Box::pin(async move {
+            if !ctx.query_type().is_write() {
+                return next.run(ctx).await;
+            }
+
+            let sql = ctx.sql().to_string();
+            let params = ctx.params().to_vec();
+            let response = next.run(ctx).await?;
+
+            if self.mirror(&sql, &params).await {
+                if let Some(table) = write_table(&sql) {
+                    self.sample(&table, &response);
+                }
+            }
+            Ok(response)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "DualWriteMiddleware"
+    }
+}
+
+/// Whether the secondary is ready to take over from the primary.
+#[derive(Debug, Clone, Serialize)]
+pub struct CutoverReport {
+    /// Writes mirrored successfully.
+    pub mirrored: u64,
+    /// Writes that failed to mirror.
+    pub failed: u64,
+    /// The latest mirror errors, oldest first.
+    pub recent_errors: Vec<String>,
+    /// Divergence of each tracked table.
+    pub tables: Vec<TableDivergence>,
+}
+
+impl CutoverReport {
+    /// Check if every write mirrored and every sampled row matches.
+    pub fn is_ready(&self) -> bool {
+        self.failed == 0 && self.tables.iter().all(TableDivergence::is_consistent)
+    }
+
+    /// A human-readable summary.
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} writes mirrored, {} failed\n",
+            self.mirrored, self.failed
+        );
+        for table in &self.tables {
+            let _ = writeln!(
+                out,
+                "{}: {}/{} sampled rows match, {} missing in secondary, {} missing in primary, {} differ",
+                table.table,
+                table.matching,
+                table.sampled,
+                table.missing_in_secondary.len(),
+                table.missing_in_primary.len(),
+                table.mismatched.len()
+            );
+        }
+        out.push_str(if self.is_ready() {
+            "Ready for cutover"
+        } else {
+            "Not ready for cutover"
+        });
+        out
+    }
+}
+
+/// How far the sampled rows of a table diverge between the two sides.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableDivergence {
+    /// Table name.
+    pub table: String,
+    /// Number of keys compared.
+    pub sampled: usize,
+    /// Keys whose rows have the same checksum on both sides.
+    pub matching: usize,
+    /// Keys of rows only on the primary.
+    pub missing_in_secondary: Vec<String>,
+    /// Keys of rows only on the secondary.
+    pub missing_in_primary: Vec<String>,
+    /// Keys of rows whose checksums differ.
+    pub mismatched: Vec<String>,
+}
+
+impl TableDivergence {
+    /// Check if every sampled row matches.
+    pub fn is_consistent(&self) -> bool {
+        self.matching == self.sampled
+    }
+}
+
+/// Rewrite a statement written for `from` to run on `to`.
+///
+/// Placeholders are renumbered (reordering and repeating the parameters as
+/// `$n` placeholders require) and double-quoted identifiers become
+/// backquoted for MySQL. String literals are left alone.
+pub fn translate_statement(
+    sql: &str,
+    params: &[FilterValue],
+    from: DatabaseType,
+    to: DatabaseType,
+) -> (String, Vec<FilterValue>) {
+    if from == to {
+        return (sql.to_string(), params.to_vec());
+    }
+
+    let mut out = String::with_capacity(sql.len());
+    let mut bound = Vec::with_capacity(params.len());
+    let mut next_param = 0;
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    if c == '\'' {
+                        if chars.peek() == Some(&'\'') {
+                            out.push(chars.next().unwrap());
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' | '`' => {
+                let mut ident = String::new();
+                for c in chars.by_ref() {
+                    if c == '"' || c == '`' {
+                        break;
+                    }
+                    ident.push(c);
+                }
+                out.push_str(&quote(to, &ident));
+            }
+            '$' if from == DatabaseType::PostgreSQL
+                && chars.peek().is_some_and(char::is_ascii_digit) =>
+            {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(d);
+                }
+                let index: usize = digits.parse().unwrap_or(0);
+                bound.push(
+                    params
+                        .get(index.wrapping_sub(1))
+                        .cloned()
+                        .unwrap_or(FilterValue::Null),
+                );
+                out.push_str(&to.placeholder(bound.len()));
+            }
+            '?' if matches!(from, DatabaseType::MySQL | DatabaseType::SQLite) => {
+                bound.push(params.get(next_param).cloned().unwrap_or(FilterValue::Null));
+                next_param += 1;
+                out.push_str(&to.placeholder(bound.len()));
+            }
+            _ => out.push(c),
+        }
+    }
+
+    (out, bound)
+}
+
+/// Quote an identifier for a database.
+pub(super) fn quote(db_type: DatabaseType, ident: &str) -> String {
+    match db_type {
+        DatabaseType::MySQL => format!("`{}`", ident.replace('`', "``")),
+        DatabaseType::MSSQL => format!("[{}]", ident.replace(']', "]]")),
+        DatabaseType::PostgreSQL | DatabaseType::SQLite => {
+            format!("\"{}\"", ident.replace('"', "\"\""))
+        }
+    }
+}
+
+/// The table an `INSERT`, `UPDATE` or `DELETE` writes to, lowercased and
+/// without its schema.
+pub(super) fn write_table(sql: &str) -> Option<String> {
+    let words: Vec<&str> = sql.split_whitespace().take(4).collect();
+    let name = match QueryType::from_sql(sql) {
+        QueryType::Insert | QueryType::Delete => words.get(2)?,
+        QueryType::Update => words.get(1)?,
+        _ => return None,
+    };
+    let name = name.split('(').next()?.rsplit('.').next()?;
+    Some(name.trim_matches(|c| c == '"' || c == '`').to_lowercase())
+}
+
+/// The values of the key columns of a row, if it has them all.
+pub(super) fn key_of(row: &JsonValue, key: &[String]) -> Option<Vec<JsonValue>> {
+    let row = row.as_object()?;
+    key.iter()
+        .map(|column| {
+            row.get(column)
+                .or_else(|| {
+                    row.iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(column))
+                        .map(|(_, value)| value)
+                })
+                .filter(|value| !value.is_null())
+                .cloned()
+        })
+        .collect()
+}
+
+/// Build a query selecting the rows with the given keys.
+pub(super) fn select_by_keys(
+    db_type: DatabaseType,
+    table: &str,
+    key: &[String],
+    keys: &[Vec<JsonValue>],
+) -> (String, Vec<FilterValue>) {
+    let mut params = Vec::with_capacity(keys.len() * key.len());
+    let conditions: Vec<String> = keys
+        .iter()
+        .map(|values| {
+            let columns: Vec<String> = key
+                .iter()
+                .zip(values)
+                .map(|(column, value)| {
+                    params.push(json_to_filter(value));
+                    format!(
+                        "{} = {}",
+                        quote(db_type, column),
+                        db_type.placeholder(params.len())
+                    )
+                })
+                .collect();
+            format!("({})", columns.join(" AND "))
+        })
+        .collect();
+
+    let sql = format!(
+        "SELECT * FROM {} WHERE {}",
+        quote(db_type, table),
+        conditions.join(" OR ")
+    );
+    (sql, params)
+}
+
+fn json_to_filter(value: &JsonValue) -> FilterValue {
+    match value {
+        JsonValue::Null => FilterValue::Null,
+        JsonValue::Bool(b) => FilterValue::Bool(*b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => FilterValue::Int(i),
+            None => FilterValue::Float(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => FilterValue::String(s.clone()),
+        other => FilterValue::Json(other.clone()),
+    }
+}
+
+/// Checksums of rows, by the canonical form of their key.
+fn checksums(rows: Vec<JsonValue>, key: &[String]) -> HashMap<String, u64> {
+    rows.iter()
+        .filter_map(|row| {
+            let values = key_of(row, key)?;
+            Some((canonical(&JsonValue::Array(values)), checksum(row)))
+        })
+        .collect()
+}
+
+/// A checksum of a row that engines agree on: column names are compared
+/// case-insensitively, booleans as 0 or 1, and numbers by value.
+fn checksum(row: &JsonValue) -> u64 {
+    // FNV-1a
+    canonical(row)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+fn canonical(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Bool(b) => u8::from(*b).to_string(),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => i.to_string(),
+            None => n.as_f64().unwrap_or_default().to_string(),
+        },
+        JsonValue::String(s) => format!("{:?}", s),
+        JsonValue::Array(items) => {
+            let items: Vec<_> = items.iter().map(canonical).collect();
+            format!("[{}]", items.join(","))
+        }
+        JsonValue::Object(map) => {
+            let mut fields: Vec<_> = map
+                .iter()
+                .map(|(name, value)| format!("{:?}:{}", name.to_lowercase(), canonical(value)))
+                .collect();
+            fields.sort();
+            format!("{{{}}}", fields.join(","))
+        }
+    }
+}
+
+fn key_label(values: &[JsonValue]) -> String {
+    let parts: Vec<_> = values
+        .iter()
+        .map(|value| match value {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .collect();
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareChain;
+    use crate::raw::testing::FakeDatabase;
+
+    async fn run(chain: &MiddlewareChain, sql: &str, returned: JsonValue) -> QueryResult<()> {
+        let ctx = QueryContext::new(sql, vec![FilterValue::Int(1), "a@b.c".into()]);
+        chain
+            .execute(ctx, move |_| {
+                let returned = returned.clone();
+                Box::pin(async move { Ok(QueryResponse::new(returned)) })
+            })
+            .await
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_translate_statement() {
+        let params = vec![FilterValue::Int(1), FilterValue::String("x".into())];
+        let (sql, bound) = translate_statement(
+            r#"UPDATE "users" SET "name" = $2 WHERE "id" = $1 AND note <> '$1 "quoted"' AND $2 <> ''"#,
+            &params,
+            DatabaseType::PostgreSQL,
+            DatabaseType::MySQL,
+        );
+        assert_eq!(
+            sql,
+            r#"UPDATE `users` SET `name` = ? WHERE `id` = ? AND note <> '$1 "quoted"' AND ? <> ''"#
+        );
+        assert_eq!(
+            bound,
+            vec![
+                FilterValue::String("x".into()),
+                FilterValue::Int(1),
+                FilterValue::String("x".into())
+            ]
+        );
+
+        let (sql, _) = translate_statement(
+            "DELETE FROM `users` WHERE id = ?",
+            &params,
+            DatabaseType::MySQL,
+            DatabaseType::PostgreSQL,
+        );
+        assert_eq!(sql, r#"DELETE FROM "users" WHERE id = $1"#);
+    }
+
+    #[tokio::test]
+    async fn test_dual_write_mirrors_writes() {
+        let secondary = FakeDatabase::new();
+        let dual = DualWriteMiddleware::new(secondary.clone())
+            .translate(DatabaseType::PostgreSQL, DatabaseType::SQLite)
+            .track("users", ["id"]);
+        let mut chain = MiddlewareChain::new();
+        chain.push(dual.clone());
+
+        run(&chain, "SELECT * FROM users", JsonValue::Null)
+            .await
+            .unwrap();
+        run(
+            &chain,
+            r#"INSERT INTO "users" (id, email) VALUES ($1, $2) RETURNING *"#,
+            serde_json::json!([{"id": 1, "email": "a@b.c"}]),
+        )
+        .await
+        .unwrap();
+
+        let executed = secondary.executed();
+        assert_eq!(executed.len(), 1);
+        assert_eq!(
+            executed[0].0,
+            r#"INSERT INTO "users" (id, email) VALUES (?, ?) RETURNING *"#
+        );
+        assert_eq!(dual.mirrored(), 1);
+        assert_eq!(
+            dual.state.keys.lock()["users"],
+            VecDeque::from([vec![serde_json::json!(1)]])
+        );
+
+        // A failed mirror is recorded, not returned
+        let failing = DualWriteMiddleware::new(FakeDatabase::new().failing("secondary is down"));
+        let mut chain = MiddlewareChain::new();
+        chain.push(failing.clone());
+        run(&chain, "DELETE FROM users WHERE id = $1", JsonValue::Null)
+            .await
+            .unwrap();
+        assert_eq!(failing.failed(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_report() {
+        let primary = FakeDatabase::new().with_rows(vec![
+            serde_json::json!({"id": 1, "email": "a@b.c", "active": true}),
+            serde_json::json!({"id": 2, "email": "d@e.f", "active": true}),
+            serde_json::json!({"id": 3, "email": "g@h.i", "active": false}),
+        ]);
+        let secondary = FakeDatabase::new().with_rows(vec![
+            // Engines disagree on booleans and column case
+            serde_json::json!({"ID": 1, "email": "a@b.c", "active": 1}),
+            serde_json::json!({"id": 2, "email": "changed", "active": 1}),
+        ]);
+
+        let dual = DualWriteMiddleware::new(secondary).track("users", ["id"]);
+        let report = dual.readiness(&primary).await.unwrap();
+
+        let users = &report.tables[0];
+        assert_eq!(users.sampled, 3);
+        assert_eq!(users.matching, 1);
+        assert_eq!(users.mismatched, vec!["2"]);
+        assert_eq!(users.missing_in_secondary, vec!["3"]);
+        assert!(!report.is_ready());
+        assert!(report.summary().contains("1/3 sampled rows match"));
+    }
+
+    #[test]
+    fn test_write_table() {
+        assert_eq!(
+            write_table(r#"INSERT INTO public."Users"(id) VALUES (1)"#).as_deref(),
+            Some("users")
+        );
+        assert_eq!(
+            write_table("UPDATE orders SET x = 1").as_deref(),
+            Some("orders")
+        );
+        assert_eq!(write_table("SELECT 1"), None);
+    }
+}
@@ -0,0 +1,330 @@
+//! Capturing query plans of slow queries.
+//!
+//! [`SlowQueryPlanMiddleware`] times each query and, when one takes longer
+//! than the threshold, asks the engine for its plan with
+//! [`QueryEngine::explain`]. The plan is logged, attached to the response
+//! metadata under `query_plan`, and kept with the most recent slow queries
+//! for inspection:
+//!
+//! ```rust,ignore
+//! use prax_query::middleware::{MiddlewareStack, SlowQueryPlanMiddleware};
+//!
+//! // `debug.slow_query_threshold` from prax.toml, in milliseconds
+//! let plans = SlowQueryPlanMiddleware::from_threshold_ms(engine.clone(), config.debug.slow_query_threshold);
+//! let stack = MiddlewareStack::new().with(plans.clone());
+//!
+//! // ... later
+//! for slow in plans.slow_queries() {
+//!     println!("{}ms: {}\n{}", slow.duration.as_millis(), slow.sql, slow.plan);
+//! }
+//! ```
+//!
+//! Plans are explained after the query returns, so the database may pick a
+//! different plan than it ran if statistics changed in between.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use super::context::{QueryContext, QueryType};
+use super::types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
+use crate::explain::QueryPlan;
+use crate::tags::QueryTags;
+use crate::traits::QueryEngine;
+
+/// A query that exceeded the slow query threshold, with its plan.
+#[derive(Debug, Clone)]
+pub struct SlowQuery {
+    /// The SQL statement.
+    pub sql: String,
+    /// How long the query took.
+    pub duration: Duration,
+    /// The plan the database reported for the query.
+    pub plan: QueryPlan,
+    /// The query's cost attribution tags.
+    pub tags: QueryTags,
+}
+
+/// Middleware explaining queries slower than a threshold.
+///
+/// Clones share the captured queries, so keep one to read them after adding
+/// another to a middleware stack.
+#[derive(Clone)]
+pub struct SlowQueryPlanMiddleware<E: QueryEngine> {
+    engine: E,
+    threshold: Duration,
+    analyze: bool,
+    capacity: usize,
+    captured: Arc<Mutex<VecDeque<SlowQuery>>>,
+}
+
+impl<E: QueryEngine> SlowQueryPlanMiddleware<E> {
+    /// Explain queries taking at least `threshold` through `engine`.
+    pub fn new(engine: E, threshold: Duration) -> Self {
+        Self {
+            engine,
+            threshold,
+            analyze: false,
+            capacity: 50,
+            captured: Arc::default(),
+        }
+    }
+
+    /// Explain queries taking at least `threshold_ms` milliseconds, as
+    /// configured by `debug.slow_query_threshold`.
+    pub fn from_threshold_ms(engine: E, threshold_ms: u64) -> Self {
+        Self::new(engine, Duration::from_millis(threshold_ms))
+    }
+
+    /// Measure the plans of slow reads with `EXPLAIN ANALYZE` (default off).
+    ///
+    /// This runs each slow read a second time. Writes are never analyzed.
+    pub fn analyze(mut self, analyze: bool) -> Self {
+        self.analyze = analyze;
+        self
+    }
+
+    /// Set the number of slow queries kept (default 50).
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Get the threshold above which queries are explained.
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Get the captured slow queries, oldest first.
+    pub fn slow_queries(&self) -> Vec<SlowQuery> {
+        self.captured.lock().iter().cloned().collect()
+    }
+
+    /// Forget the captured slow queries.
+    pub fn clear(&self) {
+        self.captured.lock().clear();
+    }
+
+    fn record(&self, slow: SlowQuery) {
+        let mut captured = self.captured.lock();
+        while captured.len() >= self.capacity {
+            captured.pop_front();
+        }
+        captured.push_back(slow);
+    }
+}
+
+impl<E: QueryEngine> Middleware for SlowQueryPlanMiddleware<E> {
+    fn handle<'a>(
+        &'a self,
+        ctx: QueryContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        Box::pin(async move {
+            let query_type = ctx.query_type();
+            let explainable = matches!(
+                query_type,
+                QueryType::Select
+                    | QueryType::Count
+                    | QueryType::Insert
+                    | QueryType::Update
+                    | QueryType::Delete
+            );
+            if !explainable {
+                return next.run(ctx).await;
+            }
+
+            let sql = ctx.sql().to_string();
+            let params = ctx.params().to_vec();
+            let tags = ctx.metadata().query_tags.clone();
+            let analyze = self.analyze && ctx.is_read();
+            let start = Instant::now();
+
+            let mut response = next.run(ctx).await?;
+
+            let duration = start.elapsed();
+            if duration < self.threshold {
+                return Ok(response);
+            }
+
+            match self.engine.explain(&sql, params, analyze).await {
+                Ok(plan) => {
+                    tracing::warn!(
+                        target: "prax::query",
+                        duration_ms = duration.as_millis() as u64,
+                        sql = %sql,
+                        tags = %tags,
+                        plan = %plan,
+                        "Slow query plan captured"
+                    );
+                    if let Ok(json) = serde_json::to_value(&plan) {
+                        response.metadata.insert("query_plan".to_string(), json);
+                    }
+                    self.record(SlowQuery {
+                        sql,
+                        duration,
+                        plan,
+                        tags,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        target: "prax::query",
+                        duration_ms = duration.as_millis() as u64,
+                        sql = %sql,
+                        tags = %tags,
+                        error = %e,
+                        "Failed to explain slow query"
+                    );
+                }
+            }
+
+            Ok(response)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "SlowQueryPlanMiddleware"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QueryResult;
+    use crate::filter::FilterValue;
+    use crate::middleware::MiddlewareStack;
+    use crate::traits::Model;
+
+    /// An engine whose plans echo the explained statement.
+    #[derive(Clone)]
+    struct PlanEngine;
+
+    impl QueryEngine for PlanEngine {
+        fn query_many<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn query_one<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            Box::pin(async { Err(crate::error::QueryError::not_found("test")) })
+        }
+
+        fn query_optional<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Option<T>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn execute_insert<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            Box::pin(async { Err(crate::error::QueryError::not_found("test")) })
+        }
+
+        fn execute_update<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn execute_delete(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn execute_raw(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn count(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn explain(
+            &self,
+            sql: &str,
+            _params: Vec<FilterValue>,
+            analyze: bool,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<QueryPlan>> {
+            let mut plan = QueryPlan::from_sqlite_rows([(1, 0, format!("SCAN {}", sql))]);
+            plan.analyzed = analyze;
+            Box::pin(async move { Ok(plan) })
+        }
+    }
+
+    async fn run(stack: &MiddlewareStack, sql: &str) -> QueryResponse {
+        stack
+            .execute(QueryContext::new(sql, Vec::new()), |_| {
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    Ok(QueryResponse::empty())
+                })
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_slow_query_plans_captured() {
+        let plans = SlowQueryPlanMiddleware::from_threshold_ms(PlanEngine, 1)
+            .analyze(true)
+            .capacity(1);
+        let stack = MiddlewareStack::new().with(plans.clone());
+
+        let response = run(&stack, "SELECT * FROM users").await;
+        assert!(response.metadata.contains_key("query_plan"));
+        let response = run(&stack, "UPDATE users SET name = 'a' /*team='accounts'*/").await;
+        assert!(response.metadata.contains_key("query_plan"));
+        // Transactions have no plan
+        let response = run(&stack, "BEGIN").await;
+        assert!(!response.metadata.contains_key("query_plan"));
+
+        // Only the latest is kept, and writes aren't analyzed
+        let slow = plans.slow_queries();
+        assert_eq!(slow.len(), 1);
+        assert_eq!(
+            slow[0].sql,
+            "UPDATE users SET name = 'a' /*team='accounts'*/"
+        );
+        assert_eq!(slow[0].tags.get("team"), Some("accounts"));
+        assert!(!slow[0].plan.analyzed);
+        assert!(slow[0].duration >= Duration::from_millis(5));
+    }
+
+    #[tokio::test]
+    async fn test_fast_queries_not_explained() {
+        let plans = SlowQueryPlanMiddleware::new(PlanEngine, Duration::from_secs(60));
+        let stack = MiddlewareStack::new().with(plans.clone());
+
+        let response = run(&stack, "SELECT * FROM users").await;
+        assert!(!response.metadata.contains_key("query_plan"));
+        assert!(plans.slow_queries().is_empty());
+    }
+}
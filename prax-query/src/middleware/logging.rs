@@ -143,6 +143,7 @@ impl LoggingMiddleware {
                 query_id = query_id,
                 query_type = %query_type,
                 sql = %sql,
+                tags = %ctx.metadata().query_tags,
                 params = ?ctx.params(),
                 model = ?ctx.metadata().model,
                 operation = ?ctx.metadata().operation,
@@ -156,6 +157,7 @@ impl LoggingMiddleware {
                 query_id = query_id,
                 query_type = %query_type,
                 sql = %sql,
+                tags = %ctx.metadata().query_tags,
                 "[{}] Starting query",
                 self.config.prefix
             );
@@ -174,6 +176,7 @@ impl LoggingMiddleware {
                 duration_us = duration_us,
                 duration_ms = duration_us / 1000,
                 sql = %sql,
+                tags = %ctx.metadata().query_tags,
                 threshold_us = self.config.slow_query_threshold_us,
                 "[{}] Slow query detected",
                 self.config.prefix
@@ -189,6 +192,7 @@ impl LoggingMiddleware {
                     rows_affected = ?response.rows_affected,
                     from_cache = response.from_cache,
                     sql = %sql,
+                    tags = %ctx.metadata().query_tags,
                     response = ?response.data,
                     "[{}] Query completed",
                     self.config.prefix
@@ -200,6 +204,7 @@ impl LoggingMiddleware {
                     duration_us = duration_us,
                     rows_affected = ?response.rows_affected,
                     from_cache = response.from_cache,
+                    tags = %ctx.metadata().query_tags,
                     "[{}] Query completed",
                     self.config.prefix
                 );
@@ -214,6 +219,7 @@ impl LoggingMiddleware {
                 target: "prax::query",
                 query_id = query_id,
                 sql = %sql,
+                tags = %ctx.metadata().query_tags,
                 error = %error,
                 "[{}] Query failed",
                 self.config.prefix
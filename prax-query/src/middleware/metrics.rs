@@ -2,6 +2,7 @@
 
 use super::context::{QueryContext, QueryType};
 use super::types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
+use crate::tags::QueryTags;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
@@ -32,6 +33,10 @@ pub struct QueryMetrics {
     pub queries_by_type: HashMap<String, u64>,
     /// Queries by model.
     pub queries_by_model: HashMap<String, u64>,
+    /// Queries by `key:value` query tag.
+    pub queries_by_tag: HashMap<String, u64>,
+    /// Total execution time in microseconds by `key:value` query tag.
+    pub time_by_tag_us: HashMap<String, u64>,
 }
 
 impl Default for QueryMetrics {
@@ -48,6 +53,8 @@ impl Default for QueryMetrics {
             cache_hits: 0,
             queries_by_type: HashMap::new(),
             queries_by_model: HashMap::new(),
+            queries_by_tag: HashMap::new(),
+            time_by_tag_us: HashMap::new(),
         }
     }
 }
@@ -98,6 +105,14 @@ pub trait MetricsCollector: Send + Sync {
         from_cache: bool,
     );
 
+    /// Record the execution time of a query against its query tags.
+    ///
+    /// Called after [`record_query`](Self::record_query) for tagged
+    /// queries. Does nothing by default.
+    fn record_tags(&self, tags: &QueryTags, duration_us: u64, success: bool) {
+        let _ = (tags, duration_us, success);
+    }
+
     /// Get current metrics.
     fn get_metrics(&self) -> QueryMetrics;
 
@@ -119,6 +134,8 @@ pub struct InMemoryMetricsCollector {
     slow_threshold_us: u64,
     queries_by_type: RwLock<HashMap<String, u64>>,
     queries_by_model: RwLock<HashMap<String, u64>>,
+    queries_by_tag: RwLock<HashMap<String, u64>>,
+    time_by_tag_us: RwLock<HashMap<String, u64>>,
 }
 
 impl InMemoryMetricsCollector {
@@ -141,6 +158,8 @@ impl InMemoryMetricsCollector {
             slow_threshold_us: threshold_us,
             queries_by_type: RwLock::new(HashMap::new()),
             queries_by_model: RwLock::new(HashMap::new()),
+            queries_by_tag: RwLock::new(HashMap::new()),
+            time_by_tag_us: RwLock::new(HashMap::new()),
         }
     }
 }
@@ -222,6 +241,15 @@ impl MetricsCollector for InMemoryMetricsCollector {
         }
     }
 
+    fn record_tags(&self, tags: &QueryTags, duration_us: u64, _success: bool) {
+        let mut by_tag = self.queries_by_tag.write().unwrap();
+        let mut time_by_tag = self.time_by_tag_us.write().unwrap();
+        for label in tags.labels() {
+            *time_by_tag.entry(label.clone()).or_insert(0) += duration_us;
+            *by_tag.entry(label).or_insert(0) += 1;
+        }
+    }
+
     fn get_metrics(&self) -> QueryMetrics {
         let total = self.total_queries.load(Ordering::SeqCst);
         let total_time = self.total_time_us.load(Ordering::SeqCst);
@@ -240,6 +268,8 @@ impl MetricsCollector for InMemoryMetricsCollector {
             cache_hits: self.cache_hits.load(Ordering::SeqCst),
             queries_by_type: self.queries_by_type.read().unwrap().clone(),
             queries_by_model: self.queries_by_model.read().unwrap().clone(),
+            queries_by_tag: self.queries_by_tag.read().unwrap().clone(),
+            time_by_tag_us: self.time_by_tag_us.read().unwrap().clone(),
         }
     }
 
@@ -254,6 +284,8 @@ impl MetricsCollector for InMemoryMetricsCollector {
         self.cache_hits.store(0, Ordering::SeqCst);
         self.queries_by_type.write().unwrap().clear();
         self.queries_by_model.write().unwrap().clear();
+        self.queries_by_tag.write().unwrap().clear();
+        self.time_by_tag_us.write().unwrap().clear();
     }
 }
 
@@ -306,6 +338,7 @@ impl Middleware for MetricsMiddleware {
         Box::pin(async move {
             let query_type = ctx.query_type();
             let model = ctx.metadata().model.clone();
+            let tags = ctx.metadata().query_tags.clone();
             let start = Instant::now();
 
             let result = next.run(ctx).await;
@@ -323,6 +356,9 @@ impl Middleware for MetricsMiddleware {
                 success,
                 from_cache,
             );
+            if !tags.is_empty() {
+                self.collector.record_tags(&tags, duration_us, success);
+            }
 
             result
         })
@@ -389,4 +425,32 @@ mod tests {
         assert!((metrics.cache_hit_rate() - 0.25).abs() < 0.01);
         assert!((metrics.slow_query_rate() - 0.25).abs() < 0.01);
     }
+
+    #[tokio::test]
+    async fn test_metrics_by_tag() {
+        use crate::middleware::MiddlewareChain;
+
+        let (middleware, collector) = MetricsMiddleware::in_memory();
+        let mut chain = MiddlewareChain::new();
+        chain.push(middleware);
+
+        for sql in [
+            "SELECT * FROM carts /*feature='cart',team='checkout'*/",
+            "SELECT * FROM orders /*team='checkout'*/",
+            "SELECT * FROM users",
+        ] {
+            chain
+                .execute(QueryContext::new(sql, Vec::new()), |_| {
+                    Box::pin(async { Ok(QueryResponse::empty()) })
+                })
+                .await
+                .unwrap();
+        }
+
+        let metrics = collector.get_metrics();
+        assert_eq!(metrics.total_queries, 3);
+        assert_eq!(metrics.queries_by_tag["team:checkout"], 2);
+        assert_eq!(metrics.queries_by_tag["feature:cart"], 1);
+        assert_eq!(metrics.time_by_tag_us.len(), 2);
+    }
 }
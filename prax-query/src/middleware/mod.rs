@@ -6,11 +6,16 @@
 //! queries before and after execution. Use cases include:
 //!
 //! - **Logging** - Log all queries and their execution times
-//! - **Metrics** - Collect query performance metrics
+//! - **Metrics** - Collect query performance metrics (exported by [`crate::metrics`])
 //! - **Caching** - Cache query results
 //! - **Authentication** - Add tenant/user context to queries
-//! - **Retry logic** - Automatically retry failed queries
+//! - **Row-level security** - Enforce policies on SQLite and MySQL by rewriting queries
+//! - **Retry logic** - Retry transient failures with backoff, guarding writes
+//! - **Tracing** - Emit an OpenTelemetry-compatible span per query
 //! - **Circuit breaking** - Prevent cascade failures
+//! - **Dual writes** - Mirror writes to a second database ahead of a cutover
+//! - **Slow query plans** - Capture `EXPLAIN` plans of queries over a threshold
+//! - **Audit logging** - Record before/after images of changes to audited tables
 //!
 //! # Example
 //!
@@ -26,18 +31,34 @@
 //! let engine = engine.with_middleware(stack);
 //! ```
 
+mod audit;
 mod chain;
 mod context;
+mod dual_write;
+mod explain;
 mod logging;
 mod metrics;
+mod policy;
 mod retry;
 mod timing;
+mod tracing;
 mod types;
 
+pub use audit::{
+    ACTOR_TAG, AUDIT_LOG_TABLE, AuditEntry, AuditMiddleware, AuditOperation, AuditSink,
+    AuditedTable, MemoryAuditSink,
+};
 pub use chain::{MiddlewareBuilder, MiddlewareChain, MiddlewareStack};
 pub use context::{QueryContext, QueryMetadata, QueryPhase, QueryType};
+pub use dual_write::{CutoverReport, DualWriteMiddleware, TableDivergence, translate_statement};
+pub use explain::{SlowQuery, SlowQueryPlanMiddleware};
 pub use logging::{LogLevel, LoggingMiddleware};
-pub use metrics::{MetricsCollector, MetricsMiddleware, QueryMetrics};
-pub use retry::{RetryConfig, RetryMiddleware};
+pub use metrics::{InMemoryMetricsCollector, MetricsCollector, MetricsMiddleware, QueryMetrics};
+pub use policy::{PolicyMiddleware, ROLE_TAG};
+pub use retry::{
+    ErrorClass, ErrorClassifier, IDEMPOTENT_TAG, RetryBudget, RetryConfig, RetryMiddleware,
+    RetryPredicate, RetryableError, WriteRetry,
+};
 pub use timing::{TimingMiddleware, TimingResult};
+pub use tracing::{POOL_WAIT_FIELD, TracingMiddleware, record_pool_wait, redact_statement};
 pub use types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
@@ -0,0 +1,987 @@
+//! Row-level security emulated by rewriting queries.
+//!
+//! PostgreSQL and SQL Server enforce `policy` blocks natively. SQLite and
+//! MySQL have no row-level security, so [`PolicyMiddleware`] enforces the same
+//! policies by rewriting each statement before it runs:
+//!
+//! - every table read in a `FROM` or `JOIN` is replaced with
+//!   `(SELECT * FROM table WHERE <using>) AS table`, which also covers joins
+//!   and subqueries;
+//! - `UPDATE` and `DELETE` get the `using` expression (and, for updates, the
+//!   `check` expression) added to their `WHERE` clause;
+//! - `INSERT ... VALUES` inserts its rows through a CTE filtered by the
+//!   `check` expression; if any row fails the check, none is inserted and the
+//!   statement fails.
+//!
+//! As in PostgreSQL, permissive policies are OR'ed, restrictive ones are
+//! AND'ed, and a table with policies but none applying to the statement is
+//! hidden entirely. A policy without a `check` expression checks new rows
+//! with its `using` expression. An `UPDATE`'s `check` is evaluated against the
+//! row before the update, so it should not depend on the columns being set.
+//!
+//! Policy expressions are inlined as written, so they must be valid SQL for
+//! the backend. Two PostgreSQL idioms are translated:
+//!
+//! - `current_setting('app.user_id')` becomes the value of the `app.user_id`
+//!   attribute or tag of the query's metadata, or of a default set with
+//!   [`PolicyMiddleware::with_setting`], and `NULL` if there is none. A
+//!   trailing cast such as `::int` is dropped.
+//! - `current_user` becomes the role in the [`ROLE_TAG`] tag, which also
+//!   selects the policies that apply.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use prax_query::middleware::{MiddlewareStack, PolicyMiddleware};
+//! use prax_query::security::RlsPolicy;
+//! use prax_query::sql::DatabaseType;
+//!
+//! let stack = MiddlewareStack::new().with(
+//!     PolicyMiddleware::new(DatabaseType::SQLite).with_policy(
+//!         RlsPolicy::new("own_posts", "posts")
+//!             .using("author_id = current_setting('app.user_id')::int")
+//!             .build(),
+//!     ),
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::context::{QueryContext, QueryMetadata, QueryType};
+use super::types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
+use crate::error::{ErrorCode, QueryError, QueryResult};
+use crate::security::{PolicyCommand, RlsPolicy};
+use crate::sql::DatabaseType;
+
+/// Metadata tag holding the role policies are applied for.
+pub const ROLE_TAG: &str = "role";
+
+/// Middleware enforcing row-level security policies by rewriting queries.
+#[derive(Debug, Clone)]
+pub struct PolicyMiddleware {
+    db_type: DatabaseType,
+    policies: HashMap<String, Vec<RlsPolicy>>,
+    settings: HashMap<String, String>,
+}
+
+impl PolicyMiddleware {
+    /// Create a middleware for the given database.
+    ///
+    /// PostgreSQL and SQL Server enforce policies themselves; their queries
+    /// are passed through unchanged.
+    pub fn new(db_type: DatabaseType) -> Self {
+        Self {
+            db_type,
+            policies: HashMap::new(),
+            settings: HashMap::new(),
+        }
+    }
+
+    /// Add a policy.
+    pub fn with_policy(mut self, policy: RlsPolicy) -> Self {
+        self.policies
+            .entry(policy.table.to_lowercase())
+            .or_default()
+            .push(policy);
+        self
+    }
+
+    /// Add policies, such as the generated `rls_policies()`.
+    pub fn with_policies(self, policies: impl IntoIterator<Item = RlsPolicy>) -> Self {
+        policies.into_iter().fold(self, Self::with_policy)
+    }
+
+    /// Set the default value of a `current_setting(...)` name.
+    pub fn with_setting(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.settings.insert(name.into(), value.into());
+        self
+    }
+
+    /// Check if queries on this database are rewritten.
+    pub fn is_active(&self) -> bool {
+        matches!(self.db_type, DatabaseType::SQLite | DatabaseType::MySQL)
+    }
+
+    /// Rewrite a statement so it only sees and writes rows the policies allow.
+    ///
+    /// Returns the statement unchanged if it touches no table with policies.
+    pub fn rewrite(&self, sql: &str, metadata: &QueryMetadata) -> QueryResult<String> {
+        Ok(self.rewrite_statement(sql, metadata)?.sql)
+    }
+
+    fn rewrite_statement(&self, sql: &str, metadata: &QueryMetadata) -> QueryResult<Rewrite> {
+        let role = metadata.tags.get(ROLE_TAG).map(String::as_str);
+        let predicate = |table: &str, command: PolicyCommand| {
+            self.predicate(table, command, role)
+                .map(|p| self.resolve_settings(&p, metadata))
+        };
+
+        let sql = self.wrap_reads(sql, &predicate);
+        match QueryType::from_sql(&sql) {
+            QueryType::Update => Ok(Rewrite::new(restrict_target(&sql, "UPDATE", |table| {
+                predicate(table, PolicyCommand::Update)
+            }))),
+            QueryType::Delete => Ok(Rewrite::new(restrict_target(&sql, "DELETE", |table| {
+                predicate(table, PolicyCommand::Delete)
+            }))),
+            QueryType::Insert => {
+                let tokens = tokenize(&sql);
+                let Some(table) = insert_table(&sql, &tokens) else {
+                    return Ok(Rewrite::new(sql));
+                };
+                let Some(check) = predicate(&table, PolicyCommand::Insert) else {
+                    return Ok(Rewrite::new(sql));
+                };
+                match check_insert(&sql, &tokens, &check) {
+                    Some(rewritten) => Ok(Rewrite {
+                        sql: rewritten,
+                        checked_table: Some(table),
+                    }),
+                    None => Err(QueryError::unsupported(format!(
+                        "cannot apply the row-level security policies of {} to this INSERT; \
+                         use INSERT ... (columns) VALUES (...)",
+                        table
+                    ))),
+                }
+            }
+            _ => Ok(Rewrite::new(sql)),
+        }
+    }
+
+    /// Build the predicate the policies of `table` impose on `command`, or
+    /// `None` if the table has no policies.
+    ///
+    /// For inserts this is the check expression; for updates, using and
+    /// check combined; otherwise the using expression.
+    fn predicate(&self, table: &str, command: PolicyCommand, role: Option<&str>) -> Option<String> {
+        let policies = self.policies.get(&table.to_lowercase())?;
+        let applicable: Vec<_> = policies
+            .iter()
+            .filter(|p| p.command == PolicyCommand::All || p.command == command)
+            .filter(|p| {
+                p.roles.is_empty()
+                    || p.roles.iter().any(|r| {
+                        r.eq_ignore_ascii_case("PUBLIC")
+                            || role.is_some_and(|role| r.eq_ignore_ascii_case(role))
+                    })
+            })
+            .collect();
+
+        let using = |p: &RlsPolicy| p.using.clone().unwrap_or_else(|| "1 = 1".to_string());
+        let check = |p: &RlsPolicy| {
+            p.with_check
+                .clone()
+                .or_else(|| p.using.clone())
+                .unwrap_or_else(|| "1 = 1".to_string())
+        };
+        let combine = |expr: &dyn Fn(&RlsPolicy) -> String| {
+            let permissive: Vec<_> = applicable
+                .iter()
+                .filter(|p| p.permissive)
+                .map(|p| format!("({})", expr(p)))
+                .collect();
+            if permissive.is_empty() {
+                return "1 = 0".to_string();
+            }
+            let mut parts = vec![format!("({})", permissive.join(" OR "))];
+            parts.extend(
+                applicable
+                    .iter()
+                    .filter(|p| !p.permissive)
+                    .map(|p| format!("({})", expr(p))),
+            );
+            parts.join(" AND ")
+        };
+
+        Some(match command {
+            PolicyCommand::Insert => combine(&check),
+            PolicyCommand::Update => format!("{} AND {}", combine(&using), combine(&check)),
+            _ => combine(&using),
+        })
+    }
+
+    /// Replace `current_setting(...)` and `current_user` with their values.
+    fn resolve_settings(&self, expr: &str, metadata: &QueryMetadata) -> String {
+        let tokens = tokenize(expr);
+        let mut out = String::with_capacity(expr.len());
+        let mut pos = 0;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let token = tokens[i];
+            let (replacement, next) = if token.is_word(expr, "current_setting")
+                && tokens.get(i + 1).is_some_and(|t| t.is_punct(expr, '('))
+                && tokens
+                    .get(i + 2)
+                    .is_some_and(|t| t.kind == TokenKind::Literal)
+            {
+                let name = tokens[i + 2].text(expr).trim_matches('\'').to_string();
+                // Skip the optional `missing_ok` argument and the closing paren
+                let mut j = i + 3;
+                while j < tokens.len() && !tokens[j].is_punct(expr, ')') {
+                    j += 1;
+                }
+                j += 1;
+                // Drop a `::type` cast
+                if tokens.get(j).is_some_and(|t| t.is_punct(expr, ':'))
+                    && tokens.get(j + 1).is_some_and(|t| t.is_punct(expr, ':'))
+                    && tokens.get(j + 2).is_some_and(|t| t.kind == TokenKind::Word)
+                {
+                    j += 3;
+                }
+                (Some(self.setting(&name, metadata)), j)
+            } else if ["current_user", "current_role", "session_user"]
+                .iter()
+                .any(|w| token.is_word(expr, w))
+            {
+                let role = metadata
+                    .tags
+                    .get(ROLE_TAG)
+                    .map(|r| sql_string(self.db_type, r));
+                (Some(role.unwrap_or_else(|| "NULL".to_string())), i + 1)
+            } else {
+                (None, i + 1)
+            };
+
+            if let Some(replacement) = replacement {
+                out.push_str(&expr[pos..token.start]);
+                out.push_str(&replacement);
+                pos = tokens.get(next - 1).map_or(expr.len(), |t| t.end);
+            }
+            i = next;
+        }
+
+        out.push_str(&expr[pos..]);
+        out
+    }
+
+    /// Get the SQL literal of a setting.
+    fn setting(&self, name: &str, metadata: &QueryMetadata) -> String {
+        if let Some(value) = metadata.attributes.get(name) {
+            return match value {
+                serde_json::Value::Null => "NULL".to_string(),
+                serde_json::Value::Bool(b) => if *b { "1" } else { "0" }.to_string(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::String(s) => sql_string(self.db_type, s),
+                other => sql_string(self.db_type, &other.to_string()),
+            };
+        }
+        metadata
+            .tags
+            .get(name)
+            .or_else(|| self.settings.get(name))
+            .map(|value| sql_string(self.db_type, value))
+            .unwrap_or_else(|| "NULL".to_string())
+    }
+
+    /// Wrap every table read in a `FROM` or `JOIN` in a filtered subquery.
+    fn wrap_reads(
+        &self,
+        sql: &str,
+        predicate: &dyn Fn(&str, PolicyCommand) -> Option<String>,
+    ) -> String {
+        let tokens = tokenize(sql);
+        let mut out = String::with_capacity(sql.len());
+        let mut pos = 0;
+        let mut expect_table = false;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let token = tokens[i];
+            if token.is_word(sql, "FROM") || token.is_word(sql, "JOIN") {
+                // The target of a DELETE is restricted through its WHERE clause
+                let is_delete_target = token.is_word(sql, "FROM")
+                    && i > 0
+                    && tokens[..i]
+                        .iter()
+                        .rev()
+                        .take_while(|t| t.kind == TokenKind::Word)
+                        .any(|t| t.is_word(sql, "DELETE"));
+                expect_table = !is_delete_target;
+                i += 1;
+                continue;
+            }
+            if !expect_table {
+                i += 1;
+                continue;
+            }
+            expect_table = false;
+
+            // Skip subqueries and table functions
+            let table = table_ref(sql, &tokens, i).filter(|t| {
+                !tokens
+                    .get(t.name_end)
+                    .is_some_and(|next| next.is_punct(sql, '('))
+            });
+            let Some(table) = table else {
+                i += 1;
+                continue;
+            };
+            let next = table.alias_end;
+            if let Some(predicate) = predicate(&table.name, PolicyCommand::Select) {
+                let reference = &sql[tokens[i].start..tokens[table.name_end - 1].end];
+                out.push_str(&sql[pos..tokens[i].start]);
+                out.push_str(&format!(
+                    "(SELECT * FROM {} WHERE {})",
+                    reference, predicate
+                ));
+                if table.alias_end == table.name_end {
+                    out.push_str(" AS ");
+                    out.push_str(tokens[table.name_end - 1].text(sql));
+                }
+                pos = tokens[table.name_end - 1].end;
+            }
+            // `FROM a, b` lists more tables
+            expect_table = tokens.get(next).is_some_and(|t| t.is_punct(sql, ','));
+            i = if expect_table { next + 1 } else { next };
+        }
+
+        out.push_str(&sql[pos..]);
+        out
+    }
+}
+
+/// The outcome of rewriting a statement.
+struct Rewrite {
+    sql: String,
+    /// Table whose check an INSERT was filtered through.
+    checked_table: Option<String>,
+}
+
+impl Rewrite {
+    fn new(sql: String) -> Self {
+        Self {
+            sql,
+            checked_table: None,
+        }
+    }
+}
+
+impl Middleware for PolicyMiddleware {
+    fn handle<'a>(
+        &'a self,
+        ctx: QueryContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        Box::pin(async move {
+            if !self.is_active() || self.policies.is_empty() {
+                return next.run(ctx).await;
+            }
+
+            let rewrite = self.rewrite_statement(ctx.sql(), ctx.metadata())?;
+            let ctx = ctx.with_sql(rewrite.sql);
+            let response = next.run(ctx).await?;
+
+            if let Some(table) = rewrite.checked_table {
+                if response.rows_affected == Some(0) {
+                    return Err(QueryError::new(
+                        ErrorCode::CheckConstraint,
+                        format!(
+                            "new row violates row-level security policy for table {}",
+                            table
+                        ),
+                    )
+                    .with_model(table));
+                }
+            }
+            Ok(response)
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "PolicyMiddleware"
+    }
+}
+
+/// Add a predicate on the target table of an UPDATE or DELETE to its WHERE
+/// clause.
+fn restrict_target(
+    sql: &str,
+    statement: &str,
+    predicate: impl Fn(&str) -> Option<String>,
+) -> String {
+    let tokens = tokenize(sql);
+    let Some(target) = write_target(sql, &tokens, statement) else {
+        return sql.to_string();
+    };
+    let Some(predicate) = predicate(&target.table) else {
+        return sql.to_string();
+    };
+    let body_end = target.body_end;
+
+    match target.condition {
+        Some(condition) => format!(
+            "{} ({}) AND {}{}",
+            &sql[..condition.start],
+            sql[condition].trim(),
+            predicate,
+            &sql[body_end..]
+        ),
+        None => format!(
+            "{} WHERE {}{}",
+            &sql[..body_end],
+            predicate,
+            &sql[body_end..]
+        ),
+    }
+}
+
+/// The target of an UPDATE or DELETE.
+pub(super) struct WriteTarget {
+    /// Unquoted table name.
+    pub(super) table: String,
+    /// Byte range of the table reference as written, with any schema and
+    /// alias.
+    pub(super) table_ref: Range<usize>,
+    /// Byte range of the WHERE condition, if there is one.
+    pub(super) condition: Option<Range<usize>>,
+    /// Byte offset where the statement body ends, before any RETURNING,
+    /// ORDER BY, LIMIT or `;`.
+    pub(super) body_end: usize,
+}
+
+/// Parse the target of an UPDATE or DELETE.
+pub(super) fn update_or_delete_target(sql: &str) -> Option<WriteTarget> {
+    let tokens = tokenize(sql);
+    ["UPDATE", "DELETE"]
+        .iter()
+        .find_map(|statement| write_target(sql, &tokens, statement))
+}
+
+/// Parse the target table and WHERE clause of a `statement`.
+fn write_target(sql: &str, tokens: &[Token], statement: &str) -> Option<WriteTarget> {
+    if !tokens.first()?.is_word(sql, statement) {
+        return None;
+    }
+
+    // Skip modifiers (`OR IGNORE`, `LOW_PRIORITY`, ...) and DELETE's FROM
+    let mut i = 1;
+    while tokens.get(i).is_some_and(|t| {
+        t.kind == TokenKind::Word
+            && [
+                "OR",
+                "ROLLBACK",
+                "ABORT",
+                "REPLACE",
+                "FAIL",
+                "IGNORE",
+                "LOW_PRIORITY",
+                "QUICK",
+                "FROM",
+            ]
+            .iter()
+            .any(|w| t.is_word(sql, w))
+    }) {
+        i += 1;
+    }
+    let table = table_ref(sql, tokens, i)?;
+
+    let top_level = top_level_indices(sql, tokens);
+    let clause_end = top_level
+        .iter()
+        .copied()
+        .filter(|&j| j >= table.alias_end)
+        .find(|&j| {
+            ["RETURNING", "ORDER", "LIMIT"]
+                .iter()
+                .any(|w| tokens[j].is_word(sql, w))
+                || tokens[j].is_punct(sql, ';')
+        })
+        .map_or(sql.len(), |j| tokens[j].start);
+    let body_end = sql[..clause_end].trim_end().len();
+
+    let condition = top_level
+        .iter()
+        .copied()
+        .find(|&j| j >= table.alias_end && tokens[j].is_word(sql, "WHERE"))
+        .map(|j| tokens[j].end..body_end);
+
+    Some(WriteTarget {
+        table_ref: tokens[i].start..tokens[table.alias_end - 1].end,
+        table: table.name,
+        condition,
+        body_end,
+    })
+}
+
+/// Get the target table of an INSERT.
+fn insert_table(sql: &str, tokens: &[Token]) -> Option<String> {
+    let into = tokens.iter().position(|t| t.is_word(sql, "INTO"))?;
+    table_ref(sql, tokens, into + 1).map(|t| t.name)
+}
+
+/// Rewrite `INSERT INTO t (cols) VALUES (...), ...` to insert its rows only if
+/// all pass `check`; `None` for other INSERT forms.
+fn check_insert(sql: &str, tokens: &[Token], check: &str) -> Option<String> {
+    let into = tokens.iter().position(|t| t.is_word(sql, "INTO"))?;
+    let table = table_ref(sql, tokens, into + 1)?;
+
+    // Column list
+    let open = table.alias_end;
+    if !tokens.get(open)?.is_punct(sql, '(') {
+        return None;
+    }
+    let close = matching_paren(sql, tokens, open)?;
+    let columns = &sql[tokens[open].end..tokens[close].start];
+
+    // Value rows
+    if !tokens.get(close + 1)?.is_word(sql, "VALUES") {
+        return None;
+    }
+    let mut rows = Vec::new();
+    let mut i = close + 2;
+    loop {
+        if !tokens.get(i)?.is_punct(sql, '(') {
+            return None;
+        }
+        let end = matching_paren(sql, tokens, i)?;
+        rows.push(sql[tokens[i].end..tokens[end].start].trim());
+        i = end + 1;
+        if !tokens.get(i).is_some_and(|t| t.is_punct(sql, ',')) {
+            break;
+        }
+        i += 1;
+    }
+    let tail = tokens.get(i).map_or("", |t| &sql[t.start..]);
+
+    let selects: Vec<_> = rows.iter().map(|row| format!("SELECT {}", row)).collect();
+    Some(format!(
+        "{} WITH prax_new ({}) AS ({}) SELECT * FROM prax_new \
+         WHERE NOT EXISTS (SELECT 1 FROM prax_new WHERE NOT COALESCE(({}), 0)){}{}",
+        &sql[..tokens[close].end],
+        columns.trim(),
+        selects.join(" UNION ALL "),
+        check,
+        if tail.is_empty() { "" } else { " " },
+        tail
+    ))
+}
+
+/// A table reference: `name`, `schema.name`, optionally aliased.
+struct TableRef {
+    /// Unquoted table name.
+    name: String,
+    /// Index of the token after the name.
+    name_end: usize,
+    /// Index of the token after the alias (`name_end` without an alias).
+    alias_end: usize,
+}
+
+/// Parse a table reference starting at token `i`.
+fn table_ref(sql: &str, tokens: &[Token], i: usize) -> Option<TableRef> {
+    let mut name = tokens.get(i)?.ident(sql)?;
+    let mut j = i + 1;
+    while tokens.get(j).is_some_and(|t| t.is_punct(sql, '.')) {
+        name = tokens.get(j + 1)?.ident(sql)?;
+        j += 2;
+    }
+    let name_end = j;
+    let alias_end = match tokens.get(j) {
+        Some(t) if t.is_word(sql, "AS") => j + 2,
+        Some(t) if t.kind == TokenKind::Quoted => j + 1,
+        Some(t) if t.kind == TokenKind::Word && !is_clause_keyword(t.text(sql)) => j + 1,
+        _ => j,
+    };
+    Some(TableRef {
+        name,
+        name_end,
+        alias_end,
+    })
+}
+
+/// Check if a word ends a table reference rather than aliasing it.
+fn is_clause_keyword(word: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "WHERE",
+        "JOIN",
+        "INNER",
+        "LEFT",
+        "RIGHT",
+        "FULL",
+        "CROSS",
+        "NATURAL",
+        "OUTER",
+        "ON",
+        "USING",
+        "GROUP",
+        "ORDER",
+        "HAVING",
+        "LIMIT",
+        "OFFSET",
+        "UNION",
+        "EXCEPT",
+        "INTERSECT",
+        "SET",
+        "VALUES",
+        "RETURNING",
+        "WINDOW",
+        "FOR",
+        "DEFAULT",
+        "SELECT",
+        "INDEXED",
+        "NOT",
+    ];
+    KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k))
+}
+
+/// Get the indices of the tokens outside parentheses.
+fn top_level_indices(sql: &str, tokens: &[Token]) -> Vec<usize> {
+    let mut depth = 0usize;
+    let mut indices = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_punct(sql, '(') {
+            depth += 1;
+        } else if token.is_punct(sql, ')') {
+            depth = depth.saturating_sub(1);
+        } else if depth == 0 {
+            indices.push(i);
+        }
+    }
+    indices
+}
+
+/// Find the token closing the parenthesis at `open`.
+fn matching_paren(sql: &str, tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_punct(sql, '(') {
+            depth += 1;
+        } else if token.is_punct(sql, ')') {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Quote a string as an SQL literal.
+///
+/// MySQL also reads backslash escapes inside literals, so backslashes are
+/// doubled there; otherwise a `\` before a doubled quote would escape its
+/// first half and the second would end the literal.
+fn sql_string(db_type: DatabaseType, value: &str) -> String {
+    let escaped = value.replace('\'', "''");
+    match db_type {
+        DatabaseType::MySQL => format!("'{}'", escaped.replace('\\', "\\\\")),
+        _ => format!("'{}'", escaped),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    /// Keyword or unquoted identifier.
+    Word,
+    /// Quoted identifier (`"x"`, `` `x` ``, `[x]`).
+    Quoted,
+    /// String or number literal, or parameter placeholder.
+    Literal,
+    /// Any other character.
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn text<'s>(&self, sql: &'s str) -> &'s str {
+        &sql[self.start..self.end]
+    }
+
+    fn is_word(&self, sql: &str, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text(sql).eq_ignore_ascii_case(word)
+    }
+
+    fn is_punct(&self, sql: &str, c: char) -> bool {
+        self.kind == TokenKind::Punct && self.text(sql).starts_with(c)
+    }
+
+    /// Get the unquoted identifier, if this token is one.
+    fn ident(&self, sql: &str) -> Option<String> {
+        let text = self.text(sql);
+        match self.kind {
+            TokenKind::Word if !is_clause_keyword(text) => Some(text.to_string()),
+            TokenKind::Quoted => Some(text[1..text.len() - 1].to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Split SQL into tokens, skipping whitespace and comments.
+fn tokenize(sql: &str) -> Vec<Token> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let closing = |open: u8| match open {
+        b'[' => b']',
+        other => other,
+    };
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let kind = match c {
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'\'' => {
+                i += 1;
+                while i < bytes.len() {
+                    if bytes[i] == b'\'' {
+                        if bytes.get(i + 1) == Some(&b'\'') {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                TokenKind::Literal
+            }
+            b'"' | b'`' | b'[' => {
+                let close = closing(c);
+                i += 1;
+                while i < bytes.len() && bytes[i] != close {
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                TokenKind::Quoted
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' || c >= 0x80 => {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric()
+                        || bytes[i] == b'_'
+                        || bytes[i] == b'$'
+                        || bytes[i] >= 0x80)
+                {
+                    i += 1;
+                }
+                TokenKind::Word
+            }
+            c if c.is_ascii_digit() || c == b'?' || c == b'$' => {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                TokenKind::Literal
+            }
+            _ => {
+                i += 1;
+                TokenKind::Punct
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareChain;
+
+    fn middleware() -> PolicyMiddleware {
+        PolicyMiddleware::new(DatabaseType::SQLite)
+            .with_policy(
+                RlsPolicy::new("own_posts", "posts")
+                    .using("author_id = current_setting('app.user_id')::int")
+                    .build(),
+            )
+            .with_policy(
+                RlsPolicy::new("published", "posts")
+                    .for_select()
+                    .using("published = 1")
+                    .build(),
+            )
+            .with_policy(
+                RlsPolicy::new("not_archived", "posts")
+                    .using("archived = 0")
+                    .restrictive()
+                    .build(),
+            )
+    }
+
+    fn metadata() -> QueryMetadata {
+        QueryMetadata::new().with_attribute("app.user_id", serde_json::json!(7))
+    }
+
+    #[test]
+    fn test_rewrite_select() {
+        let mw = middleware();
+        assert_eq!(
+            mw.rewrite(
+                "SELECT * FROM posts p JOIN users ON users.id = p.author_id WHERE p.id = ?",
+                &metadata()
+            )
+            .unwrap(),
+            "SELECT * FROM (SELECT * FROM posts WHERE ((author_id = 7) OR (published = 1)) AND (archived = 0)) p \
+             JOIN users ON users.id = p.author_id WHERE p.id = ?"
+        );
+        assert_eq!(
+            mw.rewrite("SELECT COUNT(*) FROM \"posts\"", &QueryMetadata::new())
+                .unwrap(),
+            "SELECT COUNT(*) FROM (SELECT * FROM \"posts\" WHERE ((author_id = NULL) OR (published = 1)) AND (archived = 0)) AS \"posts\""
+        );
+        assert_eq!(
+            mw.rewrite("SELECT * FROM users", &metadata()).unwrap(),
+            "SELECT * FROM users"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_update_and_delete() {
+        let mw = middleware();
+        assert_eq!(
+            mw.rewrite(
+                "UPDATE posts SET title = ? WHERE id = ? RETURNING id",
+                &metadata()
+            )
+            .unwrap(),
+            "UPDATE posts SET title = ? WHERE (id = ?) AND ((author_id = 7)) AND (archived = 0) \
+             AND ((author_id = 7)) AND (archived = 0) RETURNING id"
+        );
+        assert_eq!(
+            mw.rewrite("DELETE FROM posts", &metadata()).unwrap(),
+            "DELETE FROM posts WHERE ((author_id = 7)) AND (archived = 0)"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_insert() {
+        let mw = middleware();
+        assert_eq!(
+            mw.rewrite(
+                "INSERT INTO posts (title, author_id) VALUES (?, ?), ('b', 7) RETURNING id",
+                &metadata()
+            )
+            .unwrap(),
+            "INSERT INTO posts (title, author_id) WITH prax_new (title, author_id) AS \
+             (SELECT ?, ? UNION ALL SELECT 'b', 7) SELECT * FROM prax_new WHERE NOT EXISTS \
+             (SELECT 1 FROM prax_new WHERE NOT COALESCE((((author_id = 7)) AND (archived = 0)), 0)) RETURNING id"
+        );
+        assert!(
+            mw.rewrite("INSERT INTO posts SELECT * FROM drafts", &metadata())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_roles_and_settings() {
+        let mw = PolicyMiddleware::new(DatabaseType::MySQL)
+            .with_setting("app.tenant", "acme")
+            .with_policy(
+                RlsPolicy::new("admins", "docs")
+                    .to_roles(["admin"])
+                    .using("owner = current_user")
+                    .build(),
+            )
+            .with_policy(
+                RlsPolicy::new("tenant", "docs")
+                    .using("tenant = current_setting('app.tenant', true)")
+                    .build(),
+            );
+
+        let admin = QueryMetadata::new().with_tag(ROLE_TAG, "admin");
+        assert_eq!(
+            mw.rewrite("SELECT * FROM docs", &admin).unwrap(),
+            "SELECT * FROM (SELECT * FROM docs WHERE ((owner = 'admin') OR (tenant = 'acme'))) AS docs"
+        );
+        assert_eq!(
+            mw.rewrite("SELECT * FROM docs", &QueryMetadata::new())
+                .unwrap(),
+            "SELECT * FROM (SELECT * FROM docs WHERE ((tenant = 'acme'))) AS docs"
+        );
+
+        let hidden = PolicyMiddleware::new(DatabaseType::SQLite).with_policy(
+            RlsPolicy::new("admins", "docs")
+                .to_roles(["admin"])
+                .using("1 = 1")
+                .build(),
+        );
+        assert_eq!(
+            hidden
+                .rewrite("SELECT * FROM docs", &QueryMetadata::new())
+                .unwrap(),
+            "SELECT * FROM (SELECT * FROM docs WHERE 1 = 0) AS docs"
+        );
+    }
+
+    #[test]
+    fn test_setting_values_cannot_break_out_of_literals() {
+        let policy = RlsPolicy::new("tenant", "docs")
+            .using("tenant = current_setting('app.tenant') OR owner = current_user")
+            .build();
+        let payload = r"\' OR 1=1 -- ";
+        let metadata = QueryMetadata::new()
+            .with_tag("app.tenant", payload)
+            .with_tag(ROLE_TAG, payload);
+
+        let mysql = PolicyMiddleware::new(DatabaseType::MySQL).with_policy(policy.clone());
+        assert_eq!(
+            mysql.rewrite("SELECT * FROM docs", &metadata).unwrap(),
+            r"SELECT * FROM (SELECT * FROM docs WHERE ((tenant = '\\'' OR 1=1 -- ' OR owner = '\\'' OR 1=1 -- '))) AS docs"
+        );
+
+        let sqlite = PolicyMiddleware::new(DatabaseType::SQLite).with_policy(policy);
+        assert_eq!(
+            sqlite.rewrite("SELECT * FROM docs", &metadata).unwrap(),
+            r"SELECT * FROM (SELECT * FROM docs WHERE ((tenant = '\'' OR 1=1 -- ' OR owner = '\'' OR 1=1 -- '))) AS docs"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policy_middleware_rejects_blocked_insert() {
+        let mut chain = MiddlewareChain::new();
+        chain.push(middleware());
+
+        let ctx = QueryContext::new(
+            "INSERT INTO posts (title, author_id) VALUES (?, ?)",
+            Vec::new(),
+        )
+        .with_metadata(metadata());
+        let err = chain
+            .execute(ctx, |ctx| {
+                assert!(ctx.sql().contains("WITH prax_new"));
+                Box::pin(async { Ok(QueryResponse::with_affected(0)) })
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::CheckConstraint);
+
+        // PostgreSQL enforces policies itself
+        let mut chain = MiddlewareChain::new();
+        chain.push(
+            PolicyMiddleware::new(DatabaseType::PostgreSQL).with_policies(vec![
+                RlsPolicy::new("own_posts", "posts").using("1 = 0").build(),
+            ]),
+        );
+        let ctx = QueryContext::new("SELECT * FROM posts", Vec::new());
+        chain
+            .execute(ctx, |ctx| {
+                assert_eq!(ctx.sql(), "SELECT * FROM posts");
+                Box::pin(async { Ok(QueryResponse::empty()) })
+            })
+            .await
+            .unwrap();
+    }
+}
@@ -1,10 +1,42 @@
 //! Retry middleware for automatic query retry on transient failures.
+//!
+//! [`RetryMiddleware`] runs the rest of the chain again when a query fails
+//! with an error worth retrying, waiting an exponentially growing, jittered
+//! delay between attempts.
+//!
+//! Errors are sorted into an [`ErrorClass`]: transient network and pool
+//! failures and timeouts, conflicts such as deadlocks and serialization
+//! failures, and permanent errors. Only the first two are retried by
+//! default, and constraint violations are never retried, whatever the
+//! configuration. A [`RetryConfig::classify`] hook can reclassify errors the
+//! built-in rules don't recognise, such as driver-specific codes.
+//!
+//! Writes are guarded: a write whose error leaves it unknown whether it was
+//! applied, such as a dropped connection, is not retried unless the query is
+//! marked idempotent with the [`IDEMPOTENT_TAG`] tag. See [`WriteRetry`].
+//!
+//! A [`RetryBudget`] caps retries to a share of all queries, so that an
+//! outage doesn't multiply the load on the database.
+//!
+//! Retrying a single statement only helps outside an explicit transaction;
+//! a conflict inside one aborts the whole transaction, which has to be
+//! retried as a unit.
 
 use super::context::QueryContext;
 use super::types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
 use crate::QueryError;
+use crate::error::ErrorCode;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
+/// Metadata tag marking a write as safe to retry, with any value.
+///
+/// Set it on writes that can be applied twice without harm, such as an
+/// upsert or an update setting columns to fixed values.
+pub const IDEMPOTENT_TAG: &str = "idempotent";
+
 /// Configuration for retry behavior.
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
@@ -20,6 +52,12 @@ pub struct RetryConfig {
     pub jitter: bool,
     /// Predicate to determine if error is retryable.
     pub retry_on: RetryPredicate,
+    /// Hook reclassifying errors before the built-in rules.
+    pub classifier: Option<ErrorClassifier>,
+    /// Which writes may be retried.
+    pub write_retry: WriteRetry,
+    /// Budget shared by retries, if any.
+    pub budget: Option<RetryBudget>,
 }
 
 impl Default for RetryConfig {
@@ -31,6 +69,9 @@ impl Default for RetryConfig {
             backoff_multiplier: 2.0,
             jitter: true,
             retry_on: RetryPredicate::Default,
+            classifier: None,
+            write_retry: WriteRetry::default(),
+            budget: None,
         }
     }
 }
@@ -47,6 +88,11 @@ impl RetryConfig {
         self
     }
 
+    /// Set the total number of attempts, including the first.
+    pub fn max_attempts(self, n: u32) -> Self {
+        self.max_retries(n.saturating_sub(1))
+    }
+
     /// Set initial delay.
     pub fn initial_delay(mut self, delay: Duration) -> Self {
         self.initial_delay = delay;
@@ -77,6 +123,77 @@ impl RetryConfig {
         self
     }
 
+    /// Reclassify errors with a hook, which returns `None` to fall back to
+    /// [`ErrorClass::of`].
+    ///
+    /// ```rust
+    /// use prax_query::middleware::{ErrorClass, RetryConfig};
+    ///
+    /// // A proxy reporting failovers as a generic database error
+    /// let config = RetryConfig::new().classify(|error| {
+    ///     error
+    ///         .message
+    ///         .contains("failover in progress")
+    ///         .then_some(ErrorClass::Transient)
+    /// });
+    /// ```
+    pub fn classify<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&QueryError) -> Option<ErrorClass> + Send + Sync + 'static,
+    {
+        self.classifier = Some(ErrorClassifier(Arc::new(classifier)));
+        self
+    }
+
+    /// Set which writes may be retried.
+    pub fn write_retry(mut self, write_retry: WriteRetry) -> Self {
+        self.write_retry = write_retry;
+        self
+    }
+
+    /// Limit retries with a budget, which may be shared between middlewares.
+    pub fn retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Get the class of an error, consulting the classifier hook first.
+    pub fn error_class(&self, error: &QueryError) -> ErrorClass {
+        self.classifier
+            .as_ref()
+            .and_then(|classifier| (classifier.0)(error))
+            .unwrap_or_else(|| ErrorClass::of(error))
+    }
+
+    /// Check if a query that failed with `error` should be retried.
+    ///
+    /// This ignores the attempt count and the budget.
+    pub fn should_retry(&self, error: &QueryError, ctx: &QueryContext) -> bool {
+        // Retrying cannot make a violated constraint hold
+        if error.is_constraint_violation() {
+            return false;
+        }
+
+        let class = self.error_class(error);
+        let retryable = match &self.retry_on {
+            RetryPredicate::Default => class != ErrorClass::Permanent,
+            predicate => predicate.should_retry(error),
+        };
+        if !retryable {
+            return false;
+        }
+
+        // Reads, and writes marked idempotent, are safe to run again
+        if ctx.is_read() || ctx.metadata().tags.contains_key(IDEMPOTENT_TAG) {
+            return true;
+        }
+        match self.write_retry {
+            WriteRetry::Never => false,
+            WriteRetry::Unapplied => class == ErrorClass::Conflict || is_unsent(error),
+            WriteRetry::Always => true,
+        }
+    }
+
     /// Calculate delay for a given attempt.
     pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
         let base_delay =
@@ -96,10 +213,157 @@ impl RetryConfig {
     }
 }
 
+/// Check if an error was raised before the query reached the database.
+fn is_unsent(error: &QueryError) -> bool {
+    matches!(
+        error.code,
+        ErrorCode::PoolExhausted | ErrorCode::PoolTimeout
+    )
+}
+
+/// How an error bears on retrying the query that raised it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// The connection, pool or server failed, or the query timed out; the
+    /// same query may succeed later.
+    Transient,
+    /// The query lost a race with another transaction, such as a deadlock or
+    /// serialization failure, and was rolled back.
+    Conflict,
+    /// Running the query again fails the same way.
+    Permanent,
+}
+
+impl ErrorClass {
+    /// Classify an error with the built-in rules.
+    ///
+    /// Authentication and TLS failures are permanent, as they won't go away
+    /// by themselves.
+    pub fn of(error: &QueryError) -> Self {
+        match error.code {
+            ErrorCode::ConnectionFailed
+            | ErrorCode::PoolExhausted
+            | ErrorCode::PoolTimeout
+            | ErrorCode::ConnectionTimeout
+            | ErrorCode::QueryTimeout => Self::Transient,
+            ErrorCode::Deadlock | ErrorCode::SerializationFailure => Self::Conflict,
+            _ => Self::Permanent,
+        }
+    }
+}
+
+/// The function behind an [`ErrorClassifier`].
+type ClassifyFn = dyn Fn(&QueryError) -> Option<ErrorClass> + Send + Sync;
+
+/// A hook classifying errors, set with [`RetryConfig::classify`].
+#[derive(Clone)]
+pub struct ErrorClassifier(Arc<ClassifyFn>);
+
+impl fmt::Debug for ErrorClassifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorClassifier")
+    }
+}
+
+/// Which writes may be retried.
+///
+/// Reads are always retried, as are writes tagged with [`IDEMPOTENT_TAG`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteRetry {
+    /// Never retry writes.
+    Never,
+    /// Retry writes only when the error shows they weren't applied: the
+    /// transaction was rolled back after a conflict, or no connection was
+    /// acquired. A write whose connection dropped or timed out may have been
+    /// committed, so it is not retried.
+    #[default]
+    Unapplied,
+    /// Retry writes like reads. Only for writes that are idempotent.
+    Always,
+}
+
+/// A budget capping retries to a share of queries.
+///
+/// Every query deposits `ratio` tokens, up to `max_tokens`, and every retry
+/// withdraws one; a retry is skipped when the budget is empty. Clones share
+/// the same balance.
+///
+/// ```rust
+/// use prax_query::middleware::RetryBudget;
+///
+/// // Retry at most one query in ten, with bursts of up to 2 retries
+/// let budget = RetryBudget::new(0.1, 2);
+/// assert!(budget.try_withdraw());
+/// assert!(budget.try_withdraw());
+/// assert!(!budget.try_withdraw());
+///
+/// for _ in 0..10 {
+///     budget.deposit();
+/// }
+/// assert!(budget.try_withdraw());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    state: Arc<BudgetState>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    /// Thousandths of a token deposited per query.
+    ratio: u64,
+    /// Maximum balance, in thousandths of a token.
+    max: u64,
+    /// Current balance, in thousandths of a token.
+    balance: AtomicU64,
+}
+
+/// Thousandths of a token in a token.
+const TOKEN: u64 = 1000;
+
+impl RetryBudget {
+    /// Create a full budget earning `ratio` retries per query, holding at
+    /// most `max_tokens` retries.
+    pub fn new(ratio: f64, max_tokens: u32) -> Self {
+        let max = u64::from(max_tokens) * TOKEN;
+        Self {
+            state: Arc::new(BudgetState {
+                ratio: (ratio.max(0.0) * TOKEN as f64).round() as u64,
+                max,
+                balance: AtomicU64::new(max),
+            }),
+        }
+    }
+
+    /// Record a query, earning `ratio` retries.
+    pub fn deposit(&self) {
+        let state = &self.state;
+        let _ = state
+            .balance
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |balance| {
+                Some((balance + state.ratio).min(state.max))
+            });
+    }
+
+    /// Spend one retry, returning `false` if the budget is empty.
+    pub fn try_withdraw(&self) -> bool {
+        self.state
+            .balance
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |balance| {
+                balance.checked_sub(TOKEN)
+            })
+            .is_ok()
+    }
+
+    /// Get the number of whole retries left.
+    pub fn available(&self) -> u32 {
+        (self.state.balance.load(Ordering::Relaxed) / TOKEN) as u32
+    }
+}
+
 /// Predicate for determining if an error should trigger a retry.
 #[derive(Debug, Clone)]
 pub enum RetryPredicate {
-    /// Default: retry on connection and timeout errors.
+    /// Default: retry transient errors and conflicts (see [`ErrorClass`]).
     Default,
     /// Retry on any error.
     Always,
@@ -117,7 +381,7 @@ impl RetryPredicate {
     /// Check if an error should be retried.
     pub fn should_retry(&self, error: &QueryError) -> bool {
         match self {
-            Self::Default => error.is_connection_error() || error.is_timeout(),
+            Self::Default => ErrorClass::of(error) != ErrorClass::Permanent,
             Self::Always => true,
             Self::Never => false,
             Self::ConnectionOnly => error.is_connection_error(),
@@ -177,15 +441,17 @@ fn rand_jitter() -> f64 {
 ///
 /// # Example
 ///
-/// ```rust,ignore
-/// use prax_query::middleware::{RetryMiddleware, RetryConfig};
+/// ```rust
+/// use prax_query::middleware::{RetryBudget, RetryConfig, RetryMiddleware, WriteRetry};
 /// use std::time::Duration;
 ///
 /// let retry = RetryMiddleware::new(
 ///     RetryConfig::new()
-///         .max_retries(5)
+///         .max_attempts(5)
 ///         .initial_delay(Duration::from_millis(50))
 ///         .backoff_multiplier(2.0)
+///         .write_retry(WriteRetry::Unapplied)
+///         .retry_budget(RetryBudget::new(0.2, 10)),
 /// );
 /// ```
 pub struct RetryMiddleware {
@@ -222,17 +488,27 @@ impl Middleware for RetryMiddleware {
         next: Next<'a>,
     ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
         Box::pin(async move {
-            // For now, just pass through - actual retry logic would need
-            // to be able to re-execute the query which requires different design
-            // This is a placeholder that shows the structure
-
-            // In a real implementation, we would:
-            // 1. Execute the query
-            // 2. If it fails with a retryable error, wait and retry
-            // 3. Track retry attempts
-            // 4. Eventually return success or final failure
-
-            next.run(ctx).await
+            let config = &self.config;
+            if let Some(budget) = &config.budget {
+                budget.deposit();
+            }
+
+            let mut attempt = 0;
+            loop {
+                let error = match next.clone().run(ctx.clone()).await {
+                    Ok(response) => return Ok(response),
+                    Err(error) => error,
+                };
+
+                if attempt >= config.max_retries
+                    || !config.should_retry(&error, &ctx)
+                    || !config.budget.as_ref().is_none_or(RetryBudget::try_withdraw)
+                {
+                    return Err(error);
+                }
+                tokio::time::sleep(config.delay_for_attempt(attempt)).await;
+                attempt += 1;
+            }
         })
     }
 
@@ -244,6 +520,7 @@ impl Middleware for RetryMiddleware {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::middleware::QueryMetadata;
 
     #[test]
     fn test_retry_config_default() {
@@ -306,4 +583,105 @@ mod tests {
         assert!(predicate.should_retry(&QueryError::sql_syntax("error", "SELECT")));
         assert!(!predicate.should_retry(&QueryError::timeout(1000)));
     }
+
+    #[test]
+    fn test_error_classes() {
+        let config = RetryConfig::new();
+        let read = QueryContext::new("SELECT * FROM users", Vec::new());
+        let write = QueryContext::new("UPDATE users SET name = $1", Vec::new());
+        let conflict = QueryError::new(ErrorCode::SerializationFailure, "could not serialize");
+
+        assert_eq!(
+            ErrorClass::of(&QueryError::deadlock()),
+            ErrorClass::Conflict
+        );
+        assert!(config.should_retry(&conflict, &read));
+        assert!(config.should_retry(&QueryError::connection("reset"), &read));
+        assert!(!config.should_retry(&QueryError::unique_violation("User", "email"), &read));
+
+        // Conflicts were rolled back, but a dropped connection may have committed
+        assert!(config.should_retry(&conflict, &write));
+        assert!(config.should_retry(&QueryError::pool_exhausted(10), &write));
+        assert!(!config.should_retry(&QueryError::connection("reset"), &write));
+        let tagged = write
+            .clone()
+            .with_metadata(QueryMetadata::new().with_tag(IDEMPOTENT_TAG, "true"));
+        assert!(config.should_retry(&QueryError::connection("reset"), &tagged));
+
+        let never = RetryConfig::new().write_retry(WriteRetry::Never);
+        assert!(!never.should_retry(&conflict, &write));
+
+        // A hook can make errors retryable, but never constraint violations
+        let hooked = RetryConfig::new().classify(|error| {
+            error
+                .message
+                .contains("failover")
+                .then_some(ErrorClass::Transient)
+        });
+        let failover = QueryError::new(ErrorCode::Internal, "failover in progress");
+        assert!(!config.should_retry(&failover, &read));
+        assert!(hooked.should_retry(&failover, &read));
+        let hooked = RetryConfig::new().classify(|_| Some(ErrorClass::Transient));
+        assert!(!hooked.should_retry(&QueryError::unique_violation("User", "email"), &read));
+    }
+
+    #[test]
+    fn test_retry_budget() {
+        let budget = RetryBudget::new(0.5, 1);
+        let shared = budget.clone();
+        assert!(budget.try_withdraw());
+        assert!(!shared.try_withdraw());
+
+        budget.deposit();
+        assert_eq!(budget.available(), 0);
+        shared.deposit();
+        shared.deposit();
+        assert_eq!(budget.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware() {
+        use crate::middleware::MiddlewareChain;
+        use std::sync::atomic::AtomicU32;
+
+        let mut chain = MiddlewareChain::new();
+        chain.push(RetryMiddleware::new(
+            RetryConfig::new()
+                .max_attempts(3)
+                .initial_delay(Duration::from_millis(1))
+                .jitter(false),
+        ));
+
+        // Run a query whose first `failures` attempts fail with `error()`
+        let run = |sql: &'static str, failures: u32, error: fn() -> QueryError| {
+            let calls = Arc::new(AtomicU32::new(0));
+            let counter = calls.clone();
+            let chain = &chain;
+            async move {
+                let result = chain
+                    .execute(QueryContext::new(sql, Vec::new()), move |_| {
+                        let call = counter.fetch_add(1, Ordering::SeqCst);
+                        Box::pin(async move {
+                            if call < failures {
+                                Err(error())
+                            } else {
+                                Ok(QueryResponse::empty())
+                            }
+                        })
+                    })
+                    .await;
+                (result.is_ok(), calls.load(Ordering::SeqCst))
+            }
+        };
+
+        let select = "SELECT * FROM users";
+        let insert = "INSERT INTO users (email) VALUES ($1)";
+        let timeout = || QueryError::timeout(10);
+        assert_eq!(run(select, 2, timeout).await, (true, 3));
+        assert_eq!(run(select, 5, timeout).await, (false, 3));
+        assert_eq!(run(insert, 1, QueryError::deadlock).await, (true, 2));
+        assert_eq!(run(insert, 1, timeout).await, (false, 1));
+        let violation = || QueryError::unique_violation("User", "email");
+        assert_eq!(run(select, 1, violation).await, (false, 1));
+    }
 }
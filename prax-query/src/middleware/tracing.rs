@@ -0,0 +1,270 @@
+//! Tracing middleware emitting a span per query.
+//!
+//! Spans follow the OpenTelemetry database conventions (`db.system`,
+//! `db.statement`, `db.operation`, ...), so a `tracing-opentelemetry` layer
+//! exports them as client spans without further mapping. Each span is a child
+//! of the span current when the query starts, such as an HTTP request span,
+//! and stays entered while the engine runs, so engine events and
+//! [`record_pool_wait`] land on it.
+
+use super::context::{QueryContext, QueryType};
+use super::types::{BoxFuture, Middleware, MiddlewareResult, Next, QueryResponse};
+use crate::sql::DatabaseType;
+use std::time::Duration;
+use tracing::Instrument;
+use tracing::field::Empty;
+
+/// Span field holding the time spent waiting for a pooled connection.
+pub const POOL_WAIT_FIELD: &str = "db.pool.wait_us";
+
+/// Record the time a query waited for a pooled connection on the current span.
+///
+/// Engines call this after acquiring a connection; it does nothing outside a
+/// [`TracingMiddleware`] span.
+pub fn record_pool_wait(wait: Duration) {
+    tracing::Span::current().record(POOL_WAIT_FIELD, wait.as_micros() as u64);
+}
+
+/// Middleware that wraps each query in a tracing span.
+///
+/// The statement is recorded with literals replaced by `?`; bound parameters
+/// are never recorded.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use prax_query::middleware::{MiddlewareStack, TracingMiddleware};
+/// use prax_query::sql::DatabaseType;
+///
+/// let stack = MiddlewareStack::new()
+///     .with(TracingMiddleware::new(DatabaseType::PostgreSQL).with_db_name("shop"));
+/// ```
+pub struct TracingMiddleware {
+    system: &'static str,
+    db_name: Option<String>,
+    max_statement_length: usize,
+}
+
+impl TracingMiddleware {
+    /// Create a tracing middleware for the given database.
+    pub fn new(db_type: DatabaseType) -> Self {
+        let system = match db_type {
+            DatabaseType::PostgreSQL => "postgresql",
+            DatabaseType::MySQL => "mysql",
+            DatabaseType::SQLite => "sqlite",
+            DatabaseType::MSSQL => "mssql",
+        };
+        Self {
+            system,
+            db_name: None,
+            max_statement_length: 2048,
+        }
+    }
+
+    /// Set the database name recorded as `db.name`.
+    pub fn with_db_name(mut self, name: impl Into<String>) -> Self {
+        self.db_name = Some(name.into());
+        self
+    }
+
+    /// Set the maximum length of the recorded statement (0 = unlimited).
+    pub fn with_max_statement_length(mut self, length: usize) -> Self {
+        self.max_statement_length = length;
+        self
+    }
+
+    /// Get the `db.system` value.
+    pub fn system(&self) -> &'static str {
+        self.system
+    }
+
+    fn span(&self, ctx: &QueryContext) -> tracing::Span {
+        let operation = operation_name(ctx.query_type());
+        let metadata = ctx.metadata();
+        let name = match &metadata.model {
+            Some(model) => format!("{} {}", operation, model),
+            None => operation.to_string(),
+        };
+
+        let mut statement = redact_statement(ctx.sql());
+        if self.max_statement_length > 0 && statement.len() > self.max_statement_length {
+            let mut end = self.max_statement_length;
+            while !statement.is_char_boundary(end) {
+                end -= 1;
+            }
+            statement.truncate(end);
+            statement.push_str("...");
+        }
+
+        tracing::info_span!(
+            target: "prax::query",
+            "db.query",
+            otel.name = %name,
+            otel.kind = "client",
+            otel.status_code = Empty,
+            db.system = self.system,
+            db.name = self.db_name.as_deref(),
+            db.operation = operation,
+            db.sql.table = metadata.model.as_deref(),
+            db.statement = %statement,
+            db.rows_affected = Empty,
+            db.pool.wait_us = Empty,
+            error.message = Empty,
+            prax.request_id = metadata.request_id.as_deref(),
+        )
+    }
+}
+
+impl Middleware for TracingMiddleware {
+    fn handle<'a>(
+        &'a self,
+        ctx: QueryContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        let span = self.span(&ctx);
+        Box::pin(async move {
+            let result = next.run(ctx).instrument(span.clone()).await;
+
+            match &result {
+                Ok(response) => {
+                    let rows = response
+                        .rows_affected
+                        .or_else(|| response.data.as_array().map(|rows| rows.len() as u64));
+                    if let Some(rows) = rows {
+                        span.record("db.rows_affected", rows);
+                    }
+                }
+                Err(error) => {
+                    span.record("otel.status_code", "ERROR");
+                    span.record("error.message", tracing::field::display(error));
+                }
+            }
+
+            result
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "TracingMiddleware"
+    }
+}
+
+/// Get the `db.operation` name of a query type.
+fn operation_name(query_type: QueryType) -> &'static str {
+    match query_type {
+        QueryType::Select | QueryType::Count => "SELECT",
+        QueryType::Insert => "INSERT",
+        QueryType::Update => "UPDATE",
+        QueryType::Delete => "DELETE",
+        QueryType::TransactionBegin => "BEGIN",
+        QueryType::TransactionCommit => "COMMIT",
+        QueryType::TransactionRollback => "ROLLBACK",
+        QueryType::Raw | QueryType::Unknown => "QUERY",
+    }
+}
+
+/// Replace string and numeric literals in a statement with `?`.
+///
+/// Quoted identifiers and parameter placeholders (`$1`, `@P1`) are kept.
+pub fn redact_statement(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut prev: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // Skip to the closing quote; '' is an escaped quote
+                while let Some(c) = chars.next() {
+                    if c == '\'' {
+                        if chars.peek() == Some(&'\'') {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                out.push('?');
+            }
+            '"' | '`' => {
+                out.push(c);
+                for inner in chars.by_ref() {
+                    out.push(inner);
+                    if inner == c {
+                        break;
+                    }
+                }
+            }
+            c if c.is_ascii_digit()
+                && !prev.is_some_and(|p| p.is_alphanumeric() || p == '_' || p == '$') =>
+            {
+                while chars
+                    .peek()
+                    .is_some_and(|n| n.is_ascii_alphanumeric() || *n == '.')
+                {
+                    chars.next();
+                }
+                out.push('?');
+            }
+            _ => out.push(c),
+        }
+        prev = out.chars().next_back();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareChain;
+
+    #[test]
+    fn test_redact_statement() {
+        assert_eq!(
+            redact_statement("SELECT * FROM users WHERE email = 'a@b.c' AND age > 30 LIMIT 10"),
+            "SELECT * FROM users WHERE email = ? AND age > ? LIMIT ?"
+        );
+        assert_eq!(
+            redact_statement("UPDATE \"t1\" SET name = 'O''Brien', score = 1.5 WHERE id = $1"),
+            "UPDATE \"t1\" SET name = ?, score = ? WHERE id = $1"
+        );
+        assert_eq!(
+            redact_statement("SELECT col2 FROM [t] WHERE id = @P1"),
+            "SELECT col2 FROM [t] WHERE id = @P1"
+        );
+    }
+
+    #[test]
+    fn test_operation_name() {
+        assert_eq!(operation_name(QueryType::Count), "SELECT");
+        assert_eq!(operation_name(QueryType::Delete), "DELETE");
+        assert_eq!(operation_name(QueryType::Unknown), "QUERY");
+    }
+
+    #[tokio::test]
+    async fn test_tracing_middleware_passes_through() {
+        let mut chain = MiddlewareChain::new();
+        chain.push(TracingMiddleware::new(DatabaseType::PostgreSQL).with_db_name("shop"));
+
+        let ctx = QueryContext::new("UPDATE users SET active = $1", Vec::new());
+        let response = chain
+            .execute(ctx, |_| {
+                Box::pin(async {
+                    record_pool_wait(Duration::from_micros(250));
+                    Ok(QueryResponse::with_affected(3))
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.rows_affected, Some(3));
+
+        let ctx = QueryContext::new("SELECT 1", Vec::new());
+        let result = chain
+            .execute(ctx, |_| {
+                Box::pin(async { Err(crate::QueryError::timeout(1000)) })
+            })
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -16,9 +16,13 @@ pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 /// The next handler in the middleware chain.
 ///
 /// Call this to continue processing to the next middleware or the actual query.
+/// A middleware that needs to run the rest of the chain more than once, such
+/// as [`RetryMiddleware`](super::RetryMiddleware), clones it first.
+#[derive(Clone)]
 pub struct Next<'a> {
-    pub(crate) inner:
-        Box<dyn FnOnce(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + 'a>,
+    pub(crate) inner: Arc<
+        dyn Fn(QueryContext) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> + Send + Sync + 'a,
+    >,
 }
 
 impl<'a> Next<'a> {
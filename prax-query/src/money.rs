@@ -0,0 +1,645 @@
+//! Monetary amounts and their currencies.
+//!
+//! A `Money` field is stored in two columns, an exact amount and an
+//! ISO 4217 currency code:
+//!
+//! ```prax
+//! model Order {
+//!     id    Int   @id @auto
+//!     total Money
+//! }
+//! ```
+//!
+//! | Column | Type |
+//! |--------|------|
+//! | `total_amount` | `DECIMAL(19, 4)` |
+//! | `total_currency` | `CHAR(3)` |
+//!
+//! Amounts in different currencies never mix: arithmetic on [`Money`] fails
+//! on a currency mismatch, a [`MoneyFilter`] compares amounts only within
+//! one currency, a [`MoneyUpdate`] that adds to an amount only touches rows
+//! in the same currency, and [`MoneyTotals`] and [`sum_by_currency`] add up
+//! amounts per currency.
+//!
+//! ```rust
+//! use prax_query::money::{Currency, Money, MoneyFilter, MoneyTotals};
+//! use rust_decimal::Decimal;
+//!
+//! let price = Money::new(Decimal::new(1999, 2), Currency::USD);
+//! let shipping = Money::new(Decimal::new(500, 2), Currency::USD);
+//! assert_eq!(price.checked_add(shipping).unwrap().to_string(), "24.99 USD");
+//! assert!(price.checked_add(Money::new(Decimal::ONE, Currency::EUR)).is_err());
+//!
+//! let (sql, params) = MoneyFilter::gt(price).into_filter("total").to_sql(0);
+//! assert_eq!(sql, "(total_currency = $1 AND total_amount > $2)");
+//! assert_eq!(params[1], "19.99".into());
+//!
+//! let totals: MoneyTotals = [price, shipping, Money::new(Decimal::TEN, Currency::EUR)]
+//!     .into_iter()
+//!     .collect();
+//! assert_eq!(totals.get(Currency::USD).to_string(), "24.99 USD");
+//! assert_eq!(totals.get(Currency::EUR).to_string(), "10 EUR");
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ErrorCode, QueryError};
+use crate::filter::{FieldName, Filter, FilterValue};
+use crate::operations::GroupByOperation;
+use crate::traits::{Model, QueryEngine};
+
+/// Decimal places kept by `Money` columns.
+pub const AMOUNT_SCALE: u32 = 4;
+
+/// Get the amount column of a `Money` field stored in `column`.
+pub fn amount_column(column: &str) -> String {
+    format!("{}_amount", column)
+}
+
+/// Get the currency column of a `Money` field stored in `column`.
+pub fn currency_column(column: &str) -> String {
+    format!("{}_currency", column)
+}
+
+/// An ISO 4217 currency code, such as `USD`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    /// US dollar.
+    pub const USD: Self = Self(*b"USD");
+    /// Euro.
+    pub const EUR: Self = Self(*b"EUR");
+    /// Pound sterling.
+    pub const GBP: Self = Self(*b"GBP");
+    /// Japanese yen.
+    pub const JPY: Self = Self(*b"JPY");
+    /// No currency.
+    pub const XXX: Self = Self(*b"XXX");
+
+    /// Parse a three-letter currency code, in either case.
+    pub fn new(code: &str) -> Result<Self, MoneyError> {
+        match code.as_bytes() {
+            &[a, b, c] if code.bytes().all(|byte| byte.is_ascii_alphabetic()) => Ok(Self([
+                a.to_ascii_uppercase(),
+                b.to_ascii_uppercase(),
+                c.to_ascii_uppercase(),
+            ])),
+            _ => Err(MoneyError::InvalidCurrency(code.to_string())),
+        }
+    }
+
+    /// Get the code.
+    pub fn as_str(&self) -> &str {
+        // Only built from ASCII letters
+        std::str::from_utf8(&self.0).unwrap_or("XXX")
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Currency({})", self.as_str())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = MoneyError;
+
+    fn from_str(code: &str) -> Result<Self, MoneyError> {
+        Self::new(code)
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Self::new(&code).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<Currency> for FilterValue {
+    fn from(currency: Currency) -> Self {
+        FilterValue::String(currency.as_str().to_string())
+    }
+}
+
+/// An invalid currency or amount.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    /// A code that is not three ASCII letters.
+    InvalidCurrency(String),
+    /// Amounts in different currencies were combined.
+    CurrencyMismatch {
+        /// The currency required.
+        expected: Currency,
+        /// The currency given.
+        found: Currency,
+    },
+    /// An amount outgrew the decimal range.
+    Overflow(Currency),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCurrency(code) => {
+                write!(f, "'{}' is not a three-letter ISO 4217 code", code)
+            }
+            Self::CurrencyMismatch { expected, found } => {
+                write!(
+                    f,
+                    "currency mismatch: expected {}, found {}",
+                    expected, found
+                )
+            }
+            Self::Overflow(currency) => write!(f, "{} amount overflowed", currency),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+impl From<MoneyError> for QueryError {
+    fn from(err: MoneyError) -> Self {
+        let field = match err {
+            MoneyError::InvalidCurrency(_) | MoneyError::CurrencyMismatch { .. } => "currency",
+            MoneyError::Overflow(_) => "amount",
+        };
+        QueryError::new(ErrorCode::InvalidParameter, err.to_string()).with_field(field)
+    }
+}
+
+/// An amount in a currency.
+///
+/// Serializes as `{"amount": "19.99", "currency": "USD"}`, keeping every
+/// digit of the amount. The default is zero in `XXX`, the code for no
+/// currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    /// The amount.
+    pub amount: Decimal,
+    /// The currency of the amount.
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Create an amount in a currency.
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    /// Create a zero amount in a currency.
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    /// Check if the amount is zero.
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    /// Check if the amount is below zero.
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_sign_negative() && !self.amount.is_zero()
+    }
+
+    /// Fail unless the amount is in `currency`.
+    pub fn ensure_currency(&self, currency: Currency) -> Result<(), MoneyError> {
+        if self.currency == currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch {
+                expected: currency,
+                found: self.currency,
+            })
+        }
+    }
+
+    /// Add an amount in the same currency.
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        other.ensure_currency(self.currency)?;
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow(self.currency))
+    }
+
+    /// Subtract an amount in the same currency.
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        other.ensure_currency(self.currency)?;
+        self.amount
+            .checked_sub(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow(self.currency))
+    }
+
+    /// Multiply the amount by a factor, such as a tax rate.
+    pub fn checked_mul(self, factor: Decimal) -> Result<Money, MoneyError> {
+        self.amount
+            .checked_mul(factor)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow(self.currency))
+    }
+
+    /// Round the amount to `decimal_places`, rounding halves to even.
+    pub fn round(self, decimal_places: u32) -> Money {
+        let amount = self
+            .amount
+            .round_dp_with_strategy(decimal_places, RoundingStrategy::MidpointNearestEven);
+        Self::new(amount, self.currency)
+    }
+}
+
+impl Default for Money {
+    fn default() -> Self {
+        Self::zero(Currency::XXX)
+    }
+}
+
+/// Formats as the amount followed by the currency, such as `19.99 USD`.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// Binds as exact decimal text, which databases cast to `DECIMAL`.
+fn amount_value(amount: Decimal) -> FilterValue {
+    FilterValue::String(amount.to_string())
+}
+
+/// A filter on a `Money` field.
+///
+/// Comparisons only match amounts in the currency of the operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoneyFilter {
+    /// The same amount in the same currency.
+    Equals(Money),
+    /// A different amount or currency.
+    Not(Money),
+    /// A greater amount in the same currency.
+    Gt(Money),
+    /// A greater or equal amount in the same currency.
+    Gte(Money),
+    /// A smaller amount in the same currency.
+    Lt(Money),
+    /// A smaller or equal amount in the same currency.
+    Lte(Money),
+    /// Any amount in a currency.
+    Currency(Currency),
+}
+
+impl MoneyFilter {
+    /// Match the same amount in the same currency.
+    pub fn equals(money: Money) -> Self {
+        Self::Equals(money)
+    }
+
+    /// Match a different amount or currency.
+    pub fn not(money: Money) -> Self {
+        Self::Not(money)
+    }
+
+    /// Match greater amounts in the same currency.
+    pub fn gt(money: Money) -> Self {
+        Self::Gt(money)
+    }
+
+    /// Match greater or equal amounts in the same currency.
+    pub fn gte(money: Money) -> Self {
+        Self::Gte(money)
+    }
+
+    /// Match smaller amounts in the same currency.
+    pub fn lt(money: Money) -> Self {
+        Self::Lt(money)
+    }
+
+    /// Match smaller or equal amounts in the same currency.
+    pub fn lte(money: Money) -> Self {
+        Self::Lte(money)
+    }
+
+    /// Match any amount in `currency`.
+    pub fn currency(currency: Currency) -> Self {
+        Self::Currency(currency)
+    }
+
+    /// Apply the filter to the `Money` field stored in `column`.
+    pub fn into_filter(self, column: &str) -> Filter {
+        let amount = amount_column(column);
+        let currency = currency_column(column);
+        let in_currency = |money: Money, compare: fn(FieldName, FilterValue) -> Filter| {
+            Filter::and2(
+                Filter::Equals(currency.clone().into(), money.currency.into()),
+                compare(amount.clone().into(), amount_value(money.amount)),
+            )
+        };
+
+        match self {
+            Self::Equals(money) => in_currency(money, Filter::Equals),
+            Self::Not(money) => Filter::not(in_currency(money, Filter::Equals)),
+            Self::Gt(money) => in_currency(money, Filter::Gt),
+            Self::Gte(money) => in_currency(money, Filter::Gte),
+            Self::Lt(money) => in_currency(money, Filter::Lt),
+            Self::Lte(money) => in_currency(money, Filter::Lte),
+            Self::Currency(code) => Filter::Equals(currency.into(), code.into()),
+        }
+    }
+}
+
+/// An update to a `Money` field.
+///
+/// `increment` and `decrement` change the amount in SQL, so concurrent
+/// updates do not overwrite each other, and only apply to rows in their
+/// currency: add [`guard`](Self::guard) to the update's filter.
+///
+/// ```rust
+/// use prax_query::money::{Currency, Money, MoneyUpdate};
+/// use rust_decimal::Decimal;
+///
+/// let refund = MoneyUpdate::decrement(Money::new(Decimal::new(250, 2), Currency::EUR));
+/// let (set, params) = refund.to_sql("balance", 0);
+/// assert_eq!(set, "balance_amount = balance_amount - $1");
+/// assert_eq!(params, vec!["2.50".into()]);
+///
+/// let (guard, _) = refund.guard("balance").to_sql(1);
+/// assert_eq!(guard, "balance_currency = $2");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoneyUpdate {
+    /// Replace the amount and currency.
+    Set(Money),
+    /// Add to the amount.
+    Increment(Money),
+    /// Subtract from the amount.
+    Decrement(Money),
+    /// Multiply the amount, rounding to the column's scale.
+    Multiply(Decimal),
+}
+
+impl MoneyUpdate {
+    /// Replace the amount and currency.
+    pub fn set(money: Money) -> Self {
+        Self::Set(money)
+    }
+
+    /// Add to the amount of rows in the same currency.
+    pub fn increment(money: Money) -> Self {
+        Self::Increment(money)
+    }
+
+    /// Subtract from the amount of rows in the same currency.
+    pub fn decrement(money: Money) -> Self {
+        Self::Decrement(money)
+    }
+
+    /// Multiply the amount, keeping its currency.
+    pub fn multiply(factor: Decimal) -> Self {
+        Self::Multiply(factor)
+    }
+
+    /// Generate the assignments of a `SET` clause for the `Money` field
+    /// stored in `column`, numbering parameters after `param_idx`.
+    pub fn to_sql(&self, column: &str, param_idx: usize) -> (String, Vec<FilterValue>) {
+        let amount = amount_column(column);
+        match self {
+            Self::Set(money) => (
+                format!(
+                    "{} = ${}, {} = ${}",
+                    amount,
+                    param_idx + 1,
+                    currency_column(column),
+                    param_idx + 2
+                ),
+                vec![amount_value(money.amount), money.currency.into()],
+            ),
+            Self::Increment(money) => (
+                format!("{} = {} + ${}", amount, amount, param_idx + 1),
+                vec![amount_value(money.amount)],
+            ),
+            Self::Decrement(money) => (
+                format!("{} = {} - ${}", amount, amount, param_idx + 1),
+                vec![amount_value(money.amount)],
+            ),
+            Self::Multiply(factor) => (
+                format!(
+                    "{} = ROUND({} * ${}, {})",
+                    amount,
+                    amount,
+                    param_idx + 1,
+                    AMOUNT_SCALE
+                ),
+                vec![amount_value(*factor)],
+            ),
+        }
+    }
+
+    /// Get the condition rows must meet for the update to be safe: the
+    /// currency of an increment or decrement. Other updates need none.
+    pub fn guard(&self, column: &str) -> Filter {
+        match self {
+            Self::Increment(money) | Self::Decrement(money) => {
+                MoneyFilter::currency(money.currency).into_filter(column)
+            }
+            Self::Set(_) | Self::Multiply(_) => Filter::None,
+        }
+    }
+}
+
+/// Group a model's rows by the currency of the `Money` field stored in
+/// `column`, summing its amount in each currency.
+pub fn sum_by_currency<M: Model, E: QueryEngine>(column: &str) -> GroupByOperation<M, E> {
+    GroupByOperation::new(vec![currency_column(column)]).sum(amount_column(column))
+}
+
+/// Totals of amounts per currency.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoneyTotals {
+    totals: BTreeMap<Currency, Decimal>,
+}
+
+impl MoneyTotals {
+    /// Create empty totals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an amount to the total of its currency.
+    pub fn add(&mut self, money: Money) -> Result<(), MoneyError> {
+        let total = self.get(money.currency).checked_add(money)?;
+        self.totals.insert(money.currency, total.amount);
+        Ok(())
+    }
+
+    /// Get the total in a currency, zero if it has no amounts.
+    pub fn get(&self, currency: Currency) -> Money {
+        let amount = self.totals.get(&currency).copied().unwrap_or_default();
+        Money::new(amount, currency)
+    }
+
+    /// Iterate over the totals, ordered by currency code.
+    pub fn iter(&self) -> impl Iterator<Item = Money> + '_ {
+        self.totals
+            .iter()
+            .map(|(currency, amount)| Money::new(*amount, *currency))
+    }
+
+    /// Get the number of currencies.
+    pub fn len(&self) -> usize {
+        self.totals.len()
+    }
+
+    /// Check if there are no amounts.
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+}
+
+/// Collects amounts into their totals.
+///
+/// # Panics
+///
+/// Panics if a total overflows; use [`MoneyTotals::add`] to handle it.
+impl FromIterator<Money> for MoneyTotals {
+    fn from_iter<I: IntoIterator<Item = Money>>(iter: I) -> Self {
+        let mut totals = Self::new();
+        for money in iter {
+            totals.add(money).expect("money total overflowed");
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(cents: i64) -> Money {
+        Money::new(Decimal::new(cents, 2), Currency::USD)
+    }
+
+    #[test]
+    fn test_currency() {
+        assert_eq!(Currency::new("eur").unwrap(), Currency::EUR);
+        assert_eq!("GBP".parse::<Currency>().unwrap().as_str(), "GBP");
+        assert!(Currency::new("US").is_err());
+        assert!(Currency::new("U$D").is_err());
+        assert!(Currency::new("ÉUR").is_err());
+
+        let json = serde_json::to_value(usd(1999)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"amount": "19.99", "currency": "USD"})
+        );
+        assert_eq!(serde_json::from_value::<Money>(json).unwrap(), usd(1999));
+        assert!(serde_json::from_value::<Currency>(serde_json::json!("dollars")).is_err());
+    }
+
+    #[test]
+    fn test_money_arithmetic() {
+        assert_eq!(usd(1000).checked_sub(usd(1250)).unwrap(), usd(-250));
+        assert!(usd(-250).is_negative());
+        assert!(!Money::default().is_negative());
+
+        let err = usd(100)
+            .checked_add(Money::new(Decimal::ONE, Currency::EUR))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MoneyError::CurrencyMismatch {
+                expected: Currency::USD,
+                found: Currency::EUR,
+            }
+        );
+        assert!(
+            QueryError::from(err)
+                .to_string()
+                .ends_with("currency mismatch: expected USD, found EUR")
+        );
+        let max = Money::new(Decimal::MAX, Currency::USD);
+        assert_eq!(
+            max.checked_add(max),
+            Err(MoneyError::Overflow(Currency::USD))
+        );
+
+        let taxed = usd(1999).checked_mul(Decimal::new(1075, 3)).unwrap();
+        assert_eq!(taxed.amount, Decimal::new(21489250, 6));
+        assert_eq!(taxed.round(2), usd(2149));
+        assert_eq!(
+            usd(25).round(1),
+            Money::new(Decimal::new(2, 1), Currency::USD)
+        );
+    }
+
+    #[test]
+    fn test_money_filters() {
+        let (sql, params) = MoneyFilter::equals(usd(500)).into_filter("price").to_sql(0);
+        assert_eq!(sql, "(price_currency = $1 AND price_amount = $2)");
+        assert_eq!(
+            params,
+            vec![FilterValue::from("USD"), FilterValue::from("5.00")]
+        );
+
+        let (sql, _) = MoneyFilter::not(usd(500)).into_filter("price").to_sql(0);
+        assert_eq!(sql, "NOT ((price_currency = $1 AND price_amount = $2))");
+
+        let (sql, params) = MoneyFilter::currency(Currency::JPY)
+            .into_filter("price")
+            .to_sql(0);
+        assert_eq!(sql, "price_currency = $1");
+        assert_eq!(params, vec![FilterValue::from("JPY")]);
+    }
+
+    #[test]
+    fn test_money_updates() {
+        let (sql, params) = MoneyUpdate::set(usd(1)).to_sql("balance", 2);
+        assert_eq!(sql, "balance_amount = $3, balance_currency = $4");
+        assert_eq!(
+            params,
+            vec![FilterValue::from("0.01"), FilterValue::from("USD")]
+        );
+        assert!(MoneyUpdate::set(usd(1)).guard("balance").is_none());
+
+        let (sql, _) = MoneyUpdate::increment(usd(100)).to_sql("balance", 0);
+        assert_eq!(sql, "balance_amount = balance_amount + $1");
+
+        let (sql, params) = MoneyUpdate::multiply(Decimal::new(11, 1)).to_sql("balance", 0);
+        assert_eq!(sql, "balance_amount = ROUND(balance_amount * $1, 4)");
+        assert_eq!(params, vec![FilterValue::from("1.1")]);
+    }
+
+    #[test]
+    fn test_money_totals() {
+        let mut totals = MoneyTotals::new();
+        assert!(totals.is_empty());
+        totals.add(usd(150)).unwrap();
+        totals.add(usd(-50)).unwrap();
+        totals
+            .add(Money::new(Decimal::from(300), Currency::JPY))
+            .unwrap();
+
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals.get(Currency::USD), usd(100));
+        assert_eq!(totals.get(Currency::EUR), Money::zero(Currency::EUR));
+        let currencies: Vec<_> = totals.iter().map(|m| m.currency).collect();
+        assert_eq!(currencies, vec![Currency::JPY, Currency::USD]);
+    }
+}
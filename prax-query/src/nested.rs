@@ -55,9 +55,10 @@
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
+use crate::error::QueryResult;
 use crate::filter::{Filter, FilterValue};
 use crate::sql::quote_identifier;
-use crate::traits::Model;
+use crate::traits::{Model, QueryEngine};
 
 /// Represents a nested write operation for relations.
 #[derive(Debug, Clone)]
@@ -129,6 +130,26 @@ impl<T: Model> NestedWrite<T> {
     pub fn delete_many(filter: impl Into<Filter>) -> Self {
         Self::DeleteMany(filter.into())
     }
+
+    /// Connect an existing record, or create it if none matches the filter.
+    pub fn connect_or_create(filter: impl Into<Filter>, create: NestedCreateData<T>) -> Self {
+        Self::CreateOrConnect(vec![NestedCreateOrConnectData::new(filter, create)])
+    }
+
+    /// Update related records.
+    pub fn update(updates: Vec<NestedUpdateData<T>>) -> Self {
+        Self::Update(updates)
+    }
+
+    /// Update a related record, or create it if none matches the filter.
+    pub fn upsert(data: NestedUpsertData<T>) -> Self {
+        Self::Upsert(vec![data])
+    }
+
+    /// Update all related records matching a filter.
+    pub fn update_many(filter: impl Into<Filter>, data: Vec<(String, FilterValue)>) -> Self {
+        Self::UpdateMany(NestedUpdateManyData::new(filter, data))
+    }
 }
 
 /// Data for creating a nested record.
@@ -529,6 +550,217 @@ impl NestedWriteBuilder {
 
         statements
     }
+
+    /// Build SQL for connecting records, creating those that don't exist.
+    pub fn build_connect_or_create_sql<T: Model>(
+        &self,
+        parent_id: &FilterValue,
+        items: &[NestedCreateOrConnectData<T>],
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        let mut statements = Vec::new();
+
+        for item in items {
+            statements.push(self.insert_if_absent_sql(
+                parent_id,
+                &item.create.data,
+                &item.filter,
+                false,
+            ));
+            statements
+                .extend(self.build_connect_sql::<T>(parent_id, std::slice::from_ref(&item.filter)));
+        }
+
+        statements
+    }
+
+    /// Build SQL for updating related records.
+    pub fn build_update_sql<T: Model>(
+        &self,
+        parent_id: &FilterValue,
+        updates: &[NestedUpdateData<T>],
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        updates
+            .iter()
+            .filter(|update| !update.data.is_empty())
+            .map(|update| self.scoped_update_sql::<T>(parent_id, &update.filter, &update.data))
+            .collect()
+    }
+
+    /// Build SQL for updating all related records matching a filter.
+    pub fn build_update_many_sql<T: Model>(
+        &self,
+        parent_id: &FilterValue,
+        update: &NestedUpdateManyData<T>,
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        if update.data.is_empty() {
+            return Vec::new();
+        }
+        vec![self.scoped_update_sql::<T>(parent_id, &update.filter, &update.data)]
+    }
+
+    /// Build SQL for upserting related records.
+    ///
+    /// The update only touches records already related to the parent; the
+    /// insert runs when no related record matches the filter.
+    pub fn build_upsert_sql<T: Model>(
+        &self,
+        parent_id: &FilterValue,
+        upserts: &[NestedUpsertData<T>],
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        let mut statements = Vec::new();
+
+        for upsert in upserts {
+            if !upsert.update.is_empty() {
+                statements.push(self.scoped_update_sql::<T>(
+                    parent_id,
+                    &upsert.filter,
+                    &upsert.update,
+                ));
+            }
+
+            statements.push(self.insert_if_absent_sql(
+                parent_id,
+                &upsert.create.data,
+                &upsert.filter,
+                self.is_one_to_many,
+            ));
+            if !self.is_one_to_many {
+                statements.extend(
+                    self.build_connect_sql::<T>(parent_id, std::slice::from_ref(&upsert.filter)),
+                );
+            }
+        }
+
+        statements
+    }
+
+    /// Build SQL for deleting all related records matching a filter.
+    pub fn build_delete_many_sql<T: Model>(
+        &self,
+        parent_id: &FilterValue,
+        filter: &Filter,
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        let (where_sql, mut params) = filter.to_sql(0);
+        let sql = format!(
+            "DELETE FROM {} WHERE ({}) AND {}",
+            quote_identifier(&self.related_table),
+            where_sql,
+            self.scope_sql::<T>(params.len() + 1)
+        );
+        params.push(parent_id.clone());
+        vec![(sql, params)]
+    }
+
+    /// Build the SQL for any nested write.
+    pub fn build_sql<T: Model>(
+        &self,
+        parent_id: &FilterValue,
+        write: &NestedWrite<T>,
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        match write {
+            NestedWrite::Create(creates) => self.build_create_sql(parent_id, creates),
+            NestedWrite::CreateOrConnect(items) => {
+                self.build_connect_or_create_sql(parent_id, items)
+            }
+            NestedWrite::Connect(filters) => self.build_connect_sql::<T>(parent_id, filters),
+            NestedWrite::Disconnect(filters) => self.build_disconnect_sql(parent_id, filters),
+            NestedWrite::Set(filters) => self.build_set_sql::<T>(parent_id, filters),
+            NestedWrite::Delete(filters) => self.build_delete_sql(parent_id, filters),
+            NestedWrite::Update(updates) => self.build_update_sql(parent_id, updates),
+            NestedWrite::Upsert(upserts) => self.build_upsert_sql(parent_id, upserts),
+            NestedWrite::UpdateMany(update) => self.build_update_many_sql(parent_id, update),
+            NestedWrite::DeleteMany(filter) => self.build_delete_many_sql::<T>(parent_id, filter),
+        }
+    }
+
+    /// Condition restricting the related table to records of the parent.
+    fn scope_sql<T: Model>(&self, param_idx: usize) -> String {
+        match (&self.join_table, self.is_one_to_many) {
+            (Some(join), false) => format!(
+                "{} IN (SELECT {} FROM {} WHERE {} = ${})",
+                quote_identifier(T::PRIMARY_KEY.first().unwrap_or(&"id")),
+                quote_identifier(&join.related_column),
+                quote_identifier(&join.table_name),
+                quote_identifier(&join.parent_column),
+                param_idx
+            ),
+            _ => format!("{} = ${}", quote_identifier(&self.foreign_key), param_idx),
+        }
+    }
+
+    /// UPDATE related records matching a filter, restricted to the parent.
+    fn scoped_update_sql<T: Model>(
+        &self,
+        parent_id: &FilterValue,
+        filter: &Filter,
+        data: &[(String, FilterValue)],
+    ) -> (String, Vec<FilterValue>) {
+        let assignments: Vec<String> = data
+            .iter()
+            .enumerate()
+            .map(|(i, (column, _))| format!("{} = ${}", quote_identifier(column), i + 1))
+            .collect();
+        let mut params: Vec<FilterValue> = data.iter().map(|(_, v)| v.clone()).collect();
+
+        let (where_sql, filter_params) = filter.to_sql(params.len());
+        params.extend(filter_params);
+
+        let sql = format!(
+            "UPDATE {} SET {} WHERE ({}) AND {}",
+            quote_identifier(&self.related_table),
+            assignments.join(", "),
+            where_sql,
+            self.scope_sql::<T>(params.len() + 1)
+        );
+        params.push(parent_id.clone());
+        (sql, params)
+    }
+
+    /// INSERT a related record unless one matches the filter.
+    ///
+    /// For one-to-many relations the record is linked to the parent through
+    /// the foreign key, and `parent_only` limits the existence check to the
+    /// parent's records.
+    fn insert_if_absent_sql(
+        &self,
+        parent_id: &FilterValue,
+        data: &[(String, FilterValue)],
+        filter: &Filter,
+        parent_only: bool,
+    ) -> (String, Vec<FilterValue>) {
+        let mut columns: Vec<String> = data.iter().map(|(k, _)| quote_identifier(k)).collect();
+        let mut params: Vec<FilterValue> = data.iter().map(|(_, v)| v.clone()).collect();
+
+        if self.is_one_to_many {
+            columns.push(quote_identifier(&self.foreign_key));
+            params.push(parent_id.clone());
+        }
+
+        let placeholders: Vec<String> = (1..=params.len()).map(|i| format!("${}", i)).collect();
+        let (mut where_sql, filter_params) = filter.to_sql(params.len());
+        params.extend(filter_params);
+
+        if parent_only && self.is_one_to_many {
+            where_sql = format!(
+                "({}) AND {} = ${}",
+                where_sql,
+                quote_identifier(&self.foreign_key),
+                params.len() + 1
+            );
+            params.push(parent_id.clone());
+        }
+
+        let related = quote_identifier(&self.related_table);
+        let sql = format!(
+            "INSERT INTO {} ({}) SELECT {} WHERE NOT EXISTS (SELECT 1 FROM {} WHERE {})",
+            related,
+            columns.join(", "),
+            placeholders.join(", "),
+            related,
+            where_sql
+        );
+        (sql, params)
+    }
 }
 
 /// A container for collecting all nested write operations to execute.
@@ -571,6 +803,33 @@ impl NestedWriteOperations {
     pub fn len(&self) -> usize {
         self.pre_statements.len() + self.post_statements.len()
     }
+
+    /// Add the statements of a nested write, run after the parent is written.
+    pub fn add_nested<T: Model>(
+        &mut self,
+        builder: &NestedWriteBuilder,
+        parent_id: &FilterValue,
+        write: &NestedWrite<T>,
+    ) {
+        self.post_statements
+            .extend(builder.build_sql(parent_id, write));
+    }
+
+    /// Execute the statements in order, pre-operation statements first.
+    ///
+    /// Returns the total number of affected rows. Run this inside a transaction
+    /// so that a failing statement rolls back the whole write.
+    pub async fn execute<E: QueryEngine>(&self, engine: &E) -> QueryResult<u64> {
+        let mut affected = 0;
+        let statements = self.pre_statements.iter().chain(&self.post_statements);
+        for (index, (sql, params)) in statements.enumerate() {
+            affected += engine
+                .execute_raw(sql, params.clone())
+                .await
+                .map_err(|e| e.with_context(format!("nested write statement {}", index + 1)))?;
+        }
+        Ok(affected)
+    }
 }
 
 #[cfg(test)]
@@ -810,4 +1069,131 @@ mod tests {
         assert_eq!(upsert.create.data.len(), 1);
         assert_eq!(upsert.update.len(), 1);
     }
+
+    fn one_to_many() -> NestedWriteBuilder {
+        NestedWriteBuilder::one_to_many("users", vec!["id".to_string()], "posts", "user_id")
+    }
+
+    fn many_to_many() -> NestedWriteBuilder {
+        NestedWriteBuilder::many_to_many(
+            "posts",
+            vec!["id".to_string()],
+            "tags",
+            JoinTableInfo {
+                table_name: "post_tags".to_string(),
+                parent_column: "post_id".to_string(),
+                related_column: "tag_id".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_builder_connect_or_create() {
+        let write: NestedWrite<TestModel> = NestedWrite::connect_or_create(
+            Filter::Equals("title".into(), FilterValue::String("Hello".to_string())),
+            NestedCreateData::from_pairs([("title", FilterValue::String("Hello".to_string()))]),
+        );
+
+        let statements = one_to_many().build_sql(&FilterValue::Int(1), &write);
+        assert_eq!(statements.len(), 2);
+
+        let (insert_sql, insert_params) = &statements[0];
+        assert_eq!(
+            insert_sql,
+            "INSERT INTO posts (title, user_id) SELECT $1, $2 \
+             WHERE NOT EXISTS (SELECT 1 FROM posts WHERE title = $3)"
+        );
+        assert_eq!(insert_params.len(), 3);
+        assert!(statements[1].0.starts_with("UPDATE posts SET user_id"));
+    }
+
+    #[test]
+    fn test_builder_update_scoped_to_parent() {
+        let write: NestedWrite<TestModel> =
+            NestedWrite::update(vec![NestedUpdateData::from_pairs(
+                Filter::Equals("id".into(), FilterValue::Int(10)),
+                [("title", FilterValue::String("Updated".to_string()))],
+            )]);
+
+        let statements = one_to_many().build_sql(&FilterValue::Int(1), &write);
+        assert_eq!(statements.len(), 1);
+        let (sql, params) = &statements[0];
+        assert_eq!(
+            sql,
+            "UPDATE posts SET title = $1 WHERE (id = $2) AND user_id = $3"
+        );
+        assert_eq!(
+            params,
+            &vec![
+                FilterValue::String("Updated".to_string()),
+                FilterValue::Int(10),
+                FilterValue::Int(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builder_update_many_many_to_many() {
+        let write: NestedWrite<TagModel> = NestedWrite::update_many(
+            Filter::Equals("name".into(), FilterValue::String("old".to_string())),
+            vec![("name".to_string(), FilterValue::String("new".to_string()))],
+        );
+
+        let statements = many_to_many().build_sql(&FilterValue::Int(5), &write);
+        assert_eq!(statements.len(), 1);
+        assert_eq!(
+            statements[0].0,
+            "UPDATE tags SET name = $1 WHERE (name = $2) AND id IN \
+             (SELECT tag_id FROM post_tags WHERE post_id = $3)"
+        );
+    }
+
+    #[test]
+    fn test_builder_upsert() {
+        let write: NestedWrite<TestModel> = NestedWrite::upsert(NestedUpsertData::new(
+            Filter::Equals("id".into(), FilterValue::Int(10)),
+            NestedCreateData::from_pairs([("title", FilterValue::String("New".to_string()))]),
+            vec![(
+                "title".to_string(),
+                FilterValue::String("Updated".to_string()),
+            )],
+        ));
+
+        let statements = one_to_many().build_sql(&FilterValue::Int(1), &write);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].0.starts_with("UPDATE posts SET title = $1"));
+
+        // The existence check is scoped to the parent's records
+        let (insert_sql, insert_params) = &statements[1];
+        assert!(insert_sql.starts_with("INSERT INTO posts (title, user_id) SELECT $1, $2"));
+        assert!(insert_sql.contains("id = $3"));
+        assert!(insert_sql.contains("user_id = $4"));
+        assert_eq!(insert_params.len(), 4);
+    }
+
+    #[test]
+    fn test_builder_delete_many() {
+        let write: NestedWrite<TestModel> =
+            NestedWrite::delete_many(Filter::Equals("title".into(), FilterValue::Null));
+
+        let statements = one_to_many().build_sql(&FilterValue::Int(1), &write);
+        assert_eq!(
+            statements,
+            vec![(
+                "DELETE FROM posts WHERE (title IS NULL) AND user_id = $1".to_string(),
+                vec![FilterValue::Int(1)],
+            )]
+        );
+    }
+
+    #[test]
+    fn test_nested_write_operations_add_nested() {
+        let mut ops = NestedWriteOperations::new();
+        let write: NestedWrite<TestModel> =
+            NestedWrite::connect(vec![Filter::Equals("id".into(), FilterValue::Int(10))]);
+
+        ops.add_nested(&one_to_many(), &FilterValue::Int(1), &write);
+        assert!(ops.pre_statements.is_empty());
+        assert_eq!(ops.post_statements.len(), 1);
+    }
 }
@@ -0,0 +1,379 @@
+//! IP address columns and their filters.
+//!
+//! `Inet` fields hold a host address with an optional netmask, `Cidr` fields
+//! a network, and `MacAddr` fields a MAC address:
+//!
+//! ```prax
+//! model Device {
+//!     id     Int     @id @auto
+//!     ip     Inet
+//!     subnet Cidr
+//!     mac    MacAddr
+//! }
+//! ```
+//!
+//! PostgreSQL stores them in native `inet`, `cidr` and `macaddr` columns.
+//! Other databases store them as text. MAC addresses are kept in their
+//! usual `08:00:2b:01:02:03` form, while IP addresses use the fixed-width
+//! form of [`IpValue::emulated_text`]: the address as zero-padded hex
+//! (8 digits for IPv4, 32 for IPv6) followed by the prefix length, such as
+//! `0a000000/8` for `10.0.0.0/8`. Addresses of one family then sort as
+//! text in address order, so containment becomes a text range comparison.
+//!
+//! A [`NetworkFilter`] renders each operator for either storage:
+//!
+//! | Operator | Native | Text |
+//! |----------|--------|------|
+//! | [`contained_in`](NetworkFilter::contained_in) | `ip <<= $1::text::inet` | `ip >= $1 AND ip < $2` |
+//! | [`contains`](NetworkFilter::contains) | `subnet >>= $1::text::inet` | `subnet IN ($1, ...)` |
+//! | [`same_family`](NetworkFilter::same_family) | `family(ip) = 4` | `ip LIKE '________/%'` |
+//!
+//! Text storage compares addresses only, so it assumes network columns hold
+//! networks without host bits, as `cidr` requires.
+//!
+//! Values are built from the standard library's address types, and from
+//! `ipnetwork::IpNetwork` with the `ipnetwork` feature.
+//!
+//! ```rust
+//! use std::net::Ipv4Addr;
+//! use prax_query::network::{IpValue, NetworkFilter};
+//!
+//! let subnet = IpValue::new(Ipv4Addr::new(10, 0, 0, 0).into(), 8).unwrap();
+//! assert_eq!(subnet.to_string(), "10.0.0.0/8");
+//! assert_eq!(subnet.emulated_text(), "0a000000/8");
+//!
+//! let (sql, params) = NetworkFilter::contained_in(subnet).into_filter("ip").to_sql(0);
+//! assert_eq!(sql, "ip <<= $1::text::inet");
+//! assert_eq!(params, vec!["10.0.0.0/8".into()]);
+//!
+//! let (sql, params) = NetworkFilter::contained_in(subnet).emulated().into_filter("ip").to_sql(0);
+//! assert_eq!(sql, "(ip LIKE '________/%' AND ip >= $1 AND ip < $2)");
+//! assert_eq!(params, vec!["0a000000/".into(), "0affffff0".into()]);
+//! ```
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::filter::{FieldName, Filter, FilterValue};
+
+/// An IP address with a prefix length, as stored in `inet` and `cidr`
+/// columns. A plain address has the full prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpValue {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpValue {
+    /// Create a value, or `None` if `prefix` is too long for the family.
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<Self> {
+        let value = Self { addr, prefix };
+        (prefix <= value.width()).then_some(value)
+    }
+
+    /// Get the address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Get the prefix length.
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Check if this is an IPv4 value.
+    pub fn is_ipv4(&self) -> bool {
+        self.addr.is_ipv4()
+    }
+
+    /// Get the network containing this value, with the host bits cleared.
+    pub fn network(&self) -> Self {
+        self.with_prefix(self.prefix)
+    }
+
+    /// Get the enclosing network with a shorter `prefix`.
+    fn with_prefix(&self, prefix: u8) -> Self {
+        Self {
+            addr: self.addr_from_bits(self.addr_bits() & self.mask(prefix)),
+            prefix,
+        }
+    }
+
+    /// Get the text form stored by databases without network types.
+    pub fn emulated_text(&self) -> String {
+        format!("{}/{}", self.hex(self.addr_bits()), self.prefix)
+    }
+
+    /// Get the address width in bits.
+    fn width(&self) -> u8 {
+        if self.is_ipv4() { 32 } else { 128 }
+    }
+
+    /// Get all address bits set.
+    fn all(&self) -> u128 {
+        u128::MAX >> (128 - u32::from(self.width()))
+    }
+
+    /// Get the netmask of `prefix` bits.
+    fn mask(&self, prefix: u8) -> u128 {
+        let shift = u32::from(self.width() - prefix);
+        self.all().checked_shl(shift).unwrap_or(0) & self.all()
+    }
+
+    /// Get the address as an integer.
+    fn addr_bits(&self) -> u128 {
+        match self.addr {
+            IpAddr::V4(addr) => u32::from(addr).into(),
+            IpAddr::V6(addr) => u128::from(addr),
+        }
+    }
+
+    /// Build an address of this family from an integer.
+    fn addr_from_bits(&self, bits: u128) -> IpAddr {
+        match self.addr {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(bits as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(bits)),
+        }
+    }
+
+    /// Format an address as zero-padded hex.
+    fn hex(&self, bits: u128) -> String {
+        format!("{:0width$x}", bits, width = usize::from(self.width() / 4))
+    }
+}
+
+impl fmt::Display for IpValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl From<IpAddr> for IpValue {
+    fn from(addr: IpAddr) -> Self {
+        let prefix = if addr.is_ipv4() { 32 } else { 128 };
+        Self { addr, prefix }
+    }
+}
+
+impl From<Ipv4Addr> for IpValue {
+    fn from(addr: Ipv4Addr) -> Self {
+        IpAddr::V4(addr).into()
+    }
+}
+
+impl From<Ipv6Addr> for IpValue {
+    fn from(addr: Ipv6Addr) -> Self {
+        IpAddr::V6(addr).into()
+    }
+}
+
+#[cfg(feature = "ipnetwork")]
+impl From<ipnetwork::IpNetwork> for IpValue {
+    fn from(network: ipnetwork::IpNetwork) -> Self {
+        Self {
+            addr: network.ip(),
+            prefix: network.prefix(),
+        }
+    }
+}
+
+/// How a network field is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NetworkStorage {
+    /// A native `inet` or `cidr` column (PostgreSQL).
+    #[default]
+    Native,
+    /// A text column holding [`IpValue::emulated_text`].
+    Text,
+}
+
+/// A network operator and its operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkOp {
+    /// The column is within or equal to the network.
+    ContainedIn(IpValue),
+    /// The column contains or equals the address.
+    Contains(IpValue),
+    /// The column has the same address family.
+    SameFamily(IpValue),
+}
+
+/// A filter on an `inet` or `cidr` column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkFilter {
+    /// The operator.
+    pub op: NetworkOp,
+    /// How the column is stored.
+    pub storage: NetworkStorage,
+}
+
+impl NetworkFilter {
+    fn new(op: NetworkOp) -> Self {
+        Self {
+            op,
+            storage: NetworkStorage::Native,
+        }
+    }
+
+    /// Match addresses within `network`.
+    pub fn contained_in(network: impl Into<IpValue>) -> Self {
+        Self::new(NetworkOp::ContainedIn(network.into()))
+    }
+
+    /// Match networks containing `addr`.
+    pub fn contains(addr: impl Into<IpValue>) -> Self {
+        Self::new(NetworkOp::Contains(addr.into()))
+    }
+
+    /// Match addresses of the same family (IPv4 or IPv6) as `addr`.
+    pub fn same_family(addr: impl Into<IpValue>) -> Self {
+        Self::new(NetworkOp::SameFamily(addr.into()))
+    }
+
+    /// Filter a network stored as text.
+    pub fn emulated(mut self) -> Self {
+        self.storage = NetworkStorage::Text;
+        self
+    }
+
+    /// Apply the filter to `column`.
+    pub fn into_filter(self, column: impl Into<FieldName>) -> Filter {
+        Filter::Network(column.into(), Box::new(self))
+    }
+
+    /// Generate the condition on `column`, pushing its parameters.
+    pub(crate) fn to_sql_with_params(
+        &self,
+        column: &str,
+        param_idx: usize,
+        params: &mut Vec<FilterValue>,
+    ) -> String {
+        let mut bind = |value: String| {
+            params.push(FilterValue::String(value));
+            format!("${}", param_idx + params.len())
+        };
+
+        if self.storage == NetworkStorage::Native {
+            // Bound as text, as drivers cannot encode network values
+            return match self.op {
+                NetworkOp::ContainedIn(network) => {
+                    format!("{} <<= {}::text::inet", column, bind(network.to_string()))
+                }
+                NetworkOp::Contains(addr) => {
+                    format!("{} >>= {}::text::inet", column, bind(addr.to_string()))
+                }
+                NetworkOp::SameFamily(addr) => {
+                    let family = if addr.is_ipv4() { 4 } else { 6 };
+                    format!("family({}) = {}", column, family)
+                }
+            };
+        }
+
+        match self.op {
+            NetworkOp::ContainedIn(network) => {
+                // `/` sorts before hex digits, so `[first/, last0)` holds every
+                // address of the network whatever its prefix length
+                let network = network.network();
+                let last = network.addr_bits() | (!network.mask(network.prefix) & network.all());
+                let first = network.hex(network.addr_bits());
+                format!(
+                    "({} AND {} >= {} AND {} < {})",
+                    family_condition(column, &network),
+                    column,
+                    bind(format!("{}/", first)),
+                    column,
+                    bind(format!("{}0", network.hex(last)))
+                )
+            }
+            NetworkOp::Contains(addr) => {
+                let placeholders: Vec<_> = (0..=addr.prefix)
+                    .map(|prefix| bind(addr.with_prefix(prefix).emulated_text()))
+                    .collect();
+                format!("{} IN ({})", column, placeholders.join(", "))
+            }
+            NetworkOp::SameFamily(addr) => family_condition(column, &addr),
+        }
+    }
+}
+
+/// Match text-stored values of the family of `value`, by the position of
+/// the `/` after the hex address.
+fn family_condition(column: &str, value: &IpValue) -> String {
+    let pattern = format!("{}/%", "_".repeat(8));
+    if value.is_ipv4() {
+        format!("{} LIKE '{}'", column, pattern)
+    } else {
+        format!("{} NOT LIKE '{}'", column, pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v4(a: u8, b: u8, c: u8, d: u8, prefix: u8) -> IpValue {
+        IpValue::new(Ipv4Addr::new(a, b, c, d).into(), prefix).unwrap()
+    }
+
+    #[test]
+    fn test_ip_value() {
+        let host = IpValue::from(Ipv4Addr::new(192, 168, 1, 20));
+        assert_eq!(host.prefix(), 32);
+        assert_eq!(host.emulated_text(), "c0a80114/32");
+
+        let subnet = v4(192, 168, 1, 20, 24);
+        assert_eq!(subnet.network().to_string(), "192.168.1.0/24");
+        assert_eq!(v4(10, 1, 2, 3, 0).network().to_string(), "0.0.0.0/0");
+        assert!(IpValue::new(Ipv4Addr::LOCALHOST.into(), 33).is_none());
+
+        let v6 = IpValue::new("2001:db8::".parse().unwrap(), 32).unwrap();
+        assert_eq!(v6.emulated_text(), "20010db8000000000000000000000000/32");
+        assert!(!v6.is_ipv4());
+    }
+
+    #[test]
+    fn test_native_network_filters() {
+        let (sql, params) = NetworkFilter::contains(Ipv4Addr::new(10, 1, 2, 3))
+            .into_filter("subnet")
+            .to_sql(1);
+        assert_eq!(sql, "subnet >>= $2::text::inet");
+        assert_eq!(params, vec![FilterValue::String("10.1.2.3/32".into())]);
+
+        let (sql, params) = NetworkFilter::same_family(Ipv6Addr::LOCALHOST)
+            .into_filter("ip")
+            .to_sql(0);
+        assert_eq!(sql, "family(ip) = 6");
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_emulated_network_filters() {
+        let (sql, params) = NetworkFilter::contained_in(v4(10, 1, 2, 3, 12))
+            .emulated()
+            .into_filter("ip")
+            .to_sql(0);
+        assert_eq!(sql, "(ip LIKE '________/%' AND ip >= $1 AND ip < $2)");
+        assert_eq!(
+            params,
+            vec![
+                FilterValue::String("0a000000/".into()),
+                FilterValue::String("0a0fffff0".into())
+            ]
+        );
+
+        let (sql, params) = NetworkFilter::contains(v4(10, 1, 2, 3, 32))
+            .emulated()
+            .into_filter("subnet")
+            .to_sql(0);
+        assert!(sql.starts_with("subnet IN ($1, $2, "));
+        assert_eq!(params.len(), 33);
+        assert_eq!(params[8], FilterValue::String("0a000000/8".into()));
+        assert_eq!(params[32], FilterValue::String("0a010203/32".into()));
+
+        let (sql, _) = NetworkFilter::same_family(Ipv6Addr::LOCALHOST)
+            .emulated()
+            .into_filter("ip")
+            .to_sql(0);
+        assert_eq!(sql, "ip NOT LIKE '________/%'");
+    }
+}
@@ -43,7 +43,7 @@ use std::marker::PhantomData;
 
 use crate::error::QueryResult;
 use crate::filter::Filter;
-use crate::sql::quote_identifier;
+use crate::sql::{quote_identifier, quote_table_name};
 use crate::traits::{Model, QueryEngine};
 use crate::types::OrderByField;
 
@@ -201,7 +201,7 @@ impl<M: Model, E: QueryEngine> AggregateOperation<M, E> {
         let mut sql = format!(
             "SELECT {} FROM {}",
             select_parts.join(", "),
-            quote_identifier(M::TABLE_NAME)
+            quote_table_name(M::TABLE_NAME)
         );
 
         // Add WHERE clause
@@ -392,7 +392,7 @@ impl<M: Model, E: QueryEngine> GroupByOperation<M, E> {
         let mut sql = format!(
             "SELECT {} FROM {}",
             select_parts.join(", "),
-            quote_identifier(M::TABLE_NAME)
+            quote_table_name(M::TABLE_NAME)
         );
 
         // Add WHERE clause
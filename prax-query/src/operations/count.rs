@@ -2,9 +2,13 @@
 
 use std::marker::PhantomData;
 
+use crate::batch::BatchOp;
 use crate::error::QueryResult;
+use crate::explain::QueryPlan;
 use crate::filter::{Filter, FilterValue};
-use crate::traits::{Model, QueryEngine};
+use crate::tags::QueryTags;
+use crate::traits::{BoxFuture, Model, QueryEngine};
+use crate::ttl::live_rows;
 
 /// A count operation for counting records.
 ///
@@ -22,6 +26,8 @@ pub struct CountOperation<E: QueryEngine, M: Model> {
     engine: E,
     filter: Filter,
     distinct: Option<String>,
+    include_expired: bool,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -32,6 +38,8 @@ impl<E: QueryEngine, M: Model> CountOperation<E, M> {
             engine,
             filter: Filter::None,
             distinct: None,
+            include_expired: false,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -49,9 +57,32 @@ impl<E: QueryEngine, M: Model> CountOperation<E, M> {
         self
     }
 
+    /// Include rows whose `@@ttl` expiry has passed.
+    pub fn include_expired(mut self) -> Self {
+        self.include_expired = true;
+        self
+    }
+
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<FilterValue>) {
-        let (where_sql, params) = self.filter.to_sql(0);
+        let filter = live_rows::<M>(
+            &self.filter,
+            self.include_expired,
+            self.engine.database_type(),
+        );
+        let (where_sql, params) = filter.to_sql(0);
 
         let mut sql = String::new();
 
@@ -71,11 +102,12 @@ impl<E: QueryEngine, M: Model> CountOperation<E, M> {
         sql.push_str(M::TABLE_NAME);
 
         // WHERE clause
-        if !self.filter.is_none() {
+        if !filter.is_none() {
             sql.push_str(" WHERE ");
             sql.push_str(&where_sql);
         }
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -84,6 +116,28 @@ impl<E: QueryEngine, M: Model> CountOperation<E, M> {
         let (sql, params) = self.build_sql();
         self.engine.count(&sql, params).await
     }
+
+    /// Get the database's plan for the query without running it.
+    pub async fn explain(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, false).await
+    }
+
+    /// Run the query and get the database's plan with measured row counts
+    /// and timings.
+    pub async fn explain_analyze(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, true).await
+    }
+}
+
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for CountOperation<E, M> {
+    type Output = u64;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
 }
 
 #[cfg(test)]
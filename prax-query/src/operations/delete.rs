@@ -2,9 +2,11 @@
 
 use std::marker::PhantomData;
 
+use crate::batch::BatchOp;
 use crate::error::QueryResult;
 use crate::filter::{Filter, FilterValue};
-use crate::traits::{Model, QueryEngine};
+use crate::tags::QueryTags;
+use crate::traits::{BoxFuture, Model, QueryEngine};
 use crate::types::Select;
 
 /// A delete operation for removing records.
@@ -23,6 +25,7 @@ pub struct DeleteOperation<E: QueryEngine, M: Model> {
     engine: E,
     filter: Filter,
     select: Select,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -33,6 +36,7 @@ impl<E: QueryEngine, M: Model> DeleteOperation<E, M> {
             engine,
             filter: Filter::None,
             select: Select::All,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -50,6 +54,18 @@ impl<E: QueryEngine, M: Model> DeleteOperation<E, M> {
         self
     }
 
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<FilterValue>) {
         let (where_sql, params) = self.filter.to_sql(0);
@@ -68,8 +84,9 @@ impl<E: QueryEngine, M: Model> DeleteOperation<E, M> {
 
         // RETURNING clause
         sql.push_str(" RETURNING ");
-        sql.push_str(&self.select.to_sql());
+        sql.push_str(&self.select.to_sql_with_virtual(M::VIRTUAL_FIELDS));
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -87,6 +104,7 @@ impl<E: QueryEngine, M: Model> DeleteOperation<E, M> {
             sql.push_str(&where_sql);
         }
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -110,6 +128,7 @@ impl<E: QueryEngine, M: Model> DeleteOperation<E, M> {
 pub struct DeleteManyOperation<E: QueryEngine, M: Model> {
     engine: E,
     filter: Filter,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -119,6 +138,7 @@ impl<E: QueryEngine, M: Model> DeleteManyOperation<E, M> {
         Self {
             engine,
             filter: Filter::None,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -130,6 +150,18 @@ impl<E: QueryEngine, M: Model> DeleteManyOperation<E, M> {
         self
     }
 
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<FilterValue>) {
         let (where_sql, params) = self.filter.to_sql(0);
@@ -144,6 +176,7 @@ impl<E: QueryEngine, M: Model> DeleteManyOperation<E, M> {
             sql.push_str(&where_sql);
         }
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -154,6 +187,24 @@ impl<E: QueryEngine, M: Model> DeleteManyOperation<E, M> {
     }
 }
 
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for DeleteOperation<E, M> {
+    type Output = Vec<M>;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
+}
+
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for DeleteManyOperation<E, M> {
+    type Output = u64;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,9 +2,14 @@
 
 use std::marker::PhantomData;
 
+use crate::batch::BatchOp;
 use crate::error::QueryResult;
+use crate::etag::Tagged;
+use crate::explain::QueryPlan;
 use crate::filter::Filter;
-use crate::traits::{Model, QueryEngine};
+use crate::tags::QueryTags;
+use crate::traits::{BoxFuture, Model, QueryEngine};
+use crate::ttl::live_rows;
 use crate::types::{OrderBy, Select};
 
 /// A query operation that finds the first record matching the filter.
@@ -27,6 +32,8 @@ pub struct FindFirstOperation<E: QueryEngine, M: Model> {
     filter: Filter,
     order_by: OrderBy,
     select: Select,
+    include_expired: bool,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -38,6 +45,8 @@ impl<E: QueryEngine, M: Model> FindFirstOperation<E, M> {
             filter: Filter::None,
             order_by: OrderBy::none(),
             select: Select::All,
+            include_expired: false,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -61,22 +70,45 @@ impl<E: QueryEngine, M: Model> FindFirstOperation<E, M> {
         self
     }
 
+    /// Include rows whose `@@ttl` expiry has passed.
+    pub fn include_expired(mut self) -> Self {
+        self.include_expired = true;
+        self
+    }
+
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<crate::filter::FilterValue>) {
-        let (where_sql, params) = self.filter.to_sql(0);
+        let filter = live_rows::<M>(
+            &self.filter,
+            self.include_expired,
+            self.engine.database_type(),
+        );
+        let (where_sql, params) = filter.to_sql(0);
 
         let mut sql = String::new();
 
         // SELECT clause
         sql.push_str("SELECT ");
-        sql.push_str(&self.select.to_sql());
+        sql.push_str(&self.select.to_sql_with_virtual(M::VIRTUAL_FIELDS));
 
         // FROM clause
         sql.push_str(" FROM ");
         sql.push_str(M::TABLE_NAME);
 
         // WHERE clause
-        if !self.filter.is_none() {
+        if !filter.is_none() {
             sql.push_str(" WHERE ");
             sql.push_str(&where_sql);
         }
@@ -90,6 +122,7 @@ impl<E: QueryEngine, M: Model> FindFirstOperation<E, M> {
         // LIMIT 1
         sql.push_str(" LIMIT 1");
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -110,6 +143,37 @@ impl<E: QueryEngine, M: Model> FindFirstOperation<E, M> {
         let (sql, params) = self.build_sql();
         self.engine.query_one::<M>(&sql, params).await
     }
+
+    /// Execute the query, tagging the optional result with a content hash
+    /// for HTTP caching.
+    pub async fn exec_with_etag(self) -> QueryResult<Tagged<Option<M>>>
+    where
+        M: serde::Serialize + Send + 'static,
+    {
+        Ok(Tagged::new(self.exec().await?))
+    }
+
+    /// Get the database's plan for the query without running it.
+    pub async fn explain(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, false).await
+    }
+
+    /// Run the query and get the database's plan with measured row counts
+    /// and timings.
+    pub async fn explain_analyze(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, true).await
+    }
+}
+
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for FindFirstOperation<E, M> {
+    type Output = Option<M>;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
 }
 
 #[cfg(test)]
@@ -1,13 +1,29 @@
 //! FindMany operation for querying multiple records.
 
 use std::marker::PhantomData;
+use std::sync::Arc;
 
+use futures::{Stream, TryStreamExt, stream};
+
+use crate::batch::BatchOp;
 use crate::error::QueryResult;
+use crate::etag::Tagged;
+use crate::explain::QueryPlan;
 use crate::filter::Filter;
-use crate::pagination::Pagination;
-use crate::traits::{Model, QueryEngine};
+use crate::id_codec::IdCodec;
+use crate::live::LiveQuery;
+use crate::pagination::{
+    CursorDirection, CursorIds, Page, Pagination, decode_cursor_with, keyset_fetch_order,
+    keyset_filter, keyset_order, keyset_page_with,
+};
+use crate::tags::QueryTags;
+use crate::traits::{BoxFuture, BoxStream, Model, QueryEngine};
+use crate::ttl::live_rows;
 use crate::types::{OrderBy, Select};
 
+/// Default number of rows fetched per round-trip by [`FindManyOperation::exec_batches`].
+pub const DEFAULT_FETCH_SIZE: usize = 1000;
+
 /// A query operation that finds multiple records.
 ///
 /// # Example
@@ -22,6 +38,20 @@ use crate::types::{OrderBy, Select};
 ///     .take(10)
 ///     .exec()
 ///     .await?;
+///
+/// // Stream a large result set, fetching 500 rows at a time
+/// let mut users = client.user().find_many().fetch_size(500).stream();
+/// while let Some(user) = users.next().await {
+///     process(user?);
+/// }
+///
+/// // Page through users 20 at a time
+/// let page = client.user().find_many().cursor_paginate(None, 20).await?;
+/// let next = client
+///     .user()
+///     .find_many()
+///     .cursor_paginate(page.page_info.end_cursor.as_deref(), 20)
+///     .await?;
 /// ```
 pub struct FindManyOperation<E: QueryEngine, M: Model> {
     engine: E,
@@ -30,6 +60,10 @@ pub struct FindManyOperation<E: QueryEngine, M: Model> {
     pagination: Pagination,
     select: Select,
     distinct: Option<Vec<String>>,
+    fetch_size: usize,
+    id_codec: Option<Arc<dyn IdCodec>>,
+    include_expired: bool,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -43,6 +77,10 @@ impl<E: QueryEngine, M: Model> FindManyOperation<E, M> {
             pagination: Pagination::new(),
             select: Select::All,
             distinct: None,
+            fetch_size: DEFAULT_FETCH_SIZE,
+            id_codec: None,
+            include_expired: false,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -90,9 +128,49 @@ impl<E: QueryEngine, M: Model> FindManyOperation<E, M> {
         self
     }
 
+    /// Set how many rows are fetched per round-trip when streaming.
+    ///
+    /// Drivers use server-side cursors to honor this, bounding memory use and
+    /// allowing the stream to be dropped without transferring the remaining rows.
+    pub fn fetch_size(mut self, n: usize) -> Self {
+        self.fetch_size = n.max(1);
+        self
+    }
+
+    /// Encode primary key values in pagination cursors with `codec`.
+    ///
+    /// Cursors issued without the codec are then rejected.
+    pub fn id_codec(mut self, codec: Arc<dyn IdCodec>) -> Self {
+        self.id_codec = Some(codec);
+        self
+    }
+
+    /// Include rows whose `@@ttl` expiry has passed.
+    pub fn include_expired(mut self) -> Self {
+        self.include_expired = true;
+        self
+    }
+
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<crate::filter::FilterValue>) {
-        let (where_sql, params) = self.filter.to_sql(0);
+        let filter = live_rows::<M>(
+            &self.filter,
+            self.include_expired,
+            self.engine.database_type(),
+        );
+        let (where_sql, params) = filter.to_sql(0);
 
         let mut sql = String::new();
 
@@ -103,14 +181,14 @@ impl<E: QueryEngine, M: Model> FindManyOperation<E, M> {
             sql.push_str(&cols.join(", "));
             sql.push_str(") ");
         }
-        sql.push_str(&self.select.to_sql());
+        sql.push_str(&self.select.to_sql_with_virtual(M::VIRTUAL_FIELDS));
 
         // FROM clause
         sql.push_str(" FROM ");
         sql.push_str(M::TABLE_NAME);
 
         // WHERE clause
-        if !self.filter.is_none() {
+        if !filter.is_none() {
             sql.push_str(" WHERE ");
             sql.push_str(&where_sql);
         }
@@ -128,6 +206,7 @@ impl<E: QueryEngine, M: Model> FindManyOperation<E, M> {
             sql.push_str(&pagination_sql);
         }
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -139,6 +218,126 @@ impl<E: QueryEngine, M: Model> FindManyOperation<E, M> {
         let (sql, params) = self.build_sql();
         self.engine.query_many::<M>(&sql, params).await
     }
+
+    /// Execute the query, tagging the records and the result set with
+    /// content hashes for HTTP caching.
+    pub async fn exec_with_etag(self) -> QueryResult<Tagged<Vec<M>>>
+    where
+        M: serde::Serialize + Send + 'static,
+    {
+        Ok(Tagged::rows(self.exec().await?))
+    }
+
+    /// Get the database's plan for the query without running it.
+    pub async fn explain(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, false).await
+    }
+
+    /// Run the query and get the database's plan with measured row counts
+    /// and timings.
+    pub async fn explain_analyze(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, true).await
+    }
+
+    /// Execute the query for the `first` records after the cursor `after`.
+    ///
+    /// Records are positioned by the query's ordering followed by the
+    /// primary key, and cursors are read from the serialized records, so
+    /// ordered columns must be fields of the model. `skip` and `take` are
+    /// replaced by the page bounds.
+    pub async fn cursor_paginate(self, after: Option<&str>, first: u64) -> QueryResult<Page<M>>
+    where
+        M: serde::Serialize + Send + 'static,
+    {
+        self.paginate(after, first, CursorDirection::After).await
+    }
+
+    /// Execute the query for the `last` records before the cursor `before`.
+    ///
+    /// The page is in query order, as for
+    /// [`cursor_paginate`](Self::cursor_paginate).
+    pub async fn cursor_paginate_before(
+        self,
+        before: Option<&str>,
+        last: u64,
+    ) -> QueryResult<Page<M>>
+    where
+        M: serde::Serialize + Send + 'static,
+    {
+        self.paginate(before, last, CursorDirection::Before).await
+    }
+
+    async fn paginate(
+        mut self,
+        cursor: Option<&str>,
+        size: u64,
+        direction: CursorDirection,
+    ) -> QueryResult<Page<M>>
+    where
+        M: serde::Serialize + Send + 'static,
+    {
+        let keys = keyset_order(&self.order_by, M::PRIMARY_KEY);
+        let codec = self.id_codec.take();
+        let ids = codec.as_deref().map(|codec| CursorIds {
+            codec,
+            columns: M::PRIMARY_KEY,
+        });
+        if let Some(cursor) = cursor {
+            let values = decode_cursor_with(cursor, &keys, ids)?;
+            self.filter = self
+                .filter
+                .and_then(keyset_filter(&keys, &values, direction));
+        }
+
+        self.order_by = keyset_fetch_order(&keys, direction);
+        // One extra row tells whether another page follows
+        self.pagination = Pagination::new().take(size + 1);
+
+        let items = self.exec().await?;
+        Ok(keyset_page_with(items, size, &keys, cursor, direction, ids))
+    }
+
+    /// Turn the query into a [`LiveQuery`] that yields the results, then
+    /// an event for every record that enters, changes or leaves them.
+    pub fn live(self) -> LiveQuery<E, M> {
+        let (sql, params) = self.build_sql();
+        LiveQuery::new(self.engine, sql, params)
+    }
+
+    /// Execute the query, yielding the results in batches of the fetch size.
+    pub fn exec_batches(self) -> BoxStream<'static, QueryResult<Vec<M>>>
+    where
+        M: Send + 'static,
+    {
+        let (sql, params) = self.build_sql();
+        self.engine
+            .query_batches::<M>(&sql, params, self.fetch_size)
+    }
+
+    /// Execute the query as a stream of records.
+    ///
+    /// Unlike [`exec`](Self::exec), results are not buffered: the driver
+    /// fetches [`fetch_size`](Self::fetch_size) rows at a time as the stream is
+    /// polled, and dropping the stream stops fetching.
+    pub fn stream(self) -> impl Stream<Item = QueryResult<M>> + Send + 'static
+    where
+        M: Send + 'static,
+    {
+        self.exec_batches()
+            .map_ok(|rows| stream::iter(rows.into_iter().map(Ok)))
+            .try_flatten()
+    }
+}
+
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for FindManyOperation<E, M> {
+    type Output = Vec<M>;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
 }
 
 #[cfg(test)]
@@ -146,9 +345,10 @@ mod tests {
     use super::*;
     use crate::error::QueryError;
     use crate::filter::FilterValue;
-    use crate::pagination::{Cursor, CursorDirection, CursorValue};
+    use crate::pagination::{Cursor, CursorDirection, CursorValue, PageInfo};
     use crate::types::OrderByField;
 
+    #[derive(Debug, serde::Serialize)]
     struct TestModel;
 
     impl Model for TestModel {
@@ -225,10 +425,129 @@ mod tests {
         ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
             Box::pin(async { Ok(0) })
         }
+
+        fn explain(
+            &self,
+            sql: &str,
+            _params: Vec<FilterValue>,
+            analyze: bool,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<QueryPlan>> {
+            // Echo the statement as the plan's only step
+            let mut plan = QueryPlan::from_sqlite_rows([(1, 0, sql.to_string())]);
+            plan.analyzed = analyze;
+            Box::pin(async move { Ok(plan) })
+        }
     }
 
     // ========== Construction Tests ==========
 
+    #[test]
+    fn test_find_many_fetch_size() {
+        let op = FindManyOperation::<MockEngine, TestModel>::new(MockEngine);
+        assert_eq!(op.fetch_size, DEFAULT_FETCH_SIZE);
+
+        let op = op.fetch_size(250);
+        assert_eq!(op.fetch_size, 250);
+
+        // A zero fetch size would never make progress
+        let op = op.fetch_size(0);
+        assert_eq!(op.fetch_size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_many_explain() {
+        let plan = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .r#where(Filter::Equals("name".into(), "Alice".into()))
+            .explain()
+            .await
+            .unwrap();
+        assert!(!plan.analyzed);
+        assert!(plan.roots[0].operation.starts_with("SELECT"));
+        assert!(plan.roots[0].operation.contains("WHERE"));
+
+        let plan = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .explain_analyze()
+            .await
+            .unwrap();
+        assert!(plan.analyzed);
+    }
+
+    #[tokio::test]
+    async fn test_find_many_exec_batches_empty() {
+        use futures::StreamExt;
+
+        let batches: Vec<_> = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .fetch_size(10)
+            .exec_batches()
+            .collect()
+            .await;
+        assert!(batches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_many_stream_empty() {
+        use futures::StreamExt;
+
+        let mut records = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .r#where(Filter::Equals("name".into(), "Alice".into()))
+            .stream();
+        assert!(records.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_many_cursor_paginate() {
+        let page = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .cursor_paginate(None, 10)
+            .await
+            .unwrap();
+        assert!(page.is_empty());
+        assert_eq!(page.page_info, PageInfo::default());
+
+        let err = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .cursor_paginate_before(Some("bogus"), 10)
+            .await
+            .unwrap_err();
+        assert_eq!(err.context().field.as_deref(), Some("cursor"));
+
+        // A cursor holding the raw primary key isn't accepted with a codec
+        let raw = crate::pagination::encode_cursor(
+            &serde_json::json!({ "id": 1 }),
+            &[crate::types::OrderByField::asc("id")],
+        );
+        let err = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .id_codec(Arc::new(crate::id_codec::Sqids::new()))
+            .cursor_paginate(Some(&raw), 10)
+            .await
+            .unwrap_err();
+        assert_eq!(err.context().field.as_deref(), Some("cursor"));
+    }
+
+    #[test]
+    fn test_find_many_skips_expired() {
+        struct Session;
+
+        impl Model for Session {
+            const MODEL_NAME: &'static str = "Session";
+            const TABLE_NAME: &'static str = "sessions";
+            const PRIMARY_KEY: &'static [&'static str] = &["id"];
+            const COLUMNS: &'static [&'static str] = &["id", "expires_at"];
+            const TTL_COLUMN: Option<&'static str> = Some("expires_at");
+        }
+
+        let (sql, params) = FindManyOperation::<MockEngine, Session>::new(MockEngine).build_sql();
+        assert_eq!(
+            sql,
+            "SELECT * FROM sessions WHERE (expires_at IS NULL OR expires_at > $1::timestamptz)"
+        );
+        assert_eq!(params.len(), 1);
+
+        let (sql, params) = FindManyOperation::<MockEngine, Session>::new(MockEngine)
+            .include_expired()
+            .build_sql();
+        assert_eq!(sql, "SELECT * FROM sessions");
+        assert!(params.is_empty());
+    }
+
     #[test]
     fn test_find_many_new() {
         let op = FindManyOperation::<MockEngine, TestModel>::new(MockEngine);
@@ -247,6 +566,23 @@ mod tests {
         assert!(params.is_empty());
     }
 
+    #[test]
+    fn test_find_many_with_tags() {
+        let (sql, _) = FindManyOperation::<MockEngine, TestModel>::new(MockEngine)
+            .take(10)
+            .tags(["team:checkout", "feature:cart"])
+            .build_sql();
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM test_models LIMIT 10 /*feature='cart',team='checkout'*/"
+        );
+        assert_eq!(
+            crate::tags::QueryTags::from_sql(&sql).get("team"),
+            Some("checkout")
+        );
+    }
+
     // ========== Filter Tests ==========
 
     #[test]
@@ -2,9 +2,16 @@
 
 use std::marker::PhantomData;
 
+use crate::async_optimize::ConcurrencyConfig;
+use crate::batch::BatchOp;
 use crate::error::QueryResult;
+use crate::etag::Tagged;
+use crate::explain::QueryPlan;
 use crate::filter::Filter;
-use crate::traits::{Model, QueryEngine};
+use crate::relations::{IncludeLoad, LoadedRecord, load_includes};
+use crate::tags::QueryTags;
+use crate::traits::{BoxFuture, Model, QueryEngine};
+use crate::ttl::live_rows;
 use crate::types::Select;
 
 /// A query operation that finds a single record by unique constraint.
@@ -12,17 +19,30 @@ use crate::types::Select;
 /// # Example
 ///
 /// ```rust,ignore
-/// let user = client
-///     .user()
+/// let users = QueryBuilder::<_, user::User>::new(engine.clone());
+/// let by_id = || Filter::Equals(user::id::COLUMN.into(), 1.into());
+///
+/// let user = users.find_unique().r#where(by_id()).exec().await?;
+///
+/// // Independent includes, generated per relation, are loaded concurrently
+/// let mut loaded = users
 ///     .find_unique()
-///     .r#where(user::id::equals(1))
-///     .exec()
+///     .r#where(by_id())
+///     .include(user::posts::fetch(engine.clone()))
+///     .include(user::profile::fetch(engine.clone()))
+///     .exec_with_relations()
 ///     .await?;
+/// let posts = loaded.relations.take::<Vec<post::Post>>("posts");
+/// let profile = loaded.relations.take::<Option<profile::Profile>>("profile");
 /// ```
 pub struct FindUniqueOperation<E: QueryEngine, M: Model> {
     engine: E,
     filter: Filter,
     select: Select,
+    includes: Vec<IncludeLoad<M>>,
+    concurrency: ConcurrencyConfig,
+    include_expired: bool,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -33,6 +53,10 @@ impl<E: QueryEngine, M: Model> FindUniqueOperation<E, M> {
             engine,
             filter: Filter::None,
             select: Select::All,
+            includes: Vec::new(),
+            concurrency: ConcurrencyConfig::default(),
+            include_expired: false,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -49,22 +73,57 @@ impl<E: QueryEngine, M: Model> FindUniqueOperation<E, M> {
         self
     }
 
+    /// Include a relation, loaded by [`exec_with_relations`](Self::exec_with_relations).
+    pub fn include(mut self, include: IncludeLoad<M>) -> Self {
+        self.includes.push(include);
+        self
+    }
+
+    /// Set how many includes may load at once.
+    pub fn with_concurrency(mut self, config: ConcurrencyConfig) -> Self {
+        self.concurrency = config;
+        self
+    }
+
+    /// Include rows whose `@@ttl` expiry has passed.
+    pub fn include_expired(mut self) -> Self {
+        self.include_expired = true;
+        self
+    }
+
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<crate::filter::FilterValue>) {
-        let (where_sql, params) = self.filter.to_sql(0);
+        let filter = live_rows::<M>(
+            &self.filter,
+            self.include_expired,
+            self.engine.database_type(),
+        );
+        let (where_sql, params) = filter.to_sql(0);
 
         let mut sql = String::new();
 
         // SELECT clause
         sql.push_str("SELECT ");
-        sql.push_str(&self.select.to_sql());
+        sql.push_str(&self.select.to_sql_with_virtual(M::VIRTUAL_FIELDS));
 
         // FROM clause
         sql.push_str(" FROM ");
         sql.push_str(M::TABLE_NAME);
 
         // WHERE clause
-        if !self.filter.is_none() {
+        if !filter.is_none() {
             sql.push_str(" WHERE ");
             sql.push_str(&where_sql);
         }
@@ -72,6 +131,7 @@ impl<E: QueryEngine, M: Model> FindUniqueOperation<E, M> {
         // LIMIT 1 for unique query
         sql.push_str(" LIMIT 1");
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -92,6 +152,51 @@ impl<E: QueryEngine, M: Model> FindUniqueOperation<E, M> {
         let (sql, params) = self.build_sql();
         self.engine.query_optional::<M>(&sql, params).await
     }
+
+    /// Execute the query, tagging the record with a content hash for HTTP
+    /// caching (errors if not found).
+    pub async fn exec_with_etag(self) -> QueryResult<Tagged<M>>
+    where
+        M: serde::Serialize + Send + 'static,
+    {
+        Ok(Tagged::new(self.exec().await?))
+    }
+
+    /// Get the database's plan for the query without running it.
+    pub async fn explain(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, false).await
+    }
+
+    /// Run the query and get the database's plan with measured row counts
+    /// and timings.
+    pub async fn explain_analyze(self) -> QueryResult<QueryPlan> {
+        let (sql, params) = self.build_sql();
+        self.engine.explain(&sql, params, true).await
+    }
+
+    /// Execute the query and load the included relations (errors if not found).
+    ///
+    /// Includes are independent of each other and load concurrently, bounded
+    /// by the configured [`ConcurrencyConfig`]. A failing include is reported
+    /// with its relation name.
+    pub async fn exec_with_relations(self) -> QueryResult<LoadedRecord<M>>
+    where
+        M: Send + 'static,
+    {
+        let (sql, params) = self.build_sql();
+        let record = self.engine.query_one::<M>(&sql, params).await?;
+        load_includes(record, self.includes, &self.concurrency).await
+    }
+}
+
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for FindUniqueOperation<E, M> {
+    type Output = M;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +284,77 @@ mod tests {
         }
     }
 
+    /// Engine finding a `TestModel` for every `query_one`.
+    #[derive(Clone)]
+    struct RecordEngine;
+
+    impl QueryEngine for RecordEngine {
+        fn query_many<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn query_one<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            let record: Box<dyn std::any::Any + Send> = Box::new(TestModel);
+            Box::pin(async move { Ok(*record.downcast::<T>().unwrap()) })
+        }
+
+        fn query_optional<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Option<T>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn execute_insert<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            Box::pin(async { Err(QueryError::not_found("test")) })
+        }
+
+        fn execute_update<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn execute_delete(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn execute_raw(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn count(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+    }
+
     // ========== Construction and Basic Tests ==========
 
     #[test]
@@ -404,6 +580,91 @@ mod tests {
         assert_eq!(params.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_find_unique_exec_with_relations_not_found() {
+        let op = FindUniqueOperation::<MockEngine, TestModel>::new(MockEngine)
+            .r#where(Filter::Equals("id".into(), FilterValue::Int(1)))
+            .include(IncludeLoad::new(
+                crate::relations::IncludeSpec::new("posts"),
+                |_| async { Ok(()) },
+            ))
+            .with_concurrency(ConcurrencyConfig::default().with_max_concurrency(2));
+
+        assert_eq!(op.includes.len(), 1);
+        assert_eq!(op.includes[0].relation(), "posts");
+
+        // Includes are not loaded when the record is missing
+        let result = op.exec_with_relations().await;
+        assert!(result.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_find_unique_exec_with_relations_concurrently() {
+        // Both includes wait for each other, so loading them one after
+        // another would time out
+        let barrier = std::sync::Arc::new(tokio::sync::Barrier::new(2));
+        let include = |name: &'static str| {
+            let barrier = std::sync::Arc::clone(&barrier);
+            IncludeLoad::new(
+                crate::relations::IncludeSpec::new(name),
+                move |_: std::sync::Arc<TestModel>| async move {
+                    barrier.wait().await;
+                    Ok(vec![format!("{name}-1")])
+                },
+            )
+        };
+
+        let mut loaded = FindUniqueOperation::<RecordEngine, TestModel>::new(RecordEngine)
+            .r#where(Filter::Equals("id".into(), FilterValue::Int(1)))
+            .include(include("posts"))
+            .include(include("comments"))
+            .with_concurrency(
+                ConcurrencyConfig::default()
+                    .with_max_concurrency(2)
+                    .with_timeout(std::time::Duration::from_secs(5)),
+            )
+            .exec_with_relations()
+            .await
+            .unwrap();
+
+        assert_eq!(loaded.relations.len(), 2);
+        assert_eq!(
+            loaded.relations.take::<Vec<String>>("posts"),
+            Some(vec!["posts-1".to_string()])
+        );
+        assert_eq!(
+            loaded.relations.take::<Vec<String>>("comments"),
+            Some(vec!["comments-1".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_unique_exec_with_relations_error_attribution() {
+        let err = FindUniqueOperation::<RecordEngine, TestModel>::new(RecordEngine)
+            .r#where(Filter::Equals("id".into(), FilterValue::Int(1)))
+            .include(IncludeLoad::new(
+                crate::relations::IncludeSpec::new("profile"),
+                |_: std::sync::Arc<TestModel>| async { Ok(Some("bio")) },
+            ))
+            .include(IncludeLoad::new(
+                crate::relations::IncludeSpec::new("posts"),
+                |_: std::sync::Arc<TestModel>| async {
+                    Err::<(), _>(QueryError::database("relation \"posts\" does not exist"))
+                },
+            ))
+            .exec_with_relations()
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.context().field.as_deref(), Some("posts"));
+        assert_eq!(
+            err.context().operation.as_deref(),
+            Some("loading include `posts`")
+        );
+        assert!(err.message.contains("does not exist"));
+        assert!(err.context().related.is_empty());
+    }
+
     #[test]
     fn test_find_unique_replace_filter() {
         // Later where_ calls should replace the filter
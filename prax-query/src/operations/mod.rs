@@ -38,7 +38,7 @@ pub use count::CountOperation;
 pub use create::{CreateManyOperation, CreateOperation};
 pub use delete::{DeleteManyOperation, DeleteOperation};
 pub use find_first::FindFirstOperation;
-pub use find_many::FindManyOperation;
+pub use find_many::{DEFAULT_FETCH_SIZE, FindManyOperation};
 pub use find_unique::FindUniqueOperation;
 pub use update::{UpdateManyOperation, UpdateOperation};
 pub use upsert::UpsertOperation;
@@ -2,9 +2,11 @@
 
 use std::marker::PhantomData;
 
+use crate::batch::BatchOp;
 use crate::error::QueryResult;
 use crate::filter::{Filter, FilterValue};
-use crate::traits::{Model, QueryEngine};
+use crate::tags::QueryTags;
+use crate::traits::{BoxFuture, Model, QueryEngine};
 use crate::types::Select;
 
 /// An update operation for modifying existing records.
@@ -25,6 +27,7 @@ pub struct UpdateOperation<E: QueryEngine, M: Model> {
     filter: Filter,
     updates: Vec<(String, FilterValue)>,
     select: Select,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -36,6 +39,7 @@ impl<E: QueryEngine, M: Model> UpdateOperation<E, M> {
             filter: Filter::None,
             updates: Vec::new(),
             select: Select::All,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -77,6 +81,18 @@ impl<E: QueryEngine, M: Model> UpdateOperation<E, M> {
         self
     }
 
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<FilterValue>) {
         let mut sql = String::new();
@@ -111,8 +127,9 @@ impl<E: QueryEngine, M: Model> UpdateOperation<E, M> {
 
         // RETURNING clause
         sql.push_str(" RETURNING ");
-        sql.push_str(&self.select.to_sql());
+        sql.push_str(&self.select.to_sql_with_virtual(M::VIRTUAL_FIELDS));
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -140,6 +157,7 @@ pub struct UpdateManyOperation<E: QueryEngine, M: Model> {
     engine: E,
     filter: Filter,
     updates: Vec<(String, FilterValue)>,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -150,6 +168,7 @@ impl<E: QueryEngine, M: Model> UpdateManyOperation<E, M> {
             engine,
             filter: Filter::None,
             updates: Vec::new(),
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -167,6 +186,18 @@ impl<E: QueryEngine, M: Model> UpdateManyOperation<E, M> {
         self
     }
 
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<FilterValue>) {
         let mut sql = String::new();
@@ -199,6 +230,7 @@ impl<E: QueryEngine, M: Model> UpdateManyOperation<E, M> {
             params.extend(where_params);
         }
 
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
@@ -209,6 +241,24 @@ impl<E: QueryEngine, M: Model> UpdateManyOperation<E, M> {
     }
 }
 
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for UpdateOperation<E, M> {
+    type Output = Vec<M>;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
+}
+
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for UpdateManyOperation<E, M> {
+    type Output = u64;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,10 +2,15 @@
 
 use std::marker::PhantomData;
 
-use crate::error::QueryResult;
+use crate::batch::BatchOp;
+use crate::error::{QueryError, QueryResult};
 use crate::filter::{Filter, FilterValue};
-use crate::traits::{Model, QueryEngine};
+use crate::middleware::translate_statement;
+use crate::sql::DatabaseType;
+use crate::tags::QueryTags;
+use crate::traits::{BoxFuture, Model, QueryEngine};
 use crate::types::Select;
+use crate::upsert::{Assignment, AssignmentValue, ConflictTarget, Upsert};
 
 /// An upsert (insert or update) operation.
 ///
@@ -30,6 +35,7 @@ pub struct UpsertOperation<E: QueryEngine, M: Model> {
     update_values: Vec<FilterValue>,
     conflict_columns: Vec<String>,
     select: Select,
+    tags: QueryTags,
     _model: PhantomData<M>,
 }
 
@@ -45,6 +51,7 @@ impl<E: QueryEngine, M: Model> UpsertOperation<E, M> {
             update_values: Vec::new(),
             conflict_columns: Vec::new(),
             select: Select::All,
+            tags: QueryTags::new(),
             _model: PhantomData,
         }
     }
@@ -105,77 +112,139 @@ impl<E: QueryEngine, M: Model> UpsertOperation<E, M> {
         self
     }
 
-    /// Build the SQL query.
+    /// Tag the query for cost attribution, as `key:value` (see [`crate::tags`]).
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Tag the query with several `key:value` tags.
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
+    /// Build the SQL query for the engine's database.
+    ///
+    /// PostgreSQL and SQLite use `INSERT ... ON CONFLICT` and return the row
+    /// with `RETURNING`. MySQL uses `INSERT ... ON DUPLICATE KEY UPDATE`,
+    /// which matches any unique key rather than the conflict columns, and
+    /// `INSERT IGNORE` when there is nothing to update.
     pub fn build_sql(&self) -> (String, Vec<FilterValue>) {
-        let mut sql = String::new();
-        let mut params = Vec::new();
-        let mut param_idx = 1;
-
-        // INSERT INTO clause
-        sql.push_str("INSERT INTO ");
-        sql.push_str(M::TABLE_NAME);
-
-        // Columns
-        sql.push_str(" (");
-        sql.push_str(&self.create_columns.join(", "));
-        sql.push(')');
-
-        // VALUES
-        sql.push_str(" VALUES (");
-        let placeholders: Vec<_> = self
-            .create_values
-            .iter()
-            .map(|v| {
-                params.push(v.clone());
-                let p = format!("${}", param_idx);
-                param_idx += 1;
-                p
-            })
-            .collect();
-        sql.push_str(&placeholders.join(", "));
-        sql.push(')');
+        let db_type = self.engine.database_type();
+        let mut params = self.create_values.clone();
 
-        // ON CONFLICT
-        sql.push_str(" ON CONFLICT ");
+        let placeholders: Vec<String> = (1..=params.len())
+            .map(|i| db_type.placeholder_string(i))
+            .collect();
+        let mut upsert = Upsert::new(M::TABLE_NAME)
+            .columns(self.create_columns.iter().cloned())
+            .values(placeholders);
         if !self.conflict_columns.is_empty() {
-            sql.push('(');
-            sql.push_str(&self.conflict_columns.join(", "));
-            sql.push_str(") ");
+            upsert = upsert.on_conflict(ConflictTarget::columns(
+                self.conflict_columns.iter().cloned(),
+            ));
         }
 
         // DO UPDATE SET
-        if self.update_columns.is_empty() {
-            sql.push_str("DO NOTHING");
-        } else {
-            sql.push_str("DO UPDATE SET ");
-            let update_parts: Vec<_> = self
+        if !self.update_columns.is_empty() {
+            let assignments = self
                 .update_columns
                 .iter()
-                .zip(self.update_values.iter())
-                .map(|(col, val)| {
-                    params.push(val.clone());
-                    let part = format!("{} = ${}", col, param_idx);
-                    param_idx += 1;
-                    part
+                .enumerate()
+                .map(|(i, column)| Assignment {
+                    column: column.clone(),
+                    value: AssignmentValue::Param(params.len() + i + 1),
                 })
                 .collect();
-            sql.push_str(&update_parts.join(", "));
+            upsert = upsert.do_update_set(assignments);
+            params.extend(self.update_values.iter().cloned());
         }
 
-        // RETURNING clause
-        sql.push_str(" RETURNING ");
-        sql.push_str(&self.select.to_sql());
+        // MySQL has no RETURNING; the record is read back instead
+        if db_type != DatabaseType::MySQL {
+            upsert = upsert.returning([self.select.to_sql_with_virtual(M::VIRTUAL_FIELDS)]);
+        }
 
+        let mut sql = upsert.to_sql(db_type);
+        self.tags.append_to(&mut sql);
         (sql, params)
     }
 
+    /// Build the query reading the upserted record back, for databases
+    /// without `RETURNING`.
+    ///
+    /// The record is identified by the `where` filter, or else by the
+    /// created values of the conflict columns or the primary key.
+    pub fn build_select_sql(&self) -> QueryResult<(String, Vec<FilterValue>)> {
+        let filter = if self.filter.is_none() {
+            let columns: Vec<&str> = if self.conflict_columns.is_empty() {
+                M::PRIMARY_KEY.to_vec()
+            } else {
+                self.conflict_columns.iter().map(String::as_str).collect()
+            };
+            let conditions: Option<Vec<Filter>> = columns
+                .iter()
+                .map(|column| {
+                    let index = self.create_columns.iter().position(|c| c == column)?;
+                    Some(Filter::Equals(
+                        column.to_string().into(),
+                        self.create_values[index].clone(),
+                    ))
+                })
+                .collect();
+            match conditions {
+                Some(conditions) if !conditions.is_empty() => Filter::and(conditions),
+                _ => {
+                    return Err(QueryError::invalid_input(
+                        "where",
+                        "upsert needs a where filter, or created values for the conflict columns, to read the record back",
+                    ));
+                }
+            }
+        } else {
+            self.filter.clone()
+        };
+
+        let (where_sql, params) = filter.to_sql(0);
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} LIMIT 1",
+            self.select.to_sql_with_virtual(M::VIRTUAL_FIELDS),
+            M::TABLE_NAME,
+            where_sql
+        );
+        let (mut sql, params) = translate_statement(
+            &sql,
+            &params,
+            DatabaseType::PostgreSQL,
+            self.engine.database_type(),
+        );
+        self.tags.append_to(&mut sql);
+        Ok((sql, params))
+    }
+
     /// Execute the upsert and return the record.
     pub async fn exec(self) -> QueryResult<M>
     where
         M: Send + 'static,
     {
         let (sql, params) = self.build_sql();
-        self.engine.execute_insert::<M>(&sql, params).await
+        if self.engine.database_type() != DatabaseType::MySQL {
+            return self.engine.execute_insert::<M>(&sql, params).await;
+        }
+
+        let (select_sql, select_params) = self.build_select_sql()?;
+        self.engine.execute_raw(&sql, params).await?;
+        self.engine.query_one::<M>(&select_sql, select_params).await
+    }
+}
+
+impl<E: QueryEngine, M: Model + 'static> BatchOp<E> for UpsertOperation<E, M> {
+    type Output = M;
+
+    fn exec_on(mut self, engine: E) -> BoxFuture<'static, QueryResult<Self::Output>> {
+        self.engine = engine;
+        Box::pin(self.exec())
     }
 }
 
@@ -262,6 +331,99 @@ mod tests {
         }
     }
 
+    /// Engine of another dialect, recording the statements it runs.
+    #[derive(Clone)]
+    struct DialectEngine {
+        db_type: DatabaseType,
+        log: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl DialectEngine {
+        fn new(db_type: DatabaseType) -> Self {
+            Self {
+                db_type,
+                log: Default::default(),
+            }
+        }
+
+        fn record(&self, sql: &str) {
+            self.log.lock().unwrap().push(sql.to_string());
+        }
+    }
+
+    impl QueryEngine for DialectEngine {
+        fn query_many<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn query_one<T: Model + Send + 'static>(
+            &self,
+            sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            self.record(sql);
+            Box::pin(async { Err(QueryError::not_found("test")) })
+        }
+
+        fn query_optional<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Option<T>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn execute_insert<T: Model + Send + 'static>(
+            &self,
+            sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<T>> {
+            self.record(sql);
+            Box::pin(async { Err(QueryError::not_found("test")) })
+        }
+
+        fn execute_update<T: Model + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<Vec<T>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn execute_delete(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn execute_raw(
+            &self,
+            sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            self.record(sql);
+            Box::pin(async { Ok(1) })
+        }
+
+        fn count(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> crate::traits::BoxFuture<'_, QueryResult<u64>> {
+            Box::pin(async { Ok(0) })
+        }
+
+        fn database_type(&self) -> DatabaseType {
+            self.db_type
+        }
+    }
+
     // ========== Construction Tests ==========
 
     #[test]
@@ -562,6 +724,133 @@ mod tests {
         assert_eq!(params.len(), 3);
     }
 
+    // ========== Dialect Tests ==========
+
+    #[test]
+    fn test_upsert_mysql() {
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(DialectEngine::new(
+            DatabaseType::MySQL,
+        ))
+        .on_conflict(["email"])
+        .create_set("email", "test@example.com")
+        .create_set("name", "Test")
+        .update_set("name", "Updated");
+
+        let (sql, params) = op.build_sql();
+
+        assert_eq!(
+            sql,
+            "INSERT INTO test_models (email, name) VALUES (?, ?) ON DUPLICATE KEY UPDATE name = ?"
+        );
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[2], FilterValue::String("Updated".to_string()));
+    }
+
+    #[test]
+    fn test_upsert_mysql_do_nothing() {
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(DialectEngine::new(
+            DatabaseType::MySQL,
+        ))
+        .on_conflict(["email"])
+        .create_set("email", "test@example.com");
+
+        let (sql, _) = op.build_sql();
+
+        assert_eq!(sql, "INSERT IGNORE INTO test_models (email) VALUES (?)");
+    }
+
+    #[test]
+    fn test_upsert_sqlite() {
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(DialectEngine::new(
+            DatabaseType::SQLite,
+        ))
+        .on_conflict(["email"])
+        .create_set("email", "test@example.com")
+        .create_set("name", "Test")
+        .update_set("name", "Updated")
+        .select(Select::fields(["id", "name"]));
+
+        let (sql, params) = op.build_sql();
+
+        assert_eq!(
+            sql,
+            "INSERT INTO test_models (email, name) VALUES (?, ?) ON CONFLICT (email) DO UPDATE SET name = ? RETURNING id, name"
+        );
+        assert_eq!(params.len(), 3);
+    }
+
+    #[test]
+    fn test_upsert_select_back_key() {
+        let engine = DialectEngine::new(DatabaseType::MySQL);
+
+        // Conflict columns, taking their created values
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(engine.clone())
+            .on_conflict(["email"])
+            .create_set("name", "Test")
+            .create_set("email", "test@example.com");
+        let (sql, params) = op.build_select_sql().unwrap();
+        assert_eq!(sql, "SELECT * FROM test_models WHERE email = ? LIMIT 1");
+        assert_eq!(
+            params,
+            vec![FilterValue::String("test@example.com".to_string())]
+        );
+
+        // The primary key without conflict columns
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(engine.clone())
+            .create_set("id", FilterValue::Int(7));
+        let (sql, _) = op.build_select_sql().unwrap();
+        assert_eq!(sql, "SELECT * FROM test_models WHERE id = ? LIMIT 1");
+
+        // The where filter takes precedence
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(engine.clone())
+            .r#where(Filter::Equals("name".into(), "Test".into()))
+            .on_conflict(["email"])
+            .create_set("email", "test@example.com");
+        let (sql, _) = op.build_select_sql().unwrap();
+        assert_eq!(sql, "SELECT * FROM test_models WHERE name = ? LIMIT 1");
+
+        // Nothing identifies the record
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(engine)
+            .on_conflict(["email"])
+            .create_set("name", "Test");
+        assert!(op.build_select_sql().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_mysql_exec_reads_back() {
+        let engine = DialectEngine::new(DatabaseType::MySQL);
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(engine.clone())
+            .on_conflict(["email"])
+            .create_set("email", "test@example.com")
+            .update_set("name", "Updated");
+
+        // DialectEngine finds no record to read back
+        assert!(op.exec().await.is_err());
+        assert_eq!(
+            *engine.log.lock().unwrap(),
+            [
+                "INSERT INTO test_models (email) VALUES (?) ON DUPLICATE KEY UPDATE name = ?",
+                "SELECT * FROM test_models WHERE email = ? LIMIT 1",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_sqlite_exec_returns_row() {
+        let engine = DialectEngine::new(DatabaseType::SQLite);
+        let op = UpsertOperation::<DialectEngine, TestModel>::new(engine.clone())
+            .on_conflict(["email"])
+            .create_set("email", "test@example.com");
+
+        assert!(op.exec().await.is_err());
+        assert_eq!(
+            *engine.log.lock().unwrap(),
+            [
+                "INSERT INTO test_models (email) VALUES (?) ON CONFLICT (email) DO NOTHING RETURNING *"
+            ]
+        );
+    }
+
     // ========== Value Type Tests ==========
 
     #[test]
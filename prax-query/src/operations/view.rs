@@ -2,10 +2,13 @@
 
 use std::marker::PhantomData;
 
-use crate::error::QueryResult;
+use crate::error::{QueryError, QueryResult};
 use crate::filter::Filter;
-use crate::pagination::Pagination;
-use crate::traits::{MaterializedView, QueryEngine, View};
+use crate::pagination::{
+    CursorDirection, Page, Pagination, decode_cursor, keyset_fetch_order, keyset_filter,
+    keyset_order, keyset_page,
+};
+use crate::traits::{MaterializedView, QueryEngine, View, ViewQueryEngine};
 use crate::types::{OrderBy, Select};
 
 /// A query operation that finds multiple records from a view.
@@ -23,6 +26,14 @@ use crate::types::{OrderBy, Select};
 ///     .take(100)
 ///     .exec()
 ///     .await?;
+///
+/// // Page through a large materialized view without OFFSET
+/// let page = client
+///     .daily_revenue()
+///     .find_many()
+///     .order_by(daily_revenue::day::desc())
+///     .cursor_paginate(None, 50)
+///     .await?;
 /// ```
 #[allow(dead_code)]
 pub struct ViewFindManyOperation<E: QueryEngine, V: View> {
@@ -87,6 +98,9 @@ impl<E: QueryEngine, V: View> ViewFindManyOperation<E, V> {
     }
 
     /// Set cursor for cursor-based pagination.
+    ///
+    /// Only records beyond the cursor's column value are returned; order by
+    /// that column for the cursor to mark a position.
     pub fn cursor(mut self, cursor: crate::pagination::Cursor) -> Self {
         self.pagination = self.pagination.cursor(cursor);
         self
@@ -94,7 +108,11 @@ impl<E: QueryEngine, V: View> ViewFindManyOperation<E, V> {
 
     /// Build the SQL query.
     pub fn build_sql(&self) -> (String, Vec<crate::filter::FilterValue>) {
-        let (where_sql, params) = self.filter.to_sql(0);
+        let filter = match &self.pagination.cursor {
+            Some(cursor) => self.filter.clone().and_then(cursor.to_filter()),
+            None => self.filter.clone(),
+        };
+        let (where_sql, params) = filter.to_sql(0);
 
         let mut sql = String::new();
 
@@ -112,7 +130,7 @@ impl<E: QueryEngine, V: View> ViewFindManyOperation<E, V> {
         sql.push_str(V::DB_VIEW_NAME);
 
         // WHERE clause
-        if !self.filter.is_none() {
+        if !filter.is_none() {
             sql.push_str(" WHERE ");
             sql.push_str(&where_sql);
         }
@@ -149,6 +167,87 @@ impl<E: QueryEngine, V: View> ViewFindManyOperation<E, V> {
     }
 }
 
+impl<E: ViewQueryEngine, V: View> ViewFindManyOperation<E, V> {
+    /// Execute the query for the `first` rows after the cursor `after`.
+    ///
+    /// Rows are positioned by the query's ordering followed by
+    /// [`View::UNIQUE_KEY`], and cursors are read from the serialized rows,
+    /// so ordered columns must be fields of the view and, together, should
+    /// identify a row. Each page is a range scan from the cursor, so paging
+    /// deep into a large view costs the same as the first page. `skip`,
+    /// `take` and `cursor` are replaced by the page bounds.
+    pub async fn cursor_paginate(self, after: Option<&str>, first: u64) -> QueryResult<Page<V>>
+    where
+        V: serde::Serialize + Send + 'static,
+    {
+        self.paginate(after, first, CursorDirection::After).await
+    }
+
+    /// Execute the query for the `last` rows before the cursor `before`.
+    ///
+    /// The page is in query order, as for
+    /// [`cursor_paginate`](Self::cursor_paginate).
+    pub async fn cursor_paginate_before(
+        self,
+        before: Option<&str>,
+        last: u64,
+    ) -> QueryResult<Page<V>>
+    where
+        V: serde::Serialize + Send + 'static,
+    {
+        self.paginate(before, last, CursorDirection::Before).await
+    }
+
+    async fn paginate(
+        self,
+        cursor: Option<&str>,
+        size: u64,
+        direction: CursorDirection,
+    ) -> QueryResult<Page<V>>
+    where
+        V: serde::Serialize + Send + 'static,
+    {
+        let (op, keys) = self.keyset(cursor, size, direction)?;
+        let (sql, params) = op.build_sql();
+        let items = op.engine.query_view_many::<V>(&sql, params).await?;
+        Ok(keyset_page(items, size, &keys, cursor, direction))
+    }
+}
+
+impl<E: QueryEngine, V: View> ViewFindManyOperation<E, V> {
+    /// Restrict the query to the page of `size` rows beyond `cursor`,
+    /// returning it with the keyset ordering.
+    fn keyset(
+        mut self,
+        cursor: Option<&str>,
+        size: u64,
+        direction: CursorDirection,
+    ) -> QueryResult<(Self, Vec<crate::types::OrderByField>)> {
+        let keys = keyset_order(&self.order_by, V::UNIQUE_KEY);
+        if keys.is_empty() {
+            return Err(QueryError::invalid_input(
+                "order_by",
+                format!(
+                    "keyset pagination on view {} needs an ordering",
+                    V::VIEW_NAME
+                ),
+            )
+            .with_suggestion("Call order_by() or set View::UNIQUE_KEY"));
+        }
+        if let Some(cursor) = cursor {
+            let values = decode_cursor(cursor, keys.len())?;
+            self.filter = self
+                .filter
+                .and_then(keyset_filter(&keys, &values, direction));
+        }
+
+        self.order_by = keyset_fetch_order(&keys, direction);
+        // One extra row tells whether another page follows
+        self.pagination = Pagination::new().take(size + 1);
+        Ok((self, keys))
+    }
+}
+
 /// A query operation that finds a single record from a view.
 pub struct ViewFindFirstOperation<E: QueryEngine, V: View> {
     inner: ViewFindManyOperation<E, V>,
@@ -377,6 +476,7 @@ mod tests {
     use crate::filter::FilterValue;
     use crate::traits::BoxFuture;
 
+    #[derive(Debug, serde::Serialize)]
     struct TestView;
 
     impl View for TestView {
@@ -384,8 +484,10 @@ mod tests {
         const DB_VIEW_NAME: &'static str = "test_view";
         const COLUMNS: &'static [&'static str] = &["id", "user_id", "post_count"];
         const IS_MATERIALIZED: bool = false;
+        const UNIQUE_KEY: &'static [&'static str] = &["user_id"];
     }
 
+    #[derive(Debug, serde::Serialize)]
     struct TestMaterializedView;
 
     impl View for TestMaterializedView {
@@ -480,6 +582,24 @@ mod tests {
         }
     }
 
+    impl ViewQueryEngine for MockEngine {
+        fn query_view_many<V: View + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<V>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn query_view_optional<V: View + Send + 'static>(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Option<V>>> {
+            Box::pin(async { Ok(None) })
+        }
+    }
+
     // ========== ViewFindManyOperation Tests ==========
 
     #[test]
@@ -537,6 +657,92 @@ mod tests {
         assert!(sql.contains("DISTINCT ON (user_id)"));
     }
 
+    #[test]
+    fn test_view_find_many_with_cursor() {
+        use crate::pagination::Cursor;
+        use crate::types::OrderByField;
+
+        let op = ViewFindManyOperation::<MockEngine, TestView>::new(MockEngine)
+            .r#where(Filter::Gte("post_count".into(), FilterValue::Int(10)))
+            .order_by(OrderByField::asc("id"))
+            .cursor(Cursor::after("id", 100))
+            .take(20);
+
+        let (sql, params) = op.build_sql();
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM test_view WHERE (post_count >= $1 AND id > $2) ORDER BY id ASC LIMIT 20"
+        );
+        assert_eq!(params, vec![FilterValue::Int(10), FilterValue::Int(100)]);
+    }
+
+    #[test]
+    fn test_view_find_many_keyset() {
+        use crate::pagination::encode_cursor;
+        use crate::types::OrderByField;
+
+
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            day: &'static str,
+            user_id: i64,
+        }
+
+        let keys = [OrderByField::desc("day"), OrderByField::asc("user_id")];
+        let cursor = encode_cursor(
+            &Row {
+                day: "2024-03-01",
+                user_id: 7,
+            },
+            &keys,
+        );
+
+        let (op, _) = ViewFindManyOperation::<MockEngine, TestView>::new(MockEngine)
+            .order_by(OrderByField::desc("day"))
+            .skip(1000)
+            .keyset(Some(&cursor), 50, CursorDirection::After)
+            .unwrap();
+        let (sql, params) = op.build_sql();
+        assert_eq!(
+            sql,
+            "SELECT * FROM test_view WHERE (day < $1 OR (day = $2 AND user_id > $3)) \
+             ORDER BY day DESC, user_id ASC LIMIT 51"
+        );
+        assert_eq!(params.len(), 3);
+
+        let (op, _) = ViewFindManyOperation::<MockEngine, TestView>::new(MockEngine)
+            .order_by(OrderByField::desc("day"))
+            .keyset(Some(&cursor), 50, CursorDirection::Before)
+            .unwrap();
+        let (sql, _) = op.build_sql();
+        assert!(sql.contains("(day > $1 OR (day = $2 AND user_id < $3))"));
+        assert!(sql.ends_with("ORDER BY day ASC, user_id DESC LIMIT 51"));
+    }
+
+    #[tokio::test]
+    async fn test_view_find_many_cursor_paginate() {
+        let page = ViewFindManyOperation::<MockEngine, TestView>::new(MockEngine)
+            .cursor_paginate(None, 10)
+            .await
+            .unwrap();
+        assert!(page.is_empty());
+        assert_eq!(page.page_info, crate::pagination::PageInfo::default());
+
+        let err = ViewFindManyOperation::<MockEngine, TestView>::new(MockEngine)
+            .cursor_paginate_before(Some("bogus"), 10)
+            .await
+            .unwrap_err();
+        assert_eq!(err.context().field.as_deref(), Some("cursor"));
+
+        let err = ViewFindManyOperation::<MockEngine, TestMaterializedView>::new(MockEngine)
+            .cursor_paginate(None, 10)
+            .await
+            .unwrap_err();
+        assert_eq!(err.context().field.as_deref(), Some("order_by"));
+    }
+
     // ========== ViewFindFirstOperation Tests ==========
 
     #[test]
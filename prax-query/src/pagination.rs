@@ -39,10 +39,60 @@
 //! let with_limit = Pagination::new().take(10);
 //! assert!(!with_limit.is_empty());
 //! ```
+//!
+//! # Keyset Pagination
+//!
+//! `find_many().cursor_paginate(after, first)` returns a [`Page`] with
+//! opaque cursors and [`PageInfo`], fetching one extra row to tell whether
+//! another page follows. Records are positioned by the query's ordering plus
+//! the primary key, so cursors stay stable when ordered columns repeat:
+//!
+//! ```rust,ignore
+//! let page = client
+//!     .user()
+//!     .find_many()
+//!     .order_by(user::created_at::desc())
+//!     .cursor_paginate(None, 20)
+//!     .await?;
+//!
+//! if page.page_info.has_next_page {
+//!     let next = client
+//!         .user()
+//!         .find_many()
+//!         .order_by(user::created_at::desc())
+//!         .cursor_paginate(page.page_info.end_cursor.as_deref(), 20)
+//!         .await?;
+//! }
+//! ```
+//!
+//! Views have no primary key, so `find_many()` on a view positions rows by
+//! the query's ordering plus `View::UNIQUE_KEY`, and needs at least one of
+//! them.
+//!
+//! Cursors are readable by anyone who base64-decodes them. To keep integer
+//! primary keys out of them, give the query an [`IdCodec`]; key values are
+//! then stored as encoded ids:
+//!
+//! ```rust,ignore
+//! let page = client
+//!     .user()
+//!     .find_many()
+//!     .id_codec(Arc::new(Sqids::new()))
+//!     .cursor_paginate(None, 20)
+//!     .await?;
+//! ```
 
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 
+use crate::error::{QueryError, QueryResult};
+use crate::filter::{Filter, FilterValue};
+use crate::id_codec::IdCodec;
+use crate::types::{OrderBy, OrderByField, SortOrder};
+use crate::versioning::json_to_filter_value;
+
 /// Pagination configuration for queries.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Pagination {
@@ -208,6 +258,19 @@ impl Cursor {
             CursorDirection::Before => "<",
         }
     }
+
+    /// The filter selecting records beyond this cursor.
+    pub fn to_filter(&self) -> Filter {
+        let column = self.column.clone().into();
+        let value = match &self.value {
+            CursorValue::Int(i) => FilterValue::Int(*i),
+            CursorValue::String(s) => FilterValue::String(s.clone()),
+        };
+        match self.direction {
+            CursorDirection::After => Filter::Gt(column, value),
+            CursorDirection::Before => Filter::Lt(column, value),
+        }
+    }
 }
 
 /// Cursor value type.
@@ -326,6 +389,254 @@ impl<T> IntoIterator for PaginatedResult<T> {
     }
 }
 
+/// A page of records from keyset pagination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// The records, in query order.
+    pub items: Vec<T>,
+    /// Where this page sits in the result set.
+    pub page_info: PageInfo,
+}
+
+impl<T> Page<T> {
+    /// Get the number of records in this page.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the page is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<T> IntoIterator for Page<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// Position of a [`Page`] in the result set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageInfo {
+    /// Whether there are more records after this page.
+    pub has_next_page: bool,
+    /// Whether there are more records before this page.
+    ///
+    /// When paging forward this is `true` whenever a cursor was given, as
+    /// telling for sure would cost another query; likewise for
+    /// `has_next_page` when paging backward.
+    pub has_previous_page: bool,
+    /// Cursor of the first record, for paging backward.
+    pub start_cursor: Option<String>,
+    /// Cursor of the last record, for paging forward.
+    pub end_cursor: Option<String>,
+}
+
+/// The ordering keyset pagination runs on: `order` followed by any primary
+/// key columns it lacks, so that every record has a distinct position.
+pub fn keyset_order(order: &OrderBy, primary_key: &[&'static str]) -> Vec<OrderByField> {
+    let mut keys = match order {
+        OrderBy::Field(field) => vec![field.clone()],
+        OrderBy::Fields(fields) => fields.to_vec(),
+    };
+    for column in primary_key {
+        if !keys.iter().any(|key| key.column == *column) {
+            keys.push(OrderByField::asc_static(column));
+        }
+    }
+    keys
+}
+
+/// Filter for the records after (or before) the position `values` in the
+/// `keys` ordering.
+///
+/// For keys `(a, b)` ascending this is `a > $1 OR (a = $1 AND b > $2)`.
+pub fn keyset_filter(
+    keys: &[OrderByField],
+    values: &[FilterValue],
+    direction: CursorDirection,
+) -> Filter {
+    Filter::or((0..keys.len().min(values.len())).map(|i| {
+        let ties = keys[..i]
+            .iter()
+            .zip(values)
+            .map(|(key, value)| Filter::Equals(key.column.clone(), value.clone()));
+        let column = keys[i].column.clone();
+        let value = values[i].clone();
+        let beyond = match (keys[i].order, direction) {
+            (SortOrder::Asc, CursorDirection::After)
+            | (SortOrder::Desc, CursorDirection::Before) => Filter::Gt(column, value),
+            _ => Filter::Lt(column, value),
+        };
+        Filter::and(ties.chain(std::iter::once(beyond)))
+    }))
+}
+
+/// The ordering a keyset page is fetched in: `keys`, reversed when paging
+/// backward so that the records nearest the cursor come first.
+pub fn keyset_fetch_order(keys: &[OrderByField], direction: CursorDirection) -> OrderBy {
+    let mut order = keys.to_vec();
+    if direction == CursorDirection::Before {
+        for key in &mut order {
+            key.order = match key.order {
+                SortOrder::Asc => SortOrder::Desc,
+                SortOrder::Desc => SortOrder::Asc,
+            };
+        }
+    }
+    OrderBy::from_fields(order)
+}
+
+/// Build a [`Page`] of `size` records from rows fetched in
+/// [`keyset_fetch_order`] with a limit of `size + 1`.
+///
+/// The extra row tells whether another page follows; backward pages are
+/// restored to query order.
+pub fn keyset_page<T: Serialize>(
+    items: Vec<T>,
+    size: u64,
+    keys: &[OrderByField],
+    cursor: Option<&str>,
+    direction: CursorDirection,
+) -> Page<T> {
+    keyset_page_with(items, size, keys, cursor, direction, None)
+}
+
+/// Like [`keyset_page`], with cursors encoding the `ids` columns.
+pub fn keyset_page_with<T: Serialize>(
+    mut items: Vec<T>,
+    size: u64,
+    keys: &[OrderByField],
+    cursor: Option<&str>,
+    direction: CursorDirection,
+    ids: Option<CursorIds<'_>>,
+) -> Page<T> {
+    let has_more = items.len() as u64 > size;
+    items.truncate(size as usize);
+    if direction == CursorDirection::Before {
+        items.reverse();
+    }
+
+    let (has_next_page, has_previous_page) = match direction {
+        CursorDirection::After => (has_more, cursor.is_some()),
+        CursorDirection::Before => (cursor.is_some(), has_more),
+    };
+    let page_info = PageInfo {
+        has_next_page,
+        has_previous_page,
+        start_cursor: items
+            .first()
+            .map(|item| encode_cursor_with(item, keys, ids)),
+        end_cursor: items.last().map(|item| encode_cursor_with(item, keys, ids)),
+    };
+    Page { items, page_info }
+}
+
+/// Encode a record's position as an opaque cursor.
+///
+/// The cursor holds the record's values for the `keys` columns, read from
+/// its serialized form.
+pub fn encode_cursor<T: Serialize>(record: &T, keys: &[OrderByField]) -> String {
+    encode_cursor_with(record, keys, None)
+}
+
+/// Integer id columns whose values cursors hold encoded by an [`IdCodec`].
+#[derive(Clone, Copy)]
+pub struct CursorIds<'a> {
+    /// The codec encoding the ids.
+    pub codec: &'a dyn IdCodec,
+    /// The id columns, usually the primary key.
+    pub columns: &'a [&'static str],
+}
+
+impl CursorIds<'_> {
+    fn covers(&self, key: &OrderByField) -> bool {
+        self.columns.contains(&key.column.as_ref())
+    }
+}
+
+/// Like [`encode_cursor`], storing non-negative integers of the `ids`
+/// columns as encoded ids.
+pub fn encode_cursor_with<T: Serialize>(
+    record: &T,
+    keys: &[OrderByField],
+    ids: Option<CursorIds<'_>>,
+) -> String {
+    let row = serde_json::to_value(record).unwrap_or_default();
+    let values: Vec<FilterValue> = keys
+        .iter()
+        .map(|key| {
+            let value =
+                json_to_filter_value(row.get(key.column.as_ref()).cloned().unwrap_or_default());
+            match (ids, value) {
+                (Some(ids), FilterValue::Int(id)) if id >= 0 && ids.covers(key) => {
+                    FilterValue::String(ids.codec.encode(id as u64))
+                }
+                (_, value) => value,
+            }
+        })
+        .collect();
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(&values).unwrap_or_default())
+}
+
+/// Decode a cursor from [`encode_cursor`] into its `width` key values.
+pub fn decode_cursor(cursor: &str, width: usize) -> QueryResult<Vec<FilterValue>> {
+    let invalid = || QueryError::invalid_input("cursor", format!("malformed cursor {:?}", cursor));
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let values: Vec<FilterValue> = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+    if values.len() != width {
+        return Err(QueryError::invalid_input(
+            "cursor",
+            format!(
+                "cursor has {} values but the ordering has {} columns",
+                values.len(),
+                width
+            ),
+        )
+        .with_suggestion("Reuse cursors only with the ordering they were issued for"));
+    }
+    Ok(values)
+}
+
+/// Decode a cursor from [`encode_cursor_with`] into its values for `keys`,
+/// decoding the ids of the `ids` columns.
+///
+/// Plain integers in id columns are rejected, so cursors can't be forged
+/// from raw keys.
+pub fn decode_cursor_with(
+    cursor: &str,
+    keys: &[OrderByField],
+    ids: Option<CursorIds<'_>>,
+) -> QueryResult<Vec<FilterValue>> {
+    let mut values = decode_cursor(cursor, keys.len())?;
+    let Some(ids) = ids else {
+        return Ok(values);
+    };
+    for (key, value) in keys.iter().zip(&mut values) {
+        if !ids.covers(key) {
+            continue;
+        }
+        let id = match value {
+            FilterValue::String(encoded) => ids.codec.decode(encoded),
+            _ => None,
+        };
+        match id.and_then(|id| i64::try_from(id).ok()) {
+            Some(id) => *value = FilterValue::Int(id),
+            None => {
+                return Err(QueryError::invalid_input(
+                    "cursor",
+                    format!("malformed cursor {:?}", cursor),
+                ));
+            }
+        }
+    }
+    Ok(values)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -366,4 +677,94 @@ mod tests {
         assert!(!result.has_previous);
         assert_eq!(result.total_count, Some(100));
     }
+
+    #[test]
+    fn test_keyset_order() {
+        let keys = keyset_order(&OrderByField::desc("created_at").into(), &["id"]);
+        assert_eq!(
+            keys,
+            [OrderByField::desc("created_at"), OrderByField::asc("id")]
+        );
+
+        let keys = keyset_order(&OrderByField::desc("id").into(), &["id"]);
+        assert_eq!(keys, [OrderByField::desc("id")]);
+    }
+
+    #[test]
+    fn test_keyset_filter() {
+        let keys = [OrderByField::desc("created_at"), OrderByField::asc("id")];
+        let values = [
+            FilterValue::String("2024-01-01".into()),
+            FilterValue::Int(7),
+        ];
+
+        let (sql, params) = keyset_filter(&keys, &values, CursorDirection::After).to_sql(0);
+        assert_eq!(sql, "(created_at < $1 OR (created_at = $2 AND id > $3))");
+        assert_eq!(params.len(), 3);
+
+        let (sql, _) = keyset_filter(&keys, &values, CursorDirection::Before).to_sql(0);
+        assert_eq!(sql, "(created_at > $1 OR (created_at = $2 AND id < $3))");
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        #[derive(Serialize)]
+        struct Post {
+            id: i64,
+            title: String,
+            score: f64,
+        }
+
+        let keys = [OrderByField::desc("score"), OrderByField::asc("id")];
+        let post = Post {
+            id: 3,
+            title: "Hello".into(),
+            score: 1.5,
+        };
+        let cursor = encode_cursor(&post, &keys);
+        assert!(
+            cursor
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+        assert_eq!(
+            decode_cursor(&cursor, 2).unwrap(),
+            [FilterValue::Float(1.5), FilterValue::Int(3)]
+        );
+
+        assert!(decode_cursor(&cursor, 1).is_err());
+        assert!(decode_cursor("not a cursor", 2).is_err());
+    }
+
+    #[test]
+    fn test_cursor_ids() {
+        use crate::id_codec::Sqids;
+
+        #[derive(Serialize)]
+        struct Post {
+            id: i64,
+            score: f64,
+        }
+
+        let codec = Sqids::new();
+        let ids = Some(CursorIds {
+            codec: &codec,
+            columns: &["id"],
+        });
+        let keys = [OrderByField::desc("score"), OrderByField::asc("id")];
+        let post = Post { id: 3, score: 1.5 };
+
+        let cursor = encode_cursor_with(&post, &keys, ids);
+        let raw = decode_cursor(&cursor, 2).unwrap();
+        assert_eq!(raw[1], FilterValue::String(codec.encode(3)));
+        assert_eq!(
+            decode_cursor_with(&cursor, &keys, ids).unwrap(),
+            [FilterValue::Float(1.5), FilterValue::Int(3)]
+        );
+
+        // Cursors with raw keys are rejected
+        let plain = encode_cursor(&post, &keys);
+        let err = decode_cursor_with(&plain, &keys, ids).unwrap_err();
+        assert_eq!(err.context().field.as_deref(), Some("cursor"));
+    }
 }
@@ -0,0 +1,841 @@
+//! Event sourcing projections.
+//!
+//! A [`Projector`] folds an ordered stream of events into a read model. A
+//! [`ProjectionRunner`] feeds it events in batches from an [`EventSource`]
+//! (an events table, or any change data capture stream) and remembers how
+//! far each projection got in a checkpoint table.
+//!
+//! Projectors don't write directly: they stage operations built with the
+//! generated client into [`ProjectionWrites`]. The runner commits the staged
+//! writes of a batch and the checkpoint advance in one transaction, so each
+//! event is applied exactly once per checkpoint. A crash mid-batch rolls
+//! everything back, and the batch is projected again on the next run.
+//!
+//! ```rust,ignore
+//! use prax_query::projection::{Event, EventTable, ProjectionRunner, ProjectionWrites, Projector};
+//! use prax_query::traits::BoxFuture;
+//! use prax_query::QueryResult;
+//!
+//! struct OrderTotals {
+//!     client: PraxClient,
+//! }
+//!
+//! impl Projector for OrderTotals {
+//!     fn name(&self) -> &str {
+//!         "order_totals"
+//!     }
+//!
+//!     fn apply<'a>(
+//!         &'a self,
+//!         event: &'a Event,
+//!         writes: &'a mut ProjectionWrites,
+//!     ) -> BoxFuture<'a, QueryResult<()>> {
+//!         Box::pin(async move {
+//!             if event.event_type() == Some("OrderPlaced") {
+//!                 let placed: OrderPlaced = event.payload()?;
+//!                 writes.push(
+//!                     &self.client
+//!                         .order_total()
+//!                         .upsert(order_total::customer_id::equals(placed.customer_id))
+//!                         .create(/* ... */)
+//!                         .update(/* ... */),
+//!                 );
+//!             }
+//!             Ok(())
+//!         })
+//!     }
+//!
+//!     fn reset(&self, writes: &mut ProjectionWrites) {
+//!         writes.push(&self.client.order_total().delete_many(vec![]));
+//!     }
+//! }
+//!
+//! let events = EventTable::new(engine.clone(), "events").type_column("kind");
+//! let runner = ProjectionRunner::new(engine, events);
+//!
+//! // Project everything after the last checkpoint
+//! runner.run(&totals).await?;
+//!
+//! // Rebuild the read model from the first event
+//! runner.replay(&totals).await?;
+//! ```
+//!
+//! Checkpoints are guarded: if another runner advanced the checkpoint of a
+//! projection in the meantime, the batch fails and is rolled back instead of
+//! being applied twice.
+
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value as JsonValue};
+use tracing::{debug, info, warn};
+
+use crate::error::{QueryError, QueryResult};
+use crate::filter::FilterValue;
+use crate::operations::{
+    CreateManyOperation, CreateOperation, DeleteManyOperation, DeleteOperation,
+    UpdateManyOperation, UpdateOperation, UpsertOperation,
+};
+use crate::raw::RawDatabase;
+use crate::sql::{DatabaseType, quote_identifier};
+use crate::traits::{BoxFuture, Model, QueryEngine};
+
+/// Default table holding projection checkpoints.
+pub const DEFAULT_CHECKPOINT_TABLE: &str = "_prax_projections";
+
+/// Default number of events projected per transaction.
+pub const DEFAULT_PROJECTION_BATCH_SIZE: usize = 500;
+
+/// An event read from an [`EventSource`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Position of the event in its stream, increasing with every event.
+    pub position: i64,
+    /// Type of the event, if the source records one.
+    pub event_type: Option<String>,
+    /// The event's columns.
+    pub values: Map<String, JsonValue>,
+}
+
+impl Event {
+    /// Create an event.
+    pub fn new(position: i64, values: Map<String, JsonValue>) -> Self {
+        Self {
+            position,
+            event_type: None,
+            values,
+        }
+    }
+
+    /// Set the event type.
+    pub fn with_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_type = Some(event_type.into());
+        self
+    }
+
+    /// Get the event type.
+    pub fn event_type(&self) -> Option<&str> {
+        self.event_type.as_deref()
+    }
+
+    /// Get a column of the event.
+    pub fn get(&self, column: &str) -> Option<&JsonValue> {
+        self.values.get(column)
+    }
+
+    /// Deserialize the event's columns.
+    pub fn payload<T: DeserializeOwned>(&self) -> QueryResult<T> {
+        serde_json::from_value(JsonValue::Object(self.values.clone()))
+            .map_err(|e| QueryError::deserialization(e.to_string()))
+    }
+}
+
+/// An ordered stream of events.
+///
+/// Implement this for change data capture streams; [`EventTable`] reads an
+/// events model.
+pub trait EventSource: Send + Sync {
+    /// Read up to `limit` events after `position`, in position order.
+    fn read_after(&self, position: i64, limit: usize) -> BoxFuture<'_, QueryResult<Vec<Event>>>;
+}
+
+/// Events stored in a table, ordered by a monotonic position column.
+#[derive(Debug, Clone)]
+pub struct EventTable<D> {
+    db: D,
+    table: String,
+    position_column: String,
+    type_column: Option<String>,
+}
+
+impl<D: RawDatabase> EventTable<D> {
+    /// Read events from a table, ordered by its `id` column.
+    pub fn new(db: D, table: impl Into<String>) -> Self {
+        Self {
+            db,
+            table: table.into(),
+            position_column: "id".to_string(),
+            type_column: None,
+        }
+    }
+
+    /// Set the column events are ordered by.
+    pub fn position_column(mut self, column: impl Into<String>) -> Self {
+        self.position_column = column.into();
+        self
+    }
+
+    /// Set the column holding the event type.
+    pub fn type_column(mut self, column: impl Into<String>) -> Self {
+        self.type_column = Some(column.into());
+        self
+    }
+
+    /// Build the query reading a batch of events.
+    pub fn read_sql(&self, limit: usize) -> String {
+        let db_type = self.db.database_type();
+        let table = quote_identifier(&self.table);
+        let position = quote_identifier(&self.position_column);
+        let param = db_type.placeholder_string(1);
+
+        match db_type {
+            DatabaseType::MSSQL => format!(
+                "SELECT TOP ({}) * FROM {} WHERE {} > {} ORDER BY {}",
+                limit, table, position, param, position
+            ),
+            _ => format!(
+                "SELECT * FROM {} WHERE {} > {} ORDER BY {} LIMIT {}",
+                table, position, param, position, limit
+            ),
+        }
+    }
+
+    fn event_from_row(&self, row: JsonValue) -> QueryResult<Event> {
+        let JsonValue::Object(values) = row else {
+            return Err(QueryError::deserialization(
+                "event row is not a JSON object",
+            ));
+        };
+
+        let position = values
+            .get(&self.position_column)
+            .and_then(|v| {
+                v.as_i64()
+                    .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+            })
+            .ok_or_else(|| {
+                QueryError::deserialization(format!(
+                    "event row has no valid `{}` column",
+                    self.position_column
+                ))
+            })?;
+        let event_type = self
+            .type_column
+            .as_ref()
+            .and_then(|column| values.get(column))
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        Ok(Event {
+            position,
+            event_type,
+            values,
+        })
+    }
+}
+
+impl<D: RawDatabase> EventSource for EventTable<D> {
+    fn read_after(&self, position: i64, limit: usize) -> BoxFuture<'_, QueryResult<Vec<Event>>> {
+        Box::pin(async move {
+            let rows = self
+                .db
+                .query(&self.read_sql(limit), vec![FilterValue::Int(position)])
+                .await
+                .map_err(|e| e.with_context(format!("reading events from `{}`", self.table)))?;
+
+            let mut events = Vec::with_capacity(rows.len());
+            for row in rows {
+                events.push(self.event_from_row(row)?);
+            }
+            Ok(events)
+        })
+    }
+}
+
+/// An operation that can be staged as a projection write.
+pub trait StagedWrite {
+    /// Build the SQL of the write.
+    fn build_sql(&self) -> (String, Vec<FilterValue>);
+}
+
+macro_rules! impl_staged_write {
+    ($($op:ident),*) => {
+        $(
+            impl<E: QueryEngine, M: Model> StagedWrite for $op<E, M> {
+                fn build_sql(&self) -> (String, Vec<FilterValue>) {
+                    $op::build_sql(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_staged_write!(
+    CreateOperation,
+    CreateManyOperation,
+    UpdateOperation,
+    UpdateManyOperation,
+    DeleteOperation,
+    DeleteManyOperation,
+    UpsertOperation
+);
+
+/// Read model writes staged by a projector, committed with the checkpoint.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectionWrites {
+    statements: Vec<(String, Vec<FilterValue>)>,
+}
+
+impl ProjectionWrites {
+    /// Create an empty set of writes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stage an operation built with the generated client.
+    pub fn push(&mut self, write: &impl StagedWrite) -> &mut Self {
+        self.statements.push(write.build_sql());
+        self
+    }
+
+    /// Stage a raw SQL statement.
+    pub fn raw(&mut self, sql: impl Into<String>, params: Vec<FilterValue>) -> &mut Self {
+        self.statements.push((sql.into(), params));
+        self
+    }
+
+    /// Get the staged statements.
+    pub fn statements(&self) -> &[(String, Vec<FilterValue>)] {
+        &self.statements
+    }
+
+    /// Get the number of staged statements.
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// Check if nothing is staged.
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Take the staged statements.
+    pub fn into_statements(self) -> Vec<(String, Vec<FilterValue>)> {
+        self.statements
+    }
+}
+
+/// Builds a read model from events.
+pub trait Projector: Send + Sync {
+    /// Name of the projection, used as its checkpoint key.
+    fn name(&self) -> &str;
+
+    /// Stage the writes for one event.
+    ///
+    /// Events the projection doesn't care about are skipped by staging
+    /// nothing; the checkpoint still moves past them.
+    fn apply<'a>(
+        &'a self,
+        event: &'a Event,
+        writes: &'a mut ProjectionWrites,
+    ) -> BoxFuture<'a, QueryResult<()>>;
+
+    /// Stage the writes clearing the read model before a replay.
+    fn reset(&self, writes: &mut ProjectionWrites) {
+        let _ = writes;
+    }
+}
+
+/// Outcome of a projection run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectionReport {
+    /// Projection name.
+    pub projection: String,
+    /// Whether the read model was reset and rebuilt from the first event.
+    pub replayed: bool,
+    /// Checkpoint position before the run.
+    pub from_position: i64,
+    /// Checkpoint position after the run.
+    pub position: i64,
+    /// Events projected.
+    pub events: u64,
+    /// Transactions committed.
+    pub batches: u64,
+    /// Time spent.
+    pub elapsed: Duration,
+}
+
+/// Runs projectors against an event source, checkpointing their progress.
+///
+/// The checkpoint table is created on first use.
+pub struct ProjectionRunner<D, S> {
+    db: D,
+    source: S,
+    checkpoint_table: String,
+    batch_size: usize,
+}
+
+impl<D: RawDatabase, S: EventSource> ProjectionRunner<D, S> {
+    /// Create a runner.
+    pub fn new(db: D, source: S) -> Self {
+        Self {
+            db,
+            source,
+            checkpoint_table: DEFAULT_CHECKPOINT_TABLE.to_string(),
+            batch_size: DEFAULT_PROJECTION_BATCH_SIZE,
+        }
+    }
+
+    /// Set the number of events projected per transaction (at least 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set the table holding checkpoints.
+    pub fn with_checkpoint_table(mut self, table: impl Into<String>) -> Self {
+        self.checkpoint_table = table.into();
+        self
+    }
+
+    /// Get the database.
+    pub fn database(&self) -> &D {
+        &self.db
+    }
+
+    /// Get the event source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Get the checkpoint position of a projection, `0` if it never ran.
+    pub async fn position(&self, projector: &dyn Projector) -> QueryResult<i64> {
+        let table = quote_identifier(&self.checkpoint_table);
+        let sql = format!(
+            "SELECT position FROM {} WHERE name = {}",
+            table,
+            self.db.database_type().placeholder_string(1)
+        );
+        let rows = self
+            .db
+            .query(&sql, vec![projector.name().into()])
+            .await
+            .map_err(|e| e.with_context(format!("loading checkpoint of `{}`", projector.name())))?;
+
+        let position = rows.first().and_then(|row| {
+            let value = row
+                .get("position")
+                .or_else(|| row.as_object()?.values().next())?;
+            value
+                .as_i64()
+                .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+        });
+        Ok(position.unwrap_or(0))
+    }
+
+    /// Project every event after the checkpoint.
+    pub async fn run(&self, projector: &dyn Projector) -> QueryResult<ProjectionReport> {
+        let start = Instant::now();
+        self.ensure_checkpoint(projector).await?;
+        let mut position = self.position(projector).await?;
+        let mut report = ProjectionReport {
+            projection: projector.name().to_string(),
+            from_position: position,
+            position,
+            ..Default::default()
+        };
+
+        loop {
+            let events = self.source.read_after(position, self.batch_size).await?;
+            let Some(last) = events.iter().map(|e| e.position).max() else {
+                break;
+            };
+
+            let mut writes = ProjectionWrites::new();
+            for event in &events {
+                projector.apply(event, &mut writes).await.map_err(|e| {
+                    e.with_context(format!(
+                        "projecting event {} into `{}`",
+                        event.position,
+                        projector.name()
+                    ))
+                })?;
+            }
+
+            // The checkpoint goes first, locking it for the rest of the batch
+            let mut statements = vec![self.advance_checkpoint(projector, position, last)];
+            statements.extend(writes.into_statements());
+            self.db
+                .execute_in_transaction(statements)
+                .await
+                .map_err(|e| {
+                    e.with_context(format!(
+                        "committing events {}..={} of `{}`",
+                        position + 1,
+                        last,
+                        projector.name()
+                    ))
+                })?;
+
+            debug!(projection = %projector.name(), position = last, events = events.len(), "Projected batch");
+            position = last;
+            report.position = last;
+            report.events += events.len() as u64;
+            report.batches += 1;
+
+            if events.len() < self.batch_size {
+                break;
+            }
+        }
+
+        report.elapsed = start.elapsed();
+        if report.events > 0 {
+            info!(
+                projection = %report.projection,
+                events = report.events,
+                position = report.position,
+                "Projection caught up"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Reset the read model and the checkpoint, then project every event.
+    ///
+    /// The reset writes and the checkpoint reset commit together.
+    pub async fn replay(&self, projector: &dyn Projector) -> QueryResult<ProjectionReport> {
+        self.ensure_checkpoint(projector).await?;
+
+        let mut writes = ProjectionWrites::new();
+        projector.reset(&mut writes);
+        let mut statements = vec![self.reset_checkpoint(projector)];
+        statements.extend(writes.into_statements());
+        self.db
+            .execute_in_transaction(statements)
+            .await
+            .map_err(|e| e.with_context(format!("resetting projection `{}`", projector.name())))?;
+        info!(projection = %projector.name(), "Replaying projection");
+
+        let mut report = self.run(projector).await?;
+        report.replayed = true;
+        Ok(report)
+    }
+
+    /// Run several projectors one after another.
+    pub async fn run_all(
+        &self,
+        projectors: &[Box<dyn Projector>],
+    ) -> QueryResult<Vec<ProjectionReport>> {
+        let mut reports = Vec::with_capacity(projectors.len());
+        for projector in projectors {
+            reports.push(self.run(projector.as_ref()).await?);
+        }
+        Ok(reports)
+    }
+
+    /// Poll for new events every `interval` in a background task.
+    pub fn spawn(
+        self,
+        projectors: Vec<Box<dyn Projector>>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        D: 'static,
+        S: 'static,
+    {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_all(&projectors).await {
+                    warn!(error = %e, "Projection run failed");
+                }
+            }
+        })
+    }
+
+    /// Build the statement creating the checkpoint table.
+    pub fn create_checkpoint_table_sql(&self) -> String {
+        let table = quote_identifier(&self.checkpoint_table);
+        match self.db.database_type() {
+            DatabaseType::MSSQL => format!(
+                "IF OBJECT_ID(N'{}', N'U') IS NULL CREATE TABLE {} (\
+                 name NVARCHAR(255) NOT NULL PRIMARY KEY, \
+                 position BIGINT NOT NULL, \
+                 updated_at DATETIME2 NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+                self.checkpoint_table, table
+            ),
+            _ => format!(
+                "CREATE TABLE IF NOT EXISTS {} (\
+                 name VARCHAR(255) NOT NULL PRIMARY KEY, \
+                 position BIGINT NOT NULL, \
+                 updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+                table
+            ),
+        }
+    }
+
+    /// Create the checkpoint table and the projection's checkpoint row.
+    async fn ensure_checkpoint(&self, projector: &dyn Projector) -> QueryResult<()> {
+        let db_type = self.db.database_type();
+        let table = quote_identifier(&self.checkpoint_table);
+        let param = db_type.placeholder_string(1);
+        let insert = match db_type {
+            DatabaseType::PostgreSQL | DatabaseType::SQLite => format!(
+                "INSERT INTO {} (name, position) VALUES ({}, 0) ON CONFLICT (name) DO NOTHING",
+                table, param
+            ),
+            DatabaseType::MySQL => format!(
+                "INSERT IGNORE INTO {} (name, position) VALUES ({}, 0)",
+                table, param
+            ),
+            DatabaseType::MSSQL => format!(
+                "IF NOT EXISTS (SELECT 1 FROM {} WHERE name = {}) \
+                 INSERT INTO {} (name, position) VALUES ({}, 0)",
+                table, param, table, param
+            ),
+        };
+
+        self.db
+            .execute_in_transaction(vec![
+                (self.create_checkpoint_table_sql(), Vec::new()),
+                (insert, vec![projector.name().into()]),
+            ])
+            .await
+            .map_err(|e| {
+                e.with_context(format!("creating checkpoint of `{}`", projector.name()))
+            })?;
+        Ok(())
+    }
+
+    /// Move the checkpoint from `from` to `to`.
+    ///
+    /// If the checkpoint is no longer at `from`, the update sets it to NULL,
+    /// violating its NOT NULL constraint and failing the transaction.
+    fn advance_checkpoint(
+        &self,
+        projector: &dyn Projector,
+        from: i64,
+        to: i64,
+    ) -> (String, Vec<FilterValue>) {
+        let db_type = self.db.database_type();
+        let sql = format!(
+            "UPDATE {} SET position = CASE WHEN position = {} THEN {} END, \
+             updated_at = CURRENT_TIMESTAMP WHERE name = {}",
+            quote_identifier(&self.checkpoint_table),
+            db_type.placeholder_string(1),
+            db_type.placeholder_string(2),
+            db_type.placeholder_string(3)
+        );
+        (
+            sql,
+            vec![
+                FilterValue::Int(from),
+                FilterValue::Int(to),
+                projector.name().into(),
+            ],
+        )
+    }
+
+    fn reset_checkpoint(&self, projector: &dyn Projector) -> (String, Vec<FilterValue>) {
+        let sql = format!(
+            "UPDATE {} SET position = 0, updated_at = CURRENT_TIMESTAMP WHERE name = {}",
+            quote_identifier(&self.checkpoint_table),
+            self.db.database_type().placeholder_string(1)
+        );
+        (sql, vec![projector.name().into()])
+    }
+}
+
+impl<D, S> std::fmt::Debug for ProjectionRunner<D, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProjectionRunner")
+            .field("checkpoint_table", &self.checkpoint_table)
+            .field("batch_size", &self.batch_size)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Keeps checkpoints in memory and records committed statements.
+    #[derive(Default)]
+    struct FakeDb {
+        checkpoint: Mutex<Option<i64>>,
+        committed: Mutex<Vec<String>>,
+        fail_on: Option<&'static str>,
+    }
+
+    impl RawDatabase for Arc<FakeDb> {
+        fn query(
+            &self,
+            sql: &str,
+            params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>> {
+            let rows = if sql.contains("FROM events") {
+                let FilterValue::Int(after) = params[0] else {
+                    panic!("expected a position");
+                };
+                let limit: i64 = sql.rsplit("LIMIT ").next().unwrap().parse().unwrap();
+                (after + 1..=5)
+                    .take(limit as usize)
+                    .map(|id| json!({"id": id, "kind": "Deposited", "amount": id * 10}))
+                    .collect()
+            } else {
+                let position = *self.checkpoint.lock();
+                position
+                    .map(|p| vec![json!({"position": p})])
+                    .unwrap_or_default()
+            };
+            Box::pin(async move { Ok(rows) })
+        }
+
+        fn execute(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<u64>> {
+            unreachable!("projections write in transactions")
+        }
+
+        fn execute_in_transaction(
+            &self,
+            statements: Vec<(String, Vec<FilterValue>)>,
+        ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+            Box::pin(async move {
+                let fails = self
+                    .fail_on
+                    .is_some_and(|f| statements.iter().any(|(sql, _)| sql.contains(f)));
+                if fails {
+                    return Err(QueryError::database("connection reset"));
+                }
+
+                let mut checkpoint = self.checkpoint.lock();
+                for (sql, params) in &statements {
+                    if sql.starts_with("INSERT INTO _prax_projections") {
+                        checkpoint.get_or_insert(0);
+                    } else if sql.contains("CASE WHEN position") {
+                        let (FilterValue::Int(from), FilterValue::Int(to)) =
+                            (&params[0], &params[1])
+                        else {
+                            panic!("expected positions");
+                        };
+                        assert_eq!(*checkpoint, Some(*from));
+                        *checkpoint = Some(*to);
+                    } else if sql.contains("SET position = 0") {
+                        *checkpoint = Some(0);
+                    }
+                }
+                self.committed
+                    .lock()
+                    .extend(statements.iter().map(|(sql, _)| sql.clone()));
+                Ok(vec![1; statements.len()])
+            })
+        }
+    }
+
+    struct Balances;
+
+    impl Projector for Balances {
+        fn name(&self) -> &str {
+            "balances"
+        }
+
+        fn apply<'a>(
+            &'a self,
+            event: &'a Event,
+            writes: &'a mut ProjectionWrites,
+        ) -> BoxFuture<'a, QueryResult<()>> {
+            Box::pin(async move {
+                if event.event_type() == Some("Deposited") {
+                    let amount = event.get("amount").and_then(|v| v.as_i64()).unwrap();
+                    writes.raw(
+                        "UPDATE balances SET total = total + $1",
+                        vec![FilterValue::Int(amount)],
+                    );
+                }
+                Ok(())
+            })
+        }
+
+        fn reset(&self, writes: &mut ProjectionWrites) {
+            writes.raw("DELETE FROM balances", Vec::new());
+        }
+    }
+
+    fn runner(db: &Arc<FakeDb>) -> ProjectionRunner<Arc<FakeDb>, EventTable<Arc<FakeDb>>> {
+        let events = EventTable::new(db.clone(), "events").type_column("kind");
+        ProjectionRunner::new(db.clone(), events).with_batch_size(2)
+    }
+
+    #[test]
+    fn test_event_table_read_sql() {
+        let events = EventTable::new(Arc::new(FakeDb::default()), "events").position_column("seq");
+        assert_eq!(
+            events.read_sql(100),
+            "SELECT * FROM events WHERE seq > $1 ORDER BY seq LIMIT 100"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_checkpoints_each_batch() {
+        let db = Arc::new(FakeDb::default());
+        let runner = runner(&db);
+
+        let report = runner.run(&Balances).await.unwrap();
+        assert_eq!(report.from_position, 0);
+        assert_eq!(report.position, 5);
+        assert_eq!(report.events, 5);
+        assert_eq!(report.batches, 3);
+        assert_eq!(runner.position(&Balances).await.unwrap(), 5);
+
+        let committed = db.committed.lock().clone();
+        // Every batch starts with its checkpoint advance
+        let batches: Vec<_> = committed
+            .iter()
+            .filter(|sql| sql.contains("CASE WHEN position"))
+            .collect();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(
+            committed
+                .iter()
+                .filter(|sql| sql.starts_with("UPDATE balances"))
+                .count(),
+            5
+        );
+
+        // Nothing new to project
+        let report = runner.run(&Balances).await.unwrap();
+        assert_eq!(report.events, 0);
+        assert_eq!(report.position, 5);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_keeps_checkpoint() {
+        let db = Arc::new(FakeDb {
+            fail_on: Some("UPDATE balances"),
+            ..Default::default()
+        });
+        let runner = runner(&db);
+
+        let err = runner.run(&Balances).await.unwrap_err();
+        assert_eq!(
+            err.context().operation.as_deref(),
+            Some("committing events 1..=2 of `balances`")
+        );
+        assert_eq!(runner.position(&Balances).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_resets_read_model() {
+        let db = Arc::new(FakeDb::default());
+        let runner = runner(&db);
+        runner.run(&Balances).await.unwrap();
+
+        let report = runner.replay(&Balances).await.unwrap();
+        assert!(report.replayed);
+        assert_eq!(report.from_position, 0);
+        assert_eq!(report.events, 5);
+        assert!(
+            db.committed
+                .lock()
+                .iter()
+                .any(|sql| sql == "DELETE FROM balances")
+        );
+    }
+}
@@ -42,6 +42,11 @@ impl<E: QueryEngine, M: Model> QueryBuilder<E, M> {
         CreateOperation::new(self.engine.clone())
     }
 
+    /// Start a create_many operation.
+    pub fn create_many(&self) -> CreateManyOperation<E, M> {
+        CreateManyOperation::new(self.engine.clone())
+    }
+
     /// Start an update operation.
     pub fn update(&self) -> UpdateOperation<E, M> {
         UpdateOperation::new(self.engine.clone())
@@ -0,0 +1,462 @@
+//! JSONL query log for offline analysis (`diagnostics` feature).
+//!
+//! [`QueryLogMiddleware`] appends a [`QueryLogRecord`] for every executed
+//! query to a file, one JSON object per line: the statement's
+//! [fingerprint] and normalized text, its duration and rows, and the query
+//! tags and tenant it ran for. Parameter values are never written.
+//!
+//! ```rust,ignore
+//! use prax_query::query_log::QueryLogMiddleware;
+//!
+//! let log = QueryLogMiddleware::new("logs/queries.jsonl")
+//!     .max_bytes(100 * 1024 * 1024)
+//!     .max_files(10);
+//! let client = client.with_middleware(log);
+//! ```
+//!
+//! Once the file would grow past `max_bytes` it is rotated to
+//! `queries.jsonl.1`, pushing older files to `.2`, `.3` and so on, and the
+//! oldest beyond `max_files` is deleted.
+//!
+//! `prax analyze-log logs/queries.jsonl*` aggregates the files into a
+//! latency and frequency report with [`summarize`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostics::{fingerprint, normalize_statement};
+use crate::middleware::{
+    BoxFuture, Middleware, MiddlewareResult, Next, QueryContext, QueryResponse,
+};
+
+/// Default size from which the log file is rotated.
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Default number of rotated files kept.
+pub const DEFAULT_MAX_FILES: usize = 5;
+
+/// One executed query in the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryLogRecord {
+    /// When the query finished, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// See [`fingerprint`].
+    pub fingerprint: String,
+    /// Normalized statement text.
+    pub query: String,
+    /// Kind of statement, such as `SELECT`.
+    pub query_type: String,
+    /// Model the query was issued for, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Operation, such as `findMany`, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// Time in milliseconds.
+    pub duration_ms: f64,
+    /// Rows returned or affected, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+    /// Whether the query succeeded.
+    pub success: bool,
+    /// Whether the response was served from the cache.
+    #[serde(default)]
+    pub from_cache: bool,
+    /// Cost attribution tags (see [`crate::tags`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    /// Tenant the query ran for, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+impl QueryLogRecord {
+    /// Read the records of a log file.
+    ///
+    /// Lines that are not records, such as a last line cut short by a crash,
+    /// are skipped.
+    pub fn read_log(path: impl AsRef<Path>) -> std::io::Result<Vec<Self>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut records = Vec::new();
+        for line in reader.lines() {
+            if let Ok(record) = serde_json::from_str(&line?) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// The tags in their `key:value` form.
+    pub fn tag_labels(&self) -> impl Iterator<Item = String> + '_ {
+        self.tags.iter().map(|(key, value)| {
+            if value.is_empty() {
+                key.clone()
+            } else {
+                format!("{}:{}", key, value)
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+/// Middleware writing a [`QueryLogRecord`] per query to a rotated JSONL
+/// file.
+///
+/// Clones share the file. A record that cannot be written is reported with
+/// `tracing` and does not fail the query.
+#[derive(Debug, Clone)]
+pub struct QueryLogMiddleware {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Arc<Mutex<Option<LogFile>>>,
+}
+
+impl QueryLogMiddleware {
+    /// Log to `path`, rotating at [`DEFAULT_MAX_BYTES`] and keeping
+    /// [`DEFAULT_MAX_FILES`] rotated files.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+            file: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Rotate the file once it would grow past `bytes`.
+    pub fn max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = bytes.max(1);
+        self
+    }
+
+    /// Keep `n` rotated files; with 0 the log starts over when rotated.
+    pub fn max_files(mut self, n: usize) -> Self {
+        self.max_files = n;
+        self
+    }
+
+    /// Get the path of the current log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record to the log.
+    pub fn write(&self, record: &QueryLogRecord) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut guard = self.file.lock();
+        if guard
+            .as_ref()
+            .is_some_and(|log| log.size > 0 && log.size + line.len() as u64 > self.max_bytes)
+        {
+            *guard = None;
+            self.rotate()?;
+        }
+        if guard.is_none() {
+            *guard = Some(self.open()?);
+        }
+        if let Some(log) = guard.as_mut() {
+            log.file.write_all(&line)?;
+            log.size += line.len() as u64;
+        }
+        Ok(())
+    }
+
+    fn open(&self) -> std::io::Result<LogFile> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let size = file.metadata()?.len();
+        Ok(LogFile { file, size })
+    }
+
+    fn rotate(&self) -> std::io::Result<()> {
+        if self.max_files == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        let _ = std::fs::remove_file(self.rotated(self.max_files));
+        for n in (1..self.max_files).rev() {
+            let from = self.rotated(n);
+            if from.exists() {
+                std::fs::rename(from, self.rotated(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated(1))
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+}
+
+impl Middleware for QueryLogMiddleware {
+    fn handle<'a>(
+        &'a self,
+        ctx: QueryContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, MiddlewareResult<QueryResponse>> {
+        Box::pin(async move {
+            let sql = ctx.sql().to_string();
+            let query_type = ctx.query_type();
+            let metadata = ctx.metadata();
+            let model = metadata.model.clone();
+            let operation = metadata.operation.clone();
+            let tenant = metadata.tenant_id.clone();
+            let tags: BTreeMap<String, String> = metadata
+                .query_tags
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            let start = Instant::now();
+
+            let result = next.run(ctx).await;
+
+            let duration_ms = start.elapsed().as_micros() as f64 / 1000.0;
+            let response = result.as_ref().ok();
+            let record = QueryLogRecord {
+                timestamp_ms: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_millis() as u64),
+                fingerprint: fingerprint(&sql),
+                query: normalize_statement(&sql),
+                query_type: query_type.as_str().to_string(),
+                model,
+                operation,
+                duration_ms,
+                rows: response.and_then(|r| {
+                    r.rows_affected
+                        .or_else(|| r.data.as_array().map(|rows| rows.len() as u64))
+                }),
+                success: result.is_ok(),
+                from_cache: response.is_some_and(|r| r.from_cache),
+                tags,
+                tenant,
+            };
+            if let Err(error) = self.write(&record) {
+                tracing::warn!(
+                    target: "prax::query",
+                    path = %self.path.display(),
+                    error = %error,
+                    "Failed to write query log"
+                );
+            }
+            result
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "QueryLogMiddleware"
+    }
+}
+
+/// What [`summarize`] groups records by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogGrouping {
+    /// Statement fingerprint.
+    #[default]
+    Query,
+    /// Model.
+    Model,
+    /// Tenant.
+    Tenant,
+    /// Query tag; a record counts once for each of its tags.
+    Tag,
+}
+
+/// Latency and frequency of one group of logged queries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogSummary {
+    /// Fingerprint, model, tenant or tag; empty for records without one.
+    pub key: String,
+    /// Normalized statement text, when grouped by query.
+    pub query: Option<String>,
+    /// Model of the first record, when grouped by query.
+    pub model: Option<String>,
+    /// Executions.
+    pub calls: u64,
+    /// Executions that failed.
+    pub errors: u64,
+    /// Rows returned or affected.
+    pub rows: u64,
+    /// Total time in milliseconds.
+    pub total_ms: f64,
+    /// Mean time in milliseconds.
+    pub mean_ms: f64,
+    /// Median time in milliseconds.
+    pub p50_ms: f64,
+    /// 95th percentile time in milliseconds.
+    pub p95_ms: f64,
+    /// 99th percentile time in milliseconds.
+    pub p99_ms: f64,
+    /// Slowest execution in milliseconds.
+    pub max_ms: f64,
+}
+
+/// Aggregate log records by `by`, most total time first.
+///
+/// Records served from the cache never reached the database and are left
+/// out.
+pub fn summarize(records: &[QueryLogRecord], by: LogGrouping) -> Vec<LogSummary> {
+    let mut groups: HashMap<String, Vec<&QueryLogRecord>> = HashMap::new();
+    for record in records.iter().filter(|r| !r.from_cache) {
+        let keys: Vec<String> = match by {
+            LogGrouping::Query => vec![record.fingerprint.clone()],
+            LogGrouping::Model => vec![record.model.clone().unwrap_or_default()],
+            LogGrouping::Tenant => vec![record.tenant.clone().unwrap_or_default()],
+            LogGrouping::Tag => record.tag_labels().collect(),
+        };
+        for key in keys {
+            groups.entry(key).or_default().push(record);
+        }
+    }
+
+    let mut summaries: Vec<LogSummary> = groups
+        .into_iter()
+        .map(|(key, records)| {
+            let mut durations: Vec<f64> = records.iter().map(|r| r.duration_ms).collect();
+            durations.sort_by(f64::total_cmp);
+            let total_ms: f64 = durations.iter().sum();
+            let first = records[0];
+            LogSummary {
+                query: (by == LogGrouping::Query).then(|| first.query.clone()),
+                model: (by == LogGrouping::Query)
+                    .then(|| records.iter().find_map(|r| r.model.clone()))
+                    .flatten(),
+                key,
+                calls: records.len() as u64,
+                errors: records.iter().filter(|r| !r.success).count() as u64,
+                rows: records.iter().filter_map(|r| r.rows).sum(),
+                total_ms,
+                mean_ms: total_ms / durations.len() as f64,
+                p50_ms: percentile(&durations, 0.50),
+                p95_ms: percentile(&durations, 0.95),
+                p99_ms: percentile(&durations, 0.99),
+                max_ms: durations[durations.len() - 1],
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms).then(a.key.cmp(&b.key)));
+    summaries
+}
+
+/// Nearest-rank percentile of sorted, non-empty values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareStack;
+
+    fn record(fingerprint: &str, duration_ms: f64, tags: &[(&str, &str)]) -> QueryLogRecord {
+        QueryLogRecord {
+            timestamp_ms: 0,
+            fingerprint: fingerprint.to_string(),
+            query: format!("select {}", fingerprint),
+            query_type: "SELECT".to_string(),
+            model: None,
+            operation: None,
+            duration_ms,
+            rows: Some(1),
+            success: duration_ms < 100.0,
+            from_cache: false,
+            tags: tags
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            tenant: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_log_rotation() {
+        let dir = std::env::temp_dir().join(format!("prax-query-log-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let log = QueryLogMiddleware::new(dir.join("queries.jsonl"))
+            .max_bytes(300)
+            .max_files(1);
+        let stack = MiddlewareStack::new().with(log.clone());
+
+        for _ in 0..4 {
+            let ctx = QueryContext::new(
+                "SELECT * FROM users WHERE id = $1 /*team='accounts'*/",
+                vec![1.into()],
+            )
+            .with_metadata(crate::middleware::QueryMetadata::new().with_tenant_id("acme"));
+            stack
+                .execute(ctx, |_| {
+                    Box::pin(async { Ok(QueryResponse::new(serde_json::json!([{"id": 1}]))) })
+                })
+                .await
+                .unwrap();
+        }
+
+        let current = QueryLogRecord::read_log(log.path()).unwrap();
+        let rotated = QueryLogRecord::read_log(dir.join("queries.jsonl.1")).unwrap();
+        assert!(!dir.join("queries.jsonl.2").exists());
+        assert!(!current.is_empty() && !rotated.is_empty());
+        assert!(current.len() + rotated.len() < 4);
+
+        let record = &current[0];
+        assert_eq!(
+            record.fingerprint,
+            fingerprint("SELECT * FROM users WHERE id = $1")
+        );
+        assert_eq!(record.rows, Some(1));
+        assert_eq!(record.tenant.as_deref(), Some("acme"));
+        assert_eq!(record.tags["team"], "accounts");
+        assert!(!std::fs::read_to_string(log.path()).unwrap().contains("$1"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_summarize() {
+        let mut records: Vec<QueryLogRecord> = (1..=20)
+            .map(|i| record("a", i as f64, &[("team", "checkout")]))
+            .collect();
+        records.push(record("b", 150.0, &[("team", "search"), ("canary", "")]));
+        let mut cached = record("c", 1.0, &[]);
+        cached.from_cache = true;
+        records.push(cached);
+
+        let by_query = summarize(&records, LogGrouping::Query);
+        assert_eq!(by_query.len(), 2);
+        assert_eq!(by_query[0].key, "a");
+        assert_eq!(by_query[0].query.as_deref(), Some("select a"));
+        assert_eq!(by_query[0].calls, 20);
+        assert_eq!(by_query[0].total_ms, 210.0);
+        assert_eq!(by_query[0].p50_ms, 10.0);
+        assert_eq!(by_query[0].p95_ms, 19.0);
+        assert_eq!(by_query[0].max_ms, 20.0);
+        assert_eq!(by_query[1].errors, 1);
+
+        let by_tag = summarize(&records, LogGrouping::Tag);
+        let keys: Vec<&str> = by_tag.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(keys, ["team:checkout", "canary", "team:search"]);
+        assert!(by_tag[0].query.is_none());
+    }
+}
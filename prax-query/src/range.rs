@@ -0,0 +1,469 @@
+//! Range columns and their filters.
+//!
+//! `IntRange`, `BigIntRange` and `DateTimeRange` fields hold a range of
+//! values:
+//!
+//! ```prax
+//! model Booking {
+//!     id     Int           @id @auto
+//!     seats  IntRange
+//!     during DateTimeRange
+//! }
+//! ```
+//!
+//! PostgreSQL stores them in native `int4range`, `int8range` and `tstzrange`
+//! columns. MySQL, SQLite, SQL Server and DuckDB have no range types, so
+//! migrations store such a field in two columns, `<column>_lower` and
+//! `<column>_upper`, holding the half-open range `[lower, upper)` with `NULL`
+//! for an unbounded side.
+//!
+//! A [`RangeFilter`] renders each operator for either storage:
+//!
+//! | Operator | Native | Two columns |
+//! |----------|--------|-------------|
+//! | [`contains`](RangeFilter::contains) | `seats @> $1::int4` | `seats_lower <= $1 AND seats_upper > $2` |
+//! | [`contains_range`](RangeFilter::contains_range) | `seats @> $1::text::int4range` | `seats_lower <= $1 AND seats_upper >= $2` |
+//! | [`overlaps`](RangeFilter::overlaps) | `seats && $1::text::int4range` | `seats_lower < $1 AND seats_upper > $2` |
+//! | [`adjacent`](RangeFilter::adjacent) | `seats -\|- $1::text::int4range` | `seats_upper = $1 OR seats_lower = $2` |
+//!
+//! The two-column conditions also match a `NULL` (unbounded) side.
+//!
+//! Ranges are built from the standard library's range types, and from
+//! `chrono` timestamps with the `chrono` feature. Integer ranges are
+//! discrete, so an inclusive `1..=5` becomes `[1,6)`, the canonical form
+//! PostgreSQL stores.
+//!
+//! ```rust
+//! use prax_query::range::{RangeFilter, RangeValue};
+//!
+//! assert_eq!(RangeValue::from(1..10).literal(), "[1,10)");
+//! assert_eq!(RangeValue::from(1..=5).literal(), "[1,6)");
+//! assert_eq!(RangeValue::from(..10).literal(), "(,10)");
+//!
+//! let (sql, params) = RangeFilter::overlaps(10..20).into_filter("seats").to_sql(0);
+//! assert_eq!(sql, "seats && $1::text::int4range");
+//! assert_eq!(params, vec!["[10,20)".into()]);
+//!
+//! let (sql, _) = RangeFilter::contains(15).emulated().into_filter("seats").to_sql(0);
+//! assert_eq!(
+//!     sql,
+//!     "((seats_lower IS NULL OR seats_lower <= $1) AND (seats_upper IS NULL OR seats_upper > $2))"
+//! );
+//! ```
+
+use std::ops::{Range, RangeFrom, RangeInclusive, RangeTo};
+
+use crate::filter::{FieldName, Filter, FilterValue};
+
+/// The element type of a range column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RangeKind {
+    /// Range of 32-bit integers (`int4range`).
+    Int,
+    /// Range of 64-bit integers (`int8range`).
+    BigInt,
+    /// Range of timestamps with time zone (`tstzrange`).
+    DateTime,
+}
+
+impl RangeKind {
+    /// Get the PostgreSQL range type.
+    pub fn pg_range_type(&self) -> &'static str {
+        match self {
+            Self::Int => "int4range",
+            Self::BigInt => "int8range",
+            Self::DateTime => "tstzrange",
+        }
+    }
+
+    /// Get the PostgreSQL type of the range's elements.
+    pub fn pg_element_type(&self) -> &'static str {
+        match self {
+            Self::Int => "int4",
+            Self::BigInt => "int8",
+            Self::DateTime => "timestamptz",
+        }
+    }
+}
+
+/// A value that can bound a range.
+pub trait RangeElement {
+    /// The kind of range holding this element.
+    const KIND: RangeKind;
+
+    /// Convert to a query parameter.
+    fn into_value(self) -> FilterValue;
+}
+
+/// A range element with a successor, so that an inclusive upper bound can
+/// be made exclusive.
+pub trait DiscreteRangeElement: RangeElement + Sized {
+    /// Get the next value, or `None` on overflow.
+    fn successor(&self) -> Option<Self>;
+}
+
+impl RangeElement for i32 {
+    const KIND: RangeKind = RangeKind::Int;
+
+    fn into_value(self) -> FilterValue {
+        FilterValue::Int(self.into())
+    }
+}
+
+impl DiscreteRangeElement for i32 {
+    fn successor(&self) -> Option<Self> {
+        self.checked_add(1)
+    }
+}
+
+impl RangeElement for i64 {
+    const KIND: RangeKind = RangeKind::BigInt;
+
+    fn into_value(self) -> FilterValue {
+        FilterValue::Int(self)
+    }
+}
+
+impl DiscreteRangeElement for i64 {
+    fn successor(&self) -> Option<Self> {
+        self.checked_add(1)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl RangeElement for chrono::DateTime<chrono::Utc> {
+    const KIND: RangeKind = RangeKind::DateTime;
+
+    fn into_value(self) -> FilterValue {
+        FilterValue::String(self.to_rfc3339())
+    }
+}
+
+/// A half-open range `[lower, upper)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeValue {
+    /// The element type.
+    pub kind: RangeKind,
+    /// Inclusive lower bound; `None` is unbounded.
+    pub lower: Option<FilterValue>,
+    /// Exclusive upper bound; `None` is unbounded.
+    pub upper: Option<FilterValue>,
+}
+
+impl RangeValue {
+    /// Get the PostgreSQL range literal, such as `[1,10)` or `(,10)`.
+    pub fn literal(&self) -> String {
+        let open = if self.lower.is_some() { '[' } else { '(' };
+        format!(
+            "{}{},{})",
+            open,
+            bound_literal(self.lower.as_ref()),
+            bound_literal(self.upper.as_ref())
+        )
+    }
+}
+
+/// Format a range bound, quoting timestamps.
+fn bound_literal(bound: Option<&FilterValue>) -> String {
+    match bound {
+        Some(FilterValue::Int(i)) => i.to_string(),
+        Some(FilterValue::String(s)) => {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        _ => String::new(),
+    }
+}
+
+impl<T: RangeElement> From<Range<T>> for RangeValue {
+    fn from(range: Range<T>) -> Self {
+        Self {
+            kind: T::KIND,
+            lower: Some(range.start.into_value()),
+            upper: Some(range.end.into_value()),
+        }
+    }
+}
+
+impl<T: RangeElement> From<RangeFrom<T>> for RangeValue {
+    fn from(range: RangeFrom<T>) -> Self {
+        Self {
+            kind: T::KIND,
+            lower: Some(range.start.into_value()),
+            upper: None,
+        }
+    }
+}
+
+impl<T: RangeElement> From<RangeTo<T>> for RangeValue {
+    fn from(range: RangeTo<T>) -> Self {
+        Self {
+            kind: T::KIND,
+            lower: None,
+            upper: Some(range.end.into_value()),
+        }
+    }
+}
+
+impl<T: DiscreteRangeElement> From<RangeInclusive<T>> for RangeValue {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let (start, end) = range.into_inner();
+        Self {
+            kind: T::KIND,
+            upper: end.successor().map(RangeElement::into_value),
+            lower: Some(start.into_value()),
+        }
+    }
+}
+
+/// How a range field is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RangeStorage {
+    /// A native range column (PostgreSQL).
+    #[default]
+    Native,
+    /// Two columns, `<column>_lower` and `<column>_upper`.
+    Columns,
+}
+
+/// A range operator and its operand.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeOp {
+    /// The range contains a value.
+    Contains(FilterValue),
+    /// The range contains another range.
+    ContainsRange(RangeValue),
+    /// The ranges share a value.
+    Overlaps(RangeValue),
+    /// The ranges touch without overlapping.
+    Adjacent(RangeValue),
+}
+
+/// A filter on a range column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeFilter {
+    /// The element type of the column.
+    pub kind: RangeKind,
+    /// The operator.
+    pub op: RangeOp,
+    /// How the column is stored.
+    pub storage: RangeStorage,
+}
+
+impl RangeFilter {
+    fn new(kind: RangeKind, op: RangeOp) -> Self {
+        Self {
+            kind,
+            op,
+            storage: RangeStorage::Native,
+        }
+    }
+
+    /// Match ranges containing `value`.
+    pub fn contains<T: RangeElement>(value: T) -> Self {
+        Self::new(T::KIND, RangeOp::Contains(value.into_value()))
+    }
+
+    /// Match ranges containing all of `range`.
+    pub fn contains_range(range: impl Into<RangeValue>) -> Self {
+        let range = range.into();
+        Self::new(range.kind, RangeOp::ContainsRange(range))
+    }
+
+    /// Match ranges sharing a value with `range`.
+    pub fn overlaps(range: impl Into<RangeValue>) -> Self {
+        let range = range.into();
+        Self::new(range.kind, RangeOp::Overlaps(range))
+    }
+
+    /// Match ranges ending where `range` starts, or starting where it ends.
+    pub fn adjacent(range: impl Into<RangeValue>) -> Self {
+        let range = range.into();
+        Self::new(range.kind, RangeOp::Adjacent(range))
+    }
+
+    /// Filter a range stored in two bound columns.
+    pub fn emulated(mut self) -> Self {
+        self.storage = RangeStorage::Columns;
+        self
+    }
+
+    /// Apply the filter to `column`.
+    pub fn into_filter(self, column: impl Into<FieldName>) -> Filter {
+        Filter::Range(column.into(), Box::new(self))
+    }
+
+    /// Generate the condition on `column`, pushing its parameters.
+    pub(crate) fn to_sql_with_params(
+        &self,
+        column: &str,
+        param_idx: usize,
+        params: &mut Vec<FilterValue>,
+    ) -> String {
+        let mut bind = |value: FilterValue| {
+            params.push(value);
+            format!("${}", param_idx + params.len())
+        };
+
+        if self.storage == RangeStorage::Native {
+            let range_type = self.kind.pg_range_type();
+            let (op, operand) = match &self.op {
+                RangeOp::Contains(value) => {
+                    let placeholder = bind(value.clone());
+                    return format!(
+                        "{} @> {}::{}",
+                        column,
+                        placeholder,
+                        self.kind.pg_element_type()
+                    );
+                }
+                RangeOp::ContainsRange(range) => ("@>", range),
+                RangeOp::Overlaps(range) => ("&&", range),
+                RangeOp::Adjacent(range) => ("-|-", range),
+            };
+            // Bound as text, as drivers cannot encode range values
+            let placeholder = bind(FilterValue::String(operand.literal()));
+            return format!("{} {} {}::text::{}", column, op, placeholder, range_type);
+        }
+
+        let lower = lower_column(column);
+        let upper = upper_column(column);
+        let (parts, joiner, empty) = match &self.op {
+            RangeOp::Contains(value) => (
+                vec![
+                    format!("({0} IS NULL OR {0} <= {1})", lower, bind(value.clone())),
+                    format!("({0} IS NULL OR {0} > {1})", upper, bind(value.clone())),
+                ],
+                " AND ",
+                "TRUE",
+            ),
+            RangeOp::ContainsRange(range) => (
+                vec![
+                    match &range.lower {
+                        Some(a) => format!("({0} IS NULL OR {0} <= {1})", lower, bind(a.clone())),
+                        None => format!("{} IS NULL", lower),
+                    },
+                    match &range.upper {
+                        Some(b) => format!("({0} IS NULL OR {0} >= {1})", upper, bind(b.clone())),
+                        None => format!("{} IS NULL", upper),
+                    },
+                ],
+                " AND ",
+                "TRUE",
+            ),
+            RangeOp::Overlaps(range) => {
+                let mut parts = Vec::new();
+                if let Some(b) = &range.upper {
+                    parts.push(format!(
+                        "({0} IS NULL OR {0} < {1})",
+                        lower,
+                        bind(b.clone())
+                    ));
+                }
+                if let Some(a) = &range.lower {
+                    parts.push(format!(
+                        "({0} IS NULL OR {0} > {1})",
+                        upper,
+                        bind(a.clone())
+                    ));
+                }
+                (parts, " AND ", "TRUE")
+            }
+            RangeOp::Adjacent(range) => {
+                let mut parts = Vec::new();
+                if let Some(a) = &range.lower {
+                    parts.push(format!("{} = {}", upper, bind(a.clone())));
+                }
+                if let Some(b) = &range.upper {
+                    parts.push(format!("{} = {}", lower, bind(b.clone())));
+                }
+                (parts, " OR ", "FALSE")
+            }
+        };
+
+        match parts.len() {
+            0 => empty.to_string(),
+            1 => parts.into_iter().next().unwrap_or_default(),
+            _ => format!("({})", parts.join(joiner)),
+        }
+    }
+}
+
+/// Get the column holding the lower bound of an emulated range.
+pub fn lower_column(column: &str) -> String {
+    format!("{}_lower", column)
+}
+
+/// Get the column holding the upper bound of an emulated range.
+pub fn upper_column(column: &str) -> String {
+    format!("{}_upper", column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_literals() {
+        assert_eq!(RangeValue::from(1..10).literal(), "[1,10)");
+        assert_eq!(RangeValue::from(5i64..).literal(), "[5,)");
+        assert_eq!(RangeValue::from(1..=i32::MAX).literal(), "[1,)");
+        assert_eq!(RangeValue::from(1i64..=5).kind, RangeKind::BigInt);
+
+        let range = RangeValue {
+            kind: RangeKind::DateTime,
+            lower: Some("2024-01-01T10:00:00+00:00".into()),
+            upper: None,
+        };
+        assert_eq!(range.literal(), "[\"2024-01-01T10:00:00+00:00\",)");
+    }
+
+    #[test]
+    fn test_native_range_filters() {
+        let (sql, params) = RangeFilter::contains(7i64).into_filter("seats").to_sql(2);
+        assert_eq!(sql, "seats @> $3::int8");
+        assert_eq!(params, vec![FilterValue::Int(7)]);
+
+        let (sql, params) = RangeFilter::adjacent(..10).into_filter("seats").to_sql(0);
+        assert_eq!(sql, "seats -|- $1::text::int4range");
+        assert_eq!(params, vec![FilterValue::String("(,10)".into())]);
+
+        let (sql, _) = RangeFilter::contains_range(1..3)
+            .into_filter("seats")
+            .to_sql(0);
+        assert_eq!(sql, "seats @> $1::text::int4range");
+    }
+
+    #[test]
+    fn test_emulated_range_filters() {
+        let filter = |f: RangeFilter| f.emulated().into_filter("seats").to_sql(0);
+
+        let (sql, params) = filter(RangeFilter::overlaps(10..20));
+        assert_eq!(
+            sql,
+            "((seats_lower IS NULL OR seats_lower < $1) AND (seats_upper IS NULL OR seats_upper > $2))"
+        );
+        assert_eq!(params, vec![FilterValue::Int(20), FilterValue::Int(10)]);
+
+        let (sql, _) = filter(RangeFilter::contains_range(10..));
+        assert_eq!(
+            sql,
+            "((seats_lower IS NULL OR seats_lower <= $1) AND seats_upper IS NULL)"
+        );
+
+        let (sql, params) = filter(RangeFilter::adjacent(10..20));
+        assert_eq!(sql, "(seats_upper = $1 OR seats_lower = $2)");
+        assert_eq!(params, vec![FilterValue::Int(10), FilterValue::Int(20)]);
+
+        let (sql, _) = filter(RangeFilter::overlaps(..10));
+        assert_eq!(sql, "(seats_lower IS NULL OR seats_lower < $1)");
+
+        let combined = Filter::and2(
+            Filter::Equals("room".into(), FilterValue::Int(1)),
+            RangeFilter::contains(15).emulated().into_filter("seats"),
+        );
+        let (sql, params) = combined.to_sql(0);
+        assert!(sql.contains("room = $1"));
+        assert!(sql.contains("seats_lower <= $2"));
+        assert_eq!(params.len(), 3);
+    }
+}
@@ -0,0 +1,685 @@
+//! Rate limits and quota counters stored in the database.
+//!
+//! A [`RateLimiter`] keeps counters and token buckets in two small tables,
+//! so rate limiting works without a cache server. Each check is a single
+//! UPSERT that only applies if it stays within the limit (on MySQL, a
+//! conditional UPDATE in a transaction), so concurrent requests can't
+//! overshoot it:
+//!
+//! ```rust,ignore
+//! use prax_query::rate_limit::{RateLimiter, Refill};
+//! use std::time::Duration;
+//!
+//! let limiter = RateLimiter::new(db);
+//! limiter.create_tables().await?;
+//!
+//! // 100 requests per minute, in bursts of up to 100
+//! let refill = Refill::new(100, Duration::from_secs(60));
+//! match limiter.take_tokens(&format!("api:{}", user_id), 1, &refill).await? {
+//!     Some(remaining) => { /* handle the request */ }
+//!     None => { /* 429 Too Many Requests */ }
+//! }
+//!
+//! // At most 10 exports per day
+//! let key = format!("exports:{}:{}", user_id, today);
+//! if limiter.increment_with_cap(&key, 1, 10).await?.is_none() {
+//!     return Err(QuotaExceeded);
+//! }
+//! ```
+//!
+//! Token buckets use the generic cell rate algorithm: a bucket is stored as
+//! the time it will be full again, compared with the application's clock.
+//! Counters never reset on their own; put the window in the key and delete
+//! old keys with [`RateLimiter::reset`] or a retention job.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value as JsonValue;
+
+use crate::error::{QueryError, QueryResult};
+use crate::filter::FilterValue;
+use crate::raw::RawDatabase;
+use crate::sql::{DatabaseType, SqlBuilder, quote_identifier};
+
+/// Default name of the counter table.
+pub const DEFAULT_COUNTER_TABLE: &str = "_prax_counters";
+/// Default name of the token bucket table.
+pub const DEFAULT_BUCKET_TABLE: &str = "_prax_token_buckets";
+
+/// Slack in seconds for rounding in epoch timestamps, which only have
+/// microsecond precision as `f64`.
+const TOLERANCE: f64 = 1e-6;
+
+/// How a token bucket fills.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Refill {
+    capacity: u32,
+    interval: f64,
+}
+
+impl Refill {
+    /// A bucket holding `capacity` tokens that refills completely over
+    /// `period`, one token at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` or `period` is zero.
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        assert!(
+            capacity > 0 && !period.is_zero(),
+            "a token bucket needs a capacity and a refill period"
+        );
+        Self {
+            capacity,
+            interval: period.as_secs_f64() / f64::from(capacity),
+        }
+    }
+
+    /// Get the number of tokens a full bucket holds.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Get the time it takes to refill one token.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs_f64(self.interval)
+    }
+}
+
+/// Counters and token buckets kept in the database.
+#[derive(Debug, Clone)]
+pub struct RateLimiter<D> {
+    db: D,
+    counter_table: String,
+    bucket_table: String,
+}
+
+impl<D: RawDatabase> RateLimiter<D> {
+    /// Create a rate limiter storing its state in `db`.
+    pub fn new(db: D) -> Self {
+        Self {
+            db,
+            counter_table: DEFAULT_COUNTER_TABLE.to_string(),
+            bucket_table: DEFAULT_BUCKET_TABLE.to_string(),
+        }
+    }
+
+    /// Set the counter table (default `_prax_counters`).
+    pub fn counter_table(mut self, table: impl Into<String>) -> Self {
+        self.counter_table = table.into();
+        self
+    }
+
+    /// Set the token bucket table (default `_prax_token_buckets`).
+    pub fn bucket_table(mut self, table: impl Into<String>) -> Self {
+        self.bucket_table = table.into();
+        self
+    }
+
+    /// Build the statements creating the counter and token bucket tables.
+    pub fn create_tables_sql(&self) -> Vec<String> {
+        let (key, count, time) = match self.db.database_type() {
+            DatabaseType::PostgreSQL => ("VARCHAR(255)", "BIGINT", "DOUBLE PRECISION"),
+            DatabaseType::MySQL => ("VARCHAR(255)", "BIGINT", "DOUBLE"),
+            DatabaseType::SQLite => ("TEXT", "INTEGER", "REAL"),
+            DatabaseType::MSSQL => ("NVARCHAR(255)", "BIGINT", "FLOAT"),
+        };
+        [
+            (&self.counter_table, "count", count),
+            (&self.bucket_table, "tat", time),
+        ]
+        .into_iter()
+        .map(|(table, column, column_type)| {
+            let columns = format!(
+                "name {} NOT NULL PRIMARY KEY, {} {} NOT NULL",
+                key, column, column_type
+            );
+            match self.db.database_type() {
+                DatabaseType::MSSQL => format!(
+                    "IF OBJECT_ID(N'{}', N'U') IS NULL CREATE TABLE {} ({})",
+                    table,
+                    quote_identifier(table),
+                    columns
+                ),
+                _ => format!(
+                    "CREATE TABLE IF NOT EXISTS {} ({})",
+                    quote_identifier(table),
+                    columns
+                ),
+            }
+        })
+        .collect()
+    }
+
+    /// Create the counter and token bucket tables if they don't exist.
+    pub async fn create_tables(&self) -> QueryResult<()> {
+        for sql in self.create_tables_sql() {
+            self.db.execute(&sql, Vec::new()).await?;
+        }
+        Ok(())
+    }
+
+    /// Add `by` to the counter `key`, unless the result would exceed `cap`.
+    ///
+    /// Missing counters start at zero. Returns the new value, or `None`
+    /// without changing the counter if it would exceed the cap.
+    pub async fn increment_with_cap(
+        &self,
+        key: &str,
+        by: i64,
+        cap: i64,
+    ) -> QueryResult<Option<i64>> {
+        if by > cap {
+            return Ok(None);
+        }
+        let db_type = self.db.database_type();
+        let (sql, params) = self.increment_sql(db_type, key, by, cap);
+        let Some(row) = self
+            .upsert(&sql, params, &self.counter_table, "count", key, 0.into())
+            .await?
+        else {
+            return Ok(None);
+        };
+        number(&row, "count").map(|n| Some(n as i64))
+    }
+
+    /// Take `n` tokens from the bucket `key`, if it holds that many.
+    ///
+    /// Missing buckets start full. Returns the whole tokens left, or `None`
+    /// without taking any if there aren't enough.
+    pub async fn take_tokens(
+        &self,
+        key: &str,
+        n: u32,
+        refill: &Refill,
+    ) -> QueryResult<Option<u32>> {
+        if n > refill.capacity {
+            return Ok(None);
+        }
+        let db_type = self.db.database_type();
+        let now = unix_now();
+        let (sql, params) = self.take_sql(db_type, key, n, refill, now);
+        let Some(row) = self
+            .upsert(&sql, params, &self.bucket_table, "tat", key, now.into())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // The bucket is full again at `tat`; each interval before it is a missing token
+        let tat = number(&row, "tat")?;
+        let missing = ((tat - now - TOLERANCE) / refill.interval).max(0.0).ceil() as u32;
+        Ok(Some(refill.capacity.saturating_sub(missing)))
+    }
+
+    /// Delete the counter and token bucket of `key`.
+    pub async fn reset(&self, key: &str) -> QueryResult<()> {
+        let db_type = self.db.database_type();
+        for table in [&self.counter_table, &self.bucket_table] {
+            let sql = format!(
+                "DELETE FROM {} WHERE name = {}",
+                quote_identifier(table),
+                db_type.placeholder(1)
+            );
+            self.db.execute(&sql, vec![key.into()]).await?;
+        }
+        Ok(())
+    }
+
+    /// Run a conditional UPSERT and get the updated row, if it applied.
+    ///
+    /// MySQL can't return the row from an UPSERT, and with `CLIENT_FOUND_ROWS`
+    /// (which sqlx sets) a declined `ON DUPLICATE KEY UPDATE` reports the same
+    /// row count as an insert. There `sql` is a conditional UPDATE instead,
+    /// run in one transaction after seeding the row with `initial`; it
+    /// matches no row when the condition fails, and the row is read
+    /// afterwards.
+    async fn upsert(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+        table: &str,
+        column: &str,
+        key: &str,
+        initial: FilterValue,
+    ) -> QueryResult<Option<JsonValue>> {
+        if self.db.database_type() != DatabaseType::MySQL {
+            return Ok(self.db.query(sql, params).await?.into_iter().next());
+        }
+        let table = quote_identifier(table);
+        let seed = format!(
+            "INSERT INTO {} (name, {c}) VALUES (?, ?) ON DUPLICATE KEY UPDATE {c} = {c}",
+            table,
+            c = column
+        );
+        let affected = self
+            .db
+            .execute_in_transaction(vec![
+                (seed, vec![key.into(), initial]),
+                (sql.to_string(), params),
+            ])
+            .await?;
+        if affected.last().copied().unwrap_or(0) == 0 {
+            return Ok(None);
+        }
+        let sql = format!("SELECT {} FROM {} WHERE name = ?", column, table);
+        Ok(self
+            .db
+            .query(&sql, vec![key.into()])
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    fn increment_sql(
+        &self,
+        db_type: DatabaseType,
+        key: &str,
+        by: i64,
+        cap: i64,
+    ) -> (String, Vec<FilterValue>) {
+        let table = quote_identifier(&self.counter_table);
+        let mut sql = SqlBuilder::new(db_type);
+        match db_type {
+            DatabaseType::PostgreSQL | DatabaseType::SQLite => {
+                sql.push(format!("INSERT INTO {} (name, count) VALUES (", table))
+                    .push_param(key)
+                    .push(", ")
+                    .push_param(by)
+                    .push(format!(
+                        ") ON CONFLICT (name) DO UPDATE SET count = {t}.count + excluded.count \
+                         WHERE {t}.count + excluded.count <= ",
+                        t = table
+                    ))
+                    .push_param(cap)
+                    .push(" RETURNING count");
+            }
+            DatabaseType::MySQL => {
+                sql.push(format!("UPDATE {} SET count = count + ", table))
+                    .push_param(by)
+                    .push(" WHERE name = ")
+                    .push_param(key)
+                    .push(" AND count + ")
+                    .push_param(by)
+                    .push(" <= ")
+                    .push_param(cap);
+            }
+            DatabaseType::MSSQL => {
+                sql.push(format!(
+                    "MERGE INTO {} WITH (HOLDLOCK) AS target USING (SELECT ",
+                    table
+                ))
+                .push_param(key)
+                .push(" AS name, ")
+                .push_param(by)
+                .push(
+                    " AS count) AS source ON target.name = source.name \
+                     WHEN MATCHED AND target.count + source.count <= ",
+                )
+                .push_param(cap)
+                .push(
+                    " THEN UPDATE SET target.count = target.count + source.count \
+                     WHEN NOT MATCHED THEN INSERT (name, count) VALUES (source.name, source.count) \
+                     OUTPUT inserted.count;",
+                );
+            }
+        }
+        sql.build()
+    }
+
+    /// Build the UPSERT taking `n` tokens at `now`.
+    ///
+    /// A bucket stores its theoretical arrival time (`tat`), when it will be
+    /// full again. Taking tokens pushes it back by their refill time, from
+    /// now if it's already full, as long as it stays within one full refill
+    /// of now.
+    fn take_sql(
+        &self,
+        db_type: DatabaseType,
+        key: &str,
+        n: u32,
+        refill: &Refill,
+        now: f64,
+    ) -> (String, Vec<FilterValue>) {
+        let table = quote_identifier(&self.bucket_table);
+        let cost = f64::from(n) * refill.interval;
+        let limit = now + f64::from(refill.capacity) * refill.interval + TOLERANCE;
+        let mut sql = SqlBuilder::new(db_type);
+        match db_type {
+            DatabaseType::PostgreSQL | DatabaseType::SQLite => {
+                let greatest = if db_type == DatabaseType::SQLite {
+                    "MAX"
+                } else {
+                    "GREATEST"
+                };
+                sql.push(format!("INSERT INTO {} (name, tat) VALUES (", table))
+                    .push_param(key)
+                    .push(", ")
+                    .push_param(now + cost)
+                    .push(format!(
+                        ") ON CONFLICT (name) DO UPDATE SET tat = {}({}.tat + ",
+                        greatest, table
+                    ))
+                    .push_param(cost)
+                    .push(format!(
+                        ", excluded.tat) WHERE {}({}.tat + ",
+                        greatest, table
+                    ))
+                    .push_param(cost)
+                    .push(", excluded.tat) <= ")
+                    .push_param(limit)
+                    .push(" RETURNING tat");
+            }
+            DatabaseType::MySQL => {
+                sql.push(format!("UPDATE {} SET tat = GREATEST(tat + ", table))
+                    .push_param(cost)
+                    .push(", ")
+                    .push_param(now + cost)
+                    .push(") WHERE name = ")
+                    .push_param(key)
+                    .push(" AND GREATEST(tat + ")
+                    .push_param(cost)
+                    .push(", ")
+                    .push_param(now + cost)
+                    .push(") <= ")
+                    .push_param(limit);
+            }
+            DatabaseType::MSSQL => {
+                sql.push(format!(
+                    "MERGE INTO {} WITH (HOLDLOCK) AS target USING (SELECT ",
+                    table
+                ))
+                .push_param(key)
+                .push(" AS name, ")
+                .push_param(now + cost)
+                .push(" AS tat) AS source ON target.name = source.name WHEN MATCHED AND CASE WHEN target.tat + ")
+                .push_param(cost)
+                .push(" > source.tat THEN target.tat + ")
+                .push_param(cost)
+                .push(" ELSE source.tat END <= ")
+                .push_param(limit)
+                .push(" THEN UPDATE SET target.tat = CASE WHEN target.tat + ")
+                .push_param(cost)
+                .push(" > source.tat THEN target.tat + ")
+                .push_param(cost)
+                .push(
+                    " ELSE source.tat END \
+                     WHEN NOT MATCHED THEN INSERT (name, tat) VALUES (source.name, source.tat) \
+                     OUTPUT inserted.tat;",
+                );
+            }
+        }
+        sql.build()
+    }
+}
+
+/// Read a numeric column, which some drivers return as a string.
+fn number(row: &JsonValue, column: &str) -> QueryResult<f64> {
+    match row.get(column) {
+        Some(JsonValue::Number(n)) => n.as_f64(),
+        Some(JsonValue::String(s)) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| QueryError::deserialization(format!("expected a number in `{}`", column)))
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::BoxFuture;
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+
+    /// A fake database applying the UPSERTs (conditional UPDATEs on MySQL) to
+    /// a map of values.
+    struct FakeDb {
+        db_type: DatabaseType,
+        values: Mutex<HashMap<String, f64>>,
+    }
+
+    impl FakeDb {
+        fn new(db_type: DatabaseType) -> Self {
+            Self {
+                db_type,
+                values: Mutex::new(HashMap::new()),
+            }
+        }
+    }
+
+    fn key_of(value: &FilterValue) -> String {
+        match value {
+            FilterValue::String(key) => key.clone(),
+            other => panic!("expected a key, got {:?}", other),
+        }
+    }
+
+    fn float(value: &FilterValue) -> f64 {
+        match value {
+            FilterValue::Int(i) => *i as f64,
+            FilterValue::Float(f) => *f,
+            other => panic!("expected a number, got {:?}", other),
+        }
+    }
+
+    impl RawDatabase for FakeDb {
+        fn query(
+            &self,
+            sql: &str,
+            params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>> {
+            let FilterValue::String(key) = &params[0] else {
+                panic!("expected a key");
+            };
+            let mut values = self.values.lock();
+            let rows = if sql.starts_with("SELECT") {
+                let column = if sql.contains("count") {
+                    "count"
+                } else {
+                    "tat"
+                };
+                values
+                    .get(key)
+                    .map(|value| serde_json::json!({ column: value }))
+            } else if sql.contains("(name, count)") {
+                let (by, cap) = (float(&params[1]), float(&params[2]));
+                let next = values.get(key).map_or(by, |count| count + by);
+                (next <= cap).then(|| {
+                    values.insert(key.clone(), next);
+                    serde_json::json!({ "count": next as i64 })
+                })
+            } else {
+                let (tat, cost, limit) = (float(&params[1]), float(&params[2]), float(&params[4]));
+                let next = values.get(key).map_or(tat, |old| (old + cost).max(tat));
+                (next <= limit).then(|| {
+                    values.insert(key.clone(), next);
+                    serde_json::json!({ "tat": next })
+                })
+            };
+            Box::pin(async move { Ok(rows.into_iter().collect()) })
+        }
+
+        fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+            assert!(sql.starts_with("CREATE") || sql.starts_with("DELETE"));
+            if let Some(FilterValue::String(key)) = params.first() {
+                self.values.lock().remove(key);
+            }
+            Box::pin(async { Ok(1) })
+        }
+
+        /// Applies MySQL's seed and conditional UPDATE, reporting found rows
+        /// like sqlx's `CLIENT_FOUND_ROWS` connections.
+        fn execute_in_transaction(
+            &self,
+            statements: Vec<(String, Vec<FilterValue>)>,
+        ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+            assert_eq!(self.db_type, DatabaseType::MySQL);
+            let mut values = self.values.lock();
+            let affected = statements
+                .iter()
+                .map(|(sql, params)| {
+                    if sql.starts_with("INSERT") {
+                        let FilterValue::String(key) = &params[0] else {
+                            panic!("expected a key");
+                        };
+                        values.entry(key.clone()).or_insert(float(&params[1]));
+                        return 1;
+                    }
+                    let (key, next, limit) = if sql.contains("SET count") {
+                        let (key, by, cap) = (&params[1], float(&params[0]), float(&params[3]));
+                        (key, values[&key_of(key)] + by, cap)
+                    } else {
+                        let (cost, tat, limit) =
+                            (float(&params[0]), float(&params[1]), float(&params[5]));
+                        let key = &params[2];
+                        (key, (values[&key_of(key)] + cost).max(tat), limit)
+                    };
+                    if next <= limit {
+                        values.insert(key_of(key), next);
+                        1
+                    } else {
+                        0
+                    }
+                })
+                .collect();
+            Box::pin(async move { Ok(affected) })
+        }
+
+        fn database_type(&self) -> DatabaseType {
+            self.db_type
+        }
+    }
+
+    #[tokio::test]
+    async fn test_increment_with_cap() {
+        let limiter = RateLimiter::new(FakeDb::new(DatabaseType::PostgreSQL));
+
+        assert_eq!(
+            limiter.increment_with_cap("exports", 2, 3).await.unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            limiter.increment_with_cap("exports", 1, 3).await.unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            limiter.increment_with_cap("exports", 1, 3).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            limiter.increment_with_cap("other", 4, 3).await.unwrap(),
+            None
+        );
+
+        limiter.reset("exports").await.unwrap();
+        assert_eq!(
+            limiter.increment_with_cap("exports", 1, 3).await.unwrap(),
+            Some(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_take_tokens() {
+        let limiter = RateLimiter::new(FakeDb::new(DatabaseType::PostgreSQL));
+        let refill = Refill::new(3, Duration::from_secs(60));
+        assert_eq!(refill.interval(), Duration::from_secs(20));
+
+        assert_eq!(
+            limiter.take_tokens("api", 1, &refill).await.unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            limiter.take_tokens("api", 2, &refill).await.unwrap(),
+            Some(0)
+        );
+        assert_eq!(limiter.take_tokens("api", 1, &refill).await.unwrap(), None);
+        assert_eq!(
+            limiter.take_tokens("other", 4, &refill).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            limiter.take_tokens("other", 3, &refill).await.unwrap(),
+            Some(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mysql_denies_over_the_limit() {
+        let limiter = RateLimiter::new(FakeDb::new(DatabaseType::MySQL));
+        assert_eq!(
+            limiter.increment_with_cap("exports", 2, 3).await.unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            limiter.increment_with_cap("exports", 1, 3).await.unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            limiter.increment_with_cap("exports", 1, 3).await.unwrap(),
+            None
+        );
+
+        let refill = Refill::new(2, Duration::from_secs(60));
+        assert_eq!(
+            limiter.take_tokens("api", 2, &refill).await.unwrap(),
+            Some(0)
+        );
+        assert_eq!(limiter.take_tokens("api", 1, &refill).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_upsert_sql() {
+        let limiter = RateLimiter::new(FakeDb::new(DatabaseType::PostgreSQL));
+        let (sql, params) = limiter.increment_sql(DatabaseType::PostgreSQL, "k", 1, 10);
+        assert_eq!(
+            sql,
+            "INSERT INTO _prax_counters (name, count) VALUES ($1, $2) \
+             ON CONFLICT (name) DO UPDATE SET count = _prax_counters.count + excluded.count \
+             WHERE _prax_counters.count + excluded.count <= $3 RETURNING count"
+        );
+        assert_eq!(params.len(), 3);
+
+        let (sql, _) = limiter.increment_sql(DatabaseType::MySQL, "k", 1, 10);
+        assert_eq!(
+            sql,
+            "UPDATE _prax_counters SET count = count + ? WHERE name = ? AND count + ? <= ?"
+        );
+
+        let (sql, _) = limiter.increment_sql(DatabaseType::MSSQL, "k", 1, 10);
+        assert!(sql.starts_with("MERGE INTO _prax_counters WITH (HOLDLOCK) AS target"));
+        assert!(sql.contains("WHEN MATCHED AND target.count + source.count <= @P3"));
+        assert!(sql.ends_with("OUTPUT inserted.count;"));
+
+        let refill = Refill::new(10, Duration::from_secs(1));
+        let (sql, params) = limiter.take_sql(DatabaseType::SQLite, "k", 1, &refill, 100.0);
+        assert!(sql.contains(
+            "DO UPDATE SET tat = MAX(_prax_token_buckets.tat + ?, excluded.tat) \
+             WHERE MAX(_prax_token_buckets.tat + ?, excluded.tat) <= ?"
+        ));
+        assert_eq!(params.len(), 5);
+
+        let (sql, params) = limiter.take_sql(DatabaseType::MSSQL, "k", 1, &refill, 100.0);
+        assert!(sql.contains("THEN UPDATE SET target.tat = CASE WHEN target.tat + @P6"));
+        assert_eq!(params.len(), 7);
+    }
+
+    #[test]
+    fn test_create_tables_sql() {
+        let limiter = RateLimiter::new(FakeDb::new(DatabaseType::MSSQL)).bucket_table("buckets");
+        let sql = limiter.create_tables_sql();
+        assert_eq!(
+            sql[0],
+            "IF OBJECT_ID(N'_prax_counters', N'U') IS NULL CREATE TABLE _prax_counters \
+             (name NVARCHAR(255) NOT NULL PRIMARY KEY, count BIGINT NOT NULL)"
+        );
+        assert!(sql[1].contains(
+            "CREATE TABLE buckets (name NVARCHAR(255) NOT NULL PRIMARY KEY, tat FLOAT NOT NULL)"
+        ));
+    }
+}
@@ -71,12 +71,14 @@
 //! ```
 
 use std::marker::PhantomData;
+
+use serde_json::Value as JsonValue;
 use tracing::debug;
 
 use crate::error::QueryResult;
 use crate::filter::FilterValue;
 use crate::sql::DatabaseType;
-use crate::traits::{Model, QueryEngine};
+use crate::traits::{BoxFuture, Model, QueryEngine};
 
 /// A raw SQL query with parameterized values.
 #[derive(Debug, Clone)]
@@ -355,6 +357,45 @@ where
     }
 }
 
+/// A database running raw SQL, returning rows as JSON objects keyed by
+/// column name.
+///
+/// The features that build their own statements run on any implementation:
+/// retention, erasure, history, projections, trees, translations, schema
+/// verification, dual writes, sessions and rate limiting. Each driver
+/// implements it once for its engine.
+pub trait RawDatabase: Send + Sync {
+    /// Execute a query and return the rows.
+    fn query(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+    ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>>;
+
+    /// Execute a write statement and return the number of affected rows.
+    fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>>;
+
+    /// Execute statements in a single transaction and return the number of
+    /// rows affected by each.
+    ///
+    /// If any statement fails, the transaction must be rolled back.
+    fn execute_in_transaction(
+        &self,
+        statements: Vec<(String, Vec<FilterValue>)>,
+    ) -> BoxFuture<'_, QueryResult<Vec<u64>>>;
+
+    /// The SQL dialect of the database.
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::PostgreSQL
+    }
+
+    /// The schema (namespace) holding the tables; the dialect's default
+    /// (`public`, `dbo`, the current database) when `None`.
+    fn schema_name(&self) -> Option<&str> {
+        None
+    }
+}
+
 /// Helper function to create a raw SQL query from a string.
 pub fn sql(query: impl Into<String>) -> Sql {
     Sql::new(query)
@@ -412,6 +453,126 @@ macro_rules! raw_query {
     }};
 }
 
+/// A scripted [`RawDatabase`] for the tests of the features built on it.
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::error::QueryError;
+
+    /// A statement and its parameters.
+    pub(crate) type Statement = (String, Vec<FilterValue>);
+
+    /// Answers queries with scripted rows and records every statement.
+    ///
+    /// Each query takes the next scripted batch; once they run out, every
+    /// query returns the fixed rows. Clones share the script and the records.
+    #[derive(Clone, Default)]
+    pub(crate) struct FakeDatabase {
+        rows: Arc<Mutex<Vec<JsonValue>>>,
+        batches: Arc<Mutex<VecDeque<Vec<JsonValue>>>>,
+        queries: Arc<Mutex<Vec<Statement>>>,
+        executed: Arc<Mutex<Vec<Statement>>>,
+        transactions: Arc<Mutex<Vec<Vec<Statement>>>>,
+        failure: Option<&'static str>,
+        db_type: DatabaseType,
+    }
+
+    impl FakeDatabase {
+        pub(crate) fn new() -> Self {
+            Self::default()
+        }
+
+        /// Answer every query with `rows`.
+        pub(crate) fn with_rows(self, rows: Vec<JsonValue>) -> Self {
+            self.set_rows(rows);
+            self
+        }
+
+        /// Answer the next queries with `batches`, one each.
+        pub(crate) fn with_batches(self, batches: Vec<Vec<JsonValue>>) -> Self {
+            self.batches.lock().extend(batches);
+            self
+        }
+
+        /// Fail every write with `message`.
+        pub(crate) fn failing(mut self, message: &'static str) -> Self {
+            self.failure = Some(message);
+            self
+        }
+
+        pub(crate) fn with_database_type(mut self, db_type: DatabaseType) -> Self {
+            self.db_type = db_type;
+            self
+        }
+
+        pub(crate) fn set_rows(&self, rows: Vec<JsonValue>) {
+            *self.rows.lock() = rows;
+        }
+
+        /// The queries run so far.
+        pub(crate) fn queries(&self) -> Vec<Statement> {
+            self.queries.lock().clone()
+        }
+
+        /// The statements executed outside a transaction so far.
+        pub(crate) fn executed(&self) -> Vec<Statement> {
+            self.executed.lock().clone()
+        }
+
+        /// The statements of each transaction so far.
+        pub(crate) fn transactions(&self) -> Vec<Vec<Statement>> {
+            self.transactions.lock().clone()
+        }
+
+        fn write_result<T: Send + 'static>(&self, ok: T) -> BoxFuture<'_, QueryResult<T>> {
+            let result = match self.failure {
+                Some(message) => Err(QueryError::database(message)),
+                None => Ok(ok),
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    impl RawDatabase for FakeDatabase {
+        fn query(
+            &self,
+            sql: &str,
+            params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>> {
+            self.queries.lock().push((sql.to_string(), params));
+            let rows = self
+                .batches
+                .lock()
+                .pop_front()
+                .unwrap_or_else(|| self.rows.lock().clone());
+            Box::pin(async move { Ok(rows) })
+        }
+
+        fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+            self.executed.lock().push((sql.to_string(), params));
+            self.write_result(1)
+        }
+
+        fn execute_in_transaction(
+            &self,
+            statements: Vec<Statement>,
+        ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+            let affected = vec![1; statements.len()];
+            self.transactions.lock().push(statements);
+            self.write_result(affected)
+        }
+
+        fn database_type(&self) -> DatabaseType {
+            self.db_type
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,6 +6,7 @@
 //! - `Include` for eager loading relations
 //! - `Select` for specifying which fields to return
 //! - Nested relation specifications
+//! - Concurrent loading of independent includes
 //!
 //! ## Example
 //!
@@ -34,10 +35,12 @@
 
 mod include;
 mod loader;
+mod parallel;
 mod select;
 mod spec;
 
 pub use include::{Include, IncludeSpec};
 pub use loader::{RelationLoadStrategy, RelationLoader};
+pub use parallel::{IncludeLoad, LoadedRecord, LoadedRelation, LoadedRelations, load_includes};
 pub use select::{FieldSelection, SelectSpec};
 pub use spec::{RelationSpec, RelationType};
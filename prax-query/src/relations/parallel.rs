@@ -0,0 +1,292 @@
+//! Concurrent loading of independent includes.
+//!
+//! Top-level includes of a query only depend on the parent record, so they can
+//! be loaded at the same time. Nested includes stay inside their parent's load.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use tracing::debug;
+
+use crate::async_optimize::{ConcurrencyConfig, ConcurrentExecutor, TaskResult};
+use crate::error::{QueryError, QueryResult};
+use crate::traits::BoxFuture;
+
+use super::include::IncludeSpec;
+
+/// A loaded relation, downcast with [`LoadedRelations::take`].
+pub type LoadedRelation = Box<dyn Any + Send>;
+
+type LoadFn<M> = Box<dyn FnOnce(Arc<M>) -> BoxFuture<'static, QueryResult<LoadedRelation>> + Send>;
+
+/// An include together with the function that loads it for a parent record.
+///
+/// Passed to
+/// [`FindUniqueOperation::include`](crate::operations::FindUniqueOperation::include).
+/// Codegen generates one per relation as `<model>::<relation>::fetch(engine)`,
+/// loading the relation's records by key; custom loads are built with
+/// [`IncludeLoad::new`].
+pub struct IncludeLoad<M> {
+    spec: IncludeSpec,
+    load: LoadFn<M>,
+}
+
+impl<M: Send + Sync + 'static> IncludeLoad<M> {
+    /// Create an include load.
+    pub fn new<F, Fut, T>(spec: IncludeSpec, load: F) -> Self
+    where
+        F: FnOnce(Arc<M>) -> Fut + Send + 'static,
+        Fut: Future<Output = QueryResult<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        Self {
+            spec,
+            load: Box::new(move |parent| {
+                let future = load(parent);
+                Box::pin(async move { future.await.map(|value| Box::new(value) as LoadedRelation) })
+            }),
+        }
+    }
+
+    /// Name of the included relation.
+    pub fn relation(&self) -> &str {
+        &self.spec.relation_name
+    }
+
+    /// The include specification.
+    pub fn spec(&self) -> &IncludeSpec {
+        &self.spec
+    }
+}
+
+impl<M> std::fmt::Debug for IncludeLoad<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IncludeLoad")
+            .field("spec", &self.spec)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Relations loaded for a record, keyed by relation name.
+#[derive(Default)]
+pub struct LoadedRelations {
+    relations: HashMap<String, LoadedRelation>,
+}
+
+impl LoadedRelations {
+    /// Take a loaded relation, if present and of type `T`.
+    pub fn take<T: 'static>(&mut self, relation: &str) -> Option<T> {
+        let value = self.relations.remove(relation)?;
+        match value.downcast::<T>() {
+            Ok(value) => Some(*value),
+            Err(value) => {
+                self.relations.insert(relation.to_string(), value);
+                None
+            }
+        }
+    }
+
+    /// Check if a relation was loaded.
+    pub fn contains(&self, relation: &str) -> bool {
+        self.relations.contains_key(relation)
+    }
+
+    /// Names of the loaded relations.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.relations.keys().map(String::as_str)
+    }
+
+    /// Number of loaded relations.
+    pub fn len(&self) -> usize {
+        self.relations.len()
+    }
+
+    /// Check if no relations were loaded.
+    pub fn is_empty(&self) -> bool {
+        self.relations.is_empty()
+    }
+}
+
+impl std::fmt::Debug for LoadedRelations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.relations.keys()).finish()
+    }
+}
+
+/// A record with its included relations.
+#[derive(Debug)]
+pub struct LoadedRecord<M> {
+    /// The parent record.
+    pub record: M,
+    /// Its included relations.
+    pub relations: LoadedRelations,
+}
+
+/// Load includes for a parent record concurrently.
+///
+/// At most `config.max_concurrency` loads run at once. When loads fail, the
+/// error of the first failing include (in declaration order) is returned with
+/// the relation name as its field and operation context; the messages of any
+/// other failures are attached as related errors.
+pub async fn load_includes<M: Send + Sync + 'static>(
+    parent: M,
+    includes: Vec<IncludeLoad<M>>,
+    config: &ConcurrencyConfig,
+) -> QueryResult<LoadedRecord<M>> {
+    if includes.is_empty() {
+        return Ok(LoadedRecord {
+            record: parent,
+            relations: LoadedRelations::default(),
+        });
+    }
+
+    let parent = Arc::new(parent);
+    let names: Vec<String> = includes.iter().map(|i| i.relation().to_string()).collect();
+    debug!(relations = ?names, "Loading includes concurrently");
+
+    let tasks = includes.into_iter().map(|include| {
+        let parent = Arc::clone(&parent);
+        move || {
+            let future = (include.load)(parent);
+            // Keep the QueryError intact; the executor only sees timeouts
+            async move { Ok::<_, String>(future.await) }
+        }
+    });
+
+    // Relation loads never fail the executor, so fail-fast would not apply
+    let executor = ConcurrentExecutor::new(config.clone().with_continue_on_error(true));
+    let (results, _stats) = executor.execute_all(tasks).await;
+
+    let mut relations = LoadedRelations::default();
+    let mut errors = Vec::new();
+    for result in results {
+        let (task_id, outcome) = match result {
+            TaskResult::Success { task_id, value, .. } => (task_id, value),
+            TaskResult::Error(e) => (e.task_id, Err(timeout_error(config))),
+        };
+        let name = &names[task_id];
+        match outcome {
+            Ok(value) => {
+                relations.relations.insert(name.clone(), value);
+            }
+            Err(e) => errors.push((task_id, name.clone(), e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|(task_id, _, _)| *task_id);
+        let mut errors = errors.into_iter();
+        let (_, name, first) = errors.next().expect("at least one error");
+        let mut error = first
+            .with_context(format!("loading include `{}`", name))
+            .with_field(&name);
+        error
+            .context_mut()
+            .related
+            .extend(errors.map(|(_, name, e)| format!("include `{}`: {}", name, e)));
+        return Err(error);
+    }
+
+    let record = Arc::try_unwrap(parent).map_err(|_| {
+        QueryError::internal("an include loader kept a reference to the parent record")
+    })?;
+    Ok(LoadedRecord { record, relations })
+}
+
+fn timeout_error(config: &ConcurrencyConfig) -> QueryError {
+    let ms = config
+        .operation_timeout
+        .map_or(0, |timeout| timeout.as_millis() as u64);
+    QueryError::timeout(ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::Barrier;
+
+    #[derive(Debug, PartialEq)]
+    struct User {
+        id: i64,
+    }
+
+    #[tokio::test]
+    async fn test_includes_load_concurrently() {
+        // Both loads wait for each other, so a sequential run would time out
+        let barrier = Arc::new(Barrier::new(2));
+        let includes = ["posts", "comments"]
+            .into_iter()
+            .map(|name| {
+                let barrier = Arc::clone(&barrier);
+                IncludeLoad::new(IncludeSpec::new(name), move |user: Arc<User>| async move {
+                    barrier.wait().await;
+                    Ok(vec![format!("{}-{}", name, user.id)])
+                })
+            })
+            .collect();
+
+        let config = ConcurrencyConfig::default()
+            .with_max_concurrency(2)
+            .with_timeout(Duration::from_secs(5));
+        let mut loaded = load_includes(User { id: 7 }, includes, &config)
+            .await
+            .unwrap();
+
+        assert_eq!(loaded.record, User { id: 7 });
+        assert_eq!(loaded.relations.len(), 2);
+        assert!(loaded.relations.take::<String>("posts").is_none());
+        assert_eq!(
+            loaded.relations.take::<Vec<String>>("posts"),
+            Some(vec!["posts-7".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_include_error_attribution() {
+        let includes = vec![
+            IncludeLoad::new(IncludeSpec::new("profile"), |_: Arc<User>| async {
+                Ok(Some("bio"))
+            }),
+            IncludeLoad::new(IncludeSpec::new("posts"), |_: Arc<User>| async {
+                Err::<(), _>(QueryError::database("relation \"posts\" does not exist"))
+            }),
+            IncludeLoad::new(IncludeSpec::new("tags"), |_: Arc<User>| async {
+                Err::<(), _>(QueryError::database("permission denied"))
+            }),
+        ];
+
+        let err = load_includes(User { id: 1 }, includes, &ConcurrencyConfig::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.context().field.as_deref(), Some("posts"));
+        assert_eq!(
+            err.context().operation.as_deref(),
+            Some("loading include `posts`")
+        );
+        assert!(err.message.contains("does not exist"));
+        assert_eq!(err.context().related.len(), 1);
+        assert!(err.context().related[0].contains("include `tags`"));
+    }
+
+    #[tokio::test]
+    async fn test_include_timeout() {
+        let includes = vec![IncludeLoad::new(
+            IncludeSpec::new("posts"),
+            |_: Arc<User>| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            },
+        )];
+        let config = ConcurrencyConfig::default().with_timeout(Duration::from_millis(10));
+
+        let err = load_includes(User { id: 1 }, includes, &config)
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(err.context().field.as_deref(), Some("posts"));
+    }
+}
@@ -12,8 +12,40 @@
 //! | Connection routing | ✅         | ✅    | ❌     | ✅        | ✅          |
 //! | Auto-failover      | ✅         | ✅    | ❌     | ✅        | ✅          |
 //! | Read preference    | ❌         | ❌    | ❌     | ❌        | ✅          |
+//!
+//! # Lag-aware routing and failover
+//!
+//! [`ConnectionRouter`] stops routing reads to replicas lagging more than
+//! their `max_lag` (or the router's [`with_max_lag`](ConnectionRouter::with_max_lag)),
+//! and promotes a replica when the primary fails its health checks:
+//!
+//! ```rust,ignore
+//! use prax_query::replication::{ConnectionRouter, ReplicaSetConfig, RouterEvent};
+//!
+//! // `cluster` is the schema's `serverGroup`, tried in its failover order
+//! let order = cluster.failover_order().iter().map(|s| s.name.as_str());
+//! let config = ReplicaSetConfig::new("main")
+//!     .primary("primary", primary_url)
+//!     .secondary("replica1", replica1_url)
+//!     .secondary("replica2", replica2_url)
+//!     .failover_order(order)
+//!     .build();
+//!
+//! let mut router = ConnectionRouter::new(config)
+//!     .with_max_lag(Duration::from_secs(5))
+//!     .with_listener(|event| {
+//!         if let RouterEvent::Failover { to, .. } = event {
+//!             tracing::warn!(primary = %to, "replica promoted");
+//!         }
+//!     });
+//!
+//! // On every health check round
+//! router.apply_lag(&lag_monitor);
+//! router.update_health("primary", HealthStatus::Unhealthy, None, None);
+//! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
@@ -126,6 +158,10 @@ pub struct ReplicaSetConfig {
     pub health_check_interval: Duration,
     /// Failover timeout.
     pub failover_timeout: Duration,
+    /// Replica IDs in the order they are promoted on failover. Without one,
+    /// the healthy secondary with the highest priority is promoted.
+    #[serde(default)]
+    pub failover_order: Vec<String>,
 }
 
 impl ReplicaSetConfig {
@@ -160,6 +196,7 @@ pub struct ReplicaSetBuilder {
     default_read_preference: ReadPreference,
     health_check_interval: Duration,
     failover_timeout: Duration,
+    failover_order: Vec<String>,
 }
 
 impl ReplicaSetBuilder {
@@ -171,6 +208,7 @@ impl ReplicaSetBuilder {
             default_read_preference: ReadPreference::Primary,
             health_check_interval: Duration::from_secs(10),
             failover_timeout: Duration::from_secs(30),
+            failover_order: Vec::new(),
         }
     }
 
@@ -208,6 +246,13 @@ impl ReplicaSetBuilder {
         self
     }
 
+    /// Set the order replicas are promoted in on failover, such as a
+    /// `serverGroup`'s `failover_order()`.
+    pub fn failover_order(mut self, ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.failover_order = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Build the config.
     pub fn build(self) -> ReplicaSetConfig {
         ReplicaSetConfig {
@@ -216,6 +261,7 @@ impl ReplicaSetBuilder {
             default_read_preference: self.default_read_preference,
             health_check_interval: self.health_check_interval,
             failover_timeout: self.failover_timeout,
+            failover_order: self.failover_order,
         }
     }
 }
@@ -377,8 +423,40 @@ pub enum QueryType {
     Transaction,
 }
 
+/// An event emitted by the [`ConnectionRouter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouterEvent {
+    /// A replica went over its maximum lag and no longer serves reads.
+    ReplicaLagging {
+        /// The replica.
+        id: String,
+        /// The measured lag.
+        lag: Duration,
+    },
+    /// A lagging replica caught up and serves reads again.
+    ReplicaCaughtUp {
+        /// The replica.
+        id: String,
+        /// The measured lag.
+        lag: Duration,
+    },
+    /// A replica was promoted to primary.
+    Failover {
+        /// The previous primary.
+        from: Option<String>,
+        /// The new primary.
+        to: String,
+    },
+    /// The primary failed but no replica could be promoted.
+    FailoverFailed {
+        /// The failed primary.
+        from: Option<String>,
+    },
+}
+
+type RouterListener = Arc<dyn Fn(&RouterEvent) + Send + Sync>;
+
 /// Connection router for read/write splitting.
-#[derive(Debug)]
 pub struct ConnectionRouter {
     /// Replica set configuration.
     config: ReplicaSetConfig,
@@ -390,6 +468,14 @@ pub struct ConnectionRouter {
     round_robin: AtomicUsize,
     /// Whether router is in failover mode.
     in_failover: AtomicBool,
+    /// Lag threshold overriding the replicas' own `max_lag`.
+    max_lag: Option<Duration>,
+    /// Replicas excluded from reads for lagging.
+    lagging: HashSet<String>,
+    /// Consecutive failed health checks before the primary is replaced.
+    failover_threshold: u32,
+    /// Listeners for router events.
+    listeners: Vec<RouterListener>,
 }
 
 impl ConnectionRouter {
@@ -411,9 +497,36 @@ impl ConnectionRouter {
             current_primary: primary_id,
             round_robin: AtomicUsize::new(0),
             in_failover: AtomicBool::new(false),
+            max_lag: None,
+            lagging: HashSet::new(),
+            failover_threshold: 1,
+            listeners: Vec::new(),
         }
     }
 
+    /// Drop replicas from reads when they lag more than `max_lag`, instead
+    /// of each replica's own `max_lag`.
+    pub fn with_max_lag(mut self, max_lag: Duration) -> Self {
+        self.max_lag = Some(max_lag);
+        self
+    }
+
+    /// Fail over after the primary fails `n` health checks in a row
+    /// (default 1).
+    pub fn with_failover_threshold(mut self, n: u32) -> Self {
+        self.failover_threshold = n.max(1);
+        self
+    }
+
+    /// Register a listener for router events.
+    pub fn with_listener<F>(mut self, listener: F) -> Self
+    where
+        F: Fn(&RouterEvent) + Send + Sync + 'static,
+    {
+        self.listeners.push(Arc::new(listener));
+        self
+    }
+
     /// Get replica for a query based on query type and read preference.
     pub fn route(&self, query_type: QueryType, preference: Option<&ReadPreference>) -> QueryResult<&ReplicaConfig> {
         let pref = preference.unwrap_or(&self.config.default_read_preference);
@@ -517,12 +630,72 @@ impl ConnectionRouter {
         Ok(replicas[idx])
     }
 
-    /// Check if a replica is healthy.
+    /// Check if a replica is healthy and within its maximum lag.
     fn is_replica_healthy(&self, id: &str) -> bool {
-        self.health
-            .get(id)
-            .map(|h| h.is_usable())
-            .unwrap_or(false)
+        !self.lagging.contains(id) && self.health.get(id).map(|h| h.is_usable()).unwrap_or(false)
+    }
+
+    /// Check if a replica is excluded from reads for lagging.
+    pub fn is_lagging(&self, id: &str) -> bool {
+        self.lagging.contains(id)
+    }
+
+    /// Record the replication lag of a replica, dropping it from reads
+    /// while it is over its maximum lag.
+    pub fn record_lag(&mut self, id: &str, lag: Duration) {
+        let Some(health) = self.health.get_mut(id) else {
+            return;
+        };
+        health.lag = Some(lag);
+        self.check_replica_lag(id);
+    }
+
+    /// Record the current lag of every replica measured by `monitor`.
+    pub fn apply_lag(&mut self, monitor: &LagMonitor) {
+        let measured: Vec<(String, Duration)> = self
+            .config
+            .replicas
+            .iter()
+            .filter_map(|r| Some((r.id.clone(), monitor.get_lag(&r.id)?)))
+            .collect();
+        for (id, lag) in measured {
+            self.record_lag(&id, lag);
+        }
+    }
+
+    fn check_replica_lag(&mut self, id: &str) {
+        if self.current_primary.as_deref() == Some(id) {
+            return;
+        }
+        let Some(lag) = self.health.get(id).and_then(|h| h.lag) else {
+            return;
+        };
+        let limit = self.max_lag.or_else(|| {
+            self.config
+                .replicas
+                .iter()
+                .find(|r| r.id == id)
+                .and_then(|r| r.max_lag)
+        });
+        let over = limit.is_some_and(|limit| lag > limit);
+
+        if over && self.lagging.insert(id.to_string()) {
+            self.emit(RouterEvent::ReplicaLagging {
+                id: id.to_string(),
+                lag,
+            });
+        } else if !over && self.lagging.remove(id) {
+            self.emit(RouterEvent::ReplicaCaughtUp {
+                id: id.to_string(),
+                lag,
+            });
+        }
+    }
+
+    fn emit(&self, event: RouterEvent) {
+        for listener in &self.listeners {
+            listener(&event);
+        }
     }
 
     /// Update health status of a replica.
@@ -541,6 +714,18 @@ impl ConnectionRouter {
                 HealthStatus::Unknown => {}
             }
         }
+        if lag.is_some() {
+            self.check_replica_lag(id);
+        }
+
+        let primary_failed = self.current_primary.as_deref() == Some(id)
+            && self
+                .health
+                .get(id)
+                .is_some_and(|h| h.consecutive_failures >= self.failover_threshold);
+        if primary_failed {
+            let _ = self.initiate_failover();
+        }
     }
 
     /// Check if replication lag is acceptable.
@@ -553,27 +738,48 @@ impl ConnectionRouter {
     }
 
     /// Initiate failover to a new primary.
+    ///
+    /// The first healthy replica in the configured failover order is
+    /// promoted, or without an order the healthy secondary with the highest
+    /// priority. Lagging replicas are skipped.
     pub fn initiate_failover(&mut self) -> QueryResult<String> {
         self.in_failover.store(true, Ordering::SeqCst);
 
-        // Find best candidate (highest priority secondary that is healthy)
-        let candidate = self
-            .config
-            .replicas
-            .iter()
-            .filter(|r| r.role == ReplicaRole::Secondary)
-            .filter(|r| self.is_replica_healthy(&r.id))
-            .max_by_key(|r| r.priority);
+        let previous = self.current_primary.clone();
+        let eligible = |r: &&ReplicaConfig| {
+            Some(&r.id) != previous.as_ref()
+                && matches!(r.role, ReplicaRole::Primary | ReplicaRole::Secondary)
+                && self.is_replica_healthy(&r.id)
+        };
+        let candidate = if self.config.failover_order.is_empty() {
+            self.config
+                .replicas
+                .iter()
+                .filter(|r| r.role == ReplicaRole::Secondary)
+                .filter(eligible)
+                .max_by_key(|r| r.priority)
+        } else {
+            self.config
+                .failover_order
+                .iter()
+                .filter_map(|id| self.config.replicas.iter().find(|r| &r.id == id))
+                .find(eligible)
+        };
+        let candidate = candidate.map(|r| r.id.clone());
+        self.in_failover.store(false, Ordering::SeqCst);
 
         match candidate {
-            Some(new_primary) => {
-                let new_primary_id = new_primary.id.clone();
+            Some(new_primary_id) => {
                 self.current_primary = Some(new_primary_id.clone());
-                self.in_failover.store(false, Ordering::SeqCst);
+                self.lagging.remove(&new_primary_id);
+                self.emit(RouterEvent::Failover {
+                    from: previous,
+                    to: new_primary_id.clone(),
+                });
                 Ok(new_primary_id)
             }
             None => {
-                self.in_failover.store(false, Ordering::SeqCst);
+                self.emit(RouterEvent::FailoverFailed { from: previous });
                 Err(QueryError::connection("No suitable failover candidate found"))
             }
         }
@@ -585,6 +791,20 @@ impl ConnectionRouter {
     }
 }
 
+impl std::fmt::Debug for ConnectionRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionRouter")
+            .field("config", &self.config)
+            .field("health", &self.health)
+            .field("current_primary", &self.current_primary)
+            .field("in_failover", &self.in_failover)
+            .field("max_lag", &self.max_lag)
+            .field("lagging", &self.lagging)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
 // ============================================================================
 // Replication Lag Monitor
 // ============================================================================
@@ -1018,6 +1238,106 @@ mod tests {
         assert_eq!(new_primary, "pg2"); // Higher priority
     }
 
+    fn recorder() -> (
+        Arc<std::sync::Mutex<Vec<RouterEvent>>>,
+        impl Fn(&RouterEvent) + Send + Sync,
+    ) {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        (events, move |event: &RouterEvent| {
+            sink.lock().unwrap().push(event.clone())
+        })
+    }
+
+    #[test]
+    fn test_lagging_replicas_dropped() {
+        let config = ReplicaSetConfig::new("test")
+            .primary("pg1", "postgres://primary:5432/db")
+            .secondary("pg2", "postgres://secondary1:5432/db")
+            .secondary("pg3", "postgres://secondary2:5432/db")
+            .read_preference(ReadPreference::Secondary)
+            .build();
+        let (events, listener) = recorder();
+        let mut router = ConnectionRouter::new(config)
+            .with_max_lag(Duration::from_secs(5))
+            .with_listener(listener);
+        for id in ["pg1", "pg2", "pg3"] {
+            router.update_health(
+                id,
+                HealthStatus::Healthy,
+                Some(Duration::from_millis(5)),
+                None,
+            );
+        }
+
+        let mut monitor = LagMonitor::new(Duration::from_secs(10));
+        monitor.record("pg2", Duration::from_secs(8));
+        monitor.record("pg3", Duration::from_secs(1));
+        router.apply_lag(&monitor);
+
+        assert!(router.is_lagging("pg2"));
+        for _ in 0..4 {
+            assert_eq!(router.route(QueryType::Read, None).unwrap().id, "pg3");
+        }
+
+        router.record_lag("pg2", Duration::from_secs(2));
+        assert!(!router.is_lagging("pg2"));
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                RouterEvent::ReplicaLagging {
+                    id: "pg2".to_string(),
+                    lag: Duration::from_secs(8),
+                },
+                RouterEvent::ReplicaCaughtUp {
+                    id: "pg2".to_string(),
+                    lag: Duration::from_secs(2),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_automatic_failover_order() {
+        let config = ReplicaSetConfig::new("test")
+            .primary("pg1", "postgres://primary:5432/db")
+            .replica(
+                ReplicaConfig::secondary("pg2", "postgres://secondary1:5432/db").with_priority(80),
+            )
+            .secondary("pg3", "postgres://secondary2:5432/db")
+            .secondary("pg4", "postgres://secondary3:5432/db")
+            .failover_order(["pg4", "pg3", "pg2"])
+            .build();
+        let (events, listener) = recorder();
+        let mut router = ConnectionRouter::new(config)
+            .with_failover_threshold(2)
+            .with_listener(listener);
+        for id in ["pg1", "pg2", "pg3", "pg4"] {
+            router.update_health(
+                id,
+                HealthStatus::Healthy,
+                Some(Duration::from_millis(5)),
+                None,
+            );
+        }
+        // pg4 is first in order but too far behind to be promoted
+        router.record_lag("pg4", Duration::from_secs(60));
+
+        router.update_health("pg1", HealthStatus::Unhealthy, None, None);
+        assert_eq!(router.get_primary().unwrap().id, "pg1");
+        router.update_health("pg1", HealthStatus::Unhealthy, None, None);
+        assert_eq!(router.get_primary().unwrap().id, "pg3");
+        assert_eq!(router.route(QueryType::Write, None).unwrap().id, "pg3");
+
+        assert_eq!(
+            events.lock().unwrap().last(),
+            Some(&RouterEvent::Failover {
+                from: Some("pg1".to_string()),
+                to: "pg3".to_string(),
+            })
+        );
+    }
+
     mod mongodb_tests {
         use super::super::mongodb::*;
         use super::*;
@@ -0,0 +1,709 @@
+//! Declarative data retention with batched, scheduled purges.
+//!
+//! Models declare how long their rows are kept with the `@@retention`
+//! attribute:
+//!
+//! ```prax
+//! model AuditLog {
+//!     id        Int      @id @auto
+//!     createdAt DateTime @default(now())
+//!
+//!     @@retention(field: createdAt, keep: "90d", strategy: archive)
+//! }
+//! ```
+//!
+//! Generated clients expose the policy as `audit_log::retention_policy()`.
+//! A [`RetentionRunner`] purges expired rows in small batches so that no single
+//! statement holds locks for long, optionally only inside an off-peak window.
+//! With the archive strategy, each batch is first copied to an archive table
+//! or handed to a [`RetentionArchive`] sink (such as the Parquet archive in
+//! `prax-duckdb`) and deleted afterwards.
+//!
+//! ```rust,ignore
+//! use prax_query::retention::{OffPeakWindow, RetentionRunner};
+//! use std::time::Duration;
+//!
+//! let runner = RetentionRunner::new(db)
+//!     .with_batch_size(5_000)
+//!     .with_off_peak(OffPeakWindow::new(1, 5))
+//!     .on_progress(|report| tracing::info!(table = %report.table, purged = report.rows_purged));
+//!
+//! // See what would be purged
+//! let report = runner.dry_run(&audit_log::retention_policy()).await?;
+//!
+//! // Purge every night
+//! let handle = runner.spawn(vec![audit_log::retention_policy()], Duration::from_secs(600));
+//! ```
+//!
+//! Archiving is at-least-once: if a delete fails after its batch was archived,
+//! the next run archives those rows again.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value as JsonValue;
+use tracing::{debug, info, warn};
+
+use crate::error::{QueryError, QueryResult};
+use crate::raw::RawDatabase;
+use crate::sql::{DatabaseType, quote_identifier};
+use crate::traits::BoxFuture;
+
+/// Default number of rows purged per batch.
+pub const DEFAULT_RETENTION_BATCH_SIZE: usize = 1000;
+
+/// A destination for archived rows.
+pub trait RetentionArchive: Send + Sync {
+    /// Archive a batch of expired rows from `table`.
+    fn archive<'a>(
+        &'a self,
+        table: &'a str,
+        rows: Vec<JsonValue>,
+    ) -> BoxFuture<'a, QueryResult<()>>;
+}
+
+/// Where archived rows go.
+#[derive(Clone)]
+pub enum ArchiveTarget {
+    /// An archive table with the same columns, in the same database.
+    Table(String),
+    /// A custom sink.
+    Sink(Arc<dyn RetentionArchive>),
+}
+
+impl std::fmt::Debug for ArchiveTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Table(table) => f.debug_tuple("Table").field(table).finish(),
+            Self::Sink(_) => f.debug_tuple("Sink").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// What happens to expired rows.
+#[derive(Debug, Clone)]
+pub enum RetentionStrategy {
+    /// Delete expired rows.
+    Delete,
+    /// Archive expired rows, then delete them.
+    Archive(ArchiveTarget),
+}
+
+impl RetentionStrategy {
+    /// Get the strategy name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Archive(_) => "archive",
+        }
+    }
+}
+
+/// A retention policy for one table.
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// The table to purge.
+    pub table: String,
+    /// The timestamp column rows expire by.
+    pub column: String,
+    /// Columns that order rows within a batch, normally the primary key.
+    pub key: Vec<String>,
+    /// How long rows are kept.
+    pub keep: Duration,
+    /// What happens to expired rows.
+    pub strategy: RetentionStrategy,
+}
+
+impl RetentionPolicy {
+    /// Create a policy that deletes rows of `table` once `column` is older than `keep`.
+    pub fn new(table: impl Into<String>, column: impl Into<String>, keep: Duration) -> Self {
+        Self {
+            table: table.into(),
+            column: column.into(),
+            key: Vec::new(),
+            keep,
+            strategy: RetentionStrategy::Delete,
+        }
+    }
+
+    /// Set the columns that order rows within a batch.
+    ///
+    /// Without a key, batches are ordered by the timestamp column alone.
+    pub fn key<I, S>(mut self, key: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.key = key.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Archive expired rows to a table before deleting them.
+    pub fn archive_to_table(mut self, table: impl Into<String>) -> Self {
+        self.strategy = RetentionStrategy::Archive(ArchiveTarget::Table(table.into()));
+        self
+    }
+
+    /// Archive expired rows to a sink before deleting them.
+    pub fn archive_to(mut self, sink: impl RetentionArchive + 'static) -> Self {
+        self.strategy = RetentionStrategy::Archive(ArchiveTarget::Sink(Arc::new(sink)));
+        self
+    }
+
+    /// Default archive table name (`<table>_archive`).
+    pub fn default_archive_table(&self) -> String {
+        format!("{}_archive", self.table)
+    }
+
+    /// SQL expression for the expiry cutoff.
+    fn cutoff_sql(&self, db: DatabaseType) -> String {
+        let secs = self.keep.as_secs();
+        match db {
+            DatabaseType::PostgreSQL => format!("NOW() - INTERVAL '{} seconds'", secs),
+            DatabaseType::MySQL => format!("NOW() - INTERVAL {} SECOND", secs),
+            DatabaseType::SQLite => format!("datetime('now', '-{} seconds')", secs),
+            DatabaseType::MSSQL => format!("DATEADD(second, -{}, SYSUTCDATETIME())", secs),
+        }
+    }
+
+    fn expired_sql(&self, db: DatabaseType) -> String {
+        format!(
+            "{} < {}",
+            quote_identifier(&self.column),
+            self.cutoff_sql(db)
+        )
+    }
+
+    fn order_columns(&self) -> String {
+        if self.key.is_empty() {
+            quote_identifier(&self.column)
+        } else {
+            self.key
+                .iter()
+                .map(|c| quote_identifier(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+
+    /// SQL selecting the next batch of expired rows.
+    fn select_batch_sql(&self, db: DatabaseType, columns: &str, limit: usize) -> String {
+        let table = quote_identifier(&self.table);
+        let expired = self.expired_sql(db);
+        let order = self.order_columns();
+        match db {
+            DatabaseType::MSSQL => format!(
+                "SELECT TOP ({}) {} FROM {} WHERE {} ORDER BY {}",
+                limit, columns, table, expired, order
+            ),
+            _ => format!(
+                "SELECT {} FROM {} WHERE {} ORDER BY {} LIMIT {}",
+                columns, table, expired, order, limit
+            ),
+        }
+    }
+
+    /// SQL counting expired rows.
+    fn count_sql(&self, db: DatabaseType) -> String {
+        format!(
+            "SELECT COUNT(*) AS count FROM {} WHERE {}",
+            quote_identifier(&self.table),
+            self.expired_sql(db)
+        )
+    }
+
+    /// SQL deleting the next batch of expired rows.
+    fn delete_batch_sql(&self, db: DatabaseType, limit: usize) -> String {
+        let table = quote_identifier(&self.table);
+        match db {
+            DatabaseType::MySQL => format!(
+                "DELETE FROM {} WHERE {} ORDER BY {} LIMIT {}",
+                table,
+                self.expired_sql(db),
+                self.order_columns(),
+                limit
+            ),
+            DatabaseType::MSSQL => format!(
+                "WITH batch AS ({}) DELETE FROM batch",
+                self.select_batch_sql(db, "*", limit)
+            ),
+            DatabaseType::PostgreSQL | DatabaseType::SQLite => {
+                let key = self.order_columns();
+                format!(
+                    "DELETE FROM {} WHERE ({}) IN ({})",
+                    table,
+                    key,
+                    self.select_batch_sql(db, &key, limit)
+                )
+            }
+        }
+    }
+
+    /// SQL copying the next batch of expired rows to an archive table.
+    fn archive_batch_sql(&self, db: DatabaseType, archive: &str, limit: usize) -> String {
+        format!(
+            "INSERT INTO {} {}",
+            quote_identifier(archive),
+            self.select_batch_sql(db, "*", limit)
+        )
+    }
+}
+
+/// A daily window in which purges may run, in UTC hours.
+///
+/// A window whose end is before its start wraps around midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffPeakWindow {
+    /// First hour of the window (0-23).
+    pub start_hour: u8,
+    /// Hour the window ends (0-23, exclusive).
+    pub end_hour: u8,
+}
+
+impl OffPeakWindow {
+    /// Create a window from `start_hour` up to `end_hour` (UTC).
+    pub fn new(start_hour: u8, end_hour: u8) -> Self {
+        Self {
+            start_hour: start_hour % 24,
+            end_hour: end_hour % 24,
+        }
+    }
+
+    /// Check if the window contains the given hour.
+    pub fn contains_hour(&self, hour: u8) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+
+    /// Check if the current UTC time is inside the window.
+    pub fn is_active(&self) -> bool {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.contains_hour(((secs / 3600) % 24) as u8)
+    }
+}
+
+/// Outcome (or progress) of purging one table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionReport {
+    /// The purged table.
+    pub table: String,
+    /// The strategy applied.
+    pub strategy: &'static str,
+    /// Whether this was a dry run.
+    pub dry_run: bool,
+    /// Rows past their retention period when the run started.
+    pub expired_rows: u64,
+    /// Rows deleted so far.
+    pub rows_purged: u64,
+    /// Rows archived so far.
+    pub rows_archived: u64,
+    /// Batches processed so far.
+    pub batches: u64,
+    /// Whether all expired rows were processed; `false` if the run stopped
+    /// at the end of the off-peak window.
+    pub completed: bool,
+    /// Time spent.
+    pub elapsed: Duration,
+}
+
+type ProgressFn = Arc<dyn Fn(&RetentionReport) + Send + Sync>;
+
+/// Runs retention policies against a database.
+pub struct RetentionRunner<D> {
+    db: D,
+    batch_size: usize,
+    pause: Duration,
+    off_peak: Option<OffPeakWindow>,
+    progress: Option<ProgressFn>,
+}
+
+impl<D: RawDatabase> RetentionRunner<D> {
+    /// Create a runner.
+    pub fn new(db: D) -> Self {
+        Self {
+            db,
+            batch_size: DEFAULT_RETENTION_BATCH_SIZE,
+            pause: Duration::ZERO,
+            off_peak: None,
+            progress: None,
+        }
+    }
+
+    /// Set the number of rows purged per batch (at least 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Pause between batches to leave room for other traffic.
+    pub fn with_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Only purge inside the given off-peak window.
+    pub fn with_off_peak(mut self, window: OffPeakWindow) -> Self {
+        self.off_peak = Some(window);
+        self
+    }
+
+    /// Report progress after every batch.
+    pub fn on_progress<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RetentionReport) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(f));
+        self
+    }
+
+    /// Get the database.
+    pub fn database(&self) -> &D {
+        &self.db
+    }
+
+    /// Report how many rows a policy would purge, without changing anything.
+    pub async fn dry_run(&self, policy: &RetentionPolicy) -> QueryResult<RetentionReport> {
+        let start = Instant::now();
+        let expired_rows = self.count_expired(policy).await?;
+        let batches = expired_rows.div_ceil(self.batch_size as u64);
+
+        Ok(RetentionReport {
+            table: policy.table.clone(),
+            strategy: policy.strategy.name(),
+            dry_run: true,
+            expired_rows,
+            batches,
+            completed: true,
+            elapsed: start.elapsed(),
+            ..Default::default()
+        })
+    }
+
+    /// Purge the expired rows of a policy in batches.
+    ///
+    /// Stops early, with `completed` unset, when the off-peak window ends.
+    pub async fn run(&self, policy: &RetentionPolicy) -> QueryResult<RetentionReport> {
+        let start = Instant::now();
+        let mut report = RetentionReport {
+            table: policy.table.clone(),
+            strategy: policy.strategy.name(),
+            ..Default::default()
+        };
+
+        if !self.in_window() {
+            debug!(table = %policy.table, "Outside off-peak window, skipping retention");
+            return Ok(report);
+        }
+
+        report.expired_rows = self.count_expired(policy).await?;
+        info!(
+            table = %policy.table,
+            expired = report.expired_rows,
+            strategy = report.strategy,
+            "Running retention policy"
+        );
+
+        loop {
+            let purged = self
+                .purge_batch(policy, &mut report)
+                .await
+                .map_err(|e| e.with_context(format!("retention for table `{}`", policy.table)))?;
+            if purged == 0 {
+                report.completed = true;
+                break;
+            }
+
+            report.batches += 1;
+            report.rows_purged += purged;
+            report.elapsed = start.elapsed();
+            if let Some(progress) = &self.progress {
+                progress(&report);
+            }
+
+            if purged < self.batch_size as u64 {
+                report.completed = true;
+                break;
+            }
+            if !self.in_window() {
+                warn!(table = %policy.table, "Off-peak window ended, pausing retention");
+                break;
+            }
+            if !self.pause.is_zero() {
+                tokio::time::sleep(self.pause).await;
+            }
+        }
+
+        report.elapsed = start.elapsed();
+        Ok(report)
+    }
+
+    /// Run several policies one after another.
+    pub async fn run_all(&self, policies: &[RetentionPolicy]) -> QueryResult<Vec<RetentionReport>> {
+        let mut reports = Vec::with_capacity(policies.len());
+        for policy in policies {
+            reports.push(self.run(policy).await?);
+        }
+        Ok(reports)
+    }
+
+    /// Run the policies every `interval` in a background task.
+    ///
+    /// With an off-peak window set, ticks outside the window do nothing.
+    pub fn spawn(
+        self,
+        policies: Vec<RetentionPolicy>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        D: 'static,
+    {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_all(&policies).await {
+                    warn!(error = %e, "Retention run failed");
+                }
+            }
+        })
+    }
+
+    fn in_window(&self) -> bool {
+        self.off_peak.is_none_or(|w| w.is_active())
+    }
+
+    async fn count_expired(&self, policy: &RetentionPolicy) -> QueryResult<u64> {
+        let rows = self
+            .db
+            .query(&policy.count_sql(self.db.database_type()), Vec::new())
+            .await?;
+
+        let count = rows.first().and_then(|row| {
+            let value = row
+                .get("count")
+                .or_else(|| row.as_object()?.values().next())?;
+            value
+                .as_u64()
+                .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+        });
+        count.ok_or_else(|| QueryError::deserialization("retention count returned no rows"))
+    }
+
+    /// Archive (if needed) and delete one batch, returning the rows deleted.
+    async fn purge_batch(
+        &self,
+        policy: &RetentionPolicy,
+        report: &mut RetentionReport,
+    ) -> QueryResult<u64> {
+        let db_type = self.db.database_type();
+
+        match &policy.strategy {
+            RetentionStrategy::Delete => {}
+            RetentionStrategy::Archive(ArchiveTarget::Table(archive)) => {
+                let sql = policy.archive_batch_sql(db_type, archive, self.batch_size);
+                report.rows_archived += self.db.execute(&sql, Vec::new()).await?;
+            }
+            RetentionStrategy::Archive(ArchiveTarget::Sink(sink)) => {
+                let sql = policy.select_batch_sql(db_type, "*", self.batch_size);
+                let rows = self.db.query(&sql, Vec::new()).await?;
+                if rows.is_empty() {
+                    return Ok(0);
+                }
+                let count = rows.len() as u64;
+                sink.archive(&policy.table, rows).await?;
+                report.rows_archived += count;
+            }
+        }
+
+        let sql = policy.delete_batch_sql(db_type, self.batch_size);
+        self.db.execute(&sql, Vec::new()).await
+    }
+}
+
+impl<D> std::fmt::Debug for RetentionRunner<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetentionRunner")
+            .field("batch_size", &self.batch_size)
+            .field("pause", &self.pause)
+            .field("off_peak", &self.off_peak)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::FilterValue;
+    use parking_lot::Mutex;
+
+    /// A fake database holding `remaining` expired rows.
+    #[derive(Default)]
+    struct FakeDb {
+        remaining: Mutex<u64>,
+        statements: Mutex<Vec<String>>,
+    }
+
+    impl FakeDb {
+        fn with_rows(rows: u64) -> Self {
+            Self {
+                remaining: Mutex::new(rows),
+                ..Default::default()
+            }
+        }
+
+        fn limit(sql: &str) -> u64 {
+            sql.rsplit("LIMIT ").next().unwrap().trim().parse().unwrap()
+        }
+    }
+
+    impl RawDatabase for FakeDb {
+        fn query(
+            &self,
+            sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>> {
+            self.statements.lock().push(sql.to_string());
+            let remaining = *self.remaining.lock();
+            let rows = if sql.starts_with("SELECT COUNT(*)") {
+                vec![serde_json::json!({ "count": remaining })]
+            } else {
+                let n = remaining.min(Self::limit(sql));
+                (0..n).map(|id| serde_json::json!({ "id": id })).collect()
+            };
+            Box::pin(async move { Ok(rows) })
+        }
+
+        fn execute(&self, sql: &str, _params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+            self.statements.lock().push(sql.to_string());
+            let mut remaining = self.remaining.lock();
+            let n = (*remaining).min(Self::limit(sql.trim_end_matches(')')));
+            if sql.starts_with("DELETE") {
+                *remaining -= n;
+            }
+            Box::pin(async move { Ok(n) })
+        }
+
+        fn execute_in_transaction(
+            &self,
+            _statements: Vec<(String, Vec<FilterValue>)>,
+        ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+            unreachable!("retention purges without transactions")
+        }
+    }
+
+    struct CollectingArchive(Mutex<Vec<JsonValue>>);
+
+    impl RetentionArchive for CollectingArchive {
+        fn archive<'a>(
+            &'a self,
+            _table: &'a str,
+            rows: Vec<JsonValue>,
+        ) -> BoxFuture<'a, QueryResult<()>> {
+            self.0.lock().extend(rows);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn policy() -> RetentionPolicy {
+        RetentionPolicy::new("audit_logs", "created_at", Duration::from_secs(86_400)).key(["id"])
+    }
+
+    #[test]
+    fn test_batch_sql() {
+        let policy = policy();
+        assert_eq!(
+            policy.delete_batch_sql(DatabaseType::PostgreSQL, 500),
+            "DELETE FROM audit_logs WHERE (id) IN (SELECT id FROM audit_logs \
+             WHERE created_at < NOW() - INTERVAL '86400 seconds' ORDER BY id LIMIT 500)"
+        );
+        assert_eq!(
+            policy.delete_batch_sql(DatabaseType::MySQL, 500),
+            "DELETE FROM audit_logs WHERE created_at < NOW() - INTERVAL 86400 SECOND \
+             ORDER BY id LIMIT 500"
+        );
+        assert_eq!(
+            policy.archive_batch_sql(DatabaseType::SQLite, "audit_logs_archive", 10),
+            "INSERT INTO audit_logs_archive SELECT * FROM audit_logs \
+             WHERE created_at < datetime('now', '-86400 seconds') ORDER BY id LIMIT 10"
+        );
+        assert!(
+            policy
+                .delete_batch_sql(DatabaseType::MSSQL, 10)
+                .starts_with("WITH batch AS (SELECT TOP (10) * FROM audit_logs")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_changes_nothing() {
+        let runner = RetentionRunner::new(FakeDb::with_rows(2500)).with_batch_size(1000);
+        let report = runner.dry_run(&policy()).await.unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.expired_rows, 2500);
+        assert_eq!(report.batches, 3);
+        assert_eq!(report.rows_purged, 0);
+        assert_eq!(*runner.database().remaining.lock(), 2500);
+    }
+
+    #[tokio::test]
+    async fn test_run_deletes_in_batches() {
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&progress);
+        let runner = RetentionRunner::new(FakeDb::with_rows(2500))
+            .with_batch_size(1000)
+            .on_progress(move |report| seen.lock().push(report.rows_purged));
+
+        let report = runner.run(&policy()).await.unwrap();
+        assert!(report.completed);
+        assert_eq!(report.expired_rows, 2500);
+        assert_eq!(report.rows_purged, 2500);
+        assert_eq!(report.batches, 3);
+        assert_eq!(*progress.lock(), vec![1000, 2000, 2500]);
+        assert_eq!(*runner.database().remaining.lock(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_archives_to_table() {
+        let runner = RetentionRunner::new(FakeDb::with_rows(3)).with_batch_size(10);
+        let policy = policy().archive_to_table("audit_logs_archive");
+
+        let report = runner.run(&policy).await.unwrap();
+        assert_eq!(report.strategy, "archive");
+        assert_eq!(report.rows_archived, 3);
+        assert_eq!(report.rows_purged, 3);
+
+        let statements = runner.database().statements.lock();
+        assert!(statements[1].starts_with("INSERT INTO audit_logs_archive"));
+        assert!(statements[2].starts_with("DELETE FROM audit_logs"));
+    }
+
+    #[tokio::test]
+    async fn test_run_archives_to_sink() {
+        let sink = Arc::new(CollectingArchive(Mutex::new(Vec::new())));
+        let runner = RetentionRunner::new(FakeDb::with_rows(5)).with_batch_size(2);
+        let mut policy = policy();
+        policy.strategy = RetentionStrategy::Archive(ArchiveTarget::Sink(sink.clone()));
+
+        let report = runner.run(&policy).await.unwrap();
+        assert_eq!(report.rows_archived, 5);
+        assert_eq!(report.rows_purged, 5);
+        assert_eq!(sink.0.lock().len(), 5);
+    }
+
+    #[test]
+    fn test_off_peak_window() {
+        let night = OffPeakWindow::new(22, 4);
+        assert!(night.contains_hour(23));
+        assert!(night.contains_hour(3));
+        assert!(!night.contains_hour(12));
+
+        let early = OffPeakWindow::new(1, 5);
+        assert!(early.contains_hour(1));
+        assert!(!early.contains_hour(5));
+    }
+}
@@ -38,6 +38,8 @@
 use std::borrow::Cow;
 use std::fmt;
 
+use crate::types::DbEnum;
+
 /// Error type for row deserialization.
 #[derive(Debug, Clone)]
 pub enum RowError {
@@ -368,6 +370,27 @@ impl FromColumn for Option<Vec<u8>> {
     }
 }
 
+impl<T: DbEnum> FromColumn for T {
+    fn from_column(row: &impl RowRef, column: &str) -> Result<Self, RowError> {
+        decode_enum(row.get_str(column)?, column)
+    }
+}
+
+impl<T: DbEnum> FromColumn for Option<T> {
+    fn from_column(row: &impl RowRef, column: &str) -> Result<Self, RowError> {
+        row.get_str_opt(column)?
+            .map(|value| decode_enum(value, column))
+            .transpose()
+    }
+}
+
+fn decode_enum<T: DbEnum>(value: &str, column: &str) -> Result<T, RowError> {
+    T::decode(value).map_err(|message| RowError::TypeConversion {
+        column: column.to_string(),
+        message,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -526,6 +549,54 @@ mod tests {
         // borrowing directly from the row's buffer
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Role {
+        User,
+        Admin,
+    }
+
+    impl DbEnum for Role {
+        const DB_NAME: &'static str = "Role";
+
+        fn variants() -> &'static [Self] {
+            &[Self::User, Self::Admin]
+        }
+
+        fn db_value(&self) -> &'static str {
+            match self {
+                Self::User => "USER",
+                Self::Admin => "ADMIN",
+            }
+        }
+
+        fn from_db_value(value: &str) -> Option<Self> {
+            Self::variants()
+                .iter()
+                .copied()
+                .find(|v| v.db_value() == value)
+        }
+    }
+
+    #[test]
+    fn test_enum_from_column() {
+        let mut data = std::collections::HashMap::new();
+        data.insert("role".to_string(), "ADMIN".to_string());
+        data.insert("previous_role".to_string(), "NULL".to_string());
+        data.insert("bad_role".to_string(), "OWNER".to_string());
+        let row = MockRow { data };
+
+        assert_eq!(_get_typed_value::<Role>(&row, "role").unwrap(), Role::Admin);
+        assert_eq!(
+            _get_typed_value::<Option<Role>>(&row, "previous_role").unwrap(),
+            None
+        );
+        let err = _get_typed_value::<Role>(&row, "bad_role").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "type conversion error for 'bad_role': invalid value for enum Role: \"OWNER\""
+        );
+    }
+
     #[test]
     fn test_row_data() {
         let borrowed: RowData = RowData::borrowed("hello");
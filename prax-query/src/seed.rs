@@ -0,0 +1,346 @@
+//! Typed seed scripts.
+//!
+//! A [`Seeder`] inserts seed data with the generated client instead of raw
+//! SQL. A seed binary registers its seeders with a [`SeedRunner`], which
+//! `prax db seed` builds and runs with `DATABASE_URL` and `PRAX_ENV` set:
+//!
+//! ```rust,ignore
+//! use prax_query::seed::{SeedContext, SeedRunner, Seeder};
+//! use prax_query::traits::BoxFuture;
+//! use prax_query::QueryResult;
+//!
+//! struct Users;
+//!
+//! impl Seeder<PraxClient> for Users {
+//!     fn name(&self) -> &str {
+//!         "users"
+//!     }
+//!
+//!     fn seed<'a>(
+//!         &'a self,
+//!         client: &'a PraxClient,
+//!         _ctx: &'a SeedContext,
+//!     ) -> BoxFuture<'a, QueryResult<u64>> {
+//!         Box::pin(async move {
+//!             client.user().create(user::email::set("admin@example.com"), vec![]).exec().await?;
+//!             Ok(1)
+//!         })
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let client = PraxClient::new(&std::env::var("DATABASE_URL")?).await?;
+//!     let summary = SeedRunner::new().seeder(Users).seeder(DemoPosts).run(&client).await?;
+//!     println!("{summary}");
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The runner only logs through `tracing`. A [`SeedSummary`] displays as one
+//! `Seeded <n> records into <name>` line per seeder that ran, which
+//! `prax db seed` picks up from the seed binary's output for its summary.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+use crate::error::QueryResult;
+use crate::traits::BoxFuture;
+
+/// Environment variable holding the seed environment.
+pub const SEED_ENV_VAR: &str = "PRAX_ENV";
+
+/// Environment used when [`SEED_ENV_VAR`] is not set.
+pub const DEFAULT_SEED_ENVIRONMENT: &str = "development";
+
+/// Information about the seed run, passed to every seeder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedContext {
+    environment: String,
+}
+
+impl SeedContext {
+    /// Create a context for an environment.
+    pub fn new(environment: impl Into<String>) -> Self {
+        Self {
+            environment: environment.into(),
+        }
+    }
+
+    /// Create a context from `PRAX_ENV`, defaulting to `development`.
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var(SEED_ENV_VAR).unwrap_or_else(|_| DEFAULT_SEED_ENVIRONMENT.to_string()),
+        )
+    }
+
+    /// The environment being seeded.
+    pub fn environment(&self) -> &str {
+        &self.environment
+    }
+
+    /// Check if the production environment is being seeded.
+    pub fn is_production(&self) -> bool {
+        self.environment == "production"
+    }
+}
+
+impl Default for SeedContext {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Seed data written with a client of type `C`.
+pub trait Seeder<C>: Send + Sync {
+    /// Name shown in the seed output, typically the seeded table.
+    fn name(&self) -> &str;
+
+    /// Environments this seeder runs in; `None` runs in every environment.
+    fn environments(&self) -> Option<&[&str]> {
+        None
+    }
+
+    /// Insert the seed data and return the number of records written.
+    fn seed<'a>(&'a self, client: &'a C, ctx: &'a SeedContext) -> BoxFuture<'a, QueryResult<u64>>;
+}
+
+/// Outcome of one seeder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeederResult {
+    /// Seeder name.
+    pub name: String,
+    /// Records written, `None` if the seeder was skipped for the environment.
+    pub records: Option<u64>,
+    /// Time spent.
+    pub duration: Duration,
+}
+
+/// Outcome of a seed run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedSummary {
+    /// The seeded environment.
+    pub environment: String,
+    /// Results in seeding order.
+    pub results: Vec<SeederResult>,
+}
+
+impl SeedSummary {
+    /// Total records written.
+    pub fn total_records(&self) -> u64 {
+        self.results.iter().filter_map(|r| r.records).sum()
+    }
+
+    /// Names of seeders skipped for the environment.
+    pub fn skipped(&self) -> impl Iterator<Item = &str> {
+        self.results
+            .iter()
+            .filter(|r| r.records.is_none())
+            .map(|r| r.name.as_str())
+    }
+}
+
+impl fmt::Display for SeedSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            if let Some(records) = result.records {
+                writeln!(f, "Seeded {} records into {}", records, result.name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs seeders in registration order.
+pub struct SeedRunner<C> {
+    seeders: Vec<Box<dyn Seeder<C>>>,
+    context: SeedContext,
+}
+
+impl<C: Sync> SeedRunner<C> {
+    /// Create a runner for the environment in `PRAX_ENV`.
+    pub fn new() -> Self {
+        Self::with_context(SeedContext::from_env())
+    }
+
+    /// Create a runner with an explicit context.
+    pub fn with_context(context: SeedContext) -> Self {
+        Self {
+            seeders: Vec::new(),
+            context,
+        }
+    }
+
+    /// Register a seeder.
+    pub fn seeder(mut self, seeder: impl Seeder<C> + 'static) -> Self {
+        self.seeders.push(Box::new(seeder));
+        self
+    }
+
+    /// The seed context.
+    pub fn context(&self) -> &SeedContext {
+        &self.context
+    }
+
+    /// Run every seeder enabled for the environment, stopping at the first error.
+    pub async fn run(&self, client: &C) -> QueryResult<SeedSummary> {
+        let env = self.context.environment();
+        let mut results = Vec::with_capacity(self.seeders.len());
+
+        for seeder in &self.seeders {
+            let name = seeder.name();
+            let enabled = seeder.environments().is_none_or(|envs| envs.contains(&env));
+            if !enabled {
+                info!(seeder = %name, environment = %env, "Skipping seeder");
+                results.push(SeederResult {
+                    name: name.to_string(),
+                    records: None,
+                    duration: Duration::ZERO,
+                });
+                continue;
+            }
+
+            let start = Instant::now();
+            let records = seeder
+                .seed(client, &self.context)
+                .await
+                .map_err(|e| e.with_context(format!("running seeder `{}`", name)))?;
+            info!(seeder = %name, records, "Seeder finished");
+
+            results.push(SeederResult {
+                name: name.to_string(),
+                records: Some(records),
+                duration: start.elapsed(),
+            });
+        }
+
+        Ok(SeedSummary {
+            environment: env.to_string(),
+            results,
+        })
+    }
+}
+
+impl<C: Sync> Default for SeedRunner<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> std::fmt::Debug for SeedRunner<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SeedRunner")
+            .field(
+                "seeders",
+                &self.seeders.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            )
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QueryError;
+    use parking_lot::Mutex;
+
+    /// Stands in for a generated client.
+    #[derive(Default)]
+    struct FakeClient {
+        rows: Mutex<Vec<String>>,
+    }
+
+    struct Rows {
+        name: &'static str,
+        count: u64,
+        environments: Option<&'static [&'static str]>,
+    }
+
+    impl Seeder<FakeClient> for Rows {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn environments(&self) -> Option<&[&str]> {
+            self.environments
+        }
+
+        fn seed<'a>(
+            &'a self,
+            client: &'a FakeClient,
+            _ctx: &'a SeedContext,
+        ) -> BoxFuture<'a, QueryResult<u64>> {
+            Box::pin(async move {
+                for i in 0..self.count {
+                    client.rows.lock().push(format!("{}-{}", self.name, i));
+                }
+                Ok(self.count)
+            })
+        }
+    }
+
+    struct Failing;
+
+    impl Seeder<FakeClient> for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn seed<'a>(
+            &'a self,
+            _client: &'a FakeClient,
+            _ctx: &'a SeedContext,
+        ) -> BoxFuture<'a, QueryResult<u64>> {
+            Box::pin(async { Err(QueryError::constraint_violation("users", "duplicate key")) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seed_runner_respects_environments() {
+        let client = FakeClient::default();
+        let summary = SeedRunner::with_context(SeedContext::new("test"))
+            .seeder(Rows {
+                name: "users",
+                count: 3,
+                environments: None,
+            })
+            .seeder(Rows {
+                name: "demo_posts",
+                count: 5,
+                environments: Some(&["development"]),
+            })
+            .run(&client)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.environment, "test");
+        assert_eq!(summary.total_records(), 3);
+        assert_eq!(summary.skipped().collect::<Vec<_>>(), vec!["demo_posts"]);
+        assert_eq!(summary.to_string(), "Seeded 3 records into users\n");
+        assert_eq!(client.rows.lock().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_seed_runner_stops_on_error() {
+        let client = FakeClient::default();
+        let err = SeedRunner::with_context(SeedContext::new("development"))
+            .seeder(Failing)
+            .seeder(Rows {
+                name: "users",
+                count: 1,
+                environments: None,
+            })
+            .run(&client)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.context().operation.as_deref(),
+            Some("running seeder `failing`")
+        );
+        assert!(client.rows.lock().is_empty());
+    }
+}
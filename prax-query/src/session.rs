@@ -0,0 +1,504 @@
+//! HTTP sessions stored in a Prax model.
+//!
+//! A [`SessionStore`] keeps web framework sessions in a table of the
+//! application's own database, so no separate session server is needed. The
+//! table is an ordinary model with a `@@ttl` expiry:
+//!
+//! ```prax
+//! model Session {
+//!     id        String   @id
+//!     data      String
+//!     version   Int      @default(1)
+//!     expiresAt DateTime
+//!
+//!     @@ttl(field: expiresAt)
+//! }
+//! ```
+//!
+//! `prax-axum` and `prax-actix` wrap the store for `tower-sessions` and
+//! `actix-session` (`sessions` feature). Expired sessions are never loaded,
+//! and are deleted in the background by the generated `session::ttl_policy()`
+//! or [`SessionStore::purge_policy`]:
+//!
+//! ```rust,ignore
+//! use prax_query::retention::RetentionRunner;
+//! use prax_query::session::SessionStore;
+//! use std::time::Duration;
+//!
+//! let store = SessionStore::for_model::<session::Session>(db.clone());
+//! let purger = RetentionRunner::new(db).spawn(vec![store.purge_policy()], Duration::from_secs(600));
+//! ```
+//!
+//! Saves are optimistic: each save increments `version` and fails with a
+//! [`ErrorCode::SerializationFailure`] error if the session was saved by
+//! another request since it was loaded, instead of silently overwriting it.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value as JsonValue;
+use tracing::debug;
+
+use crate::error::{ErrorCode, QueryError, QueryResult};
+use crate::filter::FilterValue;
+use crate::raw::RawDatabase;
+use crate::retention::RetentionPolicy;
+use crate::sql::{DatabaseType, quote_identifier};
+use crate::traits::Model;
+use crate::ttl::{parse_utc_timestamp, utc_timestamp};
+
+/// Key under which framework adapters carry the loaded version in the
+/// session data, so a save can check it.
+pub const VERSION_KEY: &str = "__prax_version";
+
+/// A session loaded from the store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredSession {
+    /// The serialized session data.
+    pub data: String,
+    /// The version the data was saved as.
+    pub version: i64,
+    /// When the session expires, if ever.
+    pub expires_at: Option<SystemTime>,
+}
+
+/// Sessions kept in the table of a model.
+pub struct SessionStore<D> {
+    db: Arc<D>,
+    model: &'static str,
+    table: &'static str,
+    id_column: &'static str,
+    data_column: &'static str,
+    version_column: &'static str,
+    expiry_column: &'static str,
+}
+
+impl<D: RawDatabase> SessionStore<D> {
+    /// Store sessions in the table of `M`.
+    ///
+    /// Sessions expire by the model's `@@ttl` field, or an `expires_at`
+    /// column if it has none. The data and version columns default to
+    /// `data` and `version`.
+    pub fn for_model<M: Model>(db: D) -> Self {
+        Self {
+            db: Arc::new(db),
+            model: M::MODEL_NAME,
+            table: M::TABLE_NAME,
+            id_column: M::PRIMARY_KEY.first().copied().unwrap_or("id"),
+            data_column: "data",
+            version_column: "version",
+            expiry_column: M::TTL_COLUMN.unwrap_or("expires_at"),
+        }
+    }
+
+    /// Set the column holding the serialized session data.
+    pub fn data_column(mut self, column: &'static str) -> Self {
+        self.data_column = column;
+        self
+    }
+
+    /// Set the column holding the session version.
+    pub fn version_column(mut self, column: &'static str) -> Self {
+        self.version_column = column;
+        self
+    }
+
+    /// A policy deleting expired sessions, for a
+    /// [`RetentionRunner`](crate::retention::RetentionRunner).
+    pub fn purge_policy(&self) -> RetentionPolicy {
+        RetentionPolicy::new(self.table, self.expiry_column, Duration::ZERO).key([self.id_column])
+    }
+
+    /// Load the session `id`, unless it doesn't exist or has expired.
+    pub async fn load(&self, id: &str) -> QueryResult<Option<StoredSession>> {
+        let db_type = self.db.database_type();
+        let sql = format!(
+            "SELECT {}, {}, {} FROM {} WHERE {} = {} AND {}",
+            quote_identifier(self.data_column),
+            quote_identifier(self.version_column),
+            quote_identifier(self.expiry_column),
+            quote_identifier(self.table),
+            quote_identifier(self.id_column),
+            db_type.placeholder(1),
+            self.live(db_type, 2),
+        );
+        let rows = self.db.query(&sql, vec![id.into(), now()]).await?;
+        let Some(row) = rows.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let data = match row.get(self.data_column) {
+            Some(JsonValue::String(data)) => data.clone(),
+            Some(JsonValue::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        let version = match row.get(self.version_column) {
+            Some(JsonValue::Number(n)) => n.as_i64(),
+            Some(JsonValue::String(s)) => s.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            QueryError::deserialization(format!(
+                "session {} has no valid {} column",
+                id, self.version_column
+            ))
+            .with_model(self.model)
+        })?;
+        let expires_at = row
+            .get(self.expiry_column)
+            .and_then(JsonValue::as_str)
+            .and_then(parse_utc_timestamp);
+
+        Ok(Some(StoredSession {
+            data,
+            version,
+            expires_at,
+        }))
+    }
+
+    /// Create the session `id` at version 1.
+    ///
+    /// Returns `false` without writing anything if a live session with that
+    /// id exists.
+    pub async fn create(&self, id: &str, data: &str, expires_at: SystemTime) -> QueryResult<bool> {
+        let db_type = self.db.database_type();
+
+        // An expired session that wasn't purged yet would block the insert
+        let sql = format!(
+            "DELETE FROM {} WHERE {} = {} AND {} <= {}",
+            quote_identifier(self.table),
+            quote_identifier(self.id_column),
+            db_type.placeholder(1),
+            quote_identifier(self.expiry_column),
+            db_type.placeholder(2),
+        );
+        self.db.execute(&sql, vec![id.into(), now()]).await?;
+
+        let sql = format!(
+            "INSERT INTO {} ({}, {}, {}, {}) VALUES ({}, {}, 1, {})",
+            quote_identifier(self.table),
+            quote_identifier(self.id_column),
+            quote_identifier(self.data_column),
+            quote_identifier(self.version_column),
+            quote_identifier(self.expiry_column),
+            db_type.placeholder(1),
+            db_type.placeholder(2),
+            db_type.placeholder(3),
+        );
+        let params = vec![id.into(), data.into(), timestamp(expires_at)];
+        match self.db.execute(&sql, params).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.is_constraint_violation() => {
+                debug!(model = self.model, "Session id already taken");
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Save the data of session `id`, creating it if it doesn't exist.
+    ///
+    /// With the `version` it was loaded at, the save fails if the session
+    /// was saved since; without one, the last save wins.
+    pub async fn save(
+        &self,
+        id: &str,
+        data: &str,
+        expires_at: SystemTime,
+        version: Option<i64>,
+    ) -> QueryResult<()> {
+        let db_type = self.db.database_type();
+        let version_column = quote_identifier(self.version_column);
+        let mut sql = format!(
+            "UPDATE {} SET {} = {}, {} = {}, {} = {} + 1 WHERE {} = {} AND {}",
+            quote_identifier(self.table),
+            quote_identifier(self.data_column),
+            db_type.placeholder(1),
+            quote_identifier(self.expiry_column),
+            db_type.placeholder(2),
+            version_column,
+            version_column,
+            quote_identifier(self.id_column),
+            db_type.placeholder(3),
+            self.live(db_type, 4),
+        );
+        let mut params = vec![data.into(), timestamp(expires_at), id.into(), now()];
+        if let Some(version) = version {
+            sql.push_str(&format!(
+                " AND {} = {}",
+                version_column,
+                db_type.placeholder(5)
+            ));
+            params.push(FilterValue::Int(version));
+        }
+
+        if self.db.execute(&sql, params).await? > 0 || self.create(id, data, expires_at).await? {
+            return Ok(());
+        }
+        Err(QueryError::new(
+            ErrorCode::SerializationFailure,
+            format!("session {} was saved by another request", id),
+        )
+        .with_model(self.model)
+        .with_help("Reload the session and apply the change again"))
+    }
+
+    /// Move the expiry of session `id`, without changing its data.
+    ///
+    /// Returns `false` if the session doesn't exist or has expired.
+    pub async fn touch(&self, id: &str, expires_at: SystemTime) -> QueryResult<bool> {
+        let db_type = self.db.database_type();
+        let sql = format!(
+            "UPDATE {} SET {} = {} WHERE {} = {} AND {}",
+            quote_identifier(self.table),
+            quote_identifier(self.expiry_column),
+            db_type.placeholder(1),
+            quote_identifier(self.id_column),
+            db_type.placeholder(2),
+            self.live(db_type, 3),
+        );
+        let params = vec![timestamp(expires_at), id.into(), now()];
+        Ok(self.db.execute(&sql, params).await? > 0)
+    }
+
+    /// Delete session `id`.
+    pub async fn delete(&self, id: &str) -> QueryResult<()> {
+        let db_type = self.db.database_type();
+        let sql = format!(
+            "DELETE FROM {} WHERE {} = {}",
+            quote_identifier(self.table),
+            quote_identifier(self.id_column),
+            db_type.placeholder(1),
+        );
+        self.db.execute(&sql, vec![id.into()]).await?;
+        Ok(())
+    }
+
+    /// The condition for unexpired rows, comparing with the time bound to
+    /// parameter `index`.
+    fn live(&self, db_type: DatabaseType, index: usize) -> String {
+        let column = quote_identifier(self.expiry_column);
+        format!(
+            "({} IS NULL OR {} > {})",
+            column,
+            column,
+            db_type.placeholder(index)
+        )
+    }
+}
+
+impl<D> Clone for SessionStore<D> {
+    fn clone(&self) -> Self {
+        Self {
+            db: Arc::clone(&self.db),
+            ..*self
+        }
+    }
+}
+
+impl<D> std::fmt::Debug for SessionStore<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionStore")
+            .field("table", &self.table)
+            .finish_non_exhaustive()
+    }
+}
+
+fn timestamp(time: SystemTime) -> FilterValue {
+    FilterValue::String(utc_timestamp(time))
+}
+
+fn now() -> FilterValue {
+    timestamp(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::BoxFuture;
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+
+    struct Session;
+
+    impl Model for Session {
+        const MODEL_NAME: &'static str = "Session";
+        const TABLE_NAME: &'static str = "sessions";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "data", "version", "expires_at"];
+        const TTL_COLUMN: Option<&'static str> = Some("expires_at");
+    }
+
+    /// A fake database holding `(data, version, expires_at)` by id.
+    #[derive(Default)]
+    struct FakeDb {
+        rows: Mutex<HashMap<String, (String, i64, String)>>,
+    }
+
+    fn string(value: &FilterValue) -> String {
+        match value {
+            FilterValue::String(s) => s.clone(),
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    impl RawDatabase for FakeDb {
+        fn query(
+            &self,
+            sql: &str,
+            params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>> {
+            assert!(sql.starts_with("SELECT data, version, expires_at FROM sessions"));
+            let (id, now) = (string(&params[0]), string(&params[1]));
+            let rows = self
+                .rows
+                .lock()
+                .get(&id)
+                .filter(|(_, _, expires_at)| *expires_at > now)
+                .map(|(data, version, expires_at)| {
+                    serde_json::json!({ "data": data, "version": version, "expires_at": expires_at })
+                })
+                .into_iter()
+                .collect();
+            Box::pin(async move { Ok(rows) })
+        }
+
+        fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+            let mut rows = self.rows.lock();
+            let affected = if sql.starts_with("INSERT") {
+                let id = string(&params[0]);
+                if rows.contains_key(&id) {
+                    let e = QueryError::unique_violation("Session", "id");
+                    return Box::pin(async move { Err(e) });
+                }
+                rows.insert(id, (string(&params[1]), 1, string(&params[2])));
+                1
+            } else if sql.starts_with("UPDATE sessions SET data") {
+                let now = string(&params[3]);
+                let expected = params.get(4).map(|v| match v {
+                    FilterValue::Int(v) => *v,
+                    other => panic!("expected a version, got {:?}", other),
+                });
+                match rows.get_mut(&string(&params[2])) {
+                    Some(row) if row.2 > now && expected.is_none_or(|v| v == row.1) => {
+                        *row = (string(&params[0]), row.1 + 1, string(&params[1]));
+                        1
+                    }
+                    _ => 0,
+                }
+            } else if sql.starts_with("UPDATE") {
+                let now = string(&params[2]);
+                match rows.get_mut(&string(&params[1])) {
+                    Some(row) if row.2 > now => {
+                        row.2 = string(&params[0]);
+                        1
+                    }
+                    _ => 0,
+                }
+            } else if sql.contains("<=") {
+                let now = string(&params[1]);
+                let id = string(&params[0]);
+                u64::from(rows.remove_entry(&id).is_some_and(|(id, row)| {
+                    let expired = row.2 <= now;
+                    if !expired {
+                        rows.insert(id, row);
+                    }
+                    expired
+                }))
+            } else {
+                u64::from(rows.remove(&string(&params[0])).is_some())
+            };
+            Box::pin(async move { Ok(affected) })
+        }
+
+        fn execute_in_transaction(
+            &self,
+            _statements: Vec<(String, Vec<FilterValue>)>,
+        ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+            unreachable!("sessions are saved one statement at a time")
+        }
+    }
+
+    fn store() -> SessionStore<FakeDb> {
+        SessionStore::for_model::<Session>(FakeDb::default())
+    }
+
+    fn in_an_hour() -> SystemTime {
+        SystemTime::now() + Duration::from_secs(3_600)
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let store = store();
+        assert_eq!(store.load("abc").await.unwrap(), None);
+
+        assert!(store.create("abc", "{}", in_an_hour()).await.unwrap());
+        assert!(!store.create("abc", "{}", in_an_hour()).await.unwrap());
+
+        let session = store.load("abc").await.unwrap().unwrap();
+        assert_eq!(session.data, "{}");
+        assert_eq!(session.version, 1);
+        assert!(session.expires_at.unwrap() > SystemTime::now());
+
+        store
+            .save("abc", r#"{"user":1}"#, in_an_hour(), Some(1))
+            .await
+            .unwrap();
+        let session = store.load("abc").await.unwrap().unwrap();
+        assert_eq!(session.data, r#"{"user":1}"#);
+        assert_eq!(session.version, 2);
+
+        assert!(store.touch("abc", in_an_hour()).await.unwrap());
+        store.delete("abc").await.unwrap();
+        assert_eq!(store.load("abc").await.unwrap(), None);
+        assert!(!store.touch("abc", in_an_hour()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_save_rejected() {
+        let store = store();
+        store.create("abc", "{}", in_an_hour()).await.unwrap();
+
+        // Two requests load version 1; the second save loses
+        store
+            .save("abc", "first", in_an_hour(), Some(1))
+            .await
+            .unwrap();
+        let err = store
+            .save("abc", "second", in_an_hour(), Some(1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::SerializationFailure);
+        assert_eq!(store.load("abc").await.unwrap().unwrap().data, "first");
+
+        // Without a version the last save wins
+        store
+            .save("abc", "third", in_an_hour(), None)
+            .await
+            .unwrap();
+        assert_eq!(store.load("abc").await.unwrap().unwrap().version, 3);
+    }
+
+    #[tokio::test]
+    async fn test_expired_sessions() {
+        let store = store();
+        let past = SystemTime::now() - Duration::from_secs(60);
+        store.create("abc", "old", past).await.unwrap();
+        assert_eq!(store.load("abc").await.unwrap(), None);
+
+        // Saving an expired session starts it over
+        store
+            .save("abc", "new", in_an_hour(), Some(7))
+            .await
+            .unwrap();
+        let session = store.load("abc").await.unwrap().unwrap();
+        assert_eq!((session.data.as_str(), session.version), ("new", 1));
+
+        let policy = store.purge_policy();
+        assert_eq!(
+            (policy.table.as_str(), policy.column.as_str()),
+            ("sessions", "expires_at")
+        );
+        assert_eq!(policy.key, ["id"]);
+    }
+}
@@ -0,0 +1,307 @@
+//! Spatial columns and their filters.
+//!
+//! `Geometry` fields hold planar shapes and `Geography` fields shapes on the
+//! WGS84 spheroid. A `@db.Geometry(Point, 4326)` native type restricts the
+//! shape and spatial reference system (SRID) a column accepts:
+//!
+//! ```prax
+//! model Store {
+//!     id       Int       @id @auto
+//!     location Geography @db.Geography(Point, 4326)
+//!     area     Geometry  @db.Geometry(Polygon, 3857)
+//! }
+//! ```
+//!
+//! Both are PostGIS types, so spatial fields require PostgreSQL; migrations
+//! create the `postgis` extension for them.
+//!
+//! A [`SpatialFilter`] renders each operator as a PostGIS function call,
+//! binding its operand as EWKT text:
+//!
+//! | Operator | `geometry` | `geography` |
+//! |----------|------------|-------------|
+//! | [`within`](SpatialFilter::within) | `ST_Within(area, $1::text::geometry)` | `ST_CoveredBy(location, $1::text::geography)` |
+//! | [`intersects`](SpatialFilter::intersects) | `ST_Intersects(area, $1::text::geometry)` | `ST_Intersects(location, $1::text::geography)` |
+//! | [`dwithin`](SpatialFilter::dwithin) | `ST_DWithin(area, $1::text::geometry, $2)` | `ST_DWithin(location, $1::text::geography, $2)` |
+//!
+//! `dwithin` distances are in the units of the column's SRID for geometry,
+//! and in meters for geography. A geometry operand must have the same SRID
+//! as the column.
+//!
+//! Operands are [`Geometry`] values, built from WKT or from the
+//! [`Point`] and [`Polygon`] types of the extension module.
+//!
+//! ```rust
+//! use prax_query::extension::{Point, Polygon};
+//! use prax_query::spatial::{Geometry, SpatialFilter};
+//!
+//! assert_eq!(Geometry::from(Point::wgs84(2.35, 48.85)).to_string(), "SRID=4326;POINT(2.35 48.85)");
+//!
+//! let zone = Polygon::new(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 0.0)]).with_srid(3857);
+//! let (sql, params) = SpatialFilter::within(zone).into_filter("area").to_sql(0);
+//! assert_eq!(sql, "ST_Within(area, $1::text::geometry)");
+//! assert_eq!(params, vec!["SRID=3857;POLYGON((0 0, 4 0, 4 4, 0 0))".into()]);
+//!
+//! let (sql, params) = SpatialFilter::dwithin(Point::wgs84(2.35, 48.85), 500.0)
+//!     .geography()
+//!     .into_filter("location")
+//!     .to_sql(0);
+//! assert_eq!(sql, "ST_DWithin(location, $1::text::geography, $2)");
+//! assert_eq!(params[1], 500.0.into());
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::extension::{Point, Polygon};
+use crate::filter::{FieldName, Filter, FilterValue};
+
+/// A shape in a spatial reference system, as stored in `geometry` and
+/// `geography` columns. Serializes as EWKT.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Geometry {
+    /// The shape as Well-Known Text, such as `POINT(2.35 48.85)`.
+    pub wkt: String,
+    /// The spatial reference system; `None` is unknown (SRID 0).
+    pub srid: Option<i32>,
+}
+
+impl Geometry {
+    /// Create a shape from Well-Known Text.
+    pub fn new(wkt: impl Into<String>) -> Self {
+        Self {
+            wkt: wkt.into(),
+            srid: None,
+        }
+    }
+
+    /// Set the spatial reference system.
+    pub fn with_srid(mut self, srid: i32) -> Self {
+        self.srid = Some(srid);
+        self
+    }
+
+    /// Parse Extended Well-Known Text, as returned by `ST_AsEWKT`, such as
+    /// `SRID=4326;POINT(2.35 48.85)`. Plain WKT has no SRID.
+    pub fn from_ewkt(ewkt: &str) -> Self {
+        let srid = ewkt
+            .split_once(';')
+            .and_then(|(prefix, wkt)| Some((prefix.strip_prefix("SRID=")?.parse().ok()?, wkt)));
+        match srid {
+            Some((srid, wkt)) => Self::new(wkt).with_srid(srid),
+            None => Self::new(ewkt),
+        }
+    }
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Self::new("GEOMETRYCOLLECTION EMPTY")
+    }
+}
+
+/// Formats as Extended Well-Known Text, which PostGIS casts from text.
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.srid {
+            Some(srid) => write!(f, "SRID={};{}", srid, self.wkt),
+            None => f.write_str(&self.wkt),
+        }
+    }
+}
+
+impl Serialize for Geometry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Geometry {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ewkt = String::deserialize(deserializer)?;
+        Ok(Self::from_ewkt(&ewkt))
+    }
+}
+
+impl From<Point> for Geometry {
+    fn from(point: Point) -> Self {
+        Self {
+            wkt: point.to_wkt(),
+            srid: point.srid,
+        }
+    }
+}
+
+impl From<Polygon> for Geometry {
+    fn from(polygon: Polygon) -> Self {
+        Self {
+            wkt: polygon.to_wkt(),
+            srid: polygon.srid,
+        }
+    }
+}
+
+/// The PostGIS type of a spatial column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpatialColumn {
+    /// A planar `geometry` column.
+    #[default]
+    Geometry,
+    /// A geodetic `geography` column.
+    Geography,
+}
+
+impl SpatialColumn {
+    /// Get the PostGIS type name.
+    pub fn pg_type(&self) -> &'static str {
+        match self {
+            Self::Geometry => "geometry",
+            Self::Geography => "geography",
+        }
+    }
+}
+
+/// A spatial operator and its operand.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpatialOp {
+    /// The shape lies inside another.
+    Within(Geometry),
+    /// The shapes share a point.
+    Intersects(Geometry),
+    /// The shapes are at most a distance apart.
+    DWithin(Geometry, f64),
+}
+
+/// A filter on a spatial column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpatialFilter {
+    /// The operator.
+    pub op: SpatialOp,
+    /// The type of the column.
+    pub column: SpatialColumn,
+}
+
+impl SpatialFilter {
+    fn new(op: SpatialOp) -> Self {
+        Self {
+            op,
+            column: SpatialColumn::Geometry,
+        }
+    }
+
+    /// Match shapes lying inside `other`.
+    pub fn within(other: impl Into<Geometry>) -> Self {
+        Self::new(SpatialOp::Within(other.into()))
+    }
+
+    /// Match shapes sharing a point with `other`.
+    pub fn intersects(other: impl Into<Geometry>) -> Self {
+        Self::new(SpatialOp::Intersects(other.into()))
+    }
+
+    /// Match shapes at most `distance` from `other`.
+    pub fn dwithin(other: impl Into<Geometry>, distance: f64) -> Self {
+        Self::new(SpatialOp::DWithin(other.into(), distance))
+    }
+
+    /// Filter a `geography` column.
+    pub fn geography(mut self) -> Self {
+        self.column = SpatialColumn::Geography;
+        self
+    }
+
+    /// Apply the filter to `column`.
+    pub fn into_filter(self, column: impl Into<FieldName>) -> Filter {
+        Filter::Spatial(column.into(), Box::new(self))
+    }
+
+    /// Generate the condition on `column`, pushing its parameters.
+    pub(crate) fn to_sql_with_params(
+        &self,
+        column: &str,
+        param_idx: usize,
+        params: &mut Vec<FilterValue>,
+    ) -> String {
+        let mut bind = |value: FilterValue| {
+            params.push(value);
+            format!("${}", param_idx + params.len())
+        };
+        // Bound as text, as drivers cannot encode PostGIS values
+        let mut shape = |geometry: &Geometry| {
+            format!(
+                "{}::text::{}",
+                bind(FilterValue::String(geometry.to_string())),
+                self.column.pg_type()
+            )
+        };
+
+        match &self.op {
+            // ST_Within is only defined for geometry
+            SpatialOp::Within(other) => match self.column {
+                SpatialColumn::Geometry => format!("ST_Within({}, {})", column, shape(other)),
+                SpatialColumn::Geography => format!("ST_CoveredBy({}, {})", column, shape(other)),
+            },
+            SpatialOp::Intersects(other) => {
+                format!("ST_Intersects({}, {})", column, shape(other))
+            }
+            SpatialOp::DWithin(other, distance) => {
+                let other = shape(other);
+                params.push(FilterValue::Float(*distance));
+                format!(
+                    "ST_DWithin({}, {}, ${})",
+                    column,
+                    other,
+                    param_idx + params.len()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometry_ewkt() {
+        let point = Geometry::from(Point::new(1.5, -2.0));
+        assert_eq!(point.to_string(), "POINT(1.5 -2)");
+
+        let parsed = Geometry::from_ewkt("SRID=4326;POINT(1 2)");
+        assert_eq!(parsed, Geometry::new("POINT(1 2)").with_srid(4326));
+        assert_eq!(Geometry::from_ewkt("POINT(1 2)").srid, None);
+        assert_eq!(parsed.to_string(), "SRID=4326;POINT(1 2)");
+
+        let json = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(json, "SRID=4326;POINT(1 2)");
+        assert_eq!(serde_json::from_value::<Geometry>(json).unwrap(), parsed);
+    }
+
+    #[test]
+    fn test_spatial_filters() {
+        let area = Geometry::new("POLYGON((0 0, 1 0, 1 1, 0 0))").with_srid(4326);
+
+        let (sql, params) = SpatialFilter::within(area.clone())
+            .geography()
+            .into_filter("location")
+            .to_sql(0);
+        assert_eq!(sql, "ST_CoveredBy(location, $1::text::geography)");
+        assert_eq!(params, vec![FilterValue::String(area.to_string())]);
+
+        let (sql, _) = SpatialFilter::intersects(area.clone())
+            .into_filter("area")
+            .to_sql(0);
+        assert_eq!(sql, "ST_Intersects(area, $1::text::geometry)");
+
+        let combined = Filter::and2(
+            Filter::Equals("open".into(), FilterValue::Bool(true)),
+            SpatialFilter::dwithin(Point::wgs84(2.0, 48.0), 250.0)
+                .geography()
+                .into_filter("location"),
+        );
+        let (sql, params) = combined.to_sql(0);
+        assert!(sql.contains("ST_DWithin(location, $2::text::geography, $3)"));
+        assert_eq!(params[2], FilterValue::Float(250.0));
+    }
+}
@@ -266,6 +266,17 @@ pub fn quote_identifier(name: &str) -> String {
     }
 }
 
+/// Quote a possibly schema-qualified table name (`accounting.invoices`),
+/// quoting the schema and the table separately when needed.
+pub fn quote_table_name(name: &str) -> String {
+    match name.split_once('.') {
+        Some((schema, table)) => {
+            format!("{}.{}", quote_identifier(schema), quote_identifier(table))
+        }
+        None => quote_identifier(name),
+    }
+}
+
 /// Build a parameter placeholder for a given database type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum DatabaseType {
@@ -1646,6 +1657,17 @@ mod tests {
         assert_eq!(quote_identifier("my_table"), "my_table");
     }
 
+    #[test]
+    fn test_quote_table_name() {
+        assert_eq!(quote_table_name("invoices"), "invoices");
+        assert_eq!(quote_table_name("user"), "\"user\"");
+        assert_eq!(
+            quote_table_name("accounting.invoices"),
+            "accounting.invoices"
+        );
+        assert_eq!(quote_table_name("billing.user"), "billing.\"user\"");
+    }
+
     #[test]
     fn test_database_placeholder() {
         // Basic placeholder values
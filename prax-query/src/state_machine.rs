@@ -0,0 +1,272 @@
+//! State machine fields.
+//!
+//! An enum field marked `@stateMachine` may only move between the states
+//! its transitions allow:
+//!
+//! ```prax
+//! model Post {
+//!     id     Int        @id @auto
+//!     status PostStatus @default(draft) @stateMachine(transitions: {draft: [review], review: [published, draft]})
+//! }
+//! ```
+//!
+//! Generated models get a `transition_to()` helper. The update only matches
+//! while the stored state is still the one the transition starts from, so a
+//! disallowed or concurrent transition fails with an
+//! [`InvalidTransition`](crate::error::ErrorCode::InvalidTransition) error
+//! instead of overwriting the state:
+//!
+//! ```rust,ignore
+//! post.transition_to(&engine, PostStatus::Review).await?;
+//!
+//! // Back to draft: review -> draft is allowed
+//! post.transition_to(&engine, PostStatus::Draft).await?;
+//!
+//! let err = post.transition_to(&engine, PostStatus::Published).await.unwrap_err();
+//! // draft -> published is not
+//! assert!(err.is_invalid_transition());
+//! ```
+
+use serde::Serialize;
+
+use crate::error::{QueryError, QueryResult};
+use crate::filter::FilterValue;
+use crate::sql::{DatabaseType, quote_identifier};
+use crate::traits::QueryEngine;
+use crate::versioning::json_to_filter_value;
+
+/// The allowed transitions of a state field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateMachine {
+    field: &'static str,
+    column: &'static str,
+    transitions: &'static [(&'static str, &'static [&'static str])],
+}
+
+impl StateMachine {
+    /// Create a state machine from the states reachable from each state.
+    ///
+    /// States are database values; states without an entry are terminal.
+    pub const fn new(
+        field: &'static str,
+        column: &'static str,
+        transitions: &'static [(&'static str, &'static [&'static str])],
+    ) -> Self {
+        Self {
+            field,
+            column,
+            transitions,
+        }
+    }
+
+    /// Get the field name.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    /// Get the column name.
+    pub fn column(&self) -> &'static str {
+        self.column
+    }
+
+    /// Get the states reachable from `from`.
+    pub fn targets(&self, from: &str) -> &'static [&'static str] {
+        self.transitions
+            .iter()
+            .find(|(state, _)| *state == from)
+            .map(|(_, targets)| *targets)
+            .unwrap_or_default()
+    }
+
+    /// Check if `from` may transition to `to`.
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.targets(from).contains(&to)
+    }
+
+    /// Check if no transition leaves `state`.
+    pub fn is_terminal(&self, state: &str) -> bool {
+        self.targets(state).is_empty()
+    }
+}
+
+/// A transition of one record's state.
+#[derive(Debug, Clone)]
+pub struct StateTransition {
+    machine: StateMachine,
+    table: String,
+    key: Vec<(String, FilterValue)>,
+    from: String,
+    to: String,
+}
+
+impl StateTransition {
+    /// Create a transition of the record with the given primary key values.
+    pub fn new(
+        machine: StateMachine,
+        table: impl Into<String>,
+        key: impl IntoIterator<Item = (impl Into<String>, impl Into<FilterValue>)>,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        Self {
+            machine,
+            table: table.into(),
+            key: key
+                .into_iter()
+                .map(|(column, value)| (column.into(), value.into()))
+                .collect(),
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    /// Create a transition of a record, reading its primary key from the
+    /// record's serialized columns.
+    pub fn of(
+        machine: StateMachine,
+        table: impl Into<String>,
+        key_columns: &[&str],
+        record: &impl Serialize,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        let row = serde_json::to_value(record).unwrap_or_default();
+        let key = key_columns.iter().map(|column| {
+            let value = json_to_filter_value(row.get(*column).cloned().unwrap_or_default());
+            (*column, value)
+        });
+        Self::new(machine, table, key, from, to)
+    }
+
+    /// Check that the state machine allows the transition.
+    pub fn validate(&self) -> QueryResult<()> {
+        if self.machine.allows(&self.from, &self.to) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Build the update for `db_type`, matching only while the record is in
+    /// the start state.
+    pub fn build_sql(&self, db_type: DatabaseType) -> (String, Vec<FilterValue>) {
+        let column = quote_identifier(self.machine.column());
+        let mut params = vec![FilterValue::String(self.to.clone())];
+        let mut conditions = Vec::with_capacity(self.key.len() + 1);
+        for (key_column, value) in &self.key {
+            params.push(value.clone());
+            conditions.push(format!(
+                "{} = {}",
+                quote_identifier(key_column),
+                db_type.placeholder(params.len())
+            ));
+        }
+        params.push(FilterValue::String(self.from.clone()));
+        conditions.push(format!(
+            "{} = {}",
+            column,
+            db_type.placeholder(params.len())
+        ));
+
+        let sql = format!(
+            "UPDATE {} SET {} = {} WHERE {}",
+            quote_identifier(&self.table),
+            column,
+            db_type.placeholder(1),
+            conditions.join(" AND ")
+        );
+        (sql, params)
+    }
+
+    /// Validate and apply the transition.
+    ///
+    /// Fails with an `InvalidTransition` error, leaving the record unchanged,
+    /// if the transition is not allowed or the record is no longer in the
+    /// start state.
+    pub async fn exec<E: QueryEngine>(&self, engine: &E) -> QueryResult<()> {
+        self.validate()?;
+
+        let (sql, params) = self.build_sql(engine.database_type());
+        let updated = engine.execute_raw(&sql, params).await?;
+        if updated == 0 {
+            return Err(self.error().with_help(format!(
+                "The record is no longer in state '{}'; reload it and retry",
+                self.from
+            )));
+        }
+        Ok(())
+    }
+
+    fn error(&self) -> QueryError {
+        QueryError::invalid_transition(&self.table, self.machine.field(), &self.from, &self.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const STATUS: StateMachine = StateMachine::new(
+        "status",
+        "status",
+        &[("draft", &["review"]), ("review", &["published", "draft"])],
+    );
+
+    #[test]
+    fn test_state_machine_transitions() {
+        assert!(STATUS.allows("draft", "review"));
+        assert!(STATUS.allows("review", "draft"));
+        assert!(!STATUS.allows("draft", "published"));
+        assert!(STATUS.is_terminal("published"));
+        assert!(!STATUS.is_terminal("review"));
+    }
+
+    #[test]
+    fn test_transition_sql() {
+        let post = json!({"id": 7, "status": "draft"});
+        let transition = StateTransition::of(STATUS, "posts", &["id"], &post, "draft", "review");
+
+        let (sql, params) = transition.build_sql(DatabaseType::PostgreSQL);
+        assert_eq!(
+            sql,
+            "UPDATE posts SET status = $1 WHERE id = $2 AND status = $3"
+        );
+        assert_eq!(
+            params,
+            vec![
+                FilterValue::String("review".into()),
+                FilterValue::Int(7),
+                FilterValue::String("draft".into()),
+            ]
+        );
+        assert!(transition.validate().is_ok());
+    }
+
+    #[test]
+    fn test_transition_sql_dialects() {
+        let transition = StateTransition::new(STATUS, "posts", [("id", 7)], "draft", "review");
+
+        let (sql, params) = transition.build_sql(DatabaseType::MySQL);
+        assert_eq!(sql, "UPDATE posts SET status = ? WHERE id = ? AND status = ?");
+        assert_eq!(params.len(), 3);
+
+        let (sql, _) = transition.build_sql(DatabaseType::MSSQL);
+        assert_eq!(
+            sql,
+            "UPDATE posts SET status = @P1 WHERE id = @P2 AND status = @P3"
+        );
+    }
+
+    #[test]
+    fn test_invalid_transition() {
+        let transition = StateTransition::new(STATUS, "posts", [("id", 7)], "draft", "published");
+
+        let err = transition.validate().unwrap_err();
+        assert!(err.is_invalid_transition());
+        assert_eq!(
+            err.message,
+            "Cannot transition posts.status from 'draft' to 'published'"
+        );
+    }
+}
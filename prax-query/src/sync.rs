@@ -0,0 +1,603 @@
+//! Offline-first sync between an embedded database and a server.
+//!
+//! A client records its own writes in an oplog: every insert, update and
+//! delete on a tracked table is logged with the row's key and its new
+//! contents. The server keeps a change log of the same shape. A [`Syncer`]
+//! pushes the client's pending changes to the server, pulls what other
+//! nodes wrote since the last sync, and settles rows changed on both sides
+//! with a [`MergePolicy`]: the newest write wins by default, or a custom
+//! [`MergeHook`] decides. Every conflict and how it was settled is listed
+//! in the returned [`SyncReport`].
+//!
+//! The drivers provide the stores: `prax_sqlite::sync::SqliteSyncStore` for
+//! the client and `prax_postgres::sync::PgSyncStore` for the server.
+//!
+//! ```rust,ignore
+//! use prax_query::sync::{Conflict, MergePolicy, Resolution, Syncer};
+//!
+//! let local = SqliteSyncStore::new(sqlite_pool);
+//! local.track::<Note>().await?;
+//!
+//! let report = Syncer::new(local, PgSyncStore::new(pg_pool))
+//!     .policy(MergePolicy::custom(|conflict: &Conflict| {
+//!         // Never resurrect deleted notes
+//!         if conflict.remote.row.is_none() {
+//!             Resolution::KeepRemote
+//!         } else {
+//!             Resolution::newest(conflict)
+//!         }
+//!     }))
+//!     .sync()
+//!     .await?;
+//!
+//! for conflict in &report.conflicts {
+//!     tracing::info!(table = %conflict.conflict.table, "settled {:?}", conflict.resolution);
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tracing::debug;
+
+use crate::error::QueryResult;
+use crate::live::ChangeOp;
+use crate::traits::BoxFuture;
+
+/// A logged write to one row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    /// Position in the log the change was read from.
+    pub seq: i64,
+    /// The table written to.
+    pub table: String,
+    /// The row's primary key, as an object of column values.
+    pub key: JsonValue,
+    /// The kind of write.
+    pub op: ChangeOp,
+    /// The row after the write, as an object of column values; `None` for
+    /// deletes.
+    pub row: Option<JsonValue>,
+    /// When the write was made, in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    /// The node that made the write.
+    pub origin: String,
+}
+
+impl Change {
+    /// The table and key this change writes to.
+    fn target(&self) -> (String, String) {
+        (self.table.clone(), self.key.to_string())
+    }
+}
+
+/// A row written on both sides since the last sync.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// The table of the row.
+    pub table: String,
+    /// The row's primary key.
+    pub key: JsonValue,
+    /// The last local write to the row.
+    pub local: Change,
+    /// The last write to the row from another node.
+    pub remote: Change,
+}
+
+/// How a [`Conflict`] is settled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// Keep the local row and push it to the server.
+    KeepLocal,
+    /// Replace the local row with the remote one.
+    KeepRemote,
+    /// Store this row on both sides.
+    Merged(JsonValue),
+}
+
+impl Resolution {
+    /// Keep whichever write is newer, breaking ties by origin so every
+    /// node picks the same side.
+    pub fn newest(conflict: &Conflict) -> Self {
+        let local = (conflict.local.timestamp, &conflict.local.origin);
+        let remote = (conflict.remote.timestamp, &conflict.remote.origin);
+        if local > remote {
+            Self::KeepLocal
+        } else {
+            Self::KeepRemote
+        }
+    }
+}
+
+/// Settles conflicts for a [`MergePolicy::Custom`] policy.
+pub trait MergeHook: Send + Sync {
+    /// Decide how to settle `conflict`.
+    fn merge(&self, conflict: &Conflict) -> Resolution;
+}
+
+impl<F> MergeHook for F
+where
+    F: Fn(&Conflict) -> Resolution + Send + Sync,
+{
+    fn merge(&self, conflict: &Conflict) -> Resolution {
+        self(conflict)
+    }
+}
+
+/// How rows written on both sides are settled.
+#[derive(Clone, Default)]
+pub enum MergePolicy {
+    /// The newer write wins; see [`Resolution::newest`].
+    #[default]
+    LastWriterWins,
+    /// A hook decides.
+    Custom(Arc<dyn MergeHook>),
+}
+
+impl MergePolicy {
+    /// A policy that asks `hook`.
+    pub fn custom(hook: impl MergeHook + 'static) -> Self {
+        Self::Custom(Arc::new(hook))
+    }
+
+    /// Settle `conflict`.
+    pub fn resolve(&self, conflict: &Conflict) -> Resolution {
+        match self {
+            Self::LastWriterWins => Resolution::newest(conflict),
+            Self::Custom(hook) => hook.merge(conflict),
+        }
+    }
+}
+
+impl std::fmt::Debug for MergePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LastWriterWins => f.write_str("LastWriterWins"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// A change log that changes can be read from and applied to.
+pub trait SyncStore: Send + Sync {
+    /// The changes logged after position `after`, oldest first.
+    fn changes_since(&self, after: i64) -> BoxFuture<'_, QueryResult<Vec<Change>>>;
+
+    /// Apply changes made on another node, in order and atomically.
+    ///
+    /// Applied changes keep their origin and timestamp, and are never read
+    /// back as the store's own writes.
+    fn apply(&self, changes: Vec<Change>) -> BoxFuture<'_, QueryResult<()>>;
+}
+
+/// The client side of a sync, which also remembers how far it got.
+pub trait LocalStore: SyncStore {
+    /// Load the sync state.
+    fn load_state(&self) -> BoxFuture<'_, QueryResult<SyncState>>;
+
+    /// Save the sync state after a successful sync.
+    ///
+    /// Stores may drop the oplog entries up to `state.pushed`.
+    fn save_state(&self, state: SyncState) -> BoxFuture<'_, QueryResult<()>>;
+}
+
+/// How far a client has synced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// The client's node ID, recorded as the origin of its writes.
+    pub node_id: String,
+    /// The last local oplog position pushed to the server.
+    pub pushed: i64,
+    /// The last server log position pulled.
+    pub pulled: i64,
+}
+
+/// A conflict and how it was settled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictReport {
+    /// The conflict.
+    pub conflict: Conflict,
+    /// How it was settled.
+    pub resolution: Resolution,
+}
+
+/// The outcome of a sync.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Changes pushed to the server.
+    pub pushed: usize,
+    /// Changes applied locally.
+    pub pulled: usize,
+    /// Rows written on both sides, with their resolutions.
+    pub conflicts: Vec<ConflictReport>,
+}
+
+impl SyncReport {
+    /// Whether nothing was exchanged.
+    pub fn is_empty(&self) -> bool {
+        self.pushed == 0 && self.pulled == 0 && self.conflicts.is_empty()
+    }
+}
+
+/// Syncs a client store with a server store.
+#[derive(Debug)]
+pub struct Syncer<L, R> {
+    local: L,
+    remote: R,
+    policy: MergePolicy,
+}
+
+impl<L: LocalStore, R: SyncStore> Syncer<L, R> {
+    /// Sync `local` with `remote`, settling conflicts by last writer wins.
+    pub fn new(local: L, remote: R) -> Self {
+        Self {
+            local,
+            remote,
+            policy: MergePolicy::default(),
+        }
+    }
+
+    /// Settle conflicts with `policy`.
+    pub fn policy(mut self, policy: MergePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The client store.
+    pub fn local(&self) -> &L {
+        &self.local
+    }
+
+    /// The server store.
+    pub fn remote(&self) -> &R {
+        &self.remote
+    }
+
+    /// Exchange changes once.
+    ///
+    /// Remote changes are applied locally before local ones are pushed, and
+    /// the state is saved last, so a sync that fails part way is repeated
+    /// in full by the next one; applying a change twice is harmless.
+    pub async fn sync(&self) -> QueryResult<SyncReport> {
+        let mut state = self.local.load_state().await?;
+
+        let local = self.local.changes_since(state.pushed).await?;
+        let remote = self.remote.changes_since(state.pulled).await?;
+        let pushed = local.last().map_or(state.pushed, |change| change.seq);
+        let pulled = remote.last().map_or(state.pulled, |change| change.seq);
+
+        // Our own pushes come back in the server's log
+        let remote = remote
+            .into_iter()
+            .filter(|change| change.origin != state.node_id)
+            .collect();
+        let plan = reconcile(local, remote, &self.policy, &state.node_id);
+        debug!(
+            push = plan.push.len(),
+            pull = plan.pull.len(),
+            conflicts = plan.conflicts.len(),
+            "Syncing"
+        );
+
+        let report = SyncReport {
+            pushed: plan.push.len(),
+            pulled: plan.pull.len(),
+            conflicts: plan.conflicts,
+        };
+        if !plan.pull.is_empty() {
+            self.local.apply(plan.pull).await?;
+        }
+        if !plan.push.is_empty() {
+            self.remote.apply(plan.push).await?;
+        }
+
+        state.pushed = pushed;
+        state.pulled = pulled;
+        self.local.save_state(state).await?;
+        Ok(report)
+    }
+}
+
+/// The changes to exchange.
+#[derive(Debug, Default)]
+struct Plan {
+    push: Vec<Change>,
+    pull: Vec<Change>,
+    conflicts: Vec<ConflictReport>,
+}
+
+/// Split pending changes into what to push and pull, settling rows that
+/// were written on both sides.
+///
+/// Changes keep their log order, so writes that depend on each other,
+/// such as a parent row and its children, are replayed in the same order.
+fn reconcile(local: Vec<Change>, remote: Vec<Change>, policy: &MergePolicy, node_id: &str) -> Plan {
+    let last_local: HashMap<_, _> = local.iter().map(|c| (c.target(), c)).collect();
+    let last_remote: HashMap<_, _> = remote.iter().map(|c| (c.target(), c)).collect();
+
+    let mut plan = Plan::default();
+    let mut keep_local = HashSet::new();
+    let mut keep_remote = HashSet::new();
+    let mut merged = Vec::new();
+
+    // Settle in local log order so reports and merges are deterministic
+    let mut settled = HashSet::new();
+    for change in &local {
+        let target = change.target();
+        let Some(remote) = last_remote.get(&target) else {
+            continue;
+        };
+        if !settled.insert(target.clone()) {
+            continue;
+        }
+        let conflict = Conflict {
+            table: change.table.clone(),
+            key: change.key.clone(),
+            local: (*last_local[&target]).clone(),
+            remote: (*remote).clone(),
+        };
+        let resolution = policy.resolve(&conflict);
+        match &resolution {
+            Resolution::KeepLocal => {
+                keep_local.insert(target);
+            }
+            Resolution::KeepRemote => {
+                keep_remote.insert(target);
+            }
+            Resolution::Merged(row) => merged.push(Change {
+                seq: 0,
+                table: conflict.table.clone(),
+                key: conflict.key.clone(),
+                op: ChangeOp::Update,
+                row: Some(row.clone()),
+                timestamp: conflict.local.timestamp.max(conflict.remote.timestamp) + 1,
+                origin: node_id.to_string(),
+            }),
+        }
+        plan.conflicts.push(ConflictReport {
+            conflict,
+            resolution,
+        });
+    }
+
+    plan.push = local
+        .into_iter()
+        .filter(|c| {
+            let target = c.target();
+            !settled.contains(&target) || keep_local.contains(&target)
+        })
+        .chain(merged.iter().cloned())
+        .collect();
+    plan.pull = remote
+        .into_iter()
+        .filter(|c| {
+            let target = c.target();
+            !settled.contains(&target) || keep_remote.contains(&target)
+        })
+        .chain(merged)
+        .collect();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    fn change(
+        seq: i64,
+        id: i64,
+        op: ChangeOp,
+        name: Option<&str>,
+        ts: i64,
+        origin: &str,
+    ) -> Change {
+        Change {
+            seq,
+            table: "notes".to_string(),
+            key: json!({ "id": id }),
+            op,
+            row: name.map(|name| json!({ "id": id, "name": name })),
+            timestamp: ts,
+            origin: origin.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reconcile_without_conflicts() {
+        let local = vec![change(1, 1, ChangeOp::Insert, Some("a"), 10, "me")];
+        let remote = vec![change(7, 2, ChangeOp::Update, Some("b"), 5, "server")];
+
+        let plan = reconcile(local.clone(), remote.clone(), &MergePolicy::default(), "me");
+        assert_eq!(plan.push, local);
+        assert_eq!(plan.pull, remote);
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_last_writer_wins() {
+        let local = vec![
+            change(1, 1, ChangeOp::Insert, Some("mine"), 10, "me"),
+            change(2, 2, ChangeOp::Update, Some("old"), 10, "me"),
+        ];
+        let remote = vec![
+            change(7, 1, ChangeOp::Update, Some("theirs"), 5, "other"),
+            change(8, 2, ChangeOp::Delete, None, 20, "other"),
+        ];
+
+        let plan = reconcile(local, remote, &MergePolicy::LastWriterWins, "me");
+        assert_eq!(plan.conflicts.len(), 2);
+        assert_eq!(plan.conflicts[0].resolution, Resolution::KeepLocal);
+        assert_eq!(plan.conflicts[1].resolution, Resolution::KeepRemote);
+        assert_eq!(plan.push.iter().map(|c| c.seq).collect::<Vec<_>>(), [1]);
+        assert_eq!(plan.pull.iter().map(|c| c.seq).collect::<Vec<_>>(), [8]);
+
+        // Ties go to the greater origin on every node
+        let tie = Conflict {
+            table: "notes".to_string(),
+            key: json!({ "id": 1 }),
+            local: change(1, 1, ChangeOp::Update, Some("a"), 10, "a"),
+            remote: change(2, 1, ChangeOp::Update, Some("b"), 10, "b"),
+        };
+        assert_eq!(Resolution::newest(&tie), Resolution::KeepRemote);
+    }
+
+    #[test]
+    fn test_reconcile_custom_merge() {
+        let local = vec![change(3, 1, ChangeOp::Update, Some("a"), 10, "me")];
+        let remote = vec![change(9, 1, ChangeOp::Update, Some("b"), 12, "other")];
+        let policy = MergePolicy::custom(|conflict: &Conflict| {
+            let local = conflict.local.row.as_ref().unwrap()["name"]
+                .as_str()
+                .unwrap();
+            let remote = conflict.remote.row.as_ref().unwrap()["name"]
+                .as_str()
+                .unwrap();
+            Resolution::Merged(json!({ "id": 1, "name": format!("{}+{}", local, remote) }))
+        });
+
+        let plan = reconcile(local, remote, &policy, "me");
+        assert_eq!(plan.push, plan.pull);
+        assert_eq!(plan.push.len(), 1);
+        let merged = &plan.push[0];
+        assert_eq!(merged.row, Some(json!({ "id": 1, "name": "a+b" })));
+        assert_eq!(merged.timestamp, 13);
+        assert_eq!(merged.origin, "me");
+    }
+
+    /// A store keeping its log in memory; `node` names the writes made
+    /// through [`MemoryStore::write`].
+    #[derive(Default)]
+    struct MemoryStore {
+        node: String,
+        log: Mutex<Vec<Change>>,
+        rows: Mutex<HashMap<(String, String), JsonValue>>,
+        state: Mutex<Option<SyncState>>,
+    }
+
+    impl MemoryStore {
+        fn new(node: &str) -> Self {
+            Self {
+                node: node.to_string(),
+                ..Default::default()
+            }
+        }
+
+        fn write(&self, id: i64, name: Option<&str>, ts: i64) {
+            let op = if name.is_some() {
+                ChangeOp::Update
+            } else {
+                ChangeOp::Delete
+            };
+            let mut log = self.log.lock().unwrap();
+            let c = change(log.len() as i64 + 1, id, op, name, ts, &self.node);
+            self.store(&c);
+            log.push(c);
+        }
+
+        fn store(&self, change: &Change) {
+            let mut rows = self.rows.lock().unwrap();
+            match &change.row {
+                Some(row) => rows.insert(change.target(), row.clone()),
+                None => rows.remove(&change.target()),
+            };
+        }
+
+        fn name(&self, id: i64) -> Option<String> {
+            let target = ("notes".to_string(), json!({ "id": id }).to_string());
+            self.rows
+                .lock()
+                .unwrap()
+                .get(&target)
+                .map(|row| row["name"].as_str().unwrap().to_string())
+        }
+    }
+
+    impl SyncStore for MemoryStore {
+        fn changes_since(&self, after: i64) -> BoxFuture<'_, QueryResult<Vec<Change>>> {
+            let changes = self
+                .log
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|c| c.seq > after)
+                .cloned()
+                .collect();
+            Box::pin(async move { Ok(changes) })
+        }
+
+        fn apply(&self, changes: Vec<Change>) -> BoxFuture<'_, QueryResult<()>> {
+            for change in changes {
+                self.store(&change);
+                // Only the server logs what it applies
+                if self.node == "server" {
+                    let mut log = self.log.lock().unwrap();
+                    let seq = log.len() as i64 + 1;
+                    log.push(Change { seq, ..change });
+                }
+            }
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    impl LocalStore for MemoryStore {
+        fn load_state(&self) -> BoxFuture<'_, QueryResult<SyncState>> {
+            let state = self.state.lock().unwrap().clone().unwrap_or(SyncState {
+                node_id: self.node.clone(),
+                pushed: 0,
+                pulled: 0,
+            });
+            Box::pin(async move { Ok(state) })
+        }
+
+        fn save_state(&self, state: SyncState) -> BoxFuture<'_, QueryResult<()>> {
+            *self.state.lock().unwrap() = Some(state);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    impl SyncStore for Arc<MemoryStore> {
+        fn changes_since(&self, after: i64) -> BoxFuture<'_, QueryResult<Vec<Change>>> {
+            self.as_ref().changes_since(after)
+        }
+
+        fn apply(&self, changes: Vec<Change>) -> BoxFuture<'_, QueryResult<()>> {
+            self.as_ref().apply(changes)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_round_trip() {
+        let server = Arc::new(MemoryStore::new("server"));
+        let phone = Syncer::new(MemoryStore::new("phone"), server.clone());
+        let laptop = Syncer::new(MemoryStore::new("laptop"), server.clone());
+
+        phone.local().write(1, Some("groceries"), 10);
+        let report = phone.sync().await.unwrap();
+        assert_eq!((report.pushed, report.pulled), (1, 0));
+
+        // Both edit the note offline; the laptop's edit is newer
+        laptop.sync().await.unwrap();
+        assert_eq!(laptop.local().name(1).as_deref(), Some("groceries"));
+        phone.local().write(1, Some("groceries, milk"), 20);
+        laptop.local().write(1, Some("groceries, eggs"), 30);
+
+        phone.sync().await.unwrap();
+        let report = laptop.sync().await.unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].resolution, Resolution::KeepLocal);
+
+        let report = phone.sync().await.unwrap();
+        assert!(report.conflicts.is_empty());
+        assert_eq!(report.pulled, 1);
+        assert_eq!(phone.local().name(1).as_deref(), Some("groceries, eggs"));
+        assert_eq!(server.name(1).as_deref(), Some("groceries, eggs"));
+
+        // Nothing left to exchange
+        assert!(phone.sync().await.unwrap().is_empty());
+        assert!(laptop.sync().await.unwrap().is_empty());
+    }
+}
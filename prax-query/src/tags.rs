@@ -0,0 +1,272 @@
+//! Query tags for cost attribution.
+//!
+//! Operations can be tagged with who runs them and why, so the load on a
+//! shared database can be broken down by team or feature:
+//!
+//! ```rust,ignore
+//! let cart = client
+//!     .cart_item()
+//!     .find_many()
+//!     .r#where(cart_item::user_id::equals(user_id))
+//!     .tags(["team:checkout", "feature:cart"])
+//!     .exec()
+//!     .await?;
+//! ```
+//!
+//! Tags travel with the statement as a [sqlcommenter] comment, so they also
+//! show up in the database's own statement logs and insights tools:
+//!
+//! ```sql
+//! SELECT * FROM cart_items WHERE user_id = $1 /*feature='cart',team='checkout'*/
+//! ```
+//!
+//! [`QueryContext`](crate::middleware::QueryContext) reads them back into
+//! [`QueryMetadata::query_tags`](crate::middleware::QueryMetadata::query_tags),
+//! where the metrics, logging and slow query middleware report them.
+//!
+//! Tags are parsed from the statement, so anything that can shape the SQL
+//! can set them. They are kept apart from the metadata `tags` that
+//! middleware such as the retry and policy middleware act on.
+//!
+//! [sqlcommenter]: https://google.github.io/sqlcommenter/spec/
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Tags of a query, as `key:value` pairs sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryTags(BTreeMap<String, String>);
+
+impl QueryTags {
+    /// Create an empty set of tags.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a `key:value` tag. A tag without a `:` has an empty value.
+    ///
+    /// A later tag with the same key replaces the earlier one.
+    pub fn push(&mut self, tag: impl AsRef<str>) {
+        let tag = tag.as_ref();
+        let (key, value) = tag.split_once(':').unwrap_or((tag, ""));
+        self.insert(key.trim(), value.trim());
+    }
+
+    /// Set the tag `key` to `value`.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        if !key.is_empty() {
+            self.0.insert(key, value.into());
+        }
+    }
+
+    /// Get the value of the tag `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Iterate over the tags as key and value.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Get the tags in their `key:value` form.
+    pub fn labels(&self) -> Vec<String> {
+        self.iter().map(|(k, v)| label(k, v)).collect()
+    }
+
+    /// Check if there are no tags.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the number of tags.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Render the tags as a sqlcommenter comment, such as
+    /// `/*feature='cart',team='checkout'*/`.
+    pub fn to_comment(&self) -> String {
+        let pairs: Vec<String> = self
+            .iter()
+            .map(|(k, v)| format!("{}='{}'", encode(k), encode(v).replace('\'', "\\'")))
+            .collect();
+        format!("/*{}*/", pairs.join(","))
+    }
+
+    /// Append the tags to `sql` as a sqlcommenter comment, before any
+    /// trailing semicolon.
+    pub fn append_to(&self, sql: &mut String) {
+        if self.is_empty() {
+            return;
+        }
+        let end = sql.trim_end().trim_end_matches(';').trim_end().len();
+        let terminator = sql.split_off(end);
+        sql.push(' ');
+        sql.push_str(&self.to_comment());
+        sql.push_str(terminator.trim());
+    }
+
+    /// Read the tags from a trailing sqlcommenter comment of `sql`.
+    ///
+    /// Statements without one have no tags.
+    pub fn from_sql(sql: &str) -> Self {
+        let mut tags = Self::new();
+        let sql = sql.trim_end().trim_end_matches(';').trim_end();
+        let Some(body) = sql.strip_suffix("*/") else {
+            return tags;
+        };
+        let Some(start) = body.rfind("/*") else {
+            return tags;
+        };
+        for pair in split_pairs(&body[start + 2..]) {
+            let Some((key, value)) = pair.split_once('=') else {
+                return Self::new();
+            };
+            let Some(value) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) else {
+                return Self::new();
+            };
+            tags.insert(decode(key), decode(&value.replace("\\'", "'")));
+        }
+        tags
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for QueryTags {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut tags = Self::new();
+        for tag in iter {
+            tags.push(tag);
+        }
+        tags
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for QueryTags {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for tag in iter {
+            self.push(tag);
+        }
+    }
+}
+
+impl fmt::Display for QueryTags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.labels().join(","))
+    }
+}
+
+fn label(key: &str, value: &str) -> String {
+    if value.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}:{}", key, value)
+    }
+}
+
+/// Split the comment body at commas outside quoted values.
+fn split_pairs(body: &str) -> Vec<&str> {
+    let mut pairs = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (i, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\'' => quoted = !quoted,
+            ',' if !quoted => {
+                pairs.push(body[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = body[start..].trim();
+    if !last.is_empty() {
+        pairs.push(last);
+    }
+    pairs
+}
+
+/// Percent-encode everything but unreserved URL characters.
+fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_tags() {
+        let tags: QueryTags = ["team:checkout", "feature:cart", "canary", "team:payments"]
+            .into_iter()
+            .collect();
+
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags.get("team"), Some("payments"));
+        assert_eq!(tags.get("canary"), Some(""));
+        assert_eq!(tags.to_string(), "canary,feature:cart,team:payments");
+    }
+
+    #[test]
+    fn test_comment_round_trip() {
+        let tags: QueryTags = ["team:checkout", "feature:cart & wishlist", "owner:o'brien"]
+            .into_iter()
+            .collect();
+
+        let mut sql = "SELECT * FROM carts;".to_string();
+        tags.append_to(&mut sql);
+        assert_eq!(
+            sql,
+            "SELECT * FROM carts /*feature='cart%20%26%20wishlist',owner='o%27brien',team='checkout'*/;"
+        );
+        assert_eq!(QueryTags::from_sql(&sql), tags);
+
+        let mut sql = "SELECT 1".to_string();
+        QueryTags::new().append_to(&mut sql);
+        assert_eq!(sql, "SELECT 1");
+    }
+
+    #[test]
+    fn test_from_sql_without_comment() {
+        assert!(QueryTags::from_sql("SELECT * FROM carts").is_empty());
+        assert!(QueryTags::from_sql("SELECT /* hint */ 1").is_empty());
+        assert!(QueryTags::from_sql("SELECT 1 /* not tags */").is_empty());
+        assert_eq!(
+            QueryTags::from_sql("SELECT 1 /*route='%2Fcart',action='list'*/").get("route"),
+            Some("/cart")
+        );
+    }
+}
@@ -1,5 +1,7 @@
 //! Tenant configuration.
 
+use super::database::TenantPoolFactory;
+use super::quota::TenantQuotaManager;
 use super::resolver::TenantResolver;
 use super::strategy::{DatabaseConfig, IsolationStrategy, RowLevelConfig, SchemaConfig};
 use std::sync::Arc;
@@ -21,6 +23,8 @@ pub struct TenantConfig {
     pub enforce_on_writes: bool,
     /// Whether to log tenant context with queries.
     pub log_tenant_context: bool,
+    /// Per-tenant resource quotas.
+    pub quotas: Option<Arc<TenantQuotaManager>>,
 }
 
 impl std::fmt::Debug for TenantConfig {
@@ -32,6 +36,7 @@ impl std::fmt::Debug for TenantConfig {
             .field("allow_bypass", &self.allow_bypass)
             .field("enforce_on_writes", &self.enforce_on_writes)
             .field("log_tenant_context", &self.log_tenant_context)
+            .field("quotas", &self.quotas)
             .finish()
     }
 }
@@ -47,6 +52,7 @@ impl TenantConfig {
             resolver: None,
             enforce_on_writes: true,
             log_tenant_context: false,
+            quotas: None,
         }
     }
 
@@ -60,6 +66,7 @@ impl TenantConfig {
             resolver: None,
             enforce_on_writes: true,
             log_tenant_context: false,
+            quotas: None,
         }
     }
 
@@ -73,6 +80,22 @@ impl TenantConfig {
             resolver: None,
             enforce_on_writes: true,
             log_tenant_context: false,
+            quotas: None,
+        }
+    }
+
+    /// Create a database-per-tenant isolation config, opening tenant pools
+    /// with `factory`.
+    pub fn database_per_tenant(config: DatabaseConfig, factory: impl TenantPoolFactory) -> Self {
+        Self {
+            strategy: IsolationStrategy::database_per_tenant(config, factory),
+            require_tenant: true,
+            default_tenant: None,
+            allow_bypass: true,
+            resolver: None,
+            enforce_on_writes: true,
+            log_tenant_context: false,
+            quotas: None,
         }
     }
 
@@ -111,6 +134,12 @@ impl TenantConfig {
         self
     }
 
+    /// Enforce per-tenant resource quotas.
+    pub fn with_quotas(mut self, quotas: TenantQuotaManager) -> Self {
+        self.quotas = Some(Arc::new(quotas));
+        self
+    }
+
     /// Get the row-level config.
     pub fn row_level_config(&self) -> Option<&RowLevelConfig> {
         self.strategy.row_level_config()
@@ -137,6 +166,7 @@ pub struct TenantConfigBuilder {
     resolver: Option<Arc<dyn TenantResolver>>,
     enforce_on_writes: bool,
     log_tenant_context: bool,
+    quotas: Option<Arc<TenantQuotaManager>>,
 }
 
 impl TenantConfigBuilder {
@@ -174,6 +204,16 @@ impl TenantConfigBuilder {
         self
     }
 
+    /// Use database-per-tenant isolation.
+    pub fn database_per_tenant(
+        mut self,
+        config: DatabaseConfig,
+        factory: impl TenantPoolFactory,
+    ) -> Self {
+        self.strategy = Some(IsolationStrategy::database_per_tenant(config, factory));
+        self
+    }
+
     /// Require tenant context.
     pub fn require_tenant(mut self, require: bool) -> Self {
         self.require_tenant = require;
@@ -210,6 +250,12 @@ impl TenantConfigBuilder {
         self
     }
 
+    /// Enforce per-tenant resource quotas.
+    pub fn quotas(mut self, quotas: TenantQuotaManager) -> Self {
+        self.quotas = Some(Arc::new(quotas));
+        self
+    }
+
     /// Build the config.
     pub fn build(self) -> TenantConfig {
         TenantConfig {
@@ -222,6 +268,7 @@ impl TenantConfigBuilder {
             resolver: self.resolver,
             enforce_on_writes: self.enforce_on_writes,
             log_tenant_context: self.log_tenant_context,
+            quotas: self.quotas,
         }
     }
 }
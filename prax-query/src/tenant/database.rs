@@ -0,0 +1,393 @@
+//! Database-per-tenant routing.
+//!
+//! With the [`DatabasePerTenant`](super::IsolationStrategy::DatabasePerTenant)
+//! strategy every tenant's queries run on a connection pool of its own,
+//! connected to the tenant's database. A [`TenantPoolRouter`] maps tenant IDs
+//! to pools:
+//!
+//! - Pools are created lazily, by a [`TenantPoolFactory`], on a tenant's first
+//!   query. Concurrent first queries share a single creation.
+//! - At most [`max_tenant_connections`](DatabaseConfig::max_tenant_connections)
+//!   pools are open at once; opening another closes the least recently used
+//!   pool with no queries in flight.
+//! - Pools idle for longer than [`idle_timeout`](DatabaseConfig::idle_timeout)
+//!   are closed on the next query, or by [`TenantPoolRouter::evict_idle`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use prax_query::tenant::{TenantConfig, TenantDatabaseConfig, TenantMiddleware};
+//!
+//! let config = TenantConfig::database_per_tenant(
+//!     TenantDatabaseConfig::default()
+//!         .with_prefix("tenant_")
+//!         .with_pool_size(5)
+//!         .with_max_connections(200)
+//!         .with_idle_timeout(Duration::from_secs(600)),
+//!     |database: TenantDatabase| async move {
+//!         let url = format!("postgres://localhost/{}", database.name);
+//!         Ok(Arc::new(PgTenantPool::connect(&url, database.pool_size).await?) as Arc<dyn TenantPool>)
+//!     },
+//! );
+//! let stack = MiddlewareStack::new().with(TenantMiddleware::new(config));
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use tokio::sync::OnceCell;
+
+use super::context::TenantId;
+use super::strategy::DatabaseConfig;
+use crate::error::{ErrorCode, QueryError, QueryResult};
+use crate::middleware::{BoxFuture, QueryContext, QueryResponse};
+
+/// A connection pool to one tenant's database.
+pub trait TenantPool: Send + Sync + 'static {
+    /// Execute a query on the tenant's database.
+    fn execute(&self, ctx: QueryContext) -> BoxFuture<'_, QueryResult<QueryResponse>>;
+
+    /// Close the pool's connections. Called once the pool is evicted.
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// The database a tenant's pool connects to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantDatabase {
+    /// The tenant.
+    pub tenant_id: TenantId,
+    /// The database name, from [`DatabaseConfig::database_name`].
+    pub name: String,
+    /// Connections to open in the pool.
+    pub pool_size: usize,
+    /// Whether the database should be created if missing.
+    pub auto_create: bool,
+    /// Template database to create it from.
+    pub template: Option<String>,
+}
+
+/// Opens connection pools to tenant databases.
+///
+/// Implemented for async closures taking a [`TenantDatabase`].
+pub trait TenantPoolFactory: Send + Sync + 'static {
+    /// Open a pool to a tenant's database.
+    fn create(&self, database: TenantDatabase) -> BoxFuture<'_, QueryResult<Arc<dyn TenantPool>>>;
+}
+
+impl<F, Fut> TenantPoolFactory for F
+where
+    F: Fn(TenantDatabase) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = QueryResult<Arc<dyn TenantPool>>> + Send + 'static,
+{
+    fn create(&self, database: TenantDatabase) -> BoxFuture<'_, QueryResult<Arc<dyn TenantPool>>> {
+        Box::pin(self(database))
+    }
+}
+
+/// A tenant's pool, opened on first use.
+struct Slot {
+    pool: OnceCell<Arc<dyn TenantPool>>,
+    last_used: Mutex<Instant>,
+    in_flight: AtomicUsize,
+}
+
+impl Slot {
+    fn is_idle(&self) -> bool {
+        self.in_flight.load(Ordering::Acquire) == 0
+    }
+}
+
+/// Marks a query in flight on a slot, touching it when done.
+struct InFlight(Arc<Slot>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        *self.0.last_used.lock() = Instant::now();
+        self.0.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Routes queries to per-tenant connection pools.
+pub struct TenantPoolRouter {
+    config: DatabaseConfig,
+    factory: Arc<dyn TenantPoolFactory>,
+    pools: Mutex<HashMap<String, Arc<Slot>>>,
+}
+
+impl TenantPoolRouter {
+    /// Create a router opening pools with `factory`.
+    pub fn new(config: DatabaseConfig, factory: impl TenantPoolFactory) -> Self {
+        Self {
+            config,
+            factory: Arc::new(factory),
+            pools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the database config.
+    pub fn config(&self) -> &DatabaseConfig {
+        &self.config
+    }
+
+    /// Describe the database a tenant's pool connects to.
+    pub fn database(&self, tenant_id: &TenantId) -> TenantDatabase {
+        TenantDatabase {
+            tenant_id: tenant_id.clone(),
+            name: self.config.database_name(tenant_id.as_str()),
+            pool_size: self.config.pool_size_per_tenant,
+            auto_create: self.config.auto_create,
+            template: self.config.template_database.clone(),
+        }
+    }
+
+    /// Execute a query on the tenant's pool, opening it if needed.
+    pub async fn execute(
+        &self,
+        tenant_id: &TenantId,
+        ctx: QueryContext,
+    ) -> QueryResult<QueryResponse> {
+        let guard = self.checkout(tenant_id).await?;
+        let pool = guard
+            .0
+            .pool
+            .get_or_try_init(|| self.factory.create(self.database(tenant_id)))
+            .await?
+            .clone();
+        pool.execute(ctx).await
+    }
+
+    /// Close pools idle for longer than the idle timeout, returning how many
+    /// were closed.
+    pub async fn evict_idle(&self) -> usize {
+        let evicted = self.take_expired(&mut self.pools.lock());
+        let count = evicted.len();
+        close_all(evicted).await;
+        count
+    }
+
+    /// Close a tenant's pool, if open and idle.
+    pub async fn evict(&self, tenant_id: &TenantId) -> bool {
+        let slot = {
+            let mut pools = self.pools.lock();
+            match pools.get(tenant_id.as_str()) {
+                Some(slot) if slot.is_idle() => pools.remove(tenant_id.as_str()),
+                _ => None,
+            }
+        };
+        let evicted = slot.is_some();
+        close_all(slot).await;
+        evicted
+    }
+
+    /// Check whether a tenant has an open pool.
+    pub fn is_open(&self, tenant_id: &TenantId) -> bool {
+        self.pools.lock().contains_key(tenant_id.as_str())
+    }
+
+    /// Get the number of open tenant pools.
+    pub fn open_pools(&self) -> usize {
+        self.pools.lock().len()
+    }
+
+    /// Reserve a tenant's slot for a query, making room under the cap.
+    async fn checkout(&self, tenant_id: &TenantId) -> QueryResult<InFlight> {
+        let (slot, evicted) = {
+            let mut pools = self.pools.lock();
+            let mut evicted = self.take_expired(&mut pools);
+
+            if !pools.contains_key(tenant_id.as_str()) {
+                let max = self.config.max_tenant_connections;
+                if max > 0 && pools.len() >= max {
+                    let lru = pools
+                        .iter()
+                        .filter(|(_, slot)| slot.is_idle())
+                        .min_by_key(|(_, slot)| *slot.last_used.lock())
+                        .map(|(key, _)| key.clone());
+                    match lru.and_then(|key| pools.remove(&key)) {
+                        Some(slot) => evicted.push(slot),
+                        None => {
+                            return Err(QueryError::new(
+                                ErrorCode::PoolExhausted,
+                                format!("All {} tenant database pools are in use", max),
+                            )
+                            .with_suggestion("Increase max_tenant_connections")
+                            .with_suggestion(
+                                "Lower idle_timeout to close idle tenant pools sooner",
+                            ));
+                        }
+                    }
+                }
+            }
+
+            let slot = pools
+                .entry(tenant_id.as_str().to_string())
+                .or_insert_with(|| {
+                    Arc::new(Slot {
+                        pool: OnceCell::new(),
+                        last_used: Mutex::new(Instant::now()),
+                        in_flight: AtomicUsize::new(0),
+                    })
+                })
+                .clone();
+            // Counted under the lock, so the slot cannot be evicted meanwhile
+            slot.in_flight.fetch_add(1, Ordering::AcqRel);
+            (slot, evicted)
+        };
+
+        close_all(evicted).await;
+        Ok(InFlight(slot))
+    }
+
+    /// Remove idle slots past the idle timeout.
+    fn take_expired(&self, pools: &mut HashMap<String, Arc<Slot>>) -> Vec<Arc<Slot>> {
+        let Some(timeout) = self.config.idle_timeout else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        let expired: Vec<String> = pools
+            .iter()
+            .filter(|(_, slot)| {
+                slot.is_idle() && now.duration_since(*slot.last_used.lock()) >= timeout
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        expired.iter().filter_map(|key| pools.remove(key)).collect()
+    }
+}
+
+impl std::fmt::Debug for TenantPoolRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantPoolRouter")
+            .field("config", &self.config)
+            .field("open_pools", &self.open_pools())
+            .finish()
+    }
+}
+
+/// Close the pools of evicted slots.
+async fn close_all(slots: impl IntoIterator<Item = Arc<Slot>>) {
+    for slot in slots {
+        if let Some(pool) = slot.pool.get() {
+            pool.close().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::time::Duration;
+
+    struct TestPool {
+        database: String,
+        closed: Arc<AtomicBool>,
+    }
+
+    impl TenantPool for TestPool {
+        fn execute(&self, _ctx: QueryContext) -> BoxFuture<'_, QueryResult<QueryResponse>> {
+            let database = self.database.clone();
+            Box::pin(async move { Ok(QueryResponse::new(serde_json::json!(database))) })
+        }
+
+        fn close(&self) -> BoxFuture<'_, ()> {
+            self.closed.store(true, Ordering::Release);
+            Box::pin(async {})
+        }
+    }
+
+    /// A router counting pool creations, with the closed flags of its pools.
+    fn router(
+        config: DatabaseConfig,
+    ) -> (
+        TenantPoolRouter,
+        Arc<AtomicUsize>,
+        Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    ) {
+        let created = Arc::new(AtomicUsize::new(0));
+        let closed = Arc::new(Mutex::new(HashMap::new()));
+        let (count, flags) = (created.clone(), closed.clone());
+        let router = TenantPoolRouter::new(config, move |database: TenantDatabase| {
+            count.fetch_add(1, Ordering::AcqRel);
+            let flag = Arc::new(AtomicBool::new(false));
+            flags.lock().insert(database.name.clone(), flag.clone());
+            async move {
+                Ok(Arc::new(TestPool {
+                    database: database.name,
+                    closed: flag,
+                }) as Arc<dyn TenantPool>)
+            }
+        });
+        (router, created, closed)
+    }
+
+    fn query() -> QueryContext {
+        QueryContext::new("SELECT * FROM users", vec![])
+    }
+
+    #[tokio::test]
+    async fn test_pools_created_lazily() {
+        let (router, created, _) = router(DatabaseConfig::default().with_prefix("tenant_"));
+        let acme = TenantId::new("acme");
+        assert!(!router.is_open(&acme));
+
+        let response = router.execute(&acme, query()).await.unwrap();
+        assert_eq!(response.data, "tenant_acme");
+        router.execute(&acme, query()).await.unwrap();
+        router
+            .execute(&TenantId::new("globex"), query())
+            .await
+            .unwrap();
+
+        assert_eq!(created.load(Ordering::Acquire), 2);
+        assert_eq!(router.open_pools(), 2);
+        assert!(router.is_open(&acme));
+    }
+
+    #[tokio::test]
+    async fn test_pool_cap_evicts_least_recently_used() {
+        let (router, _, closed) = router(DatabaseConfig::default().with_max_connections(2));
+        for tenant in ["a", "b", "a", "c"] {
+            router
+                .execute(&TenantId::new(tenant), query())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(router.open_pools(), 2);
+        assert!(!router.is_open(&TenantId::new("b")));
+        assert!(closed.lock()["b"].load(Ordering::Acquire));
+        assert!(!closed.lock()["a"].load(Ordering::Acquire));
+
+        // Busy pools are never evicted
+        let _a = router.checkout(&TenantId::new("a")).await.unwrap();
+        let _c = router.checkout(&TenantId::new("c")).await.unwrap();
+        let err = router
+            .execute(&TenantId::new("d"), query())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::PoolExhausted);
+    }
+
+    #[tokio::test]
+    async fn test_idle_pools_evicted() {
+        let (router, _, closed) =
+            router(DatabaseConfig::default().with_idle_timeout(Duration::ZERO));
+        let busy = router.checkout(&TenantId::new("b")).await.unwrap();
+        router.execute(&TenantId::new("a"), query()).await.unwrap();
+
+        assert_eq!(router.evict_idle().await, 1);
+        assert!(closed.lock()["a"].load(Ordering::Acquire));
+        assert!(router.is_open(&TenantId::new("b")));
+
+        drop(busy);
+        assert!(router.evict(&TenantId::new("b")).await);
+        assert_eq!(router.open_pools(), 0);
+    }
+}
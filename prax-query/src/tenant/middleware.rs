@@ -6,8 +6,13 @@ use super::strategy::ColumnType;
 use crate::error::{QueryError, QueryResult};
 use crate::middleware::{BoxFuture, Middleware, Next, QueryContext, QueryResponse, QueryType};
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 /// Middleware that automatically applies tenant filtering to queries.
+///
+/// With database-per-tenant isolation, queries run on the tenant's pool
+/// rather than reaching the middleware after this one or the final handler,
+/// so add it last. Bypassed queries continue down the chain.
 pub struct TenantMiddleware {
     config: TenantConfig,
     current_tenant: Arc<RwLock<Option<TenantContext>>>,
@@ -252,8 +257,28 @@ impl Middleware for TenantMiddleware {
             // Set tenant in metadata for downstream middleware
             ctx.metadata_mut().tenant_id = Some(tenant_ctx.id.to_string());
 
-            // Continue with modified query
-            next.run(ctx).await
+            // With database-per-tenant isolation the query runs on the
+            // tenant's pool instead of continuing down the chain
+            let run = async {
+                match self.config.strategy.pool_router() {
+                    Some(router) => router.execute(&tenant_ctx.id, ctx).await,
+                    None => next.run(ctx).await,
+                }
+            };
+
+            // Enforce resource quotas and account for the query's usage
+            if let Some(quotas) = &self.config.quotas {
+                let tenant_id = tenant_ctx.id.as_str();
+                quotas.enforce(tenant_id).await?;
+
+                let start = Instant::now();
+                let result = run.await;
+                let rows = result.as_ref().map_or(0, response_rows);
+                quotas.record(tenant_id, start.elapsed(), rows);
+                return result;
+            }
+
+            run.await
         })
     }
 
@@ -262,6 +287,15 @@ impl Middleware for TenantMiddleware {
     }
 }
 
+/// Rows returned or affected by a query, for quota accounting.
+fn response_rows(response: &QueryResponse) -> u64 {
+    response.rows_affected.unwrap_or(match &response.data {
+        serde_json::Value::Array(rows) => rows.len() as u64,
+        serde_json::Value::Null => 0,
+        _ => 1,
+    })
+}
+
 /// A scoped tenant context that clears on drop.
 pub struct TenantScope {
     middleware: Arc<TenantMiddleware>,
@@ -313,6 +347,73 @@ mod tests {
         assert!(sql.contains("WHERE tenant_id = 'tenant-123'"));
     }
 
+    #[tokio::test]
+    async fn test_quota_enforcement() {
+        use crate::middleware::MiddlewareChain;
+        use crate::tenant::{QuotaConfig, QuotaLimits, TenantQuotaManager};
+        use std::time::Duration;
+
+        let quotas = TenantQuotaManager::new(
+            QuotaConfig::new(Duration::from_secs(60))
+                .with_limits(QuotaLimits::new().with_max_rows(2)),
+        );
+        let middleware =
+            TenantMiddleware::new(TenantConfig::row_level("tenant_id").with_quotas(quotas));
+        middleware.set_tenant(TenantContext::new("tenant-123"));
+        let mut chain = MiddlewareChain::new();
+        chain.push(middleware);
+
+        let run = || {
+            chain.execute(QueryContext::new("SELECT * FROM users", vec![]), |_| {
+                Box::pin(async { Ok(QueryResponse::new(serde_json::json!([1, 2, 3]))) })
+            })
+        };
+
+        // The first query goes over the limit, the second is rejected
+        assert!(run().await.is_ok());
+        let err = run().await.unwrap_err();
+        assert!(err.is_quota_exceeded());
+    }
+
+    #[tokio::test]
+    async fn test_database_per_tenant_routing() {
+        use crate::middleware::{BoxFuture, MiddlewareChain};
+        use crate::tenant::{TenantDatabase, TenantDatabaseConfig, TenantPool};
+
+        struct NamedPool(String);
+
+        impl TenantPool for NamedPool {
+            fn execute(&self, _ctx: QueryContext) -> BoxFuture<'_, QueryResult<QueryResponse>> {
+                let name = self.0.clone();
+                Box::pin(async move { Ok(QueryResponse::new(serde_json::json!(name))) })
+            }
+        }
+
+        let config = TenantConfig::database_per_tenant(
+            TenantDatabaseConfig::default().with_prefix("tenant_"),
+            |database: TenantDatabase| async move {
+                Ok(Arc::new(NamedPool(database.name)) as Arc<dyn TenantPool>)
+            },
+        );
+        let middleware = TenantMiddleware::new(config);
+        let mut chain = MiddlewareChain::new();
+        chain.push(middleware.clone());
+
+        let run = || {
+            chain.execute(QueryContext::new("SELECT * FROM users", vec![]), |_| {
+                Box::pin(async { Ok(QueryResponse::new(serde_json::json!("shared"))) })
+            })
+        };
+
+        middleware.set_tenant(TenantContext::new("acme"));
+        assert_eq!(run().await.unwrap().data, "tenant_acme");
+        middleware.set_tenant(TenantContext::new("globex"));
+        assert_eq!(run().await.unwrap().data, "tenant_globex");
+
+        let router = middleware.config.strategy.pool_router().unwrap();
+        assert_eq!(router.open_pools(), 2);
+    }
+
     #[test]
     fn test_tenant_scope() {
         let config = TenantConfig::row_level("tenant_id");
@@ -8,6 +8,7 @@
 //! - **Row-Level Security (RLS)**: All tenants share tables, filtered by tenant_id column
 //! - **Schema-Based**: Each tenant has their own database schema
 //! - **Database-Based**: Each tenant has their own database
+//! - **Database-per-Tenant**: Each tenant's queries run on a pool to their own database
 //!
 //! # Performance Features
 //!
@@ -93,6 +94,40 @@
 //!     });
 //! ```
 //!
+//! ## Database-per-Tenant
+//!
+//! The middleware routes each tenant's queries to a connection pool of its
+//! own, opened lazily and closed when idle or when the pool cap is reached:
+//!
+//! ```rust,ignore
+//! use prax_query::tenant::{TenantConfig, TenantDatabase, TenantDatabaseConfig};
+//!
+//! let config = TenantConfig::database_per_tenant(
+//!     TenantDatabaseConfig::default()
+//!         .with_prefix("tenant_")
+//!         .with_max_connections(200)
+//!         .with_idle_timeout(Duration::from_secs(600)),
+//!     |database: TenantDatabase| async move { open_pool(&database.name).await },
+//! );
+//! ```
+//!
+//! # Resource Quotas
+//!
+//! Per-tenant quotas cap query time and rows within a window, throttling or
+//! rejecting tenants that go over and emitting events for billing:
+//!
+//! ```rust,ignore
+//! use prax_query::tenant::{QuotaConfig, QuotaLimits, TenantQuotaManager};
+//!
+//! let quotas = TenantQuotaManager::new(
+//!     QuotaConfig::new(Duration::from_secs(60))
+//!         .with_limits(QuotaLimits::new().with_max_query_time(Duration::from_secs(10))),
+//! )
+//! .with_listener(|event| billing.record(event));
+//!
+//! let config = TenantConfig::row_level("tenant_id").with_quotas(quotas);
+//! ```
+//!
 //! # Caching
 //!
 //! The module provides high-performance caching for tenant lookups:
@@ -115,9 +150,11 @@
 mod cache;
 mod config;
 mod context;
+mod database;
 mod middleware;
 mod pool;
 mod prepared;
+mod quota;
 mod resolver;
 mod rls;
 mod strategy;
@@ -126,7 +163,12 @@ mod task_local;
 // Core types
 pub use config::{TenantConfig, TenantConfigBuilder};
 pub use context::{TenantContext, TenantId, TenantInfo};
+pub use database::{TenantDatabase, TenantPool, TenantPoolFactory, TenantPoolRouter};
 pub use middleware::TenantMiddleware;
+pub use quota::{
+    QuotaAction, QuotaConfig, QuotaDecision, QuotaEvent, QuotaLimits, QuotaResource,
+    TenantQuotaManager, TenantUsage,
+};
 pub use resolver::{
     CompositeResolver, DatabaseResolver, DynamicResolver, StaticResolver, TenantResolver,
 };
@@ -0,0 +1,491 @@
+//! Per-tenant resource quotas.
+//!
+//! Quotas limit how much query time and how many rows each tenant may use
+//! within a fixed window. Tenants over quota are either throttled or have
+//! their queries rejected until the window rolls over, and every decision is
+//! reported to the registered listeners for billing and abuse detection.
+//!
+//! ```rust,ignore
+//! use prax_query::tenant::{QuotaAction, QuotaConfig, QuotaLimits, TenantConfig, TenantQuotaManager};
+//! use std::time::Duration;
+//!
+//! let quotas = TenantQuotaManager::new(
+//!     QuotaConfig::new(Duration::from_secs(60))
+//!         .with_limits(QuotaLimits::new().with_max_rows(100_000))
+//!         .with_tenant_limits("enterprise", QuotaLimits::unlimited())
+//!         .with_action(QuotaAction::Reject),
+//! )
+//! .with_listener(|event| tracing::info!(?event, "tenant quota event"));
+//!
+//! let config = TenantConfig::row_level("tenant_id").with_quotas(quotas);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::error::{QueryError, QueryResult};
+
+/// A resource limited by tenant quotas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuotaResource {
+    /// Total query execution time.
+    QueryTime,
+    /// Rows returned or affected by queries.
+    Rows,
+}
+
+impl fmt::Display for QuotaResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QueryTime => write!(f, "query time"),
+            Self::Rows => write!(f, "rows"),
+        }
+    }
+}
+
+/// Limits for a tenant within one quota window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuotaLimits {
+    /// Maximum total query time.
+    pub max_query_time: Option<Duration>,
+    /// Maximum number of rows returned or affected.
+    pub max_rows: Option<u64>,
+}
+
+impl QuotaLimits {
+    /// Create limits with nothing limited.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits that never trigger, e.g. for a tenant exempt from quotas.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Limit total query time.
+    pub fn with_max_query_time(mut self, max: Duration) -> Self {
+        self.max_query_time = Some(max);
+        self
+    }
+
+    /// Limit rows returned or affected.
+    pub fn with_max_rows(mut self, max: u64) -> Self {
+        self.max_rows = Some(max);
+        self
+    }
+
+    /// The first limit exceeded by the given usage.
+    fn exceeded_by(&self, usage: &TenantUsage) -> Option<QuotaResource> {
+        if self
+            .max_query_time
+            .is_some_and(|max| usage.query_time > max)
+        {
+            Some(QuotaResource::QueryTime)
+        } else if self.max_rows.is_some_and(|max| usage.rows > max) {
+            Some(QuotaResource::Rows)
+        } else {
+            None
+        }
+    }
+}
+
+/// What happens to queries from a tenant over quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaAction {
+    /// Fail queries with a quota error until the window rolls over.
+    Reject,
+    /// Delay each query by the given duration until the window rolls over.
+    Throttle(Duration),
+}
+
+/// Configuration for tenant quotas.
+#[derive(Debug, Clone)]
+pub struct QuotaConfig {
+    /// Length of a quota window.
+    pub window: Duration,
+    /// Limits for tenants without an override.
+    pub limits: QuotaLimits,
+    /// Per-tenant limits.
+    pub overrides: HashMap<String, QuotaLimits>,
+    /// What happens to queries from a tenant over quota.
+    pub action: QuotaAction,
+}
+
+impl QuotaConfig {
+    /// Create a config with the given window and no limits.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            limits: QuotaLimits::default(),
+            overrides: HashMap::new(),
+            action: QuotaAction::Reject,
+        }
+    }
+
+    /// Set the limits for tenants without an override.
+    pub fn with_limits(mut self, limits: QuotaLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set the limits for a single tenant.
+    pub fn with_tenant_limits(mut self, tenant_id: impl Into<String>, limits: QuotaLimits) -> Self {
+        self.overrides.insert(tenant_id.into(), limits);
+        self
+    }
+
+    /// Set what happens to queries from a tenant over quota.
+    pub fn with_action(mut self, action: QuotaAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Get the limits for a tenant.
+    pub fn limits_for(&self, tenant_id: &str) -> &QuotaLimits {
+        self.overrides.get(tenant_id).unwrap_or(&self.limits)
+    }
+}
+
+/// Resource usage of a tenant within a quota window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantUsage {
+    /// Total query execution time.
+    pub query_time: Duration,
+    /// Rows returned or affected.
+    pub rows: u64,
+    /// Number of queries.
+    pub queries: u64,
+}
+
+/// An event emitted by tenant quota enforcement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuotaEvent {
+    /// A tenant went over a limit; emitted once per resource and window.
+    Exceeded {
+        /// The tenant.
+        tenant_id: String,
+        /// The exceeded resource.
+        resource: QuotaResource,
+        /// Usage when the limit was exceeded.
+        usage: TenantUsage,
+    },
+    /// A query from a tenant over quota was delayed.
+    Throttled {
+        /// The tenant.
+        tenant_id: String,
+        /// The delay applied.
+        delay: Duration,
+    },
+    /// A query from a tenant over quota was rejected.
+    Rejected {
+        /// The tenant.
+        tenant_id: String,
+        /// The exceeded resource.
+        resource: QuotaResource,
+    },
+    /// A tenant's quota window ended; carries the usage for billing.
+    WindowClosed {
+        /// The tenant.
+        tenant_id: String,
+        /// Usage over the whole window.
+        usage: TenantUsage,
+    },
+}
+
+/// The decision for a query about to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaDecision {
+    /// Run the query.
+    Allow,
+    /// Run the query after the given delay.
+    Throttle(Duration),
+    /// Reject the query.
+    Reject(QuotaResource),
+}
+
+type QuotaListener = Arc<dyn Fn(&QuotaEvent) + Send + Sync>;
+
+#[derive(Debug)]
+struct UsageWindow {
+    started: Instant,
+    usage: TenantUsage,
+    exceeded: Option<QuotaResource>,
+}
+
+impl UsageWindow {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            usage: TenantUsage::default(),
+            exceeded: None,
+        }
+    }
+}
+
+/// Tracks tenant usage and enforces quotas.
+///
+/// Usage is kept per tenant in fixed windows starting at the tenant's first
+/// query; a window that has run out is closed and reset lazily on the next
+/// query of that tenant.
+pub struct TenantQuotaManager {
+    config: QuotaConfig,
+    windows: Mutex<HashMap<String, UsageWindow>>,
+    listeners: Vec<QuotaListener>,
+}
+
+impl TenantQuotaManager {
+    /// Create a quota manager.
+    pub fn new(config: QuotaConfig) -> Self {
+        Self {
+            config,
+            windows: Mutex::new(HashMap::new()),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Register a listener for quota events.
+    pub fn with_listener<F>(mut self, listener: F) -> Self
+    where
+        F: Fn(&QuotaEvent) + Send + Sync + 'static,
+    {
+        self.listeners.push(Arc::new(listener));
+        self
+    }
+
+    /// Get the quota config.
+    pub fn config(&self) -> &QuotaConfig {
+        &self.config
+    }
+
+    /// Decide whether a tenant's next query may run.
+    pub fn check(&self, tenant_id: &str) -> QuotaDecision {
+        let mut events = Vec::new();
+        let exceeded = {
+            let mut windows = self.windows.lock();
+            let window = self.current_window(&mut windows, tenant_id, &mut events);
+            window.exceeded
+        };
+
+        let decision = match (exceeded, self.config.action) {
+            (None, _) => QuotaDecision::Allow,
+            (Some(resource), QuotaAction::Reject) => {
+                events.push(QuotaEvent::Rejected {
+                    tenant_id: tenant_id.to_string(),
+                    resource,
+                });
+                QuotaDecision::Reject(resource)
+            }
+            (Some(_), QuotaAction::Throttle(delay)) => {
+                events.push(QuotaEvent::Throttled {
+                    tenant_id: tenant_id.to_string(),
+                    delay,
+                });
+                QuotaDecision::Throttle(delay)
+            }
+        };
+
+        self.emit(&events);
+        decision
+    }
+
+    /// Record the resources used by a finished query.
+    pub fn record(&self, tenant_id: &str, query_time: Duration, rows: u64) {
+        let mut events = Vec::new();
+        {
+            let mut windows = self.windows.lock();
+            let window = self.current_window(&mut windows, tenant_id, &mut events);
+            window.usage.query_time += query_time;
+            window.usage.rows += rows;
+            window.usage.queries += 1;
+
+            if window.exceeded.is_none() {
+                let limits = self.config.limits_for(tenant_id);
+                if let Some(resource) = limits.exceeded_by(&window.usage) {
+                    window.exceeded = Some(resource);
+                    events.push(QuotaEvent::Exceeded {
+                        tenant_id: tenant_id.to_string(),
+                        resource,
+                        usage: window.usage,
+                    });
+                }
+            }
+        }
+
+        self.emit(&events);
+    }
+
+    /// Check a tenant's quota, waiting out a throttle delay.
+    ///
+    /// Returns a quota error if the query is rejected.
+    pub async fn enforce(&self, tenant_id: &str) -> QueryResult<()> {
+        match self.check(tenant_id) {
+            QuotaDecision::Allow => Ok(()),
+            QuotaDecision::Throttle(delay) => {
+                tokio::time::sleep(delay).await;
+                Ok(())
+            }
+            QuotaDecision::Reject(resource) => {
+                Err(QueryError::quota_exceeded(tenant_id, resource.to_string()))
+            }
+        }
+    }
+
+    /// Get a tenant's usage in its current window.
+    pub fn usage(&self, tenant_id: &str) -> Option<TenantUsage> {
+        let windows = self.windows.lock();
+        windows
+            .get(tenant_id)
+            .filter(|w| w.started.elapsed() < self.config.window)
+            .map(|w| w.usage)
+    }
+
+    /// Reset a tenant's usage, lifting any throttling or rejection.
+    pub fn reset(&self, tenant_id: &str) {
+        self.windows.lock().remove(tenant_id);
+    }
+
+    /// Get the tenant's window, closing and replacing it if it has run out.
+    fn current_window<'w>(
+        &self,
+        windows: &'w mut HashMap<String, UsageWindow>,
+        tenant_id: &str,
+        events: &mut Vec<QuotaEvent>,
+    ) -> &'w mut UsageWindow {
+        let window = windows
+            .entry(tenant_id.to_string())
+            .or_insert_with(UsageWindow::new);
+
+        if window.started.elapsed() >= self.config.window {
+            let closed = std::mem::replace(window, UsageWindow::new());
+            events.push(QuotaEvent::WindowClosed {
+                tenant_id: tenant_id.to_string(),
+                usage: closed.usage,
+            });
+        }
+
+        window
+    }
+
+    fn emit(&self, events: &[QuotaEvent]) {
+        for event in events {
+            for listener in &self.listeners {
+                listener(event);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TenantQuotaManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TenantQuotaManager")
+            .field("config", &self.config)
+            .field("tenants", &self.windows.lock().len())
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorder() -> (
+        Arc<Mutex<Vec<QuotaEvent>>>,
+        impl Fn(&QuotaEvent) + Send + Sync,
+    ) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        (events, move |event: &QuotaEvent| {
+            sink.lock().push(event.clone())
+        })
+    }
+
+    #[test]
+    fn test_reject_over_quota() {
+        let (events, listener) = recorder();
+        let quotas = TenantQuotaManager::new(
+            QuotaConfig::new(Duration::from_secs(60))
+                .with_limits(QuotaLimits::new().with_max_rows(100))
+                .with_tenant_limits("big", QuotaLimits::unlimited()),
+        )
+        .with_listener(listener);
+
+        assert_eq!(quotas.check("acme"), QuotaDecision::Allow);
+        quotas.record("acme", Duration::from_millis(5), 150);
+        quotas.record("big", Duration::from_millis(5), 150);
+
+        assert_eq!(
+            quotas.check("acme"),
+            QuotaDecision::Reject(QuotaResource::Rows)
+        );
+        assert_eq!(quotas.check("big"), QuotaDecision::Allow);
+
+        let events = events.lock();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            QuotaEvent::Exceeded { tenant_id, resource: QuotaResource::Rows, usage }
+                if tenant_id == "acme" && usage.rows == 150
+        ));
+        assert!(
+            matches!(&events[1], QuotaEvent::Rejected { tenant_id, .. } if tenant_id == "acme")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_throttle_over_quota() {
+        let quotas = TenantQuotaManager::new(
+            QuotaConfig::new(Duration::from_secs(60))
+                .with_limits(QuotaLimits::new().with_max_query_time(Duration::from_millis(10)))
+                .with_action(QuotaAction::Throttle(Duration::from_millis(1))),
+        );
+
+        quotas.record("acme", Duration::from_millis(20), 1);
+        assert_eq!(
+            quotas.check("acme"),
+            QuotaDecision::Throttle(Duration::from_millis(1))
+        );
+        assert!(quotas.enforce("acme").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_rejects_with_quota_error() {
+        let quotas = TenantQuotaManager::new(
+            QuotaConfig::new(Duration::from_secs(60))
+                .with_limits(QuotaLimits::new().with_max_rows(0)),
+        );
+
+        quotas.record("acme", Duration::ZERO, 1);
+        let err = quotas.enforce("acme").await.unwrap_err();
+        assert!(err.is_quota_exceeded());
+        assert!(err.message.contains("acme"));
+    }
+
+    #[test]
+    fn test_window_rollover() {
+        let (events, listener) = recorder();
+        let quotas = TenantQuotaManager::new(
+            QuotaConfig::new(Duration::from_millis(20))
+                .with_limits(QuotaLimits::new().with_max_rows(10)),
+        )
+        .with_listener(listener);
+
+        quotas.record("acme", Duration::from_millis(1), 50);
+        assert_eq!(quotas.usage("acme").unwrap().rows, 50);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(quotas.usage("acme").is_none());
+        assert_eq!(quotas.check("acme"), QuotaDecision::Allow);
+
+        let events = events.lock();
+        assert!(matches!(
+            events.last(),
+            Some(QuotaEvent::WindowClosed { usage, .. }) if usage.rows == 50 && usage.queries == 1
+        ));
+    }
+}
@@ -1,6 +1,10 @@
 //! Tenant isolation strategies.
 
 use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::database::{TenantPoolFactory, TenantPoolRouter};
 
 /// The isolation strategy for multi-tenancy.
 #[derive(Debug, Clone)]
@@ -11,6 +15,9 @@ pub enum IsolationStrategy {
     Schema(SchemaConfig),
     /// Database-based: each tenant has their own database.
     Database(DatabaseConfig),
+    /// Database-per-tenant: each tenant's queries run on a connection pool
+    /// to its own database.
+    DatabasePerTenant(Arc<TenantPoolRouter>),
     /// Hybrid: combination of strategies (e.g., schema + row-level).
     Hybrid(Box<IsolationStrategy>, Box<IsolationStrategy>),
 }
@@ -31,6 +38,12 @@ impl IsolationStrategy {
         Self::Database(DatabaseConfig::default())
     }
 
+    /// Create a database-per-tenant strategy, opening tenant pools with
+    /// `factory`.
+    pub fn database_per_tenant(config: DatabaseConfig, factory: impl TenantPoolFactory) -> Self {
+        Self::DatabasePerTenant(Arc::new(TenantPoolRouter::new(config, factory)))
+    }
+
     /// Check if this is row-level isolation.
     pub fn is_row_level(&self) -> bool {
         matches!(self, Self::RowLevel(_))
@@ -46,6 +59,11 @@ impl IsolationStrategy {
         matches!(self, Self::Database(_))
     }
 
+    /// Check if this is database-per-tenant isolation.
+    pub fn is_database_per_tenant(&self) -> bool {
+        matches!(self, Self::DatabasePerTenant(_))
+    }
+
     /// Get the row-level config if applicable.
     pub fn row_level_config(&self) -> Option<&RowLevelConfig> {
         match self {
@@ -68,10 +86,20 @@ impl IsolationStrategy {
     pub fn database_config(&self) -> Option<&DatabaseConfig> {
         match self {
             Self::Database(config) => Some(config),
+            Self::DatabasePerTenant(router) => Some(router.config()),
             Self::Hybrid(a, b) => a.database_config().or_else(|| b.database_config()),
             _ => None,
         }
     }
+
+    /// Get the tenant pool router if applicable.
+    pub fn pool_router(&self) -> Option<&Arc<TenantPoolRouter>> {
+        match self {
+            Self::DatabasePerTenant(router) => Some(router),
+            Self::Hybrid(a, b) => a.pool_router().or_else(|| b.pool_router()),
+            _ => None,
+        }
+    }
 }
 
 /// Configuration for row-level tenant isolation.
@@ -297,7 +325,12 @@ pub struct DatabaseConfig {
     /// Connection pool size per tenant.
     pub pool_size_per_tenant: usize,
     /// Maximum number of tenant connections to keep.
+    ///
+    /// For database-per-tenant isolation, the maximum number of tenant pools
+    /// open at once; zero means no limit.
     pub max_tenant_connections: usize,
+    /// Time after which an idle tenant pool is closed.
+    pub idle_timeout: Option<Duration>,
 }
 
 impl DatabaseConfig {
@@ -337,6 +370,12 @@ impl DatabaseConfig {
         self
     }
 
+    /// Close tenant pools idle for longer than `timeout`.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
     /// Generate the database name for a tenant.
     pub fn database_name(&self, tenant_id: &str) -> String {
         let mut name = String::new();
@@ -3,6 +3,8 @@
 use std::future::Future;
 use std::pin::Pin;
 
+use futures::{Stream, StreamExt};
+
 use crate::error::QueryResult;
 use crate::filter::Filter;
 
@@ -19,6 +21,26 @@ pub trait Model: Sized + Send + Sync {
 
     /// All column names for this model.
     const COLUMNS: &'static [&'static str];
+
+    /// Computed fields as `(name, SQL expression)` pairs.
+    ///
+    /// Computed fields have no column: reads select the expression under the
+    /// field's name, and writes never include them.
+    const VIRTUAL_FIELDS: &'static [(&'static str, &'static str)] = &[];
+
+    /// Columns with their schema types, checked by engines in strict mode.
+    ///
+    /// See [`drift`](crate::drift); models without specs are never checked.
+    const COLUMN_SPECS: &'static [crate::drift::ColumnSpec] = &[];
+
+    /// Indexes declared with `@unique`, `@@unique` and `@@index`, checked by
+    /// [`verify_schema`](crate::verify::verify_schema).
+    const INDEXES: &'static [crate::verify::IndexSpec] = &[];
+
+    /// The timestamp column rows expire at (`@@ttl`), if any.
+    ///
+    /// Finds and counts skip expired rows; see [`ttl`](crate::ttl).
+    const TTL_COLUMN: Option<&'static str> = None;
 }
 
 /// A database view that can be queried (read-only).
@@ -37,6 +59,12 @@ pub trait View: Sized + Send + Sync {
 
     /// Whether this is a materialized view.
     const IS_MATERIALIZED: bool;
+
+    /// Columns that together identify a row, if any.
+    ///
+    /// Views have no primary key, so keyset pagination appends these to the
+    /// query's ordering to give every row a distinct position.
+    const UNIQUE_KEY: &'static [&'static str] = &[];
 }
 
 /// A materialized view that supports refresh operations.
@@ -75,6 +103,9 @@ pub trait Executable {
 /// A boxed future for async operations.
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
+/// A boxed stream for incrementally fetched results.
+pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+
 /// The query engine abstraction.
 ///
 /// This trait defines how queries are executed against a database.
@@ -137,6 +168,48 @@ pub trait QueryEngine: Send + Sync + Clone + 'static {
         params: Vec<crate::filter::FilterValue>,
     ) -> BoxFuture<'_, QueryResult<u64>>;
 
+    /// Execute a SELECT query, fetching rows in batches of `fetch_size`.
+    ///
+    /// Drivers with cursor support override this to keep at most one batch in
+    /// memory and to stop fetching when the stream is dropped. The default
+    /// implementation runs [`query_many`](Self::query_many) and splits the
+    /// result into batches.
+    fn query_batches<T: Model + Send + 'static>(
+        &self,
+        sql: &str,
+        params: Vec<crate::filter::FilterValue>,
+        fetch_size: usize,
+    ) -> BoxStream<'static, QueryResult<Vec<T>>> {
+        let engine = self.clone();
+        let sql = sql.to_string();
+        Box::pin(
+            futures::stream::once(async move { engine.query_many::<T>(&sql, params).await })
+                .flat_map(move |result| {
+                    let batches = match result {
+                        Ok(rows) => into_batches(rows, fetch_size).into_iter().map(Ok).collect(),
+                        Err(e) => vec![Err(e)],
+                    };
+                    futures::stream::iter(batches)
+                }),
+        )
+    }
+
+    /// Bulk insert rows with the database's native bulk-load protocol, such
+    /// as PostgreSQL's `COPY`, returning the number of rows inserted.
+    ///
+    /// Each row holds one value per column, in order. Databases without a
+    /// bulk-load protocol return an error; use a multi-row `INSERT` instead.
+    fn copy_in(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: Vec<Vec<crate::filter::FilterValue>>,
+    ) -> BoxFuture<'_, QueryResult<u64>> {
+        let _ = (columns, rows);
+        let message = format!("Bulk COPY into {} is not supported by this database", table);
+        Box::pin(async move { Err(crate::error::QueryError::unsupported(message)) })
+    }
+
     /// Refresh a materialized view.
     ///
     /// For PostgreSQL, this executes `REFRESH MATERIALIZED VIEW`.
@@ -155,6 +228,45 @@ pub trait QueryEngine: Send + Sync + Clone + 'static {
             ))
         })
     }
+
+    /// Get the database's plan for a query, running it to measure the plan
+    /// when `analyze` is set.
+    ///
+    /// Drivers build the statement with
+    /// [`QueryPlan::explain_sql`](crate::explain::QueryPlan::explain_sql) and
+    /// parse its output; the default implementation returns an error.
+    fn explain(
+        &self,
+        sql: &str,
+        params: Vec<crate::filter::FilterValue>,
+        analyze: bool,
+    ) -> BoxFuture<'_, QueryResult<crate::explain::QueryPlan>> {
+        let _ = (sql, params, analyze);
+        Box::pin(async move {
+            Err(crate::error::QueryError::unsupported(
+                "Query plans are not supported by this database",
+            ))
+        })
+    }
+
+    /// The SQL dialect of the database.
+    ///
+    /// Operations whose syntax differs between databases, such as upserts,
+    /// generate SQL for this dialect.
+    fn database_type(&self) -> crate::sql::DatabaseType {
+        crate::sql::DatabaseType::PostgreSQL
+    }
+}
+
+/// Split rows into batches of at most `size` rows.
+fn into_batches<T>(rows: Vec<T>, size: usize) -> Vec<Vec<T>> {
+    let size = size.max(1);
+    let mut batches = Vec::with_capacity(rows.len().div_ceil(size));
+    let mut rows = rows.into_iter().peekable();
+    while rows.peek().is_some() {
+        batches.push(rows.by_ref().take(size).collect());
+    }
+    batches
 }
 
 /// Query engine extension for view operations.
@@ -275,6 +387,15 @@ mod tests {
         assert_eq!(TestModel::PRIMARY_KEY, &["id"]);
     }
 
+    #[test]
+    fn test_into_batches() {
+        let batches = into_batches((1..=5).collect::<Vec<_>>(), 2);
+        assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        assert!(into_batches(Vec::<i32>::new(), 2).is_empty());
+        assert_eq!(into_batches(vec![1, 2], 0), vec![vec![1], vec![2]]);
+    }
+
     #[test]
     fn test_into_filter() {
         let filter = Filter::Equals("id".into(), crate::filter::FilterValue::Int(1));
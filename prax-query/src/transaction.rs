@@ -72,7 +72,7 @@
 //!         tx.user().create(/* ... */).exec().await?;
 //!
 //!         // This can be rolled back independently
-//!         let savepoint_result = tx.savepoint("sp1", |sp| async move {
+//!         let savepoint_result = tx.with_savepoint("sp1", |sp| async move {
 //!             sp.post().create(/* ... */).exec().await?;
 //!             Ok(())
 //!         }).await;
@@ -85,13 +85,32 @@
 //!         Ok(())
 //!     })
 //!     .await?;
+//!
+//! // Or manage a `Transaction`'s savepoint by hand
+//! let savepoint = tx.savepoint("before_import").await?;
+//! if import(&tx).await.is_err() {
+//!     savepoint.rollback_to().await?;
+//! }
+//! savepoint.release().await?;
+//!
+//! // A transaction started inside another runs in a savepoint of it
+//! client
+//!     .transaction(|tx| async move {
+//!         tx.user().create(/* ... */).exec().await?;
+//!         // Rolling back the inner transaction keeps the user
+//!         let _ = tx.transaction(|inner| async move { /* ... */ }).await;
+//!         Ok(())
+//!     })
+//!     .await?;
 //! ```
 
-use std::future::Future;
+use std::future::{Future, IntoFuture};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use tracing::debug;
 
-use crate::error::QueryResult;
+use crate::error::{QueryError, QueryResult};
+use crate::traits::{BoxFuture, QueryEngine};
 
 /// Transaction isolation levels.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -311,6 +330,245 @@ where
     }
 }
 
+impl<E, F, Fut, T> TransactionBuilder<E, F, Fut, T>
+where
+    F: FnOnce(Transaction<E>) -> Fut,
+    Fut: Future<Output = QueryResult<T>>,
+{
+    /// Replace the transaction configuration.
+    pub fn with_config(mut self, config: TransactionConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl<E, F, Fut, T> IntoFuture for TransactionBuilder<E, F, Fut, T>
+where
+    E: TransactionalEngine,
+    F: FnOnce(Transaction<E>) -> Fut + Send + 'static,
+    Fut: Future<Output = QueryResult<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    type Output = QueryResult<T>;
+    type IntoFuture = BoxFuture<'static, QueryResult<T>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let config = self.config.clone();
+            let callback = self.callback;
+            run_transaction(&self.engine, &self.config, move |engine| {
+                callback(Transaction::new(engine, config))
+            })
+            .await
+        })
+    }
+}
+
+/// A query engine that can pin a transaction to one connection.
+///
+/// [`begin`](Self::begin) returns an engine whose queries, and those of its
+/// clones, all run inside the new transaction until it is committed or rolled
+/// back. Dropping every clone of an open transaction must roll it back.
+pub trait TransactionalEngine: QueryEngine {
+    /// Start a transaction and return an engine bound to it.
+    fn begin(&self, config: &TransactionConfig) -> BoxFuture<'_, QueryResult<Self>>;
+
+    /// Commit the transaction this engine is bound to.
+    fn commit(&self) -> BoxFuture<'_, QueryResult<()>>;
+
+    /// Roll back the transaction this engine is bound to.
+    fn rollback(&self) -> BoxFuture<'_, QueryResult<()>>;
+
+    /// Check if this engine is bound to a transaction.
+    ///
+    /// A transaction run on an engine that is nests in a savepoint of it
+    /// rather than beginning another; see [`run_transaction`].
+    fn in_transaction(&self) -> bool {
+        false
+    }
+
+    /// Create a savepoint in the current transaction.
+    fn savepoint(&self, name: &str) -> BoxFuture<'_, QueryResult<()>> {
+        let sql = format!("SAVEPOINT {}", name);
+        Box::pin(async move { self.execute_raw(&sql, Vec::new()).await.map(|_| ()) })
+    }
+
+    /// Roll back to a savepoint, keeping the rest of the transaction.
+    fn rollback_to_savepoint(&self, name: &str) -> BoxFuture<'_, QueryResult<()>> {
+        let sql = format!("ROLLBACK TO SAVEPOINT {}", name);
+        Box::pin(async move { self.execute_raw(&sql, Vec::new()).await.map(|_| ()) })
+    }
+
+    /// Release a savepoint, folding its changes into the transaction.
+    fn release_savepoint(&self, name: &str) -> BoxFuture<'_, QueryResult<()>> {
+        let sql = format!("RELEASE SAVEPOINT {}", name);
+        Box::pin(async move { self.execute_raw(&sql, Vec::new()).await.map(|_| ()) })
+    }
+}
+
+/// Run `f` in a transaction on `engine`.
+///
+/// `f` receives an engine bound to the transaction. The transaction commits
+/// when `f` succeeds and rolls back when it fails or exceeds the configured
+/// timeout.
+///
+/// If `engine` is already bound to a transaction, `f` runs in a savepoint of
+/// it instead, as with [`run_savepoint`]: a failure rolls back only `f`'s
+/// work. The outer transaction's isolation level and access mode apply.
+pub async fn run_transaction<E, F, Fut, T>(
+    engine: &E,
+    config: &TransactionConfig,
+    f: F,
+) -> QueryResult<T>
+where
+    E: TransactionalEngine,
+    F: FnOnce(E) -> Fut,
+    Fut: Future<Output = QueryResult<T>>,
+{
+    if engine.in_transaction() {
+        return run_savepoint(engine, &nested_savepoint_name(), f).await;
+    }
+
+    let tx = engine.begin(config).await?;
+    let work = f(tx.clone());
+    let result = match config.timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, work).await {
+            Ok(result) => result,
+            Err(_) => Err(QueryError::timeout(timeout.as_millis() as u64)),
+        },
+        None => work.await,
+    };
+
+    match result {
+        Ok(value) => {
+            tx.commit().await?;
+            debug!("Transaction COMMIT");
+            Ok(value)
+        }
+        Err(err) => {
+            if let Err(rollback) = tx.rollback().await {
+                debug!(error = %rollback, "Transaction ROLLBACK failed");
+            }
+            debug!("Transaction ROLLBACK");
+            Err(err)
+        }
+    }
+}
+
+/// Run `f` inside a savepoint of the transaction `engine` is bound to.
+///
+/// A failure rolls back to the savepoint and is returned, leaving the outer
+/// transaction usable; success releases the savepoint.
+pub async fn run_savepoint<E, F, Fut, T>(engine: &E, name: &str, f: F) -> QueryResult<T>
+where
+    E: TransactionalEngine,
+    F: FnOnce(E) -> Fut,
+    Fut: Future<Output = QueryResult<T>>,
+{
+    validate_savepoint_name(name)?;
+    engine.savepoint(name).await?;
+
+    match f(engine.clone()).await {
+        Ok(value) => {
+            engine.release_savepoint(name).await?;
+            Ok(value)
+        }
+        Err(err) => {
+            if let Err(rollback) = engine.rollback_to_savepoint(name).await {
+                debug!(savepoint = %name, error = %rollback, "ROLLBACK TO SAVEPOINT failed");
+            }
+            Err(err)
+        }
+    }
+}
+
+/// A unique name for the savepoint of a nested transaction.
+///
+/// MySQL replaces a savepoint when another of the same name is created, so
+/// nested transactions can't share one.
+fn nested_savepoint_name() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    format!("prax_tx_{}", NEXT.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Check that a savepoint name is a plain identifier, as it is spliced into SQL.
+fn validate_savepoint_name(name: &str) -> QueryResult<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(QueryError::invalid_input(
+            "savepoint",
+            format!("`{}` is not a valid savepoint name", name),
+        ))
+    }
+}
+
+impl<E: TransactionalEngine> Transaction<E> {
+    /// Create a savepoint in this transaction.
+    ///
+    /// Roll back to the returned [`Savepoint`] to undo the work done since,
+    /// or release it to keep that work.
+    pub async fn savepoint(&self, name: &str) -> QueryResult<Savepoint<E>> {
+        validate_savepoint_name(name)?;
+        self.engine.savepoint(name).await?;
+        Ok(Savepoint {
+            engine: self.engine.clone(),
+            name: name.to_string(),
+        })
+    }
+
+    /// Run `f` inside a savepoint of this transaction.
+    ///
+    /// See [`run_savepoint`].
+    pub async fn with_savepoint<F, Fut, T>(&self, name: &str, f: F) -> QueryResult<T>
+    where
+        F: FnOnce(Transaction<E>) -> Fut,
+        Fut: Future<Output = QueryResult<T>>,
+    {
+        let config = self.config.clone();
+        run_savepoint(&self.engine, name, move |engine| {
+            f(Transaction::new(engine, config))
+        })
+        .await
+    }
+}
+
+/// A savepoint in a transaction, created by [`Transaction::savepoint`].
+///
+/// A savepoint that is neither rolled back to nor released lasts until the
+/// transaction ends.
+#[must_use = "a savepoint does nothing unless rolled back to or released"]
+pub struct Savepoint<E> {
+    engine: E,
+    name: String,
+}
+
+impl<E: TransactionalEngine> Savepoint<E> {
+    /// Get the savepoint's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Undo the work done since the savepoint was created.
+    ///
+    /// The savepoint remains, so it can be rolled back to again.
+    pub async fn rollback_to(&self) -> QueryResult<()> {
+        debug!(savepoint = %self.name, "ROLLBACK TO SAVEPOINT");
+        self.engine.rollback_to_savepoint(&self.name).await
+    }
+
+    /// Release the savepoint, keeping the work done since it was created.
+    pub async fn release(self) -> QueryResult<()> {
+        debug!(savepoint = %self.name, "RELEASE SAVEPOINT");
+        self.engine.release_savepoint(&self.name).await
+    }
+}
+
 /// Interactive transaction for step-by-step operations.
 pub struct InteractiveTransaction<E> {
     inner: Transaction<E>,
@@ -463,4 +721,239 @@ mod tests {
         let release = tx.release_savepoint_sql("test_sp");
         assert_eq!(release, "RELEASE SAVEPOINT test_sp");
     }
+
+    mod engine {
+        use std::sync::{Arc, Mutex};
+
+        use crate::error::{QueryError, QueryResult};
+        use crate::filter::FilterValue;
+        use crate::traits::{BoxFuture, Model, QueryEngine};
+        use crate::transaction::{TransactionConfig, TransactionalEngine};
+
+        /// Engine recording every statement it runs.
+        #[derive(Clone, Default)]
+        pub struct RecordingEngine {
+            pub log: Arc<Mutex<Vec<String>>>,
+            pub in_transaction: bool,
+        }
+
+        impl RecordingEngine {
+            pub fn statements(&self) -> Vec<String> {
+                self.log.lock().unwrap().clone()
+            }
+
+            fn record(&self, sql: &str) {
+                self.log.lock().unwrap().push(sql.to_string());
+            }
+        }
+
+        impl QueryEngine for RecordingEngine {
+            fn query_many<T: Model + Send + 'static>(
+                &self,
+                _sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<Vec<T>>> {
+                Box::pin(async { Ok(Vec::new()) })
+            }
+
+            fn query_one<T: Model + Send + 'static>(
+                &self,
+                _sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<T>> {
+                Box::pin(async { Err(QueryError::not_found("test")) })
+            }
+
+            fn query_optional<T: Model + Send + 'static>(
+                &self,
+                _sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<Option<T>>> {
+                Box::pin(async { Ok(None) })
+            }
+
+            fn execute_insert<T: Model + Send + 'static>(
+                &self,
+                _sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<T>> {
+                Box::pin(async { Err(QueryError::not_found("test")) })
+            }
+
+            fn execute_update<T: Model + Send + 'static>(
+                &self,
+                _sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<Vec<T>>> {
+                Box::pin(async { Ok(Vec::new()) })
+            }
+
+            fn execute_delete(
+                &self,
+                _sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<u64>> {
+                Box::pin(async { Ok(0) })
+            }
+
+            fn execute_raw(
+                &self,
+                sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<u64>> {
+                self.record(sql);
+                Box::pin(async { Ok(0) })
+            }
+
+            fn count(
+                &self,
+                _sql: &str,
+                _params: Vec<FilterValue>,
+            ) -> BoxFuture<'_, QueryResult<u64>> {
+                Box::pin(async { Ok(0) })
+            }
+        }
+
+        impl TransactionalEngine for RecordingEngine {
+            fn begin(&self, config: &TransactionConfig) -> BoxFuture<'_, QueryResult<Self>> {
+                self.record(&config.to_begin_sql());
+                let tx = Self {
+                    log: self.log.clone(),
+                    in_transaction: true,
+                };
+                Box::pin(async move { Ok(tx) })
+            }
+
+            fn commit(&self) -> BoxFuture<'_, QueryResult<()>> {
+                self.record("COMMIT");
+                Box::pin(async { Ok(()) })
+            }
+
+            fn rollback(&self) -> BoxFuture<'_, QueryResult<()>> {
+                self.record("ROLLBACK");
+                Box::pin(async { Ok(()) })
+            }
+
+            fn in_transaction(&self) -> bool {
+                self.in_transaction
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transaction_commits_on_success() {
+        let engine = engine::RecordingEngine::default();
+
+        let value = TransactionBuilder::new(engine.clone(), |tx: Transaction<_>| async move {
+            tx.engine().execute_raw("INSERT 1", Vec::new()).await?;
+            Ok(7)
+        })
+        .isolation(IsolationLevel::Serializable)
+        .await
+        .unwrap();
+
+        assert_eq!(value, 7);
+        assert_eq!(
+            engine.statements(),
+            vec![
+                "BEGIN ISOLATION LEVEL SERIALIZABLE READ WRITE",
+                "INSERT 1",
+                "COMMIT"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rolls_back_on_error() {
+        let engine = engine::RecordingEngine::default();
+
+        let result: QueryResult<()> = run_transaction(
+            &engine,
+            &TransactionConfig::new(),
+            |tx: engine::RecordingEngine| async move {
+                tx.execute_raw("INSERT 1", Vec::new()).await?;
+                Err(QueryError::internal("boom"))
+            },
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(engine.statements().last().unwrap(), "ROLLBACK");
+    }
+
+    #[tokio::test]
+    async fn test_transaction_savepoints() {
+        let engine = engine::RecordingEngine::default();
+
+        TransactionBuilder::new(engine.clone(), |tx: Transaction<_>| async move {
+            let failed: QueryResult<()> = tx
+                .with_savepoint("sp1", |sp| async move {
+                    sp.engine().execute_raw("INSERT 2", Vec::new()).await?;
+                    Err(QueryError::internal("boom"))
+                })
+                .await;
+            assert!(failed.is_err());
+
+            tx.with_savepoint("sp2", |sp| async move {
+                sp.engine().execute_raw("INSERT 3", Vec::new()).await?;
+                Ok(())
+            })
+            .await?;
+
+            let savepoint = tx.savepoint("sp3").await?;
+            tx.engine().execute_raw("INSERT 4", Vec::new()).await?;
+            savepoint.rollback_to().await?;
+            savepoint.release().await
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            &engine.statements()[1..],
+            [
+                "SAVEPOINT sp1",
+                "INSERT 2",
+                "ROLLBACK TO SAVEPOINT sp1",
+                "SAVEPOINT sp2",
+                "INSERT 3",
+                "RELEASE SAVEPOINT sp2",
+                "SAVEPOINT sp3",
+                "INSERT 4",
+                "ROLLBACK TO SAVEPOINT sp3",
+                "RELEASE SAVEPOINT sp3",
+                "COMMIT"
+            ]
+        );
+        assert!(validate_savepoint_name("sp; DROP TABLE users").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nested_transaction_uses_savepoint() {
+        let engine = engine::RecordingEngine::default();
+
+        run_transaction(&engine, &TransactionConfig::new(), |tx| async move {
+            let nested: QueryResult<()> =
+                run_transaction(&tx, &TransactionConfig::new(), |inner| async move {
+                    inner.execute_raw("INSERT 1", Vec::new()).await?;
+                    Err(QueryError::internal("boom"))
+                })
+                .await;
+            assert!(nested.is_err());
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        let statements = engine.statements();
+        let savepoint = statements[1].strip_prefix("SAVEPOINT ").unwrap();
+        assert!(savepoint.starts_with("prax_tx_"));
+        assert_eq!(
+            &statements[2..],
+            [
+                "INSERT 1".to_string(),
+                format!("ROLLBACK TO SAVEPOINT {}", savepoint),
+                "COMMIT".to_string()
+            ]
+        );
+    }
 }
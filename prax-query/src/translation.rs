@@ -0,0 +1,405 @@
+//! Translatable fields.
+//!
+//! String fields marked `@translatable` keep their per-locale values in a
+//! translation table keyed by the record's primary key and a locale:
+//!
+//! ```prax
+//! model Post {
+//!     id    Int     @id @auto
+//!     title String  @translatable
+//!     body  String? @translatable
+//! }
+//! ```
+//!
+//! The model's own columns hold the default-locale values, and the
+//! `PostTranslation` table holds one row per record and locale. Generated
+//! models load a set of locales in one query and read values through
+//! fallback chains, ending at the model's own value:
+//!
+//! ```rust,ignore
+//! Post::load_translations(&db, &mut posts, &["de-AT", "fr"]).await?;
+//!
+//! // de-AT, then de, then the untranslated title
+//! let title = posts[0].title_in("de-AT");
+//!
+//! post.save_translation(&db, &PostTranslation {
+//!     locale: "de".into(),
+//!     title: Some("Hallo Welt".into()),
+//!     body: None,
+//! })
+//! .await?;
+//! ```
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::error::QueryResult;
+use crate::filter::FilterValue;
+use crate::raw::RawDatabase;
+use crate::sql::{DatabaseType, quote_identifier};
+use crate::versioning::json_to_filter_value;
+
+/// Translation table column holding the locale.
+pub const LOCALE_COLUMN: &str = "locale";
+
+/// Get the locales tried for `locale`, most specific first.
+///
+/// Each subtag is dropped in turn, so `de-AT` falls back to `de` and
+/// `zh_Hant_TW` to `zh_Hant`, then `zh`.
+pub fn fallback_chain(locale: &str) -> Vec<&str> {
+    let mut chain = vec![locale];
+    let mut rest = locale;
+    while let Some(end) = rest.rfind(['-', '_']) {
+        rest = &rest[..end];
+        chain.push(rest);
+    }
+    chain
+}
+
+/// The translated values of one record, by locale and column.
+///
+/// Missing and `NULL` translations are not stored, so lookups fall through
+/// to the next locale.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranslationSet {
+    values: HashMap<String, HashMap<String, String>>,
+}
+
+impl TranslationSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the value of a column in a locale.
+    pub fn insert(
+        &mut self,
+        locale: impl Into<String>,
+        column: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        self.values
+            .entry(locale.into())
+            .or_default()
+            .insert(column.into(), value.into());
+    }
+
+    /// Get the value of a column in exactly `locale`.
+    pub fn get(&self, locale: &str, column: &str) -> Option<&str> {
+        self.values.get(locale)?.get(column).map(String::as_str)
+    }
+
+    /// Get the value of a column in the first of `locales` that has one,
+    /// following each locale's [`fallback_chain`].
+    pub fn resolve(&self, column: &str, locales: &[&str]) -> Option<&str> {
+        locales
+            .iter()
+            .flat_map(|locale| fallback_chain(locale))
+            .find_map(|locale| self.get(locale, column))
+    }
+
+    /// Get the locales with at least one translated value.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Check if no translations are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// The translation table of a model with `@translatable` fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Translations {
+    table: &'static str,
+    key: &'static str,
+    columns: &'static [&'static str],
+}
+
+impl Translations {
+    /// Create a translation table keyed by the model's primary key column,
+    /// holding the given translatable columns.
+    pub const fn new(
+        table: &'static str,
+        key: &'static str,
+        columns: &'static [&'static str],
+    ) -> Self {
+        Self {
+            table,
+            key,
+            columns,
+        }
+    }
+
+    /// Get the translation table name.
+    pub fn table(&self) -> &'static str {
+        self.table
+    }
+
+    /// Get the key column, shared with the model's table.
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    /// Get the translatable columns.
+    pub fn columns(&self) -> &'static [&'static str] {
+        self.columns
+    }
+
+    /// Read a record's key from its serialized columns.
+    pub fn key_of(&self, record: &impl Serialize) -> FilterValue {
+        json_to_filter_value(self.key_json(record))
+    }
+
+    /// Build the query for the translations of `keys` records in `locales`
+    /// locales, taking the keys and then the locales.
+    pub fn load_sql(&self, db_type: DatabaseType, keys: usize, locales: usize) -> String {
+        let placeholders = |from: usize, count: usize| -> String {
+            (from..from + count)
+                .map(|i| db_type.placeholder_string(i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut columns = vec![quote_identifier(self.key), quote_identifier(LOCALE_COLUMN)];
+        columns.extend(self.columns.iter().map(|c| quote_identifier(c)));
+
+        format!(
+            "SELECT {} FROM {} WHERE {} IN ({}) AND {} IN ({})",
+            columns.join(", "),
+            quote_identifier(self.table),
+            quote_identifier(self.key),
+            placeholders(1, keys),
+            quote_identifier(LOCALE_COLUMN),
+            placeholders(keys + 1, locales)
+        )
+    }
+
+    /// Load the translations of `records` in `locales` and their fallbacks,
+    /// returning one set per record, in order.
+    pub async fn load<D, T>(
+        &self,
+        db: &D,
+        records: &[T],
+        locales: &[&str],
+    ) -> QueryResult<Vec<TranslationSet>>
+    where
+        D: RawDatabase + ?Sized,
+        T: Serialize,
+    {
+        let mut wanted: Vec<&str> = Vec::new();
+        for locale in locales.iter().flat_map(|l| fallback_chain(l)) {
+            if !wanted.contains(&locale) {
+                wanted.push(locale);
+            }
+        }
+        if records.is_empty() || wanted.is_empty() {
+            return Ok(vec![TranslationSet::new(); records.len()]);
+        }
+
+        let keys: Vec<JsonValue> = records.iter().map(|r| self.key_json(r)).collect();
+        let mut params: Vec<FilterValue> = keys.iter().cloned().map(json_to_filter_value).collect();
+        params.extend(wanted.iter().map(|l| FilterValue::String(l.to_string())));
+
+        let sql = self.load_sql(db.database_type(), keys.len(), wanted.len());
+        let rows = db
+            .query(&sql, params)
+            .await
+            .map_err(|e| e.with_context(format!("loading translations from `{}`", self.table)))?;
+
+        let mut by_key: HashMap<String, TranslationSet> = HashMap::new();
+        for row in rows {
+            let Some(locale) = row.get(LOCALE_COLUMN).and_then(JsonValue::as_str) else {
+                continue;
+            };
+            let key = row.get(self.key).cloned().unwrap_or_default().to_string();
+            let set = by_key.entry(key).or_default();
+            for column in self.columns {
+                if let Some(value) = row.get(*column).and_then(JsonValue::as_str) {
+                    set.insert(locale, *column, value);
+                }
+            }
+        }
+
+        Ok(keys
+            .iter()
+            .map(|key| by_key.get(&key.to_string()).cloned().unwrap_or_default())
+            .collect())
+    }
+
+    /// Build the statements replacing a record's translation in one locale.
+    ///
+    /// Columns not in `values` are stored as `NULL`.
+    pub fn save_sql(
+        &self,
+        db_type: DatabaseType,
+        key: FilterValue,
+        locale: &str,
+        values: &[(&str, Option<String>)],
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        let mut statements = self.remove_sql(db_type, key.clone(), locale);
+
+        let mut columns = vec![quote_identifier(self.key), quote_identifier(LOCALE_COLUMN)];
+        let mut params = vec![key, FilterValue::String(locale.to_string())];
+        for (column, value) in values {
+            if self.columns.contains(column) {
+                columns.push(quote_identifier(column));
+                params.push(value.clone().map_or(FilterValue::Null, FilterValue::String));
+            }
+        }
+        let placeholders: Vec<String> = (1..=params.len())
+            .map(|i| db_type.placeholder_string(i))
+            .collect();
+
+        statements.push((
+            format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                quote_identifier(self.table),
+                columns.join(", "),
+                placeholders.join(", ")
+            ),
+            params,
+        ));
+        statements
+    }
+
+    /// Replace a record's translation in one locale.
+    pub async fn save<D>(
+        &self,
+        db: &D,
+        key: FilterValue,
+        locale: &str,
+        values: &[(&str, Option<String>)],
+    ) -> QueryResult<()>
+    where
+        D: RawDatabase + ?Sized,
+    {
+        let statements = self.save_sql(db.database_type(), key, locale, values);
+        db.execute_in_transaction(statements)
+            .await
+            .map_err(|e| e.with_context(format!("saving a `{}` translation", self.table)))?;
+        Ok(())
+    }
+
+    /// Build the statement deleting a record's translation in one locale.
+    pub fn remove_sql(
+        &self,
+        db_type: DatabaseType,
+        key: FilterValue,
+        locale: &str,
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        vec![(
+            format!(
+                "DELETE FROM {} WHERE {} = {} AND {} = {}",
+                quote_identifier(self.table),
+                quote_identifier(self.key),
+                db_type.placeholder(1),
+                quote_identifier(LOCALE_COLUMN),
+                db_type.placeholder(2)
+            ),
+            vec![key, FilterValue::String(locale.to_string())],
+        )]
+    }
+
+    /// Delete a record's translation in one locale.
+    pub async fn remove<D>(&self, db: &D, key: FilterValue, locale: &str) -> QueryResult<()>
+    where
+        D: RawDatabase + ?Sized,
+    {
+        let statements = self.remove_sql(db.database_type(), key, locale);
+        db.execute_in_transaction(statements)
+            .await
+            .map_err(|e| e.with_context(format!("removing a `{}` translation", self.table)))?;
+        Ok(())
+    }
+
+    fn key_json(&self, record: &impl Serialize) -> JsonValue {
+        let row = serde_json::to_value(record).unwrap_or_default();
+        row.get(self.key).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::testing::FakeDatabase;
+    use serde_json::json;
+
+    const POSTS: Translations = Translations::new("PostTranslation", "id", &["title", "body"]);
+
+    #[test]
+    fn test_fallback_chain() {
+        assert_eq!(fallback_chain("de-AT"), vec!["de-AT", "de"]);
+        assert_eq!(
+            fallback_chain("zh_Hant_TW"),
+            vec!["zh_Hant_TW", "zh_Hant", "zh"]
+        );
+        assert_eq!(fallback_chain("fr"), vec!["fr"]);
+    }
+
+    #[test]
+    fn test_translation_set_resolve() {
+        let mut set = TranslationSet::new();
+        set.insert("de", "title", "Hallo");
+        set.insert("fr", "title", "Bonjour");
+        set.insert("fr", "body", "Texte");
+
+        assert_eq!(set.resolve("title", &["de-AT"]), Some("Hallo"));
+        assert_eq!(set.resolve("body", &["de-AT", "fr"]), Some("Texte"));
+        assert_eq!(set.resolve("body", &["de"]), None);
+        assert_eq!(set.get("de-AT", "title"), None);
+    }
+
+    #[test]
+    fn test_save_sql() {
+        let statements = POSTS.save_sql(
+            DatabaseType::PostgreSQL,
+            FilterValue::Int(7),
+            "de",
+            &[("title", Some("Hallo".into())), ("slug", None)],
+        );
+
+        assert_eq!(
+            statements[0].0,
+            "DELETE FROM PostTranslation WHERE id = $1 AND locale = $2"
+        );
+        assert_eq!(
+            statements[1].0,
+            "INSERT INTO PostTranslation (id, locale, title) VALUES ($1, $2, $3)"
+        );
+        assert_eq!(
+            statements[1].1,
+            vec![
+                FilterValue::Int(7),
+                FilterValue::String("de".into()),
+                FilterValue::String("Hallo".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_translations() {
+        let db = FakeDatabase::new().with_rows(vec![
+            json!({"id": 1, "locale": "de", "title": "Hallo", "body": null}),
+            json!({"id": 2, "locale": "de-AT", "title": "Servus", "body": "Text"}),
+        ]);
+        let posts = vec![json!({"id": 1}), json!({"id": 2}), json!({"id": 3})];
+
+        let sets = POSTS.load(&db, &posts, &["de-AT"]).await.unwrap();
+        assert_eq!(sets.len(), 3);
+        assert_eq!(sets[0].resolve("title", &["de-AT"]), Some("Hallo"));
+        assert_eq!(sets[0].resolve("body", &["de-AT"]), None);
+        assert_eq!(sets[1].resolve("title", &["de-AT"]), Some("Servus"));
+        assert!(sets[2].is_empty());
+
+        let (sql, params) = db.queries().remove(0);
+        assert_eq!(
+            sql,
+            "SELECT id, locale, title, body FROM PostTranslation WHERE id IN ($1, $2, $3) AND locale IN ($4, $5)"
+        );
+        assert_eq!(params[3], FilterValue::String("de-AT".into()));
+        assert_eq!(params[4], FilterValue::String("de".into()));
+    }
+}
@@ -0,0 +1,726 @@
+//! Hierarchical models.
+//!
+//! A model marked `@@tree` stores a hierarchy through a self-referencing
+//! parent field, plus an auxiliary structure that makes subtree queries cheap:
+//!
+//! ```prax
+//! model Category {
+//!     id       Int        @id @auto
+//!     name     String
+//!     parentId Int?
+//!     parent   Category?  @relation("CategoryTree", fields: [parentId], references: [id])
+//!     children Category[] @relation("CategoryTree")
+//!
+//!     @@tree(strategy: closure_table, parent: parentId)
+//! }
+//! ```
+//!
+//! - `closure_table` keeps a `<table>_closure` table with a row for every
+//!   ancestor/descendant pair and its depth.
+//! - `materialized_path` keeps a `path` column holding the keys of a node's
+//!   ancestors, such as `/1/4/`.
+//!
+//! Migrations create the auxiliary structure and triggers that maintain it as
+//! nodes are inserted; deleting a node removes its closure rows. Nodes change
+//! parent through [`Tree::move_subtree`], which moves the whole subtree in one
+//! transaction and rejects moves that would create a cycle. Generated models
+//! wrap the queries:
+//!
+//! ```rust,ignore
+//! let children = category.children(&db).await?;
+//! let descendants = category.descendants(&db).await?;
+//! // Root first
+//! let breadcrumbs = category.ancestors(&db).await?;
+//!
+//! category.move_subtree(&db, Some(&new_parent)).await?;
+//! ```
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+
+use crate::error::{ErrorCode, QueryError, QueryResult};
+use crate::filter::FilterValue;
+use crate::raw::RawDatabase;
+use crate::sql::{DatabaseType, quote_identifier};
+use crate::versioning::json_to_filter_value;
+
+/// Closure table column holding the ancestor's key.
+pub const ANCESTOR_COLUMN: &str = "ancestor";
+/// Closure table column holding the descendant's key.
+pub const DESCENDANT_COLUMN: &str = "descendant";
+/// Closure table column holding the distance between the two nodes.
+pub const DEPTH_COLUMN: &str = "depth";
+/// Default materialized path column.
+pub const DEFAULT_PATH_COLUMN: &str = "path";
+
+/// How a tree stores the hierarchy beyond the parent column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeStorage {
+    /// A closure table with the given name.
+    ClosureTable(&'static str),
+    /// A materialized path column with the given name.
+    MaterializedPath(&'static str),
+}
+
+/// The hierarchy of a `@@tree` model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tree {
+    table: &'static str,
+    key: &'static str,
+    parent: &'static str,
+    storage: TreeStorage,
+}
+
+impl Tree {
+    /// Create a tree stored in a closure table.
+    pub const fn closure_table(
+        table: &'static str,
+        key: &'static str,
+        parent: &'static str,
+        closure_table: &'static str,
+    ) -> Self {
+        Self {
+            table,
+            key,
+            parent,
+            storage: TreeStorage::ClosureTable(closure_table),
+        }
+    }
+
+    /// Create a tree stored in a materialized path column.
+    pub const fn materialized_path(
+        table: &'static str,
+        key: &'static str,
+        parent: &'static str,
+        path_column: &'static str,
+    ) -> Self {
+        Self {
+            table,
+            key,
+            parent,
+            storage: TreeStorage::MaterializedPath(path_column),
+        }
+    }
+
+    /// Get the table name.
+    pub fn table(&self) -> &'static str {
+        self.table
+    }
+
+    /// Get the primary key column.
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    /// Get the parent column.
+    pub fn parent(&self) -> &'static str {
+        self.parent
+    }
+
+    /// Get the auxiliary storage.
+    pub fn storage(&self) -> TreeStorage {
+        self.storage
+    }
+
+    /// Read a node's key from its serialized columns.
+    pub fn key_of(&self, node: &impl Serialize) -> FilterValue {
+        let row = serde_json::to_value(node).unwrap_or_default();
+        json_to_filter_value(row.get(self.key).cloned().unwrap_or_default())
+    }
+
+    /// Build the query for a node's direct children, taking the node's key.
+    pub fn children_sql(&self, db_type: DatabaseType) -> String {
+        format!(
+            "SELECT * FROM {} WHERE {} = {} ORDER BY {}",
+            quote_identifier(self.table),
+            quote_identifier(self.parent),
+            db_type.placeholder(1),
+            quote_identifier(self.key)
+        )
+    }
+
+    /// Build the query for all of a node's descendants, nearest first,
+    /// taking the node's key.
+    pub fn descendants_sql(&self, db_type: DatabaseType) -> String {
+        let table = quote_identifier(self.table);
+        let key = quote_identifier(self.key);
+        match self.storage {
+            TreeStorage::ClosureTable(closure) => format!(
+                "SELECT n.* FROM {} n JOIN {} c ON c.{} = n.{} \
+                 WHERE c.{} = {} AND c.{} > 0 ORDER BY c.{}, n.{}",
+                table,
+                quote_identifier(closure),
+                quote_identifier(DESCENDANT_COLUMN),
+                key,
+                quote_identifier(ANCESTOR_COLUMN),
+                db_type.placeholder(1),
+                quote_identifier(DEPTH_COLUMN),
+                quote_identifier(DEPTH_COLUMN),
+                key
+            ),
+            TreeStorage::MaterializedPath(path_column) => {
+                let path = quote_identifier(path_column);
+                let prefix = self.prefix(db_type, "n", path_column);
+                format!(
+                    "SELECT d.* FROM {} d JOIN {} n ON {} = {} \
+                     WHERE n.{} = {} ORDER BY {}, d.{}",
+                    table,
+                    table,
+                    substr(
+                        db_type,
+                        &format!("d.{}", path),
+                        "1",
+                        &length(db_type, &prefix)
+                    ),
+                    prefix,
+                    key,
+                    db_type.placeholder(1),
+                    length(db_type, &format!("d.{}", path)),
+                    key
+                )
+            }
+        }
+    }
+
+    /// Build the query for a node's ancestors, root first, taking the node's key.
+    pub fn ancestors_sql(&self, db_type: DatabaseType) -> String {
+        let table = quote_identifier(self.table);
+        let key = quote_identifier(self.key);
+        match self.storage {
+            TreeStorage::ClosureTable(closure) => format!(
+                "SELECT n.* FROM {} n JOIN {} c ON c.{} = n.{} \
+                 WHERE c.{} = {} AND c.{} > 0 ORDER BY c.{} DESC",
+                table,
+                quote_identifier(closure),
+                quote_identifier(ANCESTOR_COLUMN),
+                key,
+                quote_identifier(DESCENDANT_COLUMN),
+                db_type.placeholder(1),
+                quote_identifier(DEPTH_COLUMN),
+                quote_identifier(DEPTH_COLUMN)
+            ),
+            TreeStorage::MaterializedPath(path_column) => {
+                let path = quote_identifier(path_column);
+                let prefix = self.prefix(db_type, "a", path_column);
+                format!(
+                    "SELECT a.* FROM {} a JOIN {} n ON {} = {} \
+                     WHERE n.{} = {} ORDER BY {}",
+                    table,
+                    table,
+                    substr(
+                        db_type,
+                        &format!("n.{}", path),
+                        "1",
+                        &length(db_type, &prefix)
+                    ),
+                    prefix,
+                    key,
+                    db_type.placeholder(1),
+                    length(db_type, &format!("a.{}", path))
+                )
+            }
+        }
+    }
+
+    /// Fetch a node's direct children.
+    pub async fn children<D, T>(&self, db: &D, node: &impl Serialize) -> QueryResult<Vec<T>>
+    where
+        D: RawDatabase + ?Sized,
+        T: DeserializeOwned,
+    {
+        let sql = self.children_sql(db.database_type());
+        self.fetch(db, &sql, self.key_of(node)).await
+    }
+
+    /// Fetch all of a node's descendants, nearest first.
+    pub async fn descendants<D, T>(&self, db: &D, node: &impl Serialize) -> QueryResult<Vec<T>>
+    where
+        D: RawDatabase + ?Sized,
+        T: DeserializeOwned,
+    {
+        let sql = self.descendants_sql(db.database_type());
+        self.fetch(db, &sql, self.key_of(node)).await
+    }
+
+    /// Fetch a node's ancestors, root first.
+    pub async fn ancestors<D, T>(&self, db: &D, node: &impl Serialize) -> QueryResult<Vec<T>>
+    where
+        D: RawDatabase + ?Sized,
+        T: DeserializeOwned,
+    {
+        let sql = self.ancestors_sql(db.database_type());
+        self.fetch(db, &sql, self.key_of(node)).await
+    }
+
+    /// Move a node and its subtree under a new parent, or make it a root.
+    ///
+    /// The parent column and the auxiliary structure are updated in one
+    /// transaction. Fails without changes if the new parent is the node
+    /// itself or one of its descendants.
+    pub async fn move_subtree<D>(
+        &self,
+        db: &D,
+        node: FilterValue,
+        new_parent: Option<FilterValue>,
+    ) -> QueryResult<()>
+    where
+        D: RawDatabase + ?Sized,
+    {
+        let db_type = db.database_type();
+        let statements = match self.storage {
+            TreeStorage::ClosureTable(closure) => {
+                if let Some(parent) = &new_parent {
+                    let sql = format!(
+                        "SELECT 1 AS found FROM {} WHERE {} = {} AND {} = {}",
+                        quote_identifier(closure),
+                        quote_identifier(ANCESTOR_COLUMN),
+                        db_type.placeholder(1),
+                        quote_identifier(DESCENDANT_COLUMN),
+                        db_type.placeholder(2)
+                    );
+                    let rows = db.query(&sql, vec![node.clone(), parent.clone()]).await?;
+                    if !rows.is_empty() {
+                        return Err(self.cycle_error());
+                    }
+                }
+                self.closure_move(db_type, closure, node, new_parent)
+            }
+            TreeStorage::MaterializedPath(path) => {
+                self.path_move(db, path, node, new_parent).await?
+            }
+        };
+
+        let affected = db
+            .execute_in_transaction(statements)
+            .await
+            .map_err(|e| e.with_context(format!("moving a subtree of `{}`", self.table)))?;
+        if affected.first() == Some(&0) {
+            return Err(QueryError::not_found(self.table)
+                .with_help("The node was deleted or moved concurrently; reload it and retry"));
+        }
+        Ok(())
+    }
+
+    /// Statements re-linking a subtree in the closure table: pairs linking
+    /// the subtree to its old ancestors are removed, then every new ancestor
+    /// is linked to every node of the subtree.
+    fn closure_move(
+        &self,
+        db_type: DatabaseType,
+        closure: &str,
+        node: FilterValue,
+        new_parent: Option<FilterValue>,
+    ) -> Vec<(String, Vec<FilterValue>)> {
+        let closure = quote_identifier(closure);
+        let ancestor = quote_identifier(ANCESTOR_COLUMN);
+        let descendant = quote_identifier(DESCENDANT_COLUMN);
+        let depth = quote_identifier(DEPTH_COLUMN);
+        // Wrapped in a derived table, since MySQL rejects subqueries on the
+        // table being deleted from
+        let subtree = |index: usize| {
+            format!(
+                "SELECT {} FROM (SELECT {} FROM {} WHERE {} = {}) AS subtree",
+                descendant,
+                descendant,
+                closure,
+                ancestor,
+                db_type.placeholder(index)
+            )
+        };
+
+        let mut statements = vec![
+            (
+                format!(
+                    "UPDATE {} SET {} = {} WHERE {} = {}",
+                    quote_identifier(self.table),
+                    quote_identifier(self.parent),
+                    db_type.placeholder(1),
+                    quote_identifier(self.key),
+                    db_type.placeholder(2)
+                ),
+                vec![
+                    new_parent.clone().unwrap_or(FilterValue::Null),
+                    node.clone(),
+                ],
+            ),
+            (
+                format!(
+                    "DELETE FROM {} WHERE {} IN ({}) AND {} NOT IN ({})",
+                    closure,
+                    descendant,
+                    subtree(1),
+                    ancestor,
+                    subtree(2)
+                ),
+                vec![node.clone(), node.clone()],
+            ),
+        ];
+        if let Some(parent) = new_parent {
+            statements.push((
+                format!(
+                    "INSERT INTO {} ({}, {}, {}) \
+                     SELECT above.{}, below.{}, above.{} + below.{} + 1 \
+                     FROM {} above CROSS JOIN {} below \
+                     WHERE above.{} = {} AND below.{} = {}",
+                    closure,
+                    ancestor,
+                    descendant,
+                    depth,
+                    ancestor,
+                    descendant,
+                    depth,
+                    depth,
+                    closure,
+                    closure,
+                    descendant,
+                    db_type.placeholder(1),
+                    ancestor,
+                    db_type.placeholder(2)
+                ),
+                vec![parent, node],
+            ));
+        }
+        statements
+    }
+
+    /// Statements rewriting the paths of a subtree, after checking the move
+    /// against the current paths.
+    async fn path_move<D>(
+        &self,
+        db: &D,
+        path_column: &str,
+        node: FilterValue,
+        new_parent: Option<FilterValue>,
+    ) -> QueryResult<Vec<(String, Vec<FilterValue>)>>
+    where
+        D: RawDatabase + ?Sized,
+    {
+        let db_type = db.database_type();
+        let (old_path, old_prefix) = self.read_path(db, path_column, node.clone()).await?;
+        let new_path = match &new_parent {
+            Some(parent) => {
+                let (_, parent_prefix) = self.read_path(db, path_column, parent.clone()).await?;
+                if parent_prefix.starts_with(&old_prefix) {
+                    return Err(self.cycle_error());
+                }
+                parent_prefix
+            }
+            None => "/".to_string(),
+        };
+        let new_prefix = format!("{}{}", new_path, &old_prefix[old_path.len()..]);
+
+        let table = quote_identifier(self.table);
+        let path = quote_identifier(path_column);
+        let old_len = old_prefix.chars().count();
+        let suffix = substr(
+            db_type,
+            &path,
+            &(old_len + 1).to_string(),
+            &length(db_type, &path),
+        );
+
+        Ok(vec![
+            (
+                format!(
+                    "UPDATE {} SET {} = {}, {} = {} WHERE {} = {} AND {} = {}",
+                    table,
+                    quote_identifier(self.parent),
+                    db_type.placeholder(1),
+                    path,
+                    db_type.placeholder(2),
+                    quote_identifier(self.key),
+                    db_type.placeholder(3),
+                    path,
+                    db_type.placeholder(4)
+                ),
+                vec![
+                    new_parent.unwrap_or(FilterValue::Null),
+                    FilterValue::String(new_path),
+                    node,
+                    FilterValue::String(old_path),
+                ],
+            ),
+            (
+                format!(
+                    "UPDATE {} SET {} = {} WHERE {} = {}",
+                    table,
+                    path,
+                    concat(db_type, &[&db_type.placeholder_string(1), &suffix]),
+                    substr(db_type, &path, "1", &old_len.to_string()),
+                    db_type.placeholder(2)
+                ),
+                vec![
+                    FilterValue::String(new_prefix),
+                    FilterValue::String(old_prefix),
+                ],
+            ),
+        ])
+    }
+
+    /// Read a node's path and the prefix shared by its descendants' paths.
+    async fn read_path<D>(
+        &self,
+        db: &D,
+        path_column: &str,
+        node: FilterValue,
+    ) -> QueryResult<(String, String)>
+    where
+        D: RawDatabase + ?Sized,
+    {
+        let db_type = db.database_type();
+        let sql = format!(
+            "SELECT n.{} AS node_path, {} AS node_prefix FROM {} n WHERE n.{} = {}",
+            quote_identifier(path_column),
+            self.prefix(db_type, "n", path_column),
+            quote_identifier(self.table),
+            quote_identifier(self.key),
+            db_type.placeholder(1)
+        );
+        let rows = db.query(&sql, vec![node]).await?;
+        let row = rows
+            .into_iter()
+            .next()
+            .ok_or_else(|| QueryError::not_found(self.table))?;
+
+        let column = |name: &str| {
+            row.get(name)
+                .and_then(JsonValue::as_str)
+                .map(str::to_string)
+        };
+        match (column("node_path"), column("node_prefix")) {
+            (Some(path), Some(prefix)) => Ok((path, prefix)),
+            _ => Err(QueryError::deserialization(format!(
+                "tree path of `{}` is not a string",
+                self.table
+            ))),
+        }
+    }
+
+    /// The path prefix shared by the descendants of the node aliased `alias`.
+    fn prefix(&self, db_type: DatabaseType, alias: &str, path_column: &str) -> String {
+        let path = format!("{}.{}", alias, quote_identifier(path_column));
+        let key = as_text(
+            db_type,
+            &format!("{}.{}", alias, quote_identifier(self.key)),
+        );
+        concat(db_type, &[&path, &key, "'/'"])
+    }
+
+    async fn fetch<D, T>(&self, db: &D, sql: &str, key: FilterValue) -> QueryResult<Vec<T>>
+    where
+        D: RawDatabase + ?Sized,
+        T: DeserializeOwned,
+    {
+        let rows = db
+            .query(sql, vec![key])
+            .await
+            .map_err(|e| e.with_context(format!("reading the tree of `{}`", self.table)))?;
+
+        let mut nodes = Vec::with_capacity(rows.len());
+        for row in rows {
+            let node = serde_json::from_value(row).map_err(|e| {
+                QueryError::deserialization(format!("failed to read `{}` node: {}", self.table, e))
+            })?;
+            nodes.push(node);
+        }
+        Ok(nodes)
+    }
+
+    fn cycle_error(&self) -> QueryError {
+        QueryError::new(
+            ErrorCode::CheckConstraint,
+            format!(
+                "Cannot move a `{}` node under itself or one of its descendants",
+                self.table
+            ),
+        )
+        .with_model(self.table)
+    }
+}
+
+/// Concatenate string expressions.
+fn concat(db_type: DatabaseType, parts: &[&str]) -> String {
+    match db_type {
+        DatabaseType::MySQL => format!("CONCAT({})", parts.join(", ")),
+        DatabaseType::MSSQL => parts.join(" + "),
+        DatabaseType::PostgreSQL | DatabaseType::SQLite => parts.join(" || "),
+    }
+}
+
+/// Cast an expression to text.
+fn as_text(db_type: DatabaseType, expr: &str) -> String {
+    match db_type {
+        DatabaseType::MySQL => format!("CAST({} AS CHAR)", expr),
+        DatabaseType::MSSQL => format!("CAST({} AS NVARCHAR(255))", expr),
+        DatabaseType::PostgreSQL | DatabaseType::SQLite => format!("CAST({} AS TEXT)", expr),
+    }
+}
+
+/// The length of a string expression in characters.
+fn length(db_type: DatabaseType, expr: &str) -> String {
+    match db_type {
+        DatabaseType::MySQL => format!("CHAR_LENGTH({})", expr),
+        DatabaseType::MSSQL => format!("LEN({})", expr),
+        DatabaseType::PostgreSQL | DatabaseType::SQLite => format!("LENGTH({})", expr),
+    }
+}
+
+/// A substring of a string expression, starting at 1.
+fn substr(db_type: DatabaseType, expr: &str, start: &str, len: &str) -> String {
+    match db_type {
+        DatabaseType::MSSQL => format!("SUBSTRING({}, {}, {})", expr, start, len),
+        _ => format!("SUBSTR({}, {}, {})", expr, start, len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::testing::FakeDatabase;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    const CLOSURE: Tree =
+        Tree::closure_table("categories", "id", "parent_id", "categories_closure");
+    const PATH: Tree = Tree::materialized_path("categories", "id", "parent_id", "path");
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Category {
+        id: i64,
+        name: String,
+    }
+
+    #[test]
+    fn test_tree_query_sql() {
+        assert_eq!(
+            CLOSURE.children_sql(DatabaseType::PostgreSQL),
+            "SELECT * FROM categories WHERE parent_id = $1 ORDER BY id"
+        );
+        assert_eq!(
+            CLOSURE.descendants_sql(DatabaseType::PostgreSQL),
+            "SELECT n.* FROM categories n JOIN categories_closure c ON c.descendant = n.id \
+             WHERE c.ancestor = $1 AND c.depth > 0 ORDER BY c.depth, n.id"
+        );
+        assert_eq!(
+            CLOSURE.ancestors_sql(DatabaseType::SQLite),
+            "SELECT n.* FROM categories n JOIN categories_closure c ON c.ancestor = n.id \
+             WHERE c.descendant = ? AND c.depth > 0 ORDER BY c.depth DESC"
+        );
+        assert_eq!(
+            PATH.descendants_sql(DatabaseType::PostgreSQL),
+            "SELECT d.* FROM categories d JOIN categories n \
+             ON SUBSTR(d.path, 1, LENGTH(n.path || CAST(n.id AS TEXT) || '/')) \
+             = n.path || CAST(n.id AS TEXT) || '/' \
+             WHERE n.id = $1 ORDER BY LENGTH(d.path), d.id"
+        );
+        assert_eq!(
+            PATH.ancestors_sql(DatabaseType::MySQL),
+            "SELECT a.* FROM categories a JOIN categories n \
+             ON SUBSTR(n.path, 1, CHAR_LENGTH(CONCAT(a.path, CAST(a.id AS CHAR), '/'))) \
+             = CONCAT(a.path, CAST(a.id AS CHAR), '/') \
+             WHERE n.id = ? ORDER BY CHAR_LENGTH(a.path)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tree_fetch() {
+        let db = FakeDatabase::new().with_batches(vec![vec![
+            json!({"id": 2, "name": "Laptops", "parent_id": 1}),
+            json!({"id": 3, "name": "Phones", "parent_id": 1}),
+        ]]);
+        let root = Category {
+            id: 1,
+            name: "Electronics".into(),
+        };
+
+        let children: Vec<Category> = CLOSURE.children(&db, &root).await.unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[1].name, "Phones");
+        assert_eq!(db.queries()[0].1, vec![FilterValue::Int(1)]);
+    }
+
+    #[tokio::test]
+    async fn test_closure_move_subtree() {
+        let db = FakeDatabase::new().with_batches(vec![vec![]]);
+        CLOSURE
+            .move_subtree(&db, FilterValue::Int(4), Some(FilterValue::Int(2)))
+            .await
+            .unwrap();
+
+        let statements = db.transactions().remove(0);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(
+            statements[0].0,
+            "UPDATE categories SET parent_id = $1 WHERE id = $2"
+        );
+        assert_eq!(
+            statements[1].0,
+            "DELETE FROM categories_closure WHERE descendant IN \
+             (SELECT descendant FROM (SELECT descendant FROM categories_closure WHERE ancestor = $1) AS subtree) \
+             AND ancestor NOT IN \
+             (SELECT descendant FROM (SELECT descendant FROM categories_closure WHERE ancestor = $2) AS subtree)"
+        );
+        assert_eq!(
+            statements[2].0,
+            "INSERT INTO categories_closure (ancestor, descendant, depth) \
+             SELECT above.ancestor, below.descendant, above.depth + below.depth + 1 \
+             FROM categories_closure above CROSS JOIN categories_closure below \
+             WHERE above.descendant = $1 AND below.ancestor = $2"
+        );
+        assert_eq!(
+            statements[2].1,
+            vec![FilterValue::Int(2), FilterValue::Int(4)]
+        );
+
+        // The new parent is inside the subtree
+        let db = FakeDatabase::new().with_batches(vec![vec![json!({"found": 1})]]);
+        let err = CLOSURE
+            .move_subtree(&db, FilterValue::Int(4), Some(FilterValue::Int(5)))
+            .await
+            .unwrap_err();
+        assert!(err.is_constraint_violation());
+        assert!(db.transactions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_path_move_subtree() {
+        let db = FakeDatabase::new().with_batches(vec![
+            vec![json!({"node_path": "/1/", "node_prefix": "/1/4/"})],
+            vec![json!({"node_path": "/2/", "node_prefix": "/2/7/"})],
+        ]);
+        PATH.move_subtree(&db, FilterValue::Int(4), Some(FilterValue::Int(7)))
+            .await
+            .unwrap();
+
+        let statements = db.transactions().remove(0);
+        assert_eq!(
+            statements[0].0,
+            "UPDATE categories SET parent_id = $1, path = $2 WHERE id = $3 AND path = $4"
+        );
+        assert_eq!(statements[0].1[1], FilterValue::String("/2/7/".into()));
+        assert_eq!(
+            statements[1].0,
+            "UPDATE categories SET path = $1 || SUBSTR(path, 6, LENGTH(path)) \
+             WHERE SUBSTR(path, 1, 5) = $2"
+        );
+        assert_eq!(
+            statements[1].1,
+            vec![
+                FilterValue::String("/2/7/4/".into()),
+                FilterValue::String("/1/4/".into()),
+            ]
+        );
+
+        // Moving a node under its own descendant
+        let db = FakeDatabase::new().with_batches(vec![
+            vec![json!({"node_path": "/1/", "node_prefix": "/1/4/"})],
+            vec![json!({"node_path": "/1/4/", "node_prefix": "/1/4/9/"})],
+        ]);
+        let err = PATH
+            .move_subtree(&db, FilterValue::Int(4), Some(FilterValue::Int(9)))
+            .await
+            .unwrap_err();
+        assert!(err.is_constraint_violation());
+    }
+}
@@ -0,0 +1,282 @@
+//! Row expiry for databases without native TTL.
+//!
+//! Models declare the timestamp their rows expire at with `@@ttl`:
+//!
+//! ```prax
+//! model Session {
+//!     id        Int       @id @auto
+//!     token     String    @unique
+//!     expiresAt DateTime?
+//!
+//!     @@ttl(field: expiresAt)
+//! }
+//! ```
+//!
+//! Finds and counts on the model then skip rows whose expiry has passed, so
+//! expired rows disappear as soon as they expire; rows with no expiry never
+//! expire. Call `include_expired()` on the operation to see them anyway.
+//! Expired rows are deleted in the background by a
+//! [`RetentionRunner`](crate::retention::RetentionRunner) running the
+//! generated `session::ttl_policy()`:
+//!
+//! ```rust,ignore
+//! use prax_query::retention::RetentionRunner;
+//! use std::time::Duration;
+//!
+//! let purger = RetentionRunner::new(db)
+//!     .with_batch_size(1_000)
+//!     .spawn(vec![session::ttl_policy()], Duration::from_secs(300));
+//! ```
+//!
+//! Expiry is checked against the application's clock on reads and the
+//! database's clock when purging. The current time is bound as a string and
+//! cast to the column's timestamp type, which differs per database:
+//!
+//! | Database | Condition |
+//! |----------|-----------|
+//! | PostgreSQL | `expires_at > $1::timestamptz` |
+//! | MySQL | `expires_at > CAST(? AS DATETIME(6))` |
+//! | SQLite | `expires_at > ?` (compared as text) |
+//! | MSSQL | `expires_at > CAST(@P1 AS DATETIMEOFFSET)` |
+
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::filter::{FieldName, Filter, FilterValue};
+use crate::sql::DatabaseType;
+use crate::traits::Model;
+
+/// Filter for the rows whose expiry in `column` is unset or in the future,
+/// generated for `db_type`.
+pub fn not_expired(column: &'static str, db_type: DatabaseType) -> Filter {
+    Filter::or([
+        Filter::IsNull(column.into()),
+        ExpiryFilter::new(SystemTime::now(), db_type).into_filter(column),
+    ])
+}
+
+/// Add the [`not_expired`] condition to a read `filter` of a model with a
+/// TTL column.
+pub fn live_rows<M: Model>(
+    filter: &Filter,
+    include_expired: bool,
+    db_type: DatabaseType,
+) -> Cow<'_, Filter> {
+    match M::TTL_COLUMN {
+        Some(column) if !include_expired => {
+            Cow::Owned(filter.clone().and_then(not_expired(column, db_type)))
+        }
+        _ => Cow::Borrowed(filter),
+    }
+}
+
+/// Match rows of a TTL column expiring after `now`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpiryFilter {
+    /// The time rows must expire after.
+    pub now: SystemTime,
+    /// The database the SQL is generated for.
+    pub db_type: DatabaseType,
+}
+
+impl ExpiryFilter {
+    /// Match rows expiring after `now` on `db_type`.
+    pub fn new(now: SystemTime, db_type: DatabaseType) -> Self {
+        Self { now, db_type }
+    }
+
+    /// Apply the filter to `column`.
+    pub fn into_filter(self, column: impl Into<FieldName>) -> Filter {
+        Filter::Expiry(column.into(), Box::new(self))
+    }
+
+    /// Generate the condition on `column`, pushing its parameter.
+    pub(crate) fn to_sql_with_params(
+        &self,
+        column: &str,
+        param_idx: usize,
+        params: &mut Vec<FilterValue>,
+    ) -> String {
+        let now = utc_timestamp(self.now);
+        let (now, cast) = match self.db_type {
+            DatabaseType::PostgreSQL => (now, "{}::timestamptz"),
+            // DATETIME has no zone and rejects the `T` and `Z` of RFC 3339
+            DatabaseType::MySQL => (
+                now.replace('T', " ").replace('Z', ""),
+                "CAST({} AS DATETIME(6))",
+            ),
+            DatabaseType::SQLite => (now, "{}"),
+            DatabaseType::MSSQL => (now, "CAST({} AS DATETIMEOFFSET)"),
+        };
+        params.push(FilterValue::String(now));
+        let placeholder = format!("${}", param_idx + params.len());
+        format!("{} > {}", column, cast.replace("{}", &placeholder))
+    }
+}
+
+/// Format `time` as an RFC 3339 UTC timestamp with microseconds, the format
+/// `DateTime` fields are written in.
+pub(crate) fn utc_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format_utc(since_epoch.as_secs(), since_epoch.subsec_micros())
+}
+
+/// Parse an RFC 3339 timestamp such as `2024-02-29T10:30:00.5+01:00`, as
+/// databases return `DateTime` columns.
+pub(crate) fn parse_utc_timestamp(s: &str) -> Option<SystemTime> {
+    let s = s.trim();
+    let num = |range: std::ops::Range<usize>| -> Option<i64> { s.get(range)?.parse().ok() };
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+
+    let mut rest = &s[19..];
+    let mut micros = 0;
+    if let Some(frac) = rest.strip_prefix('.') {
+        let digits = frac.bytes().take_while(u8::is_ascii_digit).count();
+        let padded = format!("{:0<6}", &frac[..digits.min(6)]);
+        micros = padded.parse().ok()?;
+        rest = &frac[digits..];
+    }
+    let offset = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = if rest.starts_with('-') { -1 } else { 1 };
+            let rest = rest.get(1..)?;
+            let hours: i64 = rest.get(0..2)?.parse().ok()?;
+            let minutes: i64 = rest.get(3..5).map_or(Some(0), |m| m.parse().ok())?;
+            sign * (hours * 3_600 + minutes * 60)
+        }
+    };
+
+    // Days since 1970-01-01 from a civil date (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    let secs = u64::try_from(secs).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_micros(micros))
+}
+
+fn format_utc(secs: u64, micros: u32) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+        micros
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Session;
+
+    impl Model for Session {
+        const MODEL_NAME: &'static str = "Session";
+        const TABLE_NAME: &'static str = "sessions";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "expires_at"];
+        const TTL_COLUMN: Option<&'static str> = Some("expires_at");
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0, 0), "1970-01-01T00:00:00.000000Z");
+        assert_eq!(
+            format_utc(1_709_202_600, 500_000),
+            "2024-02-29T10:30:00.500000Z"
+        );
+        assert_eq!(format_utc(951_782_400, 0), "2000-02-29T00:00:00.000000Z");
+    }
+
+    #[test]
+    fn test_parse_utc_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_micros(1_709_202_600_500_000);
+        for s in [
+            "2024-02-29T10:30:00.500000Z",
+            "2024-02-29T10:30:00.5+00:00",
+            "2024-02-29 12:30:00.5+02",
+            "2024-02-29T09:00:00.5-01:30",
+        ] {
+            assert_eq!(parse_utc_timestamp(s), Some(time), "{}", s);
+        }
+        assert_eq!(
+            parse_utc_timestamp(&utc_timestamp(time)).map(utc_timestamp),
+            Some(utc_timestamp(time))
+        );
+        assert_eq!(parse_utc_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn test_live_rows() {
+        let filter = Filter::Equals("token".into(), "abc".into());
+
+        let pg = DatabaseType::PostgreSQL;
+        let (sql, params) = live_rows::<Session>(&filter, false, pg).to_sql(0);
+        assert_eq!(
+            sql,
+            "(token = $1 AND (expires_at IS NULL OR expires_at > $2::timestamptz))"
+        );
+        assert!(matches!(&params[1], FilterValue::String(now) if now.ends_with('Z')));
+
+        assert_eq!(*live_rows::<Session>(&filter, true, pg), filter);
+    }
+
+    #[test]
+    fn test_expiry_cast_per_dialect() {
+        let now = UNIX_EPOCH + Duration::from_micros(1_709_202_600_500_000);
+        let cases = [
+            (
+                DatabaseType::PostgreSQL,
+                "expires_at > $1::timestamptz",
+                "2024-02-29T10:30:00.500000Z",
+            ),
+            (
+                DatabaseType::MySQL,
+                "expires_at > CAST($1 AS DATETIME(6))",
+                "2024-02-29 10:30:00.500000",
+            ),
+            (
+                DatabaseType::SQLite,
+                "expires_at > $1",
+                "2024-02-29T10:30:00.500000Z",
+            ),
+            (
+                DatabaseType::MSSQL,
+                "expires_at > CAST($1 AS DATETIMEOFFSET)",
+                "2024-02-29T10:30:00.500000Z",
+            ),
+        ];
+        for (db_type, expected, bound) in cases {
+            let (sql, params) = ExpiryFilter::new(now, db_type)
+                .into_filter("expires_at")
+                .to_sql(0);
+            assert_eq!(sql, expected, "{:?}", db_type);
+            assert_eq!(params, vec![FilterValue::String(bound.into())]);
+        }
+    }
+}
@@ -402,6 +402,34 @@ impl Select {
         }
     }
 
+    /// Generate the SQL column list, selecting computed fields as
+    /// `(expression) AS name`.
+    ///
+    /// `*` is followed by every computed field; a field list computes the
+    /// fields it names.
+    pub fn to_sql_with_virtual(&self, virtual_fields: &[(&str, &str)]) -> String {
+        if virtual_fields.is_empty() {
+            return self.to_sql();
+        }
+        let column = |name: &str| match virtual_fields.iter().find(|(field, _)| *field == name) {
+            Some((field, expr)) => format!("({}) AS {}", expr, field),
+            None => name.to_string(),
+        };
+
+        match self {
+            Self::All => std::iter::once("*".to_string())
+                .chain(virtual_fields.iter().map(|(field, _)| column(field)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Self::Fields(fields) => fields
+                .iter()
+                .map(|f| column(f))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Self::Field(field) => column(field),
+        }
+    }
+
     /// Write the SQL column list directly to a buffer (zero allocation).
     #[inline]
     pub fn write_sql(&self, buffer: &mut String) {
@@ -476,6 +504,32 @@ impl<T> From<Option<T>> for SetParam<T> {
     }
 }
 
+/// A Rust enum mapped to a database enum.
+///
+/// Implemented by the enums prax-codegen generates from the schema. The
+/// drivers use it to bind variants as native enum values (PostgreSQL enum
+/// types, MySQL `ENUM` columns, `TEXT` with a `CHECK` on SQLite) and to
+/// decode columns into the enum rather than a `String`.
+pub trait DbEnum: Copy + fmt::Debug + Send + Sync + 'static {
+    /// The enum's type name in the database.
+    const DB_NAME: &'static str;
+
+    /// Every variant, in declaration order.
+    fn variants() -> &'static [Self];
+
+    /// The value stored in the database for this variant.
+    fn db_value(&self) -> &'static str;
+
+    /// The variant stored as `value`, if there is one.
+    fn from_db_value(value: &str) -> Option<Self>;
+
+    /// The variant stored as `value`, or an error naming the enum.
+    fn decode(value: &str) -> Result<Self, String> {
+        Self::from_db_value(value)
+            .ok_or_else(|| format!("invalid value for enum {}: {:?}", Self::DB_NAME, value))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -579,6 +633,20 @@ mod tests {
         assert_eq!(buffer, "SELECT id, name, email");
     }
 
+    #[test]
+    fn test_select_with_virtual() {
+        let computed = [("total", "quantity * unit_price")];
+        assert_eq!(
+            Select::all().to_sql_with_virtual(&computed),
+            "*, (quantity * unit_price) AS total"
+        );
+        assert_eq!(
+            Select::fields(["id", "total"]).to_sql_with_virtual(&computed),
+            "id, (quantity * unit_price) AS total"
+        );
+        assert_eq!(Select::all().to_sql_with_virtual(&[]), "*");
+    }
+
     #[test]
     fn test_set_param() {
         let set: SetParam<i32> = SetParam::Set(42);
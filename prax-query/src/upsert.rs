@@ -11,7 +11,14 @@
 //! | ON DUPLICATE KEY | ❌             | ✅                 | ❌             | ❌      | ❌           |
 //! | MERGE statement  | ❌             | ❌                 | ❌             | ✅      | ❌           |
 //! | Native upsert    | ❌             | ❌                 | ❌             | ❌      | ✅ upsert:true|
-//! | Conflict targets | ✅             | ❌ (implicit PK/UK)| ✅             | ✅      | ✅ filter    |
+//! | Conflict targets | ✅             | ❌ (implicit PK/UK)| ✅ (no names)  | ✅      | ✅ filter    |
+//! | RETURNING        | ✅             | ❌                 | ✅ (3.35+)     | ❌      | ❌           |
+//!
+//! [`Upsert::to_sql`] picks the syntax for a [`DatabaseType`]. MySQL has no
+//! `DO NOTHING` and uses `INSERT IGNORE` instead, and ignores the conflict
+//! target, the update `WHERE` clause and `RETURNING`. SQLite cannot name a
+//! constraint as the conflict target; without a target, `DO UPDATE` needs
+//! SQLite 3.35 or later.
 //!
 //! # Example Usage
 //!
@@ -55,6 +62,9 @@ pub struct Upsert {
 pub enum ConflictTarget {
     /// Match on specific columns (unique constraint).
     Columns(Vec<String>),
+    /// Match on the columns of a unique constraint whose NULLs conflict
+    /// with each other (`nullsNotDistinct: true`).
+    NullsNotDistinct(Vec<String>),
     /// Match on a named constraint.
     Constraint(String),
     /// Match on index expression (PostgreSQL).
@@ -73,6 +83,27 @@ impl ConflictTarget {
         Self::Columns(cols.into_iter().map(Into::into).collect())
     }
 
+    /// Create a conflict target on the columns of a `NULLS NOT DISTINCT`
+    /// unique constraint.
+    ///
+    /// PostgreSQL infers the constraint from the columns as usual; MSSQL's
+    /// `MERGE` matches NULL against NULL.
+    pub fn nulls_not_distinct<I, S>(cols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::NullsNotDistinct(cols.into_iter().map(Into::into).collect())
+    }
+
+    /// Get the columns matched on, if the target names columns.
+    pub fn columns_matched(&self) -> Option<&[String]> {
+        match self {
+            Self::Columns(cols) | Self::NullsNotDistinct(cols) => Some(cols),
+            _ => None,
+        }
+    }
+
     /// Create a constraint-based conflict target.
     pub fn constraint(name: impl Into<String>) -> Self {
         Self::Constraint(name.into())
@@ -86,7 +117,7 @@ impl ConflictTarget {
     /// Generate PostgreSQL ON CONFLICT target.
     pub fn to_postgres_sql(&self) -> String {
         match self {
-            Self::Columns(cols) => format!("({})", cols.join(", ")),
+            Self::Columns(cols) | Self::NullsNotDistinct(cols) => format!("({})", cols.join(", ")),
             Self::Constraint(name) => format!("ON CONSTRAINT {}", name),
             Self::IndexExpression(expr) => format!("({})", expr),
             Self::Implicit => String::new(),
@@ -96,12 +127,11 @@ impl ConflictTarget {
     /// Generate SQLite ON CONFLICT target.
     pub fn to_sqlite_sql(&self) -> String {
         match self {
-            Self::Columns(cols) => format!("({})", cols.join(", ")),
-            Self::Constraint(_) | Self::IndexExpression(_) => {
-                // SQLite doesn't support these directly
-                String::new()
-            }
-            Self::Implicit => String::new(),
+            Self::Columns(cols) | Self::NullsNotDistinct(cols) => format!("({})", cols.join(", ")),
+            // Unique indexes on expressions are matched like columns
+            Self::IndexExpression(expr) => format!("({})", expr),
+            // SQLite can't name a constraint as the target
+            Self::Constraint(_) | Self::Implicit => String::new(),
         }
     }
 }
@@ -401,11 +431,13 @@ impl Upsert {
         let target = self
             .conflict_target
             .as_ref()
-            .and_then(|t| match t {
-                ConflictTarget::Columns(cols) => Some(cols.clone()),
-                _ => None,
-            })
+            .and_then(|t| t.columns_matched())
+            .map(<[String]>::to_vec)
             .unwrap_or_else(|| vec![self.columns.first().cloned().unwrap_or_default()]);
+        let nulls_match = matches!(
+            self.conflict_target,
+            Some(ConflictTarget::NullsNotDistinct(_))
+        );
 
         let source_cols: Vec<String> = self
             .columns
@@ -416,7 +448,15 @@ impl Upsert {
 
         let match_conditions: Vec<String> = target
             .iter()
-            .map(|c| format!("target.{} = source.{}", c, c))
+            .map(|c| {
+                if nulls_match {
+                    format!(
+                        "(target.{c} = source.{c} OR (target.{c} IS NULL AND source.{c} IS NULL))"
+                    )
+                } else {
+                    format!("target.{} = source.{}", c, c)
+                }
+            })
             .collect();
 
         let mut sql = format!(
@@ -961,6 +1001,26 @@ mod tests {
         assert!(sql.contains("name = excluded.name"));
     }
 
+    #[test]
+    fn test_sqlite_conflict_targets() {
+        let target = ConflictTarget::index_expression("lower(email)");
+        assert_eq!(target.to_sqlite_sql(), "(lower(email))");
+        assert_eq!(
+            ConflictTarget::constraint("users_email_key").to_sqlite_sql(),
+            ""
+        );
+
+        let upsert = Upsert::new("users")
+            .columns(["email"])
+            .values(["?"])
+            .on_conflict(ConflictTarget::constraint("users_email_key"))
+            .returning(["id"]);
+        assert_eq!(
+            upsert.to_sql(DatabaseType::SQLite),
+            "INSERT INTO users (email) VALUES (?) ON CONFLICT DO NOTHING RETURNING id"
+        );
+    }
+
     #[test]
     fn test_mssql_merge() {
         let upsert = Upsert::new("users")
@@ -997,6 +1057,24 @@ mod tests {
         assert_eq!(target.to_postgres_sql(), "ON CONSTRAINT users_email_key");
     }
 
+    #[test]
+    fn test_conflict_target_nulls_not_distinct() {
+        let target = ConflictTarget::nulls_not_distinct(["email", "deleted_at"]);
+        assert_eq!(target.to_postgres_sql(), "(email, deleted_at)");
+        assert_eq!(target.to_sqlite_sql(), "(email, deleted_at)");
+
+        let upsert = Upsert::new("seats")
+            .columns(["email", "deleted_at", "name"])
+            .values(["@P1", "@P2", "@P3"])
+            .on_conflict(target)
+            .do_update(["name"]);
+        let sql = upsert.to_mssql_sql();
+        assert!(sql.contains(
+            "ON (target.email = source.email OR (target.email IS NULL AND source.email IS NULL)) \
+             AND (target.deleted_at = source.deleted_at OR (target.deleted_at IS NULL AND source.deleted_at IS NULL))"
+        ));
+    }
+
     mod mongodb_tests {
         use super::super::mongodb::*;
 
@@ -0,0 +1,81 @@
+//! Runtime checks behind generated `validate()` methods.
+//!
+//! The validator codegen plugin turns `@email` and `@regex` field attributes
+//! into calls to these functions, so generated code does not need its own
+//! regex dependency.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use prax_query::validation::{is_email, matches_pattern};
+//!
+//! assert!(is_email("alice@example.com"));
+//! assert!(!is_email("alice@localhost"));
+//! assert!(matches_pattern("^[a-z0-9-]+$", "hello-world"));
+//! ```
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use parking_lot::Mutex;
+use regex_lite::Regex;
+
+/// Compiled `@regex` patterns, keyed by pattern text.
+static PATTERNS: LazyLock<Mutex<HashMap<&'static str, Regex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Check that a value looks like an email address: a non-empty local part,
+/// a single `@` and a dotted domain, without whitespace.
+pub fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !value.chars().any(char::is_whitespace)
+        && domain.split('.').count() > 1
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+/// Check that a value matches a regular expression.
+///
+/// Patterns are compiled once and cached. A pattern that does not compile
+/// matches nothing; the schema validator rejects such patterns before code
+/// is generated.
+pub fn matches_pattern(pattern: &'static str, value: &str) -> bool {
+    let mut patterns = PATTERNS.lock();
+    if let Some(regex) = patterns.get(pattern) {
+        return regex.is_match(value);
+    }
+    let Ok(regex) = Regex::new(pattern) else {
+        return false;
+    };
+    let matched = regex.is_match(value);
+    patterns.insert(pattern, regex);
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_email() {
+        assert!(is_email("alice@example.com"));
+        assert!(is_email("a.b+tag@mail.example.org"));
+        assert!(!is_email("alice"));
+        assert!(!is_email("@example.com"));
+        assert!(!is_email("alice@example"));
+        assert!(!is_email("alice@@example.com"));
+        assert!(!is_email("alice@example..com"));
+        assert!(!is_email("alice smith@example.com"));
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("^[a-z]+$", "prax"));
+        assert!(!matches_pattern("^[a-z]+$", "Prax"));
+        assert!(matches_pattern("^[a-z]+$", "orm"));
+        assert!(!matches_pattern("[a-z", "anything"));
+    }
+}
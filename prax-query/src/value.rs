@@ -0,0 +1,363 @@
+//! The canonical query parameter value.
+//!
+//! [`Value`] is the one representation of a bound parameter shared by the
+//! query builders and every driver. Filters, create and update data, raw SQL
+//! binds and procedure arguments all carry `Value`s ([`FilterValue`] and
+//! [`FieldValue`] are aliases kept for existing code), and each driver
+//! converts them to its own parameter type in a single place:
+//!
+//! | Driver | Conversion |
+//! |--------|------------|
+//! | `prax-postgres` | `filter_value_to_sql` → `Box<dyn ToSql>` |
+//! | `prax-mysql` | `filter_value_to_mysql` → `mysql_async::Value` |
+//! | `prax-sqlite` | `filter_value_to_sqlite` → `rusqlite::types::Value` |
+//! | `prax-mssql` | `filter_value_to_sql` → `Box<dyn ToSql>` |
+//! | `prax-duckdb` | `ToSql for FilterValue` |
+//! | `prax-mongodb` | `filter_value_to_bson` → `Bson` |
+//! | `prax-scylladb` | `ToCqlValue for FilterValue` |
+//!
+//! Code that only inspects parameters, such as logging or a dyn engine
+//! forwarding them, can use [`ValueRef`], a `Copy` view borrowing strings,
+//! bytes and lists from a `Value` or from the caller without allocating.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use prax_query::{Value, ValueRef};
+//!
+//! let owned: Value = "alice@example.com".into();
+//! assert_eq!(owned.as_borrowed(), ValueRef::String("alice@example.com"));
+//!
+//! let borrowed = ValueRef::from(&b"\x00\x01"[..]);
+//! assert_eq!(Value::from(borrowed), Value::Bytes(vec![0, 1]));
+//! ```
+//!
+//! [`FilterValue`]: crate::filter::FilterValue
+//! [`FieldValue`]: crate::data::FieldValue
+
+use serde::{Deserialize, Serialize};
+
+/// A query parameter value.
+///
+/// # Examples
+///
+/// ```rust
+/// use prax_query::FilterValue;
+///
+/// // From integers
+/// let val: FilterValue = 42.into();
+/// let val: FilterValue = 42i64.into();
+///
+/// // From strings
+/// let val: FilterValue = "hello".into();
+/// let val: FilterValue = String::from("world").into();
+///
+/// // From booleans
+/// let val: FilterValue = true.into();
+///
+/// // From floats
+/// let val: FilterValue = 3.14f64.into();
+///
+/// // From bytes
+/// let val: FilterValue = (&[0xde, 0xad][..]).into();
+///
+/// // Null value
+/// let val = FilterValue::Null;
+///
+/// // From vectors
+/// let val: FilterValue = vec![1, 2, 3].into();
+///
+/// // From Option (Some becomes value, None becomes Null)
+/// let val: FilterValue = Some(42).into();
+/// let val: FilterValue = Option::<i32>::None.into();
+/// assert!(val.is_null());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    /// Null value.
+    Null,
+    /// Boolean value.
+    Bool(bool),
+    /// Integer value.
+    Int(i64),
+    /// Float value.
+    Float(f64),
+    /// String value.
+    String(String),
+    /// List of values.
+    List(Vec<Value>),
+    /// Binary value.
+    ///
+    /// Serializes as an array of numbers, which deserializes back as a
+    /// [`Value::List`] of integers.
+    Bytes(Vec<u8>),
+    /// JSON value.
+    ///
+    /// Listed last so that deserializing only falls back to it for objects
+    /// and numbers out of range of the other variants; JSON arrays
+    /// deserialize as [`Value::List`].
+    Json(serde_json::Value),
+}
+
+impl Value {
+    /// Check if this is a null value.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Convert to SQL parameter placeholder.
+    pub fn to_sql_placeholder(&self, param_index: usize) -> String {
+        format!("${}", param_index)
+    }
+
+    /// Borrow this value without copying strings, bytes or lists.
+    pub fn as_borrowed(&self) -> ValueRef<'_> {
+        match self {
+            Self::Null => ValueRef::Null,
+            Self::Bool(b) => ValueRef::Bool(*b),
+            Self::Int(i) => ValueRef::Int(*i),
+            Self::Float(f) => ValueRef::Float(*f),
+            Self::String(s) => ValueRef::String(s),
+            Self::Json(j) => ValueRef::Json(j),
+            Self::List(list) => ValueRef::List(list),
+            Self::Bytes(b) => ValueRef::Bytes(b),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(v: i32) -> Self {
+        Self::Int(v as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
+        Self::Int(v)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Self::Float(v)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(v: f32) -> Self {
+        Self::Float(v.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Self::String(v)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Self::String(v.to_string())
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(v: &[u8]) -> Self {
+        Self::Bytes(v.to_vec())
+    }
+}
+
+impl From<serde_json::Value> for Value {
+    fn from(v: serde_json::Value) -> Self {
+        Self::Json(v)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(v: Vec<T>) -> Self {
+        Self::List(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        match v {
+            Some(v) => v.into(),
+            None => Self::Null,
+        }
+    }
+}
+
+impl From<ValueRef<'_>> for Value {
+    fn from(v: ValueRef<'_>) -> Self {
+        match v {
+            ValueRef::Null => Self::Null,
+            ValueRef::Bool(b) => Self::Bool(b),
+            ValueRef::Int(i) => Self::Int(i),
+            ValueRef::Float(f) => Self::Float(f),
+            ValueRef::String(s) => Self::String(s.to_string()),
+            ValueRef::Json(j) => Self::Json(j.clone()),
+            ValueRef::List(list) => Self::List(list.to_vec()),
+            ValueRef::Bytes(b) => Self::Bytes(b.to_vec()),
+        }
+    }
+}
+
+/// A borrowed [`Value`].
+///
+/// Strings, JSON, lists and bytes point into the value or buffer they were
+/// taken from, so building one never allocates. Convert with
+/// `Value::from` when an owned parameter is needed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRef<'a> {
+    /// Null value.
+    Null,
+    /// Boolean value.
+    Bool(bool),
+    /// Integer value.
+    Int(i64),
+    /// Float value.
+    Float(f64),
+    /// Borrowed string value.
+    String(&'a str),
+    /// Borrowed JSON value.
+    Json(&'a serde_json::Value),
+    /// Borrowed list of values.
+    List(&'a [Value]),
+    /// Borrowed binary value.
+    Bytes(&'a [u8]),
+}
+
+impl ValueRef<'_> {
+    /// Check if this is a null value.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+}
+
+impl<'a> From<&'a Value> for ValueRef<'a> {
+    fn from(v: &'a Value) -> Self {
+        v.as_borrowed()
+    }
+}
+
+impl From<bool> for ValueRef<'_> {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+
+impl From<i32> for ValueRef<'_> {
+    fn from(v: i32) -> Self {
+        Self::Int(v as i64)
+    }
+}
+
+impl From<i64> for ValueRef<'_> {
+    fn from(v: i64) -> Self {
+        Self::Int(v)
+    }
+}
+
+impl From<f64> for ValueRef<'_> {
+    fn from(v: f64) -> Self {
+        Self::Float(v)
+    }
+}
+
+impl<'a> From<&'a str> for ValueRef<'a> {
+    fn from(v: &'a str) -> Self {
+        Self::String(v)
+    }
+}
+
+impl<'a> From<&'a [u8]> for ValueRef<'a> {
+    fn from(v: &'a [u8]) -> Self {
+        Self::Bytes(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_borrowed_round_trip() {
+        let values = [
+            Value::Null,
+            Value::Bool(true),
+            Value::Int(7),
+            Value::Float(1.5),
+            Value::String("alice".into()),
+            Value::Json(serde_json::json!({"a": 1})),
+            Value::List(vec![Value::Int(1), Value::String("b".into())]),
+            Value::Bytes(vec![0, 255]),
+        ];
+        for value in values {
+            assert_eq!(Value::from(value.as_borrowed()), value);
+        }
+    }
+
+    #[test]
+    fn test_value_ref_borrows() {
+        let value = Value::String("alice".into());
+        let (Value::String(owned), ValueRef::String(borrowed)) = (&value, value.as_borrowed())
+        else {
+            panic!("expected a string");
+        };
+        assert!(std::ptr::eq(owned.as_str(), borrowed));
+
+        let bytes = [1u8, 2, 3];
+        assert_eq!(ValueRef::from(&bytes[..]), ValueRef::Bytes(&[1, 2, 3]));
+        assert!(ValueRef::from(&Value::Null).is_null());
+    }
+
+    #[test]
+    fn test_value_serde_round_trip() {
+        let values = [
+            Value::Null,
+            Value::Bool(true),
+            Value::Int(-7),
+            Value::Float(1.5),
+            Value::String("alice".into()),
+            Value::List(vec![
+                Value::Int(1),
+                Value::String("b".into()),
+                Value::List(vec![Value::Null]),
+            ]),
+            Value::Json(serde_json::json!({"a": [1, 2]})),
+        ];
+        for value in values {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Value>(&json).unwrap(),
+                value,
+                "{}",
+                json
+            );
+        }
+
+        let bytes = serde_json::to_string(&Value::Bytes(vec![1, 2])).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&bytes).unwrap(),
+            Value::List(vec![Value::Int(1), Value::Int(2)])
+        );
+    }
+
+    #[test]
+    fn test_value_bytes_conversion() {
+        let value: Value = (&b"prax"[..]).into();
+        assert_eq!(value, Value::Bytes(b"prax".to_vec()));
+        assert_eq!(
+            serde_json::to_value(&value).unwrap(),
+            serde_json::json!([112, 114, 97, 120])
+        );
+    }
+}
@@ -0,0 +1,534 @@
+//! Startup schema verification.
+//!
+//! Generated clients describe each model's table with a [`TableSpec`]: its
+//! columns ([`Model::COLUMN_SPECS`](crate::traits::Model::COLUMN_SPECS)) and
+//! indexes ([`Model::INDEXES`](crate::traits::Model::INDEXES)).
+//! [`verify_schema`] introspects the live database and reports every missing
+//! table, column and index and every column whose type cannot hold its schema
+//! type, so deploy-order mistakes surface at boot instead of on the first
+//! request:
+//!
+//! ```rust,ignore
+//! let client = PraxClient::new(engine);
+//!
+//! // Log the problems and keep going...
+//! let report = client.verify_schema().await?;
+//! for issue in report.issues() {
+//!     tracing::warn!("{}", issue);
+//! }
+//!
+//! // ...or refuse to start.
+//! client.verify_schema().await?.ensure()?;
+//! ```
+
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+use tracing::warn;
+
+use crate::drift::{ColumnKind, ColumnSpec};
+use crate::error::{ErrorCode, QueryError, QueryResult};
+use crate::introspection::{ColumnInfo, IndexColumn, IndexInfo, TableInfo, queries};
+use crate::raw::RawDatabase;
+use crate::sql::DatabaseType;
+use crate::traits::Model;
+
+/// An index a model declares with `@unique`, `@@unique` or `@@index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexSpec {
+    /// Indexed columns, in order.
+    pub columns: &'static [&'static str],
+    /// Whether the index is unique.
+    pub unique: bool,
+}
+
+impl IndexSpec {
+    /// Create an index spec.
+    pub const fn new(columns: &'static [&'static str], unique: bool) -> Self {
+        Self { columns, unique }
+    }
+}
+
+/// The table a model expects to find in the database.
+#[derive(Debug, Clone, Copy)]
+pub struct TableSpec {
+    /// Table name.
+    pub table: &'static str,
+    /// Columns with their schema types.
+    pub columns: &'static [ColumnSpec],
+    /// Declared indexes.
+    pub indexes: &'static [IndexSpec],
+    /// Computed fields, which have no column.
+    pub virtual_fields: &'static [(&'static str, &'static str)],
+}
+
+impl TableSpec {
+    /// Get the table spec of a model.
+    pub const fn of<M: Model>() -> Self {
+        Self {
+            table: M::TABLE_NAME,
+            columns: M::COLUMN_SPECS,
+            indexes: M::INDEXES,
+            virtual_fields: M::VIRTUAL_FIELDS,
+        }
+    }
+
+    /// Compare the spec with an introspected table.
+    pub fn compare(&self, live: &TableInfo) -> Vec<SchemaIssue> {
+        let mut issues = Vec::new();
+
+        let stored = self.columns.iter().filter(|spec| {
+            !self
+                .virtual_fields
+                .iter()
+                .any(|(name, _)| *name == spec.name)
+        });
+        for spec in stored {
+            match live.columns.iter().find(|c| c.name == spec.name) {
+                None => issues.push(SchemaIssue::MissingColumn {
+                    table: self.table.to_string(),
+                    column: spec.name.to_string(),
+                }),
+                Some(column)
+                    if !column.db_type.is_empty() && !spec.kind.accepts(&column.db_type) =>
+                {
+                    issues.push(SchemaIssue::TypeMismatch {
+                        table: self.table.to_string(),
+                        column: spec.name.to_string(),
+                        actual: column.db_type.clone(),
+                        expected: spec.kind,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for index in self.indexes {
+            let found = live.indexes.iter().any(|live_index| {
+                live_index
+                    .columns
+                    .iter()
+                    .map(|c| c.name.as_str())
+                    .eq(index.columns.iter().copied())
+                    && (!index.unique || live_index.is_unique || live_index.is_primary)
+            });
+            if !found {
+                issues.push(SchemaIssue::MissingIndex {
+                    table: self.table.to_string(),
+                    columns: index.columns.iter().map(|c| c.to_string()).collect(),
+                    unique: index.unique,
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+/// A difference between a model and its live table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaIssue {
+    /// The table does not exist.
+    MissingTable {
+        /// Table name.
+        table: String,
+    },
+    /// A column does not exist.
+    MissingColumn {
+        /// Table name.
+        table: String,
+        /// Column name.
+        column: String,
+    },
+    /// A column's database type cannot hold its schema type.
+    TypeMismatch {
+        /// Table name.
+        table: String,
+        /// Column name.
+        column: String,
+        /// Database type, as introspection reports it.
+        actual: String,
+        /// Schema type.
+        expected: ColumnKind,
+    },
+    /// No index covers the declared columns.
+    MissingIndex {
+        /// Table name.
+        table: String,
+        /// Indexed columns, in order.
+        columns: Vec<String>,
+        /// Whether the declared index is unique.
+        unique: bool,
+    },
+}
+
+impl SchemaIssue {
+    /// Get the table name.
+    pub fn table(&self) -> &str {
+        match self {
+            Self::MissingTable { table }
+            | Self::MissingColumn { table, .. }
+            | Self::TypeMismatch { table, .. }
+            | Self::MissingIndex { table, .. } => table,
+        }
+    }
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTable { table } => write!(f, "table {} is missing", table),
+            Self::MissingColumn { table, column } => {
+                write!(f, "column {}.{} is missing", table, column)
+            }
+            Self::TypeMismatch {
+                table,
+                column,
+                actual,
+                expected,
+            } => write!(
+                f,
+                "column {}.{} is {} but schema says {}",
+                table,
+                column,
+                actual.to_uppercase(),
+                expected
+            ),
+            Self::MissingIndex {
+                table,
+                columns,
+                unique,
+            } => write!(
+                f,
+                "{}index on {}({}) is missing",
+                if *unique { "unique " } else { "" },
+                table,
+                columns.join(", ")
+            ),
+        }
+    }
+}
+
+/// The outcome of [`verify_schema`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaReport {
+    /// Number of tables checked.
+    pub tables_checked: usize,
+    /// Differences found.
+    pub issues: Vec<SchemaIssue>,
+}
+
+impl SchemaReport {
+    /// Check if the database matches the schema.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Get the differences found.
+    pub fn issues(&self) -> &[SchemaIssue] {
+        &self.issues
+    }
+
+    /// Fail with a `SchemaDrift` error listing every difference, if any.
+    pub fn ensure(self) -> QueryResult<Self> {
+        if self.is_ok() {
+            return Ok(self);
+        }
+        Err(QueryError::new(ErrorCode::SchemaDrift, self.to_string())
+            .with_suggestion("Apply pending migrations with `prax migrate deploy`")
+            .with_suggestion("Compare the schema with the database using `prax migrate diff`"))
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "{} tables match the schema", self.tables_checked);
+        }
+        write!(
+            f,
+            "{} schema issues in {} tables",
+            self.issues.len(),
+            self.tables_checked
+        )?;
+        for issue in &self.issues {
+            write!(f, "\n  - {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Introspect the tables of `specs` and compare them with the schema.
+pub async fn verify_schema<D>(db: &D, specs: &[TableSpec]) -> QueryResult<SchemaReport>
+where
+    D: RawDatabase + ?Sized,
+{
+    let mut report = SchemaReport::default();
+
+    for spec in specs {
+        let live = introspect_table(db, spec.table).await?;
+        report.tables_checked += 1;
+        match live {
+            Some(live) => report.issues.extend(spec.compare(&live)),
+            None => report.issues.push(SchemaIssue::MissingTable {
+                table: spec.table.to_string(),
+            }),
+        }
+    }
+
+    for issue in &report.issues {
+        warn!(table = %issue.table(), "Schema verification: {}", issue);
+    }
+    Ok(report)
+}
+
+/// Introspect the columns and indexes of a table; `None` if it does not exist.
+async fn introspect_table<D>(db: &D, table: &str) -> QueryResult<Option<TableInfo>>
+where
+    D: RawDatabase + ?Sized,
+{
+    let db_type = db.database_type();
+    // A schema-qualified table (`accounting.invoices`) overrides the default schema
+    let (schema, table) = match table.split_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (db.schema_name(), table),
+    };
+
+    let rows = db
+        .query(&queries::columns_query(db_type, table, schema), Vec::new())
+        .await?;
+    if rows.is_empty() {
+        return Ok(None);
+    }
+    let columns = rows
+        .iter()
+        .filter_map(|row| {
+            Some(ColumnInfo {
+                name: json_str(row, &["column_name", "COLUMN_NAME", "name"])?,
+                db_type: json_str(row, &["udt_name", "UDT_NAME", "type"]).unwrap_or_default(),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    let rows = db
+        .query(&queries::indexes_query(db_type, table, schema), Vec::new())
+        .await?;
+    let mut indexes: Vec<IndexInfo> = Vec::new();
+    for row in &rows {
+        let Some(name) = json_str(row, &["index_name", "INDEX_NAME", "name"]) else {
+            continue;
+        };
+        if db_type == DatabaseType::SQLite {
+            // PRAGMA index_list has one row per index; columns come from index_info
+            let columns = db
+                .query(&format!("PRAGMA index_info('{}')", name), Vec::new())
+                .await?
+                .iter()
+                .filter_map(|c| json_str(c, &["name"]))
+                .map(|name| IndexColumn {
+                    name,
+                    ..Default::default()
+                })
+                .collect();
+            indexes.push(IndexInfo {
+                is_unique: json_bool(row, "unique"),
+                is_primary: json_str(row, &["origin"]).as_deref() == Some("pk"),
+                name,
+                columns,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some(column) = json_str(row, &["column_name", "COLUMN_NAME"]) else {
+            continue;
+        };
+        let position = match indexes.iter().position(|i| i.name == name) {
+            Some(position) => position,
+            None => {
+                indexes.push(IndexInfo {
+                    is_unique: json_bool(row, "is_unique"),
+                    is_primary: json_bool(row, "is_primary"),
+                    name,
+                    ..Default::default()
+                });
+                indexes.len() - 1
+            }
+        };
+        indexes[position].columns.push(IndexColumn {
+            name: column,
+            ..Default::default()
+        });
+    }
+
+    Ok(Some(TableInfo {
+        name: table.to_string(),
+        schema: schema.map(str::to_string),
+        columns,
+        indexes,
+        ..Default::default()
+    }))
+}
+
+/// Get the first of `keys` present in a row as a string.
+fn json_str(row: &JsonValue, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| row.get(*key)?.as_str())
+        .map(str::to_string)
+}
+
+/// Get a flag, which dialects return as a boolean or a number.
+fn json_bool(row: &JsonValue, key: &str) -> bool {
+    match row.get(key) {
+        Some(JsonValue::Bool(value)) => *value,
+        Some(JsonValue::Number(value)) => value.as_i64().is_some_and(|v| v != 0),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::FilterValue;
+    use crate::traits::BoxFuture;
+    use serde_json::json;
+
+    const USERS: TableSpec = TableSpec {
+        table: "users",
+        columns: &[
+            ColumnSpec::new("id", ColumnKind::Int),
+            ColumnSpec::new("email", ColumnKind::String),
+            ColumnSpec::new("age", ColumnKind::Int),
+            ColumnSpec::new("display", ColumnKind::Other),
+        ],
+        indexes: &[
+            IndexSpec::new(&["email"], true),
+            IndexSpec::new(&["age", "email"], false),
+        ],
+        virtual_fields: &[("display", "email")],
+    };
+
+    const POSTS: TableSpec = TableSpec {
+        table: "posts",
+        columns: &[ColumnSpec::new("id", ColumnKind::Int)],
+        indexes: &[],
+        virtual_fields: &[],
+    };
+
+    /// Database answering the PostgreSQL introspection queries with fixed rows.
+    struct FixedDatabase;
+
+    impl RawDatabase for FixedDatabase {
+        fn query(
+            &self,
+            sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<Vec<JsonValue>>> {
+            let rows = if !sql.contains("'users'") {
+                Vec::new()
+            } else if sql.contains("information_schema.columns") {
+                vec![
+                    json!({ "column_name": "id", "udt_name": "int4" }),
+                    json!({ "column_name": "email", "udt_name": "text" }),
+                    json!({ "column_name": "age", "udt_name": "text" }),
+                ]
+            } else {
+                vec![
+                    json!({ "index_name": "users_pkey", "column_name": "id", "is_unique": true, "is_primary": true }),
+                    json!({ "index_name": "users_age_email", "column_name": "age", "is_unique": false, "is_primary": false }),
+                    json!({ "index_name": "users_age_email", "column_name": "email", "is_unique": false, "is_primary": false }),
+                ]
+            };
+            Box::pin(async move { Ok(rows) })
+        }
+
+        fn execute(
+            &self,
+            _sql: &str,
+            _params: Vec<FilterValue>,
+        ) -> BoxFuture<'_, QueryResult<u64>> {
+            unreachable!("verification only reads")
+        }
+
+        fn execute_in_transaction(
+            &self,
+            _statements: Vec<(String, Vec<FilterValue>)>,
+        ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+            unreachable!("verification only reads")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_schema() {
+        let report = verify_schema(&FixedDatabase, &[USERS, POSTS])
+            .await
+            .unwrap();
+        assert_eq!(report.tables_checked, 2);
+        assert_eq!(
+            report
+                .issues()
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "column users.age is TEXT but schema says Int",
+                "unique index on users(email) is missing",
+                "table posts is missing",
+            ]
+        );
+
+        let err = report.ensure().unwrap_err();
+        assert!(err.is_schema_drift());
+        assert!(err.message.contains("3 schema issues in 2 tables"));
+    }
+
+    #[test]
+    fn test_table_spec_compare() {
+        let live = TableInfo {
+            name: "users".to_string(),
+            columns: ["id", "email", "age"]
+                .into_iter()
+                .map(|name| ColumnInfo {
+                    name: name.to_string(),
+                    db_type: if name == "email" { "text" } else { "int4" }.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            indexes: vec![
+                IndexInfo {
+                    name: "users_email_key".to_string(),
+                    columns: vec![IndexColumn {
+                        name: "email".to_string(),
+                        ..Default::default()
+                    }],
+                    is_unique: true,
+                    ..Default::default()
+                },
+                IndexInfo {
+                    name: "users_email_age".to_string(),
+                    columns: ["email", "age"]
+                        .into_iter()
+                        .map(|name| IndexColumn {
+                            name: name.to_string(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // Column order matters for composite indexes
+        assert_eq!(
+            USERS.compare(&live),
+            vec![SchemaIssue::MissingIndex {
+                table: "users".to_string(),
+                columns: vec!["age".to_string(), "email".to_string()],
+                unique: false,
+            }]
+        );
+        assert!(SchemaReport::default().ensure().is_ok());
+    }
+}
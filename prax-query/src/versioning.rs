@@ -0,0 +1,489 @@
+//! Point-in-time reads of versioned records.
+//!
+//! Models marked `@@versioned` get a history table, kept up to date by
+//! database triggers generated with their migrations. Every insert, update
+//! and delete records a version of the row, valid from the time of the
+//! change until it is superseded:
+//!
+//! ```prax
+//! model User {
+//!     id    Int    @id @auto
+//!     email String
+//!
+//!     @@versioned
+//! }
+//! ```
+//!
+//! Generated clients expose the history of a record as `user.history()`,
+//! and of the whole table as `user::history_table()`:
+//!
+//! ```rust,ignore
+//! // The user as it was at the start of the year
+//! let then = user.history().as_of("2025-01-01T00:00:00Z").exec_first(&db).await?;
+//!
+//! // Everything that changed in January
+//! let changes = user
+//!     .history()
+//!     .changes_between("2025-01-01T00:00:00Z", "2025-02-01T00:00:00Z")
+//!     .exec(&db)
+//!     .await?;
+//! for change in &changes {
+//!     println!("{} at {}", change.operation.as_str(), change.valid_from);
+//! }
+//!
+//! // Every user as of a point in time
+//! let users: Vec<User> = user::history_table()
+//!     .all()
+//!     .as_of("2025-01-01T00:00:00Z")
+//!     .exec(&db)
+//!     .await?
+//!     .iter()
+//!     .map(|entry| entry.record())
+//!     .collect::<Result<_, _>>()?;
+//! ```
+//!
+//! Timestamps are ISO 8601 strings.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::error::{QueryError, QueryResult};
+use crate::filter::FilterValue;
+use crate::raw::RawDatabase;
+use crate::sql::{DatabaseType, quote_identifier};
+
+/// Surrogate key of a history row.
+pub const VERSION_ID_COLUMN: &str = "_version_id";
+/// When a version became current.
+pub const VALID_FROM_COLUMN: &str = "_valid_from";
+/// When a version was superseded; `NULL` while current.
+pub const VALID_TO_COLUMN: &str = "_valid_to";
+/// The change that produced a version.
+pub const OPERATION_COLUMN: &str = "_operation";
+
+/// The change that produced a version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HistoryOperation {
+    /// The record was created.
+    Insert,
+    /// The record was updated.
+    Update,
+    /// The record was deleted.
+    Delete,
+}
+
+impl HistoryOperation {
+    /// Parse an operation as stored in the history table.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "INSERT" => Some(Self::Insert),
+            "UPDATE" => Some(Self::Update),
+            "DELETE" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+
+    /// Get the operation as stored in the history table.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "INSERT",
+            Self::Update => "UPDATE",
+            Self::Delete => "DELETE",
+        }
+    }
+}
+
+/// A version of a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// Surrogate key of the version, increasing with every change.
+    pub version_id: i64,
+    /// The change that produced this version.
+    pub operation: HistoryOperation,
+    /// When this version became current.
+    pub valid_from: String,
+    /// When this version was superseded, `None` while current.
+    pub valid_to: Option<String>,
+    /// The record's columns. For a delete, the values before deletion.
+    pub values: Map<String, JsonValue>,
+}
+
+impl HistoryEntry {
+    /// Read a version from a history table row.
+    pub fn from_row(row: JsonValue) -> QueryResult<Self> {
+        let JsonValue::Object(mut values) = row else {
+            return Err(QueryError::deserialization(
+                "history row is not a JSON object",
+            ));
+        };
+
+        let version_id = values
+            .remove(VERSION_ID_COLUMN)
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| missing_column(VERSION_ID_COLUMN))?;
+        let operation = values
+            .remove(OPERATION_COLUMN)
+            .and_then(|v| v.as_str().and_then(HistoryOperation::from_str))
+            .ok_or_else(|| missing_column(OPERATION_COLUMN))?;
+        let valid_from = values
+            .remove(VALID_FROM_COLUMN)
+            .and_then(timestamp)
+            .ok_or_else(|| missing_column(VALID_FROM_COLUMN))?;
+        let valid_to = values.remove(VALID_TO_COLUMN).and_then(timestamp);
+
+        Ok(Self {
+            version_id,
+            operation,
+            valid_from,
+            valid_to,
+            values,
+        })
+    }
+
+    /// Deserialize the record as it was in this version.
+    pub fn record<T: DeserializeOwned>(&self) -> QueryResult<T> {
+        serde_json::from_value(JsonValue::Object(self.values.clone()))
+            .map_err(|e| QueryError::deserialization(e.to_string()))
+    }
+
+    /// Check if this is the current version of the record.
+    pub fn is_current(&self) -> bool {
+        self.valid_to.is_none()
+    }
+}
+
+fn missing_column(column: &str) -> QueryError {
+    QueryError::deserialization(format!("history row has no valid `{}` column", column))
+}
+
+fn timestamp(value: JsonValue) -> Option<String> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::String(s) => Some(s),
+        other => Some(other.to_string()),
+    }
+}
+
+/// The history table of a versioned model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryTable {
+    table: String,
+    key: Vec<String>,
+}
+
+impl HistoryTable {
+    /// Create a history table with the primary key columns of its model.
+    pub fn new(table: impl Into<String>, key: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            table: table.into(),
+            key: key.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Get the table name.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Get the primary key columns.
+    pub fn key(&self) -> &[String] {
+        &self.key
+    }
+
+    /// Query the history of every record.
+    pub fn all(&self) -> HistoryQuery {
+        HistoryQuery {
+            table: self.clone(),
+            key_values: Vec::new(),
+            range: HistoryRange::All,
+        }
+    }
+
+    /// Query the history of the record with the given primary key values.
+    pub fn record(&self, key: impl IntoIterator<Item = impl Into<FilterValue>>) -> HistoryQuery {
+        HistoryQuery {
+            table: self.clone(),
+            key_values: key.into_iter().map(Into::into).collect(),
+            range: HistoryRange::All,
+        }
+    }
+
+    /// Query the history of a record, reading its primary key from the
+    /// record's serialized columns.
+    pub fn record_of(&self, record: &impl Serialize) -> HistoryQuery {
+        let row = serde_json::to_value(record).unwrap_or_default();
+        let key_values = self
+            .key
+            .iter()
+            .map(|column| json_to_filter_value(row.get(column).cloned().unwrap_or_default()))
+            .collect();
+
+        HistoryQuery {
+            table: self.clone(),
+            key_values,
+            range: HistoryRange::All,
+        }
+    }
+}
+
+pub(crate) fn json_to_filter_value(value: JsonValue) -> FilterValue {
+    match value {
+        JsonValue::Null => FilterValue::Null,
+        JsonValue::Bool(b) => FilterValue::Bool(b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => FilterValue::Int(i),
+            None => FilterValue::Float(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => FilterValue::String(s),
+        other => FilterValue::Json(other),
+    }
+}
+
+/// The versions a [`HistoryQuery`] selects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryRange {
+    /// Every version.
+    All,
+    /// The version current at a point in time.
+    AsOf(FilterValue),
+    /// Versions created in `[from, to)`.
+    Between(FilterValue, FilterValue),
+}
+
+/// A query over the history table of a versioned model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryQuery {
+    table: HistoryTable,
+    key_values: Vec<FilterValue>,
+    range: HistoryRange,
+}
+
+impl HistoryQuery {
+    /// Select the versions current at a point in time.
+    ///
+    /// Records deleted by then have no current version.
+    pub fn as_of(mut self, at: impl Into<FilterValue>) -> Self {
+        self.range = HistoryRange::AsOf(at.into());
+        self
+    }
+
+    /// Select the versions created from `from` (inclusive) to `to` (exclusive),
+    /// including deletions.
+    pub fn changes_between(
+        mut self,
+        from: impl Into<FilterValue>,
+        to: impl Into<FilterValue>,
+    ) -> Self {
+        self.range = HistoryRange::Between(from.into(), to.into());
+        self
+    }
+
+    /// Get the selected range.
+    pub fn range(&self) -> &HistoryRange {
+        &self.range
+    }
+
+    /// Build the SQL and parameters for a dialect.
+    pub fn build(&self, db_type: DatabaseType) -> (String, Vec<FilterValue>) {
+        let mut params = Vec::new();
+        let mut bind = |value: &FilterValue| {
+            params.push(value.clone());
+            db_type.placeholder_string(params.len())
+        };
+
+        let mut conditions: Vec<String> = self
+            .table
+            .key
+            .iter()
+            .zip(&self.key_values)
+            .map(|(column, value)| format!("{} = {}", quote_identifier(column), bind(value)))
+            .collect();
+
+        let valid_from = quote_identifier(VALID_FROM_COLUMN);
+        let valid_to = quote_identifier(VALID_TO_COLUMN);
+        match &self.range {
+            HistoryRange::All => {}
+            HistoryRange::AsOf(at) => {
+                // Bound once per use, since `?` placeholders can't be reused
+                let from_bound = timestamp_param(db_type, bind(at));
+                let to_bound = timestamp_param(db_type, bind(at));
+                conditions.push(format!("{} <= {}", valid_from, from_bound));
+                conditions.push(format!(
+                    "({} IS NULL OR {} > {})",
+                    valid_to, valid_to, to_bound
+                ));
+                conditions.push(format!(
+                    "{} <> '{}'",
+                    quote_identifier(OPERATION_COLUMN),
+                    HistoryOperation::Delete.as_str()
+                ));
+            }
+            HistoryRange::Between(from, to) => {
+                let from = timestamp_param(db_type, bind(from));
+                let to = timestamp_param(db_type, bind(to));
+                conditions.push(format!("{} >= {}", valid_from, from));
+                conditions.push(format!("{} < {}", valid_from, to));
+            }
+        }
+
+        let mut sql = format!("SELECT * FROM {}", quote_identifier(&self.table.table));
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(&format!(
+            " ORDER BY {}",
+            quote_identifier(VERSION_ID_COLUMN)
+        ));
+
+        (sql, params)
+    }
+
+    /// Fetch the selected versions, oldest first.
+    pub async fn exec<D>(&self, db: &D) -> QueryResult<Vec<HistoryEntry>>
+    where
+        D: RawDatabase + ?Sized,
+    {
+        let (sql, params) = self.build(db.database_type());
+        let rows = db
+            .query(&sql, params)
+            .await
+            .map_err(|e| e.with_context(format!("reading history from `{}`", self.table.table)))?;
+
+        let mut entries = rows
+            .into_iter()
+            .map(HistoryEntry::from_row)
+            .collect::<QueryResult<Vec<_>>>()?;
+        entries.sort_by_key(|e| e.version_id);
+        Ok(entries)
+    }
+
+    /// Fetch the oldest selected version, such as the single version of a
+    /// record returned by [`as_of`](Self::as_of).
+    pub async fn exec_first<D>(&self, db: &D) -> QueryResult<Option<HistoryEntry>>
+    where
+        D: RawDatabase + ?Sized,
+    {
+        Ok(self.exec(db).await?.into_iter().next())
+    }
+}
+
+/// Compare a bound ISO 8601 timestamp with the history table's timestamps.
+fn timestamp_param(db_type: DatabaseType, placeholder: String) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!("CAST({} AS TIMESTAMPTZ)", placeholder),
+        DatabaseType::MySQL => format!("CAST({} AS DATETIME(6))", placeholder),
+        // Stored as text in the same format, so they compare in order
+        DatabaseType::SQLite => format!("strftime('%Y-%m-%dT%H:%M:%fZ', {})", placeholder),
+        DatabaseType::MSSQL => format!("CAST({} AS DATETIME2)", placeholder),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::testing::FakeDatabase;
+    use serde_json::json;
+
+    fn users() -> HistoryTable {
+        HistoryTable::new("users_history", ["id"])
+    }
+
+    #[test]
+    fn test_history_as_of_sql() {
+        let (sql, params) = users()
+            .record([7])
+            .as_of("2025-01-01T00:00:00Z")
+            .build(DatabaseType::PostgreSQL);
+        assert_eq!(
+            sql,
+            "SELECT * FROM users_history WHERE id = $1 \
+             AND _valid_from <= CAST($2 AS TIMESTAMPTZ) \
+             AND (_valid_to IS NULL OR _valid_to > CAST($3 AS TIMESTAMPTZ)) \
+             AND _operation <> 'DELETE' ORDER BY _version_id"
+        );
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[0], FilterValue::Int(7));
+
+        let (sql, params) = users()
+            .all()
+            .changes_between("2025-01-01T00:00:00Z", "2025-02-01T00:00:00Z")
+            .build(DatabaseType::SQLite);
+        assert_eq!(
+            sql,
+            "SELECT * FROM users_history \
+             WHERE _valid_from >= strftime('%Y-%m-%dT%H:%M:%fZ', ?) \
+             AND _valid_from < strftime('%Y-%m-%dT%H:%M:%fZ', ?) ORDER BY _version_id"
+        );
+        assert_eq!(params.len(), 2);
+    }
+
+    #[test]
+    fn test_history_record_of() {
+        #[derive(Serialize)]
+        struct User {
+            #[serde(rename = "user_id")]
+            id: i32,
+            email: String,
+        }
+
+        let table = HistoryTable::new("users_history", ["user_id"]);
+        let query = table.record_of(&User {
+            id: 3,
+            email: "a@example.com".into(),
+        });
+        let (sql, params) = query.build(DatabaseType::MySQL);
+        assert_eq!(
+            sql,
+            "SELECT * FROM users_history WHERE user_id = ? ORDER BY _version_id"
+        );
+        assert_eq!(params, vec![FilterValue::Int(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_history_exec() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct User {
+            id: i64,
+            email: String,
+        }
+
+        let db = FakeDatabase::new().with_rows(vec![
+            json!({
+                "_version_id": 2,
+                "_operation": "UPDATE",
+                "_valid_from": "2025-01-02T00:00:00Z",
+                "_valid_to": null,
+                "id": 1,
+                "email": "new@example.com",
+            }),
+            json!({
+                "_version_id": 1,
+                "_operation": "INSERT",
+                "_valid_from": "2025-01-01T00:00:00Z",
+                "_valid_to": "2025-01-02T00:00:00Z",
+                "id": 1,
+                "email": "old@example.com",
+            }),
+        ]);
+
+        let entries = users().record([1]).exec(&db).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].operation, HistoryOperation::Insert);
+        assert_eq!(entries[0].valid_to.as_deref(), Some("2025-01-02T00:00:00Z"));
+        assert!(entries[1].is_current());
+        assert_eq!(
+            entries[1].record::<User>().unwrap(),
+            User {
+                id: 1,
+                email: "new@example.com".into(),
+            }
+        );
+        assert_eq!(db.queries().len(), 1);
+
+        let db = FakeDatabase::new().with_rows(vec![json!({ "id": 1 })]);
+        assert!(users().record([1]).exec(&db).await.is_err());
+    }
+}
@@ -26,6 +26,8 @@ pub enum AttributeValue {
     FieldRef(SmolStr),
     /// A list of field references (e.g., `[field1, field2]`).
     FieldRefList(Vec<SmolStr>),
+    /// An object of named values (e.g., `{draft: [review]}`), in source order.
+    Object(Vec<(SmolStr, AttributeValue)>),
 }
 
 impl AttributeValue {
@@ -69,6 +71,14 @@ impl AttributeValue {
             _ => None,
         }
     }
+
+    /// Try to get the value as an object.
+    pub fn as_object(&self) -> Option<&[(SmolStr, AttributeValue)]> {
+        match self {
+            Self::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
 }
 
 /// An attribute argument (named or positional).
@@ -107,6 +117,13 @@ impl AttributeArg {
     }
 }
 
+/// Attribute namespaces interpreted by Prax itself.
+///
+/// Attributes in any other namespace (e.g. `@myplugin.searchable`) are
+/// custom attributes: they are accepted by the parser and validator and
+/// passed through untouched for plugins to interpret.
+pub const BUILTIN_ATTRIBUTE_NAMESPACES: &[&str] = &["db", "validate", "graphql"];
+
 /// An attribute applied to a field, model, or enum.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Attribute {
@@ -143,6 +160,25 @@ impl Attribute {
         self.name.as_str() == name
     }
 
+    /// Namespace of a dotted attribute (`myplugin` in `@myplugin.searchable`).
+    pub fn namespace(&self) -> Option<&str> {
+        self.name().split_once('.').map(|(namespace, _)| namespace)
+    }
+
+    /// Attribute name without its namespace (`searchable` in `@myplugin.searchable`).
+    pub fn local_name(&self) -> &str {
+        self.name()
+            .split_once('.')
+            .map(|(_, name)| name)
+            .unwrap_or_else(|| self.name())
+    }
+
+    /// Check if this is a custom attribute in a non-built-in namespace.
+    pub fn is_custom(&self) -> bool {
+        self.namespace()
+            .is_some_and(|ns| !BUILTIN_ATTRIBUTE_NAMESPACES.contains(&ns))
+    }
+
     /// Get the first positional argument.
     pub fn first_arg(&self) -> Option<&AttributeValue> {
         self.args.first().map(|a| &a.value)
@@ -169,6 +205,11 @@ impl Attribute {
                 | "map"
                 | "db"
                 | "relation"
+                | "personalData"
+                | "subjectId"
+                | "stateMachine"
+                | "translatable"
+                | "virtual"
         )
     }
 
@@ -176,11 +217,77 @@ impl Attribute {
     pub fn is_model_attribute(&self) -> bool {
         matches!(
             self.name(),
-            "map" | "index" | "unique" | "id" | "search" | "sql"
+            "map"
+                | "index"
+                | "unique"
+                | "exclude"
+                | "id"
+                | "search"
+                | "sql"
+                | "retention"
+                | "ttl"
+                | "versioned"
+                | "audited"
+                | "tree"
+                | "schema"
         )
     }
 }
 
+/// Custom (plugin-defined) attributes attached to a schema element.
+///
+/// A borrowed view over the custom attributes of a field, model, enum or
+/// view, queried by namespace.
+#[derive(Debug, Clone, Copy)]
+pub struct CustomAttributes<'a> {
+    attributes: &'a [Attribute],
+}
+
+impl<'a> CustomAttributes<'a> {
+    /// Create a view over a list of attributes.
+    pub fn new(attributes: &'a [Attribute]) -> Self {
+        Self { attributes }
+    }
+
+    /// Iterate over all custom attributes.
+    pub fn iter(&self) -> impl Iterator<Item = &'a Attribute> + 'a {
+        self.attributes.iter().filter(|a| a.is_custom())
+    }
+
+    /// Iterate over the custom attributes in a namespace.
+    pub fn in_namespace(&self, namespace: &'a str) -> impl Iterator<Item = &'a Attribute> + 'a {
+        self.iter()
+            .filter(move |a| a.namespace() == Some(namespace))
+    }
+
+    /// Get a custom attribute by namespace and name.
+    pub fn get(&self, namespace: &str, name: &str) -> Option<&'a Attribute> {
+        self.iter()
+            .find(|a| a.namespace() == Some(namespace) && a.local_name() == name)
+    }
+
+    /// Check if a custom attribute is present.
+    pub fn has(&self, namespace: &str, name: &str) -> bool {
+        self.get(namespace, name).is_some()
+    }
+
+    /// Distinct namespaces used, in order of first appearance.
+    pub fn namespaces(&self) -> Vec<&'a str> {
+        let mut namespaces = Vec::new();
+        for namespace in self.iter().filter_map(|a| a.namespace()) {
+            if !namespaces.contains(&namespace) {
+                namespaces.push(namespace);
+            }
+        }
+        namespaces
+    }
+
+    /// Check if there are no custom attributes.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
 /// Common field attributes.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FieldAttributes {
@@ -583,6 +690,56 @@ mod tests {
         assert!(!unknown_attr.is_model_attribute());
     }
 
+    #[test]
+    fn test_attribute_namespace() {
+        let custom = Attribute::simple(
+            Ident::new("search.indexed", Span::new(0, 14)),
+            Span::new(0, 15),
+        );
+        assert_eq!(custom.namespace(), Some("search"));
+        assert_eq!(custom.local_name(), "indexed");
+        assert!(custom.is_custom());
+
+        let builtin = Attribute::simple(Ident::new("db.Text", Span::new(0, 7)), Span::new(0, 8));
+        assert_eq!(builtin.namespace(), Some("db"));
+        assert!(!builtin.is_custom());
+
+        let plain = Attribute::simple(Ident::new("id", Span::new(0, 2)), Span::new(0, 3));
+        assert_eq!(plain.namespace(), None);
+        assert_eq!(plain.local_name(), "id");
+        assert!(!plain.is_custom());
+    }
+
+    // ==================== CustomAttributes Tests ====================
+
+    #[test]
+    fn test_custom_attributes() {
+        let attrs = vec![
+            Attribute::simple(Ident::new("unique", Span::new(0, 6)), Span::new(0, 7)),
+            Attribute::simple(
+                Ident::new("search.indexed", Span::new(0, 14)),
+                Span::new(0, 15),
+            ),
+            Attribute::simple(
+                Ident::new("audit.ignore", Span::new(0, 12)),
+                Span::new(0, 13),
+            ),
+            Attribute::simple(
+                Ident::new("search.boost", Span::new(0, 12)),
+                Span::new(0, 13),
+            ),
+        ];
+        let custom = CustomAttributes::new(&attrs);
+
+        assert!(!custom.is_empty());
+        assert_eq!(custom.iter().count(), 3);
+        assert_eq!(custom.namespaces(), vec!["search", "audit"]);
+        assert_eq!(custom.in_namespace("search").count(), 2);
+        assert!(custom.has("audit", "ignore"));
+        assert!(!custom.has("audit", "unique"));
+        assert!(CustomAttributes::new(&attrs[..1]).is_empty());
+    }
+
     // ==================== FieldAttributes Tests ====================
 
     #[test]
@@ -0,0 +1,150 @@
+//! Personal data annotations for subject erasure.
+//!
+//! `@subjectId` marks the field identifying a data subject (typically the
+//! primary key of a `User` model) and `@personalData` marks fields holding
+//! personal data, on the subject model and on models related to it:
+//!
+//! ```prax
+//! model User {
+//!     id    Int     @id @auto @subjectId
+//!     email String? @unique @personalData
+//!     name  String  @personalData(replace: "Deleted user")
+//!     posts Post[]
+//! }
+//!
+//! model Post {
+//!     id       Int    @id @auto
+//!     body     String @personalData(strategy: delete)
+//!     authorId Int
+//!     author   User   @relation(fields: [authorId], references: [id])
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::{Attribute, AttributeValue};
+
+/// How personal data is erased.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ErasureStrategy {
+    /// Overwrite the value (with `NULL` or a replacement value).
+    #[default]
+    Anonymize,
+    /// Delete the whole row.
+    Delete,
+}
+
+impl ErasureStrategy {
+    /// Parse a strategy name (`anonymize` or `delete`).
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "anonymize" => Some(Self::Anonymize),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+
+    /// Get the strategy name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Anonymize => "anonymize",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// A `@personalData` annotation.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PersonalData {
+    /// How the data is erased.
+    pub strategy: ErasureStrategy,
+    /// Value written when anonymizing; `NULL` when absent.
+    pub replacement: Option<AttributeValue>,
+}
+
+impl PersonalData {
+    /// Read the annotation from a `@personalData` attribute.
+    pub fn from_attribute(attr: &Attribute) -> Result<Self, String> {
+        let strategy = match attr.get_arg("strategy") {
+            Some(value) => {
+                let name = value.as_ident().or_else(|| value.as_string());
+                name.and_then(ErasureStrategy::from_str)
+                    .ok_or_else(|| "`strategy` must be `anonymize` or `delete`".to_string())?
+            }
+            None => ErasureStrategy::default(),
+        };
+
+        let replacement = match attr.get_arg("replace") {
+            Some(
+                value @ (AttributeValue::String(_)
+                | AttributeValue::Int(_)
+                | AttributeValue::Float(_)
+                | AttributeValue::Boolean(_)),
+            ) => Some(value.clone()),
+            Some(_) => return Err("`replace` must be a literal value".to_string()),
+            None => None,
+        };
+
+        if strategy == ErasureStrategy::Delete && replacement.is_some() {
+            return Err("`replace` has no effect with `strategy: delete`".to_string());
+        }
+
+        Ok(Self {
+            strategy,
+            replacement,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AttributeArg, Ident, Span};
+
+    fn attr(args: Vec<AttributeArg>) -> Attribute {
+        Attribute::new(
+            Ident::new("personalData", Span::new(0, 12)),
+            args,
+            Span::new(0, 12),
+        )
+    }
+
+    fn arg(name: &str, value: AttributeValue) -> AttributeArg {
+        AttributeArg::named(Ident::new(name, Span::new(0, 1)), value, Span::new(0, 1))
+    }
+
+    #[test]
+    fn test_personal_data_from_attribute() {
+        assert_eq!(
+            PersonalData::from_attribute(&attr(vec![])).unwrap(),
+            PersonalData::default()
+        );
+
+        let data = PersonalData::from_attribute(&attr(vec![arg(
+            "replace",
+            AttributeValue::String("redacted".into()),
+        )]))
+        .unwrap();
+        assert_eq!(data.strategy, ErasureStrategy::Anonymize);
+        assert_eq!(
+            data.replacement,
+            Some(AttributeValue::String("redacted".into()))
+        );
+
+        let data = PersonalData::from_attribute(&attr(vec![arg(
+            "strategy",
+            AttributeValue::Ident("delete".into()),
+        )]))
+        .unwrap();
+        assert_eq!(data.strategy, ErasureStrategy::Delete);
+
+        assert!(
+            PersonalData::from_attribute(&attr(vec![arg(
+                "strategy",
+                AttributeValue::Ident("shred".into()),
+            )]))
+            .is_err()
+        );
+    }
+}
@@ -3,8 +3,9 @@
 use serde::{Deserialize, Serialize};
 
 use super::{
-    Attribute, Documentation, EnhancedDocumentation, FieldAttributes, FieldType, FieldValidation,
-    Ident, Span, TypeModifier, ValidationRule, ValidationType,
+    Attribute, AttributeValue, CustomAttributes, Documentation, EnhancedDocumentation,
+    FieldAttributes, FieldType, FieldValidation, Ident, PersonalData, Span, StateMachine,
+    TypeModifier, ValidationRule, ValidationType,
 };
 
 /// A field in a model or composite type.
@@ -71,6 +72,11 @@ impl Field {
         self.attributes.iter().find(|a| a.is(name))
     }
 
+    /// Get the custom (plugin-defined) attributes.
+    pub fn custom_attributes(&self) -> CustomAttributes<'_> {
+        CustomAttributes::new(&self.attributes)
+    }
+
     /// Check if this is a primary key field.
     pub fn is_id(&self) -> bool {
         self.has_attribute("id")
@@ -81,6 +87,41 @@ impl Field {
         self.has_attribute("unique")
     }
 
+    /// Check if this field identifies a data subject (`@subjectId`).
+    pub fn is_subject_id(&self) -> bool {
+        self.has_attribute("subjectId")
+    }
+
+    /// Check if this field has per-locale translations (`@translatable`).
+    pub fn is_translatable(&self) -> bool {
+        self.has_attribute("translatable")
+    }
+
+    /// Check if this field is computed rather than stored (`@virtual`).
+    pub fn is_virtual(&self) -> bool {
+        self.has_attribute("virtual")
+    }
+
+    /// Get the SQL expression of a computed field (`@virtual(expr: "...")`).
+    pub fn virtual_expr(&self) -> Option<&str> {
+        let attr = self.get_attribute("virtual")?;
+        attr.get_arg("expr")
+            .or_else(|| attr.first_arg())
+            .and_then(|v| v.as_string())
+    }
+
+    /// Get the personal data annotation (from `@personalData`), if present and valid.
+    pub fn personal_data(&self) -> Option<PersonalData> {
+        self.get_attribute("personalData")
+            .and_then(|a| PersonalData::from_attribute(a).ok())
+    }
+
+    /// Get the allowed transitions (from `@stateMachine`), if present and valid.
+    pub fn state_machine(&self) -> Option<StateMachine> {
+        self.get_attribute("stateMachine")
+            .and_then(|a| StateMachine::from_attribute(a).ok())
+    }
+
     /// Check if this is a relation field.
     pub fn is_relation(&self) -> bool {
         self.field_type.is_relation() || self.has_attribute("relation")
@@ -156,6 +197,11 @@ impl Field {
 
                     attrs.relation = Some(rel);
                 }
+                // Native type written as @db.Geometry(Point, 4326)
+                _ if attr.namespace() == Some("db") => {
+                    let args = attr.args.iter().map(|arg| arg.value.clone()).collect();
+                    attrs.native_type = Some(super::NativeType::new(attr.local_name(), args));
+                }
                 _ => {}
             }
         }
@@ -163,6 +209,31 @@ impl Field {
         attrs
     }
 
+    /// Get the shape and SRID of a `Geometry` or `Geography` field, from its
+    /// `@db.Geometry(Point, 4326)` native type.
+    ///
+    /// Returns `None` for other fields, and for spatial fields without a
+    /// valid native type, which hold any shape in any reference system.
+    pub fn spatial_type(&self) -> Option<super::SpatialType> {
+        let FieldType::Scalar(scalar) = &self.field_type else {
+            return None;
+        };
+        let native = self.extract_attributes().native_type?;
+        if !scalar.is_spatial() || native.name != scalar.as_str() {
+            return None;
+        }
+
+        let kind = match native.args.first() {
+            Some(arg) => super::GeometryKind::from_str(arg.as_ident()?)?,
+            None => super::GeometryKind::Geometry,
+        };
+        let srid = match native.args.get(1) {
+            Some(arg) => Some(i32::try_from(arg.as_int()?).ok()?),
+            None => None,
+        };
+        Some(super::SpatialType { kind, srid })
+    }
+
     /// Set documentation.
     pub fn with_documentation(mut self, doc: Documentation) -> Self {
         self.documentation = Some(doc);
@@ -211,6 +282,8 @@ impl Field {
     /// - `@validate.email`
     /// - `@validate.minLength(5)`
     /// - `@validate.range(0, 100)`
+    /// - `@length(min: 3, max: 120)`, `@range(min: 0)`, `@regex("^[a-z]+$")`
+    ///   and `@email`
     pub fn extract_validation_from_attributes(&mut self) {
         for attr in &self.attributes {
             let attr_name = attr.name();
@@ -227,10 +300,48 @@ impl Field {
                         self.validation.add_rule(rule);
                     }
                 }
+            } else if let Some(rule) = self.shorthand_validation(attr) {
+                self.validation.add_rule(rule);
             }
         }
     }
 
+    /// Parse a shorthand validation attribute: `@email`, `@regex(pattern)`,
+    /// `@length(min, max)` or `@range(min, max)`.
+    ///
+    /// Bounds are given by name or by position; `@length(max: 120)` checks
+    /// only the upper bound.
+    pub(crate) fn shorthand_validation(&self, attr: &Attribute) -> Option<ValidationRule> {
+        let rule_type = match attr.name() {
+            "email" => ValidationType::Email,
+            "regex" => ValidationType::Regex(attr.first_arg()?.as_string()?.to_string()),
+            "length" => match bounds(attr) {
+                (Some(min), Some(max)) => ValidationType::Length {
+                    min: usize::try_from(min.as_int()?).ok()?,
+                    max: usize::try_from(max.as_int()?).ok()?,
+                },
+                (Some(min), None) => {
+                    ValidationType::MinLength(usize::try_from(min.as_int()?).ok()?)
+                }
+                (None, Some(max)) => {
+                    ValidationType::MaxLength(usize::try_from(max.as_int()?).ok()?)
+                }
+                (None, None) => return None,
+            },
+            "range" => match bounds(attr) {
+                (Some(min), Some(max)) => ValidationType::Range {
+                    min: min.as_float()?,
+                    max: max.as_float()?,
+                },
+                (Some(min), None) => ValidationType::Min(min.as_float()?),
+                (None, Some(max)) => ValidationType::Max(max.as_float()?),
+                (None, None) => return None,
+            },
+            _ => return None,
+        };
+        Some(ValidationRule::new(rule_type, attr.span))
+    }
+
     /// Parse a single @validate.* attribute.
     fn parse_validate_attribute(
         &self,
@@ -296,16 +407,14 @@ impl Field {
                 let n = attr.first_arg()?.as_int()? as usize;
                 ValidationType::MaxLength(n)
             }
-            "length" => {
-                let args = &attr.args;
-                if args.len() >= 2 {
-                    let min = args[0].value.as_int()? as usize;
-                    let max = args[1].value.as_int()? as usize;
+            "length" => match bounds(attr) {
+                (Some(min), Some(max)) => {
+                    let min = min.as_int()? as usize;
+                    let max = max.as_int()? as usize;
                     ValidationType::Length { min, max }
-                } else {
-                    return None;
                 }
-            }
+                _ => return None,
+            },
             "min" => {
                 let n = attr
                     .first_arg()?
@@ -320,22 +429,13 @@ impl Field {
                     .or_else(|| attr.first_arg()?.as_int().map(|i| i as f64))?;
                 ValidationType::Max(n)
             }
-            "range" => {
-                let args = &attr.args;
-                if args.len() >= 2 {
-                    let min = args[0]
-                        .value
-                        .as_float()
-                        .or_else(|| args[0].value.as_int().map(|i| i as f64))?;
-                    let max = args[1]
-                        .value
-                        .as_float()
-                        .or_else(|| args[1].value.as_int().map(|i| i as f64))?;
-                    ValidationType::Range { min, max }
-                } else {
-                    return None;
-                }
-            }
+            "range" => match bounds(attr) {
+                (Some(min), Some(max)) => ValidationType::Range {
+                    min: min.as_float()?,
+                    max: max.as_float()?,
+                },
+                _ => return None,
+            },
             "regex" => {
                 let pattern = attr.first_arg()?.as_string()?.to_string();
                 ValidationType::Regex(pattern)
@@ -498,6 +598,16 @@ impl Field {
     }
 }
 
+/// The `min` and `max` arguments of a bounds attribute, named or positional.
+fn bounds(attr: &Attribute) -> (Option<&AttributeValue>, Option<&AttributeValue>) {
+    if attr.args.iter().any(|arg| arg.name.is_some()) {
+        (attr.get_arg("min"), attr.get_arg("max"))
+    } else {
+        let arg = |i: usize| attr.args.get(i).map(|arg| &arg.value);
+        (arg(0), arg(1))
+    }
+}
+
 impl std::fmt::Display for Field {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)?;
@@ -525,7 +635,9 @@ impl std::fmt::Display for Field {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::{AttributeArg, AttributeValue, ReferentialAction, ScalarType};
+    use crate::ast::{
+        AttributeArg, AttributeValue, GeometryKind, ReferentialAction, ScalarType, SpatialType,
+    };
 
     fn make_span() -> Span {
         Span::new(0, 10)
@@ -958,6 +1070,34 @@ mod tests {
         assert_eq!(nt.args.len(), 1);
     }
 
+    #[test]
+    fn test_extract_attributes_native_type_namespaced() {
+        let mut field = make_field(
+            "location",
+            FieldType::Scalar(ScalarType::Geometry),
+            TypeModifier::Required,
+        );
+        field.attributes.push(Attribute::new(
+            Ident::new("db.Geometry", make_span()),
+            vec![
+                AttributeArg::positional(AttributeValue::Ident("Point".into()), make_span()),
+                AttributeArg::positional(AttributeValue::Int(4326), make_span()),
+            ],
+            make_span(),
+        ));
+
+        let nt = field.extract_attributes().native_type.unwrap();
+        assert_eq!(nt.name.as_str(), "Geometry");
+        assert_eq!(nt.args.len(), 2);
+        assert_eq!(
+            field.spatial_type(),
+            Some(SpatialType {
+                kind: GeometryKind::Point,
+                srid: Some(4326),
+            })
+        );
+    }
+
     #[test]
     fn test_extract_attributes_relation() {
         let mut field = make_field(
@@ -2147,6 +2287,37 @@ mod tests {
         assert!(field.has_validation());
     }
 
+    #[test]
+    fn test_extract_shorthand_validation() {
+        let mut field = make_field(
+            "title",
+            FieldType::Scalar(ScalarType::String),
+            TypeModifier::Required,
+        );
+        field.attributes.push(Attribute::new(
+            Ident::new("length", make_span()),
+            vec![AttributeArg::named(
+                Ident::new("max", make_span()),
+                AttributeValue::Int(120),
+                make_span(),
+            )],
+            make_span(),
+        ));
+        field.attributes.push(make_attribute("email"));
+
+        field.extract_validation_from_attributes();
+        let rules: Vec<_> = field
+            .validation
+            .rules
+            .iter()
+            .map(|r| &r.rule_type)
+            .collect();
+        assert_eq!(
+            rules,
+            vec![&ValidationType::MaxLength(120), &ValidationType::Email]
+        );
+    }
+
     // ==================== Comprehensive validate() attribute tests ====================
 
     #[test]
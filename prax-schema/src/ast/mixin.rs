@@ -0,0 +1,70 @@
+//! Reusable field groups declared with `mixin`.
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::{Attribute, Documentation, Field, Ident, Span};
+
+/// A group of fields and model attributes shared by several models.
+///
+/// ```prax
+/// mixin Timestamps {
+///     createdAt DateTime @default(now())
+///     updatedAt DateTime @updated_at
+/// }
+///
+/// model Post with Timestamps {
+///     id Int @id @auto
+/// }
+/// ```
+///
+/// Models using a mixin get copies of its fields and attributes when the
+/// schema is parsed, so later stages see them as ordinary fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mixin {
+    /// Mixin name.
+    pub name: Ident,
+    /// Fields added to each model using the mixin.
+    pub fields: IndexMap<SmolStr, Field>,
+    /// Model-level attributes added to each model using the mixin.
+    pub attributes: Vec<Attribute>,
+    /// Documentation comment.
+    pub documentation: Option<Documentation>,
+    /// Source location.
+    pub span: Span,
+}
+
+impl Mixin {
+    /// Create a new mixin.
+    pub fn new(name: Ident, span: Span) -> Self {
+        Self {
+            name,
+            fields: IndexMap::new(),
+            attributes: vec![],
+            documentation: None,
+            span,
+        }
+    }
+
+    /// Get the mixin name as a string.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Add a field to the mixin.
+    pub fn add_field(&mut self, field: Field) {
+        self.fields.insert(field.name.name.clone(), field);
+    }
+
+    /// Get a field by name.
+    pub fn get_field(&self, name: &str) -> Option<&Field> {
+        self.fields.get(name)
+    }
+
+    /// Set documentation.
+    pub fn with_documentation(mut self, doc: Documentation) -> Self {
+        self.documentation = Some(doc);
+        self
+    }
+}
@@ -4,24 +4,34 @@
 
 mod attribute;
 mod datasource;
+mod erasure;
 mod field;
 mod graphql;
+mod mixin;
 mod model;
 mod policy;
 mod relation;
+mod retention;
 mod schema;
 mod server_group;
+mod state_machine;
+mod tree;
 mod types;
 mod validation;
 
 pub use attribute::*;
 pub use datasource::*;
+pub use erasure::*;
 pub use field::*;
 pub use graphql::*;
+pub use mixin::*;
 pub use model::*;
 pub use policy::*;
 pub use relation::*;
+pub use retention::*;
 pub use schema::*;
 pub use server_group::*;
+pub use state_machine::*;
+pub use tree::*;
 pub use types::*;
 pub use validation::*;
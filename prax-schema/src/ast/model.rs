@@ -4,7 +4,10 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 
-use super::{Attribute, Documentation, Field, Ident, Span};
+use super::{
+    Attribute, AttributeValue, CustomAttributes, Documentation, ExclusionConstraint, Field, Ident,
+    Index, RetentionPolicy, Span, Tree,
+};
 
 /// A model definition (maps to a database table).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -15,6 +18,8 @@ pub struct Model {
     pub fields: IndexMap<SmolStr, Field>,
     /// Model-level attributes (prefixed with `@@`).
     pub attributes: Vec<Attribute>,
+    /// Mixins the model uses (`with Timestamps`), in declaration order.
+    pub mixins: Vec<Ident>,
     /// Documentation comment.
     pub documentation: Option<Documentation>,
     /// Source location.
@@ -28,6 +33,7 @@ impl Model {
             name,
             fields: IndexMap::new(),
             attributes: vec![],
+            mixins: vec![],
             documentation: None,
             span,
         }
@@ -73,6 +79,11 @@ impl Model {
         self.attributes.iter().find(|a| a.is(name))
     }
 
+    /// Get the custom (plugin-defined) attributes.
+    pub fn custom_attributes(&self) -> CustomAttributes<'_> {
+        CustomAttributes::new(&self.attributes)
+    }
+
     /// Get the database table name (from `@@map` or model name).
     pub fn table_name(&self) -> &str {
         self.get_attribute("map")
@@ -81,6 +92,141 @@ impl Model {
             .unwrap_or_else(|| self.name())
     }
 
+    /// Get the database schema (namespace) the table lives in (from `@@schema`).
+    pub fn schema_name(&self) -> Option<&str> {
+        self.get_attribute("schema")
+            .and_then(|a| a.first_arg())
+            .and_then(|v| v.as_string())
+    }
+
+    /// Get the table name qualified with its schema (`accounting.invoices`),
+    /// or the bare table name when no `@@schema` is declared.
+    pub fn qualified_table_name(&self) -> String {
+        match self.schema_name() {
+            Some(schema) => format!("{}.{}", schema, self.table_name()),
+            None => self.table_name().to_string(),
+        }
+    }
+
+    /// Get the retention policy (from `@@retention`), if declared and valid.
+    pub fn retention_policy(&self) -> Option<RetentionPolicy> {
+        self.get_attribute("retention")
+            .and_then(|a| RetentionPolicy::from_attribute(a).ok())
+    }
+
+    /// Get the field rows expire at (from `@@ttl(field: expiresAt)`), if declared.
+    pub fn ttl_field(&self) -> Option<&Field> {
+        let attr = self.get_attribute("ttl")?;
+        let name = match attr.get_arg("field")? {
+            AttributeValue::Ident(name) | AttributeValue::FieldRef(name) => name.as_str(),
+            AttributeValue::String(name) => name.as_str(),
+            _ => return None,
+        };
+        self.fields.get(name)
+    }
+
+    /// Check if the model keeps a history of row versions (`@@versioned`).
+    pub fn is_versioned(&self) -> bool {
+        self.has_attribute("versioned")
+    }
+
+    /// Get the history table name for a `@@versioned` model.
+    ///
+    /// Defaults to `<table>_history`, overridable with `@@versioned(table: "...")`.
+    pub fn history_table_name(&self) -> Option<String> {
+        let attr = self.get_attribute("versioned")?;
+        Some(
+            attr.get_arg("table")
+                .and_then(|v| v.as_string())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}_history", self.table_name())),
+        )
+    }
+
+    /// Check if changes to the model's rows are written to the audit log
+    /// (`@@audited`).
+    pub fn is_audited(&self) -> bool {
+        self.has_attribute("audited")
+    }
+
+    /// Get the fields left out of audit log images, as in
+    /// `@@audited(exclude: [passwordHash])`.
+    pub fn audit_excluded_fields(&self) -> Vec<&Field> {
+        match self
+            .get_attribute("audited")
+            .and_then(|a| a.get_arg("exclude"))
+        {
+            Some(AttributeValue::FieldRefList(names)) => names
+                .iter()
+                .filter_map(|name| self.fields.get(name.as_str()))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the fields with per-locale translations (`@translatable`).
+    pub fn translatable_fields(&self) -> Vec<&Field> {
+        self.fields
+            .values()
+            .filter(|f| f.is_translatable())
+            .collect()
+    }
+
+    /// Get the translation table name, if any field is `@translatable`.
+    ///
+    /// Defaults to `<Model>Translation`, or `<table>_translations` for a
+    /// model mapped with `@@map`.
+    pub fn translation_table_name(&self) -> Option<String> {
+        if !self.fields.values().any(|f| f.is_translatable()) {
+            return None;
+        }
+        Some(if self.has_attribute("map") {
+            format!("{}_translations", self.table_name())
+        } else {
+            format!("{}Translation", self.name())
+        })
+    }
+
+    /// Get the hierarchy (from `@@tree`), if declared and valid.
+    pub fn tree(&self) -> Option<Tree> {
+        self.get_attribute("tree")
+            .and_then(|a| Tree::from_attribute(a).ok())
+    }
+
+    /// Get the indexes declared with `@@index`, skipping invalid ones.
+    pub fn indexes(&self) -> Vec<Index> {
+        self.attributes
+            .iter()
+            .filter(|a| a.is("index"))
+            .filter_map(|a| Index::from_attribute(a).ok())
+            .collect()
+    }
+
+    /// Get the unique constraints declared with `@@unique`, skipping
+    /// invalid ones.
+    pub fn unique_constraints(&self) -> Vec<Index> {
+        self.attributes
+            .iter()
+            .filter(|a| a.is("unique"))
+            .filter_map(|a| Index::from_attribute(a).ok())
+            .collect()
+    }
+
+    /// Get the exclusion constraints declared with `@@exclude`, skipping
+    /// invalid ones.
+    pub fn exclusion_constraints(&self) -> Vec<ExclusionConstraint> {
+        self.attributes
+            .iter()
+            .filter(|a| a.is("exclude"))
+            .filter_map(|a| ExclusionConstraint::from_attribute(a).ok())
+            .collect()
+    }
+
+    /// Get the field identifying a data subject (`@subjectId`), if any.
+    pub fn subject_id_field(&self) -> Option<&Field> {
+        self.fields.values().find(|f| f.is_subject_id())
+    }
+
     /// Set documentation.
     pub fn with_documentation(mut self, doc: Documentation) -> Self {
         self.documentation = Some(doc);
@@ -140,6 +286,11 @@ impl Enum {
             .unwrap_or_else(|| self.name())
     }
 
+    /// Get the custom (plugin-defined) attributes.
+    pub fn custom_attributes(&self) -> CustomAttributes<'_> {
+        CustomAttributes::new(&self.attributes)
+    }
+
     /// Set documentation.
     pub fn with_documentation(mut self, doc: Documentation) -> Self {
         self.documentation = Some(doc);
@@ -303,6 +454,11 @@ impl View {
             .and_then(|v| v.as_string())
     }
 
+    /// Get the custom (plugin-defined) attributes.
+    pub fn custom_attributes(&self) -> CustomAttributes<'_> {
+        CustomAttributes::new(&self.attributes)
+    }
+
     /// Set documentation.
     pub fn with_documentation(mut self, doc: Documentation) -> Self {
         self.documentation = Some(doc);
@@ -532,6 +688,23 @@ mod tests {
         assert_eq!(model.table_name(), "app_users");
     }
 
+    #[test]
+    fn test_model_schema_name() {
+        let mut model = Model::new(make_ident("Invoice"), make_span());
+        assert_eq!(model.schema_name(), None);
+        assert_eq!(model.qualified_table_name(), "Invoice");
+
+        model
+            .attributes
+            .push(make_attribute_with_string("schema", "accounting"));
+        model
+            .attributes
+            .push(make_attribute_with_string("map", "invoices"));
+
+        assert_eq!(model.schema_name(), Some("accounting"));
+        assert_eq!(model.qualified_table_name(), "accounting.invoices");
+    }
+
     #[test]
     fn test_model_with_documentation() {
         let model = Model::new(make_ident("User"), make_span())
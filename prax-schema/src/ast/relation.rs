@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 
-use super::ReferentialAction;
+use super::{Attribute, AttributeValue, DatabaseProvider, ReferentialAction};
 
 /// The type of relation between two models.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -167,6 +167,12 @@ pub struct Index {
     pub hnsw_ef_construction: Option<u32>,
     /// IVFFlat lists parameter (number of inverted lists, default 100).
     pub ivfflat_lists: Option<u32>,
+    /// Whether NULLs conflict with each other (unique constraints,
+    /// PostgreSQL 15+).
+    pub nulls_not_distinct: bool,
+    /// When the unique constraint is checked (PostgreSQL); `None` checks
+    /// every statement immediately.
+    pub deferrable: Option<Deferrable>,
 }
 
 impl Index {
@@ -181,6 +187,8 @@ impl Index {
             hnsw_m: None,
             hnsw_ef_construction: None,
             ivfflat_lists: None,
+            nulls_not_distinct: false,
+            deferrable: None,
         }
     }
 
@@ -195,6 +203,8 @@ impl Index {
             hnsw_m: None,
             hnsw_ef_construction: None,
             ivfflat_lists: None,
+            nulls_not_distinct: false,
+            deferrable: None,
         }
     }
 
@@ -234,12 +244,186 @@ impl Index {
         self
     }
 
+    /// Treat NULLs as equal, so a unique constraint allows only one.
+    pub fn nulls_not_distinct(mut self) -> Self {
+        self.nulls_not_distinct = true;
+        self
+    }
+
+    /// Make the unique constraint deferrable.
+    pub fn with_deferrable(mut self, deferrable: Deferrable) -> Self {
+        self.deferrable = Some(deferrable);
+        self
+    }
+
     /// Check if this is a vector index.
     pub fn is_vector_index(&self) -> bool {
         self.index_type
             .as_ref()
             .is_some_and(|t| t.is_vector_index())
     }
+
+    /// Parse an `@@index` attribute.
+    ///
+    /// ```text
+    /// @@index([data], type: Gin, ops: jsonb_path_ops)
+    /// @@index([title, body], type: Gin, ops: [gin_trgm_ops, gin_trgm_ops])
+    /// @@index([embedding], type: Hnsw, ops: cosine, m: 16, efConstruction: 64)
+    /// ```
+    ///
+    /// `ops` is one operator class for every column or a list with one per
+    /// column; PascalCase names such as `JsonbPathOps` are accepted. For HNSW
+    /// and IVFFlat indexes it names the distance (`cosine`, `l2`, `ip`).
+    /// `name` or `map` sets the index name.
+    ///
+    /// An `@@unique` attribute parses to a unique index, which also takes
+    /// the constraint options:
+    ///
+    /// ```text
+    /// @@unique([email, deletedAt], nullsNotDistinct: true)
+    /// @@unique([position], deferrable: InitiallyDeferred)
+    /// ```
+    pub fn from_attribute(attr: &Attribute) -> Result<Self, String> {
+        let fields = match attr.first_arg() {
+            Some(AttributeValue::FieldRefList(fields)) if !fields.is_empty() => fields,
+            _ => {
+                return Err(format!(
+                    "expected a list of fields, e.g. @@{}([email])",
+                    attr.name()
+                ));
+            }
+        };
+        let fields = fields.iter().map(|f| IndexField::asc(f.clone())).collect();
+        let mut index = if attr.is("unique") {
+            Self::unique(fields)
+        } else {
+            Self::new(fields)
+        };
+
+        if let Some(name) = attr.get_arg("name").or_else(|| attr.get_arg("map")) {
+            let name = name
+                .as_string()
+                .ok_or_else(|| "`name` must be a string".to_string())?;
+            index.name = Some(name.into());
+        }
+
+        if let Some(value) = attr.get_arg("type") {
+            let name = value.as_ident().or_else(|| value.as_string());
+            let index_type = name.and_then(IndexType::from_str).ok_or_else(|| {
+                format!(
+                    "unknown index type '{}'; expected one of BTree, Hash, Gin, Gist, Brin, \
+                     SpGist, FullText, Hnsw or IvfFlat",
+                    name.unwrap_or_default()
+                )
+            })?;
+            index.index_type = Some(index_type);
+        }
+
+        if let Some(value) = attr.get_arg("ops") {
+            let ops: Vec<&str> = match value {
+                AttributeValue::Ident(name) | AttributeValue::FieldRef(name) => vec![name],
+                AttributeValue::String(name) => vec![name],
+                AttributeValue::FieldRefList(names) => names.iter().map(SmolStr::as_str).collect(),
+                AttributeValue::Array(values) => values
+                    .iter()
+                    .map(|v| v.as_ident().or_else(|| v.as_string()))
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| "`ops` must list operator class names".to_string())?,
+                _ => return Err("`ops` must be an operator class name or a list".to_string()),
+            };
+
+            if index.is_vector_index() {
+                let [ops] = ops.as_slice() else {
+                    return Err("vector indexes take a single `ops` distance".to_string());
+                };
+                index.vector_ops = Some(VectorOps::from_str(ops).ok_or_else(|| {
+                    format!(
+                        "unknown vector distance '{}'; expected cosine, l2 or ip",
+                        ops
+                    )
+                })?);
+            } else if ops.len() == 1 {
+                for field in &mut index.fields {
+                    field.ops = Some(ops_class_name(ops[0]));
+                }
+            } else if ops.len() == index.fields.len() {
+                for (field, ops) in index.fields.iter_mut().zip(ops) {
+                    field.ops = Some(ops_class_name(ops));
+                }
+            } else {
+                return Err(format!(
+                    "`ops` lists {} operator classes for {} fields",
+                    ops.len(),
+                    index.fields.len()
+                ));
+            }
+        }
+
+        for (arg, target) in [
+            ("m", &mut index.hnsw_m),
+            ("efConstruction", &mut index.hnsw_ef_construction),
+            ("lists", &mut index.ivfflat_lists),
+        ] {
+            if let Some(value) = attr.get_arg(arg) {
+                let value = value
+                    .as_int()
+                    .and_then(|v| u32::try_from(v).ok())
+                    .ok_or_else(|| format!("`{}` must be a positive integer", arg))?;
+                *target = Some(value);
+            }
+        }
+
+        for arg in ["nullsNotDistinct", "deferrable"] {
+            if !index.is_unique && attr.get_arg(arg).is_some() {
+                return Err(format!("`{}` only applies to @@unique", arg));
+            }
+        }
+
+        if let Some(value) = attr.get_arg("nullsNotDistinct") {
+            index.nulls_not_distinct = value
+                .as_bool()
+                .ok_or_else(|| "`nullsNotDistinct` must be true or false".to_string())?;
+        }
+
+        if let Some(value) = attr.get_arg("deferrable") {
+            index.deferrable = match value {
+                AttributeValue::Boolean(false) => None,
+                AttributeValue::Boolean(true) => Some(Deferrable::InitiallyImmediate),
+                _ => {
+                    let name = value.as_ident().or_else(|| value.as_string());
+                    Some(name.and_then(Deferrable::from_str).ok_or_else(|| {
+                        format!(
+                            "unknown deferrable mode '{}'; expected InitiallyImmediate or \
+                             InitiallyDeferred",
+                            name.unwrap_or_default()
+                        )
+                    })?)
+                }
+            };
+        }
+
+        Ok(index)
+    }
+}
+
+/// Normalize an operator class name: `JsonbPathOps` becomes
+/// `jsonb_path_ops`, snake_case names are kept.
+fn ops_class_name(name: &str) -> SmolStr {
+    if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return name.into();
+    }
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake.into()
 }
 
 /// A field in an index.
@@ -249,6 +433,8 @@ pub struct IndexField {
     pub name: SmolStr,
     /// Sort order.
     pub sort: SortOrder,
+    /// Operator class (PostgreSQL), e.g. `jsonb_path_ops`.
+    pub ops: Option<SmolStr>,
 }
 
 impl IndexField {
@@ -257,6 +443,7 @@ impl IndexField {
         Self {
             name: name.into(),
             sort: SortOrder::Asc,
+            ops: None,
         }
     }
 
@@ -265,8 +452,15 @@ impl IndexField {
         Self {
             name: name.into(),
             sort: SortOrder::Desc,
+            ops: None,
         }
     }
+
+    /// Set the operator class.
+    pub fn with_ops(mut self, ops: impl Into<SmolStr>) -> Self {
+        self.ops = Some(ops.into());
+        self
+    }
 }
 
 /// Sort order for index fields.
@@ -294,6 +488,8 @@ pub enum IndexType {
     FullText,
     /// BRIN index (PostgreSQL - Block Range Index).
     Brin,
+    /// SP-GiST index (PostgreSQL - space-partitioned GiST).
+    SpGist,
     /// HNSW index for vector similarity search (pgvector).
     Hnsw,
     /// IVFFlat index for vector similarity search (pgvector).
@@ -311,6 +507,7 @@ impl IndexType {
             "gin" => Some(Self::Gin),
             "fulltext" => Some(Self::FullText),
             "brin" => Some(Self::Brin),
+            "spgist" | "sp-gist" => Some(Self::SpGist),
             "hnsw" => Some(Self::Hnsw),
             "ivfflat" => Some(Self::IvfFlat),
             _ => None,
@@ -322,6 +519,38 @@ impl IndexType {
         matches!(self, Self::Hnsw | Self::IvfFlat)
     }
 
+    /// Check if `provider` can build this index type.
+    pub fn is_supported_by(&self, provider: &DatabaseProvider) -> bool {
+        match provider {
+            DatabaseProvider::PostgreSQL => true,
+            DatabaseProvider::MySQL | DatabaseProvider::MongoDB => {
+                matches!(self, Self::BTree | Self::Hash | Self::FullText)
+            }
+            DatabaseProvider::SQLite => matches!(self, Self::BTree),
+        }
+    }
+
+    /// The PostgreSQL index method an operator class belongs to, for
+    /// operator classes tied to a single method. Operator classes shared by
+    /// several methods (`jsonb_ops`, `range_ops`, ...) and unknown ones
+    /// return `None`.
+    pub fn for_ops_class(ops: &str) -> Option<Self> {
+        match ops {
+            "jsonb_path_ops" | "gin_trgm_ops" => Some(Self::Gin),
+            "gist_trgm_ops" => Some(Self::Gist),
+            "text_pattern_ops" | "varchar_pattern_ops" | "bpchar_pattern_ops" => Some(Self::BTree),
+            "quad_point_ops" | "kd_point_ops" => Some(Self::SpGist),
+            _ if ops.ends_with("_minmax_ops")
+                || ops.ends_with("_minmax_multi_ops")
+                || ops.ends_with("_bloom_ops")
+                || ops.ends_with("_inclusion_ops") =>
+            {
+                Some(Self::Brin)
+            }
+            _ => None,
+        }
+    }
+
     /// Get the SQL name for this index type.
     pub fn as_sql(&self) -> &'static str {
         match self {
@@ -331,12 +560,165 @@ impl IndexType {
             Self::Gin => "GIN",
             Self::FullText => "GIN", // Full-text uses GIN in PostgreSQL
             Self::Brin => "BRIN",
+            Self::SpGist => "SPGIST",
             Self::Hnsw => "hnsw",
             Self::IvfFlat => "ivfflat",
         }
     }
 }
 
+/// When a deferrable unique constraint is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Deferrable {
+    /// Checked after each statement unless deferred with `SET CONSTRAINTS`.
+    InitiallyImmediate,
+    /// Checked at commit, so rows can swap values within a transaction.
+    InitiallyDeferred,
+}
+
+impl Deferrable {
+    /// Parse from string.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "initiallyimmediate" | "initially_immediate" | "immediate" => {
+                Some(Self::InitiallyImmediate)
+            }
+            "initiallydeferred" | "initially_deferred" | "deferred" => {
+                Some(Self::InitiallyDeferred)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the SQL clause following the constraint definition.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::InitiallyImmediate => "DEFERRABLE INITIALLY IMMEDIATE",
+            Self::InitiallyDeferred => "DEFERRABLE INITIALLY DEFERRED",
+        }
+    }
+}
+
+/// An exclusion constraint (PostgreSQL), declared with `@@exclude`.
+///
+/// Two rows conflict when every element's operator returns true for their
+/// values, e.g. the same room with overlapping booking periods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExclusionConstraint {
+    /// Constraint name (auto-generated if not specified).
+    pub name: Option<SmolStr>,
+    /// Fields compared, each with its operator.
+    pub elements: Vec<ExclusionElement>,
+    /// Index method enforcing the constraint (GiST by default).
+    pub using: IndexType,
+}
+
+/// A field of an exclusion constraint and the operator comparing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExclusionElement {
+    /// Field name.
+    pub field: SmolStr,
+    /// Operator, e.g. `=` or `&&`.
+    pub operator: SmolStr,
+}
+
+impl ExclusionConstraint {
+    /// Create an exclusion constraint enforced by a GiST index.
+    pub fn new(elements: Vec<ExclusionElement>) -> Self {
+        Self {
+            name: None,
+            elements,
+            using: IndexType::Gist,
+        }
+    }
+
+    /// Parse an `@@exclude` attribute.
+    ///
+    /// ```text
+    /// @@exclude([room, during], using: Gist, with: ["=", "&&"])
+    /// ```
+    ///
+    /// `with` lists one operator per field, or a single operator for every
+    /// field. `using` defaults to `Gist`; `name` or `map` sets the
+    /// constraint name.
+    pub fn from_attribute(attr: &Attribute) -> Result<Self, String> {
+        let fields = match attr.first_arg() {
+            Some(AttributeValue::FieldRefList(fields)) if !fields.is_empty() => fields,
+            _ => {
+                return Err("expected a list of fields, e.g. @@exclude([room, during])".to_string());
+            }
+        };
+
+        let operators: Vec<&str> = match attr.get_arg("with") {
+            Some(AttributeValue::String(op)) => vec![op],
+            Some(AttributeValue::Array(values)) => values
+                .iter()
+                .map(AttributeValue::as_string)
+                .collect::<Option<_>>()
+                .ok_or_else(|| "`with` must list operators as strings".to_string())?,
+            Some(_) => return Err("`with` must be an operator or a list of operators".to_string()),
+            None => return Err("missing `with` operators, e.g. with: [\"=\", \"&&\"]".to_string()),
+        };
+        let operators = match operators.len() {
+            1 => vec![operators[0]; fields.len()],
+            n if n == fields.len() => operators,
+            n => {
+                return Err(format!(
+                    "`with` lists {} operators for {} fields",
+                    n,
+                    fields.len()
+                ));
+            }
+        };
+        // Operators are written into the DDL as-is
+        if let Some(op) = operators
+            .iter()
+            .find(|op| op.is_empty() || !op.chars().all(|c| "+-*/<>=~!@#%^&|`?".contains(c)))
+        {
+            return Err(format!("invalid operator '{}'", op));
+        }
+
+        let elements = fields
+            .iter()
+            .zip(operators)
+            .map(|(field, op)| ExclusionElement {
+                field: field.clone(),
+                operator: op.into(),
+            })
+            .collect();
+        let mut constraint = Self::new(elements);
+
+        if let Some(name) = attr.get_arg("name").or_else(|| attr.get_arg("map")) {
+            let name = name
+                .as_string()
+                .ok_or_else(|| "`name` must be a string".to_string())?;
+            constraint.name = Some(name.into());
+        }
+
+        if let Some(value) = attr.get_arg("using") {
+            let name = value.as_ident().or_else(|| value.as_string());
+            constraint.using = name
+                .and_then(IndexType::from_str)
+                .filter(|t| {
+                    matches!(
+                        t,
+                        IndexType::Gist | IndexType::SpGist | IndexType::BTree | IndexType::Hash
+                    )
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "index method '{}' can't enforce exclusion constraints; expected Gist, \
+                         SpGist, BTree or Hash",
+                        name.unwrap_or_default()
+                    )
+                })?;
+        }
+
+        Ok(constraint)
+    }
+}
+
 /// Vector distance operation for similarity search.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum VectorOps {
@@ -691,10 +1073,201 @@ mod tests {
         assert_ne!(IndexType::BTree, IndexType::Hash);
     }
 
+    #[test]
+    fn test_index_from_attribute() {
+        use crate::ast::{AttributeArg, Ident, Span};
+
+        let span = Span::new(0, 0);
+        let attr = Attribute::new(
+            Ident::new("index", span),
+            vec![
+                AttributeArg::positional(
+                    AttributeValue::FieldRefList(vec!["title".into(), "body".into()]),
+                    span,
+                ),
+                AttributeArg::named(
+                    Ident::new("type", span),
+                    AttributeValue::Ident("Gin".into()),
+                    span,
+                ),
+                AttributeArg::named(
+                    Ident::new("ops", span),
+                    AttributeValue::Ident("GinTrgmOps".into()),
+                    span,
+                ),
+            ],
+            span,
+        );
+
+        let idx = Index::from_attribute(&attr).unwrap();
+        assert_eq!(idx.index_type, Some(IndexType::Gin));
+        assert_eq!(
+            idx.fields,
+            vec![
+                IndexField::asc("title").with_ops("gin_trgm_ops"),
+                IndexField::asc("body").with_ops("gin_trgm_ops"),
+            ]
+        );
+
+        let mut attr = attr;
+        attr.args[2].value = AttributeValue::FieldRefList(vec!["gin_trgm_ops".into()]);
+        assert!(Index::from_attribute(&attr).is_ok());
+
+        attr.args[1].value = AttributeValue::Ident("Bitmap".into());
+        assert!(Index::from_attribute(&attr).unwrap_err().contains("Bitmap"));
+    }
+
+    #[test]
+    fn test_unique_from_attribute() {
+        use crate::ast::{AttributeArg, Ident, Span};
+
+        let span = Span::new(0, 0);
+        let attr = Attribute::new(
+            Ident::new("unique", span),
+            vec![
+                AttributeArg::positional(
+                    AttributeValue::FieldRefList(vec!["email".into(), "deletedAt".into()]),
+                    span,
+                ),
+                AttributeArg::named(
+                    Ident::new("nullsNotDistinct", span),
+                    AttributeValue::Boolean(true),
+                    span,
+                ),
+                AttributeArg::named(
+                    Ident::new("deferrable", span),
+                    AttributeValue::Ident("InitiallyDeferred".into()),
+                    span,
+                ),
+            ],
+            span,
+        );
+
+        let idx = Index::from_attribute(&attr).unwrap();
+        assert!(idx.is_unique);
+        assert!(idx.nulls_not_distinct);
+        assert_eq!(idx.deferrable, Some(Deferrable::InitiallyDeferred));
+        assert_eq!(
+            Deferrable::InitiallyDeferred.as_sql(),
+            "DEFERRABLE INITIALLY DEFERRED"
+        );
+
+        let mut attr = attr;
+        attr.args[2].value = AttributeValue::Boolean(true);
+        assert_eq!(
+            Index::from_attribute(&attr).unwrap().deferrable,
+            Some(Deferrable::InitiallyImmediate)
+        );
+
+        attr.args[2].value = AttributeValue::Ident("Later".into());
+        assert!(Index::from_attribute(&attr).unwrap_err().contains("Later"));
+
+        attr.name = Ident::new("index", span);
+        attr.args.truncate(2);
+        assert_eq!(
+            Index::from_attribute(&attr).unwrap_err(),
+            "`nullsNotDistinct` only applies to @@unique"
+        );
+    }
+
+    #[test]
+    fn test_exclusion_from_attribute() {
+        use crate::ast::{AttributeArg, Ident, Span};
+
+        let span = Span::new(0, 0);
+        let attr = Attribute::new(
+            Ident::new("exclude", span),
+            vec![
+                AttributeArg::positional(
+                    AttributeValue::FieldRefList(vec!["room".into(), "during".into()]),
+                    span,
+                ),
+                AttributeArg::named(
+                    Ident::new("using", span),
+                    AttributeValue::Ident("Gist".into()),
+                    span,
+                ),
+                AttributeArg::named(
+                    Ident::new("with", span),
+                    AttributeValue::Array(vec![
+                        AttributeValue::String("=".into()),
+                        AttributeValue::String("&&".into()),
+                    ]),
+                    span,
+                ),
+            ],
+            span,
+        );
+
+        let constraint = ExclusionConstraint::from_attribute(&attr).unwrap();
+        assert_eq!(constraint.using, IndexType::Gist);
+        assert_eq!(
+            constraint.elements,
+            vec![
+                ExclusionElement {
+                    field: "room".into(),
+                    operator: "=".into(),
+                },
+                ExclusionElement {
+                    field: "during".into(),
+                    operator: "&&".into(),
+                },
+            ]
+        );
+
+        let mut attr = attr;
+        attr.args[2].value = AttributeValue::Array(vec![AttributeValue::String("&&".into())]);
+        let constraint = ExclusionConstraint::from_attribute(&attr).unwrap();
+        assert!(constraint.elements.iter().all(|e| e.operator == "&&"));
+
+        attr.args[2].value = AttributeValue::String("=); DROP TABLE x; --".into());
+        assert!(
+            ExclusionConstraint::from_attribute(&attr)
+                .unwrap_err()
+                .starts_with("invalid operator")
+        );
+
+        attr.args[2].value = AttributeValue::String("&&".into());
+        attr.args[1].value = AttributeValue::Ident("Gin".into());
+        assert!(
+            ExclusionConstraint::from_attribute(&attr)
+                .unwrap_err()
+                .contains("'Gin'")
+        );
+
+        attr.args.truncate(2);
+        assert!(
+            ExclusionConstraint::from_attribute(&attr)
+                .unwrap_err()
+                .contains("missing `with`")
+        );
+    }
+
+    #[test]
+    fn test_index_type_for_ops_class() {
+        assert_eq!(
+            IndexType::for_ops_class("jsonb_path_ops"),
+            Some(IndexType::Gin)
+        );
+        assert_eq!(
+            IndexType::for_ops_class("gist_trgm_ops"),
+            Some(IndexType::Gist)
+        );
+        assert_eq!(
+            IndexType::for_ops_class("int8_minmax_ops"),
+            Some(IndexType::Brin)
+        );
+        assert_eq!(IndexType::for_ops_class("jsonb_ops"), None);
+        assert!(IndexType::SpGist.is_supported_by(&DatabaseProvider::PostgreSQL));
+        assert!(!IndexType::Gin.is_supported_by(&DatabaseProvider::MySQL));
+        assert!(!IndexType::Hash.is_supported_by(&DatabaseProvider::SQLite));
+    }
+
     #[test]
     fn test_index_type_from_str_brin() {
         assert_eq!(IndexType::from_str("brin"), Some(IndexType::Brin));
         assert_eq!(IndexType::from_str("BRIN"), Some(IndexType::Brin));
+        assert_eq!(IndexType::from_str("SpGist"), Some(IndexType::SpGist));
     }
 
     #[test]
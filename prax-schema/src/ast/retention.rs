@@ -0,0 +1,147 @@
+//! Data retention policies declared with `@@retention`.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::{Attribute, AttributeValue};
+
+/// What happens to rows past their retention period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RetentionStrategy {
+    /// Delete expired rows.
+    #[default]
+    Delete,
+    /// Move expired rows to an archive before deleting them.
+    Archive,
+}
+
+impl RetentionStrategy {
+    /// Parse a strategy name (`delete` or `archive`).
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "delete" => Some(Self::Delete),
+            "archive" => Some(Self::Archive),
+            _ => None,
+        }
+    }
+
+    /// Get the strategy name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Archive => "archive",
+        }
+    }
+}
+
+/// A retention policy declared on a model.
+///
+/// ```prax
+/// model AuditLog {
+///     id        Int      @id @auto
+///     createdAt DateTime @default(now())
+///
+///     @@retention(field: createdAt, keep: "90d", strategy: archive)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// The timestamp field rows expire by.
+    pub field: SmolStr,
+    /// How long rows are kept.
+    pub keep: Duration,
+    /// What happens to expired rows.
+    pub strategy: RetentionStrategy,
+}
+
+impl RetentionPolicy {
+    /// Read a policy from a `@@retention` attribute.
+    pub fn from_attribute(attr: &Attribute) -> Result<Self, String> {
+        let field = match attr.get_arg("field") {
+            Some(AttributeValue::Ident(name) | AttributeValue::FieldRef(name)) => name.clone(),
+            Some(AttributeValue::String(name)) => SmolStr::new(name),
+            Some(_) => return Err("`field` must be a field name".to_string()),
+            None => return Err("missing `field` argument".to_string()),
+        };
+
+        let keep = match attr.get_arg("keep") {
+            Some(AttributeValue::String(period)) => parse_retention_period(period)
+                .ok_or_else(|| format!("invalid `keep` period '{}'", period))?,
+            Some(_) => return Err("`keep` must be a string such as \"90d\"".to_string()),
+            None => return Err("missing `keep` argument".to_string()),
+        };
+
+        let strategy = match attr.get_arg("strategy") {
+            Some(value) => {
+                let name = value.as_ident().or_else(|| value.as_string());
+                name.and_then(RetentionStrategy::from_str)
+                    .ok_or_else(|| "`strategy` must be `delete` or `archive`".to_string())?
+            }
+            None => RetentionStrategy::default(),
+        };
+
+        Ok(Self {
+            field,
+            keep,
+            strategy,
+        })
+    }
+}
+
+/// Parse a retention period such as `"90d"`, `"12h"` or `"2w"`.
+///
+/// Supported units are `s`, `m` (minutes), `h`, `d` and `w`.
+pub fn parse_retention_period(period: &str) -> Option<Duration> {
+    let period = period.trim();
+    let split = period.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = period.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    amount.checked_mul(seconds).map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retention_period() {
+        assert_eq!(
+            parse_retention_period("90d"),
+            Some(Duration::from_secs(90 * 86_400))
+        );
+        assert_eq!(
+            parse_retention_period("12h"),
+            Some(Duration::from_secs(43_200))
+        );
+        assert_eq!(
+            parse_retention_period("2w"),
+            Some(Duration::from_secs(1_209_600))
+        );
+        assert_eq!(parse_retention_period("90"), None);
+        assert_eq!(parse_retention_period("d"), None);
+        assert_eq!(parse_retention_period("90y"), None);
+    }
+
+    #[test]
+    fn test_retention_strategy() {
+        assert_eq!(
+            RetentionStrategy::from_str("archive"),
+            Some(RetentionStrategy::Archive)
+        );
+        assert_eq!(RetentionStrategy::from_str("truncate"), None);
+        assert_eq!(RetentionStrategy::Delete.as_str(), "delete");
+    }
+}
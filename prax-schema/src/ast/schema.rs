@@ -4,7 +4,10 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 
-use super::{CompositeType, Datasource, Enum, Model, Policy, Relation, ServerGroup, View};
+use super::{
+    Attribute, AttributeValue, CompositeType, CustomAttributes, Datasource, Enum, Field, FieldType,
+    Mixin, Model, Policy, Relation, ServerGroup, View,
+};
 
 /// A complete Prax schema.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
@@ -17,6 +20,8 @@ pub struct Schema {
     pub enums: IndexMap<SmolStr, Enum>,
     /// All composite types in the schema.
     pub types: IndexMap<SmolStr, CompositeType>,
+    /// All mixins in the schema.
+    pub mixins: IndexMap<SmolStr, Mixin>,
     /// All views in the schema.
     pub views: IndexMap<SmolStr, View>,
     /// Server groups for multi-server configurations.
@@ -75,6 +80,11 @@ impl Schema {
         self.types.insert(t.name.name.clone(), t);
     }
 
+    /// Add a mixin to the schema.
+    pub fn add_mixin(&mut self, mixin: Mixin) {
+        self.mixins.insert(mixin.name.name.clone(), mixin);
+    }
+
     /// Add a view to the schema.
     pub fn add_view(&mut self, v: View) {
         self.views.insert(v.name.name.clone(), v);
@@ -115,6 +125,37 @@ impl Schema {
         self.types.get(name)
     }
 
+    /// Get a mixin by name.
+    pub fn get_mixin(&self, name: &str) -> Option<&Mixin> {
+        self.mixins.get(name)
+    }
+
+    /// Copy the fields and attributes of each model's mixins into the model.
+    ///
+    /// Mixin fields follow the model's own fields, and a field declared on the
+    /// model takes precedence over a mixin field of the same name. Unknown
+    /// mixins are left for the validator to report. Applying mixins again
+    /// changes nothing.
+    pub fn apply_mixins(&mut self) {
+        for model in self.models.values_mut() {
+            for name in &model.mixins {
+                let Some(mixin) = self.mixins.get(name.as_str()) else {
+                    continue;
+                };
+                for (field_name, field) in &mixin.fields {
+                    if !model.fields.contains_key(field_name) {
+                        model.fields.insert(field_name.clone(), field.clone());
+                    }
+                }
+                for attr in &mixin.attributes {
+                    if !model.attributes.contains(attr) {
+                        model.attributes.push(attr.clone());
+                    }
+                }
+            }
+        }
+    }
+
     /// Get a view by name.
     pub fn get_view(&self, name: &str) -> Option<&View> {
         self.views.get(name)
@@ -192,13 +233,311 @@ impl Schema {
         self.models.extend(other.models);
         self.enums.extend(other.enums);
         self.types.extend(other.types);
+        self.mixins.extend(other.mixins);
         self.views.extend(other.views);
         self.server_groups.extend(other.server_groups);
         self.policies.extend(other.policies);
         self.raw_sql.extend(other.raw_sql);
+        // Models may use mixins declared in the other schema
+        self.apply_mixins();
+    }
+
+    /// Namespaces of all custom (plugin-defined) attributes in the schema.
+    pub fn custom_attribute_namespaces(&self) -> Vec<&str> {
+        let models = self.models.values().flat_map(|m| {
+            std::iter::once(&m.attributes).chain(m.fields.values().map(|f| &f.attributes))
+        });
+        let enums = self.enums.values().map(|e| &e.attributes);
+        let views = self.views.values().flat_map(|v| {
+            std::iter::once(&v.attributes).chain(v.fields.values().map(|f| &f.attributes))
+        });
+
+        let mut namespaces = Vec::new();
+        for attributes in models.chain(enums).chain(views) {
+            for namespace in CustomAttributes::new(attributes).namespaces() {
+                if !namespaces.contains(&namespace) {
+                    namespaces.push(namespace);
+                }
+            }
+        }
+        namespaces
+    }
+
+    /// Copy custom (plugin-defined) attributes from a previous version of
+    /// this schema.
+    ///
+    /// Used when a schema is regenerated, e.g. by introspection, so that
+    /// attributes such as `@myplugin.searchable` survive. Models are matched
+    /// by table name and fields by column name. Returns the number of
+    /// attributes copied.
+    pub fn preserve_custom_attributes(&mut self, previous: &Schema) -> usize {
+        let mut copied = 0;
+
+        for model in self.models.values_mut() {
+            let Some(old) = previous
+                .models
+                .values()
+                .find(|m| m.table_name() == model.table_name())
+            else {
+                continue;
+            };
+
+            copied += copy_custom(&mut model.attributes, &old.attributes);
+            for field in model.fields.values_mut() {
+                let column = column_name(field);
+                if let Some(old_field) = old.fields.values().find(|f| column_name(f) == column) {
+                    copied += copy_custom(&mut field.attributes, &old_field.attributes);
+                }
+            }
+        }
+
+        for enum_def in self.enums.values_mut() {
+            if let Some(old) = previous
+                .enums
+                .values()
+                .find(|e| e.db_name() == enum_def.db_name())
+            {
+                copied += copy_custom(&mut enum_def.attributes, &old.attributes);
+            }
+        }
+
+        copied
+    }
+
+    /// Restore model and field names, and doc comments, from a previous
+    /// version of this schema.
+    ///
+    /// Introspection derives names from tables and columns, so models and
+    /// fields that were renamed with `@@map`/`@map` come back under their
+    /// database names. Models are matched by table name (ignoring case),
+    /// scalar fields by column name and relation fields by the fields they
+    /// hold. References to renamed models and fields are updated. Returns
+    /// the number of names and doc comments restored.
+    pub fn preserve_names(&mut self, previous: &Schema) -> usize {
+        let mut restored = 0;
+        let mut model_renames = Vec::new();
+        let mut field_renames = Vec::new();
+
+        for model in self.models.values_mut() {
+            let Some(old) = previous
+                .models
+                .values()
+                .find(|m| m.table_name().eq_ignore_ascii_case(model.table_name()))
+            else {
+                continue;
+            };
+
+            if model.documentation.is_none() && old.documentation.is_some() {
+                model.documentation = old.documentation.clone();
+                restored += 1;
+            }
+            let renames = restore_fields(model, old, &mut restored);
+            field_renames.push((model.name.name.clone(), renames));
+            if model.name() != old.name() {
+                model_renames.push((model.name.name.clone(), old));
+            }
+        }
+
+        for (from, old) in model_renames {
+            let to = old.name.name.clone();
+            if self.models.contains_key(&to) {
+                continue;
+            }
+            self.models = std::mem::take(&mut self.models)
+                .into_iter()
+                .map(|(name, mut model)| {
+                    if name != from {
+                        return (name, model);
+                    }
+                    model.name.name = to.clone();
+                    model.attributes.retain(|a| !a.is("map"));
+                    model.attributes.extend(old.get_attribute("map").cloned());
+                    (to.clone(), model)
+                })
+                .collect();
+            for field in self.models.values_mut().flat_map(|m| m.fields.values_mut()) {
+                if field.field_type == FieldType::Model(from.clone()) {
+                    field.field_type = FieldType::Model(to.clone());
+                }
+            }
+            for (model, _) in field_renames.iter_mut().filter(|(m, _)| *m == from) {
+                *model = to.clone();
+            }
+            restored += 1;
+        }
+
+        // Relations to a model reference its renamed fields
+        for (target, renames) in &field_renames {
+            for field in self.models.values_mut().flat_map(|m| m.fields.values_mut()) {
+                if field.field_type == FieldType::Model(target.clone()) {
+                    rename_relation_refs(field, "references", renames);
+                }
+            }
+        }
+
+        for enum_def in self.enums.values_mut() {
+            if enum_def.documentation.is_some() {
+                continue;
+            }
+            if let Some(old) = previous
+                .enums
+                .values()
+                .find(|e| e.db_name() == enum_def.db_name() && e.documentation.is_some())
+            {
+                enum_def.documentation = old.documentation.clone();
+                restored += 1;
+            }
+        }
+
+        restored
+    }
+}
+
+/// Restore field names and doc comments of `model` from `old`.
+///
+/// Returns the fields renamed, after updating the model's own references
+/// to them.
+fn restore_fields(model: &mut Model, old: &Model, restored: &mut usize) -> Vec<(SmolStr, SmolStr)> {
+    let mut matches: Vec<(SmolStr, &Field)> = Vec::new();
+    for field in model.fields.values().filter(|f| !f.is_relation()) {
+        let column = column_name(field);
+        if let Some(old_field) = old
+            .fields
+            .values()
+            .find(|f| !f.is_relation() && column_name(f) == column)
+        {
+            matches.push((field.name.name.clone(), old_field));
+        }
+    }
+    let scalar_renames: Vec<(SmolStr, SmolStr)> = matches
+        .iter()
+        .map(|(name, old_field)| (name.clone(), old_field.name.name.clone()))
+        .collect();
+    for field in model.fields.values().filter(|f| f.is_relation()) {
+        let mut fields = relation_fields(field);
+        fields.iter_mut().for_each(|f| rename(f, &scalar_renames));
+        if let Some(old_field) = old
+            .fields
+            .values()
+            .find(|f| f.is_relation() && !fields.is_empty() && relation_fields(f) == fields)
+        {
+            matches.push((field.name.name.clone(), old_field));
+        }
+    }
+
+    // A field keeping its name blocks restoring that name on another field
+    let candidates: Vec<(SmolStr, SmolStr)> = matches
+        .iter()
+        .filter(|(name, old_field)| name != old_field.name())
+        .map(|(name, old_field)| (name.clone(), old_field.name.name.clone()))
+        .collect();
+    let renames: Vec<(SmolStr, SmolStr)> = candidates
+        .iter()
+        .filter(|(_, to)| {
+            !model.fields.contains_key(to) || candidates.iter().any(|(from, _)| from == to)
+        })
+        .cloned()
+        .collect();
+
+    for (name, old_field) in &matches {
+        let field = &mut model.fields[name];
+        if field.documentation.is_none() && old_field.documentation.is_some() {
+            field.documentation = old_field.documentation.clone();
+            *restored += 1;
+        }
+        if !field.is_relation() && renames.iter().any(|(from, _)| from == name) {
+            field.attributes.retain(|a| !a.is("map"));
+            field
+                .attributes
+                .extend(old_field.get_attribute("map").cloned());
+        }
+    }
+    for field in model.fields.values_mut() {
+        rename_relation_refs(field, "fields", &renames);
+    }
+    for attr in model.attributes.iter_mut() {
+        for arg in attr.args.iter_mut() {
+            rename_refs(&mut arg.value, &renames);
+        }
+    }
+
+    model.fields = std::mem::take(&mut model.fields)
+        .into_iter()
+        .map(|(mut name, mut field)| {
+            rename(&mut name, &renames);
+            field.name.name = name.clone();
+            (name, field)
+        })
+        .collect();
+    *restored += renames.len();
+    renames
+}
+
+/// The `fields` of a field's `@relation`.
+fn relation_fields(field: &Field) -> Vec<SmolStr> {
+    match field
+        .get_attribute("relation")
+        .and_then(|a| a.get_arg("fields"))
+    {
+        Some(AttributeValue::FieldRefList(fields)) => fields.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// Rename field references in the `arg` argument of a field's `@relation`.
+fn rename_relation_refs(field: &mut Field, arg: &str, renames: &[(SmolStr, SmolStr)]) {
+    for attr in field.attributes.iter_mut().filter(|a| a.is("relation")) {
+        for value in attr.args.iter_mut() {
+            if value.name.as_ref().is_some_and(|n| n.as_str() == arg) {
+                rename_refs(&mut value.value, renames);
+            }
+        }
+    }
+}
+
+/// Apply the first rename matching `name`.
+fn rename(name: &mut SmolStr, renames: &[(SmolStr, SmolStr)]) {
+    if let Some((_, to)) = renames.iter().find(|(from, _)| from == name) {
+        *name = to.clone();
+    }
+}
+
+/// Rename field references in an attribute value.
+fn rename_refs(value: &mut AttributeValue, renames: &[(SmolStr, SmolStr)]) {
+    match value {
+        AttributeValue::FieldRef(name) | AttributeValue::Ident(name) => rename(name, renames),
+        AttributeValue::FieldRefList(names) => {
+            names.iter_mut().for_each(|name| rename(name, renames))
+        }
+        AttributeValue::Array(values) => values.iter_mut().for_each(|v| rename_refs(v, renames)),
+        AttributeValue::Object(entries) => entries
+            .iter_mut()
+            .for_each(|(_, v)| rename_refs(v, renames)),
+        _ => {}
     }
 }
 
+/// Column name of a field (from `@map` or the field name).
+fn column_name(field: &Field) -> &str {
+    field
+        .get_attribute("map")
+        .and_then(|a| a.first_arg())
+        .and_then(|v| v.as_string())
+        .unwrap_or_else(|| field.name())
+}
+
+/// Append custom attributes from `from` that are missing in `to`.
+fn copy_custom(to: &mut Vec<Attribute>, from: &[Attribute]) -> usize {
+    let missing: Vec<Attribute> = from
+        .iter()
+        .filter(|a| a.is_custom() && !to.iter().any(|b| b.is(a.name())))
+        .cloned()
+        .collect();
+    let count = missing.len();
+    to.extend(missing);
+    count
+}
+
 /// A raw SQL definition.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RawSql {
@@ -860,4 +1199,118 @@ mod tests {
         assert_eq!(stats.model_count, 1);
         assert_eq!(stats.policy_count, 2);
     }
+
+    #[test]
+    fn test_preserve_custom_attributes() {
+        let previous = crate::parse_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                email String @search.indexed @map("email_address")
+                @@map("users")
+                @@audit.track
+            }
+        "#,
+        )
+        .unwrap();
+        let mut pulled = crate::parse_schema(
+            r#"
+            model Users {
+                id            Int    @id @auto
+                email_address String
+                @@map("users")
+            }
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(pulled.preserve_custom_attributes(&previous), 2);
+
+        let model = pulled.get_model("Users").unwrap();
+        assert!(model.custom_attributes().has("audit", "track"));
+        let field = model.get_field("email_address").unwrap();
+        assert!(field.custom_attributes().has("search", "indexed"));
+
+        // Running again does not duplicate attributes
+        assert_eq!(pulled.preserve_custom_attributes(&previous), 0);
+        assert_eq!(
+            pulled.custom_attribute_namespaces(),
+            vec!["audit", "search"]
+        );
+    }
+
+    #[test]
+    fn test_preserve_names() {
+        let previous = crate::parse_schema(
+            r#"
+            /// A registered account
+            model Account {
+                id   Int    @id @auto
+                /// Where we send mail
+                mail String @map("email")
+                @@map("users")
+            }
+
+            model Post {
+                id     Int     @id @auto
+                userId Int     @map("author_id")
+                writer Account @relation(fields: [userId], references: [id])
+                @@index([userId])
+                @@map("posts")
+            }
+        "#,
+        )
+        .unwrap();
+        let mut pulled = crate::parse_schema(
+            r#"
+            model Users {
+                id    Int    @id @auto
+                email String
+            }
+
+            model Posts {
+                id       Int   @id @auto
+                authorId Int   @map("author_id")
+                author   Users @relation(fields: [authorId], references: [id])
+                @@index([authorId])
+            }
+        "#,
+        )
+        .unwrap();
+
+        // Two docs, two model names and two field names
+        // Two doc comments, two model names and three field names
+        assert_eq!(pulled.preserve_names(&previous), 7);
+
+        let account = pulled.get_model("Account").unwrap();
+        assert_eq!(account.table_name(), "users");
+        assert_eq!(
+            account.documentation.as_ref().unwrap().text,
+            "A registered account"
+        );
+        let mail = account.get_field("mail").unwrap();
+        assert_eq!(column_name(mail), "email");
+        assert_eq!(
+            mail.documentation.as_ref().unwrap().text,
+            "Where we send mail"
+        );
+
+        let post = pulled.get_model("Post").unwrap();
+        assert_eq!(post.table_name(), "posts");
+        let fields: Vec<_> = post.fields.keys().map(SmolStr::as_str).collect();
+        assert_eq!(fields, vec!["id", "userId", "writer"]);
+        let writer = post.get_field("writer").unwrap();
+        assert_eq!(writer.field_type, FieldType::Model("Account".into()));
+        assert_eq!(
+            writer.get_attribute("relation").unwrap().get_arg("fields"),
+            Some(&AttributeValue::FieldRefList(vec!["userId".into()]))
+        );
+        assert_eq!(
+            post.get_attribute("index").unwrap().first_arg(),
+            Some(&AttributeValue::FieldRefList(vec!["userId".into()]))
+        );
+
+        // Running again restores nothing
+        assert_eq!(pulled.preserve_names(&previous), 0);
+    }
 }
@@ -0,0 +1,92 @@
+//! State machine fields declared with `@stateMachine`.
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::{Attribute, AttributeValue};
+
+/// The allowed transitions of an enum field.
+///
+/// ```prax
+/// enum PostStatus {
+///     draft
+///     review
+///     published
+/// }
+///
+/// model Post {
+///     id     Int        @id @auto
+///     status PostStatus @default(draft) @stateMachine(transitions: {draft: [review], review: [published, draft]})
+/// }
+/// ```
+///
+/// States without an entry are terminal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateMachine {
+    /// The states reachable from each state, in declaration order.
+    pub transitions: Vec<(SmolStr, Vec<SmolStr>)>,
+}
+
+impl StateMachine {
+    /// Read the transitions from a `@stateMachine` attribute.
+    pub fn from_attribute(attr: &Attribute) -> Result<Self, String> {
+        let entries = match attr.get_arg("transitions") {
+            Some(AttributeValue::Object(entries)) => entries,
+            Some(_) => {
+                return Err(
+                    "`transitions` must be an object such as `{draft: [review]}`".to_string(),
+                );
+            }
+            None => return Err("missing `transitions` argument".to_string()),
+        };
+
+        let mut transitions: Vec<(SmolStr, Vec<SmolStr>)> = Vec::with_capacity(entries.len());
+        for (from, targets) in entries {
+            if transitions.iter().any(|(state, _)| state == from) {
+                return Err(format!("state '{}' is listed more than once", from));
+            }
+
+            let targets = match targets {
+                AttributeValue::Array(items) => {
+                    items.iter().map(state_name).collect::<Option<Vec<_>>>()
+                }
+                AttributeValue::FieldRefList(items) => Some(items.clone()),
+                single => state_name(single).map(|s| vec![s]),
+            }
+            .ok_or_else(|| format!("targets of '{}' must be state names", from))?;
+
+            transitions.push((from.clone(), targets));
+        }
+
+        Ok(Self { transitions })
+    }
+
+    /// Get the states reachable from `from`.
+    pub fn targets(&self, from: &str) -> &[SmolStr] {
+        self.transitions
+            .iter()
+            .find(|(state, _)| state == from)
+            .map(|(_, targets)| targets.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Check if `from` may transition to `to`.
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.targets(from).iter().any(|target| target == to)
+    }
+
+    /// Get every state named in the transitions.
+    pub fn states(&self) -> impl Iterator<Item = &SmolStr> {
+        self.transitions
+            .iter()
+            .flat_map(|(from, targets)| std::iter::once(from).chain(targets))
+    }
+}
+
+fn state_name(value: &AttributeValue) -> Option<SmolStr> {
+    match value {
+        AttributeValue::Ident(name) | AttributeValue::FieldRef(name) => Some(name.clone()),
+        AttributeValue::String(name) => Some(SmolStr::new(name)),
+        _ => None,
+    }
+}
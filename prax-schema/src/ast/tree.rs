@@ -0,0 +1,91 @@
+//! Hierarchical models declared with `@@tree`.
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::{Attribute, AttributeValue};
+
+/// How a tree stores the hierarchy beyond the parent field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TreeStrategy {
+    /// A `<table>_closure` table holding every ancestor/descendant pair.
+    #[default]
+    ClosureTable,
+    /// A `path` column holding the keys of a node's ancestors.
+    MaterializedPath,
+}
+
+impl TreeStrategy {
+    /// Parse a strategy name (`closure_table` or `materialized_path`).
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "closure_table" => Some(Self::ClosureTable),
+            "materialized_path" => Some(Self::MaterializedPath),
+            _ => None,
+        }
+    }
+
+    /// Get the strategy name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ClosureTable => "closure_table",
+            Self::MaterializedPath => "materialized_path",
+        }
+    }
+}
+
+/// The hierarchy of a model.
+///
+/// ```prax
+/// model Category {
+///     id       Int        @id @auto
+///     parentId Int?
+///     parent   Category?  @relation("CategoryTree", fields: [parentId], references: [id])
+///     children Category[] @relation("CategoryTree")
+///
+///     @@tree(strategy: closure_table, parent: parentId)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tree {
+    /// How the hierarchy is stored.
+    pub strategy: TreeStrategy,
+    /// The field holding the parent's primary key; `parentId` by default.
+    pub parent: SmolStr,
+}
+
+impl Tree {
+    /// Column holding the path of a `materialized_path` tree.
+    pub const PATH_COLUMN: &'static str = "path";
+
+    /// Read a tree from a `@@tree` attribute.
+    pub fn from_attribute(attr: &Attribute) -> Result<Self, String> {
+        let strategy = match attr.get_arg("strategy") {
+            Some(value) => {
+                let name = value.as_ident().or_else(|| value.as_string());
+                name.and_then(TreeStrategy::from_str).ok_or_else(|| {
+                    "`strategy` must be `closure_table` or `materialized_path`".to_string()
+                })?
+            }
+            None => TreeStrategy::default(),
+        };
+
+        let parent = match attr.get_arg("parent") {
+            Some(AttributeValue::Ident(name) | AttributeValue::FieldRef(name)) => name.clone(),
+            Some(AttributeValue::String(name)) => SmolStr::new(name),
+            Some(_) => return Err("`parent` must be a field name".to_string()),
+            None => SmolStr::new_static("parentId"),
+        };
+
+        Ok(Self { strategy, parent })
+    }
+
+    /// Get the closure table name of a `closure_table` tree over `table`.
+    pub fn closure_table_name(&self, table: &str) -> Option<String> {
+        match self.strategy {
+            TreeStrategy::ClosureTable => Some(format!("{}_closure", table)),
+            TreeStrategy::MaterializedPath => None,
+        }
+    }
+}
@@ -108,6 +108,27 @@ pub enum ScalarType {
     NanoId,
     /// ULID type (Universally Unique Lexicographically Sortable Identifier).
     Ulid,
+    /// Range of 32-bit integers (maps to PostgreSQL INT4RANGE).
+    IntRange,
+    /// Range of 64-bit integers (maps to PostgreSQL INT8RANGE).
+    BigIntRange,
+    /// Range of timestamps with time zone (maps to PostgreSQL TSTZRANGE).
+    /// Databases without range types store a range in two bound columns.
+    DateTimeRange,
+    /// IPv4 or IPv6 host address with optional netmask (maps to PostgreSQL INET).
+    Inet,
+    /// IPv4 or IPv6 network (maps to PostgreSQL CIDR).
+    Cidr,
+    /// MAC address (maps to PostgreSQL MACADDR).
+    MacAddr,
+    /// Planar spatial data (maps to PostGIS GEOMETRY).
+    /// Usage: `location Geometry @db.Geometry(Point, 4326)`.
+    Geometry,
+    /// Geodetic spatial data on the WGS84 spheroid (maps to PostGIS GEOGRAPHY).
+    Geography,
+    /// Monetary amount in an ISO 4217 currency, stored in two columns:
+    /// `<column>_amount` (DECIMAL(19, 4)) and `<column>_currency` (CHAR(3)).
+    Money,
 
     // ==================== PostgreSQL Extension Types ====================
     // These types require the corresponding PostgreSQL extension to be enabled.
@@ -151,6 +172,15 @@ impl ScalarType {
             "Cuid2" | "CUID2" => Some(Self::Cuid2),
             "NanoId" | "NanoID" | "Nanoid" => Some(Self::NanoId),
             "Ulid" | "ULID" => Some(Self::Ulid),
+            "IntRange" | "Int4Range" | "Int4range" => Some(Self::IntRange),
+            "BigIntRange" | "Int8Range" | "Int8range" => Some(Self::BigIntRange),
+            "DateTimeRange" | "TsTzRange" | "Tstzrange" => Some(Self::DateTimeRange),
+            "Inet" | "INET" => Some(Self::Inet),
+            "Cidr" | "CIDR" => Some(Self::Cidr),
+            "MacAddr" | "Macaddr" | "MACADDR" => Some(Self::MacAddr),
+            "Geometry" => Some(Self::Geometry),
+            "Geography" => Some(Self::Geography),
+            "Money" => Some(Self::Money),
             // Vector types without dimension
             "Vector" => Some(Self::Vector(None)),
             "HalfVector" | "Halfvec" => Some(Self::HalfVector(None)),
@@ -191,6 +221,15 @@ impl ScalarType {
             Self::Cuid2 => "Cuid2",
             Self::NanoId => "NanoId",
             Self::Ulid => "Ulid",
+            Self::IntRange => "IntRange",
+            Self::BigIntRange => "BigIntRange",
+            Self::DateTimeRange => "DateTimeRange",
+            Self::Inet => "Inet",
+            Self::Cidr => "Cidr",
+            Self::MacAddr => "MacAddr",
+            Self::Geometry => "Geometry",
+            Self::Geography => "Geography",
+            Self::Money => "Money",
             Self::Vector(_) => "Vector",
             Self::HalfVector(_) => "HalfVector",
             Self::SparseVector(_) => "SparseVector",
@@ -214,6 +253,34 @@ impl ScalarType {
         )
     }
 
+    /// Check if this is a range type.
+    pub fn is_range(&self) -> bool {
+        matches!(
+            self,
+            Self::IntRange | Self::BigIntRange | Self::DateTimeRange
+        )
+    }
+
+    /// Get the element type of a range type.
+    pub fn range_element(&self) -> Option<ScalarType> {
+        match self {
+            Self::IntRange => Some(Self::Int),
+            Self::BigIntRange => Some(Self::BigInt),
+            Self::DateTimeRange => Some(Self::DateTime),
+            _ => None,
+        }
+    }
+
+    /// Check if this is an IP address or network type.
+    pub fn is_network(&self) -> bool {
+        matches!(self, Self::Inet | Self::Cidr)
+    }
+
+    /// Check if this is a spatial type, which requires the `postgis` extension.
+    pub fn is_spatial(&self) -> bool {
+        matches!(self, Self::Geometry | Self::Geography)
+    }
+
     /// Get the dimension for vector types, if specified.
     pub fn dimension(&self) -> Option<u32> {
         match self {
@@ -238,6 +305,16 @@ impl ScalarType {
             Self::Bytes => "BYTEA".to_string(),
             Self::Uuid => "UUID".to_string(),
             Self::Cuid | Self::Cuid2 | Self::NanoId | Self::Ulid => "TEXT".to_string(),
+            Self::IntRange => "INT4RANGE".to_string(),
+            Self::BigIntRange => "INT8RANGE".to_string(),
+            Self::DateTimeRange => "TSTZRANGE".to_string(),
+            Self::Inet => "INET".to_string(),
+            Self::Cidr => "CIDR".to_string(),
+            Self::MacAddr => "MACADDR".to_string(),
+            Self::Geometry => "GEOMETRY".to_string(),
+            Self::Geography => "GEOGRAPHY".to_string(),
+            // The amount column; the currency is a CHAR(3) column beside it
+            Self::Money => "DECIMAL(19, 4)".to_string(),
             Self::Vector(Some(dim)) => format!("vector({})", dim),
             Self::Vector(None) => "vector".to_string(),
             Self::HalfVector(Some(dim)) => format!("halfvec({})", dim),
@@ -256,6 +333,86 @@ impl std::fmt::Display for ScalarType {
     }
 }
 
+/// The shape a spatial column holds, as in `@db.Geometry(Point, 4326)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GeometryKind {
+    /// Any shape.
+    Geometry,
+    /// A single point.
+    Point,
+    /// A line of connected points.
+    LineString,
+    /// A polygon, possibly with holes.
+    Polygon,
+    /// A set of points.
+    MultiPoint,
+    /// A set of lines.
+    MultiLineString,
+    /// A set of polygons.
+    MultiPolygon,
+    /// A set of shapes of any kind.
+    GeometryCollection,
+}
+
+impl GeometryKind {
+    /// Parse a geometry kind, as written in PostGIS type modifiers.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "geometry" => Some(Self::Geometry),
+            "point" => Some(Self::Point),
+            "linestring" => Some(Self::LineString),
+            "polygon" => Some(Self::Polygon),
+            "multipoint" => Some(Self::MultiPoint),
+            "multilinestring" => Some(Self::MultiLineString),
+            "multipolygon" => Some(Self::MultiPolygon),
+            "geometrycollection" => Some(Self::GeometryCollection),
+            _ => None,
+        }
+    }
+
+    /// Get the kind's name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Geometry => "Geometry",
+            Self::Point => "Point",
+            Self::LineString => "LineString",
+            Self::Polygon => "Polygon",
+            Self::MultiPoint => "MultiPoint",
+            Self::MultiLineString => "MultiLineString",
+            Self::MultiPolygon => "MultiPolygon",
+            Self::GeometryCollection => "GeometryCollection",
+        }
+    }
+}
+
+impl std::fmt::Display for GeometryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The shape and spatial reference system of a spatial column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SpatialType {
+    /// The shape the column holds.
+    pub kind: GeometryKind,
+    /// The spatial reference system, such as 4326 for WGS84.
+    pub srid: Option<i32>,
+}
+
+impl SpatialType {
+    /// Get the PostGIS column type, such as `GEOMETRY(Point, 4326)`.
+    ///
+    /// `scalar` is `Geometry` or `Geography`.
+    pub fn column_type(&self, scalar: &ScalarType) -> String {
+        match self.srid {
+            Some(srid) => format!("{}({}, {})", scalar.postgres_type(), self.kind, srid),
+            None => format!("{}({})", scalar.postgres_type(), self.kind),
+        }
+    }
+}
+
 /// A field type in the schema.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FieldType {
@@ -574,6 +731,70 @@ mod tests {
         assert_eq!(ScalarType::from_str("ULID"), Some(ScalarType::Ulid));
     }
 
+    #[test]
+    fn test_scalar_type_from_str_datetime_range() {
+        let range = ScalarType::from_str("TsTzRange").unwrap();
+        assert_eq!(range, ScalarType::DateTimeRange);
+        assert_eq!(ScalarType::from_str("DateTimeRange"), Some(range.clone()));
+        assert!(range.is_range());
+        assert_eq!(range.postgres_type(), "TSTZRANGE");
+        assert_eq!(range.range_element(), Some(ScalarType::DateTime));
+    }
+
+    #[test]
+    fn test_scalar_type_from_str_int_ranges() {
+        let range = ScalarType::from_str("Int4Range").unwrap();
+        assert_eq!(range, ScalarType::IntRange);
+        assert_eq!(range.postgres_type(), "INT4RANGE");
+        assert_eq!(range.range_element(), Some(ScalarType::Int));
+
+        let range = ScalarType::from_str("BigIntRange").unwrap();
+        assert_eq!(range.as_str(), "BigIntRange");
+        assert_eq!(range.postgres_type(), "INT8RANGE");
+        assert!(range.is_range());
+        assert!(!ScalarType::Int.is_range());
+    }
+
+    #[test]
+    fn test_scalar_type_from_str_network() {
+        assert_eq!(ScalarType::from_str("Inet"), Some(ScalarType::Inet));
+        assert_eq!(ScalarType::from_str("CIDR"), Some(ScalarType::Cidr));
+        assert_eq!(ScalarType::from_str("Macaddr"), Some(ScalarType::MacAddr));
+        assert_eq!(ScalarType::Cidr.postgres_type(), "CIDR");
+        assert_eq!(ScalarType::MacAddr.as_str(), "MacAddr");
+        assert!(ScalarType::Inet.is_network());
+        assert!(!ScalarType::MacAddr.is_network());
+    }
+
+    #[test]
+    fn test_scalar_type_spatial() {
+        assert_eq!(
+            ScalarType::from_str("Geography"),
+            Some(ScalarType::Geography)
+        );
+        assert_eq!(ScalarType::Geometry.postgres_type(), "GEOMETRY");
+        assert!(ScalarType::Geometry.is_spatial());
+        assert!(!ScalarType::Bytes.is_spatial());
+
+        assert_eq!(GeometryKind::from_str("POINT"), Some(GeometryKind::Point));
+        assert_eq!(GeometryKind::from_str("Circle"), None);
+        let spatial = SpatialType {
+            kind: GeometryKind::MultiPolygon,
+            srid: Some(4326),
+        };
+        assert_eq!(
+            spatial.column_type(&ScalarType::Geography),
+            "GEOGRAPHY(MultiPolygon, 4326)"
+        );
+    }
+
+    #[test]
+    fn test_scalar_type_money() {
+        assert_eq!(ScalarType::from_str("Money"), Some(ScalarType::Money));
+        assert_eq!(ScalarType::Money.as_str(), "Money");
+        assert_eq!(ScalarType::Money.postgres_type(), "DECIMAL(19, 4)");
+    }
+
     #[test]
     fn test_scalar_type_from_str_unknown() {
         assert_eq!(ScalarType::from_str("Unknown"), None);
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+use crate::ast::Schema;
 use crate::error::{SchemaError, SchemaResult};
+use crate::naming::NamingCase;
 
 /// Main configuration structure for `prax.toml`.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -232,6 +234,13 @@ pub struct GeneratorConfig {
     /// Client generator settings.
     #[serde(default)]
     pub client: ClientGeneratorConfig,
+
+    /// External generator plugins, keyed by name.
+    ///
+    /// Entries are interpreted by `prax generate`; see the
+    /// `prax-plugin-api` crate for their format.
+    #[serde(default)]
+    pub plugins: HashMap<String, toml::Value>,
 }
 
 /// Style of model code generation.
@@ -287,6 +296,22 @@ pub struct ClientGeneratorConfig {
     /// - `graphql`: Adds async-graphql derives (SimpleObject, InputObject, etc.)
     #[serde(default)]
     pub model_style: ModelStyle,
+
+    /// How `prax generate` splits the client across files.
+    #[serde(default)]
+    pub split: OutputSplit,
+
+    /// Gate each generated model behind a `model-<name>` Cargo feature.
+    #[serde(default)]
+    pub feature_gates: bool,
+
+    /// How field names become column names, unless mapped with `@map`.
+    #[serde(default)]
+    pub field_case: NamingCase,
+
+    /// How model names become table names, unless mapped with `@@map`.
+    #[serde(default)]
+    pub table_case: NamingCase,
 }
 
 impl Default for ClientGeneratorConfig {
@@ -297,10 +322,33 @@ impl Default for ClientGeneratorConfig {
             tracing: false,
             preview_features: vec![],
             model_style: ModelStyle::default(),
+            split: OutputSplit::default(),
+            feature_gates: false,
+            field_case: NamingCase::default(),
+            table_case: NamingCase::default(),
         }
     }
 }
 
+impl ClientGeneratorConfig {
+    /// Apply the configured naming conventions to a schema; see
+    /// [`Schema::apply_naming`].
+    pub fn apply_naming(&self, schema: &mut Schema) {
+        schema.apply_naming(self.field_case, self.table_case);
+    }
+}
+
+/// File layout of the generated client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputSplit {
+    /// Everything in a single `mod.rs` with inline modules.
+    Single,
+    /// One file per model and enum (default).
+    #[default]
+    PerModel,
+}
+
 fn default_output() -> String {
     "./src/generated".to_string()
 }
@@ -774,6 +822,51 @@ mod tests {
         assert_eq!(config.generator.client.preview_features.len(), 2);
     }
 
+    #[test]
+    fn test_generator_config_plugins() {
+        let toml = r#"
+            [generator.plugins.openapi]
+            output = "./generated/openapi"
+            title = "My API"
+        "#;
+
+        let config = PraxConfig::from_str(toml).unwrap();
+        let plugin = &config.generator.plugins["openapi"];
+        assert_eq!(plugin.get("title").and_then(|v| v.as_str()), Some("My API"));
+    }
+
+    #[test]
+    fn test_generator_config_output_split() {
+        let toml = r#"
+            [generator.client]
+            split = "single"
+            feature_gates = true
+        "#;
+
+        let config = PraxConfig::from_str(toml).unwrap();
+        assert_eq!(config.generator.client.split, OutputSplit::Single);
+        assert!(config.generator.client.feature_gates);
+    }
+
+    #[test]
+    fn test_generator_config_naming() {
+        let toml = r#"
+            [generator.client]
+            field_case = "snake_case"
+            table_case = "snake_case_plural"
+        "#;
+
+        let config = PraxConfig::from_str(toml).unwrap();
+        assert_eq!(config.generator.client.field_case, NamingCase::SnakeCase);
+        assert_eq!(
+            config.generator.client.table_case,
+            NamingCase::SnakeCasePlural
+        );
+
+        let config = PraxConfig::default();
+        assert_eq!(config.generator.client.table_case, NamingCase::Preserve);
+    }
+
     #[test]
     fn test_generator_config_graphql_model_style() {
         let toml = r#"
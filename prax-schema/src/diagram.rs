@@ -0,0 +1,424 @@
+//! Entity-relationship diagram export.
+//!
+//! Renders the models of a schema and the relations between them as a
+//! [Mermaid](https://mermaid.js.org/syntax/entityRelationshipDiagram.html)
+//! `erDiagram` or a [Graphviz](https://graphviz.org/) DOT graph:
+//!
+//! ```rust
+//! use prax_schema::parse_schema;
+//!
+//! let schema = parse_schema(r#"
+//! model User {
+//!     id    Int    @id @auto
+//!     posts Post[]
+//! }
+//!
+//! model Post {
+//!     id       Int  @id @auto
+//!     authorId Int
+//!     author   User @relation(fields: [authorId], references: [id])
+//! }
+//! "#).unwrap();
+//!
+//! let mermaid = schema.to_mermaid_er();
+//! assert!(mermaid.contains("User ||--o{ Post : author"));
+//!
+//! let dot = schema.to_dot();
+//! assert!(dot.starts_with("digraph schema {"));
+//! ```
+
+use smol_str::SmolStr;
+
+use crate::ast::{AttributeValue, Field, FieldType, Model, Schema};
+
+/// How many records can be on one end of a relationship.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinality {
+    /// Zero or one record.
+    ZeroOrOne,
+    /// Exactly one record.
+    ExactlyOne,
+    /// Zero or more records.
+    ZeroOrMany,
+}
+
+impl Cardinality {
+    /// Mermaid marker when this end is on the left of the line.
+    fn mermaid_left(self) -> &'static str {
+        match self {
+            Self::ZeroOrOne => "|o",
+            Self::ExactlyOne => "||",
+            Self::ZeroOrMany => "}o",
+        }
+    }
+
+    /// Mermaid marker when this end is on the right of the line.
+    fn mermaid_right(self) -> &'static str {
+        match self {
+            Self::ZeroOrOne => "o|",
+            Self::ExactlyOne => "||",
+            Self::ZeroOrMany => "o{",
+        }
+    }
+
+    /// UML-style multiplicity label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ZeroOrOne => "0..1",
+            Self::ExactlyOne => "1",
+            Self::ZeroOrMany => "0..*",
+        }
+    }
+}
+
+/// A relationship between two models in an ER diagram.
+///
+/// `left_cardinality` is how many `left` records relate to one `right`
+/// record, and vice versa. For a foreign key, `left` is the referenced
+/// model and `right` the model holding the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErRelationship {
+    /// Model on the left end.
+    pub left: SmolStr,
+    /// Cardinality of the left end.
+    pub left_cardinality: Cardinality,
+    /// Model on the right end.
+    pub right: SmolStr,
+    /// Cardinality of the right end.
+    pub right_cardinality: Cardinality,
+    /// Relation field the relationship was derived from.
+    pub label: SmolStr,
+}
+
+impl Schema {
+    /// Relationships between models, one per relation.
+    ///
+    /// Relations are taken from the side holding the foreign key
+    /// (`@relation(fields: ...)`); implicit many-to-many relations (list
+    /// fields on both sides) are included once.
+    pub fn er_relationships(&self) -> Vec<ErRelationship> {
+        let mut relationships = Vec::new();
+
+        for model in self.models.values() {
+            for field in model.fields.values() {
+                let Some(target) = self.relation_target(field) else {
+                    continue;
+                };
+                let back = back_relation(model, field, target);
+                let attrs = field.extract_attributes();
+
+                if attrs
+                    .relation
+                    .as_ref()
+                    .is_some_and(|r| !r.fields.is_empty())
+                {
+                    let fields = attrs.relation.map(|r| r.fields).unwrap_or_default();
+                    let optional = field.is_optional()
+                        || fields
+                            .iter()
+                            .filter_map(|f| model.get_field(f))
+                            .any(|f| f.is_optional());
+
+                    relationships.push(ErRelationship {
+                        left: target.name.name.clone(),
+                        left_cardinality: if optional {
+                            Cardinality::ZeroOrOne
+                        } else {
+                            Cardinality::ExactlyOne
+                        },
+                        right: model.name.name.clone(),
+                        right_cardinality: if back.is_some_and(|b| !b.is_list()) {
+                            Cardinality::ZeroOrOne
+                        } else {
+                            Cardinality::ZeroOrMany
+                        },
+                        label: field.name.name.clone(),
+                    });
+                } else if let Some(back) = back.filter(|b| field.is_list() && b.is_list()) {
+                    // Emit implicit many-to-many relations from one side only
+                    if (model.name(), field.name()) <= (target.name(), back.name()) {
+                        relationships.push(ErRelationship {
+                            left: model.name.name.clone(),
+                            left_cardinality: Cardinality::ZeroOrMany,
+                            right: target.name.name.clone(),
+                            right_cardinality: Cardinality::ZeroOrMany,
+                            label: field.name.name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        relationships
+    }
+
+    /// Render the schema as a Mermaid `erDiagram`.
+    pub fn to_mermaid_er(&self) -> String {
+        let mut output = String::from("erDiagram\n");
+
+        for rel in self.er_relationships() {
+            output.push_str(&format!(
+                "    {} {}--{} {} : {}\n",
+                rel.left,
+                rel.left_cardinality.mermaid_left(),
+                rel.right_cardinality.mermaid_right(),
+                rel.right,
+                rel.label
+            ));
+        }
+
+        for model in self.models.values() {
+            output.push_str(&format!("    {} {{\n", model.name()));
+            for (field, keys) in self.columns(model) {
+                let type_name = if field.is_list() {
+                    format!("{}[]", field.field_type.type_name())
+                } else {
+                    field.field_type.type_name().to_string()
+                };
+                output.push_str(&format!("        {} {}", type_name, field.name()));
+                if !keys.is_empty() {
+                    output.push_str(&format!(" {}", keys.join(", ")));
+                }
+                if field.is_optional() {
+                    output.push_str(" \"nullable\"");
+                }
+                output.push('\n');
+            }
+            output.push_str("    }\n");
+        }
+
+        output
+    }
+
+    /// Render the schema as a Graphviz DOT graph.
+    pub fn to_dot(&self) -> String {
+        let mut output = String::from("digraph schema {\n");
+        output.push_str("    rankdir=LR;\n");
+        output.push_str("    node [shape=record, fontname=\"Helvetica\"];\n");
+        output.push_str("    edge [fontname=\"Helvetica\", fontsize=10];\n");
+
+        for model in self.models.values() {
+            let columns: Vec<String> = self
+                .columns(model)
+                .into_iter()
+                .map(|(field, keys)| {
+                    let optional = if field.is_optional() { "?" } else { "" };
+                    let list = if field.is_list() { "[]" } else { "" };
+                    let keys = if keys.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", keys.join(", "))
+                    };
+                    dot_escape(&format!(
+                        "{}: {}{}{}{}",
+                        field.name(),
+                        field.field_type.type_name(),
+                        list,
+                        optional,
+                        keys
+                    ))
+                })
+                .map(|line| format!("{}\\l", line))
+                .collect();
+
+            output.push_str(&format!(
+                "    \"{}\" [label=\"{{{}|{}}}\"];\n",
+                model.name(),
+                model.name(),
+                columns.join("")
+            ));
+        }
+
+        for rel in self.er_relationships() {
+            output.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\", taillabel=\"{}\", headlabel=\"{}\", dir=none];\n",
+                rel.right,
+                rel.left,
+                rel.label,
+                rel.right_cardinality.label(),
+                rel.left_cardinality.label()
+            ));
+        }
+
+        output.push_str("}\n");
+        output
+    }
+
+    /// The model a relation field points to, if it is a relation.
+    fn relation_target(&self, field: &Field) -> Option<&Model> {
+        match &field.field_type {
+            FieldType::Model(name) => self.models.get(name),
+            _ => None,
+        }
+    }
+
+    /// Non-relation fields of a model with their key markers.
+    fn columns<'a>(&self, model: &'a Model) -> Vec<(&'a Field, Vec<&'static str>)> {
+        let composite_id = match model.get_attribute("id").and_then(|a| a.first_arg()) {
+            Some(AttributeValue::FieldRefList(fields)) => fields.clone(),
+            _ => vec![],
+        };
+        let foreign_keys: Vec<SmolStr> = model
+            .fields
+            .values()
+            .filter(|f| self.relation_target(f).is_some())
+            .filter_map(|f| f.extract_attributes().relation)
+            .flat_map(|r| r.fields)
+            .collect();
+
+        model
+            .fields
+            .values()
+            .filter(|f| self.relation_target(f).is_none())
+            .map(|field| {
+                let mut keys = Vec::new();
+                if field.is_id() || composite_id.contains(&field.name.name) {
+                    keys.push("PK");
+                }
+                if foreign_keys.contains(&field.name.name) {
+                    keys.push("FK");
+                }
+                if field.is_unique() {
+                    keys.push("UK");
+                }
+                (field, keys)
+            })
+            .collect()
+    }
+}
+
+/// The field on `target` pointing back at `model` for the relation `field`.
+fn back_relation<'a>(model: &Model, field: &Field, target: &'a Model) -> Option<&'a Field> {
+    let name = relation_name(field);
+    target.fields.values().find(|f| {
+        matches!(&f.field_type, FieldType::Model(m) if m == model.name())
+            && relation_name(f) == name
+            // A self-relation field is not its own back-relation
+            && !(model.name() == target.name() && f.name() == field.name())
+    })
+}
+
+fn relation_name(field: &Field) -> Option<String> {
+    field.extract_attributes().relation.and_then(|r| r.name)
+}
+
+/// Escape characters with special meaning in DOT record labels.
+fn dot_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_schema;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        model User {
+            id      Int      @id @auto
+            email   String   @unique
+            name    String?
+            posts   Post[]
+            profile Profile?
+            groups  Group[]
+        }
+
+        model Profile {
+            id     Int  @id @auto
+            userId Int  @unique
+            user   User @relation(fields: [userId], references: [id])
+        }
+
+        model Post {
+            id         Int   @id @auto
+            authorId   Int?
+            author     User? @relation(fields: [authorId], references: [id])
+        }
+
+        model Group {
+            id      Int    @id @auto
+            members User[]
+        }
+    "#;
+
+    #[test]
+    fn test_er_relationships() {
+        let schema = parse_schema(SCHEMA).unwrap();
+        let rels = schema.er_relationships();
+
+        assert_eq!(rels.len(), 3);
+
+        let profile = rels.iter().find(|r| r.right == "Profile").unwrap();
+        assert_eq!(profile.left, "User");
+        assert_eq!(profile.left_cardinality, Cardinality::ExactlyOne);
+        assert_eq!(profile.right_cardinality, Cardinality::ZeroOrOne);
+
+        let post = rels.iter().find(|r| r.right == "Post").unwrap();
+        assert_eq!(post.left_cardinality, Cardinality::ZeroOrOne);
+        assert_eq!(post.right_cardinality, Cardinality::ZeroOrMany);
+
+        let groups = rels.iter().find(|r| r.label == "members").unwrap();
+        assert_eq!(groups.left, "Group");
+        assert_eq!(groups.left_cardinality, Cardinality::ZeroOrMany);
+        assert_eq!(groups.right_cardinality, Cardinality::ZeroOrMany);
+    }
+
+    #[test]
+    fn test_to_mermaid_er() {
+        let schema = parse_schema(SCHEMA).unwrap();
+        let mermaid = schema.to_mermaid_er();
+
+        assert!(mermaid.starts_with("erDiagram\n"));
+        assert!(mermaid.contains("    User ||--o| Profile : user\n"));
+        assert!(mermaid.contains("    User |o--o{ Post : author\n"));
+        assert!(mermaid.contains("    Group }o--o{ User : members\n"));
+        assert!(mermaid.contains("        Int id PK\n"));
+        assert!(mermaid.contains("        Int userId FK, UK\n"));
+        assert!(mermaid.contains("        String name \"nullable\"\n"));
+        // Relation fields are edges, not columns
+        assert!(!mermaid.contains("Post[] posts"));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let schema = parse_schema(SCHEMA).unwrap();
+        let dot = schema.to_dot();
+
+        assert!(dot.starts_with("digraph schema {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("\"Post\" [label=\"{Post|id: Int (PK)\\lauthorId: Int? (FK)\\l}\"];"));
+        assert!(dot.contains(
+            "\"Post\" -> \"User\" [label=\"author\", taillabel=\"0..*\", headlabel=\"0..1\", dir=none];"
+        ));
+    }
+
+    #[test]
+    fn test_composite_primary_key() {
+        let schema = parse_schema(
+            r#"
+            model Membership {
+                userId  Int
+                groupId Int
+                @@id([userId, groupId])
+            }
+        "#,
+        )
+        .unwrap();
+
+        let mermaid = schema.to_mermaid_er();
+        assert!(mermaid.contains("Int userId PK"));
+        assert!(mermaid.contains("Int groupId PK"));
+    }
+
+    #[test]
+    fn test_dot_escape() {
+        assert_eq!(dot_escape("a{b}|<c>"), "a\\{b\\}\\|\\<c\\>");
+    }
+}
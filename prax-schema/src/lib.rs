@@ -242,7 +242,9 @@
 pub mod ast;
 pub mod cache;
 pub mod config;
+pub mod diagram;
 pub mod error;
+pub mod naming;
 pub mod parser;
 pub mod validator;
 
@@ -250,8 +252,10 @@ pub use ast::*;
 pub use cache::{
     CacheStats, DocString, FieldAttrsCache, LazyFieldAttrs, SchemaCache, ValidationTypePool,
 };
-pub use config::{ModelStyle, PraxConfig};
+pub use config::{ModelStyle, OutputSplit, PraxConfig};
+pub use diagram::{Cardinality, ErRelationship};
 pub use error::{SchemaError, SchemaResult};
+pub use naming::NamingCase;
 pub use parser::{parse_schema, parse_schema_file};
 pub use validator::{Validator, validate_schema};
 
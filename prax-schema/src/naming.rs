@@ -0,0 +1,222 @@
+//! Naming conventions mapping schema names to database identifiers.
+//!
+//! `prax.toml` can set how field and model names become column and table
+//! names, instead of annotating each one with `@map`:
+//!
+//! ```toml
+//! [generator.client]
+//! field_case = "snake_case"         # createdAt -> created_at
+//! table_case = "snake_case_plural"  # UserProfile -> user_profiles
+//! ```
+//!
+//! [`Schema::apply_naming`] adds the implied `@map` and `@@map` attributes
+//! to a parsed schema, so code generation and migrations see the same names.
+//! Explicit `@map` and `@@map` attributes always win.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Attribute, AttributeArg, AttributeValue, FieldType, Ident, Schema};
+
+/// A naming convention for database identifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum NamingCase {
+    /// Use schema names as they are (default).
+    #[default]
+    #[serde(rename = "preserve")]
+    Preserve,
+    /// `created_at`, `user_profile`.
+    #[serde(rename = "snake_case")]
+    SnakeCase,
+    /// `snake_case` with the last word pluralized: `user_profiles`.
+    #[serde(rename = "snake_case_plural")]
+    SnakeCasePlural,
+    /// `createdAt`, `userProfile`.
+    #[serde(rename = "camelCase")]
+    CamelCase,
+    /// `CreatedAt`, `UserProfile`.
+    #[serde(rename = "PascalCase")]
+    PascalCase,
+}
+
+impl NamingCase {
+    /// Convert a schema name to this convention.
+    pub fn apply(&self, name: &str) -> String {
+        let mut words = split_words(name);
+        if words.is_empty() {
+            return name.to_string();
+        }
+        match self {
+            Self::Preserve => name.to_string(),
+            Self::SnakeCase => words.join("_"),
+            Self::SnakeCasePlural => {
+                if let Some(last) = words.last_mut() {
+                    *last = pluralize(last);
+                }
+                words.join("_")
+            }
+            Self::CamelCase => {
+                let rest: String = words[1..].iter().map(|w| capitalize(w)).collect();
+                format!("{}{}", words[0], rest)
+            }
+            Self::PascalCase => words.iter().map(|w| capitalize(w)).collect(),
+        }
+    }
+}
+
+impl Schema {
+    /// Add `@@map` to models and `@map` to stored fields whose database
+    /// names differ under the given conventions.
+    ///
+    /// Models and fields that already have a `@map` attribute are left alone,
+    /// as are relation fields, which have no column.
+    pub fn apply_naming(&mut self, field_case: NamingCase, table_case: NamingCase) {
+        for model in self.models.values_mut() {
+            if table_case != NamingCase::Preserve && model.get_attribute("map").is_none() {
+                let table = table_case.apply(model.name());
+                if table != model.name() {
+                    model.attributes.push(map_attribute(table, model.span));
+                }
+            }
+
+            if field_case == NamingCase::Preserve {
+                continue;
+            }
+            for field in model.fields.values_mut() {
+                if matches!(field.field_type, FieldType::Model(_)) || field.has_attribute("map") {
+                    continue;
+                }
+                let column = field_case.apply(field.name());
+                if column != field.name() {
+                    field.attributes.push(map_attribute(column, field.span));
+                }
+            }
+        }
+    }
+}
+
+fn map_attribute(name: String, span: crate::ast::Span) -> Attribute {
+    Attribute::new(
+        Ident::new("map", span),
+        vec![AttributeArg::positional(AttributeValue::String(name), span)],
+        span,
+    )
+}
+
+/// Split a name into lowercase words at underscores and case changes:
+/// `HTTPRequest_id` is `http`, `request`, `id`.
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Pluralize an English word with the regular rules.
+fn pluralize(word: &str) -> String {
+    let consonant_y = word.ends_with('y')
+        && !word
+            .chars()
+            .rev()
+            .nth(1)
+            .is_some_and(|c| "aeiou".contains(c));
+    if consonant_y && word.len() > 1 {
+        format!("{}ies", &word[..word.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|end| word.ends_with(end))
+    {
+        format!("{}es", word)
+    } else {
+        format!("{}s", word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate_schema;
+
+    #[test]
+    fn test_naming_case() {
+        assert_eq!(NamingCase::SnakeCase.apply("createdAt"), "created_at");
+        assert_eq!(NamingCase::SnakeCase.apply("HTTPRequest"), "http_request");
+        assert_eq!(NamingCase::SnakeCase.apply("userID"), "user_id");
+        assert_eq!(NamingCase::SnakeCase.apply("address2Line"), "address2_line");
+        assert_eq!(
+            NamingCase::SnakeCasePlural.apply("UserProfile"),
+            "user_profiles"
+        );
+        assert_eq!(NamingCase::SnakeCasePlural.apply("Category"), "categories");
+        assert_eq!(NamingCase::SnakeCasePlural.apply("Address"), "addresses");
+        assert_eq!(NamingCase::SnakeCasePlural.apply("Day"), "days");
+        assert_eq!(NamingCase::CamelCase.apply("created_at"), "createdAt");
+        assert_eq!(NamingCase::PascalCase.apply("user_profile"), "UserProfile");
+        assert_eq!(NamingCase::Preserve.apply("createdAt"), "createdAt");
+    }
+
+    #[test]
+    fn test_apply_naming() {
+        let mut schema = validate_schema(
+            r#"
+            model UserProfile {
+                id        Int      @id @auto
+                createdAt DateTime
+                legacyId  Int      @map("LegacyID")
+                posts     Post[]
+            }
+
+            model Post {
+                id       Int         @id @auto
+                authorId Int
+                author   UserProfile @relation(fields: [authorId], references: [id])
+
+                @@map("articles")
+            }
+            "#,
+        )
+        .unwrap();
+        schema.apply_naming(NamingCase::SnakeCase, NamingCase::SnakeCasePlural);
+
+        let profile = schema.get_model("UserProfile").unwrap();
+        assert_eq!(profile.table_name(), "user_profiles");
+        let column = |name: &str| profile.fields[name].extract_attributes().map;
+        assert_eq!(column("createdAt").as_deref(), Some("created_at"));
+        assert_eq!(column("legacyId").as_deref(), Some("LegacyID"));
+        assert_eq!(column("id"), None);
+        assert!(!profile.fields["posts"].has_attribute("map"));
+
+        let post = schema.get_model("Post").unwrap();
+        assert_eq!(post.table_name(), "articles");
+        assert_eq!(
+            post.fields["authorId"].extract_attributes().map.as_deref(),
+            Some("author_id")
+        );
+    }
+}
@@ -51,6 +51,13 @@ pub fn parse_schema(input: &str) -> SchemaResult<Schema> {
                 }
                 schema.add_model(model);
             }
+            Rule::mixin_def => {
+                let mut mixin = parse_mixin(pair)?;
+                if let Some(doc) = current_doc.take() {
+                    mixin = mixin.with_documentation(doc);
+                }
+                schema.add_mixin(mixin);
+            }
             Rule::enum_def => {
                 let mut e = parse_enum(pair)?;
                 if let Some(doc) = current_doc.take() {
@@ -105,10 +112,16 @@ pub fn parse_schema(input: &str) -> SchemaResult<Schema> {
         }
     }
 
+    attach_doc_comments(input, &mut schema);
+
+    // Mixins may be declared after the models using them
+    schema.apply_mixins();
+
     info!(
         models = schema.models.len(),
         enums = schema.enums.len(),
         types = schema.types.len(),
+        mixins = schema.mixins.len(),
         views = schema.views.len(),
         policies = schema.policies.len(),
         "Schema parsed successfully"
@@ -116,6 +129,67 @@ pub fn parse_schema(input: &str) -> SchemaResult<Schema> {
     Ok(schema)
 }
 
+/// Attach `///` comments to the models, fields, enums and views they
+/// precede.
+///
+/// The grammar skips doc comments inside blocks like any other comment, so
+/// they are recovered from the source text.
+fn attach_doc_comments(input: &str, schema: &mut Schema) {
+    for model in schema.models.values_mut() {
+        if model.documentation.is_none() {
+            model.documentation = doc_comment_before(input, model.span.start);
+        }
+        for field in model.fields.values_mut() {
+            if field.documentation.is_none() {
+                field.documentation = doc_comment_before(input, field.span.start);
+            }
+        }
+    }
+    for e in schema.enums.values_mut() {
+        if e.documentation.is_none() {
+            e.documentation = doc_comment_before(input, e.span.start);
+        }
+        for variant in &mut e.variants {
+            if variant.documentation.is_none() {
+                variant.documentation = doc_comment_before(input, variant.span.start);
+            }
+        }
+    }
+    for view in schema.views.values_mut() {
+        if view.documentation.is_none() {
+            view.documentation = doc_comment_before(input, view.span.start);
+        }
+        for field in view.fields.values_mut() {
+            if field.documentation.is_none() {
+                field.documentation = doc_comment_before(input, field.span.start);
+            }
+        }
+    }
+}
+
+/// The `///` lines directly above the line containing `offset`.
+fn doc_comment_before(input: &str, offset: usize) -> Option<Documentation> {
+    let line_start = input.get(..offset)?.rfind('\n').map_or(0, |i| i + 1);
+    if !input[line_start..offset].trim().is_empty() {
+        return None;
+    }
+
+    let mut start = line_start;
+    let mut lines = Vec::new();
+    for line in input[..line_start].lines().rev() {
+        let Some(text) = line.trim().strip_prefix("///") else {
+            break;
+        };
+        lines.push(text.trim());
+        start -= line.len() + 1;
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    Some(Documentation::new(lines.join("\n"), Span::new(start, line_start)))
+}
+
 /// Parse a schema from a file.
 pub fn parse_schema_file(path: impl AsRef<Path>) -> SchemaResult<Schema> {
     let path = path.as_ref();
@@ -143,6 +217,17 @@ fn parse_model(pair: pest::iterators::Pair<'_, Rule>) -> SchemaResult<Model> {
 
     for item in inner {
         match item.as_rule() {
+            Rule::mixin_list => {
+                model.mixins = item
+                    .into_inner()
+                    .map(|p| {
+                        Ident::new(
+                            p.as_str(),
+                            Span::new(p.as_span().start(), p.as_span().end()),
+                        )
+                    })
+                    .collect();
+            }
             Rule::field_def => {
                 let field = parse_field(item)?;
                 model.add_field(field);
@@ -173,6 +258,40 @@ fn parse_model(pair: pest::iterators::Pair<'_, Rule>) -> SchemaResult<Model> {
     Ok(model)
 }
 
+/// Parse a mixin definition.
+fn parse_mixin(pair: pest::iterators::Pair<'_, Rule>) -> SchemaResult<Mixin> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+
+    let name_pair = inner.next().unwrap();
+    let name = Ident::new(
+        name_pair.as_str(),
+        Span::new(name_pair.as_span().start(), name_pair.as_span().end()),
+    );
+
+    let mut mixin = Mixin::new(name, Span::new(span.start(), span.end()));
+
+    for item in inner {
+        // Unwrap the model_body_item to get the actual field_def or model_attribute
+        let Some(inner_item) = item.into_inner().next() else {
+            continue;
+        };
+        match inner_item.as_rule() {
+            Rule::field_def => {
+                let field = parse_field(inner_item)?;
+                mixin.add_field(field);
+            }
+            Rule::model_attribute => {
+                let attr = parse_attribute(inner_item)?;
+                mixin.attributes.push(attr);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(mixin)
+}
+
 /// Parse an enum definition.
 fn parse_enum(pair: pest::iterators::Pair<'_, Rule>) -> SchemaResult<Enum> {
     let span = pair.as_span();
@@ -331,13 +450,15 @@ fn parse_field(pair: pest::iterators::Pair<'_, Rule>) -> SchemaResult<Field> {
         }
     }
 
-    Ok(Field::new(
+    let mut field = Field::new(
         name,
         field_type,
         modifier,
         attributes,
         Span::new(span.start(), span.end()),
-    ))
+    );
+    field.extract_validation_from_attributes();
+    Ok(field)
 }
 
 /// Parse a field type with optional modifier.
@@ -489,6 +610,16 @@ fn parse_attribute_value(pair: pest::iterators::Pair<'_, Rule>) -> SchemaResult<
             let values: Result<Vec<_>, _> = pair.into_inner().map(parse_attribute_value).collect();
             Ok(AttributeValue::Array(values?))
         }
+        Rule::object_literal => {
+            let mut entries = vec![];
+            for entry in pair.into_inner() {
+                let mut inner = entry.into_inner();
+                let key = SmolStr::new(inner.next().unwrap().as_str());
+                let value = parse_attribute_value(inner.next().unwrap())?;
+                entries.push((key, value));
+            }
+            Ok(AttributeValue::Object(entries))
+        }
         Rule::attribute_value => {
             // Unwrap nested attribute_value
             parse_attribute_value(pair.into_inner().next().unwrap())
@@ -1186,6 +1317,33 @@ mod tests {
         assert!(user.get_field("email").unwrap().is_unique());
     }
 
+    #[test]
+    fn test_parse_object_argument() {
+        let schema = parse_schema(
+            r#"
+            model Post {
+                id     Int    @id
+                status Status @stateMachine(transitions: {draft: [review], review: [published, draft]})
+            }
+        "#,
+        )
+        .unwrap();
+
+        let post = schema.get_model("Post").unwrap();
+        let attr = post
+            .get_field("status")
+            .unwrap()
+            .get_attribute("stateMachine")
+            .unwrap();
+        let entries = attr.get_arg("transitions").unwrap().as_object().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, "draft");
+        assert_eq!(
+            entries[1].1,
+            AttributeValue::FieldRefList(vec!["published".into(), "draft".into()])
+        );
+    }
+
     #[test]
     fn test_parse_default_int() {
         let schema = parse_schema(
@@ -1383,6 +1541,46 @@ mod tests {
         assert!(post_tag.has_attribute("id"));
     }
 
+    #[test]
+    fn test_parse_model_with_mixins() {
+        let schema = parse_schema(
+            r#"
+            model Post with Timestamps, SoftDelete {
+                id        Int    @id @auto
+                title     String
+                createdAt DateTime @map("created")
+            }
+
+            mixin Timestamps {
+                createdAt DateTime @default(now())
+                updatedAt DateTime @updated_at
+
+                @@index([createdAt])
+            }
+
+            mixin SoftDelete {
+                deletedAt DateTime?
+            }
+        "#,
+        )
+        .unwrap();
+
+        let timestamps = schema.get_mixin("Timestamps").unwrap();
+        assert_eq!(timestamps.fields.len(), 2);
+
+        let post = schema.get_model("Post").unwrap();
+        let mixins: Vec<_> = post.mixins.iter().map(|m| m.as_str()).collect();
+        assert_eq!(mixins, vec!["Timestamps", "SoftDelete"]);
+        let fields: Vec<_> = post.fields.keys().map(|f| f.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["id", "title", "createdAt", "updatedAt", "deletedAt"]
+        );
+        // The model's own field wins over the mixin's
+        assert!(post.get_field("createdAt").unwrap().has_attribute("map"));
+        assert!(post.has_attribute("index"));
+    }
+
     // ==================== Enum Parsing ====================
 
     #[test]
@@ -1512,11 +1710,50 @@ model User {
         .unwrap();
 
         let user = schema.get_model("User").unwrap();
-        // Documentation parsing is optional - the model should still parse
-        // If documentation is present, it should contain "user"
-        if let Some(doc) = &user.documentation {
-            assert!(doc.text.contains("user"));
-        }
+        assert_eq!(
+            user.documentation.as_ref().unwrap().text,
+            "Represents a user in the system"
+        );
+    }
+
+    #[test]
+    fn test_parse_field_documentation() {
+        let schema = parse_schema(
+            r#"
+            model User {
+                id    Int    @id
+                /// Login address
+                /// Must be verified
+                email String
+
+                // Not a doc comment
+                name  String
+            }
+
+            enum Role {
+                /// Full access
+                ADMIN
+                USER
+            }
+        "#,
+        )
+        .unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        assert!(user.documentation.is_none());
+        assert!(user.get_field("id").unwrap().documentation.is_none());
+        assert_eq!(
+            user.get_field("email").unwrap().documentation.as_ref().unwrap().text,
+            "Login address\nMust be verified"
+        );
+        assert!(user.get_field("name").unwrap().documentation.is_none());
+
+        let role = schema.get_enum("Role").unwrap();
+        assert_eq!(
+            role.variants[0].documentation.as_ref().unwrap().text,
+            "Full access"
+        );
+        assert!(role.variants[1].documentation.is_none());
     }
 
     // ==================== Complete Schema Parsing ====================
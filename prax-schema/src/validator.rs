@@ -118,6 +118,15 @@ impl Validator {
 
     /// Validate a model definition.
     fn validate_model(&mut self, model: &Model, schema: &Schema) {
+        for mixin in &model.mixins {
+            if schema.get_mixin(mixin.as_str()).is_none() {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!("unknown mixin `{}`", mixin.as_str()),
+                ));
+            }
+        }
+
         // Check for @id field
         let id_fields: Vec<_> = model.fields.values().filter(|f| f.is_id()).collect();
         if id_fields.is_empty() && !self.has_composite_id(model) {
@@ -131,9 +140,20 @@ impl Validator {
             self.validate_field(field, model.name(), schema);
         }
 
+        if model.fields.values().filter(|f| f.is_subject_id()).count() > 1 {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                "only one field can be marked @subjectId",
+            ));
+        }
+
+        if let Some(table) = model.translation_table_name() {
+            self.validate_translations(model, &table, schema);
+        }
+
         // Validate model attributes
         for attr in &model.attributes {
-            self.validate_model_attribute(attr, model);
+            self.validate_model_attribute(attr, model, schema);
         }
     }
 
@@ -184,6 +204,64 @@ impl Validator {
             _ => {}
         }
 
+        // SQL databases without range types store a range in two bound columns
+        let is_range = matches!(&field.field_type, FieldType::Scalar(s) if s.is_range());
+        if let Some(provider) = schema
+            .datasource()
+            .map(|ds| &ds.provider)
+            .filter(|p| is_range && **p == DatabaseProvider::MongoDB)
+        {
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                format!("{} is not supported by {}", field.field_type, provider),
+            ));
+        }
+
+        // Spatial types are provided by PostGIS
+        let is_spatial = matches!(&field.field_type, FieldType::Scalar(s) if s.is_spatial());
+        if let Some(provider) = schema
+            .datasource()
+            .map(|ds| &ds.provider)
+            .filter(|p| is_spatial && **p != DatabaseProvider::PostgreSQL)
+        {
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                format!(
+                    "{} requires PostgreSQL with PostGIS, not {}",
+                    field.field_type, provider
+                ),
+            ));
+        }
+        if let Some(native) = field
+            .extract_attributes()
+            .native_type
+            .filter(|n| matches!(n.name.as_str(), "Geometry" | "Geography"))
+        {
+            self.validate_spatial_type(&native, field, model_name);
+        }
+
+        // Money is stored in an amount and a currency column
+        if matches!(field.field_type, FieldType::Scalar(ScalarType::Money)) {
+            if field.is_list() {
+                self.errors.push(SchemaError::invalid_field(
+                    model_name,
+                    field.name(),
+                    "Money lists are not supported",
+                ));
+            }
+            for name in ["id", "unique", "default"] {
+                if field.has_attribute(name) {
+                    self.errors.push(SchemaError::invalid_field(
+                        model_name,
+                        field.name(),
+                        format!("@{} is not supported on Money fields", name),
+                    ));
+                }
+            }
+        }
+
         // Validate field attributes
         for attr in &field.attributes {
             self.validate_field_attribute(attr, field, model_name, schema);
@@ -214,6 +292,37 @@ impl Validator {
         }
     }
 
+    /// Validate a `@db.Geometry(Point, 4326)` native type.
+    fn validate_spatial_type(&mut self, native: &NativeType, field: &Field, model_name: &str) {
+        let mut error = |message: String| {
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                message,
+            ));
+        };
+
+        if !matches!(&field.field_type, FieldType::Scalar(s) if s.as_str() == native.name) {
+            error(format!("@db.{0} requires a {0} field", native.name));
+        }
+        if native.args.len() > 2 {
+            error(format!(
+                "@db.{} takes a geometry kind and an SRID",
+                native.name
+            ));
+        }
+        if let Some(kind) = native.args.first() {
+            if kind.as_ident().and_then(GeometryKind::from_str).is_none() {
+                error(format!("unknown geometry kind {:?}", kind));
+            }
+        }
+        if let Some(srid) = native.args.get(1) {
+            if srid.as_int().and_then(|s| i32::try_from(s).ok()).is_none() {
+                error(format!("invalid SRID {:?}", srid));
+            }
+        }
+    }
+
     /// Validate a field attribute.
     fn validate_field_attribute(
         &mut self,
@@ -271,6 +380,95 @@ impl Validator {
                     });
                 }
             }
+            "personalData" | "subjectId" if field.field_type.is_relation() => {
+                self.errors.push(SchemaError::InvalidAttribute {
+                    attribute: attr.name().to_string(),
+                    message: format!(
+                        "@{} cannot be applied to relation field '{}.{}'",
+                        attr.name(),
+                        model_name,
+                        field.name()
+                    ),
+                });
+            }
+            "personalData" => match PersonalData::from_attribute(attr) {
+                Ok(data) => {
+                    let anonymize = data.strategy == ErasureStrategy::Anonymize;
+                    if anonymize && data.replacement.is_none() && !field.is_optional() {
+                        self.errors.push(SchemaError::invalid_field(
+                            model_name,
+                            field.name(),
+                            "required personal data needs a `replace` value to be anonymized",
+                        ));
+                    }
+                    if anonymize && data.replacement.is_some() && field.is_unique() {
+                        self.errors.push(SchemaError::invalid_field(
+                            model_name,
+                            field.name(),
+                            "unique personal data cannot be anonymized with a fixed `replace` value",
+                        ));
+                    }
+                }
+                Err(message) => {
+                    self.errors.push(SchemaError::invalid_field(
+                        model_name,
+                        field.name(),
+                        format!("invalid @personalData: {}", message),
+                    ));
+                }
+            },
+            "stateMachine" => self.validate_state_machine(attr, field, model_name, schema),
+            "translatable" => {
+                let is_string = matches!(field.field_type, FieldType::Scalar(ScalarType::String));
+                if !is_string || field.is_list() {
+                    self.errors.push(SchemaError::invalid_field(
+                        model_name,
+                        field.name(),
+                        "@translatable can only be applied to String fields",
+                    ));
+                }
+                if field.is_id() || field.is_unique() {
+                    self.errors.push(SchemaError::invalid_field(
+                        model_name,
+                        field.name(),
+                        "@translatable fields cannot be @id or @unique",
+                    ));
+                }
+            }
+            "virtual" => {
+                if field
+                    .virtual_expr()
+                    .is_none_or(|expr| expr.trim().is_empty())
+                {
+                    self.errors.push(SchemaError::invalid_field(
+                        model_name,
+                        field.name(),
+                        "@virtual requires an `expr` string, e.g. @virtual(expr: \"a * b\")",
+                    ));
+                }
+                if field.is_relation() || field.is_list() {
+                    self.errors.push(SchemaError::invalid_field(
+                        model_name,
+                        field.name(),
+                        "@virtual can only be applied to scalar or enum fields",
+                    ));
+                }
+                let stored = [
+                    "id",
+                    "auto",
+                    "unique",
+                    "default",
+                    "updated_at",
+                    "translatable",
+                ];
+                if let Some(other) = stored.iter().find(|name| field.has_attribute(name)) {
+                    self.errors.push(SchemaError::invalid_field(
+                        model_name,
+                        field.name(),
+                        format!("@virtual fields are not stored and cannot be @{}", other),
+                    ));
+                }
+            }
             "updated_at" => {
                 // @updated_at should only be on DateTime
                 if !matches!(field.field_type, FieldType::Scalar(ScalarType::DateTime)) {
@@ -284,10 +482,355 @@ impl Validator {
                     });
                 }
             }
+            "email" | "regex" | "length" | "range" => {
+                self.validate_shorthand_validation(attr, field, model_name)
+            }
             _ => {}
         }
     }
 
+    /// Validate `@email`, `@regex`, `@length` and `@range` against the
+    /// field's type and check their arguments.
+    fn validate_shorthand_validation(&mut self, attr: &Attribute, field: &Field, model_name: &str) {
+        let name = attr.name();
+        let applies = match (name, &field.field_type) {
+            (_, _) if field.is_list() => false,
+            ("range", FieldType::Scalar(scalar)) => matches!(
+                scalar,
+                ScalarType::Int | ScalarType::BigInt | ScalarType::Float
+            ),
+            (_, FieldType::Scalar(scalar)) => *scalar == ScalarType::String,
+            _ => false,
+        };
+        if !applies {
+            let types = if name == "range" {
+                "Int, BigInt or Float"
+            } else {
+                "String"
+            };
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                format!("@{} can only be applied to {} fields", name, types),
+            ));
+            return;
+        }
+
+        let Some(rule) = field.shorthand_validation(attr) else {
+            let usage = match name {
+                "regex" => "a pattern string, e.g. @regex(\"^[a-z]+$\")",
+                "length" => "non-negative integer bounds, e.g. @length(min: 3, max: 120)",
+                "range" => "numeric bounds, e.g. @range(min: 0, max: 100)",
+                _ => "no arguments",
+            };
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                format!("@{} requires {}", name, usage),
+            ));
+            return;
+        };
+
+        let message = match rule.rule_type {
+            ValidationType::Length { min, max } if min > max => {
+                Some(format!("@length min {} is greater than max {}", min, max))
+            }
+            ValidationType::Range { min, max } if min > max => {
+                Some(format!("@range min {} is greater than max {}", min, max))
+            }
+            ValidationType::Regex(ref pattern) => regex_lite::Regex::new(pattern)
+                .err()
+                .map(|e| format!("@regex pattern is invalid: {}", e)),
+            _ => None,
+        };
+        if let Some(message) = message {
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                message,
+            ));
+        }
+    }
+
+    /// Validate the index type and operator classes of an `@@index` or
+    /// `@@unique`, and the constraint options of an `@@unique`, against the
+    /// datasource provider.
+    fn validate_index(&mut self, attr: &Attribute, model: &Model, schema: &Schema) {
+        let index = match Index::from_attribute(attr) {
+            Ok(index) => index,
+            Err(message) => {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!("invalid @@{}: {}", attr.name(), message),
+                ));
+                return;
+            }
+        };
+        let index_type = index.index_type.unwrap_or(IndexType::BTree);
+        let provider = schema.datasource().map(|ds| &ds.provider);
+
+        if let Some(provider) = provider.filter(|p| !index_type.is_supported_by(p)) {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                format!(
+                    "@@{} type {} is not supported by {}",
+                    attr.name(),
+                    index_type.as_sql(),
+                    provider
+                ),
+            ));
+        }
+
+        let has_ops = index.fields.iter().any(|f| f.ops.is_some()) || index.vector_ops.is_some();
+        if has_ops && provider.is_some_and(|p| *p != DatabaseProvider::PostgreSQL) {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                format!(
+                    "@@{} operator classes are only supported by PostgreSQL",
+                    attr.name()
+                ),
+            ));
+        }
+
+        for ops in index.fields.iter().filter_map(|f| f.ops.as_deref()) {
+            let method = IndexType::for_ops_class(ops);
+            if let Some(method) = method.filter(|m| *m != index_type) {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@{} operator class '{}' belongs to {} indexes, not {}",
+                        attr.name(),
+                        ops,
+                        method.as_sql(),
+                        index_type.as_sql()
+                    ),
+                ));
+            }
+        }
+
+        // Both options change how PostgreSQL enforces the constraint; other
+        // databases would silently enforce something else
+        for (option, used) in [
+            ("nullsNotDistinct", index.nulls_not_distinct),
+            ("deferrable", index.deferrable.is_some()),
+        ] {
+            if used && provider.is_some_and(|p| *p != DatabaseProvider::PostgreSQL) {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!("@@unique `{}` is only supported by PostgreSQL", option),
+                ));
+            }
+        }
+
+        // Only constraints can be deferred, and constraints are always
+        // plain B-tree indexes
+        if index.deferrable.is_some() && (index.index_type.is_some() || has_ops) {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                "a deferrable @@unique can't set an index `type` or `ops`",
+            ));
+        }
+    }
+
+    /// Validate an `@@exclude` exclusion constraint.
+    fn validate_exclusion(&mut self, attr: &Attribute, model: &Model, schema: &Schema) {
+        let constraint = match ExclusionConstraint::from_attribute(attr) {
+            Ok(constraint) => constraint,
+            Err(message) => {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!("invalid @@exclude: {}", message),
+                ));
+                return;
+            }
+        };
+
+        if let Some(provider) = schema
+            .datasource()
+            .map(|ds| &ds.provider)
+            .filter(|p| **p != DatabaseProvider::PostgreSQL)
+        {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                format!("@@exclude is not supported by {}", provider),
+            ));
+        }
+
+        for element in &constraint.elements {
+            let Some(field) = model.fields.get(element.field.as_str()) else {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@exclude references non-existent field '{}'",
+                        element.field
+                    ),
+                ));
+                continue;
+            };
+            if field.is_list() || field.is_relation() {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@exclude field '{}' must be a scalar, enum or range column",
+                        element.field
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// Validate `@stateMachine` transitions against the field's enum.
+    fn validate_state_machine(
+        &mut self,
+        attr: &Attribute,
+        field: &Field,
+        model_name: &str,
+        schema: &Schema,
+    ) {
+        let enum_def = match &field.field_type {
+            FieldType::Enum(name) | FieldType::Model(name) => schema.get_enum(name),
+            _ => None,
+        };
+        let Some(enum_def) = enum_def else {
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                "@stateMachine can only be applied to enum fields",
+            ));
+            return;
+        };
+        if field.is_optional() || field.is_list() {
+            self.errors.push(SchemaError::invalid_field(
+                model_name,
+                field.name(),
+                "@stateMachine fields must be required and hold a single value",
+            ));
+        }
+
+        match StateMachine::from_attribute(attr) {
+            Ok(machine) => {
+                for state in machine.states() {
+                    if enum_def.get_variant(state).is_none() {
+                        self.errors.push(SchemaError::invalid_field(
+                            model_name,
+                            field.name(),
+                            format!(
+                                "@stateMachine state '{}' is not a variant of enum '{}'",
+                                state,
+                                enum_def.name()
+                            ),
+                        ));
+                    }
+                }
+            }
+            Err(message) => {
+                self.errors.push(SchemaError::invalid_field(
+                    model_name,
+                    field.name(),
+                    format!("invalid @stateMachine: {}", message),
+                ));
+            }
+        }
+    }
+
+    /// Validate that the translations of a model can be keyed by its primary key.
+    fn validate_translations(&mut self, model: &Model, table: &str, schema: &Schema) {
+        if model.id_fields().len() != 1 {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                "models with @translatable fields need a single @id field",
+            ));
+        }
+        if schema.models.values().any(|m| m.table_name() == table) {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                format!(
+                    "translation table '{}' clashes with an existing model",
+                    table
+                ),
+            ));
+        }
+    }
+
+    /// Validate that a `@@tree` model can reference its parent by primary key.
+    fn validate_tree(&mut self, attr: &Attribute, model: &Model) {
+        let tree = match Tree::from_attribute(attr) {
+            Ok(tree) => tree,
+            Err(message) => {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!("invalid @@tree: {}", message),
+                ));
+                return;
+            }
+        };
+
+        let ids = model.id_fields();
+        let [id] = ids.as_slice() else {
+            self.errors.push(SchemaError::invalid_model(
+                model.name(),
+                "@@tree requires a single-field primary key",
+            ));
+            return;
+        };
+
+        match model.fields.get(tree.parent.as_str()) {
+            Some(parent) if parent.is_relation() || parent.is_list() => {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@tree parent '{}' must be the scalar field holding the parent's key",
+                        tree.parent
+                    ),
+                ));
+            }
+            Some(parent) if !parent.is_optional() => {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@tree parent '{}' must be optional, since root nodes have no parent",
+                        tree.parent
+                    ),
+                ));
+            }
+            Some(parent) if parent.field_type != id.field_type => {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@tree parent '{}' must have the same type as the primary key '{}'",
+                        tree.parent,
+                        id.name()
+                    ),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@tree references non-existent parent field '{}'",
+                        tree.parent
+                    ),
+                ));
+            }
+        }
+
+        if tree.strategy == TreeStrategy::MaterializedPath {
+            let clash = model.fields.values().any(|f| {
+                f.extract_attributes().map.as_deref().unwrap_or(f.name()) == Tree::PATH_COLUMN
+            });
+            if clash {
+                self.errors.push(SchemaError::invalid_model(
+                    model.name(),
+                    format!(
+                        "@@tree(strategy: materialized_path) adds a `{}` column, which a field already maps to",
+                        Tree::PATH_COLUMN
+                    ),
+                ));
+            }
+        }
+    }
+
     /// Validate a default value matches the field type.
     fn validate_default_value(
         &mut self,
@@ -366,7 +909,7 @@ impl Validator {
     }
 
     /// Validate a model-level attribute.
-    fn validate_model_attribute(&mut self, attr: &Attribute, model: &Model) {
+    fn validate_model_attribute(&mut self, attr: &Attribute, model: &Model, schema: &Schema) {
         match attr.name() {
             "index" | "unique" => {
                 // Validate referenced fields exist
@@ -384,6 +927,7 @@ impl Validator {
                         }
                     }
                 }
+                self.validate_index(attr, model, schema);
             }
             "id" => {
                 // Composite primary key
@@ -422,43 +966,164 @@ impl Validator {
                     }
                 }
             }
-            _ => {}
-        }
-    }
-
-    /// Validate an enum definition.
-    fn validate_enum(&mut self, e: &Enum) {
-        if e.variants.is_empty() {
-            self.errors.push(SchemaError::invalid_model(
-                e.name(),
-                "enum must have at least one variant".to_string(),
-            ));
-        }
-
-        // Check for duplicate variant names
-        let mut seen = std::collections::HashSet::new();
-        for variant in &e.variants {
-            if !seen.insert(variant.name()) {
-                self.errors.push(SchemaError::duplicate(
-                    format!("enum variant in {}", e.name()),
-                    variant.name(),
-                ));
-            }
-        }
-    }
-
-    /// Validate a composite type definition.
-    fn validate_composite_type(&mut self, t: &CompositeType, schema: &Schema) {
-        if t.fields.is_empty() {
-            self.errors.push(SchemaError::invalid_model(
-                t.name(),
-                "composite type must have at least one field".to_string(),
-            ));
-        }
-
-        // Validate field types
-        for field in t.fields.values() {
-            match &field.field_type {
+            "retention" => match RetentionPolicy::from_attribute(attr) {
+                Ok(policy) => match model.fields.get(policy.field.as_str()) {
+                    Some(field)
+                        if !matches!(field.field_type, FieldType::Scalar(ScalarType::DateTime)) =>
+                    {
+                        self.errors.push(SchemaError::invalid_model(
+                            model.name(),
+                            format!(
+                                "@@retention field '{}' must be of type DateTime",
+                                policy.field
+                            ),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        self.errors.push(SchemaError::invalid_model(
+                            model.name(),
+                            format!(
+                                "@@retention references non-existent field '{}'",
+                                policy.field
+                            ),
+                        ));
+                    }
+                },
+                Err(message) => {
+                    self.errors.push(SchemaError::invalid_model(
+                        model.name(),
+                        format!("invalid @@retention: {}", message),
+                    ));
+                }
+            },
+            "ttl" => match model.ttl_field() {
+                Some(field)
+                    if !matches!(field.field_type, FieldType::Scalar(ScalarType::DateTime)) =>
+                {
+                    self.errors.push(SchemaError::invalid_model(
+                        model.name(),
+                        format!("@@ttl field '{}' must be of type DateTime", field.name()),
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    self.errors.push(SchemaError::invalid_model(
+                        model.name(),
+                        "@@ttl must name an existing field, as in @@ttl(field: expiresAt)",
+                    ));
+                }
+            },
+            "versioned" => {
+                for arg in &attr.args {
+                    match arg.name.as_ref().map(|n| n.as_str()) {
+                        Some("table") if arg.value.as_string().is_some() => {}
+                        Some("table") => {
+                            self.errors.push(SchemaError::invalid_model(
+                                model.name(),
+                                "@@versioned `table` must be a string",
+                            ));
+                        }
+                        _ => {
+                            self.errors.push(SchemaError::invalid_model(
+                                model.name(),
+                                "@@versioned only accepts a `table` argument",
+                            ));
+                        }
+                    }
+                }
+            }
+            "audited" => {
+                for arg in &attr.args {
+                    match (arg.name.as_ref().map(|n| n.as_str()), &arg.value) {
+                        (Some("exclude"), AttributeValue::FieldRefList(fields)) => {
+                            for field_name in fields {
+                                if !model.fields.contains_key(field_name.as_str()) {
+                                    self.errors.push(SchemaError::invalid_model(
+                                        model.name(),
+                                        format!(
+                                            "@@audited excludes non-existent field '{}'",
+                                            field_name
+                                        ),
+                                    ));
+                                }
+                            }
+                        }
+                        (Some("exclude"), _) => {
+                            self.errors.push(SchemaError::invalid_model(
+                                model.name(),
+                                "@@audited `exclude` must be a list of fields",
+                            ));
+                        }
+                        _ => {
+                            self.errors.push(SchemaError::invalid_model(
+                                model.name(),
+                                "@@audited only accepts an `exclude` argument",
+                            ));
+                        }
+                    }
+                }
+            }
+            "exclude" => self.validate_exclusion(attr, model, schema),
+            "schema" => {
+                match attr.first_arg().and_then(|v| v.as_string()) {
+                    Some(name) if !name.is_empty() && !name.contains('.') => {}
+                    _ => {
+                        self.errors.push(SchemaError::invalid_model(
+                            model.name(),
+                            "@@schema requires a schema name string, e.g. @@schema(\"accounting\")",
+                        ));
+                    }
+                }
+                if let Some(provider) = schema
+                    .datasource()
+                    .map(|ds| &ds.provider)
+                    .filter(|p| **p != DatabaseProvider::PostgreSQL)
+                {
+                    self.errors.push(SchemaError::invalid_model(
+                        model.name(),
+                        format!("@@schema is not supported by {}", provider),
+                    ));
+                }
+            }
+            "tree" => self.validate_tree(attr, model),
+            _ => {}
+        }
+    }
+
+    /// Validate an enum definition.
+    fn validate_enum(&mut self, e: &Enum) {
+        if e.variants.is_empty() {
+            self.errors.push(SchemaError::invalid_model(
+                e.name(),
+                "enum must have at least one variant".to_string(),
+            ));
+        }
+
+        // Check for duplicate variant names
+        let mut seen = std::collections::HashSet::new();
+        for variant in &e.variants {
+            if !seen.insert(variant.name()) {
+                self.errors.push(SchemaError::duplicate(
+                    format!("enum variant in {}", e.name()),
+                    variant.name(),
+                ));
+            }
+        }
+    }
+
+    /// Validate a composite type definition.
+    fn validate_composite_type(&mut self, t: &CompositeType, schema: &Schema) {
+        if t.fields.is_empty() {
+            self.errors.push(SchemaError::invalid_model(
+                t.name(),
+                "composite type must have at least one field".to_string(),
+            ));
+        }
+
+        // Validate field types
+        for field in t.fields.values() {
+            match &field.field_type {
                 FieldType::Model(_) => {
                     self.errors.push(SchemaError::invalid_field(
                         t.name(),
@@ -717,6 +1382,772 @@ mod tests {
         assert_eq!(schema.models.len(), 1);
     }
 
+    #[test]
+    fn test_validate_custom_namespaced_attributes() {
+        let schema = validate_schema(
+            r#"
+            model Post {
+                id    Int    @id @auto
+                title String @search.indexed @search.boost(2)
+                @@audit.track("all")
+            }
+        "#,
+        )
+        .unwrap();
+
+        let post = schema.get_model("Post").unwrap();
+        assert_eq!(post.custom_attributes().namespaces(), vec!["audit"]);
+        let title = post.get_field("title").unwrap();
+        assert_eq!(
+            title
+                .custom_attributes()
+                .get("search", "boost")
+                .and_then(|a| a.first_arg())
+                .and_then(|v| v.as_int()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_validate_retention_policy() {
+        let schema = validate_schema(
+            r#"
+            model AuditLog {
+                id        Int      @id @auto
+                createdAt DateTime @default(now())
+                @@retention(field: createdAt, keep: "90d", strategy: archive)
+            }
+        "#,
+        )
+        .unwrap();
+
+        let policy = schema
+            .get_model("AuditLog")
+            .unwrap()
+            .retention_policy()
+            .unwrap();
+        assert_eq!(policy.field, "createdAt");
+        assert_eq!(policy.keep, std::time::Duration::from_secs(90 * 86_400));
+        assert_eq!(policy.strategy, RetentionStrategy::Archive);
+    }
+
+    #[test]
+    fn test_validate_retention_policy_errors() {
+        let result = validate_schema(
+            r#"
+            model AuditLog {
+                id      Int    @id @auto
+                message String
+                @@retention(field: message, keep: "90d")
+            }
+        "#,
+        );
+        assert!(result.is_err());
+
+        let result = validate_schema(
+            r#"
+            model AuditLog {
+                id        Int      @id @auto
+                createdAt DateTime
+                @@retention(field: createdAt, keep: "soon")
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_ttl() {
+        let schema = validate_schema(
+            r#"
+            model Session {
+                id        Int       @id @auto
+                expiresAt DateTime?
+                @@ttl(field: expiresAt)
+            }
+        "#,
+        )
+        .unwrap();
+        let model = schema.get_model("Session").unwrap();
+        assert_eq!(model.ttl_field().unwrap().name(), "expiresAt");
+
+        let result = validate_schema(
+            r#"
+            model Session {
+                id    Int    @id @auto
+                token String
+                @@ttl(field: token)
+            }
+        "#,
+        );
+        assert!(result.is_err());
+
+        let result = validate_schema(
+            r#"
+            model Session {
+                id Int @id @auto
+                @@ttl(field: expiresAt)
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_versioned() {
+        let schema = validate_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                email String
+                @@map("users")
+                @@versioned
+            }
+
+            model Order {
+                id    Int @id @auto
+                total Int
+                @@versioned(table: "order_versions")
+            }
+        "#,
+        )
+        .unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        assert!(user.is_versioned());
+        assert_eq!(user.history_table_name().as_deref(), Some("users_history"));
+        assert_eq!(
+            schema.get_model("Order").unwrap().history_table_name().as_deref(),
+            Some("order_versions")
+        );
+
+        let result = validate_schema(
+            r#"
+            model User {
+                id Int @id @auto
+                @@versioned(table: users_history)
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_audited() {
+        let schema = validate_schema(
+            r#"
+            model User {
+                id           Int    @id @auto
+                email        String
+                passwordHash String
+                @@audited(exclude: [passwordHash])
+            }
+
+            model Post {
+                id    Int    @id @auto
+                title String
+            }
+        "#,
+        )
+        .unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        assert!(user.is_audited());
+        let excluded: Vec<_> = user
+            .audit_excluded_fields()
+            .iter()
+            .map(|f| f.name())
+            .collect();
+        assert_eq!(excluded, vec!["passwordHash"]);
+        assert!(!schema.get_model("Post").unwrap().is_audited());
+
+        let result = validate_schema(
+            r#"
+            model User {
+                id Int @id @auto
+                @@audited(exclude: [password])
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_state_machine() {
+        let schema = validate_schema(
+            r#"
+            enum PostStatus {
+                draft
+                review
+                published
+            }
+
+            model Post {
+                id     Int        @id @auto
+                status PostStatus @default(draft) @stateMachine(transitions: {draft: [review], review: [published, draft]})
+            }
+        "#,
+        )
+        .unwrap();
+
+        let post = schema.get_model("Post").unwrap();
+        let machine = post.get_field("status").unwrap().state_machine().unwrap();
+        assert!(machine.allows("draft", "review"));
+        assert!(machine.allows("review", "draft"));
+        assert!(!machine.allows("draft", "published"));
+        assert!(machine.targets("published").is_empty());
+
+        let result = validate_schema(
+            r#"
+            enum PostStatus {
+                draft
+                published
+            }
+
+            model Post {
+                id     Int        @id @auto
+                status PostStatus @stateMachine(transitions: {draft: [archived]})
+            }
+        "#,
+        );
+        assert!(result.is_err());
+
+        let result = validate_schema(
+            r#"
+            model Post {
+                id     Int    @id @auto
+                status String @stateMachine(transitions: {draft: [published]})
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_tree() {
+        let schema = validate_schema(
+            r#"
+            model Category {
+                id       Int        @id @auto
+                name     String
+                parentId Int?
+                parent   Category?  @relation("CategoryTree", fields: [parentId], references: [id])
+                children Category[] @relation("CategoryTree")
+
+                @@map("categories")
+                @@tree(strategy: materialized_path)
+            }
+        "#,
+        )
+        .unwrap();
+
+        let tree = schema.get_model("Category").unwrap().tree().unwrap();
+        assert_eq!(tree.strategy, TreeStrategy::MaterializedPath);
+        assert_eq!(tree.parent, "parentId");
+        assert_eq!(tree.closure_table_name("categories"), None);
+
+        // The parent must be optional
+        let result = validate_schema(
+            r#"
+            model Category {
+                id     Int @id @auto
+                parent Int
+                @@tree(strategy: closure_table, parent: parent)
+            }
+        "#,
+        );
+        assert!(result.is_err());
+
+        let result = validate_schema(
+            r#"
+            model Category {
+                id       Int @id @auto
+                parentId Int?
+                @@tree(strategy: nested_set)
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_translatable() {
+        let schema = validate_schema(
+            r#"
+            model Post {
+                id    Int     @id @auto
+                slug  String  @unique
+                title String  @translatable
+                body  String? @translatable
+            }
+        "#,
+        )
+        .unwrap();
+
+        let post = schema.get_model("Post").unwrap();
+        assert_eq!(post.translatable_fields().len(), 2);
+        assert_eq!(
+            post.translation_table_name(),
+            Some("PostTranslation".to_string())
+        );
+
+        let result = validate_schema(
+            r#"
+            model Post {
+                id    Int @id @auto
+                views Int @translatable
+            }
+        "#,
+        );
+        assert!(result.is_err());
+
+        // The translation table name is taken
+        let result = validate_schema(
+            r#"
+            model Post {
+                id    Int    @id @auto
+                title String @translatable
+            }
+
+            model PostTranslation {
+                id Int @id @auto
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_index_types() {
+        let schema = validate_schema(
+            r#"
+            datasource db {
+                provider = "postgresql"
+            }
+
+            model Post {
+                id    Int    @id @auto
+                title String
+                data  Json
+                @@index([data], type: Gin, ops: JsonbPathOps)
+                @@index([title], type: Gist, ops: gist_trgm_ops)
+                @@index([id], type: Brin)
+            }
+        "#,
+        )
+        .unwrap();
+        let indexes = schema.get_model("Post").unwrap().indexes();
+        assert_eq!(indexes.len(), 3);
+        assert_eq!(indexes[0].fields[0].ops.as_deref(), Some("jsonb_path_ops"));
+
+        for (provider, index) in [
+            (
+                "postgresql",
+                "@@index([data], type: Gist, ops: jsonb_path_ops)",
+            ),
+            ("postgresql", "@@index([data], type: Bitmap)"),
+            (
+                "postgresql",
+                "@@index([data, title], type: Gin, ops: [jsonb_path_ops, gin_trgm_ops, gin_trgm_ops])",
+            ),
+            ("mysql", "@@index([data], type: Gin)"),
+            ("mysql", "@@index([title], ops: text_pattern_ops)"),
+            ("sqlite", "@@index([title], type: Hash)"),
+        ] {
+            let result = validate_schema(&format!(
+                "datasource db {{\n provider = \"{}\"\n}}\n\
+                 model Post {{\n id Int @id @auto\n title String\n data Json\n {}\n}}",
+                provider, index
+            ));
+            assert!(
+                result.is_err(),
+                "{} should be rejected on {}",
+                index,
+                provider
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_unique_options() {
+        let schema = validate_schema(
+            r#"
+            datasource db {
+                provider = "postgresql"
+            }
+
+            model Seat {
+                id        Int       @id @auto
+                email     String
+                deletedAt DateTime?
+                position  Int
+                @@unique([email, deletedAt], nullsNotDistinct: true)
+                @@unique([position], deferrable: InitiallyDeferred)
+            }
+        "#,
+        )
+        .unwrap();
+        let uniques = schema.get_model("Seat").unwrap().unique_constraints();
+        assert_eq!(uniques.len(), 2);
+        assert!(uniques[0].nulls_not_distinct);
+        assert_eq!(uniques[1].deferrable, Some(Deferrable::InitiallyDeferred));
+
+        for (provider, unique) in [
+            ("mysql", "@@unique([email], nullsNotDistinct: true)"),
+            (
+                "sqlite",
+                "@@unique([position], deferrable: InitiallyDeferred)",
+            ),
+            ("postgresql", "@@unique([email], deferrable: Sometime)"),
+            (
+                "postgresql",
+                "@@unique([email], type: Hash, deferrable: true)",
+            ),
+            ("postgresql", "@@index([email], nullsNotDistinct: true)"),
+        ] {
+            let result = validate_schema(&format!(
+                "datasource db {{\n provider = \"{}\"\n}}\n\
+                 model Seat {{\n id Int @id @auto\n email String\n position Int\n {}\n}}",
+                provider, unique
+            ));
+            assert!(
+                result.is_err(),
+                "{} should be rejected on {}",
+                unique,
+                provider
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_exclusion_constraint() {
+        let schema = validate_schema(
+            r#"
+            datasource db {
+                provider = "postgresql"
+            }
+
+            model Booking {
+                id     Int           @id @auto
+                room   Int
+                during DateTimeRange
+                @@exclude([room, during], using: Gist, with: ["=", "&&"])
+            }
+        "#,
+        )
+        .unwrap();
+        let booking = schema.get_model("Booking").unwrap();
+        assert!(matches!(
+            booking.get_field("during").unwrap().field_type,
+            FieldType::Scalar(ScalarType::DateTimeRange)
+        ));
+        let constraints = booking.exclusion_constraints();
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0].elements[1].operator, "&&");
+
+        for (provider, exclude) in [
+            ("mysql", r#"@@exclude([room, during], with: ["=", "&&"])"#),
+            ("mongodb", ""),
+            ("postgresql", r#"@@exclude([room, missing], with: "&&")"#),
+            ("postgresql", r#"@@exclude([during], with: ["=", "&&"])"#),
+            (
+                "postgresql",
+                r#"@@exclude([room, during], with: ["=", "&", "&&"])"#,
+            ),
+            (
+                "postgresql",
+                r#"@@exclude([during], using: Brin, with: "&&")"#,
+            ),
+        ] {
+            let result = validate_schema(&format!(
+                "datasource db {{\n provider = \"{}\"\n}}\n\
+                 model Booking {{\n id Int @id @auto\n room Int\n during DateTimeRange\n {}\n}}",
+                provider, exclude
+            ));
+            assert!(
+                result.is_err(),
+                "{} should be rejected on {}",
+                exclude,
+                provider
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_spatial_fields() {
+        let schema = |provider: &str, field: &str| {
+            validate_schema(&format!(
+                "datasource db {{\n provider = \"{}\"\n}}\n\
+                 model Store {{\n id Int @id @auto\n {}\n}}",
+                provider, field
+            ))
+        };
+
+        let valid = schema(
+            "postgresql",
+            "location Geography @db.Geography(Point, 4326)",
+        )
+        .unwrap();
+        let field = valid
+            .get_model("Store")
+            .unwrap()
+            .get_field("location")
+            .unwrap();
+        assert_eq!(
+            field.spatial_type(),
+            Some(SpatialType {
+                kind: GeometryKind::Point,
+                srid: Some(4326),
+            })
+        );
+        assert!(schema("postgresql", "area Geometry").is_ok());
+
+        for (provider, field) in [
+            ("mysql", "location Geometry"),
+            ("postgresql", "location Geometry @db.Geometry(Circle, 4326)"),
+            (
+                "postgresql",
+                "location Geometry @db.Geometry(Point, \"4326\")",
+            ),
+            ("postgresql", "location Geometry @db.Geography(Point)"),
+            ("postgresql", "location Bytes @db.Geometry(Point)"),
+        ] {
+            assert!(
+                schema(provider, field).is_err(),
+                "{} should be rejected on {}",
+                field,
+                provider
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_money_fields() {
+        let schema = |field: &str| {
+            validate_schema(&format!(
+                "model Order {{
+ id Int @id @auto
+ {}
+}}",
+                field
+            ))
+        };
+
+        assert!(schema("total Money").is_ok());
+        assert!(schema("refund Money?").is_ok());
+        for field in [
+            "total Money @unique",
+            "total Money @default(0)",
+            "totals Money[]",
+        ] {
+            assert!(schema(field).is_err(), "{} should be rejected", field);
+        }
+    }
+
+    #[test]
+    fn test_validate_model_schema() {
+        let schema = validate_schema(
+            r#"
+            datasource db {
+                provider = "postgresql"
+            }
+
+            model Invoice {
+                id Int @id @auto
+                @@schema("accounting")
+                @@map("invoices")
+            }
+        "#,
+        )
+        .unwrap();
+        let invoice = schema.get_model("Invoice").unwrap();
+        assert_eq!(invoice.schema_name(), Some("accounting"));
+        assert_eq!(invoice.qualified_table_name(), "accounting.invoices");
+
+        for (provider, attr) in [
+            ("mysql", r#"@@schema("accounting")"#),
+            ("postgresql", "@@schema(accounting)"),
+            ("postgresql", r#"@@schema("a.b")"#),
+        ] {
+            let result = validate_schema(&format!(
+                "datasource db {{\n provider = \"{}\"\n}}\n\
+                 model Invoice {{\n id Int @id @auto\n {}\n}}",
+                provider, attr
+            ));
+            assert!(
+                result.is_err(),
+                "{} should be rejected on {}",
+                attr,
+                provider
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_shorthand_validation() {
+        let schema = validate_schema(
+            r#"
+            model Post {
+                id     Int     @id @auto
+                title  String  @length(min: 3, max: 120)
+                slug   String  @regex("^[a-z0-9-]+$")
+                author String? @email
+                rating Float   @range(min: 0, max: 5)
+                views  Int     @range(min: 0)
+            }
+        "#,
+        )
+        .unwrap();
+
+        let post = schema.get_model("Post").unwrap();
+        let title = post.get_field("title").unwrap();
+        assert_eq!(
+            title.validation.rules[0].rule_type,
+            ValidationType::Length { min: 3, max: 120 }
+        );
+        let views = post.get_field("views").unwrap();
+        assert_eq!(
+            views.validation.rules[0].rule_type,
+            ValidationType::Min(0.0)
+        );
+
+        for field in [
+            "views Int @length(max: 10)",
+            "title String @range(min: 1)",
+            "title String @length(min: 5, max: 2)",
+            "title String @regex(\"[a-z\")",
+            "title String @regex",
+        ] {
+            let result =
+                validate_schema(&format!("model Post {{\n id Int @id @auto\n {}\n}}", field));
+            assert!(result.is_err(), "{} should be rejected", field);
+        }
+    }
+
+    #[test]
+    fn test_validate_virtual() {
+        let schema = validate_schema(
+            r#"
+            model OrderLine {
+                id        Int     @id @auto
+                quantity  Int
+                unitPrice Decimal @map("unit_price")
+                total     Decimal @virtual(expr: "quantity * unit_price")
+            }
+        "#,
+        )
+        .unwrap();
+
+        let line = schema.get_model("OrderLine").unwrap();
+        let total = line.get_field("total").unwrap();
+        assert!(total.is_virtual());
+        assert_eq!(total.virtual_expr(), Some("quantity * unit_price"));
+
+        let result = validate_schema(
+            r#"
+            model OrderLine {
+                id    Int     @id @auto
+                total Decimal @virtual
+            }
+        "#,
+        );
+        assert!(result.is_err());
+
+        let result = validate_schema(
+            r#"
+            model OrderLine {
+                id    Int     @id @auto
+                total Decimal @virtual(expr: "1") @default(0)
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_mixins() {
+        let schema = validate_schema(
+            r#"
+            mixin Authored {
+                authorId Int
+                author   User @relation(fields: [authorId], references: [id])
+            }
+
+            model User {
+                id    Int    @id @auto
+                posts Post[]
+            }
+
+            model Post with Authored {
+                id Int @id @auto
+            }
+        "#,
+        )
+        .unwrap();
+
+        // Mixin fields take part in relation resolution like any other field
+        assert!(
+            schema
+                .relations_from("Post")
+                .iter()
+                .any(|r| r.to_model == "User")
+        );
+
+        let result = validate_schema(
+            r#"
+            model Post with Timestamps {
+                id Int @id @auto
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_personal_data() {
+        let schema = validate_schema(
+            r#"
+            model User {
+                id    Int     @id @auto @subjectId
+                email String? @unique @personalData
+                name  String  @personalData(replace: "anonymous")
+                bio   String  @personalData(strategy: delete)
+            }
+        "#,
+        )
+        .unwrap();
+
+        let user = schema.get_model("User").unwrap();
+        assert_eq!(user.subject_id_field().unwrap().name(), "id");
+        let name = user.get_field("name").unwrap().personal_data().unwrap();
+        assert_eq!(
+            name.replacement,
+            Some(AttributeValue::String("anonymous".into()))
+        );
+
+        // Required fields need a replacement value
+        let result = validate_schema(
+            r#"
+            model User {
+                id   Int    @id @auto
+                name String @personalData
+            }
+        "#,
+        );
+        assert!(result.is_err());
+
+        // Unique fields cannot share a replacement value
+        let result = validate_schema(
+            r#"
+            model User {
+                id    Int    @id @auto
+                email String @unique @personalData(replace: "gone")
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_validate_model_missing_id() {
         let result = validate_schema(
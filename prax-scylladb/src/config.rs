@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use crate::error::{ScyllaError, ScyllaResult};
+use crate::options::RetryPolicy;
 
 /// Configuration for ScyllaDB connections.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +49,18 @@ pub struct ScyllaConfig {
 
     /// Serial consistency level for LWT.
     serial_consistency: Option<SerialConsistencyLevel>,
+
+    /// Retry policy for failed statements.
+    #[serde(default)]
+    retry_policy: RetryPolicy,
+
+    /// Maximum number of cached prepared statements.
+    #[serde(default = "default_statement_cache_size")]
+    statement_cache_size: usize,
+}
+
+fn default_statement_cache_size() -> usize {
+    1000
 }
 
 /// CQL consistency levels.
@@ -199,6 +212,11 @@ impl ScyllaConfig {
                         "compression" => {
                             builder.compression = Some(value.to_string());
                         }
+                        "statement_cache_size" => {
+                            if let Ok(size) = value.parse() {
+                                builder.statement_cache_size = Some(size);
+                            }
+                        }
                         "consistency" => {
                             builder.consistency = match value.to_uppercase().as_str() {
                                 "ANY" => ConsistencyLevel::Any,
@@ -213,6 +231,18 @@ impl ScyllaConfig {
                                 _ => ConsistencyLevel::Quorum,
                             };
                         }
+                        "serial_consistency" => {
+                            builder.serial_consistency = match value.to_uppercase().as_str() {
+                                "SERIAL" => Some(SerialConsistencyLevel::Serial),
+                                "LOCAL_SERIAL" => Some(SerialConsistencyLevel::LocalSerial),
+                                _ => None,
+                            };
+                        }
+                        "retry_policy" => {
+                            builder.retry_policy = RetryPolicy::parse(value).ok_or_else(|| {
+                                ScyllaError::Configuration(format!("Unknown retry policy: {value}"))
+                            })?;
+                        }
                         _ => {}
                     }
                 }
@@ -299,6 +329,18 @@ impl ScyllaConfig {
     pub fn serial_consistency(&self) -> Option<SerialConsistencyLevel> {
         self.serial_consistency
     }
+
+    /// Get retry policy.
+    #[must_use]
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Get the maximum number of cached prepared statements.
+    #[must_use]
+    pub fn statement_cache_size(&self) -> usize {
+        self.statement_cache_size
+    }
 }
 
 impl Default for ScyllaConfig {
@@ -317,6 +359,8 @@ impl Default for ScyllaConfig {
             compression: None,
             consistency: ConsistencyLevel::Quorum,
             serial_consistency: None,
+            retry_policy: RetryPolicy::Default,
+            statement_cache_size: default_statement_cache_size(),
         }
     }
 }
@@ -337,6 +381,8 @@ pub struct ScyllaConfigBuilder {
     compression: Option<String>,
     consistency: ConsistencyLevel,
     serial_consistency: Option<SerialConsistencyLevel>,
+    retry_policy: RetryPolicy,
+    statement_cache_size: Option<usize>,
 }
 
 impl ScyllaConfigBuilder {
@@ -442,6 +488,22 @@ impl ScyllaConfigBuilder {
         self
     }
 
+    /// Set the retry policy. [`RetryPolicy::DowngradingConsistency`] trades
+    /// consistency for availability and must be opted into.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Set the maximum number of cached prepared statements (0 disables
+    /// caching).
+    #[must_use]
+    pub fn statement_cache_size(mut self, size: usize) -> Self {
+        self.statement_cache_size = Some(size);
+        self
+    }
+
     /// Build the configuration.
     #[must_use]
     pub fn build(self) -> ScyllaConfig {
@@ -471,6 +533,10 @@ impl ScyllaConfigBuilder {
             compression: self.compression,
             consistency: self.consistency,
             serial_consistency: self.serial_consistency,
+            retry_policy: self.retry_policy,
+            statement_cache_size: self
+                .statement_cache_size
+                .unwrap_or_else(default_statement_cache_size),
         }
     }
 }
@@ -486,6 +552,7 @@ mod tests {
         assert_eq!(config.pool_size(), 4);
         assert_eq!(config.connection_timeout(), Duration::from_secs(5));
         assert_eq!(config.consistency(), ConsistencyLevel::Quorum);
+        assert_eq!(config.statement_cache_size(), 1000);
     }
 
     #[test]
@@ -531,12 +598,28 @@ mod tests {
     #[test]
     fn test_from_url_with_params() {
         let config = ScyllaConfig::from_url(
-            "scylla://localhost/ks?timeout=30&pool_size=16&consistency=LOCAL_QUORUM",
+            "scylla://localhost/ks?timeout=30&pool_size=16&consistency=LOCAL_QUORUM&statement_cache_size=0",
         )
         .unwrap();
         assert_eq!(config.request_timeout(), Duration::from_secs(30));
         assert_eq!(config.pool_size(), 16);
+        assert_eq!(config.statement_cache_size(), 0);
         assert_eq!(config.consistency(), ConsistencyLevel::LocalQuorum);
+        assert_eq!(config.retry_policy(), RetryPolicy::Default);
+    }
+
+    #[test]
+    fn test_from_url_with_retry_policy() {
+        let config = ScyllaConfig::from_url(
+            "scylla://localhost/ks?serial_consistency=LOCAL_SERIAL&retry_policy=downgrading_consistency",
+        )
+        .unwrap();
+        assert_eq!(
+            config.serial_consistency(),
+            Some(SerialConsistencyLevel::LocalSerial)
+        );
+        assert_eq!(config.retry_policy(), RetryPolicy::DowngradingConsistency);
+        assert!(ScyllaConfig::from_url("scylla://localhost/ks?retry_policy=always").is_err());
     }
 
     #[test]
@@ -100,19 +100,20 @@ pub async fn connect(config: ScyllaConfig) -> ScyllaResult<ScyllaConnection> {
         builder = builder.user(username, password);
     }
 
-    // Set local datacenter if specified
+    // Default consistency and retry policy, preferring the local datacenter
+    // if specified
+    let mut profile = scylla::execution_profile::ExecutionProfile::builder()
+        .consistency(config.consistency().into())
+        .serial_consistency(config.serial_consistency().map(Into::into))
+        .retry_policy(config.retry_policy().to_driver_boxed());
     if let Some(dc) = config.local_datacenter() {
-        builder = builder.default_execution_profile_handle(
-            scylla::execution_profile::ExecutionProfile::builder()
-                .load_balancing_policy(
-                    scylla::load_balancing::DefaultPolicy::builder()
-                        .prefer_datacenter(dc.to_string())
-                        .build(),
-                )
-                .build()
-                .into_handle(),
+        profile = profile.load_balancing_policy(
+            scylla::load_balancing::DefaultPolicy::builder()
+                .prefer_datacenter(dc.to_string())
+                .build(),
         );
     }
+    builder = builder.default_execution_profile_handle(profile.build().into_handle());
 
     // Set compression
     if let Some(compression) = config.compression() {
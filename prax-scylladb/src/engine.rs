@@ -11,8 +11,10 @@ use scylla::serialize::row::SerializeRow;
 use std::marker::PhantomData;
 
 use crate::error::{ScyllaError, ScyllaResult};
+use crate::options::QueryOptions;
 use crate::pool::ScyllaPool;
 use crate::row::FromScyllaRow;
+use crate::statement::StatementCacheStats;
 
 /// The ScyllaDB query engine.
 ///
@@ -21,13 +23,43 @@ use crate::row::FromScyllaRow;
 #[derive(Clone)]
 pub struct ScyllaEngine {
     pool: ScyllaPool,
+    options: QueryOptions,
 }
 
 impl ScyllaEngine {
     /// Create a new engine with the given pool.
     #[must_use]
     pub fn new(pool: ScyllaPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            options: QueryOptions::default(),
+        }
+    }
+
+    /// Create an engine running its statements with the given consistency,
+    /// serial consistency or retry policy instead of the configured ones.
+    ///
+    /// ```rust,no_run
+    /// # use prax_scylladb::{ConsistencyLevel, QueryOptions, ScyllaEngine};
+    /// # async fn example(engine: ScyllaEngine) -> prax_scylladb::ScyllaResult<()> {
+    /// let options = QueryOptions::new().consistency(ConsistencyLevel::LocalOne);
+    /// let local = engine.with_options(options);
+    /// let count = local.count("events", None, &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_options(&self, options: QueryOptions) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            options: self.options.merge(options),
+        }
+    }
+
+    /// Get the per-statement options of this engine.
+    #[must_use]
+    pub fn options(&self) -> &QueryOptions {
+        &self.options
     }
 
     /// Execute a query and return all rows.
@@ -36,7 +68,10 @@ impl ScyllaEngine {
         cql: &str,
         values: impl SerializeRow,
     ) -> ScyllaResult<Vec<T>> {
-        let result = self.pool.execute(cql, values).await?;
+        let result = self
+            .pool
+            .execute_with_options(cql, values, &self.options)
+            .await?;
 
         let rows = result.rows.unwrap_or_default();
         rows.into_iter()
@@ -50,7 +85,10 @@ impl ScyllaEngine {
         cql: &str,
         values: impl SerializeRow,
     ) -> ScyllaResult<Option<T>> {
-        let result = self.pool.execute(cql, values).await?;
+        let result = self
+            .pool
+            .execute_with_options(cql, values, &self.options)
+            .await?;
 
         let rows = result.rows.unwrap_or_default();
         match rows.len() {
@@ -77,19 +115,22 @@ impl ScyllaEngine {
         cql: &str,
         values: impl SerializeRow,
     ) -> ScyllaResult<()> {
-        self.pool.execute(cql, values).await?;
+        self
+            .pool
+            .execute_with_options(cql, values, &self.options)
+            .await?;
         Ok(())
     }
 
     /// Execute a raw CQL query without preparing.
     pub async fn execute_raw(&self, cql: &str) -> ScyllaResult<scylla::QueryResult> {
-        self.pool.query(cql, &[]).await
+        self.pool.query_with_options(cql, &[], &self.options).await
     }
 
     /// Create a new batch operation.
     #[must_use]
     pub fn batch(&self) -> ScyllaBatch {
-        ScyllaBatch::new(self.pool.clone())
+        ScyllaBatch::new(self.pool.clone(), self.options)
     }
 
     /// Insert a row into a table.
@@ -166,7 +207,10 @@ impl ScyllaEngine {
             placeholders.join(", ")
         );
 
-        let result = self.pool.execute(&cql, values).await?;
+        let result = self
+            .pool
+            .execute_with_options(&cql, values, &self.options)
+            .await?;
 
         // Check if the operation was applied
         if let Some(rows) = result.rows {
@@ -197,7 +241,10 @@ impl ScyllaEngine {
             table, set_clause, where_clause, condition
         );
 
-        let result = self.pool.execute(&cql, values).await?;
+        let result = self
+            .pool
+            .execute_with_options(&cql, values, &self.options)
+            .await?;
 
         // Check if the operation was applied
         if let Some(rows) = result.rows {
@@ -225,7 +272,10 @@ impl ScyllaEngine {
             None => format!("SELECT COUNT(*) FROM {}", table),
         };
 
-        let result = self.pool.execute(&cql, values).await?;
+        let result = self
+            .pool
+            .execute_with_options(&cql, values, &self.options)
+            .await?;
 
         if let Some(rows) = result.rows {
             if let Some(first_row) = rows.first() {
@@ -259,6 +309,13 @@ impl ScyllaEngine {
         Ok(count > 0)
     }
 
+    /// Get prepared statement cache statistics: size, hits, misses,
+    /// evictions, re-preparations and per-statement hit counts.
+    #[must_use]
+    pub fn cache_stats(&self) -> StatementCacheStats {
+        self.pool.cache_stats()
+    }
+
     /// Get a reference to the underlying pool.
     #[must_use]
     pub fn pool(&self) -> &ScyllaPool {
@@ -276,6 +333,7 @@ impl std::fmt::Debug for ScyllaEngine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ScyllaEngine")
             .field("pool", &self.pool)
+            .field("options", &self.options)
             .finish()
     }
 }
@@ -285,18 +343,28 @@ pub struct ScyllaBatch {
     pool: ScyllaPool,
     batch: Batch,
     statements: Vec<String>,
+    options: QueryOptions,
 }
 
 impl ScyllaBatch {
     /// Create a new batch.
-    fn new(pool: ScyllaPool) -> Self {
+    fn new(pool: ScyllaPool, options: QueryOptions) -> Self {
         Self {
             pool,
             batch: Batch::default(),
             statements: Vec::new(),
+            options,
         }
     }
 
+    /// Override the consistency, serial consistency or retry policy for
+    /// this batch.
+    #[must_use]
+    pub fn options(mut self, options: QueryOptions) -> Self {
+        self.options = self.options.merge(options);
+        self
+    }
+
     /// Create a logged batch (atomic, with a performance cost).
     #[must_use]
     pub fn logged(mut self) -> Self {
@@ -327,7 +395,8 @@ impl ScyllaBatch {
     }
 
     /// Execute the batch.
-    pub async fn execute(self) -> ScyllaResult<()> {
+    pub async fn execute(mut self) -> ScyllaResult<()> {
+        self.options.apply_to_batch(&mut self.batch);
         // Note: For simplicity, we're executing without values here.
         // In a production implementation, you'd want to support bound values.
         self.pool
@@ -339,7 +408,8 @@ impl ScyllaBatch {
     }
 
     /// Execute the batch with values.
-    pub async fn execute_with_values<V: BatchValues>(self, values: V) -> ScyllaResult<()> {
+    pub async fn execute_with_values<V: BatchValues>(mut self, values: V) -> ScyllaResult<()> {
+        self.options.apply_to_batch(&mut self.batch);
         self.pool
             .session()
             .batch(&self.batch, values)
@@ -365,6 +435,7 @@ impl std::fmt::Debug for ScyllaBatch {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ScyllaBatch")
             .field("statements", &self.statements.len())
+            .field("options", &self.options)
             .finish()
     }
 }
@@ -445,6 +516,7 @@ mod tests {
             pool: unsafe { std::mem::zeroed() }, // Not used in this test
             batch: Batch::default(),
             statements: vec![],
+            options: QueryOptions::default(),
         };
 
         // Just verify the types compile
@@ -10,10 +10,11 @@
 //!
 //! - **High Performance**: Built on the official `scylla` async driver
 //! - **Connection Pooling**: Automatic connection management with configurable pool sizes
-//! - **Prepared Statements**: Efficient query execution with automatic caching
+//! - **Prepared Statements**: Efficient query execution with an LRU statement cache
 //! - **Async/Await**: Full async support with Tokio runtime
 //! - **Type Safety**: Strong typing with automatic CQL type conversions
 //! - **Lightweight Transactions**: Support for conditional updates (LWT)
+//! - **Tunable Consistency**: Per-statement consistency, serial consistency and retry policy
 //!
 //! ## Quick Start
 //!
@@ -100,24 +101,31 @@ mod config;
 mod connection;
 mod engine;
 mod error;
+mod options;
 mod pool;
 mod row;
+mod statement;
 mod types;
 
-pub use config::{ScyllaConfig, ScyllaConfigBuilder};
+pub use config::{ConsistencyLevel, ScyllaConfig, ScyllaConfigBuilder, SerialConsistencyLevel};
 pub use connection::ScyllaConnection;
 pub use engine::{ScyllaBatch, ScyllaEngine};
 pub use error::{ScyllaError, ScyllaResult};
+pub use options::{QueryOptions, RetryPolicy};
 pub use pool::ScyllaPool;
 pub use row::FromScyllaRow;
+pub use statement::{StatementCache, StatementCacheStats, StatementStats};
 pub use types::{ScyllaValue, ToCqlValue};
 
 /// Prelude module for convenient imports.
 pub mod prelude {
-    pub use crate::config::{ScyllaConfig, ScyllaConfigBuilder};
+    pub use crate::config::{
+        ConsistencyLevel, ScyllaConfig, ScyllaConfigBuilder, SerialConsistencyLevel,
+    };
     pub use crate::connection::ScyllaConnection;
     pub use crate::engine::{ScyllaBatch, ScyllaEngine};
     pub use crate::error::{ScyllaError, ScyllaResult};
+    pub use crate::options::{QueryOptions, RetryPolicy};
     pub use crate::pool::ScyllaPool;
     pub use crate::row::FromScyllaRow;
     pub use crate::types::{ScyllaValue, ToCqlValue};
@@ -0,0 +1,266 @@
+//! Per-statement execution options.
+//!
+//! Consistency, serial consistency and the retry policy default to the
+//! cluster-wide values in [`ScyllaConfig`](crate::ScyllaConfig). A
+//! [`QueryOptions`] overrides them for the statements it is applied to, for
+//! instance to read at `ONE` on a latency-sensitive path while writes stay at
+//! `QUORUM`.
+
+use scylla::batch::Batch;
+use scylla::prepared_statement::PreparedStatement;
+use scylla::query::Query;
+use scylla::statement::{Consistency, SerialConsistency};
+use scylla::transport::downgrading_consistency_retry_policy::DowngradingConsistencyRetryPolicy;
+use scylla::transport::retry_policy::{
+    DefaultRetryPolicy, FallthroughRetryPolicy, RetryPolicy as DriverRetryPolicy,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::config::{ConsistencyLevel, SerialConsistencyLevel};
+
+/// How failed statements are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryPolicy {
+    /// The driver's default: retry timeouts and unavailable errors once,
+    /// on the same or the next node, at the same consistency.
+    #[default]
+    Default,
+    /// Never retry; every error is returned to the caller.
+    Fallthrough,
+    /// Retry at a lower consistency level when not enough replicas
+    /// answered. Reads may then return stale data, so this is opt-in.
+    DowngradingConsistency,
+}
+
+impl RetryPolicy {
+    /// Parse a policy name as used in connection URLs.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().replace('-', "_").as_str() {
+            "default" => Some(Self::Default),
+            "fallthrough" | "none" => Some(Self::Fallthrough),
+            "downgrading_consistency" | "downgrading" => Some(Self::DowngradingConsistency),
+            _ => None,
+        }
+    }
+
+    /// Create the driver policy for a statement.
+    pub(crate) fn to_driver(self) -> Arc<dyn DriverRetryPolicy> {
+        Arc::from(self.to_driver_boxed())
+    }
+
+    /// Create the driver policy for an execution profile.
+    pub(crate) fn to_driver_boxed(self) -> Box<dyn DriverRetryPolicy> {
+        match self {
+            Self::Default => Box::new(DefaultRetryPolicy::new()),
+            Self::Fallthrough => Box::new(FallthroughRetryPolicy::new()),
+            Self::DowngradingConsistency => Box::new(DowngradingConsistencyRetryPolicy::new()),
+        }
+    }
+}
+
+impl From<ConsistencyLevel> for Consistency {
+    fn from(level: ConsistencyLevel) -> Self {
+        match level {
+            ConsistencyLevel::Any => Consistency::Any,
+            ConsistencyLevel::One => Consistency::One,
+            ConsistencyLevel::Two => Consistency::Two,
+            ConsistencyLevel::Three => Consistency::Three,
+            ConsistencyLevel::Quorum => Consistency::Quorum,
+            ConsistencyLevel::All => Consistency::All,
+            ConsistencyLevel::LocalQuorum => Consistency::LocalQuorum,
+            ConsistencyLevel::EachQuorum => Consistency::EachQuorum,
+            ConsistencyLevel::LocalOne => Consistency::LocalOne,
+        }
+    }
+}
+
+impl From<SerialConsistencyLevel> for SerialConsistency {
+    fn from(level: SerialConsistencyLevel) -> Self {
+        match level {
+            SerialConsistencyLevel::Serial => SerialConsistency::Serial,
+            SerialConsistencyLevel::LocalSerial => SerialConsistency::LocalSerial,
+        }
+    }
+}
+
+/// Options overriding the configured defaults for individual statements.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use prax_scylladb::{ConsistencyLevel, QueryOptions, RetryPolicy, ScyllaEngine};
+///
+/// async fn recent_events(engine: &ScyllaEngine) -> prax_scylladb::ScyllaResult<()> {
+///     let fast = engine.with_options(
+///         QueryOptions::new()
+///             .consistency(ConsistencyLevel::One)
+///             .retry_policy(RetryPolicy::DowngradingConsistency),
+///     );
+///     fast.execute_raw("SELECT * FROM events LIMIT 100").await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct QueryOptions {
+    /// Consistency level, or `None` for the configured default.
+    #[serde(default)]
+    pub consistency: Option<ConsistencyLevel>,
+    /// Serial consistency for lightweight transactions, or `None` for the
+    /// configured default.
+    #[serde(default)]
+    pub serial_consistency: Option<SerialConsistencyLevel>,
+    /// Retry policy, or `None` for the configured default.
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+}
+
+impl QueryOptions {
+    /// Create options that keep every configured default.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the consistency level.
+    #[must_use]
+    pub fn consistency(mut self, consistency: ConsistencyLevel) -> Self {
+        self.consistency = Some(consistency);
+        self
+    }
+
+    /// Set the serial consistency level for lightweight transactions.
+    #[must_use]
+    pub fn serial_consistency(mut self, consistency: SerialConsistencyLevel) -> Self {
+        self.serial_consistency = Some(consistency);
+        self
+    }
+
+    /// Set the retry policy.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Check whether no option is overridden.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.consistency.is_none()
+            && self.serial_consistency.is_none()
+            && self.retry_policy.is_none()
+    }
+
+    /// Combine with `other`, whose options take precedence.
+    #[must_use]
+    pub fn merge(self, other: QueryOptions) -> Self {
+        Self {
+            consistency: other.consistency.or(self.consistency),
+            serial_consistency: other.serial_consistency.or(self.serial_consistency),
+            retry_policy: other.retry_policy.or(self.retry_policy),
+        }
+    }
+
+    /// Apply the options to an unprepared query.
+    pub fn apply_to_query(&self, query: &mut Query) {
+        if let Some(consistency) = self.consistency {
+            query.set_consistency(consistency.into());
+        }
+        if let Some(serial) = self.serial_consistency {
+            query.set_serial_consistency(Some(serial.into()));
+        }
+        if let Some(policy) = self.retry_policy {
+            query.set_retry_policy(Some(policy.to_driver()));
+        }
+    }
+
+    /// Apply the options to a prepared statement.
+    pub fn apply_to_prepared(&self, statement: &mut PreparedStatement) {
+        if let Some(consistency) = self.consistency {
+            statement.set_consistency(consistency.into());
+        }
+        if let Some(serial) = self.serial_consistency {
+            statement.set_serial_consistency(Some(serial.into()));
+        }
+        if let Some(policy) = self.retry_policy {
+            statement.set_retry_policy(Some(policy.to_driver()));
+        }
+    }
+
+    /// Apply the options to a batch.
+    pub fn apply_to_batch(&self, batch: &mut Batch) {
+        if let Some(consistency) = self.consistency {
+            batch.set_consistency(consistency.into());
+        }
+        if let Some(serial) = self.serial_consistency {
+            batch.set_serial_consistency(Some(serial.into()));
+        }
+        if let Some(policy) = self.retry_policy {
+            batch.set_retry_policy(Some(policy.to_driver()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_builder() {
+        let options = QueryOptions::new()
+            .consistency(ConsistencyLevel::LocalQuorum)
+            .serial_consistency(SerialConsistencyLevel::LocalSerial);
+
+        assert!(QueryOptions::new().is_empty());
+        assert!(!options.is_empty());
+        assert_eq!(options.consistency, Some(ConsistencyLevel::LocalQuorum));
+        assert_eq!(
+            options.serial_consistency,
+            Some(SerialConsistencyLevel::LocalSerial)
+        );
+        assert_eq!(options.retry_policy, None);
+    }
+
+    #[test]
+    fn test_options_merge() {
+        let defaults = QueryOptions::new()
+            .consistency(ConsistencyLevel::Quorum)
+            .retry_policy(RetryPolicy::Fallthrough);
+        let merged = defaults.merge(QueryOptions::new().consistency(ConsistencyLevel::One));
+
+        assert_eq!(merged.consistency, Some(ConsistencyLevel::One));
+        assert_eq!(merged.retry_policy, Some(RetryPolicy::Fallthrough));
+        assert_eq!(merged.serial_consistency, None);
+    }
+
+    #[test]
+    fn test_apply_to_query() {
+        let mut query = Query::new("SELECT * FROM users");
+        QueryOptions::new()
+            .consistency(ConsistencyLevel::LocalOne)
+            .serial_consistency(SerialConsistencyLevel::LocalSerial)
+            .apply_to_query(&mut query);
+
+        assert_eq!(query.get_consistency(), Some(Consistency::LocalOne));
+        assert_eq!(
+            query.get_serial_consistency(),
+            Some(SerialConsistency::LocalSerial)
+        );
+    }
+
+    #[test]
+    fn test_retry_policy_parse() {
+        assert_eq!(
+            RetryPolicy::parse("downgrading-consistency"),
+            Some(RetryPolicy::DowngradingConsistency)
+        );
+        assert_eq!(
+            RetryPolicy::parse("FALLTHROUGH"),
+            Some(RetryPolicy::Fallthrough)
+        );
+        assert_eq!(RetryPolicy::parse("default"), Some(RetryPolicy::Default));
+        assert_eq!(RetryPolicy::parse("sometimes"), None);
+    }
+}
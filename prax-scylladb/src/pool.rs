@@ -6,14 +6,17 @@
 
 use std::sync::Arc;
 use scylla::Session;
-use parking_lot::RwLock;
+use scylla::prepared_statement::PreparedStatement;
+use scylla::transport::errors::{DbError, QueryError};
 
 use crate::config::ScyllaConfig;
 use crate::connection::{connect, ScyllaConnection};
 use crate::engine::ScyllaEngine;
 use crate::error::ScyllaResult;
+use crate::options::QueryOptions;
 #[allow(unused_imports)]
 use crate::error::ScyllaError;
+use crate::statement::{StatementCache, StatementCacheStats};
 
 /// A connection pool for ScyllaDB.
 ///
@@ -26,7 +29,7 @@ pub struct ScyllaPool {
     connection: Arc<ScyllaConnection>,
     config: Arc<ScyllaConfig>,
     /// Cache of prepared statements
-    prepared_cache: Arc<RwLock<std::collections::HashMap<String, scylla::prepared_statement::PreparedStatement>>>,
+    prepared_cache: Arc<StatementCache<PreparedStatement>>,
 }
 
 impl ScyllaPool {
@@ -36,8 +39,8 @@ impl ScyllaPool {
 
         Ok(Self {
             connection: Arc::new(connection),
+            prepared_cache: Arc::new(StatementCache::new(config.statement_cache_size())),
             config: Arc::new(config),
-            prepared_cache: Arc::new(RwLock::new(std::collections::HashMap::new())),
         })
     }
 
@@ -89,44 +92,91 @@ impl ScyllaPool {
             .map_err(Into::into)
     }
 
-    /// Execute a prepared statement with caching.
-    pub async fn execute<V: scylla::serialize::row::SerializeRow>(
+    /// Execute a raw CQL query with per-statement options.
+    pub async fn query_with_options(
         &self,
         query: &str,
-        values: V,
+        values: impl scylla::serialize::row::SerializeRow,
+        options: &QueryOptions,
     ) -> ScyllaResult<scylla::QueryResult> {
-        let prepared = self.prepare(query).await?;
+        let mut statement = scylla::query::Query::new(query);
+        options.apply_to_query(&mut statement);
         self.connection
             .session()
-            .execute_unpaged(&prepared, values)
+            .query_unpaged(statement, values)
             .await
             .map_err(Into::into)
     }
 
-    /// Prepare a statement (cached).
-    pub async fn prepare(&self, query: &str) -> ScyllaResult<scylla::prepared_statement::PreparedStatement> {
-        // Check cache first
-        {
-            let cache = self.prepared_cache.read();
-            if let Some(stmt) = cache.get(query) {
-                return Ok(stmt.clone());
+    /// Execute a prepared statement with caching.
+    ///
+    /// If the cluster no longer knows the cached statement, for instance after
+    /// a node restarted or the schema changed, it is prepared again and the
+    /// query retried once.
+    pub async fn execute<V: scylla::serialize::row::SerializeRow>(
+        &self,
+        query: &str,
+        values: V,
+    ) -> ScyllaResult<scylla::QueryResult> {
+        self.execute_with_options(query, values, &QueryOptions::default())
+            .await
+    }
+
+    /// Execute a prepared statement with caching and per-statement options.
+    ///
+    /// The options apply to this execution only; the cached statement keeps
+    /// the configured defaults.
+    pub async fn execute_with_options<V: scylla::serialize::row::SerializeRow>(
+        &self,
+        query: &str,
+        values: V,
+        options: &QueryOptions,
+    ) -> ScyllaResult<scylla::QueryResult> {
+        let prepared = self.prepare_with_options(query, options).await?;
+        match self.session().execute_unpaged(&prepared, &values).await {
+            Err(QueryError::DbError(DbError::Unprepared { .. }, _)) => {
+                tracing::debug!(cql = %query, "Statement unprepared, preparing again");
+                self.prepared_cache.invalidate(query);
+                let prepared = self.prepare_with_options(query, options).await?;
+                self.session()
+                    .execute_unpaged(&prepared, &values)
+                    .await
+                    .map_err(Into::into)
             }
+            result => result.map_err(Into::into),
         }
+    }
 
-        // Prepare and cache
-        let stmt = self.connection.session().prepare(query).await?;
-        {
-            let mut cache = self.prepared_cache.write();
-            cache.insert(query.to_string(), stmt.clone());
+    /// Prepare a statement (cached).
+    pub async fn prepare(&self, query: &str) -> ScyllaResult<PreparedStatement> {
+        if let Some(stmt) = self.prepared_cache.get(query) {
+            return Ok(stmt);
         }
 
+        let stmt = self.connection.session().prepare(query).await?;
+        self.prepared_cache.insert(query, stmt.clone());
+        Ok(stmt)
+    }
+
+    async fn prepare_with_options(
+        &self,
+        query: &str,
+        options: &QueryOptions,
+    ) -> ScyllaResult<PreparedStatement> {
+        let mut stmt = self.prepare(query).await?;
+        options.apply_to_prepared(&mut stmt);
         Ok(stmt)
     }
 
     /// Clear the prepared statement cache.
     pub fn clear_cache(&self) {
-        let mut cache = self.prepared_cache.write();
-        cache.clear();
+        self.prepared_cache.clear();
+    }
+
+    /// Get prepared statement cache statistics.
+    #[must_use]
+    pub fn cache_stats(&self) -> StatementCacheStats {
+        self.prepared_cache.stats()
     }
 
     /// Check if the pool is healthy.
@@ -143,7 +193,7 @@ impl ScyllaPool {
     #[must_use]
     pub fn stats(&self) -> PoolStats {
         PoolStats {
-            cached_statements: self.prepared_cache.read().len(),
+            cached_statements: self.prepared_cache.len(),
             known_nodes: self.config.known_nodes().len(),
         }
     }
@@ -154,7 +204,7 @@ impl std::fmt::Debug for ScyllaPool {
         f.debug_struct("ScyllaPool")
             .field("keyspace", &self.config.default_keyspace())
             .field("nodes", &self.config.known_nodes())
-            .field("cached_statements", &self.prepared_cache.read().len())
+            .field("cached_statements", &self.prepared_cache.len())
             .finish()
     }
 }
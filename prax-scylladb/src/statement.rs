@@ -0,0 +1,269 @@
+//! Prepared statement cache.
+//!
+//! [`ScyllaPool`](crate::ScyllaPool) keeps every statement it prepares in a
+//! [`StatementCache`] keyed by CQL text. The cache holds at most
+//! `statement_cache_size` statements and evicts the least recently used one
+//! when full, counting hits per statement so [`StatementCacheStats`] can show
+//! which statements are worth keeping.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// A size-bounded cache of prepared statements with LRU eviction.
+pub struct StatementCache<S> {
+    capacity: usize,
+    inner: Mutex<CacheInner<S>>,
+}
+
+struct CacheInner<S> {
+    entries: HashMap<String, CachedStatement<S>>,
+    /// Logical clock, bumped on every access, ordering entries by recency.
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    reprepared: u64,
+}
+
+struct CachedStatement<S> {
+    statement: S,
+    hits: u64,
+    last_used: u64,
+}
+
+impl<S: Clone> StatementCache<S> {
+    /// Create a cache holding at most `capacity` statements.
+    ///
+    /// A capacity of zero disables caching: every lookup misses.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(CacheInner {
+                entries: HashMap::new(),
+                clock: 0,
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+                reprepared: 0,
+            }),
+        }
+    }
+
+    /// Look up the statement prepared for `cql`, counting a hit or a miss.
+    pub fn get(&self, cql: &str) -> Option<S> {
+        let mut inner = self.inner.lock();
+        inner.clock += 1;
+        let now = inner.clock;
+        let statement = inner.entries.get_mut(cql).map(|entry| {
+            entry.hits += 1;
+            entry.last_used = now;
+            entry.statement.clone()
+        });
+        if statement.is_some() {
+            inner.hits += 1;
+        } else {
+            inner.misses += 1;
+        }
+        statement
+    }
+
+    /// Cache the statement prepared for `cql`, evicting the least recently
+    /// used statement if the cache is full.
+    pub fn insert(&self, cql: &str, statement: S) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        inner.clock += 1;
+        let now = inner.clock;
+        if let Some(entry) = inner.entries.get_mut(cql) {
+            entry.statement = statement;
+            entry.last_used = now;
+            return;
+        }
+        while inner.entries.len() >= self.capacity {
+            let Some(lru) = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(cql, _)| cql.clone())
+            else {
+                break;
+            };
+            inner.entries.remove(&lru);
+            inner.evictions += 1;
+        }
+        inner.entries.insert(
+            cql.to_string(),
+            CachedStatement {
+                statement,
+                hits: 0,
+                last_used: now,
+            },
+        );
+    }
+
+    /// Drop the statement cached for `cql`, returning whether there was one.
+    pub fn remove(&self, cql: &str) -> bool {
+        self.inner.lock().entries.remove(cql).is_some()
+    }
+
+    /// Drop the statement cached for `cql` because the server no longer
+    /// knows it, counting the re-preparation that follows.
+    pub(crate) fn invalidate(&self, cql: &str) {
+        let mut inner = self.inner.lock();
+        inner.entries.remove(cql);
+        inner.reprepared += 1;
+    }
+
+    /// Drop every cached statement. Counters are kept.
+    pub fn clear(&self) {
+        self.inner.lock().entries.clear();
+    }
+
+    /// Number of cached statements.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    /// Check if no statements are cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum number of cached statements.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Snapshot of the cache counters and per-statement hit counts.
+    #[must_use]
+    pub fn stats(&self) -> StatementCacheStats {
+        let inner = self.inner.lock();
+        let mut statements: Vec<StatementStats> = inner
+            .entries
+            .iter()
+            .map(|(cql, entry)| StatementStats {
+                cql: cql.clone(),
+                hits: entry.hits,
+            })
+            .collect();
+        statements.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.cql.cmp(&b.cql)));
+
+        StatementCacheStats {
+            size: inner.entries.len(),
+            capacity: self.capacity,
+            hits: inner.hits,
+            misses: inner.misses,
+            evictions: inner.evictions,
+            reprepared: inner.reprepared,
+            statements,
+        }
+    }
+}
+
+impl<S> std::fmt::Debug for StatementCache<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatementCache")
+            .field("capacity", &self.capacity)
+            .field("size", &self.inner.lock().entries.len())
+            .finish()
+    }
+}
+
+/// Statistics about the prepared statement cache.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementCacheStats {
+    /// Number of cached statements.
+    pub size: usize,
+    /// Maximum number of cached statements.
+    pub capacity: usize,
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that had to prepare the statement.
+    pub misses: u64,
+    /// Statements evicted to make room for others.
+    pub evictions: u64,
+    /// Statements prepared again after the server reported them unprepared.
+    pub reprepared: u64,
+    /// Cached statements, most hit first.
+    pub statements: Vec<StatementStats>,
+}
+
+impl StatementCacheStats {
+    /// Fraction of lookups answered from the cache, between 0 and 1.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Hit count of one cached statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementStats {
+    /// CQL text of the statement.
+    pub cql: String,
+    /// Times the cached statement was reused.
+    pub hits: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statement_cache_lru_eviction() {
+        let cache = StatementCache::new(2);
+        cache.insert("SELECT a", 1);
+        cache.insert("SELECT b", 2);
+        assert_eq!(cache.get("SELECT a"), Some(1));
+
+        cache.insert("SELECT c", 3);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("SELECT b"), None);
+        assert_eq!(cache.get("SELECT a"), Some(1));
+        assert_eq!(cache.get("SELECT c"), Some(3));
+
+        let stats = cache.stats();
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.hits, 3);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(
+            stats.statements,
+            vec![
+                StatementStats {
+                    cql: "SELECT a".into(),
+                    hits: 2,
+                },
+                StatementStats {
+                    cql: "SELECT c".into(),
+                    hits: 1,
+                },
+            ]
+        );
+        assert!((stats.hit_rate() - 0.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_statement_cache_invalidate() {
+        let cache = StatementCache::new(10);
+        cache.insert("SELECT a", 1);
+        cache.invalidate("SELECT a");
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().reprepared, 1);
+
+        let disabled = StatementCache::new(0);
+        disabled.insert("SELECT a", 1);
+        assert_eq!(disabled.get("SELECT a"), None);
+    }
+}
@@ -137,6 +137,7 @@ impl ToCqlValue for FilterValue {
                 // Convert JSON to text representation
                 Ok(CqlValue::Text(serde_json::to_string(json).unwrap_or_default()))
             }
+            FilterValue::Bytes(v) => Ok(CqlValue::Blob(v.clone())),
             FilterValue::List(arr) => {
                 let values: ScyllaResult<Vec<CqlValue>> = arr.iter().map(|v| v.to_cql()).collect();
                 Ok(CqlValue::List(values?))
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use parking_lot::Mutex;
-use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::{OwnedSemaphorePermit, mpsc};
 use tokio_rusqlite::Connection;
 use tracing::{debug, trace};
 
@@ -134,6 +134,58 @@ impl SqliteConnection {
             .map_err(SqliteError::from)
     }
 
+    /// Execute a query with parameters, sending the rows in batches of `fetch_size`.
+    ///
+    /// SQLite steps through rows lazily, so once the receiver is dropped the
+    /// rest of the result set is never read.
+    pub async fn query_batches(
+        &self,
+        sql: &str,
+        params: Vec<rusqlite::types::Value>,
+        fetch_size: usize,
+        tx: mpsc::Sender<SqliteResult<Vec<serde_json::Value>>>,
+    ) -> SqliteResult<()> {
+        let sql = sql.to_string();
+        let fetch_size = fetch_size.max(1);
+        debug!(sql = %sql, fetch_size, "Executing query in batches");
+
+        self.conn()
+            .call(move |conn| {
+                let mut stmt = conn.prepare(&sql)?;
+                let columns: Vec<String> =
+                    stmt.column_names().iter().map(|s| s.to_string()).collect();
+
+                let params_ref: Vec<&dyn rusqlite::ToSql> =
+                    params.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+
+                let mut rows = stmt.query(params_ref.as_slice())?;
+                let mut batch = Vec::with_capacity(fetch_size);
+                while let Some(row) = rows.next()? {
+                    let mut map = serde_json::Map::new();
+                    for (i, col) in columns.iter().enumerate() {
+                        let value = crate::types::get_value_at_index(row, i);
+                        map.insert(col.clone(), value);
+                    }
+                    batch.push(serde_json::Value::Object(map));
+
+                    if batch.len() == fetch_size {
+                        let full = std::mem::replace(&mut batch, Vec::with_capacity(fetch_size));
+                        // Runs on the connection's own thread, so blocking is fine here
+                        if tx.blocking_send(Ok(full)).is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+
+                if !batch.is_empty() {
+                    let _ = tx.blocking_send(Ok(batch));
+                }
+                Ok(())
+            })
+            .await
+            .map_err(SqliteError::from)
+    }
+
     /// Execute a query and return a single row.
     pub async fn query_one(&self, sql: &str) -> SqliteResult<serde_json::Value> {
         let sql = sql.to_string();
@@ -2,11 +2,18 @@
 
 use std::collections::HashMap;
 
+use futures::TryStreamExt;
+use futures::stream::{self, BoxStream};
 use rusqlite::types::Value;
 use serde_json::Value as JsonValue;
+use tokio::sync::mpsc;
 use tracing::{debug, instrument};
 
+use prax_query::QueryResult;
+use prax_query::capabilities::Capabilities;
+use prax_query::explain::QueryPlan;
 use prax_query::filter::FilterValue;
+use prax_query::sql::DatabaseType;
 use prax_query::types::SortOrder;
 
 use crate::error::SqliteError;
@@ -124,11 +131,13 @@ impl SqliteEngine {
         (sql, params)
     }
 
-    /// Build an INSERT query.
+    /// Build an INSERT query, returning the inserted row if the SQLite
+    /// version supports `RETURNING`.
     fn build_insert(
         &self,
         table: &str,
         data: &HashMap<String, FilterValue>,
+        capabilities: &Capabilities,
     ) -> (String, Vec<Value>) {
         let mut columns = Vec::new();
         let mut placeholders = Vec::new();
@@ -140,12 +149,15 @@ impl SqliteEngine {
             params.push(filter_value_to_sqlite(val));
         }
 
-        let sql = format!(
+        let mut sql = format!(
             "INSERT INTO \"{}\" ({}) VALUES ({})",
             table,
             columns.join(", "),
             placeholders.join(", ")
         );
+        if capabilities.supports_returning() {
+            sql.push_str(" RETURNING *");
+        }
 
         (sql, params)
     }
@@ -293,11 +305,23 @@ impl SqliteEngine {
         table: &str,
         data: &HashMap<String, FilterValue>,
     ) -> Result<SqliteQueryResult, SqliteError> {
-        let (sql, params) = self.build_insert(table, data);
+        let capabilities = self.pool.capabilities();
+        let (sql, params) = self.build_insert(table, data, &capabilities);
         debug!(sql = %sql, "Executing insert");
 
         let conn = self.pool.get().await?;
 
+        // SQLite 3.35+ returns the row as stored, with defaults filled in
+        if capabilities.supports_returning() {
+            return conn
+                .query_params(&sql, params)
+                .await?
+                .into_iter()
+                .next()
+                .map(SqliteQueryResult::new)
+                .ok_or_else(|| SqliteError::query("INSERT ... RETURNING returned no row"));
+        }
+
         let last_rowid = conn.execute_insert_params(&sql, params).await?;
 
         // Return the inserted row
@@ -367,6 +391,61 @@ impl SqliteEngine {
         Ok(results.into_iter().map(SqliteQueryResult::new).collect())
     }
 
+    /// Execute raw SQL and yield the results in batches of `fetch_size` rows.
+    ///
+    /// Rows are stepped on the connection as batches are consumed, so at most
+    /// one batch is buffered and dropping the stream stops the query.
+    pub fn raw_sql_batches(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+        fetch_size: usize,
+    ) -> BoxStream<'static, Result<Vec<SqliteQueryResult>, SqliteError>> {
+        let pool = self.pool.clone();
+        let sql = sql.to_string();
+        let sqlite_params: Vec<Value> = params.iter().map(filter_value_to_sqlite).collect();
+        let (tx, rx) = mpsc::channel(1);
+
+        tokio::spawn(async move {
+            let errors = tx.clone();
+            let result = match pool.get().await {
+                Ok(conn) => {
+                    conn.query_batches(&sql, sqlite_params, fetch_size, tx)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                let _ = errors.send(Err(e)).await;
+            }
+        });
+
+        Box::pin(
+            stream::unfold(rx, |mut rx| async move {
+                rx.recv().await.map(|batch| (batch, rx))
+            })
+            .map_ok(|rows| rows.into_iter().map(SqliteQueryResult::new).collect()),
+        )
+    }
+
+    /// Execute raw SQL and stream the results row by row.
+    ///
+    /// Rows are fetched in batches of `fetch_size`; see
+    /// [`raw_sql_batches`](Self::raw_sql_batches).
+    pub fn raw_sql_stream(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+        fetch_size: usize,
+    ) -> BoxStream<'static, Result<SqliteQueryResult, SqliteError>> {
+        Box::pin(
+            self.raw_sql_batches(sql, params, fetch_size)
+                .map_ok(|rows| stream::iter(rows.into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
     // =========================================================================
     // Raw SQL Functions
     // =========================================================================
@@ -576,6 +655,30 @@ impl SqliteEngine {
         conn.execute_batch(sql).await
     }
 
+    /// Get SQLite's plan for a query from `EXPLAIN QUERY PLAN`.
+    ///
+    /// SQLite can't measure plans, so `analyze` is an error.
+    #[instrument(skip(self, params), fields(sql = %sql))]
+    pub async fn explain(
+        &self,
+        sql: &str,
+        params: &[FilterValue],
+        analyze: bool,
+    ) -> QueryResult<QueryPlan> {
+        let explain = QueryPlan::explain_sql(DatabaseType::SQLite, sql, analyze)?;
+        let rows = self.execute_raw(&explain, params).await?;
+
+        Ok(QueryPlan::from_sqlite_rows(rows.iter().map(|row| {
+            let row = row.json();
+            let int = |column: &str| row.get(column).and_then(JsonValue::as_i64).unwrap_or(0);
+            let detail = row
+                .get("detail")
+                .and_then(JsonValue::as_str)
+                .unwrap_or_default();
+            (int("id"), int("parent"), detail.to_string())
+        })))
+    }
+
     /// Count rows matching the filter.
     #[instrument(skip(self, filters), fields(table = %table))]
     pub async fn count(
@@ -631,6 +734,7 @@ fn filter_value_to_json(value: &FilterValue) -> JsonValue {
             .unwrap_or(JsonValue::Null),
         FilterValue::String(s) => JsonValue::String(s.clone()),
         FilterValue::Json(j) => j.clone(),
+        FilterValue::Bytes(b) => JsonValue::from(b.clone()),
         FilterValue::List(list) => {
             JsonValue::Array(list.iter().map(filter_value_to_json).collect())
         }
@@ -692,4 +796,42 @@ mod tests {
         assert!(query.contains("users"));
         assert_eq!(params.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_explain() {
+        use crate::config::SqliteConfig;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pool = SqlitePool::new(SqliteConfig::file(dir.path().join("plans.db")))
+            .await
+            .unwrap();
+        let engine = SqliteEngine::new(pool);
+        engine
+            .raw_sql_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT, name TEXT);
+                 CREATE INDEX users_email_idx ON users (email);",
+            )
+            .await
+            .unwrap();
+
+        let plan = engine
+            .explain("SELECT * FROM users WHERE name = ?", &["a".into()], false)
+            .await
+            .unwrap();
+        assert_eq!(plan.full_scans(), vec!["users"]);
+
+        let plan = engine
+            .explain("SELECT * FROM users WHERE email = ?", &["a".into()], false)
+            .await
+            .unwrap();
+        assert!(plan.full_scans().is_empty());
+        assert_eq!(plan.indexes(), vec!["users_email_idx"]);
+
+        assert!(
+            engine
+                .explain("SELECT * FROM users", &[], true)
+                .await
+                .is_err()
+        );
+    }
 }
@@ -1,7 +1,9 @@
 //! Error types for SQLite operations.
 
 use std::fmt;
+use std::time::Duration;
 
+use prax_query::connection::PoolSnapshot;
 use prax_query::error::QueryError;
 
 /// Result type for SQLite operations.
@@ -12,6 +14,13 @@ pub type SqliteResult<T> = Result<T, SqliteError>;
 pub enum SqliteError {
     /// Pool error.
     Pool(String),
+    /// Timed out waiting for a pooled connection.
+    PoolTimeout {
+        /// The acquire timeout.
+        timeout: Duration,
+        /// The pool's state when the checkout gave up.
+        stats: Box<PoolSnapshot>,
+    },
     /// SQLite driver error.
     Sqlite(tokio_rusqlite::Error),
     /// Configuration error.
@@ -76,6 +85,12 @@ impl fmt::Display for SqliteError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Pool(msg) => write!(f, "Pool error: {}", msg),
+            Self::PoolTimeout { timeout, stats } => write!(
+                f,
+                "Pool timeout: no connection after {}ms ({})",
+                timeout.as_millis(),
+                stats
+            ),
             Self::Sqlite(e) => write!(f, "SQLite error: {}", e),
             Self::Config(msg) => write!(f, "Configuration error: {}", msg),
             Self::Connection(msg) => write!(f, "Connection error: {}", msg),
@@ -113,6 +128,9 @@ impl From<SqliteError> for QueryError {
     fn from(err: SqliteError) -> Self {
         match err {
             SqliteError::Pool(msg) => QueryError::connection(msg),
+            SqliteError::PoolTimeout { timeout, stats } => {
+                QueryError::pool_timeout(timeout, *stats)
+            }
             SqliteError::Sqlite(e) => QueryError::database(e.to_string()),
             SqliteError::Config(msg) => QueryError::internal(format!("config: {}", msg)),
             SqliteError::Connection(msg) => QueryError::connection(msg),
@@ -155,5 +173,14 @@ mod tests {
         let err = SqliteError::timeout("connection timed out");
         let query_err: QueryError = err.into();
         assert!(query_err.is_timeout());
+
+        let err = SqliteError::PoolTimeout {
+            timeout: Duration::from_millis(250),
+            stats: Box::default(),
+        };
+        assert!(err.to_string().contains("no connection after 250ms"));
+        let query_err: QueryError = err.into();
+        assert!(query_err.is_retryable());
+        assert!(query_err.context().pool.is_some());
     }
 }
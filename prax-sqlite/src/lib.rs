@@ -10,6 +10,7 @@
 //! - Type-safe query building
 //! - Transaction support
 //! - In-memory and file-based databases
+//! - Offline-first sync with a server ([`sync`])
 //!
 //! # Example
 //!
@@ -32,6 +33,7 @@ pub mod engine;
 pub mod error;
 pub mod pool;
 pub mod row;
+pub mod sync;
 pub mod types;
 
 pub use config::{DatabasePath, JournalMode, SqliteConfig, SynchronousMode};
@@ -39,4 +41,6 @@ pub use connection::SqliteConnection;
 pub use engine::{SqliteEngine, SqliteQueryResult};
 pub use error::{SqliteError, SqliteResult};
 pub use pool::{PoolConfig, SqlitePool, SqlitePoolBuilder};
-pub use row::FromSqliteRow;
+pub use row::{FromSqliteRow, check_drift};
+pub use sync::SqliteSyncStore;
+pub use types::SqliteEnum;
@@ -14,9 +14,12 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use parking_lot::Mutex;
+use prax_query::capabilities::Capabilities;
+use prax_query::connection::{PoolSnapshot, PoolTelemetry};
+use prax_query::sql::DatabaseType;
 use tokio::sync::Semaphore;
 use tokio_rusqlite::Connection;
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 
 use crate::config::SqliteConfig;
 use crate::connection::{PooledConnection, SqliteConnection};
@@ -47,6 +50,10 @@ pub struct SqlitePool {
     pool_config: Arc<PoolConfig>,
     /// Statistics about pool usage.
     stats: Arc<Mutex<PoolStats>>,
+    /// Capabilities of the linked SQLite library.
+    capabilities: Arc<Capabilities>,
+    /// Queue depth and wait times of checkouts.
+    telemetry: Arc<PoolTelemetry>,
 }
 
 /// Statistics about pool usage.
@@ -79,8 +86,9 @@ impl SqlitePool {
             "SQLite connection pool created"
         );
 
-        // Verify we can open at least one connection
+        // Verify we can open at least one connection, and probe it
         let test_conn = Self::open_connection(&config).await?;
+        let capabilities = Self::probe(&test_conn).await?;
         drop(test_conn);
 
         let pool = Self {
@@ -91,6 +99,8 @@ impl SqlitePool {
             ))),
             pool_config: Arc::new(pool_config),
             stats: Arc::new(Mutex::new(PoolStats::default())),
+            capabilities: Arc::new(capabilities),
+            telemetry: Arc::new(PoolTelemetry::new()),
         };
 
         // Pre-warm the pool with min_connections
@@ -131,20 +141,60 @@ impl SqlitePool {
         Ok(conn)
     }
 
+    /// Probe the SQLite version and the extensions compiled in.
+    async fn probe(conn: &Connection) -> SqliteResult<Capabilities> {
+        let capabilities = conn
+            .call(|conn| {
+                let version: String = conn.query_row(
+                    Capabilities::version_query(DatabaseType::SQLite),
+                    [],
+                    |row| row.get(0),
+                )?;
+                let mut extensions = Vec::new();
+                if let Some(query) = Capabilities::extensions_query(DatabaseType::SQLite) {
+                    let mut stmt = conn.prepare(query)?;
+                    for option in stmt.query_map([], |row| row.get::<_, String>(0))? {
+                        extensions.extend(Capabilities::sqlite_extension(&option?));
+                    }
+                }
+                Ok(Capabilities::new(
+                    DatabaseType::SQLite,
+                    &version,
+                    extensions,
+                ))
+            })
+            .await?;
+
+        debug!(version = ?capabilities.version(), "Probed SQLite capabilities");
+        Ok(capabilities)
+    }
+
+    /// Get the capabilities of the SQLite library, probed when the pool was
+    /// created.
+    pub fn capabilities(&self) -> Arc<Capabilities> {
+        self.capabilities.clone()
+    }
+
     /// Get a connection from the pool.
     ///
     /// For file-based databases, this will try to reuse an idle connection
     /// before opening a new one. For in-memory databases, always opens a new
     /// connection (since each connection has its own database).
+    ///
+    /// Fails with [`SqliteError::PoolTimeout`] when no connection frees up
+    /// within the acquire timeout.
     pub async fn get(&self) -> SqliteResult<SqliteConnection> {
         trace!("Acquiring connection from pool");
 
         // Wait for a permit (limits concurrent connections)
         let permit = self
-            .semaphore
-            .clone()
-            .acquire_owned()
+            .telemetry
+            .acquire(
+                self.pool_config.acquire_timeout,
+                self.semaphore.clone().acquire_owned(),
+            )
             .await
+            .ok_or_else(|| self.timed_out())?
             .map_err(|e| SqliteError::pool(format!("failed to acquire permit: {}", e)))?;
 
         // Update stats
@@ -238,6 +288,25 @@ impl SqlitePool {
     pub fn reset_stats(&self) {
         let mut stats = self.stats.lock();
         *stats = PoolStats::default();
+        self.telemetry.reset();
+    }
+
+    /// Get a snapshot of the pool's size, queue depth and checkout wait times.
+    pub fn snapshot(&self) -> PoolSnapshot {
+        let max_size = self.pool_config.max_connections;
+        let in_use = max_size.saturating_sub(self.semaphore.available_permits());
+        let idle = self.idle_count();
+        self.telemetry.snapshot(max_size, in_use + idle, idle)
+    }
+
+    /// Build the error for a checkout that ran out of time.
+    fn timed_out(&self) -> SqliteError {
+        let stats = self.snapshot();
+        warn!(pool = %stats, "Timed out waiting for a SQLite connection");
+        SqliteError::PoolTimeout {
+            timeout: self.pool_config.acquire_timeout.unwrap_or_default(),
+            stats: Box::new(stats),
+        }
     }
 
     /// Check if the pool is healthy by attempting to get a connection.
@@ -281,6 +350,9 @@ pub struct PoolConfig {
     pub min_connections: usize,
     /// Connection timeout.
     pub connection_timeout: Option<Duration>,
+    /// Maximum time to wait for a connection to check out; `None` waits
+    /// indefinitely.
+    pub acquire_timeout: Option<Duration>,
     /// Maximum idle time before a connection is closed.
     pub idle_timeout: Option<Duration>,
     /// Maximum lifetime of a connection before it's recycled.
@@ -293,6 +365,7 @@ impl Default for PoolConfig {
             max_connections: 5, // SQLite benefits from fewer connections
             min_connections: 1,
             connection_timeout: Some(Duration::from_secs(30)),
+            acquire_timeout: Some(Duration::from_secs(30)),
             idle_timeout: Some(Duration::from_secs(300)), // 5 minutes
             max_lifetime: Some(Duration::from_secs(1800)), // 30 minutes
         }
@@ -341,6 +414,18 @@ impl SqlitePoolBuilder {
         self
     }
 
+    /// Set the acquire timeout.
+    pub fn acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_config.acquire_timeout = Some(timeout);
+        self
+    }
+
+    /// Wait indefinitely for a connection to check out.
+    pub fn no_acquire_timeout(mut self) -> Self {
+        self.pool_config.acquire_timeout = None;
+        self
+    }
+
     /// Set the idle timeout.
     pub fn idle_timeout(mut self, timeout: Duration) -> Self {
         self.pool_config.idle_timeout = Some(timeout);
@@ -395,4 +480,41 @@ mod tests {
         let conn = pool.get().await;
         assert!(conn.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_pool_capabilities() {
+        let pool = SqlitePool::new(SqliteConfig::memory()).await.unwrap();
+        let capabilities = pool.capabilities();
+        assert_eq!(capabilities.database_type(), DatabaseType::SQLite);
+        assert!(capabilities.version().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_pool_acquire_timeout() {
+        let pool = SqlitePoolBuilder::new()
+            .config(SqliteConfig::memory())
+            .max_connections(1)
+            .acquire_timeout(Duration::from_millis(20))
+            .build()
+            .await
+            .unwrap();
+
+        let held = pool.get().await.unwrap();
+        let err = match pool.get().await {
+            Err(err) => err,
+            Ok(_) => panic!("checkout should time out while the only connection is held"),
+        };
+        let SqliteError::PoolTimeout { timeout, stats } = err else {
+            panic!("expected a pool timeout, got {err}");
+        };
+        assert_eq!(timeout, Duration::from_millis(20));
+        assert_eq!(stats.in_use(), 1);
+        assert_eq!(stats.acquired, 1);
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.peak_waiting, 1);
+
+        drop(held);
+        assert!(pool.get().await.is_ok());
+        assert_eq!(pool.snapshot().acquired, 2);
+    }
 }
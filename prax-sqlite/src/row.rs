@@ -1,5 +1,6 @@
 //! Row deserialization traits for SQLite.
 
+use prax_query::drift::{ColumnKind, ColumnSpec, DriftError, DriftGuard};
 use rusqlite::Row;
 use serde_json::Value as JsonValue;
 
@@ -76,6 +77,57 @@ impl FromSqliteRow for JsonValue {
     }
 }
 
+/// Check the columns of a result row against a model's column specs.
+///
+/// SQLite reports declared types only for table columns, and stores dates,
+/// JSON and UUIDs as `TEXT` and booleans as `INTEGER`; such columns pass.
+/// Each statement is verified once per `guard`; see [`prax_query::drift`].
+pub fn check_drift(
+    guard: &DriftGuard,
+    sql: &str,
+    table: &str,
+    specs: &[ColumnSpec],
+    row: &Row<'_>,
+) -> Result<(), DriftError> {
+    let columns = row.as_ref().columns();
+    guard.check(
+        sql,
+        table,
+        specs,
+        columns.iter().map(|column| {
+            let kind = specs
+                .iter()
+                .find(|spec| spec.name == column.name())
+                .map(|spec| spec.kind);
+            (column.name(), declared_type(column.decl_type(), kind))
+        }),
+    )
+}
+
+/// Get the declared type to check, or `""` where SQLite's storage of `kind`
+/// explains it.
+fn declared_type(decl_type: Option<&str>, kind: Option<ColumnKind>) -> &str {
+    let Some(decl_type) = decl_type else {
+        return "";
+    };
+    let stored_as = match kind {
+        Some(
+            ColumnKind::DateTime
+            | ColumnKind::Date
+            | ColumnKind::Time
+            | ColumnKind::Json
+            | ColumnKind::Uuid,
+        ) => "TEXT",
+        Some(ColumnKind::Boolean) => "INTEGER",
+        _ => return decl_type,
+    };
+    if decl_type.eq_ignore_ascii_case(stored_as) {
+        ""
+    } else {
+        decl_type
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,4 +153,15 @@ mod tests {
         assert!(display.contains("email"));
         assert!(display.contains("missing value"));
     }
+
+    #[test]
+    fn test_declared_type() {
+        assert_eq!(declared_type(Some("TEXT"), Some(ColumnKind::DateTime)), "");
+        assert_eq!(
+            declared_type(Some("integer"), Some(ColumnKind::Boolean)),
+            ""
+        );
+        assert_eq!(declared_type(Some("TEXT"), Some(ColumnKind::Int)), "TEXT");
+        assert_eq!(declared_type(None, Some(ColumnKind::Int)), "");
+    }
 }
@@ -0,0 +1,464 @@
+//! The client side of offline-first sync.
+//!
+//! [`SqliteSyncStore::track`] installs triggers that log every write to a
+//! model's table in the shared `_prax_oplog` table, in the order the writes
+//! happened across all tracked tables. Changes pulled from the server are
+//! applied with the triggers switched off, so only the application's own
+//! writes are ever pushed. See [`prax_query::sync`] for the protocol.
+
+use rusqlite::types::Value;
+use serde_json::Value as JsonValue;
+use tracing::debug;
+
+use prax_query::error::{QueryError, QueryResult};
+use prax_query::live::ChangeOp;
+use prax_query::sql::quote_identifier;
+use prax_query::sync::{Change, LocalStore, SyncState, SyncStore};
+use prax_query::traits::{BoxFuture, Model};
+
+use crate::error::{SqliteError, SqliteResult};
+use crate::pool::SqlitePool;
+
+/// The table local writes are logged in.
+pub const OPLOG_TABLE: &str = "_prax_oplog";
+
+/// The single-row table holding the node ID and sync positions.
+pub const STATE_TABLE: &str = "_prax_sync_state";
+
+/// The current time in milliseconds since the Unix epoch.
+const NOW_MS: &str = "CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER)";
+
+/// A SQLite database synced with a server.
+#[derive(Clone)]
+pub struct SqliteSyncStore {
+    pool: SqlitePool,
+}
+
+impl SqliteSyncStore {
+    /// Sync the database behind `pool`.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// The connection pool.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// Start logging writes to the model's table.
+    ///
+    /// Creates the oplog and state tables on first use. Writes made before
+    /// tracking started are never pushed.
+    pub async fn track<M: Model>(&self) -> SqliteResult<()> {
+        let conn = self.pool.get().await?;
+        conn.execute_batch(&oplog_sql::<M>()).await
+    }
+}
+
+/// The oplog and state tables, and triggers logging writes to the model's
+/// table.
+pub fn oplog_sql<M: Model>() -> String {
+    let table = M::TABLE_NAME;
+    let object = |row: &str, columns: &[&str]| {
+        let pairs = columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "'{}', {}.{}",
+                    column.replace('\'', "''"),
+                    row,
+                    quote_identifier(column)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("json_object({})", pairs)
+    };
+
+    let mut sql = format!(
+        "CREATE TABLE IF NOT EXISTS {OPLOG_TABLE} (\n    \
+         id INTEGER PRIMARY KEY AUTOINCREMENT,\n    \
+         table_name TEXT NOT NULL,\n    \
+         row_key TEXT NOT NULL,\n    \
+         op TEXT NOT NULL,\n    \
+         row TEXT,\n    \
+         ts INTEGER NOT NULL\n\
+         );\n\
+         CREATE TABLE IF NOT EXISTS {STATE_TABLE} (\n    \
+         id INTEGER PRIMARY KEY CHECK (id = 1),\n    \
+         node_id TEXT NOT NULL,\n    \
+         applying INTEGER NOT NULL DEFAULT 0,\n    \
+         pushed INTEGER NOT NULL DEFAULT 0,\n    \
+         pulled INTEGER NOT NULL DEFAULT 0\n\
+         );\n\
+         INSERT OR IGNORE INTO {STATE_TABLE} (id, node_id) VALUES (1, lower(hex(randomblob(16))));\n"
+    );
+
+    for (event, op, row) in [
+        ("INSERT", ChangeOp::Insert, "NEW"),
+        ("UPDATE", ChangeOp::Update, "NEW"),
+        ("DELETE", ChangeOp::Delete, "OLD"),
+    ] {
+        let contents = if op == ChangeOp::Delete {
+            "NULL".to_string()
+        } else {
+            object(row, M::COLUMNS)
+        };
+        sql.push_str(&format!(
+            "CREATE TRIGGER IF NOT EXISTS {trigger} AFTER {event} ON {table}\n\
+             WHEN (SELECT applying FROM {STATE_TABLE} WHERE id = 1) = 0\n\
+             BEGIN\n    \
+             INSERT INTO {OPLOG_TABLE} (table_name, row_key, op, row, ts) \
+             VALUES ('{name}', {key}, '{op}', {contents}, {NOW_MS});\n\
+             END;\n",
+            trigger = quote_identifier(&format!(
+                "{}_{}_{}",
+                OPLOG_TABLE,
+                table,
+                event.to_lowercase()
+            )),
+            table = quote_identifier(table),
+            name = table.replace('\'', "''"),
+            key = object(row, M::PRIMARY_KEY),
+            op = event,
+        ));
+    }
+    sql
+}
+
+/// The statement applying a change, with its parameters.
+///
+/// Inserts and updates upsert the row by its key, so applying a change
+/// twice leaves the same row.
+pub fn apply_sql(change: &Change) -> QueryResult<(String, Vec<Value>)> {
+    let key = object_of(&change.key, "key")?;
+    let table = quote_identifier(&change.table);
+
+    let Some(row) = &change.row else {
+        let conditions = key
+            .keys()
+            .map(|column| format!("{} = ?", quote_identifier(column)))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let params = key.values().map(json_to_sqlite).collect();
+        return Ok((
+            format!("DELETE FROM {} WHERE {}", table, conditions),
+            params,
+        ));
+    };
+
+    let row = object_of(row, "row")?;
+    let columns: Vec<String> = row.keys().map(|c| quote_identifier(c)).collect();
+    let updates = row
+        .keys()
+        .filter(|column| !key.contains_key(*column))
+        .map(|column| {
+            let column = quote_identifier(column);
+            format!("{} = excluded.{}", column, column)
+        })
+        .collect::<Vec<_>>();
+    let target = key
+        .keys()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let action = if updates.is_empty() {
+        "NOTHING".to_string()
+    } else {
+        format!("UPDATE SET {}", updates.join(", "))
+    };
+
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO {}",
+        table,
+        columns.join(", "),
+        vec!["?"; columns.len()].join(", "),
+        target,
+        action
+    );
+    Ok((sql, row.values().map(json_to_sqlite).collect()))
+}
+
+fn object_of<'a>(
+    value: &'a JsonValue,
+    what: &str,
+) -> QueryResult<&'a serde_json::Map<String, JsonValue>> {
+    value
+        .as_object()
+        .filter(|object| !object.is_empty())
+        .ok_or_else(|| {
+            QueryError::invalid_input(what, format!("expected an object, got {}", value))
+        })
+}
+
+fn json_to_sqlite(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(b) => Value::Integer(i64::from(*b)),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn change_from_row(row: &JsonValue) -> SqliteResult<Change> {
+    let text = |column: &str| row[column].as_str().unwrap_or_default().to_string();
+    // Rows read JSON text back as objects already
+    let json = |column: &str| -> SqliteResult<Option<JsonValue>> {
+        match &row[column] {
+            JsonValue::Null => Ok(None),
+            JsonValue::Object(_) => Ok(Some(row[column].clone())),
+            other => Err(SqliteError::deserialization(format!(
+                "invalid {}: {}",
+                column, other
+            ))),
+        }
+    };
+    let op = match row["op"].as_str() {
+        Some("INSERT") => ChangeOp::Insert,
+        Some("UPDATE") => ChangeOp::Update,
+        Some("DELETE") => ChangeOp::Delete,
+        other => {
+            return Err(SqliteError::deserialization(format!(
+                "invalid oplog op: {:?}",
+                other
+            )));
+        }
+    };
+
+    Ok(Change {
+        seq: row["id"].as_i64().unwrap_or_default(),
+        table: text("table_name"),
+        key: json("row_key")?.unwrap_or_default(),
+        op,
+        row: json("row")?,
+        timestamp: row["ts"].as_i64().unwrap_or_default(),
+        origin: text("node_id"),
+    })
+}
+
+impl SyncStore for SqliteSyncStore {
+    fn changes_since(&self, after: i64) -> BoxFuture<'_, QueryResult<Vec<Change>>> {
+        Box::pin(async move {
+            let conn = self.pool.get().await?;
+            let rows = conn
+                .query_params(
+                    &format!(
+                        "SELECT o.id, o.table_name, o.row_key, o.op, o.row, o.ts, s.node_id \
+                         FROM {OPLOG_TABLE} o, {STATE_TABLE} s WHERE o.id > ? ORDER BY o.id"
+                    ),
+                    vec![Value::Integer(after)],
+                )
+                .await?;
+            let changes = rows
+                .iter()
+                .map(change_from_row)
+                .collect::<SqliteResult<Vec<_>>>()?;
+            Ok(changes)
+        })
+    }
+
+    fn apply(&self, changes: Vec<Change>) -> BoxFuture<'_, QueryResult<()>> {
+        Box::pin(async move {
+            let statements = changes
+                .iter()
+                .map(apply_sql)
+                .collect::<QueryResult<Vec<_>>>()?;
+            debug!(changes = statements.len(), "Applying synced changes");
+
+            let conn = self.pool.get().await?;
+            conn.inner()
+                .call(move |conn| {
+                    let tx = conn.transaction()?;
+                    tx.execute(&format!("UPDATE {STATE_TABLE} SET applying = 1"), [])?;
+                    for (sql, params) in &statements {
+                        let params: Vec<&dyn rusqlite::ToSql> =
+                            params.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+                        tx.execute(sql, params.as_slice())?;
+                    }
+                    tx.execute(&format!("UPDATE {STATE_TABLE} SET applying = 0"), [])?;
+                    tx.commit()?;
+                    Ok(())
+                })
+                .await
+                .map_err(SqliteError::from)?;
+            Ok(())
+        })
+    }
+}
+
+impl LocalStore for SqliteSyncStore {
+    fn load_state(&self) -> BoxFuture<'_, QueryResult<SyncState>> {
+        Box::pin(async move {
+            let conn = self.pool.get().await?;
+            let row = conn
+                .query_optional(&format!(
+                    "SELECT node_id, pushed, pulled FROM {STATE_TABLE} WHERE id = 1"
+                ))
+                .await?
+                .ok_or_else(|| {
+                    QueryError::not_found(STATE_TABLE)
+                        .with_help("Call SqliteSyncStore::track for each synced model first")
+                })?;
+
+            Ok(SyncState {
+                node_id: row["node_id"].as_str().unwrap_or_default().to_string(),
+                pushed: row["pushed"].as_i64().unwrap_or_default(),
+                pulled: row["pulled"].as_i64().unwrap_or_default(),
+            })
+        })
+    }
+
+    fn save_state(&self, state: SyncState) -> BoxFuture<'_, QueryResult<()>> {
+        Box::pin(async move {
+            let conn = self.pool.get().await?;
+            conn.inner()
+                .call(move |conn| {
+                    let tx = conn.transaction()?;
+                    tx.execute(
+                        &format!("UPDATE {STATE_TABLE} SET pushed = ?1, pulled = ?2 WHERE id = 1"),
+                        [state.pushed, state.pulled],
+                    )?;
+                    // Pushed entries are in the server's log now
+                    tx.execute(
+                        &format!("DELETE FROM {OPLOG_TABLE} WHERE id <= ?1"),
+                        [state.pushed],
+                    )?;
+                    tx.commit()?;
+                    Ok(())
+                })
+                .await
+                .map_err(SqliteError::from)?;
+            Ok(())
+        })
+    }
+}
+
+impl std::fmt::Debug for SqliteSyncStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteSyncStore").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SqliteConfig;
+    use serde_json::json;
+
+    struct Note;
+
+    impl Model for Note {
+        const MODEL_NAME: &'static str = "Note";
+        const TABLE_NAME: &'static str = "notes";
+        const PRIMARY_KEY: &'static [&'static str] = &["id"];
+        const COLUMNS: &'static [&'static str] = &["id", "body"];
+    }
+
+    #[test]
+    fn test_oplog_sql() {
+        let sql = oplog_sql::<Note>();
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS _prax_oplog"));
+        assert!(sql.contains(
+            "CREATE TRIGGER IF NOT EXISTS _prax_oplog_notes_update AFTER UPDATE ON notes"
+        ));
+        assert!(sql.contains(
+            "VALUES ('notes', json_object('id', NEW.id), 'UPDATE', json_object('id', NEW.id, 'body', NEW.body)"
+        ));
+        assert!(sql.contains("VALUES ('notes', json_object('id', OLD.id), 'DELETE', NULL"));
+    }
+
+    #[test]
+    fn test_apply_sql() {
+        let mut change = Change {
+            seq: 4,
+            table: "notes".to_string(),
+            key: json!({ "id": 1 }),
+            op: ChangeOp::Update,
+            row: Some(json!({ "id": 1, "body": "hi" })),
+            timestamp: 0,
+            origin: "server".to_string(),
+        };
+        let (sql, params) = apply_sql(&change).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO notes (body, id) VALUES (?, ?) ON CONFLICT (id) DO UPDATE SET body = excluded.body"
+        );
+        assert_eq!(params, [Value::Text("hi".into()), Value::Integer(1)]);
+
+        change.op = ChangeOp::Delete;
+        change.row = None;
+        let (sql, params) = apply_sql(&change).unwrap();
+        assert_eq!(sql, "DELETE FROM notes WHERE id = ?");
+        assert_eq!(params, [Value::Integer(1)]);
+
+        change.key = json!(1);
+        assert!(apply_sql(&change).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = SqlitePool::new(SqliteConfig::file(dir.path().join("notes.db")))
+            .await
+            .unwrap();
+        let conn = pool.get().await.unwrap();
+        conn.execute("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)")
+            .await
+            .unwrap();
+        drop(conn);
+
+        let store = SqliteSyncStore::new(pool.clone());
+        store.track::<Note>().await.unwrap();
+        let state = store.load_state().await.unwrap();
+        assert_eq!((state.pushed, state.pulled), (0, 0));
+
+        let conn = pool.get().await.unwrap();
+        conn.execute("INSERT INTO notes VALUES (1, 'mine')")
+            .await
+            .unwrap();
+        drop(conn);
+        let changes = store.changes_since(0).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].op, ChangeOp::Insert);
+        assert_eq!(changes[0].row, Some(json!({ "id": 1, "body": "mine" })));
+        assert_eq!(changes[0].origin, state.node_id);
+
+        // Pulled changes are applied without being logged
+        let pulled = Change {
+            seq: 9,
+            table: "notes".to_string(),
+            key: json!({ "id": 2 }),
+            op: ChangeOp::Insert,
+            row: Some(json!({ "id": 2, "body": "theirs" })),
+            timestamp: 0,
+            origin: "server".to_string(),
+        };
+        store.apply(vec![pulled.clone(), pulled]).await.unwrap();
+        assert_eq!(store.changes_since(0).await.unwrap().len(), 1);
+
+        store
+            .save_state(SyncState {
+                pushed: changes[0].seq,
+                pulled: 9,
+                ..state
+            })
+            .await
+            .unwrap();
+        assert!(store.changes_since(0).await.unwrap().is_empty());
+        assert_eq!(store.load_state().await.unwrap().pulled, 9);
+
+        let conn = pool.get().await.unwrap();
+        let rows = conn
+            .query("SELECT body FROM notes ORDER BY id")
+            .await
+            .unwrap();
+        assert_eq!(
+            rows,
+            [json!({ "body": "mine" }), json!({ "body": "theirs" })]
+        );
+    }
+}
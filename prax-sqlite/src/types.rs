@@ -1,9 +1,11 @@
 //! Type conversion utilities for SQLite.
 
-use rusqlite::types::{FromSql, FromSqlError, Value, ValueRef};
+use rusqlite::ToSql;
+use rusqlite::types::{FromSql, FromSqlError, ToSqlOutput, Value, ValueRef};
 use serde_json::Value as JsonValue;
 
 use prax_query::filter::FilterValue;
+use prax_query::types::DbEnum;
 
 /// Convert a FilterValue to a SQLite Value.
 pub fn filter_value_to_sqlite(value: &FilterValue) -> Value {
@@ -14,6 +16,7 @@ pub fn filter_value_to_sqlite(value: &FilterValue) -> Value {
         FilterValue::Float(f) => Value::Real(*f),
         FilterValue::String(s) => Value::Text(s.clone()),
         FilterValue::Json(j) => Value::Text(j.to_string()),
+        FilterValue::Bytes(b) => Value::Blob(b.clone()),
         FilterValue::List(list) => {
             // Serialize list as JSON
             let json_array: Vec<JsonValue> = list
@@ -27,6 +30,7 @@ pub fn filter_value_to_sqlite(value: &FilterValue) -> Value {
                         .unwrap_or(JsonValue::Null),
                     FilterValue::String(s) => JsonValue::String(s.clone()),
                     FilterValue::Json(j) => j.clone(),
+                    FilterValue::Bytes(b) => JsonValue::from(b.clone()),
                     FilterValue::List(_) => JsonValue::Null, // Nested lists not directly supported
                 })
                 .collect();
@@ -127,10 +131,86 @@ impl FromSql for JsonColumn {
     }
 }
 
+/// A [`DbEnum`] bound to, or decoded from, a `TEXT` column.
+///
+/// ```rust,ignore
+/// let role: SqliteEnum<Role> = row.get("role")?;
+/// assert_eq!(role.0, Role::Admin);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SqliteEnum<T>(pub T);
+
+impl<T: DbEnum> ToSql for SqliteEnum<T> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Text(
+            self.0.db_value().as_bytes(),
+        )))
+    }
+}
+
+impl<T: DbEnum> FromSql for SqliteEnum<T> {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        T::decode(value.as_str()?)
+            .map(SqliteEnum)
+            .map_err(|e| FromSqlError::Other(e.into()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Role {
+        User,
+        Admin,
+    }
+
+    impl DbEnum for Role {
+        const DB_NAME: &'static str = "Role";
+
+        fn variants() -> &'static [Self] {
+            &[Self::User, Self::Admin]
+        }
+
+        fn db_value(&self) -> &'static str {
+            match self {
+                Self::User => "USER",
+                Self::Admin => "ADMIN",
+            }
+        }
+
+        fn from_db_value(value: &str) -> Option<Self> {
+            Self::variants()
+                .iter()
+                .copied()
+                .find(|v| v.db_value() == value)
+        }
+    }
+
+    #[test]
+    fn test_sqlite_enum() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (role TEXT NOT NULL CHECK (role IN ('USER', 'ADMIN')))",
+        )
+        .unwrap();
+        conn.execute("INSERT INTO users VALUES (?1)", [SqliteEnum(Role::Admin)])
+            .unwrap();
+
+        let role: SqliteEnum<Role> = conn
+            .query_row("SELECT role FROM users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(role.0, Role::Admin);
+
+        let err = conn
+            .query_row("SELECT 'OWNER'", [], |row| {
+                row.get::<_, SqliteEnum<Role>>(0)
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid value for enum Role"));
+    }
+
     #[test]
     fn test_filter_value_to_sqlite_null() {
         let result = filter_value_to_sqlite(&FilterValue::Null);
@@ -168,6 +248,12 @@ mod tests {
         assert!(matches!(result, Value::Text(s) if s == "hello"));
     }
 
+    #[test]
+    fn test_filter_value_to_sqlite_bytes() {
+        let result = filter_value_to_sqlite(&FilterValue::Bytes(vec![0, 1]));
+        assert!(matches!(result, Value::Blob(b) if b == [0, 1]));
+    }
+
     #[test]
     fn test_from_sqlite_value_null() {
         let result = from_sqlite_value(ValueRef::Null);
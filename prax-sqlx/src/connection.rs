@@ -50,6 +50,40 @@ impl SqlxConnection {
             Self::Sqlite(_) => DatabaseBackend::Sqlite,
         }
     }
+
+    /// Execute a statement without parameters, which isn't prepared.
+    ///
+    /// For transaction control statements, which not every backend can
+    /// prepare.
+    pub async fn execute_unprepared(&mut self, sql: &str) -> SqlxResult<()> {
+        match self {
+            #[cfg(feature = "postgres")]
+            Self::Postgres(conn) => {
+                sqlx::Executor::execute(&mut **conn, sql).await?;
+            }
+            #[cfg(feature = "mysql")]
+            Self::MySql(conn) => {
+                sqlx::Executor::execute(&mut **conn, sql).await?;
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(conn) => {
+                sqlx::Executor::execute(&mut **conn, sql).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Close the connection instead of returning it to the pool.
+    pub fn discard(self) {
+        match self {
+            #[cfg(feature = "postgres")]
+            Self::Postgres(conn) => drop(conn.detach()),
+            #[cfg(feature = "mysql")]
+            Self::MySql(conn) => drop(conn.detach()),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(conn) => drop(conn.detach()),
+        }
+    }
 }
 
 /// A transaction handle.
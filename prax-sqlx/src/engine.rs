@@ -1,17 +1,104 @@
 //! SQLx query engine implementation.
 
 use crate::config::{DatabaseBackend, SqlxConfig};
-use crate::error::SqlxResult;
+use crate::connection::SqlxConnection;
+use crate::error::{SqlxError, SqlxResult};
 use crate::pool::SqlxPool;
 use crate::row::SqlxRow;
 use crate::types::quote_identifier;
 use prax_query::QueryResult;
 use prax_query::filter::FilterValue;
+use prax_query::raw::RawDatabase;
 use prax_query::traits::{BoxFuture, Model, QueryEngine};
+use prax_query::transaction::{TransactionConfig, TransactionalEngine, run_transaction};
 use sqlx::Row;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use tracing::debug;
 
+/// Bind `$params` to `$sql` and evaluate `$body` with the query, the executor
+/// to run it on and the backend's `SqlxRow` constructor.
+///
+/// The executor is the transaction's connection when the engine is bound to
+/// one, else the pool.
+macro_rules! on_executor {
+    ($engine:expr, $sql:expr, $params:expr, |$query:ident, $executor:ident, $row:pat_param| $body:expr) => {{
+        let mut guard = match &$engine.transaction {
+            Some(transaction) => Some(transaction.0.lock().await),
+            None => None,
+        };
+        let connection = match guard.as_mut() {
+            Some(guard) => Some(guard.as_mut().ok_or_else(transaction_finished)?),
+            None => None,
+        };
+
+        match (&*$engine.pool, connection) {
+            #[cfg(feature = "postgres")]
+            (_, Some(SqlxConnection::Postgres(conn))) => {
+                let mut $query = sqlx::query($sql);
+                for param in $params {
+                    $query = bind_pg_param($query, param);
+                }
+                let $executor = &mut **conn;
+                let $row = SqlxRow::Postgres;
+                $body
+            }
+            #[cfg(feature = "postgres")]
+            (SqlxPool::Postgres(pool), None) => {
+                let mut $query = sqlx::query($sql);
+                for param in $params {
+                    $query = bind_pg_param($query, param);
+                }
+                let $executor = pool;
+                let $row = SqlxRow::Postgres;
+                $body
+            }
+            #[cfg(feature = "mysql")]
+            (_, Some(SqlxConnection::MySql(conn))) => {
+                let mut $query = sqlx::query($sql);
+                for param in $params {
+                    $query = bind_mysql_param($query, param);
+                }
+                let $executor = &mut **conn;
+                let $row = SqlxRow::MySql;
+                $body
+            }
+            #[cfg(feature = "mysql")]
+            (SqlxPool::MySql(pool), None) => {
+                let mut $query = sqlx::query($sql);
+                for param in $params {
+                    $query = bind_mysql_param($query, param);
+                }
+                let $executor = pool;
+                let $row = SqlxRow::MySql;
+                $body
+            }
+            #[cfg(feature = "sqlite")]
+            (_, Some(SqlxConnection::Sqlite(conn))) => {
+                let mut $query = sqlx::query($sql);
+                for param in $params {
+                    $query = bind_sqlite_param($query, param);
+                }
+                let $executor = &mut **conn;
+                let $row = SqlxRow::Sqlite;
+                $body
+            }
+            #[cfg(feature = "sqlite")]
+            (SqlxPool::Sqlite(pool), None) => {
+                let mut $query = sqlx::query($sql);
+                for param in $params {
+                    $query = bind_sqlite_param($query, param);
+                }
+                let $executor = pool;
+                let $row = SqlxRow::Sqlite;
+                $body
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("a transaction's connection comes from the engine's pool"),
+        }
+    }};
+}
+
 /// SQLx-based query engine for Prax.
 ///
 /// This engine provides compile-time checked queries through SQLx,
@@ -32,6 +119,27 @@ use tracing::debug;
 pub struct SqlxEngine {
     pool: Arc<SqlxPool>,
     backend: DatabaseBackend,
+    /// The connection of the open transaction, when bound to one.
+    transaction: Option<Arc<TransactionConnection>>,
+}
+
+/// The connection a transaction runs on.
+///
+/// Emptied on commit or rollback. A connection still held when the last
+/// engine clone drops is closed rather than pooled, so the server rolls back.
+struct TransactionConnection(Mutex<Option<SqlxConnection>>);
+
+impl Drop for TransactionConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.0.get_mut().take() {
+            debug!("Discarding connection of an unfinished transaction");
+            conn.discard();
+        }
+    }
+}
+
+fn transaction_finished() -> SqlxError {
+    SqlxError::transaction("the transaction has already finished")
 }
 
 impl SqlxEngine {
@@ -42,6 +150,7 @@ impl SqlxEngine {
         Ok(Self {
             pool: Arc::new(pool),
             backend,
+            transaction: None,
         })
     }
 
@@ -51,6 +160,7 @@ impl SqlxEngine {
         Self {
             pool: Arc::new(pool),
             backend,
+            transaction: None,
         }
     }
 
@@ -69,6 +179,47 @@ impl SqlxEngine {
         self.pool.close().await;
     }
 
+    /// Check if this engine is bound to a transaction.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Run a transaction control statement on the bound transaction.
+    async fn control(&self, sql: &str) -> QueryResult<()> {
+        let transaction = self
+            .transaction
+            .as_ref()
+            .ok_or_else(|| SqlxError::transaction("no transaction is open"))?;
+        let mut guard = transaction.0.lock().await;
+        let conn = guard.as_mut().ok_or_else(transaction_finished)?;
+
+        debug!(sql = %sql, "Executing transaction statement");
+        conn.execute_unprepared(sql).await?;
+        Ok(())
+    }
+
+    /// End the bound transaction with `COMMIT` or `ROLLBACK`.
+    async fn finish(&self, sql: &str) -> QueryResult<()> {
+        let transaction = self
+            .transaction
+            .as_ref()
+            .ok_or_else(|| SqlxError::transaction("no transaction to end"))?;
+        let mut conn = transaction
+            .0
+            .lock()
+            .await
+            .take()
+            .ok_or_else(transaction_finished)?;
+
+        debug!(sql = %sql, "Ending transaction");
+        if let Err(e) = conn.execute_unprepared(sql).await {
+            // The connection may still be in the transaction
+            conn.discard();
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
     // ==================== Low-Level Query Methods ====================
 
     /// Execute a raw SQL query and return multiple rows.
@@ -79,70 +230,20 @@ impl SqlxEngine {
     ) -> SqlxResult<Vec<SqlxRow>> {
         debug!(sql = %sql, "Executing raw_query_many");
 
-        match &*self.pool {
-            #[cfg(feature = "postgres")]
-            SqlxPool::Postgres(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_pg_param(query, param);
-                }
-                let rows = query.fetch_all(pool).await?;
-                Ok(rows.into_iter().map(SqlxRow::Postgres).collect())
-            }
-            #[cfg(feature = "mysql")]
-            SqlxPool::MySql(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_mysql_param(query, param);
-                }
-                let rows = query.fetch_all(pool).await?;
-                Ok(rows.into_iter().map(SqlxRow::MySql).collect())
-            }
-            #[cfg(feature = "sqlite")]
-            SqlxPool::Sqlite(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_sqlite_param(query, param);
-                }
-                let rows = query.fetch_all(pool).await?;
-                Ok(rows.into_iter().map(SqlxRow::Sqlite).collect())
-            }
-        }
+        on_executor!(self, sql, params, |query, executor, row| {
+            let rows = query.fetch_all(executor).await?;
+            Ok(rows.into_iter().map(row).collect())
+        })
     }
 
     /// Execute a raw SQL query and return a single row.
     pub async fn raw_query_one(&self, sql: &str, params: &[FilterValue]) -> SqlxResult<SqlxRow> {
         debug!(sql = %sql, "Executing raw_query_one");
 
-        match &*self.pool {
-            #[cfg(feature = "postgres")]
-            SqlxPool::Postgres(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_pg_param(query, param);
-                }
-                let row = query.fetch_one(pool).await?;
-                Ok(SqlxRow::Postgres(row))
-            }
-            #[cfg(feature = "mysql")]
-            SqlxPool::MySql(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_mysql_param(query, param);
-                }
-                let row = query.fetch_one(pool).await?;
-                Ok(SqlxRow::MySql(row))
-            }
-            #[cfg(feature = "sqlite")]
-            SqlxPool::Sqlite(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_sqlite_param(query, param);
-                }
-                let row = query.fetch_one(pool).await?;
-                Ok(SqlxRow::Sqlite(row))
-            }
-        }
+        on_executor!(self, sql, params, |query, executor, row| {
+            let result = query.fetch_one(executor).await?;
+            Ok(row(result))
+        })
     }
 
     /// Execute a raw SQL query and return an optional row.
@@ -153,70 +254,20 @@ impl SqlxEngine {
     ) -> SqlxResult<Option<SqlxRow>> {
         debug!(sql = %sql, "Executing raw_query_optional");
 
-        match &*self.pool {
-            #[cfg(feature = "postgres")]
-            SqlxPool::Postgres(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_pg_param(query, param);
-                }
-                let row = query.fetch_optional(pool).await?;
-                Ok(row.map(SqlxRow::Postgres))
-            }
-            #[cfg(feature = "mysql")]
-            SqlxPool::MySql(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_mysql_param(query, param);
-                }
-                let row = query.fetch_optional(pool).await?;
-                Ok(row.map(SqlxRow::MySql))
-            }
-            #[cfg(feature = "sqlite")]
-            SqlxPool::Sqlite(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_sqlite_param(query, param);
-                }
-                let row = query.fetch_optional(pool).await?;
-                Ok(row.map(SqlxRow::Sqlite))
-            }
-        }
+        on_executor!(self, sql, params, |query, executor, row| {
+            let result = query.fetch_optional(executor).await?;
+            Ok(result.map(row))
+        })
     }
 
     /// Execute a SQL statement (INSERT, UPDATE, DELETE) and return affected rows.
     pub async fn raw_execute(&self, sql: &str, params: &[FilterValue]) -> SqlxResult<u64> {
         debug!(sql = %sql, "Executing raw_execute");
 
-        match &*self.pool {
-            #[cfg(feature = "postgres")]
-            SqlxPool::Postgres(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_pg_param(query, param);
-                }
-                let result = query.execute(pool).await?;
-                Ok(result.rows_affected())
-            }
-            #[cfg(feature = "mysql")]
-            SqlxPool::MySql(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_mysql_param(query, param);
-                }
-                let result = query.execute(pool).await?;
-                Ok(result.rows_affected())
-            }
-            #[cfg(feature = "sqlite")]
-            SqlxPool::Sqlite(pool) => {
-                let mut query = sqlx::query(sql);
-                for param in params {
-                    query = bind_sqlite_param(query, param);
-                }
-                let result = query.execute(pool).await?;
-                Ok(result.rows_affected())
-            }
-        }
+        on_executor!(self, sql, params, |query, executor, _| {
+            let result = query.execute(executor).await?;
+            Ok(result.rows_affected())
+        })
     }
 
     /// Count rows in a table with optional filter.
@@ -253,6 +304,7 @@ fn bind_pg_param<'q>(
         FilterValue::Bool(b) => query.bind(*b),
         FilterValue::Null => query.bind(Option::<String>::None),
         FilterValue::Json(j) => query.bind(j.clone()),
+        FilterValue::Bytes(b) => query.bind(b.as_slice()),
         FilterValue::List(arr) => {
             // Convert list to JSON for PostgreSQL
             let json = serde_json::to_value(arr).unwrap_or(serde_json::Value::Null);
@@ -273,6 +325,7 @@ fn bind_mysql_param<'q>(
         FilterValue::Bool(b) => query.bind(*b),
         FilterValue::Null => query.bind(Option::<String>::None),
         FilterValue::Json(j) => query.bind(j.to_string()),
+        FilterValue::Bytes(b) => query.bind(b.as_slice()),
         FilterValue::List(arr) => {
             let json = serde_json::to_string(arr).unwrap_or_default();
             query.bind(json)
@@ -292,6 +345,7 @@ fn bind_sqlite_param<'q>(
         FilterValue::Bool(b) => query.bind(*b),
         FilterValue::Null => query.bind(Option::<String>::None),
         FilterValue::Json(j) => query.bind(j.to_string()),
+        FilterValue::Bytes(b) => query.bind(b.as_slice()),
         FilterValue::List(arr) => {
             let json = serde_json::to_string(arr).unwrap_or_default();
             query.bind(json)
@@ -469,6 +523,122 @@ impl QueryEngine for SqlxEngine {
             Ok(count)
         })
     }
+
+    fn database_type(&self) -> prax_query::sql::DatabaseType {
+        self.backend.database_type()
+    }
+}
+
+impl TransactionalEngine for SqlxEngine {
+    fn begin(&self, config: &TransactionConfig) -> BoxFuture<'_, QueryResult<Self>> {
+        let statements = begin_statements(self.backend, config);
+        Box::pin(async move {
+            if self.in_transaction() {
+                return Err(prax_query::QueryError::transaction(
+                    "already in a transaction; use a savepoint to nest",
+                ));
+            }
+
+            let mut conn = SqlxConnection::acquire(&self.pool).await?;
+            for sql in &statements {
+                debug!(sql = %sql, "Beginning transaction");
+                conn.execute_unprepared(sql).await?;
+            }
+
+            Ok(Self {
+                pool: self.pool.clone(),
+                backend: self.backend,
+                transaction: Some(Arc::new(TransactionConnection(Mutex::new(Some(conn))))),
+            })
+        })
+    }
+
+    fn commit(&self) -> BoxFuture<'_, QueryResult<()>> {
+        Box::pin(self.finish("COMMIT"))
+    }
+
+    fn rollback(&self) -> BoxFuture<'_, QueryResult<()>> {
+        Box::pin(self.finish("ROLLBACK"))
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    fn savepoint(&self, name: &str) -> BoxFuture<'_, QueryResult<()>> {
+        let sql = format!("SAVEPOINT {}", name);
+        Box::pin(async move { self.control(&sql).await })
+    }
+
+    fn rollback_to_savepoint(&self, name: &str) -> BoxFuture<'_, QueryResult<()>> {
+        let sql = format!("ROLLBACK TO SAVEPOINT {}", name);
+        Box::pin(async move { self.control(&sql).await })
+    }
+
+    fn release_savepoint(&self, name: &str) -> BoxFuture<'_, QueryResult<()>> {
+        let sql = format!("RELEASE SAVEPOINT {}", name);
+        Box::pin(async move { self.control(&sql).await })
+    }
+}
+
+impl RawDatabase for SqlxEngine {
+    fn query(
+        &self,
+        sql: &str,
+        params: Vec<FilterValue>,
+    ) -> BoxFuture<'_, QueryResult<Vec<serde_json::Value>>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let rows = self.raw_query_many(&sql, &params).await?;
+            Ok(rows
+                .iter()
+                .map(SqlxRow::to_json)
+                .collect::<SqlxResult<_>>()?)
+        })
+    }
+
+    fn execute(&self, sql: &str, params: Vec<FilterValue>) -> BoxFuture<'_, QueryResult<u64>> {
+        let sql = sql.to_string();
+        Box::pin(async move { Ok(self.raw_execute(&sql, &params).await?) })
+    }
+
+    fn execute_in_transaction(
+        &self,
+        statements: Vec<(String, Vec<FilterValue>)>,
+    ) -> BoxFuture<'_, QueryResult<Vec<u64>>> {
+        Box::pin(async move {
+            run_transaction(self, &TransactionConfig::default(), |tx| async move {
+                let mut affected = Vec::with_capacity(statements.len());
+                for (sql, params) in &statements {
+                    affected.push(tx.raw_execute(sql, params).await?);
+                }
+                Ok(affected)
+            })
+            .await
+        })
+    }
+
+    fn database_type(&self) -> prax_query::sql::DatabaseType {
+        self.backend.database_type()
+    }
+}
+
+/// The statements that begin a transaction on a backend.
+///
+/// MySQL sets the isolation level before `START TRANSACTION`; SQLite
+/// transactions are always serializable.
+fn begin_statements(backend: DatabaseBackend, config: &TransactionConfig) -> Vec<String> {
+    match backend {
+        DatabaseBackend::Postgres => vec![config.to_begin_sql()],
+        DatabaseBackend::MySql => vec![
+            format!(
+                "SET TRANSACTION ISOLATION LEVEL {}",
+                config.isolation.as_sql()
+            ),
+            format!("START TRANSACTION {}", config.access_mode.as_sql()),
+        ],
+        DatabaseBackend::Sqlite => vec!["BEGIN".to_string()],
+    }
 }
 
 #[cfg(test)]
@@ -491,4 +661,130 @@ mod tests {
         );
         assert_eq!(quote_identifier(DatabaseBackend::MySql, "users"), "`users`");
     }
+
+    #[test]
+    fn test_begin_statements() {
+        let config = TransactionConfig::new().isolation(prax_query::IsolationLevel::Serializable);
+        assert_eq!(
+            begin_statements(DatabaseBackend::MySql, &config),
+            vec![
+                "SET TRANSACTION ISOLATION LEVEL SERIALIZABLE",
+                "START TRANSACTION READ WRITE"
+            ]
+        );
+        assert_eq!(
+            begin_statements(DatabaseBackend::Sqlite, &config),
+            vec!["BEGIN"]
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_nested_transactions_use_savepoints() {
+        use prax_query::QueryError;
+        use prax_query::transaction::run_transaction;
+
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("tx.db").display());
+        let engine = SqlxEngine::new(SqlxConfig::from_url(url).unwrap())
+            .await
+            .unwrap();
+        engine
+            .execute_raw("CREATE TABLE users (name TEXT)", Vec::new())
+            .await
+            .unwrap();
+
+        run_transaction(&engine, &TransactionConfig::new(), |tx| async move {
+            tx.execute_raw("INSERT INTO users VALUES ('alice')", Vec::new())
+                .await?;
+
+            let nested: QueryResult<()> =
+                run_transaction(&tx, &TransactionConfig::new(), |inner| async move {
+                    inner
+                        .execute_raw("INSERT INTO users VALUES ('bob')", Vec::new())
+                        .await?;
+                    Err(QueryError::internal("boom"))
+                })
+                .await;
+            assert!(nested.is_err());
+
+            tx.savepoint("before_carol").await?;
+            tx.execute_raw("INSERT INTO users VALUES ('carol')", Vec::new())
+                .await?;
+            tx.rollback_to_savepoint("before_carol").await?;
+            tx.release_savepoint("before_carol").await
+        })
+        .await
+        .unwrap();
+
+        let count = engine
+            .count("SELECT COUNT(*) FROM users", Vec::new())
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dual_write_readiness() {
+        use prax_query::middleware::{
+            DualWriteMiddleware, MiddlewareChain, QueryContext, QueryResponse,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut engines = Vec::new();
+        for name in ["blue.db", "green.db"] {
+            let url = format!("sqlite://{}?mode=rwc", dir.path().join(name).display());
+            let engine = SqlxEngine::new(SqlxConfig::from_url(url).unwrap())
+                .await
+                .unwrap();
+            engine
+                .execute_raw(
+                    "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT)",
+                    Vec::new(),
+                )
+                .await
+                .unwrap();
+            engines.push(engine);
+        }
+        let (primary, secondary) = (engines.remove(0), engines.remove(0));
+
+        let dual = DualWriteMiddleware::new(secondary.clone()).track("users", ["id"]);
+        let mut chain = MiddlewareChain::new();
+        chain.push(dual.clone());
+
+        for id in 1..=3 {
+            let ctx = QueryContext::new(
+                "INSERT INTO users (id, email) VALUES (?, ?) RETURNING id",
+                vec![
+                    FilterValue::Int(id),
+                    format!("user{}@example.com", id).into(),
+                ],
+            );
+            let primary = &primary;
+            chain
+                .execute(ctx, |ctx| {
+                    Box::pin(async move {
+                        let rows =
+                            RawDatabase::query(primary, ctx.sql(), ctx.params().to_vec()).await?;
+                        Ok(QueryResponse::new(serde_json::Value::Array(rows)))
+                    })
+                })
+                .await
+                .unwrap();
+        }
+
+        let report = dual.readiness(&primary).await.unwrap();
+        assert_eq!(report.mirrored, 3);
+        assert!(report.is_ready(), "{}", report.summary());
+
+        // A write that bypasses the middleware shows up as divergence
+        secondary
+            .execute_raw("UPDATE users SET email = 'stale' WHERE id = 2", Vec::new())
+            .await
+            .unwrap();
+        let report = dual.readiness(&primary).await.unwrap();
+        assert!(!report.is_ready());
+        assert_eq!(report.tables[0].mismatched, vec!["2"]);
+    }
 }
@@ -41,6 +41,10 @@ pub enum SqlxError {
     #[error("Operation timed out after {0}ms")]
     Timeout(u64),
 
+    /// Transaction error
+    #[error("Transaction error: {0}")]
+    Transaction(String),
+
     /// Migration error
     #[error("Migration error: {0}")]
     Migration(String),
@@ -70,6 +74,7 @@ impl From<SqlxError> for QueryError {
             SqlxError::TypeConversion(msg) => QueryError::serialization(msg),
             SqlxError::Pool(msg) => QueryError::connection(msg),
             SqlxError::Timeout(ms) => QueryError::timeout(ms),
+            SqlxError::Transaction(msg) => QueryError::transaction(msg),
             SqlxError::Migration(msg) => QueryError::database(msg),
             SqlxError::Internal(msg) => QueryError::internal(msg),
         }
@@ -106,6 +111,11 @@ impl SqlxError {
     pub fn type_conversion(msg: impl Into<String>) -> Self {
         Self::TypeConversion(msg.into())
     }
+
+    /// Create a transaction error.
+    pub fn transaction(msg: impl Into<String>) -> Self {
+        Self::Transaction(msg.into())
+    }
 }
 
 #[cfg(test)]
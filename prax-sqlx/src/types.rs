@@ -20,6 +20,10 @@ pub fn filter_value_to_string(value: &FilterValue) -> String {
         FilterValue::Bool(b) => b.to_string(),
         FilterValue::Null => "NULL".to_string(),
         FilterValue::Json(j) => j.to_string(),
+        FilterValue::Bytes(b) => {
+            let hex: String = b.iter().map(|byte| format!("{:02x}", byte)).collect();
+            format!("0x{}", hex)
+        }
         FilterValue::List(arr) => {
             let items: Vec<String> = arr.iter().map(filter_value_to_string).collect();
             format!("[{}]", items.join(", "))
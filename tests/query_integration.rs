@@ -7,7 +7,7 @@
 //! - Error handling
 
 use prax_query::connection::{ConnectionString, Driver};
-use prax_query::data::{DataBuilder, FieldValue};
+use prax_query::data::{DataBuilder, FieldOp, FieldValue};
 use prax_query::error::{ErrorCode, QueryError};
 use prax_query::filter::{Filter, FilterValue, ScalarFilter};
 use prax_query::raw::Sql;
@@ -200,7 +200,10 @@ fn test_data_builder_set_null() {
     let builder = DataBuilder::new().set("bio", FieldValue::Null);
 
     let fields = builder.into_fields();
-    assert!(matches!(fields.get("bio"), Some(FieldValue::Null)));
+    assert!(matches!(
+        fields.get("bio"),
+        Some(FieldOp::Set(FieldValue::Null))
+    ));
 }
 
 /// Test connection string parsing